@@ -13,12 +13,13 @@ use tower::ServiceExt;
 
 use torii::etl::decoder::{ContractFilter, DecoderContext, DecoderId};
 use torii::etl::engine_db::{EngineDb, EngineDbConfig};
-use torii::etl::envelope::{Envelope, TypeId, TypedBody};
+use torii::etl::envelope::{Envelope, EnvelopeSlab, TypeId, TypedBody};
 use torii::etl::extractor::{ExtractionBatch, RetryPolicy};
 use torii::etl::sink::{EventBus, MultiSink, Sink, SinkContext, TopicInfo};
 use torii::etl::Decoder;
 use torii::grpc::{proto::TopicSubscription, SubscriptionManager};
 use torii::http::create_http_router;
+use torii::ToriiResult;
 use torii_common::{blob_to_felt, blob_to_u256, felt_to_blob, u256_to_blob};
 use torii_erc1155::decoder::Erc1155Decoder;
 use torii_erc20::{
@@ -57,7 +58,7 @@ impl Decoder for MockDecoder {
         self.name
     }
 
-    async fn decode_event(&self, event: &EmittedEvent) -> anyhow::Result<Vec<Envelope>> {
+    async fn decode_event(&self, event: &EmittedEvent) -> ToriiResult<Vec<Envelope>> {
         if event.from_address != self.contract {
             return Ok(Vec::new());
         }
@@ -86,11 +87,7 @@ impl Sink for MockSink {
         vec![TypeId::new("bench.body")]
     }
 
-    async fn process(
-        &self,
-        envelopes: &[Envelope],
-        _batch: &ExtractionBatch,
-    ) -> anyhow::Result<()> {
+    async fn process(&self, envelopes: &[Envelope], _batch: &ExtractionBatch) -> ToriiResult<()> {
         for envelope in envelopes {
             let _ = envelope.downcast_ref::<BenchBody>();
         }
@@ -109,7 +106,7 @@ impl Sink for MockSink {
         &mut self,
         _event_bus: Arc<EventBus>,
         _context: &SinkContext,
-    ) -> anyhow::Result<()> {
+    ) -> ToriiResult<()> {
         Ok(())
     }
 }
@@ -208,6 +205,8 @@ fn make_transfer_batch(size: usize, offset: u64) -> Vec<TransferData> {
                 block_number: 4_000_000 + i + offset,
                 tx_hash: Felt::from(0x8000 + i + offset),
                 timestamp: None,
+                event_index: None,
+                provenance: None,
             }
         })
         .collect()
@@ -459,6 +458,17 @@ fn benchmark_envelope_core(c: &mut Criterion) {
         });
     });
 
+    group.bench_function("envelope_from_body", |b| {
+        b.iter(|| {
+            let envelope = Envelope::from_body(
+                black_box("bench-envelope".to_string()),
+                BenchBody { value: 7 },
+                HashMap::new(),
+            );
+            black_box(envelope)
+        });
+    });
+
     let envelope = Envelope::new(
         "bench-envelope".to_string(),
         Box::new(BenchBody { value: 99 }),
@@ -473,6 +483,28 @@ fn benchmark_envelope_core(c: &mut Criterion) {
         });
     });
 
+    for size in [100_usize, 10_000] {
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(
+            BenchmarkId::new("envelope_slab", size),
+            &size,
+            |b, &size| {
+                b.iter(|| {
+                    let mut slab = EnvelopeSlab::with_capacity(size);
+                    for i in 0..size {
+                        slab.push(
+                            format!("bench-envelope-{i}"),
+                            BenchBody { value: i as u64 },
+                            HashMap::new(),
+                            None,
+                        );
+                    }
+                    black_box(slab.into_envelopes())
+                });
+            },
+        );
+    }
+
     group.finish();
 }
 
@@ -166,6 +166,12 @@ pub struct Config {
     #[arg(long, default_value = "0")]
     pub rpc_parallelism: usize,
 
+    #[arg(long, default_value = "0")]
+    pub rpc_rate_limit: u32,
+
+    #[arg(long, default_value = "0")]
+    pub rpc_burst: u32,
+
     #[arg(long)]
     pub max_db_connections: Option<u32>,
 
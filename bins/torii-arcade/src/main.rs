@@ -21,6 +21,7 @@ use torii::etl::sink::{EventBus, Sink, SinkContext, TopicInfo};
 use torii::etl::EngineDb;
 use torii::etl::TypeId;
 use torii::EtlConcurrencyConfig;
+use torii::ToriiResult;
 use torii_arcade_sink::proto::arcade::arcade_server::ArcadeServer;
 use torii_arcade_sink::{ArcadeSink, FILE_DESCRIPTOR_SET as ARCADE_DESCRIPTOR_SET};
 use torii_common::{MetadataFetcher, TokenUriService};
@@ -59,8 +60,7 @@ use torii_runtime_common::database::{
 use torii_runtime_common::token_support::{resolve_installed_token_support, InstalledTokenSupport};
 use torii_sqlite::{is_sqlite_memory_path, sqlite_connect_options};
 
-type StarknetProvider =
-    starknet::providers::jsonrpc::JsonRpcClient<starknet::providers::jsonrpc::HttpTransport>;
+type StarknetProvider = torii_common::RpcProvider;
 
 const TOKEN_COMMAND_QUEUE_SIZE: usize = 4096;
 const TOKEN_METADATA_COMMAND_PARALLELISM: usize = 1;
@@ -174,7 +174,7 @@ impl Sink for ArcadeProjectionPipeline {
         &self,
         envelopes: &[torii::etl::Envelope],
         batch: &torii::etl::extractor::ExtractionBatch,
-    ) -> anyhow::Result<()> {
+    ) -> ToriiResult<()> {
         for sink in &self.sinks {
             if let Err(error) = sink.process(envelopes, batch).await {
                 Self::abort_on_sink_failure("process", sink.name(), error);
@@ -203,7 +203,7 @@ impl Sink for ArcadeProjectionPipeline {
         &mut self,
         event_bus: Arc<EventBus>,
         context: &SinkContext,
-    ) -> anyhow::Result<()> {
+    ) -> ToriiResult<()> {
         for sink in &mut self.sinks {
             sink.initialize(event_bus.clone(), context).await?;
         }
@@ -259,10 +259,10 @@ async fn run_indexer(config: Config) -> Result<()> {
         "torii-arcade does not support mixed storage backends in one runtime; configure all databases as either SQLite or PostgreSQL",
     )?;
 
-    let provider = starknet::providers::jsonrpc::JsonRpcClient::new(
-        starknet::providers::jsonrpc::HttpTransport::new(
-            url::Url::parse(&config.rpc_url).expect("Invalid RPC URL"),
-        ),
+    let provider = torii_common::rate_limited_provider(
+        url::Url::parse(&config.rpc_url).expect("Invalid RPC URL"),
+        config.rpc_rate_limit,
+        config.rpc_burst,
     );
     let provider = Arc::new(provider);
 
@@ -781,6 +781,9 @@ fn build_extractor(
             retry_policy: RetryPolicy::default(),
             ignore_saved_state: config.ignore_saved_state,
             rpc_parallelism: config.rpc_parallelism,
+            confirmation_depth: 0,
+            detect_deployment_block: false,
+            shards: None,
         },
     );
     #[allow(clippy::single_match_else)]
@@ -58,6 +58,12 @@ pub struct Config {
     /// Delay between ETL idle/retry cycles in seconds.
     #[arg(long, default_value = "3")]
     pub cycle_interval: u64,
+
+    /// Record provenance (extractor, decoder, batch id, timestamps) for stored rows (debug)
+    ///
+    /// Exposed in GetTransfers/GetApprovals responses when `include_provenance` is set.
+    #[arg(long, env = "TORII_PROVENANCE")]
+    pub provenance: bool,
 }
 
 impl Config {
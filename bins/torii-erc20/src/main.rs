@@ -96,7 +96,8 @@ async fn main() -> Result<()> {
     let provider = Arc::new(starknet::providers::jsonrpc::JsonRpcClient::new(
         starknet::providers::jsonrpc::HttpTransport::new(
             url::Url::parse(&config.rpc_url).expect("Invalid RPC URL"),
-        ),
+        )
+        .into(),
     ));
 
     // Create extractor
@@ -107,6 +108,12 @@ async fn main() -> Result<()> {
         batch_size: 50,
         retry_policy: torii::etl::extractor::RetryPolicy::default(),
         rpc_parallelism: 0,
+        max_concurrent_block_fetches: 0,
+        adaptive_batch: None,
+        include_receipts: false,
+        confirmation_depth: 0,
+        consolidate_event_cursors: false,
+        ignore_saved_state: false,
     };
 
     let extractor = Box::new(BlockRangeExtractor::new(provider.clone(), extractor_config));
@@ -148,6 +155,7 @@ async fn main() -> Result<()> {
         .database_root(&db_setup.database_root)
         .command_bus_queue_size(ERC20_METADATA_COMMAND_QUEUE_SIZE)
         .cycle_interval(config.cycle_interval)
+        .with_provenance(config.provenance)
         .engine_database_url(db_setup.engine_url.clone())
         .with_extractor(extractor)
         .add_decoder(decoder)
@@ -1,5 +1,5 @@
 use anyhow::{bail, Result};
-use clap::{ArgGroup, Parser};
+use clap::{ArgGroup, Parser, ValueEnum};
 use starknet::core::types::Felt;
 use std::path::{Path, PathBuf};
 
@@ -9,6 +9,16 @@ pub enum StorageBackend {
     Sqlite,
 }
 
+/// Where Dojo table definitions are persisted.
+#[derive(Clone, Copy, Debug, Default, ValueEnum, PartialEq, Eq)]
+pub enum DojoStoreBackend {
+    /// Alongside the decoded data in the storage database
+    #[default]
+    Sink,
+    /// Versioned in the engine database
+    EngineDb,
+}
+
 /// Dojo introspect indexer backed by PostgreSQL or SQLite.
 ///
 /// This binary targets explicitly configured Dojo contracts and persists the
@@ -125,6 +135,14 @@ pub struct Config {
     #[arg(long, default_value = "0")]
     pub rpc_parallelism: usize,
 
+    /// Maximum RPC requests per second shared by all components (`0` = unlimited).
+    #[arg(long, default_value = "0")]
+    pub rpc_rate_limit: u32,
+
+    /// Maximum burst of RPC requests above the rate limit (`0` = one second worth).
+    #[arg(long, default_value = "0")]
+    pub rpc_burst: u32,
+
     /// Maximum SQL connections for the storage backend.
     #[arg(long)]
     pub max_db_connections: Option<u32>,
@@ -145,6 +163,13 @@ pub struct Config {
     )]
     pub index_external_contracts: bool,
 
+    /// Where Dojo table definitions are persisted (`sink` or `engine-db`).
+    ///
+    /// `engine-db` keeps every schema version in the engine database, independent
+    /// of the storage database.
+    #[arg(long, value_enum, default_value_t = DojoStoreBackend::Sink)]
+    pub dojo_store: DojoStoreBackend,
+
     /// Exact Dojo model names to mirror into append-only `_historical` tables.
     #[arg(long, value_delimiter = ',')]
     pub historical: Vec<String>,
@@ -379,6 +404,21 @@ mod tests {
         assert!(!cfg.index_external_contracts);
     }
 
+    #[test]
+    fn dojo_store_defaults_to_sink() {
+        let cfg = Config::parse_from(["torii-server", "--contract", "0x1"]);
+        assert_eq!(cfg.dojo_store, DojoStoreBackend::Sink);
+
+        let cfg = Config::parse_from([
+            "torii-server",
+            "--contract",
+            "0x1",
+            "--dojo-store",
+            "engine-db",
+        ]);
+        assert_eq!(cfg.dojo_store, DojoStoreBackend::EngineDb);
+    }
+
     #[test]
     fn historical_models_parse_as_exact_names() {
         let cfg = Config::parse_from([
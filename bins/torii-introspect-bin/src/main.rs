@@ -4,7 +4,7 @@ mod config;
 
 use anyhow::Result;
 use clap::Parser;
-use config::{Config, StorageBackend};
+use config::{Config, DojoStoreBackend, StorageBackend};
 use sqlx::postgres::PgPoolOptions;
 use sqlx::sqlite::SqlitePoolOptions;
 use starknet::core::types::Felt;
@@ -18,6 +18,7 @@ use torii::etl::extractor::{
     ContractEventConfig, EventExtractor, EventExtractorConfig, Extractor, RetryPolicy,
 };
 use torii::etl::EngineDb;
+use torii::ToriiResult;
 use torii::{EtlConcurrencyConfig, ToriiConfigBuilder};
 use torii_common::{MetadataFetcher, TokenUriService};
 use torii_config_common::apply_observability_env;
@@ -27,8 +28,10 @@ use torii_dojo::external_contract::{
     contract_type_from_decoder_ids, RegisterExternalContractCommandHandler, RegisteredContractType,
     SharedContractTypeRegistry, SharedDecoderRegistry,
 };
+use torii_dojo::store::engine_db::EngineDbStore;
 use torii_dojo::store::postgres::PgStore;
 use torii_dojo::store::sqlite::SqliteStore;
+use torii_dojo::store::DojoStore;
 use torii_ecs_sink::proto::world::world_server::WorldServer;
 use torii_ecs_sink::{EcsSink, FILE_DESCRIPTOR_SET as ECS_DESCRIPTOR_SET};
 use torii_entities_historical_sink::EntitiesHistoricalSink;
@@ -47,6 +50,7 @@ use torii_erc721::{
     Erc721Decoder, Erc721MetadataCommandHandler, Erc721Service, Erc721Sink, Erc721Storage,
     FILE_DESCRIPTOR_SET as ERC721_DESCRIPTOR_SET,
 };
+use torii_introspect::{IntrospectSink, FILE_DESCRIPTOR_SET as INTROSPECT_DESCRIPTOR_SET};
 use torii_introspect_postgres_sink::processor::IntrospectPgDb;
 use torii_introspect_sqlite_sink::processor::IntrospectSqliteDb;
 use torii_runtime_common::database::{
@@ -55,8 +59,7 @@ use torii_runtime_common::database::{
 use torii_runtime_common::token_support::{resolve_installed_token_support, InstalledTokenSupport};
 use torii_sqlite::{is_sqlite_memory_path, sqlite_connect_options};
 
-type StarknetProvider =
-    starknet::providers::jsonrpc::JsonRpcClient<starknet::providers::jsonrpc::HttpTransport>;
+type StarknetProvider = torii_common::RpcProvider;
 type ReflectionBuilder = tonic_reflection::server::Builder<'static>;
 
 const TOKEN_COMMAND_QUEUE_SIZE: usize = 4096;
@@ -108,7 +111,7 @@ impl torii::etl::sink::Sink for OrderedSinkPipeline {
         &self,
         envelopes: &[torii::etl::Envelope],
         batch: &torii::etl::extractor::ExtractionBatch,
-    ) -> Result<()> {
+    ) -> ToriiResult<()> {
         for sink in &self.sinks {
             sink.process(envelopes, batch).await?;
         }
@@ -135,7 +138,7 @@ impl torii::etl::sink::Sink for OrderedSinkPipeline {
         &mut self,
         event_bus: Arc<torii::etl::sink::EventBus>,
         context: &torii::etl::sink::SinkContext,
-    ) -> Result<()> {
+    ) -> ToriiResult<()> {
         for sink in &mut self.sinks {
             sink.initialize(event_bus.clone(), context).await?;
         }
@@ -588,10 +591,10 @@ async fn run_indexer(config: Config) -> Result<()> {
     let installed_external_decoders =
         installed_external_decoder_ids(config.index_external_contracts);
 
-    let provider = starknet::providers::jsonrpc::JsonRpcClient::new(
-        starknet::providers::jsonrpc::HttpTransport::new(
-            url::Url::parse(&config.rpc_url).expect("Invalid RPC URL"),
-        ),
+    let provider = torii_common::rate_limited_provider(
+        url::Url::parse(&config.rpc_url).expect("Invalid RPC URL"),
+        config.rpc_rate_limit,
+        config.rpc_burst,
     );
     let to_block = config.to_block.unwrap_or(u64::MAX);
     let mut extractor_contracts = Vec::new();
@@ -635,6 +638,9 @@ async fn run_indexer(config: Config) -> Result<()> {
             retry_policy: RetryPolicy::default(),
             ignore_saved_state: config.ignore_saved_state,
             rpc_parallelism: config.rpc_parallelism,
+            confirmation_depth: 0,
+            detect_deployment_block: false,
+            shards: None,
         },
     ));
 
@@ -712,16 +718,29 @@ async fn run_with_postgres(
             .await?,
     );
 
-    let decoder = DojoDecoder::<PgStore<_>, _>::new(pool.clone(), provider);
+    let store = match config.dojo_store {
+        DojoStoreBackend::Sink => DojoStore::Sink(PgStore(pool.clone())),
+        DojoStoreBackend::EngineDb => {
+            DojoStore::EngineDb(EngineDbStore::new(registry_engine_db.clone()))
+        }
+    };
+    let decoder = DojoDecoder::<DojoStore<PgStore<_>>, _>::new(store, provider);
     let introspect_sink = IntrospectPgDb::new(pool.clone(), ());
     decoder.store.initialize().await?;
     decoder.load_tables(&[]).await?;
+    let update_sink = IntrospectSink::new().with_tables(
+        decoder
+            .get_tables()?
+            .into_iter()
+            .map(|table| (table.id, table.name)),
+    );
 
     let decoder: Arc<dyn torii::etl::Decoder> = Arc::new(decoder);
 
     let reflection_builder = ReflectionBuilder::configure()
         .register_encoded_file_descriptor_set(torii::TORII_DESCRIPTOR_SET)
-        .register_encoded_file_descriptor_set(ECS_DESCRIPTOR_SET);
+        .register_encoded_file_descriptor_set(ECS_DESCRIPTOR_SET)
+        .register_encoded_file_descriptor_set(INTROSPECT_DESCRIPTOR_SET);
 
     let mut torii_config = torii::ToriiConfig::builder()
         .port(config.port)
@@ -737,6 +756,7 @@ async fn run_with_postgres(
         .add_sink_boxed(Box::new(
             OrderedSinkPipeline::new("introspect-projection-pipeline")
                 .push(Box::new(introspect_sink))
+                .push(Box::new(update_sink))
                 .push(Box::new(
                     EntitiesHistoricalSink::new(
                         storage_database_url,
@@ -796,6 +816,12 @@ async fn run_with_postgres(
         installed_external_decoders.clone(),
     )
     .await?;
+    let ecs_sink = match config.dojo_store {
+        DojoStoreBackend::Sink => ecs_sink,
+        DojoStoreBackend::EngineDb => {
+            ecs_sink.with_dojo_table_store(EngineDbStore::new(registry_engine_db.clone()))
+        }
+    };
     let ecs_grpc_service = ecs_sink.get_grpc_service_impl();
     let torii_config = torii_config.add_sink_boxed(Box::new(ecs_sink));
 
@@ -907,15 +933,28 @@ async fn run_with_sqlite(
         .execute(pool.as_ref())
         .await?;
 
-    let decoder = DojoDecoder::<SqliteStore<_>, _>::new(pool.clone(), provider);
+    let store = match config.dojo_store {
+        DojoStoreBackend::Sink => DojoStore::Sink(SqliteStore(pool.clone())),
+        DojoStoreBackend::EngineDb => {
+            DojoStore::EngineDb(EngineDbStore::new(registry_engine_db.clone()))
+        }
+    };
+    let decoder = DojoDecoder::<DojoStore<SqliteStore<_>>, _>::new(store, provider);
     decoder.store.initialize().await?;
     decoder.load_tables(&[]).await?;
+    let update_sink = IntrospectSink::new().with_tables(
+        decoder
+            .get_tables()?
+            .into_iter()
+            .map(|table| (table.id, table.name)),
+    );
 
     let decoder: Arc<dyn torii::etl::Decoder> = Arc::new(decoder);
 
     let reflection_builder = ReflectionBuilder::configure()
         .register_encoded_file_descriptor_set(torii::TORII_DESCRIPTOR_SET)
-        .register_encoded_file_descriptor_set(ECS_DESCRIPTOR_SET);
+        .register_encoded_file_descriptor_set(ECS_DESCRIPTOR_SET)
+        .register_encoded_file_descriptor_set(INTROSPECT_DESCRIPTOR_SET);
 
     let mut torii_config = torii::ToriiConfig::builder()
         .port(config.port)
@@ -931,6 +970,7 @@ async fn run_with_sqlite(
         .add_sink_boxed(Box::new(
             OrderedSinkPipeline::new("introspect-projection-pipeline")
                 .push(Box::new(IntrospectSqliteDb::new(pool.clone(), ())))
+                .push(Box::new(update_sink))
                 .push(Box::new(
                     EntitiesHistoricalSink::new(
                         storage_database_url,
@@ -990,6 +1030,12 @@ async fn run_with_sqlite(
         installed_external_decoders.clone(),
     )
     .await?;
+    let ecs_sink = match config.dojo_store {
+        DojoStoreBackend::Sink => ecs_sink,
+        DojoStoreBackend::EngineDb => {
+            ecs_sink.with_dojo_table_store(EngineDbStore::new(registry_engine_db.clone()))
+        }
+    };
     let ecs_grpc_service = ecs_sink.get_grpc_service_impl();
     let torii_config = torii_config.add_sink_boxed(Box::new(ecs_sink));
 
@@ -25,6 +25,7 @@ use torii::etl::extractor::{BlockContext, ExtractionBatch, Extractor, Transactio
 use torii::etl::sink::{EventBus, Sink, SinkContext};
 use torii::etl::Decoder;
 use torii::grpc::SubscriptionManager;
+use torii::ToriiResult;
 use torii_dojo::decoder::DojoDecoder;
 use torii_dojo::store::postgres::PgStore;
 use torii_introspect_postgres_sink::IntrospectPgDb;
@@ -275,6 +276,7 @@ impl SyntheticIntrospectExtractor {
                     hash: block_hash_for(block_number),
                     parent_hash: block_hash_for(block_number.saturating_sub(1)),
                     timestamp: 1_700_000_000 + block_number * 12,
+                    header: None,
                 }),
             );
 
@@ -287,6 +289,7 @@ impl SyntheticIntrospectExtractor {
                         block_number,
                         sender_address: Some(FROM_ADDRESS),
                         calldata: Vec::new(),
+                        receipt: None,
                     }),
                 );
                 events.push(event);
@@ -317,6 +320,7 @@ impl SyntheticIntrospectExtractor {
                         block_number,
                         sender_address: Some(FROM_ADDRESS),
                         calldata: vec![self.table_id(), entity_id, owner, initial_score],
+                        receipt: None,
                     }),
                 );
                 events.push(set_event);
@@ -334,6 +338,7 @@ impl SyntheticIntrospectExtractor {
                         block_number,
                         sender_address: Some(FROM_ADDRESS),
                         calldata: vec![self.table_id(), entity_id, final_score],
+                        receipt: None,
                     }),
                 );
                 events.push(update_event);
@@ -362,7 +367,7 @@ impl Extractor for SyntheticIntrospectExtractor {
         &mut self,
         cursor: Option<String>,
         engine_db: &EngineDb,
-    ) -> Result<ExtractionBatch> {
+    ) -> ToriiResult<ExtractionBatch> {
         self.initialize(cursor, engine_db).await?;
 
         if self.finished {
@@ -381,7 +386,7 @@ impl Extractor for SyntheticIntrospectExtractor {
         self.finished
     }
 
-    async fn commit_cursor(&mut self, cursor: &str, engine_db: &EngineDb) -> Result<()> {
+    async fn commit_cursor(&mut self, cursor: &str, engine_db: &EngineDb) -> ToriiResult<()> {
         let block = Self::parse_cursor(cursor)?;
         engine_db
             .set_extractor_state(EXTRACTOR_TYPE, STATE_KEY, &block.to_string())
@@ -0,0 +1,72 @@
+//! `backfill` subcommand: re-index a block range without touching the indexing cursors.
+
+use std::any::Any;
+use torii::async_trait;
+use torii::etl::extractor::{CycleFeedback, Extractor, ExtractorCapabilities};
+use torii::etl::{EngineDb, ExtractionBatch};
+use torii::ToriiResult;
+
+/// Extractor of a backfill, built with `ignore_saved_state`.
+///
+/// Its cursors are never committed: the range is extracted once and indexing resumes
+/// from its own cursors on the next `index` run.
+pub struct BackfillExtractor {
+    inner: Box<dyn Extractor>,
+}
+
+impl BackfillExtractor {
+    pub fn new(inner: Box<dyn Extractor>) -> Self {
+        Self { inner }
+    }
+}
+
+#[async_trait]
+impl Extractor for BackfillExtractor {
+    fn set_start_block(&mut self, start_block: u64) {
+        self.inner.set_start_block(start_block);
+    }
+
+    async fn extract(
+        &mut self,
+        cursor: Option<String>,
+        engine_db: &EngineDb,
+    ) -> ToriiResult<ExtractionBatch> {
+        self.inner.extract(cursor, engine_db).await
+    }
+
+    fn is_finished(&self) -> bool {
+        self.inner.is_finished()
+    }
+
+    async fn chain_head(&self) -> ToriiResult<Option<u64>> {
+        self.inner.chain_head().await
+    }
+
+    fn observe_cycle(&mut self, feedback: &CycleFeedback) {
+        self.inner.observe_cycle(feedback);
+    }
+
+    fn observe_backpressure(&mut self) {
+        self.inner.observe_backpressure();
+    }
+
+    fn set_events_per_cycle(&mut self, events: u64) -> bool {
+        self.inner.set_events_per_cycle(events)
+    }
+
+    fn set_batch_size(&mut self, blocks: u64) -> bool {
+        self.inner.set_batch_size(blocks)
+    }
+
+    fn capabilities(&self) -> ExtractorCapabilities {
+        self.inner.capabilities()
+    }
+
+    fn extractor_type(&self) -> &'static str {
+        self.inner.extractor_type()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
@@ -1,8 +1,19 @@
 //! Configuration for the unified token indexer
 
-use anyhow::Result;
-use clap::{Parser, ValueEnum};
+use anyhow::{bail, Result};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use starknet::core::types::Felt;
+use starknet::core::utils::get_selector_from_name;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+use torii::etl::decoder::SelectorAliases;
+use torii::etl::extractor::{AdaptiveBatchConfig, RetryPolicy};
+use torii::etl::sink::SinkContractFilter;
+use torii::etl::{ShardingConfig, StartupConsistency};
+use torii::tonic::codec::CompressionEncoding;
+use torii::{GrpcServerOptions, Namespaces};
+use torii_common::{ExportFormat, MetadataCacheConfig, ObjectStoreConfig, ObjectStoreProvider};
 
 /// Extraction mode for the token indexer.
 ///
@@ -26,6 +37,40 @@ pub enum ExtractionMode {
     GlobalEvent,
 }
 
+/// Startup check of the cursor against the blocks committed by the sinks.
+#[derive(Clone, Copy, Debug, Default, ValueEnum, PartialEq, Eq)]
+pub enum StartupConsistencyMode {
+    /// No check.
+    #[default]
+    Off,
+    /// Log the sinks behind the cursor.
+    Warn,
+    /// Log the sinks behind the cursor and rewind the cursor to the lowest of them.
+    Rewind,
+}
+
+impl From<StartupConsistencyMode> for StartupConsistency {
+    fn from(mode: StartupConsistencyMode) -> Self {
+        match mode {
+            StartupConsistencyMode::Off => Self::Disabled,
+            StartupConsistencyMode::Warn => Self::Warn,
+            StartupConsistencyMode::Rewind => Self::Rewind,
+        }
+    }
+}
+
+/// Compression of gRPC responses.
+#[derive(Clone, Copy, Debug, Default, ValueEnum, PartialEq, Eq)]
+pub enum GrpcCompression {
+    /// Uncompressed responses.
+    #[default]
+    None,
+    /// Gzip, for clients accepting it.
+    Gzip,
+    /// Zstandard, for clients accepting it.
+    Zstd,
+}
+
 /// Metadata fetching behavior.
 #[derive(Clone, Debug, ValueEnum, PartialEq, Eq)]
 pub enum MetadataMode {
@@ -35,6 +80,164 @@ pub enum MetadataMode {
     Deferred,
 }
 
+/// Accounts whose ERC20 balances are maintained.
+#[derive(Clone, Copy, Debug, Default, ValueEnum, PartialEq, Eq)]
+pub enum Erc20BalanceScope {
+    /// Every address.
+    #[default]
+    All,
+    /// The accounts of the balance watchlist.
+    Watched,
+    /// No address (same as `--erc20-index-only`).
+    None,
+}
+
+/// USD price source for ERC20 tokens.
+#[derive(Clone, Debug, ValueEnum, PartialEq, Eq)]
+pub enum PriceFeedKind {
+    /// Pragma oracle on-chain median spot prices (priced at each block window).
+    Pragma,
+    /// External HTTP endpoint serving current prices (used near the chain head only).
+    Http,
+}
+
+/// Object storage backend for cached NFT images and metadata.
+#[derive(Clone, Debug, ValueEnum, PartialEq, Eq)]
+pub enum ImageStoreKind {
+    /// Amazon S3 or an S3-compatible service (MinIO, R2, ...).
+    S3,
+    /// Google Cloud Storage through its S3-compatible API (HMAC keys).
+    Gcs,
+}
+
+/// Token standard selected by `verify` and `export`.
+#[derive(Clone, Copy, Debug, ValueEnum, PartialEq, Eq)]
+pub enum Standard {
+    Erc20,
+    Erc721,
+    Erc1155,
+}
+
+/// File format of `export`.
+#[derive(Clone, Copy, Debug, Default, ValueEnum, PartialEq, Eq)]
+pub enum ExportFileFormat {
+    /// Comma-separated values with a header line.
+    #[default]
+    Csv,
+    /// One JSON object per line.
+    Jsonl,
+}
+
+impl From<ExportFileFormat> for ExportFormat {
+    fn from(format: ExportFileFormat) -> Self {
+        match format {
+            ExportFileFormat::Csv => Self::Csv,
+            ExportFileFormat::Jsonl => Self::Jsonl,
+        }
+    }
+}
+
+/// Command line: a subcommand followed by its flags, or the `index` flags alone.
+#[derive(Parser, Debug)]
+#[command(name = "torii-tokens")]
+#[command(about = "Index ERC20, ERC721, and ERC1155 tokens on Starknet", long_about = None)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Flags of `index` when no subcommand is given.
+    #[command(flatten)]
+    pub config: Config,
+}
+
+impl Cli {
+    /// Subcommand to run (`index` when none is given).
+    pub fn command(self) -> Command {
+        self.command.unwrap_or(Command::Index {
+            config: self.config,
+        })
+    }
+}
+
+/// `torii-tokens` subcommands, sharing the [`Config`] flags.
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Index tokens and serve the gRPC API (default).
+    Index {
+        #[command(flatten)]
+        config: Config,
+    },
+    /// Re-index `--from-block..=--to-block` from the chain. The indexing cursors are
+    /// neither read nor moved.
+    Backfill {
+        #[command(flatten)]
+        config: Config,
+    },
+    /// Compare a sample of the stored balances and owners with the chain.
+    Verify(VerifyArgs),
+    /// Dump the stored transfers of one token standard to a file.
+    Export(ExportArgs),
+}
+
+/// Flags of `verify`.
+#[derive(Args, Debug)]
+pub struct VerifyArgs {
+    #[command(flatten)]
+    pub config: Config,
+
+    /// Standards to check (comma-separated, default: all).
+    #[arg(long, value_enum, value_delimiter = ',')]
+    pub standards: Vec<Standard>,
+
+    /// Balances and owners checked per standard.
+    #[arg(long, default_value = "100")]
+    pub sample: u32,
+
+    /// Block at which the chain is read (default: last block stored per standard).
+    #[arg(long)]
+    pub at_block: Option<u64>,
+}
+
+impl VerifyArgs {
+    /// Selected standards, all of them when none is given.
+    pub fn standards(&self) -> Vec<Standard> {
+        if self.standards.is_empty() {
+            vec![Standard::Erc20, Standard::Erc721, Standard::Erc1155]
+        } else {
+            self.standards.clone()
+        }
+    }
+}
+
+/// Flags of `export`.
+///
+/// Transfers are filtered with `--from-block`/`--to-block` and written newest first.
+#[derive(Args, Debug)]
+pub struct ExportArgs {
+    #[command(flatten)]
+    pub config: Config,
+
+    /// Standard of the exported transfers.
+    #[arg(long, value_enum)]
+    pub standard: Standard,
+
+    /// Output format.
+    #[arg(long, value_enum, default_value = "csv")]
+    pub format: ExportFileFormat,
+
+    /// Output file (default: stdout).
+    #[arg(long, short)]
+    pub output: Option<PathBuf>,
+
+    /// Only transfers of this token contract (hex).
+    #[arg(long)]
+    pub contract: Option<String>,
+
+    /// Only transfers sent or received by this address (hex).
+    #[arg(long)]
+    pub address: Option<String>,
+}
+
 /// Unified Token Indexer for Starknet
 ///
 /// Indexes ERC20, ERC721, and ERC1155 token transfers and events.
@@ -68,8 +271,7 @@ pub enum MetadataMode {
 ///
 /// ```
 #[derive(Parser, Debug)]
-#[command(name = "torii-tokens")]
-#[command(about = "Index ERC20, ERC721, and ERC1155 tokens on Starknet", long_about = None)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct Config {
     /// Extraction mode
     ///
@@ -95,6 +297,20 @@ pub struct Config {
     #[arg(long)]
     pub to_block: Option<u64>,
 
+    /// Archive the raw events of processed batches in the engine database, so they
+    /// can be replayed with `--replay-from-block`.
+    #[arg(long, env = "TORII_ARCHIVE_EVENTS")]
+    pub archive_events: bool,
+
+    /// Replay mode: re-run decoders and sinks over the archived events from this
+    /// block instead of extracting from the chain (see `--archive-events`).
+    #[arg(long)]
+    pub replay_from_block: Option<u64>,
+
+    /// Last block to replay (default: last archived block).
+    #[arg(long, requires = "replay_from_block")]
+    pub replay_to_block: Option<u64>,
+
     /// Directory where all databases will be stored
     ///
     /// Creates: engine.db, erc20.db, erc721.db, erc1155.db
@@ -115,10 +331,75 @@ pub struct Config {
     #[arg(long, env = "STORAGE_DATABASE_URL")]
     pub storage_database_url: Option<String>,
 
+    /// Split each token storage into this many databases, routing writes by contract
+    ///
+    /// Shard 0 keeps the unsharded path; shard `i` uses `erc20.shard<i>.db` (SQLite) or
+    /// a `<database>_shard<i>` database (PostgreSQL, created beforehand). Queries fan
+    /// out to every shard. Keep the count fixed once data is indexed.
+    #[arg(long, env = "TORII_STORAGE_SHARDS", default_value = "1")]
+    pub storage_shards: usize,
+
+    /// Seconds between SQLite maintenance runs of the token storages (`0` = off)
+    ///
+    /// Runs an incremental vacuum, `ANALYZE` and `PRAGMA optimize` when the indexer is
+    /// idle (no batch pending). Ignored for PostgreSQL storages.
+    #[arg(
+        long,
+        env = "TORII_SQLITE_MAINTENANCE_INTERVAL",
+        default_value = "3600"
+    )]
+    pub sqlite_maintenance_interval: u64,
+
     /// Port for the HTTP/gRPC API
     #[arg(long, default_value = "3000")]
     pub port: u16,
 
+    /// Lame-duck drain period in seconds on shutdown (`0` = exit right away)
+    ///
+    /// New subscriptions are rejected and `/health` returns 503 while existing
+    /// streams and queries are served for this duration (rolling deploys).
+    #[arg(long, env = "TORII_DRAIN_PERIOD", default_value = "0")]
+    pub drain_period: u64,
+
+    /// Enable admin RPCs (`torii.Torii/EnterLameDuck`, `torii.Admin/*`) on the gRPC API
+    #[arg(long, env = "TORII_ADMIN_RPC")]
+    pub admin_rpc: bool,
+
+    /// PEM-encoded TLS certificate for the HTTP/gRPC listener (serves HTTPS/gRPC-TLS)
+    #[arg(long, env = "TORII_TLS_CERT")]
+    pub tls_cert: Option<PathBuf>,
+
+    /// PEM-encoded TLS private key for the HTTP/gRPC listener
+    #[arg(long, env = "TORII_TLS_KEY")]
+    pub tls_key: Option<PathBuf>,
+
+    /// Compression of gRPC responses, for clients accepting it
+    ///
+    /// Gzip and zstd compressed requests are always accepted.
+    #[arg(
+        long,
+        env = "TORII_GRPC_COMPRESSION",
+        value_enum,
+        default_value = "none"
+    )]
+    pub grpc_compression: GrpcCompression,
+
+    /// Largest gRPC request message accepted, in bytes (default: 4 MiB)
+    #[arg(long, env = "TORII_GRPC_MAX_DECODING_MESSAGE_SIZE")]
+    pub grpc_max_decoding_message_size: Option<usize>,
+
+    /// Largest gRPC response message sent, in bytes (default: unlimited)
+    #[arg(long, env = "TORII_GRPC_MAX_ENCODING_MESSAGE_SIZE")]
+    pub grpc_max_encoding_message_size: Option<usize>,
+
+    /// TOML file with `[[contracts]]`/`[blacklist]`/`[[selector_denylist]]` sections
+    /// reloaded at runtime
+    ///
+    /// Mappings added to the file are applied without restart; contracts removed
+    /// from it are blacklisted.
+    #[arg(long, env = "TORII_DECODER_CONFIG")]
+    pub decoder_config: Option<PathBuf>,
+
     /// Enable observability features (Prometheus metrics endpoint and metric collection)
     ///
     /// If not set, observability is disabled.
@@ -131,6 +412,73 @@ pub struct Config {
     #[arg(long, value_delimiter = ',')]
     pub erc20: Vec<String>,
 
+    /// Index ERC20 transfer history only, without balance tracking
+    ///
+    /// Skips balance adjustments and RPC reconciliation; GetBalance/GetBalances are
+    /// rejected. Can be toggled between restarts without schema changes.
+    #[arg(long, env = "TORII_ERC20_INDEX_ONLY")]
+    pub erc20_index_only: bool,
+
+    /// Accounts whose ERC20 balances are maintained
+    ///
+    /// `watched` only maintains the balances of the accounts in the balance watchlist,
+    /// stored in the ERC20 database: the `--erc20-watched-accounts` ones and those added
+    /// with the `AddWatchedAccounts` admin RPC, which backfills their balances.
+    #[arg(
+        long,
+        env = "TORII_ERC20_BALANCE_SCOPE",
+        value_enum,
+        default_value = "all"
+    )]
+    pub erc20_balance_scope: Erc20BalanceScope,
+
+    /// Accounts added to the ERC20 balance watchlist at startup (comma-separated hex
+    /// addresses, requires `--erc20-balance-scope watched`)
+    #[arg(long, env = "TORII_ERC20_WATCHED_ACCOUNTS", value_delimiter = ',')]
+    pub erc20_watched_accounts: Vec<String>,
+
+    /// Validate the configuration and print the plan without indexing
+    ///
+    /// Opens and migrates the databases, loads the decoder config and pings the RPC,
+    /// then exits without starting the ETL loop or the server.
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Light mode: stream decoded token events without storing anything
+    ///
+    /// Skips the token, label, metadata, relay and account databases and keeps the
+    /// engine state in memory. Decoded events are only published on the EventBus
+    /// topics; the ERC gRPC query services are not served. Starts at the chain head
+    /// unless `--from-block` is set.
+    #[arg(long, env = "TORII_LIGHT")]
+    pub light: bool,
+
+    /// Record ERC20 USD prices from a price feed, enabling USD fields in
+    /// GetTransfers/GetBalance/GetBalances (with `include_usd`)
+    #[arg(long, value_enum)]
+    pub price_feed: Option<PriceFeedKind>,
+
+    /// Pragma oracle contract address (with --price-feed pragma)
+    #[arg(long)]
+    pub pragma_oracle: Option<String>,
+
+    /// Token to Pragma pair mappings (comma-separated TOKEN=PAIR, with --price-feed pragma)
+    ///
+    /// Example: --price-pairs 0x049D...dC7=ETH/USD,0x0471...38D=STRK/USD
+    #[arg(long, value_delimiter = ',')]
+    pub price_pairs: Vec<String>,
+
+    /// HTTP price endpoint (with --price-feed http)
+    ///
+    /// Queried as `GET <url>?tokens=<hex>,...&block=<n>`; must return a JSON object
+    /// mapping token addresses to USD prices.
+    #[arg(long)]
+    pub price_feed_url: Option<String>,
+
+    /// Number of blocks sharing a recorded token price
+    #[arg(long, default_value = "100")]
+    pub price_window_blocks: u64,
+
     /// ERC721 contracts to index (comma-separated hex addresses)
     ///
     /// Example: --erc721 0x...nft_contract
@@ -154,6 +502,52 @@ pub struct Config {
     #[arg(long, default_value = "50")]
     pub batch_size: u64,
 
+    /// Adapt the block batch size to keep cycle time near a target (block-range mode)
+    ///
+    /// `--batch-size` becomes the initial size.
+    #[arg(long)]
+    pub adaptive_batch_size: bool,
+
+    /// Smallest block batch size when adaptive batch sizing is enabled
+    #[arg(long, default_value = "10")]
+    pub adaptive_batch_min: u64,
+
+    /// Largest block batch size when adaptive batch sizing is enabled
+    #[arg(long, default_value = "1000")]
+    pub adaptive_batch_max: u64,
+
+    /// Target cycle time (extract + decode + sink) in milliseconds for adaptive batch sizing
+    #[arg(long, default_value = "5000")]
+    pub adaptive_target_cycle_ms: u64,
+
+    /// Expose transaction receipts (actual fee, execution status) to sinks (block-range mode)
+    ///
+    /// Reverted transactions are kept in the batch, which increases memory usage.
+    #[arg(long)]
+    pub include_receipts: bool,
+
+    /// Blocks to stay behind the chain head before indexing (`0` = follow the head)
+    ///
+    /// Trades latency for protection against shallow reorgs (block-range and event modes).
+    #[arg(long, env = "TORII_CONFIRMATION_DEPTH", default_value = "0")]
+    pub confirmation_depth: u64,
+
+    /// Detect the deployment block of each contract when `--from-block` is 0 (event mode)
+    ///
+    /// Found by binary search over `starknet_getClassHashAt` and persisted in the engine
+    /// database, so extraction skips the blocks before each contract exists.
+    #[arg(long, env = "TORII_DETECT_DEPLOYMENT_BLOCK")]
+    pub detect_deployment_block: bool,
+
+    /// Fold the per-contract cursors of a previous event-mode run into the global
+    /// cursor on startup (block-range mode)
+    ///
+    /// Resumes from the lowest contract cursor and skips the events of contracts that
+    /// event mode already indexed further, so switching modes neither loses nor
+    /// double-processes blocks.
+    #[arg(long, env = "TORII_CONSOLIDATE_EVENT_CURSORS")]
+    pub consolidate_event_cursors: bool,
+
     /// Events per RPC request (event mode, max 1024 for most providers)
     #[arg(long, default_value = "1000")]
     pub event_chunk_size: u64,
@@ -174,10 +568,177 @@ pub struct Config {
     #[arg(long, default_value = "3")]
     pub cycle_interval: u64,
 
+    /// Recently processed events remembered to drop duplicates, keyed by
+    /// (tx_hash, event_index) (`0` = disabled).
+    ///
+    /// Useful when switching extraction modes or restarting with overlapping cursors.
+    #[arg(long, default_value = "0")]
+    pub dedupe_window: usize,
+
+    /// Startup check of the cursor against the last block stored by each sink
+    ///
+    /// After a crash, the cursor can be ahead of what a sink committed. `warn` logs the
+    /// sinks behind, `rewind` also rewinds the cursor to the lowest of them.
+    #[arg(
+        long,
+        env = "TORII_STARTUP_CONSISTENCY",
+        value_enum,
+        default_value = "off"
+    )]
+    pub startup_consistency: StartupConsistencyMode,
+
+    /// Log a record per decoded envelope: source event, decoder and the rule that mapped
+    /// the contract to it (explicit, registry or fallback) (debug)
+    ///
+    /// Helps diagnosing why an event ended up in a table. Very verbose.
+    #[arg(long, env = "TORII_DEBUG_ENVELOPES")]
+    pub debug_envelopes: bool,
+
+    /// Split the contracts between instances into this many work shards (`0` = off)
+    ///
+    /// Instances sharing the same PostgreSQL `--database-url` claim disjoint shards
+    /// through leases in the engine database and only index the contracts of their
+    /// shards (event mode). Every instance must use the same count.
+    #[arg(long, env = "TORII_WORK_SHARDS", default_value = "0")]
+    pub work_shards: u32,
+
+    /// Work shards claimed by each instance (instances without a free shard stand by)
+    #[arg(long, env = "TORII_WORK_SHARDS_PER_INSTANCE", default_value = "1")]
+    pub work_shards_per_instance: u32,
+
+    /// Unique id of this instance in the shard leases (default: `$HOSTNAME-<pid>`)
+    #[arg(long, env = "TORII_INSTANCE_ID")]
+    pub instance_id: Option<String>,
+
+    /// Seconds a work shard lease stays valid without renewal
+    #[arg(long, env = "TORII_WORK_SHARD_LEASE_TTL", default_value = "30")]
+    pub work_shard_lease_ttl: u64,
+
+    /// Sinks processing a batch at the same time (`0` = all of them).
+    #[arg(long, default_value = "0")]
+    pub max_concurrent_sinks: usize,
+
+    /// Decoded batches waiting for the sinks above which extraction is throttled
+    /// until they catch up (`0` = never).
+    #[arg(long, env = "TORII_SINK_BACKPRESSURE_THRESHOLD", default_value = "0")]
+    pub sink_backpressure_threshold: usize,
+
+    /// Per-sink processing timeouts (comma-separated SINK=SECONDS); a sink over its
+    /// timeout is cancelled and counted as failed for the batch.
+    ///
+    /// Example: --sink-timeouts erc721=30,erc1155=30
+    #[arg(long, value_delimiter = ',')]
+    pub sink_timeouts: Vec<String>,
+
+    /// Contracts routed to a sink (comma-separated SINK=0xA:0xB; other contracts are
+    /// not routed to it).
+    ///
+    /// Example: --sink-allow-contracts erc20=0x49d3...,erc721=0x1:0x2
+    #[arg(long, value_delimiter = ',')]
+    pub sink_allow_contracts: Vec<String>,
+
+    /// Contracts never routed to a sink (comma-separated SINK=0xA:0xB).
+    ///
+    /// Example: --sink-deny-contracts erc1155=0x3
+    #[arg(long, value_delimiter = ',')]
+    pub sink_deny_contracts: Vec<String>,
+
+    /// Namespaces of the sinks on the gRPC API (comma-separated SINK=NAMESPACE).
+    ///
+    /// Once set, `ListTopics`, `DescribeSinks` and subscriptions must name the
+    /// namespace they access; unmapped sinks belong to `default`.
+    ///
+    /// Example: --sink-namespaces erc20=game-a,erc721=game-b
+    #[arg(long, env = "TORII_SINK_NAMESPACES", value_delimiter = ',')]
+    pub sink_namespaces: Vec<String>,
+
+    /// API keys of the gRPC API and the namespaces they may access
+    /// (comma-separated KEY=NS1:NS2, `*` = all namespaces).
+    ///
+    /// Once set, requests carry a key in `x-api-key` or `authorization: Bearer`.
+    #[arg(long, env = "TORII_API_KEYS", value_delimiter = ',')]
+    pub api_keys: Vec<String>,
+
     /// Maximum chunked RPC requests to run concurrently (`0` = auto).
     #[arg(long, default_value = "0")]
     pub rpc_parallelism: usize,
 
+    /// Block subranges fetched at the same time within a block-range batch
+    /// (`0` = `--rpc-parallelism`). Failed subranges are fetched again on their own.
+    #[arg(long, env = "TORII_MAX_CONCURRENT_BLOCK_FETCHES", default_value = "0")]
+    pub max_concurrent_block_fetches: usize,
+
+    /// Maximum RPC requests per second shared by all components (`0` = unlimited).
+    #[arg(long, default_value = "0")]
+    pub rpc_rate_limit: u32,
+
+    /// Maximum burst of RPC requests above the rate limit (`0` = one second worth).
+    #[arg(long, default_value = "0")]
+    pub rpc_burst: u32,
+
+    /// Retries of a failed extractor or identification RPC request
+    /// (default: 5 for extraction, 3 for identification).
+    #[arg(long, env = "TORII_RPC_MAX_RETRIES")]
+    pub rpc_max_retries: Option<u32>,
+
+    /// Backoff before the first retry of a failed RPC request, in milliseconds; doubled
+    /// after each attempt (default: 1000 for extraction, 500 for identification).
+    #[arg(long, env = "TORII_RPC_RETRY_BACKOFF_MS")]
+    pub rpc_retry_backoff_ms: Option<u64>,
+
+    /// Upper bound of the RPC retry backoff, in milliseconds
+    /// (default: 60000 for extraction, 10000 for identification).
+    #[arg(long, env = "TORII_RPC_RETRY_MAX_BACKOFF_MS")]
+    pub rpc_retry_max_backoff_ms: Option<u64>,
+
+    /// Random fraction in `[0, 1]` taken off each RPC retry backoff, so that concurrent
+    /// callers do not retry in lockstep (default: 0 for extraction, 0.5 for identification).
+    #[arg(long, env = "TORII_RPC_RETRY_JITTER")]
+    pub rpc_retry_jitter: Option<f64>,
+
+    /// Maximum contracts identified per ETL cycle (`0` = unlimited).
+    ///
+    /// Contracts over the budget are deferred to subsequent cycles.
+    #[arg(long, default_value = "0")]
+    pub identification_budget: usize,
+
+    /// Seconds after which identified contracts are re-checked for class upgrades (`0` = never).
+    #[arg(long, default_value = "0")]
+    pub identification_ttl: u64,
+
+    /// Contracts never identified by auto-discovery, e.g. known spam tokens
+    /// (comma-separated hex addresses). Explicitly mapped contracts are still indexed.
+    #[arg(long, value_delimiter = ',')]
+    pub token_denylist: Vec<String>,
+
+    /// Only these contracts are identified by auto-discovery (comma-separated hex
+    /// addresses; empty = all).
+    #[arg(long, value_delimiter = ',')]
+    pub token_allowlist: Vec<String>,
+
+    /// Additional component variants of the token events (comma-separated
+    /// DECODER:VARIANT, names or hex selectors), for components embedded without
+    /// `#[flat]`. OpenZeppelin's `ERC20Event`, `ERC721Event` and `ERC1155Event` are
+    /// always decoded.
+    ///
+    /// Example: --component-events erc20:TokenEvent,erc721:NftEvent
+    #[arg(long, value_delimiter = ',')]
+    pub component_events: Vec<String>,
+
+    /// Additional names of the token events (comma-separated DECODER:ALIAS=EVENT,
+    /// names or hex selectors); aliased events must have the members of the event.
+    ///
+    /// Example: --event-aliases erc20:TokenTransfer=Transfer
+    #[arg(long, value_delimiter = ',')]
+    pub event_aliases: Vec<String>,
+
+    /// JSON file of address labels (`[{"address", "name", "tags"}]`) upserted at startup.
+    ///
+    /// Labels are attached to ERC20/ERC721 query responses requesting them
+    /// (`include_labels`) and managed at runtime with the `torii.Admin` label RPCs.
+    #[arg(long, env = "TORII_ADDRESS_LABELS")]
+    pub address_labels: Option<PathBuf>,
+
     /// Concurrent workers for async token metadata fetching.
     #[arg(long, default_value = "8")]
     pub metadata_parallelism: usize,
@@ -190,6 +751,23 @@ pub struct Config {
     #[arg(long, default_value = "5")]
     pub metadata_max_retries: u8,
 
+    /// Token metadata lookups kept in the shared metadata cache (`0` = no cache).
+    #[arg(long, env = "TORII_METADATA_CACHE_CAPACITY", default_value = "100000")]
+    pub metadata_cache_capacity: usize,
+
+    /// Seconds a successful metadata lookup stays cached.
+    #[arg(long, env = "TORII_METADATA_CACHE_TTL", default_value = "86400")]
+    pub metadata_cache_ttl: u64,
+
+    /// Seconds a failed metadata lookup (empty metadata, broken URI) is not retried,
+    /// doubled on each consecutive failure up to `--metadata-cache-max-negative-ttl`.
+    #[arg(long, env = "TORII_METADATA_CACHE_NEGATIVE_TTL", default_value = "60")]
+    pub metadata_cache_negative_ttl: u64,
+
+    /// Upper bound in seconds of the failed lookup backoff.
+    #[arg(long, default_value = "21600")]
+    pub metadata_cache_max_negative_ttl: u64,
+
     /// Metadata fetching mode.
     ///
     /// If omitted: defaults to `inline`.
@@ -201,6 +779,58 @@ pub struct Config {
     /// Note: currently implemented as a guarded no-op placeholder.
     #[arg(long)]
     pub metadata_backfill_only: bool,
+
+    /// Upload cached NFT images and metadata to object storage instead of `./data/image-cache`
+    ///
+    /// Objects are content-addressed; GetTokenImage serves signed URLs to them.
+    /// Credentials are read from `TORII_IMAGE_STORE_ACCESS_KEY`/`TORII_IMAGE_STORE_SECRET_KEY`.
+    #[arg(long, value_enum, env = "TORII_IMAGE_STORE")]
+    pub image_store: Option<ImageStoreKind>,
+
+    /// Bucket for cached images (with --image-store)
+    #[arg(long, env = "TORII_IMAGE_STORE_BUCKET")]
+    pub image_store_bucket: Option<String>,
+
+    /// Bucket region (defaults to `us-east-1` for S3, `auto` for GCS)
+    #[arg(long, env = "TORII_IMAGE_STORE_REGION")]
+    pub image_store_region: Option<String>,
+
+    /// Custom endpoint for S3-compatible services (path-style URLs)
+    #[arg(long, env = "TORII_IMAGE_STORE_ENDPOINT")]
+    pub image_store_endpoint: Option<String>,
+
+    /// Key prefix for all uploaded objects
+    #[arg(long, env = "TORII_IMAGE_STORE_PREFIX", default_value = "")]
+    pub image_store_prefix: String,
+
+    /// Access key for the image store
+    #[arg(long, env = "TORII_IMAGE_STORE_ACCESS_KEY", hide_env_values = true)]
+    pub image_store_access_key: Option<String>,
+
+    /// Secret key for the image store
+    #[arg(long, env = "TORII_IMAGE_STORE_SECRET_KEY", hide_env_values = true)]
+    pub image_store_secret_key: Option<String>,
+
+    /// Validity in seconds of the signed URLs returned by GetTokenImage (max 7 days)
+    #[arg(long, env = "TORII_IMAGE_URL_TTL", default_value = "3600")]
+    pub image_url_ttl: u64,
+
+    /// Relay signed offchain messages (`torii.relay.Relay` gRPC service, `relay` topic)
+    ///
+    /// Signatures are checked against the signer's account contract over RPC.
+    #[arg(long, env = "TORII_RELAY")]
+    pub relay: bool,
+
+    /// Index account contract events: owner/signer changes, upgrades and executions
+    /// (`torii.sinks.account.Account` gRPC service)
+    ///
+    /// Accounts are identified by ABI (`__validate__`/`__execute__`) in block-range mode.
+    #[arg(long, env = "TORII_ACCOUNTS")]
+    pub accounts: bool,
+
+    /// Set by the `backfill` subcommand.
+    #[arg(skip)]
+    pub backfill: bool,
 }
 
 impl Config {
@@ -209,6 +839,30 @@ impl Config {
         Felt::from_hex(addr).map_err(|e| anyhow::anyhow!("Invalid address {addr}: {e}"))
     }
 
+    /// Parsed `--erc20-watched-accounts` addresses
+    pub fn erc20_watched_accounts(&self) -> Result<Vec<Felt>> {
+        anyhow::ensure!(
+            self.erc20_watched_accounts.is_empty()
+                || self.erc20_balance_scope == Erc20BalanceScope::Watched,
+            "--erc20-watched-accounts requires --erc20-balance-scope watched"
+        );
+        self.erc20_watched_accounts
+            .iter()
+            .map(|addr| Self::parse_address(addr.trim()))
+            .collect()
+    }
+
+    /// Parsed `--token-denylist` and `--token-allowlist` addresses
+    pub fn token_lists(&self) -> Result<(Vec<Felt>, Vec<Felt>)> {
+        let parse = |addresses: &[String]| {
+            addresses
+                .iter()
+                .map(|addr| Self::parse_address(addr.trim()))
+                .collect::<Result<Vec<_>>>()
+        };
+        Ok((parse(&self.token_denylist)?, parse(&self.token_allowlist)?))
+    }
+
     /// Get well-known ERC20 contracts (ETH, STRK)
     pub fn well_known_erc20_contracts() -> Vec<(Felt, &'static str)> {
         vec![
@@ -227,6 +881,274 @@ impl Config {
         ]
     }
 
+    /// Adaptive batch sizing configuration, if enabled
+    pub fn adaptive_batch_config(&self) -> Option<AdaptiveBatchConfig> {
+        self.adaptive_batch_size.then(|| {
+            AdaptiveBatchConfig::new(
+                self.adaptive_batch_min,
+                self.adaptive_batch_max,
+                Duration::from_millis(self.adaptive_target_cycle_ms),
+            )
+        })
+    }
+
+    /// `base` retry policy with the `--rpc-max-retries` / `--rpc-retry-*` overrides applied
+    pub fn retry_policy(&self, base: RetryPolicy) -> Result<RetryPolicy> {
+        let mut policy = base;
+        if let Some(max_retries) = self.rpc_max_retries {
+            policy.max_retries = max_retries;
+        }
+        if let Some(backoff) = self.rpc_retry_backoff_ms {
+            policy.initial_backoff = Duration::from_millis(backoff);
+        }
+        if let Some(max_backoff) = self.rpc_retry_max_backoff_ms {
+            policy.max_backoff = Duration::from_millis(max_backoff);
+        }
+        if let Some(jitter) = self.rpc_retry_jitter {
+            if !(0.0..=1.0).contains(&jitter) {
+                bail!("--rpc-retry-jitter must be between 0 and 1, got {jitter}");
+            }
+            policy = policy.with_jitter(jitter);
+        }
+        if policy.initial_backoff > policy.max_backoff {
+            bail!(
+                "RPC retry backoff ({:?}) exceeds the maximum backoff ({:?})",
+                policy.initial_backoff,
+                policy.max_backoff
+            );
+        }
+        Ok(policy)
+    }
+
+    /// TLS configuration for the HTTP/gRPC listener, if enabled
+    pub fn tls_config(&self) -> Result<Option<torii::ToriiTlsConfig>> {
+        match (&self.tls_cert, &self.tls_key) {
+            (Some(cert), Some(key)) => {
+                Ok(Some(torii::ToriiTlsConfig::new(cert.clone(), key.clone())))
+            }
+            (None, None) => Ok(None),
+            _ => bail!("--tls-cert and --tls-key must be provided together"),
+        }
+    }
+
+    /// Object storage configuration for cached images, if `--image-store` is set
+    pub fn image_store_config(&self) -> Result<Option<ObjectStoreConfig>> {
+        let Some(kind) = &self.image_store else {
+            return Ok(None);
+        };
+        let (Some(bucket), Some(access_key), Some(secret_key)) = (
+            &self.image_store_bucket,
+            &self.image_store_access_key,
+            &self.image_store_secret_key,
+        ) else {
+            bail!("--image-store requires --image-store-bucket and image store credentials");
+        };
+        let provider = match kind {
+            ImageStoreKind::S3 => ObjectStoreProvider::S3,
+            ImageStoreKind::Gcs => ObjectStoreProvider::Gcs,
+        };
+        let mut store = ObjectStoreConfig::new(provider, bucket, access_key, secret_key)
+            .with_prefix(&self.image_store_prefix);
+        if let Some(region) = &self.image_store_region {
+            store = store.with_region(region);
+        }
+        if let Some(endpoint) = &self.image_store_endpoint {
+            store = store.with_endpoint(endpoint);
+        }
+        Ok(Some(store))
+    }
+
+    /// Parsed `--price-pairs` mappings (token, Pragma pair)
+    pub fn price_pairs(&self) -> Result<Vec<(Felt, String)>> {
+        self.price_pairs
+            .iter()
+            .map(|mapping| {
+                let Some((token, pair)) = mapping.split_once('=') else {
+                    bail!("Invalid price pair {mapping}: expected TOKEN=PAIR");
+                };
+                Ok((Self::parse_address(token.trim())?, pair.trim().to_string()))
+            })
+            .collect()
+    }
+
+    /// Selector aliases per token decoder, from `--component-events` and `--event-aliases`.
+    pub fn selector_aliases(&self) -> Result<HashMap<String, SelectorAliases>> {
+        let mut aliases: HashMap<String, SelectorAliases> = HashMap::new();
+        for mapping in &self.component_events {
+            let Some((decoder, variant)) = mapping.split_once(':') else {
+                bail!("Invalid component event {mapping}: expected DECODER:VARIANT");
+            };
+            let decoder = Self::token_decoder(decoder, mapping)?;
+            let entry = aliases.entry(decoder).or_default();
+            *entry = std::mem::take(entry).with_component(Self::parse_selector(variant)?);
+        }
+        for mapping in &self.event_aliases {
+            let Some((decoder, alias)) = mapping.split_once(':') else {
+                bail!("Invalid event alias {mapping}: expected DECODER:ALIAS=EVENT");
+            };
+            let Some((alias, event)) = alias.split_once('=') else {
+                bail!("Invalid event alias {mapping}: expected DECODER:ALIAS=EVENT");
+            };
+            let decoder = Self::token_decoder(decoder, mapping)?;
+            let entry = aliases.entry(decoder).or_default();
+            *entry = std::mem::take(entry)
+                .with_alias(Self::parse_selector(alias)?, Self::parse_selector(event)?);
+        }
+        Ok(aliases)
+    }
+
+    fn token_decoder(decoder: &str, mapping: &str) -> Result<String> {
+        let decoder = decoder.trim().to_lowercase();
+        if !matches!(decoder.as_str(), "erc20" | "erc721" | "erc1155") {
+            bail!("Invalid decoder in {mapping}: expected erc20, erc721 or erc1155");
+        }
+        Ok(decoder)
+    }
+
+    /// Event selector from a hex selector or an event name.
+    fn parse_selector(value: &str) -> Result<Felt> {
+        let value = value.trim();
+        if value.starts_with("0x") {
+            return Self::parse_address(value);
+        }
+        get_selector_from_name(value)
+            .map_err(|e| anyhow::anyhow!("Invalid event name {value}: {e}"))
+    }
+
+    /// Parsed `--sink-timeouts`
+    pub fn sink_timeouts(&self) -> Result<Vec<(String, Duration)>> {
+        self.sink_timeouts
+            .iter()
+            .map(|mapping| {
+                let Some((sink, seconds)) = mapping.split_once('=') else {
+                    bail!("Invalid sink timeout {mapping}: expected SINK=SECONDS");
+                };
+                let seconds: u64 = seconds
+                    .trim()
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid sink timeout {mapping}: {e}"))?;
+                Ok((sink.trim().to_string(), Duration::from_secs(seconds)))
+            })
+            .collect()
+    }
+
+    /// Contract filters of the sinks parsed from `--sink-allow-contracts` and
+    /// `--sink-deny-contracts`, by sink name.
+    pub fn sink_contract_filters(&self) -> Result<HashMap<String, SinkContractFilter>> {
+        let mut filters: HashMap<String, SinkContractFilter> = HashMap::new();
+        for (mappings, allow) in [
+            (&self.sink_allow_contracts, true),
+            (&self.sink_deny_contracts, false),
+        ] {
+            for mapping in mappings {
+                let Some((sink, contracts)) = mapping.split_once('=') else {
+                    bail!("Invalid sink contracts {mapping}: expected SINK=0xA[:0xB...]");
+                };
+                let contracts = contracts
+                    .split(':')
+                    .map(|contract| Self::parse_address(contract.trim()))
+                    .collect::<Result<Vec<_>>>()?;
+                let filter = filters.remove(sink.trim()).unwrap_or_default();
+                let filter = if allow {
+                    filter.allow_contracts(contracts)
+                } else {
+                    filter.deny_contracts(contracts)
+                };
+                filters.insert(sink.trim().to_string(), filter);
+            }
+        }
+        for filter in filters.values() {
+            filter.validate()?;
+        }
+        Ok(filters)
+    }
+
+    /// Namespaces parsed from `--sink-namespaces` and `--api-keys`.
+    pub fn namespaces(&self) -> Result<Namespaces> {
+        let mut namespaces = Namespaces::new();
+        for mapping in &self.sink_namespaces {
+            let Some((sink, namespace)) = mapping.split_once('=') else {
+                bail!("Invalid sink namespace {mapping}: expected SINK=NAMESPACE");
+            };
+            namespaces = namespaces.with_sink(sink.trim(), namespace.trim());
+        }
+        for mapping in &self.api_keys {
+            let Some((key, permitted)) = mapping.split_once('=') else {
+                bail!("Invalid API key mapping: expected KEY=NAMESPACE[:NAMESPACE...]");
+            };
+            namespaces = namespaces.with_api_key(key.trim(), permitted.split(':').map(str::trim));
+        }
+        Ok(namespaces)
+    }
+
+    /// Compression and message size limits of every gRPC service.
+    pub fn grpc_options(&self) -> GrpcServerOptions {
+        let mut options = GrpcServerOptions::default();
+        match self.grpc_compression {
+            GrpcCompression::None => {}
+            GrpcCompression::Gzip => {
+                options = options.with_send_compression(CompressionEncoding::Gzip);
+            }
+            GrpcCompression::Zstd => {
+                options = options.with_send_compression(CompressionEncoding::Zstd);
+            }
+        }
+        if let Some(limit) = self.grpc_max_decoding_message_size {
+            options = options.with_max_decoding_message_size(limit);
+        }
+        if let Some(limit) = self.grpc_max_encoding_message_size {
+            options = options.with_max_encoding_message_size(limit);
+        }
+        options
+    }
+
+    /// Interval of the SQLite storage maintenance, if enabled.
+    pub fn sqlite_maintenance_interval(&self) -> Option<Duration> {
+        (self.sqlite_maintenance_interval > 0)
+            .then(|| Duration::from_secs(self.sqlite_maintenance_interval))
+    }
+
+    /// Shared token metadata cache settings, unless disabled (`--metadata-cache-capacity 0`).
+    pub fn metadata_cache_config(&self) -> Option<MetadataCacheConfig> {
+        (self.metadata_cache_capacity > 0).then(|| {
+            MetadataCacheConfig::default()
+                .with_capacity(self.metadata_cache_capacity)
+                .with_ttl(Duration::from_secs(self.metadata_cache_ttl))
+                .with_negative_ttl(
+                    Duration::from_secs(self.metadata_cache_negative_ttl),
+                    Duration::from_secs(self.metadata_cache_max_negative_ttl),
+                )
+        })
+    }
+
+    /// Work sharding configuration, if `--work-shards` is set.
+    pub fn sharding_config(&self) -> Result<Option<ShardingConfig>> {
+        if self.work_shards == 0 {
+            return Ok(None);
+        }
+        if self.mode != ExtractionMode::Event {
+            bail!("--work-shards requires --mode event (contracts are split between instances)");
+        }
+        if !self
+            .database_url
+            .as_deref()
+            .is_some_and(|url| url.starts_with("postgres://") || url.starts_with("postgresql://"))
+        {
+            bail!("--work-shards requires a shared PostgreSQL --database-url");
+        }
+        if self.work_shards_per_instance == 0 || self.work_shard_lease_ttl == 0 {
+            bail!("--work-shards-per-instance and --work-shard-lease-ttl must be positive");
+        }
+
+        let mut config = ShardingConfig::new(self.work_shards);
+        config.max_shards = self.work_shards_per_instance;
+        config.lease_ttl = Duration::from_secs(self.work_shard_lease_ttl);
+        if let Some(instance_id) = &self.instance_id {
+            config.instance_id.clone_from(instance_id);
+        }
+        Ok(Some(config))
+    }
+
     /// Check if any token types are configured
     pub fn has_tokens(&self) -> bool {
         !self.erc20.is_empty()
@@ -240,6 +1162,7 @@ impl Config {
 mod tests {
     use super::*;
     use clap::Parser;
+    use std::collections::HashSet;
 
     #[test]
     fn observability_defaults_to_disabled() {
@@ -261,6 +1184,12 @@ mod tests {
             "4",
             "--rpc-parallelism",
             "6",
+            "--max-concurrent-block-fetches",
+            "3",
+            "--rpc-rate-limit",
+            "50",
+            "--rpc-burst",
+            "100",
             "--metadata-parallelism",
             "12",
             "--metadata-queue-capacity",
@@ -270,14 +1199,552 @@ mod tests {
         ]);
         assert_eq!(cfg.max_prefetch_batches, 4);
         assert_eq!(cfg.rpc_parallelism, 6);
+        assert_eq!(cfg.max_concurrent_block_fetches, 3);
+        assert_eq!(cfg.rpc_rate_limit, 50);
+        assert_eq!(cfg.rpc_burst, 100);
         assert_eq!(cfg.metadata_parallelism, 12);
         assert_eq!(cfg.metadata_queue_capacity, 4096);
         assert_eq!(cfg.metadata_max_retries, 5);
     }
 
+    #[test]
+    fn metadata_cache_flags_parse() {
+        let cfg = Config::parse_from(["torii-tokens"]);
+        assert_eq!(
+            cfg.metadata_cache_config(),
+            Some(MetadataCacheConfig::default())
+        );
+
+        let cfg = Config::parse_from([
+            "torii-tokens",
+            "--metadata-cache-capacity",
+            "500",
+            "--metadata-cache-ttl",
+            "3600",
+            "--metadata-cache-negative-ttl",
+            "30",
+            "--metadata-cache-max-negative-ttl",
+            "600",
+        ]);
+        assert_eq!(
+            cfg.metadata_cache_config(),
+            Some(
+                MetadataCacheConfig::default()
+                    .with_capacity(500)
+                    .with_ttl(Duration::from_secs(3600))
+                    .with_negative_ttl(Duration::from_secs(30), Duration::from_secs(600))
+            )
+        );
+
+        let cfg = Config::parse_from(["torii-tokens", "--metadata-cache-capacity", "0"]);
+        assert!(cfg.metadata_cache_config().is_none());
+    }
+
+    #[test]
+    fn adaptive_batch_flags_parse() {
+        let cfg = Config::parse_from(["torii-tokens"]);
+        assert!(cfg.adaptive_batch_config().is_none());
+
+        let cfg = Config::parse_from([
+            "torii-tokens",
+            "--adaptive-batch-size",
+            "--adaptive-batch-min",
+            "20",
+            "--adaptive-target-cycle-ms",
+            "2000",
+        ]);
+        let adaptive = cfg.adaptive_batch_config().unwrap();
+        assert_eq!(adaptive.min_batch_size, 20);
+        assert_eq!(adaptive.max_batch_size, 1000);
+        assert_eq!(adaptive.target_cycle_time, Duration::from_secs(2));
+    }
+
+    #[test]
+    fn retry_policy_flags_override_the_base_policy() {
+        let cfg = Config::parse_from(["torii-tokens"]);
+        let policy = cfg.retry_policy(RetryPolicy::default()).unwrap();
+        assert_eq!(policy.max_retries, RetryPolicy::default().max_retries);
+        assert_eq!(policy.jitter, 0.0);
+
+        let cfg = Config::parse_from([
+            "torii-tokens",
+            "--rpc-max-retries",
+            "8",
+            "--rpc-retry-backoff-ms",
+            "250",
+            "--rpc-retry-jitter",
+            "0.2",
+        ]);
+        let policy = cfg.retry_policy(RetryPolicy::default()).unwrap();
+        assert_eq!(policy.max_retries, 8);
+        assert_eq!(policy.initial_backoff, Duration::from_millis(250));
+        assert_eq!(policy.max_backoff, RetryPolicy::default().max_backoff);
+        assert_eq!(policy.jitter, 0.2);
+
+        let cfg = Config::parse_from(["torii-tokens", "--rpc-retry-jitter", "1.5"]);
+        assert!(cfg.retry_policy(RetryPolicy::default()).is_err());
+        let cfg = Config::parse_from(["torii-tokens", "--rpc-retry-max-backoff-ms", "100"]);
+        assert!(cfg.retry_policy(RetryPolicy::default()).is_err());
+    }
+
+    #[test]
+    fn price_feed_flags_parse() {
+        let cfg = Config::parse_from(["torii-tokens"]);
+        assert!(cfg.price_feed.is_none());
+        assert_eq!(cfg.price_window_blocks, 100);
+
+        let cfg = Config::parse_from([
+            "torii-tokens",
+            "--price-feed",
+            "pragma",
+            "--pragma-oracle",
+            "0x123",
+            "--price-pairs",
+            "0x49d=ETH/USD, 0x471=STRK/USD",
+        ]);
+        assert_eq!(cfg.price_feed, Some(PriceFeedKind::Pragma));
+        let pairs = cfg.price_pairs().unwrap();
+        assert_eq!(pairs.len(), 2);
+        assert_eq!(pairs[0], (Felt::from(0x49d_u64), "ETH/USD".to_string()));
+        assert_eq!(pairs[1], (Felt::from(0x471_u64), "STRK/USD".to_string()));
+
+        let cfg = Config::parse_from(["torii-tokens", "--price-pairs", "0x49d"]);
+        assert!(cfg.price_pairs().is_err());
+    }
+
+    #[test]
+    fn token_list_and_label_flags_parse() {
+        let cfg = Config::parse_from(["torii-tokens"]);
+        assert_eq!(cfg.token_lists().unwrap(), (Vec::new(), Vec::new()));
+        assert!(cfg.address_labels.is_none());
+
+        let cfg = Config::parse_from([
+            "torii-tokens",
+            "--token-denylist",
+            "0x10, 0x20",
+            "--token-allowlist",
+            "0x30",
+            "--address-labels",
+            "./labels.json",
+        ]);
+        let (denylist, allowlist) = cfg.token_lists().unwrap();
+        assert_eq!(denylist, vec![Felt::from(0x10_u64), Felt::from(0x20_u64)]);
+        assert_eq!(allowlist, vec![Felt::from(0x30_u64)]);
+        assert_eq!(cfg.address_labels, Some(PathBuf::from("./labels.json")));
+
+        let cfg = Config::parse_from(["torii-tokens", "--token-denylist", "spam"]);
+        assert!(cfg.token_lists().is_err());
+    }
+
+    #[test]
+    fn sink_worker_flags_parse() {
+        let cfg = Config::parse_from(["torii-tokens"]);
+        assert_eq!(cfg.max_concurrent_sinks, 0);
+        assert_eq!(cfg.sink_backpressure_threshold, 0);
+        assert!(cfg.sink_timeouts().unwrap().is_empty());
+        assert_eq!(cfg.dedupe_window, 0);
+
+        let cfg = Config::parse_from([
+            "torii-tokens",
+            "--max-concurrent-sinks",
+            "2",
+            "--sink-backpressure-threshold",
+            "4",
+            "--sink-timeouts",
+            "erc721=30, erc1155=45",
+            "--dedupe-window",
+            "100000",
+        ]);
+        assert_eq!(cfg.max_concurrent_sinks, 2);
+        assert_eq!(cfg.sink_backpressure_threshold, 4);
+        assert_eq!(cfg.dedupe_window, 100_000);
+        assert_eq!(
+            cfg.sink_timeouts().unwrap(),
+            vec![
+                ("erc721".to_string(), Duration::from_secs(30)),
+                ("erc1155".to_string(), Duration::from_secs(45)),
+            ]
+        );
+
+        let cfg = Config::parse_from(["torii-tokens", "--sink-timeouts", "erc721"]);
+        assert!(cfg.sink_timeouts().is_err());
+    }
+
+    #[test]
+    fn sink_contract_flags_parse() {
+        let cfg = Config::parse_from(["torii-tokens"]);
+        assert!(cfg.sink_contract_filters().unwrap().is_empty());
+
+        let cfg = Config::parse_from([
+            "torii-tokens",
+            "--sink-allow-contracts",
+            "erc20=0x1:0x2, erc721=0x3",
+            "--sink-deny-contracts",
+            "erc721=0x4",
+        ]);
+        let filters = cfg.sink_contract_filters().unwrap();
+        assert_eq!(filters.len(), 2);
+        assert_eq!(
+            filters["erc20"].allowlist,
+            HashSet::from([Felt::from(1u64), Felt::from(2u64)])
+        );
+        assert!(filters["erc20"].denylist.is_empty());
+        assert_eq!(
+            filters["erc721"].allowlist,
+            HashSet::from([Felt::from(3u64)])
+        );
+        assert_eq!(
+            filters["erc721"].denylist,
+            HashSet::from([Felt::from(4u64)])
+        );
+
+        let cfg = Config::parse_from([
+            "torii-tokens",
+            "--sink-allow-contracts",
+            "erc20=0x1",
+            "--sink-deny-contracts",
+            "erc20=0x1",
+        ]);
+        assert!(cfg.sink_contract_filters().is_err());
+        let cfg = Config::parse_from(["torii-tokens", "--sink-deny-contracts", "erc20"]);
+        assert!(cfg.sink_contract_filters().is_err());
+        let cfg = Config::parse_from(["torii-tokens", "--sink-deny-contracts", "erc20=zz"]);
+        assert!(cfg.sink_contract_filters().is_err());
+    }
+
+    #[test]
+    fn namespace_flags_parse() {
+        use torii::tonic::metadata::MetadataMap;
+
+        let cfg = Config::parse_from(["torii-tokens"]);
+        let namespaces = cfg.namespaces().unwrap();
+        assert!(!namespaces.is_required());
+        assert!(!namespaces.requires_api_key());
+
+        let cfg = Config::parse_from([
+            "torii-tokens",
+            "--sink-namespaces",
+            "erc20=game-a, erc721=game-b",
+            "--api-keys",
+            "secret=game-a:game-b",
+        ]);
+        let namespaces = cfg.namespaces().unwrap();
+        assert_eq!(namespaces.namespace_of("erc721"), "game-b");
+        assert_eq!(namespaces.namespace_of("erc1155"), "default");
+        let mut metadata = MetadataMap::new();
+        metadata.insert("x-api-key", "secret".parse().unwrap());
+        assert_eq!(namespaces.authorize(&metadata, "game-b").unwrap(), "game-b");
+        assert!(namespaces.authorize(&metadata, "default").is_err());
+
+        let cfg = Config::parse_from(["torii-tokens", "--api-keys", "secret"]);
+        assert!(cfg.namespaces().is_err());
+    }
+
+    #[test]
+    fn startup_consistency_flag_parses() {
+        let cfg = Config::parse_from(["torii-tokens"]);
+        assert_eq!(cfg.startup_consistency, StartupConsistencyMode::Off);
+
+        let cfg = Config::parse_from(["torii-tokens", "--startup-consistency", "rewind"]);
+        assert_eq!(
+            StartupConsistency::from(cfg.startup_consistency),
+            StartupConsistency::Rewind
+        );
+    }
+
+    #[test]
+    fn work_sharding_requires_event_mode_and_postgres() {
+        let cfg = Config::parse_from(["torii-tokens"]);
+        assert!(cfg.sharding_config().unwrap().is_none());
+
+        let cfg = Config::parse_from(["torii-tokens", "--work-shards", "4"]);
+        assert!(cfg.sharding_config().is_err());
+
+        let postgres = "postgres://torii@localhost/torii";
+        let cfg = Config::parse_from([
+            "torii-tokens",
+            "--mode",
+            "event",
+            "--database-url",
+            postgres,
+            "--work-shards",
+            "4",
+            "--work-shards-per-instance",
+            "2",
+            "--instance-id",
+            "indexer-a",
+        ]);
+        let sharding = cfg.sharding_config().unwrap().unwrap();
+        assert_eq!(sharding.shard_count, 4);
+        assert_eq!(sharding.max_shards, 2);
+        assert_eq!(sharding.instance_id, "indexer-a");
+    }
+
+    #[test]
+    fn replay_flags_parse() {
+        let cfg = Config::parse_from(["torii-tokens"]);
+        assert!(!cfg.archive_events);
+        assert_eq!(cfg.replay_from_block, None);
+
+        let cfg = Config::parse_from([
+            "torii-tokens",
+            "--archive-events",
+            "--replay-from-block",
+            "100",
+            "--replay-to-block",
+            "200",
+        ]);
+        assert!(cfg.archive_events);
+        assert_eq!(cfg.replay_from_block, Some(100));
+        assert_eq!(cfg.replay_to_block, Some(200));
+
+        assert!(Config::try_parse_from(["torii-tokens", "--replay-to-block", "200"]).is_err());
+    }
+
+    #[test]
+    fn relay_flag_parses() {
+        assert!(!Config::parse_from(["torii-tokens"]).relay);
+        assert!(Config::parse_from(["torii-tokens", "--relay"]).relay);
+    }
+
+    #[test]
+    fn accounts_flag_parses() {
+        assert!(!Config::parse_from(["torii-tokens"]).accounts);
+        assert!(Config::parse_from(["torii-tokens", "--accounts"]).accounts);
+    }
+
+    #[test]
+    fn sqlite_maintenance_interval_parses() {
+        let cfg = Config::parse_from(["torii-tokens"]);
+        assert_eq!(
+            cfg.sqlite_maintenance_interval(),
+            Some(Duration::from_secs(3600))
+        );
+
+        let cfg = Config::parse_from(["torii-tokens", "--sqlite-maintenance-interval", "0"]);
+        assert_eq!(cfg.sqlite_maintenance_interval(), None);
+    }
+
+    #[test]
+    fn storage_shards_flag_parses() {
+        let cfg = Config::parse_from(["torii-tokens"]);
+        assert_eq!(cfg.storage_shards, 1);
+
+        let cfg = Config::parse_from(["torii-tokens", "--storage-shards", "4"]);
+        assert_eq!(cfg.storage_shards, 4);
+    }
+
+    #[test]
+    fn image_store_flags_parse() {
+        let cfg = Config::parse_from(["torii-tokens"]);
+        assert!(cfg.image_store_config().unwrap().is_none());
+        assert_eq!(cfg.image_url_ttl, 3600);
+
+        let cfg = Config::parse_from([
+            "torii-tokens",
+            "--image-store",
+            "gcs",
+            "--image-store-bucket",
+            "nft-cache",
+            "--image-store-prefix",
+            "mainnet",
+            "--image-store-access-key",
+            "GOOG1",
+            "--image-store-secret-key",
+            "secret",
+        ]);
+        let store = cfg.image_store_config().unwrap().unwrap();
+        assert_eq!(store.provider, ObjectStoreProvider::Gcs);
+        assert_eq!(store.bucket, "nft-cache");
+        assert_eq!(store.region, "auto");
+        assert_eq!(store.prefix, "mainnet/");
+
+        let cfg = Config::parse_from(["torii-tokens", "--image-store", "s3"]);
+        assert!(cfg.image_store_config().is_err());
+    }
+
+    #[test]
+    fn grpc_options_from_flags() {
+        assert_eq!(
+            Config::parse_from(["torii-tokens"]).grpc_options(),
+            GrpcServerOptions::default()
+        );
+        let cfg = Config::parse_from([
+            "torii-tokens",
+            "--grpc-compression",
+            "zstd",
+            "--grpc-max-decoding-message-size",
+            "16777216",
+        ]);
+        let options = cfg.grpc_options();
+        assert_eq!(options.send_compression, Some(CompressionEncoding::Zstd));
+        assert_eq!(options.max_decoding_message_size, Some(16_777_216));
+        assert_eq!(options.max_encoding_message_size, None);
+    }
+
+    #[test]
+    fn consolidate_event_cursors_is_opt_in() {
+        assert!(!Config::parse_from(["torii-tokens"]).consolidate_event_cursors);
+        let cfg = Config::parse_from([
+            "torii-tokens",
+            "--mode",
+            "block-range",
+            "--consolidate-event-cursors",
+        ]);
+        assert!(cfg.consolidate_event_cursors);
+    }
+
+    #[test]
+    fn selector_aliases_from_flags() {
+        assert!(Config::parse_from(["torii-tokens"])
+            .selector_aliases()
+            .unwrap()
+            .is_empty());
+
+        let cfg = Config::parse_from([
+            "torii-tokens",
+            "--component-events",
+            "erc20:TokenEvent,ERC721:0x1234",
+            "--event-aliases",
+            "erc20:TokenTransfer=Transfer",
+        ]);
+        let aliases = cfg.selector_aliases().unwrap();
+        assert_eq!(
+            aliases["erc20"],
+            SelectorAliases::new()
+                .with_component(get_selector_from_name("TokenEvent").unwrap())
+                .with_alias(
+                    get_selector_from_name("TokenTransfer").unwrap(),
+                    get_selector_from_name("Transfer").unwrap()
+                )
+        );
+        assert_eq!(
+            aliases["erc721"],
+            SelectorAliases::new().with_component(Felt::from(0x1234u64))
+        );
+
+        for invalid in [
+            ["--component-events", "TokenEvent"],
+            ["--component-events", "erc4626:TokenEvent"],
+            ["--event-aliases", "erc20:TokenTransfer"],
+        ] {
+            let cfg = Config::parse_from(std::iter::once("torii-tokens").chain(invalid));
+            assert!(cfg.selector_aliases().is_err(), "{invalid:?}");
+        }
+    }
+
+    #[test]
+    fn subcommands_parse() {
+        let Command::Index { config } =
+            Cli::parse_from(["torii-tokens", "--from-block", "5"]).command()
+        else {
+            panic!("expected index");
+        };
+        assert_eq!(config.from_block, 5);
+        assert!(!config.backfill);
+
+        let Command::Backfill { config } = Cli::parse_from([
+            "torii-tokens",
+            "backfill",
+            "--from-block",
+            "10",
+            "--to-block",
+            "20",
+        ])
+        .command() else {
+            panic!("expected backfill");
+        };
+        assert_eq!((config.from_block, config.to_block), (10, Some(20)));
+
+        let Command::Verify(args) = Cli::parse_from(["torii-tokens", "verify"]).command() else {
+            panic!("expected verify");
+        };
+        assert_eq!(args.sample, 100);
+        assert_eq!(args.standards().len(), 3);
+        let Command::Verify(args) =
+            Cli::parse_from(["torii-tokens", "verify", "--standards", "erc20,erc1155"]).command()
+        else {
+            panic!("expected verify");
+        };
+        assert_eq!(args.standards(), vec![Standard::Erc20, Standard::Erc1155]);
+
+        let Command::Export(args) = Cli::parse_from([
+            "torii-tokens",
+            "export",
+            "--standard",
+            "erc721",
+            "--format",
+            "jsonl",
+            "-o",
+            "out.jsonl",
+        ])
+        .command() else {
+            panic!("expected export");
+        };
+        assert_eq!(args.standard, Standard::Erc721);
+        assert_eq!(args.format, ExportFileFormat::Jsonl);
+        assert_eq!(args.output, Some(PathBuf::from("out.jsonl")));
+        assert!(Cli::try_parse_from(["torii-tokens", "export"]).is_err());
+    }
+
+    #[test]
+    fn dry_run_flag_parses() {
+        assert!(!Config::parse_from(["torii-tokens"]).dry_run);
+        assert!(Config::parse_from(["torii-tokens", "--dry-run"]).dry_run);
+    }
+
+    #[test]
+    fn light_flag_parses() {
+        assert!(!Config::parse_from(["torii-tokens"]).light);
+        let cfg = Config::parse_from(["torii-tokens", "--light", "--from-block", "100"]);
+        assert!(cfg.light);
+        assert_eq!(cfg.from_block, 100);
+    }
+
     #[test]
     fn supports_global_event_mode() {
         let cfg = Config::parse_from(["torii-tokens", "--mode", "global-event"]);
         assert_eq!(cfg.mode, ExtractionMode::GlobalEvent);
     }
+
+    #[test]
+    fn tls_flags_require_both_cert_and_key() {
+        let cfg = Config::parse_from([
+            "torii-tokens",
+            "--tls-cert",
+            "./certs/cert.pem",
+            "--tls-key",
+            "./certs/key.pem",
+        ]);
+        let tls = cfg.tls_config().unwrap().unwrap();
+        assert_eq!(tls.cert_path, PathBuf::from("./certs/cert.pem"));
+        assert_eq!(tls.key_path, PathBuf::from("./certs/key.pem"));
+
+        let cfg = Config::parse_from(["torii-tokens", "--tls-cert", "./certs/cert.pem"]);
+        assert!(cfg.tls_config().is_err());
+        assert!(Config::parse_from(["torii-tokens"])
+            .tls_config()
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn watched_accounts_require_the_watched_scope() {
+        let cfg = Config::parse_from([
+            "torii-tokens",
+            "--erc20-balance-scope",
+            "watched",
+            "--erc20-watched-accounts",
+            "0x1,0x2",
+        ]);
+        assert_eq!(cfg.erc20_balance_scope, Erc20BalanceScope::Watched);
+        assert_eq!(
+            cfg.erc20_watched_accounts().unwrap(),
+            vec![Felt::from(1u64), Felt::from(2u64)]
+        );
+
+        let cfg = Config::parse_from(["torii-tokens", "--erc20-watched-accounts", "0x1"]);
+        assert_eq!(cfg.erc20_balance_scope, Erc20BalanceScope::All);
+        assert!(cfg.erc20_watched_accounts().is_err());
+    }
 }
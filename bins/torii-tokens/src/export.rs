@@ -0,0 +1,183 @@
+//! `export` subcommand: dumps the stored transfers of one token standard.
+//!
+//! Rows are the ones of the `/<standard>/transfers/export` HTTP endpoints, read page
+//! by page off the storage cursors, newest first.
+
+use anyhow::{Context, Result};
+use starknet::core::types::Felt;
+use std::future::Future;
+use std::io::Write;
+use std::path::Path;
+use torii_common::export::{encode_records, EXPORT_PAGE_SIZE};
+use torii_common::{ExportFormat, ExportRecord};
+use torii_erc1155::ShardedErc1155Storage;
+use torii_erc20::storage::TransferDirection;
+use torii_erc20::ShardedErc20Storage;
+use torii_erc721::ShardedErc721Storage;
+use torii_runtime_common::database::resolve_token_db_setup;
+
+use crate::config::{Config, ExportArgs, Standard};
+
+pub async fn run(args: ExportArgs) -> Result<()> {
+    let config = &args.config;
+    let contract = args
+        .contract
+        .as_deref()
+        .map(Config::parse_address)
+        .transpose()?;
+    let address = args
+        .address
+        .as_deref()
+        .map(Config::parse_address)
+        .transpose()?;
+    let tokens: Vec<Felt> = contract.into_iter().collect();
+    let format = ExportFormat::from(args.format);
+    let (from_block, to_block) = (Some(config.from_block), config.to_block);
+
+    let db_setup = resolve_token_db_setup(
+        Path::new(&config.db_dir),
+        config.database_url.as_deref(),
+        config.storage_database_url.as_deref(),
+    )?;
+    let mut out: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(std::io::BufWriter::new(
+            std::fs::File::create(path)
+                .with_context(|| format!("Failed to create {}", path.display()))?,
+        )),
+        None => Box::new(std::io::stdout().lock()),
+    };
+
+    let rows = match args.standard {
+        Standard::Erc20 => {
+            let storage =
+                ShardedErc20Storage::open(&db_setup.erc20_url, config.storage_shards).await?;
+            write_pages(&mut out, format, |cursor| {
+                let storage = &storage;
+                let tokens = &tokens;
+                async move {
+                    let (transfers, next) = storage
+                        .get_transfers_filtered(
+                            address,
+                            None,
+                            None,
+                            tokens,
+                            TransferDirection::All,
+                            from_block,
+                            to_block,
+                            cursor,
+                            EXPORT_PAGE_SIZE,
+                        )
+                        .await?;
+                    Ok((
+                        transfers
+                            .into_iter()
+                            .map(torii_erc20::api::TransferRow::from)
+                            .collect(),
+                        next,
+                    ))
+                }
+            })
+            .await?
+        }
+        Standard::Erc721 => {
+            let storage =
+                ShardedErc721Storage::open(&db_setup.erc721_url, config.storage_shards).await?;
+            write_pages(&mut out, format, |cursor| {
+                let storage = &storage;
+                let tokens = &tokens;
+                async move {
+                    let (transfers, next) = storage
+                        .get_transfers_filtered(
+                            address,
+                            None,
+                            None,
+                            tokens,
+                            &[],
+                            from_block,
+                            to_block,
+                            cursor,
+                            EXPORT_PAGE_SIZE,
+                        )
+                        .await?;
+                    Ok((
+                        transfers
+                            .into_iter()
+                            .map(torii_erc721::api::TransferRow::from)
+                            .collect(),
+                        next,
+                    ))
+                }
+            })
+            .await?
+        }
+        Standard::Erc1155 => {
+            let storage =
+                ShardedErc1155Storage::open(&db_setup.erc1155_url, config.storage_shards).await?;
+            write_pages(&mut out, format, |cursor| {
+                let storage = &storage;
+                let tokens = &tokens;
+                async move {
+                    let (transfers, next) = storage
+                        .get_transfers_filtered(
+                            address,
+                            None,
+                            None,
+                            None,
+                            tokens,
+                            &[],
+                            from_block,
+                            to_block,
+                            cursor,
+                            EXPORT_PAGE_SIZE,
+                        )
+                        .await?;
+                    Ok((
+                        transfers
+                            .into_iter()
+                            .map(torii_erc1155::api::TransferRow::from)
+                            .collect(),
+                        next,
+                    ))
+                }
+            })
+            .await?
+        }
+    };
+    out.flush()?;
+
+    tracing::info!(
+        standard = ?args.standard,
+        format = ?format,
+        rows,
+        "Export complete"
+    );
+    Ok(())
+}
+
+/// Writes the pages returned by `fetch_page` until it returns no cursor, with the CSV
+/// header first. Returns the number of rows written.
+async fn write_pages<R, C, F, Fut>(
+    out: &mut dyn Write,
+    format: ExportFormat,
+    mut fetch_page: F,
+) -> Result<u64>
+where
+    R: ExportRecord,
+    F: FnMut(Option<C>) -> Fut,
+    Fut: Future<Output = Result<(Vec<R>, Option<C>)>>,
+{
+    if format == ExportFormat::Csv {
+        writeln!(out, "{}", R::COLUMNS.join(","))?;
+    }
+    let mut rows = 0u64;
+    let mut cursor = None;
+    loop {
+        let (records, next) = fetch_page(cursor).await?;
+        out.write_all(encode_records(format, &records)?.as_bytes())?;
+        rows += records.len() as u64;
+        match next {
+            Some(next) => cursor = Some(next),
+            None => return Ok(rows),
+        }
+    }
+}
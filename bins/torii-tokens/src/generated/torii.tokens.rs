@@ -0,0 +1,261 @@
+// This file is @generated by prost-build.
+/// Token movement (transfer, mint or burn)
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TokenEvent {
+    /// Token standard of the contract
+    #[prost(enumeration = "TokenStandard", tag = "1")]
+    pub standard: i32,
+    /// Token contract address (32 bytes)
+    #[prost(bytes = "vec", tag = "2")]
+    pub token: ::prost::alloc::vec::Vec<u8>,
+    /// Sender address (32 bytes, zero for mints)
+    #[prost(bytes = "vec", tag = "3")]
+    pub from: ::prost::alloc::vec::Vec<u8>,
+    /// Receiver address (32 bytes, zero for burns)
+    #[prost(bytes = "vec", tag = "4")]
+    pub to: ::prost::alloc::vec::Vec<u8>,
+    /// Token ID (32 bytes, ERC721 and ERC1155 only)
+    #[prost(bytes = "vec", optional, tag = "5")]
+    pub token_id: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+    /// Amount moved (32 bytes, 1 for ERC721)
+    #[prost(bytes = "vec", tag = "6")]
+    pub amount: ::prost::alloc::vec::Vec<u8>,
+    /// Operator address (32 bytes, ERC1155 only)
+    #[prost(bytes = "vec", optional, tag = "7")]
+    pub operator: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+    /// Block number
+    #[prost(uint64, tag = "8")]
+    pub block_number: u64,
+    /// Position among the events of the transaction (unset for events indexed before it was recorded)
+    #[prost(uint32, optional, tag = "9")]
+    pub event_index: ::core::option::Option<u32>,
+    /// Block timestamp (unix seconds)
+    #[prost(int64, tag = "10")]
+    pub timestamp: i64,
+}
+/// Request for GetTransactionEvents RPC
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetTransactionEventsRequest {
+    /// Transaction hash (32 bytes)
+    #[prost(bytes = "vec", tag = "1")]
+    pub tx_hash: ::prost::alloc::vec::Vec<u8>,
+}
+/// Response for GetTransactionEvents RPC
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetTransactionEventsResponse {
+    /// Token movements of the transaction, in event order
+    #[prost(message, repeated, tag = "1")]
+    pub events: ::prost::alloc::vec::Vec<TokenEvent>,
+}
+/// Token standard of an event
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum TokenStandard {
+    Erc20 = 0,
+    Erc721 = 1,
+    Erc1155 = 2,
+}
+impl TokenStandard {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Erc20 => "TOKEN_STANDARD_ERC20",
+            Self::Erc721 => "TOKEN_STANDARD_ERC721",
+            Self::Erc1155 => "TOKEN_STANDARD_ERC1155",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "TOKEN_STANDARD_ERC20" => Some(Self::Erc20),
+            "TOKEN_STANDARD_ERC721" => Some(Self::Erc721),
+            "TOKEN_STANDARD_ERC1155" => Some(Self::Erc1155),
+            _ => None,
+        }
+    }
+}
+/// Generated server implementations.
+pub mod tokens_server {
+    #![allow(
+        unused_variables,
+        dead_code,
+        missing_docs,
+        clippy::wildcard_imports,
+        clippy::let_unit_value,
+    )]
+    use tonic::codegen::*;
+    /// Generated trait containing gRPC methods that should be implemented for use with TokensServer.
+    #[async_trait]
+    pub trait Tokens: std::marker::Send + std::marker::Sync + 'static {
+        /// Every token movement of a transaction, in event order
+        async fn get_transaction_events(
+            &self,
+            request: tonic::Request<super::GetTransactionEventsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetTransactionEventsResponse>,
+            tonic::Status,
+        >;
+    }
+    /// Tokens service - queries spanning the ERC20, ERC721 and ERC1155 indexes
+    #[derive(Debug)]
+    pub struct TokensServer<T> {
+        inner: Arc<T>,
+        accept_compression_encodings: EnabledCompressionEncodings,
+        send_compression_encodings: EnabledCompressionEncodings,
+        max_decoding_message_size: Option<usize>,
+        max_encoding_message_size: Option<usize>,
+    }
+    impl<T> TokensServer<T> {
+        pub fn new(inner: T) -> Self {
+            Self::from_arc(Arc::new(inner))
+        }
+        pub fn from_arc(inner: Arc<T>) -> Self {
+            Self {
+                inner,
+                accept_compression_encodings: Default::default(),
+                send_compression_encodings: Default::default(),
+                max_decoding_message_size: None,
+                max_encoding_message_size: None,
+            }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> InterceptedService<Self, F>
+        where
+            F: tonic::service::Interceptor,
+        {
+            InterceptedService::new(Self::new(inner), interceptor)
+        }
+        /// Enable decompressing requests with the given encoding.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.accept_compression_encodings.enable(encoding);
+            self
+        }
+        /// Compress responses with the given encoding, if the client supports it.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.send_compression_encodings.enable(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.max_decoding_message_size = Some(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.max_encoding_message_size = Some(limit);
+            self
+        }
+    }
+    impl<T, B> tonic::codegen::Service<http::Request<B>> for TokensServer<T>
+    where
+        T: Tokens,
+        B: Body + std::marker::Send + 'static,
+        B::Error: Into<StdError> + std::marker::Send + 'static,
+    {
+        type Response = http::Response<tonic::body::BoxBody>;
+        type Error = std::convert::Infallible;
+        type Future = BoxFuture<Self::Response, Self::Error>;
+        fn poll_ready(
+            &mut self,
+            _cx: &mut Context<'_>,
+        ) -> Poll<std::result::Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+        fn call(&mut self, req: http::Request<B>) -> Self::Future {
+            match req.uri().path() {
+                "/torii.tokens.Tokens/GetTransactionEvents" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetTransactionEventsSvc<T: Tokens>(pub Arc<T>);
+                    impl<
+                        T: Tokens,
+                    > tonic::server::UnaryService<super::GetTransactionEventsRequest>
+                    for GetTransactionEventsSvc<T> {
+                        type Response = super::GetTransactionEventsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetTransactionEventsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Tokens>::get_transaction_events(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetTransactionEventsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                _ => {
+                    Box::pin(async move {
+                        let mut response = http::Response::new(empty_body());
+                        let headers = response.headers_mut();
+                        headers
+                            .insert(
+                                tonic::Status::GRPC_STATUS,
+                                (tonic::Code::Unimplemented as i32).into(),
+                            );
+                        headers
+                            .insert(
+                                http::header::CONTENT_TYPE,
+                                tonic::metadata::GRPC_CONTENT_TYPE,
+                            );
+                        Ok(response)
+                    })
+                }
+            }
+        }
+    }
+    impl<T> Clone for TokensServer<T> {
+        fn clone(&self) -> Self {
+            let inner = self.inner.clone();
+            Self {
+                inner,
+                accept_compression_encodings: self.accept_compression_encodings,
+                send_compression_encodings: self.send_compression_encodings,
+                max_decoding_message_size: self.max_decoding_message_size,
+                max_encoding_message_size: self.max_encoding_message_size,
+            }
+        }
+    }
+    /// Generated gRPC service name
+    pub const SERVICE_NAME: &str = "torii.tokens.Tokens";
+    impl<T> tonic::server::NamedService for TokensServer<T> {
+        const NAME: &'static str = SERVICE_NAME;
+    }
+}
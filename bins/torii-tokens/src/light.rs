@@ -0,0 +1,400 @@
+//! Light mode: a streaming-only gateway without storage.
+//!
+//! With `--light`, the token sinks and their databases are replaced by [`LightSink`].
+//! It publishes every decoded transfer, approval and URI update on the EventBus
+//! topics of the token sinks (same message types and filters), as soon as its
+//! batch is extracted. Nothing is stored, so there is nothing to query and no
+//! history to replay: clients only see what happens while they are subscribed.
+
+use prost::Message;
+use prost_types::Any;
+use std::collections::HashMap;
+use std::sync::Arc;
+use torii::async_trait;
+use torii::axum::Router;
+use torii::etl::sink::{EventBus, SinkContext, TopicInfo};
+use torii::etl::{Envelope, ExtractionBatch, Sink, TypeId};
+use torii::grpc::UpdateType;
+use torii::ToriiResult;
+use torii_common::u256_to_bytes;
+use torii_erc1155::{Erc1155Sink, TransferBatch, TransferSingle, UriUpdate};
+use torii_erc20::{Approval, Erc20Sink, Transfer};
+use torii_erc721::{Erc721Sink, NftTransfer};
+
+/// Publishes decoded token events on the EventBus without storing them.
+#[derive(Default)]
+pub struct LightSink {
+    erc20: bool,
+    erc721: bool,
+    erc1155: bool,
+    event_bus: Option<Arc<EventBus>>,
+}
+
+impl LightSink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Streams ERC20 transfers and approvals.
+    pub fn with_erc20(mut self) -> Self {
+        self.erc20 = true;
+        self
+    }
+
+    /// Streams ERC721 transfers.
+    pub fn with_erc721(mut self) -> Self {
+        self.erc721 = true;
+        self
+    }
+
+    /// Streams ERC1155 transfers and URI updates.
+    pub fn with_erc1155(mut self) -> Self {
+        self.erc1155 = true;
+        self
+    }
+
+    /// Publishes `message` on `topic` to the matching subscribers.
+    fn publish<T, F>(&self, topic: &str, message_type: &str, message: &T, filter_fn: F) -> usize
+    where
+        T: Message + Clone + Send + Sync + 'static,
+        F: Fn(&T, &HashMap<String, String>) -> bool + Send + Sync + 'static,
+    {
+        let Some(event_bus) = &self.event_bus else {
+            return 0;
+        };
+        let any = Any {
+            type_url: format!("type.googleapis.com/{message_type}"),
+            value: message.encode_to_vec(),
+        };
+        event_bus.publish_protobuf(topic, topic, &any, message, UpdateType::Created, filter_fn)
+    }
+
+    /// Publishes one decoded envelope, returning the number of subscribers reached.
+    fn publish_envelope(&self, envelope: &Envelope, timestamp: i64) -> usize {
+        if let Some(transfer) = envelope.downcast_ref::<Transfer>() {
+            let message = torii_erc20::proto::Transfer {
+                token: transfer.token.to_bytes_be().to_vec(),
+                from: transfer.from.to_bytes_be().to_vec(),
+                to: transfer.to.to_bytes_be().to_vec(),
+                amount: u256_to_bytes(transfer.amount),
+                block_number: transfer.block_number,
+                tx_hash: transfer.transaction_hash.to_bytes_be().to_vec(),
+                timestamp,
+                provenance: None,
+                amount_usd: None,
+            };
+            self.publish(
+                "erc20.transfer",
+                "torii.sinks.erc20.Transfer",
+                &message,
+                Erc20Sink::matches_transfer_filters,
+            )
+        } else if let Some(approval) = envelope.downcast_ref::<Approval>() {
+            let message = torii_erc20::proto::Approval {
+                token: approval.token.to_bytes_be().to_vec(),
+                owner: approval.owner.to_bytes_be().to_vec(),
+                spender: approval.spender.to_bytes_be().to_vec(),
+                amount: u256_to_bytes(approval.amount),
+                block_number: approval.block_number,
+                tx_hash: approval.transaction_hash.to_bytes_be().to_vec(),
+                timestamp,
+                provenance: None,
+            };
+            self.publish(
+                "erc20.approval",
+                "torii.sinks.erc20.Approval",
+                &message,
+                Erc20Sink::matches_approval_filters,
+            )
+        } else if let Some(transfer) = envelope.downcast_ref::<NftTransfer>() {
+            let message = torii_erc721::proto::NftTransfer {
+                token: transfer.token.to_bytes_be().to_vec(),
+                token_id: u256_to_bytes(transfer.token_id),
+                from: transfer.from.to_bytes_be().to_vec(),
+                to: transfer.to.to_bytes_be().to_vec(),
+                block_number: transfer.block_number,
+                tx_hash: transfer.transaction_hash.to_bytes_be().to_vec(),
+                timestamp,
+            };
+            self.publish(
+                "erc721.transfer",
+                "torii.sinks.erc721.NftTransfer",
+                &message,
+                Erc721Sink::matches_transfer_filters,
+            )
+        } else if let Some(transfer) = envelope.downcast_ref::<TransferSingle>() {
+            let message = torii_erc1155::proto::TokenTransfer {
+                token: transfer.token.to_bytes_be().to_vec(),
+                operator: transfer.operator.to_bytes_be().to_vec(),
+                from: transfer.from.to_bytes_be().to_vec(),
+                to: transfer.to.to_bytes_be().to_vec(),
+                token_id: u256_to_bytes(transfer.id),
+                amount: u256_to_bytes(transfer.value),
+                block_number: transfer.block_number,
+                tx_hash: transfer.transaction_hash.to_bytes_be().to_vec(),
+                timestamp,
+                is_batch: false,
+                batch_index: 0,
+            };
+            self.publish(
+                "erc1155.transfer",
+                "torii.sinks.erc1155.TokenTransfer",
+                &message,
+                Erc1155Sink::matches_transfer_filters,
+            )
+        } else if let Some(transfer) = envelope.downcast_ref::<TransferBatch>() {
+            let message = torii_erc1155::proto::TokenTransfer {
+                token: transfer.token.to_bytes_be().to_vec(),
+                operator: transfer.operator.to_bytes_be().to_vec(),
+                from: transfer.from.to_bytes_be().to_vec(),
+                to: transfer.to.to_bytes_be().to_vec(),
+                token_id: u256_to_bytes(transfer.id),
+                amount: u256_to_bytes(transfer.value),
+                block_number: transfer.block_number,
+                tx_hash: transfer.transaction_hash.to_bytes_be().to_vec(),
+                timestamp,
+                is_batch: true,
+                batch_index: transfer.batch_index,
+            };
+            self.publish(
+                "erc1155.transfer",
+                "torii.sinks.erc1155.TokenTransfer",
+                &message,
+                Erc1155Sink::matches_transfer_filters,
+            )
+        } else if let Some(uri) = envelope.downcast_ref::<UriUpdate>() {
+            let message = torii_erc1155::proto::TokenUri {
+                token: uri.token.to_bytes_be().to_vec(),
+                token_id: u256_to_bytes(uri.token_id),
+                uri: uri.uri.clone(),
+                block_number: uri.block_number,
+            };
+            self.publish(
+                "erc1155.uri",
+                "torii.sinks.erc1155.TokenUri",
+                &message,
+                Erc1155Sink::matches_uri_filters,
+            )
+        } else {
+            0
+        }
+    }
+}
+
+/// Block number of a decoded token envelope.
+fn block_number(envelope: &Envelope) -> Option<u64> {
+    if let Some(transfer) = envelope.downcast_ref::<Transfer>() {
+        Some(transfer.block_number)
+    } else if let Some(approval) = envelope.downcast_ref::<Approval>() {
+        Some(approval.block_number)
+    } else if let Some(transfer) = envelope.downcast_ref::<NftTransfer>() {
+        Some(transfer.block_number)
+    } else if let Some(transfer) = envelope.downcast_ref::<TransferSingle>() {
+        Some(transfer.block_number)
+    } else if let Some(transfer) = envelope.downcast_ref::<TransferBatch>() {
+        Some(transfer.block_number)
+    } else {
+        envelope
+            .downcast_ref::<UriUpdate>()
+            .map(|uri| uri.block_number)
+    }
+}
+
+#[async_trait]
+impl Sink for LightSink {
+    fn name(&self) -> &'static str {
+        "light"
+    }
+
+    fn version(&self) -> Option<&str> {
+        Some(env!("CARGO_PKG_VERSION"))
+    }
+
+    fn interested_types(&self) -> Vec<TypeId> {
+        let mut types = Vec::new();
+        if self.erc20 {
+            types.extend([TypeId::new("erc20.transfer"), TypeId::new("erc20.approval")]);
+        }
+        if self.erc721 {
+            types.push(TypeId::new("erc721.transfer"));
+        }
+        if self.erc1155 {
+            types.extend([
+                TypeId::new("erc1155.transfer_single"),
+                TypeId::new("erc1155.transfer_batch"),
+                TypeId::new("erc1155.uri"),
+            ]);
+        }
+        types
+    }
+
+    async fn process(&self, envelopes: &[Envelope], batch: &ExtractionBatch) -> ToriiResult<()> {
+        let mut delivered = 0;
+        for envelope in envelopes {
+            let timestamp = envelope
+                .block_timestamp()
+                .or_else(|| {
+                    block_number(envelope)
+                        .and_then(|number| batch.blocks.get(&number))
+                        .map(|block| block.timestamp)
+                })
+                .unwrap_or(0);
+            delivered += self.publish_envelope(envelope, timestamp as i64);
+        }
+        tracing::debug!(
+            target: "torii_tokens::light",
+            envelopes = envelopes.len(),
+            delivered,
+            "Streamed decoded token events"
+        );
+        Ok(())
+    }
+
+    fn topics(&self) -> Vec<TopicInfo> {
+        let wallet_filters = || {
+            vec![
+                "token".to_string(),
+                "from".to_string(),
+                "to".to_string(),
+                "wallet".to_string(),
+            ]
+        };
+        let mut topics = Vec::new();
+        if self.erc20 {
+            topics.push(
+                TopicInfo::new(
+                    "erc20.transfer",
+                    wallet_filters(),
+                    "ERC20 token transfers (light mode, live only). Use 'wallet' filter for from OR to matching.",
+                )
+                .with_message_type("torii.sinks.erc20.Transfer"),
+            );
+            topics.push(
+                TopicInfo::new(
+                    "erc20.approval",
+                    vec![
+                        "token".to_string(),
+                        "owner".to_string(),
+                        "spender".to_string(),
+                        "account".to_string(),
+                    ],
+                    "ERC20 token approvals (light mode, live only). Use 'account' filter for owner OR spender matching.",
+                )
+                .with_message_type("torii.sinks.erc20.Approval"),
+            );
+        }
+        if self.erc721 {
+            topics.push(
+                TopicInfo::new(
+                    "erc721.transfer",
+                    wallet_filters(),
+                    "ERC721 NFT transfers (light mode, live only). Use 'wallet' filter for from OR to matching.",
+                )
+                .with_message_type("torii.sinks.erc721.NftTransfer"),
+            );
+        }
+        if self.erc1155 {
+            let mut transfer_filters = wallet_filters();
+            transfer_filters.push("token_id".to_string());
+            topics.push(
+                TopicInfo::new(
+                    "erc1155.transfer",
+                    transfer_filters,
+                    "ERC1155 token transfers (light mode, live only), one update per transferred id of batch transfers.",
+                )
+                .with_message_type("torii.sinks.erc1155.TokenTransfer"),
+            );
+            topics.push(
+                TopicInfo::new(
+                    "erc1155.uri",
+                    vec!["token".to_string(), "token_id".to_string()],
+                    "ERC1155 token URI updates (light mode, live only).",
+                )
+                .with_message_type("torii.sinks.erc1155.TokenUri"),
+            );
+        }
+        topics
+    }
+
+    fn build_routes(&self) -> Router {
+        Router::new()
+    }
+
+    async fn initialize(
+        &mut self,
+        event_bus: Arc<EventBus>,
+        _context: &SinkContext,
+    ) -> ToriiResult<()> {
+        self.event_bus = Some(event_bus);
+        tracing::info!(target: "torii_tokens::light", "Light sink initialized");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use starknet::core::types::{Felt, U256};
+    use tokio::sync::mpsc;
+    use torii::command::CommandBus;
+    use torii::grpc::proto::TopicSubscription;
+    use torii::grpc::SubscriptionManager;
+
+    #[tokio::test]
+    async fn streams_decoded_transfers_with_sink_filters() {
+        let manager = Arc::new(SubscriptionManager::new());
+        let mut sink = LightSink::new().with_erc20();
+        let command_bus = CommandBus::new(Vec::new(), 1).unwrap();
+        sink.initialize(
+            Arc::new(EventBus::new(manager.clone())),
+            &SinkContext {
+                database_root: ".".into(),
+                command_bus: command_bus.sender(),
+            },
+        )
+        .await
+        .unwrap();
+
+        let (tx, mut rx) = mpsc::channel(8);
+        manager.register_client("client".to_string(), tx);
+        manager.update_subscriptions(
+            "client",
+            vec![TopicSubscription {
+                topic: "erc20.transfer".to_string(),
+                filters: HashMap::from([("wallet".to_string(), "0xb".to_string())]),
+                filter_data: None,
+            }],
+            Vec::new(),
+        );
+
+        let transfer = |to: u64| {
+            Envelope::from_body(
+                format!("transfer-{to}"),
+                Transfer {
+                    from: Felt::from(0xa_u64),
+                    to: Felt::from(to),
+                    amount: U256::from(5_u64),
+                    token: Felt::from(0x7_u64),
+                    block_number: 42,
+                    transaction_hash: Felt::from(0x1_u64),
+                },
+                HashMap::new(),
+            )
+        };
+        sink.process(&[transfer(0xb), transfer(0xc)], &ExtractionBatch::empty())
+            .await
+            .unwrap();
+
+        let update = rx.recv().await.unwrap();
+        assert_eq!(update.topic, "erc20.transfer");
+        let data = update.data.unwrap();
+        assert_eq!(
+            data.type_url,
+            "type.googleapis.com/torii.sinks.erc20.Transfer"
+        );
+        let message = torii_erc20::proto::Transfer::decode(data.value.as_slice()).unwrap();
+        assert_eq!(message.block_number, 42);
+        assert_eq!(message.to, Felt::from(0xb_u64).to_bytes_be().to_vec());
+        assert!(rx.try_recv().is_err());
+    }
+}
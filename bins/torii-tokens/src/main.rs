@@ -35,13 +35,40 @@
 //! # Add a new contract in event mode (just restart with updated list)
 //! torii-tokens --mode event --erc20 0x...ETH,0x...STRK,0x...USDC --from-block 0
 //! # USDC starts from block 0, ETH and STRK resume from their cursors
+//!
+//! # Re-index a block range without touching the saved cursors
+//! torii-tokens backfill --from-block 100000 --to-block 110000
+//!
+//! # Compare stored balances/owners with the chain
+//! torii-tokens verify --standards erc20,erc721 --sample 200
+//!
+//! # Dump stored transfers
+//! torii-tokens export --standard erc721 --format jsonl -o transfers.jsonl
 //! ```
 
+mod backfill;
 mod config;
+mod export;
+mod light;
+mod tokens_service;
+mod verify;
+
+// Include generated protobuf code
+mod proto {
+    include!("generated/torii.tokens.rs");
+}
+
+// File descriptor set for gRPC reflection
+const TOKENS_DESCRIPTOR_SET: &[u8] = include_bytes!("generated/tokens_descriptor.bin");
 
 use anyhow::Result;
+use backfill::BackfillExtractor;
 use clap::Parser;
-use config::{Config, ExtractionMode, MetadataMode};
+use config::{
+    Cli, Command, Config, Erc20BalanceScope, ExtractionMode, MetadataMode, PriceFeedKind,
+};
+use light::LightSink;
+use proto::tokens_server::TokensServer;
 use starknet::core::types::Felt;
 use starknet::providers::Provider;
 use std::collections::HashSet;
@@ -49,15 +76,21 @@ use std::path::Path;
 use std::sync::Arc;
 #[cfg(feature = "profiling")]
 use std::time::{SystemTime, UNIX_EPOCH};
-use tonic::codec::CompressionEncoding;
 use torii::etl::decoder::DecoderId;
 use torii::etl::extractor::{
-    BlockRangeConfig, BlockRangeExtractor, ContractEventConfig, EventExtractor,
-    EventExtractorConfig, Extractor, GlobalEventExtractor, GlobalEventExtractorConfig, RetryPolicy,
+    ArchiveConfig, ArchiveExtractor, BlockRangeConfig, BlockRangeExtractor, ContractEventConfig,
+    EventExtractor, EventExtractorConfig, Extractor, GlobalEventExtractor,
+    GlobalEventExtractorConfig, RetryPolicy,
 };
 use torii::etl::identification::ContractRegistry;
-use torii::EtlConcurrencyConfig;
-use torii_common::{MetadataFetcher, TokenUriService};
+use torii::etl::sink::SinkWorkerConfig;
+use torii::etl::{ShardCoordinator, StartupConsistency};
+use torii::tonic::service::interceptor::InterceptedService;
+use torii::{configure_grpc_server, EtlConcurrencyConfig};
+use torii_common::{
+    AddressLabel, AddressLabels, ImageCache, MetadataCache, MetadataFetcher, ObjectStore,
+    TokenUriService,
+};
 use torii_config_common::apply_observability_env;
 use torii_runtime_common::database::resolve_token_db_setup;
 #[cfg(feature = "profiling")]
@@ -66,21 +99,33 @@ use torii_runtime_common::database::DatabaseBackend;
 // Import from ERC20 library crate
 use torii_erc20::proto::erc20_server::Erc20Server;
 use torii_erc20::{
-    Erc20Decoder, Erc20MetadataCommandHandler, Erc20Rule, Erc20Service, Erc20Sink, Erc20Storage,
-    FILE_DESCRIPTOR_SET as ERC20_DESCRIPTOR_SET,
+    BalanceFetcher, BalanceScope, BalanceWatchlist, Erc20Decoder, Erc20MetadataCommandHandler,
+    Erc20Rule, Erc20Service, Erc20Sink, HttpPriceFeed, PragmaPriceFeed, PriceFeed,
+    ShardedErc20Storage, FILE_DESCRIPTOR_SET as ERC20_DESCRIPTOR_SET,
 };
 
 use torii_erc721::proto::erc721_server::Erc721Server;
 use torii_erc721::{
     Erc721Decoder, Erc721MetadataCommandHandler, Erc721Rule, Erc721Service, Erc721Sink,
-    Erc721Storage, FILE_DESCRIPTOR_SET as ERC721_DESCRIPTOR_SET,
+    ShardedErc721Storage, FILE_DESCRIPTOR_SET as ERC721_DESCRIPTOR_SET,
 };
 
 // Import from ERC1155 library crate
 use torii_erc1155::proto::erc1155_server::Erc1155Server;
 use torii_erc1155::{
     Erc1155Decoder, Erc1155MetadataCommandHandler, Erc1155Rule, Erc1155Service, Erc1155Sink,
-    Erc1155Storage, FILE_DESCRIPTOR_SET as ERC1155_DESCRIPTOR_SET,
+    ShardedErc1155Storage, FILE_DESCRIPTOR_SET as ERC1155_DESCRIPTOR_SET,
+};
+
+use torii_decoder_account::proto::account_server::AccountServer;
+use torii_decoder_account::{
+    AccountDecoder, AccountRule, AccountSink, AccountStorage,
+    FILE_DESCRIPTOR_SET as ACCOUNT_DESCRIPTOR_SET,
+};
+use torii_relay::proto::relay_server::RelayServer;
+use torii_relay::{
+    AccountSignatureVerifier, Relay, RelaySink, RelayStorage,
+    FILE_DESCRIPTOR_SET as RELAY_DESCRIPTOR_SET,
 };
 
 async fn contracts_from_registry(
@@ -110,6 +155,37 @@ async fn contracts_from_registry(
     Ok((erc20, erc721, erc1155))
 }
 
+/// Builds the ERC20 price feed selected by `--price-feed`, if any.
+fn build_price_feed(
+    config: &Config,
+    provider: &Arc<torii_common::RpcProvider>,
+) -> Result<Option<Arc<dyn PriceFeed>>> {
+    let Some(kind) = &config.price_feed else {
+        return Ok(None);
+    };
+    let feed: Arc<dyn PriceFeed> = match kind {
+        PriceFeedKind::Pragma => {
+            let oracle = config
+                .pragma_oracle
+                .as_deref()
+                .ok_or_else(|| anyhow::anyhow!("--price-feed pragma requires --pragma-oracle"))?;
+            let mut feed = PragmaPriceFeed::new(provider.clone(), Config::parse_address(oracle)?);
+            for (token, pair) in config.price_pairs()? {
+                feed = feed.with_pair(token, &pair)?;
+            }
+            Arc::new(feed)
+        }
+        PriceFeedKind::Http => {
+            let url = config
+                .price_feed_url
+                .as_deref()
+                .ok_or_else(|| anyhow::anyhow!("--price-feed http requires --price-feed-url"))?;
+            Arc::new(HttpPriceFeed::new(url)?)
+        }
+    };
+    Ok(Some(feed))
+}
+
 fn extend_unique(target: &mut Vec<Felt>, additions: Vec<Felt>) {
     let mut seen: HashSet<Felt> = target.iter().copied().collect();
     for addr in additions {
@@ -120,12 +196,11 @@ fn extend_unique(target: &mut Vec<Felt>, additions: Vec<Felt>) {
 }
 
 async fn bootstrap_registry_for_event_mode(
-    provider: Arc<
-        starknet::providers::jsonrpc::JsonRpcClient<starknet::providers::jsonrpc::HttpTransport>,
-    >,
+    provider: Arc<torii_common::RpcProvider>,
     engine_db: &torii::etl::EngineDb,
     registry: &ContractRegistry,
     config: &Config,
+    retry_policy: RetryPolicy,
 ) -> Result<usize> {
     let chain_head = provider
         .block_number()
@@ -164,8 +239,14 @@ async fn bootstrap_registry_for_event_mode(
             from_block: config.from_block,
             to_block: Some(bootstrap_to),
             batch_size: config.batch_size,
-            retry_policy: RetryPolicy::default(),
+            retry_policy,
             rpc_parallelism: config.rpc_parallelism,
+            max_concurrent_block_fetches: config.max_concurrent_block_fetches,
+            adaptive_batch: None,
+            include_receipts: false,
+            confirmation_depth: 0,
+            consolidate_event_cursors: false,
+            ignore_saved_state: false,
         },
     );
 
@@ -215,6 +296,15 @@ async fn bootstrap_registry_for_event_mode(
     Ok(identified_total)
 }
 
+/// Validates the configuration and prints the plan instead of running (`--dry-run`).
+async fn dry_run(torii_config: torii::ToriiConfig) -> Result<()> {
+    let report = torii::validate(torii_config)
+        .await
+        .map_err(|e| anyhow::anyhow!("Invalid configuration: {e}"))?;
+    println!("{report}");
+    Ok(())
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     let env_filter = tracing_subscriber::EnvFilter::try_from_default_env()
@@ -225,8 +315,26 @@ async fn main() -> Result<()> {
         .with_target(true)
         .init();
 
-    let config = Config::parse();
-    run_indexer(config).await
+    match Cli::parse().command() {
+        Command::Index { config } => run_indexer(config).await,
+        Command::Backfill { mut config } => {
+            let Some(to_block) = config.to_block else {
+                anyhow::bail!("backfill needs --to-block");
+            };
+            if config.light || config.replay_from_block.is_some() {
+                anyhow::bail!("backfill cannot be combined with --light or --replay-from-block");
+            }
+            tracing::info!(
+                "Backfilling blocks {}..={} without moving the indexing cursors",
+                config.from_block,
+                to_block
+            );
+            config.backfill = true;
+            run_indexer(config).await
+        }
+        Command::Verify(args) => verify::run(args).await,
+        Command::Export(args) => export::run(args).await,
+    }
 }
 
 /// Run the main indexer
@@ -260,6 +368,14 @@ async fn run_indexer(config: Config) -> Result<()> {
     if let Some(url) = &config.storage_database_url {
         tracing::info!("Storage database URL: {}", url);
     }
+    if config.light {
+        tracing::info!("Light mode: streaming decoded events only, nothing is stored");
+        if config.replay_from_block.is_some() {
+            anyhow::bail!(
+                "--replay-from-block reads the event archive, which light mode does not keep"
+            );
+        }
+    }
 
     if config.mode == ExtractionMode::BlockRange || config.mode == ExtractionMode::GlobalEvent {
         if config.has_tokens() {
@@ -271,12 +387,29 @@ async fn run_indexer(config: Config) -> Result<()> {
         }
     }
 
-    let provider = Arc::new(starknet::providers::jsonrpc::JsonRpcClient::new(
-        starknet::providers::jsonrpc::HttpTransport::new(
-            url::Url::parse(&config.rpc_url).expect("Invalid RPC URL"),
-        ),
+    let provider = Arc::new(torii_common::rate_limited_provider(
+        url::Url::parse(&config.rpc_url).expect("Invalid RPC URL"),
+        config.rpc_rate_limit,
+        config.rpc_burst,
     ));
 
+    let extractor_retry_policy = config.retry_policy(RetryPolicy::default())?;
+    let identification_retry_policy =
+        config.retry_policy(ContractRegistry::default_retry_policy())?;
+    for (component, policy) in [
+        ("extractor", &extractor_retry_policy),
+        ("identification", &identification_retry_policy),
+    ] {
+        tracing::info!(
+            component,
+            max_retries = policy.max_retries,
+            initial_backoff = ?policy.initial_backoff,
+            max_backoff = ?policy.max_backoff,
+            jitter = policy.jitter,
+            "RPC retry policy"
+        );
+    }
+
     let mut all_erc20_addresses: Vec<Felt> = Vec::new();
     let mut all_erc721_addresses: Vec<Felt> = Vec::new();
     let mut all_erc1155_addresses: Vec<Felt> = Vec::new();
@@ -307,7 +440,9 @@ async fn run_indexer(config: Config) -> Result<()> {
     }
 
     let db_dir = Path::new(&config.db_dir);
-    std::fs::create_dir_all(db_dir)?;
+    if !config.light {
+        std::fs::create_dir_all(db_dir)?;
+    }
 
     let effective_metadata_mode = config.metadata_mode.clone().unwrap_or(MetadataMode::Inline);
     tracing::info!("Metadata mode: {:?}", effective_metadata_mode);
@@ -346,18 +481,44 @@ async fn run_indexer(config: Config) -> Result<()> {
         db_setup.erc1155_url
     );
 
+    // Light mode keeps cursors and contract identifications in memory only.
+    let engine_url = if config.light {
+        ":memory:".to_string()
+    } else {
+        db_setup.engine_url.clone()
+    };
     let engine_db_config = torii::etl::engine_db::EngineDbConfig {
-        path: db_setup.engine_url.clone(),
+        path: engine_url.clone(),
     };
     let engine_db = Arc::new(torii::etl::EngineDb::new(engine_db_config).await?);
 
-    let registry = Arc::new(
-        ContractRegistry::new(provider.clone(), engine_db.clone())
-            .with_rpc_parallelism(config.rpc_parallelism)
-            .with_rule(Box::new(Erc20Rule::new()))
-            .with_rule(Box::new(Erc721Rule::new()))
-            .with_rule(Box::new(Erc1155Rule::new())),
-    );
+    let mut registry = ContractRegistry::new(provider.clone(), engine_db.clone())
+        .with_rpc_parallelism(config.rpc_parallelism)
+        .with_retry_policy(identification_retry_policy)
+        .with_identification_budget(config.identification_budget)
+        .with_rule(Box::new(Erc20Rule::new()))
+        .with_rule(Box::new(Erc721Rule::new()))
+        .with_rule(Box::new(Erc1155Rule::new()));
+    if config.accounts {
+        registry = registry.with_rule(Box::new(AccountRule::new()));
+    }
+    if config.identification_ttl > 0 {
+        registry = registry
+            .with_identification_ttl(std::time::Duration::from_secs(config.identification_ttl));
+    }
+    let (token_denylist, token_allowlist) = config.token_lists()?;
+    let mut selector_aliases = config.selector_aliases()?;
+    if !token_denylist.is_empty() || !token_allowlist.is_empty() {
+        tracing::info!(
+            denied = token_denylist.len(),
+            allowed = token_allowlist.len(),
+            "Token identification lists configured"
+        );
+    }
+    registry = registry
+        .with_denylist(token_denylist)
+        .with_allowlist(token_allowlist);
+    let registry = Arc::new(registry);
 
     // Load any previously identified contracts from database
     let loaded_count = registry.load_from_db().await?;
@@ -365,7 +526,7 @@ async fn run_indexer(config: Config) -> Result<()> {
         tracing::info!("Loaded {} contract mappings from database", loaded_count);
     }
 
-    if config.mode == ExtractionMode::Event {
+    if config.mode == ExtractionMode::Event && config.replay_from_block.is_none() {
         let (reg_erc20, reg_erc721, reg_erc1155) = contracts_from_registry(&engine_db).await?;
         let before = (
             all_erc20_addresses.len(),
@@ -395,9 +556,14 @@ async fn run_indexer(config: Config) -> Result<()> {
             && all_erc721_addresses.is_empty()
             && all_erc1155_addresses.is_empty()
         {
-            let identified =
-                bootstrap_registry_for_event_mode(provider.clone(), &engine_db, &registry, &config)
-                    .await?;
+            let identified = bootstrap_registry_for_event_mode(
+                provider.clone(),
+                &engine_db,
+                &registry,
+                &config,
+                extractor_retry_policy.clone(),
+            )
+            .await?;
             if identified > 0 {
                 let (boot_erc20, boot_erc721, boot_erc1155) =
                     contracts_from_registry(&engine_db).await?;
@@ -417,18 +583,75 @@ async fn run_indexer(config: Config) -> Result<()> {
         }
     }
 
+    // Work sharding: claim contract shards before building the extractor restricted to them.
+    let shard_coordinator = match config.sharding_config()? {
+        Some(sharding)
+            if config.replay_from_block.is_none()
+                && !config.light
+                && !config.dry_run
+                && !config.backfill =>
+        {
+            let coordinator = Arc::new(ShardCoordinator::new(engine_db.clone(), sharding));
+            coordinator.acquire().await?;
+            // Leases run from the claim: renew them while the indexer is set up.
+            coordinator.start_renewal();
+            Some(coordinator)
+        }
+        _ => None,
+    };
+
+    // Light mode has no history to catch up on: follow the chain head by default.
+    let from_block = if config.light && config.from_block == 0 {
+        let head = provider.block_number().await?;
+        tracing::info!("Light mode: starting at chain head {}", head);
+        head
+    } else {
+        config.from_block
+    };
+
     let extractor: Box<dyn Extractor> = match config.mode {
+        _ if config.replay_from_block.is_some() => {
+            let from_block = config.replay_from_block.unwrap_or_default();
+            tracing::info!("Using Replay mode (archived events)");
+            tracing::info!(
+                "  Blocks: {}..={}",
+                from_block,
+                config
+                    .replay_to_block
+                    .map_or("last archived".to_string(), |block| block.to_string())
+            );
+
+            Box::new(ArchiveExtractor::new(ArchiveConfig {
+                from_block,
+                to_block: config.replay_to_block,
+                batch_size: config.batch_size,
+            }))
+        }
         ExtractionMode::BlockRange => {
             tracing::info!("Using Block Range mode (single global cursor)");
             tracing::info!("  Batch size: {} blocks", config.batch_size);
+            if config.adaptive_batch_size {
+                tracing::info!(
+                    "  Adaptive batch size: {}-{} blocks, target cycle {}ms",
+                    config.adaptive_batch_min,
+                    config.adaptive_batch_max,
+                    config.adaptive_target_cycle_ms
+                );
+            }
 
             let extractor_config = BlockRangeConfig {
                 rpc_url: config.rpc_url.clone(),
-                from_block: config.from_block,
+                from_block,
                 to_block: config.to_block,
                 batch_size: config.batch_size,
-                retry_policy: RetryPolicy::default(),
+                retry_policy: extractor_retry_policy.clone(),
                 rpc_parallelism: config.rpc_parallelism,
+                max_concurrent_block_fetches: config.max_concurrent_block_fetches,
+                adaptive_batch: config.adaptive_batch_config(),
+                include_receipts: config.include_receipts,
+                confirmation_depth: config.confirmation_depth,
+                consolidate_event_cursors: config.consolidate_event_cursors,
+                ignore_saved_state: config.backfill,
             };
             Box::new(BlockRangeExtractor::new(provider.clone(), extractor_config))
         }
@@ -446,7 +669,7 @@ async fn run_indexer(config: Config) -> Result<()> {
             for addr in &all_erc20_addresses {
                 event_configs.push(ContractEventConfig {
                     address: *addr,
-                    from_block: config.from_block,
+                    from_block,
                     to_block,
                 });
             }
@@ -454,7 +677,7 @@ async fn run_indexer(config: Config) -> Result<()> {
             for addr in &all_erc721_addresses {
                 event_configs.push(ContractEventConfig {
                     address: *addr,
-                    from_block: config.from_block,
+                    from_block,
                     to_block,
                 });
             }
@@ -462,7 +685,7 @@ async fn run_indexer(config: Config) -> Result<()> {
             for addr in &all_erc1155_addresses {
                 event_configs.push(ContractEventConfig {
                     address: *addr,
-                    from_block: config.from_block,
+                    from_block,
                     to_block,
                 });
             }
@@ -471,14 +694,31 @@ async fn run_indexer(config: Config) -> Result<()> {
                 "  Configured {} contracts for event extraction",
                 event_configs.len()
             );
+            let shards = shard_coordinator
+                .as_ref()
+                .map(|coordinator| coordinator.assignment());
+            if let Some(shards) = &shards {
+                tracing::info!(
+                    "  Work shards {:?} of {}: {} of these contracts",
+                    shards.shards,
+                    shards.shard_count,
+                    event_configs
+                        .iter()
+                        .filter(|contract| shards.owns(contract.address))
+                        .count()
+                );
+            }
 
             let extractor_config = EventExtractorConfig {
                 contracts: event_configs,
                 chunk_size: config.event_chunk_size,
                 block_batch_size: config.event_block_batch_size,
-                retry_policy: RetryPolicy::default(),
-                ignore_saved_state: false,
+                retry_policy: extractor_retry_policy.clone(),
+                ignore_saved_state: config.backfill,
                 rpc_parallelism: config.rpc_parallelism,
+                confirmation_depth: config.confirmation_depth,
+                detect_deployment_block: config.detect_deployment_block,
+                shards,
             };
             Box::new(EventExtractor::new(provider.clone(), extractor_config))
         }
@@ -491,12 +731,12 @@ async fn run_indexer(config: Config) -> Result<()> {
             );
 
             let extractor_config = GlobalEventExtractorConfig {
-                from_block: config.from_block,
+                from_block,
                 to_block: config.to_block.unwrap_or(u64::MAX),
                 chunk_size: config.event_chunk_size,
                 block_batch_size: config.event_block_batch_size,
-                retry_policy: RetryPolicy::default(),
-                ignore_saved_state: false,
+                retry_policy: extractor_retry_policy.clone(),
+                ignore_saved_state: config.backfill,
                 rpc_parallelism: config.rpc_parallelism,
             };
             Box::new(GlobalEventExtractor::new(
@@ -506,28 +746,65 @@ async fn run_indexer(config: Config) -> Result<()> {
         }
     };
 
+    // Backfills extract their range once and keep the indexing cursors where they are.
+    let extractor: Box<dyn Extractor> = if config.backfill {
+        Box::new(BackfillExtractor::new(extractor))
+    } else {
+        extractor
+    };
+
     tracing::info!("Extractor configured");
 
     let mut reflection_builder = tonic_reflection::server::Builder::configure()
         .register_encoded_file_descriptor_set(torii::TORII_DESCRIPTOR_SET);
 
+    let grpc_options = config.grpc_options();
+    // Sink services are only served to API keys permitted in their sink namespace.
+    let namespaces = config.namespaces()?;
+    let mut contract_filters = config.sink_contract_filters()?;
     let mut torii_config = torii::ToriiConfig::builder()
         .port(config.port)
+        .drain_period(config.drain_period)
+        .with_admin_rpc(config.admin_rpc)
         .database_root(&config.db_dir)
         .cycle_interval(config.cycle_interval)
         .etl_concurrency(EtlConcurrencyConfig {
             max_prefetch_batches: config.max_prefetch_batches,
         })
         .command_bus_queue_size(config.metadata_queue_capacity)
-        .engine_database_url(db_setup.engine_url.clone())
+        .engine_database_url(engine_url)
         .with_extractor(extractor)
         .with_contract_identifier(registry);
 
-    let mut enabled_types: Vec<&str> = Vec::new();
-    let mut erc20_grpc_service: Option<Erc20Service> = None;
-    let mut erc721_grpc_service: Option<Erc721Service> = None;
-    let mut erc1155_grpc_service: Option<Erc1155Service> = None;
-    let mut token_uri_services = Vec::new();
+    if let Some(tls) = config.tls_config()? {
+        torii_config = torii_config.with_tls(tls);
+    }
+    // Replayed events were already processed, backfilled ones may overlap indexed
+    // blocks: keep them out of the dedupe window and the archive.
+    let replaying = config.replay_from_block.is_some() || config.backfill;
+    torii_config = torii_config
+        .max_concurrent_sinks(config.max_concurrent_sinks)
+        .sink_backpressure_threshold(config.sink_backpressure_threshold)
+        .with_namespaces(namespaces.clone())
+        .dedupe_window(if replaying { 0 } else { config.dedupe_window })
+        .startup_consistency(if replaying || config.light {
+            StartupConsistency::Disabled
+        } else {
+            config.startup_consistency.into()
+        })
+        .archive_events(config.archive_events && !replaying && !config.light)
+        .with_debug_envelopes(config.debug_envelopes)
+        .with_grpc_options(grpc_options);
+    if let Some(coordinator) = shard_coordinator {
+        torii_config = torii_config.with_shard_coordinator(coordinator);
+    }
+    for (sink, timeout) in config.sink_timeouts()? {
+        torii_config =
+            torii_config.sink_worker(sink, SinkWorkerConfig::default().with_timeout(timeout));
+    }
+    if let Some(path) = &config.decoder_config {
+        torii_config = torii_config.decoder_config(path);
+    }
 
     // Global extraction modes create all token infra for runtime auto-discovery.
     let is_global_mode =
@@ -536,32 +813,199 @@ async fn run_indexer(config: Config) -> Result<()> {
     let create_erc721 = is_global_mode || !all_erc721_addresses.is_empty();
     let create_erc1155 = is_global_mode || !all_erc1155_addresses.is_empty();
 
+    if config.light {
+        if config.relay || config.accounts {
+            tracing::warn!("--relay and --accounts need storage and are ignored in light mode");
+        }
+
+        let mut enabled_types: Vec<&str> = Vec::new();
+        let mut sink = LightSink::new();
+        if create_erc20 {
+            enabled_types.push("ERC20");
+            torii_config = torii_config
+                .add_decoder(Arc::new(Erc20Decoder::new().with_selector_aliases(
+                    selector_aliases.remove("erc20").unwrap_or_default(),
+                )));
+            for address in &all_erc20_addresses {
+                torii_config = torii_config.map_contract(*address, vec![DecoderId::new("erc20")]);
+            }
+            sink = sink.with_erc20();
+        }
+        if create_erc721 {
+            enabled_types.push("ERC721");
+            torii_config = torii_config
+                .add_decoder(Arc::new(Erc721Decoder::new().with_selector_aliases(
+                    selector_aliases.remove("erc721").unwrap_or_default(),
+                )));
+            for address in &all_erc721_addresses {
+                torii_config = torii_config.map_contract(*address, vec![DecoderId::new("erc721")]);
+            }
+            sink = sink.with_erc721();
+        }
+        if create_erc1155 {
+            enabled_types.push("ERC1155");
+            torii_config = torii_config
+                .add_decoder(Arc::new(Erc1155Decoder::new().with_selector_aliases(
+                    selector_aliases.remove("erc1155").unwrap_or_default(),
+                )));
+            for address in &all_erc1155_addresses {
+                torii_config = torii_config.map_contract(*address, vec![DecoderId::new("erc1155")]);
+            }
+            sink = sink.with_erc1155();
+        }
+
+        let reflection = configure_grpc_server!(
+            reflection_builder
+                .build_v1()
+                .expect("Failed to build gRPC reflection service"),
+            grpc_options
+        );
+        let torii_config = torii_config
+            .add_sink_boxed(Box::new(sink))
+            .with_grpc_router(tonic::transport::Server::builder().add_service(reflection))
+            .with_custom_reflection(true)
+            .build();
+
+        if config.dry_run {
+            return dry_run(torii_config).await;
+        }
+
+        tracing::info!("Torii configured in light mode, starting ETL pipeline...");
+        tracing::info!("Streamed token types: {}", enabled_types.join(", "));
+        tracing::info!("gRPC service available at localhost:{}", config.port);
+        tracing::info!("  - torii.Torii (EventBus subscriptions)");
+
+        torii::run(torii_config)
+            .await
+            .map_err(|e| anyhow::anyhow!("Torii error: {e}"))?;
+        tracing::info!("Torii shutdown complete");
+        return Ok(());
+    }
+
+    let address_labels = AddressLabels::open(&db_setup.labels_url).await?;
+    if let Some(path) = &config.address_labels {
+        let labels = AddressLabel::load_file(path)?;
+        tracing::info!(
+            "Loaded {} address labels from {}",
+            labels.len(),
+            path.display()
+        );
+        address_labels.upsert(labels).await?;
+    }
+    torii_config = torii_config.with_address_labels(address_labels.clone());
+
+    // One metadata fetcher shared by the token sinks, so they share its cache.
+    let mut metadata_fetcher = MetadataFetcher::new(provider.clone());
+    if let Some(cache_config) = config.metadata_cache_config() {
+        let cache = MetadataCache::open(&db_setup.metadata_cache_url, cache_config).await?;
+        tracing::info!(
+            "Metadata cache initialized: {} ({} entries)",
+            db_setup.metadata_cache_url,
+            cache.len()
+        );
+        metadata_fetcher = metadata_fetcher.with_cache(Arc::new(cache));
+    }
+    let metadata_fetcher = Arc::new(metadata_fetcher);
+
+    let mut enabled_types: Vec<&str> = Vec::new();
+    let mut erc20_grpc_service: Option<Erc20Service> = None;
+    let mut erc721_grpc_service: Option<Erc721Service> = None;
+    let mut erc1155_grpc_service: Option<Erc1155Service> = None;
+    let mut tokens_service = tokens_service::TokensService::new();
+    let mut token_uri_services = Vec::new();
+
+    let image_store = match config.image_store_config()? {
+        Some(store_config) => {
+            tracing::info!(
+                "Caching token images in {:?} bucket {}",
+                store_config.provider,
+                store_config.bucket
+            );
+            Some(Arc::new(ObjectStore::new(store_config)?))
+        }
+        None => None,
+    };
+    let image_cache = image_store.clone().map_or_else(
+        || ImageCache::Local(Path::new("./data").join("image-cache")),
+        ImageCache::Object,
+    );
+    let image_url_ttl = std::time::Duration::from_secs(config.image_url_ttl);
+
     if create_erc20 {
         enabled_types.push("ERC20");
 
-        let storage = Arc::new(Erc20Storage::new(&db_setup.erc20_url).await?);
-        tracing::info!("ERC20 database initialized: {}", db_setup.erc20_url);
+        let storage = ShardedErc20Storage::open(&db_setup.erc20_url, config.storage_shards).await?;
+        tracing::info!(
+            "ERC20 database initialized: {} ({} shards)",
+            db_setup.erc20_url,
+            storage.shards().len()
+        );
 
-        let decoder = Arc::new(Erc20Decoder::new());
+        let decoder = Arc::new(
+            Erc20Decoder::new()
+                .with_selector_aliases(selector_aliases.remove("erc20").unwrap_or_default()),
+        );
         torii_config = torii_config.add_decoder(decoder);
 
-        let grpc_service = Erc20Service::new(storage.clone());
-        torii_config =
-            torii_config.with_command_handler(Box::new(Erc20MetadataCommandHandler::new(
+        let index_only =
+            config.erc20_index_only || config.erc20_balance_scope == Erc20BalanceScope::None;
+        let mut grpc_service = Erc20Service::new(storage.clone())
+            .with_index_only(index_only)
+            .with_labels(address_labels.clone())
+            .with_admin_rpc(config.admin_rpc);
+        let balance_scope = match config.erc20_balance_scope {
+            Erc20BalanceScope::All => BalanceScope::All,
+            Erc20BalanceScope::None => BalanceScope::None,
+            Erc20BalanceScope::Watched => {
+                let watchlist = BalanceWatchlist::load(
+                    storage.clone(),
+                    Arc::new(BalanceFetcher::new(provider.clone())),
+                )
+                .await?;
+                let seeded = watchlist.add(&config.erc20_watched_accounts()?).await?;
+                tracing::info!(
+                    "ERC20 balances scoped to {} watched accounts ({} added at startup)",
+                    watchlist.len(),
+                    seeded.added
+                );
+                grpc_service = grpc_service.with_balance_watchlist(watchlist.clone());
+                BalanceScope::Watched(watchlist)
+            }
+        };
+        torii_config = torii_config.with_command_handler(Box::new(
+            Erc20MetadataCommandHandler::new(
                 provider.clone(),
                 storage.clone(),
                 config.metadata_max_retries,
-            )));
-        let sink = Box::new(
-            Erc20Sink::new(storage)
-                .with_grpc_service(grpc_service.clone())
-                .with_balance_tracking(provider.clone())
-                .with_metadata_pipeline(
-                    config.metadata_parallelism,
-                    config.metadata_queue_capacity,
-                    config.metadata_max_retries,
-                ),
-        );
+            )
+            .with_fetcher(metadata_fetcher.clone()),
+        ));
+        tokens_service = tokens_service.with_erc20(storage.clone());
+        let mut sink = Erc20Sink::new(storage)
+            .with_grpc_service(grpc_service.clone())
+            .with_balance_tracking(provider.clone())
+            .with_index_only(config.erc20_index_only)
+            .with_balance_scope(balance_scope)
+            .with_metadata_pipeline(
+                config.metadata_parallelism,
+                config.metadata_queue_capacity,
+                config.metadata_max_retries,
+            );
+        if let Some(filter) = contract_filters.remove("erc20") {
+            sink = sink.with_contract_filter(filter);
+        }
+        if let Some(interval) = config.sqlite_maintenance_interval() {
+            sink = sink.with_maintenance_interval(interval);
+        }
+        if let Some(price_feed) = build_price_feed(&config, &provider)? {
+            tracing::info!(
+                "ERC20 price feed: {} ({} block windows)",
+                price_feed.name(),
+                config.price_window_blocks
+            );
+            sink = sink.with_price_feed(price_feed, config.price_window_blocks);
+        }
+        let sink = Box::new(sink);
         torii_config = torii_config.add_sink_boxed(sink);
 
         erc20_grpc_service = Some(grpc_service);
@@ -586,27 +1030,48 @@ async fn run_indexer(config: Config) -> Result<()> {
     if create_erc721 {
         enabled_types.push("ERC721");
 
-        let storage = Arc::new(Erc721Storage::new(&db_setup.erc721_url).await?);
-        tracing::info!("ERC721 database initialized: {}", db_setup.erc721_url);
+        let storage =
+            ShardedErc721Storage::open(&db_setup.erc721_url, config.storage_shards).await?;
+        tracing::info!(
+            "ERC721 database initialized: {} ({} shards)",
+            db_setup.erc721_url,
+            storage.shards().len()
+        );
 
-        let decoder = Arc::new(Erc721Decoder::new());
+        let decoder = Arc::new(
+            Erc721Decoder::new()
+                .with_selector_aliases(selector_aliases.remove("erc721").unwrap_or_default()),
+        );
         torii_config = torii_config.add_decoder(decoder);
 
-        let grpc_service = Erc721Service::new(storage.clone());
-        torii_config =
-            torii_config.with_command_handler(Box::new(Erc721MetadataCommandHandler::new(
+        let mut grpc_service =
+            Erc721Service::new(storage.clone()).with_labels(address_labels.clone());
+        if let Some(store) = &image_store {
+            grpc_service = grpc_service.with_object_store(store.clone(), image_url_ttl);
+        }
+        torii_config = torii_config.with_command_handler(Box::new(
+            Erc721MetadataCommandHandler::new(
                 provider.clone(),
                 storage.clone(),
                 config.metadata_max_retries,
-            )));
+            )
+            .with_fetcher(metadata_fetcher.clone()),
+        ));
+        tokens_service = tokens_service.with_erc721(storage.clone());
         let mut sink = Erc721Sink::new(storage).with_grpc_service(grpc_service.clone());
+        if let Some(filter) = contract_filters.remove("erc721") {
+            sink = sink.with_contract_filter(filter);
+        }
+        if let Some(interval) = config.sqlite_maintenance_interval() {
+            sink = sink.with_maintenance_interval(interval);
+        }
         if effective_metadata_mode == MetadataMode::Inline {
-            let (token_uri_sender, token_uri_service) = TokenUriService::spawn_with_image_cache(
-                Arc::new(MetadataFetcher::new(provider.clone())),
-                sink.storage().clone(),
+            let (token_uri_sender, token_uri_service) = TokenUriService::spawn_with_cache(
+                metadata_fetcher.clone(),
+                Arc::new(sink.storage().clone()),
                 config.metadata_queue_capacity,
                 config.metadata_parallelism.max(1),
-                Some(Path::new("./data").join("image-cache")),
+                Some(image_cache.clone()),
                 4,
             );
             token_uri_services.push(token_uri_service);
@@ -641,26 +1106,45 @@ async fn run_indexer(config: Config) -> Result<()> {
     if create_erc1155 {
         enabled_types.push("ERC1155");
 
-        let storage = Arc::new(Erc1155Storage::new(&db_setup.erc1155_url).await?);
-        tracing::info!("ERC1155 database initialized: {}", db_setup.erc1155_url);
+        let storage =
+            ShardedErc1155Storage::open(&db_setup.erc1155_url, config.storage_shards).await?;
+        tracing::info!(
+            "ERC1155 database initialized: {} ({} shards)",
+            db_setup.erc1155_url,
+            storage.shards().len()
+        );
 
-        let decoder = Arc::new(Erc1155Decoder::new());
+        let decoder = Arc::new(
+            Erc1155Decoder::new()
+                .with_selector_aliases(selector_aliases.remove("erc1155").unwrap_or_default()),
+        );
         torii_config = torii_config.add_decoder(decoder);
 
-        let grpc_service = Erc1155Service::new(storage.clone());
+        let mut grpc_service = Erc1155Service::new(storage.clone());
+        if let Some(store) = &image_store {
+            grpc_service = grpc_service.with_object_store(store.clone(), image_url_ttl);
+        }
         torii_config = torii_config.with_command_handler(Box::new(
-            Erc1155MetadataCommandHandler::new(provider.clone(), storage.clone()),
+            Erc1155MetadataCommandHandler::new(provider.clone(), storage.clone())
+                .with_fetcher(metadata_fetcher.clone()),
         ));
+        tokens_service = tokens_service.with_erc1155(storage.clone());
         let mut sink = Erc1155Sink::new(storage)
             .with_grpc_service(grpc_service.clone())
             .with_balance_tracking(provider.clone());
+        if let Some(filter) = contract_filters.remove("erc1155") {
+            sink = sink.with_contract_filter(filter);
+        }
+        if let Some(interval) = config.sqlite_maintenance_interval() {
+            sink = sink.with_maintenance_interval(interval);
+        }
         if effective_metadata_mode == MetadataMode::Inline {
-            let (token_uri_sender, token_uri_service) = TokenUriService::spawn_with_image_cache(
-                Arc::new(MetadataFetcher::new(provider.clone())),
-                sink.storage().clone(),
+            let (token_uri_sender, token_uri_service) = TokenUriService::spawn_with_cache(
+                metadata_fetcher.clone(),
+                Arc::new(sink.storage().clone()),
                 config.metadata_queue_capacity,
                 config.metadata_parallelism.max(1),
-                Some(Path::new("./data").join("image-cache")),
+                Some(image_cache.clone()),
                 4,
             );
             token_uri_services.push(token_uri_service);
@@ -692,17 +1176,84 @@ async fn run_indexer(config: Config) -> Result<()> {
         }
     }
 
-    let reflection = reflection_builder
-        .build_v1()
-        .expect("Failed to build gRPC reflection service")
-        .accept_compressed(CompressionEncoding::Gzip);
+    let relay_server = if config.relay {
+        let storage = RelayStorage::open(&db_setup.relay_url).await?;
+        let relay = Arc::new(Relay::new(
+            storage,
+            Arc::new(AccountSignatureVerifier::new(provider.clone())),
+        ));
+        let sink = RelaySink::new(relay);
+        let service = sink.grpc_service();
+        torii_config = torii_config.add_sink_boxed(Box::new(sink));
+        reflection_builder =
+            reflection_builder.register_encoded_file_descriptor_set(RELAY_DESCRIPTOR_SET);
+        tracing::info!("Offchain message relay enabled ({})", db_setup.relay_url);
+        Some(InterceptedService::new(
+            configure_grpc_server!(RelayServer::new(service), grpc_options),
+            namespaces.interceptor(&["relay"]),
+        ))
+    } else {
+        None
+    };
 
-    let erc20_server = erc20_grpc_service
-        .map(|service| Erc20Server::new(service).accept_compressed(CompressionEncoding::Gzip));
-    let erc721_server = erc721_grpc_service
-        .map(|service| Erc721Server::new(service).accept_compressed(CompressionEncoding::Gzip));
-    let erc1155_server = erc1155_grpc_service
-        .map(|service| Erc1155Server::new(service).accept_compressed(CompressionEncoding::Gzip));
+    let account_server = if config.accounts {
+        let storage = AccountStorage::open(&db_setup.accounts_url).await?;
+        let sink = AccountSink::new(storage);
+        let service = sink.grpc_service();
+        torii_config = torii_config
+            .add_decoder(Arc::new(AccountDecoder::new()))
+            .add_sink_boxed(Box::new(sink));
+        reflection_builder =
+            reflection_builder.register_encoded_file_descriptor_set(ACCOUNT_DESCRIPTOR_SET);
+        enabled_types.push("Accounts");
+        tracing::info!(
+            "Account activity indexing enabled ({})",
+            db_setup.accounts_url
+        );
+        Some(InterceptedService::new(
+            configure_grpc_server!(AccountServer::new(service), grpc_options),
+            namespaces.interceptor(&["account"]),
+        ))
+    } else {
+        None
+    };
+
+    let tokens_server = if create_erc20 || create_erc721 || create_erc1155 {
+        reflection_builder =
+            reflection_builder.register_encoded_file_descriptor_set(TOKENS_DESCRIPTOR_SET);
+        Some(InterceptedService::new(
+            configure_grpc_server!(TokensServer::new(tokens_service), grpc_options),
+            namespaces.interceptor(&["erc20", "erc721", "erc1155"]),
+        ))
+    } else {
+        None
+    };
+
+    let reflection = configure_grpc_server!(
+        reflection_builder
+            .build_v1()
+            .expect("Failed to build gRPC reflection service"),
+        grpc_options
+    );
+
+    let erc20_server = erc20_grpc_service.map(|service| {
+        InterceptedService::new(
+            configure_grpc_server!(Erc20Server::new(service), grpc_options),
+            namespaces.interceptor(&["erc20"]),
+        )
+    });
+    let erc721_server = erc721_grpc_service.map(|service| {
+        InterceptedService::new(
+            configure_grpc_server!(Erc721Server::new(service), grpc_options),
+            namespaces.interceptor(&["erc721"]),
+        )
+    });
+    let erc1155_server = erc1155_grpc_service.map(|service| {
+        InterceptedService::new(
+            configure_grpc_server!(Erc1155Server::new(service), grpc_options),
+            namespaces.interceptor(&["erc1155"]),
+        )
+    });
 
     let mut grpc_builder = tonic::transport::Server::builder();
     let grpc_router = match (erc20_server, erc721_server, erc1155_server) {
@@ -737,12 +1288,20 @@ async fn run_indexer(config: Config) -> Result<()> {
             grpc_builder.add_service(reflection)
         }
     };
+    let grpc_router = grpc_router
+        .add_optional_service(tokens_server.map(tonic_web::enable))
+        .add_optional_service(relay_server.map(tonic_web::enable))
+        .add_optional_service(account_server.map(tonic_web::enable));
 
     let torii_config = torii_config
         .with_grpc_router(grpc_router)
         .with_custom_reflection(true)
         .build();
 
+    if config.dry_run {
+        return dry_run(torii_config).await;
+    }
+
     tracing::info!("Torii configured, starting ETL pipeline...");
     tracing::info!("Enabled token types: {}", enabled_types.join(", "));
     tracing::info!(
@@ -766,6 +1325,12 @@ async fn run_indexer(config: Config) -> Result<()> {
     if create_erc1155 {
         tracing::info!("  - torii.sinks.erc1155.Erc1155 (ERC1155 queries and subscriptions)");
     }
+    if config.relay {
+        tracing::info!("  - torii.relay.Relay (offchain message relay)");
+    }
+    if config.accounts {
+        tracing::info!("  - torii.sinks.account.Account (account activity)");
+    }
 
     torii::run(torii_config)
         .await
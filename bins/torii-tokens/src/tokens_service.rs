@@ -0,0 +1,143 @@
+//! `torii.tokens.Tokens` gRPC service: queries spanning the token indexes.
+//!
+//! Each token sink serves its own standard; this service reads the ERC20, ERC721
+//! and ERC1155 storages that are enabled and merges their results.
+
+use starknet::core::types::{Felt, U256};
+use tonic::{Request, Response, Status};
+use torii_common::{bytes_to_felt, u256_to_bytes};
+use torii_erc1155::storage::TokenTransferData;
+use torii_erc1155::ShardedErc1155Storage;
+use torii_erc20::storage::TransferData;
+use torii_erc20::ShardedErc20Storage;
+use torii_erc721::storage::NftTransferData;
+use torii_erc721::ShardedErc721Storage;
+
+use crate::proto::tokens_server::Tokens;
+use crate::proto::{
+    GetTransactionEventsRequest, GetTransactionEventsResponse, TokenEvent, TokenStandard,
+};
+
+/// Tokens service over the enabled token storages.
+#[derive(Clone, Default)]
+pub struct TokensService {
+    erc20: Option<ShardedErc20Storage>,
+    erc721: Option<ShardedErc721Storage>,
+    erc1155: Option<ShardedErc1155Storage>,
+}
+
+impl TokensService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_erc20(mut self, storage: ShardedErc20Storage) -> Self {
+        self.erc20 = Some(storage);
+        self
+    }
+
+    pub fn with_erc721(mut self, storage: ShardedErc721Storage) -> Self {
+        self.erc721 = Some(storage);
+        self
+    }
+
+    pub fn with_erc1155(mut self, storage: ShardedErc1155Storage) -> Self {
+        self.erc1155 = Some(storage);
+        self
+    }
+
+    /// Token movements of `tx_hash` in every enabled storage, in event order.
+    pub async fn transaction_events(&self, tx_hash: Felt) -> anyhow::Result<Vec<TokenEvent>> {
+        let (erc20, erc721, erc1155) = tokio::try_join!(
+            async {
+                match &self.erc20 {
+                    Some(storage) => storage.get_transfers_by_tx(tx_hash).await,
+                    None => Ok(Vec::new()),
+                }
+            },
+            async {
+                match &self.erc721 {
+                    Some(storage) => storage.get_transfers_by_tx(tx_hash).await,
+                    None => Ok(Vec::new()),
+                }
+            },
+            async {
+                match &self.erc1155 {
+                    Some(storage) => storage.get_transfers_by_tx(tx_hash).await,
+                    None => Ok(Vec::new()),
+                }
+            },
+        )?;
+
+        let mut events: Vec<TokenEvent> = erc20
+            .into_iter()
+            .map(erc20_event)
+            .chain(erc721.into_iter().map(erc721_event))
+            .chain(erc1155.into_iter().map(erc1155_event))
+            .collect();
+        // Stable: events of one storage keep their (batch) order.
+        events.sort_by_key(|event| (event.event_index.is_none(), event.event_index));
+        Ok(events)
+    }
+}
+
+fn erc20_event(transfer: TransferData) -> TokenEvent {
+    TokenEvent {
+        standard: TokenStandard::Erc20 as i32,
+        token: transfer.token.to_bytes_be().to_vec(),
+        from: transfer.from.to_bytes_be().to_vec(),
+        to: transfer.to.to_bytes_be().to_vec(),
+        token_id: None,
+        amount: u256_to_bytes(transfer.amount),
+        operator: None,
+        block_number: transfer.block_number,
+        event_index: transfer.event_index,
+        timestamp: transfer.timestamp.unwrap_or(0),
+    }
+}
+
+fn erc721_event(transfer: NftTransferData) -> TokenEvent {
+    TokenEvent {
+        standard: TokenStandard::Erc721 as i32,
+        token: transfer.token.to_bytes_be().to_vec(),
+        from: transfer.from.to_bytes_be().to_vec(),
+        to: transfer.to.to_bytes_be().to_vec(),
+        token_id: Some(u256_to_bytes(transfer.token_id)),
+        amount: u256_to_bytes(U256::from(1u64)),
+        operator: None,
+        block_number: transfer.block_number,
+        event_index: transfer.event_index,
+        timestamp: transfer.timestamp.unwrap_or(0),
+    }
+}
+
+fn erc1155_event(transfer: TokenTransferData) -> TokenEvent {
+    TokenEvent {
+        standard: TokenStandard::Erc1155 as i32,
+        token: transfer.token.to_bytes_be().to_vec(),
+        from: transfer.from.to_bytes_be().to_vec(),
+        to: transfer.to.to_bytes_be().to_vec(),
+        token_id: Some(u256_to_bytes(transfer.token_id)),
+        amount: u256_to_bytes(transfer.amount),
+        operator: Some(transfer.operator.to_bytes_be().to_vec()),
+        block_number: transfer.block_number,
+        event_index: transfer.event_index,
+        timestamp: transfer.timestamp.unwrap_or(0),
+    }
+}
+
+#[tonic::async_trait]
+impl Tokens for TokensService {
+    async fn get_transaction_events(
+        &self,
+        request: Request<GetTransactionEventsRequest>,
+    ) -> Result<Response<GetTransactionEventsResponse>, Status> {
+        let tx_hash = bytes_to_felt(&request.into_inner().tx_hash)
+            .ok_or_else(|| Status::invalid_argument("Invalid transaction hash"))?;
+        let events = self
+            .transaction_events(tx_hash)
+            .await
+            .map_err(|e| Status::internal(format!("Query failed: {e}")))?;
+        Ok(Response::new(GetTransactionEventsResponse { events }))
+    }
+}
@@ -0,0 +1,263 @@
+//! `verify` subcommand: spot-checks stored token state against the chain.
+//!
+//! For each standard the latest indexed block is compared with the chain head, then a
+//! sample of stored balances (ERC20/ERC1155) or owners (ERC721) is re-read with
+//! `starknet_call` at the verification block. Any mismatch fails the command.
+
+use anyhow::Result;
+use starknet::core::types::{BlockId, Felt, FunctionCall, U256};
+use starknet::macros::selector;
+use starknet::providers::Provider;
+use std::path::Path;
+use std::sync::Arc;
+use torii_common::RpcProvider;
+use torii_erc1155::{Erc1155BalanceFetchRequest, Erc1155BalanceFetcher, ShardedErc1155Storage};
+use torii_erc20::{BalanceFetchRequest, BalanceFetcher, ShardedErc20Storage};
+use torii_erc721::ShardedErc721Storage;
+use torii_runtime_common::database::resolve_token_db_setup;
+
+use crate::config::{Standard, VerifyArgs};
+
+/// Outcome of verifying one standard.
+#[derive(Debug, Default)]
+struct Report {
+    checked: usize,
+    mismatches: usize,
+}
+
+pub async fn run(args: VerifyArgs) -> Result<()> {
+    let config = &args.config;
+    let provider = Arc::new(torii_common::rate_limited_provider(
+        url::Url::parse(&config.rpc_url)?,
+        config.rpc_rate_limit,
+        config.rpc_burst,
+    ));
+    let head = provider.block_number().await?;
+    let db_setup = resolve_token_db_setup(
+        Path::new(&config.db_dir),
+        config.database_url.as_deref(),
+        config.storage_database_url.as_deref(),
+    )?;
+
+    let mut mismatches = 0;
+    for standard in args.standards() {
+        let (latest, report) = match standard {
+            Standard::Erc20 => {
+                let storage =
+                    ShardedErc20Storage::open(&db_setup.erc20_url, config.storage_shards).await?;
+                let latest = storage.get_latest_block().await?;
+                let block = args.at_block.or(latest);
+                let report = match block {
+                    Some(block) => verify_erc20(&storage, &provider, block, args.sample).await?,
+                    None => Report::default(),
+                };
+                (latest, report)
+            }
+            Standard::Erc721 => {
+                let storage =
+                    ShardedErc721Storage::open(&db_setup.erc721_url, config.storage_shards).await?;
+                let latest = storage.get_latest_block().await?;
+                let block = args.at_block.or(latest);
+                let report = match block {
+                    Some(block) => verify_erc721(&storage, &provider, block, args.sample).await?,
+                    None => Report::default(),
+                };
+                (latest, report)
+            }
+            Standard::Erc1155 => {
+                let storage =
+                    ShardedErc1155Storage::open(&db_setup.erc1155_url, config.storage_shards)
+                        .await?;
+                let latest = storage.get_latest_block().await?;
+                let block = args.at_block.or(latest);
+                let report = match block {
+                    Some(block) => verify_erc1155(&storage, &provider, block, args.sample).await?,
+                    None => Report::default(),
+                };
+                (latest, report)
+            }
+        };
+
+        match latest {
+            Some(latest) => tracing::info!(
+                standard = ?standard,
+                latest_block = latest,
+                chain_head = head,
+                lag = head.saturating_sub(latest),
+                checked = report.checked,
+                mismatches = report.mismatches,
+                "Verified stored state"
+            ),
+            None => tracing::warn!(standard = ?standard, "Nothing indexed yet, skipping"),
+        }
+        mismatches += report.mismatches;
+    }
+
+    if mismatches > 0 {
+        anyhow::bail!("{mismatches} stored value(s) differ from the chain");
+    }
+    Ok(())
+}
+
+/// Re-reads a sample of stored ERC20 balances with `balance_of`.
+async fn verify_erc20(
+    storage: &ShardedErc20Storage,
+    provider: &Arc<RpcProvider>,
+    block: u64,
+    sample: u32,
+) -> Result<Report> {
+    let (balances, _) = storage
+        .get_balances_filtered(None, None, None, sample)
+        .await?;
+    // Balances touched after the verification block can't be compared.
+    let balances: Vec<_> = balances
+        .into_iter()
+        .filter(|b| b.last_block <= block)
+        .collect();
+    let requests: Vec<_> = balances
+        .iter()
+        .map(|b| BalanceFetchRequest {
+            token: b.token,
+            wallet: b.wallet,
+            block_number: block,
+        })
+        .collect();
+    let onchain = BalanceFetcher::new(provider.clone())
+        .fetch_balances_batch(&requests)
+        .await?;
+
+    let mut report = Report::default();
+    for (stored, (_, _, actual)) in balances.iter().zip(onchain) {
+        report.checked += 1;
+        if stored.balance != actual {
+            report.mismatches += 1;
+            tracing::warn!(
+                token = %format!("{:#x}", stored.token),
+                wallet = %format!("{:#x}", stored.wallet),
+                stored = %stored.balance,
+                onchain = %actual,
+                "ERC20 balance mismatch"
+            );
+        }
+    }
+    Ok(report)
+}
+
+/// Re-reads the owners of recently transferred tokens with `owner_of`.
+async fn verify_erc721(
+    storage: &ShardedErc721Storage,
+    provider: &Arc<RpcProvider>,
+    block: u64,
+    sample: u32,
+) -> Result<Report> {
+    let (transfers, _) = storage
+        .get_transfers_filtered(None, None, None, &[], &[], None, Some(block), None, sample)
+        .await?;
+
+    let mut report = Report::default();
+    for transfer in transfers {
+        let Some(stored) = storage
+            .get_owner_at_block(transfer.token, transfer.token_id, block)
+            .await?
+            .map(|change| change.owner)
+            .filter(|owner| *owner != Felt::ZERO)
+        else {
+            continue;
+        };
+        let actual = provider
+            .call(
+                FunctionCall {
+                    contract_address: transfer.token,
+                    entry_point_selector: selector!("owner_of"),
+                    calldata: u256_calldata(transfer.token_id),
+                },
+                BlockId::Number(block),
+            )
+            .await?
+            .first()
+            .copied()
+            .unwrap_or_default();
+        report.checked += 1;
+        if stored != actual {
+            report.mismatches += 1;
+            tracing::warn!(
+                token = %format!("{:#x}", transfer.token),
+                token_id = %transfer.token_id,
+                stored = %format!("{stored:#x}"),
+                onchain = %format!("{actual:#x}"),
+                "ERC721 owner mismatch"
+            );
+        }
+    }
+    Ok(report)
+}
+
+/// Re-reads the balances of the recipients of recent transfers with `balance_of`.
+async fn verify_erc1155(
+    storage: &ShardedErc1155Storage,
+    provider: &Arc<RpcProvider>,
+    block: u64,
+    sample: u32,
+) -> Result<Report> {
+    let (transfers, _) = storage
+        .get_transfers_filtered(
+            None,
+            None,
+            None,
+            None,
+            &[],
+            &[],
+            None,
+            Some(block),
+            None,
+            sample,
+        )
+        .await?;
+
+    let mut stored = Vec::new();
+    for transfer in transfers.into_iter().filter(|t| t.to != Felt::ZERO) {
+        if let Some((balance, last_block)) = storage
+            .get_balance_with_block(transfer.token, transfer.to, transfer.token_id)
+            .await?
+        {
+            if last_block <= block {
+                stored.push((transfer.token, transfer.to, transfer.token_id, balance));
+            }
+        }
+    }
+    let requests: Vec<_> = stored
+        .iter()
+        .map(
+            |(contract, wallet, token_id, _)| Erc1155BalanceFetchRequest {
+                contract: *contract,
+                wallet: *wallet,
+                token_id: *token_id,
+                block_number: block,
+            },
+        )
+        .collect();
+    let onchain = Erc1155BalanceFetcher::new(provider.clone())
+        .fetch_balances_batch(&requests)
+        .await?;
+
+    let mut report = Report::default();
+    for ((contract, wallet, token_id, balance), (_, _, _, actual)) in stored.iter().zip(onchain) {
+        report.checked += 1;
+        if *balance != actual {
+            report.mismatches += 1;
+            tracing::warn!(
+                contract = %format!("{contract:#x}"),
+                wallet = %format!("{wallet:#x}"),
+                token_id = %token_id,
+                stored = %balance,
+                onchain = %actual,
+                "ERC1155 balance mismatch"
+            );
+        }
+    }
+    Ok(report)
+}
+
+fn u256_calldata(value: U256) -> Vec<Felt> {
+    vec![Felt::from(value.low()), Felt::from(value.high())]
+}
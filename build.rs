@@ -6,5 +6,27 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .build_client(true)
         .file_descriptor_set_path("target/descriptor.bin")
         .compile_protos(&["proto/torii.proto"], &["proto"])?;
+
+    // Embed the git commit for GetVersion/GetCapabilities.
+    // TORII_GIT_COMMIT can be set explicitly when building outside a git checkout (e.g. Docker).
+    let git_commit = std::env::var("TORII_GIT_COMMIT")
+        .ok()
+        .or_else(|| {
+            std::process::Command::new("git")
+                .args(["rev-parse", "--short=12", "HEAD"])
+                .output()
+                .ok()
+                .filter(|output| output.status.success())
+                .and_then(|output| String::from_utf8(output.stdout).ok())
+        })
+        .map(|commit| commit.trim().to_string())
+        .filter(|commit| !commit.is_empty())
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=TORII_GIT_COMMIT={git_commit}");
+    println!("cargo:rerun-if-env-changed=TORII_GIT_COMMIT");
+    if std::path::Path::new(".git/HEAD").exists() {
+        println!("cargo:rerun-if-changed=.git/HEAD");
+    }
+
     Ok(())
 }
@@ -0,0 +1,119 @@
+//! Minimal token contracts deployed by the integration tests.
+//!
+//! They only implement what the tests exercise (minting and transfers) and emit
+//! the standard `Transfer` events (`from` and `to` as keys, the ERC721 token ID as key).
+
+use starknet::ContractAddress;
+
+#[starknet::interface]
+pub trait ITestErc20<T> {
+    fn mint(ref self: T, recipient: ContractAddress, amount: u256);
+    fn transfer(ref self: T, recipient: ContractAddress, amount: u256) -> bool;
+    fn balance_of(self: @T, account: ContractAddress) -> u256;
+}
+
+#[starknet::interface]
+pub trait ITestErc721<T> {
+    fn mint(ref self: T, to: ContractAddress, token_id: u256);
+    fn transfer_from(ref self: T, from: ContractAddress, to: ContractAddress, token_id: u256);
+    fn owner_of(self: @T, token_id: u256) -> ContractAddress;
+}
+
+#[starknet::contract]
+pub mod TestErc20 {
+    use core::num::traits::Zero;
+    use starknet::storage::{Map, StorageMapReadAccess, StorageMapWriteAccess};
+    use starknet::{ContractAddress, get_caller_address};
+
+    #[storage]
+    struct Storage {
+        balances: Map<ContractAddress, u256>,
+    }
+
+    #[event]
+    #[derive(Drop, starknet::Event)]
+    pub enum Event {
+        Transfer: Transfer,
+    }
+
+    #[derive(Drop, starknet::Event)]
+    pub struct Transfer {
+        #[key]
+        pub from: ContractAddress,
+        #[key]
+        pub to: ContractAddress,
+        pub value: u256,
+    }
+
+    #[abi(embed_v0)]
+    impl TestErc20Impl of super::ITestErc20<ContractState> {
+        fn mint(ref self: ContractState, recipient: ContractAddress, amount: u256) {
+            self.balances.write(recipient, self.balances.read(recipient) + amount);
+            self.emit(Transfer { from: Zero::zero(), to: recipient, value: amount });
+        }
+
+        fn transfer(ref self: ContractState, recipient: ContractAddress, amount: u256) -> bool {
+            let sender = get_caller_address();
+            let balance = self.balances.read(sender);
+            assert(balance >= amount, 'insufficient balance');
+            self.balances.write(sender, balance - amount);
+            self.balances.write(recipient, self.balances.read(recipient) + amount);
+            self.emit(Transfer { from: sender, to: recipient, value: amount });
+            true
+        }
+
+        fn balance_of(self: @ContractState, account: ContractAddress) -> u256 {
+            self.balances.read(account)
+        }
+    }
+}
+
+#[starknet::contract]
+pub mod TestErc721 {
+    use core::num::traits::Zero;
+    use starknet::storage::{Map, StorageMapReadAccess, StorageMapWriteAccess};
+    use starknet::{ContractAddress, get_caller_address};
+
+    #[storage]
+    struct Storage {
+        owners: Map<u256, ContractAddress>,
+    }
+
+    #[event]
+    #[derive(Drop, starknet::Event)]
+    pub enum Event {
+        Transfer: Transfer,
+    }
+
+    #[derive(Drop, starknet::Event)]
+    pub struct Transfer {
+        #[key]
+        pub from: ContractAddress,
+        #[key]
+        pub to: ContractAddress,
+        #[key]
+        pub token_id: u256,
+    }
+
+    #[abi(embed_v0)]
+    impl TestErc721Impl of super::ITestErc721<ContractState> {
+        fn mint(ref self: ContractState, to: ContractAddress, token_id: u256) {
+            assert(self.owners.read(token_id).is_zero(), 'token already minted');
+            self.owners.write(token_id, to);
+            self.emit(Transfer { from: Zero::zero(), to, token_id });
+        }
+
+        fn transfer_from(
+            ref self: ContractState, from: ContractAddress, to: ContractAddress, token_id: u256,
+        ) {
+            assert(self.owners.read(token_id) == from, 'wrong owner');
+            assert(get_caller_address() == from, 'caller is not owner');
+            self.owners.write(token_id, to);
+            self.emit(Transfer { from, to, token_id });
+        }
+
+        fn owner_of(self: @ContractState, token_id: u256) -> ContractAddress {
+            self.owners.read(token_id)
+        }
+    }
+}
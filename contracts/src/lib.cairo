@@ -7,6 +7,8 @@
 //! This is useful for indexers like Torii that need to identify contract types
 //! for many contracts efficiently.
 
+pub mod fixtures;
+
 use starknet::ContractAddress;
 
 /// SRC-5 supports_interface selector.
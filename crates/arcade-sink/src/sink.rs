@@ -11,6 +11,7 @@ use torii::etl::{
     extractor::ExtractionBatch,
     sink::{EventBus, Sink, SinkContext, TopicInfo},
 };
+use torii::ToriiResult;
 use torii_introspect::events::{IntrospectBody, IntrospectMsg};
 
 use crate::grpc_service::ArcadeService;
@@ -313,11 +314,15 @@ impl Sink for ArcadeSink {
         "arcade"
     }
 
+    fn descriptor_set(&self) -> Option<&'static [u8]> {
+        Some(crate::FILE_DESCRIPTOR_SET)
+    }
+
     fn interested_types(&self) -> Vec<TypeId> {
         vec![TypeId::new("introspect")]
     }
 
-    async fn process(&self, envelopes: &[Envelope], _batch: &ExtractionBatch) -> Result<()> {
+    async fn process(&self, envelopes: &[Envelope], _batch: &ExtractionBatch) -> ToriiResult<()> {
         let mut plan = self.build_batch_plan(envelopes);
 
         if plan.reload_tracked_tables {
@@ -361,7 +366,7 @@ impl Sink for ArcadeSink {
         &mut self,
         _event_bus: Arc<EventBus>,
         _context: &SinkContext,
-    ) -> Result<()> {
+    ) -> ToriiResult<()> {
         Ok(())
     }
 }
@@ -23,6 +23,7 @@ use std::fmt::Debug;
 use std::sync::RwLock;
 use torii::etl::event::EmittedEventExt;
 use torii::etl::{Decoder, Envelope, EventMsg};
+use torii::ToriiResult;
 use torii_introspect::events::{IntrospectBody, IntrospectMsg};
 use torii_introspect::schema::{TableMetadata, TableSchema};
 use torii_introspect::EventId;
@@ -322,7 +323,7 @@ where
         "dojo-introspect"
     }
 
-    async fn decode_event(&self, event: &EmittedEvent) -> AnyResult<Vec<Envelope>> {
+    async fn decode_event(&self, event: &EmittedEvent) -> ToriiResult<Vec<Envelope>> {
         let (selector, keys) = event
             .split_keys()
             .ok_or(DojoToriiError::MissingEventSelector)?;
@@ -0,0 +1,79 @@
+use super::DojoStoreTrait;
+use crate::DojoTable;
+use async_trait::async_trait;
+use starknet_types_core::felt::Felt;
+use std::sync::Arc;
+use torii::etl::EngineDb;
+
+/// Decoder name table definitions are recorded under in the engine database
+pub const DOJO_ENGINE_DB_DECODER: &str = "dojo";
+
+#[derive(Debug, thiserror::Error)]
+pub enum EngineDbStoreError {
+    #[error(transparent)]
+    EngineDb(#[from] anyhow::Error),
+    #[error(transparent)]
+    SerdeError(#[from] serde_json::Error),
+}
+
+/// Stores Dojo table definitions in the engine database.
+///
+/// Each table is stored as JSON, versioned per (owner, table): a new version is
+/// recorded whenever a schema upgrade changes the definition, and reads return
+/// the latest version. Definitions survive restarts and are independent of the
+/// sink database.
+#[derive(Clone)]
+pub struct EngineDbStore {
+    engine_db: Arc<EngineDb>,
+}
+
+impl EngineDbStore {
+    pub fn new(engine_db: Arc<EngineDb>) -> Self {
+        Self { engine_db }
+    }
+
+    pub fn engine_db(&self) -> &Arc<EngineDb> {
+        &self.engine_db
+    }
+}
+
+impl From<Arc<EngineDb>> for EngineDbStore {
+    fn from(engine_db: Arc<EngineDb>) -> Self {
+        Self::new(engine_db)
+    }
+}
+
+#[async_trait]
+impl DojoStoreTrait for EngineDbStore {
+    type Error = EngineDbStoreError;
+
+    async fn save_table(
+        &self,
+        owner: &Felt,
+        table: &DojoTable,
+        tx_hash: &Felt,
+        block_number: u64,
+    ) -> Result<(), Self::Error> {
+        let definition = serde_json::to_string(table)?;
+        self.engine_db
+            .save_table_definition(
+                DOJO_ENGINE_DB_DECODER,
+                *owner,
+                table.id,
+                &definition,
+                block_number,
+                *tx_hash,
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn read_tables(&self, owners: &[Felt]) -> Result<Vec<DojoTable>, Self::Error> {
+        self.engine_db
+            .get_table_definitions(DOJO_ENGINE_DB_DECODER, owners)
+            .await?
+            .iter()
+            .map(|definition| serde_json::from_str(&definition.definition).map_err(Into::into))
+            .collect()
+    }
+}
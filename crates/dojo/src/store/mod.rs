@@ -1,3 +1,4 @@
+pub mod engine_db;
 pub mod json;
 pub mod postgres;
 pub mod sqlite;
@@ -5,6 +6,7 @@ pub mod sqlite;
 use crate::table::DojoTableInfo;
 use crate::DojoTable;
 use async_trait::async_trait;
+use engine_db::{EngineDbStore, EngineDbStoreError};
 use starknet_types_core::felt::Felt;
 use std::collections::HashMap;
 
@@ -34,3 +36,54 @@ where
             .collect())
     }
 }
+
+/// Table store kept in the sink database or in the engine database
+pub enum DojoStore<S> {
+    Sink(S),
+    EngineDb(EngineDbStore),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DojoStoreError<E: std::error::Error> {
+    #[error(transparent)]
+    Sink(E),
+    #[error(transparent)]
+    EngineDb(#[from] EngineDbStoreError),
+}
+
+#[async_trait]
+impl<S> DojoStoreTrait for DojoStore<S>
+where
+    S: DojoStoreTrait,
+    S::Error: Send + Sync,
+{
+    type Error = DojoStoreError<S::Error>;
+
+    async fn save_table(
+        &self,
+        owner: &Felt,
+        table: &DojoTable,
+        tx_hash: &Felt,
+        block_number: u64,
+    ) -> Result<(), Self::Error> {
+        match self {
+            Self::Sink(store) => store
+                .save_table(owner, table, tx_hash, block_number)
+                .await
+                .map_err(DojoStoreError::Sink),
+            Self::EngineDb(store) => Ok(store
+                .save_table(owner, table, tx_hash, block_number)
+                .await?),
+        }
+    }
+
+    async fn read_tables(&self, owners: &[Felt]) -> Result<Vec<DojoTable>, Self::Error> {
+        match self {
+            Self::Sink(store) => store
+                .read_tables(owners)
+                .await
+                .map_err(DojoStoreError::Sink),
+            Self::EngineDb(store) => Ok(store.read_tables(owners).await?),
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use super::DojoStoreTrait;
+use super::{DojoStore, DojoStoreTrait};
 use crate::decoder::primary_field_def;
 use crate::table::DojoTableInfo;
 use crate::DojoTable;
@@ -323,6 +323,16 @@ impl<T: PostgresConnection + Send + Sync> PgStore<T> {
     }
 }
 
+impl<T: PostgresConnection + Send + Sync> DojoStore<PgStore<T>> {
+    /// Runs the sink store migrations; the engine database migrates itself.
+    pub async fn initialize(&self) -> SqlxResult<()> {
+        match self {
+            Self::Sink(store) => store.initialize().await,
+            Self::EngineDb(_) => Ok(()),
+        }
+    }
+}
+
 impl<T: PostgresConnection> From<T> for PgStore<T> {
     fn from(pool: T) -> Self {
         PgStore(pool)
@@ -1,4 +1,4 @@
-use super::DojoStoreTrait;
+use super::{DojoStore, DojoStoreTrait};
 use crate::decoder::primary_field_def;
 use crate::DojoTable;
 use async_trait::async_trait;
@@ -169,6 +169,16 @@ impl<T: SqliteConnection + Send + Sync> SqliteStore<T> {
     }
 }
 
+impl<T: SqliteConnection + Send + Sync> DojoStore<SqliteStore<T>> {
+    /// Runs the sink store migrations; the engine database migrates itself.
+    pub async fn initialize(&self) -> SqlxResult<()> {
+        match self {
+            Self::Sink(store) => store.initialize().await,
+            Self::EngineDb(_) => Ok(()),
+        }
+    }
+}
+
 impl<T: SqliteConnection> From<T> for SqliteStore<T> {
     fn from(pool: T) -> Self {
         Self(pool)
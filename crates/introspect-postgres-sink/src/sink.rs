@@ -8,6 +8,7 @@ use torii::etl::{
     extractor::ExtractionBatch,
     sink::{EventBus, Sink, SinkContext, TopicInfo},
 };
+use torii::ToriiResult;
 use torii_introspect::events::{IntrospectBody, IntrospectMsg};
 use torii_postgres::PostgresConnection;
 
@@ -24,7 +25,7 @@ impl<T: Send + Sync + PostgresConnection> Sink for IntrospectPgDb<T> {
         vec![INTROSPECT_TYPE]
     }
 
-    async fn process(&self, envelopes: &[Envelope], _batch: &ExtractionBatch) -> Result<()> {
+    async fn process(&self, envelopes: &[Envelope], _batch: &ExtractionBatch) -> ToriiResult<()> {
         let mut processed = 0usize;
         let mut create_tables: usize = 0usize;
         let mut update_tables = 0usize;
@@ -91,7 +92,7 @@ impl<T: Send + Sync + PostgresConnection> Sink for IntrospectPgDb<T> {
         &mut self,
         _event_bus: Arc<EventBus>,
         _context: &SinkContext,
-    ) -> Result<()> {
+    ) -> ToriiResult<()> {
         self.initialize_introspect_pg_sink().await?;
         tracing::info!(
             target: LOGGING_TARGET,
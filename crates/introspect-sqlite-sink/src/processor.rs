@@ -1,18 +1,20 @@
 use crate::json::SqliteJsonSerializer;
-use crate::table::{SqliteTable, SqliteTableError};
+use crate::table::{NestedStrategy, SqliteChildTable, SqliteColumn, SqliteTable, SqliteTableError};
 use crate::INTROSPECT_SQLITE_SINK_MIGRATIONS;
-use introspect_types::{PrimaryTypeDef, TypeDef};
-use serde_json::{Serializer as JsonSerializer, Value};
+use introspect_types::{Attributes, ColumnDef, PrimaryTypeDef, PrimaryValue, TypeDef};
+use serde_json::{Map, Serializer as JsonSerializer, Value};
+use sqlx::query::Query;
+use sqlx::sqlite::SqliteArguments;
 use sqlx::Error as SqlxError;
-use sqlx::Row;
+use sqlx::{Row, Sqlite};
 use starknet_types_core::felt::Felt;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Display;
 use std::ops::Deref;
 use std::sync::{PoisonError, RwLock};
 use torii::etl::envelope::MetaData;
 use torii::etl::EventMsg;
-use torii_introspect::events::{IntrospectBody, IntrospectMsg};
+use torii_introspect::events::{DeleteRecords, DeletesFields, IntrospectBody, IntrospectMsg};
 use torii_introspect::schema::TableSchema;
 use torii_introspect::InsertsFields;
 use torii_sqlite::SqliteConnection;
@@ -105,6 +107,49 @@ impl Display for SqliteNamespace {
     }
 }
 
+/// Storage options of the introspect SQLite sink.
+#[derive(Debug, Clone)]
+pub struct SqliteSinkConfig {
+    /// Strategy for nested columns of tables without an override
+    pub nested: NestedStrategy,
+    /// Per-table strategy overrides, keyed by table name
+    pub table_nested: HashMap<String, NestedStrategy>,
+    /// Nesting levels expanded into columns by `Flatten` (deeper values stay JSONB)
+    pub max_flatten_depth: usize,
+}
+
+impl Default for SqliteSinkConfig {
+    fn default() -> Self {
+        Self {
+            nested: NestedStrategy::Json,
+            table_nested: HashMap::new(),
+            max_flatten_depth: 4,
+        }
+    }
+}
+
+impl SqliteSinkConfig {
+    /// Uses `strategy` for the nested columns of the table named `table`.
+    pub fn with_table_strategy(
+        mut self,
+        table: impl Into<String>,
+        strategy: NestedStrategy,
+    ) -> Self {
+        self.table_nested.insert(table.into(), strategy);
+        self
+    }
+
+    /// Strategy applied to the nested columns of the table named `table`.
+    pub fn nested_strategy(&self, table: &str) -> NestedStrategy {
+        self.table_nested.get(table).copied().unwrap_or(self.nested)
+    }
+
+    fn table(&self, namespace: &SqliteNamespace, table: TableSchema) -> (Felt, SqliteTable) {
+        let strategy = self.nested_strategy(&table.name);
+        SqliteTable::new_from_table(namespace.prefix(), table, strategy, self.max_flatten_depth)
+    }
+}
+
 impl SqliteTables {
     pub fn assert_table_not_exists(&self, id: &Felt, name: &str) -> SqliteDbResult<()> {
         match self.read()?.get(id) {
@@ -120,14 +165,15 @@ impl SqliteTables {
     pub fn create_table(
         &self,
         namespace: &SqliteNamespace,
+        config: &SqliteSinkConfig,
         to_table: impl Into<TableSchema>,
-    ) -> SqliteDbResult<(Felt, String)> {
+    ) -> SqliteDbResult<(Felt, Vec<String>)> {
         let table = to_table.into();
         self.assert_table_not_exists(&table.id, &table.name)?;
-        let (id, sqlite_table) = SqliteTable::new_from_table(namespace.prefix(), table);
-        let create_query = create_table_query(&sqlite_table);
+        let (id, sqlite_table) = config.table(namespace, table);
+        let create_queries = create_table_queries(&sqlite_table);
         self.write()?.insert(id, sqlite_table);
-        Ok((id, create_query))
+        Ok((id, create_queries))
     }
 
     pub fn set_table_dead(&self, id: &Felt) -> SqliteDbResult<()> {
@@ -189,25 +235,214 @@ fn is_json_type(type_def: &TypeDef) -> bool {
     )
 }
 
-fn create_table_query(table: &SqliteTable) -> String {
-    let primary_type = sqlite_primary_type(&table.primary.type_def);
-    let mut columns = Vec::with_capacity(table.columns.len() + 1);
-    columns.push(format!(
-        r#""{}" {primary_type} PRIMARY KEY"#,
-        table.primary.name
+/// Column attributes requesting a secondary index: Dojo `#[key]` members and fields
+/// flagged `index`.
+const INDEXED_ATTRIBUTES: [&str; 2] = ["key", "index"];
+
+/// Physical columns of `table` with a secondary index: top-level scalar columns
+/// carrying one of [`INDEXED_ATTRIBUTES`]. Nested and JSONB values are not indexed.
+fn indexed_columns(table: &SqliteTable) -> impl Iterator<Item = &SqliteColumn> + '_ {
+    table.stored.iter().filter(|column| {
+        column.path.is_empty()
+            && !is_json_type(&column.type_def)
+            && table.columns.get(&column.column).is_some_and(|info| {
+                INDEXED_ATTRIBUTES
+                    .iter()
+                    .any(|attribute| info.attributes.has_attribute(attribute))
+            })
+    })
+}
+
+fn index_name(storage_name: &str, column_name: &str) -> String {
+    format!("{storage_name}__{column_name}__idx")
+}
+
+/// Indexes of `new` that `old` (if any) does not have under the same name.
+fn create_index_queries(old: Option<&SqliteTable>, new: &SqliteTable) -> Vec<String> {
+    let existing: HashSet<String> = old
+        .into_iter()
+        .flat_map(|old| {
+            indexed_columns(old).map(|column| index_name(&old.storage_name, &column.name))
+        })
+        .collect();
+    indexed_columns(new)
+        .filter_map(|column| {
+            let name = index_name(&new.storage_name, &column.name);
+            (!existing.contains(&name)).then(|| {
+                format!(
+                    r#"CREATE INDEX IF NOT EXISTS "{name}" ON "{}"("{}")"#,
+                    new.storage_name, column.name
+                )
+            })
+        })
+        .collect()
+}
+
+/// Indexes of `old` that `new` does not keep under the same name. They are dropped
+/// before the columns change, as SQLite cannot drop or rename away an indexed column.
+fn drop_index_queries(old: &SqliteTable, new: &SqliteTable) -> Vec<String> {
+    let kept: HashSet<String> = indexed_columns(new)
+        .map(|column| index_name(&new.storage_name, &column.name))
+        .collect();
+    indexed_columns(old)
+        .map(|column| index_name(&old.storage_name, &column.name))
+        .filter(|name| !kept.contains(name))
+        .map(|name| format!(r#"DROP INDEX IF EXISTS "{name}""#))
+        .collect()
+}
+
+fn column_definitions(columns: &[SqliteColumn]) -> impl Iterator<Item = String> + '_ {
+    columns.iter().map(|column| {
+        format!(
+            r#""{}" {}"#,
+            column.name,
+            sqlite_column_type(&column.type_def)
+        )
+    })
+}
+
+fn create_table_queries(table: &SqliteTable) -> Vec<String> {
+    let primary = format!(
+        r#""{}" {} PRIMARY KEY"#,
+        table.primary.name,
+        sqlite_primary_type(&table.primary.type_def)
+    );
+    let mut queries = Vec::with_capacity(table.children.len() + 1);
+    queries.push(format!(
+        r#"CREATE TABLE IF NOT EXISTS "{}" ({});"#,
+        table.storage_name,
+        std::iter::once(primary.clone())
+            .chain(column_definitions(&table.stored))
+            .collect::<Vec<_>>()
+            .join(", ")
     ));
-    for column_id in &table.order {
-        let column = &table.columns[column_id];
-        let col_type = sqlite_column_type(&column.type_def);
-        columns.push(format!(r#""{}" {col_type}"#, column.name));
+    for child in &table.children {
+        queries.push(create_child_table_query(table, child));
     }
+    queries.extend(create_index_queries(None, table));
+    queries
+}
+
+fn create_child_table_query(table: &SqliteTable, child: &SqliteChildTable) -> String {
+    let primary = format!(
+        r#""{}" {} PRIMARY KEY REFERENCES "{}"("{}") ON DELETE CASCADE"#,
+        table.primary.name,
+        sqlite_primary_type(&table.primary.type_def),
+        table.storage_name,
+        table.primary.name
+    );
     format!(
         r#"CREATE TABLE IF NOT EXISTS "{}" ({});"#,
-        table.storage_name,
-        columns.join(", ")
+        child.storage_name,
+        std::iter::once(primary)
+            .chain(column_definitions(&child.columns))
+            .collect::<Vec<_>>()
+            .join(", ")
     )
 }
 
+/// Physical columns of `new` paired with the column storing the same value in `old`.
+fn matching_columns<'a>(
+    old: &'a [SqliteColumn],
+    new: &'a [SqliteColumn],
+) -> impl Iterator<Item = (Option<&'a SqliteColumn>, &'a SqliteColumn)> + 'a {
+    new.iter().map(|column| {
+        let existing = old
+            .iter()
+            .find(|existing| existing.column == column.column && existing.path == column.path);
+        (existing, column)
+    })
+}
+
+/// Renames, drops and adds the physical columns of `storage_name` from `old` to `new`,
+/// matching them by column id and nested path rather than by name.
+fn migrate_columns(
+    queries: &mut Vec<String>,
+    storage_name: &str,
+    old: &[SqliteColumn],
+    new: &[SqliteColumn],
+) {
+    for (existing, column) in matching_columns(old, new) {
+        match existing {
+            Some(existing) if existing.name != column.name => queries.push(format!(
+                r#"ALTER TABLE "{storage_name}" RENAME COLUMN "{}" TO "{}""#,
+                existing.name, column.name
+            )),
+            Some(_) => {}
+            None => queries.push(format!(
+                r#"ALTER TABLE "{storage_name}" ADD COLUMN "{}" {}"#,
+                column.name,
+                sqlite_column_type(&column.type_def)
+            )),
+        }
+    }
+    for column in old.iter().filter(|column| {
+        !new.iter()
+            .any(|kept| kept.column == column.column && kept.path == column.path)
+    }) {
+        queries.push(format!(
+            r#"ALTER TABLE "{storage_name}" DROP COLUMN "{}""#,
+            column.name
+        ));
+    }
+}
+
+/// Queries bringing the storage of `old` to `new` after a schema change: renamed
+/// tables and columns, dropped columns and child tables, added columns and child tables.
+fn migrate_table_queries(old: &SqliteTable, new: &SqliteTable) -> Vec<String> {
+    let mut queries = drop_index_queries(old, new);
+    if old.storage_name != new.storage_name {
+        queries.push(format!(
+            r#"ALTER TABLE "{}" RENAME TO "{}""#,
+            old.storage_name, new.storage_name
+        ));
+    }
+    let rename_primary = |queries: &mut Vec<String>, storage_name: &str| {
+        if old.primary.name != new.primary.name {
+            queries.push(format!(
+                r#"ALTER TABLE "{storage_name}" RENAME COLUMN "{}" TO "{}""#,
+                old.primary.name, new.primary.name
+            ));
+        }
+    };
+    rename_primary(&mut queries, &new.storage_name);
+    migrate_columns(&mut queries, &new.storage_name, &old.stored, &new.stored);
+
+    for child in &new.children {
+        let Some(existing) = old
+            .children
+            .iter()
+            .find(|existing| existing.column == child.column)
+        else {
+            queries.push(create_child_table_query(new, child));
+            continue;
+        };
+        if existing.storage_name != child.storage_name {
+            queries.push(format!(
+                r#"ALTER TABLE "{}" RENAME TO "{}""#,
+                existing.storage_name, child.storage_name
+            ));
+        }
+        rename_primary(&mut queries, &child.storage_name);
+        migrate_columns(
+            &mut queries,
+            &child.storage_name,
+            &existing.columns,
+            &child.columns,
+        );
+    }
+    for child in old
+        .children
+        .iter()
+        .filter(|child| !new.children.iter().any(|kept| kept.column == child.column))
+    {
+        queries.push(format!(r#"DROP TABLE IF EXISTS "{}""#, child.storage_name));
+    }
+    queries.extend(create_index_queries(Some(old), new));
+    queries
+}
+
+#[derive(Clone)]
 enum SqliteBindValue {
     Null,
     Integer(i64),
@@ -291,6 +526,33 @@ fn to_bind_value(value: &Value, type_def: &TypeDef) -> SqliteBindValue {
     }
 }
 
+fn bind_value<'q>(
+    query: Query<'q, Sqlite, SqliteArguments<'q>>,
+    value: SqliteBindValue,
+) -> Query<'q, Sqlite, SqliteArguments<'q>> {
+    match value {
+        SqliteBindValue::Null => query.bind(None::<String>),
+        SqliteBindValue::Integer(n) => query.bind(n),
+        SqliteBindValue::Text(s) => query.bind(s),
+    }
+}
+
+/// Binds the primary key then `columns` (resolved from `record`) to an upsert query.
+fn bind_columns<'q>(
+    query: Query<'q, Sqlite, SqliteArguments<'q>>,
+    table: &SqliteTable,
+    columns: &[SqliteColumn],
+    record: &Map<String, Value>,
+    primary: SqliteBindValue,
+) -> Query<'q, Sqlite, SqliteArguments<'q>> {
+    columns
+        .iter()
+        .fold(bind_value(query, primary), |query, column| {
+            let value = column.resolve(record, &table.columns[&column.column].name);
+            bind_value(query, to_bind_value(value, &column.type_def))
+        })
+}
+
 fn primary_to_bind_value(value: &Value, type_def: &PrimaryTypeDef) -> SqliteBindValue {
     if value.is_null() {
         return SqliteBindValue::Null;
@@ -360,9 +622,45 @@ fn primary_to_bind_value(value: &Value, type_def: &PrimaryTypeDef) -> SqliteBind
     }
 }
 
+/// Bind value of a primary key from a delete event, formatted like inserted keys.
+fn primary_value_to_bind_value(value: &PrimaryValue) -> SqliteBindValue {
+    match value {
+        PrimaryValue::Bool(b) => SqliteBindValue::Integer(i64::from(*b)),
+        PrimaryValue::U8(n) => SqliteBindValue::Integer(i64::from(*n)),
+        PrimaryValue::U16(n) => SqliteBindValue::Integer(i64::from(*n)),
+        PrimaryValue::U32(n) => SqliteBindValue::Integer(i64::from(*n)),
+        PrimaryValue::I8(n) => SqliteBindValue::Integer(i64::from(*n)),
+        PrimaryValue::I16(n) => SqliteBindValue::Integer(i64::from(*n)),
+        PrimaryValue::I32(n) => SqliteBindValue::Integer(i64::from(*n)),
+        PrimaryValue::U64(n) => SqliteBindValue::Text(format!("0x{n:x}")),
+        PrimaryValue::I64(n) => SqliteBindValue::Text(format!("0x{n:x}")),
+        PrimaryValue::U128(n) => SqliteBindValue::Text(format!("0x{n:032x}")),
+        PrimaryValue::I128(n) => SqliteBindValue::Text(n.to_string()),
+        PrimaryValue::ShortUtf8(s) => SqliteBindValue::Text(s.to_string()),
+        PrimaryValue::EthAddress(_) => SqliteBindValue::Text(format!(
+            "0x{}",
+            hex::encode(&value.to_felt().to_bytes_be()[12..])
+        )),
+        // Felt-like keys (felts, addresses, class hashes, bytes31) as 32 padded bytes.
+        _ => SqliteBindValue::Text(format!("0x{}", hex::encode(value.to_felt().to_bytes_be()))),
+    }
+}
+
+fn schema_column<'a>(schema: &'a mut TableSchema, id: &Felt) -> SqliteDbResult<&'a mut ColumnDef> {
+    let table = &schema.name;
+    schema
+        .columns
+        .iter_mut()
+        .find(|column| column.id == *id)
+        .ok_or_else(|| SqliteTableError::ColumnNotFound(*id, table.clone()).into())
+}
+
 pub struct IntrospectSqliteDb<T> {
     tables: SqliteTables,
+    /// Introspect schema of each table, updated by schema change events
+    schemas: RwLock<HashMap<Felt, TableSchema>>,
     namespace: SqliteNamespace,
+    config: SqliteSinkConfig,
     pool: T,
 }
 
@@ -376,11 +674,22 @@ impl<T: SqliteConnection + Send + Sync> IntrospectSqliteDb<T> {
     pub fn new(pool: T, namespace: impl Into<SqliteNamespace>) -> Self {
         Self {
             tables: SqliteTables::default(),
+            schemas: RwLock::default(),
             namespace: namespace.into(),
+            config: SqliteSinkConfig::default(),
             pool,
         }
     }
 
+    /// Sets the storage options (nested column strategies).
+    ///
+    /// Strategies apply when a table is created or reloaded: changing the strategy of
+    /// an existing table requires dropping its storage first.
+    pub fn with_config(mut self, config: SqliteSinkConfig) -> Self {
+        self.config = config;
+        self
+    }
+
     pub async fn initialize_introspect_sqlite_sink(&self) -> SqliteDbResult<()> {
         self.migrate(Some("introspect"), INTROSPECT_SQLITE_SINK_MIGRATIONS)
             .await?;
@@ -399,15 +708,26 @@ impl<T: SqliteConnection + Send + Sync> IntrospectSqliteDb<T> {
         .fetch_all(self.pool())
         .await?;
 
-        let mut tables = self.tables.write()?;
-        for row in rows {
-            let schema_json: String = row.try_get("table_schema_json")?;
-            let alive: i64 = row.try_get("alive")?;
-            let table_schema: TableSchema = serde_json::from_str(&schema_json)?;
-            let (id, mut table) =
-                SqliteTable::new_from_table(self.namespace.prefix(), table_schema);
-            table.alive = alive != 0;
-            tables.insert(id, table);
+        // Tables created before their columns were indexed get the missing indexes.
+        let mut index_queries = Vec::new();
+        {
+            let mut tables = self.tables.write()?;
+            let mut schemas = self.schemas.write()?;
+            for row in rows {
+                let schema_json: String = row.try_get("table_schema_json")?;
+                let alive: i64 = row.try_get("alive")?;
+                let table_schema: TableSchema = serde_json::from_str(&schema_json)?;
+                schemas.insert(table_schema.id, table_schema.clone());
+                let (id, mut table) = self.config.table(&self.namespace, table_schema);
+                table.alive = alive != 0;
+                if table.alive {
+                    index_queries.extend(create_index_queries(None, &table));
+                }
+                tables.insert(id, table);
+            }
+        }
+        if !index_queries.is_empty() {
+            self.execute_queries(&index_queries).await?;
         }
 
         Ok(())
@@ -441,47 +761,93 @@ impl<T: SqliteConnection + Send + Sync> IntrospectSqliteDb<T> {
         let exists_in_memory = self.tables.read()?.contains_key(&id);
 
         if !exists_in_memory {
-            let (_, query) = self
-                .tables
-                .create_table(&self.namespace, table_schema.clone())?;
-            self.execute_queries(&[query]).await?;
+            let (_, queries) =
+                self.tables
+                    .create_table(&self.namespace, &self.config, table_schema.clone())?;
+            self.execute_queries(&queries).await?;
             self.persist_table_state(&table_schema, true).await?;
+            self.schemas.write()?.insert(id, table_schema);
             return Ok(());
         }
 
-        let (old_columns, storage_name) = {
+        let (_, new_table) = self.config.table(&self.namespace, table_schema.clone());
+        let alter_queries = {
             let tables = self.tables.read()?;
-            let old = tables.get(&id).unwrap();
-            (old.columns.clone(), old.storage_name.clone())
+            migrate_table_queries(tables.get(&id).unwrap(), &new_table)
         };
 
-        let (_, new_table) =
-            SqliteTable::new_from_table(self.namespace.prefix(), table_schema.clone());
-
-        let mut alter_queries = Vec::new();
-        for (col_id, col_info) in &new_table.columns {
-            if !old_columns.contains_key(col_id) {
-                let col_type = sqlite_column_type(&col_info.type_def);
-                alter_queries.push(format!(
-                    r#"ALTER TABLE "{storage_name}" ADD COLUMN "{}" {col_type}"#,
-                    col_info.name
-                ));
-            }
-        }
-
         if !alter_queries.is_empty() {
             self.execute_queries(&alter_queries).await?;
         }
 
         self.tables.write()?.insert(id, new_table);
         self.persist_table_state(&table_schema, true).await?;
+        self.schemas.write()?.insert(id, table_schema);
+        Ok(())
+    }
+
+    /// Applies `change` to the schema of table `id`, migrates its storage and persists it.
+    async fn alter_table(
+        &self,
+        id: Felt,
+        change: impl FnOnce(&mut TableSchema) -> SqliteDbResult<()>,
+    ) -> SqliteDbResult<()> {
+        let mut table_schema = self
+            .schemas
+            .read()?
+            .get(&id)
+            .cloned()
+            .ok_or(SqliteDbError::TableNotFound(id))?;
+        change(&mut table_schema)?;
+
+        let (_, mut new_table) = self.config.table(&self.namespace, table_schema.clone());
+        let queries = {
+            let tables = self.tables.read()?;
+            let old_table = tables.get(&id).ok_or(SqliteDbError::TableNotFound(id))?;
+            new_table.alive = old_table.alive;
+            migrate_table_queries(old_table, &new_table)
+        };
+        if !queries.is_empty() {
+            self.execute_queries(&queries).await?;
+        }
+
+        let alive = new_table.alive;
+        self.tables.write()?.insert(id, new_table);
+        self.persist_table_state(&table_schema, alive).await?;
+        self.schemas.write()?.insert(id, table_schema);
+        Ok(())
+    }
+
+    /// Drops the storage of table `id` (and its child tables) and forgets its schema.
+    async fn drop_table(&self, id: Felt) -> SqliteDbResult<()> {
+        let table = self
+            .tables
+            .write()?
+            .remove(&id)
+            .ok_or(SqliteDbError::TableNotFound(id))?;
+        self.schemas.write()?.remove(&id);
+
+        let queries = table
+            .children
+            .iter()
+            .map(|child| child.storage_name.as_str())
+            .chain([table.storage_name.as_str()])
+            .map(|storage_name| format!(r#"DROP TABLE IF EXISTS "{storage_name}""#))
+            .collect::<Vec<_>>();
+        self.execute_queries(&queries).await?;
+        sqlx::query("DELETE FROM introspect_sink_schema_state WHERE table_id = ?1")
+            .bind(format!("{id:#x}"))
+            .execute(self.pool())
+            .await?;
         Ok(())
     }
 
     pub fn load_tables_no_commit(&self, table_schemas: Vec<TableSchema>) -> SqliteDbResult<()> {
         let mut tables = self.tables.write()?;
+        let mut schemas = self.schemas.write()?;
         for table in table_schemas {
-            let (id, sqlite_table) = SqliteTable::new_from_table(self.namespace.prefix(), table);
+            schemas.insert(table.id, table.clone());
+            let (id, sqlite_table) = self.config.table(&self.namespace, table);
             tables.insert(id, sqlite_table);
         }
         Ok(())
@@ -494,52 +860,87 @@ impl<T: SqliteConnection + Send + Sync> IntrospectSqliteDb<T> {
     ) -> SqliteDbResult<()> {
         match msg {
             IntrospectMsg::CreateTable(event) => {
-                let (_, query) = self.tables.create_table(&self.namespace, event.clone())?;
-                self.execute_queries(&[query]).await?;
-                self.persist_table_state(&event.clone().into(), true)
-                    .await?;
+                let (_, queries) =
+                    self.tables
+                        .create_table(&self.namespace, &self.config, event.clone())?;
+                self.execute_queries(&queries).await?;
+                let table_schema: TableSchema = event.clone().into();
+                self.persist_table_state(&table_schema, true).await?;
+                self.schemas.write()?.insert(table_schema.id, table_schema);
                 Ok(())
             }
             IntrospectMsg::UpdateTable(event) => self.update_table(event.clone()).await,
-            IntrospectMsg::AddColumns(event) => {
-                tracing::warn!(
-                    target: "torii::introspect_sqlite_sink",
-                    table = %event.table,
-                    "AddColumns received — table kept alive, new columns ignored until next UpdateTable"
-                );
-                Ok(())
+            IntrospectMsg::RenameTable(event) => {
+                self.alter_table(event.id, |schema| {
+                    schema.name.clone_from(&event.name);
+                    Ok(())
+                })
+                .await
             }
-            IntrospectMsg::DropColumns(event) => {
-                tracing::warn!(
-                    target: "torii::introspect_sqlite_sink",
-                    table = %event.table,
-                    "DropColumns received — table kept alive, columns left in place"
-                );
-                Ok(())
+            IntrospectMsg::RenamePrimary(event) => {
+                self.alter_table(event.table, |schema| {
+                    schema.primary.name.clone_from(&event.name);
+                    Ok(())
+                })
+                .await
+            }
+            // SQLite keeps the declared key type: only later keys use the new type.
+            IntrospectMsg::RetypePrimary(event) => {
+                self.alter_table(event.table, |schema| {
+                    schema.primary.type_def = event.type_def.clone();
+                    schema.primary.attributes.clone_from(&event.attributes);
+                    Ok(())
+                })
+                .await
+            }
+            IntrospectMsg::RenameColumns(event) => {
+                self.alter_table(event.table, |schema| {
+                    for column in &event.columns {
+                        schema_column(schema, &column.id)?
+                            .name
+                            .clone_from(&column.name);
+                    }
+                    Ok(())
+                })
+                .await
             }
             IntrospectMsg::RetypeColumns(event) => {
-                tracing::warn!(
-                    target: "torii::introspect_sqlite_sink",
-                    table = %event.table,
-                    "RetypeColumns received — table kept alive, types unchanged"
-                );
-                Ok(())
+                self.alter_table(event.table, |schema| {
+                    for column in &event.columns {
+                        schema_column(schema, &column.id)?.type_def = column.type_def.clone();
+                    }
+                    Ok(())
+                })
+                .await
             }
-            IntrospectMsg::RetypePrimary(event) => {
-                tracing::warn!(
-                    target: "torii::introspect_sqlite_sink",
-                    table = %event.table,
-                    "RetypePrimary received — table kept alive, primary type unchanged"
-                );
-                Ok(())
+            IntrospectMsg::AddColumns(event) => {
+                self.alter_table(event.table, |schema| {
+                    for column in &event.columns {
+                        if !schema
+                            .columns
+                            .iter()
+                            .any(|existing| existing.id == column.id)
+                        {
+                            schema.columns.push(column.clone());
+                        }
+                    }
+                    Ok(())
+                })
+                .await
+            }
+            IntrospectMsg::DropColumns(event) => {
+                self.alter_table(event.table, |schema| {
+                    schema
+                        .columns
+                        .retain(|column| !event.columns.contains(&column.id));
+                    Ok(())
+                })
+                .await
             }
-            IntrospectMsg::RenameTable(_)
-            | IntrospectMsg::DropTable(_)
-            | IntrospectMsg::RenameColumns(_)
-            | IntrospectMsg::RenamePrimary(_)
-            | IntrospectMsg::DeleteRecords(_)
-            | IntrospectMsg::DeletesFields(_) => Ok(()),
+            IntrospectMsg::DropTable(event) => self.drop_table(event.id).await,
             IntrospectMsg::InsertsFields(event) => self.insert_fields(event, metadata).await,
+            IntrospectMsg::DeleteRecords(event) => self.delete_records(event).await,
+            IntrospectMsg::DeletesFields(event) => self.delete_fields(event).await,
         }
     }
 
@@ -580,21 +981,6 @@ impl<T: SqliteConnection + Send + Sync> IntrospectSqliteDb<T> {
         }
 
         let record_schema = table.get_schema(&event.columns)?;
-        let column_names = std::iter::once(table.primary.name.as_str())
-            .chain(
-                event
-                    .columns
-                    .iter()
-                    .map(|id| table.columns[id].name.as_str()),
-            )
-            .collect::<Vec<_>>();
-
-        let column_type_defs: Vec<&TypeDef> = event
-            .columns
-            .iter()
-            .map(|id| &table.columns[id].type_def)
-            .collect();
-
         let mut bytes = Vec::new();
         let mut serializer = JsonSerializer::new(&mut bytes);
         record_schema.parse_records_with_metadata(
@@ -608,43 +994,512 @@ impl<T: SqliteConnection + Send + Sync> IntrospectSqliteDb<T> {
         let mut tx = self.begin().await?;
         for value in rows {
             let object = value.as_object().ok_or(SqliteDbError::InvalidRecordFrame)?;
-
-            let mut query = sqlx::query(&table.upsert_sql);
-
             let primary_value = object
                 .get(table.primary.name.as_str())
-                .cloned()
-                .unwrap_or(Value::Null);
-            match primary_to_bind_value(&primary_value, &table.primary.type_def) {
-                SqliteBindValue::Null => {
-                    query = query.bind(None::<Vec<u8>>);
-                }
-                SqliteBindValue::Integer(n) => {
-                    query = query.bind(n);
-                }
-                SqliteBindValue::Text(s) => {
-                    query = query.bind(s);
+                .unwrap_or(&Value::Null);
+            let primary = primary_to_bind_value(primary_value, &table.primary.type_def);
+
+            // Columns missing from the event are bound as NULL and keep their stored value.
+            let query = bind_columns(
+                sqlx::query(&table.upsert_sql),
+                &table,
+                &table.stored,
+                object,
+                primary.clone(),
+            );
+            query.execute(&mut *tx).await?;
+
+            for child in &table.children {
+                if object
+                    .get(table.columns[&child.column].name.as_str())
+                    .is_none_or(Value::is_null)
+                {
+                    continue;
                 }
+                let query = bind_columns(
+                    sqlx::query(&child.upsert_sql),
+                    &table,
+                    &child.columns,
+                    object,
+                    primary.clone(),
+                );
+                query.execute(&mut *tx).await?;
             }
+        }
+        tx.commit().await?;
+        Ok(())
+    }
 
-            for (column_name, type_def) in column_names.iter().skip(1).zip(column_type_defs.iter())
-            {
-                let val = object.get(*column_name).cloned().unwrap_or(Value::Null);
-                match to_bind_value(&val, type_def) {
-                    SqliteBindValue::Null => {
-                        query = query.bind(None::<String>);
-                    }
-                    SqliteBindValue::Integer(n) => {
-                        query = query.bind(n);
-                    }
-                    SqliteBindValue::Text(s) => {
-                        query = query.bind(s);
-                    }
-                }
+    fn live_table(&self, id: &Felt) -> SqliteDbResult<SqliteTable> {
+        self.tables
+            .read()?
+            .get(id)
+            .cloned()
+            .ok_or(SqliteDbError::TableNotFound(*id))
+    }
+
+    /// Deletes rows by primary key, along with their child table rows.
+    async fn delete_records(&self, event: &DeleteRecords) -> SqliteDbResult<()> {
+        let table = self.live_table(&event.table)?;
+        if !table.alive {
+            return Ok(());
+        }
+
+        // Child rows first: foreign keys are not enforced on every connection.
+        let statements = table
+            .children
+            .iter()
+            .map(|child| child.storage_name.as_str())
+            .chain([table.storage_name.as_str()])
+            .map(|storage_name| {
+                format!(
+                    r#"DELETE FROM "{storage_name}" WHERE "{}" = ?"#,
+                    table.primary.name
+                )
+            })
+            .collect::<Vec<_>>();
+        self.execute_for_rows(&table, &event.rows, &statements)
+            .await
+    }
+
+    /// Clears columns of rows: stored columns are set to NULL and child table rows deleted.
+    async fn delete_fields(&self, event: &DeletesFields) -> SqliteDbResult<()> {
+        let table = self.live_table(&event.table)?;
+        if !table.alive {
+            return Ok(());
+        }
+        for id in &event.columns {
+            table.get_column(id)?;
+        }
+
+        let cleared = table
+            .stored
+            .iter()
+            .filter(|column| event.columns.contains(&column.column))
+            .map(|column| format!(r#""{}" = NULL"#, column.name))
+            .collect::<Vec<_>>();
+        let mut statements = Vec::new();
+        if !cleared.is_empty() {
+            statements.push(format!(
+                r#"UPDATE "{}" SET {} WHERE "{}" = ?"#,
+                table.storage_name,
+                cleared.join(", "),
+                table.primary.name
+            ));
+        }
+        statements.extend(
+            table
+                .children
+                .iter()
+                .filter(|child| event.columns.contains(&child.column))
+                .map(|child| {
+                    format!(
+                        r#"DELETE FROM "{}" WHERE "{}" = ?"#,
+                        child.storage_name, table.primary.name
+                    )
+                }),
+        );
+        self.execute_for_rows(&table, &event.rows, &statements)
+            .await
+    }
+
+    /// Runs every statement (binding the primary key) for each row, in one transaction.
+    async fn execute_for_rows(
+        &self,
+        table: &SqliteTable,
+        rows: &[PrimaryValue],
+        statements: &[String],
+    ) -> SqliteDbResult<()> {
+        if statements.is_empty() || rows.is_empty() {
+            return Ok(());
+        }
+        let mut tx = self.begin().await?;
+        for row in rows {
+            let primary = primary_value_to_bind_value(row);
+            for statement in statements {
+                bind_value(sqlx::query(statement), primary.clone())
+                    .execute(&mut *tx)
+                    .await?;
             }
-            query.execute(&mut *tx).await?;
         }
         tx.commit().await?;
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use introspect_types::{Attribute, PrimaryDef};
+    use sqlx::sqlite::SqlitePoolOptions;
+    use sqlx::SqlitePool;
+    use std::sync::Arc;
+    use torii_introspect::events::{
+        AddColumns, CreateTable, DeleteRecords, DropColumns, DropTable, IdTypeDef, RenameTable,
+        RetypeColumns,
+    };
+
+    const TABLE: Felt = Felt::from_hex_unchecked("0x7ab1e");
+    const SCORE: Felt = Felt::from_hex_unchecked("0x1");
+    const OWNER: Felt = Felt::from_hex_unchecked("0x2");
+    const LEVEL: Felt = Felt::from_hex_unchecked("0x3");
+
+    fn metadata() -> MetaData {
+        MetaData {
+            block_number: Some(1),
+            transaction_hash: Felt::ONE,
+            from_address: Felt::TWO,
+        }
+    }
+
+    fn column(id: Felt, name: &str, type_def: TypeDef, attributes: &[&str]) -> ColumnDef {
+        ColumnDef {
+            id,
+            name: name.to_string(),
+            attributes: attributes
+                .iter()
+                .map(|attribute| Attribute::new_empty(attribute.to_string()))
+                .collect(),
+            type_def,
+        }
+    }
+
+    fn create_table(columns: Vec<ColumnDef>) -> IntrospectMsg {
+        IntrospectMsg::CreateTable(CreateTable {
+            id: TABLE,
+            name: "Position".to_string(),
+            attributes: vec![],
+            primary: PrimaryDef {
+                name: "entity_id".to_string(),
+                attributes: vec![],
+                type_def: PrimaryTypeDef::Felt252,
+            },
+            columns,
+        })
+    }
+
+    async fn db() -> IntrospectSqliteDb<Arc<SqlitePool>> {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let db = IntrospectSqliteDb::new(Arc::new(pool), ());
+        db.initialize_introspect_sqlite_sink().await.unwrap();
+        db
+    }
+
+    async fn apply(db: &IntrospectSqliteDb<Arc<SqlitePool>>, msg: IntrospectMsg) {
+        db.process_message(&msg, &metadata()).await.unwrap();
+    }
+
+    async fn columns(db: &IntrospectSqliteDb<Arc<SqlitePool>>, table: &str) -> Vec<String> {
+        sqlx::query(&format!(r#"PRAGMA table_info("{table}")"#))
+            .fetch_all(db.pool())
+            .await
+            .unwrap()
+            .iter()
+            .map(|row| row.get("name"))
+            .collect()
+    }
+
+    async fn table_exists(db: &IntrospectSqliteDb<Arc<SqlitePool>>, table: &str) -> bool {
+        sqlx::query("SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1")
+            .bind(table)
+            .fetch_optional(db.pool())
+            .await
+            .unwrap()
+            .is_some()
+    }
+
+    fn key(id: u64) -> String {
+        format!("0x{}", hex::encode(Felt::from(id).to_bytes_be()))
+    }
+
+    async fn insert_row(db: &IntrospectSqliteDb<Arc<SqlitePool>>, id: u64, score: i64) {
+        sqlx::query(
+            r#"INSERT INTO "Position" ("entity_id", "score", "owner") VALUES (?1, ?2, ?3)"#,
+        )
+        .bind(key(id))
+        .bind(score)
+        .bind(key(0xa))
+        .execute(db.pool())
+        .await
+        .unwrap();
+    }
+
+    async fn rows(db: &IntrospectSqliteDb<Arc<SqlitePool>>) -> Vec<(String, Option<i64>)> {
+        sqlx::query(r#"SELECT "entity_id", "score" FROM "Position" ORDER BY "entity_id""#)
+            .fetch_all(db.pool())
+            .await
+            .unwrap()
+            .iter()
+            .map(|row| (row.get("entity_id"), row.get("score")))
+            .collect()
+    }
+
+    async fn position_db() -> IntrospectSqliteDb<Arc<SqlitePool>> {
+        let db = db().await;
+        apply(
+            &db,
+            create_table(vec![
+                column(SCORE, "score", TypeDef::U32, &[]),
+                column(OWNER, "owner", TypeDef::ContractAddress, &[]),
+            ]),
+        )
+        .await;
+        db
+    }
+
+    #[tokio::test]
+    async fn add_and_drop_columns() {
+        let db = position_db().await;
+        assert_eq!(
+            columns(&db, "Position").await,
+            ["entity_id", "score", "owner"]
+        );
+
+        apply(
+            &db,
+            IntrospectMsg::AddColumns(AddColumns {
+                table: TABLE,
+                columns: vec![column(LEVEL, "level", TypeDef::U8, &[])],
+            }),
+        )
+        .await;
+        assert_eq!(
+            columns(&db, "Position").await,
+            ["entity_id", "score", "owner", "level"]
+        );
+
+        apply(
+            &db,
+            IntrospectMsg::DropColumns(DropColumns {
+                owner: None,
+                table: TABLE,
+                columns: vec![SCORE],
+            }),
+        )
+        .await;
+        assert_eq!(
+            columns(&db, "Position").await,
+            ["entity_id", "owner", "level"]
+        );
+    }
+
+    #[tokio::test]
+    async fn retype_column_keeps_its_values() {
+        let db = position_db().await;
+        insert_row(&db, 1, 10).await;
+
+        apply(
+            &db,
+            IntrospectMsg::RetypeColumns(RetypeColumns {
+                table: TABLE,
+                columns: vec![IdTypeDef {
+                    id: SCORE,
+                    type_def: TypeDef::U64,
+                }],
+            }),
+        )
+        .await;
+        assert_eq!(
+            columns(&db, "Position").await,
+            ["entity_id", "score", "owner"]
+        );
+        assert_eq!(rows(&db).await, [(key(1), Some(10))]);
+
+        let persisted: String = sqlx::query_scalar(
+            "SELECT table_schema_json FROM introspect_sink_schema_state WHERE table_id = ?1",
+        )
+        .bind(format!("{TABLE:#x}"))
+        .fetch_one(db.pool())
+        .await
+        .unwrap();
+        let schema: TableSchema = serde_json::from_str(&persisted).unwrap();
+        assert!(matches!(schema.columns[0].type_def, TypeDef::U64));
+    }
+
+    #[tokio::test]
+    async fn drop_table_removes_storage_and_state() {
+        let db = position_db().await;
+        insert_row(&db, 1, 10).await;
+
+        apply(
+            &db,
+            IntrospectMsg::DropTable(DropTable {
+                owner: None,
+                id: TABLE,
+            }),
+        )
+        .await;
+        assert!(!table_exists(&db, "Position").await);
+        let states: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM introspect_sink_schema_state")
+            .fetch_one(db.pool())
+            .await
+            .unwrap();
+        assert_eq!(states, 0);
+        assert!(matches!(
+            db.process_message(
+                &IntrospectMsg::DeleteRecords(DeleteRecords::new(TABLE, vec![])),
+                &metadata()
+            )
+            .await,
+            Err(SqliteDbError::TableNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn delete_records_by_primary_key() {
+        let db = position_db().await;
+        for id in 1..=3 {
+            insert_row(&db, id, id as i64 * 10).await;
+        }
+
+        apply(
+            &db,
+            IntrospectMsg::DeleteRecords(DeleteRecords::new(
+                TABLE,
+                vec![
+                    PrimaryValue::Felt252(Felt::from(1u64)),
+                    PrimaryValue::Felt252(Felt::from(3u64)),
+                ],
+            )),
+        )
+        .await;
+        assert_eq!(rows(&db).await, [(key(2), Some(20))]);
+    }
+
+    #[tokio::test]
+    async fn delete_fields_clears_columns() {
+        let db = position_db().await;
+        insert_row(&db, 1, 10).await;
+        insert_row(&db, 2, 20).await;
+
+        apply(
+            &db,
+            IntrospectMsg::DeletesFields(DeletesFields {
+                table: TABLE,
+                rows: vec![PrimaryValue::Felt252(Felt::from(2u64))],
+                columns: vec![SCORE],
+            }),
+        )
+        .await;
+        assert_eq!(rows(&db).await, [(key(1), Some(10)), (key(2), None)]);
+        let owner: String =
+            sqlx::query_scalar(r#"SELECT "owner" FROM "Position" WHERE "entity_id" = ?1"#)
+                .bind(key(2))
+                .fetch_one(db.pool())
+                .await
+                .unwrap();
+        assert_eq!(owner, key(0xa));
+    }
+
+    async fn indexes(db: &IntrospectSqliteDb<Arc<SqlitePool>>, table: &str) -> Vec<String> {
+        sqlx::query_scalar(
+            "SELECT name FROM sqlite_master
+             WHERE type = 'index' AND tbl_name = ?1 AND sql IS NOT NULL ORDER BY name",
+        )
+        .bind(table)
+        .fetch_all(db.pool())
+        .await
+        .unwrap()
+    }
+
+    fn indexed_position(owner_attributes: &[&str]) -> Vec<ColumnDef> {
+        vec![
+            column(SCORE, "score", TypeDef::U32, &["index"]),
+            column(OWNER, "owner", TypeDef::ContractAddress, owner_attributes),
+            column(LEVEL, "level", TypeDef::U8, &[]),
+        ]
+    }
+
+    #[tokio::test]
+    async fn indexes_key_and_index_columns() {
+        let db = db().await;
+        apply(&db, create_table(indexed_position(&["key"]))).await;
+        assert_eq!(
+            indexes(&db, "Position").await,
+            ["Position__owner__idx", "Position__score__idx"]
+        );
+    }
+
+    #[tokio::test]
+    async fn drops_index_of_unflagged_column() {
+        let db = db().await;
+        apply(&db, create_table(indexed_position(&["key"]))).await;
+
+        let IntrospectMsg::CreateTable(table) = create_table(indexed_position(&[])) else {
+            unreachable!()
+        };
+        apply(
+            &db,
+            IntrospectMsg::UpdateTable(TableSchema::from(table).into()),
+        )
+        .await;
+        assert_eq!(indexes(&db, "Position").await, ["Position__score__idx"]);
+    }
+
+    #[tokio::test]
+    async fn indexes_follow_table_rename_and_column_retype() {
+        let db = db().await;
+        apply(&db, create_table(indexed_position(&["key"]))).await;
+
+        apply(
+            &db,
+            IntrospectMsg::RenameTable(RenameTable {
+                id: TABLE,
+                name: "Spot".to_string(),
+            }),
+        )
+        .await;
+        assert!(indexes(&db, "Position").await.is_empty());
+        assert_eq!(
+            indexes(&db, "Spot").await,
+            ["Spot__owner__idx", "Spot__score__idx"]
+        );
+
+        let (old, new) = {
+            let tables = db.tables.read().unwrap();
+            let old = tables[&TABLE].clone();
+            let mut schema = db.schemas.read().unwrap()[&TABLE].clone();
+            schema_column(&mut schema, &SCORE).unwrap().type_def = TypeDef::U64;
+            (old, db.config.table(&db.namespace, schema).1)
+        };
+        assert!(!migrate_table_queries(&old, &new)
+            .iter()
+            .any(|query| query.contains("INDEX")));
+
+        apply(
+            &db,
+            IntrospectMsg::RetypeColumns(RetypeColumns {
+                table: TABLE,
+                columns: vec![IdTypeDef {
+                    id: SCORE,
+                    type_def: TypeDef::U64,
+                }],
+            }),
+        )
+        .await;
+        assert_eq!(
+            indexes(&db, "Spot").await,
+            ["Spot__owner__idx", "Spot__score__idx"]
+        );
+    }
+
+    #[test]
+    fn primary_values_bind_like_inserted_keys() {
+        assert!(matches!(
+            primary_value_to_bind_value(&PrimaryValue::Felt252(Felt::from(0xabu64))),
+            SqliteBindValue::Text(key) if key == format!("0x{:064x}", 0xab)
+        ));
+        assert!(matches!(
+            primary_value_to_bind_value(&PrimaryValue::U32(7)),
+            SqliteBindValue::Integer(7)
+        ));
+        assert!(matches!(
+            primary_value_to_bind_value(&PrimaryValue::U64(255)),
+            SqliteBindValue::Text(key) if key == "0xff"
+        ));
+    }
+}
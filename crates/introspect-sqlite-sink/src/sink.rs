@@ -8,6 +8,7 @@ use torii::etl::{
     extractor::ExtractionBatch,
     sink::{EventBus, Sink, SinkContext, TopicInfo},
 };
+use torii::ToriiResult;
 use torii_introspect::events::{IntrospectBody, IntrospectMsg};
 use torii_sqlite::SqliteConnection;
 
@@ -24,7 +25,7 @@ impl<T: Send + Sync + SqliteConnection> Sink for IntrospectSqliteDb<T> {
         vec![TypeId::new("introspect")]
     }
 
-    async fn process(&self, envelopes: &[Envelope], _batch: &ExtractionBatch) -> Result<()> {
+    async fn process(&self, envelopes: &[Envelope], _batch: &ExtractionBatch) -> ToriiResult<()> {
         let mut processed = 0usize;
         let mut create_tables = 0usize;
         let mut update_tables = 0usize;
@@ -101,7 +102,7 @@ impl<T: Send + Sync + SqliteConnection> Sink for IntrospectSqliteDb<T> {
         &mut self,
         _event_bus: Arc<EventBus>,
         _context: &SinkContext,
-    ) -> Result<()> {
+    ) -> ToriiResult<()> {
         self.initialize_introspect_sqlite_sink().await?;
         tracing::info!(
             target: LOGGING_TARGET,
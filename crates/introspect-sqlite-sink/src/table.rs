@@ -1,5 +1,7 @@
-use introspect_types::{ColumnDef, ColumnInfo, FeltIds, PrimaryDef};
+use introspect_types::{ColumnDef, ColumnInfo, FeltIds, PrimaryDef, TypeDef};
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use starknet_types_core::felt::Felt;
 use std::collections::HashMap;
 use thiserror::Error;
@@ -14,6 +16,69 @@ pub enum SqliteTableError {
 
 pub type TableResult<T> = std::result::Result<T, SqliteTableError>;
 
+/// How nested (struct and enum) columns are stored.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NestedStrategy {
+    /// One JSONB column per nested column.
+    #[default]
+    Json,
+    /// Struct members become `"{column}.{member}"` columns (recursively, up to the
+    /// configured depth) and enums become `"{column}.variant"` (TEXT) and
+    /// `"{column}.value"` (JSONB payload) columns.
+    Flatten,
+    /// Struct columns are stored in a child table `"{table}__{column}"` keyed by the
+    /// parent primary key (flattened); other nested columns stay JSONB.
+    ChildTable,
+}
+
+/// Step from a column value to a nested value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathSegment {
+    /// Struct member
+    Member(String),
+    /// Name of the selected enum variant
+    Variant,
+    /// Payload of the selected enum variant
+    VariantValue,
+}
+
+/// Physical column storing a top-level column or a value nested in it.
+#[derive(Debug, Clone)]
+pub struct SqliteColumn {
+    pub name: String,
+    pub column: Felt,
+    pub path: Vec<PathSegment>,
+    pub type_def: TypeDef,
+}
+
+impl SqliteColumn {
+    /// Resolves the value stored in this column from a record object.
+    pub fn resolve<'a>(&self, record: &'a Map<String, Value>, column_name: &str) -> &'a Value {
+        let mut value = record.get(column_name);
+        for segment in &self.path {
+            value = value.and_then(|value| match segment {
+                PathSegment::Member(member) => value.get(member),
+                PathSegment::Variant => value.get("variant"),
+                PathSegment::VariantValue => value
+                    .get("variant")
+                    .and_then(Value::as_str)
+                    .and_then(|variant| value.get(format!("_{variant}"))),
+            });
+        }
+        value.unwrap_or(&Value::Null)
+    }
+}
+
+/// Child table holding one struct column of a [`NestedStrategy::ChildTable`] table.
+#[derive(Debug, Clone)]
+pub struct SqliteChildTable {
+    pub column: Felt,
+    pub storage_name: String,
+    pub columns: Vec<SqliteColumn>,
+    pub upsert_sql: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct SqliteTable {
     pub name: String,
@@ -21,6 +86,10 @@ pub struct SqliteTable {
     pub primary: PrimaryDef,
     pub columns: HashMap<Felt, ColumnInfo>,
     pub order: Vec<Felt>,
+    pub strategy: NestedStrategy,
+    /// Physical columns of the table (after the primary key), in table order
+    pub stored: Vec<SqliteColumn>,
+    pub children: Vec<SqliteChildTable>,
     pub upsert_sql: String,
     pub alive: bool,
 }
@@ -31,6 +100,8 @@ impl SqliteTable {
         name: String,
         primary: PrimaryDef,
         columns: Vec<ColumnDef>,
+        strategy: NestedStrategy,
+        max_flatten_depth: usize,
     ) -> Self {
         Self {
             name,
@@ -38,13 +109,21 @@ impl SqliteTable {
             primary,
             order: columns.ids(),
             columns: columns.into_iter().map_into().collect(),
+            strategy,
+            stored: Vec::new(),
+            children: Vec::new(),
             upsert_sql: String::new(),
             alive: true,
         }
-        .with_upsert_sql()
+        .with_layout(max_flatten_depth)
     }
 
-    pub fn new_from_table(namespace: &str, table: impl Into<TableSchema>) -> (Felt, Self) {
+    pub fn new_from_table(
+        namespace: &str,
+        table: impl Into<TableSchema>,
+        strategy: NestedStrategy,
+        max_flatten_depth: usize,
+    ) -> (Felt, Self) {
         let table = table.into();
         let storage_name = if namespace.is_empty() {
             table.name.clone()
@@ -53,7 +132,14 @@ impl SqliteTable {
         };
         (
             table.id,
-            Self::new(storage_name, table.name, table.primary, table.columns),
+            Self::new(
+                storage_name,
+                table.name,
+                table.primary,
+                table.columns,
+                strategy,
+                max_flatten_depth,
+            ),
         )
     }
 
@@ -71,23 +157,119 @@ impl SqliteTable {
         Ok(RecordSchema::new(&self.primary, columns))
     }
 
-    fn with_upsert_sql(mut self) -> Self {
-        self.upsert_sql = build_upsert_sql(&self);
+    fn with_layout(mut self, max_flatten_depth: usize) -> Self {
+        let mut stored = Vec::with_capacity(self.order.len());
+        let mut children = Vec::new();
+        for id in &self.order {
+            let column = &self.columns[id];
+            match (&column.type_def, self.strategy) {
+                (TypeDef::Struct(def), NestedStrategy::ChildTable) => {
+                    let mut columns = Vec::with_capacity(def.members.len());
+                    for member in &def.members {
+                        expand_column(
+                            &mut columns,
+                            member.name.clone(),
+                            *id,
+                            vec![PathSegment::Member(member.name.clone())],
+                            &member.type_def,
+                            max_flatten_depth,
+                        );
+                    }
+                    let storage_name = format!("{}__{}", self.storage_name, column.name);
+                    let upsert_sql = build_upsert_sql(&storage_name, &self.primary.name, &columns);
+                    children.push(SqliteChildTable {
+                        column: *id,
+                        storage_name,
+                        columns,
+                        upsert_sql,
+                    });
+                }
+                (type_def, NestedStrategy::Flatten) => expand_column(
+                    &mut stored,
+                    column.name.clone(),
+                    *id,
+                    Vec::new(),
+                    type_def,
+                    max_flatten_depth,
+                ),
+                (type_def, _) => stored.push(SqliteColumn {
+                    name: column.name.clone(),
+                    column: *id,
+                    path: Vec::new(),
+                    type_def: type_def.clone(),
+                }),
+            }
+        }
+        self.upsert_sql = build_upsert_sql(&self.storage_name, &self.primary.name, &stored);
+        self.stored = stored;
+        self.children = children;
         self
     }
 }
 
-fn sqlite_column_type(type_def: &introspect_types::TypeDef) -> &'static str {
+/// Pushes the physical columns of a (possibly nested) value, flattening structs
+/// and enums down to `depth` levels.
+fn expand_column(
+    columns: &mut Vec<SqliteColumn>,
+    name: String,
+    column: Felt,
+    path: Vec<PathSegment>,
+    type_def: &TypeDef,
+    depth: usize,
+) {
+    match type_def {
+        TypeDef::Struct(def) if depth > 0 => {
+            for member in &def.members {
+                let mut member_path = path.clone();
+                member_path.push(PathSegment::Member(member.name.clone()));
+                expand_column(
+                    columns,
+                    format!("{name}.{}", member.name),
+                    column,
+                    member_path,
+                    &member.type_def,
+                    depth - 1,
+                );
+            }
+        }
+        TypeDef::Enum(_) if depth > 0 => {
+            let mut variant_path = path.clone();
+            variant_path.push(PathSegment::Variant);
+            columns.push(SqliteColumn {
+                name: format!("{name}.variant"),
+                column,
+                path: variant_path,
+                type_def: TypeDef::Utf8String,
+            });
+            let mut value_path = path;
+            value_path.push(PathSegment::VariantValue);
+            columns.push(SqliteColumn {
+                name: format!("{name}.value"),
+                column,
+                path: value_path,
+                type_def: type_def.clone(),
+            });
+        }
+        _ => columns.push(SqliteColumn {
+            name,
+            column,
+            path,
+            type_def: type_def.clone(),
+        }),
+    }
+}
+
+fn sqlite_column_type(type_def: &TypeDef) -> &'static str {
     if matches!(
         type_def,
-        introspect_types::TypeDef::Struct(_)
-            | introspect_types::TypeDef::Enum(_)
-            | introspect_types::TypeDef::Tuple(_)
-            | introspect_types::TypeDef::Array(_)
-            | introspect_types::TypeDef::FixedArray(_)
-            | introspect_types::TypeDef::Option(_)
-            | introspect_types::TypeDef::Nullable(_)
-            | introspect_types::TypeDef::Result(_)
+        TypeDef::Struct(_)
+            | TypeDef::Enum(_)
+            | TypeDef::Tuple(_)
+            | TypeDef::Array(_)
+            | TypeDef::FixedArray(_)
+            | TypeDef::Option(_)
+            | TypeDef::Nullable(_)
+            | TypeDef::Result(_)
     ) {
         "JSONB"
     } else {
@@ -95,19 +277,10 @@ fn sqlite_column_type(type_def: &introspect_types::TypeDef) -> &'static str {
     }
 }
 
-fn build_upsert_sql(table: &SqliteTable) -> String {
-    let column_names = std::iter::once(table.primary.name.as_str())
-        .chain(table.order.iter().map(|id| table.columns[id].name.as_str()))
-        .collect::<Vec<_>>();
-    let column_type_defs = table
-        .order
-        .iter()
-        .map(|id| &table.columns[id].type_def)
-        .collect::<Vec<_>>();
-
+fn build_upsert_sql(storage_name: &str, primary_name: &str, columns: &[SqliteColumn]) -> String {
     let placeholders = std::iter::once("?".to_string())
-        .chain(column_type_defs.iter().map(|td| {
-            if sqlite_column_type(td) == "JSONB" {
+        .chain(columns.iter().map(|column| {
+            if sqlite_column_type(&column.type_def) == "JSONB" {
                 "jsonb(?)".to_string()
             } else {
                 "?".to_string()
@@ -116,36 +289,177 @@ fn build_upsert_sql(table: &SqliteTable) -> String {
         .collect::<Vec<_>>()
         .join(", ");
 
-    let update_columns = column_names
+    let update_columns = columns
         .iter()
-        .skip(1)
-        .zip(column_type_defs.iter())
-        .map(|(name, td)| {
-            if sqlite_column_type(td) == "JSONB" {
+        .map(|column| {
+            let name = &column.name;
+            if sqlite_column_type(&column.type_def) == "JSONB" {
                 format!(
-                    r#""{name}" = COALESCE(jsonb(excluded."{name}"), "{table_name}"."{name}")"#,
-                    table_name = table.storage_name
+                    r#""{name}" = COALESCE(jsonb(excluded."{name}"), "{storage_name}"."{name}")"#
                 )
             } else {
-                format!(
-                    r#""{name}" = COALESCE(excluded."{name}", "{table_name}"."{name}")"#,
-                    table_name = table.storage_name
-                )
+                format!(r#""{name}" = COALESCE(excluded."{name}", "{storage_name}"."{name}")"#)
             }
         })
         .collect::<Vec<_>>()
         .join(", ");
 
     format!(
-        r#"INSERT INTO "{}" ({}) VALUES ({}) ON CONFLICT("{}") DO UPDATE SET {}"#,
-        table.storage_name,
-        column_names
-            .iter()
+        r#"INSERT INTO "{storage_name}" ({}) VALUES ({placeholders}) ON CONFLICT("{primary_name}") DO UPDATE SET {update_columns}"#,
+        std::iter::once(primary_name)
+            .chain(columns.iter().map(|column| column.name.as_str()))
             .map(|name| format!(r#""{name}""#))
             .collect::<Vec<_>>()
             .join(", "),
-        placeholders,
-        table.primary.name,
-        update_columns
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use introspect_types::{MemberDef, PrimaryTypeDef, StructDef};
+    use serde_json::json;
+
+    const SCORE: Felt = Felt::from_hex_unchecked("0x1");
+    const POSITION: Felt = Felt::from_hex_unchecked("0x2");
+
+    fn member(name: &str, type_def: TypeDef) -> MemberDef {
+        MemberDef {
+            name: name.to_string(),
+            attributes: Vec::new(),
+            type_def,
+        }
+    }
+
+    /// `Vec3 { x, y, z: Vec2 { a, b } }`
+    fn vec3() -> TypeDef {
+        TypeDef::Struct(StructDef {
+            name: "Vec3".to_string(),
+            attributes: Vec::new(),
+            members: vec![
+                member("x", TypeDef::U32),
+                member("y", TypeDef::U32),
+                member(
+                    "z",
+                    TypeDef::Struct(StructDef {
+                        name: "Vec2".to_string(),
+                        attributes: Vec::new(),
+                        members: vec![member("a", TypeDef::U8), member("b", TypeDef::U8)],
+                    }),
+                ),
+            ],
+        })
+    }
+
+    fn table(strategy: NestedStrategy, max_flatten_depth: usize) -> SqliteTable {
+        let column = |id, name: &str, type_def| ColumnDef {
+            id,
+            name: name.to_string(),
+            attributes: Vec::new(),
+            type_def,
+        };
+        SqliteTable::new(
+            "ns-Player".to_string(),
+            "Player".to_string(),
+            PrimaryDef {
+                name: "entity_id".to_string(),
+                attributes: Vec::new(),
+                type_def: PrimaryTypeDef::Felt252,
+            },
+            vec![
+                column(SCORE, "score", TypeDef::U32),
+                column(POSITION, "position", vec3()),
+            ],
+            strategy,
+            max_flatten_depth,
+        )
+    }
+
+    fn names(columns: &[SqliteColumn]) -> Vec<&str> {
+        columns.iter().map(|column| column.name.as_str()).collect()
+    }
+
+    #[test]
+    fn json_strategy_keeps_one_column_per_column() {
+        let table = table(NestedStrategy::Json, 2);
+        assert_eq!(names(&table.stored), ["score", "position"]);
+        assert!(table.children.is_empty());
+        assert!(table.upsert_sql.starts_with(
+            r#"INSERT INTO "ns-Player" ("entity_id", "score", "position") VALUES (?, ?, jsonb(?))"#
+        ));
+    }
+
+    #[test]
+    fn flatten_strategy_expands_structs_down_to_the_depth() {
+        let shallow = table(NestedStrategy::Flatten, 1);
+        assert_eq!(
+            names(&shallow.stored),
+            ["score", "position.x", "position.y", "position.z"]
+        );
+        // Past the depth, the nested struct stays JSONB.
+        assert!(shallow.upsert_sql.contains("VALUES (?, ?, ?, ?, jsonb(?))"));
+
+        let deep = table(NestedStrategy::Flatten, 2);
+        assert_eq!(
+            names(&deep.stored),
+            [
+                "score",
+                "position.x",
+                "position.y",
+                "position.z.a",
+                "position.z.b"
+            ]
+        );
+
+        let record = json!({ "position": { "x": 1, "y": 2, "z": { "a": 3, "b": 4 } } });
+        let record = record.as_object().unwrap();
+        let resolved = deep
+            .stored
+            .iter()
+            .map(|column| column.resolve(record, &deep.columns[&column.column].name))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            resolved,
+            [&Value::Null, &json!(1), &json!(2), &json!(3), &json!(4)]
+        );
+    }
+
+    #[test]
+    fn child_table_strategy_moves_struct_columns_out() {
+        let table = table(NestedStrategy::ChildTable, 1);
+        assert_eq!(names(&table.stored), ["score"]);
+        assert_eq!(table.children.len(), 1);
+        let child = &table.children[0];
+        assert_eq!(child.column, POSITION);
+        assert_eq!(child.storage_name, "ns-Player__position");
+        assert_eq!(names(&child.columns), ["x", "y", "z.a", "z.b"]);
+        assert!(child.upsert_sql.starts_with(
+            r#"INSERT INTO "ns-Player__position" ("entity_id", "x", "y", "z.a", "z.b")"#
+        ));
+    }
+
+    #[test]
+    fn enum_paths_resolve_the_selected_variant() {
+        let column = |path| SqliteColumn {
+            name: "direction".to_string(),
+            column: POSITION,
+            path,
+            type_def: TypeDef::Utf8String,
+        };
+        let record = json!({ "direction": { "variant": "Left", "_Left": 3 } });
+        let record = record.as_object().unwrap();
+
+        assert_eq!(
+            column(vec![PathSegment::Variant]).resolve(record, "direction"),
+            &json!("Left")
+        );
+        assert_eq!(
+            column(vec![PathSegment::VariantValue]).resolve(record, "direction"),
+            &json!(3)
+        );
+        assert_eq!(
+            column(vec![PathSegment::VariantValue]).resolve(record, "missing"),
+            &Value::Null
+        );
+    }
+}
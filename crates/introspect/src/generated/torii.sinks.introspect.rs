@@ -0,0 +1,48 @@
+// This file is @generated by prost-build.
+/// Table declared or redeclared, published on the `introspect` topic
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeclareTable {
+    /// World (emitting contract) address (32 bytes)
+    #[prost(bytes = "vec", tag = "1")]
+    pub world: ::prost::alloc::vec::Vec<u8>,
+    /// Table id (32 bytes)
+    #[prost(bytes = "vec", tag = "2")]
+    pub table_id: ::prost::alloc::vec::Vec<u8>,
+    /// Table name
+    #[prost(string, tag = "3")]
+    pub name: ::prost::alloc::string::String,
+    /// Whether the table already existed (schema update)
+    #[prost(bool, tag = "4")]
+    pub redeclared: bool,
+    /// JSON encoded table schema (attributes, primary and columns)
+    #[prost(string, tag = "5")]
+    pub schema_json: ::prost::alloc::string::String,
+    /// Block number of the declaration
+    #[prost(uint64, tag = "6")]
+    pub block_number: u64,
+}
+/// Fields of a record set, published on the `introspect` topic
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpdateRecord {
+    /// World (emitting contract) address (32 bytes)
+    #[prost(bytes = "vec", tag = "1")]
+    pub world: ::prost::alloc::vec::Vec<u8>,
+    /// Table id (32 bytes)
+    #[prost(bytes = "vec", tag = "2")]
+    pub table_id: ::prost::alloc::vec::Vec<u8>,
+    /// Table name (empty if the table is unknown to the sink)
+    #[prost(string, tag = "3")]
+    pub table_name: ::prost::alloc::string::String,
+    /// Record id (32 bytes)
+    #[prost(bytes = "vec", tag = "4")]
+    pub record_id: ::prost::alloc::vec::Vec<u8>,
+    /// Ids of the set columns (32 bytes each)
+    #[prost(bytes = "vec", repeated, tag = "5")]
+    pub columns: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+    /// Serialized values of `columns`, in order
+    #[prost(bytes = "vec", tag = "6")]
+    pub values: ::prost::alloc::vec::Vec<u8>,
+    /// Block number of the update
+    #[prost(uint64, tag = "7")]
+    pub block_number: u64,
+}
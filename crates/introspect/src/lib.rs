@@ -1,6 +1,7 @@
 pub mod events;
 pub mod postgres;
 pub mod schema;
+pub mod sink;
 pub mod store;
 pub mod tables;
 pub mod types;
@@ -10,3 +11,12 @@ pub use events::{
     UpdateTable,
 };
 pub use schema::ColumnKey;
+pub use sink::IntrospectSink;
+
+// Include generated protobuf code
+pub mod proto {
+    include!("generated/torii.sinks.introspect.rs");
+}
+
+// File descriptor set for gRPC reflection
+pub const FILE_DESCRIPTOR_SET: &[u8] = include_bytes!("generated/introspect_descriptor.bin");
@@ -0,0 +1,265 @@
+//! Sink streaming introspect schema and record changes to EventBus subscribers.
+//!
+//! Publishes [`DeclareTable`] updates for `CreateTable`/`UpdateTable` messages and one
+//! [`UpdateRecord`] update per record of `InsertsFields` messages on the `introspect`
+//! topic (via `torii.Torii/Subscribe`). Subscribers can filter on the table name with the
+//! `table` filter.
+
+use async_trait::async_trait;
+use prost::Message;
+use prost_types::Any;
+use starknet_types_core::felt::Felt;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use torii::axum::Router;
+use torii::etl::envelope::{Envelope, MetaData, TypeId};
+use torii::etl::extractor::ExtractionBatch;
+use torii::etl::sink::{EventBus, Sink, SinkContext, SinkContractFilter, TopicInfo};
+use torii::grpc::UpdateType;
+use torii::ToriiResult;
+
+use crate::events::{IntrospectBody, IntrospectMsg};
+use crate::proto::{DeclareTable, UpdateRecord};
+
+pub const LOGGING_TARGET: &str = "torii::sinks::introspect";
+/// EventBus topic of the introspect updates
+pub const INTROSPECT_TOPIC: &str = "introspect";
+const INTROSPECT_TYPE: TypeId = TypeId::new("introspect");
+
+/// Sink publishing introspect schema changes and record updates on the `introspect` topic
+///
+/// Table names are learnt from the declarations the sink processes; tables declared
+/// before the sink started can be registered with [`IntrospectSink::with_tables`].
+#[derive(Default)]
+pub struct IntrospectSink {
+    event_bus: Option<Arc<EventBus>>,
+    tables: RwLock<HashMap<Felt, String>>,
+    contract_filter: Option<SinkContractFilter>,
+}
+
+impl IntrospectSink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the names of already declared tables (e.g. the tables loaded by the decoder)
+    pub fn with_tables(self, tables: impl IntoIterator<Item = (Felt, String)>) -> Self {
+        self.tables.write().unwrap().extend(tables);
+        self
+    }
+
+    /// Only publish the tables of the worlds `filter` routes to this sink (e.g. one
+    /// sink per world, each under its own namespace)
+    pub fn with_contract_filter(mut self, filter: SinkContractFilter) -> Self {
+        self.contract_filter = Some(filter);
+        self
+    }
+
+    fn table_name(&self, id: &Felt) -> String {
+        self.tables
+            .read()
+            .unwrap()
+            .get(id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Keep the table names in sync with the schema changes
+    fn track_table(&self, msg: &IntrospectMsg) {
+        let mut tables = self.tables.write().unwrap();
+        match msg {
+            IntrospectMsg::CreateTable(table) => {
+                tables.insert(table.id, table.name.clone());
+            }
+            IntrospectMsg::UpdateTable(table) => {
+                tables.insert(table.id, table.name.clone());
+            }
+            IntrospectMsg::RenameTable(table) => {
+                tables.insert(table.id, table.name.clone());
+            }
+            IntrospectMsg::DropTable(table) => {
+                tables.remove(&table.id);
+            }
+            _ => {}
+        }
+    }
+
+    /// Convert an introspect message to the updates to publish
+    fn updates(&self, msg: &IntrospectMsg, metadata: &MetaData) -> Vec<IntrospectUpdate> {
+        let world = metadata.from_address.to_bytes_be().to_vec();
+        let block_number = metadata.block_number.unwrap_or_default();
+        match msg {
+            IntrospectMsg::CreateTable(table) => vec![IntrospectUpdate::Table(DeclareTable {
+                world,
+                table_id: table.id.to_bytes_be().to_vec(),
+                name: table.name.clone(),
+                redeclared: false,
+                schema_json: serde_json::to_string(table).unwrap_or_default(),
+                block_number,
+            })],
+            IntrospectMsg::UpdateTable(table) => vec![IntrospectUpdate::Table(DeclareTable {
+                world,
+                table_id: table.id.to_bytes_be().to_vec(),
+                name: table.name.clone(),
+                redeclared: true,
+                schema_json: serde_json::to_string(table).unwrap_or_default(),
+                block_number,
+            })],
+            IntrospectMsg::InsertsFields(event) => {
+                let table_id = event.table.to_bytes_be().to_vec();
+                let table_name = self.table_name(&event.table);
+                let columns: Vec<Vec<u8>> = event
+                    .columns
+                    .iter()
+                    .map(|column| column.to_bytes_be().to_vec())
+                    .collect();
+                event
+                    .records
+                    .iter()
+                    .map(|record| {
+                        IntrospectUpdate::Record(UpdateRecord {
+                            world: world.clone(),
+                            table_id: table_id.clone(),
+                            table_name: table_name.clone(),
+                            record_id: record.id.to_vec(),
+                            columns: columns.clone(),
+                            values: record.values.clone(),
+                            block_number,
+                        })
+                    })
+                    .collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn publish(event_bus: &EventBus, update: IntrospectUpdate) -> Result<(), prost::EncodeError> {
+        let (type_id, type_url, table, update_type, value) = match &update {
+            IntrospectUpdate::Table(table) => {
+                let mut buf = Vec::new();
+                table.encode(&mut buf)?;
+                let update_type = if table.redeclared {
+                    UpdateType::Updated
+                } else {
+                    UpdateType::Created
+                };
+                (
+                    "introspect.declare_table",
+                    "type.googleapis.com/torii.sinks.introspect.DeclareTable",
+                    table.name.clone(),
+                    update_type,
+                    buf,
+                )
+            }
+            IntrospectUpdate::Record(record) => {
+                let mut buf = Vec::new();
+                record.encode(&mut buf)?;
+                (
+                    "introspect.update_record",
+                    "type.googleapis.com/torii.sinks.introspect.UpdateRecord",
+                    record.table_name.clone(),
+                    UpdateType::Updated,
+                    buf,
+                )
+            }
+        };
+        let any = Any {
+            type_url: type_url.to_string(),
+            value,
+        };
+        event_bus.publish_protobuf(
+            INTROSPECT_TOPIC,
+            type_id,
+            &any,
+            &table,
+            update_type,
+            |table: &String, filters: &HashMap<String, String>| {
+                filters.get("table").is_none_or(|name| name == table)
+            },
+        );
+        Ok(())
+    }
+}
+
+/// Update published on the `introspect` topic
+enum IntrospectUpdate {
+    Table(DeclareTable),
+    Record(UpdateRecord),
+}
+
+#[async_trait]
+impl Sink for IntrospectSink {
+    fn name(&self) -> &'static str {
+        "introspect"
+    }
+
+    fn version(&self) -> Option<&str> {
+        Some(env!("CARGO_PKG_VERSION"))
+    }
+
+    fn descriptor_set(&self) -> Option<&'static [u8]> {
+        Some(crate::FILE_DESCRIPTOR_SET)
+    }
+
+    fn interested_types(&self) -> Vec<TypeId> {
+        vec![INTROSPECT_TYPE]
+    }
+
+    fn contract_filter(&self) -> Option<&SinkContractFilter> {
+        self.contract_filter.as_ref()
+    }
+
+    async fn process(&self, envelopes: &[Envelope], _batch: &ExtractionBatch) -> ToriiResult<()> {
+        let mut published = 0usize;
+        for envelope in envelopes {
+            if envelope.type_id != INTROSPECT_TYPE {
+                continue;
+            }
+            let Some(body) = envelope.downcast_ref::<IntrospectBody>() else {
+                continue;
+            };
+            self.track_table(&body.msg);
+            let Some(event_bus) = &self.event_bus else {
+                continue;
+            };
+            for update in self.updates(&body.msg, &body.metadata) {
+                Self::publish(event_bus, update).map_err(anyhow::Error::from)?;
+                published += 1;
+            }
+        }
+
+        if published > 0 {
+            tracing::debug!(
+                target: LOGGING_TARGET,
+                published,
+                "Published introspect updates"
+            );
+            ::metrics::counter!("torii_introspect_sink_published_total")
+                .increment(published as u64);
+        }
+        Ok(())
+    }
+
+    fn topics(&self) -> Vec<TopicInfo> {
+        vec![TopicInfo::new(
+            INTROSPECT_TOPIC,
+            vec!["table".to_string()],
+            "Dojo table declarations (DeclareTable) and record updates (UpdateRecord). \
+             Use the 'table' filter to follow a single table.",
+        )]
+    }
+
+    fn build_routes(&self) -> Router {
+        Router::new()
+    }
+
+    async fn initialize(
+        &mut self,
+        event_bus: Arc<EventBus>,
+        _context: &SinkContext,
+    ) -> ToriiResult<()> {
+        self.event_bus = Some(event_bus);
+        tracing::info!(target: LOGGING_TARGET, "Initialized introspect sink");
+        Ok(())
+    }
+}
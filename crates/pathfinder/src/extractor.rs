@@ -1,5 +1,4 @@
 use crate::{connect, EventFetcher, PFResult};
-use anyhow::Result as AnyResult;
 use async_trait::async_trait;
 use rusqlite::Connection;
 use starknet::core::types::EmittedEvent;
@@ -7,6 +6,7 @@ use std::collections::HashMap;
 use std::path::Path;
 use std::sync::{Arc, Mutex};
 use torii::etl::{BlockContext, EngineDb, ExtractionBatch, Extractor};
+use torii::ToriiResult;
 
 #[derive(Debug)]
 pub struct PathfinderExtractor {
@@ -79,7 +79,7 @@ impl Extractor for PathfinderExtractor {
         &mut self,
         _cursor: Option<String>,
         _engine_db: &EngineDb,
-    ) -> AnyResult<ExtractionBatch> {
+    ) -> ToriiResult<ExtractionBatch> {
         let (blocks, events) = self.next_batch()?;
         let blocks = blocks
             .into_iter()
@@ -113,7 +113,7 @@ impl<T: Extractor + Send + Sync + 'static> Extractor for PathfinderCombinedExtra
         &mut self,
         cursor: Option<String>,
         engine_db: &EngineDb,
-    ) -> AnyResult<ExtractionBatch> {
+    ) -> ToriiResult<ExtractionBatch> {
         if self.on_head {
             self.head.extract(cursor, engine_db).await
         } else if self.pathfinder.is_finished() {
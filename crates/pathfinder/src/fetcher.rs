@@ -27,6 +27,8 @@ impl From<BlockContextRow> for BlockContext {
             hash: value.hash.into(),
             parent_hash: value.parent_hash.into(),
             timestamp: value.timestamp,
+            #[cfg(feature = "etl")]
+            header: None,
         }
     }
 }
@@ -0,0 +1,137 @@
+//! Golden decoder tests from captured event fixtures.
+//!
+//! A fixture is an event batch as written by `dojo-fixtures`
+//! (`{"continuation_token": ..., "events": [...]}`). Every event is decoded on its
+//! own and the resulting envelopes are compared with a serde snapshot stored next to
+//! the fixture (`erc20.json` -> `erc20.golden.json`).
+//!
+//! Missing snapshots are written on the first run. Set `TORII_UPDATE_GOLDEN=1` to
+//! rewrite them after an intended decoder change.
+
+use serde::{Deserialize, Serialize};
+use starknet::core::types::EmittedEvent;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use torii::etl::{Decoder, Envelope};
+
+use crate::event_reader::EventBatch;
+use crate::read_json_file;
+
+/// Environment variable forcing snapshots to be rewritten.
+pub const UPDATE_GOLDEN_ENV: &str = "TORII_UPDATE_GOLDEN";
+
+/// Serializable view of an envelope produced by a decoder.
+///
+/// The creation timestamp is left out so snapshots are stable across runs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EnvelopeSnapshot {
+    /// Index of the source event in the fixture.
+    pub event_index: usize,
+    pub id: String,
+    pub type_id: u64,
+    pub metadata: BTreeMap<String, String>,
+    /// `Debug` rendering of the body (see `TypedBody::debug_repr`).
+    pub body: Option<String>,
+}
+
+impl EnvelopeSnapshot {
+    pub fn new(event_index: usize, envelope: &Envelope) -> Self {
+        Self {
+            event_index,
+            id: envelope.id.clone(),
+            type_id: envelope.type_id.as_u64(),
+            metadata: envelope
+                .metadata
+                .iter()
+                .map(|(key, value)| (key.clone(), value.to_string()))
+                .collect(),
+            body: envelope.body.debug_repr(),
+        }
+    }
+}
+
+/// Loads the events of a fixture file.
+pub fn load_fixture_events(path: impl AsRef<Path>) -> Vec<EmittedEvent> {
+    let path = path.as_ref().to_path_buf();
+    let batch: EventBatch = read_json_file(&path)
+        .unwrap_or_else(|e| panic!("failed to read fixture {}: {e}", path.display()));
+    batch.events.into_iter().map(Into::into).collect()
+}
+
+/// Snapshot file of a fixture: `<stem>.golden.json` in the same directory.
+pub fn golden_path(fixture: &Path) -> PathBuf {
+    let stem = fixture
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    fixture.with_file_name(format!("{stem}.golden.json"))
+}
+
+/// Decodes every event of `fixture` with `decoder` and asserts the envelopes match the
+/// golden snapshot.
+pub async fn assert_decoder_golden<D: Decoder + ?Sized>(decoder: &D, fixture: impl AsRef<Path>) {
+    let fixture = fixture.as_ref();
+    let mut snapshots = Vec::new();
+    for (index, event) in load_fixture_events(fixture).iter().enumerate() {
+        let envelopes = decoder
+            .decode_event(event)
+            .await
+            .unwrap_or_else(|e| panic!("{} failed on event {index}: {e}", decoder.decoder_name()));
+        snapshots.extend(
+            envelopes
+                .iter()
+                .map(|envelope| EnvelopeSnapshot::new(index, envelope)),
+        );
+    }
+
+    let golden = golden_path(fixture);
+    let update = std::env::var(UPDATE_GOLDEN_ENV).is_ok_and(|value| value == "1");
+    if update || !golden.exists() {
+        let json = serde_json::to_string_pretty(&snapshots).expect("snapshots serialize");
+        fs::write(&golden, json + "\n")
+            .unwrap_or_else(|e| panic!("failed to write {}: {e}", golden.display()));
+        return;
+    }
+
+    let expected: Vec<EnvelopeSnapshot> = read_json_file(&golden)
+        .unwrap_or_else(|e| panic!("failed to read {}: {e}", golden.display()));
+    assert_eq!(
+        snapshots,
+        expected,
+        "{} output differs from {} (rerun with {UPDATE_GOLDEN_ENV}=1 if intended)",
+        decoder.decoder_name(),
+        golden.display()
+    );
+}
+
+/// Blocking wrapper around [`assert_decoder_golden`] for plain `#[test]` functions.
+pub fn run_decoder_golden<D: Decoder + ?Sized>(decoder: &D, fixture: impl AsRef<Path>) {
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .expect("tokio runtime")
+        .block_on(assert_decoder_golden(decoder, fixture));
+}
+
+/// Generates a golden test decoding a fixture, relative to the calling crate's manifest.
+///
+/// ```rust,ignore
+/// torii_test_utils::decoder_golden_test!(Erc20Decoder, "fixtures/erc20.json");
+/// // Several tests in one module need a name:
+/// torii_test_utils::decoder_golden_test!(legacy_golden, Erc20Decoder, "fixtures/legacy.json");
+/// ```
+#[macro_export]
+macro_rules! decoder_golden_test {
+    ($decoder:expr, $fixture:literal) => {
+        $crate::decoder_golden_test!(decoder_golden, $decoder, $fixture);
+    };
+    ($name:ident, $decoder:expr, $fixture:literal) => {
+        #[test]
+        fn $name() {
+            $crate::golden::run_decoder_golden(
+                &$decoder,
+                concat!(env!("CARGO_MANIFEST_DIR"), "/", $fixture),
+            );
+        }
+    };
+}
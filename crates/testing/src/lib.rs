@@ -1,6 +1,10 @@
+#[cfg(feature = "dojo")]
 mod dojo;
 mod event_reader;
+pub mod golden;
 pub mod utils;
+#[cfg(feature = "dojo")]
 pub use dojo::FakeProvider;
 pub use event_reader::EventIterator;
+pub use golden::{assert_decoder_golden, load_fixture_events, EnvelopeSnapshot};
 pub use utils::{read_json_file, resolve_path_like};
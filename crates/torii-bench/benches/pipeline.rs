@@ -0,0 +1,171 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+use tokio::runtime::Runtime;
+
+use torii::etl::sink::MultiSink;
+use torii::etl::Decoder;
+use torii_bench::{
+    decoder_context, run_pipeline, BuiltinSink, EventGenerator, EventKind, EventMix,
+    GeneratorConfig,
+};
+
+/// Events per generated block (one batch per iteration)
+const EVENTS_PER_BLOCK: usize = 256;
+
+const TOKEN_SINKS: [BuiltinSink; 3] = [
+    BuiltinSink::Erc20,
+    BuiltinSink::Erc721,
+    BuiltinSink::Erc1155,
+];
+
+fn runtime() -> Runtime {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("failed to create tokio runtime")
+}
+
+fn generator(mix: EventMix, contracts_per_kind: usize) -> EventGenerator {
+    EventGenerator::new(GeneratorConfig {
+        mix,
+        events_per_block: EVENTS_PER_BLOCK,
+        contracts_per_kind,
+        ..GeneratorConfig::default()
+    })
+    .expect("invalid generator config")
+}
+
+/// Mix generating only the events of `sink` (everything for the log sink).
+fn sink_mix(sink: BuiltinSink) -> EventMix {
+    let weight = |kind| u32::from(sink.event_kind().is_none_or(|k| k == kind));
+    EventMix {
+        erc20: weight(EventKind::Erc20Transfer),
+        erc721: weight(EventKind::Erc721Transfer),
+        erc1155: weight(EventKind::Erc1155TransferSingle),
+        introspect: weight(EventKind::IntrospectSetRecord),
+    }
+}
+
+fn benchmark_decode(c: &mut Criterion) {
+    let rt = runtime();
+    let mut group = c.benchmark_group("synthetic_decode");
+    group.throughput(Throughput::Elements(EVENTS_PER_BLOCK as u64));
+
+    let mixes = [
+        ("erc20", EventMix::erc20()),
+        ("tokens", EventMix::tokens()),
+        ("mixed", EventMix::mixed()),
+    ];
+    for (name, mix) in mixes {
+        for contracts in [4_usize, 256] {
+            let mut generator = generator(mix, contracts);
+            let context = rt
+                .block_on(decoder_context(&TOKEN_SINKS, &generator))
+                .expect("failed to create decoder context");
+            let batch = generator.next_batch(1);
+            group.bench_with_input(
+                BenchmarkId::new(name, format!("{contracts}_contracts")),
+                &batch,
+                |b, batch| {
+                    b.to_async(&rt).iter(|| async {
+                        black_box(
+                            context
+                                .decode(black_box(&batch.events))
+                                .await
+                                .expect("decode failed"),
+                        )
+                    });
+                },
+            );
+        }
+    }
+
+    group.finish();
+}
+
+fn benchmark_sinks(c: &mut Criterion) {
+    let rt = runtime();
+    let mut group = c.benchmark_group("synthetic_sink");
+    group.throughput(Throughput::Elements(EVENTS_PER_BLOCK as u64));
+    group.measurement_time(Duration::from_secs(10));
+
+    for builtin in BuiltinSink::ALL {
+        let dir = TempDir::new().expect("failed to create tempdir");
+        let mut generator = generator(sink_mix(builtin), 16);
+        let context = rt
+            .block_on(decoder_context(&[builtin], &generator))
+            .expect("failed to create decoder context");
+        let sink = rt
+            .block_on(builtin.create(dir.path()))
+            .expect("failed to create sink");
+
+        group.bench_function(builtin.name(), |b| {
+            b.iter_custom(|iters| {
+                // Fresh blocks every iteration, so storage sees new rows.
+                let inputs: Vec<_> = (0..iters)
+                    .map(|_| {
+                        let batch = generator.next_batch(1);
+                        let envelopes = rt
+                            .block_on(context.decode(&batch.events))
+                            .expect("decode failed");
+                        (batch, envelopes)
+                    })
+                    .collect();
+
+                let start = Instant::now();
+                for (batch, envelopes) in &inputs {
+                    rt.block_on(sink.process(black_box(envelopes), black_box(batch)))
+                        .expect("sink process failed");
+                }
+                start.elapsed()
+            });
+        });
+    }
+
+    group.finish();
+}
+
+fn benchmark_pipeline(c: &mut Criterion) {
+    let rt = runtime();
+    let mut group = c.benchmark_group("synthetic_pipeline");
+    group.throughput(Throughput::Elements(EVENTS_PER_BLOCK as u64));
+    group.measurement_time(Duration::from_secs(10));
+
+    let dir = TempDir::new().expect("failed to create tempdir");
+    let mut generator = generator(EventMix::mixed(), 16);
+    let context = rt
+        .block_on(decoder_context(&BuiltinSink::ALL, &generator))
+        .expect("failed to create decoder context");
+    let sinks = rt.block_on(async {
+        let mut sinks = Vec::new();
+        for builtin in BuiltinSink::ALL {
+            sinks.push(
+                builtin
+                    .create(dir.path())
+                    .await
+                    .expect("failed to create sink"),
+            );
+        }
+        MultiSink::new(sinks)
+    });
+
+    group.bench_function("mixed_all_sinks", |b| {
+        b.iter_custom(|iters| {
+            let batches: Vec<_> = (0..iters).map(|_| generator.next_batch(1)).collect();
+            rt.block_on(run_pipeline(&context, &sinks, &batches))
+                .expect("pipeline failed")
+                .elapsed
+        });
+    });
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    benchmark_decode,
+    benchmark_sinks,
+    benchmark_pipeline
+);
+criterion_main!(benches);
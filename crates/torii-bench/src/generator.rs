@@ -0,0 +1,377 @@
+//! Deterministic synthetic event generator.
+//!
+//! Produces extraction batches with a configurable mix of canonical ERC20, ERC721,
+//! ERC1155 and Dojo introspect (`StoreSetRecord`) events, a fixed number of events
+//! per block and a bounded number of emitting contracts per kind. The same
+//! configuration always yields the same events, so runs are comparable.
+
+use anyhow::Result;
+use starknet::core::types::{EmittedEvent, Felt};
+use starknet::macros::selector;
+use torii::etl::extractor::ExtractionBatch;
+
+/// Kind of generated event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventKind {
+    /// ERC20 `Transfer(from, to, amount)`
+    Erc20Transfer,
+    /// ERC721 `Transfer(from, to, token_id)`
+    Erc721Transfer,
+    /// ERC1155 `TransferSingle(operator, from, to, id, value)`
+    Erc1155TransferSingle,
+    /// Dojo world `StoreSetRecord(selector, entity_id, keys, values)`
+    IntrospectSetRecord,
+}
+
+impl EventKind {
+    pub const ALL: [Self; 4] = [
+        Self::Erc20Transfer,
+        Self::Erc721Transfer,
+        Self::Erc1155TransferSingle,
+        Self::IntrospectSetRecord,
+    ];
+
+    /// Base of the contract addresses emitting this kind
+    fn contract_base(self) -> u64 {
+        match self {
+            Self::Erc20Transfer => 0x0100_0000,
+            Self::Erc721Transfer => 0x0200_0000,
+            Self::Erc1155TransferSingle => 0x0300_0000,
+            Self::IntrospectSetRecord => 0x0400_0000,
+        }
+    }
+}
+
+/// Relative weights of each event kind
+///
+/// A kind with weight 0 is never generated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventMix {
+    pub erc20: u32,
+    pub erc721: u32,
+    pub erc1155: u32,
+    pub introspect: u32,
+}
+
+impl EventMix {
+    /// ERC20 transfers only
+    pub fn erc20() -> Self {
+        Self {
+            erc20: 1,
+            erc721: 0,
+            erc1155: 0,
+            introspect: 0,
+        }
+    }
+
+    /// Token traffic dominated by fungible transfers
+    pub fn tokens() -> Self {
+        Self {
+            erc20: 70,
+            erc721: 20,
+            erc1155: 10,
+            introspect: 0,
+        }
+    }
+
+    /// Token and Dojo world traffic
+    pub fn mixed() -> Self {
+        Self {
+            erc20: 40,
+            erc721: 15,
+            erc1155: 15,
+            introspect: 30,
+        }
+    }
+
+    pub fn weight(&self, kind: EventKind) -> u32 {
+        match kind {
+            EventKind::Erc20Transfer => self.erc20,
+            EventKind::Erc721Transfer => self.erc721,
+            EventKind::Erc1155TransferSingle => self.erc1155,
+            EventKind::IntrospectSetRecord => self.introspect,
+        }
+    }
+
+    fn total(&self) -> u64 {
+        EventKind::ALL
+            .iter()
+            .map(|kind| u64::from(self.weight(*kind)))
+            .sum()
+    }
+
+    /// Kind at position `value` (in `0..total`) of the cumulative weights.
+    fn pick(&self, value: u64) -> EventKind {
+        let mut cumulative = 0;
+        for kind in EventKind::ALL {
+            cumulative += u64::from(self.weight(kind));
+            if value < cumulative {
+                return kind;
+            }
+        }
+        EventKind::Erc20Transfer
+    }
+}
+
+/// Configuration of the generated workload
+#[derive(Debug, Clone)]
+pub struct GeneratorConfig {
+    pub mix: EventMix,
+    /// First generated block
+    pub from_block: u64,
+    /// Events per block (event rate)
+    pub events_per_block: usize,
+    /// Distinct emitting contracts per event kind (contract cardinality)
+    pub contracts_per_kind: usize,
+    /// Distinct accounts used as senders and receivers
+    pub account_count: usize,
+    /// Distinct token ids per ERC721/ERC1155 contract
+    pub token_ids_per_contract: usize,
+    pub seed: u64,
+}
+
+impl Default for GeneratorConfig {
+    fn default() -> Self {
+        Self {
+            mix: EventMix::tokens(),
+            from_block: 1_000_000,
+            events_per_block: 500,
+            contracts_per_kind: 16,
+            account_count: 10_000,
+            token_ids_per_contract: 1_000,
+            seed: 42,
+        }
+    }
+}
+
+impl GeneratorConfig {
+    fn validate(&self) -> Result<()> {
+        if self.mix.total() == 0 {
+            anyhow::bail!("event mix must have at least one non-zero weight");
+        }
+        if self.events_per_block == 0 {
+            anyhow::bail!("events_per_block must be > 0");
+        }
+        if self.contracts_per_kind == 0 {
+            anyhow::bail!("contracts_per_kind must be > 0");
+        }
+        if self.account_count == 0 {
+            anyhow::bail!("account_count must be > 0");
+        }
+        if self.token_ids_per_contract == 0 {
+            anyhow::bail!("token_ids_per_contract must be > 0");
+        }
+        Ok(())
+    }
+}
+
+/// Generator of synthetic extraction batches
+pub struct EventGenerator {
+    config: GeneratorConfig,
+    next_block: u64,
+    state: u64,
+}
+
+impl EventGenerator {
+    pub fn new(config: GeneratorConfig) -> Result<Self> {
+        config.validate()?;
+        Ok(Self {
+            next_block: config.from_block,
+            state: config.seed,
+            config,
+        })
+    }
+
+    pub fn config(&self) -> &GeneratorConfig {
+        &self.config
+    }
+
+    /// Address of the `index`-th contract emitting `kind`.
+    pub fn contract(&self, kind: EventKind, index: usize) -> Felt {
+        Felt::from(kind.contract_base() + (index % self.config.contracts_per_kind) as u64)
+    }
+
+    /// Contracts emitting `kind`.
+    pub fn contracts(&self, kind: EventKind) -> Vec<Felt> {
+        (0..self.config.contracts_per_kind)
+            .map(|index| self.contract(kind, index))
+            .collect()
+    }
+
+    /// Generates the next `block_count` blocks.
+    pub fn next_batch(&mut self, block_count: u64) -> ExtractionBatch {
+        let events = block_count as usize * self.config.events_per_block;
+        let mut batch =
+            ExtractionBatch::with_capacities(events, block_count as usize, events, 0, 0);
+
+        for block_number in self.next_block..self.next_block + block_count {
+            batch.add_block_context(
+                block_number,
+                block_hash(block_number),
+                block_hash(block_number.saturating_sub(1)),
+                1_700_000_000 + block_number * 2,
+            );
+            for index in 0..self.config.events_per_block {
+                let tx_hash =
+                    Felt::from(block_number) * Felt::from(1_000_000_u64) + Felt::from(index as u64);
+                let (event, sender) = self.next_event(block_number, tx_hash);
+                batch.add_event_with_tx_context(event, Some(sender), Vec::new());
+            }
+        }
+
+        self.next_block += block_count;
+        batch.set_cursor(format!("torii_bench:block:{}", self.next_block - 1));
+        batch.set_chain_head(self.next_block - 1);
+        batch
+    }
+
+    /// splitmix64
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn next_below(&mut self, bound: usize) -> u64 {
+        self.next_u64() % bound as u64
+    }
+
+    fn account(&mut self) -> Felt {
+        Felt::from(0x0500_0000 + self.next_below(self.config.account_count))
+    }
+
+    /// Generates one event and the account sending its transaction.
+    fn next_event(&mut self, block_number: u64, tx_hash: Felt) -> (EmittedEvent, Felt) {
+        let mix = self.config.mix;
+        let kind = mix.pick(self.next_u64() % mix.total());
+        let index = self.next_below(self.config.contracts_per_kind) as usize;
+        let contract = self.contract(kind, index);
+        let from = self.account();
+        let to = self.account();
+        let (keys, data) = match kind {
+            EventKind::Erc20Transfer => {
+                let amount = Felt::from(1 + self.next_below(1_000_000));
+                (
+                    vec![selector!("Transfer"), from, to],
+                    vec![amount, Felt::ZERO],
+                )
+            }
+            EventKind::Erc721Transfer => {
+                let token_id = Felt::from(self.next_below(self.config.token_ids_per_contract));
+                (
+                    vec![selector!("Transfer"), from, to, token_id, Felt::ZERO],
+                    Vec::new(),
+                )
+            }
+            EventKind::Erc1155TransferSingle => {
+                let token_id = Felt::from(self.next_below(self.config.token_ids_per_contract));
+                let value = Felt::from(1 + self.next_below(100));
+                (
+                    vec![selector!("TransferSingle"), from, from, to],
+                    vec![token_id, Felt::ZERO, value, Felt::ZERO],
+                )
+            }
+            EventKind::IntrospectSetRecord => {
+                let model = Felt::from(0x0600_0000 + self.next_below(8));
+                let entity = Felt::from(self.next_below(self.config.account_count));
+                let value = Felt::from(self.next_u64());
+                (
+                    vec![selector!("StoreSetRecord"), model, entity],
+                    vec![
+                        Felt::ONE,
+                        entity,
+                        Felt::TWO,
+                        value,
+                        Felt::from(block_number),
+                    ],
+                )
+            }
+        };
+        let event = EmittedEvent {
+            from_address: contract,
+            keys,
+            data,
+            block_hash: Some(block_hash(block_number)),
+            block_number: Some(block_number),
+            transaction_hash: tx_hash,
+        };
+        (event, from)
+    }
+}
+
+fn block_hash(block_number: u64) -> Felt {
+    Felt::from(0x0700_0000_u64 + block_number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::{HashMap, HashSet};
+
+    fn kind_of(generator: &EventGenerator, event: &EmittedEvent) -> EventKind {
+        *EventKind::ALL
+            .iter()
+            .find(|kind| generator.contracts(**kind).contains(&event.from_address))
+            .expect("event from unknown contract")
+    }
+
+    #[test]
+    fn test_generation_is_deterministic() {
+        let config = GeneratorConfig {
+            events_per_block: 20,
+            ..GeneratorConfig::default()
+        };
+        let first = EventGenerator::new(config.clone()).unwrap().next_batch(3);
+        let second = EventGenerator::new(config).unwrap().next_batch(3);
+        assert_eq!(first.events, second.events);
+        assert_eq!(first.events.len(), 60);
+        assert_eq!(first.blocks.len(), 3);
+    }
+
+    #[test]
+    fn test_batches_follow_mix_rate_and_cardinality() {
+        let mut generator = EventGenerator::new(GeneratorConfig {
+            mix: EventMix {
+                erc20: 1,
+                erc721: 0,
+                erc1155: 1,
+                introspect: 0,
+            },
+            events_per_block: 100,
+            contracts_per_kind: 3,
+            ..GeneratorConfig::default()
+        })
+        .unwrap();
+
+        let first = generator.next_batch(5);
+        let second = generator.next_batch(5);
+        assert_eq!(first.chain_head, Some(1_000_004));
+        assert!(second.blocks.contains_key(&1_000_005));
+
+        let mut per_kind: HashMap<EventKind, usize> = HashMap::new();
+        let contracts: HashSet<Felt> = first.events.iter().map(|e| e.from_address).collect();
+        for event in &first.events {
+            *per_kind.entry(kind_of(&generator, event)).or_default() += 1;
+        }
+        assert_eq!(contracts.len(), 6);
+        assert!(!per_kind.contains_key(&EventKind::Erc721Transfer));
+        assert!(per_kind[&EventKind::Erc20Transfer] > 150);
+        assert!(per_kind[&EventKind::Erc1155TransferSingle] > 150);
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let config = GeneratorConfig {
+            mix: EventMix {
+                erc20: 0,
+                erc721: 0,
+                erc1155: 0,
+                introspect: 0,
+            },
+            ..GeneratorConfig::default()
+        };
+        assert!(EventGenerator::new(config).is_err());
+    }
+}
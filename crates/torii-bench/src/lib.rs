@@ -0,0 +1,15 @@
+//! Stress and benchmark harness for the Torii pipeline
+//!
+//! [`generator`] produces deterministic extraction batches with a configurable mix
+//! of ERC20/ERC721/ERC1155/introspect events, event rate and contract cardinality.
+//! [`harness`] runs them through a [`DecoderContext`](torii::etl::decoder::DecoderContext)
+//! and the built-in sinks and reports events/sec.
+//!
+//! The criterion benches (`cargo bench -p torii-bench`) use both to track
+//! decoding, per-sink and end-to-end throughput.
+
+pub mod generator;
+pub mod harness;
+
+pub use generator::{EventGenerator, EventKind, EventMix, GeneratorConfig};
+pub use harness::{decoder_context, run_pipeline, BuiltinSink, Throughput};
@@ -0,0 +1,99 @@
+//! SQLite encryption at rest
+//!
+//! Local SQLite databases (token storages and the engine database) are encrypted with
+//! SQLCipher when a key is set in [`SQLITE_ENCRYPTION_KEY_ENV`], or in the file named by
+//! [`SQLITE_ENCRYPTION_KEY_FILE_ENV`] (for mounted secrets). SQLCipher replaces the bundled
+//! SQLite when building with the `sqlcipher` feature; without it, a configured key is
+//! rejected when opening a database instead of silently leaving it in plaintext.
+//!
+//! The key must be applied before any other statement on a connection. Existing
+//! plaintext databases are not converted: they have to be re-indexed (or exported with
+//! `sqlcipher_export`).
+
+use anyhow::{Context, Result};
+use rusqlite::{Connection, OptionalExtension};
+
+/// Environment variable holding the SQLCipher passphrase
+pub const SQLITE_ENCRYPTION_KEY_ENV: &str = "TORII_DB_ENCRYPTION_KEY";
+/// Environment variable naming a file that holds the SQLCipher passphrase
+pub const SQLITE_ENCRYPTION_KEY_FILE_ENV: &str = "TORII_DB_ENCRYPTION_KEY_FILE";
+
+/// Returns the SQLCipher version on SQLCipher builds, no row on plain SQLite.
+pub const SQLITE_CIPHER_VERSION_SQL: &str = "PRAGMA cipher_version";
+/// Reads the schema, failing if the key does not decrypt the database.
+pub const SQLITE_KEY_CHECK_SQL: &str = "SELECT count(*) FROM sqlite_master";
+
+/// Configured encryption key, if any (the key variable wins over the key file).
+pub fn sqlite_encryption_key() -> Result<Option<String>> {
+    if let Some(key) = std::env::var(SQLITE_ENCRYPTION_KEY_ENV)
+        .ok()
+        .filter(|key| !key.is_empty())
+    {
+        return Ok(Some(key));
+    }
+    let Some(path) = std::env::var_os(SQLITE_ENCRYPTION_KEY_FILE_ENV) else {
+        return Ok(None);
+    };
+    let key = std::fs::read_to_string(&path).with_context(|| {
+        format!(
+            "Failed to read {SQLITE_ENCRYPTION_KEY_FILE_ENV} ({})",
+            path.to_string_lossy()
+        )
+    })?;
+    let key = key.trim_end_matches(['\r', '\n']);
+    anyhow::ensure!(
+        !key.is_empty(),
+        "{SQLITE_ENCRYPTION_KEY_FILE_ENV} points to an empty file"
+    );
+    Ok(Some(key.to_string()))
+}
+
+/// `PRAGMA key` statement for `key`, used as a passphrase.
+pub fn sqlite_key_sql(key: &str) -> String {
+    format!("PRAGMA key = '{}';", key.replace('\'', "''"))
+}
+
+/// Fails unless `cipher_version` (the result of [`SQLITE_CIPHER_VERSION_SQL`]) shows
+/// SQLite is SQLCipher.
+pub fn ensure_sqlcipher(cipher_version: Option<&str>) -> Result<()> {
+    anyhow::ensure!(
+        cipher_version.is_some_and(|version| !version.is_empty()),
+        "{SQLITE_ENCRYPTION_KEY_ENV} is set but SQLite was built without SQLCipher \
+         (build with the `sqlcipher` feature)"
+    );
+    Ok(())
+}
+
+/// Keys a freshly opened connection when an encryption key is configured.
+///
+/// Returns whether the database is encrypted.
+pub fn apply_sqlite_encryption(conn: &Connection) -> Result<bool> {
+    let Some(key) = sqlite_encryption_key()? else {
+        return Ok(false);
+    };
+    conn.execute_batch(&sqlite_key_sql(&key))?;
+    let cipher_version: Option<String> = conn
+        .query_row(SQLITE_CIPHER_VERSION_SQL, [], |row| row.get(0))
+        .optional()?;
+    ensure_sqlcipher(cipher_version.as_deref())?;
+    conn.query_row(SQLITE_KEY_CHECK_SQL, [], |_| Ok(()))
+        .context("Cannot read the database with the configured encryption key")?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_sql_escapes_quotes() {
+        assert_eq!(sqlite_key_sql("s3cret"), "PRAGMA key = 's3cret';");
+        assert_eq!(sqlite_key_sql("it's"), "PRAGMA key = 'it''s';");
+    }
+
+    #[test]
+    fn plain_sqlite_is_rejected() {
+        assert!(ensure_sqlcipher(None).is_err());
+        assert!(ensure_sqlcipher(Some("4.6.1 community")).is_ok());
+    }
+}
@@ -0,0 +1,99 @@
+//! Field masks for selective column projection in query RPCs.
+//!
+//! A client lists the fields of the returned rows it needs (`field_mask`); storage only
+//! selects those columns (the others are replaced by an empty placeholder) and the gRPC
+//! layer leaves the other fields unset, which keeps them off the wire.
+
+use std::collections::HashSet;
+
+/// Fields of a query result requested by a client (all fields when empty)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FieldMask {
+    fields: Option<HashSet<String>>,
+}
+
+impl FieldMask {
+    /// Mask selecting every field
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    /// Parse the field names sent by a client against the fields of the message.
+    ///
+    /// An empty list selects every field. Returns the first unknown field name as error.
+    pub fn parse<S: AsRef<str>>(paths: &[S], allowed: &[&str]) -> Result<Self, String> {
+        if paths.is_empty() {
+            return Ok(Self::all());
+        }
+        let mut fields = HashSet::with_capacity(paths.len());
+        for path in paths {
+            let path = path.as_ref().trim();
+            if !allowed.contains(&path) {
+                return Err(path.to_string());
+            }
+            fields.insert(path.to_string());
+        }
+        Ok(Self {
+            fields: Some(fields),
+        })
+    }
+
+    /// Mask also selecting `fields` (e.g. fields the server needs to enrich the results)
+    #[must_use]
+    pub fn with_fields(mut self, fields: &[&str]) -> Self {
+        if let Some(selected) = &mut self.fields {
+            selected.extend(fields.iter().map(|field| (*field).to_string()));
+        }
+        self
+    }
+
+    /// Whether every field is selected
+    pub fn is_all(&self) -> bool {
+        self.fields.is_none()
+    }
+
+    /// Whether `field` is selected
+    pub fn includes(&self, field: &str) -> bool {
+        self.fields
+            .as_ref()
+            .is_none_or(|fields| fields.contains(field))
+    }
+
+    /// SQL expression selecting `column` when `field` is selected, `placeholder` otherwise
+    pub fn column<'a>(&self, field: &str, column: &'a str, placeholder: &'a str) -> &'a str {
+        if self.includes(field) {
+            column
+        } else {
+            placeholder
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIELDS: &[&str] = &["token", "from", "to", "amount"];
+
+    #[test]
+    fn parses_requested_fields() {
+        let mask = FieldMask::parse(&["token", " amount"], FIELDS).unwrap();
+        assert!(!mask.is_all());
+        assert!(mask.includes("token"));
+        assert!(mask.includes("amount"));
+        assert!(!mask.includes("from"));
+        assert_eq!(mask.column("from", "t.from_addr", "X''"), "X''");
+        assert_eq!(mask.column("token", "t.token", "X''"), "t.token");
+        assert!(mask.with_fields(&["from"]).includes("from"));
+
+        let all = FieldMask::parse::<&str>(&[], FIELDS).unwrap();
+        assert!(all.is_all());
+        assert!(all.includes("from"));
+        assert!(all.with_fields(&["to"]).is_all());
+
+        assert_eq!(
+            FieldMask::parse(&["token", "metadata"], FIELDS),
+            Err("metadata".to_string())
+        );
+    }
+}
@@ -0,0 +1,261 @@
+//! Human-readable labels of addresses (exchanges, bridges, known spam, ...).
+//!
+//! [`AddressLabels`] keeps `address -> (name, tags)` in an `address_labels` table
+//! (SQLite or PostgreSQL) and mirrors it in memory, so token services can attach the
+//! labels of the addresses in a response without a database round trip. Labels are set
+//! from a configuration file at startup ([`AddressLabel::load_file`]) or through the
+//! admin RPCs.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sqlx::any::{AnyPoolOptions, AnyRow};
+use sqlx::{Any, Pool, Row};
+use starknet::core::types::Felt;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+
+const CREATE_TABLE: &str = "CREATE TABLE IF NOT EXISTS address_labels (
+    address TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    tags TEXT NOT NULL
+)";
+
+const UPSERT: &str = "INSERT INTO address_labels (address, name, tags) VALUES ($1, $2, $3)
+    ON CONFLICT (address) DO UPDATE SET name = excluded.name, tags = excluded.tags";
+
+/// Human-readable name and tags of an address
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddressLabel {
+    pub address: Felt,
+    pub name: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl AddressLabel {
+    pub fn new(address: Felt, name: impl Into<String>) -> Self {
+        Self {
+            address,
+            name: name.into(),
+            tags: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    /// Reads labels from a JSON file holding an array of `{"address", "name", "tags"}`
+    /// objects.
+    pub fn load_file(path: &Path) -> Result<Vec<Self>> {
+        let source = std::fs::read(path)
+            .with_context(|| format!("Failed to read address labels {}", path.display()))?;
+        serde_json::from_slice(&source)
+            .with_context(|| format!("Invalid address labels {}", path.display()))
+    }
+}
+
+/// Address labels persisted in a database and cached in memory
+#[derive(Clone)]
+pub struct AddressLabels {
+    pool: Pool<Any>,
+    cache: Arc<RwLock<HashMap<Felt, AddressLabel>>>,
+}
+
+impl AddressLabels {
+    /// Opens the store at `url` (PostgreSQL URL, `sqlite:` URL or SQLite file path) and
+    /// loads every label in memory.
+    pub async fn open(url: &str) -> Result<Self> {
+        sqlx::any::install_default_drivers();
+        let url = if url.starts_with("postgres://")
+            || url.starts_with("postgresql://")
+            || url.starts_with("sqlite:")
+        {
+            url.to_string()
+        } else {
+            format!("sqlite://{url}?mode=rwc")
+        };
+        let max_connections = if url.contains(":memory:") { 1 } else { 4 };
+        let pool = AnyPoolOptions::new()
+            .max_connections(max_connections)
+            .connect(&url)
+            .await
+            .context("Failed to connect to the address labels database")?;
+        sqlx::query(CREATE_TABLE).execute(&pool).await?;
+
+        let rows = sqlx::query("SELECT address, name, tags FROM address_labels")
+            .fetch_all(&pool)
+            .await?;
+        let mut cache = HashMap::with_capacity(rows.len());
+        for row in &rows {
+            let label = label_from_row(row)?;
+            cache.insert(label.address, label);
+        }
+        tracing::info!(
+            target: "torii_common::labels",
+            labels = cache.len(),
+            "Address labels loaded"
+        );
+
+        Ok(Self {
+            pool,
+            cache: Arc::new(RwLock::new(cache)),
+        })
+    }
+
+    /// Inserts labels, replacing the name and tags of already labelled addresses.
+    pub async fn upsert(&self, labels: Vec<AddressLabel>) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        for label in &labels {
+            sqlx::query(UPSERT)
+                .bind(format!("{:#x}", label.address))
+                .bind(label.name.as_str())
+                .bind(serde_json::to_string(&label.tags)?)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+
+        let mut cache = self.cache.write().unwrap();
+        for label in labels {
+            cache.insert(label.address, label);
+        }
+        Ok(())
+    }
+
+    /// Removes the labels of `addresses`, returning how many were labelled.
+    pub async fn remove(&self, addresses: &[Felt]) -> Result<usize> {
+        let mut tx = self.pool.begin().await?;
+        for address in addresses {
+            sqlx::query("DELETE FROM address_labels WHERE address = $1")
+                .bind(format!("{address:#x}"))
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+
+        let mut cache = self.cache.write().unwrap();
+        Ok(addresses
+            .iter()
+            .filter(|address| cache.remove(address).is_some())
+            .count())
+    }
+
+    /// Label of `address`, if any
+    pub fn get(&self, address: Felt) -> Option<AddressLabel> {
+        self.cache.read().unwrap().get(&address).cloned()
+    }
+
+    /// Labels of the labelled `addresses`, each once, in order of first occurrence.
+    pub fn lookup(&self, addresses: impl IntoIterator<Item = Felt>) -> Vec<AddressLabel> {
+        let cache = self.cache.read().unwrap();
+        let mut seen = HashSet::new();
+        addresses
+            .into_iter()
+            .filter(|address| seen.insert(*address))
+            .filter_map(|address| cache.get(&address).cloned())
+            .collect()
+    }
+
+    /// All labels, sorted by address.
+    pub fn list(&self) -> Vec<AddressLabel> {
+        let mut labels: Vec<AddressLabel> = self.cache.read().unwrap().values().cloned().collect();
+        labels.sort_by_key(|label| label.address);
+        labels
+    }
+
+    /// Number of labelled addresses
+    pub fn len(&self) -> usize {
+        self.cache.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+fn label_from_row(row: &AnyRow) -> Result<AddressLabel> {
+    let address: String = row.try_get("address")?;
+    let tags: String = row.try_get("tags")?;
+    Ok(AddressLabel {
+        address: Felt::from_hex(&address)
+            .with_context(|| format!("Invalid labelled address {address}"))?,
+        name: row.try_get("name")?,
+        tags: serde_json::from_str(&tags)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn upsert_lookup_and_remove() {
+        let labels = AddressLabels::open("sqlite::memory:").await.unwrap();
+        let exchange = Felt::from(0x10_u64);
+        let spam = Felt::from(0x20_u64);
+
+        labels
+            .upsert(vec![
+                AddressLabel::new(exchange, "Exchange").with_tags(vec!["cex".to_string()]),
+                AddressLabel::new(spam, "Airdrop"),
+            ])
+            .await
+            .unwrap();
+        labels
+            .upsert(vec![
+                AddressLabel::new(spam, "Fake airdrop").with_tags(vec!["spam".to_string()])
+            ])
+            .await
+            .unwrap();
+
+        assert_eq!(labels.len(), 2);
+        assert_eq!(labels.get(spam).unwrap().name, "Fake airdrop");
+        let found = labels.lookup([spam, Felt::from(0x30_u64), exchange, spam]);
+        assert_eq!(
+            found.iter().map(|label| label.address).collect::<Vec<_>>(),
+            vec![spam, exchange]
+        );
+
+        let rows: Vec<(String, String)> =
+            sqlx::query_as("SELECT name, tags FROM address_labels ORDER BY address")
+                .fetch_all(&labels.pool)
+                .await
+                .unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                ("Exchange".to_string(), r#"["cex"]"#.to_string()),
+                ("Fake airdrop".to_string(), r#"["spam"]"#.to_string()),
+            ]
+        );
+
+        assert_eq!(
+            labels.remove(&[spam, Felt::from(0x30_u64)]).await.unwrap(),
+            1
+        );
+        assert_eq!(
+            labels.list(),
+            vec![AddressLabel::new(exchange, "Exchange").with_tags(vec!["cex".to_string()])]
+        );
+    }
+
+    #[test]
+    fn parses_label_files() {
+        let labels: Vec<AddressLabel> = serde_json::from_str(
+            r#"[{"address": "0x10", "name": "Exchange", "tags": ["cex"]}, {"address": "0x20", "name": "Bridge"}]"#,
+        )
+        .unwrap();
+        assert_eq!(
+            labels,
+            vec![
+                AddressLabel::new(Felt::from(0x10_u64), "Exchange")
+                    .with_tags(vec!["cex".to_string()]),
+                AddressLabel::new(Felt::from(0x20_u64), "Bridge"),
+            ]
+        );
+    }
+}
@@ -1,21 +1,49 @@
 //! Common utilities for Torii token indexers
 //!
 //! Provides efficient conversions between Starknet types and storage/wire formats,
-//! and shared helpers like token metadata fetching.
-
+//! and shared helpers like token metadata fetching, RPC rate limiting, history exports,
+//! historical transfer replay, address labels, query field masks, object storage for
+//! cached token assets, a shared token metadata cache, SQLite maintenance scheduling
+//! and SQLite encryption at rest.
+
+pub mod encryption;
+pub mod export;
+pub mod field_mask;
 pub mod json;
+pub mod labels;
+pub mod maintenance;
 pub mod metadata;
+pub mod metadata_cache;
+pub mod object_store;
+pub mod replay;
+pub mod rpc;
+pub mod sharding;
 pub mod sql;
 pub mod token_uri;
 pub mod utils;
+pub mod watchlist;
 
 use starknet::core::types::{Felt, U256};
 
+pub use encryption::apply_sqlite_encryption;
+pub use export::{ExportFormat, ExportRecord};
+pub use field_mask::FieldMask;
+pub use labels::{AddressLabel, AddressLabels};
+pub use maintenance::{MaintenanceSchedule, SQLITE_MAINTENANCE_SQL};
 pub use metadata::{MetadataFetcher, TokenMetadata};
+pub use metadata_cache::{CacheLookup, MetadataCache, MetadataCacheConfig};
+pub use object_store::{
+    ObjectStore, ObjectStoreConfig, ObjectStoreProvider, TokenAssetUrls, TokenAssets,
+};
+pub use replay::{replay, ReplayParam, ReplayRange, ReplayRow, ReplaySource};
+pub use rpc::{rate_limited_provider, RateLimitedTransport, RpcProvider, RpcRateLimiter};
+pub use sharding::{merge_pages, shard_index, shard_url, split_page, StorageShards};
 pub use token_uri::{
-    process_token_uri_request, TokenStandard, TokenUriRequest, TokenUriResult, TokenUriSender,
-    TokenUriService, TokenUriStore,
+    ipfs_gateway_urls, process_token_uri_request, substitute_token_id, ImageCache,
+    NormalizedMetadata, TokenStandard, TokenUriRequest, TokenUriResult, TokenUriSender,
+    TokenUriService, TokenUriStore, IPFS_GATEWAYS,
 };
+pub use watchlist::{AddressWatcher, AddressWatchlist};
 
 // ===== Felt conversions =====
 
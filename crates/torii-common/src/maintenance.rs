@@ -0,0 +1,84 @@
+//! Scheduled SQLite maintenance
+//!
+//! Long-running SQLite storages degrade as tables grow and churn: query plans rely on
+//! stale statistics and freed pages are never returned to the file system. Storages run
+//! [`SQLITE_MAINTENANCE_SQL`] from their sink's idle hook (no batch pending), at most
+//! once per interval, as tracked by [`MaintenanceSchedule`].
+//!
+//! `incremental_vacuum` only reclaims pages in databases created with
+//! `auto_vacuum = INCREMENTAL` (set by the storages when creating a database);
+//! existing databases keep their mode until a full `VACUUM`.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Maintenance statements: reclaim up to 10 000 free pages, refresh the planner
+/// statistics (sampled, so that large tables stay cheap to analyze) and let SQLite apply
+/// its own recommended optimizations.
+pub const SQLITE_MAINTENANCE_SQL: &str = "PRAGMA incremental_vacuum(10000);
+     PRAGMA analysis_limit=1000;
+     ANALYZE;
+     PRAGMA optimize;";
+
+/// Rate-limits maintenance runs to one per interval (`None` disables them).
+#[derive(Debug)]
+pub struct MaintenanceSchedule {
+    interval: Option<Duration>,
+    last_run: Mutex<Instant>,
+}
+
+impl Default for MaintenanceSchedule {
+    fn default() -> Self {
+        Self::disabled()
+    }
+}
+
+impl MaintenanceSchedule {
+    /// Schedule running at most once per `interval`, the first time one interval after
+    /// its creation (restarts do not trigger a run).
+    pub fn new(interval: Option<Duration>) -> Self {
+        Self {
+            interval,
+            last_run: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Schedule never running maintenance.
+    pub fn disabled() -> Self {
+        Self::new(None)
+    }
+
+    pub fn interval(&self) -> Option<Duration> {
+        self.interval
+    }
+
+    /// Whether maintenance is due; if so, records a run starting now.
+    pub fn try_start(&self) -> bool {
+        let Some(interval) = self.interval else {
+            return false;
+        };
+        let mut last_run = self.last_run.lock().unwrap();
+        if last_run.elapsed() < interval {
+            return false;
+        }
+        *last_run = Instant::now();
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runs_once_per_interval() {
+        let schedule = MaintenanceSchedule::new(Some(Duration::from_secs(3600)));
+        assert!(!schedule.try_start());
+
+        let schedule = MaintenanceSchedule::new(Some(Duration::ZERO));
+        assert!(schedule.try_start());
+        assert!(schedule.try_start());
+
+        assert!(!MaintenanceSchedule::disabled().try_start());
+    }
+}
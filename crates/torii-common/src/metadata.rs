@@ -3,15 +3,23 @@
 //! Fetches `name()`, `symbol()`, `decimals()`, and `token_uri(token_id)` by
 //! making `starknet_call` requests. Handles both snake_case and camelCase
 //! selectors, felt-encoded strings and ByteArray returns.
-
+//!
+//! With [`MetadataFetcher::with_cache`], contract metadata is served from a shared
+//! [`MetadataCache`] and empty results are not fetched again until their backoff
+//! expires.
+
+use crate::metadata_cache::{CacheLookup, MetadataCache};
+use crate::rpc::RpcProvider;
+use crate::{blob_to_u256, u256_to_blob};
+use serde::{Deserialize, Serialize};
 use starknet::core::codec::Decode;
 use starknet::core::types::{
     requests::CallRequest, BlockId, BlockTag, ByteArray, Felt, FunctionCall, U256,
 };
 use starknet::core::utils::parse_cairo_short_string;
 use starknet::macros::selector;
-use starknet::providers::jsonrpc::{HttpTransport, JsonRpcClient};
 use starknet::providers::{Provider, ProviderRequestData, ProviderResponseData};
+use std::future::Future;
 use std::sync::Arc;
 
 /// Token metadata (common fields for all ERC standards)
@@ -27,18 +35,151 @@ pub struct TokenMetadata {
     pub total_supply: Option<U256>,
 }
 
+impl TokenMetadata {
+    /// Whether no field could be fetched (not a token, or a failing contract).
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none()
+            && self.symbol.is_none()
+            && self.decimals.is_none()
+            && self.total_supply.is_none()
+    }
+}
+
+/// [`TokenMetadata`] as stored in the [`MetadataCache`] (total supply as hex blob).
+#[derive(Serialize, Deserialize)]
+struct CachedTokenMetadata {
+    name: Option<String>,
+    symbol: Option<String>,
+    decimals: Option<u8>,
+    total_supply: Option<String>,
+}
+
+impl From<&TokenMetadata> for CachedTokenMetadata {
+    fn from(meta: &TokenMetadata) -> Self {
+        Self {
+            name: meta.name.clone(),
+            symbol: meta.symbol.clone(),
+            decimals: meta.decimals,
+            total_supply: meta
+                .total_supply
+                .map(|supply| hex::encode(u256_to_blob(supply))),
+        }
+    }
+}
+
+impl From<CachedTokenMetadata> for TokenMetadata {
+    fn from(cached: CachedTokenMetadata) -> Self {
+        Self {
+            name: cached.name,
+            symbol: cached.symbol,
+            decimals: cached.decimals,
+            total_supply: cached
+                .total_supply
+                .and_then(|supply| hex::decode(supply).ok())
+                .map(|bytes| blob_to_u256(&bytes)),
+        }
+    }
+}
+
 /// Fetches token metadata from on-chain contracts via RPC calls.
 pub struct MetadataFetcher {
-    provider: Arc<JsonRpcClient<HttpTransport>>,
+    provider: Arc<RpcProvider>,
+    cache: Option<Arc<MetadataCache>>,
 }
 
 impl MetadataFetcher {
-    pub fn new(provider: Arc<JsonRpcClient<HttpTransport>>) -> Self {
-        Self { provider }
+    pub fn new(provider: Arc<RpcProvider>) -> Self {
+        Self {
+            provider,
+            cache: None,
+        }
+    }
+
+    /// Serve contract metadata and token URI documents from `cache`.
+    #[must_use]
+    pub fn with_cache(mut self, cache: Arc<MetadataCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// The metadata cache, if any (shared with the token URI resolution).
+    pub fn cache(&self) -> Option<Arc<MetadataCache>> {
+        self.cache.clone()
     }
 
     /// Fetch metadata for an ERC20 token (name, symbol, decimals).
     pub async fn fetch_erc20_metadata(&self, contract: Felt) -> TokenMetadata {
+        self.cached_metadata(
+            "erc20",
+            contract,
+            self.fetch_erc20_metadata_uncached(contract),
+        )
+        .await
+    }
+
+    /// Fetch metadata for an ERC721 contract (name, symbol, totalSupply).
+    pub async fn fetch_erc721_metadata(&self, contract: Felt) -> TokenMetadata {
+        self.cached_metadata(
+            "erc721",
+            contract,
+            self.fetch_erc721_metadata_uncached(contract),
+        )
+        .await
+    }
+
+    /// Fetch metadata for an ERC1155 contract (name, symbol, totalSupply if available).
+    pub async fn fetch_erc1155_metadata(&self, contract: Felt) -> TokenMetadata {
+        self.cached_metadata(
+            "erc1155",
+            contract,
+            self.fetch_erc1155_metadata_uncached(contract),
+        )
+        .await
+    }
+
+    /// Look the metadata of `contract` up in the cache, running `fetch` on a miss.
+    ///
+    /// Contracts whose last fetch came back empty get empty metadata until their
+    /// backoff expires.
+    async fn cached_metadata(
+        &self,
+        standard: &str,
+        contract: Felt,
+        fetch: impl Future<Output = TokenMetadata>,
+    ) -> TokenMetadata {
+        let Some(cache) = &self.cache else {
+            return fetch.await;
+        };
+
+        let key = format!("{standard}:{contract:#x}");
+        match cache.get(&key) {
+            CacheLookup::Hit(value) => {
+                if let Ok(cached) = serde_json::from_str::<CachedTokenMetadata>(&value) {
+                    return cached.into();
+                }
+            }
+            CacheLookup::Negative => return TokenMetadata::default(),
+            CacheLookup::Miss => {}
+        }
+
+        let meta = fetch.await;
+        let value = if meta.is_empty() {
+            None
+        } else {
+            serde_json::to_string(&CachedTokenMetadata::from(&meta)).ok()
+        };
+        if let Err(error) = cache.insert(&key, value).await {
+            tracing::warn!(
+                target: "torii_common::metadata",
+                contract = %format!("{contract:#x}"),
+                %error,
+                "Failed to cache token metadata"
+            );
+        }
+        meta
+    }
+
+    async fn fetch_erc20_metadata_uncached(&self, contract: Felt) -> TokenMetadata {
         let name = self.fetch_string(contract, "name").await;
         let symbol = self.fetch_string(contract, "symbol").await;
         let decimals = self.fetch_decimals(contract).await;
@@ -52,8 +193,7 @@ impl MetadataFetcher {
         }
     }
 
-    /// Fetch metadata for an ERC721 contract (name, symbol, totalSupply).
-    pub async fn fetch_erc721_metadata(&self, contract: Felt) -> TokenMetadata {
+    async fn fetch_erc721_metadata_uncached(&self, contract: Felt) -> TokenMetadata {
         let name = self.fetch_string(contract, "name").await;
         let symbol = self.fetch_string(contract, "symbol").await;
         let total_supply = self.fetch_total_supply(contract).await;
@@ -66,8 +206,7 @@ impl MetadataFetcher {
         }
     }
 
-    /// Fetch metadata for an ERC1155 contract (name, symbol, totalSupply if available).
-    pub async fn fetch_erc1155_metadata(&self, contract: Felt) -> TokenMetadata {
+    async fn fetch_erc1155_metadata_uncached(&self, contract: Felt) -> TokenMetadata {
         let name = self.fetch_string(contract, "name").await;
         let symbol = self.fetch_string(contract, "symbol").await;
         let total_supply = self.fetch_total_supply(contract).await;
@@ -333,7 +472,9 @@ impl MetadataFetcher {
         // Fallback: try as legacy array [len, felt1, felt2, ...]
         if result.len() >= 2 {
             let array_len: u64 = result[0].try_into().unwrap_or(0);
-            if array_len > 0 && array_len < 100 && result.len() >= (array_len as usize + 1) {
+            // On-chain metadata (e.g. base64 JSON data URIs) spans hundreds of chunks,
+            // so the length is only bounded by the response.
+            if array_len > 0 && result.len() > array_len as usize {
                 let mut s = String::with_capacity(array_len as usize * 31);
                 for felt in &result[1..=array_len as usize] {
                     if let Ok(chunk) = parse_cairo_short_string(felt) {
@@ -462,6 +603,17 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_decode_long_legacy_array() {
+        // 150 chunks of "abc" (more than any short metadata string)
+        let mut result = vec![Felt::from(150u64)];
+        result.extend(std::iter::repeat_n(Felt::from(0x616263u64), 150));
+        assert_eq!(
+            MetadataFetcher::decode_string_result(&result),
+            Some("abc".repeat(150))
+        );
+    }
+
     #[test]
     fn test_decode_empty() {
         assert_eq!(MetadataFetcher::decode_string_result(&[]), None);
@@ -0,0 +1,396 @@
+//! Shared cache of token metadata lookups.
+//!
+//! [`MetadataCache`] remembers the outcome of metadata fetches (contract
+//! `name`/`symbol`/`decimals`, token URI documents) so sinks sharing a
+//! [`MetadataFetcher`](crate::MetadataFetcher) do not fetch them again on every miss.
+//! Entries live in a `metadata_cache` table (SQLite or PostgreSQL) mirrored by an
+//! in-memory LRU of bounded capacity; the least recently used entries are evicted
+//! from both.
+//!
+//! Successful lookups expire after [`MetadataCacheConfig::ttl`]. Failed lookups
+//! (empty contract metadata, broken URIs) are cached too, for
+//! [`MetadataCacheConfig::negative_ttl`] doubled on every consecutive failure up to
+//! [`MetadataCacheConfig::max_negative_ttl`], so broken sources are retried with
+//! backoff instead of on every request.
+
+use anyhow::{Context, Result};
+use sqlx::any::AnyPoolOptions;
+use sqlx::{Any, Pool, Row};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::Duration;
+
+const CREATE_TABLE: &str = "CREATE TABLE IF NOT EXISTS metadata_cache (
+    key TEXT PRIMARY KEY,
+    value TEXT,
+    failures BIGINT NOT NULL,
+    expires_at BIGINT NOT NULL,
+    updated_at BIGINT NOT NULL
+)";
+
+const UPSERT: &str = "INSERT INTO metadata_cache (key, value, failures, expires_at, updated_at)
+    VALUES ($1, $2, $3, $4, $5)
+    ON CONFLICT (key) DO UPDATE SET value = excluded.value, failures = excluded.failures,
+        expires_at = excluded.expires_at, updated_at = excluded.updated_at";
+
+/// Metadata cache settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MetadataCacheConfig {
+    /// Entries kept in memory and on disk
+    pub capacity: usize,
+    /// Lifetime of successful lookups
+    pub ttl: Duration,
+    /// Lifetime of a first failed lookup, doubled on each consecutive failure
+    pub negative_ttl: Duration,
+    /// Upper bound of the failed lookup lifetime
+    pub max_negative_ttl: Duration,
+}
+
+impl Default for MetadataCacheConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 100_000,
+            ttl: Duration::from_secs(24 * 60 * 60),
+            negative_ttl: Duration::from_secs(60),
+            max_negative_ttl: Duration::from_secs(6 * 60 * 60),
+        }
+    }
+}
+
+impl MetadataCacheConfig {
+    #[must_use]
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    #[must_use]
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    #[must_use]
+    pub fn with_negative_ttl(mut self, negative_ttl: Duration, max_negative_ttl: Duration) -> Self {
+        self.negative_ttl = negative_ttl;
+        self.max_negative_ttl = max_negative_ttl;
+        self
+    }
+
+    /// Lifetime of a failed lookup after `failures` consecutive failures.
+    pub fn negative_ttl_for(&self, failures: u32) -> Duration {
+        let factor = 1u32 << failures.saturating_sub(1).min(16);
+        self.negative_ttl
+            .saturating_mul(factor)
+            .min(self.max_negative_ttl.max(self.negative_ttl))
+    }
+}
+
+/// Outcome of a cache lookup
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CacheLookup {
+    /// A successful lookup that has not expired
+    Hit(String),
+    /// A failed lookup still backing off: do not fetch again yet
+    Negative,
+    /// Nothing usable cached: fetch
+    Miss,
+}
+
+#[derive(Debug, Clone)]
+struct CacheEntry {
+    value: Option<String>,
+    failures: u32,
+    /// Unix milliseconds
+    expires_at: i64,
+    /// LRU position
+    tick: u64,
+}
+
+#[derive(Default)]
+struct CacheState {
+    entries: HashMap<String, CacheEntry>,
+    /// `tick -> key`, least recently used first
+    order: BTreeMap<u64, String>,
+    next_tick: u64,
+}
+
+impl CacheState {
+    fn touch(&mut self, key: &str) {
+        let tick = self.next_tick;
+        if let Some(entry) = self.entries.get_mut(key) {
+            self.order.remove(&entry.tick);
+            entry.tick = tick;
+            self.order.insert(tick, key.to_string());
+            self.next_tick += 1;
+        }
+    }
+
+    fn insert(&mut self, key: String, mut entry: CacheEntry) {
+        entry.tick = self.next_tick;
+        self.next_tick += 1;
+        self.order.insert(entry.tick, key.clone());
+        if let Some(previous) = self.entries.insert(key, entry) {
+            self.order.remove(&previous.tick);
+        }
+    }
+
+    /// Drops least recently used entries above `capacity`, returning their keys.
+    fn evict(&mut self, capacity: usize) -> Vec<String> {
+        let mut evicted = Vec::new();
+        while self.entries.len() > capacity.max(1) {
+            let Some((_, key)) = self.order.pop_first() else {
+                break;
+            };
+            self.entries.remove(&key);
+            evicted.push(key);
+        }
+        evicted
+    }
+}
+
+/// Metadata lookups persisted in a database and cached in memory
+pub struct MetadataCache {
+    pool: Pool<Any>,
+    config: MetadataCacheConfig,
+    state: Mutex<CacheState>,
+}
+
+impl MetadataCache {
+    /// Opens the cache at `url` (PostgreSQL URL, `sqlite:` URL or SQLite file path),
+    /// drops expired entries and loads the most recent ones in memory.
+    pub async fn open(url: &str, config: MetadataCacheConfig) -> Result<Self> {
+        sqlx::any::install_default_drivers();
+        let url = if url.starts_with("postgres://")
+            || url.starts_with("postgresql://")
+            || url.starts_with("sqlite:")
+        {
+            url.to_string()
+        } else {
+            format!("sqlite://{url}?mode=rwc")
+        };
+        let max_connections = if url.contains(":memory:") { 1 } else { 4 };
+        let pool = AnyPoolOptions::new()
+            .max_connections(max_connections)
+            .connect(&url)
+            .await
+            .context("Failed to connect to the metadata cache database")?;
+        sqlx::query(CREATE_TABLE).execute(&pool).await?;
+
+        let now = chrono::Utc::now().timestamp_millis();
+        sqlx::query("DELETE FROM metadata_cache WHERE expires_at <= $1")
+            .bind(now)
+            .execute(&pool)
+            .await?;
+
+        let capacity = i64::try_from(config.capacity.max(1)).unwrap_or(i64::MAX);
+        sqlx::query(
+            "DELETE FROM metadata_cache WHERE key NOT IN (
+                SELECT key FROM metadata_cache ORDER BY updated_at DESC LIMIT $1
+            )",
+        )
+        .bind(capacity)
+        .execute(&pool)
+        .await?;
+
+        let rows = sqlx::query(
+            "SELECT key, value, failures, expires_at FROM metadata_cache ORDER BY updated_at DESC",
+        )
+        .fetch_all(&pool)
+        .await?;
+        let mut state = CacheState::default();
+        // Oldest first, so the most recent entries end up last in the LRU order.
+        for row in rows.iter().rev() {
+            let failures: i64 = row.try_get("failures")?;
+            state.insert(
+                row.try_get("key")?,
+                CacheEntry {
+                    value: row.try_get("value")?,
+                    failures: u32::try_from(failures).unwrap_or(u32::MAX),
+                    expires_at: row.try_get("expires_at")?,
+                    tick: 0,
+                },
+            );
+        }
+        tracing::info!(
+            target: "torii_common::metadata_cache",
+            entries = state.entries.len(),
+            "Metadata cache loaded"
+        );
+
+        Ok(Self {
+            pool,
+            config,
+            state: Mutex::new(state),
+        })
+    }
+
+    pub fn config(&self) -> &MetadataCacheConfig {
+        &self.config
+    }
+
+    /// Looks `key` up, marking it recently used.
+    pub fn get(&self, key: &str) -> CacheLookup {
+        let now = chrono::Utc::now().timestamp_millis();
+        let mut state = self.state.lock().unwrap();
+        let lookup = match state.entries.get(key) {
+            Some(entry) if entry.expires_at > now => match &entry.value {
+                Some(value) => CacheLookup::Hit(value.clone()),
+                None => CacheLookup::Negative,
+            },
+            _ => CacheLookup::Miss,
+        };
+        if lookup != CacheLookup::Miss {
+            state.touch(key);
+        }
+        drop(state);
+
+        let result = match lookup {
+            CacheLookup::Hit(_) => "hit",
+            CacheLookup::Negative => "negative",
+            CacheLookup::Miss => "miss",
+        };
+        ::metrics::counter!("torii_metadata_cache_lookups_total", "result" => result).increment(1);
+        lookup
+    }
+
+    /// Records the outcome of a lookup: `Some` for a success, `None` for a failure.
+    ///
+    /// Consecutive failures of the same key back off exponentially; a success resets
+    /// the failure count.
+    pub async fn insert(&self, key: &str, value: Option<String>) -> Result<()> {
+        let now = chrono::Utc::now().timestamp_millis();
+        let (entry, evicted) = {
+            let mut state = self.state.lock().unwrap();
+            let failures = match value {
+                Some(_) => 0,
+                None => state
+                    .entries
+                    .get(key)
+                    .map_or(0, |entry| entry.failures)
+                    .saturating_add(1),
+            };
+            let ttl = if value.is_some() {
+                self.config.ttl
+            } else {
+                self.config.negative_ttl_for(failures)
+            };
+            let entry = CacheEntry {
+                value,
+                failures,
+                expires_at: now.saturating_add(i64::try_from(ttl.as_millis()).unwrap_or(i64::MAX)),
+                tick: 0,
+            };
+            state.insert(key.to_string(), entry.clone());
+            (entry, state.evict(self.config.capacity))
+        };
+
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(UPSERT)
+            .bind(key)
+            .bind(entry.value)
+            .bind(i64::from(entry.failures))
+            .bind(entry.expires_at)
+            .bind(now)
+            .execute(&mut *tx)
+            .await?;
+        for key in &evicted {
+            sqlx::query("DELETE FROM metadata_cache WHERE key = $1")
+                .bind(key.as_str())
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+
+        if !evicted.is_empty() {
+            ::metrics::counter!("torii_metadata_cache_evictions_total")
+                .increment(evicted.len() as u64);
+        }
+        Ok(())
+    }
+
+    /// Number of cached entries (expired ones included until evicted or replaced)
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn negative_ttl_backs_off_up_to_the_cap() {
+        let config = MetadataCacheConfig::default()
+            .with_negative_ttl(Duration::from_secs(10), Duration::from_secs(60));
+        assert_eq!(config.negative_ttl_for(1), Duration::from_secs(10));
+        assert_eq!(config.negative_ttl_for(2), Duration::from_secs(20));
+        assert_eq!(config.negative_ttl_for(3), Duration::from_secs(40));
+        assert_eq!(config.negative_ttl_for(4), Duration::from_secs(60));
+        assert_eq!(config.negative_ttl_for(40), Duration::from_secs(60));
+    }
+
+    #[tokio::test]
+    async fn caches_hits_and_failures_and_evicts_lru() {
+        let cache = MetadataCache::open(
+            "sqlite::memory:",
+            MetadataCacheConfig::default().with_capacity(2),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(cache.get("erc20:0x1"), CacheLookup::Miss);
+        cache
+            .insert("erc20:0x1", Some("{\"name\":\"Ether\"}".to_string()))
+            .await
+            .unwrap();
+        cache.insert("uri:https://broken", None).await.unwrap();
+        assert_eq!(
+            cache.get("erc20:0x1"),
+            CacheLookup::Hit("{\"name\":\"Ether\"}".to_string())
+        );
+        assert_eq!(cache.get("uri:https://broken"), CacheLookup::Negative);
+
+        // A second failure keeps backing off.
+        cache.insert("uri:https://broken", None).await.unwrap();
+        let failures: i64 =
+            sqlx::query_scalar("SELECT failures FROM metadata_cache WHERE key = $1")
+                .bind("uri:https://broken")
+                .fetch_one(&cache.pool)
+                .await
+                .unwrap();
+        assert_eq!(failures, 2);
+
+        // erc20:0x1 is the least recently used entry.
+        cache
+            .insert("erc721:0x2", Some("{}".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get("erc20:0x1"), CacheLookup::Miss);
+        let rows: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM metadata_cache")
+            .fetch_one(&cache.pool)
+            .await
+            .unwrap();
+        assert_eq!(rows, 2);
+    }
+
+    #[tokio::test]
+    async fn expired_entries_are_misses() {
+        let cache = MetadataCache::open(
+            "sqlite::memory:",
+            MetadataCacheConfig::default().with_ttl(Duration::ZERO),
+        )
+        .await
+        .unwrap();
+        cache
+            .insert("erc20:0x1", Some("{}".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(cache.get("erc20:0x1"), CacheLookup::Miss);
+    }
+}
@@ -0,0 +1,278 @@
+//! Historical transfer replay shared by the token services.
+//!
+//! `ReplayTransfers` streams the stored transfers of a block range, oldest row first.
+//! Storages page through their transfers table by row id ([`ReplayRange::page_clause`]),
+//! and [`replay`] turns those pages into a lazy stream: the next page is only read once
+//! the previous one has been consumed, so a slow client throttles the query.
+
+use anyhow::{ensure, Result};
+use async_trait::async_trait;
+use futures::stream::{self, Stream, TryStreamExt};
+use rusqlite::types::ToSqlOutput;
+use starknet::core::types::Felt;
+
+use crate::felt_to_blob;
+
+/// Page size used when a request leaves it unset
+pub const DEFAULT_REPLAY_PAGE_SIZE: u32 = 500;
+
+/// Largest page size a request may ask for
+pub const MAX_REPLAY_PAGE_SIZE: u32 = 5000;
+
+/// Transfers replayed by a `ReplayTransfers` request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayRange {
+    /// Token whitelist (empty = all tokens)
+    pub tokens: Vec<Felt>,
+    /// First block replayed (inclusive)
+    pub block_from: Option<u64>,
+    /// Last block replayed (inclusive)
+    pub block_to: Option<u64>,
+    /// Rows read per storage query
+    pub page_size: u32,
+}
+
+impl ReplayRange {
+    /// Validates the block range and bounds `page_size` (`0` = default).
+    pub fn new(
+        tokens: Vec<Felt>,
+        block_from: Option<u64>,
+        block_to: Option<u64>,
+        page_size: u32,
+    ) -> Result<Self> {
+        if let (Some(block_from), Some(block_to)) = (block_from, block_to) {
+            ensure!(
+                block_from <= block_to,
+                "block_from must not be greater than block_to"
+            );
+        }
+        let page_size = if page_size == 0 {
+            DEFAULT_REPLAY_PAGE_SIZE
+        } else {
+            page_size.min(MAX_REPLAY_PAGE_SIZE)
+        };
+        Ok(Self {
+            tokens,
+            block_from,
+            block_to,
+            page_size,
+        })
+    }
+
+    /// The same range restricted to `tokens` (e.g. the tokens of one shard).
+    pub fn with_tokens(&self, tokens: Vec<Felt>) -> Self {
+        Self {
+            tokens,
+            ..self.clone()
+        }
+    }
+
+    /// `WHERE`, `ORDER BY` and `LIMIT` clauses of the page after row `after_id`, for a
+    /// transfers table aliased `t` with `id`, `token` and `block_number` columns.
+    ///
+    /// `param` receives each parameter in order and returns its placeholder.
+    pub fn page_clause(
+        &self,
+        after_id: Option<i64>,
+        mut param: impl FnMut(ReplayParam) -> String,
+    ) -> String {
+        let mut clause = format!(
+            " WHERE t.id > {}",
+            param(ReplayParam::Integer(after_id.unwrap_or(0)))
+        );
+        if !self.tokens.is_empty() {
+            let placeholders: Vec<String> = self
+                .tokens
+                .iter()
+                .map(|token| param(ReplayParam::Blob(felt_to_blob(*token))))
+                .collect();
+            clause.push_str(&format!(" AND t.token IN ({})", placeholders.join(",")));
+        }
+        // Block numbers are stored as text: compare them as numbers.
+        if let Some(block_min) = self.block_from {
+            clause.push_str(" AND CAST(t.block_number AS BIGINT) >= ");
+            clause.push_str(&param(ReplayParam::Integer(block_min as i64)));
+        }
+        if let Some(block_max) = self.block_to {
+            clause.push_str(" AND CAST(t.block_number AS BIGINT) <= ");
+            clause.push_str(&param(ReplayParam::Integer(block_max as i64)));
+        }
+        clause.push_str(" ORDER BY t.id ASC LIMIT ");
+        clause.push_str(&param(ReplayParam::Integer(i64::from(self.page_size))));
+        clause
+    }
+}
+
+/// Parameter of a [`ReplayRange::page_clause`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplayParam {
+    Integer(i64),
+    Blob(Vec<u8>),
+}
+
+impl rusqlite::ToSql for ReplayParam {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        match self {
+            Self::Integer(value) => value.to_sql(),
+            Self::Blob(value) => value.to_sql(),
+        }
+    }
+}
+
+/// Row of a replay, identified by its storage row id
+pub trait ReplayRow {
+    fn replay_id(&self) -> Option<i64>;
+}
+
+/// Storage paging through its transfers for [`replay`]
+#[async_trait]
+pub trait ReplaySource: Send + Sync + 'static {
+    type Row: ReplayRow + Send + 'static;
+
+    /// Up to `range.page_size` transfers of `range` after row `after_id`, in id order.
+    async fn replay_page(
+        &self,
+        range: &ReplayRange,
+        after_id: Option<i64>,
+    ) -> Result<Vec<Self::Row>>;
+}
+
+/// Lazily streams the transfers of `range` from `source`, one page at a time.
+pub fn replay<S: ReplaySource + Clone>(
+    source: S,
+    range: ReplayRange,
+) -> impl Stream<Item = Result<S::Row>> + Send {
+    // `None` once the last page has been read.
+    stream::try_unfold(Some(None), move |cursor: Option<Option<i64>>| {
+        let source = source.clone();
+        let range = range.clone();
+        async move {
+            let Some(after_id) = cursor else {
+                return Ok(None);
+            };
+            let page = source.replay_page(&range, after_id).await?;
+            let next = if page.len() < range.page_size as usize {
+                None
+            } else {
+                page.last().and_then(ReplayRow::replay_id).map(Some)
+            };
+            Ok(Some((stream::iter(page.into_iter().map(Ok)), next)))
+        }
+    })
+    .try_flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::Connection;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Debug, PartialEq)]
+    struct Row {
+        id: i64,
+        block_number: u64,
+    }
+
+    impl ReplayRow for Row {
+        fn replay_id(&self) -> Option<i64> {
+            Some(self.id)
+        }
+    }
+
+    #[derive(Clone)]
+    struct Table {
+        conn: Arc<Mutex<Connection>>,
+        queries: Arc<Mutex<usize>>,
+    }
+
+    #[async_trait]
+    impl ReplaySource for Table {
+        type Row = Row;
+
+        async fn replay_page(
+            &self,
+            range: &ReplayRange,
+            after_id: Option<i64>,
+        ) -> Result<Vec<Row>> {
+            *self.queries.lock().unwrap() += 1;
+            let conn = self.conn.lock().unwrap();
+            let mut params = Vec::new();
+            let query = format!(
+                "SELECT t.id, t.block_number FROM transfers t{}",
+                range.page_clause(after_id, |param| {
+                    params.push(param);
+                    "?".to_string()
+                })
+            );
+            let mut stmt = conn.prepare(&query)?;
+            let rows = stmt.query_map(rusqlite::params_from_iter(&params), |row| {
+                Ok(Row {
+                    id: row.get(0)?,
+                    block_number: row.get::<_, String>(1)?.parse().unwrap(),
+                })
+            })?;
+            Ok(rows.collect::<Result<_, _>>()?)
+        }
+    }
+
+    /// Transfers of blocks 1..=10, of token `0x1` on odd blocks and `0x2` on even ones.
+    fn table() -> Table {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE transfers (id INTEGER PRIMARY KEY, token BLOB, block_number TEXT)",
+        )
+        .unwrap();
+        for block in 1..=10u64 {
+            conn.execute(
+                "INSERT INTO transfers (token, block_number) VALUES (?, ?)",
+                rusqlite::params![felt_to_blob(Felt::from(2 - block % 2)), block.to_string()],
+            )
+            .unwrap();
+        }
+        Table {
+            conn: Arc::new(Mutex::new(conn)),
+            queries: Arc::default(),
+        }
+    }
+
+    async fn blocks(table: &Table, range: ReplayRange) -> Vec<u64> {
+        replay(table.clone(), range)
+            .map_ok(|row| row.block_number)
+            .try_collect()
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn range_is_validated_and_page_size_bounded() {
+        assert!(ReplayRange::new(Vec::new(), Some(5), Some(4), 0).is_err());
+        let range = ReplayRange::new(Vec::new(), Some(4), Some(4), 0).unwrap();
+        assert_eq!(range.page_size, DEFAULT_REPLAY_PAGE_SIZE);
+        let range = ReplayRange::new(Vec::new(), None, None, 100_000).unwrap();
+        assert_eq!(range.page_size, MAX_REPLAY_PAGE_SIZE);
+    }
+
+    #[tokio::test]
+    async fn replays_block_range_page_by_page() {
+        let table = table();
+        let range = ReplayRange::new(Vec::new(), Some(3), Some(10), 4).unwrap();
+        assert_eq!(blocks(&table, range).await, vec![3, 4, 5, 6, 7, 8, 9, 10]);
+        // Two full pages, then an empty one.
+        assert_eq!(*table.queries.lock().unwrap(), 3);
+
+        let range = ReplayRange::new(vec![Felt::from(1u64)], None, Some(6), 1).unwrap();
+        assert_eq!(blocks(&table, range).await, vec![1, 3, 5]);
+    }
+
+    #[tokio::test]
+    async fn empty_range_replays_nothing() {
+        let table = table();
+        let range = ReplayRange::new(Vec::new(), Some(11), None, 4).unwrap();
+        assert!(blocks(&table, range).await.is_empty());
+        assert_eq!(*table.queries.lock().unwrap(), 1);
+
+        let range = ReplayRange::new(vec![Felt::from(3u64)], None, None, 4).unwrap();
+        assert!(blocks(&table, range).await.is_empty());
+    }
+}
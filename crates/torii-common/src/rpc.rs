@@ -0,0 +1,204 @@
+//! Shared RPC rate limiting.
+//!
+//! The extractor, the contract registry, the balance fetchers and the metadata fetcher
+//! all call the same provider. Wrapping the provider's transport in a
+//! [`RateLimitedTransport`] makes every request draw from one shared token bucket, so
+//! indexing as a whole stays within the provider's request limits.
+
+use async_trait::async_trait;
+use serde::{de::DeserializeOwned, Serialize};
+use starknet::providers::jsonrpc::{
+    HttpTransport, HttpTransportError, JsonRpcClient, JsonRpcMethod, JsonRpcResponse,
+    JsonRpcTransport,
+};
+use starknet::providers::{ProviderRequestData, Url};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Starknet JSON-RPC provider with an optional shared rate limit.
+pub type RpcProvider = JsonRpcClient<RateLimitedTransport>;
+
+/// Builds a provider for `url` limited to `requests_per_second` with bursts of `burst`.
+///
+/// `requests_per_second == 0` disables the limit; `burst == 0` defaults to one
+/// second worth of requests. Clones of the provider share the limiter.
+pub fn rate_limited_provider(url: Url, requests_per_second: u32, burst: u32) -> RpcProvider {
+    let limiter = (requests_per_second > 0).then(|| {
+        let burst = if burst == 0 {
+            requests_per_second
+        } else {
+            burst
+        };
+        RpcRateLimiter::new(requests_per_second, burst)
+    });
+    JsonRpcClient::new(RateLimitedTransport::new(HttpTransport::new(url)).with_limiter(limiter))
+}
+
+#[derive(Debug)]
+struct Bucket {
+    /// Available requests; negative when callers are waiting on reserved requests
+    tokens: f64,
+    last_refill: Instant,
+}
+
+/// Token bucket shared by every component calling the same provider.
+///
+/// Refills at `requests_per_second` up to `burst` requests. Callers reserve their
+/// requests up front and wait for the deficit to refill, so concurrent callers are
+/// served in arrival order. Cheap to clone.
+#[derive(Debug, Clone)]
+pub struct RpcRateLimiter {
+    bucket: Arc<Mutex<Bucket>>,
+    requests_per_second: f64,
+    burst: f64,
+}
+
+impl RpcRateLimiter {
+    /// Creates a limiter allowing `requests_per_second` with bursts of up to `burst`
+    /// requests (both at least 1).
+    pub fn new(requests_per_second: u32, burst: u32) -> Self {
+        let burst = f64::from(burst.max(1));
+        Self {
+            bucket: Arc::new(Mutex::new(Bucket {
+                tokens: burst,
+                last_refill: Instant::now(),
+            })),
+            requests_per_second: f64::from(requests_per_second.max(1)),
+            burst,
+        }
+    }
+
+    /// Waits until `requests` requests may be sent.
+    pub async fn acquire(&self, requests: usize) {
+        let wait = self.reserve(requests as f64);
+        if !wait.is_zero() {
+            ::metrics::counter!("torii_rpc_rate_limited_total").increment(1);
+            ::metrics::histogram!("torii_rpc_rate_limit_wait_seconds").record(wait.as_secs_f64());
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Takes `requests` tokens and returns how long the caller must wait for them.
+    fn reserve(&self, requests: f64) -> Duration {
+        let mut bucket = self.bucket.lock().unwrap();
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = elapsed
+            .mul_add(self.requests_per_second, bucket.tokens)
+            .min(self.burst);
+        bucket.last_refill = now;
+        bucket.tokens -= requests;
+        if bucket.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-bucket.tokens / self.requests_per_second)
+        }
+    }
+}
+
+/// HTTP transport acquiring from an optional [`RpcRateLimiter`] before each request.
+///
+/// Batch requests count as one request per call in the batch.
+#[derive(Debug, Clone)]
+pub struct RateLimitedTransport {
+    inner: HttpTransport,
+    limiter: Option<RpcRateLimiter>,
+}
+
+impl RateLimitedTransport {
+    /// Wraps `inner` without a rate limit.
+    pub fn new(inner: HttpTransport) -> Self {
+        Self {
+            inner,
+            limiter: None,
+        }
+    }
+
+    /// Draws requests from `limiter` (None = unlimited).
+    pub fn with_limiter(mut self, limiter: Option<RpcRateLimiter>) -> Self {
+        self.limiter = limiter;
+        self
+    }
+
+    /// The shared limiter, if any
+    pub fn limiter(&self) -> Option<&RpcRateLimiter> {
+        self.limiter.as_ref()
+    }
+}
+
+impl From<HttpTransport> for RateLimitedTransport {
+    fn from(inner: HttpTransport) -> Self {
+        Self::new(inner)
+    }
+}
+
+#[async_trait]
+impl JsonRpcTransport for RateLimitedTransport {
+    type Error = HttpTransportError;
+
+    async fn send_request<P, R>(
+        &self,
+        method: JsonRpcMethod,
+        params: P,
+    ) -> Result<JsonRpcResponse<R>, Self::Error>
+    where
+        P: Serialize + Send + Sync,
+        R: DeserializeOwned + Send,
+    {
+        if let Some(limiter) = &self.limiter {
+            limiter.acquire(1).await;
+        }
+        self.inner.send_request(method, params).await
+    }
+
+    async fn send_requests<R>(
+        &self,
+        requests: R,
+    ) -> Result<Vec<JsonRpcResponse<serde_json::Value>>, Self::Error>
+    where
+        R: AsRef<[ProviderRequestData]> + Send + Sync,
+    {
+        if let Some(limiter) = &self.limiter {
+            limiter.acquire(requests.as_ref().len()).await;
+        }
+        self.inner.send_requests(requests).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn burst_is_available_immediately() {
+        let limiter = RpcRateLimiter::new(10, 3);
+        assert_eq!(limiter.reserve(1.0), Duration::ZERO);
+        assert_eq!(limiter.reserve(2.0), Duration::ZERO);
+
+        // Bucket is empty: the next request waits about 1/10s.
+        let wait = limiter.reserve(1.0);
+        assert!(wait > Duration::from_millis(90) && wait <= Duration::from_millis(100));
+    }
+
+    #[test]
+    fn reservations_queue_behind_each_other() {
+        let limiter = RpcRateLimiter::new(100, 1);
+        assert_eq!(limiter.reserve(1.0), Duration::ZERO);
+        let first = limiter.reserve(1.0);
+        let second = limiter.reserve(1.0);
+        assert!(second > first);
+
+        // A batch larger than the burst waits for the whole deficit.
+        let batch = limiter.reserve(50.0);
+        assert!(batch > Duration::from_millis(500));
+    }
+
+    #[tokio::test]
+    async fn acquire_waits_for_refill() {
+        let limiter = RpcRateLimiter::new(100, 1);
+        let start = Instant::now();
+        limiter.acquire(1).await;
+        limiter.acquire(1).await;
+        assert!(start.elapsed() >= Duration::from_millis(8));
+    }
+}
@@ -0,0 +1,414 @@
+//! Contract-hash sharding of token storage
+//!
+//! Large deployments can split a token database into several shards (SQLite files or
+//! PostgreSQL databases, see [`shard_url`]). Rows are routed by a hash of their token
+//! contract, so all the rows of a contract live in one shard: per-contract queries read
+//! a single shard and cross-contract queries fan out to every shard.
+//!
+//! Row ids are only unique within a shard. Paginated fan-out queries expose global ids
+//! (`local * shards + shard`), which keep the order of the rows of each shard and map
+//! back to shard-local cursors. With a single shard, global ids are the row ids.
+//! Queries ordered by block merge the pages of every shard ([`merge_pages`]); queries
+//! paginated by row id only read the shards one after the other
+//! ([`StorageShards::try_paginate`]).
+//!
+//! Changing the number of shards re-routes contracts: shards must then be re-indexed.
+
+use crate::token_uri::{TokenUriResult, TokenUriStore};
+use futures::future::try_join_all;
+use starknet::core::types::Felt;
+use std::future::Future;
+use std::sync::Arc;
+
+/// Shard (out of `count`) storing the rows of `contract`.
+pub fn shard_index(contract: Felt, count: usize) -> usize {
+    if count <= 1 {
+        return 0;
+    }
+    // FNV-1a: stable across releases and platforms, unlike `DefaultHasher`.
+    let hash = contract
+        .to_bytes_be()
+        .iter()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+    (hash % count as u64) as usize
+}
+
+/// Database URL of shard `index`, derived from the URL of the unsharded database.
+///
+/// Shard 0 keeps `url`. Other shards get a `.shard<index>` suffix before the SQLite file
+/// extension (`erc20.db` -> `erc20.shard1.db`), or a `_shard<index>` suffix on the
+/// PostgreSQL database name (`.../torii` -> `.../torii_shard1`), which must exist.
+pub fn shard_url(url: &str, index: usize) -> String {
+    if index == 0 || url == ":memory:" {
+        return url.to_string();
+    }
+    if url.starts_with("postgres://") || url.starts_with("postgresql://") {
+        let (base, query) = url
+            .split_once('?')
+            .map_or((url, None), |(base, query)| (base, Some(query)));
+        let (prefix, database) = base.rsplit_once('/').unwrap_or((base, ""));
+        let mut sharded = format!("{prefix}/{database}_shard{index}");
+        if let Some(query) = query {
+            sharded.push('?');
+            sharded.push_str(query);
+        }
+        return sharded;
+    }
+    let file_start = url.rfind('/').map_or(0, |slash| slash + 1);
+    match url[file_start..].rfind('.') {
+        Some(dot) if dot > 0 => {
+            let (stem, extension) = url.split_at(file_start + dot);
+            format!("{stem}.shard{index}{extension}")
+        }
+        _ => format!("{url}.shard{index}"),
+    }
+}
+
+/// Storages of a sharded token database, indexed by shard.
+pub struct StorageShards<S> {
+    shards: Arc<[Arc<S>]>,
+}
+
+impl<S> Clone for StorageShards<S> {
+    fn clone(&self) -> Self {
+        Self {
+            shards: self.shards.clone(),
+        }
+    }
+}
+
+impl<S> From<Arc<S>> for StorageShards<S> {
+    fn from(storage: Arc<S>) -> Self {
+        Self::single(storage)
+    }
+}
+
+impl<S> StorageShards<S> {
+    /// Creates shards from their storages, in shard order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shards` is empty.
+    pub fn new(shards: Vec<Arc<S>>) -> Self {
+        assert!(!shards.is_empty(), "at least one storage shard is required");
+        Self {
+            shards: shards.into(),
+        }
+    }
+
+    /// Unsharded storage.
+    pub fn single(storage: Arc<S>) -> Self {
+        Self::new(vec![storage])
+    }
+
+    /// Number of shards.
+    pub fn len(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Always false: there is at least one shard.
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// Whether the storage is split in several shards.
+    pub fn is_sharded(&self) -> bool {
+        self.shards.len() > 1
+    }
+
+    /// Storage of shard `index`.
+    pub fn get(&self, index: usize) -> &Arc<S> {
+        &self.shards[index]
+    }
+
+    /// Shard storing the rows of `contract`.
+    pub fn index_of(&self, contract: Felt) -> usize {
+        shard_index(contract, self.shards.len())
+    }
+
+    /// Storage of the shard storing the rows of `contract`.
+    pub fn for_contract(&self, contract: Felt) -> &Arc<S> {
+        &self.shards[self.index_of(contract)]
+    }
+
+    /// Storages, in shard order.
+    pub fn iter(&self) -> impl Iterator<Item = &Arc<S>> {
+        self.shards.iter()
+    }
+
+    /// Splits `items` by shard of their contract, keeping their order within a shard.
+    ///
+    /// Only shards with items are returned, in shard order.
+    pub fn partition<T>(
+        &self,
+        items: impl IntoIterator<Item = T>,
+        contract: impl Fn(&T) -> Felt,
+    ) -> Vec<(usize, Vec<T>)> {
+        let mut groups: Vec<Vec<T>> = (0..self.shards.len()).map(|_| Vec::new()).collect();
+        for item in items {
+            groups[self.index_of(contract(&item))].push(item);
+        }
+        groups
+            .into_iter()
+            .enumerate()
+            .filter(|(_, items)| !items.is_empty())
+            .collect()
+    }
+
+    /// Shards to query for a contract whitelist, with the contracts of each shard.
+    ///
+    /// An empty whitelist (all contracts) selects every shard with an empty whitelist.
+    pub fn select(&self, contracts: &[Felt]) -> Vec<(usize, Vec<Felt>)> {
+        if contracts.is_empty() {
+            return (0..self.shards.len())
+                .map(|index| (index, Vec::new()))
+                .collect();
+        }
+        self.partition(contracts.iter().copied(), |contract| *contract)
+    }
+
+    /// Runs `query` on every shard concurrently, returning the results in shard order.
+    pub async fn try_fan_out<'a, T, E, F, Fut>(&'a self, query: F) -> Result<Vec<T>, E>
+    where
+        F: Fn(usize, &'a S) -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        try_join_all(
+            self.shards
+                .iter()
+                .enumerate()
+                .map(|(index, storage)| query(index, storage.as_ref())),
+        )
+        .await
+    }
+
+    /// Pages through `shards` one after the other, with a global id cursor.
+    ///
+    /// `query(storage, cursor, limit)` reads a page of rows with a shard-local id above
+    /// `cursor` in ascending id order, returning the id of its last row when the page is
+    /// full. A cursor with local id 0 starts a shard from its first row.
+    pub async fn try_paginate<'a, T, E, F, Fut>(
+        &'a self,
+        shards: &[usize],
+        cursor: Option<i64>,
+        limit: u32,
+        query: F,
+    ) -> Result<(Vec<T>, Option<i64>), E>
+    where
+        F: Fn(&'a S, Option<i64>, u32) -> Fut,
+        Fut: Future<Output = Result<(Vec<T>, Option<i64>), E>>,
+    {
+        let (first_shard, mut local_cursor) = match cursor {
+            Some(cursor) => {
+                let (shard, local) = self.local_id(cursor);
+                (shard, Some(local))
+            }
+            None => (0, None),
+        };
+        let mut rows = Vec::new();
+        let mut remaining = shards.iter().copied().filter(|shard| *shard >= first_shard);
+        while let Some(shard) = remaining.next() {
+            let cursor = if shard == first_shard {
+                local_cursor.take()
+            } else {
+                None
+            };
+            let (page, next) =
+                query(self.get(shard).as_ref(), cursor, limit - rows.len() as u32).await?;
+            rows.extend(page);
+            if rows.len() as u32 >= limit {
+                let next = match next {
+                    Some(local) => Some(self.global_id(shard, local)),
+                    None => remaining.next().map(|shard| self.global_id(shard, 0)),
+                };
+                return Ok((rows, next));
+            }
+        }
+        Ok((rows, None))
+    }
+
+    /// Global id of the row `id` of shard `shard`.
+    pub fn global_id(&self, shard: usize, id: i64) -> i64 {
+        id * self.shards.len() as i64 + shard as i64
+    }
+
+    /// Shard and shard-local id of a global id.
+    pub fn local_id(&self, global_id: i64) -> (usize, i64) {
+        let count = self.shards.len() as i64;
+        (
+            global_id.rem_euclid(count) as usize,
+            global_id.div_euclid(count),
+        )
+    }
+
+    /// Splits global ids by shard, as shard-local ids.
+    pub fn local_ids(&self, global_ids: &[i64]) -> Vec<(usize, Vec<i64>)> {
+        let mut groups: Vec<Vec<i64>> = (0..self.shards.len()).map(|_| Vec::new()).collect();
+        for global_id in global_ids {
+            let (shard, id) = self.local_id(*global_id);
+            groups[shard].push(id);
+        }
+        groups
+            .into_iter()
+            .enumerate()
+            .filter(|(_, ids)| !ids.is_empty())
+            .collect()
+    }
+
+    /// Shard-local cursor selecting the rows of `shard` with a global id below `global_id`
+    /// (`id < cursor`, descending pagination).
+    pub fn local_cursor_before(&self, shard: usize, global_id: i64) -> i64 {
+        let count = self.shards.len() as i64;
+        (global_id - shard as i64 + count - 1).div_euclid(count)
+    }
+
+    /// Shard-local cursor selecting the rows of `shard` with a global id above `global_id`
+    /// (`id > cursor`, ascending pagination).
+    pub fn local_cursor_after(&self, shard: usize, global_id: i64) -> i64 {
+        (global_id - shard as i64).div_euclid(self.shards.len() as i64)
+    }
+}
+
+/// Merges the pages read from several shards into a page of `limit` rows ordered by `key`.
+///
+/// Each page must hold the first rows of its shard (in `key` order) after the cursor.
+/// Returns whether rows were left out.
+pub fn merge_pages<T, K: Ord>(
+    pages: impl IntoIterator<Item = Vec<T>>,
+    limit: usize,
+    key: impl FnMut(&T) -> K,
+) -> (Vec<T>, bool) {
+    let mut rows: Vec<T> = pages.into_iter().flatten().collect();
+    rows.sort_by_key(key);
+    let truncated = rows.len() > limit;
+    rows.truncate(limit);
+    (rows, truncated)
+}
+
+/// Splits rows fetched with `limit + 1` rows into a page of `limit` rows and, when the
+/// extra row was found, the cursor of the page's last row.
+pub fn split_page<T, C>(
+    mut rows: Vec<T>,
+    limit: usize,
+    cursor: impl FnOnce(&T) -> C,
+) -> (Vec<T>, Option<C>) {
+    if rows.len() <= limit {
+        return (rows, None);
+    }
+    rows.truncate(limit);
+    let next_cursor = rows.last().map(cursor);
+    (rows, next_cursor)
+}
+
+#[async_trait::async_trait]
+impl<S: TokenUriStore> TokenUriStore for StorageShards<S> {
+    async fn store_token_uris_batch(&self, results: &[TokenUriResult]) -> anyhow::Result<()> {
+        for (index, results) in self.partition(results.iter().cloned(), |result| result.contract) {
+            self.get(index).store_token_uris_batch(&results).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shard_url() {
+        assert_eq!(shard_url("./data/erc20.db", 0), "./data/erc20.db");
+        assert_eq!(shard_url("./data/erc20.db", 2), "./data/erc20.shard2.db");
+        assert_eq!(shard_url("./data.v1/erc20", 1), "./data.v1/erc20.shard1");
+        assert_eq!(
+            shard_url("postgres://u:p@localhost:5432/torii?sslmode=disable", 3),
+            "postgres://u:p@localhost:5432/torii_shard3?sslmode=disable"
+        );
+    }
+
+    #[test]
+    fn test_partition_is_stable_per_contract() {
+        let shards = StorageShards::new((0..4).map(Arc::new).collect());
+        let contracts: Vec<Felt> = (0..64_u64).map(Felt::from).collect();
+        let groups = shards.partition(contracts.iter().copied(), |contract| *contract);
+
+        assert!(groups.len() > 1);
+        assert_eq!(groups.iter().map(|(_, g)| g.len()).sum::<usize>(), 64);
+        for (index, group) in &groups {
+            assert!(group.iter().all(|c| shards.index_of(*c) == *index));
+            assert!(group.windows(2).all(|w| w[0] < w[1]));
+        }
+        assert_eq!(shard_index(Felt::from(7_u64), 1), 0);
+    }
+
+    #[tokio::test]
+    async fn test_try_paginate_across_shards() {
+        let shards = StorageShards::new(vec![Arc::new(vec![1, 2, 3]), Arc::new(vec![1, 2])]);
+        let query = |ids: &Vec<i64>, cursor: Option<i64>, limit: u32| {
+            let page: Vec<i64> = ids
+                .iter()
+                .copied()
+                .filter(|id| *id > cursor.unwrap_or(0))
+                .take(limit as usize)
+                .collect();
+            let next = (page.len() == limit as usize).then(|| *page.last().unwrap());
+            std::future::ready(Ok::<_, ()>((page, next)))
+        };
+
+        let mut cursor = None;
+        let mut pages = Vec::new();
+        loop {
+            let (page, next) = shards
+                .try_paginate(&[0, 1], cursor, 2, query)
+                .await
+                .unwrap();
+            pages.push(page);
+            cursor = next;
+            if cursor.is_none() {
+                break;
+            }
+        }
+        assert_eq!(pages.concat(), vec![1, 2, 3, 1, 2]);
+    }
+
+    #[test]
+    fn test_global_id_cursors() {
+        let shards = StorageShards::new((0..3).map(Arc::new).collect());
+        for shard in 0..3 {
+            for id in 1..10 {
+                let global = shards.global_id(shard, id);
+                assert_eq!(shards.local_id(global), (shard, id));
+                for cursor in 0..40 {
+                    assert_eq!(
+                        global < cursor,
+                        id < shards.local_cursor_before(shard, cursor)
+                    );
+                    assert_eq!(
+                        global > cursor,
+                        id > shards.local_cursor_after(shard, cursor)
+                    );
+                }
+            }
+        }
+
+        let (merged, truncated) = merge_pages(vec![vec![5, 1], vec![4, 2]], 3, |row| *row);
+        assert_eq!(merged, vec![1, 2, 4]);
+        assert!(truncated);
+
+        let single = StorageShards::single(Arc::new(()));
+        assert_eq!(single.global_id(0, 42), 42);
+        assert_eq!(single.local_cursor_before(0, 42), 42);
+        assert_eq!(single.local_cursor_after(0, 42), 42);
+    }
+
+    #[test]
+    fn test_split_page() {
+        let (page, next) = split_page(vec![1, 2, 3], 2, |row| *row);
+        assert_eq!((page, next), (vec![1, 2], Some(2)));
+        let (page, next) = split_page(vec![1, 2], 2, |row| *row);
+        assert_eq!((page, next), (vec![1, 2], None));
+        let (page, next) = split_page(Vec::<i32>::new(), 0, |row| *row);
+        assert_eq!((page, next), (vec![], None));
+    }
+}
@@ -13,9 +13,15 @@
 //! - Tries multiple selectors: token_uri, tokenURI, uri
 //! - ERC1155 `{id}` substitution in URIs
 //! - data: URI support (base64 and URL-encoded JSON)
-//! - IPFS gateway resolution
+//! - IPFS gateway resolution, falling back across [`IPFS_GATEWAYS`]
 //! - JSON sanitization for broken metadata (control chars, unescaped quotes)
 //! - Raw JSON fallback for inline metadata
+//! - URIs that failed to resolve are skipped, with backoff, when the fetcher has a
+//!   [`MetadataCache`]
+//!
+//! Images referenced by the metadata can be cached on local disk or, with
+//! [`ImageCache::Object`], uploaded with the metadata JSON to object storage
+//! (see [`crate::object_store`]).
 
 use starknet::core::types::{Felt, U256};
 use std::collections::{HashMap, VecDeque};
@@ -25,6 +31,8 @@ use std::time::Duration;
 use tokio::sync::mpsc;
 use tokio::task::JoinHandle;
 
+use crate::metadata_cache::{CacheLookup, MetadataCache};
+use crate::object_store::{ObjectStore, TokenAssets};
 use crate::MetadataFetcher;
 
 // Retry configuration
@@ -36,6 +44,14 @@ const RPC_BATCH_SIZE_CAP: usize = 128;
 const WRITE_BATCH_SIZE: usize = 128;
 const WRITE_BATCH_DELAY: Duration = Duration::from_millis(25);
 
+/// Public IPFS gateways tried in order for `ipfs://` URIs and gateway URLs
+pub const IPFS_GATEWAYS: &[&str] = &[
+    "https://ipfs.io/ipfs/",
+    "https://cloudflare-ipfs.com/ipfs/",
+    "https://gateway.pinata.cloud/ipfs/",
+    "https://dweb.link/ipfs/",
+];
+
 /// Token standard hint for URI fetching
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TokenStandard {
@@ -52,6 +68,8 @@ pub struct TokenUriRequest {
     pub token_id: U256,
     /// Which standard to use for fetching
     pub standard: TokenStandard,
+    /// URI already known (e.g. from an ERC1155 `URI` event); skips the on-chain call
+    pub uri: Option<String>,
 }
 
 /// Dedupe key for in-flight tasks
@@ -118,6 +136,7 @@ impl TokenUriSender {
                 contract,
                 token_id,
                 standard,
+                uri: None,
             }));
         }
         accepted
@@ -179,9 +198,18 @@ pub struct TokenUriService {
     handle: JoinHandle<()>,
 }
 
+/// Where images referenced by token metadata are cached
+#[derive(Clone)]
+pub enum ImageCache {
+    /// Files under `<dir>/<contract>/<token id>.<ext>`
+    Local(PathBuf),
+    /// Content-addressed objects, uploaded along with the metadata JSON
+    Object(Arc<ObjectStore>),
+}
+
 #[derive(Clone)]
 struct ImageCacheConfig {
-    dir: PathBuf,
+    cache: ImageCache,
     max_concurrent: usize,
 }
 
@@ -216,10 +244,30 @@ impl TokenUriService {
         max_concurrent: usize,
         image_cache_dir: Option<PathBuf>,
         image_max_concurrent: usize,
+    ) -> (TokenUriSender, Self) {
+        Self::spawn_with_cache(
+            fetcher,
+            store,
+            buffer_size,
+            max_concurrent,
+            image_cache_dir.map(ImageCache::Local),
+            image_max_concurrent,
+        )
+    }
+
+    /// Spawn the token URI service caching images to `image_cache` (local disk or
+    /// object storage).
+    pub fn spawn_with_cache<S: TokenUriStore>(
+        fetcher: Arc<MetadataFetcher>,
+        store: Arc<S>,
+        buffer_size: usize,
+        max_concurrent: usize,
+        image_cache: Option<ImageCache>,
+        image_max_concurrent: usize,
     ) -> (TokenUriSender, Self) {
         let (tx, rx) = mpsc::unbounded_channel();
-        let image_cache = image_cache_dir.map(|dir| ImageCacheConfig {
-            dir,
+        let image_cache = image_cache.map(|cache| ImageCacheConfig {
+            cache,
             max_concurrent: image_max_concurrent.max(1),
         });
         let handle = tokio::spawn(Self::run(
@@ -411,6 +459,7 @@ impl TokenUriService {
             if let (Some(cfg), Some(image_uri)) = (image_cache.clone(), buffered.image_uri) {
                 let contract = buffered.result.contract;
                 let token_id = buffered.result.token_id;
+                let metadata_json = buffered.result.metadata_json;
                 let semaphore = image_semaphore.clone();
                 tokio::spawn(async move {
                     let _image_permit = match semaphore {
@@ -418,16 +467,31 @@ impl TokenUriService {
                         None => None,
                     };
 
-                    if let Err(error) =
-                        cache_image_locally(&cfg.dir, contract, token_id, &image_uri).await
-                    {
+                    let cached = match &cfg.cache {
+                        ImageCache::Local(dir) => {
+                            cache_image_locally(dir, contract, token_id, &image_uri)
+                                .await
+                                .map(|_| ())
+                        }
+                        ImageCache::Object(objects) => {
+                            upload_token_assets(
+                                objects,
+                                contract,
+                                token_id,
+                                &image_uri,
+                                metadata_json.as_deref(),
+                            )
+                            .await
+                        }
+                    };
+                    if let Err(error) = cached {
                         tracing::debug!(
                             target: "torii_common::token_uri",
                             contract = %format!("{:#x}", contract),
                             token_id = %token_id,
                             image_uri = %image_uri,
                             error = %error,
-                            "Failed to cache image locally"
+                            "Failed to cache token image"
                         );
                     }
                 });
@@ -470,6 +534,10 @@ impl TokenUriService {
         let mut erc1155_requests = Vec::new();
 
         for (idx, request) in requests.iter().enumerate() {
+            if request.uri.is_some() {
+                raw_uris[idx].clone_from(&request.uri);
+                continue;
+            }
             let token_id = Felt::from(request.token_id.low());
             match request.standard {
                 TokenStandard::Erc721 => {
@@ -502,8 +570,10 @@ impl TokenUriService {
             while next_idx < requests.len() && join_set.len() < metadata_parallelism {
                 let request = requests[next_idx].clone();
                 let raw_uri = raw_uris[next_idx].clone();
+                let cache = fetcher.cache();
                 join_set.spawn(async move {
-                    let result = resolve_token_uri_from_uri(request, raw_uri).await;
+                    let result =
+                        resolve_token_uri_from_uri(request, raw_uri, cache.as_deref()).await;
                     let image_uri = result.metadata_json.as_deref().and_then(extract_image_uri);
                     (next_idx, BufferedTokenUriResult { result, image_uri })
                 });
@@ -563,18 +633,22 @@ async fn resolve_token_uri_request(
     fetcher: &MetadataFetcher,
     request: &TokenUriRequest,
 ) -> TokenUriResult {
-    let uri = fetch_token_uri_with_retry(
-        fetcher,
-        request.contract,
-        request.token_id,
-        request.standard,
-    )
-    .await;
+    let uri = match &request.uri {
+        Some(uri) => Some(uri.clone()),
+        None => {
+            fetch_token_uri_with_retry(
+                fetcher,
+                request.contract,
+                request.token_id,
+                request.standard,
+            )
+            .await
+        }
+    };
 
     let uri = uri.map(|u| {
         if request.standard == TokenStandard::Erc1155 {
-            let token_id_hex = format!("{:064x}", request.token_id);
-            u.replace("{id}", &token_id_hex)
+            substitute_token_id(&u, request.token_id)
         } else {
             u
         }
@@ -584,7 +658,7 @@ async fn resolve_token_uri_request(
         if uri_str.is_empty() {
             None
         } else {
-            resolve_metadata(uri_str).await
+            resolve_metadata_cached(fetcher.cache().as_deref(), uri_str).await
         }
     } else {
         None
@@ -601,11 +675,11 @@ async fn resolve_token_uri_request(
 async fn resolve_token_uri_from_uri(
     request: TokenUriRequest,
     raw_uri: Option<String>,
+    cache: Option<&MetadataCache>,
 ) -> TokenUriResult {
     let uri = raw_uri.map(|value| {
         if request.standard == TokenStandard::Erc1155 {
-            let token_id_hex = format!("{:064x}", request.token_id);
-            value.replace("{id}", &token_id_hex)
+            substitute_token_id(&value, request.token_id)
         } else {
             value
         }
@@ -615,7 +689,7 @@ async fn resolve_token_uri_from_uri(
         if uri_str.is_empty() {
             None
         } else {
-            resolve_metadata(uri_str).await
+            resolve_metadata_cached(cache, uri_str).await
         }
     } else {
         None
@@ -629,27 +703,80 @@ async fn resolve_token_uri_from_uri(
     }
 }
 
-fn extract_image_uri(metadata_json: &str) -> Option<String> {
-    let value: serde_json::Value = serde_json::from_str(metadata_json).ok()?;
-    for key in ["image", "image_url", "imageUrl"] {
-        if let Some(v) = value.get(key).and_then(|v| v.as_str()) {
-            let trimmed = v.trim();
-            if !trimmed.is_empty() {
-                return Some(trimmed.to_owned());
-            }
+/// Display fields of NFT metadata JSON, normalized across common layouts
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NormalizedMetadata {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    /// Image URI (`image`, `image_url`, `imageUrl`, or the same under `properties`)
+    pub image: Option<String>,
+}
+
+impl NormalizedMetadata {
+    /// Extracts the display fields of `metadata_json`; empty if it is not a JSON object.
+    pub fn from_json(metadata_json: &str) -> Self {
+        let Ok(value) = serde_json::from_str::<serde_json::Value>(metadata_json) else {
+            return Self::default();
+        };
+        Self {
+            name: metadata_text(&value, &["name", "title"]),
+            description: metadata_text(&value, &["description"]),
+            image: metadata_text(&value, &["image", "image_url", "imageUrl"]).or_else(|| {
+                value
+                    .get("properties")
+                    .and_then(|props| metadata_text(props, &["image", "image_url", "imageUrl"]))
+            }),
         }
     }
-    if let Some(props) = value.get("properties") {
-        for key in ["image", "image_url", "imageUrl"] {
-            if let Some(v) = props.get(key).and_then(|v| v.as_str()) {
-                let trimmed = v.trim();
-                if !trimmed.is_empty() {
-                    return Some(trimmed.to_owned());
-                }
-            }
+
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none() && self.description.is_none() && self.image.is_none()
+    }
+}
+
+/// First non-empty string (or number) among `keys` of a JSON object
+fn metadata_text(value: &serde_json::Value, keys: &[&str]) -> Option<String> {
+    keys.iter().find_map(|key| {
+        let text = match value.get(key)? {
+            serde_json::Value::String(s) => s.trim().to_owned(),
+            serde_json::Value::Number(n) => n.to_string(),
+            _ => return None,
+        };
+        (!text.is_empty()).then_some(text)
+    })
+}
+
+fn extract_image_uri(metadata_json: &str) -> Option<String> {
+    NormalizedMetadata::from_json(metadata_json).image
+}
+
+/// Gateway URLs to try for an IPFS resource, in order.
+///
+/// Handles `ipfs://<cid>`, `ipfs://ipfs/<cid>` and HTTP gateway URLs (`.../ipfs/<cid>`),
+/// which are tried first and then re-routed through [`IPFS_GATEWAYS`]. Returns `None`
+/// for other URIs.
+pub fn ipfs_gateway_urls(uri: &str) -> Option<Vec<String>> {
+    let (original, path) = if let Some(rest) = uri.strip_prefix("ipfs://") {
+        (None, rest.strip_prefix("ipfs/").unwrap_or(rest))
+    } else if uri.starts_with("http://") || uri.starts_with("https://") {
+        let (_, path) = uri.split_once("/ipfs/")?;
+        (Some(uri.to_owned()), path)
+    } else {
+        return None;
+    };
+    if path.is_empty() {
+        return None;
+    }
+
+    let mut urls = Vec::with_capacity(IPFS_GATEWAYS.len() + 1);
+    urls.extend(original);
+    for gateway in IPFS_GATEWAYS {
+        let url = format!("{gateway}{path}");
+        if !urls.contains(&url) {
+            urls.push(url);
         }
     }
-    None
+    Some(urls)
 }
 
 async fn cache_image_locally(
@@ -687,16 +814,68 @@ async fn cache_image_locally(
     Ok(final_path)
 }
 
+/// Uploads the image and metadata JSON of a token, then its [`TokenAssets`] pointer.
+async fn upload_token_assets(
+    objects: &ObjectStore,
+    contract: Felt,
+    token_id: U256,
+    image_uri: &str,
+    metadata_json: Option<&str>,
+) -> anyhow::Result<()> {
+    let (bytes, content_type, source_url) = fetch_image_bytes_with_retry(image_uri)
+        .await
+        .ok_or_else(|| anyhow::anyhow!("image download failed"))?;
+    let ext = image_extension(content_type.as_deref(), &source_url);
+    let content_type = content_type.unwrap_or_else(|| "application/octet-stream".to_owned());
+
+    let image = objects
+        .put_content("images", bytes, ext, &content_type)
+        .await?;
+    let metadata = match metadata_json {
+        Some(json) => Some(
+            objects
+                .put_content(
+                    "metadata",
+                    json.as_bytes().to_vec(),
+                    "json",
+                    "application/json",
+                )
+                .await?,
+        ),
+        None => None,
+    };
+    objects
+        .put_token_assets(
+            contract,
+            token_id,
+            &TokenAssets {
+                image: Some(image),
+                image_content_type: Some(content_type),
+                metadata,
+            },
+        )
+        .await?;
+    ::metrics::counter!("torii_token_uri_assets_uploaded_total").increment(1);
+
+    tracing::debug!(
+        target: "torii_common::token_uri",
+        contract = %format!("{:#x}", contract),
+        token_id = %token_id,
+        "Uploaded token assets to object storage"
+    );
+    Ok(())
+}
+
 async fn fetch_image_bytes_with_retry(uri: &str) -> Option<(Vec<u8>, Option<String>, String)> {
     if uri.starts_with("data:") {
         let (bytes, content_type) = resolve_data_uri_bytes(uri)?;
         return Some((bytes, content_type, uri.to_owned()));
     }
 
-    let url = if let Some(cid) = uri.strip_prefix("ipfs://") {
-        format!("https://ipfs.io/ipfs/{cid}")
+    let urls = if let Some(urls) = ipfs_gateway_urls(uri) {
+        urls
     } else if uri.starts_with("http://") || uri.starts_with("https://") {
-        uri.to_owned()
+        vec![uri.to_owned()]
     } else {
         return None;
     };
@@ -706,6 +885,18 @@ async fn fetch_image_bytes_with_retry(uri: &str) -> Option<(Vec<u8>, Option<Stri
         .build()
         .ok()?;
 
+    for url in urls {
+        if let Some(image) = fetch_image_bytes_from(&client, url).await {
+            return Some(image);
+        }
+    }
+    None
+}
+
+async fn fetch_image_bytes_from(
+    client: &reqwest::Client,
+    url: String,
+) -> Option<(Vec<u8>, Option<String>, String)> {
     let mut retries = 0;
     let mut backoff = INITIAL_BACKOFF;
     loop {
@@ -793,6 +984,18 @@ fn image_extension(content_type: Option<&str>, source_url: &str) -> &'static str
 // Token URI fetching (from chain)
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 
+/// Substitutes the ERC1155 `{id}` placeholder with the token id.
+///
+/// Per the ERC1155 metadata spec, the id is lowercase hex, zero-padded to 64
+/// characters, without a `0x` prefix.
+pub fn substitute_token_id(uri: &str, token_id: U256) -> String {
+    if uri.contains("{id}") {
+        uri.replace("{id}", &format!("{token_id:064x}"))
+    } else {
+        uri.to_owned()
+    }
+}
+
 /// Fetch token URI with retries, trying multiple selectors.
 ///
 /// Tries `token_uri`, `tokenURI`, and `uri` selectors in order.
@@ -816,6 +1019,37 @@ async fn fetch_token_uri_with_retry(
 // Metadata resolution (URI → JSON)
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 
+/// [`resolve_metadata`], skipping URIs whose previous resolutions failed until their
+/// backoff in `cache` expires.
+async fn resolve_metadata_cached(cache: Option<&MetadataCache>, uri: &str) -> Option<String> {
+    let Some(cache) = cache else {
+        return resolve_metadata(uri).await;
+    };
+
+    let key = format!("uri:{uri}");
+    if cache.get(&key) == CacheLookup::Negative {
+        tracing::debug!(
+            target: "torii_common::token_uri",
+            uri = %uri,
+            "Skipping URI that recently failed to resolve"
+        );
+        return None;
+    }
+
+    let metadata = resolve_metadata(uri).await;
+    if metadata.is_none() {
+        if let Err(error) = cache.insert(&key, None).await {
+            tracing::warn!(
+                target: "torii_common::token_uri",
+                uri = %uri,
+                %error,
+                "Failed to cache URI resolution failure"
+            );
+        }
+    }
+    metadata
+}
+
 /// Resolve a URI to JSON metadata string.
 ///
 /// Handles:
@@ -829,15 +1063,19 @@ async fn fetch_token_uri_with_retry(
 /// Based on dojoengine/torii's battle-tested `fetch_metadata`.
 async fn resolve_metadata(uri: &str) -> Option<String> {
     let result = match uri {
+        u if ipfs_gateway_urls(u).is_some() => {
+            let mut fetched = None;
+            for url in ipfs_gateway_urls(u).unwrap_or_default() {
+                fetched = fetch_http_with_retry(&url).await;
+                if fetched.is_some() {
+                    break;
+                }
+            }
+            fetched
+        }
         u if u.starts_with("http://") || u.starts_with("https://") => {
             fetch_http_with_retry(u).await
         }
-        u if u.starts_with("ipfs://") => {
-            let cid = &u[7..];
-            // Try multiple IPFS gateways
-            let gateway_url = format!("https://ipfs.io/ipfs/{cid}");
-            fetch_http_with_retry(&gateway_url).await
-        }
         u if u.starts_with("data:") => resolve_data_uri(u),
         u => {
             // Fallback: try to parse as raw JSON
@@ -1059,11 +1297,19 @@ fn sanitize_json_string(s: &str) -> String {
     result
 }
 
-/// Simple base64 decode helper
+/// Base64 decode helper, tolerating whitespace, missing padding and the URL-safe alphabet
 fn base64_decode(input: &str) -> Option<String> {
+    use base64::engine::general_purpose::{STANDARD, STANDARD_NO_PAD, URL_SAFE_NO_PAD};
     use base64::Engine;
-    let bytes = base64::engine::general_purpose::STANDARD
-        .decode(input)
+    let input = input
+        .chars()
+        .filter(|c| !c.is_ascii_whitespace())
+        .collect::<String>();
+    let unpadded = input.trim_end_matches('=');
+    let bytes = STANDARD
+        .decode(&input)
+        .or_else(|_| STANDARD_NO_PAD.decode(unpadded))
+        .or_else(|_| URL_SAFE_NO_PAD.decode(unpadded))
         .ok()?;
     String::from_utf8(bytes).ok()
 }
@@ -1124,13 +1370,52 @@ mod tests {
         assert!(result.is_some());
     }
 
+    #[test]
+    fn test_resolve_data_uri_base64_without_padding() {
+        let uri = "data:application/json;base64,eyJuYW1lIjoiIzEifQ";
+        assert_eq!(resolve_data_uri(uri).as_deref(), Some(r##"{"name":"#1"}"##));
+    }
+
+    #[test]
+    fn ipfs_gateway_urls_fall_back_across_gateways() {
+        let urls = ipfs_gateway_urls("ipfs://ipfs/QmHash/1.json").unwrap();
+        assert_eq!(urls.len(), IPFS_GATEWAYS.len());
+        assert_eq!(urls[0], "https://ipfs.io/ipfs/QmHash/1.json");
+
+        let urls = ipfs_gateway_urls("https://gateway.example/ipfs/QmHash").unwrap();
+        assert_eq!(urls[0], "https://gateway.example/ipfs/QmHash");
+        assert_eq!(urls[1], "https://ipfs.io/ipfs/QmHash");
+        assert_eq!(urls.len(), IPFS_GATEWAYS.len() + 1);
+
+        assert!(ipfs_gateway_urls("https://api.example/token/1").is_none());
+        assert!(ipfs_gateway_urls("data:application/json,{}").is_none());
+    }
+
+    #[test]
+    fn normalized_metadata_reads_display_fields() {
+        let meta = NormalizedMetadata::from_json(
+            r#"{"name":" Beast #1 ","description":"A wolf","properties":{"image_url":"ipfs://img"}}"#,
+        );
+        assert_eq!(meta.name.as_deref(), Some("Beast #1"));
+        assert_eq!(meta.description.as_deref(), Some("A wolf"));
+        assert_eq!(meta.image.as_deref(), Some("ipfs://img"));
+
+        let meta = NormalizedMetadata::from_json(r#"{"name":42,"image":""}"#);
+        assert_eq!(meta.name.as_deref(), Some("42"));
+        assert_eq!(meta.image, None);
+        assert!(NormalizedMetadata::from_json("not json").is_empty());
+    }
+
     #[test]
     fn test_erc1155_id_substitution() {
         let uri = "https://example.com/token/{id}.json";
         let token_id = U256::from(42u64);
-        let token_id_hex = format!("{token_id:064x}");
-        let result = uri.replace("{id}", &token_id_hex);
-        assert!(result.contains("000000000000000000000000000000000000000000000000000000000000002a"));
+        let result = substitute_token_id(uri, token_id);
+        assert_eq!(
+            result,
+            "https://example.com/token/000000000000000000000000000000000000000000000000000000000000002a.json"
+        );
+        assert_eq!(substitute_token_id("ipfs://abc", token_id), "ipfs://abc");
     }
 
     #[test]
@@ -1141,11 +1426,13 @@ mod tests {
             contract,
             token_id: U256::from(1u64),
             standard: TokenStandard::Erc721,
+            uri: None,
         });
         backlog.enqueue(TokenUriRequest {
             contract,
             token_id: U256::from(1u64),
             standard: TokenStandard::Erc1155,
+            uri: None,
         });
 
         assert_eq!(backlog.len(), 1);
@@ -1162,11 +1449,13 @@ mod tests {
             contract,
             token_id: U256::from(1u64),
             standard: TokenStandard::Erc721,
+            uri: None,
         });
         backlog.enqueue(TokenUriRequest {
             contract,
             token_id: U256::from(2u64),
             standard: TokenStandard::Erc721,
+            uri: None,
         });
 
         let first = backlog.pop_batch(1);
@@ -0,0 +1,240 @@
+//! Address watchlists for server-side filtered subscriptions.
+//!
+//! A watcher registers a set of account addresses and only receives the updates
+//! involving one of them. Watched addresses are indexed (address -> watchers), so
+//! routing an update costs one lookup per involved address instead of one filter
+//! evaluation per subscriber.
+
+use starknet::core::types::Felt;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use tokio::sync::mpsc;
+
+struct Entry<T> {
+    addresses: HashSet<Felt>,
+    tx: mpsc::Sender<T>,
+}
+
+struct Watchers<T> {
+    entries: HashMap<u64, Entry<T>>,
+    index: HashMap<Felt, HashSet<u64>>,
+}
+
+impl<T> Watchers<T> {
+    fn unindex(&mut self, id: u64, addresses: &HashSet<Felt>) {
+        for address in addresses {
+            if let Some(ids) = self.index.get_mut(address) {
+                ids.remove(&id);
+                if ids.is_empty() {
+                    self.index.remove(address);
+                }
+            }
+        }
+    }
+
+    fn remove(&mut self, id: u64) {
+        if let Some(entry) = self.entries.remove(&id) {
+            self.unindex(id, &entry.addresses);
+        }
+    }
+}
+
+/// Routes updates to the watchers of the addresses they involve.
+pub struct AddressWatchlist<T> {
+    watchers: Arc<RwLock<Watchers<T>>>,
+    next_id: Arc<AtomicU64>,
+}
+
+impl<T> Clone for AddressWatchlist<T> {
+    fn clone(&self) -> Self {
+        Self {
+            watchers: self.watchers.clone(),
+            next_id: self.next_id.clone(),
+        }
+    }
+}
+
+impl<T> Default for AddressWatchlist<T> {
+    fn default() -> Self {
+        Self {
+            watchers: Arc::new(RwLock::new(Watchers {
+                entries: HashMap::new(),
+                index: HashMap::new(),
+            })),
+            next_id: Arc::new(AtomicU64::new(0)),
+        }
+    }
+}
+
+impl<T: Clone> AddressWatchlist<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a watcher of `addresses` buffering up to `capacity` updates.
+    ///
+    /// The watcher is removed when the returned [`AddressWatcher`] is dropped.
+    pub fn watch(
+        &self,
+        addresses: impl IntoIterator<Item = Felt>,
+        capacity: usize,
+    ) -> AddressWatcher<T> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = mpsc::channel(capacity.max(1));
+        self.watchers.write().unwrap().entries.insert(
+            id,
+            Entry {
+                addresses: HashSet::new(),
+                tx,
+            },
+        );
+        let watcher = AddressWatcher {
+            id,
+            watchlist: self.clone(),
+            rx,
+        };
+        watcher.set_addresses(addresses);
+        watcher
+    }
+
+    /// Number of registered watchers.
+    pub fn len(&self) -> usize {
+        self.watchers.read().unwrap().entries.len()
+    }
+
+    /// Whether no watcher is registered (updates can be skipped).
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Sends `update` to every watcher of at least one of `addresses`.
+    ///
+    /// Never blocks: updates for a watcher whose buffer is full are dropped.
+    /// Returns the number of watchers the update was delivered to.
+    pub fn notify(&self, addresses: &[Felt], update: &T) -> usize {
+        let mut closed = Vec::new();
+        let mut delivered = 0;
+        {
+            let watchers = self.watchers.read().unwrap();
+            let mut ids = addresses
+                .iter()
+                .filter_map(|address| watchers.index.get(address))
+                .flatten()
+                .copied()
+                .collect::<Vec<_>>();
+            ids.sort_unstable();
+            ids.dedup();
+
+            for id in ids {
+                let Some(entry) = watchers.entries.get(&id) else {
+                    continue;
+                };
+                match entry.tx.try_send(update.clone()) {
+                    Ok(()) => delivered += 1,
+                    Err(mpsc::error::TrySendError::Full(_)) => {
+                        tracing::warn!(
+                            target: "torii_common::watchlist",
+                            watcher = id,
+                            "Address watcher lagging, dropped update"
+                        );
+                    }
+                    Err(mpsc::error::TrySendError::Closed(_)) => closed.push(id),
+                }
+            }
+        }
+
+        if !closed.is_empty() {
+            let mut watchers = self.watchers.write().unwrap();
+            for id in closed {
+                watchers.remove(id);
+            }
+        }
+        delivered
+    }
+
+    /// Sends `update` to every watcher, whatever it watches (e.g. a shutdown notice).
+    pub fn notify_all(&self, update: &T) -> usize {
+        let watchers = self.watchers.read().unwrap();
+        watchers
+            .entries
+            .values()
+            .filter(|entry| entry.tx.try_send(update.clone()).is_ok())
+            .count()
+    }
+}
+
+/// Receiving end of a watcher registered with [`AddressWatchlist::watch`].
+pub struct AddressWatcher<T> {
+    id: u64,
+    watchlist: AddressWatchlist<T>,
+    rx: mpsc::Receiver<T>,
+}
+
+impl<T> AddressWatcher<T> {
+    /// Replaces the watched addresses.
+    pub fn set_addresses(&self, addresses: impl IntoIterator<Item = Felt>) {
+        let addresses = addresses.into_iter().collect::<HashSet<_>>();
+        let mut watchers = self.watchlist.watchers.write().unwrap();
+        let Some(entry) = watchers.entries.get_mut(&self.id) else {
+            return;
+        };
+        let previous = std::mem::replace(&mut entry.addresses, addresses.clone());
+        watchers.unindex(self.id, &previous);
+        for address in addresses {
+            watchers.index.entry(address).or_default().insert(self.id);
+        }
+    }
+
+    /// Receives the next update involving a watched address.
+    pub async fn recv(&mut self) -> Option<T> {
+        self.rx.recv().await
+    }
+}
+
+impl<T> Drop for AddressWatcher<T> {
+    fn drop(&mut self) {
+        if let Ok(mut watchers) = self.watchlist.watchers.write() {
+            watchers.remove(self.id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_routes_updates_to_watchers_of_involved_addresses() {
+        let watchlist = AddressWatchlist::<u32>::new();
+        let alice = Felt::from(1u8);
+        let bob = Felt::from(2u8);
+        let carol = Felt::from(3u8);
+
+        let mut alice_watcher = watchlist.watch([alice], 8);
+        let mut both_watcher = watchlist.watch([alice, bob], 8);
+
+        assert_eq!(watchlist.notify(&[alice, carol], &1), 2);
+        assert_eq!(watchlist.notify(&[bob], &2), 1);
+        assert_eq!(watchlist.notify(&[carol], &3), 0);
+        // An update involving two watched addresses is delivered once.
+        assert_eq!(watchlist.notify(&[alice, bob], &4), 2);
+
+        assert_eq!(alice_watcher.recv().await, Some(1));
+        assert_eq!(alice_watcher.recv().await, Some(4));
+        assert_eq!(both_watcher.recv().await, Some(1));
+        assert_eq!(both_watcher.recv().await, Some(2));
+        assert_eq!(both_watcher.recv().await, Some(4));
+
+        alice_watcher.set_addresses([carol]);
+        assert_eq!(watchlist.notify(&[alice], &5), 1);
+        assert_eq!(watchlist.notify(&[carol], &6), 1);
+        assert_eq!(alice_watcher.recv().await, Some(6));
+
+        drop(both_watcher);
+        assert_eq!(watchlist.len(), 1);
+        assert_eq!(watchlist.notify(&[alice, bob], &7), 0);
+        assert_eq!(watchlist.notify_all(&8), 1);
+        assert_eq!(alice_watcher.recv().await, Some(8));
+    }
+}
@@ -16,6 +16,7 @@ use torii::axum::Router;
 use torii::etl::extractor::ExtractionBatch;
 use torii::etl::sink::{EventBus, Sink, SinkContext, TopicInfo};
 use torii::etl::TypeId;
+use torii::ToriiResult;
 use torii_runtime_common::database::DEFAULT_SQLITE_MAX_CONNECTIONS;
 
 pub const DEFAULT_API_QUERY_URL: &str = "https://api.cartridge.gg/query";
@@ -509,13 +510,13 @@ impl Sink for ControllersSink {
         &self,
         _envelopes: &[torii::etl::Envelope],
         batch: &ExtractionBatch,
-    ) -> Result<()> {
+    ) -> ToriiResult<()> {
         match self.sync_batch(batch).await {
             Ok(()) => Ok(()),
             Err(error) => {
                 ::metrics::counter!("torii_controller_sync_batches_total", "status" => "error")
                     .increment(1);
-                Err(error)
+                Err(error.into())
             }
         }
     }
@@ -532,7 +533,7 @@ impl Sink for ControllersSink {
         &mut self,
         _event_bus: Arc<EventBus>,
         _context: &SinkContext,
-    ) -> Result<()> {
+    ) -> ToriiResult<()> {
         self.store.initialize().await?;
         if self.store.is_empty().await? {
             self.full_sync_from_api().await?;
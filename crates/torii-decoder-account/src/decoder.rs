@@ -0,0 +1,274 @@
+//! Account contract event decoder
+//!
+//! Account implementations emit differently shaped events for the same lifecycle
+//! changes, so every recognized event is decoded into a single [`AccountEvent`] body
+//! with its [`AccountEventKind`] and subject: the first member of the event, read from
+//! the keys when the event has key members and from the data otherwise.
+//!
+//! | Event | Emitted by | Kind | Subject |
+//! |-------|------------|------|---------|
+//! | `OwnerChanged` | Argent (< 0.4) | `owner_changed` | new owner |
+//! | `OwnerAdded`, `OwnerAddedGuid` | OpenZeppelin, Argent | `owner_added` | owner (guid) |
+//! | `OwnerRemoved`, `OwnerRemovedGuid` | OpenZeppelin, Argent | `owner_removed` | owner (guid) |
+//! | `GuardianChanged` | Argent | `guardian_changed` | new guardian |
+//! | `SignerAdded`, `SignerLinked` | Braavos, Argent | `signer_added` | signer (id/guid) |
+//! | `SignerRemoved` | Braavos | `signer_removed` | signer (id) |
+//! | `AccountUpgraded`, `Upgraded` | Argent, OpenZeppelin | `upgraded` | new class hash |
+//! | `TransactionExecuted` | Argent | `executed` / `outside_executed` | executed hash |
+//!
+//! `TransactionExecuted` carries the hash of what was executed: the transaction hash for
+//! a regular transaction, or the outside execution message hash (SNIP-9) when the account
+//! executed calls submitted by another account. The latter are `outside_executed`.
+//!
+//! `Upgraded` and `OwnerChanged` are also emitted by other upgradeable or owned contracts:
+//! map the decoder to account contracts ([`crate::AccountRule`] or explicit mappings)
+//! rather than letting it decode every contract.
+
+use async_trait::async_trait;
+use starknet::core::types::{EmittedEvent, Felt};
+use starknet::macros::selector;
+use std::any::Any;
+use std::str::FromStr;
+use torii::etl::envelope::{TypeId, TypedBody};
+use torii::etl::{Decoder, Envelope, EnvelopeMetadata};
+use torii::ToriiResult;
+
+/// Envelope type id of [`AccountEvent`]
+pub const ACCOUNT_EVENT_TYPE: &str = "account.event";
+
+/// Lifecycle change or execution of an account
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AccountEventKind {
+    OwnerChanged,
+    OwnerAdded,
+    OwnerRemoved,
+    GuardianChanged,
+    SignerAdded,
+    SignerRemoved,
+    /// Account class upgraded
+    Upgraded,
+    /// Transaction executed by the account
+    Executed,
+    /// Outside execution (SNIP-9) executed by the account
+    OutsideExecuted,
+}
+
+impl AccountEventKind {
+    pub const ALL: [Self; 9] = [
+        Self::OwnerChanged,
+        Self::OwnerAdded,
+        Self::OwnerRemoved,
+        Self::GuardianChanged,
+        Self::SignerAdded,
+        Self::SignerRemoved,
+        Self::Upgraded,
+        Self::Executed,
+        Self::OutsideExecuted,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::OwnerChanged => "owner_changed",
+            Self::OwnerAdded => "owner_added",
+            Self::OwnerRemoved => "owner_removed",
+            Self::GuardianChanged => "guardian_changed",
+            Self::SignerAdded => "signer_added",
+            Self::SignerRemoved => "signer_removed",
+            Self::Upgraded => "upgraded",
+            Self::Executed => "executed",
+            Self::OutsideExecuted => "outside_executed",
+        }
+    }
+}
+
+impl FromStr for AccountEventKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.as_str() == s)
+            .ok_or_else(|| anyhow::anyhow!("Unknown account event kind {s}"))
+    }
+}
+
+impl std::fmt::Display for AccountEventKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Recognized event names, by selector. `TransactionExecuted` is refined into
+/// [`AccountEventKind::OutsideExecuted`] by [`AccountDecoder`].
+const EVENTS: [(Felt, AccountEventKind); 12] = [
+    (selector!("OwnerChanged"), AccountEventKind::OwnerChanged),
+    (selector!("OwnerAdded"), AccountEventKind::OwnerAdded),
+    (selector!("OwnerAddedGuid"), AccountEventKind::OwnerAdded),
+    (selector!("OwnerRemoved"), AccountEventKind::OwnerRemoved),
+    (
+        selector!("OwnerRemovedGuid"),
+        AccountEventKind::OwnerRemoved,
+    ),
+    (
+        selector!("GuardianChanged"),
+        AccountEventKind::GuardianChanged,
+    ),
+    (selector!("SignerAdded"), AccountEventKind::SignerAdded),
+    (selector!("SignerLinked"), AccountEventKind::SignerAdded),
+    (selector!("SignerRemoved"), AccountEventKind::SignerRemoved),
+    (selector!("AccountUpgraded"), AccountEventKind::Upgraded),
+    (selector!("Upgraded"), AccountEventKind::Upgraded),
+    (selector!("TransactionExecuted"), AccountEventKind::Executed),
+];
+
+/// Account event (see the [module docs](self) for the recognized events)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountEvent {
+    pub account: Felt,
+    pub kind: AccountEventKind,
+    /// New owner/guardian/signer, removed owner/signer, new class hash or executed hash
+    pub subject: Felt,
+    pub block_number: u64,
+    pub transaction_hash: Felt,
+}
+
+impl TypedBody for AccountEvent {
+    fn envelope_type_id(&self) -> TypeId {
+        TypeId::new(ACCOUNT_EVENT_TYPE)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+impl AccountEvent {
+    /// Decodes a recognized account event. Returns `None` for other events and events
+    /// without members.
+    pub fn decode(event: &EmittedEvent) -> Option<Self> {
+        let selector = *event.keys.first()?;
+        let (_, mut kind) = EVENTS.iter().find(|(s, _)| *s == selector)?;
+        let subject = match event.keys.get(1) {
+            Some(key) => *key,
+            None => *event.data.first()?,
+        };
+        if kind == AccountEventKind::Executed && subject != event.transaction_hash {
+            kind = AccountEventKind::OutsideExecuted;
+        }
+        Some(Self {
+            account: event.from_address,
+            kind,
+            subject,
+            block_number: event.block_number.unwrap_or(0),
+            transaction_hash: event.transaction_hash,
+        })
+    }
+}
+
+/// Account event decoder (`account`)
+pub struct AccountDecoder;
+
+impl AccountDecoder {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for AccountDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Decoder for AccountDecoder {
+    fn decoder_name(&self) -> &'static str {
+        "account"
+    }
+
+    fn decoder_version(&self) -> Option<&str> {
+        Some(env!("CARGO_PKG_VERSION"))
+    }
+
+    async fn decode_event(&self, event: &EmittedEvent) -> ToriiResult<Vec<Envelope>> {
+        let Some(body) = AccountEvent::decode(event) else {
+            return Ok(Vec::new());
+        };
+
+        let mut metadata = EnvelopeMetadata::new();
+        metadata.insert("account".to_string(), format!("{:#x}", body.account).into());
+        metadata.insert("kind".to_string(), body.kind.as_str().to_string().into());
+        let id = format!(
+            "account_{}_{}_{:#x}_{:#x}",
+            body.kind, body.block_number, body.transaction_hash, body.subject
+        );
+        Ok(vec![Envelope::from_body(id, body, metadata)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(name: &str, keys: &[u64], data: &[u64], tx_hash: u64) -> EmittedEvent {
+        EmittedEvent {
+            from_address: Felt::from(0xacc_u64),
+            keys: std::iter::once(starknet::core::utils::get_selector_from_name(name).unwrap())
+                .chain(keys.iter().map(|key| Felt::from(*key)))
+                .collect(),
+            data: data.iter().map(|value| Felt::from(*value)).collect(),
+            block_hash: None,
+            block_number: Some(10),
+            transaction_hash: Felt::from(tx_hash),
+        }
+    }
+
+    fn decoded(event: &EmittedEvent) -> Option<(AccountEventKind, Felt)> {
+        AccountEvent::decode(event).map(|body| (body.kind, body.subject))
+    }
+
+    #[test]
+    fn decodes_account_events() {
+        assert_eq!(
+            decoded(&event("OwnerChanged", &[], &[5], 1)),
+            Some((AccountEventKind::OwnerChanged, Felt::from(5_u64)))
+        );
+        assert_eq!(
+            decoded(&event("OwnerAdded", &[6], &[], 1)),
+            Some((AccountEventKind::OwnerAdded, Felt::from(6_u64)))
+        );
+        assert_eq!(
+            decoded(&event("SignerLinked", &[7], &[0, 1, 2], 1)),
+            Some((AccountEventKind::SignerAdded, Felt::from(7_u64)))
+        );
+        assert_eq!(
+            decoded(&event("Upgraded", &[], &[0x99], 1)),
+            Some((AccountEventKind::Upgraded, Felt::from(0x99_u64)))
+        );
+
+        // Executed hash is the transaction hash, unless executed from outside.
+        assert_eq!(
+            decoded(&event("TransactionExecuted", &[1], &[0], 1)),
+            Some((AccountEventKind::Executed, Felt::ONE))
+        );
+        assert_eq!(
+            decoded(&event("TransactionExecuted", &[0x55], &[0], 1)),
+            Some((AccountEventKind::OutsideExecuted, Felt::from(0x55_u64)))
+        );
+
+        assert_eq!(decoded(&event("Transfer", &[1, 2], &[3, 0], 1)), None);
+        assert_eq!(decoded(&event("OwnerChanged", &[], &[], 1)), None);
+    }
+
+    #[test]
+    fn kinds_round_trip() {
+        for kind in AccountEventKind::ALL {
+            assert_eq!(kind.as_str().parse::<AccountEventKind>().unwrap(), kind);
+        }
+        assert!("unknown".parse::<AccountEventKind>().is_err());
+    }
+}
@@ -0,0 +1,248 @@
+// This file is @generated by prost-build.
+/// Account event
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AccountActivity {
+    /// Account address (32 bytes)
+    #[prost(bytes = "vec", tag = "1")]
+    pub account: ::prost::alloc::vec::Vec<u8>,
+    /// owner_changed, owner_added, owner_removed, guardian_changed, signer_added,
+    /// signer_removed, upgraded, executed or outside_executed
+    #[prost(string, tag = "2")]
+    pub kind: ::prost::alloc::string::String,
+    /// New owner/guardian/signer, removed owner/signer, new class hash or executed hash
+    /// (32 bytes)
+    #[prost(bytes = "vec", tag = "3")]
+    pub subject: ::prost::alloc::vec::Vec<u8>,
+    #[prost(uint64, tag = "4")]
+    pub block_number: u64,
+    /// Transaction hash (32 bytes)
+    #[prost(bytes = "vec", tag = "5")]
+    pub tx_hash: ::prost::alloc::vec::Vec<u8>,
+    /// Index of the event within its transaction
+    #[prost(uint32, tag = "6")]
+    pub event_index: u32,
+    /// Block timestamp (unix seconds), if known
+    #[prost(int64, optional, tag = "7")]
+    pub timestamp: ::core::option::Option<i64>,
+}
+/// Request for GetAccountActivity RPC
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetAccountActivityRequest {
+    /// Account address (32 bytes)
+    #[prost(bytes = "vec", tag = "1")]
+    pub address: ::prost::alloc::vec::Vec<u8>,
+    /// Only events of these kinds (all when empty)
+    #[prost(string, repeated, tag = "2")]
+    pub kinds: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Only events strictly before this block (pagination)
+    #[prost(uint64, optional, tag = "3")]
+    pub before_block: ::core::option::Option<u64>,
+    /// Maximum number of events to return (default: 100, max: 1000)
+    #[prost(uint32, tag = "4")]
+    pub limit: u32,
+}
+/// Response for GetAccountActivity RPC
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetAccountActivityResponse {
+    /// Account address (32 bytes)
+    #[prost(bytes = "vec", tag = "1")]
+    pub address: ::prost::alloc::vec::Vec<u8>,
+    #[prost(message, repeated, tag = "2")]
+    pub activity: ::prost::alloc::vec::Vec<AccountActivity>,
+    /// Executions recorded for the account (all blocks)
+    #[prost(uint64, tag = "3")]
+    pub executions: u64,
+    /// Outside executions (SNIP-9) among them
+    #[prost(uint64, tag = "4")]
+    pub outside_executions: u64,
+    /// First and last block with an event of the account
+    #[prost(uint64, optional, tag = "5")]
+    pub first_block: ::core::option::Option<u64>,
+    #[prost(uint64, optional, tag = "6")]
+    pub last_block: ::core::option::Option<u64>,
+    /// Class hash of the last upgrade (32 bytes)
+    #[prost(bytes = "vec", optional, tag = "7")]
+    pub class_hash: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+}
+/// Generated server implementations.
+pub mod account_server {
+    #![allow(
+        unused_variables,
+        dead_code,
+        missing_docs,
+        clippy::wildcard_imports,
+        clippy::let_unit_value,
+    )]
+    use tonic::codegen::*;
+    /// Generated trait containing gRPC methods that should be implemented for use with AccountServer.
+    #[async_trait]
+    pub trait Account: std::marker::Send + std::marker::Sync + 'static {
+        /// Activity of an account (newest first) and its execution summary
+        async fn get_account_activity(
+            &self,
+            request: tonic::Request<super::GetAccountActivityRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetAccountActivityResponse>,
+            tonic::Status,
+        >;
+    }
+    /// Account service - activity of indexed account contracts
+    #[derive(Debug)]
+    pub struct AccountServer<T> {
+        inner: Arc<T>,
+        accept_compression_encodings: EnabledCompressionEncodings,
+        send_compression_encodings: EnabledCompressionEncodings,
+        max_decoding_message_size: Option<usize>,
+        max_encoding_message_size: Option<usize>,
+    }
+    impl<T> AccountServer<T> {
+        pub fn new(inner: T) -> Self {
+            Self::from_arc(Arc::new(inner))
+        }
+        pub fn from_arc(inner: Arc<T>) -> Self {
+            Self {
+                inner,
+                accept_compression_encodings: Default::default(),
+                send_compression_encodings: Default::default(),
+                max_decoding_message_size: None,
+                max_encoding_message_size: None,
+            }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> InterceptedService<Self, F>
+        where
+            F: tonic::service::Interceptor,
+        {
+            InterceptedService::new(Self::new(inner), interceptor)
+        }
+        /// Enable decompressing requests with the given encoding.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.accept_compression_encodings.enable(encoding);
+            self
+        }
+        /// Compress responses with the given encoding, if the client supports it.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.send_compression_encodings.enable(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.max_decoding_message_size = Some(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.max_encoding_message_size = Some(limit);
+            self
+        }
+    }
+    impl<T, B> tonic::codegen::Service<http::Request<B>> for AccountServer<T>
+    where
+        T: Account,
+        B: Body + std::marker::Send + 'static,
+        B::Error: Into<StdError> + std::marker::Send + 'static,
+    {
+        type Response = http::Response<tonic::body::BoxBody>;
+        type Error = std::convert::Infallible;
+        type Future = BoxFuture<Self::Response, Self::Error>;
+        fn poll_ready(
+            &mut self,
+            _cx: &mut Context<'_>,
+        ) -> Poll<std::result::Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+        fn call(&mut self, req: http::Request<B>) -> Self::Future {
+            match req.uri().path() {
+                "/torii.sinks.account.Account/GetAccountActivity" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetAccountActivitySvc<T: Account>(pub Arc<T>);
+                    impl<
+                        T: Account,
+                    > tonic::server::UnaryService<super::GetAccountActivityRequest>
+                    for GetAccountActivitySvc<T> {
+                        type Response = super::GetAccountActivityResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetAccountActivityRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Account>::get_account_activity(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetAccountActivitySvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                _ => {
+                    Box::pin(async move {
+                        let mut response = http::Response::new(empty_body());
+                        let headers = response.headers_mut();
+                        headers
+                            .insert(
+                                tonic::Status::GRPC_STATUS,
+                                (tonic::Code::Unimplemented as i32).into(),
+                            );
+                        headers
+                            .insert(
+                                http::header::CONTENT_TYPE,
+                                tonic::metadata::GRPC_CONTENT_TYPE,
+                            );
+                        Ok(response)
+                    })
+                }
+            }
+        }
+    }
+    impl<T> Clone for AccountServer<T> {
+        fn clone(&self) -> Self {
+            let inner = self.inner.clone();
+            Self {
+                inner,
+                accept_compression_encodings: self.accept_compression_encodings,
+                send_compression_encodings: self.send_compression_encodings,
+                max_decoding_message_size: self.max_decoding_message_size,
+                max_encoding_message_size: self.max_encoding_message_size,
+            }
+        }
+    }
+    /// Generated gRPC service name
+    pub const SERVICE_NAME: &str = "torii.sinks.account.Account";
+    impl<T> tonic::server::NamedService for AccountServer<T> {
+        const NAME: &'static str = SERVICE_NAME;
+    }
+}
@@ -0,0 +1,85 @@
+//! gRPC service of the account sink (`torii.sinks.account.Account`).
+
+use async_trait::async_trait;
+use tonic::{Request, Response, Status};
+use torii_common::bytes_to_felt;
+
+use crate::decoder::AccountEventKind;
+use crate::proto::{
+    account_server::Account as AccountTrait, AccountActivity as ProtoAccountActivity,
+    GetAccountActivityRequest, GetAccountActivityResponse,
+};
+use crate::storage::{AccountActivity, AccountStorage, ActivityQuery};
+
+/// gRPC service implementation for account activity
+#[derive(Clone)]
+pub struct AccountService {
+    storage: AccountStorage,
+}
+
+impl AccountService {
+    pub fn new(storage: AccountStorage) -> Self {
+        Self { storage }
+    }
+}
+
+fn activity_to_proto(activity: &AccountActivity) -> ProtoAccountActivity {
+    ProtoAccountActivity {
+        account: activity.account.to_bytes_be().to_vec(),
+        kind: activity.kind.as_str().to_string(),
+        subject: activity.subject.to_bytes_be().to_vec(),
+        block_number: activity.block_number,
+        tx_hash: activity.tx_hash.to_bytes_be().to_vec(),
+        event_index: activity.event_index,
+        timestamp: activity.timestamp,
+    }
+}
+
+#[async_trait]
+impl AccountTrait for AccountService {
+    async fn get_account_activity(
+        &self,
+        request: Request<GetAccountActivityRequest>,
+    ) -> Result<Response<GetAccountActivityResponse>, Status> {
+        let req = request.into_inner();
+        let account = bytes_to_felt(&req.address)
+            .ok_or_else(|| Status::invalid_argument("Invalid address"))?;
+        let kinds = req
+            .kinds
+            .iter()
+            .map(|kind| kind.parse::<AccountEventKind>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+        let query = ActivityQuery {
+            account,
+            kinds,
+            before_block: req.before_block,
+            limit: if req.limit == 0 {
+                100
+            } else {
+                req.limit.min(1000)
+            },
+        };
+
+        let activity = self
+            .storage
+            .activity(&query)
+            .await
+            .map_err(|e| Status::internal(format!("Query failed: {e}")))?;
+        let summary = self
+            .storage
+            .summary(account)
+            .await
+            .map_err(|e| Status::internal(format!("Query failed: {e}")))?;
+
+        Ok(Response::new(GetAccountActivityResponse {
+            address: account.to_bytes_be().to_vec(),
+            activity: activity.iter().map(activity_to_proto).collect(),
+            executions: summary.executions,
+            outside_executions: summary.outside_executions,
+            first_block: summary.first_block,
+            last_block: summary.last_block,
+            class_hash: summary.class_hash.map(|hash| hash.to_bytes_be().to_vec()),
+        }))
+    }
+}
@@ -0,0 +1,71 @@
+//! Account contract identification rule
+//!
+//! Identifies account contracts (SRC-6) by inspecting their ABI for:
+//! - `__validate__` function
+//! - `__execute__` function
+
+use anyhow::Result;
+use starknet::core::types::Felt;
+use torii::etl::decoder::DecoderId;
+use torii::etl::extractor::ContractAbi;
+use torii::etl::identification::IdentificationRule;
+
+/// Account identification rule
+///
+/// A contract is identified as an account if its ABI contains the SRC-6 `__validate__`
+/// and `__execute__` entrypoints, which every account implementation (OpenZeppelin,
+/// Argent, Braavos, Cartridge) exposes. Its events are then decoded by the `account`
+/// decoder.
+pub struct AccountRule;
+
+impl AccountRule {
+    /// Create a new account identification rule
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for AccountRule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IdentificationRule for AccountRule {
+    fn name(&self) -> &'static str {
+        "account"
+    }
+
+    fn decoder_ids(&self) -> Vec<DecoderId> {
+        vec![DecoderId::new("account")]
+    }
+
+    fn identify_by_abi(
+        &self,
+        _contract_address: Felt,
+        _class_hash: Felt,
+        abi: &ContractAbi,
+    ) -> Result<Vec<DecoderId>> {
+        if abi.has_function("__validate__") && abi.has_function("__execute__") {
+            tracing::debug!(
+                target: "torii_decoder_account::identification",
+                "Contract matches account pattern"
+            );
+            Ok(vec![DecoderId::new("account")])
+        } else {
+            Ok(Vec::new())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_account_rule_decoder_ids() {
+        let rule = AccountRule::new();
+        assert_eq!(rule.name(), "account");
+        assert_eq!(rule.decoder_ids(), vec![DecoderId::new("account")]);
+    }
+}
@@ -0,0 +1,125 @@
+//! Account contract events for Torii.
+//!
+//! Tracks the lifecycle and activity of account contracts, e.g. wallets or game session
+//! accounts: owner, guardian and signer changes, class upgrades and executions, including
+//! outside executions (SNIP-9) submitted on the account's behalf.
+//!
+//! - [`AccountDecoder`] (`account`) decodes the events of the common account
+//!   implementations into [`AccountEvent`] envelopes (see [`decoder`]).
+//! - [`AccountRule`] identifies account contracts by ABI so that only their events are
+//!   decoded.
+//! - [`AccountSink`] stores the events ([`AccountStorage`], `account_activity` table) and
+//!   serves them through the `torii.sinks.account.Account/GetAccountActivity` RPC.
+
+pub mod decoder;
+pub mod grpc_service;
+pub mod identification;
+pub mod storage;
+
+// Include generated protobuf code
+pub mod proto {
+    include!("generated/torii.sinks.account.rs");
+}
+
+// File descriptor set for gRPC reflection
+pub const FILE_DESCRIPTOR_SET: &[u8] = include_bytes!("generated/account_descriptor.bin");
+
+use async_trait::async_trait;
+use std::sync::Arc;
+use torii::axum::Router;
+use torii::etl::envelope::{Envelope, TypeId};
+use torii::etl::extractor::ExtractionBatch;
+use torii::etl::sink::{EventBus, Sink, SinkContext, TopicInfo};
+use torii::ToriiResult;
+
+pub use decoder::{AccountDecoder, AccountEvent, AccountEventKind, ACCOUNT_EVENT_TYPE};
+pub use grpc_service::AccountService;
+pub use identification::AccountRule;
+pub use storage::{AccountActivity, AccountStorage, AccountSummary, ActivityQuery};
+
+/// Sink storing [`AccountEvent`]s
+pub struct AccountSink {
+    storage: AccountStorage,
+}
+
+impl AccountSink {
+    pub fn new(storage: AccountStorage) -> Self {
+        Self { storage }
+    }
+
+    pub fn storage(&self) -> &AccountStorage {
+        &self.storage
+    }
+
+    /// gRPC service to add to the Torii router
+    pub fn grpc_service(&self) -> AccountService {
+        AccountService::new(self.storage.clone())
+    }
+}
+
+#[async_trait]
+impl Sink for AccountSink {
+    fn name(&self) -> &'static str {
+        "account"
+    }
+
+    fn version(&self) -> Option<&str> {
+        Some(env!("CARGO_PKG_VERSION"))
+    }
+
+    fn descriptor_set(&self) -> Option<&'static [u8]> {
+        Some(FILE_DESCRIPTOR_SET)
+    }
+
+    fn interested_types(&self) -> Vec<TypeId> {
+        vec![TypeId::new(ACCOUNT_EVENT_TYPE)]
+    }
+
+    async fn process(&self, envelopes: &[Envelope], _batch: &ExtractionBatch) -> ToriiResult<()> {
+        let activity: Vec<AccountActivity> = envelopes
+            .iter()
+            .filter_map(|envelope| {
+                let event = envelope.downcast_ref::<AccountEvent>()?;
+                Some(AccountActivity {
+                    account: event.account,
+                    kind: event.kind,
+                    subject: event.subject,
+                    block_number: event.block_number,
+                    tx_hash: event.transaction_hash,
+                    event_index: envelope.meta.event_index.unwrap_or(0),
+                    timestamp: envelope
+                        .block_timestamp()
+                        .and_then(|timestamp| i64::try_from(timestamp).ok()),
+                })
+            })
+            .collect();
+        if activity.is_empty() {
+            return Ok(());
+        }
+
+        let inserted = self.storage.insert(&activity).await?;
+        tracing::debug!(
+            target: "torii_decoder_account::sink",
+            events = activity.len(),
+            inserted,
+            "Stored account activity"
+        );
+        Ok(())
+    }
+
+    fn topics(&self) -> Vec<TopicInfo> {
+        Vec::new()
+    }
+
+    fn build_routes(&self) -> Router {
+        Router::new()
+    }
+
+    async fn initialize(
+        &mut self,
+        _event_bus: Arc<EventBus>,
+        _context: &SinkContext,
+    ) -> ToriiResult<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,264 @@
+//! Persistence of account activity (SQLite or PostgreSQL).
+
+use anyhow::{Context, Result};
+use sqlx::any::{AnyPoolOptions, AnyRow};
+use sqlx::{Any, Pool, QueryBuilder, Row};
+use starknet::core::types::Felt;
+
+use crate::decoder::AccountEventKind;
+
+const CREATE_TABLE: &str = "CREATE TABLE IF NOT EXISTS account_activity (
+    account TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    subject TEXT NOT NULL,
+    block_number BIGINT NOT NULL,
+    tx_hash TEXT NOT NULL,
+    event_index BIGINT NOT NULL,
+    timestamp BIGINT,
+    PRIMARY KEY (tx_hash, event_index, account)
+)";
+
+const CREATE_INDEX: &str = "CREATE INDEX IF NOT EXISTS idx_account_activity_account
+    ON account_activity (account, block_number)";
+
+/// Rows inserted per statement (7 parameters each)
+const INSERT_CHUNK: usize = 1000;
+
+/// Stored account event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountActivity {
+    pub account: Felt,
+    pub kind: AccountEventKind,
+    pub subject: Felt,
+    pub block_number: u64,
+    pub tx_hash: Felt,
+    /// Index of the event within its transaction
+    pub event_index: u32,
+    /// Block timestamp (unix seconds)
+    pub timestamp: Option<i64>,
+}
+
+/// Filter of [`AccountStorage::activity`]
+#[derive(Debug, Clone)]
+pub struct ActivityQuery {
+    pub account: Felt,
+    /// Only these kinds (all when empty)
+    pub kinds: Vec<AccountEventKind>,
+    /// Only events strictly before this block
+    pub before_block: Option<u64>,
+    pub limit: u32,
+}
+
+/// Aggregates over the whole activity of an account
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccountSummary {
+    pub executions: u64,
+    pub outside_executions: u64,
+    pub first_block: Option<u64>,
+    pub last_block: Option<u64>,
+    /// Class hash of the last upgrade
+    pub class_hash: Option<Felt>,
+}
+
+/// Account events, keyed by transaction and event index
+#[derive(Clone)]
+pub struct AccountStorage {
+    pool: Pool<Any>,
+}
+
+impl AccountStorage {
+    /// Opens the store at `url` (PostgreSQL URL, `sqlite:` URL or SQLite file path).
+    pub async fn open(url: &str) -> Result<Self> {
+        sqlx::any::install_default_drivers();
+        let url = if url.starts_with("postgres://")
+            || url.starts_with("postgresql://")
+            || url.starts_with("sqlite:")
+        {
+            url.to_string()
+        } else {
+            format!("sqlite://{url}?mode=rwc")
+        };
+        let max_connections = if url.contains(":memory:") { 1 } else { 4 };
+        let pool = AnyPoolOptions::new()
+            .max_connections(max_connections)
+            .connect(&url)
+            .await
+            .context("Failed to connect to the account database")?;
+        sqlx::query(CREATE_TABLE).execute(&pool).await?;
+        sqlx::query(CREATE_INDEX).execute(&pool).await?;
+        Ok(Self { pool })
+    }
+
+    /// Stores `activity`, skipping events already stored. Returns the number inserted.
+    pub async fn insert(&self, activity: &[AccountActivity]) -> Result<u64> {
+        let mut tx = self.pool.begin().await?;
+        let mut inserted = 0;
+        for chunk in activity.chunks(INSERT_CHUNK) {
+            let mut builder = QueryBuilder::<Any>::new(
+                "INSERT INTO account_activity
+                    (account, kind, subject, block_number, tx_hash, event_index, timestamp) ",
+            );
+            builder.push_values(chunk, |mut row, event| {
+                row.push_bind(format!("{:#x}", event.account))
+                    .push_bind(event.kind.as_str())
+                    .push_bind(format!("{:#x}", event.subject))
+                    .push_bind(event.block_number as i64)
+                    .push_bind(format!("{:#x}", event.tx_hash))
+                    .push_bind(i64::from(event.event_index))
+                    .push_bind(event.timestamp);
+            });
+            builder.push(" ON CONFLICT (tx_hash, event_index, account) DO NOTHING");
+            inserted += builder.build().execute(&mut *tx).await?.rows_affected();
+        }
+        tx.commit().await?;
+        Ok(inserted)
+    }
+
+    /// Activity matching `query`, newest first
+    pub async fn activity(&self, query: &ActivityQuery) -> Result<Vec<AccountActivity>> {
+        let mut builder = QueryBuilder::<Any>::new(
+            "SELECT account, kind, subject, block_number, tx_hash, event_index, timestamp
+             FROM account_activity WHERE account = ",
+        );
+        builder.push_bind(format!("{:#x}", query.account));
+        if !query.kinds.is_empty() {
+            builder.push(" AND kind IN (");
+            let mut kinds = builder.separated(", ");
+            for kind in &query.kinds {
+                kinds.push_bind(kind.as_str());
+            }
+            builder.push(")");
+        }
+        if let Some(before_block) = query.before_block {
+            builder
+                .push(" AND block_number < ")
+                .push_bind(before_block as i64);
+        }
+        builder
+            .push(" ORDER BY block_number DESC, tx_hash, event_index DESC LIMIT ")
+            .push_bind(i64::from(query.limit));
+
+        let rows = builder.build().fetch_all(&self.pool).await?;
+        rows.iter().map(activity_from_row).collect()
+    }
+
+    /// Execution counts, active block range and current class hash of `account`
+    pub async fn summary(&self, account: Felt) -> Result<AccountSummary> {
+        let account = format!("{account:#x}");
+        let row = sqlx::query(
+            "SELECT
+                COALESCE(SUM(CASE WHEN kind IN ('executed', 'outside_executed') THEN 1 ELSE 0 END), 0)
+                    AS executions,
+                COALESCE(SUM(CASE WHEN kind = 'outside_executed' THEN 1 ELSE 0 END), 0)
+                    AS outside_executions,
+                MIN(block_number) AS first_block,
+                MAX(block_number) AS last_block
+             FROM account_activity WHERE account = $1",
+        )
+        .bind(account.as_str())
+        .fetch_one(&self.pool)
+        .await?;
+        let class_hash: Option<String> = sqlx::query_scalar(
+            "SELECT subject FROM account_activity WHERE account = $1 AND kind = 'upgraded'
+             ORDER BY block_number DESC, event_index DESC LIMIT 1",
+        )
+        .bind(account.as_str())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(AccountSummary {
+            executions: row.try_get::<i64, _>("executions")? as u64,
+            outside_executions: row.try_get::<i64, _>("outside_executions")? as u64,
+            first_block: row
+                .try_get::<Option<i64>, _>("first_block")?
+                .map(|block| block as u64),
+            last_block: row
+                .try_get::<Option<i64>, _>("last_block")?
+                .map(|block| block as u64),
+            class_hash: class_hash
+                .map(|hash| Felt::from_hex(&hash))
+                .transpose()
+                .context("Invalid class hash")?,
+        })
+    }
+}
+
+fn activity_from_row(row: &AnyRow) -> Result<AccountActivity> {
+    let felt = |column: &str| -> Result<Felt> {
+        let value: String = row.try_get(column)?;
+        Felt::from_hex(&value).with_context(|| format!("Invalid {column} {value}"))
+    };
+    Ok(AccountActivity {
+        account: felt("account")?,
+        kind: row.try_get::<String, _>("kind")?.parse()?,
+        subject: felt("subject")?,
+        block_number: row.try_get::<i64, _>("block_number")? as u64,
+        tx_hash: felt("tx_hash")?,
+        event_index: u32::try_from(row.try_get::<i64, _>("event_index")?)?,
+        timestamp: row.try_get("timestamp")?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn activity(kind: AccountEventKind, block_number: u64, tx_hash: u64) -> AccountActivity {
+        AccountActivity {
+            account: Felt::from(0xacc_u64),
+            kind,
+            subject: Felt::from(block_number),
+            block_number,
+            tx_hash: Felt::from(tx_hash),
+            event_index: 0,
+            timestamp: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn stores_and_summarizes_activity() {
+        let storage = AccountStorage::open("sqlite::memory:").await.unwrap();
+        let events = [
+            activity(AccountEventKind::Executed, 10, 1),
+            activity(AccountEventKind::Upgraded, 11, 2),
+            activity(AccountEventKind::OutsideExecuted, 12, 3),
+            activity(AccountEventKind::Upgraded, 13, 4),
+        ];
+        assert_eq!(storage.insert(&events).await.unwrap(), 4);
+        // Replayed events are skipped.
+        assert_eq!(storage.insert(&events[..1]).await.unwrap(), 0);
+
+        let query = ActivityQuery {
+            account: Felt::from(0xacc_u64),
+            kinds: vec![AccountEventKind::Upgraded],
+            before_block: None,
+            limit: 10,
+        };
+        let upgrades = storage.activity(&query).await.unwrap();
+        assert_eq!(upgrades, vec![events[3].clone(), events[1].clone()]);
+        let page = storage
+            .activity(&ActivityQuery {
+                kinds: Vec::new(),
+                before_block: Some(12),
+                ..query
+            })
+            .await
+            .unwrap();
+        assert_eq!(page, vec![events[1].clone(), events[0].clone()]);
+
+        assert_eq!(
+            storage.summary(Felt::from(0xacc_u64)).await.unwrap(),
+            AccountSummary {
+                executions: 2,
+                outside_executions: 1,
+                first_block: Some(10),
+                last_block: Some(13),
+                class_hash: Some(Felt::from(13_u64)),
+            }
+        );
+        assert_eq!(
+            storage.summary(Felt::ONE).await.unwrap(),
+            AccountSummary::default()
+        );
+    }
+}
@@ -1,7 +1,7 @@
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::pin::Pin;
 use std::str::FromStr;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 use std::task::{Context, Poll};
 use std::time::Instant;
 
@@ -31,6 +31,7 @@ use tokio::sync::{mpsc, Mutex, RwLock};
 use tokio::time::{sleep, Duration};
 use tokio_stream::{wrappers::ReceiverStream, Stream};
 use tonic::{Request, Response, Status};
+use torii_dojo::store::engine_db::EngineDbStore;
 use torii_dojo::store::postgres::PgStore;
 use torii_dojo::store::sqlite::SqliteStore;
 use torii_dojo::store::DojoStoreTrait;
@@ -128,6 +129,8 @@ struct EcsState {
     erc20_url: Option<String>,
     erc721_url: Option<String>,
     erc1155_url: Option<String>,
+    /// Dojo table definitions kept in the engine database instead of the sink database
+    dojo_table_store: OnceLock<EngineDbStore>,
     managed_tables: Mutex<Option<Arc<HashMap<String, ManagedTable>>>>,
     cached_views_sql: RwLock<Option<CachedViewsSql>>,
     entity_subscriptions: RwLock<EntitySubscriptionRegistry>,
@@ -705,6 +708,7 @@ impl EcsService {
                 erc20_url,
                 erc721_url,
                 erc1155_url,
+                dojo_table_store: OnceLock::new(),
                 managed_tables: Mutex::new(None),
                 cached_views_sql: RwLock::new(None),
                 entity_subscriptions: RwLock::new(EntitySubscriptionRegistry::default()),
@@ -901,6 +905,13 @@ impl EcsService {
         Ok(())
     }
 
+    /// Reads Dojo table definitions from `store` rather than the sink database.
+    ///
+    /// Required when the Dojo decoder persists its tables in the engine database.
+    pub fn set_dojo_table_store(&self, store: EngineDbStore) {
+        let _ = self.state.dojo_table_store.set(store);
+    }
+
     pub async fn attach_erc_databases(&self) -> Result<()> {
         if self.state.backend != DbBackend::Sqlite {
             return Ok(());
@@ -3363,6 +3374,9 @@ impl EcsService {
     }
 
     async fn load_dojo_tables(&self) -> Result<Vec<DojoTable>> {
+        if let Some(store) = self.state.dojo_table_store.get() {
+            return Ok(store.read_tables(&[]).await?);
+        }
         match self.state.backend {
             DbBackend::Sqlite => {
                 let pool = SqlitePoolOptions::new()
@@ -16,10 +16,12 @@ use torii::etl::{
     extractor::ExtractionBatch,
     sink::{EventBus, Sink, SinkContext, TopicInfo},
 };
+use torii::ToriiResult;
 use torii_dojo::external_contract::{
     resolve_external_contract, ExternalContractRegisteredBody, RegisterExternalContractCommand,
     RegisteredContractType, SharedContractTypeRegistry,
 };
+use torii_dojo::store::engine_db::EngineDbStore;
 use torii_introspect::events::{IntrospectBody, IntrospectMsg};
 
 use crate::grpc_service::{EcsService, TableKind};
@@ -64,6 +66,13 @@ impl EcsSink {
         })
     }
 
+    /// Reads Dojo table definitions from the engine database store.
+    #[must_use]
+    pub fn with_dojo_table_store(self, store: EngineDbStore) -> Self {
+        self.service.set_dojo_table_store(store);
+        self
+    }
+
     pub fn get_grpc_service_impl(&self) -> Arc<EcsService> {
         self.service.clone()
     }
@@ -167,6 +176,10 @@ impl Sink for EcsSink {
         "ecs"
     }
 
+    fn descriptor_set(&self) -> Option<&'static [u8]> {
+        Some(crate::proto::world::FILE_DESCRIPTOR_SET)
+    }
+
     fn interested_types(&self) -> Vec<TypeId> {
         vec![
             TypeId::new("introspect"),
@@ -174,7 +187,7 @@ impl Sink for EcsSink {
         ]
     }
 
-    async fn process(&self, envelopes: &[Envelope], batch: &ExtractionBatch) -> Result<()> {
+    async fn process(&self, envelopes: &[Envelope], batch: &ExtractionBatch) -> ToriiResult<()> {
         for (ordinal, event) in batch.events.iter().enumerate() {
             let context = batch
                 .get_event_context(&event.transaction_hash, event.from_address)
@@ -355,7 +368,11 @@ impl Sink for EcsSink {
         Router::new()
     }
 
-    async fn initialize(&mut self, _event_bus: Arc<EventBus>, context: &SinkContext) -> Result<()> {
+    async fn initialize(
+        &mut self,
+        _event_bus: Arc<EventBus>,
+        context: &SinkContext,
+    ) -> ToriiResult<()> {
         if let Ok(mut command_bus) = self.command_bus.write() {
             *command_bus = Some(context.command_bus.clone());
         }
@@ -11,6 +11,7 @@ use torii::axum::Router;
 use torii::etl::envelope::{Envelope, TypeId};
 use torii::etl::extractor::ExtractionBatch;
 use torii::etl::sink::{EventBus, Sink, SinkContext, TopicInfo};
+use torii::ToriiResult;
 use torii_introspect::events::{CreateTable, IntrospectBody, IntrospectMsg, UpdateTable};
 use torii_introspect::schema::TableSchema;
 use torii_runtime_common::database::DEFAULT_SQLITE_MAX_CONNECTIONS;
@@ -810,7 +811,7 @@ impl Sink for EntitiesHistoricalSink {
         vec![INTROSPECT_TYPE]
     }
 
-    async fn process(&self, envelopes: &[Envelope], batch: &ExtractionBatch) -> Result<()> {
+    async fn process(&self, envelopes: &[Envelope], batch: &ExtractionBatch) -> ToriiResult<()> {
         for envelope in envelopes {
             if envelope.type_id != INTROSPECT_TYPE {
                 continue;
@@ -897,7 +898,7 @@ impl Sink for EntitiesHistoricalSink {
         &mut self,
         _event_bus: Arc<EventBus>,
         _context: &SinkContext,
-    ) -> Result<()> {
+    ) -> ToriiResult<()> {
         self.bootstrap().await?;
         Ok(())
     }
@@ -0,0 +1,133 @@
+-- ERC1155 storage schema (PostgreSQL)
+
+CREATE SCHEMA IF NOT EXISTS erc1155;
+
+CREATE TABLE IF NOT EXISTS erc1155.token_transfers (
+    id BIGSERIAL PRIMARY KEY,
+    token BYTEA NOT NULL,
+    operator BYTEA NOT NULL,
+    from_addr BYTEA NOT NULL,
+    to_addr BYTEA NOT NULL,
+    token_id BYTEA NOT NULL,
+    amount BYTEA NOT NULL,
+    is_batch TEXT NOT NULL DEFAULT '0',
+    batch_index TEXT NOT NULL DEFAULT '0',
+    block_number TEXT NOT NULL,
+    tx_hash BYTEA NOT NULL,
+    timestamp TEXT,
+    UNIQUE(token, tx_hash, token_id, from_addr, to_addr, batch_index)
+);
+CREATE INDEX IF NOT EXISTS idx_token_transfers_token ON erc1155.token_transfers(token);
+CREATE INDEX IF NOT EXISTS idx_token_transfers_from ON erc1155.token_transfers(from_addr);
+CREATE INDEX IF NOT EXISTS idx_token_transfers_to ON erc1155.token_transfers(to_addr);
+CREATE INDEX IF NOT EXISTS idx_token_transfers_block ON erc1155.token_transfers(block_number DESC);
+CREATE INDEX IF NOT EXISTS idx_token_transfers_token_id ON erc1155.token_transfers(token, token_id);
+
+CREATE TABLE IF NOT EXISTS erc1155.token_wallet_activity (
+    id BIGSERIAL PRIMARY KEY,
+    wallet_address BYTEA NOT NULL,
+    token BYTEA NOT NULL,
+    transfer_id BIGINT NOT NULL REFERENCES erc1155.token_transfers(id),
+    direction TEXT NOT NULL CHECK(direction IN ('sent', 'received', 'both')),
+    block_number TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_token_wallet_activity_wallet_block ON erc1155.token_wallet_activity(wallet_address, block_number DESC);
+CREATE INDEX IF NOT EXISTS idx_token_wallet_activity_wallet_token ON erc1155.token_wallet_activity(wallet_address, token, block_number DESC);
+
+CREATE TABLE IF NOT EXISTS erc1155.token_operators (
+    id BIGSERIAL PRIMARY KEY,
+    token BYTEA NOT NULL,
+    owner BYTEA NOT NULL,
+    operator BYTEA NOT NULL,
+    approved TEXT NOT NULL,
+    block_number TEXT NOT NULL,
+    tx_hash BYTEA NOT NULL,
+    timestamp TEXT,
+    UNIQUE(token, owner, operator)
+);
+
+CREATE TABLE IF NOT EXISTS erc1155.token_uris (
+    token BYTEA NOT NULL,
+    token_id BYTEA NOT NULL,
+    uri TEXT,
+    metadata_json TEXT,
+    updated_at TEXT NOT NULL DEFAULT (EXTRACT(EPOCH FROM NOW())::BIGINT::TEXT),
+    PRIMARY KEY (token, token_id)
+);
+
+CREATE TABLE IF NOT EXISTS erc1155.token_attributes (
+    token BYTEA NOT NULL,
+    token_id BYTEA NOT NULL,
+    key TEXT NOT NULL,
+    value TEXT NOT NULL,
+    PRIMARY KEY (token, token_id, key)
+);
+CREATE INDEX IF NOT EXISTS idx_token_attributes_token ON erc1155.token_attributes(token);
+CREATE INDEX IF NOT EXISTS idx_token_attributes_key ON erc1155.token_attributes(key);
+CREATE INDEX IF NOT EXISTS idx_token_attributes_key_value ON erc1155.token_attributes(key, value);
+CREATE INDEX IF NOT EXISTS idx_token_attributes_token_key_value ON erc1155.token_attributes(token, key, value);
+CREATE INDEX IF NOT EXISTS idx_token_attributes_token_token_id ON erc1155.token_attributes(token, token_id);
+
+CREATE TABLE IF NOT EXISTS erc1155.facet_keys (
+    id BIGSERIAL PRIMARY KEY,
+    token BYTEA NOT NULL,
+    key_norm TEXT NOT NULL,
+    key_display TEXT NOT NULL,
+    UNIQUE(token, key_norm)
+);
+CREATE INDEX IF NOT EXISTS idx_facet_keys_token_key_norm ON erc1155.facet_keys(token, key_norm);
+
+CREATE TABLE IF NOT EXISTS erc1155.facet_values (
+    id BIGSERIAL PRIMARY KEY,
+    token BYTEA NOT NULL,
+    facet_key_id BIGINT NOT NULL REFERENCES erc1155.facet_keys(id) ON DELETE CASCADE,
+    value_norm TEXT NOT NULL,
+    value_display TEXT NOT NULL,
+    token_count TEXT NOT NULL DEFAULT '0',
+    UNIQUE(token, facet_key_id, value_norm)
+);
+CREATE INDEX IF NOT EXISTS idx_facet_values_token_key_value ON erc1155.facet_values(token, facet_key_id, value_norm);
+
+CREATE TABLE IF NOT EXISTS erc1155.facet_token_map (
+    token BYTEA NOT NULL,
+    token_id BYTEA NOT NULL,
+    facet_key_id BIGINT NOT NULL REFERENCES erc1155.facet_keys(id) ON DELETE CASCADE,
+    facet_value_id BIGINT NOT NULL REFERENCES erc1155.facet_values(id) ON DELETE CASCADE,
+    PRIMARY KEY (token, token_id, facet_key_id)
+);
+CREATE INDEX IF NOT EXISTS idx_facet_token_map_token_value_token_id ON erc1155.facet_token_map(token, facet_value_id, token_id);
+CREATE INDEX IF NOT EXISTS idx_facet_token_map_token_token_id_key_value ON erc1155.facet_token_map(token, token_id, facet_key_id, facet_value_id);
+
+CREATE TABLE IF NOT EXISTS erc1155.erc1155_balances (
+    id BIGSERIAL PRIMARY KEY,
+    contract BYTEA NOT NULL,
+    wallet BYTEA NOT NULL,
+    token_id BYTEA NOT NULL,
+    balance BYTEA NOT NULL,
+    last_block TEXT NOT NULL,
+    updated_at TEXT DEFAULT (EXTRACT(EPOCH FROM NOW())::BIGINT::TEXT),
+    UNIQUE(contract, wallet, token_id)
+);
+CREATE INDEX IF NOT EXISTS idx_erc1155_balances_contract ON erc1155.erc1155_balances(contract);
+CREATE INDEX IF NOT EXISTS idx_erc1155_balances_wallet ON erc1155.erc1155_balances(wallet);
+CREATE INDEX IF NOT EXISTS idx_erc1155_balances_contract_wallet ON erc1155.erc1155_balances(contract, wallet);
+
+CREATE TABLE IF NOT EXISTS erc1155.erc1155_balance_adjustments (
+    id BIGSERIAL PRIMARY KEY,
+    contract BYTEA NOT NULL,
+    wallet BYTEA NOT NULL,
+    token_id BYTEA NOT NULL,
+    computed_balance BYTEA NOT NULL,
+    actual_balance BYTEA NOT NULL,
+    adjusted_at_block TEXT NOT NULL,
+    tx_hash BYTEA NOT NULL,
+    created_at TEXT DEFAULT (EXTRACT(EPOCH FROM NOW())::BIGINT::TEXT)
+);
+CREATE INDEX IF NOT EXISTS idx_erc1155_adjustments_wallet ON erc1155.erc1155_balance_adjustments(wallet);
+
+CREATE TABLE IF NOT EXISTS erc1155.token_metadata (
+    token BYTEA PRIMARY KEY,
+    name TEXT,
+    symbol TEXT,
+    total_supply BYTEA
+);
@@ -0,0 +1,6 @@
+-- Account inventory (GetInventory): non-zero balances of a wallet in (contract, token ID)
+-- order. Covers the balance columns so pages are read from the index alone.
+CREATE INDEX IF NOT EXISTS idx_erc1155_balances_inventory
+    ON erc1155.erc1155_balances(wallet, contract, octet_length(token_id), token_id)
+    INCLUDE (balance, last_block)
+    WHERE balance <> '\x00'::bytea;
@@ -0,0 +1,146 @@
+-- ERC1155 storage schema (SQLite)
+
+-- Token transfers table (both single and batch transfers)
+CREATE TABLE IF NOT EXISTS token_transfers (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    token BLOB NOT NULL,
+    operator BLOB NOT NULL,
+    from_addr BLOB NOT NULL,
+    to_addr BLOB NOT NULL,
+    token_id BLOB NOT NULL,
+    amount BLOB NOT NULL,
+    is_batch TEXT NOT NULL DEFAULT '0',
+    batch_index TEXT NOT NULL DEFAULT '0',
+    block_number TEXT NOT NULL,
+    tx_hash BLOB NOT NULL,
+    timestamp TEXT,
+    UNIQUE(token, tx_hash, token_id, from_addr, to_addr, batch_index)
+);
+CREATE INDEX IF NOT EXISTS idx_token_transfers_token ON token_transfers(token);
+CREATE INDEX IF NOT EXISTS idx_token_transfers_from ON token_transfers(from_addr);
+CREATE INDEX IF NOT EXISTS idx_token_transfers_to ON token_transfers(to_addr);
+CREATE INDEX IF NOT EXISTS idx_token_transfers_block ON token_transfers(block_number DESC);
+CREATE INDEX IF NOT EXISTS idx_token_transfers_token_id ON token_transfers(token, token_id);
+
+-- Wallet activity table for efficient OR queries
+CREATE TABLE IF NOT EXISTS token_wallet_activity (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    wallet_address BLOB NOT NULL,
+    token BLOB NOT NULL,
+    transfer_id INTEGER NOT NULL,
+    direction TEXT NOT NULL CHECK(direction IN ('sent', 'received', 'both')),
+    block_number TEXT NOT NULL,
+    FOREIGN KEY (transfer_id) REFERENCES token_transfers(id)
+);
+CREATE INDEX IF NOT EXISTS idx_token_wallet_activity_wallet_block ON token_wallet_activity(wallet_address, block_number DESC);
+CREATE INDEX IF NOT EXISTS idx_token_wallet_activity_wallet_token ON token_wallet_activity(wallet_address, token, block_number DESC);
+
+-- Operator approvals
+CREATE TABLE IF NOT EXISTS token_operators (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    token BLOB NOT NULL,
+    owner BLOB NOT NULL,
+    operator BLOB NOT NULL,
+    approved TEXT NOT NULL,
+    block_number TEXT NOT NULL,
+    tx_hash BLOB NOT NULL,
+    timestamp TEXT,
+    UNIQUE(token, owner, operator)
+);
+
+-- URI metadata
+CREATE TABLE IF NOT EXISTS token_uris (
+    token BLOB NOT NULL,
+    token_id BLOB NOT NULL,
+    uri TEXT,
+    metadata_json TEXT,
+    updated_at TEXT NOT NULL DEFAULT (strftime('%s', 'now')),
+    PRIMARY KEY (token, token_id)
+);
+
+CREATE TABLE IF NOT EXISTS token_attributes (
+    token BLOB NOT NULL,
+    token_id BLOB NOT NULL,
+    key TEXT NOT NULL,
+    value TEXT NOT NULL,
+    PRIMARY KEY (token, token_id, key),
+    FOREIGN KEY (token, token_id) REFERENCES token_uris(token, token_id)
+);
+CREATE INDEX IF NOT EXISTS idx_token_attributes_token ON token_attributes(token);
+CREATE INDEX IF NOT EXISTS idx_token_attributes_key ON token_attributes(key);
+CREATE INDEX IF NOT EXISTS idx_token_attributes_key_value ON token_attributes(key, value);
+CREATE INDEX IF NOT EXISTS idx_token_attributes_token_key_value ON token_attributes(token, key, value);
+CREATE INDEX IF NOT EXISTS idx_token_attributes_token_token_id ON token_attributes(token, token_id);
+
+CREATE TABLE IF NOT EXISTS facet_keys (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    token BLOB NOT NULL,
+    key_norm TEXT NOT NULL,
+    key_display TEXT NOT NULL,
+    UNIQUE(token, key_norm)
+);
+CREATE INDEX IF NOT EXISTS idx_facet_keys_token_key_norm ON facet_keys(token, key_norm);
+
+CREATE TABLE IF NOT EXISTS facet_values (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    token BLOB NOT NULL,
+    facet_key_id INTEGER NOT NULL,
+    value_norm TEXT NOT NULL,
+    value_display TEXT NOT NULL,
+    token_count TEXT NOT NULL DEFAULT '0',
+    UNIQUE(token, facet_key_id, value_norm),
+    FOREIGN KEY (facet_key_id) REFERENCES facet_keys(id) ON DELETE CASCADE
+);
+CREATE INDEX IF NOT EXISTS idx_facet_values_token_key_value ON facet_values(token, facet_key_id, value_norm);
+
+CREATE TABLE IF NOT EXISTS facet_token_map (
+    token BLOB NOT NULL,
+    token_id BLOB NOT NULL,
+    facet_key_id INTEGER NOT NULL,
+    facet_value_id INTEGER NOT NULL,
+    PRIMARY KEY (token, token_id, facet_key_id),
+    FOREIGN KEY (facet_key_id) REFERENCES facet_keys(id) ON DELETE CASCADE,
+    FOREIGN KEY (facet_value_id) REFERENCES facet_values(id) ON DELETE CASCADE
+);
+CREATE INDEX IF NOT EXISTS idx_facet_token_map_token_value_token_id ON facet_token_map(token, facet_value_id, token_id);
+CREATE INDEX IF NOT EXISTS idx_facet_token_map_token_token_id_key_value ON facet_token_map(token, token_id, facet_key_id, facet_value_id);
+
+-- Balance tracking tables
+-- Tracks current balance per (contract, wallet, token_id) tuple
+CREATE TABLE IF NOT EXISTS erc1155_balances (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    contract BLOB NOT NULL,
+    wallet BLOB NOT NULL,
+    token_id BLOB NOT NULL,
+    balance BLOB NOT NULL,
+    last_block TEXT NOT NULL,
+    updated_at TEXT DEFAULT (strftime('%s', 'now')),
+    UNIQUE(contract, wallet, token_id)
+);
+CREATE INDEX IF NOT EXISTS idx_erc1155_balances_contract ON erc1155_balances(contract);
+CREATE INDEX IF NOT EXISTS idx_erc1155_balances_wallet ON erc1155_balances(wallet);
+CREATE INDEX IF NOT EXISTS idx_erc1155_balances_contract_wallet ON erc1155_balances(contract, wallet);
+CREATE INDEX IF NOT EXISTS idx_erc1155_balances_contract_wallet_token_id_ord ON erc1155_balances(contract, wallet, length(token_id), token_id);
+CREATE INDEX IF NOT EXISTS idx_erc1155_balances_wallet_contract_token_id_ord ON erc1155_balances(wallet, contract, length(token_id), token_id);
+
+-- Balance adjustments table for audit trail
+CREATE TABLE IF NOT EXISTS erc1155_balance_adjustments (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    contract BLOB NOT NULL,
+    wallet BLOB NOT NULL,
+    token_id BLOB NOT NULL,
+    computed_balance BLOB NOT NULL,
+    actual_balance BLOB NOT NULL,
+    adjusted_at_block TEXT NOT NULL,
+    tx_hash BLOB NOT NULL,
+    created_at TEXT DEFAULT (strftime('%s', 'now'))
+);
+CREATE INDEX IF NOT EXISTS idx_erc1155_adjustments_wallet ON erc1155_balance_adjustments(wallet);
+
+-- Token metadata table
+CREATE TABLE IF NOT EXISTS token_metadata (
+    token BLOB PRIMARY KEY,
+    name TEXT,
+    symbol TEXT,
+    total_supply BLOB
+);
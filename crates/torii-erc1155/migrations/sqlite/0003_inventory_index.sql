@@ -0,0 +1,5 @@
+-- Account inventory (GetInventory): non-zero balances of a wallet in (contract, token ID)
+-- order. Covers the balance columns so pages are read from the index alone.
+CREATE INDEX IF NOT EXISTS idx_erc1155_balances_inventory
+    ON erc1155_balances(wallet, contract, length(token_id), token_id, balance, last_block)
+    WHERE balance != X'00';
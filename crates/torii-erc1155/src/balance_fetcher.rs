@@ -8,9 +8,9 @@
 use anyhow::{Context, Result};
 use starknet::core::types::{requests::CallRequest, BlockId, Felt, FunctionCall, U256};
 use starknet::macros::selector;
-use starknet::providers::jsonrpc::{HttpTransport, JsonRpcClient};
 use starknet::providers::{Provider, ProviderRequestData, ProviderResponseData};
 use std::sync::Arc;
+use torii_common::RpcProvider;
 
 /// Request for fetching an ERC1155 balance at a specific block
 #[derive(Debug, Clone)]
@@ -31,12 +31,12 @@ pub struct Erc1155BalanceFetchRequest {
 /// Used for detecting and correcting balance inconsistencies caused by
 /// genesis allocations, airdrops, or other transfers without events.
 pub struct Erc1155BalanceFetcher {
-    provider: Arc<JsonRpcClient<HttpTransport>>,
+    provider: Arc<RpcProvider>,
 }
 
 impl Erc1155BalanceFetcher {
     /// Create a new balance fetcher with the given provider
-    pub fn new(provider: Arc<JsonRpcClient<HttpTransport>>) -> Self {
+    pub fn new(provider: Arc<RpcProvider>) -> Self {
         Self { provider }
     }
 
@@ -7,9 +7,10 @@ use starknet::core::types::{ByteArray, EmittedEvent, Felt, U256};
 use starknet::core::utils::parse_cairo_short_string;
 use starknet::macros::selector;
 use std::any::Any;
-use std::collections::HashMap;
-use torii::etl::{Decoder, Envelope, TypedBody};
-use torii_common::bytes_to_u256;
+use torii::etl::decoder::SelectorAliases;
+use torii::etl::{Decoder, Envelope, EnvelopeMetadata, EnvelopeSlab, TypedBody};
+use torii::ToriiResult;
+use torii_common::{bytes_to_u256, substitute_token_id};
 
 /// TransferSingle event from ERC1155 token
 #[derive(Debug, Clone)]
@@ -38,6 +39,10 @@ impl TypedBody for TransferSingle {
     fn as_any_mut(&mut self) -> &mut dyn Any {
         self
     }
+
+    fn debug_repr(&self) -> Option<String> {
+        Some(format!("{self:?}"))
+    }
 }
 
 /// TransferBatch event from ERC1155 token (denormalized into individual transfers)
@@ -69,6 +74,10 @@ impl TypedBody for TransferBatch {
     fn as_any_mut(&mut self) -> &mut dyn Any {
         self
     }
+
+    fn debug_repr(&self) -> Option<String> {
+        Some(format!("{self:?}"))
+    }
 }
 
 /// ApprovalForAll event from ERC1155 token
@@ -94,6 +103,10 @@ impl TypedBody for OperatorApproval {
     fn as_any_mut(&mut self) -> &mut dyn Any {
         self
     }
+
+    fn debug_repr(&self) -> Option<String> {
+        Some(format!("{self:?}"))
+    }
 }
 
 /// URI event from ERC1155 token
@@ -118,6 +131,10 @@ impl TypedBody for UriUpdate {
     fn as_any_mut(&mut self) -> &mut dyn Any {
         self
     }
+
+    fn debug_repr(&self) -> Option<String> {
+        Some(format!("{self:?}"))
+    }
 }
 
 /// ERC1155 event decoder
@@ -128,11 +145,23 @@ impl TypedBody for UriUpdate {
 /// - ApprovalForAll(owner, operator, approved)
 ///
 /// Supports both modern (keys) and legacy (data-only) formats.
-pub struct Erc1155Decoder;
+pub struct Erc1155Decoder {
+    aliases: SelectorAliases,
+}
 
 impl Erc1155Decoder {
+    /// Decoder of the flat events and of OpenZeppelin's `ERC1155Component` events emitted
+    /// under the `ERC1155Event` variant.
     pub fn new() -> Self {
-        Self
+        Self {
+            aliases: SelectorAliases::new().with_component(selector!("ERC1155Event")),
+        }
+    }
+
+    /// Also decodes events emitted under the component variants and aliases of `aliases`.
+    pub fn with_selector_aliases(mut self, aliases: SelectorAliases) -> Self {
+        self.aliases = self.aliases.merge(&aliases);
+        self
     }
 
     fn felt_to_u256(felt: Felt) -> U256 {
@@ -277,16 +306,20 @@ impl Erc1155Decoder {
             transaction_hash: event.transaction_hash,
         };
 
-        let mut metadata = HashMap::new();
-        metadata.insert("token".to_string(), format!("{:#x}", event.from_address));
+        let mut metadata = EnvelopeMetadata::new();
+        metadata.insert(
+            "token".to_string(),
+            format!("{:#x}", event.from_address).into(),
+        );
         metadata.insert(
             "block_number".to_string(),
-            event.block_number.unwrap_or(0).to_string(),
+            event.block_number.unwrap_or(0).to_string().into(),
         );
         metadata.insert(
             "tx_hash".to_string(),
-            format!("{:#x}", event.transaction_hash),
+            format!("{:#x}", event.transaction_hash).into(),
         );
+        metadata.insert("token_id".to_string(), format!("{id:#x}").into());
 
         let envelope_id = format!(
             "erc1155_transfer_single_{}_{}",
@@ -294,11 +327,7 @@ impl Erc1155Decoder {
             format!("{:#x}", event.transaction_hash)
         );
 
-        Ok(Some(Envelope::new(
-            envelope_id,
-            Box::new(transfer),
-            metadata,
-        )))
+        Ok(Some(Envelope::from_body(envelope_id, transfer, metadata)))
     }
 
     /// Decode TransferBatch event into multiple envelopes (one per id/value pair)
@@ -420,8 +449,11 @@ impl Erc1155Decoder {
             return Ok(vec![]);
         }
 
-        // Create envelope for each id/value pair
-        let mut envelopes = Vec::new();
+        // Create envelope for each id/value pair (bodies share one slab allocation)
+        let mut slab = EnvelopeSlab::with_capacity(ids.len());
+        let token_hex = slab.hex(event.from_address);
+        let tx_hash_hex = slab.hex(event.transaction_hash);
+        let block_number = event.block_number.unwrap_or(0);
         for (i, (id, value)) in ids.iter().zip(values.iter()).enumerate() {
             let transfer = TransferBatch {
                 operator,
@@ -431,33 +463,23 @@ impl Erc1155Decoder {
                 value: *value,
                 batch_index: i as u32,
                 token: event.from_address,
-                block_number: event.block_number.unwrap_or(0),
+                block_number,
                 transaction_hash: event.transaction_hash,
             };
 
-            let mut metadata = HashMap::new();
-            metadata.insert("token".to_string(), format!("{:#x}", event.from_address));
-            metadata.insert(
-                "block_number".to_string(),
-                event.block_number.unwrap_or(0).to_string(),
-            );
-            metadata.insert(
-                "tx_hash".to_string(),
-                format!("{:#x}", event.transaction_hash),
-            );
-            metadata.insert("batch_index".to_string(), i.to_string());
+            let mut metadata = EnvelopeMetadata::new();
+            metadata.insert("token".to_string(), token_hex.clone());
+            metadata.insert("block_number".to_string(), block_number.to_string().into());
+            metadata.insert("tx_hash".to_string(), tx_hash_hex.clone());
+            metadata.insert("batch_index".to_string(), i.to_string().into());
+            metadata.insert("token_id".to_string(), format!("{id:#x}").into());
 
-            let envelope_id = format!(
-                "erc1155_transfer_batch_{}_{}_{}",
-                event.block_number.unwrap_or(0),
-                format!("{:#x}", event.transaction_hash),
-                i
-            );
+            let envelope_id = format!("erc1155_transfer_batch_{block_number}_{tx_hash_hex}_{i}");
 
-            envelopes.push(Envelope::new(envelope_id, Box::new(transfer), metadata));
+            slab.push(envelope_id, transfer, metadata, None);
         }
 
-        Ok(envelopes)
+        Ok(slab.into_envelopes())
     }
 
     /// Decode ApprovalForAll event into envelope
@@ -498,15 +520,18 @@ impl Erc1155Decoder {
             transaction_hash: event.transaction_hash,
         };
 
-        let mut metadata = HashMap::new();
-        metadata.insert("token".to_string(), format!("{:#x}", event.from_address));
+        let mut metadata = EnvelopeMetadata::new();
+        metadata.insert(
+            "token".to_string(),
+            format!("{:#x}", event.from_address).into(),
+        );
         metadata.insert(
             "block_number".to_string(),
-            event.block_number.unwrap_or(0).to_string(),
+            event.block_number.unwrap_or(0).to_string().into(),
         );
         metadata.insert(
             "tx_hash".to_string(),
-            format!("{:#x}", event.transaction_hash),
+            format!("{:#x}", event.transaction_hash).into(),
         );
 
         let envelope_id = format!(
@@ -515,11 +540,7 @@ impl Erc1155Decoder {
             format!("{:#x}", event.transaction_hash)
         );
 
-        Ok(Some(Envelope::new(
-            envelope_id,
-            Box::new(approval),
-            metadata,
-        )))
+        Ok(Some(Envelope::from_body(envelope_id, approval, metadata)))
     }
 
     /// Decode URI event into envelope
@@ -528,6 +549,8 @@ impl Erc1155Decoder {
     /// - keys[0]: URI selector
     /// - keys[1]: token id (felt-encoded)
     /// - data: URI payload (short string or ByteArray)
+    ///
+    /// The `{id}` placeholder is substituted, so the stored URI is the token's own.
     async fn decode_uri(&self, event: &EmittedEvent) -> Result<Option<Envelope>> {
         if event.keys.len() < 2 || event.data.is_empty() {
             return Ok(None);
@@ -537,6 +560,7 @@ impl Erc1155Decoder {
         let Some(uri) = Self::decode_string_result(&event.data) else {
             return Ok(None);
         };
+        let uri = substitute_token_id(&uri, token_id);
 
         let uri_update = UriUpdate {
             token: event.from_address,
@@ -546,15 +570,18 @@ impl Erc1155Decoder {
             transaction_hash: event.transaction_hash,
         };
 
-        let mut metadata = HashMap::new();
-        metadata.insert("token".to_string(), format!("{:#x}", event.from_address));
+        let mut metadata = EnvelopeMetadata::new();
+        metadata.insert(
+            "token".to_string(),
+            format!("{:#x}", event.from_address).into(),
+        );
         metadata.insert(
             "block_number".to_string(),
-            event.block_number.unwrap_or(0).to_string(),
+            event.block_number.unwrap_or(0).to_string().into(),
         );
         metadata.insert(
             "tx_hash".to_string(),
-            format!("{:#x}", event.transaction_hash),
+            format!("{:#x}", event.transaction_hash).into(),
         );
 
         let envelope_id = format!(
@@ -563,11 +590,7 @@ impl Erc1155Decoder {
             format!("{:#x}", event.transaction_hash)
         );
 
-        Ok(Some(Envelope::new(
-            envelope_id,
-            Box::new(uri_update),
-            metadata,
-        )))
+        Ok(Some(Envelope::from_body(envelope_id, uri_update, metadata)))
     }
 }
 
@@ -583,7 +606,13 @@ impl Decoder for Erc1155Decoder {
         "erc1155"
     }
 
-    async fn decode_event(&self, event: &EmittedEvent) -> Result<Vec<Envelope>> {
+    fn decoder_version(&self) -> Option<&str> {
+        Some(env!("CARGO_PKG_VERSION"))
+    }
+
+    async fn decode_event(&self, event: &EmittedEvent) -> ToriiResult<Vec<Envelope>> {
+        let event = self.aliases.normalize(event);
+        let event: &EmittedEvent = &event;
         if event.keys.is_empty() {
             return Ok(Vec::new());
         }
@@ -595,7 +624,7 @@ impl Decoder for Erc1155Decoder {
                 return Ok(vec![envelope]);
             }
         } else if selector == Self::transfer_batch_selector() {
-            return self.decode_transfer_batch(event).await;
+            return Ok(self.decode_transfer_batch(event).await?);
         } else if selector == Self::approval_for_all_selector() {
             if let Some(envelope) = self.decode_approval_for_all(event).await? {
                 return Ok(vec![envelope]);
@@ -625,6 +654,8 @@ impl Decoder for Erc1155Decoder {
 mod tests {
     use super::*;
 
+    torii_test_utils::decoder_golden_test!(Erc1155Decoder::new(), "fixtures/erc1155.json");
+
     #[tokio::test]
     async fn test_decode_transfer_single_modern() {
         let decoder = Erc1155Decoder::new();
@@ -775,6 +806,13 @@ mod tests {
         assert_eq!(first.value, U256::from(101u64));
         assert_eq!(second.id, U256::from(12u64));
         assert_eq!(second.value, U256::from(102u64));
+        assert_eq!(
+            envelopes[1].id,
+            "erc1155_transfer_batch_102_0xabcf_1".to_string()
+        );
+        assert_eq!(&*envelopes[1].metadata["token"], "0x123");
+        assert_eq!(&*envelopes[0].metadata["token_id"], "0xb");
+        assert_eq!(&*envelopes[1].metadata["token_id"], "0xc");
     }
 
     #[tokio::test]
@@ -807,4 +845,29 @@ mod tests {
         assert_eq!(uri.token_id, U256::from(7u64));
         assert_eq!(uri.uri, "abc".to_string());
     }
+
+    #[tokio::test]
+    async fn test_decode_uri_event_substitutes_id() {
+        let decoder = Erc1155Decoder::new();
+
+        let event = EmittedEvent {
+            from_address: Felt::from(0x123u64),
+            keys: vec![Erc1155Decoder::uri_selector(), Felt::from(0x2au64)],
+            data: vec![starknet::core::utils::cairo_short_string_to_felt("ipfs://x/{id}").unwrap()],
+            block_hash: None,
+            block_number: Some(104),
+            transaction_hash: Felt::from(0xabd1u64),
+        };
+
+        let envelopes = decoder.decode_event(&event).await.unwrap();
+        let uri = envelopes[0]
+            .body
+            .as_any()
+            .downcast_ref::<UriUpdate>()
+            .unwrap();
+        assert_eq!(
+            uri.uri,
+            "ipfs://x/000000000000000000000000000000000000000000000000000000000000002a"
+        );
+    }
 }
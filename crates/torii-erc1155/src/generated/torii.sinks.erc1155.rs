@@ -95,7 +95,7 @@ pub struct TransferFilter {
     /// Token contract whitelist (empty = all tokens)
     #[prost(bytes = "vec", repeated, tag = "5")]
     pub tokens: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
-    /// Specific token IDs to filter
+    /// Specific token IDs to filter (batch transfers are matched per transferred id)
     #[prost(bytes = "vec", repeated, tag = "6")]
     pub token_ids: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
     /// Minimum block number (inclusive)
@@ -127,6 +127,9 @@ pub struct GetTransfersRequest {
     /// Maximum number of transfers to return (default: 100, max: 1000)
     #[prost(uint32, tag = "3")]
     pub limit: u32,
+    /// Transfer fields to return (empty = all). Other fields are left unset.
+    #[prost(string, repeated, tag = "4")]
+    pub field_mask: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
 /// Response for GetTransfers RPC
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -138,6 +141,22 @@ pub struct GetTransfersResponse {
     #[prost(message, optional, tag = "2")]
     pub next_cursor: ::core::option::Option<Cursor>,
 }
+/// Request for ReplayTransfers RPC
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReplayTransfersRequest {
+    /// Minimum block number (inclusive)
+    #[prost(uint64, optional, tag = "1")]
+    pub block_from: ::core::option::Option<u64>,
+    /// Maximum block number (inclusive)
+    #[prost(uint64, optional, tag = "2")]
+    pub block_to: ::core::option::Option<u64>,
+    /// Token whitelist (empty = all tokens)
+    #[prost(bytes = "vec", repeated, tag = "3")]
+    pub tokens: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+    /// Rows read from storage per page (default: 500, max: 5000)
+    #[prost(uint32, tag = "4")]
+    pub page_size: u32,
+}
 /// Request for SubscribeTransfers RPC
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SubscribeTransfersRequest {
@@ -149,6 +168,13 @@ pub struct SubscribeTransfersRequest {
     pub filter: ::core::option::Option<TransferFilter>,
 }
 /// Update message for transfer subscriptions
+/// Last message of a subscription stream: the server is shutting down
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct StreamShutdown {
+    /// Last block fully indexed; resume (e.g. with ReplayTransfers) from the next block
+    #[prost(uint64, tag = "1")]
+    pub block_number: u64,
+}
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct TransferUpdate {
     /// The transfer event
@@ -157,6 +183,36 @@ pub struct TransferUpdate {
     /// Unix timestamp when the update was generated
     #[prost(int64, tag = "2")]
     pub timestamp: i64,
+    /// Set on the last update when the server shuts down (transfer is then absent)
+    #[prost(message, optional, tag = "3")]
+    pub shutdown: ::core::option::Option<StreamShutdown>,
+}
+/// Request for WatchAddresses RPC (each message replaces the watched set)
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct WatchAddressesRequest {
+    /// Client identifier for logging/debugging
+    #[prost(string, tag = "1")]
+    pub client_id: ::prost::alloc::string::String,
+    /// Account addresses to watch (32 bytes each, max 10000)
+    #[prost(bytes = "vec", repeated, tag = "2")]
+    pub addresses: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+    /// Only transfers of these token IDs (empty = all, max 1000). Batch transfers
+    /// are matched per transferred id.
+    #[prost(bytes = "vec", repeated, tag = "3")]
+    pub token_ids: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+}
+/// Update pushed to address watchers: a transfer involving a watched address
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct WatchUpdate {
+    /// The transfer event (from, to or operator is watched)
+    #[prost(message, optional, tag = "1")]
+    pub transfer: ::core::option::Option<TokenTransfer>,
+    /// Unix timestamp when the update was generated
+    #[prost(int64, tag = "2")]
+    pub timestamp: i64,
+    /// Set on the last update when the server shuts down (transfer is then absent)
+    #[prost(message, optional, tag = "3")]
+    pub shutdown: ::core::option::Option<StreamShutdown>,
 }
 /// Request for GetBalance RPC
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -181,6 +237,67 @@ pub struct GetBalanceResponse {
     #[prost(uint64, tag = "2")]
     pub last_block: u64,
 }
+/// Cursor for GetInventory (opaque to clients)
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct InventoryCursor {
+    /// Token contract address of the last returned balance (32 bytes)
+    #[prost(bytes = "vec", tag = "1")]
+    pub contract: ::prost::alloc::vec::Vec<u8>,
+    /// Token ID of the last returned balance (U256 bytes)
+    #[prost(bytes = "vec", tag = "2")]
+    pub token_id: ::prost::alloc::vec::Vec<u8>,
+}
+/// Non-zero balance held by an account
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct InventoryItem {
+    /// Token contract address (32 bytes)
+    #[prost(bytes = "vec", tag = "1")]
+    pub contract: ::prost::alloc::vec::Vec<u8>,
+    /// Token ID as U256 (variable length, up to 32 bytes)
+    #[prost(bytes = "vec", tag = "2")]
+    pub token_id: ::prost::alloc::vec::Vec<u8>,
+    /// Balance as U256 (variable length, up to 32 bytes)
+    #[prost(bytes = "vec", tag = "3")]
+    pub balance: ::prost::alloc::vec::Vec<u8>,
+    /// Last block number where balance was updated
+    #[prost(uint64, tag = "4")]
+    pub last_block: u64,
+    /// On-chain token URI (if requested and known)
+    #[prost(string, optional, tag = "5")]
+    pub uri: ::core::option::Option<::prost::alloc::string::String>,
+    /// Raw metadata JSON (if requested and cached)
+    #[prost(string, optional, tag = "6")]
+    pub metadata_json: ::core::option::Option<::prost::alloc::string::String>,
+}
+/// Request for GetInventory RPC
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetInventoryRequest {
+    /// Account address (32 bytes)
+    #[prost(bytes = "vec", tag = "1")]
+    pub account: ::prost::alloc::vec::Vec<u8>,
+    /// Only list these token contracts (empty = all)
+    #[prost(bytes = "vec", repeated, tag = "2")]
+    pub contracts: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+    /// Cursor from previous response (omit for first page)
+    #[prost(message, optional, tag = "3")]
+    pub cursor: ::core::option::Option<InventoryCursor>,
+    /// Maximum number of balances to return (default: 100, max: 1000)
+    #[prost(uint32, tag = "4")]
+    pub limit: u32,
+    /// Inline token URI and metadata JSON in each item
+    #[prost(bool, tag = "5")]
+    pub include_metadata: bool,
+}
+/// Response for GetInventory RPC
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetInventoryResponse {
+    /// Non-zero balances, ordered by contract then token ID
+    #[prost(message, repeated, tag = "1")]
+    pub items: ::prost::alloc::vec::Vec<InventoryItem>,
+    /// Cursor for next page (absent if no more results)
+    #[prost(message, optional, tag = "2")]
+    pub next_cursor: ::core::option::Option<InventoryCursor>,
+}
 /// OR-within-key filter values; AND logic is applied across keys.
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct AttributeFilter {
@@ -242,6 +359,32 @@ pub struct QueryTokensByAttributesResponse {
     #[prost(message, repeated, tag = "4")]
     pub facets: ::prost::alloc::vec::Vec<AttributeFacetCount>,
 }
+/// Request for GetTokenImage RPC
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetTokenImageRequest {
+    /// Token contract address (32 bytes)
+    #[prost(bytes = "vec", tag = "1")]
+    pub token: ::prost::alloc::vec::Vec<u8>,
+    /// Token ID as U256 (variable length, up to 32 bytes)
+    #[prost(bytes = "vec", tag = "2")]
+    pub token_id: ::prost::alloc::vec::Vec<u8>,
+}
+/// Response for GetTokenImage RPC (signed URLs of the assets in object storage)
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetTokenImageResponse {
+    /// Signed URL of the cached image
+    #[prost(string, tag = "1")]
+    pub image_url: ::prost::alloc::string::String,
+    /// Content type of the image
+    #[prost(string, tag = "2")]
+    pub content_type: ::prost::alloc::string::String,
+    /// Signed URL of the metadata JSON (absent if the token has none)
+    #[prost(string, optional, tag = "3")]
+    pub metadata_url: ::core::option::Option<::prost::alloc::string::String>,
+    /// Unix timestamp (seconds) when the URLs expire
+    #[prost(int64, tag = "4")]
+    pub expires_at: i64,
+}
 /// Request for GetTokenMetadata RPC
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GetTokenMetadataRequest {
@@ -254,6 +397,10 @@ pub struct GetTokenMetadataRequest {
     /// Maximum number of entries to return (default: 100, max: 1000).
     #[prost(uint32, tag = "3")]
     pub limit: u32,
+    /// Token IDs (U256, up to 32 bytes each) whose URI and metadata to return.
+    /// Requires token; at most 1000.
+    #[prost(bytes = "vec", repeated, tag = "4")]
+    pub token_ids: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
 }
 /// Token metadata entry
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -271,6 +418,22 @@ pub struct TokenMetadataEntry {
     #[prost(bytes = "vec", optional, tag = "4")]
     pub total_supply: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
 }
+/// Per token ID metadata entry
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TokenIdMetadataEntry {
+    /// Token contract address (32 bytes)
+    #[prost(bytes = "vec", tag = "1")]
+    pub token: ::prost::alloc::vec::Vec<u8>,
+    /// Token ID as U256 (variable length, up to 32 bytes)
+    #[prost(bytes = "vec", tag = "2")]
+    pub token_id: ::prost::alloc::vec::Vec<u8>,
+    /// Token URI, with `{id}` substituted
+    #[prost(string, optional, tag = "3")]
+    pub uri: ::core::option::Option<::prost::alloc::string::String>,
+    /// Resolved JSON metadata (absent until fetched)
+    #[prost(string, optional, tag = "4")]
+    pub metadata_json: ::core::option::Option<::prost::alloc::string::String>,
+}
 /// Response for GetTokenMetadata RPC
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GetTokenMetadataResponse {
@@ -280,6 +443,9 @@ pub struct GetTokenMetadataResponse {
     /// Cursor for next page (absent if no more results).
     #[prost(bytes = "vec", optional, tag = "2")]
     pub next_cursor: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+    /// Metadata of the requested token IDs that have a known URI
+    #[prost(message, repeated, tag = "3")]
+    pub token_ids: ::prost::alloc::vec::Vec<TokenIdMetadataEntry>,
 }
 /// Token row returned by collection endpoints
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -486,6 +652,14 @@ pub mod erc1155_server {
             tonic::Response<super::GetBalanceResponse>,
             tonic::Status,
         >;
+        /// List the non-zero balances of an account across token contracts
+        async fn get_inventory(
+            &self,
+            request: tonic::Request<super::GetInventoryRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetInventoryResponse>,
+            tonic::Status,
+        >;
         /// Get token metadata (name, symbol)
         async fn get_token_metadata(
             &self,
@@ -494,6 +668,14 @@ pub mod erc1155_server {
             tonic::Response<super::GetTokenMetadataResponse>,
             tonic::Status,
         >;
+        /// Get signed URLs of the image and metadata JSON cached in object storage
+        async fn get_token_image(
+            &self,
+            request: tonic::Request<super::GetTokenImageRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetTokenImageResponse>,
+            tonic::Status,
+        >;
         /// Query token IDs by flattened metadata attributes (supports intersections)
         async fn query_tokens_by_attributes(
             &self,
@@ -540,6 +722,35 @@ pub mod erc1155_server {
             tonic::Response<Self::SubscribeTransfersStream>,
             tonic::Status,
         >;
+        /// Server streaming response type for the WatchAddresses method.
+        type WatchAddressesStream: tonic::codegen::tokio_stream::Stream<
+                Item = std::result::Result<super::WatchUpdate, tonic::Status>,
+            >
+            + std::marker::Send
+            + 'static;
+        /// Watch account addresses: pushes only transfers involving them.
+        /// Send a new request on the stream to replace the watched set.
+        async fn watch_addresses(
+            &self,
+            request: tonic::Request<tonic::Streaming<super::WatchAddressesRequest>>,
+        ) -> std::result::Result<
+            tonic::Response<Self::WatchAddressesStream>,
+            tonic::Status,
+        >;
+        /// Server streaming response type for the ReplayTransfers method.
+        type ReplayTransfersStream: tonic::codegen::tokio_stream::Stream<
+                Item = std::result::Result<super::TokenTransfer, tonic::Status>,
+            >
+            + std::marker::Send
+            + 'static;
+        /// Stream all stored transfers in a block range, in indexing order
+        async fn replay_transfers(
+            &self,
+            request: tonic::Request<super::ReplayTransfersRequest>,
+        ) -> std::result::Result<
+            tonic::Response<Self::ReplayTransfersStream>,
+            tonic::Status,
+        >;
         /// Get indexer statistics
         async fn get_stats(
             &self,
@@ -716,6 +927,51 @@ pub mod erc1155_server {
                     };
                     Box::pin(fut)
                 }
+                "/torii.sinks.erc1155.Erc1155/GetInventory" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetInventorySvc<T: Erc1155>(pub Arc<T>);
+                    impl<
+                        T: Erc1155,
+                    > tonic::server::UnaryService<super::GetInventoryRequest>
+                    for GetInventorySvc<T> {
+                        type Response = super::GetInventoryResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetInventoryRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Erc1155>::get_inventory(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetInventorySvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/torii.sinks.erc1155.Erc1155/GetTokenMetadata" => {
                     #[allow(non_camel_case_types)]
                     struct GetTokenMetadataSvc<T: Erc1155>(pub Arc<T>);
@@ -761,6 +1017,51 @@ pub mod erc1155_server {
                     };
                     Box::pin(fut)
                 }
+                "/torii.sinks.erc1155.Erc1155/GetTokenImage" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetTokenImageSvc<T: Erc1155>(pub Arc<T>);
+                    impl<
+                        T: Erc1155,
+                    > tonic::server::UnaryService<super::GetTokenImageRequest>
+                    for GetTokenImageSvc<T> {
+                        type Response = super::GetTokenImageResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetTokenImageRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Erc1155>::get_token_image(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetTokenImageSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/torii.sinks.erc1155.Erc1155/QueryTokensByAttributes" => {
                     #[allow(non_camel_case_types)]
                     struct QueryTokensByAttributesSvc<T: Erc1155>(pub Arc<T>);
@@ -995,6 +1296,101 @@ pub mod erc1155_server {
                     };
                     Box::pin(fut)
                 }
+                "/torii.sinks.erc1155.Erc1155/WatchAddresses" => {
+                    #[allow(non_camel_case_types)]
+                    struct WatchAddressesSvc<T: Erc1155>(pub Arc<T>);
+                    impl<
+                        T: Erc1155,
+                    > tonic::server::StreamingService<super::WatchAddressesRequest>
+                    for WatchAddressesSvc<T> {
+                        type Response = super::WatchUpdate;
+                        type ResponseStream = T::WatchAddressesStream;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::ResponseStream>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                tonic::Streaming<super::WatchAddressesRequest>,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Erc1155>::watch_addresses(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = WatchAddressesSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/torii.sinks.erc1155.Erc1155/ReplayTransfers" => {
+                    #[allow(non_camel_case_types)]
+                    struct ReplayTransfersSvc<T: Erc1155>(pub Arc<T>);
+                    impl<
+                        T: Erc1155,
+                    > tonic::server::ServerStreamingService<
+                        super::ReplayTransfersRequest,
+                    > for ReplayTransfersSvc<T> {
+                        type Response = super::TokenTransfer;
+                        type ResponseStream = T::ReplayTransfersStream;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::ResponseStream>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ReplayTransfersRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Erc1155>::replay_transfers(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ReplayTransfersSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/torii.sinks.erc1155.Erc1155/GetStats" => {
                     #[allow(non_camel_case_types)]
                     struct GetStatsSvc<T: Erc1155>(pub Arc<T>);
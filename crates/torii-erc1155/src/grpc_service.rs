@@ -5,53 +5,134 @@ use crate::proto::{
     ContractCollectionOverview, Cursor, GetBalanceRequest, GetBalanceResponse,
     GetCollectionOverviewRequest, GetCollectionOverviewResponse, GetCollectionTokensRequest,
     GetCollectionTokensResponse, GetCollectionTraitFacetsRequest, GetCollectionTraitFacetsResponse,
-    GetStatsRequest, GetStatsResponse, GetTokenMetadataRequest, GetTokenMetadataResponse,
-    GetTransfersRequest, GetTransfersResponse, QueryTokensByAttributesRequest,
-    QueryTokensByAttributesResponse, SubscribeTransfersRequest, TokenMetadataEntry, TokenTransfer,
-    TraitSummary, TransferFilter, TransferUpdate,
+    GetInventoryRequest, GetInventoryResponse, GetStatsRequest, GetStatsResponse,
+    GetTokenImageRequest, GetTokenImageResponse, GetTokenMetadataRequest, GetTokenMetadataResponse,
+    GetTransfersRequest, GetTransfersResponse, InventoryCursor, InventoryItem,
+    QueryTokensByAttributesRequest, QueryTokensByAttributesResponse, ReplayTransfersRequest,
+    StreamShutdown, SubscribeTransfersRequest, TokenIdMetadataEntry, TokenMetadataEntry,
+    TokenTransfer, TraitSummary, TransferFilter, TransferUpdate, WatchAddressesRequest,
+    WatchUpdate,
 };
-use crate::storage::{Erc1155Storage, TokenTransferData, TransferCursor};
+use crate::sharding::ShardedErc1155Storage;
+use crate::storage::{TokenTransferData, TransferCursor};
 use async_trait::async_trait;
-use futures::stream::Stream;
+use futures::future::Either;
+use futures::stream::{Stream, StreamExt};
 use starknet::core::types::Felt;
 use starknet::core::types::U256;
 use std::collections::{HashMap, HashSet};
 use std::pin::Pin;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::broadcast;
-use tonic::{Request, Response, Status};
-use torii_common::{bytes_to_felt, bytes_to_u256, u256_to_bytes};
+use tonic::{Request, Response, Status, Streaming};
+use torii_common::{
+    bytes_to_felt, bytes_to_u256, replay, u256_to_bytes, AddressWatchlist, FieldMask, ObjectStore,
+    ReplayRange,
+};
+
+/// Updates buffered per address watcher before new ones are dropped
+const WATCH_CHANNEL_CAPACITY: usize = 1000;
+/// Maximum number of addresses in a single watchlist
+const MAX_WATCHED_ADDRESSES: usize = 10_000;
+/// Maximum number of token ids in a subscription filter
+const MAX_FILTER_TOKEN_IDS: usize = 1000;
+/// Fields of `TokenTransfer` selectable with a `field_mask`
+const TRANSFER_FIELDS: &[&str] = &[
+    "token",
+    "operator",
+    "from",
+    "to",
+    "token_id",
+    "amount",
+    "block_number",
+    "tx_hash",
+    "timestamp",
+    "is_batch",
+    "batch_index",
+];
 
 const DEFAULT_PROJECT_ID: &str = "arcade-main";
 
 /// gRPC service implementation for ERC1155
 #[derive(Clone)]
 pub struct Erc1155Service {
-    storage: Arc<Erc1155Storage>,
+    storage: ShardedErc1155Storage,
     /// Broadcast channel for real-time transfer updates
     pub transfer_tx: broadcast::Sender<TransferUpdate>,
+    /// Address watchers (WatchAddresses), indexed by watched address
+    watchlist: AddressWatchlist<WatchUpdate>,
+    /// Object store holding the cached token images (GetTokenImage)
+    object_store: Option<Arc<ObjectStore>>,
+    /// Validity of the signed URLs returned by GetTokenImage
+    image_url_ttl: Duration,
 }
 
 impl Erc1155Service {
     /// Creates a new Erc1155Service
-    pub fn new(storage: Arc<Erc1155Storage>) -> Self {
+    ///
+    /// Takes a single storage or a [`ShardedErc1155Storage`].
+    pub fn new(storage: impl Into<ShardedErc1155Storage>) -> Self {
         let (transfer_tx, _) = broadcast::channel(1000);
 
         Self {
-            storage,
+            storage: storage.into(),
             transfer_tx,
+            watchlist: AddressWatchlist::new(),
+            object_store: None,
+            image_url_ttl: Duration::from_secs(3600),
         }
     }
 
-    /// Broadcasts a transfer to all subscribers
+    /// Serves GetTokenImage from `store`, signing URLs valid for `url_ttl`
+    #[must_use]
+    pub fn with_object_store(mut self, store: Arc<ObjectStore>, url_ttl: Duration) -> Self {
+        self.object_store = Some(store);
+        self.image_url_ttl = url_ttl;
+        self
+    }
+
+    /// Broadcasts a transfer to all subscribers and to the watchers of its addresses
     pub fn broadcast_transfer(&self, transfer: TokenTransfer) {
+        let timestamp = chrono::Utc::now().timestamp();
+        if !self.watchlist.is_empty() {
+            let addresses =
+                watched_addresses(&[&transfer.from[..], &transfer.to[..], &transfer.operator[..]]);
+            self.watchlist.notify(
+                &addresses,
+                &WatchUpdate {
+                    transfer: Some(transfer.clone()),
+                    timestamp,
+                    shutdown: None,
+                },
+            );
+        }
         let update = TransferUpdate {
             transfer: Some(transfer),
-            timestamp: chrono::Utc::now().timestamp(),
+            timestamp,
+            shutdown: None,
         };
         let _ = self.transfer_tx.send(update);
     }
 
+    /// Ends every subscription and address watch stream with a shutdown update.
+    ///
+    /// `block_number` is the last fully indexed block, from which clients resume.
+    pub fn notify_shutdown(&self, block_number: u64) {
+        let timestamp = chrono::Utc::now().timestamp();
+        let shutdown = StreamShutdown { block_number };
+        self.watchlist.notify_all(&WatchUpdate {
+            transfer: None,
+            timestamp,
+            shutdown: Some(shutdown),
+        });
+        let _ = self.transfer_tx.send(TransferUpdate {
+            transfer: None,
+            timestamp,
+            shutdown: Some(shutdown),
+        });
+    }
+
     /// Convert storage TokenTransferData to proto TokenTransfer
     fn transfer_data_to_proto(data: &TokenTransferData) -> TokenTransfer {
         TokenTransfer {
@@ -69,6 +150,43 @@ impl Erc1155Service {
         }
     }
 
+    /// Clear the fields of a transfer left out of `mask`
+    fn mask_transfer(transfer: &mut TokenTransfer, mask: &FieldMask) {
+        if !mask.includes("token") {
+            transfer.token.clear();
+        }
+        if !mask.includes("operator") {
+            transfer.operator.clear();
+        }
+        if !mask.includes("from") {
+            transfer.from.clear();
+        }
+        if !mask.includes("to") {
+            transfer.to.clear();
+        }
+        if !mask.includes("token_id") {
+            transfer.token_id.clear();
+        }
+        if !mask.includes("amount") {
+            transfer.amount.clear();
+        }
+        if !mask.includes("block_number") {
+            transfer.block_number = 0;
+        }
+        if !mask.includes("tx_hash") {
+            transfer.tx_hash.clear();
+        }
+        if !mask.includes("timestamp") {
+            transfer.timestamp = 0;
+        }
+        if !mask.includes("is_batch") {
+            transfer.is_batch = false;
+        }
+        if !mask.includes("batch_index") {
+            transfer.batch_index = 0;
+        }
+    }
+
     /// Check if a transfer matches a filter (for subscriptions)
     fn matches_transfer_filter(transfer: &TokenTransfer, filter: &TransferFilter) -> bool {
         // Wallet filter (OR logic: matches from OR to)
@@ -260,6 +378,9 @@ impl Erc1155Trait for Erc1155Service {
     ) -> Result<Response<GetTransfersResponse>, Status> {
         let req = request.into_inner();
         let filter = req.filter.unwrap_or_default();
+        let mask = FieldMask::parse(&req.field_mask, TRANSFER_FIELDS).map_err(|field| {
+            Status::invalid_argument(format!("unknown field_mask field: {field}"))
+        })?;
 
         let wallet = filter.wallet.as_ref().and_then(|b| bytes_to_felt(b));
         let from = filter.from.as_ref().and_then(|b| bytes_to_felt(b));
@@ -285,7 +406,7 @@ impl Erc1155Trait for Erc1155Service {
 
         let (transfers, next_cursor) = self
             .storage
-            .get_transfers_filtered(
+            .get_transfers_projected(
                 wallet,
                 from,
                 to,
@@ -296,12 +417,19 @@ impl Erc1155Trait for Erc1155Service {
                 filter.block_to,
                 cursor,
                 limit,
+                &mask,
             )
             .await
             .map_err(|e| Status::internal(format!("Query failed: {e}")))?;
 
-        let proto_transfers: Vec<TokenTransfer> =
-            transfers.iter().map(Self::transfer_data_to_proto).collect();
+        let proto_transfers: Vec<TokenTransfer> = transfers
+            .iter()
+            .map(|data| {
+                let mut transfer = Self::transfer_data_to_proto(data);
+                Self::mask_transfer(&mut transfer, &mask);
+                transfer
+            })
+            .collect();
 
         let proto_cursor = next_cursor.map(|c| Cursor {
             block_number: c.block_number,
@@ -348,6 +476,126 @@ impl Erc1155Trait for Erc1155Service {
         }))
     }
 
+    /// List the non-zero balances of an account, optionally restricted to some contracts
+    async fn get_inventory(
+        &self,
+        request: Request<GetInventoryRequest>,
+    ) -> Result<Response<GetInventoryResponse>, Status> {
+        let req = request.into_inner();
+
+        let account = bytes_to_felt(&req.account)
+            .ok_or_else(|| Status::invalid_argument("Invalid account address"))?;
+        let contracts: Vec<Felt> = req
+            .contracts
+            .iter()
+            .filter_map(|b| bytes_to_felt(b))
+            .collect();
+        let cursor = req
+            .cursor
+            .map(|c| {
+                Ok::<_, Status>(crate::storage::InventoryCursor {
+                    contract: bytes_to_felt(&c.contract)
+                        .ok_or_else(|| Status::invalid_argument("Invalid cursor contract"))?,
+                    token_id: bytes_to_u256(&c.token_id),
+                })
+            })
+            .transpose()?;
+
+        let limit = if req.limit == 0 {
+            100
+        } else {
+            req.limit.min(1000)
+        };
+
+        tracing::debug!(
+            target: "torii_erc1155::grpc",
+            "GetInventory: account={:#x}, contracts={}, limit={}",
+            account,
+            contracts.len(),
+            limit
+        );
+
+        let (balances, next_cursor) = self
+            .storage
+            .get_inventory(account, &contracts, cursor, limit)
+            .await
+            .map_err(|e| Status::internal(format!("Query failed: {e}")))?;
+
+        let mut metadata: HashMap<(Felt, U256), (Option<String>, Option<String>)> = HashMap::new();
+        if req.include_metadata {
+            let mut token_ids_by_contract: HashMap<Felt, Vec<U256>> = HashMap::new();
+            for b in &balances {
+                token_ids_by_contract
+                    .entry(b.contract)
+                    .or_default()
+                    .push(b.token_id);
+            }
+            for (contract, token_ids) in token_ids_by_contract {
+                let uri_rows = self
+                    .storage
+                    .get_token_uris_batch(contract, &token_ids)
+                    .await
+                    .map_err(|e| Status::internal(format!("Query failed: {e}")))?;
+                metadata.extend(uri_rows.into_iter().map(|(token_id, uri, metadata_json)| {
+                    ((contract, token_id), (uri, metadata_json))
+                }));
+            }
+        }
+
+        let items = balances
+            .iter()
+            .map(|b| {
+                let (uri, metadata_json) = metadata
+                    .remove(&(b.contract, b.token_id))
+                    .unwrap_or((None, None));
+                InventoryItem {
+                    contract: b.contract.to_bytes_be().to_vec(),
+                    token_id: u256_to_bytes(b.token_id),
+                    balance: u256_to_bytes(b.balance),
+                    last_block: b.last_block,
+                    uri,
+                    metadata_json,
+                }
+            })
+            .collect();
+
+        Ok(Response::new(GetInventoryResponse {
+            items,
+            next_cursor: next_cursor.map(|c| InventoryCursor {
+                contract: c.contract.to_bytes_be().to_vec(),
+                token_id: u256_to_bytes(c.token_id),
+            }),
+        }))
+    }
+
+    /// Get signed URLs of the cached image and metadata of a token
+    async fn get_token_image(
+        &self,
+        request: Request<GetTokenImageRequest>,
+    ) -> Result<Response<GetTokenImageResponse>, Status> {
+        let req = request.into_inner();
+        let store = self
+            .object_store
+            .as_ref()
+            .ok_or_else(|| Status::failed_precondition("Image object store not configured"))?;
+        let token = bytes_to_felt(&req.token)
+            .ok_or_else(|| Status::invalid_argument("Invalid token address"))?;
+        let token_id = bytes_to_u256(&req.token_id);
+
+        let urls = store
+            .token_asset_urls(token, token_id, self.image_url_ttl)
+            .await
+            .map_err(|e| Status::internal(format!("Query failed: {e}")))?
+            .ok_or_else(|| Status::not_found("Token image not cached"))?;
+
+        Ok(Response::new(GetTokenImageResponse {
+            image_url: urls.image_url,
+            content_type: urls.content_type,
+            metadata_url: urls.metadata_url,
+            expires_at: urls.expires_at,
+        }))
+    }
+
     /// Get token metadata (name, symbol)
     async fn get_token_metadata(
         &self,
@@ -355,10 +603,41 @@ impl Erc1155Trait for Erc1155Service {
     ) -> Result<Response<GetTokenMetadataResponse>, Status> {
         let req = request.into_inner();
 
+        if req.token.is_none() && !req.token_ids.is_empty() {
+            return Err(Status::invalid_argument("token_ids requires token"));
+        }
+        if req.token_ids.len() > 1000 {
+            return Err(Status::invalid_argument(
+                "At most 1000 token_ids per request",
+            ));
+        }
+
         if let Some(token_bytes) = req.token {
             let token = bytes_to_felt(&token_bytes)
                 .ok_or_else(|| Status::invalid_argument("Invalid token address"))?;
 
+            let token_ids = if req.token_ids.is_empty() {
+                Vec::new()
+            } else {
+                let ids = req
+                    .token_ids
+                    .iter()
+                    .map(|bytes| bytes_to_u256(bytes))
+                    .collect::<Vec<U256>>();
+                self.storage
+                    .get_token_uris_batch(token, &ids)
+                    .await
+                    .map_err(|e| Status::internal(format!("Query failed: {e}")))?
+                    .into_iter()
+                    .map(|(token_id, uri, metadata_json)| TokenIdMetadataEntry {
+                        token: token.to_bytes_be().to_vec(),
+                        token_id: u256_to_bytes(token_id),
+                        uri,
+                        metadata_json,
+                    })
+                    .collect()
+            };
+
             let entries = match self.storage.get_token_metadata(token).await {
                 Ok(Some((name, symbol, total_supply))) => vec![TokenMetadataEntry {
                     token: token.to_bytes_be().to_vec(),
@@ -373,6 +652,7 @@ impl Erc1155Trait for Erc1155Service {
             return Ok(Response::new(GetTokenMetadataResponse {
                 tokens: entries,
                 next_cursor: None,
+                token_ids,
             }));
         }
 
@@ -402,6 +682,7 @@ impl Erc1155Trait for Erc1155Service {
         Ok(Response::new(GetTokenMetadataResponse {
             tokens: entries,
             next_cursor: next_cursor.map(|c| c.to_bytes_be().to_vec()),
+            token_ids: Vec::new(),
         }))
     }
 
@@ -607,7 +888,9 @@ impl Erc1155Trait for Erc1155Service {
         request: Request<SubscribeTransfersRequest>,
     ) -> Result<Response<Self::SubscribeTransfersStream>, Status> {
         let req = request.into_inner();
-        let filter = req.filter.unwrap_or_default();
+        let mut filter = req.filter.unwrap_or_default();
+        // Compare token ids by value: clients may send them zero-padded.
+        filter.token_ids = normalize_token_ids(&filter.token_ids)?;
 
         tracing::info!(
             target: "torii_erc1155::grpc",
@@ -626,7 +909,11 @@ impl Erc1155Trait for Erc1155Service {
                                 continue;
                             }
                         }
+                        let shutdown = update.shutdown.is_some();
                         yield update;
+                        if shutdown {
+                            break;
+                        }
                     }
                     Err(broadcast::error::RecvError::Lagged(skipped)) => {
                         tracing::warn!(
@@ -647,6 +934,113 @@ impl Erc1155Trait for Erc1155Service {
         Ok(Response::new(Box::pin(stream)))
     }
 
+    /// Watch account addresses (server-side filtered transfers)
+    type WatchAddressesStream = Pin<Box<dyn Stream<Item = Result<WatchUpdate, Status>> + Send>>;
+
+    async fn watch_addresses(
+        &self,
+        request: Request<Streaming<WatchAddressesRequest>>,
+    ) -> Result<Response<Self::WatchAddressesStream>, Status> {
+        let mut requests = request.into_inner();
+        let first = requests
+            .message()
+            .await?
+            .ok_or_else(|| Status::invalid_argument("Expected an initial watch request"))?;
+        let client_id = first.client_id;
+        let mut token_ids = normalize_token_ids(&first.token_ids)?;
+        let mut watcher = self.watchlist.watch(
+            parse_watched_addresses(&first.addresses)?,
+            WATCH_CHANNEL_CAPACITY,
+        );
+
+        tracing::info!(
+            target: "torii_erc1155::grpc",
+            "New address watch from client {}: {} addresses",
+            client_id,
+            first.addresses.len()
+        );
+
+        let stream = async_stream::try_stream! {
+            let mut requests_open = true;
+            loop {
+                let event = tokio::select! {
+                    update = watcher.recv() => Either::Left(update),
+                    request = requests.message(), if requests_open => Either::Right(request),
+                };
+                match event {
+                    Either::Left(Some(update)) => {
+                        if let Some(ref transfer) = update.transfer {
+                            if !token_ids.is_empty() && !token_ids.contains(&transfer.token_id) {
+                                continue;
+                            }
+                        }
+                        let shutdown = update.shutdown.is_some();
+                        yield update;
+                        if shutdown {
+                            break;
+                        }
+                    }
+                    Either::Left(None) => break,
+                    Either::Right(request) => match request? {
+                        Some(request) => {
+                            watcher.set_addresses(parse_watched_addresses(&request.addresses)?);
+                            token_ids = normalize_token_ids(&request.token_ids)?;
+                            tracing::debug!(
+                                target: "torii_erc1155::grpc",
+                                "Client {} now watches {} addresses ({} token ids)",
+                                client_id,
+                                request.addresses.len(),
+                                token_ids.len()
+                            );
+                        }
+                        // The client stopped updating its watchlist; keep streaming.
+                        None => requests_open = false,
+                    },
+                }
+            }
+
+            tracing::info!(
+                target: "torii_erc1155::grpc",
+                "Address watch ended for client: {}",
+                client_id
+            );
+        };
+
+        Ok(Response::new(Box::pin(stream)))
+    }
+
+    /// Stream stored transfers in a block range (historical replay)
+    type ReplayTransfersStream = Pin<Box<dyn Stream<Item = Result<TokenTransfer, Status>> + Send>>;
+
+    /// Pages through storage lazily: the next page is only read once the previous one has
+    /// been sent, so a slow client throttles the query through tonic's flow control.
+    async fn replay_transfers(
+        &self,
+        request: Request<ReplayTransfersRequest>,
+    ) -> Result<Response<Self::ReplayTransfersStream>, Status> {
+        let req = request.into_inner();
+        let tokens: Vec<Felt> = req.tokens.iter().filter_map(|b| bytes_to_felt(b)).collect();
+        let range = ReplayRange::new(tokens, req.block_from, req.block_to, req.page_size)
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+
+        tracing::info!(
+            target: "torii_erc1155::grpc",
+            "ReplayTransfers: blocks={:?}..={:?}, tokens={}, page_size={}",
+            range.block_from,
+            range.block_to,
+            range.tokens.len(),
+            range.page_size
+        );
+
+        let stream = replay(self.storage.clone(), range).map(|transfer| {
+            transfer
+                .map(|transfer| Self::transfer_data_to_proto(&transfer))
+                .map_err(|e| Status::internal(format!("Replay query failed: {e}")))
+        });
+
+        Ok(Response::new(Box::pin(stream)))
+    }
+
     /// Get indexer statistics
     async fn get_stats(
         &self,
@@ -685,3 +1079,42 @@ impl Erc1155Trait for Erc1155Service {
         }))
     }
 }
+
+/// Parses the addresses of a watch request.
+fn parse_watched_addresses(addresses: &[Vec<u8>]) -> Result<Vec<Felt>, Status> {
+    if addresses.len() > MAX_WATCHED_ADDRESSES {
+        return Err(Status::invalid_argument(format!(
+            "Too many addresses to watch (max {MAX_WATCHED_ADDRESSES})"
+        )));
+    }
+    addresses
+        .iter()
+        .map(|address| {
+            bytes_to_felt(address).ok_or_else(|| Status::invalid_argument("Invalid address"))
+        })
+        .collect()
+}
+
+/// Token ids of a subscription filter, in the canonical encoding of published transfers.
+fn normalize_token_ids(token_ids: &[Vec<u8>]) -> Result<Vec<Vec<u8>>, Status> {
+    if token_ids.len() > MAX_FILTER_TOKEN_IDS {
+        return Err(Status::invalid_argument(format!(
+            "At most {MAX_FILTER_TOKEN_IDS} token_ids per filter"
+        )));
+    }
+    if token_ids.iter().any(|token_id| token_id.len() > 32) {
+        return Err(Status::invalid_argument("Invalid token_id"));
+    }
+    Ok(token_ids
+        .iter()
+        .map(|token_id| u256_to_bytes(bytes_to_u256(token_id)))
+        .collect())
+}
+
+/// Addresses an update is routed by (malformed addresses are skipped).
+fn watched_addresses(addresses: &[&[u8]]) -> Vec<Felt> {
+    addresses
+        .iter()
+        .filter_map(|address| bytes_to_felt(address))
+        .collect()
+}
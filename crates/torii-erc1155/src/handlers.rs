@@ -3,17 +3,17 @@ use async_trait::async_trait;
 use prost::Message;
 use prost_types::Any;
 use starknet::core::types::Felt;
-use starknet::providers::jsonrpc::{HttpTransport, JsonRpcClient};
 use std::collections::HashSet;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 use torii::command::CommandHandler;
 use torii::etl::sink::EventBus;
 use torii::UpdateType;
+use torii_common::RpcProvider;
 use torii_common::{process_token_uri_request, u256_to_bytes, MetadataFetcher, TokenUriRequest};
 
 use crate::proto;
-use crate::storage::Erc1155Storage;
+use crate::sharding::ShardedErc1155Storage;
 
 #[derive(Debug, Clone)]
 pub struct FetchErc1155MetadataCommand {
@@ -24,25 +24,34 @@ pub struct FetchErc1155MetadataCommand {
 pub struct RefreshErc1155TokenUriCommand {
     pub contract: Felt,
     pub token_id: starknet::core::types::U256,
+    /// URI from a `URI` event, used instead of calling `uri(token_id)`
+    pub uri: Option<String>,
 }
 
 pub struct Erc1155MetadataCommandHandler {
     fetcher: Arc<MetadataFetcher>,
-    storage: Arc<Erc1155Storage>,
+    storage: ShardedErc1155Storage,
     event_bus: Mutex<Option<Arc<EventBus>>>,
     in_flight: Mutex<HashSet<Felt>>,
 }
 
 impl Erc1155MetadataCommandHandler {
-    pub fn new(provider: Arc<JsonRpcClient<HttpTransport>>, storage: Arc<Erc1155Storage>) -> Self {
+    pub fn new(provider: Arc<RpcProvider>, storage: impl Into<ShardedErc1155Storage>) -> Self {
         Self {
             fetcher: Arc::new(MetadataFetcher::new(provider)),
-            storage,
+            storage: storage.into(),
             event_bus: Mutex::new(None),
             in_flight: Mutex::new(HashSet::new()),
         }
     }
 
+    /// Use a shared fetcher (e.g. one backed by a [`torii_common::MetadataCache`]).
+    #[must_use]
+    pub fn with_fetcher(mut self, fetcher: Arc<MetadataFetcher>) -> Self {
+        self.fetcher = fetcher;
+        self
+    }
+
     fn matches_metadata_filters(
         metadata: &proto::TokenMetadataEntry,
         filters: &std::collections::HashMap<String, String>,
@@ -140,20 +149,20 @@ impl CommandHandler for Erc1155MetadataCommandHandler {
 
 pub struct Erc1155TokenUriCommandHandler {
     fetcher: Arc<MetadataFetcher>,
-    storage: Arc<Erc1155Storage>,
+    storage: ShardedErc1155Storage,
     image_cache_dir: Option<PathBuf>,
     in_flight: Mutex<HashSet<(Felt, starknet::core::types::U256)>>,
 }
 
 impl Erc1155TokenUriCommandHandler {
     pub fn new(
-        provider: Arc<JsonRpcClient<HttpTransport>>,
-        storage: Arc<Erc1155Storage>,
+        provider: Arc<RpcProvider>,
+        storage: impl Into<ShardedErc1155Storage>,
         image_cache_dir: Option<PathBuf>,
     ) -> Self {
         Self {
             fetcher: Arc::new(MetadataFetcher::new(provider)),
-            storage,
+            storage: storage.into(),
             image_cache_dir,
             in_flight: Mutex::new(HashSet::new()),
         }
@@ -190,11 +199,12 @@ impl CommandHandler for Erc1155TokenUriCommandHandler {
 
         let result = process_token_uri_request(
             self.fetcher.as_ref(),
-            self.storage.as_ref(),
+            &self.storage,
             &TokenUriRequest {
                 contract: command.contract,
                 token_id: command.token_id,
                 standard: torii_common::TokenStandard::Erc1155,
+                uri: command.uri,
             },
             self.image_cache_dir.as_deref(),
         )
@@ -10,6 +10,7 @@
 //! - [`Erc1155Decoder`]: Decodes ERC1155 TransferSingle, TransferBatch, ApprovalForAll, and URI events
 //! - [`Erc1155Sink`]: Processes decoded events, stores in SQLite, and publishes updates
 //! - [`Erc1155Storage`]: SQLite storage with efficient pagination
+//! - [`ShardedErc1155Storage`]: Storage split over several databases by token contract
 //! - [`Erc1155Service`]: gRPC service for queries and real-time subscriptions
 //!
 //! # Example
@@ -36,11 +37,13 @@
 //!     .add_service(Erc1155Server::new(grpc_service));
 //! ```
 
+pub mod api;
 pub mod balance_fetcher;
 pub mod decoder;
 pub mod grpc_service;
 pub mod handlers;
 pub mod identification;
+pub mod sharding;
 pub mod sink;
 pub mod storage;
 pub mod synthetic;
@@ -59,9 +62,10 @@ pub use decoder::{Erc1155Decoder, OperatorApproval, TransferBatch, TransferSingl
 pub use grpc_service::Erc1155Service;
 pub use handlers::{Erc1155MetadataCommandHandler, Erc1155TokenUriCommandHandler};
 pub use identification::Erc1155Rule;
+pub use sharding::ShardedErc1155Storage;
 pub use sink::Erc1155Sink;
 pub use storage::{
-    Erc1155BalanceAdjustment, Erc1155BalanceData, Erc1155Storage, TokenTransferData, TokenUriData,
-    TransferCursor,
+    Erc1155BalanceAdjustment, Erc1155BalanceData, Erc1155Storage, InventoryCursor,
+    TokenTransferData, TokenUriData, TransferCursor,
 };
 pub use synthetic::{SyntheticErc1155Config, SyntheticErc1155Extractor};
@@ -0,0 +1,578 @@
+//! ERC1155 storage sharded by token contract
+//!
+//! [`ShardedErc1155Storage`] splits the ERC1155 tables over several [`Erc1155Storage`]
+//! shards (see [`torii_common::sharding`]). This type mirrors the [`Erc1155Storage`]
+//! methods used by the sink and the services: writes and contract-scoped queries are
+//! routed to the shard of the contract, other queries fan out to every shard.
+//!
+//! Transfer ids (and the cursors built from them) are global ids.
+
+use crate::balance_fetcher::Erc1155BalanceFetchRequest;
+use crate::storage::{
+    Erc1155BalanceData, Erc1155Storage, InventoryCursor, OperatorApprovalData,
+    TokenAttributeQueryResult, TokenTransferData, TokenUriData, TransferCursor,
+};
+use anyhow::Result;
+use async_trait::async_trait;
+use futures::future::try_join_all;
+use starknet::core::types::{Felt, U256};
+use std::borrow::Cow;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use torii_common::{
+    merge_pages, shard_url, FieldMask, ReplayRange, ReplaySource, StorageShards, TokenUriResult,
+    TokenUriStore,
+};
+
+/// Token metadata row: (token, name, symbol, total supply)
+type TokenMetadataRow = (Felt, Option<String>, Option<String>, Option<U256>);
+
+/// ERC1155 storage split by token contract
+#[derive(Clone)]
+pub struct ShardedErc1155Storage {
+    shards: StorageShards<Erc1155Storage>,
+}
+
+impl From<Arc<Erc1155Storage>> for ShardedErc1155Storage {
+    fn from(storage: Arc<Erc1155Storage>) -> Self {
+        Self::new(StorageShards::single(storage))
+    }
+}
+
+impl ShardedErc1155Storage {
+    pub fn new(shards: StorageShards<Erc1155Storage>) -> Self {
+        Self { shards }
+    }
+
+    /// Create or open `count` shards, derived from `db_path` with [`shard_url`].
+    pub async fn open(db_path: &str, count: usize) -> Result<Self> {
+        let mut shards = Vec::with_capacity(count.max(1));
+        for index in 0..count.max(1) {
+            shards.push(Arc::new(
+                Erc1155Storage::new(&shard_url(db_path, index)).await?,
+            ));
+        }
+        Ok(Self::new(StorageShards::new(shards)))
+    }
+
+    pub fn shards(&self) -> &StorageShards<Erc1155Storage> {
+        &self.shards
+    }
+
+    /// Storage of the shard of `token`.
+    pub fn for_token(&self, token: Felt) -> &Arc<Erc1155Storage> {
+        self.shards.for_contract(token)
+    }
+
+    /// Splits `items` by shard of their token; a single shard borrows them as is.
+    fn split<'a, T: Clone>(
+        &self,
+        items: &'a [T],
+        token: impl Fn(&T) -> Felt,
+    ) -> Vec<(usize, Cow<'a, [T]>)> {
+        if !self.shards.is_sharded() {
+            return vec![(0, Cow::Borrowed(items))];
+        }
+        self.shards
+            .partition(items.iter().cloned(), token)
+            .into_iter()
+            .map(|(shard, items)| (shard, Cow::Owned(items)))
+            .collect()
+    }
+
+    pub async fn insert_transfers_batch(&self, transfers: &[TokenTransferData]) -> Result<usize> {
+        let mut inserted = 0;
+        for (shard, transfers) in self.split(transfers, |t| t.token) {
+            inserted += self
+                .shards
+                .get(shard)
+                .insert_transfers_batch(&transfers)
+                .await?;
+        }
+        Ok(inserted)
+    }
+
+    pub async fn insert_operator_approvals_batch(
+        &self,
+        approvals: &[OperatorApprovalData],
+    ) -> Result<usize> {
+        let mut inserted = 0;
+        for (shard, approvals) in self.split(approvals, |a| a.token) {
+            inserted += self
+                .shards
+                .get(shard)
+                .insert_operator_approvals_batch(&approvals)
+                .await?;
+        }
+        Ok(inserted)
+    }
+
+    pub async fn upsert_token_uris_batch(&self, uris: &[TokenUriData]) -> Result<usize> {
+        let mut upserted = 0;
+        for (shard, uris) in self.split(uris, |u| u.token) {
+            upserted += self
+                .shards
+                .get(shard)
+                .upsert_token_uris_batch(&uris)
+                .await?;
+        }
+        Ok(upserted)
+    }
+
+    pub async fn check_balances_batch(
+        &self,
+        transfers: &[TokenTransferData],
+    ) -> Result<Vec<Erc1155BalanceFetchRequest>> {
+        let mut requests = Vec::new();
+        for (shard, transfers) in self.split(transfers, |t| t.token) {
+            requests.extend(
+                self.shards
+                    .get(shard)
+                    .check_balances_batch(&transfers)
+                    .await?,
+            );
+        }
+        Ok(requests)
+    }
+
+    /// Apply balance updates, handing each shard its transfers and adjustments.
+    pub async fn apply_transfers_with_adjustments(
+        &self,
+        transfers: &[TokenTransferData],
+        adjustments: &HashMap<(Felt, Felt, U256), U256>,
+    ) -> Result<()> {
+        if !self.shards.is_sharded() {
+            return self
+                .shards
+                .get(0)
+                .apply_transfers_with_adjustments(transfers, adjustments)
+                .await;
+        }
+        for (shard, transfers) in self.split(transfers, |t| t.token) {
+            let adjustments = adjustments
+                .iter()
+                .filter(|((contract, _, _), _)| self.shards.index_of(*contract) == shard)
+                .map(|(key, balance)| (*key, *balance))
+                .collect();
+            self.shards
+                .get(shard)
+                .apply_transfers_with_adjustments(&transfers, &adjustments)
+                .await?;
+        }
+        Ok(())
+    }
+
+    pub async fn has_token_metadata_batch(&self, tokens: &[Felt]) -> Result<HashSet<Felt>> {
+        let mut existing = HashSet::new();
+        for (shard, tokens) in self.split(tokens, |token| *token) {
+            existing.extend(
+                self.shards
+                    .get(shard)
+                    .has_token_metadata_batch(&tokens)
+                    .await?,
+            );
+        }
+        Ok(existing)
+    }
+
+    pub async fn has_token_uri_batch(
+        &self,
+        tokens: &[(Felt, U256)],
+    ) -> Result<HashSet<(Felt, U256)>> {
+        let mut existing = HashSet::new();
+        for (shard, tokens) in self.split(tokens, |(token, _)| *token) {
+            existing.extend(self.shards.get(shard).has_token_uri_batch(&tokens).await?);
+        }
+        Ok(existing)
+    }
+
+    pub async fn upsert_token_metadata(
+        &self,
+        token: Felt,
+        name: Option<&str>,
+        symbol: Option<&str>,
+        total_supply: Option<U256>,
+    ) -> Result<()> {
+        self.for_token(token)
+            .upsert_token_metadata(token, name, symbol, total_supply)
+            .await
+    }
+
+    pub async fn get_token_metadata(
+        &self,
+        token: Felt,
+    ) -> Result<Option<(Option<String>, Option<String>, Option<U256>)>> {
+        self.for_token(token).get_token_metadata(token).await
+    }
+
+    /// Get token metadata in token order, merged across shards
+    pub async fn get_token_metadata_paginated(
+        &self,
+        cursor: Option<Felt>,
+        limit: u32,
+    ) -> Result<(Vec<TokenMetadataRow>, Option<Felt>)> {
+        let pages = self
+            .shards
+            .try_fan_out(|_, storage| storage.get_token_metadata_paginated(cursor, limit))
+            .await?;
+        let more_in_shards = pages.iter().any(|(_, next)| next.is_some());
+        let (rows, truncated) = merge_pages(
+            pages.into_iter().map(|(rows, _)| rows),
+            limit as usize,
+            |row| row.0,
+        );
+        let next_cursor = if more_in_shards || truncated {
+            rows.last().map(|row| row.0)
+        } else {
+            None
+        };
+        Ok((rows, next_cursor))
+    }
+
+    pub async fn get_token_uris_batch(
+        &self,
+        token: Felt,
+        token_ids: &[U256],
+    ) -> Result<Vec<(U256, Option<String>, Option<String>)>> {
+        self.for_token(token)
+            .get_token_uris_batch(token, token_ids)
+            .await
+    }
+
+    pub async fn query_token_ids_by_attributes(
+        &self,
+        token: Felt,
+        filters: &[(String, Vec<String>)],
+        cursor_token_id: Option<U256>,
+        limit: u32,
+        include_facets: bool,
+        facet_limit: u32,
+    ) -> Result<TokenAttributeQueryResult> {
+        self.for_token(token)
+            .query_token_ids_by_attributes(
+                token,
+                filters,
+                cursor_token_id,
+                limit,
+                include_facets,
+                facet_limit,
+            )
+            .await
+    }
+
+    pub async fn get_balance_with_block(
+        &self,
+        contract: Felt,
+        wallet: Felt,
+        token_id: U256,
+    ) -> Result<Option<(U256, u64)>> {
+        self.for_token(contract)
+            .get_balance_with_block(contract, wallet, token_id)
+            .await
+    }
+
+    /// Get the non-zero balances of a wallet in (contract, token id) order, merged across
+    /// the shards of `contracts` (every shard when empty)
+    pub async fn get_inventory(
+        &self,
+        wallet: Felt,
+        contracts: &[Felt],
+        cursor: Option<InventoryCursor>,
+        limit: u32,
+    ) -> Result<(Vec<Erc1155BalanceData>, Option<InventoryCursor>)> {
+        let shards = &self.shards;
+        let pages = try_join_all(shards.select(contracts).into_iter().map(
+            |(shard, contracts)| async move {
+                shards
+                    .get(shard)
+                    .get_inventory(wallet, &contracts, cursor, limit)
+                    .await
+            },
+        ))
+        .await?;
+        let more_in_shards = pages.iter().any(|(_, next)| next.is_some());
+        let (balances, truncated) = merge_pages(
+            pages.into_iter().map(|(balances, _)| balances),
+            limit as usize,
+            |b| (b.contract, b.token_id.high(), b.token_id.low()),
+        );
+        let next_cursor = if more_in_shards || truncated {
+            balances.last().map(|b| InventoryCursor {
+                contract: b.contract,
+                token_id: b.token_id,
+            })
+        } else {
+            None
+        };
+        Ok((balances, next_cursor))
+    }
+
+    /// Get filtered transfers, merged across shards (see [`Erc1155Storage::get_transfers_filtered`])
+    #[allow(clippy::too_many_arguments)]
+    pub async fn get_transfers_filtered(
+        &self,
+        wallet: Option<Felt>,
+        from: Option<Felt>,
+        to: Option<Felt>,
+        operator: Option<Felt>,
+        tokens: &[Felt],
+        token_ids: &[U256],
+        block_from: Option<u64>,
+        block_to: Option<u64>,
+        cursor: Option<TransferCursor>,
+        limit: u32,
+    ) -> Result<(Vec<TokenTransferData>, Option<TransferCursor>)> {
+        self.get_transfers_projected(
+            wallet,
+            from,
+            to,
+            operator,
+            tokens,
+            token_ids,
+            block_from,
+            block_to,
+            cursor,
+            limit,
+            &FieldMask::all(),
+        )
+        .await
+    }
+
+    /// Get projected transfers, merged across shards (see [`Erc1155Storage::get_transfers_projected`])
+    #[allow(clippy::too_many_arguments)]
+    pub async fn get_transfers_projected(
+        &self,
+        wallet: Option<Felt>,
+        from: Option<Felt>,
+        to: Option<Felt>,
+        operator: Option<Felt>,
+        tokens: &[Felt],
+        token_ids: &[U256],
+        block_from: Option<u64>,
+        block_to: Option<u64>,
+        cursor: Option<TransferCursor>,
+        limit: u32,
+        mask: &FieldMask,
+    ) -> Result<(Vec<TokenTransferData>, Option<TransferCursor>)> {
+        let shards = &self.shards;
+        let pages = try_join_all(shards.select(tokens).into_iter().map(
+            |(shard, tokens)| async move {
+                let cursor = cursor.map(|c| TransferCursor {
+                    block_number: c.block_number,
+                    id: shards.local_cursor_before(shard, c.id),
+                });
+                let (mut transfers, _) = shards
+                    .get(shard)
+                    .get_transfers_projected(
+                        wallet, from, to, operator, &tokens, token_ids, block_from, block_to,
+                        cursor, limit, mask,
+                    )
+                    .await?;
+                for transfer in &mut transfers {
+                    transfer.id = transfer.id.map(|id| shards.global_id(shard, id));
+                }
+                Ok::<_, anyhow::Error>(transfers)
+            },
+        ))
+        .await?;
+
+        let (transfers, _) =
+            merge_pages(pages, limit as usize, |t| Reverse((t.block_number, t.id)));
+        let next_cursor = if transfers.len() == limit as usize {
+            transfers.last().map(|t| TransferCursor {
+                block_number: t.block_number,
+                id: t.id.unwrap(),
+            })
+        } else {
+            None
+        };
+        Ok((transfers, next_cursor))
+    }
+
+    /// Transfers of a transaction from every shard, in event order
+    pub async fn get_transfers_by_tx(&self, tx_hash: Felt) -> Result<Vec<TokenTransferData>> {
+        let shards = &self.shards;
+        let pages = try_join_all((0..shards.len()).map(|shard| async move {
+            let mut transfers = shards.get(shard).get_transfers_by_tx(tx_hash).await?;
+            for transfer in &mut transfers {
+                transfer.id = transfer.id.map(|id| shards.global_id(shard, id));
+            }
+            Ok::<_, anyhow::Error>(transfers)
+        }))
+        .await?;
+        let mut transfers: Vec<TokenTransferData> = pages.into_iter().flatten().collect();
+        transfers.sort_by_key(|t| (t.event_index.is_none(), t.event_index, t.id));
+        Ok(transfers)
+    }
+
+    pub async fn get_transfer_count(&self) -> Result<u64> {
+        let counts = self
+            .shards
+            .try_fan_out(|_, storage| storage.get_transfer_count())
+            .await?;
+        Ok(counts.into_iter().sum())
+    }
+
+    /// Contracts never span shards, so per-shard counts add up.
+    pub async fn get_token_count(&self) -> Result<u64> {
+        let counts = self
+            .shards
+            .try_fan_out(|_, storage| storage.get_token_count())
+            .await?;
+        Ok(counts.into_iter().sum())
+    }
+
+    pub async fn get_token_id_count(&self) -> Result<u64> {
+        let counts = self
+            .shards
+            .try_fan_out(|_, storage| storage.get_token_id_count())
+            .await?;
+        Ok(counts.into_iter().sum())
+    }
+
+    pub async fn get_latest_block(&self) -> Result<Option<u64>> {
+        let blocks = self
+            .shards
+            .try_fan_out(|_, storage| storage.get_latest_block())
+            .await?;
+        Ok(blocks.into_iter().flatten().max())
+    }
+
+    /// Runs SQLite maintenance on every shard.
+    pub async fn run_maintenance(&self) -> Result<()> {
+        self.shards
+            .try_fan_out(|_, storage| storage.run_maintenance())
+            .await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl TokenUriStore for ShardedErc1155Storage {
+    async fn store_token_uris_batch(&self, results: &[TokenUriResult]) -> Result<()> {
+        self.shards.store_token_uris_batch(results).await
+    }
+}
+
+#[async_trait]
+impl ReplaySource for ShardedErc1155Storage {
+    type Row = TokenTransferData;
+
+    /// Transfers in id order, merged across shards by global id
+    async fn replay_page(
+        &self,
+        range: &ReplayRange,
+        after_id: Option<i64>,
+    ) -> Result<Vec<TokenTransferData>> {
+        let shards = &self.shards;
+        let pages = try_join_all(shards.select(&range.tokens).into_iter().map(
+            |(shard, tokens)| async move {
+                let after_id = after_id.map(|id| shards.local_cursor_after(shard, id));
+                let mut transfers = shards
+                    .get(shard)
+                    .get_transfers_for_replay(&range.with_tokens(tokens), after_id)
+                    .await?;
+                for transfer in &mut transfers {
+                    transfer.id = transfer.id.map(|id| shards.global_id(shard, id));
+                }
+                Ok::<_, anyhow::Error>(transfers)
+            },
+        ))
+        .await?;
+
+        Ok(merge_pages(pages, range.page_size as usize, |t| t.id).0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_db_path(test_name: &str) -> String {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .expect("system time before unix epoch")
+            .as_nanos();
+        std::env::temp_dir()
+            .join(format!("torii-erc1155-{test_name}-{nanos}.db"))
+            .to_string_lossy()
+            .to_string()
+    }
+
+    fn transfer(token: u64, from: Felt, to: Felt, token_id: u64, amount: u64) -> TokenTransferData {
+        TokenTransferData {
+            id: None,
+            token: Felt::from(token),
+            operator: from,
+            from,
+            to,
+            token_id: U256::from(token_id),
+            amount: U256::from(amount),
+            is_batch: false,
+            batch_index: 0,
+            block_number: token,
+            tx_hash: Felt::from(token * 100 + token_id),
+            timestamp: Some(1_700_000_000),
+            event_index: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn inventory_skips_zero_balances_across_shards() {
+        let storage = ShardedErc1155Storage::open(&temp_db_path("inventory"), 2)
+            .await
+            .expect("create storage");
+        let wallet = Felt::from(0xabcu64);
+        let other = Felt::from(0xdefu64);
+        let mut transfers: Vec<_> = (1..=4u64)
+            .flat_map(|token| {
+                [
+                    transfer(token, Felt::ZERO, wallet, 1, 5),
+                    transfer(token, Felt::ZERO, wallet, 256, 1),
+                ]
+            })
+            .collect();
+        // Token id 256 of contract 2 is sent away: its zero balance is not listed.
+        transfers.push(transfer(2, wallet, other, 256, 1));
+        storage
+            .apply_transfers_with_adjustments(&transfers, &HashMap::new())
+            .await
+            .expect("apply transfers");
+
+        let mut listed = Vec::new();
+        let mut cursor = None;
+        loop {
+            let (page, next) = storage
+                .get_inventory(wallet, &[], cursor, 3)
+                .await
+                .expect("inventory page");
+            listed.extend(page.iter().map(|b| (b.contract, b.token_id, b.balance)));
+            cursor = next;
+            if cursor.is_none() {
+                break;
+            }
+        }
+        let expected: Vec<_> = [
+            (1u64, 1u64, 5u64),
+            (1, 256, 1),
+            (2, 1, 5),
+            (3, 1, 5),
+            (3, 256, 1),
+            (4, 1, 5),
+            (4, 256, 1),
+        ]
+        .into_iter()
+        .map(|(c, id, balance)| (Felt::from(c), U256::from(id), U256::from(balance)))
+        .collect();
+        assert_eq!(listed, expected);
+
+        let (filtered, next) = storage
+            .get_inventory(wallet, &[Felt::from(2u64), Felt::from(4u64)], None, 10)
+            .await
+            .expect("filtered inventory");
+        let contracts: Vec<Felt> = filtered.iter().map(|b| b.contract).collect();
+        assert_eq!(contracts, [2u64, 4, 4].map(Felt::from));
+        assert!(next.is_none());
+    }
+}
@@ -11,6 +11,7 @@
 //! - When a balance would go negative (genesis allocation, airdrop, etc.),
 //!   fetches the actual balance from the chain and adjusts
 
+use crate::api::{self, Erc1155ApiState};
 use crate::balance_fetcher::Erc1155BalanceFetcher;
 use crate::decoder::{
     OperatorApproval as DecodedOperatorApproval, TransferBatch as DecodedTransferBatch,
@@ -19,22 +20,27 @@ use crate::decoder::{
 use crate::grpc_service::Erc1155Service;
 use crate::handlers::{FetchErc1155MetadataCommand, RefreshErc1155TokenUriCommand};
 use crate::proto;
-use crate::storage::{Erc1155Storage, OperatorApprovalData, TokenTransferData, TokenUriData};
-use anyhow::Result;
+use crate::sharding::ShardedErc1155Storage;
+use crate::storage::{OperatorApprovalData, TokenTransferData, TokenUriData};
 use async_trait::async_trait;
+use axum::routing::get;
 use axum::Router;
 use prost::Message;
 use prost_types::Any;
 use starknet::core::types::{Felt, U256};
-use starknet::providers::jsonrpc::{HttpTransport, JsonRpcClient};
 use std::collections::{HashMap, HashSet};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 use torii::command::CommandBusSender;
-use torii::etl::sink::{EventBus, TopicInfo};
+use torii::etl::sink::{
+    ColumnSchema, EnvelopeSchema, EventBus, SinkContractFilter, TableSchema, TopicInfo,
+};
 use torii::etl::{Envelope, ExtractionBatch, Sink, TypeId};
 use torii::grpc::UpdateType;
-use torii_common::{u256_to_bytes, TokenStandard, TokenUriRequest, TokenUriSender};
+use torii::ToriiResult;
+use torii_common::{bytes_to_u256, u256_to_bytes, TokenStandard, TokenUriRequest, TokenUriSender};
+use torii_common::{MaintenanceSchedule, RpcProvider};
 
 /// Default threshold for "live" detection: 100 blocks from chain head.
 /// Events from blocks older than this won't be broadcast to real-time subscribers.
@@ -51,7 +57,7 @@ const LIVE_THRESHOLD_BLOCKS: u64 = 100;
 /// During historical indexing (more than 100 blocks from chain head), events are
 /// stored but not broadcast to avoid overwhelming real-time subscribers.
 pub struct Erc1155Sink {
-    storage: Arc<Erc1155Storage>,
+    storage: ShardedErc1155Storage,
     event_bus: Option<Arc<EventBus>>,
     grpc_service: Option<Erc1155Service>,
     /// Balance fetcher for RPC calls (None = balance tracking disabled)
@@ -70,12 +76,17 @@ pub struct Erc1155Sink {
     total_transfers: AtomicU64,
     total_operator_approvals: AtomicU64,
     total_uri_updates: AtomicU64,
+    /// SQLite maintenance run on idle cycles (disabled by default).
+    maintenance: MaintenanceSchedule,
+    /// Contracts routed to the sink (all when unset)
+    contract_filter: Option<SinkContractFilter>,
 }
 
 impl Erc1155Sink {
-    pub fn new(storage: Arc<Erc1155Storage>) -> Self {
+    /// Writes go to `storage`, a single storage or a [`ShardedErc1155Storage`].
+    pub fn new(storage: impl Into<ShardedErc1155Storage>) -> Self {
         Self {
-            storage,
+            storage: storage.into(),
             event_bus: None,
             grpc_service: None,
             balance_fetcher: None,
@@ -89,6 +100,8 @@ impl Erc1155Sink {
             total_transfers: AtomicU64::new(0),
             total_operator_approvals: AtomicU64::new(0),
             total_uri_updates: AtomicU64::new(0),
+            maintenance: MaintenanceSchedule::disabled(),
+            contract_filter: None,
         }
     }
 
@@ -98,6 +111,18 @@ impl Erc1155Sink {
         self
     }
 
+    /// Only process the envelopes of the contracts `filter` routes to this sink
+    pub fn with_contract_filter(mut self, filter: SinkContractFilter) -> Self {
+        self.contract_filter = Some(filter);
+        self
+    }
+
+    /// Runs SQLite maintenance (vacuum, `ANALYZE`) on idle cycles, at most once per `interval`
+    pub fn with_maintenance_interval(mut self, interval: Duration) -> Self {
+        self.maintenance = MaintenanceSchedule::new(Some(interval));
+        self
+    }
+
     /// Enable balance tracking with a provider for RPC calls
     ///
     /// When enabled, the sink will:
@@ -105,18 +130,21 @@ impl Erc1155Sink {
     /// - Detect when a balance would go negative (indicating missed history)
     /// - Fetch actual balance from the chain and adjust
     /// - Record adjustments in an audit table
-    pub fn with_balance_tracking(mut self, provider: Arc<JsonRpcClient<HttpTransport>>) -> Self {
+    pub fn with_balance_tracking(mut self, provider: Arc<RpcProvider>) -> Self {
         self.balance_fetcher = Some(Arc::new(Erc1155BalanceFetcher::new(provider)));
         self
     }
 
     /// Get a reference to the storage
-    pub fn storage(&self) -> &Arc<Erc1155Storage> {
+    pub fn storage(&self) -> &ShardedErc1155Storage {
         &self.storage
     }
 
     /// Filter function for ERC1155 transfer events
-    fn matches_transfer_filters(
+    ///
+    /// Batch transfers are published once per (token_id, value) pair, so the
+    /// "token_id" filter (hex string) applies to them as well.
+    pub fn matches_transfer_filters(
         transfer: &proto::TokenTransfer,
         filters: &HashMap<String, String>,
     ) -> bool {
@@ -159,6 +187,13 @@ impl Erc1155Sink {
             }
         }
 
+        // Exact token id filter
+        if let Some(token_id_filter) = filters.get("token_id") {
+            if !token_id_matches(&transfer.token_id, token_id_filter) {
+                return false;
+            }
+        }
+
         true
     }
 
@@ -167,7 +202,7 @@ impl Erc1155Sink {
     /// Supports filters:
     /// - "token": Filter by token contract address (hex string)
     /// - "token_id": Filter by token id (hex string)
-    fn matches_uri_filters(uri: &proto::TokenUri, filters: &HashMap<String, String>) -> bool {
+    pub fn matches_uri_filters(uri: &proto::TokenUri, filters: &HashMap<String, String>) -> bool {
         if filters.is_empty() {
             return true;
         }
@@ -180,8 +215,7 @@ impl Erc1155Sink {
         }
 
         if let Some(token_id_filter) = filters.get("token_id") {
-            let token_id_hex = format!("0x{}", hex::encode(&uri.token_id));
-            if !token_id_hex.eq_ignore_ascii_case(token_id_filter) {
+            if !token_id_matches(&uri.token_id, token_id_filter) {
                 return false;
             }
         }
@@ -189,19 +223,28 @@ impl Erc1155Sink {
         true
     }
 
-    fn enqueue_token_uri_request(&self, contract: Felt, token_id: U256) -> bool {
+    /// Queues a token URI fetch. A known `uri` (from a `URI` event) skips the on-chain call.
+    fn enqueue_token_uri_request(
+        &self,
+        contract: Felt,
+        token_id: U256,
+        uri: Option<String>,
+    ) -> bool {
         if let Some(sender) = &self.token_uri_sender {
             return sender.request_update(TokenUriRequest {
                 contract,
                 token_id,
                 standard: TokenStandard::Erc1155,
+                uri,
             });
         }
 
         if let Some(command_bus) = &self.command_bus {
-            if let Err(error) =
-                command_bus.dispatch(RefreshErc1155TokenUriCommand { contract, token_id })
-            {
+            if let Err(error) = command_bus.dispatch(RefreshErc1155TokenUriCommand {
+                contract,
+                token_id,
+                uri,
+            }) {
                 tracing::warn!(
                     target: "torii_erc1155::sink",
                     error = %error,
@@ -216,12 +259,31 @@ impl Erc1155Sink {
     }
 }
 
+/// Whether an encoded token id equals a hex filter value, regardless of leading zeros.
+fn token_id_matches(token_id: &[u8], filter: &str) -> bool {
+    let filter = filter
+        .strip_prefix("0x")
+        .or_else(|| filter.strip_prefix("0X"))
+        .unwrap_or(filter)
+        .trim_start_matches('0');
+    let filter = if filter.is_empty() { "0" } else { filter };
+    format!("{:x}", bytes_to_u256(token_id)).eq_ignore_ascii_case(filter)
+}
+
 #[async_trait]
 impl Sink for Erc1155Sink {
     fn name(&self) -> &'static str {
         "erc1155"
     }
 
+    fn version(&self) -> Option<&str> {
+        Some(env!("CARGO_PKG_VERSION"))
+    }
+
+    fn descriptor_set(&self) -> Option<&'static [u8]> {
+        Some(crate::FILE_DESCRIPTOR_SET)
+    }
+
     fn interested_types(&self) -> Vec<TypeId> {
         vec![
             TypeId::new("erc1155.transfer_single"),
@@ -231,18 +293,49 @@ impl Sink for Erc1155Sink {
         ]
     }
 
+    fn contract_filter(&self) -> Option<&SinkContractFilter> {
+        self.contract_filter.as_ref()
+    }
+
+    fn envelope_schemas(&self) -> Vec<EnvelopeSchema> {
+        vec![
+            EnvelopeSchema::new(
+                "erc1155.transfer_single",
+                "torii.sinks.erc1155.TokenTransfer",
+            ),
+            EnvelopeSchema::new(
+                "erc1155.transfer_batch",
+                "torii.sinks.erc1155.TokenTransfer",
+            ),
+            EnvelopeSchema::new(
+                "erc1155.approval_for_all",
+                "torii.sinks.erc1155.OperatorApproval",
+            ),
+            EnvelopeSchema::new("erc1155.uri", "torii.sinks.erc1155.TokenUri"),
+        ]
+    }
+
     async fn initialize(
         &mut self,
         event_bus: Arc<EventBus>,
         context: &torii::etl::sink::SinkContext,
-    ) -> Result<()> {
+    ) -> ToriiResult<()> {
+        // End the service's streams with the server-wide shutdown notice.
+        if let Some(grpc_service) = &self.grpc_service {
+            let grpc_service = grpc_service.clone();
+            let shutdown = event_bus.subscription_manager().shutdown_signal().clone();
+            tokio::spawn(async move {
+                let notice = shutdown.notified().await;
+                grpc_service.notify_shutdown(notice.block_number);
+            });
+        }
         self.event_bus = Some(event_bus);
         self.command_bus = Some(context.command_bus.clone());
         tracing::info!(target: "torii_erc1155::sink", "ERC1155 sink initialized");
         Ok(())
     }
 
-    async fn process(&self, envelopes: &[Envelope], batch: &ExtractionBatch) -> Result<()> {
+    async fn process(&self, envelopes: &[Envelope], batch: &ExtractionBatch) -> ToriiResult<()> {
         let mut transfers: Vec<TokenTransferData> = Vec::with_capacity(envelopes.len());
         let mut operator_approvals: Vec<OperatorApprovalData> = Vec::with_capacity(envelopes.len());
         let mut uri_updates: Vec<TokenUriData> = Vec::with_capacity(envelopes.len());
@@ -279,6 +372,7 @@ impl Sink for Erc1155Sink {
                         block_number: transfer.block_number,
                         tx_hash: transfer.transaction_hash,
                         timestamp,
+                        event_index: envelope.meta.event_index,
                     });
                 }
             }
@@ -303,6 +397,7 @@ impl Sink for Erc1155Sink {
                         block_number: transfer.block_number,
                         tx_hash: transfer.transaction_hash,
                         timestamp,
+                        event_index: envelope.meta.event_index,
                     });
                 }
             }
@@ -430,7 +525,7 @@ impl Sink for Erc1155Sink {
                         if !pending.insert((contract, token_id)) {
                             continue;
                         }
-                        if !self.enqueue_token_uri_request(contract, token_id) {
+                        if !self.enqueue_token_uri_request(contract, token_id, None) {
                             pending.remove(&(contract, token_id));
                         }
                     }
@@ -456,7 +551,7 @@ impl Sink for Erc1155Sink {
                         error = %e,
                         "Failed to batch insert transfers"
                     );
-                    return Err(e);
+                    return Err(e.into());
                 }
             };
 
@@ -559,7 +654,9 @@ impl Sink for Erc1155Sink {
                         // Publish to EventBus
                         if let Some(event_bus) = &self.event_bus {
                             let mut buf = Vec::new();
-                            proto_transfer.encode(&mut buf)?;
+                            proto_transfer
+                                .encode(&mut buf)
+                                .map_err(anyhow::Error::from)?;
                             let any = Any {
                                 type_url: "type.googleapis.com/torii.sinks.erc1155.TokenTransfer"
                                     .to_string(),
@@ -610,7 +707,7 @@ impl Sink for Erc1155Sink {
                         operator_approvals.len(),
                         e
                     );
-                    return Err(e);
+                    return Err(e.into());
                 }
             }
         }
@@ -629,6 +726,17 @@ impl Sink for Erc1155Sink {
                         "Batch upserted token URI updates"
                     );
 
+                    // Resolve metadata of the new URIs (latest URI per token id wins)
+                    if self.token_uri_commands_enabled {
+                        let latest = uri_updates
+                            .iter()
+                            .map(|uri| ((uri.token, uri.token_id), uri.uri.clone()))
+                            .collect::<HashMap<_, _>>();
+                        for ((contract, token_id), uri) in latest {
+                            self.enqueue_token_uri_request(contract, token_id, Some(uri));
+                        }
+                    }
+
                     // Publish URI updates to topic subscribers
                     if let Some(event_bus) = &self.event_bus {
                         for uri in &uri_updates {
@@ -640,7 +748,7 @@ impl Sink for Erc1155Sink {
                             };
 
                             let mut buf = Vec::new();
-                            proto_uri.encode(&mut buf)?;
+                            proto_uri.encode(&mut buf).map_err(anyhow::Error::from)?;
                             let any = Any {
                                 type_url: "type.googleapis.com/torii.sinks.erc1155.TokenUri"
                                     .to_string(),
@@ -665,7 +773,7 @@ impl Sink for Erc1155Sink {
                         error = %e,
                         "Failed to batch upsert token URI updates"
                     );
-                    return Err(e);
+                    return Err(e.into());
                 }
             }
         }
@@ -688,6 +796,25 @@ impl Sink for Erc1155Sink {
         Ok(())
     }
 
+    async fn indexed_block(&self) -> ToriiResult<Option<u64>> {
+        // Block of the latest stored transfer
+        Ok(self.storage.get_latest_block().await?)
+    }
+
+    async fn on_idle(&self) -> ToriiResult<()> {
+        if !self.maintenance.try_start() {
+            return Ok(());
+        }
+        let start = std::time::Instant::now();
+        self.storage.run_maintenance().await?;
+        tracing::info!(
+            target: "torii_erc1155::sink",
+            elapsed_ms = start.elapsed().as_millis() as u64,
+            "Ran storage maintenance"
+        );
+        Ok(())
+    }
+
     fn topics(&self) -> Vec<TopicInfo> {
         vec![
             TopicInfo::new(
@@ -697,24 +824,108 @@ impl Sink for Erc1155Sink {
                     "from".to_string(),
                     "to".to_string(),
                     "wallet".to_string(),
+                    "token_id".to_string(),
                 ],
-                "ERC1155 token transfers. Use 'wallet' filter for from OR to matching.",
-            ),
+                "ERC1155 token transfers, one update per transferred id of batch transfers. Use 'wallet' filter for from OR to matching.",
+            )
+            .with_message_type("torii.sinks.erc1155.TokenTransfer"),
             TopicInfo::new(
                 "erc1155.metadata",
                 vec!["token".to_string()],
                 "ERC1155 token metadata updates (registered/updated token attributes).",
-            ),
+            )
+            .with_message_type("torii.sinks.erc1155.TokenMetadataEntry"),
             TopicInfo::new(
                 "erc1155.uri",
                 vec!["token".to_string(), "token_id".to_string()],
                 "ERC1155 token URI updates (registered/updated token attributes).",
+            )
+            .with_message_type("torii.sinks.erc1155.TokenUri"),
+        ]
+    }
+
+    fn tables(&self) -> Vec<TableSchema> {
+        vec![
+            TableSchema::new(
+                "token_transfers",
+                "One row per transferred id of an ERC1155 TransferSingle/TransferBatch event.",
+                vec![
+                    ColumnSchema::new("id", "i64"),
+                    ColumnSchema::new("token", "felt"),
+                    ColumnSchema::new("operator", "felt"),
+                    ColumnSchema::new("from_addr", "felt"),
+                    ColumnSchema::new("to_addr", "felt"),
+                    ColumnSchema::new("token_id", "u256"),
+                    ColumnSchema::new("amount", "u256"),
+                    ColumnSchema::new("is_batch", "bool"),
+                    ColumnSchema::new("batch_index", "u64"),
+                    ColumnSchema::new("block_number", "u64"),
+                    ColumnSchema::new("tx_hash", "felt"),
+                    ColumnSchema::new("timestamp", "i64").nullable(),
+                ],
+            ),
+            TableSchema::new(
+                "erc1155_balances",
+                "Current balance per (contract, wallet, token_id).",
+                vec![
+                    ColumnSchema::new("id", "i64"),
+                    ColumnSchema::new("contract", "felt"),
+                    ColumnSchema::new("wallet", "felt"),
+                    ColumnSchema::new("token_id", "u256"),
+                    ColumnSchema::new("balance", "u256"),
+                    ColumnSchema::new("last_block", "u64"),
+                    ColumnSchema::new("updated_at", "i64").nullable(),
+                ],
+            ),
+            TableSchema::new(
+                "token_operators",
+                "Current operator approval per (token, owner, operator).",
+                vec![
+                    ColumnSchema::new("id", "i64"),
+                    ColumnSchema::new("token", "felt"),
+                    ColumnSchema::new("owner", "felt"),
+                    ColumnSchema::new("operator", "felt"),
+                    ColumnSchema::new("approved", "bool"),
+                    ColumnSchema::new("block_number", "u64"),
+                    ColumnSchema::new("tx_hash", "felt"),
+                    ColumnSchema::new("timestamp", "i64").nullable(),
+                ],
+            ),
+            TableSchema::new(
+                "token_metadata",
+                "Token attributes fetched from the contract.",
+                vec![
+                    ColumnSchema::new("token", "felt"),
+                    ColumnSchema::new("name", "text").nullable(),
+                    ColumnSchema::new("symbol", "text").nullable(),
+                    ColumnSchema::new("total_supply", "u256").nullable(),
+                ],
+            ),
+            TableSchema::new(
+                "token_uris",
+                "Token URI and resolved metadata per (token, token_id).",
+                vec![
+                    ColumnSchema::new("token", "felt"),
+                    ColumnSchema::new("token_id", "u256"),
+                    ColumnSchema::new("uri", "text").nullable(),
+                    ColumnSchema::new("metadata_json", "json").nullable(),
+                    ColumnSchema::new("updated_at", "i64"),
+                ],
             ),
         ]
     }
 
     fn build_routes(&self) -> Router {
+        let state = Erc1155ApiState {
+            storage: self.storage.clone(),
+        };
+
         Router::new()
+            .route(
+                "/erc1155/transfers/export",
+                get(api::transfers_export_handler),
+            )
+            .with_state(state)
     }
 }
 
@@ -724,7 +935,7 @@ impl Erc1155Sink {
         self
     }
 
-    pub fn with_metadata_fetching(self, _provider: Arc<JsonRpcClient<HttpTransport>>) -> Self {
+    pub fn with_metadata_fetching(self, _provider: Arc<RpcProvider>) -> Self {
         self.with_metadata_commands()
     }
 
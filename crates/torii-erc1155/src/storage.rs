@@ -12,12 +12,53 @@ use starknet::core::types::{Felt, U256};
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::{Arc, Mutex};
 use tokio_postgres::{types::ToSql as PgToSql, Client, NoTls};
+use torii::etl::migrations::{self, Migration};
 use torii_common::{
-    blob_to_felt, blob_to_u256, felt_to_blob, u256_to_blob, TokenUriResult, TokenUriStore,
+    apply_sqlite_encryption, blob_to_felt, blob_to_u256, felt_to_blob, split_page, u256_to_blob,
+    FieldMask, ReplayParam, ReplayRange, TokenUriResult, TokenUriStore, SQLITE_MAINTENANCE_SQL,
 };
 
 use crate::balance_fetcher::Erc1155BalanceFetchRequest;
 
+/// Migration component name recorded in `schema_version`
+const MIGRATION_COMPONENT: &str = "erc1155";
+
+/// Embedded schema migrations
+const SQLITE_MIGRATIONS: &[Migration] = &[
+    Migration::new(
+        1,
+        "initial",
+        include_str!("../migrations/sqlite/0001_initial.sql"),
+    ),
+    Migration::new(
+        2,
+        "transfer_event_index",
+        include_str!("../migrations/sqlite/0002_transfer_event_index.sql"),
+    ),
+    Migration::new(
+        3,
+        "inventory_index",
+        include_str!("../migrations/sqlite/0003_inventory_index.sql"),
+    ),
+];
+const POSTGRES_MIGRATIONS: &[Migration] = &[
+    Migration::new(
+        1,
+        "initial",
+        include_str!("../migrations/postgres/0001_initial.sql"),
+    ),
+    Migration::new(
+        2,
+        "transfer_event_index",
+        include_str!("../migrations/postgres/0002_transfer_event_index.sql"),
+    ),
+    Migration::new(
+        3,
+        "inventory_index",
+        include_str!("../migrations/postgres/0003_inventory_index.sql"),
+    ),
+];
+
 const SQLITE_MAX_BIND_VARS: usize = 900;
 const SQLITE_TOKEN_BATCH_SIZE: usize = SQLITE_MAX_BIND_VARS;
 const SQLITE_TOKEN_PAIR_BATCH_SIZE: usize = SQLITE_MAX_BIND_VARS / 2;
@@ -66,6 +107,7 @@ enum StorageBackend {
 }
 
 /// Token transfer data for batch insertion
+#[derive(Clone)]
 pub struct TokenTransferData {
     pub id: Option<i64>,
     pub token: Felt,
@@ -79,9 +121,18 @@ pub struct TokenTransferData {
     pub block_number: u64,
     pub tx_hash: Felt,
     pub timestamp: Option<i64>,
+    /// Position of the transfer among the events of its transaction
+    pub event_index: Option<u32>,
+}
+
+impl ReplayRow for TokenTransferData {
+    fn replay_id(&self) -> Option<i64> {
+        self.id
+    }
 }
 
 /// Operator approval data
+#[derive(Clone)]
 pub struct OperatorApprovalData {
     pub id: Option<i64>,
     pub token: Felt,
@@ -94,6 +145,7 @@ pub struct OperatorApprovalData {
 }
 
 /// Token URI data
+#[derive(Clone)]
 pub struct TokenUriData {
     pub token: Felt,
     pub token_id: U256,
@@ -110,6 +162,13 @@ pub struct TransferCursor {
     pub id: i64,
 }
 
+/// Cursor for enumerating the inventory of a wallet (last returned balance)
+#[derive(Debug, Clone, Copy)]
+pub struct InventoryCursor {
+    pub contract: Felt,
+    pub token_id: U256,
+}
+
 /// Aggregated facet count for one key/value pair.
 pub struct AttributeFacetCount {
     pub key: String,
@@ -160,148 +219,14 @@ impl Erc1155Storage {
     /// Create or open the database
     pub async fn new(db_path: &str) -> Result<Self> {
         if db_path.starts_with("postgres://") || db_path.starts_with("postgresql://") {
-            let (client, connection) = tokio_postgres::connect(db_path, NoTls).await?;
+            let (mut client, connection) = tokio_postgres::connect(db_path, NoTls).await?;
             tokio::spawn(async move {
                 if let Err(e) = connection.await {
                     tracing::error!(target: "torii_erc1155::storage", error = %e, "PostgreSQL connection task failed");
                 }
             });
 
-            client.batch_execute(
-                r"
-                CREATE SCHEMA IF NOT EXISTS erc1155;
-
-                CREATE TABLE IF NOT EXISTS erc1155.token_transfers (
-                    id BIGSERIAL PRIMARY KEY,
-                    token BYTEA NOT NULL,
-                    operator BYTEA NOT NULL,
-                    from_addr BYTEA NOT NULL,
-                    to_addr BYTEA NOT NULL,
-                    token_id BYTEA NOT NULL,
-                    amount BYTEA NOT NULL,
-                    is_batch TEXT NOT NULL DEFAULT '0',
-                    batch_index TEXT NOT NULL DEFAULT '0',
-                    block_number TEXT NOT NULL,
-                    tx_hash BYTEA NOT NULL,
-                    timestamp TEXT,
-                    UNIQUE(token, tx_hash, token_id, from_addr, to_addr, batch_index)
-                );
-                CREATE INDEX IF NOT EXISTS idx_token_transfers_token ON erc1155.token_transfers(token);
-                CREATE INDEX IF NOT EXISTS idx_token_transfers_from ON erc1155.token_transfers(from_addr);
-                CREATE INDEX IF NOT EXISTS idx_token_transfers_to ON erc1155.token_transfers(to_addr);
-                CREATE INDEX IF NOT EXISTS idx_token_transfers_block ON erc1155.token_transfers(block_number DESC);
-                CREATE INDEX IF NOT EXISTS idx_token_transfers_token_id ON erc1155.token_transfers(token, token_id);
-
-                CREATE TABLE IF NOT EXISTS erc1155.token_wallet_activity (
-                    id BIGSERIAL PRIMARY KEY,
-                    wallet_address BYTEA NOT NULL,
-                    token BYTEA NOT NULL,
-                    transfer_id BIGINT NOT NULL REFERENCES erc1155.token_transfers(id),
-                    direction TEXT NOT NULL CHECK(direction IN ('sent', 'received', 'both')),
-                    block_number TEXT NOT NULL
-                );
-                CREATE INDEX IF NOT EXISTS idx_token_wallet_activity_wallet_block ON erc1155.token_wallet_activity(wallet_address, block_number DESC);
-                CREATE INDEX IF NOT EXISTS idx_token_wallet_activity_wallet_token ON erc1155.token_wallet_activity(wallet_address, token, block_number DESC);
-
-                CREATE TABLE IF NOT EXISTS erc1155.token_operators (
-                    id BIGSERIAL PRIMARY KEY,
-                    token BYTEA NOT NULL,
-                    owner BYTEA NOT NULL,
-                    operator BYTEA NOT NULL,
-                    approved TEXT NOT NULL,
-                    block_number TEXT NOT NULL,
-                    tx_hash BYTEA NOT NULL,
-                    timestamp TEXT,
-                    UNIQUE(token, owner, operator)
-                );
-
-                CREATE TABLE IF NOT EXISTS erc1155.token_uris (
-                    token BYTEA NOT NULL,
-                    token_id BYTEA NOT NULL,
-                    uri TEXT,
-                    metadata_json TEXT,
-                    updated_at TEXT NOT NULL DEFAULT (EXTRACT(EPOCH FROM NOW())::BIGINT::TEXT),
-                    PRIMARY KEY (token, token_id)
-                );
-
-                CREATE TABLE IF NOT EXISTS erc1155.token_attributes (
-                    token BYTEA NOT NULL,
-                    token_id BYTEA NOT NULL,
-                    key TEXT NOT NULL,
-                    value TEXT NOT NULL,
-                    PRIMARY KEY (token, token_id, key)
-                );
-                CREATE INDEX IF NOT EXISTS idx_token_attributes_token ON erc1155.token_attributes(token);
-                CREATE INDEX IF NOT EXISTS idx_token_attributes_key ON erc1155.token_attributes(key);
-                CREATE INDEX IF NOT EXISTS idx_token_attributes_key_value ON erc1155.token_attributes(key, value);
-                CREATE INDEX IF NOT EXISTS idx_token_attributes_token_key_value ON erc1155.token_attributes(token, key, value);
-                CREATE INDEX IF NOT EXISTS idx_token_attributes_token_token_id ON erc1155.token_attributes(token, token_id);
-
-                CREATE TABLE IF NOT EXISTS erc1155.facet_keys (
-                    id BIGSERIAL PRIMARY KEY,
-                    token BYTEA NOT NULL,
-                    key_norm TEXT NOT NULL,
-                    key_display TEXT NOT NULL,
-                    UNIQUE(token, key_norm)
-                );
-                CREATE INDEX IF NOT EXISTS idx_facet_keys_token_key_norm ON erc1155.facet_keys(token, key_norm);
-
-                CREATE TABLE IF NOT EXISTS erc1155.facet_values (
-                    id BIGSERIAL PRIMARY KEY,
-                    token BYTEA NOT NULL,
-                    facet_key_id BIGINT NOT NULL REFERENCES erc1155.facet_keys(id) ON DELETE CASCADE,
-                    value_norm TEXT NOT NULL,
-                    value_display TEXT NOT NULL,
-                    token_count TEXT NOT NULL DEFAULT '0',
-                    UNIQUE(token, facet_key_id, value_norm)
-                );
-                CREATE INDEX IF NOT EXISTS idx_facet_values_token_key_value ON erc1155.facet_values(token, facet_key_id, value_norm);
-
-                CREATE TABLE IF NOT EXISTS erc1155.facet_token_map (
-                    token BYTEA NOT NULL,
-                    token_id BYTEA NOT NULL,
-                    facet_key_id BIGINT NOT NULL REFERENCES erc1155.facet_keys(id) ON DELETE CASCADE,
-                    facet_value_id BIGINT NOT NULL REFERENCES erc1155.facet_values(id) ON DELETE CASCADE,
-                    PRIMARY KEY (token, token_id, facet_key_id)
-                );
-                CREATE INDEX IF NOT EXISTS idx_facet_token_map_token_value_token_id ON erc1155.facet_token_map(token, facet_value_id, token_id);
-                CREATE INDEX IF NOT EXISTS idx_facet_token_map_token_token_id_key_value ON erc1155.facet_token_map(token, token_id, facet_key_id, facet_value_id);
-
-                CREATE TABLE IF NOT EXISTS erc1155.erc1155_balances (
-                    id BIGSERIAL PRIMARY KEY,
-                    contract BYTEA NOT NULL,
-                    wallet BYTEA NOT NULL,
-                    token_id BYTEA NOT NULL,
-                    balance BYTEA NOT NULL,
-                    last_block TEXT NOT NULL,
-                    updated_at TEXT DEFAULT (EXTRACT(EPOCH FROM NOW())::BIGINT::TEXT),
-                    UNIQUE(contract, wallet, token_id)
-                );
-                CREATE INDEX IF NOT EXISTS idx_erc1155_balances_contract ON erc1155.erc1155_balances(contract);
-                CREATE INDEX IF NOT EXISTS idx_erc1155_balances_wallet ON erc1155.erc1155_balances(wallet);
-                CREATE INDEX IF NOT EXISTS idx_erc1155_balances_contract_wallet ON erc1155.erc1155_balances(contract, wallet);
-
-                CREATE TABLE IF NOT EXISTS erc1155.erc1155_balance_adjustments (
-                    id BIGSERIAL PRIMARY KEY,
-                    contract BYTEA NOT NULL,
-                    wallet BYTEA NOT NULL,
-                    token_id BYTEA NOT NULL,
-                    computed_balance BYTEA NOT NULL,
-                    actual_balance BYTEA NOT NULL,
-                    adjusted_at_block TEXT NOT NULL,
-                    tx_hash BYTEA NOT NULL,
-                    created_at TEXT DEFAULT (EXTRACT(EPOCH FROM NOW())::BIGINT::TEXT)
-                );
-                CREATE INDEX IF NOT EXISTS idx_erc1155_adjustments_wallet ON erc1155.erc1155_balance_adjustments(wallet);
-
-                CREATE TABLE IF NOT EXISTS erc1155.token_metadata (
-                    token BYTEA PRIMARY KEY,
-                    name TEXT,
-                    symbol TEXT,
-                    total_supply BYTEA
-                );
-                ",
-            ).await?;
+            migrations::migrate(&mut client, MIGRATION_COMPONENT, POSTGRES_MIGRATIONS).await?;
 
             tracing::info!(target: "torii_erc1155::storage", "PostgreSQL storage initialized");
             return Ok(Self {
@@ -311,11 +236,15 @@ impl Erc1155Storage {
             });
         }
 
-        let conn = Connection::open(db_path)?;
+        let mut conn = Connection::open(db_path)?;
+        if apply_sqlite_encryption(&conn)? {
+            tracing::info!(target: "torii_erc1155::storage", "SQLite storage encrypted with SQLCipher");
+        }
 
         // Enable WAL mode + Performance PRAGMAs
         conn.execute_batch(
-            "PRAGMA journal_mode=WAL;
+            "PRAGMA auto_vacuum=INCREMENTAL;
+             PRAGMA journal_mode=WAL;
              PRAGMA synchronous=NORMAL;
              PRAGMA foreign_keys=ON;
              PRAGMA cache_size=-64000;
@@ -328,231 +257,8 @@ impl Erc1155Storage {
 
         tracing::info!(target: "torii_erc1155::storage", "SQLite configured: WAL mode, 64MB cache, 256MB mmap, NORMAL sync");
 
-        // Token transfers table (both single and batch transfers)
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS token_transfers (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                token BLOB NOT NULL,
-                operator BLOB NOT NULL,
-                from_addr BLOB NOT NULL,
-                to_addr BLOB NOT NULL,
-                token_id BLOB NOT NULL,
-                amount BLOB NOT NULL,
-                is_batch TEXT NOT NULL DEFAULT '0',
-                batch_index TEXT NOT NULL DEFAULT '0',
-                block_number TEXT NOT NULL,
-                tx_hash BLOB NOT NULL,
-                timestamp TEXT,
-                UNIQUE(token, tx_hash, token_id, from_addr, to_addr, batch_index)
-            )",
-            [],
-        )?;
-
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_token_transfers_token ON token_transfers(token)",
-            [],
-        )?;
-
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_token_transfers_from ON token_transfers(from_addr)",
-            [],
-        )?;
-
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_token_transfers_to ON token_transfers(to_addr)",
-            [],
-        )?;
-
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_token_transfers_block ON token_transfers(block_number DESC)",
-            [],
-        )?;
-
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_token_transfers_token_id ON token_transfers(token, token_id)",
-            [],
-        )?;
-
-        // Wallet activity table for efficient OR queries
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS token_wallet_activity (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                wallet_address BLOB NOT NULL,
-                token BLOB NOT NULL,
-                transfer_id INTEGER NOT NULL,
-                direction TEXT NOT NULL CHECK(direction IN ('sent', 'received', 'both')),
-                block_number TEXT NOT NULL,
-                FOREIGN KEY (transfer_id) REFERENCES token_transfers(id)
-            )",
-            [],
-        )?;
-
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_token_wallet_activity_wallet_block
-             ON token_wallet_activity(wallet_address, block_number DESC)",
-            [],
-        )?;
-
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_token_wallet_activity_wallet_token
-             ON token_wallet_activity(wallet_address, token, block_number DESC)",
-            [],
-        )?;
-
-        // Operator approvals
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS token_operators (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                token BLOB NOT NULL,
-                owner BLOB NOT NULL,
-                operator BLOB NOT NULL,
-                approved TEXT NOT NULL,
-                block_number TEXT NOT NULL,
-                tx_hash BLOB NOT NULL,
-                timestamp TEXT,
-                UNIQUE(token, owner, operator)
-            )",
-            [],
-        )?;
-
-        // URI metadata
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS token_uris (
-                token BLOB NOT NULL,
-                token_id BLOB NOT NULL,
-                uri TEXT,
-                metadata_json TEXT,
-                updated_at TEXT NOT NULL DEFAULT (strftime('%s', 'now')),
-                PRIMARY KEY (token, token_id)
-            )",
-            [],
-        )?;
-
-        conn.execute_batch(
-            "CREATE TABLE IF NOT EXISTS token_attributes (
-                token BLOB NOT NULL,
-                token_id BLOB NOT NULL,
-                key TEXT NOT NULL,
-                value TEXT NOT NULL,
-                PRIMARY KEY (token, token_id, key),
-                FOREIGN KEY (token, token_id) REFERENCES token_uris(token, token_id)
-            );
-            CREATE INDEX IF NOT EXISTS idx_token_attributes_token ON token_attributes(token);
-            CREATE INDEX IF NOT EXISTS idx_token_attributes_key ON token_attributes(key);
-            CREATE INDEX IF NOT EXISTS idx_token_attributes_key_value ON token_attributes(key, value);
-            CREATE INDEX IF NOT EXISTS idx_token_attributes_token_key_value ON token_attributes(token, key, value);
-            CREATE INDEX IF NOT EXISTS idx_token_attributes_token_token_id ON token_attributes(token, token_id);
-
-            CREATE TABLE IF NOT EXISTS facet_keys (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                token BLOB NOT NULL,
-                key_norm TEXT NOT NULL,
-                key_display TEXT NOT NULL,
-                UNIQUE(token, key_norm)
-            );
-            CREATE INDEX IF NOT EXISTS idx_facet_keys_token_key_norm ON facet_keys(token, key_norm);
-
-            CREATE TABLE IF NOT EXISTS facet_values (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                token BLOB NOT NULL,
-                facet_key_id INTEGER NOT NULL,
-                value_norm TEXT NOT NULL,
-                value_display TEXT NOT NULL,
-                token_count TEXT NOT NULL DEFAULT '0',
-                UNIQUE(token, facet_key_id, value_norm),
-                FOREIGN KEY (facet_key_id) REFERENCES facet_keys(id) ON DELETE CASCADE
-            );
-            CREATE INDEX IF NOT EXISTS idx_facet_values_token_key_value ON facet_values(token, facet_key_id, value_norm);
-
-            CREATE TABLE IF NOT EXISTS facet_token_map (
-                token BLOB NOT NULL,
-                token_id BLOB NOT NULL,
-                facet_key_id INTEGER NOT NULL,
-                facet_value_id INTEGER NOT NULL,
-                PRIMARY KEY (token, token_id, facet_key_id),
-                FOREIGN KEY (facet_key_id) REFERENCES facet_keys(id) ON DELETE CASCADE,
-                FOREIGN KEY (facet_value_id) REFERENCES facet_values(id) ON DELETE CASCADE
-            );
-            CREATE INDEX IF NOT EXISTS idx_facet_token_map_token_value_token_id ON facet_token_map(token, facet_value_id, token_id);
-            CREATE INDEX IF NOT EXISTS idx_facet_token_map_token_token_id_key_value ON facet_token_map(token, token_id, facet_key_id, facet_value_id);",
-        )?;
-
-        // Balance tracking tables
-        // Tracks current balance per (contract, wallet, token_id) tuple
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS erc1155_balances (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                contract BLOB NOT NULL,
-                wallet BLOB NOT NULL,
-                token_id BLOB NOT NULL,
-                balance BLOB NOT NULL,
-                last_block TEXT NOT NULL,
-                updated_at TEXT DEFAULT (strftime('%s', 'now')),
-                UNIQUE(contract, wallet, token_id)
-            )",
-            [],
-        )?;
-
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_erc1155_balances_contract ON erc1155_balances(contract)",
-            [],
-        )?;
-
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_erc1155_balances_wallet ON erc1155_balances(wallet)",
-            [],
-        )?;
-
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_erc1155_balances_contract_wallet
-             ON erc1155_balances(contract, wallet)",
-            [],
-        )?;
-
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_erc1155_balances_contract_wallet_token_id_ord
-             ON erc1155_balances(contract, wallet, length(token_id), token_id)",
-            [],
-        )?;
-
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_erc1155_balances_wallet_contract_token_id_ord
-             ON erc1155_balances(wallet, contract, length(token_id), token_id)",
-            [],
-        )?;
-
-        // Balance adjustments table for audit trail
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS erc1155_balance_adjustments (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                contract BLOB NOT NULL,
-                wallet BLOB NOT NULL,
-                token_id BLOB NOT NULL,
-                computed_balance BLOB NOT NULL,
-                actual_balance BLOB NOT NULL,
-                adjusted_at_block TEXT NOT NULL,
-                tx_hash BLOB NOT NULL,
-                created_at TEXT DEFAULT (strftime('%s', 'now'))
-            )",
-            [],
-        )?;
-
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_erc1155_adjustments_wallet
-             ON erc1155_balance_adjustments(wallet)",
-            [],
-        )?;
-
-        // Token metadata table
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS token_metadata (
-                token BLOB PRIMARY KEY,
-                name TEXT,
-                symbol TEXT,
-                total_supply BLOB
-            )",
-            [],
-        )?;
+        // Create or upgrade tables
+        migrations::migrate(&mut conn, MIGRATION_COMPONENT, SQLITE_MIGRATIONS).await?;
 
         tracing::info!(target: "torii_erc1155::storage", db_path = %db_path, "ERC1155 database initialized");
 
@@ -579,8 +285,8 @@ impl Erc1155Storage {
 
         {
             let mut stmt = tx.prepare_cached(
-                "INSERT OR IGNORE INTO token_transfers (token, operator, from_addr, to_addr, token_id, amount, is_batch, batch_index, block_number, tx_hash, timestamp)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, COALESCE(?11, CAST(strftime('%s', 'now') AS TEXT)))",
+                "INSERT OR IGNORE INTO token_transfers (token, operator, from_addr, to_addr, token_id, amount, is_batch, batch_index, block_number, tx_hash, timestamp, event_index)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, COALESCE(?11, CAST(strftime('%s', 'now') AS TEXT)), ?12)",
             )?;
             let mut wallet_both_stmt = tx.prepare_cached(
                 "INSERT INTO token_wallet_activity (wallet_address, token, transfer_id, direction, block_number)
@@ -617,6 +323,7 @@ impl Erc1155Storage {
                     transfer.block_number.to_string(),
                     &tx_hash_blob,
                     ts_str,
+                    transfer.event_index,
                 ])?;
 
                 if rows > 0 {
@@ -710,6 +417,8 @@ impl Erc1155Storage {
     }
 
     /// Insert or update token URIs in a single transaction
+    ///
+    /// Resolved metadata is cleared when a token's URI changes, until it is fetched again.
     pub async fn upsert_token_uris_batch(&self, uris: &[TokenUriData]) -> Result<usize> {
         if self.backend == StorageBackend::Postgres {
             return self.pg_upsert_token_uris_batch(uris).await;
@@ -726,6 +435,8 @@ impl Erc1155Storage {
             "INSERT INTO token_uris (token, token_id, uri, updated_at)
              VALUES (?1, ?2, ?3, strftime('%s', 'now'))
              ON CONFLICT(token, token_id) DO UPDATE SET
+               metadata_json = CASE WHEN token_uris.uri IS excluded.uri
+                 THEN token_uris.metadata_json ELSE NULL END,
                uri = excluded.uri,
                updated_at = excluded.updated_at",
         )?;
@@ -758,27 +469,63 @@ impl Erc1155Storage {
         block_to: Option<u64>,
         cursor: Option<TransferCursor>,
         limit: u32,
+    ) -> Result<(Vec<TokenTransferData>, Option<TransferCursor>)> {
+        self.get_transfers_projected(
+            wallet,
+            from,
+            to,
+            operator,
+            tokens,
+            token_ids,
+            block_from,
+            block_to,
+            cursor,
+            limit,
+            &FieldMask::all(),
+        )
+        .await
+    }
+
+    /// Get filtered transfers, only selecting the columns of the fields in `mask`
+    ///
+    /// Unselected fields are left zeroed (`id` and `block_number` are always selected
+    /// for pagination).
+    #[allow(clippy::too_many_arguments)]
+    pub async fn get_transfers_projected(
+        &self,
+        wallet: Option<Felt>,
+        from: Option<Felt>,
+        to: Option<Felt>,
+        operator: Option<Felt>,
+        tokens: &[Felt],
+        token_ids: &[U256],
+        block_from: Option<u64>,
+        block_to: Option<u64>,
+        cursor: Option<TransferCursor>,
+        limit: u32,
+        mask: &FieldMask,
     ) -> Result<(Vec<TokenTransferData>, Option<TransferCursor>)> {
         if self.backend == StorageBackend::Postgres {
             return self
                 .pg_get_transfers_filtered(
                     wallet, from, to, operator, tokens, token_ids, block_from, block_to, cursor,
-                    limit,
+                    limit, mask,
                 )
                 .await;
         }
+        let columns = transfer_columns(mask, "X''", "'0'", "NULL");
         let conn = self.conn.lock().unwrap();
 
         let mut query = String::new();
         let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
 
         if let Some(wallet_addr) = wallet {
-            query.push_str(
-                "SELECT DISTINCT t.id, t.token, t.operator, t.from_addr, t.to_addr, t.token_id, t.amount, t.is_batch, t.batch_index, t.block_number, t.tx_hash, t.timestamp
+            query.push_str(&format!(
+                "SELECT DISTINCT {columns}
                  FROM token_wallet_activity wa
                  JOIN token_transfers t ON wa.transfer_id = t.id
-                 WHERE wa.wallet_address = ?",
-            );
+                 WHERE wa.wallet_address = ?"
+            ));
             params_vec.push(Box::new(felt_to_blob(wallet_addr)));
 
             if !tokens.is_empty() {
@@ -789,11 +536,11 @@ impl Erc1155Storage {
                 }
             }
         } else {
-            query.push_str(
-                "SELECT t.id, t.token, t.operator, t.from_addr, t.to_addr, t.token_id, t.amount, t.is_batch, t.batch_index, t.block_number, t.tx_hash, t.timestamp
+            query.push_str(&format!(
+                "SELECT {columns}
                  FROM token_transfers t
-                 WHERE 1=1",
-            );
+                 WHERE 1=1"
+            ));
 
             if let Some(from_addr) = from {
                 query.push_str(" AND t.from_addr = ?");
@@ -878,6 +625,7 @@ impl Erc1155Storage {
                 block_number: block_number_str.parse::<u64>().unwrap_or(0),
                 tx_hash: blob_to_felt(&tx_hash_bytes),
                 timestamp: timestamp_str.and_then(|s| s.parse::<i64>().ok()),
+                event_index: None,
             })
         })?;
 
@@ -895,6 +643,57 @@ impl Erc1155Storage {
         Ok((transfers, next_cursor))
     }
 
+    /// Get a page of the transfers of `range` after row `after_id`, for historical replay
+    ///
+    /// Pages by row id (ascending) rather than block number, so that a replay follows the
+    /// order in which transfers were indexed and stays stable while new rows are appended.
+    pub async fn get_transfers_for_replay(
+        &self,
+        range: &ReplayRange,
+        after_id: Option<i64>,
+    ) -> Result<Vec<TokenTransferData>> {
+        if self.backend == StorageBackend::Postgres {
+            return self.pg_get_transfers_for_replay(range, after_id).await;
+        }
+        let conn = self.conn.lock().unwrap();
+
+        let mut query = String::from(
+            "SELECT t.id, t.token, t.operator, t.from_addr, t.to_addr, t.token_id, t.amount, t.is_batch, t.batch_index, t.block_number, t.tx_hash, t.timestamp
+             FROM token_transfers t",
+        );
+        let mut params = Vec::new();
+        query.push_str(&range.page_clause(after_id, |param| {
+            params.push(param);
+            "?".to_string()
+        }));
+
+        let mut stmt = conn.prepare_cached(&query)?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(&params), |row| {
+            let is_batch_str: String = row.get(7)?;
+            let batch_index_str: String = row.get(8)?;
+            let block_number_str: String = row.get(9)?;
+            let timestamp_str: Option<String> = row.get(11)?;
+
+            Ok(TokenTransferData {
+                id: Some(row.get(0)?),
+                token: blob_to_felt(&row.get::<_, Vec<u8>>(1)?),
+                operator: blob_to_felt(&row.get::<_, Vec<u8>>(2)?),
+                from: blob_to_felt(&row.get::<_, Vec<u8>>(3)?),
+                to: blob_to_felt(&row.get::<_, Vec<u8>>(4)?),
+                token_id: blob_to_u256(&row.get::<_, Vec<u8>>(5)?),
+                amount: blob_to_u256(&row.get::<_, Vec<u8>>(6)?),
+                is_batch: is_batch_str.parse::<i32>().unwrap_or(0) != 0,
+                batch_index: batch_index_str.parse::<u32>().unwrap_or(0),
+                block_number: block_number_str.parse::<u64>().unwrap_or(0),
+                tx_hash: blob_to_felt(&row.get::<_, Vec<u8>>(10)?),
+                timestamp: timestamp_str.and_then(|s| s.parse::<i64>().ok()),
+                event_index: None,
+            })
+        })?;
+
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
     /// Query token IDs by flattened metadata attributes.
     ///
     /// Filter semantics:
@@ -958,6 +757,78 @@ impl Erc1155Storage {
         )
     }
 
+    /// Get the transfers of a transaction, in event order
+    ///
+    /// Transfers of a batch share their event index and follow their batch order.
+    /// Transfers stored before event indexes were recorded come last, in insertion order.
+    pub async fn get_transfers_by_tx(&self, tx_hash: Felt) -> Result<Vec<TokenTransferData>> {
+        let tx_hash_blob = felt_to_blob(tx_hash);
+
+        if self.backend == StorageBackend::Postgres {
+            let client = self.pg_client().await?;
+            let rows = client
+                .query(
+                    "SELECT id, token, operator, from_addr, to_addr, token_id, amount, is_batch, batch_index, block_number, tx_hash, timestamp, event_index
+                     FROM erc1155.token_transfers
+                     WHERE tx_hash = $1
+                     ORDER BY event_index NULLS LAST, id",
+                    &[&tx_hash_blob],
+                )
+                .await?;
+            return Ok(rows
+                .into_iter()
+                .map(|row| TokenTransferData {
+                    id: Some(row.get::<usize, i64>(0)),
+                    token: blob_to_felt(&row.get::<usize, Vec<u8>>(1)),
+                    operator: blob_to_felt(&row.get::<usize, Vec<u8>>(2)),
+                    from: blob_to_felt(&row.get::<usize, Vec<u8>>(3)),
+                    to: blob_to_felt(&row.get::<usize, Vec<u8>>(4)),
+                    token_id: blob_to_u256(&row.get::<usize, Vec<u8>>(5)),
+                    amount: blob_to_u256(&row.get::<usize, Vec<u8>>(6)),
+                    is_batch: row.get::<usize, String>(7).parse::<i32>().unwrap_or(0) != 0,
+                    batch_index: row.get::<usize, String>(8).parse::<u32>().unwrap_or(0),
+                    block_number: row.get::<usize, String>(9).parse::<u64>().unwrap_or(0),
+                    tx_hash: blob_to_felt(&row.get::<usize, Vec<u8>>(10)),
+                    timestamp: row
+                        .get::<usize, Option<String>>(11)
+                        .and_then(|s| s.parse::<i64>().ok()),
+                    event_index: row.get::<usize, Option<i64>>(12).map(|i| i as u32),
+                })
+                .collect());
+        }
+
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare_cached(
+            "SELECT id, token, operator, from_addr, to_addr, token_id, amount, is_batch, batch_index, block_number, tx_hash, timestamp, event_index
+             FROM token_transfers
+             WHERE tx_hash = ?1
+             ORDER BY event_index IS NULL, event_index, id",
+        )?;
+        let rows = stmt.query_map(params![&tx_hash_blob], |row| {
+            let is_batch_str: String = row.get(7)?;
+            let batch_index_str: String = row.get(8)?;
+            let block_number_str: String = row.get(9)?;
+            let timestamp_str: Option<String> = row.get(11)?;
+
+            Ok(TokenTransferData {
+                id: Some(row.get(0)?),
+                token: blob_to_felt(&row.get::<_, Vec<u8>>(1)?),
+                operator: blob_to_felt(&row.get::<_, Vec<u8>>(2)?),
+                from: blob_to_felt(&row.get::<_, Vec<u8>>(3)?),
+                to: blob_to_felt(&row.get::<_, Vec<u8>>(4)?),
+                token_id: blob_to_u256(&row.get::<_, Vec<u8>>(5)?),
+                amount: blob_to_u256(&row.get::<_, Vec<u8>>(6)?),
+                is_batch: is_batch_str.parse::<i32>().unwrap_or(0) != 0,
+                batch_index: batch_index_str.parse::<u32>().unwrap_or(0),
+                block_number: block_number_str.parse::<u64>().unwrap_or(0),
+                tx_hash: blob_to_felt(&row.get::<_, Vec<u8>>(10)?),
+                timestamp: timestamp_str.and_then(|s| s.parse::<i64>().ok()),
+                event_index: row.get::<_, Option<u32>>(12)?,
+            })
+        })?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+    }
+
     /// Get transfer count
     pub async fn get_transfer_count(&self) -> Result<u64> {
         if self.backend == StorageBackend::Postgres {
@@ -1012,6 +883,18 @@ impl Erc1155Storage {
         Ok(block.and_then(|b| b.parse::<u64>().ok()))
     }
 
+    /// Runs SQLite maintenance: incremental vacuum, `ANALYZE` and `PRAGMA optimize`.
+    ///
+    /// A no-op on PostgreSQL, which relies on autovacuum.
+    pub async fn run_maintenance(&self) -> Result<()> {
+        if self.backend == StorageBackend::Postgres {
+            return Ok(());
+        }
+        let conn = self.conn.lock().unwrap();
+        conn.execute_batch(SQLITE_MAINTENANCE_SQL)?;
+        Ok(())
+    }
+
     // ===== Balance Tracking Methods =====
 
     /// Get current balance for a (contract, wallet, token_id) tuple
@@ -1111,6 +994,71 @@ impl Erc1155Storage {
         Ok(result)
     }
 
+    /// Get the non-zero balances of `wallet`, ordered by contract then token ID, with
+    /// cursor-based pagination. An empty `contracts` list means every contract.
+    pub async fn get_inventory(
+        &self,
+        wallet: Felt,
+        contracts: &[Felt],
+        cursor: Option<InventoryCursor>,
+        limit: u32,
+    ) -> Result<(Vec<Erc1155BalanceData>, Option<InventoryCursor>)> {
+        if self.backend == StorageBackend::Postgres {
+            return self
+                .pg_get_inventory(wallet, contracts, cursor, limit)
+                .await;
+        }
+        let conn = self.conn.lock().unwrap();
+
+        // `balance != X'00'` matches the partial index predicate (zero is stored as X'00').
+        let mut query = String::from(
+            "SELECT contract, wallet, token_id, balance, last_block FROM erc1155_balances
+             WHERE wallet = ? AND balance != X'00'",
+        );
+        let mut params_vec: Vec<Box<dyn ToSql>> = vec![Box::new(felt_to_blob(wallet))];
+        if !contracts.is_empty() {
+            let placeholders: Vec<&str> = contracts.iter().map(|_| "?").collect();
+            query.push_str(&format!(" AND contract IN ({})", placeholders.join(",")));
+            for contract in contracts {
+                params_vec.push(Box::new(felt_to_blob(*contract)));
+            }
+        }
+        if let Some(c) = cursor {
+            let token_id = u256_to_blob(c.token_id);
+            query.push_str(
+                " AND (contract > ? OR (contract = ? AND (length(token_id) > ? \
+                 OR (length(token_id) = ? AND token_id > ?))))",
+            );
+            params_vec.push(Box::new(felt_to_blob(c.contract)));
+            params_vec.push(Box::new(felt_to_blob(c.contract)));
+            params_vec.push(Box::new(token_id.len() as i64));
+            params_vec.push(Box::new(token_id.len() as i64));
+            params_vec.push(Box::new(token_id));
+        }
+        query.push_str(" ORDER BY contract ASC, length(token_id) ASC, token_id ASC LIMIT ?");
+        params_vec.push(Box::new(i64::from(limit) + 1));
+
+        let mut stmt = conn.prepare_cached(&query)?;
+        let params_refs: Vec<&dyn ToSql> =
+            params_vec.iter().map(std::convert::AsRef::as_ref).collect();
+        let rows = stmt.query_map(params_refs.as_slice(), |row| {
+            let last_block: String = row.get(4)?;
+            Ok(Erc1155BalanceData {
+                contract: blob_to_felt(&row.get::<_, Vec<u8>>(0)?),
+                wallet: blob_to_felt(&row.get::<_, Vec<u8>>(1)?),
+                token_id: blob_to_u256(&row.get::<_, Vec<u8>>(2)?),
+                balance: blob_to_u256(&row.get::<_, Vec<u8>>(3)?),
+                last_block: last_block.parse::<u64>().unwrap_or(0),
+            })
+        })?;
+        let balances: Vec<Erc1155BalanceData> = rows.collect::<Result<_, _>>()?;
+
+        Ok(split_page(balances, limit as usize, |b| InventoryCursor {
+            contract: b.contract,
+            token_id: b.token_id,
+        }))
+    }
+
     /// Check which transfers need balance adjustments
     ///
     /// For each transfer, checks if the sender's current balance would go negative.
@@ -2077,6 +2025,7 @@ impl Erc1155Storage {
         let mut block_vec: Vec<String> = Vec::with_capacity(transfers.len());
         let mut tx_hash_vec = Vec::with_capacity(transfers.len());
         let mut ts_vec: Vec<String> = Vec::with_capacity(transfers.len());
+        let mut event_index_vec: Vec<Option<i64>> = Vec::with_capacity(transfers.len());
 
         for transfer in transfers {
             token_vec.push(felt_to_blob(transfer.token));
@@ -2095,6 +2044,7 @@ impl Erc1155Storage {
                     .unwrap_or_else(|| chrono::Utc::now().timestamp())
                     .to_string(),
             );
+            event_index_vec.push(transfer.event_index.map(i64::from));
         }
 
         let client = self.pg_client().await?;
@@ -2102,9 +2052,9 @@ impl Erc1155Storage {
             .query_one(
                 "WITH inserted AS (
                     INSERT INTO erc1155.token_transfers
-                        (token, operator, from_addr, to_addr, token_id, amount, is_batch, batch_index, block_number, tx_hash, timestamp)
+                        (token, operator, from_addr, to_addr, token_id, amount, is_batch, batch_index, block_number, tx_hash, timestamp, event_index)
                     SELECT
-                        i.token, i.operator, i.from_addr, i.to_addr, i.token_id, i.amount, i.is_batch, i.batch_index, i.block_number, i.tx_hash, i.timestamp
+                        i.token, i.operator, i.from_addr, i.to_addr, i.token_id, i.amount, i.is_batch, i.batch_index, i.block_number, i.tx_hash, i.timestamp, i.event_index
                     FROM unnest(
                         $1::bytea[],
                         $2::bytea[],
@@ -2116,8 +2066,9 @@ impl Erc1155Storage {
                         $8::text[],
                         $9::text[],
                         $10::bytea[],
-                        $11::text[]
-                    ) AS i(token, operator, from_addr, to_addr, token_id, amount, is_batch, batch_index, block_number, tx_hash, timestamp)
+                        $11::text[],
+                        $12::bigint[]
+                    ) AS i(token, operator, from_addr, to_addr, token_id, amount, is_batch, batch_index, block_number, tx_hash, timestamp, event_index)
                     ON CONFLICT (token, tx_hash, token_id, from_addr, to_addr, batch_index) DO NOTHING
                     RETURNING id, token, from_addr, to_addr, block_number
                 ),
@@ -2125,15 +2076,15 @@ impl Erc1155Storage {
                     INSERT INTO erc1155.token_wallet_activity (wallet_address, token, transfer_id, direction, block_number)
                     SELECT from_addr, token, id, 'both', block_number
                     FROM inserted
-                    WHERE from_addr <> $12::bytea AND to_addr <> $12::bytea AND from_addr = to_addr
+                    WHERE from_addr <> $13::bytea AND to_addr <> $13::bytea AND from_addr = to_addr
                     UNION ALL
                     SELECT from_addr, token, id, 'sent', block_number
                     FROM inserted
-                    WHERE from_addr <> $12::bytea AND from_addr <> to_addr
+                    WHERE from_addr <> $13::bytea AND from_addr <> to_addr
                     UNION ALL
                     SELECT to_addr, token, id, 'received', block_number
                     FROM inserted
-                    WHERE to_addr <> $12::bytea AND from_addr <> to_addr
+                    WHERE to_addr <> $13::bytea AND from_addr <> to_addr
                 )
                 SELECT COUNT(*)::bigint FROM inserted",
                 &[
@@ -2148,6 +2099,7 @@ impl Erc1155Storage {
                     &block_vec,
                     &tx_hash_vec,
                     &ts_vec,
+                    &event_index_vec,
                     &zero_blob,
                 ],
             )
@@ -2238,7 +2190,7 @@ impl Erc1155Storage {
         let client = self.pg_client().await?;
         let rows = client
             .execute(
-                "INSERT INTO erc1155.token_uris (token, token_id, uri, updated_at)
+                "INSERT INTO erc1155.token_uris AS t (token, token_id, uri, updated_at)
                 SELECT DISTINCT ON (token, token_id) i.token, i.token_id, i.uri, EXTRACT(EPOCH FROM NOW())::BIGINT::TEXT
                 FROM unnest(
                     $1::bytea[],
@@ -2247,6 +2199,8 @@ impl Erc1155Storage {
                 ) WITH ORDINALITY AS i(token, token_id, uri, ord)
                 ORDER BY token, token_id, ord DESC
                 ON CONFLICT(token, token_id) DO UPDATE SET
+                    metadata_json = CASE WHEN t.uri IS NOT DISTINCT FROM EXCLUDED.uri
+                        THEN t.metadata_json ELSE NULL END,
                     uri = EXCLUDED.uri,
                     updated_at = EXCLUDED.updated_at",
                 &[&token_vec, &token_id_vec, &uri_vec],
@@ -2268,18 +2222,20 @@ impl Erc1155Storage {
         block_to: Option<u64>,
         cursor: Option<TransferCursor>,
         limit: u32,
+        mask: &FieldMask,
     ) -> Result<(Vec<TokenTransferData>, Option<TransferCursor>)> {
         let client = self.pg_client().await?;
+        let columns = transfer_columns(mask, "''::bytea", "'0'::text", "NULL::text");
         let mut query = String::new();
         let mut params: Vec<Box<dyn PgToSql + Sync + Send>> = Vec::new();
 
         if let Some(wallet_addr) = wallet {
-            query.push_str(
-                "SELECT DISTINCT t.id, t.token, t.operator, t.from_addr, t.to_addr, t.token_id, t.amount, t.is_batch, t.batch_index, t.block_number, t.tx_hash, t.timestamp
+            query.push_str(&format!(
+                "SELECT DISTINCT {columns}
                  FROM erc1155.token_wallet_activity wa
                  JOIN erc1155.token_transfers t ON wa.transfer_id = t.id
-                 WHERE wa.wallet_address = ",
-            );
+                 WHERE wa.wallet_address = "
+            ));
             query.push_str(&Self::pg_next_param(&mut params, felt_to_blob(wallet_addr)));
             if !tokens.is_empty() {
                 let list = tokens
@@ -2290,11 +2246,11 @@ impl Erc1155Storage {
                 query.push_str(&format!(" AND wa.token IN ({list})"));
             }
         } else {
-            query.push_str(
-                "SELECT t.id, t.token, t.operator, t.from_addr, t.to_addr, t.token_id, t.amount, t.is_batch, t.batch_index, t.block_number, t.tx_hash, t.timestamp
+            query.push_str(&format!(
+                "SELECT {columns}
                  FROM erc1155.token_transfers t
-                 WHERE 1=1",
-            );
+                 WHERE 1=1"
+            ));
             if let Some(from_addr) = from {
                 query.push_str(" AND t.from_addr = ");
                 query.push_str(&Self::pg_next_param(&mut params, felt_to_blob(from_addr)));
@@ -2364,6 +2320,7 @@ impl Erc1155Storage {
                 block_number: row.get::<usize, String>(9).parse::<u64>().unwrap_or(0),
                 tx_hash: blob_to_felt(&row.get::<usize, Vec<u8>>(10)),
                 timestamp: row.get::<usize, String>(11).parse::<i64>().ok(),
+                event_index: None,
             })
             .collect();
 
@@ -2379,6 +2336,47 @@ impl Erc1155Storage {
         Ok((transfers, next_cursor))
     }
 
+    async fn pg_get_transfers_for_replay(
+        &self,
+        range: &ReplayRange,
+        after_id: Option<i64>,
+    ) -> Result<Vec<TokenTransferData>> {
+        let client = self.pg_client().await?;
+        let mut params: Vec<Box<dyn PgToSql + Sync + Send>> = Vec::new();
+        let mut query = String::from(
+            "SELECT t.id, t.token, t.operator, t.from_addr, t.to_addr, t.token_id, t.amount, t.is_batch, t.batch_index, t.block_number, t.tx_hash, t.timestamp
+             FROM erc1155.token_transfers t",
+        );
+        query.push_str(&range.page_clause(after_id, |param| match param {
+            ReplayParam::Integer(value) => Self::pg_next_param(&mut params, value),
+            ReplayParam::Blob(value) => Self::pg_next_param(&mut params, value),
+        }));
+
+        let refs: Vec<&(dyn PgToSql + Sync)> = params
+            .iter()
+            .map(|p| p.as_ref() as &(dyn PgToSql + Sync))
+            .collect();
+        let rows = client.query(&query, &refs).await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| TokenTransferData {
+                id: Some(row.get::<usize, i64>(0)),
+                token: blob_to_felt(&row.get::<usize, Vec<u8>>(1)),
+                operator: blob_to_felt(&row.get::<usize, Vec<u8>>(2)),
+                from: blob_to_felt(&row.get::<usize, Vec<u8>>(3)),
+                to: blob_to_felt(&row.get::<usize, Vec<u8>>(4)),
+                token_id: blob_to_u256(&row.get::<usize, Vec<u8>>(5)),
+                amount: blob_to_u256(&row.get::<usize, Vec<u8>>(6)),
+                is_batch: row.get::<usize, String>(7).parse::<i32>().unwrap_or(0) != 0,
+                batch_index: row.get::<usize, String>(8).parse::<u32>().unwrap_or(0),
+                block_number: row.get::<usize, String>(9).parse::<u64>().unwrap_or(0),
+                tx_hash: blob_to_felt(&row.get::<usize, Vec<u8>>(10)),
+                timestamp: row.get::<usize, String>(11).parse::<i64>().ok(),
+                event_index: None,
+            })
+            .collect())
+    }
+
     async fn pg_query_token_ids_by_facets(
         &self,
         token: Felt,
@@ -2562,6 +2560,61 @@ impl Erc1155Storage {
         }))
     }
 
+    async fn pg_get_inventory(
+        &self,
+        wallet: Felt,
+        contracts: &[Felt],
+        cursor: Option<InventoryCursor>,
+        limit: u32,
+    ) -> Result<(Vec<Erc1155BalanceData>, Option<InventoryCursor>)> {
+        let client = self.pg_client().await?;
+        let mut query = String::from(
+            "SELECT contract, wallet, token_id, balance, last_block FROM erc1155.erc1155_balances
+             WHERE wallet = $1 AND balance <> '\\x00'::bytea",
+        );
+        let mut params: Vec<Box<dyn PgToSql + Sync + Send>> = vec![Box::new(felt_to_blob(wallet))];
+        if !contracts.is_empty() {
+            let list = contracts
+                .iter()
+                .map(|contract| Self::pg_next_param(&mut params, felt_to_blob(*contract)))
+                .collect::<Vec<_>>()
+                .join(",");
+            query.push_str(&format!(" AND contract IN ({list})"));
+        }
+        if let Some(c) = cursor {
+            let token_id = u256_to_blob(c.token_id);
+            let contract_param = Self::pg_next_param(&mut params, felt_to_blob(c.contract));
+            let len_param = Self::pg_next_param(&mut params, token_id.len() as i32);
+            let token_id_param = Self::pg_next_param(&mut params, token_id);
+            query.push_str(&format!(
+                " AND (contract > {contract_param} OR (contract = {contract_param} AND \
+                 (octet_length(token_id) > {len_param} \
+                 OR (octet_length(token_id) = {len_param} AND token_id > {token_id_param}))))"
+            ));
+        }
+        query.push_str(" ORDER BY contract ASC, octet_length(token_id) ASC, token_id ASC LIMIT ");
+        query.push_str(&Self::pg_next_param(&mut params, i64::from(limit) + 1));
+        let refs: Vec<&(dyn PgToSql + Sync)> = params
+            .iter()
+            .map(|p| p.as_ref() as &(dyn PgToSql + Sync))
+            .collect();
+        let rows = client.query(&query, &refs).await?;
+        let balances: Vec<Erc1155BalanceData> = rows
+            .into_iter()
+            .map(|row| Erc1155BalanceData {
+                contract: blob_to_felt(&row.get::<usize, Vec<u8>>(0)),
+                wallet: blob_to_felt(&row.get::<usize, Vec<u8>>(1)),
+                token_id: blob_to_u256(&row.get::<usize, Vec<u8>>(2)),
+                balance: blob_to_u256(&row.get::<usize, Vec<u8>>(3)),
+                last_block: row.get::<usize, String>(4).parse::<u64>().unwrap_or(0),
+            })
+            .collect();
+        Ok(split_page(balances, limit as usize, |b| InventoryCursor {
+            contract: b.contract,
+            token_id: b.token_id,
+        }))
+    }
+
     async fn pg_get_balances_batch(
         &self,
         tuples: &[(Felt, Felt, U256)],
@@ -3154,6 +3207,25 @@ impl TokenUriStore for Erc1155Storage {
     }
 }
 
+/// Select list of a transfer query (id, token, operator, from, to, token_id, amount,
+/// is_batch, batch_index, block_number, tx_hash, timestamp), with the columns of fields
+/// outside `mask` replaced by placeholders.
+fn transfer_columns(mask: &FieldMask, empty_blob: &str, zero: &str, null: &str) -> String {
+    format!(
+        "t.id, {}, {}, {}, {}, {}, {}, {}, {}, t.block_number, {}, {}",
+        mask.column("token", "t.token", empty_blob),
+        mask.column("operator", "t.operator", empty_blob),
+        mask.column("from", "t.from_addr", empty_blob),
+        mask.column("to", "t.to_addr", empty_blob),
+        mask.column("token_id", "t.token_id", empty_blob),
+        mask.column("amount", "t.amount", empty_blob),
+        mask.column("is_batch", "t.is_batch", zero),
+        mask.column("batch_index", "t.batch_index", zero),
+        mask.column("tx_hash", "t.tx_hash", empty_blob),
+        mask.column("timestamp", "t.timestamp", null),
+    )
+}
+
 fn extract_metadata_attributes(metadata_json: Option<&str>) -> Vec<(String, String)> {
     let Some(metadata_json) = metadata_json else {
         return Vec::new();
@@ -3434,3 +3506,81 @@ fn sanitize_metadata_text(input: &str) -> Option<String> {
         Some(sanitized)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_db_path(test_name: &str) -> String {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .expect("system time before unix epoch")
+            .as_nanos();
+        std::env::temp_dir()
+            .join(format!("torii-erc1155-{test_name}-{nanos}.db"))
+            .to_string_lossy()
+            .to_string()
+    }
+
+    #[tokio::test]
+    async fn replay_pages_transfers_of_a_block_range() {
+        let db_path = temp_db_path("replay");
+        let storage = Erc1155Storage::new(&db_path).await.expect("create storage");
+        let transfer = |token: u64, block_number: u64| TokenTransferData {
+            id: None,
+            token: Felt::from(token),
+            operator: Felt::from(0x10u64),
+            from: Felt::ZERO,
+            to: Felt::from(0x10u64),
+            token_id: U256::from(1u64),
+            amount: U256::from(block_number),
+            is_batch: false,
+            batch_index: 0,
+            block_number,
+            tx_hash: Felt::from(block_number),
+            timestamp: None,
+            event_index: None,
+        };
+        storage
+            .insert_transfers_batch(&[
+                transfer(1, 5),
+                transfer(1, 9),
+                transfer(2, 12),
+                transfer(1, 100),
+            ])
+            .await
+            .expect("insert transfers");
+        let blocks =
+            |page: Vec<TokenTransferData>| page.iter().map(|t| t.block_number).collect::<Vec<_>>();
+
+        // Block 100 sorts before 9 as text.
+        let range = ReplayRange::new(Vec::new(), Some(9), Some(100), 2).unwrap();
+        let first = storage
+            .get_transfers_for_replay(&range, None)
+            .await
+            .expect("first page");
+        let after_id = first.last().and_then(|t| t.id);
+        assert_eq!(blocks(first), vec![9, 12]);
+        let second = storage
+            .get_transfers_for_replay(&range, after_id)
+            .await
+            .expect("second page");
+        assert_eq!(blocks(second), vec![100]);
+
+        let token_range = range.with_tokens(vec![Felt::from(1u64)]);
+        let page = storage
+            .get_transfers_for_replay(&token_range, None)
+            .await
+            .expect("token page");
+        assert_eq!(blocks(page), vec![9, 100]);
+
+        let empty = ReplayRange::new(Vec::new(), Some(101), None, 2).unwrap();
+        assert!(storage
+            .get_transfers_for_replay(&empty, None)
+            .await
+            .expect("empty page")
+            .is_empty());
+
+        let _ = std::fs::remove_file(db_path);
+    }
+}
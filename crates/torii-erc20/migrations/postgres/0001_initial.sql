@@ -0,0 +1,95 @@
+-- ERC20 storage schema (PostgreSQL)
+
+CREATE SCHEMA IF NOT EXISTS erc20;
+
+CREATE TABLE IF NOT EXISTS erc20.transfers (
+    id BIGSERIAL PRIMARY KEY,
+    token BYTEA NOT NULL,
+    from_addr BYTEA NOT NULL,
+    to_addr BYTEA NOT NULL,
+    amount BYTEA NOT NULL,
+    block_number TEXT NOT NULL,
+    tx_hash BYTEA NOT NULL,
+    timestamp TEXT DEFAULT (EXTRACT(EPOCH FROM NOW())::TEXT),
+    UNIQUE(token, tx_hash, from_addr, to_addr)
+);
+CREATE INDEX IF NOT EXISTS idx_transfers_token ON erc20.transfers(token);
+CREATE INDEX IF NOT EXISTS idx_transfers_from ON erc20.transfers(from_addr);
+CREATE INDEX IF NOT EXISTS idx_transfers_to ON erc20.transfers(to_addr);
+CREATE INDEX IF NOT EXISTS idx_transfers_block ON erc20.transfers(block_number);
+CREATE INDEX IF NOT EXISTS idx_transfers_token_block ON erc20.transfers(token, block_number DESC);
+CREATE INDEX IF NOT EXISTS idx_transfers_from_block ON erc20.transfers(from_addr, block_number DESC);
+CREATE INDEX IF NOT EXISTS idx_transfers_to_block ON erc20.transfers(to_addr, block_number DESC);
+
+CREATE TABLE IF NOT EXISTS erc20.approvals (
+    id BIGSERIAL PRIMARY KEY,
+    token BYTEA NOT NULL,
+    owner BYTEA NOT NULL,
+    spender BYTEA NOT NULL,
+    amount BYTEA NOT NULL,
+    block_number TEXT NOT NULL,
+    tx_hash BYTEA NOT NULL,
+    timestamp TEXT DEFAULT (EXTRACT(EPOCH FROM NOW())::TEXT),
+    UNIQUE(token, tx_hash, owner, spender)
+);
+CREATE INDEX IF NOT EXISTS idx_approvals_owner ON erc20.approvals(owner, block_number DESC);
+CREATE INDEX IF NOT EXISTS idx_approvals_spender ON erc20.approvals(spender, block_number DESC);
+CREATE INDEX IF NOT EXISTS idx_approvals_token ON erc20.approvals(token, block_number DESC);
+
+CREATE TABLE IF NOT EXISTS erc20.wallet_activity (
+    id BIGSERIAL PRIMARY KEY,
+    wallet_address BYTEA NOT NULL,
+    token BYTEA NOT NULL,
+    transfer_id BIGINT NOT NULL REFERENCES erc20.transfers(id),
+    direction TEXT NOT NULL CHECK(direction IN ('sent', 'received', 'both')),
+    block_number TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_wallet_activity_wallet_block ON erc20.wallet_activity(wallet_address, block_number DESC);
+CREATE INDEX IF NOT EXISTS idx_wallet_activity_wallet_token ON erc20.wallet_activity(wallet_address, token, block_number DESC);
+CREATE INDEX IF NOT EXISTS idx_wallet_activity_transfer ON erc20.wallet_activity(transfer_id);
+
+CREATE TABLE IF NOT EXISTS erc20.approval_activity (
+    id BIGSERIAL PRIMARY KEY,
+    account_address BYTEA NOT NULL,
+    token BYTEA NOT NULL,
+    approval_id BIGINT NOT NULL REFERENCES erc20.approvals(id),
+    role TEXT NOT NULL CHECK(role IN ('owner', 'spender', 'both')),
+    block_number TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_approval_activity_account_block ON erc20.approval_activity(account_address, block_number DESC);
+CREATE INDEX IF NOT EXISTS idx_approval_activity_account_token ON erc20.approval_activity(account_address, token, block_number DESC);
+CREATE INDEX IF NOT EXISTS idx_approval_activity_approval ON erc20.approval_activity(approval_id);
+
+CREATE TABLE IF NOT EXISTS erc20.balances (
+    id BIGSERIAL PRIMARY KEY,
+    token BYTEA NOT NULL,
+    wallet BYTEA NOT NULL,
+    balance BYTEA NOT NULL,
+    last_block TEXT NOT NULL,
+    last_tx_hash BYTEA NOT NULL,
+    updated_at TEXT DEFAULT (EXTRACT(EPOCH FROM NOW())::TEXT),
+    UNIQUE(token, wallet)
+);
+CREATE INDEX IF NOT EXISTS idx_balances_token ON erc20.balances(token);
+CREATE INDEX IF NOT EXISTS idx_balances_wallet ON erc20.balances(wallet);
+
+CREATE TABLE IF NOT EXISTS erc20.balance_adjustments (
+    id BIGSERIAL PRIMARY KEY,
+    token BYTEA NOT NULL,
+    wallet BYTEA NOT NULL,
+    computed_balance BYTEA NOT NULL,
+    actual_balance BYTEA NOT NULL,
+    adjusted_at_block TEXT NOT NULL,
+    tx_hash BYTEA NOT NULL,
+    created_at TEXT DEFAULT (EXTRACT(EPOCH FROM NOW())::TEXT)
+);
+CREATE INDEX IF NOT EXISTS idx_adjustments_wallet ON erc20.balance_adjustments(wallet);
+CREATE INDEX IF NOT EXISTS idx_adjustments_token ON erc20.balance_adjustments(token);
+
+CREATE TABLE IF NOT EXISTS erc20.token_metadata (
+    token BYTEA PRIMARY KEY,
+    name TEXT,
+    symbol TEXT,
+    decimals TEXT,
+    total_supply BYTEA
+);
@@ -0,0 +1,20 @@
+-- Provenance tables (debug): only populated when provenance tracking is enabled
+CREATE TABLE IF NOT EXISTS erc20.transfer_provenance (
+    transfer_id BIGINT PRIMARY KEY REFERENCES erc20.transfers(id),
+    extractor TEXT NOT NULL,
+    decoder TEXT NOT NULL,
+    batch_id BIGINT NOT NULL,
+    extracted_at BIGINT NOT NULL,
+    decoded_at BIGINT NOT NULL,
+    stored_at BIGINT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS erc20.approval_provenance (
+    approval_id BIGINT PRIMARY KEY REFERENCES erc20.approvals(id),
+    extractor TEXT NOT NULL,
+    decoder TEXT NOT NULL,
+    batch_id BIGINT NOT NULL,
+    extracted_at BIGINT NOT NULL,
+    decoded_at BIGINT NOT NULL,
+    stored_at BIGINT NOT NULL
+);
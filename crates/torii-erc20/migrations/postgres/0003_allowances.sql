@@ -0,0 +1,22 @@
+-- Current allowances: the latest approval per (token, owner, spender)
+CREATE TABLE IF NOT EXISTS erc20.allowances (
+    id BIGSERIAL PRIMARY KEY,
+    token BYTEA NOT NULL,
+    owner BYTEA NOT NULL,
+    spender BYTEA NOT NULL,
+    amount BYTEA NOT NULL,
+    block_number TEXT NOT NULL,
+    tx_hash BYTEA NOT NULL,
+    timestamp TEXT,
+    UNIQUE(token, owner, spender)
+);
+CREATE INDEX IF NOT EXISTS idx_allowances_owner ON erc20.allowances(owner, token);
+CREATE INDEX IF NOT EXISTS idx_allowances_spender ON erc20.allowances(spender, token);
+
+-- Backfill from already indexed approvals
+INSERT INTO erc20.allowances (token, owner, spender, amount, block_number, tx_hash, timestamp)
+SELECT DISTINCT ON (token, owner, spender)
+    token, owner, spender, amount, block_number, tx_hash, timestamp
+FROM erc20.approvals
+ORDER BY token, owner, spender, block_number::BIGINT DESC, id DESC
+ON CONFLICT (token, owner, spender) DO NOTHING;
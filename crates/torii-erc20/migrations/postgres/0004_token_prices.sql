@@ -0,0 +1,9 @@
+-- USD price per token and block window, recorded by the optional price feed
+CREATE TABLE IF NOT EXISTS erc20.token_prices (
+    token BYTEA NOT NULL,
+    block_window BIGINT NOT NULL,
+    block_number BIGINT NOT NULL,
+    price_usd DOUBLE PRECISION NOT NULL,
+    source TEXT NOT NULL,
+    PRIMARY KEY (token, block_window)
+);
@@ -0,0 +1,22 @@
+-- Latest supply of each token, tracked from mints (from zero) and burns (to zero)
+CREATE TABLE IF NOT EXISTS erc20.token_supply (
+    token BYTEA PRIMARY KEY,
+    block_number BIGINT NOT NULL,
+    minted BYTEA NOT NULL,
+    burned BYTEA NOT NULL,
+    total_minted BYTEA NOT NULL,
+    total_burned BYTEA NOT NULL,
+    circulating BYTEA NOT NULL
+);
+
+-- Supply snapshot per token and block with mints or burns
+CREATE TABLE IF NOT EXISTS erc20.token_supply_history (
+    token BYTEA NOT NULL,
+    block_number BIGINT NOT NULL,
+    minted BYTEA NOT NULL,
+    burned BYTEA NOT NULL,
+    total_minted BYTEA NOT NULL,
+    total_burned BYTEA NOT NULL,
+    circulating BYTEA NOT NULL,
+    PRIMARY KEY (token, block_number)
+);
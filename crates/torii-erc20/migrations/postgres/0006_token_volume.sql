@@ -0,0 +1,21 @@
+-- Transfer rollups per token, interval ('hour' or 'day') and bucket start (unix seconds)
+CREATE TABLE IF NOT EXISTS erc20.token_volume (
+    token BYTEA NOT NULL,
+    interval TEXT NOT NULL,
+    bucket_start BIGINT NOT NULL,
+    transfer_count BIGINT NOT NULL,
+    volume BYTEA NOT NULL,
+    unique_senders BIGINT NOT NULL,
+    unique_receivers BIGINT NOT NULL,
+    PRIMARY KEY (token, interval, bucket_start)
+);
+
+-- Addresses already counted in a bucket (role: 0 = sender, 1 = receiver)
+CREATE TABLE IF NOT EXISTS erc20.token_volume_participants (
+    token BYTEA NOT NULL,
+    interval TEXT NOT NULL,
+    bucket_start BIGINT NOT NULL,
+    role SMALLINT NOT NULL,
+    address BYTEA NOT NULL,
+    PRIMARY KEY (token, interval, bucket_start, role, address)
+);
@@ -0,0 +1,5 @@
+-- Accounts whose balances are maintained in the watched balance scope
+CREATE TABLE IF NOT EXISTS erc20.balance_watchlist (
+    account BYTEA PRIMARY KEY,
+    added_at BIGINT NOT NULL DEFAULT EXTRACT(EPOCH FROM NOW())::BIGINT
+);
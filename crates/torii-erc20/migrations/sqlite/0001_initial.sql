@@ -0,0 +1,121 @@
+-- ERC20 storage schema (SQLite)
+
+-- Create transfers table with BLOB columns for efficient storage
+CREATE TABLE IF NOT EXISTS transfers (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    token BLOB NOT NULL,
+    from_addr BLOB NOT NULL,
+    to_addr BLOB NOT NULL,
+    amount BLOB NOT NULL,
+    block_number TEXT NOT NULL,
+    tx_hash BLOB NOT NULL,
+    timestamp TEXT DEFAULT (strftime('%s', 'now')),
+    UNIQUE(token, tx_hash, from_addr, to_addr)
+);
+CREATE INDEX IF NOT EXISTS idx_transfers_token ON transfers(token);
+CREATE INDEX IF NOT EXISTS idx_transfers_from ON transfers(from_addr);
+CREATE INDEX IF NOT EXISTS idx_transfers_to ON transfers(to_addr);
+CREATE INDEX IF NOT EXISTS idx_transfers_block ON transfers(block_number);
+
+-- Composite indexes for efficient queries
+CREATE INDEX IF NOT EXISTS idx_transfers_token_block ON transfers(token, block_number DESC);
+CREATE INDEX IF NOT EXISTS idx_transfers_from_block ON transfers(from_addr, block_number DESC);
+CREATE INDEX IF NOT EXISTS idx_transfers_to_block ON transfers(to_addr, block_number DESC);
+
+-- Create approvals table
+CREATE TABLE IF NOT EXISTS approvals (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    token BLOB NOT NULL,
+    owner BLOB NOT NULL,
+    spender BLOB NOT NULL,
+    amount BLOB NOT NULL,
+    block_number TEXT NOT NULL,
+    tx_hash BLOB NOT NULL,
+    timestamp TEXT DEFAULT (strftime('%s', 'now')),
+    UNIQUE(token, tx_hash, owner, spender)
+);
+CREATE INDEX IF NOT EXISTS idx_approvals_owner ON approvals(owner, block_number DESC);
+CREATE INDEX IF NOT EXISTS idx_approvals_spender ON approvals(spender, block_number DESC);
+CREATE INDEX IF NOT EXISTS idx_approvals_token ON approvals(token, block_number DESC);
+
+-- Metadata table
+CREATE TABLE IF NOT EXISTS metadata (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+
+-- Wallet activity table - denormalized for efficient wallet queries
+-- Solves the OR query problem: "get all transfers where wallet is sender OR receiver"
+-- Instead of: WHERE from_addr = ? OR to_addr = ? (can only use one index)
+-- We use: JOIN wallet_activity WHERE wallet_address = ? (uses this table's index)
+CREATE TABLE IF NOT EXISTS wallet_activity (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    wallet_address BLOB NOT NULL,
+    token BLOB NOT NULL,
+    transfer_id INTEGER NOT NULL,
+    direction TEXT NOT NULL CHECK(direction IN ('sent', 'received', 'both')),
+    block_number TEXT NOT NULL,
+    FOREIGN KEY (transfer_id) REFERENCES transfers(id)
+);
+
+-- Compound index: wallet -> block (optimal for wallet activity queries)
+CREATE INDEX IF NOT EXISTS idx_wallet_activity_wallet_block ON wallet_activity(wallet_address, block_number DESC);
+
+-- Compound index: wallet -> token -> block (token-specific activity)
+CREATE INDEX IF NOT EXISTS idx_wallet_activity_wallet_token ON wallet_activity(wallet_address, token, block_number DESC);
+
+-- Index for reverse lookup (transfer -> wallets)
+CREATE INDEX IF NOT EXISTS idx_wallet_activity_transfer ON wallet_activity(transfer_id);
+
+-- Approval activity table - similar pattern for approvals
+CREATE TABLE IF NOT EXISTS approval_activity (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    account_address BLOB NOT NULL,
+    token BLOB NOT NULL,
+    approval_id INTEGER NOT NULL,
+    role TEXT NOT NULL CHECK(role IN ('owner', 'spender', 'both')),
+    block_number TEXT NOT NULL,
+    FOREIGN KEY (approval_id) REFERENCES approvals(id)
+);
+CREATE INDEX IF NOT EXISTS idx_approval_activity_account_block ON approval_activity(account_address, block_number DESC);
+CREATE INDEX IF NOT EXISTS idx_approval_activity_account_token ON approval_activity(account_address, token, block_number DESC);
+CREATE INDEX IF NOT EXISTS idx_approval_activity_approval ON approval_activity(approval_id);
+
+-- Balance tracking tables
+-- Tracks current balance per (token, wallet) pair
+CREATE TABLE IF NOT EXISTS balances (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    token BLOB NOT NULL,
+    wallet BLOB NOT NULL,
+    balance BLOB NOT NULL,
+    last_block TEXT NOT NULL,
+    last_tx_hash BLOB NOT NULL,
+    updated_at TEXT DEFAULT (strftime('%s', 'now')),
+    UNIQUE(token, wallet)
+);
+CREATE INDEX IF NOT EXISTS idx_balances_token ON balances(token);
+CREATE INDEX IF NOT EXISTS idx_balances_wallet ON balances(wallet);
+
+-- Balance adjustments table for audit trail
+-- Records when we had to fetch balance from RPC due to inconsistency
+CREATE TABLE IF NOT EXISTS balance_adjustments (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    token BLOB NOT NULL,
+    wallet BLOB NOT NULL,
+    computed_balance BLOB NOT NULL,
+    actual_balance BLOB NOT NULL,
+    adjusted_at_block TEXT NOT NULL,
+    tx_hash BLOB NOT NULL,
+    created_at TEXT DEFAULT (strftime('%s', 'now'))
+);
+CREATE INDEX IF NOT EXISTS idx_adjustments_wallet ON balance_adjustments(wallet);
+CREATE INDEX IF NOT EXISTS idx_adjustments_token ON balance_adjustments(token);
+
+-- Token metadata table
+CREATE TABLE IF NOT EXISTS token_metadata (
+    token BLOB PRIMARY KEY,
+    name TEXT,
+    symbol TEXT,
+    decimals TEXT,
+    total_supply BLOB
+);
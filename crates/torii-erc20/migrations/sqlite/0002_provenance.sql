@@ -0,0 +1,22 @@
+-- Provenance tables (debug): only populated when provenance tracking is enabled
+CREATE TABLE IF NOT EXISTS transfer_provenance (
+    transfer_id INTEGER PRIMARY KEY,
+    extractor TEXT NOT NULL,
+    decoder TEXT NOT NULL,
+    batch_id INTEGER NOT NULL,
+    extracted_at INTEGER NOT NULL,
+    decoded_at INTEGER NOT NULL,
+    stored_at INTEGER NOT NULL,
+    FOREIGN KEY (transfer_id) REFERENCES transfers(id)
+);
+
+CREATE TABLE IF NOT EXISTS approval_provenance (
+    approval_id INTEGER PRIMARY KEY,
+    extractor TEXT NOT NULL,
+    decoder TEXT NOT NULL,
+    batch_id INTEGER NOT NULL,
+    extracted_at INTEGER NOT NULL,
+    decoded_at INTEGER NOT NULL,
+    stored_at INTEGER NOT NULL,
+    FOREIGN KEY (approval_id) REFERENCES approvals(id)
+);
@@ -0,0 +1,26 @@
+-- Current allowances: the latest approval per (token, owner, spender)
+CREATE TABLE IF NOT EXISTS allowances (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    token BLOB NOT NULL,
+    owner BLOB NOT NULL,
+    spender BLOB NOT NULL,
+    amount BLOB NOT NULL,
+    block_number TEXT NOT NULL,
+    tx_hash BLOB NOT NULL,
+    timestamp TEXT,
+    UNIQUE(token, owner, spender)
+);
+CREATE INDEX IF NOT EXISTS idx_allowances_owner ON allowances(owner, token);
+CREATE INDEX IF NOT EXISTS idx_allowances_spender ON allowances(spender, token);
+
+-- Backfill from already indexed approvals
+INSERT OR IGNORE INTO allowances (token, owner, spender, amount, block_number, tx_hash, timestamp)
+SELECT a.token, a.owner, a.spender, a.amount, a.block_number, a.tx_hash, a.timestamp
+FROM approvals a
+WHERE a.id = (
+    SELECT latest.id FROM approvals latest
+    WHERE latest.token = a.token AND latest.owner = a.owner AND latest.spender = a.spender
+    ORDER BY CAST(latest.block_number AS INTEGER) DESC, latest.id DESC
+    LIMIT 1
+)
+ORDER BY a.id;
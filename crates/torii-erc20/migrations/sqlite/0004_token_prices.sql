@@ -0,0 +1,9 @@
+-- USD price per token and block window, recorded by the optional price feed
+CREATE TABLE IF NOT EXISTS token_prices (
+    token BLOB NOT NULL,
+    block_window INTEGER NOT NULL,
+    block_number INTEGER NOT NULL,
+    price_usd REAL NOT NULL,
+    source TEXT NOT NULL,
+    PRIMARY KEY (token, block_window)
+);
@@ -0,0 +1,22 @@
+-- Latest supply of each token, tracked from mints (from zero) and burns (to zero)
+CREATE TABLE IF NOT EXISTS token_supply (
+    token BLOB PRIMARY KEY,
+    block_number INTEGER NOT NULL,
+    minted BLOB NOT NULL,
+    burned BLOB NOT NULL,
+    total_minted BLOB NOT NULL,
+    total_burned BLOB NOT NULL,
+    circulating BLOB NOT NULL
+);
+
+-- Supply snapshot per token and block with mints or burns
+CREATE TABLE IF NOT EXISTS token_supply_history (
+    token BLOB NOT NULL,
+    block_number INTEGER NOT NULL,
+    minted BLOB NOT NULL,
+    burned BLOB NOT NULL,
+    total_minted BLOB NOT NULL,
+    total_burned BLOB NOT NULL,
+    circulating BLOB NOT NULL,
+    PRIMARY KEY (token, block_number)
+);
@@ -0,0 +1,21 @@
+-- Transfer rollups per token, interval ('hour' or 'day') and bucket start (unix seconds)
+CREATE TABLE IF NOT EXISTS token_volume (
+    token BLOB NOT NULL,
+    interval TEXT NOT NULL,
+    bucket_start INTEGER NOT NULL,
+    transfer_count INTEGER NOT NULL,
+    volume BLOB NOT NULL,
+    unique_senders INTEGER NOT NULL,
+    unique_receivers INTEGER NOT NULL,
+    PRIMARY KEY (token, interval, bucket_start)
+);
+
+-- Addresses already counted in a bucket (role: 0 = sender, 1 = receiver)
+CREATE TABLE IF NOT EXISTS token_volume_participants (
+    token BLOB NOT NULL,
+    interval TEXT NOT NULL,
+    bucket_start INTEGER NOT NULL,
+    role INTEGER NOT NULL,
+    address BLOB NOT NULL,
+    PRIMARY KEY (token, interval, bucket_start, role, address)
+);
@@ -0,0 +1,5 @@
+-- Position of the transfer among the events of its transaction (NULL for rows stored before)
+ALTER TABLE transfers ADD COLUMN event_index INTEGER;
+
+-- Transaction lookups (GetTransactionEvents)
+CREATE INDEX IF NOT EXISTS idx_transfers_tx ON transfers(tx_hash, event_index);
@@ -0,0 +1,5 @@
+-- Accounts whose balances are maintained in the watched balance scope
+CREATE TABLE IF NOT EXISTS balance_watchlist (
+    account BLOB PRIMARY KEY,
+    added_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+);
@@ -0,0 +1,166 @@
+//! HTTP endpoints of the ERC20 sink
+//!
+//! - `GET /erc20/transfers/export`: streams transfers as CSV or JSONL
+
+use crate::sharding::ShardedErc20Storage;
+use crate::storage::{TransferCursor, TransferData, TransferDirection};
+use axum::{
+    body::Body,
+    extract::{Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
+use serde::{Deserialize, Serialize};
+use starknet::core::types::Felt;
+use torii_common::export::{export_stream, EXPORT_PAGE_SIZE};
+use torii_common::{ExportFormat, ExportRecord};
+
+/// Shared state for HTTP handlers
+#[derive(Clone)]
+pub struct Erc20ApiState {
+    pub storage: ShardedErc20Storage,
+}
+
+/// Query parameters for GET /erc20/transfers/export
+#[derive(Debug, Default, Deserialize)]
+pub struct TransfersExportQuery {
+    #[serde(default)]
+    pub format: ExportFormat,
+    /// Token contract (hex)
+    pub contract: Option<String>,
+    /// Wallet sending or receiving (hex)
+    pub address: Option<String>,
+    pub from_block: Option<u64>,
+    pub to_block: Option<u64>,
+}
+
+/// Exported transfer row
+#[derive(Serialize)]
+pub struct TransferRow {
+    pub block_number: u64,
+    pub timestamp: Option<i64>,
+    pub tx_hash: String,
+    pub token: String,
+    pub from: String,
+    pub to: String,
+    /// Raw amount (decimal)
+    pub amount: String,
+}
+
+impl From<TransferData> for TransferRow {
+    fn from(transfer: TransferData) -> Self {
+        Self {
+            block_number: transfer.block_number,
+            timestamp: transfer.timestamp,
+            tx_hash: format!("{:#x}", transfer.tx_hash),
+            token: format!("{:#x}", transfer.token),
+            from: format!("{:#x}", transfer.from),
+            to: format!("{:#x}", transfer.to),
+            amount: transfer.amount.to_string(),
+        }
+    }
+}
+
+impl ExportRecord for TransferRow {
+    const COLUMNS: &'static [&'static str] = &[
+        "block_number",
+        "timestamp",
+        "tx_hash",
+        "token",
+        "from",
+        "to",
+        "amount",
+    ];
+
+    fn csv_fields(&self) -> Vec<String> {
+        vec![
+            self.block_number.to_string(),
+            self.timestamp.map(|ts| ts.to_string()).unwrap_or_default(),
+            self.tx_hash.clone(),
+            self.token.clone(),
+            self.from.clone(),
+            self.to.clone(),
+            self.amount.clone(),
+        ]
+    }
+}
+
+/// GET /erc20/transfers/export - Streams transfers, newest first.
+///
+/// Query parameters:
+/// - format: `csv` (default) or `jsonl`
+/// - contract: token contract address
+/// - address: wallet sending or receiving the transfers
+/// - from_block / to_block: inclusive block range
+pub async fn transfers_export_handler(
+    State(state): State<Erc20ApiState>,
+    Query(query): Query<TransfersExportQuery>,
+) -> Response {
+    let (contract, address) = match (
+        parse_address(query.contract.as_deref()),
+        parse_address(query.address.as_deref()),
+    ) {
+        (Ok(contract), Ok(address)) => (contract, address),
+        (Err(e), _) | (_, Err(e)) => return (StatusCode::BAD_REQUEST, e).into_response(),
+    };
+    let tokens: Vec<Felt> = contract.into_iter().collect();
+    let format = query.format;
+
+    tracing::debug!(
+        target: "torii_erc20::api",
+        ?format,
+        ?contract,
+        ?address,
+        from_block = ?query.from_block,
+        to_block = ?query.to_block,
+        "GET /erc20/transfers/export"
+    );
+
+    let stream = export_stream(format, move |cursor: Option<TransferCursor>| {
+        let storage = state.storage.clone();
+        let tokens = tokens.clone();
+        async move {
+            let (transfers, next) = storage
+                .get_transfers_filtered(
+                    address,
+                    None,
+                    None,
+                    &tokens,
+                    TransferDirection::All,
+                    query.from_block,
+                    query.to_block,
+                    cursor,
+                    EXPORT_PAGE_SIZE,
+                )
+                .await
+                .inspect_err(|e| {
+                    tracing::warn!(target: "torii_erc20::api", error = %e, "Transfer export failed");
+                })?;
+            Ok((
+                transfers
+                    .into_iter()
+                    .map(TransferRow::from)
+                    .collect::<Vec<_>>(),
+                next,
+            ))
+        }
+    });
+
+    Response::builder()
+        .header(header::CONTENT_TYPE, format.content_type())
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!(
+                "attachment; filename=\"erc20-transfers.{}\"",
+                format.extension()
+            ),
+        )
+        .body(Body::from_stream(stream))
+        .unwrap_or_else(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())
+}
+
+fn parse_address(value: Option<&str>) -> Result<Option<Felt>, String> {
+    value
+        .map(|value| Felt::from_hex(value).map_err(|_| format!("Invalid address: {value}")))
+        .transpose()
+}
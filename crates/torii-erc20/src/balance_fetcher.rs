@@ -9,9 +9,9 @@ use anyhow::{Context, Result};
 const MAX_BATCH_SIZE: usize = 500;
 use starknet::core::types::{requests::CallRequest, BlockId, Felt, FunctionCall, U256};
 use starknet::macros::selector;
-use starknet::providers::jsonrpc::{HttpTransport, JsonRpcClient};
 use starknet::providers::{Provider, ProviderRequestData, ProviderResponseData};
 use std::sync::Arc;
+use torii_common::RpcProvider;
 
 /// Request for fetching a balance at a specific block
 #[derive(Debug, Clone)]
@@ -30,12 +30,12 @@ pub struct BalanceFetchRequest {
 /// Used for detecting and correcting balance inconsistencies caused by
 /// genesis allocations, airdrops, or other transfers without events.
 pub struct BalanceFetcher {
-    provider: Arc<JsonRpcClient<HttpTransport>>,
+    provider: Arc<RpcProvider>,
 }
 
 impl BalanceFetcher {
     /// Create a new balance fetcher with the given provider
-    pub fn new(provider: Arc<JsonRpcClient<HttpTransport>>) -> Self {
+    pub fn new(provider: Arc<RpcProvider>) -> Self {
         Self { provider }
     }
 
@@ -0,0 +1,440 @@
+//! Accounts whose balances the sink maintains
+//!
+//! Tracking the balance of every address explodes on popular tokens. With
+//! [`BalanceScope::Watched`], only the accounts of a [`BalanceWatchlist`] get balances:
+//! the sink drops the unwatched side of each transfer before updating balances (a
+//! transfer from a watched account to an unwatched one only debits the watched account).
+//!
+//! The watchlist is persisted in the storage and can be changed at runtime (see the
+//! `AddWatchedAccounts` RPC). Added accounts are backfilled with their balances read
+//! from the chain at the latest indexed block, for every token they transferred.
+//! Removed accounts keep their stored balances, which are no longer updated.
+
+use crate::balance_fetcher::{BalanceFetchRequest, BalanceFetcher};
+use crate::sharding::ShardedErc20Storage;
+use crate::storage::TransferData;
+use anyhow::Result;
+use starknet::core::types::Felt;
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::sync::{Arc, RwLock};
+
+/// Accounts whose balances are maintained by the sink
+#[derive(Clone, Default)]
+pub enum BalanceScope {
+    /// Every address (default)
+    #[default]
+    All,
+    /// The accounts of a watchlist
+    Watched(BalanceWatchlist),
+    /// No address: transfer history only
+    None,
+}
+
+impl BalanceScope {
+    /// The transfers to apply to balances: all of them, only the sides of watched
+    /// accounts, or none.
+    pub fn balance_transfers<'a>(&self, transfers: &'a [TransferData]) -> Cow<'a, [TransferData]> {
+        match self {
+            Self::All => Cow::Borrowed(transfers),
+            Self::Watched(watchlist) => Cow::Owned(watchlist.scope_transfers(transfers)),
+            Self::None => Cow::Borrowed(&[]),
+        }
+    }
+
+    /// Whether balances are maintained for some addresses.
+    pub fn tracks_balances(&self) -> bool {
+        !matches!(self, Self::None)
+    }
+}
+
+/// Outcome of [`BalanceWatchlist::add`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WatchlistAddition {
+    /// Accounts not watched before
+    pub added: usize,
+    /// Balances read from the chain for the added accounts
+    pub backfilled: usize,
+    /// Block the balances were read at (`None` if nothing is indexed yet)
+    pub block_number: Option<u64>,
+}
+
+/// Persisted set of watched accounts, shared by the sink and the gRPC service
+#[derive(Clone)]
+pub struct BalanceWatchlist {
+    storage: ShardedErc20Storage,
+    fetcher: Arc<BalanceFetcher>,
+    accounts: Arc<RwLock<HashSet<Felt>>>,
+}
+
+impl BalanceWatchlist {
+    /// Loads the watchlist stored in `storage`. `fetcher` backfills added accounts.
+    pub async fn load(
+        storage: impl Into<ShardedErc20Storage>,
+        fetcher: Arc<BalanceFetcher>,
+    ) -> Result<Self> {
+        let storage = storage.into();
+        let accounts = storage.get_watched_accounts().await?;
+        Ok(Self {
+            storage,
+            fetcher,
+            accounts: Arc::new(RwLock::new(accounts.into_iter().collect())),
+        })
+    }
+
+    /// Whether `account` is watched.
+    pub fn contains(&self, account: Felt) -> bool {
+        self.accounts.read().unwrap().contains(&account)
+    }
+
+    /// Watched accounts, in address order.
+    pub fn accounts(&self) -> Vec<Felt> {
+        let mut accounts: Vec<_> = self.accounts.read().unwrap().iter().copied().collect();
+        accounts.sort();
+        accounts
+    }
+
+    pub fn len(&self) -> usize {
+        self.accounts.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Watches `accounts` and backfills the balances of the ones not watched before.
+    ///
+    /// Accounts are watched before the backfill, so transfers indexed meanwhile are not
+    /// missed; balances updated by a later block than the backfill one are kept.
+    pub async fn add(&self, accounts: &[Felt]) -> Result<WatchlistAddition> {
+        let new_accounts: Vec<Felt> = {
+            let watched = self.accounts.read().unwrap();
+            accounts
+                .iter()
+                .copied()
+                .filter(|account| !watched.contains(account))
+                .collect::<HashSet<_>>()
+                .into_iter()
+                .collect()
+        };
+        if new_accounts.is_empty() {
+            return Ok(WatchlistAddition::default());
+        }
+        self.storage.add_watched_accounts(&new_accounts).await?;
+        self.accounts.write().unwrap().extend(&new_accounts);
+
+        let Some(block_number) = self.storage.get_latest_block().await? else {
+            return Ok(WatchlistAddition {
+                added: new_accounts.len(),
+                ..WatchlistAddition::default()
+            });
+        };
+        let mut requests = Vec::new();
+        for account in &new_accounts {
+            for token in self.storage.get_wallet_tokens(*account).await? {
+                requests.push(BalanceFetchRequest {
+                    token,
+                    wallet: *account,
+                    block_number,
+                });
+            }
+        }
+        let balances = self.fetcher.fetch_balances_batch(&requests).await?;
+        self.storage
+            .upsert_fetched_balances(&balances, block_number)
+            .await?;
+
+        tracing::info!(
+            target: "torii_erc20::balance_scope",
+            added = new_accounts.len(),
+            backfilled = balances.len(),
+            block_number,
+            "Watched accounts added"
+        );
+        Ok(WatchlistAddition {
+            added: new_accounts.len(),
+            backfilled: balances.len(),
+            block_number: Some(block_number),
+        })
+    }
+
+    /// Stops watching `accounts`. Returns the number of accounts that were watched.
+    pub async fn remove(&self, accounts: &[Felt]) -> Result<usize> {
+        self.storage.remove_watched_accounts(accounts).await?;
+        let mut watched = self.accounts.write().unwrap();
+        Ok(accounts
+            .iter()
+            .filter(|account| watched.remove(account))
+            .count())
+    }
+
+    /// `transfers` with unwatched addresses replaced by the zero address, dropping the
+    /// transfers between unwatched addresses.
+    pub fn scope_transfers(&self, transfers: &[TransferData]) -> Vec<TransferData> {
+        let watched = self.accounts.read().unwrap();
+        scope_transfers(transfers, &watched)
+    }
+}
+
+fn scope_transfers(transfers: &[TransferData], watched: &HashSet<Felt>) -> Vec<TransferData> {
+    transfers
+        .iter()
+        .filter_map(|transfer| {
+            let from = watched.contains(&transfer.from);
+            let to = watched.contains(&transfer.to);
+            (from || to).then(|| TransferData {
+                from: if from { transfer.from } else { Felt::ZERO },
+                to: if to { transfer.to } else { Felt::ZERO },
+                ..transfer.clone()
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoder::Transfer as DecodedTransfer;
+    use crate::sink::Erc20Sink;
+    use crate::storage::Erc20Storage;
+    use axum::extract::State;
+    use axum::Json;
+    use starknet::core::types::U256;
+    use starknet::providers::Url;
+    use std::collections::HashMap;
+    use torii::etl::{Envelope, ExtractionBatch, Sink};
+    use torii_common::RpcProvider;
+
+    /// Balance returned by [`balance_rpc`] for every account
+    const BALANCE: u64 = 42;
+
+    type Calls = Arc<std::sync::Mutex<Vec<(Felt, Felt, u64)>>>;
+
+    /// JSON-RPC endpoint answering every `balanceOf` call with [`BALANCE`], recording the
+    /// (token, wallet, block) of each call.
+    async fn balance_of(
+        State(calls): State<Calls>,
+        Json(batch): Json<Vec<serde_json::Value>>,
+    ) -> Json<Vec<serde_json::Value>> {
+        let felt = |value: &serde_json::Value| Felt::from_hex(value.as_str().unwrap()).unwrap();
+        let responses = batch
+            .iter()
+            .map(|request| {
+                let params = &request["params"];
+                let call = params.get("request").unwrap_or(&params[0]);
+                let block_id = params.get("block_id").unwrap_or(&params[1]);
+                calls.lock().unwrap().push((
+                    felt(&call["contract_address"]),
+                    felt(&call["calldata"][0]),
+                    block_id["block_number"].as_u64().unwrap(),
+                ));
+                serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": request["id"],
+                    "result": [format!("{BALANCE:#x}"), "0x0"],
+                })
+            })
+            .collect();
+        Json(responses)
+    }
+
+    async fn balance_rpc() -> (Arc<RpcProvider>, Calls) {
+        let calls = Calls::default();
+        let app = axum::Router::new()
+            .route("/", axum::routing::post(balance_of))
+            .with_state(calls.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = Url::parse(&format!("http://{}", listener.local_addr().unwrap())).unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        (
+            Arc::new(torii_common::rate_limited_provider(url, 0, 0)),
+            calls,
+        )
+    }
+
+    fn transfer(from: u64, to: u64, amount: u64) -> TransferData {
+        TransferData {
+            id: None,
+            token: Felt::from(1u64),
+            from: Felt::from(from),
+            to: Felt::from(to),
+            amount: U256::from(amount),
+            block_number: 10,
+            tx_hash: Felt::from(10u64),
+            timestamp: None,
+            event_index: None,
+            provenance: None,
+        }
+    }
+
+    #[test]
+    fn unwatched_sides_are_dropped() {
+        let watched = HashSet::from([Felt::from(0xa_u64)]);
+        let transfers = vec![
+            transfer(0xa, 0xb, 5),
+            transfer(0xb, 0xa, 3),
+            transfer(0xb, 0xc, 7),
+            transfer(0, 0xa, 1),
+        ];
+
+        let scoped = scope_transfers(&transfers, &watched);
+        let sides: Vec<_> = scoped.iter().map(|t| (t.from, t.to, t.amount)).collect();
+        assert_eq!(
+            sides,
+            vec![
+                (Felt::from(0xa_u64), Felt::ZERO, U256::from(5u64)),
+                (Felt::ZERO, Felt::from(0xa_u64), U256::from(3u64)),
+                (Felt::ZERO, Felt::from(0xa_u64), U256::from(1u64)),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn added_accounts_are_backfilled_at_the_latest_block() {
+        let (provider, calls) = balance_rpc().await;
+        let fetcher = Arc::new(BalanceFetcher::new(provider));
+        let storage = Arc::new(Erc20Storage::new(":memory:").await.unwrap());
+        let (token_a, token_b) = (Felt::from(1u64), Felt::from(2u64));
+        let (account, other) = (Felt::from(0xa_u64), Felt::from(0xc_u64));
+        storage
+            .insert_transfers_batch(&[
+                transfer(0, 0xa, 10),
+                TransferData {
+                    token: token_b,
+                    block_number: 12,
+                    ..transfer(0xa, 0xb, 4)
+                },
+            ])
+            .await
+            .unwrap();
+        // Updated by a block after the backfill one.
+        storage
+            .upsert_fetched_balances(&[(token_b, account, U256::from(99u64))], 20)
+            .await
+            .unwrap();
+
+        let watchlist = BalanceWatchlist::load(storage.clone(), fetcher.clone())
+            .await
+            .unwrap();
+        let addition = watchlist.add(&[account, account, other]).await.unwrap();
+        assert_eq!(
+            addition,
+            WatchlistAddition {
+                added: 2,
+                backfilled: 2,
+                block_number: Some(12),
+            }
+        );
+        let mut fetched = calls.lock().unwrap().clone();
+        fetched.sort();
+        assert_eq!(
+            fetched,
+            vec![(token_a, account, 12), (token_b, account, 12)]
+        );
+        assert_eq!(
+            storage.get_balance(token_a, account).await.unwrap(),
+            Some(U256::from(BALANCE))
+        );
+        assert_eq!(
+            storage.get_balance(token_b, account).await.unwrap(),
+            Some(U256::from(99u64))
+        );
+
+        // Watched accounts are neither added nor backfilled twice.
+        let addition = watchlist.add(&[account]).await.unwrap();
+        assert_eq!(addition, WatchlistAddition::default());
+        assert_eq!(calls.lock().unwrap().len(), 2);
+
+        let reloaded = BalanceWatchlist::load(storage.clone(), fetcher)
+            .await
+            .unwrap();
+        assert_eq!(reloaded.accounts(), vec![account, other]);
+    }
+
+    #[tokio::test]
+    async fn removed_accounts_keep_their_balances() {
+        let (provider, _) = balance_rpc().await;
+        let storage = Arc::new(Erc20Storage::new(":memory:").await.unwrap());
+        storage
+            .insert_transfers_batch(&[transfer(0, 0xa, 10)])
+            .await
+            .unwrap();
+        let watchlist =
+            BalanceWatchlist::load(storage.clone(), Arc::new(BalanceFetcher::new(provider)))
+                .await
+                .unwrap();
+        let (token, account) = (Felt::from(1u64), Felt::from(0xa_u64));
+        watchlist.add(&[account]).await.unwrap();
+
+        let removed = watchlist
+            .remove(&[account, Felt::from(0xd_u64)])
+            .await
+            .unwrap();
+        assert_eq!(removed, 1);
+        assert!(!watchlist.contains(account));
+        assert!(storage.get_watched_accounts().await.unwrap().is_empty());
+        assert_eq!(
+            storage.get_balance(token, account).await.unwrap(),
+            Some(U256::from(BALANCE))
+        );
+    }
+
+    fn transfer_envelope(from: u64, to: u64, amount: u64, tx_hash: u64) -> Envelope {
+        let token = Felt::from(1u64);
+        let body = DecodedTransfer {
+            from: Felt::from(from),
+            to: Felt::from(to),
+            amount: U256::from(amount),
+            token,
+            block_number: 10,
+            transaction_hash: Felt::from(tx_hash),
+        };
+        Envelope::from_body(format!("transfer-{tx_hash}"), body, HashMap::new())
+            .with_from_address(token)
+    }
+
+    #[tokio::test]
+    async fn unwatched_sides_leave_zero_address_accounting_alone() {
+        let (provider, calls) = balance_rpc().await;
+        let storage = Arc::new(Erc20Storage::new(":memory:").await.unwrap());
+        let watchlist = BalanceWatchlist::load(
+            storage.clone(),
+            Arc::new(BalanceFetcher::new(provider.clone())),
+        )
+        .await
+        .unwrap();
+        let (token, watched, unwatched) =
+            (Felt::from(1u64), Felt::from(0xa_u64), Felt::from(0xb_u64));
+        // Nothing indexed yet: no backfill.
+        watchlist.add(&[watched]).await.unwrap();
+        let sink = Erc20Sink::new(storage.clone())
+            .with_balance_tracking(provider)
+            .with_balance_scope(BalanceScope::Watched(watchlist));
+
+        let envelopes = vec![
+            transfer_envelope(0xa, 0xb, 5, 1),
+            transfer_envelope(0xb, 0xc, 7, 2),
+        ];
+        sink.process(&envelopes, &ExtractionBatch::empty())
+            .await
+            .unwrap();
+
+        // The watched account is reconciled then debited; the unwatched sides and the
+        // zero address get no balance.
+        assert_eq!(*calls.lock().unwrap(), vec![(token, watched, 9)]);
+        assert_eq!(
+            storage.get_balance(token, watched).await.unwrap(),
+            Some(U256::from(BALANCE - 5))
+        );
+        assert_eq!(storage.get_balance(token, unwatched).await.unwrap(), None);
+        assert_eq!(storage.get_balance(token, Felt::ZERO).await.unwrap(), None);
+        // The scoped transfer is not a burn, and the history keeps the real recipient.
+        let (supply, _) = storage
+            .get_supply_history(token, None, None, None, 10)
+            .await
+            .unwrap();
+        assert!(supply.is_empty());
+        let stored = storage.get_transfers_by_tx(Felt::from(1u64)).await.unwrap();
+        assert_eq!(stored[0].to, unwatched);
+    }
+}
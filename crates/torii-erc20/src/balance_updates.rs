@@ -0,0 +1,117 @@
+//! Balance changes streamed to account subscribers (SubscribeBalances)
+//!
+//! The sink aggregates the transfers of a batch per token and account
+//! ([`balance_changes`]), then reads the balances they produced and pushes one
+//! update per touched `(token, account)`. Deltas are computed from the transfers only:
+//! RPC balance adjustments (missed history) show up in the new balance, not in the delta.
+
+use crate::storage::{safe_u256_add, TransferData};
+use starknet::core::types::{Felt, U256};
+use std::collections::BTreeMap;
+
+/// Net effect of a batch of transfers on the balance of an account
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BalanceChange {
+    pub token: Felt,
+    pub account: Felt,
+    /// Received in the batch
+    pub received: U256,
+    /// Sent in the batch
+    pub sent: U256,
+    /// Block of the last transfer touching the account
+    pub block_number: u64,
+    /// Transaction of the last transfer touching the account
+    pub tx_hash: Felt,
+}
+
+impl BalanceChange {
+    /// Absolute balance change and whether the balance decreased
+    pub fn delta(&self) -> (U256, bool) {
+        if self.received >= self.sent {
+            (self.received - self.sent, false)
+        } else {
+            (self.sent - self.received, true)
+        }
+    }
+
+    fn new(token: Felt, account: Felt, transfer: &TransferData) -> Self {
+        Self {
+            token,
+            account,
+            received: U256::from(0u64),
+            sent: U256::from(0u64),
+            block_number: transfer.block_number,
+            tx_hash: transfer.tx_hash,
+        }
+    }
+}
+
+/// Aggregates `transfers` per token and account, skipping the zero address.
+///
+/// Transfers are expected in chain order; the last one sets the block and transaction.
+pub fn balance_changes(transfers: &[TransferData]) -> Vec<BalanceChange> {
+    let mut changes: BTreeMap<(Felt, Felt), BalanceChange> = BTreeMap::new();
+    for transfer in transfers {
+        if transfer.from != Felt::ZERO {
+            let change = changes
+                .entry((transfer.token, transfer.from))
+                .or_insert_with(|| BalanceChange::new(transfer.token, transfer.from, transfer));
+            change.sent = safe_u256_add(change.sent, transfer.amount);
+            change.block_number = transfer.block_number;
+            change.tx_hash = transfer.tx_hash;
+        }
+        if transfer.to != Felt::ZERO {
+            let change = changes
+                .entry((transfer.token, transfer.to))
+                .or_insert_with(|| BalanceChange::new(transfer.token, transfer.to, transfer));
+            change.received = safe_u256_add(change.received, transfer.amount);
+            change.block_number = transfer.block_number;
+            change.tx_hash = transfer.tx_hash;
+        }
+    }
+    changes.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transfer(from: u64, to: u64, amount: u64, block_number: u64) -> TransferData {
+        TransferData {
+            id: None,
+            token: Felt::from(1u64),
+            from: Felt::from(from),
+            to: Felt::from(to),
+            amount: U256::from(amount),
+            block_number,
+            tx_hash: Felt::from(block_number),
+            timestamp: None,
+            event_index: None,
+            provenance: None,
+        }
+    }
+
+    #[test]
+    fn aggregates_per_account() {
+        let changes = balance_changes(&[
+            transfer(0, 10, 100, 1),
+            transfer(10, 11, 30, 2),
+            transfer(11, 10, 5, 3),
+        ]);
+        assert_eq!(changes.len(), 2);
+
+        let first = &changes[0];
+        assert_eq!(first.account, Felt::from(10u64));
+        assert_eq!(first.delta(), (U256::from(75u64), false));
+        assert_eq!(first.block_number, 3);
+
+        let second = &changes[1];
+        assert_eq!(second.account, Felt::from(11u64));
+        assert_eq!(second.delta(), (U256::from(25u64), false));
+        assert_eq!(second.tx_hash, Felt::from(3u64));
+
+        let burn = balance_changes(&[transfer(10, 0, 7, 4)]);
+        assert_eq!(burn.len(), 1);
+        assert_eq!(burn[0].delta(), (U256::from(7u64), true));
+    }
+}
@@ -4,62 +4,63 @@ use anyhow::Result;
 use async_trait::async_trait;
 use starknet::core::types::{EmittedEvent, Felt, U256};
 use starknet::macros::selector;
-use std::any::Any;
-use std::collections::HashMap;
-use torii::etl::{Decoder, Envelope, TypedBody};
+use torii::etl::decoder::{SelectorAliases, StarknetEvent};
+use torii::etl::{Decoder, Envelope, EnvelopeMetadata};
+use torii::ToriiResult;
 
 /// Transfer event from ERC20 token
-#[derive(Debug, Clone)]
+///
+/// Supported layouts (`keys[0]` is the selector):
+/// - Modern (standard): `from`, `to` in keys; `amount` (low, high) in data
+/// - All-in-keys: `from`, `to`, `amount` (low, high) in keys
+/// - Legacy (pre-keys era): `from`, `to`, `amount` (low, high) in data
+/// - Felt-based variants of each, with `amount` as a single felt
+/// - Modern without data: `amount` omitted (zero)
+#[derive(Debug, Clone, StarknetEvent)]
+#[starknet_event(
+    type_id = "erc20.transfer",
+    layouts(declared, keys, data, declared_compact, keys_compact, data_compact)
+)]
 pub struct Transfer {
+    #[event(key)]
     pub from: Felt,
+    #[event(key)]
     pub to: Felt,
     /// Amount as U256 (256-bit), properly representing ERC20 token amounts
+    #[event(data, default)]
     pub amount: U256,
+    #[event(from_address)]
     pub token: Felt,
+    #[event(block_number)]
     pub block_number: u64,
+    #[event(transaction_hash)]
     pub transaction_hash: Felt,
 }
 
-impl TypedBody for Transfer {
-    fn envelope_type_id(&self) -> torii::etl::envelope::TypeId {
-        torii::etl::envelope::TypeId::new("erc20.transfer")
-    }
-
-    fn as_any(&self) -> &dyn Any {
-        self
-    }
-
-    fn as_any_mut(&mut self) -> &mut dyn Any {
-        self
-    }
-}
-
 /// Approval event from ERC20 token
-#[derive(Debug, Clone)]
+///
+/// Same layouts as [`Transfer`], with `owner` and `spender`.
+#[derive(Debug, Clone, StarknetEvent)]
+#[starknet_event(
+    type_id = "erc20.approval",
+    layouts(declared, keys, data, declared_compact, keys_compact, data_compact)
+)]
 pub struct Approval {
+    #[event(key)]
     pub owner: Felt,
+    #[event(key)]
     pub spender: Felt,
     /// Amount as U256 (256-bit), properly representing ERC20 token amounts
+    #[event(data, default)]
     pub amount: U256,
+    #[event(from_address)]
     pub token: Felt,
+    #[event(block_number)]
     pub block_number: u64,
+    #[event(transaction_hash)]
     pub transaction_hash: Felt,
 }
 
-impl TypedBody for Approval {
-    fn envelope_type_id(&self) -> torii::etl::envelope::TypeId {
-        torii::etl::envelope::TypeId::new("erc20.approval")
-    }
-
-    fn as_any(&self) -> &dyn Any {
-        self
-    }
-
-    fn as_any_mut(&mut self) -> &mut dyn Any {
-        self
-    }
-}
-
 /// ERC20 event decoder
 ///
 /// Decodes multiple ERC20 events:
@@ -74,301 +75,59 @@ impl TypedBody for Approval {
 /// 3. Each method returns Result<Option<Envelope>> (None if not interested/malformed)
 /// 4. Main decode_event() collects results
 ///
-/// This pattern scales cleanly to many event types without complex branching.
-pub struct Erc20Decoder;
+/// Event layouts are declared on the bodies with `#[derive(StarknetEvent)]`, which
+/// generates the key/data parsing.
+pub struct Erc20Decoder {
+    aliases: SelectorAliases,
+}
 
 impl Erc20Decoder {
+    /// Decoder of the flat events and of OpenZeppelin's `ERC20Component` events emitted
+    /// under the `ERC20Event` variant.
     pub fn new() -> Self {
-        Self
+        Self {
+            aliases: SelectorAliases::new().with_component(selector!("ERC20Event")),
+        }
+    }
+
+    /// Also decodes events emitted under the component variants and aliases of `aliases`.
+    pub fn with_selector_aliases(mut self, aliases: SelectorAliases) -> Self {
+        self.aliases = self.aliases.merge(&aliases);
+        self
     }
 
     /// Transfer event selector: sn_keccak("Transfer")
     fn transfer_selector() -> Felt {
-        selector!("Transfer")
+        Transfer::SELECTOR
     }
 
     /// Approval event selector: sn_keccak("Approval")
     fn approval_selector() -> Felt {
-        selector!("Approval")
+        Approval::SELECTOR
     }
 
-    /// Decode Transfer event into envelope
-    ///
-    /// Transfer event signatures (supports multiple formats):
-    ///
-    /// Modern ERC20 (standard):
-    /// - keys[0]: Transfer selector
-    /// - keys[1]: from address
-    /// - keys[2]: to address
-    /// - data[0]: amount_low (u128)
-    /// - data[1]: amount_high (u128)
-    ///
-    /// All-in-keys format (some tokens put everything in keys):
-    /// - keys[0]: Transfer selector
-    /// - keys[1]: from address
-    /// - keys[2]: to address
-    /// - keys[3]: amount_low (u128)
-    /// - keys[4]: amount_high (u128)
-    /// - data: empty
-    ///
-    /// Legacy ERC20 (pre-keys era):
-    /// - keys[0]: Transfer selector
-    /// - data[0]: from address
-    /// - data[1]: to address
-    /// - data[2]: amount_low (u128)
-    /// - data[3]: amount_high (u128)
-    ///
-    /// Felt-based variants use single felt for amount instead of U256.
-    async fn decode_transfer(&self, event: &EmittedEvent) -> Result<Option<Envelope>> {
-        let from;
-        let to;
-        let amount: U256;
-
-        if event.keys.len() == 5 && event.data.is_empty() {
-            // All-in-keys format: selector, from, to, amount_low, amount_high all in keys
-            from = event.keys[1];
-            to = event.keys[2];
-            let low: u128 = event.keys[3].try_into().unwrap_or(0);
-            let high: u128 = event.keys[4].try_into().unwrap_or(0);
-            amount = U256::from_words(low, high);
-            tracing::debug!(
-                target: "torii_erc20::decoder",
-                token = %format!("{:#x}", event.from_address),
-                from = %format!("{:#x}", from),
-                to = %format!("{:#x}", to),
-                amount = %amount,
-                "Decoded all-in-keys transfer (U256)"
-            );
-        } else if event.keys.len() == 4 && event.data.is_empty() {
-            // All-in-keys format with felt amount: selector, from, to, amount in keys
-            from = event.keys[1];
-            to = event.keys[2];
-            let amount_felt: u128 = event.keys[3].try_into().unwrap_or(0);
-            amount = U256::from(amount_felt);
-            tracing::debug!(
-                target: "torii_erc20::decoder",
-                token = %format!("{:#x}", event.from_address),
-                from = %format!("{:#x}", from),
-                to = %format!("{:#x}", to),
-                amount = %amount,
-                "Decoded all-in-keys transfer (felt)"
-            );
-        } else if event.keys.len() == 1 && event.data.len() == 4 {
-            // Legacy ERC20: from, to, amount_low, amount_high in data
-            from = event.data[0];
-            to = event.data[1];
-            let low: u128 = event.data[2].try_into().unwrap_or(0);
-            let high: u128 = event.data[3].try_into().unwrap_or(0);
-            amount = U256::from_words(low, high);
-        } else if event.keys.len() == 3 && event.data.len() == 2 {
-            // Modern ERC20: from, to in keys; amount_low, amount_high in data
-            from = event.keys[1];
-            to = event.keys[2];
-            let low: u128 = event.data[0].try_into().unwrap_or(0);
-            let high: u128 = event.data[1].try_into().unwrap_or(0);
-            amount = U256::from_words(low, high);
-        } else if event.keys.len() == 3 && event.data.len() == 1 {
-            // Felt-based modern format: from, to in keys; amount as single felt (fits in u128)
-            from = event.keys[1];
-            to = event.keys[2];
-            let amount_felt: u128 = event.data[0].try_into().unwrap_or(0);
-            amount = U256::from(amount_felt);
-            tracing::debug!(
-                target: "torii_erc20::decoder",
-                token = %format!("{:#x}", event.from_address),
-                from = %format!("{:#x}", from),
-                to = %format!("{:#x}", to),
-                amount = %amount,
-                "Decoded felt-based modern transfer"
-            );
-        } else if event.keys.len() == 1 && event.data.len() == 3 {
-            // Felt-based legacy format: from, to, amount in data
-            from = event.data[0];
-            to = event.data[1];
-            let amount_felt: u128 = event.data[2].try_into().unwrap_or(0);
-            amount = U256::from(amount_felt);
-            tracing::debug!(
-                target: "torii_erc20::decoder",
-                token = %format!("{:#x}", event.from_address),
-                from = %format!("{:#x}", from),
-                to = %format!("{:#x}", to),
-                amount = %amount,
-                "Decoded felt-based legacy transfer"
-            );
-        } else if event.keys.len() == 3 && event.data.is_empty() {
-            // All-in-keys format with zero amount (or amount omitted): from, to in keys, no data
-            from = event.keys[1];
-            to = event.keys[2];
-            amount = U256::from(0u64);
-            tracing::debug!(
-                target: "torii_erc20::decoder",
-                token = %format!("{:#x}", event.from_address),
-                from = %format!("{:#x}", from),
-                to = %format!("{:#x}", to),
-                "Decoded transfer with zero/omitted amount"
-            );
-        } else {
-            tracing::warn!(
-                target: "torii_erc20::decoder",
-                token = %format!("{:#x}", event.from_address),
-                tx_hash = %format!("{:#x}", event.transaction_hash),
-                block_number = event.block_number.unwrap_or(0),
-                keys_len = event.keys.len(),
-                data_len = event.data.len(),
-                "Malformed Transfer event"
-            );
-            return Ok(None);
-        }
-
-        let transfer = Transfer {
-            from,
-            to,
-            amount,
-            token: event.from_address,
-            block_number: event.block_number.unwrap_or(0),
-            transaction_hash: event.transaction_hash,
-        };
-
-        let mut metadata = HashMap::new();
-        metadata.insert("token".to_string(), format!("{:#x}", event.from_address));
+    /// Token, block and transaction metadata of an envelope
+    fn event_metadata(event: &EmittedEvent) -> EnvelopeMetadata {
+        let mut metadata = EnvelopeMetadata::new();
+        metadata.insert(
+            "token".to_string(),
+            format!("{:#x}", event.from_address).into(),
+        );
         metadata.insert(
             "block_number".to_string(),
-            event.block_number.unwrap_or(0).to_string(),
+            event.block_number.unwrap_or(0).to_string().into(),
         );
         metadata.insert(
             "tx_hash".to_string(),
-            format!("{:#x}", event.transaction_hash),
-        );
-
-        let envelope_id = format!(
-            "erc20_transfer_{}_{}",
-            event.block_number.unwrap_or(0),
-            format!("{:#x}", event.transaction_hash)
+            format!("{:#x}", event.transaction_hash).into(),
         );
-
-        Ok(Some(Envelope::new(
-            envelope_id,
-            Box::new(transfer),
-            metadata,
-        )))
+        metadata
     }
 
-    /// Decode Approval event into envelope
-    ///
-    /// Approval event signatures (supports multiple formats):
-    ///
-    /// Modern ERC20 (standard):
-    /// - keys[0]: Approval selector
-    /// - keys[1]: owner address
-    /// - keys[2]: spender address
-    /// - data[0]: amount_low (u128)
-    /// - data[1]: amount_high (u128)
-    ///
-    /// All-in-keys format (some tokens put everything in keys):
-    /// - keys[0]: Approval selector
-    /// - keys[1]: owner address
-    /// - keys[2]: spender address
-    /// - keys[3]: amount_low (u128)
-    /// - keys[4]: amount_high (u128)
-    /// - data: empty
-    ///
-    /// Legacy ERC20 (pre-keys era):
-    /// - keys[0]: Approval selector
-    /// - data[0]: owner address
-    /// - data[1]: spender address
-    /// - data[2]: amount_low (u128)
-    /// - data[3]: amount_high (u128)
-    ///
-    /// Felt-based variants use single felt for amount instead of U256.
-    async fn decode_approval(&self, event: &EmittedEvent) -> Result<Option<Envelope>> {
-        let owner;
-        let spender;
-        let amount: U256;
-
-        if event.keys.len() == 5 && event.data.is_empty() {
-            // All-in-keys format: selector, owner, spender, amount_low, amount_high all in keys
-            owner = event.keys[1];
-            spender = event.keys[2];
-            let low: u128 = event.keys[3].try_into().unwrap_or(0);
-            let high: u128 = event.keys[4].try_into().unwrap_or(0);
-            amount = U256::from_words(low, high);
-            tracing::debug!(
-                target: "torii_erc20::decoder",
-                token = %format!("{:#x}", event.from_address),
-                owner = %format!("{:#x}", owner),
-                spender = %format!("{:#x}", spender),
-                amount = %amount,
-                "Decoded all-in-keys approval (U256)"
-            );
-        } else if event.keys.len() == 4 && event.data.is_empty() {
-            // All-in-keys format with felt amount: selector, owner, spender, amount in keys
-            owner = event.keys[1];
-            spender = event.keys[2];
-            let amount_felt: u128 = event.keys[3].try_into().unwrap_or(0);
-            amount = U256::from(amount_felt);
-            tracing::debug!(
-                target: "torii_erc20::decoder",
-                token = %format!("{:#x}", event.from_address),
-                owner = %format!("{:#x}", owner),
-                spender = %format!("{:#x}", spender),
-                amount = %amount,
-                "Decoded all-in-keys approval (felt)"
-            );
-        } else if event.keys.len() == 1 && event.data.len() == 4 {
-            // Legacy ERC20: owner, spender, amount_low, amount_high in data
-            owner = event.data[0];
-            spender = event.data[1];
-            let low: u128 = event.data[2].try_into().unwrap_or(0);
-            let high: u128 = event.data[3].try_into().unwrap_or(0);
-            amount = U256::from_words(low, high);
-        } else if event.keys.len() == 3 && event.data.len() == 2 {
-            // Modern ERC20: owner, spender in keys; amount_low, amount_high in data
-            owner = event.keys[1];
-            spender = event.keys[2];
-            let low: u128 = event.data[0].try_into().unwrap_or(0);
-            let high: u128 = event.data[1].try_into().unwrap_or(0);
-            amount = U256::from_words(low, high);
-        } else if event.keys.len() == 3 && event.data.len() == 1 {
-            // Felt-based modern format: owner, spender in keys; amount as single felt
-            owner = event.keys[1];
-            spender = event.keys[2];
-            let amount_felt: u128 = event.data[0].try_into().unwrap_or(0);
-            amount = U256::from(amount_felt);
-            tracing::debug!(
-                target: "torii_erc20::decoder",
-                token = %format!("{:#x}", event.from_address),
-                owner = %format!("{:#x}", owner),
-                spender = %format!("{:#x}", spender),
-                amount = %amount,
-                "Decoded felt-based modern approval"
-            );
-        } else if event.keys.len() == 1 && event.data.len() == 3 {
-            // Felt-based legacy format: owner, spender, amount in data
-            owner = event.data[0];
-            spender = event.data[1];
-            let amount_felt: u128 = event.data[2].try_into().unwrap_or(0);
-            amount = U256::from(amount_felt);
-            tracing::debug!(
-                target: "torii_erc20::decoder",
-                token = %format!("{:#x}", event.from_address),
-                owner = %format!("{:#x}", owner),
-                spender = %format!("{:#x}", spender),
-                amount = %amount,
-                "Decoded felt-based legacy approval"
-            );
-        } else if event.keys.len() == 3 && event.data.is_empty() {
-            // All-in-keys format with zero amount (or amount omitted)
-            owner = event.keys[1];
-            spender = event.keys[2];
-            amount = U256::from(0u64);
-            tracing::debug!(
-                target: "torii_erc20::decoder",
-                token = %format!("{:#x}", event.from_address),
-                owner = %format!("{:#x}", owner),
-                spender = %format!("{:#x}", spender),
-                "Decoded approval with zero/omitted amount"
-            );
-        } else {
+    /// Decodes `event` as `T`, logging malformed events.
+    fn decode_body<T: StarknetEvent>(event: &EmittedEvent) -> Option<T> {
+        let body = T::decode(event);
+        if body.is_none() {
             tracing::warn!(
                 target: "torii_erc20::decoder",
                 token = %format!("{:#x}", event.from_address),
@@ -376,42 +135,33 @@ impl Erc20Decoder {
                 block_number = event.block_number.unwrap_or(0),
                 keys_len = event.keys.len(),
                 data_len = event.data.len(),
-                "Malformed Approval event"
+                "Malformed {} event",
+                T::NAME
             );
-            return Ok(None);
         }
+        body
+    }
 
-        let approval = Approval {
-            owner,
-            spender,
-            amount,
-            token: event.from_address,
-            block_number: event.block_number.unwrap_or(0),
-            transaction_hash: event.transaction_hash,
-        };
-
-        let mut metadata = HashMap::new();
-        metadata.insert("token".to_string(), format!("{:#x}", event.from_address));
-        metadata.insert(
-            "block_number".to_string(),
-            event.block_number.unwrap_or(0).to_string(),
-        );
-        metadata.insert(
-            "tx_hash".to_string(),
-            format!("{:#x}", event.transaction_hash),
-        );
-
-        let envelope_id = format!(
-            "erc20_approval_{}_{}",
-            event.block_number.unwrap_or(0),
-            format!("{:#x}", event.transaction_hash)
-        );
+    /// Decode Transfer event into envelope (see [`Transfer`] for the layouts)
+    async fn decode_transfer(&self, event: &EmittedEvent) -> Result<Option<Envelope>> {
+        Ok(Self::decode_body::<Transfer>(event).map(|transfer| {
+            Envelope::from_body(
+                Transfer::envelope_id(event),
+                transfer,
+                Self::event_metadata(event),
+            )
+        }))
+    }
 
-        Ok(Some(Envelope::new(
-            envelope_id,
-            Box::new(approval),
-            metadata,
-        )))
+    /// Decode Approval event into envelope (see [`Approval`] for the layouts)
+    async fn decode_approval(&self, event: &EmittedEvent) -> Result<Option<Envelope>> {
+        Ok(Self::decode_body::<Approval>(event).map(|approval| {
+            Envelope::from_body(
+                Approval::envelope_id(event),
+                approval,
+                Self::event_metadata(event),
+            )
+        }))
     }
 }
 
@@ -427,7 +177,13 @@ impl Decoder for Erc20Decoder {
         "erc20"
     }
 
-    async fn decode_event(&self, event: &EmittedEvent) -> Result<Vec<Envelope>> {
+    fn decoder_version(&self) -> Option<&str> {
+        Some(env!("CARGO_PKG_VERSION"))
+    }
+
+    async fn decode_event(&self, event: &EmittedEvent) -> ToriiResult<Vec<Envelope>> {
+        let event = self.aliases.normalize(event);
+        let event: &EmittedEvent = &event;
         if event.keys.is_empty() {
             return Ok(Vec::new());
         }
@@ -465,6 +221,8 @@ impl Decoder for Erc20Decoder {
 mod tests {
     use super::*;
 
+    torii_test_utils::decoder_golden_test!(Erc20Decoder::new(), "fixtures/erc20.json");
+
     #[tokio::test]
     async fn test_decode_transfer() {
         let decoder = Erc20Decoder::new();
@@ -535,6 +293,47 @@ mod tests {
         assert_eq!(approval.token, Felt::from(0x456u64));
     }
 
+    #[tokio::test]
+    async fn test_decode_component_and_aliased_transfer() {
+        let decoder = Erc20Decoder::new().with_selector_aliases(
+            SelectorAliases::new().with_alias(selector!("TokenTransfer"), selector!("Transfer")),
+        );
+
+        // OpenZeppelin ERC20Component embedded without #[flat]
+        let mut event = EmittedEvent {
+            from_address: Felt::from(0x123u64),
+            keys: vec![
+                selector!("ERC20Event"),
+                Erc20Decoder::transfer_selector(),
+                Felt::from(0x1u64), // from
+                Felt::from(0x2u64), // to
+            ],
+            data: vec![Felt::from(1000u64), Felt::ZERO],
+            block_hash: None,
+            block_number: Some(100),
+            transaction_hash: Felt::from(0xabcdu64),
+        };
+
+        let envelopes = decoder.decode_event(&event).await.unwrap();
+        assert_eq!(envelopes.len(), 1);
+        let transfer = envelopes[0]
+            .body
+            .as_any()
+            .downcast_ref::<Transfer>()
+            .unwrap();
+        assert_eq!(transfer.from, Felt::from(0x1u64));
+        assert_eq!(transfer.to, Felt::from(0x2u64));
+        assert_eq!(transfer.amount, U256::from(1000u64));
+
+        event.keys[1] = selector!("TokenTransfer");
+        let envelopes = decoder.decode_event(&event).await.unwrap();
+        assert_eq!(envelopes.len(), 1);
+
+        // Aliases are per decoder
+        let envelopes = Erc20Decoder::new().decode_event(&event).await.unwrap();
+        assert!(envelopes.is_empty());
+    }
+
     #[tokio::test]
     async fn test_decode_unknown_event() {
         let decoder = Erc20Decoder::new();
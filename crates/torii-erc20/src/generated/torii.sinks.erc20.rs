@@ -1,4 +1,26 @@
 // This file is @generated by prost-build.
+/// Where a stored row came from (only recorded when provenance tracking is enabled)
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Provenance {
+    /// Extractor type that produced the source event
+    #[prost(string, tag = "1")]
+    pub extractor: ::prost::alloc::string::String,
+    /// Decoder that produced the event
+    #[prost(string, tag = "2")]
+    pub decoder: ::prost::alloc::string::String,
+    /// ETL batch id (monotonic per process run)
+    #[prost(uint64, tag = "3")]
+    pub batch_id: u64,
+    /// Unix timestamp (ms) when the batch was extracted
+    #[prost(int64, tag = "4")]
+    pub extracted_at: i64,
+    /// Unix timestamp (ms) when the event was decoded
+    #[prost(int64, tag = "5")]
+    pub decoded_at: i64,
+    /// Unix timestamp (ms) when the row was stored
+    #[prost(int64, tag = "6")]
+    pub stored_at: i64,
+}
 /// ERC20 Transfer event
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Transfer {
@@ -23,6 +45,13 @@ pub struct Transfer {
     /// Unix timestamp of the block
     #[prost(int64, tag = "7")]
     pub timestamp: i64,
+    /// Provenance (only set when requested and recorded)
+    #[prost(message, optional, tag = "8")]
+    pub provenance: ::core::option::Option<Provenance>,
+    /// USD value at the token price of the transfer's block window
+    /// (only set when requested and a price and decimals are known)
+    #[prost(double, optional, tag = "9")]
+    pub amount_usd: ::core::option::Option<f64>,
 }
 /// ERC20 Approval event
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -48,6 +77,9 @@ pub struct Approval {
     /// Unix timestamp of the block
     #[prost(int64, tag = "7")]
     pub timestamp: i64,
+    /// Provenance (only set when requested and recorded)
+    #[prost(message, optional, tag = "8")]
+    pub provenance: ::core::option::Option<Provenance>,
 }
 /// Filter for transfer queries and subscriptions
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -118,6 +150,19 @@ pub struct GetTransfersRequest {
     /// Maximum number of transfers to return (default: 100, max: 1000)
     #[prost(uint32, tag = "3")]
     pub limit: u32,
+    /// Attach provenance to returned transfers (when recorded)
+    #[prost(bool, tag = "4")]
+    pub include_provenance: bool,
+    /// Attach USD valuation to returned transfers (when prices are recorded)
+    #[prost(bool, tag = "5")]
+    pub include_usd: bool,
+    /// Attach the labels of the tokens and wallets in the returned transfers
+    #[prost(bool, tag = "6")]
+    pub include_labels: bool,
+    /// Transfer fields to return (empty = all). Other fields are left unset, e.g.
+    /// \["token", "amount", "timestamp"\] for a lightweight activity feed.
+    #[prost(string, repeated, tag = "7")]
+    pub field_mask: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
 /// Response for GetTransfers RPC
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -128,6 +173,22 @@ pub struct GetTransfersResponse {
     /// Cursor for next page (absent if no more results)
     #[prost(message, optional, tag = "2")]
     pub next_cursor: ::core::option::Option<Cursor>,
+    /// Labels of the labelled addresses in `transfers` (when include_labels is set)
+    #[prost(message, repeated, tag = "3")]
+    pub labels: ::prost::alloc::vec::Vec<AddressLabel>,
+}
+/// Human-readable label of an address (exchange, bridge, known spam, ...)
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AddressLabel {
+    /// Labelled address (32 bytes)
+    #[prost(bytes = "vec", tag = "1")]
+    pub address: ::prost::alloc::vec::Vec<u8>,
+    /// Display name
+    #[prost(string, tag = "2")]
+    pub name: ::prost::alloc::string::String,
+    /// Free-form tags (e.g. "cex", "spam")
+    #[prost(string, repeated, tag = "3")]
+    pub tags: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
 /// Request for GetApprovals RPC
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -141,6 +202,9 @@ pub struct GetApprovalsRequest {
     /// Maximum number of approvals to return (default: 100, max: 1000)
     #[prost(uint32, tag = "3")]
     pub limit: u32,
+    /// Attach provenance to returned approvals (when recorded)
+    #[prost(bool, tag = "4")]
+    pub include_provenance: bool,
 }
 /// Response for GetApprovals RPC
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -152,6 +216,22 @@ pub struct GetApprovalsResponse {
     #[prost(message, optional, tag = "2")]
     pub next_cursor: ::core::option::Option<Cursor>,
 }
+/// Request for ReplayTransfers RPC
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReplayTransfersRequest {
+    /// Minimum block number (inclusive)
+    #[prost(uint64, optional, tag = "1")]
+    pub block_from: ::core::option::Option<u64>,
+    /// Maximum block number (inclusive)
+    #[prost(uint64, optional, tag = "2")]
+    pub block_to: ::core::option::Option<u64>,
+    /// Token whitelist (empty = all tokens)
+    #[prost(bytes = "vec", repeated, tag = "3")]
+    pub tokens: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+    /// Rows read from storage per page (default: 500, max: 5000)
+    #[prost(uint32, tag = "4")]
+    pub page_size: u32,
+}
 /// Request for SubscribeTransfers RPC
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SubscribeTransfersRequest {
@@ -172,7 +252,27 @@ pub struct SubscribeApprovalsRequest {
     #[prost(message, optional, tag = "2")]
     pub filter: ::core::option::Option<ApprovalFilter>,
 }
+/// Request for SubscribeBalances RPC
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SubscribeBalancesRequest {
+    /// Client identifier for logging/debugging
+    #[prost(string, tag = "1")]
+    pub client_id: ::prost::alloc::string::String,
+    /// Account whose balances are streamed (32 bytes)
+    #[prost(bytes = "vec", tag = "2")]
+    pub account: ::prost::alloc::vec::Vec<u8>,
+    /// Token contracts to stream (32 bytes each; empty = all tokens)
+    #[prost(bytes = "vec", repeated, tag = "3")]
+    pub tokens: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+}
 /// Update message for transfer subscriptions
+/// Last message of a subscription stream: the server is shutting down
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct StreamShutdown {
+    /// Last block fully indexed; resume (e.g. with ReplayTransfers) from the next block
+    #[prost(uint64, tag = "1")]
+    pub block_number: u64,
+}
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct TransferUpdate {
     /// The transfer event
@@ -181,6 +281,9 @@ pub struct TransferUpdate {
     /// Unix timestamp when the update was generated
     #[prost(int64, tag = "2")]
     pub timestamp: i64,
+    /// Set on the last update when the server shuts down (transfer is then absent)
+    #[prost(message, optional, tag = "3")]
+    pub shutdown: ::core::option::Option<StreamShutdown>,
 }
 /// Update message for approval subscriptions
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -191,6 +294,74 @@ pub struct ApprovalUpdate {
     /// Unix timestamp when the update was generated
     #[prost(int64, tag = "2")]
     pub timestamp: i64,
+    /// Set on the last update when the server shuts down (approval is then absent)
+    #[prost(message, optional, tag = "3")]
+    pub shutdown: ::core::option::Option<StreamShutdown>,
+}
+/// Update message for balance subscriptions: one per token whose balance changed in a batch
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BalanceUpdate {
+    /// Token contract address (32 bytes)
+    #[prost(bytes = "vec", tag = "1")]
+    pub token: ::prost::alloc::vec::Vec<u8>,
+    /// Account address (32 bytes)
+    #[prost(bytes = "vec", tag = "2")]
+    pub account: ::prost::alloc::vec::Vec<u8>,
+    /// New balance as U256 (variable length, up to 32 bytes)
+    #[prost(bytes = "vec", tag = "3")]
+    pub balance: ::prost::alloc::vec::Vec<u8>,
+    /// Absolute balance change from the transfers of the batch as U256
+    #[prost(bytes = "vec", tag = "4")]
+    pub delta: ::prost::alloc::vec::Vec<u8>,
+    /// Whether the transfers decreased the balance
+    #[prost(bool, tag = "5")]
+    pub decreased: bool,
+    /// Block of the last transfer touching the account
+    #[prost(uint64, tag = "6")]
+    pub block_number: u64,
+    /// Transaction of the last transfer touching the account (32 bytes)
+    #[prost(bytes = "vec", tag = "7")]
+    pub tx_hash: ::prost::alloc::vec::Vec<u8>,
+    /// Unix timestamp when the update was generated
+    #[prost(int64, tag = "8")]
+    pub timestamp: i64,
+    /// Set on the last update when the server shuts down (other fields are then empty)
+    #[prost(message, optional, tag = "9")]
+    pub shutdown: ::core::option::Option<StreamShutdown>,
+}
+/// Request for WatchAddresses RPC (each message replaces the watched set)
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct WatchAddressesRequest {
+    /// Client identifier for logging/debugging
+    #[prost(string, tag = "1")]
+    pub client_id: ::prost::alloc::string::String,
+    /// Account addresses to watch (32 bytes each, max 10000)
+    #[prost(bytes = "vec", repeated, tag = "2")]
+    pub addresses: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+}
+/// Update pushed to address watchers: a transfer or approval involving a watched address
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct WatchUpdate {
+    /// Unix timestamp when the update was generated
+    #[prost(int64, tag = "3")]
+    pub timestamp: i64,
+    #[prost(oneof = "watch_update::Event", tags = "1, 2, 4")]
+    pub event: ::core::option::Option<watch_update::Event>,
+}
+/// Nested message and enum types in `WatchUpdate`.
+pub mod watch_update {
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Event {
+        /// Transfer from or to a watched address
+        #[prost(message, tag = "1")]
+        Transfer(super::Transfer),
+        /// Approval granted by or to a watched address
+        #[prost(message, tag = "2")]
+        Approval(super::Approval),
+        /// Last update: the server is shutting down
+        #[prost(message, tag = "4")]
+        Shutdown(super::StreamShutdown),
+    }
 }
 /// Request for GetBalance RPC
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -201,6 +372,9 @@ pub struct GetBalanceRequest {
     /// Wallet address (32 bytes)
     #[prost(bytes = "vec", tag = "2")]
     pub wallet: ::prost::alloc::vec::Vec<u8>,
+    /// Attach USD valuation at the latest recorded token price
+    #[prost(bool, tag = "3")]
+    pub include_usd: bool,
 }
 /// Response for GetBalance RPC
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -211,6 +385,12 @@ pub struct GetBalanceResponse {
     /// Last block number where balance was updated
     #[prost(uint64, tag = "2")]
     pub last_block: u64,
+    /// Latest recorded USD price of the token (only set when requested and known)
+    #[prost(double, optional, tag = "3")]
+    pub price_usd: ::core::option::Option<f64>,
+    /// Balance value in USD (only set when requested and price and decimals are known)
+    #[prost(double, optional, tag = "4")]
+    pub balance_usd: ::core::option::Option<f64>,
 }
 /// Balance row for batch balance queries
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -227,6 +407,12 @@ pub struct BalanceEntry {
     /// Last block number where balance was updated
     #[prost(uint64, tag = "4")]
     pub last_block: u64,
+    /// Latest recorded USD price of the token (only set when requested and known)
+    #[prost(double, optional, tag = "5")]
+    pub price_usd: ::core::option::Option<f64>,
+    /// Balance value in USD (only set when requested and price and decimals are known)
+    #[prost(double, optional, tag = "6")]
+    pub balance_usd: ::core::option::Option<f64>,
 }
 /// Request for GetBalances RPC (batch balance query)
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -243,6 +429,12 @@ pub struct GetBalancesRequest {
     /// Maximum number of rows to return (default: 1000, max: 10000)
     #[prost(uint32, tag = "4")]
     pub limit: u32,
+    /// Attach USD valuation at the latest recorded token prices
+    #[prost(bool, tag = "5")]
+    pub include_usd: bool,
+    /// Attach the labels of the tokens and wallets in the returned balances
+    #[prost(bool, tag = "6")]
+    pub include_labels: bool,
 }
 /// Response for GetBalances RPC
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -253,6 +445,134 @@ pub struct GetBalancesResponse {
     /// Cursor for next page (absent if no more results)
     #[prost(int64, optional, tag = "2")]
     pub next_cursor: ::core::option::Option<i64>,
+    /// Labels of the labelled addresses in `balances` (when include_labels is set)
+    #[prost(message, repeated, tag = "3")]
+    pub labels: ::prost::alloc::vec::Vec<AddressLabel>,
+}
+/// Request for GetPortfolio RPC
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetPortfolioRequest {
+    /// Account address (32 bytes)
+    #[prost(bytes = "vec", tag = "1")]
+    pub account: ::prost::alloc::vec::Vec<u8>,
+    /// Attach USD valuation at the latest recorded token prices
+    #[prost(bool, tag = "2")]
+    pub include_usd: bool,
+}
+/// Non-zero holding of an account in one token, with the token metadata
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PortfolioHolding {
+    /// Token contract address (32 bytes)
+    #[prost(bytes = "vec", tag = "1")]
+    pub token: ::prost::alloc::vec::Vec<u8>,
+    /// Balance as U256 (variable length, up to 32 bytes)
+    #[prost(bytes = "vec", tag = "2")]
+    pub balance: ::prost::alloc::vec::Vec<u8>,
+    /// Last block number where balance was updated
+    #[prost(uint64, tag = "3")]
+    pub last_block: u64,
+    /// Token name (unset until metadata is fetched)
+    #[prost(string, optional, tag = "4")]
+    pub name: ::core::option::Option<::prost::alloc::string::String>,
+    /// Token symbol (unset until metadata is fetched)
+    #[prost(string, optional, tag = "5")]
+    pub symbol: ::core::option::Option<::prost::alloc::string::String>,
+    /// Token decimals (unset until metadata is fetched)
+    #[prost(uint32, optional, tag = "6")]
+    pub decimals: ::core::option::Option<u32>,
+    /// Latest recorded USD price of the token (only set when requested and known)
+    #[prost(double, optional, tag = "7")]
+    pub price_usd: ::core::option::Option<f64>,
+    /// Balance value in USD (only set when requested and price and decimals are known)
+    #[prost(double, optional, tag = "8")]
+    pub balance_usd: ::core::option::Option<f64>,
+}
+/// Response for GetPortfolio RPC
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetPortfolioResponse {
+    /// Holdings of the account, in token address order
+    #[prost(message, repeated, tag = "1")]
+    pub holdings: ::prost::alloc::vec::Vec<PortfolioHolding>,
+    /// Sum of the holdings valued in USD (only set when requested)
+    #[prost(double, optional, tag = "2")]
+    pub total_usd: ::core::option::Option<f64>,
+}
+/// Current allowance (latest approval) of a spender over an owner's tokens
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Allowance {
+    /// Token contract address (32 bytes)
+    #[prost(bytes = "vec", tag = "1")]
+    pub token: ::prost::alloc::vec::Vec<u8>,
+    /// Owner address (32 bytes)
+    #[prost(bytes = "vec", tag = "2")]
+    pub owner: ::prost::alloc::vec::Vec<u8>,
+    /// Spender address (32 bytes)
+    #[prost(bytes = "vec", tag = "3")]
+    pub spender: ::prost::alloc::vec::Vec<u8>,
+    /// Allowance as U256 (variable length, up to 32 bytes). Zero once revoked.
+    #[prost(bytes = "vec", tag = "4")]
+    pub amount: ::prost::alloc::vec::Vec<u8>,
+    /// Block number of the approval that set this allowance
+    #[prost(uint64, tag = "5")]
+    pub block_number: u64,
+    /// Transaction hash of the approval (32 bytes)
+    #[prost(bytes = "vec", tag = "6")]
+    pub tx_hash: ::prost::alloc::vec::Vec<u8>,
+    /// Unix timestamp of the approval
+    #[prost(int64, tag = "7")]
+    pub timestamp: i64,
+}
+/// Request for GetAllowances RPC
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetAllowancesRequest {
+    /// Owner address (32 bytes)
+    #[prost(bytes = "vec", tag = "1")]
+    pub owner: ::prost::alloc::vec::Vec<u8>,
+    /// Optional token filter
+    #[prost(bytes = "vec", optional, tag = "2")]
+    pub token: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+    /// Cursor from previous response (row id). Omit for first page.
+    #[prost(int64, optional, tag = "3")]
+    pub cursor: ::core::option::Option<i64>,
+    /// Maximum number of rows to return (default: 1000, max: 10000)
+    #[prost(uint32, tag = "4")]
+    pub limit: u32,
+}
+/// Response for GetAllowances RPC
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetAllowancesResponse {
+    /// Current allowances granted by the owner
+    #[prost(message, repeated, tag = "1")]
+    pub allowances: ::prost::alloc::vec::Vec<Allowance>,
+    /// Cursor for next page (absent if no more results)
+    #[prost(int64, optional, tag = "2")]
+    pub next_cursor: ::core::option::Option<i64>,
+}
+/// Request for GetApprovalsForSpender RPC
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetApprovalsForSpenderRequest {
+    /// Spender address (32 bytes)
+    #[prost(bytes = "vec", tag = "1")]
+    pub spender: ::prost::alloc::vec::Vec<u8>,
+    /// Optional token filter
+    #[prost(bytes = "vec", optional, tag = "2")]
+    pub token: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+    /// Cursor from previous response (row id). Omit for first page.
+    #[prost(int64, optional, tag = "3")]
+    pub cursor: ::core::option::Option<i64>,
+    /// Maximum number of rows to return (default: 1000, max: 10000)
+    #[prost(uint32, tag = "4")]
+    pub limit: u32,
+}
+/// Response for GetApprovalsForSpender RPC
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetApprovalsForSpenderResponse {
+    /// Current allowances granted to the spender
+    #[prost(message, repeated, tag = "1")]
+    pub allowances: ::prost::alloc::vec::Vec<Allowance>,
+    /// Cursor for next page (absent if no more results)
+    #[prost(int64, optional, tag = "2")]
+    pub next_cursor: ::core::option::Option<i64>,
 }
 /// Request for GetTokenMetadata RPC
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -296,6 +616,115 @@ pub struct GetTokenMetadataResponse {
     #[prost(bytes = "vec", optional, tag = "2")]
     pub next_cursor: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
 }
+/// Supply of a token after a block with mints (from zero) or burns (to zero)
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SupplySnapshot {
+    /// Token contract address (32 bytes)
+    #[prost(bytes = "vec", tag = "1")]
+    pub token: ::prost::alloc::vec::Vec<u8>,
+    /// Block number of the snapshot
+    #[prost(uint64, tag = "2")]
+    pub block_number: u64,
+    /// Minted in this block as U256 (variable length, up to 32 bytes)
+    #[prost(bytes = "vec", tag = "3")]
+    pub minted: ::prost::alloc::vec::Vec<u8>,
+    /// Burned in this block as U256 (variable length, up to 32 bytes)
+    #[prost(bytes = "vec", tag = "4")]
+    pub burned: ::prost::alloc::vec::Vec<u8>,
+    /// Minted up to and including this block as U256
+    #[prost(bytes = "vec", tag = "5")]
+    pub total_minted: ::prost::alloc::vec::Vec<u8>,
+    /// Burned up to and including this block as U256
+    #[prost(bytes = "vec", tag = "6")]
+    pub total_burned: ::prost::alloc::vec::Vec<u8>,
+    /// Circulating supply (total minted - total burned) as U256.
+    /// Only covers the indexed history; saturates at zero.
+    #[prost(bytes = "vec", tag = "7")]
+    pub circulating_supply: ::prost::alloc::vec::Vec<u8>,
+}
+/// Request for GetSupplyHistory RPC
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetSupplyHistoryRequest {
+    /// Token contract address (32 bytes)
+    #[prost(bytes = "vec", tag = "1")]
+    pub token: ::prost::alloc::vec::Vec<u8>,
+    /// Inclusive lower block bound
+    #[prost(uint64, optional, tag = "2")]
+    pub from_block: ::core::option::Option<u64>,
+    /// Inclusive upper block bound
+    #[prost(uint64, optional, tag = "3")]
+    pub to_block: ::core::option::Option<u64>,
+    /// Cursor from previous response (block number). Omit for first page.
+    #[prost(uint64, optional, tag = "4")]
+    pub cursor: ::core::option::Option<u64>,
+    /// Maximum number of snapshots to return (default: 1000, max: 10000)
+    #[prost(uint32, tag = "5")]
+    pub limit: u32,
+}
+/// Response for GetSupplyHistory RPC
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetSupplyHistoryResponse {
+    /// Snapshots in ascending block order
+    #[prost(message, repeated, tag = "1")]
+    pub snapshots: ::prost::alloc::vec::Vec<SupplySnapshot>,
+    /// Cursor for next page (absent if no more results)
+    #[prost(uint64, optional, tag = "2")]
+    pub next_cursor: ::core::option::Option<u64>,
+}
+/// Transfer activity of a token within one bucket
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct VolumeBucket {
+    /// Token contract address (32 bytes)
+    #[prost(bytes = "vec", tag = "1")]
+    pub token: ::prost::alloc::vec::Vec<u8>,
+    /// Bucket start (unix seconds)
+    #[prost(int64, tag = "2")]
+    pub bucket_start: i64,
+    /// Number of transfers in the bucket
+    #[prost(uint64, tag = "3")]
+    pub transfer_count: u64,
+    /// Sum of transferred amounts as U256 (variable length, up to 32 bytes)
+    #[prost(bytes = "vec", tag = "4")]
+    pub volume: ::prost::alloc::vec::Vec<u8>,
+    /// Distinct senders (zero address excluded)
+    #[prost(uint64, tag = "5")]
+    pub unique_senders: u64,
+    /// Distinct receivers (zero address excluded)
+    #[prost(uint64, tag = "6")]
+    pub unique_receivers: u64,
+}
+/// Request for GetVolumeSeries RPC
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetVolumeSeriesRequest {
+    /// Token contract address (32 bytes)
+    #[prost(bytes = "vec", tag = "1")]
+    pub token: ::prost::alloc::vec::Vec<u8>,
+    /// Bucket width
+    #[prost(enumeration = "VolumeInterval", tag = "2")]
+    pub interval: i32,
+    /// Inclusive lower bound on the bucket start (unix seconds)
+    #[prost(int64, optional, tag = "3")]
+    pub from_timestamp: ::core::option::Option<i64>,
+    /// Inclusive upper bound on the bucket start (unix seconds)
+    #[prost(int64, optional, tag = "4")]
+    pub to_timestamp: ::core::option::Option<i64>,
+    /// Cursor from previous response (bucket start). Omit for first page.
+    #[prost(int64, optional, tag = "5")]
+    pub cursor: ::core::option::Option<i64>,
+    /// Maximum number of buckets to return (default: 1000, max: 10000)
+    #[prost(uint32, tag = "6")]
+    pub limit: u32,
+}
+/// Response for GetVolumeSeries RPC
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetVolumeSeriesResponse {
+    /// Buckets with transfers, in ascending order
+    #[prost(message, repeated, tag = "1")]
+    pub buckets: ::prost::alloc::vec::Vec<VolumeBucket>,
+    /// Cursor for next page (absent if no more results)
+    #[prost(int64, optional, tag = "2")]
+    pub next_cursor: ::core::option::Option<i64>,
+}
 /// Request for GetStats RPC
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
 pub struct GetStatsRequest {}
@@ -315,6 +744,50 @@ pub struct GetStatsResponse {
     #[prost(uint64, tag = "4")]
     pub latest_block: u64,
 }
+/// Request for AddWatchedAccounts RPC
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AddWatchedAccountsRequest {
+    /// Accounts to watch (32 bytes each)
+    #[prost(bytes = "vec", repeated, tag = "1")]
+    pub accounts: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+}
+/// Response for AddWatchedAccounts RPC
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct AddWatchedAccountsResponse {
+    /// Accounts not watched before
+    #[prost(uint32, tag = "1")]
+    pub added: u32,
+    /// Balances read from the chain for the added accounts
+    #[prost(uint32, tag = "2")]
+    pub backfilled: u32,
+    /// Block the balances were read at (absent if nothing is indexed yet)
+    #[prost(uint64, optional, tag = "3")]
+    pub block_number: ::core::option::Option<u64>,
+}
+/// Request for RemoveWatchedAccounts RPC
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RemoveWatchedAccountsRequest {
+    /// Accounts to stop watching (32 bytes each)
+    #[prost(bytes = "vec", repeated, tag = "1")]
+    pub accounts: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+}
+/// Response for RemoveWatchedAccounts RPC
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct RemoveWatchedAccountsResponse {
+    /// Accounts that were watched
+    #[prost(uint32, tag = "1")]
+    pub removed: u32,
+}
+/// Request for ListWatchedAccounts RPC
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct ListWatchedAccountsRequest {}
+/// Response for ListWatchedAccounts RPC
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListWatchedAccountsResponse {
+    /// Watched accounts, in address order
+    #[prost(bytes = "vec", repeated, tag = "1")]
+    pub accounts: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+}
 /// Direction filter for transfer queries
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
@@ -348,6 +821,35 @@ impl TransferDirection {
         }
     }
 }
+/// Width of a volume rollup bucket
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum VolumeInterval {
+    /// One hour (bucket starts aligned to the hour, UTC)
+    Hour = 0,
+    /// One day (bucket starts aligned to midnight, UTC)
+    Day = 1,
+}
+impl VolumeInterval {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Hour => "VOLUME_INTERVAL_HOUR",
+            Self::Day => "VOLUME_INTERVAL_DAY",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "VOLUME_INTERVAL_HOUR" => Some(Self::Hour),
+            "VOLUME_INTERVAL_DAY" => Some(Self::Day),
+            _ => None,
+        }
+    }
+}
 /// Generated server implementations.
 pub mod erc20_server {
     #![allow(
@@ -393,6 +895,30 @@ pub mod erc20_server {
             tonic::Response<super::GetBalancesResponse>,
             tonic::Status,
         >;
+        /// Get the balances of an account across all indexed tokens, with token metadata
+        async fn get_portfolio(
+            &self,
+            request: tonic::Request<super::GetPortfolioRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetPortfolioResponse>,
+            tonic::Status,
+        >;
+        /// Get current allowances granted by an owner
+        async fn get_allowances(
+            &self,
+            request: tonic::Request<super::GetAllowancesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetAllowancesResponse>,
+            tonic::Status,
+        >;
+        /// Get current allowances granted to a spender
+        async fn get_approvals_for_spender(
+            &self,
+            request: tonic::Request<super::GetApprovalsForSpenderRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetApprovalsForSpenderResponse>,
+            tonic::Status,
+        >;
         /// Get token metadata (name, symbol, decimals)
         async fn get_token_metadata(
             &self,
@@ -429,6 +955,65 @@ pub mod erc20_server {
             tonic::Response<Self::SubscribeApprovalsStream>,
             tonic::Status,
         >;
+        /// Server streaming response type for the SubscribeBalances method.
+        type SubscribeBalancesStream: tonic::codegen::tokio_stream::Stream<
+                Item = std::result::Result<super::BalanceUpdate, tonic::Status>,
+            >
+            + std::marker::Send
+            + 'static;
+        /// Subscribe to the balance changes of an account (requires balance tracking)
+        async fn subscribe_balances(
+            &self,
+            request: tonic::Request<super::SubscribeBalancesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<Self::SubscribeBalancesStream>,
+            tonic::Status,
+        >;
+        /// Server streaming response type for the WatchAddresses method.
+        type WatchAddressesStream: tonic::codegen::tokio_stream::Stream<
+                Item = std::result::Result<super::WatchUpdate, tonic::Status>,
+            >
+            + std::marker::Send
+            + 'static;
+        /// Watch account addresses: pushes only transfers/approvals involving them.
+        /// Send a new request on the stream to replace the watched set.
+        async fn watch_addresses(
+            &self,
+            request: tonic::Request<tonic::Streaming<super::WatchAddressesRequest>>,
+        ) -> std::result::Result<
+            tonic::Response<Self::WatchAddressesStream>,
+            tonic::Status,
+        >;
+        /// Server streaming response type for the ReplayTransfers method.
+        type ReplayTransfersStream: tonic::codegen::tokio_stream::Stream<
+                Item = std::result::Result<super::Transfer, tonic::Status>,
+            >
+            + std::marker::Send
+            + 'static;
+        /// Stream all stored transfers in a block range, in indexing order
+        async fn replay_transfers(
+            &self,
+            request: tonic::Request<super::ReplayTransfersRequest>,
+        ) -> std::result::Result<
+            tonic::Response<Self::ReplayTransfersStream>,
+            tonic::Status,
+        >;
+        /// Get circulating supply snapshots of a token, one per block with mints or burns
+        async fn get_supply_history(
+            &self,
+            request: tonic::Request<super::GetSupplyHistoryRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetSupplyHistoryResponse>,
+            tonic::Status,
+        >;
+        /// Get hourly or daily transfer rollups of a token
+        async fn get_volume_series(
+            &self,
+            request: tonic::Request<super::GetVolumeSeriesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetVolumeSeriesResponse>,
+            tonic::Status,
+        >;
         /// Get indexer statistics
         async fn get_stats(
             &self,
@@ -437,6 +1022,31 @@ pub mod erc20_server {
             tonic::Response<super::GetStatsResponse>,
             tonic::Status,
         >;
+        /// Watch accounts in the watched balance scope and backfill their balances
+        /// from the chain (admin, requires the watched balance scope)
+        async fn add_watched_accounts(
+            &self,
+            request: tonic::Request<super::AddWatchedAccountsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::AddWatchedAccountsResponse>,
+            tonic::Status,
+        >;
+        /// Stop maintaining the balances of accounts; stored balances are kept (admin)
+        async fn remove_watched_accounts(
+            &self,
+            request: tonic::Request<super::RemoveWatchedAccountsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RemoveWatchedAccountsResponse>,
+            tonic::Status,
+        >;
+        /// List the accounts of the watched balance scope
+        async fn list_watched_accounts(
+            &self,
+            request: tonic::Request<super::ListWatchedAccountsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListWatchedAccountsResponse>,
+            tonic::Status,
+        >;
     }
     /// ERC20 indexer service providing queries and subscriptions
     #[derive(Debug)]
@@ -691,12 +1301,148 @@ pub mod erc20_server {
                     };
                     Box::pin(fut)
                 }
-                "/torii.sinks.erc20.Erc20/GetTokenMetadata" => {
+                "/torii.sinks.erc20.Erc20/GetPortfolio" => {
                     #[allow(non_camel_case_types)]
-                    struct GetTokenMetadataSvc<T: Erc20>(pub Arc<T>);
+                    struct GetPortfolioSvc<T: Erc20>(pub Arc<T>);
                     impl<
                         T: Erc20,
-                    > tonic::server::UnaryService<super::GetTokenMetadataRequest>
+                    > tonic::server::UnaryService<super::GetPortfolioRequest>
+                    for GetPortfolioSvc<T> {
+                        type Response = super::GetPortfolioResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetPortfolioRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Erc20>::get_portfolio(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetPortfolioSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/torii.sinks.erc20.Erc20/GetAllowances" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetAllowancesSvc<T: Erc20>(pub Arc<T>);
+                    impl<
+                        T: Erc20,
+                    > tonic::server::UnaryService<super::GetAllowancesRequest>
+                    for GetAllowancesSvc<T> {
+                        type Response = super::GetAllowancesResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetAllowancesRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Erc20>::get_allowances(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetAllowancesSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/torii.sinks.erc20.Erc20/GetApprovalsForSpender" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetApprovalsForSpenderSvc<T: Erc20>(pub Arc<T>);
+                    impl<
+                        T: Erc20,
+                    > tonic::server::UnaryService<super::GetApprovalsForSpenderRequest>
+                    for GetApprovalsForSpenderSvc<T> {
+                        type Response = super::GetApprovalsForSpenderResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetApprovalsForSpenderRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Erc20>::get_approvals_for_spender(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetApprovalsForSpenderSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/torii.sinks.erc20.Erc20/GetTokenMetadata" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetTokenMetadataSvc<T: Erc20>(pub Arc<T>);
+                    impl<
+                        T: Erc20,
+                    > tonic::server::UnaryService<super::GetTokenMetadataRequest>
                     for GetTokenMetadataSvc<T> {
                         type Response = super::GetTokenMetadataResponse;
                         type Future = BoxFuture<
@@ -830,6 +1576,238 @@ pub mod erc20_server {
                     };
                     Box::pin(fut)
                 }
+                "/torii.sinks.erc20.Erc20/SubscribeBalances" => {
+                    #[allow(non_camel_case_types)]
+                    struct SubscribeBalancesSvc<T: Erc20>(pub Arc<T>);
+                    impl<
+                        T: Erc20,
+                    > tonic::server::ServerStreamingService<
+                        super::SubscribeBalancesRequest,
+                    > for SubscribeBalancesSvc<T> {
+                        type Response = super::BalanceUpdate;
+                        type ResponseStream = T::SubscribeBalancesStream;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::ResponseStream>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SubscribeBalancesRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Erc20>::subscribe_balances(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = SubscribeBalancesSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/torii.sinks.erc20.Erc20/WatchAddresses" => {
+                    #[allow(non_camel_case_types)]
+                    struct WatchAddressesSvc<T: Erc20>(pub Arc<T>);
+                    impl<
+                        T: Erc20,
+                    > tonic::server::StreamingService<super::WatchAddressesRequest>
+                    for WatchAddressesSvc<T> {
+                        type Response = super::WatchUpdate;
+                        type ResponseStream = T::WatchAddressesStream;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::ResponseStream>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                tonic::Streaming<super::WatchAddressesRequest>,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Erc20>::watch_addresses(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = WatchAddressesSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/torii.sinks.erc20.Erc20/ReplayTransfers" => {
+                    #[allow(non_camel_case_types)]
+                    struct ReplayTransfersSvc<T: Erc20>(pub Arc<T>);
+                    impl<
+                        T: Erc20,
+                    > tonic::server::ServerStreamingService<
+                        super::ReplayTransfersRequest,
+                    > for ReplayTransfersSvc<T> {
+                        type Response = super::Transfer;
+                        type ResponseStream = T::ReplayTransfersStream;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::ResponseStream>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ReplayTransfersRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Erc20>::replay_transfers(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ReplayTransfersSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/torii.sinks.erc20.Erc20/GetSupplyHistory" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetSupplyHistorySvc<T: Erc20>(pub Arc<T>);
+                    impl<
+                        T: Erc20,
+                    > tonic::server::UnaryService<super::GetSupplyHistoryRequest>
+                    for GetSupplyHistorySvc<T> {
+                        type Response = super::GetSupplyHistoryResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetSupplyHistoryRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Erc20>::get_supply_history(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetSupplyHistorySvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/torii.sinks.erc20.Erc20/GetVolumeSeries" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetVolumeSeriesSvc<T: Erc20>(pub Arc<T>);
+                    impl<
+                        T: Erc20,
+                    > tonic::server::UnaryService<super::GetVolumeSeriesRequest>
+                    for GetVolumeSeriesSvc<T> {
+                        type Response = super::GetVolumeSeriesResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetVolumeSeriesRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Erc20>::get_volume_series(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetVolumeSeriesSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/torii.sinks.erc20.Erc20/GetStats" => {
                     #[allow(non_camel_case_types)]
                     struct GetStatsSvc<T: Erc20>(pub Arc<T>);
@@ -873,6 +1851,135 @@ pub mod erc20_server {
                     };
                     Box::pin(fut)
                 }
+                "/torii.sinks.erc20.Erc20/AddWatchedAccounts" => {
+                    #[allow(non_camel_case_types)]
+                    struct AddWatchedAccountsSvc<T: Erc20>(pub Arc<T>);
+                    impl<T: Erc20> tonic::server::UnaryService<super::AddWatchedAccountsRequest>
+                    for AddWatchedAccountsSvc<T> {
+                        type Response = super::AddWatchedAccountsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::AddWatchedAccountsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Erc20>::add_watched_accounts(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = AddWatchedAccountsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/torii.sinks.erc20.Erc20/RemoveWatchedAccounts" => {
+                    #[allow(non_camel_case_types)]
+                    struct RemoveWatchedAccountsSvc<T: Erc20>(pub Arc<T>);
+                    impl<T: Erc20> tonic::server::UnaryService<super::RemoveWatchedAccountsRequest>
+                    for RemoveWatchedAccountsSvc<T> {
+                        type Response = super::RemoveWatchedAccountsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::RemoveWatchedAccountsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Erc20>::remove_watched_accounts(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = RemoveWatchedAccountsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/torii.sinks.erc20.Erc20/ListWatchedAccounts" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListWatchedAccountsSvc<T: Erc20>(pub Arc<T>);
+                    impl<T: Erc20> tonic::server::UnaryService<super::ListWatchedAccountsRequest>
+                    for ListWatchedAccountsSvc<T> {
+                        type Response = super::ListWatchedAccountsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListWatchedAccountsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Erc20>::list_watched_accounts(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ListWatchedAccountsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 _ => {
                     Box::pin(async move {
                         let mut response = http::Response::new(empty_body());
@@ -2,73 +2,287 @@
 //!
 //! Provides:
 //! - Historical queries with filtering and pagination (GetTransfers, GetApprovals)
+//! - Optional USD valuation of transfers and balances from recorded token prices
+//! - Balances of an account across all tokens in one query (GetPortfolio)
+//! - Current allowance queries (GetAllowances, GetApprovalsForSpender)
 //! - Real-time subscriptions with filtering (SubscribeTransfers, SubscribeApprovals)
+//! - Balance changes of an account as they are indexed (SubscribeBalances)
+//! - Address watchlists filtered server-side (WatchAddresses)
+//! - Historical replay as a server stream (ReplayTransfers)
+//! - Circulating supply history from mints and burns (GetSupplyHistory)
 //! - Indexer statistics (GetStats)
+//! - Balance watchlist management, when admin RPCs are enabled (AddWatchedAccounts,
+//!   RemoveWatchedAccounts, ListWatchedAccounts)
 
+use crate::balance_scope::BalanceWatchlist;
+use crate::price_feed::usd_value;
 use crate::proto::{
-    erc20_server::Erc20 as Erc20Trait, Approval, ApprovalFilter, ApprovalUpdate, BalanceEntry,
-    Cursor, GetApprovalsRequest, GetApprovalsResponse, GetBalanceRequest, GetBalanceResponse,
-    GetBalancesRequest, GetBalancesResponse, GetStatsRequest, GetStatsResponse,
-    GetTokenMetadataRequest, GetTokenMetadataResponse, GetTransfersRequest, GetTransfersResponse,
-    SubscribeApprovalsRequest, SubscribeTransfersRequest, TokenMetadataEntry, Transfer,
-    TransferFilter, TransferUpdate,
+    erc20_server::Erc20 as Erc20Trait, watch_update, AddWatchedAccountsRequest,
+    AddWatchedAccountsResponse, AddressLabel, Allowance, Approval, ApprovalFilter, ApprovalUpdate,
+    BalanceEntry, BalanceUpdate, Cursor, GetAllowancesRequest, GetAllowancesResponse,
+    GetApprovalsForSpenderRequest, GetApprovalsForSpenderResponse, GetApprovalsRequest,
+    GetApprovalsResponse, GetBalanceRequest, GetBalanceResponse, GetBalancesRequest,
+    GetBalancesResponse, GetPortfolioRequest, GetPortfolioResponse, GetStatsRequest,
+    GetStatsResponse, GetSupplyHistoryRequest, GetSupplyHistoryResponse, GetTokenMetadataRequest,
+    GetTokenMetadataResponse, GetTransfersRequest, GetTransfersResponse, GetVolumeSeriesRequest,
+    GetVolumeSeriesResponse, ListWatchedAccountsRequest, ListWatchedAccountsResponse,
+    PortfolioHolding, Provenance, RemoveWatchedAccountsRequest, RemoveWatchedAccountsResponse,
+    ReplayTransfersRequest, StreamShutdown, SubscribeApprovalsRequest, SubscribeBalancesRequest,
+    SubscribeTransfersRequest, SupplySnapshot, TokenMetadataEntry, Transfer, TransferFilter,
+    TransferUpdate, VolumeBucket, WatchAddressesRequest, WatchUpdate,
 };
+use crate::sharding::ShardedErc20Storage;
 use crate::storage::{
-    ApprovalCursor, ApprovalData, Erc20Storage, TransferCursor, TransferData, TransferDirection,
+    AllowanceData, ApprovalCursor, ApprovalData, StoredProvenance, TransferCursor, TransferData,
+    TransferDirection,
 };
+use crate::volume::VolumeInterval;
 use async_trait::async_trait;
-use futures::stream::Stream;
+use futures::future::Either;
+use futures::stream::{Stream, StreamExt};
 use starknet::core::types::Felt;
+use std::collections::{HashMap, HashSet};
 use std::pin::Pin;
-use std::sync::Arc;
 use tokio::sync::broadcast;
-use tonic::{Request, Response, Status};
-use torii_common::{bytes_to_felt, u256_to_bytes};
+use tonic::{Request, Response, Status, Streaming};
+use torii_common::{
+    bytes_to_felt, replay, u256_to_bytes, AddressLabels, AddressWatchlist, FieldMask, ReplayRange,
+};
+
+/// Updates buffered per address watcher before new ones are dropped
+const WATCH_CHANNEL_CAPACITY: usize = 1000;
+/// Maximum number of addresses in a single watchlist
+const MAX_WATCHED_ADDRESSES: usize = 10_000;
+/// Fields of `Transfer` selectable with a `field_mask`
+const TRANSFER_FIELDS: &[&str] = &[
+    "token",
+    "from",
+    "to",
+    "amount",
+    "block_number",
+    "tx_hash",
+    "timestamp",
+];
 
 /// gRPC service implementation for ERC20
 #[derive(Clone)]
 pub struct Erc20Service {
-    storage: Arc<Erc20Storage>,
+    storage: ShardedErc20Storage,
     /// Broadcast channel for real-time transfer updates
     pub transfer_tx: broadcast::Sender<TransferUpdate>,
     /// Broadcast channel for real-time approval updates
     pub approval_tx: broadcast::Sender<ApprovalUpdate>,
+    /// Broadcast channel for real-time balance updates
+    pub balance_tx: broadcast::Sender<BalanceUpdate>,
+    /// Address watchers (WatchAddresses), indexed by watched address
+    watchlist: AddressWatchlist<WatchUpdate>,
+    /// Balances are not maintained (index-only mode)
+    index_only: bool,
+    /// Address labels attached to responses on request (`include_labels`)
+    labels: Option<AddressLabels>,
+    /// Accounts of the watched balance scope, managed through the admin RPCs
+    balance_watchlist: Option<BalanceWatchlist>,
+    /// Whether the admin RPCs are enabled
+    admin_rpc: bool,
 }
 
 impl Erc20Service {
     /// Creates a new Erc20Service
-    pub fn new(storage: Arc<Erc20Storage>) -> Self {
+    ///
+    /// Takes a single storage or a [`ShardedErc20Storage`].
+    pub fn new(storage: impl Into<ShardedErc20Storage>) -> Self {
         // Create broadcast channels with capacity for 1000 pending updates
         let (transfer_tx, _) = broadcast::channel(1000);
         let (approval_tx, _) = broadcast::channel(1000);
+        let (balance_tx, _) = broadcast::channel(1000);
 
         Self {
-            storage,
+            storage: storage.into(),
             transfer_tx,
             approval_tx,
+            balance_tx,
+            watchlist: AddressWatchlist::new(),
+            index_only: false,
+            labels: None,
+            balance_watchlist: None,
+            admin_rpc: false,
+        }
+    }
+
+    /// Marks the service as index-only: balance queries are rejected since the
+    /// sink does not maintain balances.
+    pub fn with_index_only(mut self, index_only: bool) -> Self {
+        self.index_only = index_only;
+        self
+    }
+
+    /// Attaches labels from `labels` to the responses requesting them
+    #[must_use]
+    pub fn with_labels(mut self, labels: AddressLabels) -> Self {
+        self.labels = Some(labels);
+        self
+    }
+
+    /// Manages `watchlist` through the watched account RPCs
+    #[must_use]
+    pub fn with_balance_watchlist(mut self, watchlist: BalanceWatchlist) -> Self {
+        self.balance_watchlist = Some(watchlist);
+        self
+    }
+
+    /// Enables the admin RPCs (watched account management); they are rejected otherwise.
+    #[must_use]
+    pub fn with_admin_rpc(mut self, enabled: bool) -> Self {
+        self.admin_rpc = enabled;
+        self
+    }
+
+    fn balance_watchlist(&self) -> Result<&BalanceWatchlist, Status> {
+        if !self.admin_rpc {
+            return Err(Status::permission_denied("Admin RPCs are disabled"));
+        }
+        self.balance_watchlist.as_ref().ok_or_else(|| {
+            Status::failed_precondition("Balance tracking is not scoped to watched accounts")
+        })
+    }
+
+    /// Labels of the labelled `addresses`, when requested and a label store is configured
+    fn labels_for(
+        &self,
+        include_labels: bool,
+        addresses: impl IntoIterator<Item = Felt>,
+    ) -> Vec<AddressLabel> {
+        match &self.labels {
+            Some(labels) if include_labels => labels
+                .lookup(addresses)
+                .into_iter()
+                .map(|label| AddressLabel {
+                    address: label.address.to_bytes_be().to_vec(),
+                    name: label.name,
+                    tags: label.tags,
+                })
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn ensure_balances_tracked(&self) -> Result<(), Status> {
+        if self.index_only {
+            return Err(Status::failed_precondition(
+                "Balance tracking disabled (index-only mode)",
+            ));
         }
+        Ok(())
     }
 
-    /// Broadcasts a transfer to all subscribers
+    /// Broadcasts a transfer to all subscribers and to the watchers of its addresses
     pub fn broadcast_transfer(&self, transfer: Transfer) {
+        let timestamp = chrono::Utc::now().timestamp();
+        if !self.watchlist.is_empty() {
+            let addresses = watched_addresses(&[&transfer.from[..], &transfer.to[..]]);
+            self.watchlist.notify(
+                &addresses,
+                &WatchUpdate {
+                    event: Some(watch_update::Event::Transfer(transfer.clone())),
+                    timestamp,
+                },
+            );
+        }
         let update = TransferUpdate {
             transfer: Some(transfer),
-            timestamp: chrono::Utc::now().timestamp(),
+            timestamp,
+            shutdown: None,
         };
         // Send to all subscribers (ignore if no receivers)
         let _ = self.transfer_tx.send(update);
     }
 
-    /// Broadcasts an approval to all subscribers
+    /// Broadcasts an approval to all subscribers and to the watchers of its addresses
     pub fn broadcast_approval(&self, approval: Approval) {
+        let timestamp = chrono::Utc::now().timestamp();
+        if !self.watchlist.is_empty() {
+            let addresses = watched_addresses(&[&approval.owner[..], &approval.spender[..]]);
+            self.watchlist.notify(
+                &addresses,
+                &WatchUpdate {
+                    event: Some(watch_update::Event::Approval(approval.clone())),
+                    timestamp,
+                },
+            );
+        }
         let update = ApprovalUpdate {
             approval: Some(approval),
-            timestamp: chrono::Utc::now().timestamp(),
+            timestamp,
+            shutdown: None,
         };
         // Send to all subscribers (ignore if no receivers)
         let _ = self.approval_tx.send(update);
     }
 
+    /// Whether a client subscribed to balance updates (the sink skips reading the new
+    /// balances otherwise)
+    pub fn has_balance_subscribers(&self) -> bool {
+        self.balance_tx.receiver_count() > 0
+    }
+
+    /// Broadcasts a balance change to the subscribers of its account
+    pub fn broadcast_balance(&self, update: BalanceUpdate) {
+        // Send to all subscribers (ignore if no receivers)
+        let _ = self.balance_tx.send(update);
+    }
+
+    /// Ends every subscription and address watch stream with a shutdown update.
+    ///
+    /// `block_number` is the last fully indexed block, from which clients resume.
+    pub fn notify_shutdown(&self, block_number: u64) {
+        let timestamp = chrono::Utc::now().timestamp();
+        let shutdown = StreamShutdown { block_number };
+        self.watchlist.notify_all(&WatchUpdate {
+            event: Some(watch_update::Event::Shutdown(shutdown)),
+            timestamp,
+        });
+        let _ = self.transfer_tx.send(TransferUpdate {
+            transfer: None,
+            timestamp,
+            shutdown: Some(shutdown),
+        });
+        let _ = self.approval_tx.send(ApprovalUpdate {
+            approval: None,
+            timestamp,
+            shutdown: Some(shutdown),
+        });
+        let _ = self.balance_tx.send(BalanceUpdate {
+            timestamp,
+            shutdown: Some(shutdown),
+            ..BalanceUpdate::default()
+        });
+    }
+
+    /// Clear the fields of a transfer left out of `mask`
+    fn mask_transfer(transfer: &mut Transfer, mask: &FieldMask) {
+        if !mask.includes("token") {
+            transfer.token.clear();
+        }
+        if !mask.includes("from") {
+            transfer.from.clear();
+        }
+        if !mask.includes("to") {
+            transfer.to.clear();
+        }
+        if !mask.includes("amount") {
+            transfer.amount.clear();
+        }
+        if !mask.includes("block_number") {
+            transfer.block_number = 0;
+        }
+        if !mask.includes("tx_hash") {
+            transfer.tx_hash.clear();
+        }
+        if !mask.includes("timestamp") {
+            transfer.timestamp = 0;
+        }
+    }
+
     /// Convert storage TransferData to proto Transfer
     fn transfer_data_to_proto(data: &TransferData) -> Transfer {
         Transfer {
@@ -79,7 +293,39 @@ impl Erc20Service {
             block_number: data.block_number,
             tx_hash: data.tx_hash.to_bytes_be().to_vec(),
             timestamp: data.timestamp.unwrap_or(0),
+            provenance: None,
+            amount_usd: None,
+        }
+    }
+
+    /// Recorded USD prices of (token, block) pairs and decimals of their tokens.
+    ///
+    /// Use `u64::MAX` as the block for the latest price.
+    async fn usd_pricing(
+        &self,
+        lookups: &[(Felt, u64)],
+    ) -> Result<(HashMap<(Felt, u64), f64>, HashMap<Felt, u8>), Status> {
+        let prices = self
+            .storage
+            .get_token_prices_at(lookups)
+            .await
+            .map_err(|e| Status::internal(format!("Price query failed: {e}")))?;
+        let priced_tokens = prices
+            .keys()
+            .map(|(token, _)| *token)
+            .collect::<HashSet<_>>();
+        let mut decimals = HashMap::with_capacity(priced_tokens.len());
+        for token in priced_tokens {
+            let metadata = self
+                .storage
+                .get_token_metadata(token)
+                .await
+                .map_err(|e| Status::internal(format!("Metadata query failed: {e}")))?;
+            if let Some((_, _, Some(token_decimals), _)) = metadata {
+                decimals.insert(token, token_decimals);
+            }
         }
+        Ok((prices, decimals))
     }
 
     /// Convert storage ApprovalData to proto Approval
@@ -92,6 +338,73 @@ impl Erc20Service {
             block_number: data.block_number,
             tx_hash: data.tx_hash.to_bytes_be().to_vec(),
             timestamp: data.timestamp.unwrap_or(0),
+            provenance: None,
+        }
+    }
+
+    /// Convert storage AllowanceData to proto Allowance
+    fn allowance_data_to_proto(data: &AllowanceData) -> Allowance {
+        Allowance {
+            token: data.token.to_bytes_be().to_vec(),
+            owner: data.owner.to_bytes_be().to_vec(),
+            spender: data.spender.to_bytes_be().to_vec(),
+            amount: u256_to_bytes(data.amount),
+            block_number: data.block_number,
+            tx_hash: data.tx_hash.to_bytes_be().to_vec(),
+            timestamp: data.timestamp.unwrap_or(0),
+        }
+    }
+
+    /// Query current allowances by owner or spender, shared by the allowance RPCs
+    async fn query_allowances(
+        &self,
+        owner: Option<Felt>,
+        spender: Option<Felt>,
+        token: Option<&Vec<u8>>,
+        cursor: Option<i64>,
+        limit: u32,
+    ) -> Result<(Vec<Allowance>, Option<i64>), Status> {
+        let token = token
+            .map(|b| {
+                bytes_to_felt(b).ok_or_else(|| Status::invalid_argument("Invalid token address"))
+            })
+            .transpose()?;
+        let limit = if limit == 0 { 1000 } else { limit.min(10_000) };
+
+        tracing::debug!(
+            target: "torii_erc20::grpc",
+            "Allowances: owner={:?}, spender={:?}, token={:?}, cursor={:?}, limit={}",
+            owner.map(|o| format!("{o:#x}")),
+            spender.map(|s| format!("{s:#x}")),
+            token.map(|t| format!("{t:#x}")),
+            cursor,
+            limit
+        );
+
+        let (allowances, next_cursor) = self
+            .storage
+            .get_allowances_filtered(owner, spender, token, cursor, limit)
+            .await
+            .map_err(|e| Status::internal(format!("Query failed: {e}")))?;
+
+        Ok((
+            allowances
+                .iter()
+                .map(Self::allowance_data_to_proto)
+                .collect(),
+            next_cursor,
+        ))
+    }
+
+    /// Convert stored provenance to proto Provenance
+    fn provenance_to_proto(stored: &StoredProvenance) -> Provenance {
+        Provenance {
+            extractor: stored.provenance.extractor.clone(),
+            decoder: stored.provenance.decoder.clone(),
+            batch_id: stored.provenance.batch_id,
+            extracted_at: stored.provenance.extracted_at,
+            decoded_at: stored.provenance.decoded_at,
+            stored_at: stored.stored_at,
         }
     }
 
@@ -211,6 +524,17 @@ impl Erc20Trait for Erc20Service {
     ) -> Result<Response<GetTransfersResponse>, Status> {
         let req = request.into_inner();
         let filter = req.filter.unwrap_or_default();
+        let mask = FieldMask::parse(&req.field_mask, TRANSFER_FIELDS).map_err(|field| {
+            Status::invalid_argument(format!("unknown field_mask field: {field}"))
+        })?;
+        // USD valuation and labels need the token and addresses of each transfer.
+        let mut storage_mask = mask.clone();
+        if req.include_usd {
+            storage_mask = storage_mask.with_fields(&["token", "amount"]);
+        }
+        if req.include_labels {
+            storage_mask = storage_mask.with_fields(&["token", "from", "to"]);
+        }
 
         // Parse filter fields
         let wallet = filter.wallet.as_ref().and_then(|b| bytes_to_felt(b));
@@ -254,7 +578,7 @@ impl Erc20Trait for Erc20Service {
         // Execute query
         let (transfers, next_cursor) = self
             .storage
-            .get_transfers_filtered(
+            .get_transfers_projected(
                 wallet,
                 from,
                 to,
@@ -264,12 +588,48 @@ impl Erc20Trait for Erc20Service {
                 filter.block_to,
                 cursor,
                 limit,
+                &storage_mask,
             )
             .await
             .map_err(|e| Status::internal(format!("Query failed: {e}")))?;
 
-        let proto_transfers: Vec<Transfer> =
-            transfers.iter().map(Self::transfer_data_to_proto).collect();
+        let mut proto_transfers: Vec<Transfer> = transfers
+            .iter()
+            .map(|data| {
+                let mut transfer = Self::transfer_data_to_proto(data);
+                Self::mask_transfer(&mut transfer, &mask);
+                transfer
+            })
+            .collect();
+
+        if req.include_provenance {
+            let ids: Vec<i64> = transfers.iter().filter_map(|t| t.id).collect();
+            let provenance = self
+                .storage
+                .get_transfer_provenance(&ids)
+                .await
+                .map_err(|e| Status::internal(format!("Provenance query failed: {e}")))?;
+            for (proto, data) in proto_transfers.iter_mut().zip(&transfers) {
+                proto.provenance = data
+                    .id
+                    .and_then(|id| provenance.get(&id))
+                    .map(Self::provenance_to_proto);
+            }
+        }
+
+        if req.include_usd {
+            let lookups = transfers
+                .iter()
+                .map(|t| (t.token, t.block_number))
+                .collect::<Vec<_>>();
+            let (prices, decimals) = self.usd_pricing(&lookups).await?;
+            for (proto, data) in proto_transfers.iter_mut().zip(&transfers) {
+                proto.amount_usd = prices
+                    .get(&(data.token, data.block_number))
+                    .zip(decimals.get(&data.token))
+                    .map(|(price, decimals)| usd_value(data.amount, *decimals, *price));
+            }
+        }
 
         let proto_cursor = next_cursor.map(|c| Cursor {
             block_number: c.block_number,
@@ -283,9 +643,15 @@ impl Erc20Trait for Erc20Service {
             proto_cursor.is_some()
         );
 
+        let labels = self.labels_for(
+            req.include_labels,
+            transfers.iter().flat_map(|t| [t.token, t.from, t.to]),
+        );
+
         Ok(Response::new(GetTransfersResponse {
             transfers: proto_transfers,
             next_cursor: proto_cursor,
+            labels,
         }))
     }
 
@@ -346,9 +712,24 @@ impl Erc20Trait for Erc20Service {
             .await
             .map_err(|e| Status::internal(format!("Query failed: {e}")))?;
 
-        let proto_approvals: Vec<Approval> =
+        let mut proto_approvals: Vec<Approval> =
             approvals.iter().map(Self::approval_data_to_proto).collect();
 
+        if req.include_provenance {
+            let ids: Vec<i64> = approvals.iter().filter_map(|a| a.id).collect();
+            let provenance = self
+                .storage
+                .get_approval_provenance(&ids)
+                .await
+                .map_err(|e| Status::internal(format!("Provenance query failed: {e}")))?;
+            for (proto, data) in proto_approvals.iter_mut().zip(&approvals) {
+                proto.provenance = data
+                    .id
+                    .and_then(|id| provenance.get(&id))
+                    .map(Self::provenance_to_proto);
+            }
+        }
+
         let proto_cursor = next_cursor.map(|c| Cursor {
             block_number: c.block_number,
             id: c.id,
@@ -373,6 +754,7 @@ impl Erc20Trait for Erc20Service {
         request: Request<GetBalanceRequest>,
     ) -> Result<Response<GetBalanceResponse>, Status> {
         let req = request.into_inner();
+        self.ensure_balances_tracked()?;
 
         let token = bytes_to_felt(&req.token)
             .ok_or_else(|| Status::invalid_argument("Invalid token address"))?;
@@ -393,9 +775,22 @@ impl Erc20Trait for Erc20Service {
             .map_err(|e| Status::internal(format!("Query failed: {e}")))?
             .unwrap_or((starknet::core::types::U256::from(0u64), 0));
 
+        let (price_usd, balance_usd) = if req.include_usd {
+            let (prices, decimals) = self.usd_pricing(&[(token, u64::MAX)]).await?;
+            let price = prices.get(&(token, u64::MAX)).copied();
+            let value = price
+                .zip(decimals.get(&token))
+                .map(|(price, decimals)| usd_value(balance, *decimals, price));
+            (price, value)
+        } else {
+            (None, None)
+        };
+
         Ok(Response::new(GetBalanceResponse {
             balance: u256_to_bytes(balance),
             last_block,
+            price_usd,
+            balance_usd,
         }))
     }
 
@@ -405,6 +800,7 @@ impl Erc20Trait for Erc20Service {
         request: Request<GetBalancesRequest>,
     ) -> Result<Response<GetBalancesResponse>, Status> {
         let req = request.into_inner();
+        self.ensure_balances_tracked()?;
 
         let token = req.token.as_ref().and_then(|b| bytes_to_felt(b));
         let wallet = req.wallet.as_ref().and_then(|b| bytes_to_felt(b));
@@ -430,19 +826,140 @@ impl Erc20Trait for Erc20Service {
             .await
             .map_err(|e| Status::internal(format!("Query failed: {e}")))?;
 
+        let (prices, decimals) = if req.include_usd {
+            let lookups = balances
+                .iter()
+                .map(|b| (b.token, u64::MAX))
+                .collect::<Vec<_>>();
+            self.usd_pricing(&lookups).await?
+        } else {
+            (HashMap::new(), HashMap::new())
+        };
+        let labels = self.labels_for(
+            req.include_labels,
+            balances.iter().flat_map(|b| [b.token, b.wallet]),
+        );
+
         let rows = balances
             .into_iter()
-            .map(|b| BalanceEntry {
-                token: b.token.to_bytes_be().to_vec(),
-                wallet: b.wallet.to_bytes_be().to_vec(),
-                balance: u256_to_bytes(b.balance),
-                last_block: b.last_block,
+            .map(|b| {
+                let price_usd = prices.get(&(b.token, u64::MAX)).copied();
+                let balance_usd = price_usd
+                    .zip(decimals.get(&b.token))
+                    .map(|(price, decimals)| usd_value(b.balance, *decimals, price));
+                BalanceEntry {
+                    token: b.token.to_bytes_be().to_vec(),
+                    wallet: b.wallet.to_bytes_be().to_vec(),
+                    balance: u256_to_bytes(b.balance),
+                    last_block: b.last_block,
+                    price_usd,
+                    balance_usd,
+                }
             })
             .collect();
 
         Ok(Response::new(GetBalancesResponse {
             balances: rows,
             next_cursor,
+            labels,
+        }))
+    }
+
+    /// Get the balances of an account across all indexed tokens
+    async fn get_portfolio(
+        &self,
+        request: Request<GetPortfolioRequest>,
+    ) -> Result<Response<GetPortfolioResponse>, Status> {
+        let req = request.into_inner();
+        self.ensure_balances_tracked()?;
+
+        let account = bytes_to_felt(&req.account)
+            .ok_or_else(|| Status::invalid_argument("Invalid account address"))?;
+
+        let entries = self
+            .storage
+            .get_portfolio(account)
+            .await
+            .map_err(|e| Status::internal(format!("Query failed: {e}")))?;
+
+        tracing::debug!(
+            target: "torii_erc20::grpc",
+            "GetPortfolio: account={:#x}, holdings={}",
+            account,
+            entries.len()
+        );
+
+        let mut total_usd = req.include_usd.then_some(0.0);
+        let holdings = entries
+            .into_iter()
+            .map(|entry| {
+                let price_usd = entry.price_usd.filter(|_| req.include_usd);
+                let balance_usd = price_usd
+                    .zip(entry.decimals)
+                    .map(|(price, decimals)| usd_value(entry.balance, decimals, price));
+                if let (Some(total), Some(value)) = (total_usd.as_mut(), balance_usd) {
+                    *total += value;
+                }
+                PortfolioHolding {
+                    token: entry.token.to_bytes_be().to_vec(),
+                    balance: u256_to_bytes(entry.balance),
+                    last_block: entry.last_block,
+                    name: entry.name,
+                    symbol: entry.symbol,
+                    decimals: entry.decimals.map(u32::from),
+                    price_usd,
+                    balance_usd,
+                }
+            })
+            .collect();
+
+        Ok(Response::new(GetPortfolioResponse {
+            holdings,
+            total_usd,
+        }))
+    }
+
+    /// Get current allowances granted by an owner
+    async fn get_allowances(
+        &self,
+        request: Request<GetAllowancesRequest>,
+    ) -> Result<Response<GetAllowancesResponse>, Status> {
+        let req = request.into_inner();
+        let owner = bytes_to_felt(&req.owner)
+            .ok_or_else(|| Status::invalid_argument("Invalid owner address"))?;
+
+        let (allowances, next_cursor) = self
+            .query_allowances(Some(owner), None, req.token.as_ref(), req.cursor, req.limit)
+            .await?;
+
+        Ok(Response::new(GetAllowancesResponse {
+            allowances,
+            next_cursor,
+        }))
+    }
+
+    /// Get current allowances granted to a spender
+    async fn get_approvals_for_spender(
+        &self,
+        request: Request<GetApprovalsForSpenderRequest>,
+    ) -> Result<Response<GetApprovalsForSpenderResponse>, Status> {
+        let req = request.into_inner();
+        let spender = bytes_to_felt(&req.spender)
+            .ok_or_else(|| Status::invalid_argument("Invalid spender address"))?;
+
+        let (allowances, next_cursor) = self
+            .query_allowances(
+                None,
+                Some(spender),
+                req.token.as_ref(),
+                req.cursor,
+                req.limit,
+            )
+            .await?;
+
+        Ok(Response::new(GetApprovalsForSpenderResponse {
+            allowances,
+            next_cursor,
         }))
     }
 
@@ -549,7 +1066,11 @@ impl Erc20Trait for Erc20Service {
                             req.client_id
                         );
 
+                        let shutdown = update.shutdown.is_some();
                         yield update;
+                        if shutdown {
+                            break;
+                        }
                     }
                     Err(broadcast::error::RecvError::Lagged(skipped)) => {
                         tracing::warn!(
@@ -623,7 +1144,11 @@ impl Erc20Trait for Erc20Service {
                             req.client_id
                         );
 
+                        let shutdown = update.shutdown.is_some();
                         yield update;
+                        if shutdown {
+                            break;
+                        }
                     }
                     Err(broadcast::error::RecvError::Lagged(skipped)) => {
                         tracing::warn!(
@@ -655,6 +1180,279 @@ impl Erc20Trait for Erc20Service {
         Ok(Response::new(Box::pin(stream)))
     }
 
+    /// Subscribe to the balance changes of an account, optionally restricted to tokens
+    type SubscribeBalancesStream =
+        Pin<Box<dyn Stream<Item = Result<BalanceUpdate, Status>> + Send>>;
+
+    async fn subscribe_balances(
+        &self,
+        request: Request<SubscribeBalancesRequest>,
+    ) -> Result<Response<Self::SubscribeBalancesStream>, Status> {
+        let req = request.into_inner();
+        self.ensure_balances_tracked()?;
+
+        let account = bytes_to_felt(&req.account)
+            .ok_or_else(|| Status::invalid_argument("Invalid account address"))?;
+        let tokens = req
+            .tokens
+            .iter()
+            .map(|token| {
+                bytes_to_felt(token)
+                    .ok_or_else(|| Status::invalid_argument("Invalid token address"))
+            })
+            .collect::<Result<HashSet<_>, _>>()?;
+
+        tracing::info!(
+            target: "torii_erc20::grpc",
+            "New balance subscription from client {}: account={:#x}, tokens={}",
+            req.client_id,
+            account,
+            tokens.len()
+        );
+
+        let mut rx = self.balance_tx.subscribe();
+
+        let stream = async_stream::try_stream! {
+            loop {
+                match rx.recv().await {
+                    Ok(update) => {
+                        let shutdown = update.shutdown.is_some();
+                        if !shutdown && !matches_balance_subscription(&update, account, &tokens) {
+                            continue;
+                        }
+                        yield update;
+                        if shutdown {
+                            break;
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!(
+                            target: "torii_erc20::grpc",
+                            "Client {} lagged, skipped {} balance updates",
+                            req.client_id,
+                            skipped
+                        );
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+
+            tracing::info!(
+                target: "torii_erc20::grpc",
+                "Balance subscription stream ended for client: {}",
+                req.client_id
+            );
+        };
+
+        Ok(Response::new(Box::pin(stream)))
+    }
+
+    /// Watch account addresses (server-side filtered transfers and approvals)
+    type WatchAddressesStream = Pin<Box<dyn Stream<Item = Result<WatchUpdate, Status>> + Send>>;
+
+    async fn watch_addresses(
+        &self,
+        request: Request<Streaming<WatchAddressesRequest>>,
+    ) -> Result<Response<Self::WatchAddressesStream>, Status> {
+        let mut requests = request.into_inner();
+        let first = requests
+            .message()
+            .await?
+            .ok_or_else(|| Status::invalid_argument("Expected an initial watch request"))?;
+        let client_id = first.client_id;
+        let mut watcher = self.watchlist.watch(
+            parse_watched_addresses(&first.addresses)?,
+            WATCH_CHANNEL_CAPACITY,
+        );
+
+        tracing::info!(
+            target: "torii_erc20::grpc",
+            "New address watch from client {}: {} addresses",
+            client_id,
+            first.addresses.len()
+        );
+
+        let stream = async_stream::try_stream! {
+            let mut requests_open = true;
+            loop {
+                let event = tokio::select! {
+                    update = watcher.recv() => Either::Left(update),
+                    request = requests.message(), if requests_open => Either::Right(request),
+                };
+                match event {
+                    Either::Left(Some(update)) => {
+                        let shutdown = matches!(update.event, Some(watch_update::Event::Shutdown(_)));
+                        yield update;
+                        if shutdown {
+                            break;
+                        }
+                    }
+                    Either::Left(None) => break,
+                    Either::Right(request) => match request? {
+                        Some(request) => {
+                            watcher.set_addresses(parse_watched_addresses(&request.addresses)?);
+                            tracing::debug!(
+                                target: "torii_erc20::grpc",
+                                "Client {} now watches {} addresses",
+                                client_id,
+                                request.addresses.len()
+                            );
+                        }
+                        // The client stopped updating its watchlist; keep streaming.
+                        None => requests_open = false,
+                    },
+                }
+            }
+
+            tracing::info!(
+                target: "torii_erc20::grpc",
+                "Address watch ended for client: {}",
+                client_id
+            );
+        };
+
+        Ok(Response::new(Box::pin(stream)))
+    }
+
+    /// Stream stored transfers in a block range (historical replay)
+    type ReplayTransfersStream = Pin<Box<dyn Stream<Item = Result<Transfer, Status>> + Send>>;
+
+    /// Pages through storage lazily: the next page is only read once the previous one has
+    /// been sent, so a slow client throttles the query through tonic's flow control.
+    async fn replay_transfers(
+        &self,
+        request: Request<ReplayTransfersRequest>,
+    ) -> Result<Response<Self::ReplayTransfersStream>, Status> {
+        let req = request.into_inner();
+        let tokens: Vec<Felt> = req.tokens.iter().filter_map(|b| bytes_to_felt(b)).collect();
+        let range = ReplayRange::new(tokens, req.block_from, req.block_to, req.page_size)
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+
+        tracing::info!(
+            target: "torii_erc20::grpc",
+            "ReplayTransfers: blocks={:?}..={:?}, tokens={}, page_size={}",
+            range.block_from,
+            range.block_to,
+            range.tokens.len(),
+            range.page_size
+        );
+
+        let stream = replay(self.storage.clone(), range).map(|transfer| {
+            transfer
+                .map(|transfer| Self::transfer_data_to_proto(&transfer))
+                .map_err(|e| Status::internal(format!("Replay query failed: {e}")))
+        });
+
+        Ok(Response::new(Box::pin(stream)))
+    }
+
+    /// Get circulating supply snapshots of a token
+    async fn get_supply_history(
+        &self,
+        request: Request<GetSupplyHistoryRequest>,
+    ) -> Result<Response<GetSupplyHistoryResponse>, Status> {
+        let req = request.into_inner();
+        let token = bytes_to_felt(&req.token)
+            .ok_or_else(|| Status::invalid_argument("Invalid token address"))?;
+        let limit = if req.limit == 0 {
+            1000
+        } else {
+            req.limit.min(10_000)
+        };
+
+        tracing::debug!(
+            target: "torii_erc20::grpc",
+            "GetSupplyHistory: token={:#x}, from_block={:?}, to_block={:?}, cursor={:?}, limit={}",
+            token,
+            req.from_block,
+            req.to_block,
+            req.cursor,
+            limit
+        );
+
+        let (snapshots, next_cursor) = self
+            .storage
+            .get_supply_history(token, req.from_block, req.to_block, req.cursor, limit)
+            .await
+            .map_err(|e| Status::internal(format!("Query failed: {e}")))?;
+
+        Ok(Response::new(GetSupplyHistoryResponse {
+            snapshots: snapshots
+                .iter()
+                .map(|snapshot| SupplySnapshot {
+                    token: snapshot.token.to_bytes_be().to_vec(),
+                    block_number: snapshot.block_number,
+                    minted: u256_to_bytes(snapshot.minted),
+                    burned: u256_to_bytes(snapshot.burned),
+                    total_minted: u256_to_bytes(snapshot.total_minted),
+                    total_burned: u256_to_bytes(snapshot.total_burned),
+                    circulating_supply: u256_to_bytes(snapshot.circulating()),
+                })
+                .collect(),
+            next_cursor,
+        }))
+    }
+
+    /// Get hourly or daily transfer rollups of a token
+    async fn get_volume_series(
+        &self,
+        request: Request<GetVolumeSeriesRequest>,
+    ) -> Result<Response<GetVolumeSeriesResponse>, Status> {
+        let req = request.into_inner();
+        let token = bytes_to_felt(&req.token)
+            .ok_or_else(|| Status::invalid_argument("Invalid token address"))?;
+        let interval = match crate::proto::VolumeInterval::try_from(req.interval) {
+            Ok(crate::proto::VolumeInterval::Hour) => VolumeInterval::Hour,
+            Ok(crate::proto::VolumeInterval::Day) => VolumeInterval::Day,
+            Err(_) => return Err(Status::invalid_argument("Invalid volume interval")),
+        };
+        let limit = if req.limit == 0 {
+            1000
+        } else {
+            req.limit.min(10_000)
+        };
+
+        tracing::debug!(
+            target: "torii_erc20::grpc",
+            "GetVolumeSeries: token={:#x}, interval={}, from={:?}, to={:?}, cursor={:?}, limit={}",
+            token,
+            interval.as_str(),
+            req.from_timestamp,
+            req.to_timestamp,
+            req.cursor,
+            limit
+        );
+
+        let (buckets, next_cursor) = self
+            .storage
+            .get_volume_series(
+                token,
+                interval,
+                req.from_timestamp,
+                req.to_timestamp,
+                req.cursor,
+                limit,
+            )
+            .await
+            .map_err(|e| Status::internal(format!("Query failed: {e}")))?;
+
+        Ok(Response::new(GetVolumeSeriesResponse {
+            buckets: buckets
+                .iter()
+                .map(|bucket| VolumeBucket {
+                    token: bucket.token.to_bytes_be().to_vec(),
+                    bucket_start: bucket.bucket_start,
+                    transfer_count: bucket.transfer_count,
+                    volume: u256_to_bytes(bucket.volume),
+                    unique_senders: bucket.unique_senders,
+                    unique_receivers: bucket.unique_receivers,
+                })
+                .collect(),
+            next_cursor,
+        }))
+    }
+
     /// Get indexer statistics
     async fn get_stats(
         &self,
@@ -701,4 +1499,186 @@ impl Erc20Trait for Erc20Service {
             latest_block,
         }))
     }
+
+    /// Watch accounts and backfill their balances
+    async fn add_watched_accounts(
+        &self,
+        request: Request<AddWatchedAccountsRequest>,
+    ) -> Result<Response<AddWatchedAccountsResponse>, Status> {
+        let watchlist = self.balance_watchlist()?;
+        let accounts = parse_watched_addresses(&request.into_inner().accounts)?;
+        let addition = watchlist
+            .add(&accounts)
+            .await
+            .map_err(|e| Status::internal(format!("Failed to add watched accounts: {e}")))?;
+        Ok(Response::new(AddWatchedAccountsResponse {
+            added: addition.added as u32,
+            backfilled: addition.backfilled as u32,
+            block_number: addition.block_number,
+        }))
+    }
+
+    /// Stop watching accounts
+    async fn remove_watched_accounts(
+        &self,
+        request: Request<RemoveWatchedAccountsRequest>,
+    ) -> Result<Response<RemoveWatchedAccountsResponse>, Status> {
+        let watchlist = self.balance_watchlist()?;
+        let accounts = parse_watched_addresses(&request.into_inner().accounts)?;
+        let removed = watchlist
+            .remove(&accounts)
+            .await
+            .map_err(|e| Status::internal(format!("Failed to remove watched accounts: {e}")))?;
+        Ok(Response::new(RemoveWatchedAccountsResponse {
+            removed: removed as u32,
+        }))
+    }
+
+    /// List watched accounts
+    async fn list_watched_accounts(
+        &self,
+        _request: Request<ListWatchedAccountsRequest>,
+    ) -> Result<Response<ListWatchedAccountsResponse>, Status> {
+        let watchlist = self.balance_watchlist()?;
+        Ok(Response::new(ListWatchedAccountsResponse {
+            accounts: watchlist
+                .accounts()
+                .iter()
+                .map(|account| account.to_bytes_be().to_vec())
+                .collect(),
+        }))
+    }
+}
+
+/// Whether a balance update is for `account` and one of `tokens` (any token when empty).
+fn matches_balance_subscription(
+    update: &BalanceUpdate,
+    account: Felt,
+    tokens: &HashSet<Felt>,
+) -> bool {
+    bytes_to_felt(&update.account) == Some(account)
+        && (tokens.is_empty()
+            || bytes_to_felt(&update.token).is_some_and(|token| tokens.contains(&token)))
+}
+
+/// Parses the addresses of a watch request.
+fn parse_watched_addresses(addresses: &[Vec<u8>]) -> Result<Vec<Felt>, Status> {
+    if addresses.len() > MAX_WATCHED_ADDRESSES {
+        return Err(Status::invalid_argument(format!(
+            "Too many addresses to watch (max {MAX_WATCHED_ADDRESSES})"
+        )));
+    }
+    addresses
+        .iter()
+        .map(|address| {
+            bytes_to_felt(address).ok_or_else(|| Status::invalid_argument("Invalid address"))
+        })
+        .collect()
+}
+
+/// Addresses an update is routed by (malformed addresses are skipped).
+fn watched_addresses(addresses: &[&[u8]]) -> Vec<Felt> {
+    addresses
+        .iter()
+        .filter_map(|address| bytes_to_felt(address))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::balance_fetcher::BalanceFetcher;
+    use crate::storage::Erc20Storage;
+    use starknet::providers::Url;
+    use std::sync::Arc;
+
+    async fn service(admin_rpc: bool) -> (Erc20Service, BalanceWatchlist) {
+        let storage = Arc::new(Erc20Storage::new(":memory:").await.unwrap());
+        // Nothing is indexed, so adding accounts never reaches the RPC.
+        let provider =
+            torii_common::rate_limited_provider(Url::parse("http://127.0.0.1:1").unwrap(), 0, 0);
+        let watchlist = BalanceWatchlist::load(
+            storage.clone(),
+            Arc::new(BalanceFetcher::new(provider.into())),
+        )
+        .await
+        .unwrap();
+        let service = Erc20Service::new(storage)
+            .with_balance_watchlist(watchlist.clone())
+            .with_admin_rpc(admin_rpc);
+        (service, watchlist)
+    }
+
+    fn accounts(accounts: &[u64]) -> Vec<Vec<u8>> {
+        accounts
+            .iter()
+            .map(|account| Felt::from(*account).to_bytes_be().to_vec())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn watched_account_rpcs_require_admin_and_a_watchlist() {
+        let (service, _) = service(false).await;
+        let status = service
+            .list_watched_accounts(Request::new(ListWatchedAccountsRequest {}))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::PermissionDenied);
+
+        let storage = Arc::new(Erc20Storage::new(":memory:").await.unwrap());
+        let service = Erc20Service::new(storage).with_admin_rpc(true);
+        let status = service
+            .add_watched_accounts(Request::new(AddWatchedAccountsRequest {
+                accounts: accounts(&[0xa]),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::FailedPrecondition);
+    }
+
+    #[tokio::test]
+    async fn watched_account_rpcs_manage_the_watchlist() {
+        let (service, watchlist) = service(true).await;
+
+        let added = service
+            .add_watched_accounts(Request::new(AddWatchedAccountsRequest {
+                accounts: accounts(&[0xb, 0xa, 0xb]),
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(
+            added,
+            AddWatchedAccountsResponse {
+                added: 2,
+                backfilled: 0,
+                block_number: None,
+            }
+        );
+        assert!(watchlist.contains(Felt::from(0xa_u64)));
+
+        let removed = service
+            .remove_watched_accounts(Request::new(RemoveWatchedAccountsRequest {
+                accounts: accounts(&[0xb, 0xc]),
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(removed.removed, 1);
+
+        let listed = service
+            .list_watched_accounts(Request::new(ListWatchedAccountsRequest {}))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(listed.accounts, accounts(&[0xa]));
+
+        let status = service
+            .add_watched_accounts(Request::new(AddWatchedAccountsRequest {
+                accounts: vec![vec![0xff; 40]],
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    }
 }
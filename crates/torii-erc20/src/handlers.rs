@@ -3,16 +3,16 @@ use async_trait::async_trait;
 use prost::Message;
 use prost_types::Any;
 use starknet::core::types::Felt;
-use starknet::providers::jsonrpc::{HttpTransport, JsonRpcClient};
 use std::collections::HashSet;
 use std::sync::{Arc, Mutex};
 use torii::command::CommandHandler;
 use torii::etl::sink::EventBus;
 use torii::UpdateType;
+use torii_common::RpcProvider;
 use torii_common::{u256_to_bytes, MetadataFetcher};
 
 use crate::proto;
-use crate::storage::Erc20Storage;
+use crate::sharding::ShardedErc20Storage;
 
 #[derive(Debug, Clone)]
 pub struct FetchErc20MetadataCommand {
@@ -21,7 +21,7 @@ pub struct FetchErc20MetadataCommand {
 
 pub struct Erc20MetadataCommandHandler {
     fetcher: Arc<MetadataFetcher>,
-    storage: Arc<Erc20Storage>,
+    storage: ShardedErc20Storage,
     event_bus: Mutex<Option<Arc<EventBus>>>,
     in_flight: Mutex<HashSet<Felt>>,
     max_retries: u8,
@@ -29,19 +29,26 @@ pub struct Erc20MetadataCommandHandler {
 
 impl Erc20MetadataCommandHandler {
     pub fn new(
-        provider: Arc<JsonRpcClient<HttpTransport>>,
-        storage: Arc<Erc20Storage>,
+        provider: Arc<RpcProvider>,
+        storage: impl Into<ShardedErc20Storage>,
         max_retries: u8,
     ) -> Self {
         Self {
             fetcher: Arc::new(MetadataFetcher::new(provider)),
-            storage,
+            storage: storage.into(),
             event_bus: Mutex::new(None),
             in_flight: Mutex::new(HashSet::new()),
             max_retries: max_retries.max(1),
         }
     }
 
+    /// Use a shared fetcher (e.g. one backed by a [`torii_common::MetadataCache`]).
+    #[must_use]
+    pub fn with_fetcher(mut self, fetcher: Arc<MetadataFetcher>) -> Self {
+        self.fetcher = fetcher;
+        self
+    }
+
     fn matches_metadata_filters(
         metadata: &proto::TokenMetadataEntry,
         filters: &std::collections::HashMap<String, String>,
@@ -4,6 +4,8 @@
 //! - `transfer` function
 //! - `balance_of` function
 //! - `Transfer` event
+//!
+//! or, when the ABI is unavailable, their entry point selectors.
 
 use anyhow::Result;
 use starknet::core::types::Felt;
@@ -24,6 +26,9 @@ use torii::etl::identification::IdentificationRule;
 /// - `Transfer` event
 ///
 /// This covers both snake_case (standard Cairo) and camelCase (legacy) naming.
+///
+/// When the ABI is unavailable, the class must have `transfer` and `balance_of`
+/// (or `balanceOf`) entry points and no `owner_of` (or `ownerOf`) entry point.
 pub struct Erc20Rule;
 
 impl Erc20Rule {
@@ -54,16 +59,25 @@ impl IdentificationRule for Erc20Rule {
         _class_hash: Felt,
         abi: &ContractAbi,
     ) -> Result<Vec<DecoderId>> {
-        // Check for transfer function (snake_case or camelCase)
-        let has_transfer = abi.has_function("transfer");
+        let matches = if abi.has_abi() {
+            // Check for transfer function (snake_case or camelCase)
+            let has_transfer = abi.has_function("transfer");
+
+            // Check for balance_of function (snake_case or camelCase)
+            let has_balance_of = abi.has_function("balance_of") || abi.has_function("balanceOf");
 
-        // Check for balance_of function (snake_case or camelCase)
-        let has_balance_of = abi.has_function("balance_of") || abi.has_function("balanceOf");
+            // Check for Transfer event
+            let has_transfer_event = abi.has_event("Transfer");
 
-        // Check for Transfer event
-        let has_transfer_event = abi.has_event("Transfer");
+            has_transfer && has_balance_of && has_transfer_event
+        } else {
+            // Events are not visible in entry points: exclude ERC721 by owner_of instead
+            abi.has_entry_point("transfer")
+                && (abi.has_entry_point("balance_of") || abi.has_entry_point("balanceOf"))
+                && !(abi.has_entry_point("owner_of") || abi.has_entry_point("ownerOf"))
+        };
 
-        if has_transfer && has_balance_of && has_transfer_event {
+        if matches {
             tracing::debug!(
                 target: "torii_erc20::identification",
                 "Contract matches ERC20 pattern"
@@ -78,6 +92,32 @@ impl IdentificationRule for Erc20Rule {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use starknet::core::types::{
+        ContractClass, EntryPointsByType, FlattenedSierraClass, SierraEntryPoint,
+    };
+    use starknet::core::utils::get_selector_from_name;
+
+    fn abi_without_json(entry_points: &[&str]) -> ContractAbi {
+        let external = entry_points
+            .iter()
+            .enumerate()
+            .map(|(i, name)| SierraEntryPoint {
+                selector: get_selector_from_name(name).unwrap(),
+                function_idx: i as u64,
+            })
+            .collect();
+        ContractAbi::from_contract_class(ContractClass::Sierra(FlattenedSierraClass {
+            sierra_program: Vec::new(),
+            contract_class_version: "0.1.0".to_string(),
+            entry_points_by_type: EntryPointsByType {
+                constructor: Vec::new(),
+                external,
+                l1_handler: Vec::new(),
+            },
+            abi: String::new(),
+        }))
+        .unwrap()
+    }
 
     #[test]
     fn test_erc20_rule_name() {
@@ -92,4 +132,21 @@ mod tests {
         assert_eq!(ids.len(), 1);
         assert_eq!(ids[0], DecoderId::new("erc20"));
     }
+
+    #[test]
+    fn test_erc20_rule_falls_back_to_entry_points() {
+        let rule = Erc20Rule::new();
+        let abi = abi_without_json(&["transfer", "balanceOf", "total_supply"]);
+        assert!(!abi.has_abi());
+        assert_eq!(
+            rule.identify_by_abi(Felt::ONE, Felt::TWO, &abi).unwrap(),
+            vec![DecoderId::new("erc20")]
+        );
+
+        let abi = abi_without_json(&["transfer_from", "balance_of", "owner_of", "get_approved"]);
+        assert!(rule
+            .identify_by_abi(Felt::ONE, Felt::TWO, &abi)
+            .unwrap()
+            .is_empty());
+    }
 }
@@ -9,7 +9,12 @@
 //! - [`Erc20Decoder`]: Decodes ERC20 Transfer and Approval events
 //! - [`Erc20Sink`]: Processes decoded events, stores in SQLite, and publishes updates
 //! - [`Erc20Storage`]: SQLite storage with efficient BLOB encoding and cursor pagination
+//! - [`ShardedErc20Storage`]: Storage split over several databases by token contract
 //! - [`Erc20Service`]: gRPC service for queries and real-time subscriptions
+//! - [`BalanceWatchlist`]: Accounts whose balances are maintained in the watched [`BalanceScope`]
+//! - [`PriceFeed`]: Optional token price source for USD-denominated queries
+//! - [`SupplySnapshot`]: Circulating supply per token and block, tracked from mints and burns
+//! - [`VolumeBucket`]: Hourly and daily transfer rollups per token (count, volume, participants)
 //!
 //! # Example
 //!
@@ -35,14 +40,21 @@
 //!     .add_service(Erc20Server::new(grpc_service));
 //! ```
 
+pub mod api;
 pub mod balance_fetcher;
+pub mod balance_scope;
+pub mod balance_updates;
 pub mod decoder;
 pub mod grpc_service;
 pub mod handlers;
 pub mod identification;
+pub mod price_feed;
+pub mod sharding;
 pub mod sink;
 pub mod storage;
+pub mod supply;
 pub mod synthetic;
+pub mod volume;
 
 // Include generated protobuf code
 pub mod proto {
@@ -54,13 +66,19 @@ pub const FILE_DESCRIPTOR_SET: &[u8] = include_bytes!("generated/erc20_descripto
 
 // Re-export main types for convenience
 pub use balance_fetcher::{BalanceFetchRequest, BalanceFetcher};
+pub use balance_scope::{BalanceScope, BalanceWatchlist, WatchlistAddition};
+pub use balance_updates::BalanceChange;
 pub use decoder::{Approval, Erc20Decoder, Transfer};
 pub use grpc_service::Erc20Service;
 pub use handlers::Erc20MetadataCommandHandler;
 pub use identification::Erc20Rule;
+pub use price_feed::{HttpPriceFeed, PragmaPriceFeed, PriceFeed, TokenPrice};
+pub use sharding::ShardedErc20Storage;
 pub use sink::Erc20Sink;
 pub use storage::{
-    ApprovalCursor, ApprovalData, BalanceAdjustment, BalanceData, Erc20Storage, TransferCursor,
-    TransferData, TransferDirection,
+    AllowanceData, ApprovalCursor, ApprovalData, BalanceAdjustment, BalanceData, Erc20Storage,
+    PortfolioEntry, TransferCursor, TransferData, TransferDirection,
 };
+pub use supply::{SupplyChange, SupplySnapshot, TransferKind};
 pub use synthetic::{SyntheticErc20Config, SyntheticErc20Extractor};
+pub use volume::{VolumeBucket, VolumeDelta, VolumeInterval};
@@ -0,0 +1,266 @@
+//! Token price feeds for USD valuation
+//!
+//! A [`PriceFeed`] returns USD prices for a set of tokens at a given block. The sink
+//! records one price per token and block window (see [`Erc20Sink::with_price_feed`]),
+//! and the gRPC service values transfers and balances from the recorded prices.
+//!
+//! Two feeds are provided:
+//! - [`PragmaPriceFeed`]: reads the median spot price from the Pragma oracle contract
+//!   at the requested block, for tokens mapped to a Pragma pair (e.g. `ETH/USD`).
+//! - [`HttpPriceFeed`]: queries an external HTTP endpoint returning current prices.
+//!
+//! [`Erc20Sink::with_price_feed`]: crate::Erc20Sink::with_price_feed
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use starknet::core::types::{BlockId, Felt, FunctionCall, U256};
+use starknet::core::utils::cairo_short_string_to_felt;
+use starknet::macros::selector;
+use starknet::providers::Provider;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use torii_common::RpcProvider;
+
+/// Timeout of a single HTTP price request
+const HTTP_PRICE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// USD price of a token
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TokenPrice {
+    pub token: Felt,
+    pub price_usd: f64,
+}
+
+/// Source of token prices
+#[async_trait]
+pub trait PriceFeed: Send + Sync {
+    /// Short name recorded alongside stored prices (e.g. "pragma")
+    fn name(&self) -> &'static str;
+
+    /// Whether prices can be fetched at past blocks.
+    ///
+    /// Feeds serving current prices only are not used while backfilling history.
+    fn supports_historical(&self) -> bool {
+        true
+    }
+
+    /// Fetches the USD price of `tokens` at `block_number`.
+    ///
+    /// Tokens the feed has no price for are omitted from the result.
+    async fn fetch_prices(&self, tokens: &[Felt], block_number: u64) -> Result<Vec<TokenPrice>>;
+}
+
+/// Pragma oracle price feed
+///
+/// Calls `get_data_median(SpotEntry(pair_id))` on the oracle contract at the
+/// requested block, so historical windows are priced at their own block.
+pub struct PragmaPriceFeed {
+    provider: Arc<RpcProvider>,
+    oracle: Felt,
+    /// Token address -> Pragma pair id (e.g. `ETH/USD` as a short string)
+    pairs: HashMap<Felt, Felt>,
+}
+
+impl PragmaPriceFeed {
+    pub fn new(provider: Arc<RpcProvider>, oracle: Felt) -> Self {
+        Self {
+            provider,
+            oracle,
+            pairs: HashMap::new(),
+        }
+    }
+
+    /// Maps a token to a Pragma pair (e.g. `"ETH/USD"`)
+    pub fn with_pair(mut self, token: Felt, pair: &str) -> Result<Self> {
+        let pair_id = cairo_short_string_to_felt(pair)
+            .with_context(|| format!("Invalid Pragma pair id {pair}"))?;
+        self.pairs.insert(token, pair_id);
+        Ok(self)
+    }
+}
+
+#[async_trait]
+impl PriceFeed for PragmaPriceFeed {
+    fn name(&self) -> &'static str {
+        "pragma"
+    }
+
+    async fn fetch_prices(&self, tokens: &[Felt], block_number: u64) -> Result<Vec<TokenPrice>> {
+        let calls = tokens.iter().filter_map(|token| {
+            let pair_id = *self.pairs.get(token)?;
+            let call = FunctionCall {
+                contract_address: self.oracle,
+                entry_point_selector: selector!("get_data_median"),
+                // DataType::SpotEntry(pair_id)
+                calldata: vec![Felt::ZERO, pair_id],
+            };
+            Some(async move {
+                let result = self
+                    .provider
+                    .call(call, BlockId::Number(block_number))
+                    .await;
+                (*token, result)
+            })
+        });
+
+        let mut prices = Vec::new();
+        for (token, result) in futures::future::join_all(calls).await {
+            let felts = match result {
+                Ok(felts) => felts,
+                Err(e) => {
+                    tracing::warn!(
+                        target: "torii_erc20::price_feed",
+                        token = %format!("{token:#x}"),
+                        block = block_number,
+                        error = %e,
+                        "Failed to fetch Pragma price"
+                    );
+                    continue;
+                }
+            };
+            if let Some(price_usd) = parse_pragma_price(&felts) {
+                prices.push(TokenPrice { token, price_usd });
+            } else {
+                tracing::debug!(
+                    target: "torii_erc20::price_feed",
+                    token = %format!("{token:#x}"),
+                    block = block_number,
+                    "No Pragma price for pair"
+                );
+            }
+        }
+        Ok(prices)
+    }
+}
+
+/// HTTP price feed
+///
+/// Sends `GET <url>?tokens=<hex>,<hex>&block=<n>` and expects a JSON object mapping
+/// token addresses (hex) to USD prices, e.g. `{"0x49d3...": 3150.2}`. External sources
+/// usually only serve current prices, so the block is informational and the feed is
+/// only used near the chain head.
+pub struct HttpPriceFeed {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl HttpPriceFeed {
+    pub fn new(url: impl Into<String>) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(HTTP_PRICE_TIMEOUT)
+            .build()
+            .context("Failed to build HTTP price feed client")?;
+        Ok(Self {
+            client,
+            url: url.into(),
+        })
+    }
+}
+
+#[async_trait]
+impl PriceFeed for HttpPriceFeed {
+    fn name(&self) -> &'static str {
+        "http"
+    }
+
+    fn supports_historical(&self) -> bool {
+        false
+    }
+
+    async fn fetch_prices(&self, tokens: &[Felt], block_number: u64) -> Result<Vec<TokenPrice>> {
+        if tokens.is_empty() {
+            return Ok(Vec::new());
+        }
+        let token_list = tokens
+            .iter()
+            .map(|token| format!("{token:#x}"))
+            .collect::<Vec<_>>()
+            .join(",");
+        let response: HashMap<String, f64> = self
+            .client
+            .get(&self.url)
+            .query(&[("tokens", token_list), ("block", block_number.to_string())])
+            .send()
+            .await
+            .context("Price request failed")?
+            .error_for_status()
+            .context("Price request failed")?
+            .json()
+            .await
+            .context("Invalid price response")?;
+
+        Ok(response
+            .into_iter()
+            .filter_map(|(token, price_usd)| {
+                let token = Felt::from_hex(&token).ok()?;
+                (tokens.contains(&token) && price_usd.is_finite() && price_usd >= 0.0)
+                    .then_some(TokenPrice { token, price_usd })
+            })
+            .collect())
+    }
+}
+
+/// Parses a serialized `PragmaPricesResponse`: `[price, decimals, last_updated_timestamp,
+/// num_sources_aggregated, expiration_timestamp: Option<u64>]`.
+///
+/// Returns `None` when the oracle has no data for the pair (no aggregated source).
+fn parse_pragma_price(result: &[Felt]) -> Option<f64> {
+    let [price, decimals, _, num_sources, ..] = result else {
+        return None;
+    };
+    if *num_sources == Felt::ZERO {
+        return None;
+    }
+    let price = u128::try_from(*price).ok()?;
+    let decimals = i32::try_from(u32::try_from(*decimals).ok()?).ok()?;
+    Some(price as f64 / 10f64.powi(decimals))
+}
+
+/// USD value of a raw token `amount` with `decimals` at `price_usd`.
+pub fn usd_value(amount: U256, decimals: u8, price_usd: f64) -> f64 {
+    let amount = (amount.high() as f64).mul_add(2f64.powi(128), amount.low() as f64);
+    amount / 10f64.powi(i32::from(decimals)) * price_usd
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pragma_price() {
+        // 3150.25 with 8 decimals, from 5 sources, no expiration
+        let result = [
+            Felt::from(315_025_000_000u64),
+            Felt::from(8u64),
+            Felt::from(1_700_000_000u64),
+            Felt::from(5u64),
+            Felt::ONE,
+        ];
+        let price = parse_pragma_price(&result).unwrap();
+        assert!((price - 3150.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_parse_pragma_price_without_sources() {
+        let result = [
+            Felt::ZERO,
+            Felt::from(8u64),
+            Felt::ZERO,
+            Felt::ZERO,
+            Felt::ONE,
+        ];
+        assert!(parse_pragma_price(&result).is_none());
+        assert!(parse_pragma_price(&[]).is_none());
+    }
+
+    #[test]
+    fn test_usd_value() {
+        // 1.5 tokens with 18 decimals at $2
+        let amount = U256::from(1_500_000_000_000_000_000u128);
+        assert!((usd_value(amount, 18, 2.0) - 3.0).abs() < 1e-9);
+
+        // 10 tokens with 6 decimals at $1
+        assert!((usd_value(U256::from(10_000_000u64), 6, 1.0) - 10.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,686 @@
+//! ERC20 storage sharded by token contract
+//!
+//! [`ShardedErc20Storage`] splits the ERC20 tables over several [`Erc20Storage`] shards
+//! (see [`torii_common::sharding`]). This type mirrors the [`Erc20Storage`] methods used by
+//! the sink and the services: writes and token-scoped queries are routed to the shard of
+//! the token, other queries fan out to every shard.
+//!
+//! Transfer and approval ids (and the cursors built from them) are global ids. Balance
+//! and allowance pages read the shards one after the other.
+
+use crate::price_feed::TokenPrice;
+use crate::storage::{
+    AllowanceData, ApprovalCursor, ApprovalData, BalanceCheckBatch, BalanceData, Erc20Storage,
+    PortfolioEntry, StoredProvenance, TransferCursor, TransferData, TransferDirection,
+};
+use crate::supply::{SupplyChange, SupplySnapshot};
+use crate::volume::{VolumeBucket, VolumeDelta, VolumeInterval};
+use anyhow::Result;
+use async_trait::async_trait;
+use futures::future::try_join_all;
+use starknet::core::types::{Felt, U256};
+use std::borrow::Cow;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use torii_common::{merge_pages, shard_url, FieldMask, ReplayRange, ReplaySource, StorageShards};
+
+/// Token metadata row: (token, name, symbol, decimals, total supply)
+type TokenMetadataRow = (
+    Felt,
+    Option<String>,
+    Option<String>,
+    Option<u8>,
+    Option<U256>,
+);
+
+/// ERC20 storage split by token contract
+#[derive(Clone)]
+pub struct ShardedErc20Storage {
+    shards: StorageShards<Erc20Storage>,
+}
+
+impl From<Arc<Erc20Storage>> for ShardedErc20Storage {
+    fn from(storage: Arc<Erc20Storage>) -> Self {
+        Self::new(StorageShards::single(storage))
+    }
+}
+
+impl ShardedErc20Storage {
+    pub fn new(shards: StorageShards<Erc20Storage>) -> Self {
+        Self { shards }
+    }
+
+    /// Create or open `count` shards, derived from `db_path` with [`shard_url`].
+    pub async fn open(db_path: &str, count: usize) -> Result<Self> {
+        let mut shards = Vec::with_capacity(count.max(1));
+        for index in 0..count.max(1) {
+            shards.push(Arc::new(
+                Erc20Storage::new(&shard_url(db_path, index)).await?,
+            ));
+        }
+        Ok(Self::new(StorageShards::new(shards)))
+    }
+
+    /// The shards, for writes routed by token.
+    pub fn shards(&self) -> &StorageShards<Erc20Storage> {
+        &self.shards
+    }
+
+    /// Storage of the shard of `token`.
+    pub fn for_token(&self, token: Felt) -> &Arc<Erc20Storage> {
+        self.shards.for_contract(token)
+    }
+
+    /// Splits `items` by shard of their token; a single shard borrows them as is.
+    fn split<'a, T: Clone>(
+        &self,
+        items: &'a [T],
+        token: impl Fn(&T) -> Felt,
+    ) -> Vec<(usize, Cow<'a, [T]>)> {
+        if !self.shards.is_sharded() {
+            return vec![(0, Cow::Borrowed(items))];
+        }
+        self.shards
+            .partition(items.iter().cloned(), token)
+            .into_iter()
+            .map(|(shard, items)| (shard, Cow::Owned(items)))
+            .collect()
+    }
+
+    pub async fn insert_transfers_batch(&self, transfers: &[TransferData]) -> Result<usize> {
+        let mut inserted = 0;
+        for (shard, transfers) in self.split(transfers, |t| t.token) {
+            inserted += self
+                .shards
+                .get(shard)
+                .insert_transfers_batch(&transfers)
+                .await?;
+        }
+        Ok(inserted)
+    }
+
+    pub async fn insert_approvals_batch(&self, approvals: &[ApprovalData]) -> Result<usize> {
+        let mut inserted = 0;
+        for (shard, approvals) in self.split(approvals, |a| a.token) {
+            inserted += self
+                .shards
+                .get(shard)
+                .insert_approvals_batch(&approvals)
+                .await?;
+        }
+        Ok(inserted)
+    }
+
+    pub async fn has_token_metadata_batch(&self, tokens: &[Felt]) -> Result<HashSet<Felt>> {
+        let mut existing = HashSet::new();
+        for (shard, tokens) in self.shards.select(tokens) {
+            if tokens.is_empty() {
+                continue;
+            }
+            existing.extend(
+                self.shards
+                    .get(shard)
+                    .has_token_metadata_batch(&tokens)
+                    .await?,
+            );
+        }
+        Ok(existing)
+    }
+
+    /// Balance checks of every shard; tokens never span shards, so the results merge.
+    pub async fn check_balances_batch_with_snapshot(
+        &self,
+        transfers: &[TransferData],
+    ) -> Result<BalanceCheckBatch> {
+        let mut merged = BalanceCheckBatch {
+            adjustment_requests: Vec::new(),
+            balance_snapshot: HashMap::new(),
+        };
+        for (shard, transfers) in self.split(transfers, |t| t.token) {
+            let batch = self
+                .shards
+                .get(shard)
+                .check_balances_batch_with_snapshot(&transfers)
+                .await?;
+            merged.adjustment_requests.extend(batch.adjustment_requests);
+            merged.balance_snapshot.extend(batch.balance_snapshot);
+        }
+        Ok(merged)
+    }
+
+    /// Apply balance updates, handing each shard its transfers, adjustments and snapshot.
+    pub async fn apply_transfers_with_adjustments_with_snapshot(
+        &self,
+        transfers: &[TransferData],
+        adjustments: &HashMap<(Felt, Felt), U256>,
+        balance_snapshot: Option<HashMap<(Felt, Felt), U256>>,
+    ) -> Result<()> {
+        if !self.shards.is_sharded() {
+            return self
+                .shards
+                .get(0)
+                .apply_transfers_with_adjustments_with_snapshot(
+                    transfers,
+                    adjustments,
+                    balance_snapshot,
+                )
+                .await;
+        }
+        for (shard, transfers) in self.split(transfers, |t| t.token) {
+            let in_shard = |(token, _): &(Felt, Felt)| self.shards.index_of(*token) == shard;
+            let adjustments = adjustments
+                .iter()
+                .filter(|(key, _)| in_shard(key))
+                .map(|(key, balance)| (*key, *balance))
+                .collect();
+            let snapshot = balance_snapshot.as_ref().map(|snapshot| {
+                snapshot
+                    .iter()
+                    .filter(|(key, _)| in_shard(key))
+                    .map(|(key, balance)| (*key, *balance))
+                    .collect()
+            });
+            self.shards
+                .get(shard)
+                .apply_transfers_with_adjustments_with_snapshot(&transfers, &adjustments, snapshot)
+                .await?;
+        }
+        Ok(())
+    }
+
+    pub async fn apply_supply_changes(
+        &self,
+        changes: &[SupplyChange],
+    ) -> Result<Vec<SupplySnapshot>> {
+        let mut snapshots = Vec::new();
+        for (shard, changes) in self.split(changes, |c| c.token) {
+            snapshots.extend(
+                self.shards
+                    .get(shard)
+                    .apply_supply_changes(&changes)
+                    .await?,
+            );
+        }
+        Ok(snapshots)
+    }
+
+    pub async fn apply_volume_deltas(&self, deltas: &[VolumeDelta]) -> Result<usize> {
+        let mut updated = 0;
+        for (shard, deltas) in self.split(deltas, |d| d.token) {
+            updated += self.shards.get(shard).apply_volume_deltas(&deltas).await?;
+        }
+        Ok(updated)
+    }
+
+    pub async fn upsert_token_prices(
+        &self,
+        block_window: u64,
+        block_number: u64,
+        prices: &[TokenPrice],
+        source: &str,
+    ) -> Result<usize> {
+        let mut upserted = 0;
+        for (shard, prices) in self.split(prices, |p| p.token) {
+            upserted += self
+                .shards
+                .get(shard)
+                .upsert_token_prices(block_window, block_number, &prices, source)
+                .await?;
+        }
+        Ok(upserted)
+    }
+
+    /// Get filtered transfers, merged across shards (see [`Erc20Storage::get_transfers_filtered`])
+    #[allow(clippy::too_many_arguments)]
+    pub async fn get_transfers_filtered(
+        &self,
+        wallet: Option<Felt>,
+        from: Option<Felt>,
+        to: Option<Felt>,
+        tokens: &[Felt],
+        direction: TransferDirection,
+        block_from: Option<u64>,
+        block_to: Option<u64>,
+        cursor: Option<TransferCursor>,
+        limit: u32,
+    ) -> Result<(Vec<TransferData>, Option<TransferCursor>)> {
+        self.get_transfers_projected(
+            wallet,
+            from,
+            to,
+            tokens,
+            direction,
+            block_from,
+            block_to,
+            cursor,
+            limit,
+            &FieldMask::all(),
+        )
+        .await
+    }
+
+    /// Get projected transfers, merged across shards (see [`Erc20Storage::get_transfers_projected`])
+    #[allow(clippy::too_many_arguments)]
+    pub async fn get_transfers_projected(
+        &self,
+        wallet: Option<Felt>,
+        from: Option<Felt>,
+        to: Option<Felt>,
+        tokens: &[Felt],
+        direction: TransferDirection,
+        block_from: Option<u64>,
+        block_to: Option<u64>,
+        cursor: Option<TransferCursor>,
+        limit: u32,
+        mask: &FieldMask,
+    ) -> Result<(Vec<TransferData>, Option<TransferCursor>)> {
+        let shards = &self.shards;
+        let pages = try_join_all(shards.select(tokens).into_iter().map(
+            |(shard, tokens)| async move {
+                let cursor = cursor.map(|c| TransferCursor {
+                    block_number: c.block_number,
+                    id: shards.local_cursor_before(shard, c.id),
+                });
+                let (mut transfers, _) = shards
+                    .get(shard)
+                    .get_transfers_projected(
+                        wallet, from, to, &tokens, direction, block_from, block_to, cursor, limit,
+                        mask,
+                    )
+                    .await?;
+                for transfer in &mut transfers {
+                    transfer.id = transfer.id.map(|id| shards.global_id(shard, id));
+                }
+                Ok::<_, anyhow::Error>(transfers)
+            },
+        ))
+        .await?;
+
+        let (transfers, _) =
+            merge_pages(pages, limit as usize, |t| Reverse((t.block_number, t.id)));
+        let next_cursor = if transfers.len() == limit as usize {
+            transfers.last().map(|t| TransferCursor {
+                block_number: t.block_number,
+                id: t.id.unwrap(),
+            })
+        } else {
+            None
+        };
+        Ok((transfers, next_cursor))
+    }
+
+    /// Get approvals, merged across shards (see [`Erc20Storage::get_approvals_filtered`])
+    #[allow(clippy::too_many_arguments)]
+    pub async fn get_approvals_filtered(
+        &self,
+        account: Option<Felt>,
+        owner: Option<Felt>,
+        spender: Option<Felt>,
+        tokens: &[Felt],
+        block_from: Option<u64>,
+        block_to: Option<u64>,
+        cursor: Option<ApprovalCursor>,
+        limit: u32,
+    ) -> Result<(Vec<ApprovalData>, Option<ApprovalCursor>)> {
+        let shards = &self.shards;
+        let pages = try_join_all(shards.select(tokens).into_iter().map(
+            |(shard, tokens)| async move {
+                let cursor = cursor.map(|c| ApprovalCursor {
+                    block_number: c.block_number,
+                    id: shards.local_cursor_before(shard, c.id),
+                });
+                let (mut approvals, _) = shards
+                    .get(shard)
+                    .get_approvals_filtered(
+                        account, owner, spender, &tokens, block_from, block_to, cursor, limit,
+                    )
+                    .await?;
+                for approval in &mut approvals {
+                    approval.id = approval.id.map(|id| shards.global_id(shard, id));
+                }
+                Ok::<_, anyhow::Error>(approvals)
+            },
+        ))
+        .await?;
+
+        let (approvals, _) =
+            merge_pages(pages, limit as usize, |a| Reverse((a.block_number, a.id)));
+        let next_cursor = if approvals.len() == limit as usize {
+            approvals.last().map(|a| ApprovalCursor {
+                block_number: a.block_number,
+                id: a.id.unwrap(),
+            })
+        } else {
+            None
+        };
+        Ok((approvals, next_cursor))
+    }
+
+    /// Transfers of a transaction from every shard, in event order
+    pub async fn get_transfers_by_tx(&self, tx_hash: Felt) -> Result<Vec<TransferData>> {
+        let shards = &self.shards;
+        let pages = try_join_all((0..shards.len()).map(|shard| async move {
+            let mut transfers = shards.get(shard).get_transfers_by_tx(tx_hash).await?;
+            for transfer in &mut transfers {
+                transfer.id = transfer.id.map(|id| shards.global_id(shard, id));
+            }
+            Ok::<_, anyhow::Error>(transfers)
+        }))
+        .await?;
+        let mut transfers: Vec<TransferData> = pages.into_iter().flatten().collect();
+        transfers.sort_by_key(|t| (t.event_index.is_none(), t.event_index, t.id));
+        Ok(transfers)
+    }
+
+    /// Provenance of transfers by global id
+    pub async fn get_transfer_provenance(
+        &self,
+        ids: &[i64],
+    ) -> Result<HashMap<i64, StoredProvenance>> {
+        let mut provenance = HashMap::with_capacity(ids.len());
+        for (shard, ids) in self.shards.local_ids(ids) {
+            let stored = self.shards.get(shard).get_transfer_provenance(&ids).await?;
+            provenance.extend(
+                stored
+                    .into_iter()
+                    .map(|(id, row)| (self.shards.global_id(shard, id), row)),
+            );
+        }
+        Ok(provenance)
+    }
+
+    /// Provenance of approvals by global id
+    pub async fn get_approval_provenance(
+        &self,
+        ids: &[i64],
+    ) -> Result<HashMap<i64, StoredProvenance>> {
+        let mut provenance = HashMap::with_capacity(ids.len());
+        for (shard, ids) in self.shards.local_ids(ids) {
+            let stored = self.shards.get(shard).get_approval_provenance(&ids).await?;
+            provenance.extend(
+                stored
+                    .into_iter()
+                    .map(|(id, row)| (self.shards.global_id(shard, id), row)),
+            );
+        }
+        Ok(provenance)
+    }
+
+    /// Shards holding `token`, or all shards.
+    fn token_shards(&self, token: Option<Felt>) -> Vec<usize> {
+        match token {
+            Some(token) => vec![self.shards.index_of(token)],
+            None => (0..self.shards.len()).collect(),
+        }
+    }
+
+    pub async fn get_balance_with_block(
+        &self,
+        token: Felt,
+        wallet: Felt,
+    ) -> Result<Option<(U256, u64)>> {
+        self.for_token(token)
+            .get_balance_with_block(token, wallet)
+            .await
+    }
+
+    /// Get balances of `(token, wallet)` pairs, merged across shards
+    pub async fn get_balances_batch(
+        &self,
+        pairs: &[(Felt, Felt)],
+    ) -> Result<HashMap<(Felt, Felt), U256>> {
+        let mut balances = HashMap::with_capacity(pairs.len());
+        for (shard, pairs) in self.split(pairs, |(token, _)| *token) {
+            balances.extend(self.shards.get(shard).get_balances_batch(&pairs).await?);
+        }
+        Ok(balances)
+    }
+
+    /// Get balances, one shard after the other (see [`Erc20Storage::get_balances_filtered`])
+    pub async fn get_balances_filtered(
+        &self,
+        token: Option<Felt>,
+        wallet: Option<Felt>,
+        cursor: Option<i64>,
+        limit: u32,
+    ) -> Result<(Vec<BalanceData>, Option<i64>)> {
+        self.shards
+            .try_paginate(
+                &self.token_shards(token),
+                cursor,
+                limit,
+                |storage, cursor, limit| {
+                    storage.get_balances_filtered(token, wallet, cursor, limit)
+                },
+            )
+            .await
+    }
+
+    /// Get the non-zero balances of a wallet, merged across shards in token order
+    pub async fn get_portfolio(&self, wallet: Felt) -> Result<Vec<PortfolioEntry>> {
+        let mut entries = self
+            .shards
+            .try_fan_out(|_, storage| storage.get_portfolio(wallet))
+            .await?
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+        entries.sort_by_key(|entry| entry.token);
+        Ok(entries)
+    }
+
+    /// Get allowances, one shard after the other (see [`Erc20Storage::get_allowances_filtered`])
+    pub async fn get_allowances_filtered(
+        &self,
+        owner: Option<Felt>,
+        spender: Option<Felt>,
+        token: Option<Felt>,
+        cursor: Option<i64>,
+        limit: u32,
+    ) -> Result<(Vec<AllowanceData>, Option<i64>)> {
+        self.shards
+            .try_paginate(
+                &self.token_shards(token),
+                cursor,
+                limit,
+                |storage, cursor, limit| {
+                    storage.get_allowances_filtered(owner, spender, token, cursor, limit)
+                },
+            )
+            .await
+    }
+
+    pub async fn upsert_token_metadata(
+        &self,
+        token: Felt,
+        name: Option<&str>,
+        symbol: Option<&str>,
+        decimals: Option<u8>,
+        total_supply: Option<U256>,
+    ) -> Result<()> {
+        self.for_token(token)
+            .upsert_token_metadata(token, name, symbol, decimals, total_supply)
+            .await
+    }
+
+    pub async fn get_token_metadata(
+        &self,
+        token: Felt,
+    ) -> Result<Option<(Option<String>, Option<String>, Option<u8>, Option<U256>)>> {
+        self.for_token(token).get_token_metadata(token).await
+    }
+
+    /// Get token metadata in token order, merged across shards
+    pub async fn get_token_metadata_paginated(
+        &self,
+        cursor: Option<Felt>,
+        limit: u32,
+    ) -> Result<(Vec<TokenMetadataRow>, Option<Felt>)> {
+        let pages = self
+            .shards
+            .try_fan_out(|_, storage| storage.get_token_metadata_paginated(cursor, limit))
+            .await?;
+        let more_in_shards = pages.iter().any(|(_, next)| next.is_some());
+        let (rows, truncated) = merge_pages(
+            pages.into_iter().map(|(rows, _)| rows),
+            limit as usize,
+            |row| row.0,
+        );
+        let next_cursor = if more_in_shards || truncated {
+            rows.last().map(|row| row.0)
+        } else {
+            None
+        };
+        Ok((rows, next_cursor))
+    }
+
+    pub async fn get_token_prices_at(
+        &self,
+        lookups: &[(Felt, u64)],
+    ) -> Result<HashMap<(Felt, u64), f64>> {
+        let mut prices = HashMap::new();
+        for (shard, lookups) in self.shards.partition(lookups.iter().copied(), |l| l.0) {
+            prices.extend(self.shards.get(shard).get_token_prices_at(&lookups).await?);
+        }
+        Ok(prices)
+    }
+
+    pub async fn get_supply_history(
+        &self,
+        token: Felt,
+        from_block: Option<u64>,
+        to_block: Option<u64>,
+        cursor: Option<u64>,
+        limit: u32,
+    ) -> Result<(Vec<SupplySnapshot>, Option<u64>)> {
+        self.for_token(token)
+            .get_supply_history(token, from_block, to_block, cursor, limit)
+            .await
+    }
+
+    pub async fn get_volume_series(
+        &self,
+        token: Felt,
+        interval: VolumeInterval,
+        from: Option<i64>,
+        to: Option<i64>,
+        cursor: Option<i64>,
+        limit: u32,
+    ) -> Result<(Vec<VolumeBucket>, Option<i64>)> {
+        self.for_token(token)
+            .get_volume_series(token, interval, from, to, cursor, limit)
+            .await
+    }
+
+    pub async fn get_transfer_count(&self) -> Result<u64> {
+        let counts = self
+            .shards
+            .try_fan_out(|_, storage| storage.get_transfer_count())
+            .await?;
+        Ok(counts.into_iter().sum())
+    }
+
+    pub async fn get_approval_count(&self) -> Result<u64> {
+        let counts = self
+            .shards
+            .try_fan_out(|_, storage| storage.get_approval_count())
+            .await?;
+        Ok(counts.into_iter().sum())
+    }
+
+    /// Tokens never span shards, so per-shard counts add up.
+    pub async fn get_token_count(&self) -> Result<u64> {
+        let counts = self
+            .shards
+            .try_fan_out(|_, storage| storage.get_token_count())
+            .await?;
+        Ok(counts.into_iter().sum())
+    }
+
+    pub async fn get_latest_block(&self) -> Result<Option<u64>> {
+        let blocks = self
+            .shards
+            .try_fan_out(|_, storage| storage.get_latest_block())
+            .await?;
+        Ok(blocks.into_iter().flatten().max())
+    }
+
+    /// The balance watchlist lives in the first shard.
+    pub async fn add_watched_accounts(&self, accounts: &[Felt]) -> Result<usize> {
+        self.shards.get(0).add_watched_accounts(accounts).await
+    }
+
+    pub async fn remove_watched_accounts(&self, accounts: &[Felt]) -> Result<usize> {
+        self.shards.get(0).remove_watched_accounts(accounts).await
+    }
+
+    pub async fn get_watched_accounts(&self) -> Result<Vec<Felt>> {
+        self.shards.get(0).get_watched_accounts().await
+    }
+
+    /// Tokens of `wallet` in every shard, in token order.
+    pub async fn get_wallet_tokens(&self, wallet: Felt) -> Result<Vec<Felt>> {
+        let mut tokens = self
+            .shards
+            .try_fan_out(|_, storage| storage.get_wallet_tokens(wallet))
+            .await?
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+        tokens.sort();
+        Ok(tokens)
+    }
+
+    pub async fn upsert_fetched_balances(
+        &self,
+        balances: &[(Felt, Felt, U256)],
+        block_number: u64,
+    ) -> Result<()> {
+        for (shard, balances) in self.split(balances, |b| b.0) {
+            self.shards
+                .get(shard)
+                .upsert_fetched_balances(&balances, block_number)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Runs SQLite maintenance on every shard.
+    pub async fn run_maintenance(&self) -> Result<()> {
+        self.shards
+            .try_fan_out(|_, storage| storage.run_maintenance())
+            .await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ReplaySource for ShardedErc20Storage {
+    type Row = TransferData;
+
+    /// Transfers in id order, merged across shards by global id
+    async fn replay_page(
+        &self,
+        range: &ReplayRange,
+        after_id: Option<i64>,
+    ) -> Result<Vec<TransferData>> {
+        let shards = &self.shards;
+        let pages = try_join_all(shards.select(&range.tokens).into_iter().map(
+            |(shard, tokens)| async move {
+                let after_id = after_id.map(|id| shards.local_cursor_after(shard, id));
+                let mut transfers = shards
+                    .get(shard)
+                    .get_transfers_for_replay(&range.with_tokens(tokens), after_id)
+                    .await?;
+                for transfer in &mut transfers {
+                    transfer.id = transfer.id.map(|id| shards.global_id(shard, id));
+                }
+                Ok::<_, anyhow::Error>(transfers)
+            },
+        ))
+        .await?;
+
+        Ok(merge_pages(pages, range.page_size as usize, |t| t.id).0)
+    }
+}
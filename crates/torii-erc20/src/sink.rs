@@ -3,6 +3,9 @@
 //! This sink:
 //! - Stores transfer and approval records in the database
 //! - Tracks balances with automatic inconsistency detection
+//! - Classifies transfers from/to the zero address as mints/burns and tracks the
+//!   circulating supply of each token
+//! - Maintains hourly and daily transfer rollups per token (see [`crate::volume`])
 //! - Publishes events via EventBus for real-time subscriptions (simple clients)
 //! - Broadcasts events via gRPC service for rich subscriptions (advanced clients)
 //!
@@ -10,33 +13,51 @@
 //! - Computes balances from transfer events
 //! - When a balance would go negative (genesis allocation, airdrop, etc.),
 //!   fetches the actual balance from the chain and adjusts
+//!
+//! In index-only mode (see [`Erc20Sink::with_index_only`]) only transfer and approval
+//! history is recorded; balance adjustments and RPC reconciliation are skipped.
+//! [`Erc20Sink::with_balance_scope`] restricts balances to a watchlist of accounts.
 
+use crate::api::{self, Erc20ApiState};
 use crate::balance_fetcher::BalanceFetcher;
+use crate::balance_scope::BalanceScope;
+use crate::balance_updates::balance_changes;
 use crate::decoder::{Approval as DecodedApproval, Transfer as DecodedTransfer};
 use crate::grpc_service::Erc20Service;
 use crate::handlers::FetchErc20MetadataCommand;
+use crate::price_feed::PriceFeed;
 use crate::proto;
-use crate::storage::{ApprovalData, Erc20Storage, TransferData};
-use anyhow::Result;
+use crate::sharding::ShardedErc20Storage;
+use crate::storage::{ApprovalData, TransferData};
+use crate::supply::supply_changes;
+use crate::volume::volume_deltas;
 use async_trait::async_trait;
+use axum::routing::get;
 use axum::Router;
 use prost::Message;
 use prost_types::Any;
 use starknet::core::types::{Felt, U256};
-use starknet::providers::jsonrpc::{HttpTransport, JsonRpcClient};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 use torii::command::CommandBusSender;
-use torii::etl::sink::{EventBus, TopicInfo};
+use torii::etl::sink::{
+    ColumnSchema, EnvelopeSchema, EventBus, SinkContractFilter, TableSchema, TopicInfo,
+};
 use torii::etl::{Envelope, ExtractionBatch, Sink, TypeId};
 use torii::grpc::UpdateType;
+use torii::ToriiResult;
 use torii_common::u256_to_bytes;
+use torii_common::{MaintenanceSchedule, RpcProvider};
 
 /// Default threshold for "live" detection: 100 blocks from chain head.
 /// Events from blocks older than this won't be broadcast to real-time subscribers.
 const LIVE_THRESHOLD_BLOCKS: u64 = 100;
 
+/// Default number of blocks sharing a recorded token price.
+pub const DEFAULT_PRICE_WINDOW_BLOCKS: u64 = 100;
+
 /// ERC20 transfer and approval sink
 ///
 /// Processes ERC20 Transfer and Approval events and:
@@ -48,11 +69,15 @@ const LIVE_THRESHOLD_BLOCKS: u64 = 100;
 /// During historical indexing (more than 100 blocks from chain head), events are
 /// stored but not broadcast to avoid overwhelming real-time subscribers.
 pub struct Erc20Sink {
-    storage: Arc<Erc20Storage>,
+    storage: ShardedErc20Storage,
     event_bus: Option<Arc<EventBus>>,
     grpc_service: Option<Erc20Service>,
     /// Balance fetcher for RPC calls (None = balance tracking disabled)
     balance_fetcher: Option<Arc<BalanceFetcher>>,
+    /// Record transfer history only, skipping balance tracking even if a fetcher is set.
+    index_only: bool,
+    /// Accounts whose balances are maintained
+    balance_scope: BalanceScope,
     /// Whether contract metadata commands should be dispatched.
     metadata_commands_enabled: bool,
     /// Command bus sender for background metadata work.
@@ -62,21 +87,39 @@ pub struct Erc20Sink {
     /// In-memory counters to avoid full-table COUNT(*) in the ingest hot path.
     total_transfers: AtomicU64,
     total_approvals: AtomicU64,
+    /// Price feed for USD valuation (None = prices not recorded)
+    price_feed: Option<Arc<dyn PriceFeed>>,
+    /// Number of blocks sharing a recorded token price
+    price_window_blocks: u64,
+    /// Latest block window priced per token.
+    priced_windows: tokio::sync::Mutex<HashMap<Felt, u64>>,
+    /// SQLite maintenance run on idle cycles (disabled by default).
+    maintenance: MaintenanceSchedule,
+    /// Contracts routed to the sink (all when unset)
+    contract_filter: Option<SinkContractFilter>,
 }
 
 impl Erc20Sink {
-    pub fn new(storage: Arc<Erc20Storage>) -> Self {
+    /// Writes go to `storage`, a single storage or a [`ShardedErc20Storage`].
+    pub fn new(storage: impl Into<ShardedErc20Storage>) -> Self {
         Self {
-            storage,
+            storage: storage.into(),
             event_bus: None,
             grpc_service: None,
             balance_fetcher: None,
+            index_only: false,
+            balance_scope: BalanceScope::All,
             metadata_commands_enabled: false,
             command_bus: None,
             pending_metadata_commands: tokio::sync::Mutex::new(HashSet::new()),
             // Avoid startup full-table COUNT(*) scans on large datasets.
             total_transfers: AtomicU64::new(0),
             total_approvals: AtomicU64::new(0),
+            price_feed: None,
+            price_window_blocks: DEFAULT_PRICE_WINDOW_BLOCKS,
+            priced_windows: tokio::sync::Mutex::new(HashMap::new()),
+            maintenance: MaintenanceSchedule::disabled(),
+            contract_filter: None,
         }
     }
 
@@ -86,6 +129,21 @@ impl Erc20Sink {
         self
     }
 
+    /// Only process the envelopes of the contracts `filter` routes to this sink
+    ///
+    /// Envelopes are filtered by `MultiSink` before reaching the sink (see
+    /// [`SinkContractFilter`]); without a filter every contract is processed.
+    pub fn with_contract_filter(mut self, filter: SinkContractFilter) -> Self {
+        self.contract_filter = Some(filter);
+        self
+    }
+
+    /// Runs SQLite maintenance (vacuum, `ANALYZE`) on idle cycles, at most once per `interval`
+    pub fn with_maintenance_interval(mut self, interval: Duration) -> Self {
+        self.maintenance = MaintenanceSchedule::new(Some(interval));
+        self
+    }
+
     /// Enable balance tracking with a provider for RPC calls
     ///
     /// When enabled, the sink will:
@@ -93,13 +151,48 @@ impl Erc20Sink {
     /// - Detect when a balance would go negative (indicating missed history)
     /// - Fetch actual balance from the chain and adjust
     /// - Record adjustments in an audit table
-    pub fn with_balance_tracking(mut self, provider: Arc<JsonRpcClient<HttpTransport>>) -> Self {
+    pub fn with_balance_tracking(mut self, provider: Arc<RpcProvider>) -> Self {
         self.balance_fetcher = Some(Arc::new(BalanceFetcher::new(provider)));
         self
     }
 
+    /// Run in index-only mode (transfer history without balances)
+    ///
+    /// When enabled, balance adjustments and RPC reconciliation are skipped, even if
+    /// balance tracking was configured. The schema is unchanged, so the mode can be
+    /// toggled between restarts; balances are not backfilled when it is turned off.
+    pub fn with_index_only(mut self, index_only: bool) -> Self {
+        self.index_only = index_only;
+        self
+    }
+
+    /// Restrict balance tracking to the accounts of `scope` (all addresses by default)
+    ///
+    /// With [`BalanceScope::Watched`], transfers only update the balances of watched
+    /// accounts; [`BalanceScope::None`] skips balances like index-only mode.
+    pub fn with_balance_scope(mut self, scope: BalanceScope) -> Self {
+        self.balance_scope = scope;
+        self
+    }
+
+    /// Whether balances are maintained (balance tracking enabled, within the scope).
+    fn tracks_balances(&self) -> bool {
+        self.balance_fetcher.is_some() && !self.index_only && self.balance_scope.tracks_balances()
+    }
+
+    /// Record token USD prices from a price feed
+    ///
+    /// Each transferred token is priced once per window of `window_blocks` blocks, at
+    /// the latest block of the window seen in the batch. Prices are stored in
+    /// `token_prices` and used by the gRPC service for USD valuation.
+    pub fn with_price_feed(mut self, feed: Arc<dyn PriceFeed>, window_blocks: u64) -> Self {
+        self.price_feed = Some(feed);
+        self.price_window_blocks = window_blocks.max(1);
+        self
+    }
+
     /// Get a reference to the storage
-    pub fn storage(&self) -> &Arc<Erc20Storage> {
+    pub fn storage(&self) -> &ShardedErc20Storage {
         &self.storage
     }
 
@@ -110,7 +203,7 @@ impl Erc20Sink {
     /// - "from": Filter by sender address (hex string)
     /// - "to": Filter by receiver address (hex string)
     /// - "wallet": Filter by wallet address - matches from OR to (OR logic)
-    fn matches_transfer_filters(
+    pub fn matches_transfer_filters(
         transfer: &proto::Transfer,
         filters: &HashMap<String, String>,
     ) -> bool {
@@ -163,7 +256,7 @@ impl Erc20Sink {
     /// - "owner": Filter by owner address (hex string)
     /// - "spender": Filter by spender address (hex string)
     /// - "account": Filter by account address - matches owner OR spender (OR logic)
-    fn matches_approval_filters(
+    pub fn matches_approval_filters(
         approval: &proto::Approval,
         filters: &HashMap<String, String>,
     ) -> bool {
@@ -216,22 +309,59 @@ impl Sink for Erc20Sink {
         "erc20"
     }
 
+    fn version(&self) -> Option<&str> {
+        Some(env!("CARGO_PKG_VERSION"))
+    }
+
+    fn descriptor_set(&self) -> Option<&'static [u8]> {
+        Some(crate::FILE_DESCRIPTOR_SET)
+    }
+
     fn interested_types(&self) -> Vec<TypeId> {
         vec![TypeId::new("erc20.transfer"), TypeId::new("erc20.approval")]
     }
 
+    fn contract_filter(&self) -> Option<&SinkContractFilter> {
+        self.contract_filter.as_ref()
+    }
+
+    fn envelope_schemas(&self) -> Vec<EnvelopeSchema> {
+        vec![
+            EnvelopeSchema::new("erc20.transfer", "torii.sinks.erc20.Transfer"),
+            EnvelopeSchema::new("erc20.approval", "torii.sinks.erc20.Approval"),
+        ]
+    }
+
     async fn initialize(
         &mut self,
         event_bus: Arc<EventBus>,
         context: &torii::etl::sink::SinkContext,
-    ) -> Result<()> {
+    ) -> ToriiResult<()> {
+        // End the service's streams with the server-wide shutdown notice.
+        if let Some(grpc_service) = &self.grpc_service {
+            let grpc_service = grpc_service.clone();
+            let shutdown = event_bus.subscription_manager().shutdown_signal().clone();
+            tokio::spawn(async move {
+                let notice = shutdown.notified().await;
+                grpc_service.notify_shutdown(notice.block_number);
+            });
+        }
         self.event_bus = Some(event_bus);
         self.command_bus = Some(context.command_bus.clone());
-        tracing::info!(target: "torii_erc20::sink", "ERC20 sink initialized");
+        tracing::info!(
+            target: "torii_erc20::sink",
+            index_only = self.index_only,
+            balance_tracking = self.tracks_balances(),
+            watched_accounts = match &self.balance_scope {
+                BalanceScope::Watched(watchlist) => Some(watchlist.len()),
+                _ => None,
+            },
+            "ERC20 sink initialized"
+        );
         Ok(())
     }
 
-    async fn process(&self, envelopes: &[Envelope], batch: &ExtractionBatch) -> Result<()> {
+    async fn process(&self, envelopes: &[Envelope], batch: &ExtractionBatch) -> ToriiResult<()> {
         let mut transfers: Vec<TransferData> = Vec::with_capacity(envelopes.len());
         let mut approvals: Vec<ApprovalData> = Vec::with_capacity(envelopes.len());
         let mut inserted_transfers: u64 = 0;
@@ -258,6 +388,8 @@ impl Sink for Erc20Sink {
                         block_number: transfer.block_number,
                         tx_hash: transfer.transaction_hash,
                         timestamp,
+                        event_index: envelope.meta.event_index,
+                        provenance: envelope.provenance.clone(),
                     });
                 }
             }
@@ -274,6 +406,7 @@ impl Sink for Erc20Sink {
                         block_number: approval.block_number,
                         tx_hash: approval.transaction_hash,
                         timestamp,
+                        provenance: envelope.provenance.clone(),
                     });
                 }
             }
@@ -353,7 +486,7 @@ impl Sink for Erc20Sink {
                         error = %e,
                         "Failed to batch insert transfers"
                     );
-                    return Err(e);
+                    return Err(e.into());
                 }
             };
             ::metrics::histogram!("torii_erc20_sink_insert_transfers_duration_seconds")
@@ -370,8 +503,14 @@ impl Sink for Erc20Sink {
                     "Batch inserted transfers"
                 );
 
-                // Update balances if balance tracking is enabled
-                if let Some(ref fetcher) = self.balance_fetcher {
+                // Update balances if balance tracking is enabled (skipped in index-only mode)
+                if let Some(fetcher) = self
+                    .balance_fetcher
+                    .as_ref()
+                    .filter(|_| self.tracks_balances())
+                {
+                    // Only the sides of the transfers within the balance scope
+                    let transfers = self.balance_scope.balance_transfers(&transfers);
                     // Step 1: Check which balances need adjustment (would go negative)
                     let check_balances_start = std::time::Instant::now();
                     let (adjustment_requests, balance_snapshot) = match self
@@ -441,10 +580,18 @@ impl Sink for Erc20Sink {
                     }
                     ::metrics::histogram!("torii_erc20_sink_apply_balances_duration_seconds")
                         .record(apply_balances_start.elapsed().as_secs_f64());
+
+                    if batch.is_live(LIVE_THRESHOLD_BLOCKS) {
+                        self.broadcast_balances(&transfers).await;
+                    }
                 }
 
+                self.record_supply_changes(&transfers).await;
+                self.record_volume(&transfers).await;
+
                 // Only broadcast to real-time subscribers when near chain head
                 let is_live = batch.is_live(LIVE_THRESHOLD_BLOCKS);
+                self.record_token_prices(&transfers, is_live).await;
                 if is_live {
                     // Publish transfer events
                     for transfer in &transfers {
@@ -456,12 +603,16 @@ impl Sink for Erc20Sink {
                             block_number: transfer.block_number,
                             tx_hash: transfer.tx_hash.to_bytes_be().to_vec(),
                             timestamp: transfer.timestamp.unwrap_or(0),
+                            provenance: None,
+                            amount_usd: None,
                         };
 
                         // Publish to EventBus (simple clients)
                         if let Some(event_bus) = &self.event_bus {
                             let mut buf = Vec::new();
-                            proto_transfer.encode(&mut buf)?;
+                            proto_transfer
+                                .encode(&mut buf)
+                                .map_err(anyhow::Error::from)?;
                             let any = Any {
                                 type_url: "type.googleapis.com/torii.sinks.erc20.Transfer"
                                     .to_string(),
@@ -499,7 +650,7 @@ impl Sink for Erc20Sink {
                         error = %e,
                         "Failed to batch insert approvals"
                     );
-                    return Err(e);
+                    return Err(e.into());
                 }
             };
             ::metrics::histogram!("torii_erc20_sink_insert_approvals_duration_seconds")
@@ -529,12 +680,15 @@ impl Sink for Erc20Sink {
                             block_number: approval.block_number,
                             tx_hash: approval.tx_hash.to_bytes_be().to_vec(),
                             timestamp: approval.timestamp.unwrap_or(0),
+                            provenance: None,
                         };
 
                         // Publish to EventBus (simple clients)
                         if let Some(event_bus) = &self.event_bus {
                             let mut buf = Vec::new();
-                            proto_approval.encode(&mut buf)?;
+                            proto_approval
+                                .encode(&mut buf)
+                                .map_err(anyhow::Error::from)?;
                             let any = Any {
                                 type_url: "type.googleapis.com/torii.sinks.erc20.Approval"
                                     .to_string(),
@@ -576,6 +730,25 @@ impl Sink for Erc20Sink {
         Ok(())
     }
 
+    async fn indexed_block(&self) -> ToriiResult<Option<u64>> {
+        // Block of the latest stored transfer
+        Ok(self.storage.get_latest_block().await?)
+    }
+
+    async fn on_idle(&self) -> ToriiResult<()> {
+        if !self.maintenance.try_start() {
+            return Ok(());
+        }
+        let start = std::time::Instant::now();
+        self.storage.run_maintenance().await?;
+        tracing::info!(
+            target: "torii_erc20::sink",
+            elapsed_ms = start.elapsed().as_millis() as u64,
+            "Ran storage maintenance"
+        );
+        Ok(())
+    }
+
     fn topics(&self) -> Vec<TopicInfo> {
         vec![
             TopicInfo::new(
@@ -587,7 +760,8 @@ impl Sink for Erc20Sink {
                     "wallet".to_string(),
                 ],
                 "ERC20 token transfers. Use 'wallet' filter for from OR to matching.",
-            ),
+            )
+            .with_message_type("torii.sinks.erc20.Transfer"),
             TopicInfo::new(
                 "erc20.approval",
                 vec![
@@ -597,22 +771,304 @@ impl Sink for Erc20Sink {
                     "account".to_string(),
                 ],
                 "ERC20 token approvals. Use 'account' filter for owner OR spender matching.",
-            ),
+            )
+            .with_message_type("torii.sinks.erc20.Approval"),
             TopicInfo::new(
                 "erc20.metadata",
                 vec!["token".to_string()],
                 "ERC20 token metadata updates (registered/updated token attributes).",
+            )
+            .with_message_type("torii.sinks.erc20.TokenMetadataEntry"),
+        ]
+    }
+
+    fn tables(&self) -> Vec<TableSchema> {
+        vec![
+            TableSchema::new(
+                "transfers",
+                "One row per ERC20 Transfer event.",
+                vec![
+                    ColumnSchema::new("id", "i64"),
+                    ColumnSchema::new("token", "felt"),
+                    ColumnSchema::new("from_addr", "felt"),
+                    ColumnSchema::new("to_addr", "felt"),
+                    ColumnSchema::new("amount", "u256"),
+                    ColumnSchema::new("block_number", "u64"),
+                    ColumnSchema::new("tx_hash", "felt"),
+                    ColumnSchema::new("timestamp", "i64").nullable(),
+                ],
+            ),
+            TableSchema::new(
+                "approvals",
+                "One row per ERC20 Approval event.",
+                vec![
+                    ColumnSchema::new("id", "i64"),
+                    ColumnSchema::new("token", "felt"),
+                    ColumnSchema::new("owner", "felt"),
+                    ColumnSchema::new("spender", "felt"),
+                    ColumnSchema::new("amount", "u256"),
+                    ColumnSchema::new("block_number", "u64"),
+                    ColumnSchema::new("tx_hash", "felt"),
+                    ColumnSchema::new("timestamp", "i64").nullable(),
+                ],
+            ),
+            TableSchema::new(
+                "allowances",
+                "Current allowance per (token, owner, spender): the latest approval.",
+                vec![
+                    ColumnSchema::new("id", "i64"),
+                    ColumnSchema::new("token", "felt"),
+                    ColumnSchema::new("owner", "felt"),
+                    ColumnSchema::new("spender", "felt"),
+                    ColumnSchema::new("amount", "u256"),
+                    ColumnSchema::new("block_number", "u64"),
+                    ColumnSchema::new("tx_hash", "felt"),
+                    ColumnSchema::new("timestamp", "i64").nullable(),
+                ],
+            ),
+            TableSchema::new(
+                "balances",
+                "Current balance per (token, wallet).",
+                vec![
+                    ColumnSchema::new("id", "i64"),
+                    ColumnSchema::new("token", "felt"),
+                    ColumnSchema::new("wallet", "felt"),
+                    ColumnSchema::new("balance", "u256"),
+                    ColumnSchema::new("last_block", "u64"),
+                    ColumnSchema::new("last_tx_hash", "felt"),
+                    ColumnSchema::new("updated_at", "i64").nullable(),
+                ],
+            ),
+            TableSchema::new(
+                "token_supply_history",
+                "Supply per (token, block) with mints (from zero) or burns (to zero).",
+                vec![
+                    ColumnSchema::new("token", "felt"),
+                    ColumnSchema::new("block_number", "u64"),
+                    ColumnSchema::new("minted", "u256"),
+                    ColumnSchema::new("burned", "u256"),
+                    ColumnSchema::new("total_minted", "u256"),
+                    ColumnSchema::new("total_burned", "u256"),
+                    ColumnSchema::new("circulating", "u256"),
+                ],
+            ),
+            TableSchema::new(
+                "token_volume",
+                "Transfer rollups per (token, interval, bucket_start); interval is 'hour' or 'day'.",
+                vec![
+                    ColumnSchema::new("token", "felt"),
+                    ColumnSchema::new("interval", "text"),
+                    ColumnSchema::new("bucket_start", "i64"),
+                    ColumnSchema::new("transfer_count", "u64"),
+                    ColumnSchema::new("volume", "u256"),
+                    ColumnSchema::new("unique_senders", "u64"),
+                    ColumnSchema::new("unique_receivers", "u64"),
+                ],
+            ),
+            TableSchema::new(
+                "token_metadata",
+                "Token attributes fetched from the contract.",
+                vec![
+                    ColumnSchema::new("token", "felt"),
+                    ColumnSchema::new("name", "text").nullable(),
+                    ColumnSchema::new("symbol", "text").nullable(),
+                    ColumnSchema::new("decimals", "u64").nullable(),
+                    ColumnSchema::new("total_supply", "u256").nullable(),
+                ],
             ),
         ]
     }
 
     fn build_routes(&self) -> Router {
-        // No custom HTTP routes for now
+        let state = Erc20ApiState {
+            storage: self.storage.clone(),
+        };
+
         Router::new()
+            .route(
+                "/erc20/transfers/export",
+                get(api::transfers_export_handler),
+            )
+            .with_state(state)
     }
 }
 
 impl Erc20Sink {
+    /// Pushes the balances changed by `transfers` to the balance subscribers.
+    ///
+    /// Failures are logged; the transfers are already stored.
+    async fn broadcast_balances(&self, transfers: &[TransferData]) {
+        let Some(grpc_service) = self
+            .grpc_service
+            .as_ref()
+            .filter(|service| service.has_balance_subscribers())
+        else {
+            return;
+        };
+
+        let changes = balance_changes(transfers);
+        let pairs: Vec<_> = changes.iter().map(|c| (c.token, c.account)).collect();
+        let balances = match self.storage.get_balances_batch(&pairs).await {
+            Ok(balances) => balances,
+            Err(e) => {
+                tracing::warn!(
+                    target: "torii_erc20::sink",
+                    error = %e,
+                    "Failed to read balances for balance subscribers"
+                );
+                return;
+            }
+        };
+
+        let timestamp = chrono::Utc::now().timestamp();
+        for change in changes {
+            let balance = balances
+                .get(&(change.token, change.account))
+                .copied()
+                .unwrap_or(U256::from(0u64));
+            let (delta, decreased) = change.delta();
+            grpc_service.broadcast_balance(proto::BalanceUpdate {
+                token: change.token.to_bytes_be().to_vec(),
+                account: change.account.to_bytes_be().to_vec(),
+                balance: u256_to_bytes(balance),
+                delta: u256_to_bytes(delta),
+                decreased,
+                block_number: change.block_number,
+                tx_hash: change.tx_hash.to_bytes_be().to_vec(),
+                timestamp,
+                shutdown: None,
+            });
+        }
+    }
+
+    /// Folds the mints and burns of `transfers` into the token supply.
+    ///
+    /// Failures are logged; the transfers are already stored.
+    async fn record_supply_changes(&self, transfers: &[TransferData]) {
+        let changes = supply_changes(transfers);
+        if changes.is_empty() {
+            return;
+        }
+        let start = std::time::Instant::now();
+        match self.storage.apply_supply_changes(&changes).await {
+            Ok(snapshots) => {
+                ::metrics::counter!("torii_erc20_supply_snapshots_total")
+                    .increment(snapshots.len() as u64);
+                tracing::debug!(
+                    target: "torii_erc20::sink",
+                    count = snapshots.len(),
+                    "Recorded supply changes"
+                );
+            }
+            Err(e) => {
+                tracing::error!(
+                    target: "torii_erc20::sink",
+                    error = %e,
+                    "Failed to record supply changes"
+                );
+            }
+        }
+        ::metrics::histogram!("torii_erc20_sink_supply_duration_seconds")
+            .record(start.elapsed().as_secs_f64());
+    }
+
+    /// Adds `transfers` to the hourly and daily volume rollups.
+    ///
+    /// Failures are logged; the transfers are already stored.
+    async fn record_volume(&self, transfers: &[TransferData]) {
+        let deltas = volume_deltas(transfers);
+        if deltas.is_empty() {
+            return;
+        }
+        let start = std::time::Instant::now();
+        match self.storage.apply_volume_deltas(&deltas).await {
+            Ok(buckets) => {
+                ::metrics::counter!("torii_erc20_volume_buckets_updated_total")
+                    .increment(buckets as u64);
+            }
+            Err(e) => {
+                tracing::error!(
+                    target: "torii_erc20::sink",
+                    error = %e,
+                    "Failed to record volume rollups"
+                );
+            }
+        }
+        ::metrics::histogram!("torii_erc20_sink_volume_duration_seconds")
+            .record(start.elapsed().as_secs_f64());
+    }
+
+    /// Prices the transferred tokens for block windows not priced yet.
+    ///
+    /// Failures are logged and retried on the next batch touching the token.
+    async fn record_token_prices(&self, transfers: &[TransferData], is_live: bool) {
+        let Some(feed) = &self.price_feed else {
+            return;
+        };
+        if !is_live && !feed.supports_historical() {
+            return;
+        }
+
+        let window = self.price_window_blocks;
+        // Block window -> (latest block in the batch, tokens to price)
+        let mut pending: BTreeMap<u64, (u64, HashSet<Felt>)> = BTreeMap::new();
+        {
+            let priced = self.priced_windows.lock().await;
+            for transfer in transfers {
+                let block_window = transfer.block_number / window * window;
+                if priced
+                    .get(&transfer.token)
+                    .is_some_and(|priced_window| *priced_window >= block_window)
+                {
+                    continue;
+                }
+                let (block_number, tokens) = pending
+                    .entry(block_window)
+                    .or_insert_with(|| (transfer.block_number, HashSet::new()));
+                *block_number = (*block_number).max(transfer.block_number);
+                tokens.insert(transfer.token);
+            }
+        }
+
+        for (block_window, (block_number, tokens)) in pending {
+            let tokens = tokens.into_iter().collect::<Vec<_>>();
+            let prices = match feed.fetch_prices(&tokens, block_number).await {
+                Ok(prices) => prices,
+                Err(e) => {
+                    tracing::warn!(
+                        target: "torii_erc20::sink",
+                        feed = feed.name(),
+                        block = block_number,
+                        error = %e,
+                        "Failed to fetch token prices"
+                    );
+                    continue;
+                }
+            };
+            if let Err(e) = self
+                .storage
+                .upsert_token_prices(block_window, block_number, &prices, feed.name())
+                .await
+            {
+                tracing::warn!(
+                    target: "torii_erc20::sink",
+                    error = %e,
+                    "Failed to store token prices"
+                );
+                continue;
+            }
+            ::metrics::counter!("torii_erc20_token_prices_recorded_total")
+                .increment(prices.len() as u64);
+
+            // Tokens without a price are not retried within the window either.
+            let mut priced = self.priced_windows.lock().await;
+            for token in tokens {
+                let priced_window = priced.entry(token).or_insert(block_window);
+                *priced_window = (*priced_window).max(block_window);
+            }
+        }
+    }
+
     /// Enable background metadata commands.
     pub fn with_metadata_pipeline(
         mut self,
@@ -624,3 +1080,55 @@ impl Erc20Sink {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::Erc20Storage;
+    use torii::etl::sink::MultiSink;
+
+    fn transfer(token: Felt, tx_hash: u64) -> Envelope {
+        let body = DecodedTransfer {
+            from: Felt::from(0x1_u64),
+            to: Felt::from(0x2_u64),
+            amount: U256::from(10_u64),
+            token,
+            block_number: 1,
+            transaction_hash: Felt::from(tx_hash),
+        };
+        Envelope::from_body(format!("transfer-{tx_hash}"), body, HashMap::new())
+            .with_from_address(token)
+    }
+
+    #[tokio::test]
+    async fn multi_sink_routes_transfers_by_contract_filter() {
+        let token_a = Felt::from(0xa_u64);
+        let token_b = Felt::from(0xb_u64);
+        let storage_a = Arc::new(Erc20Storage::new(":memory:").await.unwrap());
+        let storage_rest = Arc::new(Erc20Storage::new(":memory:").await.unwrap());
+        let sink_a = Erc20Sink::new(storage_a.clone())
+            .with_contract_filter(SinkContractFilter::new().allow_contract(token_a));
+        let sink_rest = Erc20Sink::new(storage_rest.clone())
+            .with_contract_filter(SinkContractFilter::new().deny_contract(token_a));
+
+        let multi_sink = MultiSink::new(vec![Arc::new(sink_a), Arc::new(sink_rest)]);
+        let envelopes = vec![
+            transfer(token_a, 1),
+            transfer(token_b, 2),
+            transfer(token_a, 3),
+        ];
+        multi_sink
+            .process(&envelopes, &ExtractionBatch::empty())
+            .await
+            .unwrap();
+
+        assert_eq!(storage_a.get_transfer_count().await.unwrap(), 2);
+        assert_eq!(storage_rest.get_transfer_count().await.unwrap(), 1);
+        let routed = storage_rest
+            .get_transfers_by_tx(Felt::from(2_u64))
+            .await
+            .unwrap();
+        assert_eq!(routed.len(), 1);
+        assert_eq!(routed[0].token, token_b);
+    }
+}
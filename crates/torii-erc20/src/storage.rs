@@ -17,9 +17,106 @@ use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 use tokio_postgres::types::ToSql as PgToSql;
 use tokio_postgres::{Client, NoTls};
-use torii_common::{blob_to_felt, blob_to_u256, felt_to_blob, u256_to_blob};
+use torii::etl::migrations::{self, Migration};
+use torii::etl::Provenance;
+use torii_common::{
+    apply_sqlite_encryption, blob_to_felt, blob_to_u256, felt_to_blob, u256_to_blob, FieldMask,
+    ReplayParam, ReplayRange, SQLITE_MAINTENANCE_SQL,
+};
 
 use crate::balance_fetcher::BalanceFetchRequest;
+use crate::price_feed::TokenPrice;
+use crate::supply::{fold_supply_changes, SupplyChange, SupplySnapshot};
+use crate::volume::{VolumeBucket, VolumeDelta, VolumeInterval};
+
+/// Migration component name recorded in `schema_version`
+const MIGRATION_COMPONENT: &str = "erc20";
+
+/// Embedded schema migrations
+const SQLITE_MIGRATIONS: &[Migration] = &[
+    Migration::new(
+        1,
+        "initial",
+        include_str!("../migrations/sqlite/0001_initial.sql"),
+    ),
+    Migration::new(
+        2,
+        "provenance",
+        include_str!("../migrations/sqlite/0002_provenance.sql"),
+    ),
+    Migration::new(
+        3,
+        "allowances",
+        include_str!("../migrations/sqlite/0003_allowances.sql"),
+    ),
+    Migration::new(
+        4,
+        "token_prices",
+        include_str!("../migrations/sqlite/0004_token_prices.sql"),
+    ),
+    Migration::new(
+        5,
+        "token_supply",
+        include_str!("../migrations/sqlite/0005_token_supply.sql"),
+    ),
+    Migration::new(
+        6,
+        "token_volume",
+        include_str!("../migrations/sqlite/0006_token_volume.sql"),
+    ),
+    Migration::new(
+        7,
+        "transfer_event_index",
+        include_str!("../migrations/sqlite/0007_transfer_event_index.sql"),
+    ),
+    Migration::new(
+        8,
+        "balance_watchlist",
+        include_str!("../migrations/sqlite/0008_balance_watchlist.sql"),
+    ),
+];
+const POSTGRES_MIGRATIONS: &[Migration] = &[
+    Migration::new(
+        1,
+        "initial",
+        include_str!("../migrations/postgres/0001_initial.sql"),
+    ),
+    Migration::new(
+        2,
+        "provenance",
+        include_str!("../migrations/postgres/0002_provenance.sql"),
+    ),
+    Migration::new(
+        3,
+        "allowances",
+        include_str!("../migrations/postgres/0003_allowances.sql"),
+    ),
+    Migration::new(
+        4,
+        "token_prices",
+        include_str!("../migrations/postgres/0004_token_prices.sql"),
+    ),
+    Migration::new(
+        5,
+        "token_supply",
+        include_str!("../migrations/postgres/0005_token_supply.sql"),
+    ),
+    Migration::new(
+        6,
+        "token_volume",
+        include_str!("../migrations/postgres/0006_token_volume.sql"),
+    ),
+    Migration::new(
+        7,
+        "transfer_event_index",
+        include_str!("../migrations/postgres/0007_transfer_event_index.sql"),
+    ),
+    Migration::new(
+        8,
+        "balance_watchlist",
+        include_str!("../migrations/postgres/0008_balance_watchlist.sql"),
+    ),
+];
 
 /// Maximum value for U256 (2^256 - 1)
 const U256_MAX: U256 = U256::from_words(u128::MAX, u128::MAX);
@@ -144,7 +241,7 @@ struct AdjustmentInsertRow {
 /// - Malicious or buggy contract minting excessive tokens
 /// - Data corruption in blockchain event data
 /// - Accumulation of many transfers to the same address
-fn safe_u256_add(a: U256, b: U256) -> U256 {
+pub(crate) fn safe_u256_add(a: U256, b: U256) -> U256 {
     // Check if addition would overflow
     // If a > U256_MAX - b, then a + b would overflow
     let max_minus_b = U256_MAX - b;
@@ -188,6 +285,7 @@ enum StorageBackend {
 }
 
 /// Transfer data for batch insertion
+#[derive(Clone)]
 pub struct TransferData {
     pub id: Option<i64>,
     pub token: Felt,
@@ -198,9 +296,20 @@ pub struct TransferData {
     pub block_number: u64,
     pub tx_hash: Felt,
     pub timestamp: Option<i64>,
+    /// Position of the transfer among the events of its transaction
+    pub event_index: Option<u32>,
+    /// Provenance recorded alongside the row (only when provenance tracking is enabled)
+    pub provenance: Option<Provenance>,
+}
+
+impl ReplayRow for TransferData {
+    fn replay_id(&self) -> Option<i64> {
+        self.id
+    }
 }
 
 /// Approval data for batch insertion
+#[derive(Clone)]
 pub struct ApprovalData {
     pub id: Option<i64>,
     pub token: Felt,
@@ -211,6 +320,16 @@ pub struct ApprovalData {
     pub block_number: u64,
     pub tx_hash: Felt,
     pub timestamp: Option<i64>,
+    /// Provenance recorded alongside the row (only when provenance tracking is enabled)
+    pub provenance: Option<Provenance>,
+}
+
+/// Stored provenance of a transfer or approval row
+#[derive(Debug, Clone)]
+pub struct StoredProvenance {
+    pub provenance: Provenance,
+    /// Unix timestamp (ms) when the row was stored
+    pub stored_at: i64,
 }
 
 /// Cursor for paginated transfer queries
@@ -237,6 +356,32 @@ pub struct BalanceData {
     pub last_tx_hash: Felt,
 }
 
+/// Holding of a wallet in one token, with the token metadata and latest price
+#[derive(Debug, Clone)]
+pub struct PortfolioEntry {
+    pub token: Felt,
+    pub balance: U256,
+    pub last_block: u64,
+    pub name: Option<String>,
+    pub symbol: Option<String>,
+    pub decimals: Option<u8>,
+    /// Latest recorded USD price of the token
+    pub price_usd: Option<f64>,
+}
+
+/// Current allowance of a spender over an owner's tokens (latest approval)
+#[derive(Debug, Clone)]
+pub struct AllowanceData {
+    pub token: Felt,
+    pub owner: Felt,
+    pub spender: Felt,
+    /// Amount as U256 (zero once revoked)
+    pub amount: U256,
+    pub block_number: u64,
+    pub tx_hash: Felt,
+    pub timestamp: Option<i64>,
+}
+
 /// Balance adjustment record for audit trail
 #[derive(Debug, Clone)]
 pub struct BalanceAdjustment {
@@ -345,106 +490,8 @@ impl Erc20Storage {
                 .first()
                 .expect("PostgreSQL connection pool must contain at least one client")
                 .clone();
-            let client = schema_client.lock().await;
-            client
-                .batch_execute(
-                    r"
-                CREATE SCHEMA IF NOT EXISTS erc20;
-
-                CREATE TABLE IF NOT EXISTS erc20.transfers (
-                    id BIGSERIAL PRIMARY KEY,
-                    token BYTEA NOT NULL,
-                    from_addr BYTEA NOT NULL,
-                    to_addr BYTEA NOT NULL,
-                    amount BYTEA NOT NULL,
-                    block_number TEXT NOT NULL,
-                    tx_hash BYTEA NOT NULL,
-                    timestamp TEXT DEFAULT (EXTRACT(EPOCH FROM NOW())::TEXT),
-                    UNIQUE(token, tx_hash, from_addr, to_addr)
-                );
-                CREATE INDEX IF NOT EXISTS idx_transfers_token ON erc20.transfers(token);
-                CREATE INDEX IF NOT EXISTS idx_transfers_from ON erc20.transfers(from_addr);
-                CREATE INDEX IF NOT EXISTS idx_transfers_to ON erc20.transfers(to_addr);
-                CREATE INDEX IF NOT EXISTS idx_transfers_block ON erc20.transfers(block_number);
-                CREATE INDEX IF NOT EXISTS idx_transfers_token_block ON erc20.transfers(token, block_number DESC);
-                CREATE INDEX IF NOT EXISTS idx_transfers_from_block ON erc20.transfers(from_addr, block_number DESC);
-                CREATE INDEX IF NOT EXISTS idx_transfers_to_block ON erc20.transfers(to_addr, block_number DESC);
-
-                CREATE TABLE IF NOT EXISTS erc20.approvals (
-                    id BIGSERIAL PRIMARY KEY,
-                    token BYTEA NOT NULL,
-                    owner BYTEA NOT NULL,
-                    spender BYTEA NOT NULL,
-                    amount BYTEA NOT NULL,
-                    block_number TEXT NOT NULL,
-                    tx_hash BYTEA NOT NULL,
-                    timestamp TEXT DEFAULT (EXTRACT(EPOCH FROM NOW())::TEXT),
-                    UNIQUE(token, tx_hash, owner, spender)
-                );
-                CREATE INDEX IF NOT EXISTS idx_approvals_owner ON erc20.approvals(owner, block_number DESC);
-                CREATE INDEX IF NOT EXISTS idx_approvals_spender ON erc20.approvals(spender, block_number DESC);
-                CREATE INDEX IF NOT EXISTS idx_approvals_token ON erc20.approvals(token, block_number DESC);
-
-                CREATE TABLE IF NOT EXISTS erc20.wallet_activity (
-                    id BIGSERIAL PRIMARY KEY,
-                    wallet_address BYTEA NOT NULL,
-                    token BYTEA NOT NULL,
-                    transfer_id BIGINT NOT NULL REFERENCES erc20.transfers(id),
-                    direction TEXT NOT NULL CHECK(direction IN ('sent', 'received', 'both')),
-                    block_number TEXT NOT NULL
-                );
-                CREATE INDEX IF NOT EXISTS idx_wallet_activity_wallet_block ON erc20.wallet_activity(wallet_address, block_number DESC);
-                CREATE INDEX IF NOT EXISTS idx_wallet_activity_wallet_token ON erc20.wallet_activity(wallet_address, token, block_number DESC);
-                CREATE INDEX IF NOT EXISTS idx_wallet_activity_transfer ON erc20.wallet_activity(transfer_id);
-
-                CREATE TABLE IF NOT EXISTS erc20.approval_activity (
-                    id BIGSERIAL PRIMARY KEY,
-                    account_address BYTEA NOT NULL,
-                    token BYTEA NOT NULL,
-                    approval_id BIGINT NOT NULL REFERENCES erc20.approvals(id),
-                    role TEXT NOT NULL CHECK(role IN ('owner', 'spender', 'both')),
-                    block_number TEXT NOT NULL
-                );
-                CREATE INDEX IF NOT EXISTS idx_approval_activity_account_block ON erc20.approval_activity(account_address, block_number DESC);
-                CREATE INDEX IF NOT EXISTS idx_approval_activity_account_token ON erc20.approval_activity(account_address, token, block_number DESC);
-                CREATE INDEX IF NOT EXISTS idx_approval_activity_approval ON erc20.approval_activity(approval_id);
-
-                CREATE TABLE IF NOT EXISTS erc20.balances (
-                    id BIGSERIAL PRIMARY KEY,
-                    token BYTEA NOT NULL,
-                    wallet BYTEA NOT NULL,
-                    balance BYTEA NOT NULL,
-                    last_block TEXT NOT NULL,
-                    last_tx_hash BYTEA NOT NULL,
-                    updated_at TEXT DEFAULT (EXTRACT(EPOCH FROM NOW())::TEXT),
-                    UNIQUE(token, wallet)
-                );
-                CREATE INDEX IF NOT EXISTS idx_balances_token ON erc20.balances(token);
-                CREATE INDEX IF NOT EXISTS idx_balances_wallet ON erc20.balances(wallet);
-
-                CREATE TABLE IF NOT EXISTS erc20.balance_adjustments (
-                    id BIGSERIAL PRIMARY KEY,
-                    token BYTEA NOT NULL,
-                    wallet BYTEA NOT NULL,
-                    computed_balance BYTEA NOT NULL,
-                    actual_balance BYTEA NOT NULL,
-                    adjusted_at_block TEXT NOT NULL,
-                    tx_hash BYTEA NOT NULL,
-                    created_at TEXT DEFAULT (EXTRACT(EPOCH FROM NOW())::TEXT)
-                );
-                CREATE INDEX IF NOT EXISTS idx_adjustments_wallet ON erc20.balance_adjustments(wallet);
-                CREATE INDEX IF NOT EXISTS idx_adjustments_token ON erc20.balance_adjustments(token);
-
-                CREATE TABLE IF NOT EXISTS erc20.token_metadata (
-                    token BYTEA PRIMARY KEY,
-                    name TEXT,
-                    symbol TEXT,
-                    decimals TEXT,
-                    total_supply BYTEA
-                );
-                ",
-                )
-                .await?;
+            let mut client = schema_client.lock().await;
+            migrations::migrate(&mut *client, MIGRATION_COMPONENT, POSTGRES_MIGRATIONS).await?;
 
             tracing::info!(target: "torii_erc20::storage", pool_size, "PostgreSQL storage initialized");
             return Ok(Self {
@@ -456,7 +503,10 @@ impl Erc20Storage {
             });
         }
 
-        let conn = Connection::open(db_path)?;
+        let mut conn = Connection::open(db_path)?;
+        if apply_sqlite_encryption(&conn)? {
+            tracing::info!(target: "torii_erc20::storage", "SQLite storage encrypted with SQLCipher");
+        }
 
         let cache_size_kb = std::env::var("TORII_ERC20_SQLITE_CACHE_SIZE_KB")
             .ok()
@@ -486,7 +536,8 @@ impl Erc20Storage {
 
         // Enable WAL mode + Performance PRAGMAs (critical for production scale)
         conn.execute_batch(&format!(
-            "PRAGMA journal_mode=WAL;
+            "PRAGMA auto_vacuum=INCREMENTAL;
+                 PRAGMA journal_mode=WAL;
                  PRAGMA synchronous={synchronous};
                  PRAGMA foreign_keys=ON;
                  PRAGMA cache_size={};
@@ -508,231 +559,8 @@ impl Erc20Storage {
             "SQLite configured"
         );
 
-        // Create transfers table with BLOB columns for efficient storage
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS transfers (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                token BLOB NOT NULL,
-                from_addr BLOB NOT NULL,
-                to_addr BLOB NOT NULL,
-                amount BLOB NOT NULL,
-                block_number TEXT NOT NULL,
-                tx_hash BLOB NOT NULL,
-                timestamp TEXT DEFAULT (strftime('%s', 'now')),
-                UNIQUE(token, tx_hash, from_addr, to_addr)
-            )",
-            [],
-        )?;
-
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_transfers_token ON transfers(token)",
-            [],
-        )?;
-
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_transfers_from ON transfers(from_addr)",
-            [],
-        )?;
-
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_transfers_to ON transfers(to_addr)",
-            [],
-        )?;
-
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_transfers_block ON transfers(block_number)",
-            [],
-        )?;
-
-        // Composite indexes for efficient queries
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_transfers_token_block ON transfers(token, block_number DESC)",
-            [],
-        )?;
-
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_transfers_from_block ON transfers(from_addr, block_number DESC)",
-            [],
-        )?;
-
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_transfers_to_block ON transfers(to_addr, block_number DESC)",
-            [],
-        )?;
-
-        // Create approvals table
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS approvals (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                token BLOB NOT NULL,
-                owner BLOB NOT NULL,
-                spender BLOB NOT NULL,
-                amount BLOB NOT NULL,
-                block_number TEXT NOT NULL,
-                tx_hash BLOB NOT NULL,
-                timestamp TEXT DEFAULT (strftime('%s', 'now')),
-                UNIQUE(token, tx_hash, owner, spender)
-            )",
-            [],
-        )?;
-
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_approvals_owner ON approvals(owner, block_number DESC)",
-            [],
-        )?;
-
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_approvals_spender ON approvals(spender, block_number DESC)",
-            [],
-        )?;
-
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_approvals_token ON approvals(token, block_number DESC)",
-            [],
-        )?;
-
-        // Metadata table
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS metadata (
-                key TEXT PRIMARY KEY,
-                value TEXT NOT NULL
-            )",
-            [],
-        )?;
-
-        // Wallet activity table - denormalized for efficient wallet queries
-        // Solves the OR query problem: "get all transfers where wallet is sender OR receiver"
-        // Instead of: WHERE from_addr = ? OR to_addr = ? (can only use one index)
-        // We use: JOIN wallet_activity WHERE wallet_address = ? (uses this table's index)
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS wallet_activity (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                wallet_address BLOB NOT NULL,
-                token BLOB NOT NULL,
-                transfer_id INTEGER NOT NULL,
-                direction TEXT NOT NULL CHECK(direction IN ('sent', 'received', 'both')),
-                block_number TEXT NOT NULL,
-                FOREIGN KEY (transfer_id) REFERENCES transfers(id)
-            )",
-            [],
-        )?;
-
-        // Compound index: wallet -> block (optimal for wallet activity queries)
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_wallet_activity_wallet_block
-             ON wallet_activity(wallet_address, block_number DESC)",
-            [],
-        )?;
-
-        // Compound index: wallet -> token -> block (token-specific activity)
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_wallet_activity_wallet_token
-             ON wallet_activity(wallet_address, token, block_number DESC)",
-            [],
-        )?;
-
-        // Index for reverse lookup (transfer -> wallets)
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_wallet_activity_transfer
-             ON wallet_activity(transfer_id)",
-            [],
-        )?;
-
-        // Approval activity table - similar pattern for approvals
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS approval_activity (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                account_address BLOB NOT NULL,
-                token BLOB NOT NULL,
-                approval_id INTEGER NOT NULL,
-                role TEXT NOT NULL CHECK(role IN ('owner', 'spender', 'both')),
-                block_number TEXT NOT NULL,
-                FOREIGN KEY (approval_id) REFERENCES approvals(id)
-            )",
-            [],
-        )?;
-
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_approval_activity_account_block
-             ON approval_activity(account_address, block_number DESC)",
-            [],
-        )?;
-
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_approval_activity_account_token
-             ON approval_activity(account_address, token, block_number DESC)",
-            [],
-        )?;
-
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_approval_activity_approval
-             ON approval_activity(approval_id)",
-            [],
-        )?;
-
-        // Balance tracking tables
-        // Tracks current balance per (token, wallet) pair
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS balances (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                token BLOB NOT NULL,
-                wallet BLOB NOT NULL,
-                balance BLOB NOT NULL,
-                last_block TEXT NOT NULL,
-                last_tx_hash BLOB NOT NULL,
-                updated_at TEXT DEFAULT (strftime('%s', 'now')),
-                UNIQUE(token, wallet)
-            )",
-            [],
-        )?;
-
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_balances_token ON balances(token)",
-            [],
-        )?;
-
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_balances_wallet ON balances(wallet)",
-            [],
-        )?;
-
-        // Balance adjustments table for audit trail
-        // Records when we had to fetch balance from RPC due to inconsistency
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS balance_adjustments (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                token BLOB NOT NULL,
-                wallet BLOB NOT NULL,
-                computed_balance BLOB NOT NULL,
-                actual_balance BLOB NOT NULL,
-                adjusted_at_block TEXT NOT NULL,
-                tx_hash BLOB NOT NULL,
-                created_at TEXT DEFAULT (strftime('%s', 'now'))
-            )",
-            [],
-        )?;
-
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_adjustments_wallet ON balance_adjustments(wallet)",
-            [],
-        )?;
-
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_adjustments_token ON balance_adjustments(token)",
-            [],
-        )?;
-
-        // Token metadata table
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS token_metadata (
-                token BLOB PRIMARY KEY,
-                name TEXT,
-                symbol TEXT,
-                decimals TEXT,
-                total_supply BLOB
-            )",
-            [],
-        )?;
+        // Create or upgrade tables
+        migrations::migrate(&mut conn, MIGRATION_COMPONENT, SQLITE_MIGRATIONS).await?;
 
         tracing::info!(target: "torii_erc20::storage", db_path = %db_path, "Database initialized");
 
@@ -919,6 +747,35 @@ impl Erc20Storage {
         Ok(())
     }
 
+    fn sqlite_insert_provenance_rows(
+        tx: &rusqlite::Transaction<'_>,
+        table: &str,
+        id_col: &str,
+        rows: &[(i64, &Provenance)],
+    ) -> Result<()> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+
+        let stored_at = chrono::Utc::now().timestamp_millis();
+        let mut stmt = tx.prepare_cached(&format!(
+            "INSERT OR IGNORE INTO {table} ({id_col}, extractor, decoder, batch_id, extracted_at, decoded_at, stored_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)"
+        ))?;
+        for (id, provenance) in rows {
+            stmt.execute(params![
+                id,
+                provenance.extractor,
+                provenance.decoder,
+                provenance.batch_id as i64,
+                provenance.extracted_at,
+                provenance.decoded_at,
+                stored_at,
+            ])?;
+        }
+        Ok(())
+    }
+
     /// Insert multiple transfers in a single transaction
     ///
     /// This is significantly faster than inserting one by one because:
@@ -940,10 +797,11 @@ impl Erc20Storage {
 
         {
             let mut transfer_stmt = tx.prepare_cached(
-                "INSERT OR IGNORE INTO transfers (token, from_addr, to_addr, amount, block_number, tx_hash, timestamp)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, COALESCE(?7, strftime('%s', 'now')))",
+                "INSERT OR IGNORE INTO transfers (token, from_addr, to_addr, amount, block_number, tx_hash, timestamp, event_index)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, COALESCE(?7, strftime('%s', 'now')), ?8)",
             )?;
             let mut wallet_activity_rows = Vec::with_capacity(transfers.len() * 2);
+            let mut provenance_rows = Vec::new();
 
             for transfer in transfers {
                 let token_blob = felt_to_blob(transfer.token);
@@ -960,12 +818,16 @@ impl Erc20Storage {
                     transfer.block_number.to_string(),
                     &tx_hash_blob,
                     transfer.timestamp.map(|t| t.to_string()),
+                    transfer.event_index,
                 ])?;
 
                 if rows > 0 {
                     inserted += 1;
                     let transfer_id = tx.last_insert_rowid();
                     let block_number = transfer.block_number.to_string();
+                    if let Some(provenance) = &transfer.provenance {
+                        provenance_rows.push((transfer_id, provenance));
+                    }
 
                     // Insert wallet activity records
                     // This enables O(log n) wallet queries instead of O(n) OR scans
@@ -1013,6 +875,12 @@ impl Erc20Storage {
                 "direction",
                 &wallet_activity_rows,
             )?;
+            Self::sqlite_insert_provenance_rows(
+                &tx,
+                "transfer_provenance",
+                "transfer_id",
+                &provenance_rows,
+            )?;
         }
 
         tx.commit()?;
@@ -1039,7 +907,19 @@ impl Erc20Storage {
                 "INSERT OR IGNORE INTO approvals (token, owner, spender, amount, block_number, tx_hash, timestamp)
                  VALUES (?1, ?2, ?3, ?4, ?5, ?6, COALESCE(?7, strftime('%s', 'now')))",
             )?;
+            // The latest approval wins; approvals within a batch are in chain order.
+            let mut allowance_stmt = tx.prepare_cached(
+                "INSERT INTO allowances (token, owner, spender, amount, block_number, tx_hash, timestamp)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, COALESCE(?7, strftime('%s', 'now')))
+                 ON CONFLICT(token, owner, spender) DO UPDATE SET
+                    amount = excluded.amount,
+                    block_number = excluded.block_number,
+                    tx_hash = excluded.tx_hash,
+                    timestamp = excluded.timestamp
+                 WHERE CAST(excluded.block_number AS INTEGER) >= CAST(allowances.block_number AS INTEGER)",
+            )?;
             let mut approval_activity_rows = Vec::with_capacity(approvals.len() * 2);
+            let mut provenance_rows = Vec::new();
 
             for approval in approvals {
                 let token_blob = felt_to_blob(approval.token);
@@ -1057,11 +937,23 @@ impl Erc20Storage {
                     &tx_hash_blob,
                     approval.timestamp.map(|t| t.to_string()),
                 ])?;
+                allowance_stmt.execute(params![
+                    &token_blob,
+                    &owner_blob,
+                    &spender_blob,
+                    &amount_blob,
+                    approval.block_number.to_string(),
+                    &tx_hash_blob,
+                    approval.timestamp.map(|t| t.to_string()),
+                ])?;
 
                 if rows > 0 {
                     inserted += 1;
                     let approval_id = tx.last_insert_rowid();
                     let block_number = approval.block_number.to_string();
+                    if let Some(provenance) = &approval.provenance {
+                        provenance_rows.push((approval_id, provenance));
+                    }
 
                     // Insert approval activity records
                     if approval.owner != Felt::ZERO
@@ -1108,6 +1000,12 @@ impl Erc20Storage {
                 "role",
                 &approval_activity_rows,
             )?;
+            Self::sqlite_insert_provenance_rows(
+                &tx,
+                "approval_provenance",
+                "approval_id",
+                &provenance_rows,
+            )?;
         }
 
         tx.commit()?;
@@ -1137,14 +1035,48 @@ impl Erc20Storage {
         block_to: Option<u64>,
         cursor: Option<TransferCursor>,
         limit: u32,
+    ) -> Result<(Vec<TransferData>, Option<TransferCursor>)> {
+        self.get_transfers_projected(
+            wallet,
+            from,
+            to,
+            tokens,
+            direction,
+            block_from,
+            block_to,
+            cursor,
+            limit,
+            &FieldMask::all(),
+        )
+        .await
+    }
+
+    /// Get filtered transfers, only selecting the columns of the fields in `mask`
+    ///
+    /// Same filters as [`Self::get_transfers_filtered`]. Unselected fields are left zeroed
+    /// (`id` and `block_number` are always selected for pagination).
+    #[allow(clippy::too_many_arguments)]
+    pub async fn get_transfers_projected(
+        &self,
+        wallet: Option<Felt>,
+        from: Option<Felt>,
+        to: Option<Felt>,
+        tokens: &[Felt],
+        direction: TransferDirection,
+        block_from: Option<u64>,
+        block_to: Option<u64>,
+        cursor: Option<TransferCursor>,
+        limit: u32,
+        mask: &FieldMask,
     ) -> Result<(Vec<TransferData>, Option<TransferCursor>)> {
         if self.backend == StorageBackend::Postgres {
             return self
                 .pg_get_transfers_filtered(
-                    wallet, from, to, tokens, direction, block_from, block_to, cursor, limit,
+                    wallet, from, to, tokens, direction, block_from, block_to, cursor, limit, mask,
                 )
                 .await;
         }
+        let columns = transfer_columns(mask, "X''", "NULL");
         let conn = self.conn.lock().unwrap();
 
         // Build dynamic query based on filters
@@ -1153,12 +1085,12 @@ impl Erc20Storage {
 
         if let Some(wallet_addr) = wallet {
             // Use wallet_activity table for efficient OR queries
-            query.push_str(
-                "SELECT DISTINCT t.id, t.token, t.from_addr, t.to_addr, t.amount, t.block_number, t.tx_hash, t.timestamp
+            query.push_str(&format!(
+                "SELECT DISTINCT {columns}
                  FROM wallet_activity wa
                  JOIN transfers t ON wa.transfer_id = t.id
-                 WHERE wa.wallet_address = ?",
-            );
+                 WHERE wa.wallet_address = ?"
+            ));
             params_vec.push(Box::new(felt_to_blob(wallet_addr)));
 
             // Apply direction filter
@@ -1182,11 +1114,11 @@ impl Erc20Storage {
             }
         } else {
             // Standard query without wallet optimization
-            query.push_str(
-                "SELECT t.id, t.token, t.from_addr, t.to_addr, t.amount, t.block_number, t.tx_hash, t.timestamp
+            query.push_str(&format!(
+                "SELECT {columns}
                  FROM transfers t
-                 WHERE 1=1",
-            );
+                 WHERE 1=1"
+            ));
 
             if let Some(from_addr) = from {
                 query.push_str(" AND t.from_addr = ?");
@@ -1252,6 +1184,8 @@ impl Erc20Storage {
                 block_number: block_number_str.parse::<u64>().unwrap_or(0),
                 tx_hash: blob_to_felt(&tx_hash_bytes),
                 timestamp: timestamp_str.and_then(|s| s.parse::<i64>().ok()),
+                event_index: None,
+                provenance: None,
             })
         })?;
 
@@ -1270,6 +1204,52 @@ impl Erc20Storage {
         Ok((transfers, next_cursor))
     }
 
+    /// Get a page of the transfers of `range` after row `after_id`, for historical replay
+    ///
+    /// Pages by row id (ascending) rather than block number, so that a replay follows the
+    /// order in which transfers were indexed and stays stable while new rows are appended.
+    pub async fn get_transfers_for_replay(
+        &self,
+        range: &ReplayRange,
+        after_id: Option<i64>,
+    ) -> Result<Vec<TransferData>> {
+        if self.backend == StorageBackend::Postgres {
+            return self.pg_get_transfers_for_replay(range, after_id).await;
+        }
+        let conn = self.conn.lock().unwrap();
+
+        let mut query = String::from(
+            "SELECT t.id, t.token, t.from_addr, t.to_addr, t.amount, t.block_number, t.tx_hash, t.timestamp
+             FROM transfers t",
+        );
+        let mut params = Vec::new();
+        query.push_str(&range.page_clause(after_id, |param| {
+            params.push(param);
+            "?".to_string()
+        }));
+
+        let mut stmt = conn.prepare_cached(&query)?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(&params), |row| {
+            let block_number_str: String = row.get(5)?;
+            let timestamp_str: Option<String> = row.get(7)?;
+
+            Ok(TransferData {
+                id: Some(row.get(0)?),
+                token: blob_to_felt(&row.get::<_, Vec<u8>>(1)?),
+                from: blob_to_felt(&row.get::<_, Vec<u8>>(2)?),
+                to: blob_to_felt(&row.get::<_, Vec<u8>>(3)?),
+                amount: blob_to_u256(&row.get::<_, Vec<u8>>(4)?),
+                block_number: block_number_str.parse::<u64>().unwrap_or(0),
+                tx_hash: blob_to_felt(&row.get::<_, Vec<u8>>(6)?),
+                timestamp: timestamp_str.and_then(|s| s.parse::<i64>().ok()),
+                event_index: None,
+                provenance: None,
+            })
+        })?;
+
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
     /// Get filtered approvals with cursor-based pagination
     ///
     /// Supports:
@@ -1394,6 +1374,7 @@ impl Erc20Storage {
                 block_number: block_number_str.parse::<u64>().unwrap_or(0),
                 tx_hash: blob_to_felt(&tx_hash_bytes),
                 timestamp: timestamp_str.and_then(|s| s.parse::<i64>().ok()),
+                provenance: None,
             })
         })?;
 
@@ -1412,6 +1393,163 @@ impl Erc20Storage {
         Ok((approvals, next_cursor))
     }
 
+    /// Get the transfers of a transaction, in event order
+    ///
+    /// Transfers stored before event indexes were recorded come last, in insertion order.
+    pub async fn get_transfers_by_tx(&self, tx_hash: Felt) -> Result<Vec<TransferData>> {
+        let tx_hash_blob = felt_to_blob(tx_hash);
+
+        if self.backend == StorageBackend::Postgres {
+            let client = self.pg_client().await?;
+            let rows = client
+                .query(
+                    "SELECT id, token, from_addr, to_addr, amount, block_number, tx_hash, timestamp, event_index
+                     FROM erc20.transfers
+                     WHERE tx_hash = $1
+                     ORDER BY event_index NULLS LAST, id",
+                    &[&tx_hash_blob],
+                )
+                .await?;
+            return Ok(rows
+                .into_iter()
+                .map(|row| TransferData {
+                    id: Some(row.get::<usize, i64>(0)),
+                    token: blob_to_felt(&row.get::<usize, Vec<u8>>(1)),
+                    from: blob_to_felt(&row.get::<usize, Vec<u8>>(2)),
+                    to: blob_to_felt(&row.get::<usize, Vec<u8>>(3)),
+                    amount: blob_to_u256(&row.get::<usize, Vec<u8>>(4)),
+                    block_number: row.get::<usize, String>(5).parse::<u64>().unwrap_or(0),
+                    tx_hash: blob_to_felt(&row.get::<usize, Vec<u8>>(6)),
+                    timestamp: row
+                        .get::<usize, Option<String>>(7)
+                        .and_then(|s| s.parse::<i64>().ok()),
+                    event_index: row.get::<usize, Option<i64>>(8).map(|i| i as u32),
+                    provenance: None,
+                })
+                .collect());
+        }
+
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare_cached(
+            "SELECT id, token, from_addr, to_addr, amount, block_number, tx_hash, timestamp, event_index
+             FROM transfers
+             WHERE tx_hash = ?1
+             ORDER BY event_index IS NULL, event_index, id",
+        )?;
+        let rows = stmt.query_map(params![&tx_hash_blob], |row| {
+            let block_number_str: String = row.get(5)?;
+            let timestamp_str: Option<String> = row.get(7)?;
+
+            Ok(TransferData {
+                id: Some(row.get(0)?),
+                token: blob_to_felt(&row.get::<_, Vec<u8>>(1)?),
+                from: blob_to_felt(&row.get::<_, Vec<u8>>(2)?),
+                to: blob_to_felt(&row.get::<_, Vec<u8>>(3)?),
+                amount: blob_to_u256(&row.get::<_, Vec<u8>>(4)?),
+                block_number: block_number_str.parse::<u64>().unwrap_or(0),
+                tx_hash: blob_to_felt(&row.get::<_, Vec<u8>>(6)?),
+                timestamp: timestamp_str.and_then(|s| s.parse::<i64>().ok()),
+                event_index: row.get::<_, Option<u32>>(8)?,
+                provenance: None,
+            })
+        })?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+    }
+
+    /// Get recorded provenance for transfers by row id
+    ///
+    /// Rows stored without provenance tracking are absent from the result.
+    pub async fn get_transfer_provenance(
+        &self,
+        ids: &[i64],
+    ) -> Result<HashMap<i64, StoredProvenance>> {
+        self.get_provenance("transfer_provenance", "transfer_id", ids)
+            .await
+    }
+
+    /// Get recorded provenance for approvals by row id
+    ///
+    /// Rows stored without provenance tracking are absent from the result.
+    pub async fn get_approval_provenance(
+        &self,
+        ids: &[i64],
+    ) -> Result<HashMap<i64, StoredProvenance>> {
+        self.get_provenance("approval_provenance", "approval_id", ids)
+            .await
+    }
+
+    async fn get_provenance(
+        &self,
+        table: &str,
+        id_col: &str,
+        ids: &[i64],
+    ) -> Result<HashMap<i64, StoredProvenance>> {
+        let mut out = HashMap::new();
+        if ids.is_empty() {
+            return Ok(out);
+        }
+
+        if self.backend == StorageBackend::Postgres {
+            let client = self.pg_client().await?;
+            let rows = client
+                .query(
+                    &format!(
+                        "SELECT {id_col}, extractor, decoder, batch_id, extracted_at, decoded_at, stored_at
+                         FROM erc20.{table}
+                         WHERE {id_col} = ANY($1::bigint[])"
+                    ),
+                    &[&ids],
+                )
+                .await?;
+            for row in rows {
+                out.insert(
+                    row.get::<usize, i64>(0),
+                    StoredProvenance {
+                        provenance: Provenance {
+                            extractor: row.get(1),
+                            decoder: row.get(2),
+                            batch_id: row.get::<usize, i64>(3) as u64,
+                            extracted_at: row.get(4),
+                            decoded_at: row.get(5),
+                        },
+                        stored_at: row.get(6),
+                    },
+                );
+            }
+            return Ok(out);
+        }
+
+        let conn = self.conn.lock().unwrap();
+        for chunk in ids.chunks(SQLITE_MAX_BIND_VARS) {
+            let placeholders = vec!["?"; chunk.len()].join(",");
+            let mut stmt = conn.prepare_cached(&format!(
+                "SELECT {id_col}, extractor, decoder, batch_id, extracted_at, decoded_at, stored_at
+                 FROM {table}
+                 WHERE {id_col} IN ({placeholders})"
+            ))?;
+            let rows = stmt.query_map(params_from_iter(chunk.iter()), |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    StoredProvenance {
+                        provenance: Provenance {
+                            extractor: row.get(1)?,
+                            decoder: row.get(2)?,
+                            batch_id: row.get::<_, i64>(3)? as u64,
+                            extracted_at: row.get(4)?,
+                            decoded_at: row.get(5)?,
+                        },
+                        stored_at: row.get(6)?,
+                    },
+                ))
+            })?;
+            for row in rows {
+                let (id, provenance) = row?;
+                out.insert(id, provenance);
+            }
+        }
+        Ok(out)
+    }
+
     /// Get transfer count
     pub async fn get_transfer_count(&self) -> Result<u64> {
         if self.backend == StorageBackend::Postgres {
@@ -1460,9 +1598,21 @@ impl Erc20Storage {
         Ok(block.and_then(|b| b.parse::<u64>().ok()))
     }
 
-    // ===== Balance Tracking Methods =====
-
-    /// Get current balance for a wallet/token pair
+    /// Runs SQLite maintenance: incremental vacuum, `ANALYZE` and `PRAGMA optimize`.
+    ///
+    /// A no-op on PostgreSQL, which relies on autovacuum.
+    pub async fn run_maintenance(&self) -> Result<()> {
+        if self.backend == StorageBackend::Postgres {
+            return Ok(());
+        }
+        let conn = self.conn.lock().unwrap();
+        conn.execute_batch(SQLITE_MAINTENANCE_SQL)?;
+        Ok(())
+    }
+
+    // ===== Balance Tracking Methods =====
+
+    /// Get current balance for a wallet/token pair
     pub async fn get_balance(&self, token: Felt, wallet: Felt) -> Result<Option<U256>> {
         if self.backend == StorageBackend::Postgres {
             return self.pg_get_balance(token, wallet).await;
@@ -1593,6 +1743,146 @@ impl Erc20Storage {
         Ok((out, next_cursor))
     }
 
+    /// Get the non-zero balances of a wallet across all tokens, in token order.
+    ///
+    /// Balances are joined with the token metadata and the latest recorded token price
+    /// in a single query.
+    pub async fn get_portfolio(&self, wallet: Felt) -> Result<Vec<PortfolioEntry>> {
+        if self.backend == StorageBackend::Postgres {
+            return self.pg_get_portfolio(wallet).await;
+        }
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare_cached(
+            "SELECT b.token, b.balance, b.last_block, m.name, m.symbol, m.decimals,
+                    (SELECT p.price_usd FROM token_prices p
+                     WHERE p.token = b.token
+                     ORDER BY p.block_window DESC
+                     LIMIT 1)
+             FROM balances b
+             LEFT JOIN token_metadata m ON m.token = b.token
+             WHERE b.wallet = ?1
+             ORDER BY b.token ASC",
+        )?;
+        let rows = stmt.query_map(params![felt_to_blob(wallet)], |row| {
+            let token_bytes: Vec<u8> = row.get(0)?;
+            let balance_bytes: Vec<u8> = row.get(1)?;
+            let last_block: String = row.get(2)?;
+            let decimals: Option<String> = row.get(5)?;
+            Ok(PortfolioEntry {
+                token: blob_to_felt(&token_bytes),
+                balance: blob_to_u256(&balance_bytes),
+                last_block: last_block.parse::<u64>().unwrap_or(0),
+                name: row.get(3)?,
+                symbol: row.get(4)?,
+                decimals: decimals.and_then(|d| d.parse::<u8>().ok()),
+                price_usd: row.get(6)?,
+            })
+        })?;
+        let mut out = Vec::new();
+        for row in rows {
+            let entry = row?;
+            if entry.balance != U256::from(0u64) {
+                out.push(entry);
+            }
+        }
+        Ok(out)
+    }
+
+    /// Get current allowances with optional owner/spender/token filters and cursor pagination.
+    ///
+    /// Pagination is cursor-based on the `allowances.id` primary key in ascending order.
+    /// Returns `(rows, next_cursor)`.
+    pub async fn get_allowances_filtered(
+        &self,
+        owner: Option<Felt>,
+        spender: Option<Felt>,
+        token: Option<Felt>,
+        cursor: Option<i64>,
+        limit: u32,
+    ) -> Result<(Vec<AllowanceData>, Option<i64>)> {
+        if self.backend == StorageBackend::Postgres {
+            return self
+                .pg_get_allowances_filtered(owner, spender, token, cursor, limit)
+                .await;
+        }
+        let conn = self.conn.lock().unwrap();
+
+        let mut query = String::from(
+            "SELECT id, token, owner, spender, amount, block_number, tx_hash, timestamp
+             FROM allowances
+             WHERE 1=1",
+        );
+        let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+        if let Some(owner_addr) = owner {
+            query.push_str(" AND owner = ?");
+            params_vec.push(Box::new(felt_to_blob(owner_addr)));
+        }
+
+        if let Some(spender_addr) = spender {
+            query.push_str(" AND spender = ?");
+            params_vec.push(Box::new(felt_to_blob(spender_addr)));
+        }
+
+        if let Some(token_addr) = token {
+            query.push_str(" AND token = ?");
+            params_vec.push(Box::new(felt_to_blob(token_addr)));
+        }
+
+        if let Some(c) = cursor {
+            query.push_str(" AND id > ?");
+            params_vec.push(Box::new(c));
+        }
+
+        query.push_str(" ORDER BY id ASC LIMIT ?");
+        params_vec.push(Box::new(limit as i64));
+
+        let mut stmt = conn.prepare_cached(&query)?;
+        let params_refs: Vec<&dyn rusqlite::ToSql> =
+            params_vec.iter().map(std::convert::AsRef::as_ref).collect();
+
+        let rows = stmt.query_map(params_refs.as_slice(), |row| {
+            let id: i64 = row.get(0)?;
+            let token_bytes: Vec<u8> = row.get(1)?;
+            let owner_bytes: Vec<u8> = row.get(2)?;
+            let spender_bytes: Vec<u8> = row.get(3)?;
+            let amount_bytes: Vec<u8> = row.get(4)?;
+            let block_number_str: String = row.get(5)?;
+            let tx_hash_bytes: Vec<u8> = row.get(6)?;
+            let timestamp_str: Option<String> = row.get(7)?;
+
+            Ok((
+                id,
+                AllowanceData {
+                    token: blob_to_felt(&token_bytes),
+                    owner: blob_to_felt(&owner_bytes),
+                    spender: blob_to_felt(&spender_bytes),
+                    amount: blob_to_u256(&amount_bytes),
+                    block_number: block_number_str.parse::<u64>().unwrap_or(0),
+                    tx_hash: blob_to_felt(&tx_hash_bytes),
+                    timestamp: timestamp_str.and_then(|s| s.parse::<i64>().ok()),
+                },
+            ))
+        })?;
+
+        let mut out: Vec<AllowanceData> = Vec::new();
+        let mut last_id: Option<i64> = None;
+
+        for row in rows {
+            let (id, data) = row?;
+            last_id = Some(id);
+            out.push(data);
+        }
+
+        let next_cursor = if out.len() == limit as usize {
+            last_id
+        } else {
+            None
+        };
+
+        Ok((out, next_cursor))
+    }
+
     /// Get balances for multiple wallet/token pairs in a single query
     pub async fn get_balances_batch(
         &self,
@@ -1948,6 +2238,144 @@ impl Erc20Storage {
         Ok(count as u64)
     }
 
+    // ===== Balance Watchlist Methods =====
+
+    /// Adds accounts to the balance watchlist. Returns the number of accounts not
+    /// watched before.
+    pub async fn add_watched_accounts(&self, accounts: &[Felt]) -> Result<usize> {
+        if accounts.is_empty() {
+            return Ok(0);
+        }
+        if self.backend == StorageBackend::Postgres {
+            return self.pg_add_watched_accounts(accounts).await;
+        }
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let mut added = 0;
+        {
+            let mut stmt = tx.prepare_cached(
+                "INSERT INTO balance_watchlist (account) VALUES (?1)
+                 ON CONFLICT(account) DO NOTHING",
+            )?;
+            for account in accounts {
+                added += stmt.execute(params![felt_to_blob(*account)])?;
+            }
+        }
+        tx.commit()?;
+        Ok(added)
+    }
+
+    /// Removes accounts from the balance watchlist. Returns the number of removed
+    /// accounts.
+    pub async fn remove_watched_accounts(&self, accounts: &[Felt]) -> Result<usize> {
+        if accounts.is_empty() {
+            return Ok(0);
+        }
+        if self.backend == StorageBackend::Postgres {
+            return self.pg_remove_watched_accounts(accounts).await;
+        }
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let mut removed = 0;
+        {
+            let mut stmt = tx.prepare_cached("DELETE FROM balance_watchlist WHERE account = ?1")?;
+            for account in accounts {
+                removed += stmt.execute(params![felt_to_blob(*account)])?;
+            }
+        }
+        tx.commit()?;
+        Ok(removed)
+    }
+
+    /// Accounts of the balance watchlist, in address order.
+    pub async fn get_watched_accounts(&self) -> Result<Vec<Felt>> {
+        if self.backend == StorageBackend::Postgres {
+            return self.pg_get_watched_accounts().await;
+        }
+        let conn = self.conn.lock().unwrap();
+        let mut stmt =
+            conn.prepare_cached("SELECT account FROM balance_watchlist ORDER BY account")?;
+        let rows = stmt.query_map([], |row| row.get::<_, Vec<u8>>(0))?;
+        let mut accounts = Vec::new();
+        for row in rows {
+            accounts.push(blob_to_felt(&row?));
+        }
+        Ok(accounts)
+    }
+
+    /// Tokens `wallet` sent or received, from the wallet activity index.
+    pub async fn get_wallet_tokens(&self, wallet: Felt) -> Result<Vec<Felt>> {
+        if self.backend == StorageBackend::Postgres {
+            return self.pg_get_wallet_tokens(wallet).await;
+        }
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare_cached(
+            "SELECT DISTINCT token FROM wallet_activity WHERE wallet_address = ?1",
+        )?;
+        let rows = stmt.query_map(params![felt_to_blob(wallet)], |row| {
+            row.get::<_, Vec<u8>>(0)
+        })?;
+        let mut tokens = Vec::new();
+        for row in rows {
+            tokens.push(blob_to_felt(&row?));
+        }
+        Ok(tokens)
+    }
+
+    /// Stores balances read from the chain at `block_number`, as (token, wallet, balance).
+    ///
+    /// Balances already updated by a later block are left untouched.
+    pub async fn upsert_fetched_balances(
+        &self,
+        balances: &[(Felt, Felt, U256)],
+        block_number: u64,
+    ) -> Result<()> {
+        if balances.is_empty() {
+            return Ok(());
+        }
+        let pairs = balances
+            .iter()
+            .map(|(token, wallet, _)| (*token, *wallet))
+            .collect::<Vec<_>>();
+        let stored = if self.backend == StorageBackend::Postgres {
+            self.pg_upsert_fetched_balances(balances, block_number)
+                .await?;
+            self.pg_get_balances_batch(&pairs).await?
+        } else {
+            {
+                let mut conn = self.conn.lock().unwrap();
+                let tx = conn.transaction()?;
+                {
+                    let mut stmt = tx.prepare_cached(
+                        "INSERT INTO balances (token, wallet, balance, last_block, last_tx_hash)
+                         VALUES (?1, ?2, ?3, ?4, ?5)
+                         ON CONFLICT(token, wallet) DO UPDATE SET
+                             balance = excluded.balance,
+                             last_block = excluded.last_block,
+                             last_tx_hash = excluded.last_tx_hash,
+                             updated_at = strftime('%s', 'now')
+                         WHERE CAST(balances.last_block AS INTEGER)
+                             <= CAST(excluded.last_block AS INTEGER)",
+                    )?;
+                    for (token, wallet, balance) in balances {
+                        stmt.execute(params![
+                            felt_to_blob(*token),
+                            felt_to_blob(*wallet),
+                            u256_to_blob(*balance),
+                            block_number.to_string(),
+                            felt_to_blob(Felt::ZERO)
+                        ])?;
+                    }
+                }
+                tx.commit()?;
+            }
+            self.sqlite_load_balances_for_pairs(&pairs)?
+        };
+        // Cache what won, not what was fetched.
+        self.store_cached_balances(&stored);
+        Ok(())
+    }
+
     // ===== Token Metadata Methods =====
 
     /// Check if metadata exists for a token
@@ -2180,67 +2608,395 @@ impl Erc20Storage {
         Ok((out, next_cursor))
     }
 
-    async fn pg_client(&self) -> Result<tokio::sync::MutexGuard<'_, Client>> {
-        let conns = self
-            .pg_conns
-            .as_ref()
-            .ok_or_else(|| anyhow::anyhow!("PostgreSQL connections not initialized"))?;
-        let idx = self.pg_rr.fetch_add(1, Ordering::Relaxed) % conns.len();
-        Ok(conns[idx].lock().await)
+    /// Records token prices for the block window starting at `block_window`, fetched at
+    /// `block_number`. An existing price for the same window is replaced.
+    pub async fn upsert_token_prices(
+        &self,
+        block_window: u64,
+        block_number: u64,
+        prices: &[TokenPrice],
+        source: &str,
+    ) -> Result<usize> {
+        if prices.is_empty() {
+            return Ok(0);
+        }
+        if self.backend == StorageBackend::Postgres {
+            return self
+                .pg_upsert_token_prices(block_window, block_number, prices, source)
+                .await;
+        }
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        {
+            let mut stmt = tx.prepare_cached(
+                "INSERT INTO token_prices (token, block_window, block_number, price_usd, source)
+                 VALUES (?1, ?2, ?3, ?4, ?5)
+                 ON CONFLICT(token, block_window) DO UPDATE SET
+                     block_number = excluded.block_number,
+                     price_usd = excluded.price_usd,
+                     source = excluded.source",
+            )?;
+            for price in prices {
+                stmt.execute(params![
+                    felt_to_blob(price.token),
+                    block_window as i64,
+                    block_number as i64,
+                    price.price_usd,
+                    source
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(prices.len())
     }
 
-    fn pg_next_param(
-        params: &mut Vec<Box<dyn PgToSql + Sync + Send>>,
-        value: impl PgToSql + Sync + Send + 'static,
-    ) -> String {
-        params.push(Box::new(value));
-        format!("${}", params.len())
+    /// Price of each (token, block) pair: the one recorded for the latest window starting
+    /// at or before the block. Pairs without a recorded price are omitted.
+    ///
+    /// Use `u64::MAX` as the block for the most recent price.
+    pub async fn get_token_prices_at(
+        &self,
+        lookups: &[(Felt, u64)],
+    ) -> Result<HashMap<(Felt, u64), f64>> {
+        let unique = lookups.iter().copied().collect::<HashSet<_>>();
+        if unique.is_empty() {
+            return Ok(HashMap::new());
+        }
+        if self.backend == StorageBackend::Postgres {
+            return self.pg_get_token_prices_at(unique).await;
+        }
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare_cached(
+            "SELECT price_usd FROM token_prices
+             WHERE token = ?1 AND block_window <= ?2
+             ORDER BY block_window DESC
+             LIMIT 1",
+        )?;
+        let mut prices = HashMap::with_capacity(unique.len());
+        for (token, block) in unique {
+            let mut rows = stmt.query(params![felt_to_blob(token), clamp_block(block)])?;
+            if let Some(row) = rows.next()? {
+                prices.insert((token, block), row.get::<_, f64>(0)?);
+            }
+        }
+        Ok(prices)
     }
 
-    async fn pg_insert_transfers_batch(&self, transfers: &[TransferData]) -> Result<usize> {
-        if transfers.is_empty() {
-            return Ok(0);
+    /// Folds mint/burn changes (in block order) into the supply of their tokens.
+    ///
+    /// Updates the latest totals in `token_supply` and upserts one
+    /// `token_supply_history` row per token and block. Returns the written snapshots.
+    pub async fn apply_supply_changes(
+        &self,
+        changes: &[SupplyChange],
+    ) -> Result<Vec<SupplySnapshot>> {
+        if changes.is_empty() {
+            return Ok(Vec::new());
         }
-
-        let zero_blob = felt_to_blob(Felt::ZERO);
-        let mut token_vec = Vec::with_capacity(transfers.len());
-        let mut from_vec = Vec::with_capacity(transfers.len());
-        let mut to_vec = Vec::with_capacity(transfers.len());
-        let mut amount_vec = Vec::with_capacity(transfers.len());
-        let mut block_vec: Vec<String> = Vec::with_capacity(transfers.len());
-        let mut tx_hash_vec = Vec::with_capacity(transfers.len());
-        let mut ts_vec: Vec<String> = Vec::with_capacity(transfers.len());
-
-        for transfer in transfers {
-            token_vec.push(felt_to_blob(transfer.token));
-            from_vec.push(felt_to_blob(transfer.from));
-            to_vec.push(felt_to_blob(transfer.to));
-            amount_vec.push(u256_to_blob(transfer.amount));
-            block_vec.push(transfer.block_number.to_string());
-            tx_hash_vec.push(felt_to_blob(transfer.tx_hash));
-            ts_vec.push(
-                transfer
-                    .timestamp
-                    .unwrap_or_else(|| chrono::Utc::now().timestamp())
-                    .to_string(),
-            );
+        if self.backend == StorageBackend::Postgres {
+            return self.pg_apply_supply_changes(changes).await;
+        }
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let mut latest = HashMap::new();
+        {
+            let mut stmt = tx.prepare_cached(
+                "SELECT token, block_number, minted, burned, total_minted, total_burned
+                 FROM token_supply WHERE token = ?1",
+            )?;
+            let tokens = changes.iter().map(|c| c.token).collect::<HashSet<_>>();
+            for token in tokens {
+                let mut rows = stmt.query(params![felt_to_blob(token)])?;
+                if let Some(row) = rows.next()? {
+                    latest.insert(token, sqlite_supply_row(row)?);
+                }
+            }
+        }
+        let snapshots = fold_supply_changes(&mut latest, changes);
+        {
+            let mut history_stmt = tx.prepare_cached(
+                "INSERT INTO token_supply_history
+                     (token, block_number, minted, burned, total_minted, total_burned, circulating)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                 ON CONFLICT(token, block_number) DO UPDATE SET
+                     minted = excluded.minted,
+                     burned = excluded.burned,
+                     total_minted = excluded.total_minted,
+                     total_burned = excluded.total_burned,
+                     circulating = excluded.circulating",
+            )?;
+            for snapshot in &snapshots {
+                history_stmt.execute(params![
+                    felt_to_blob(snapshot.token),
+                    clamp_block(snapshot.block_number),
+                    u256_to_blob(snapshot.minted),
+                    u256_to_blob(snapshot.burned),
+                    u256_to_blob(snapshot.total_minted),
+                    u256_to_blob(snapshot.total_burned),
+                    u256_to_blob(snapshot.circulating()),
+                ])?;
+            }
+            let mut supply_stmt = tx.prepare_cached(
+                "INSERT INTO token_supply
+                     (token, block_number, minted, burned, total_minted, total_burned, circulating)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                 ON CONFLICT(token) DO UPDATE SET
+                     block_number = excluded.block_number,
+                     minted = excluded.minted,
+                     burned = excluded.burned,
+                     total_minted = excluded.total_minted,
+                     total_burned = excluded.total_burned,
+                     circulating = excluded.circulating",
+            )?;
+            for snapshot in latest.values() {
+                supply_stmt.execute(params![
+                    felt_to_blob(snapshot.token),
+                    clamp_block(snapshot.block_number),
+                    u256_to_blob(snapshot.minted),
+                    u256_to_blob(snapshot.burned),
+                    u256_to_blob(snapshot.total_minted),
+                    u256_to_blob(snapshot.total_burned),
+                    u256_to_blob(snapshot.circulating()),
+                ])?;
+            }
         }
+        tx.commit()?;
+        Ok(snapshots)
+    }
 
-        let client = self.pg_client().await?;
-        let row = client
-            .query_one(
-                "WITH inserted AS (
-                    INSERT INTO erc20.transfers (token, from_addr, to_addr, amount, block_number, tx_hash, timestamp)
-                    SELECT i.token, i.from_addr, i.to_addr, i.amount, i.block_number, i.tx_hash, i.timestamp
-                    FROM unnest(
-                        $1::bytea[],
-                        $2::bytea[],
-                        $3::bytea[],
-                        $4::bytea[],
+    /// Supply snapshots of `token` in ascending block order.
+    ///
+    /// Only blocks with mints or burns have a snapshot. `cursor` is the block of the
+    /// last snapshot of the previous page.
+    pub async fn get_supply_history(
+        &self,
+        token: Felt,
+        from_block: Option<u64>,
+        to_block: Option<u64>,
+        cursor: Option<u64>,
+        limit: u32,
+    ) -> Result<(Vec<SupplySnapshot>, Option<u64>)> {
+        if self.backend == StorageBackend::Postgres {
+            return self
+                .pg_get_supply_history(token, from_block, to_block, cursor, limit)
+                .await;
+        }
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare_cached(
+            "SELECT token, block_number, minted, burned, total_minted, total_burned
+             FROM token_supply_history
+             WHERE token = ?1 AND block_number >= ?2 AND block_number <= ?3 AND block_number > ?4
+             ORDER BY block_number ASC
+             LIMIT ?5",
+        )?;
+        let rows = stmt.query_map(
+            params![
+                felt_to_blob(token),
+                from_block.map_or(0, clamp_block),
+                to_block.map_or(i64::MAX, clamp_block),
+                cursor.map_or(-1, clamp_block),
+                i64::from(limit),
+            ],
+            sqlite_supply_row,
+        )?;
+        let snapshots = rows.collect::<rusqlite::Result<Vec<_>>>()?;
+        let next_cursor = (snapshots.len() == limit as usize)
+            .then(|| snapshots.last().map(|s| s.block_number))
+            .flatten();
+        Ok((snapshots, next_cursor))
+    }
+
+    /// Adds per-bucket transfer deltas to the `token_volume` rollups.
+    ///
+    /// Participants are recorded in `token_volume_participants` so that an address
+    /// seen in several batches is counted once per bucket. Returns the number of
+    /// updated buckets.
+    pub async fn apply_volume_deltas(&self, deltas: &[VolumeDelta]) -> Result<usize> {
+        if deltas.is_empty() {
+            return Ok(0);
+        }
+        if self.backend == StorageBackend::Postgres {
+            return self.pg_apply_volume_deltas(deltas).await;
+        }
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        {
+            let mut participant_stmt = tx.prepare_cached(
+                "INSERT INTO token_volume_participants (token, interval, bucket_start, role, address)
+                 VALUES (?1, ?2, ?3, ?4, ?5)
+                 ON CONFLICT DO NOTHING",
+            )?;
+            let mut volume_stmt = tx.prepare_cached(
+                "SELECT volume FROM token_volume
+                 WHERE token = ?1 AND interval = ?2 AND bucket_start = ?3",
+            )?;
+            let mut upsert_stmt = tx.prepare_cached(
+                "INSERT INTO token_volume
+                     (token, interval, bucket_start, transfer_count, volume, unique_senders, unique_receivers)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                 ON CONFLICT(token, interval, bucket_start) DO UPDATE SET
+                     transfer_count = token_volume.transfer_count + excluded.transfer_count,
+                     volume = excluded.volume,
+                     unique_senders = token_volume.unique_senders + excluded.unique_senders,
+                     unique_receivers = token_volume.unique_receivers + excluded.unique_receivers",
+            )?;
+            for delta in deltas {
+                let token = felt_to_blob(delta.token);
+                let interval = delta.interval.as_str();
+                let mut new_participants = [0i64; 2];
+                for (role, addresses) in [&delta.senders, &delta.receivers].into_iter().enumerate()
+                {
+                    for address in addresses {
+                        new_participants[role] += participant_stmt.execute(params![
+                            token,
+                            interval,
+                            delta.bucket_start,
+                            role as i64,
+                            felt_to_blob(*address),
+                        ])? as i64;
+                    }
+                }
+                let mut volume = delta.volume;
+                let mut rows = volume_stmt.query(params![token, interval, delta.bucket_start])?;
+                if let Some(row) = rows.next()? {
+                    volume = safe_u256_add(blob_to_u256(&row.get::<_, Vec<u8>>(0)?), volume);
+                }
+                drop(rows);
+                upsert_stmt.execute(params![
+                    token,
+                    interval,
+                    delta.bucket_start,
+                    delta.transfer_count as i64,
+                    u256_to_blob(volume),
+                    new_participants[0],
+                    new_participants[1],
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(deltas.len())
+    }
+
+    /// Rolled-up buckets of `token` for `interval`, in ascending bucket order.
+    ///
+    /// Buckets without transfers are absent. `from`/`to` bound the bucket start
+    /// (inclusive, unix seconds) and `cursor` is the bucket start of the last bucket
+    /// of the previous page.
+    pub async fn get_volume_series(
+        &self,
+        token: Felt,
+        interval: VolumeInterval,
+        from: Option<i64>,
+        to: Option<i64>,
+        cursor: Option<i64>,
+        limit: u32,
+    ) -> Result<(Vec<VolumeBucket>, Option<i64>)> {
+        if self.backend == StorageBackend::Postgres {
+            return self
+                .pg_get_volume_series(token, interval, from, to, cursor, limit)
+                .await;
+        }
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare_cached(
+            "SELECT token, bucket_start, transfer_count, volume, unique_senders, unique_receivers
+             FROM token_volume
+             WHERE token = ?1 AND interval = ?2 AND bucket_start >= ?3 AND bucket_start <= ?4
+                 AND bucket_start > ?5
+             ORDER BY bucket_start ASC
+             LIMIT ?6",
+        )?;
+        let rows = stmt.query_map(
+            params![
+                felt_to_blob(token),
+                interval.as_str(),
+                from.unwrap_or(i64::MIN),
+                to.unwrap_or(i64::MAX),
+                cursor.unwrap_or(i64::MIN),
+                i64::from(limit),
+            ],
+            |row| {
+                Ok(VolumeBucket {
+                    token: blob_to_felt(&row.get::<_, Vec<u8>>(0)?),
+                    interval,
+                    bucket_start: row.get(1)?,
+                    transfer_count: row.get::<_, i64>(2)?.max(0) as u64,
+                    volume: blob_to_u256(&row.get::<_, Vec<u8>>(3)?),
+                    unique_senders: row.get::<_, i64>(4)?.max(0) as u64,
+                    unique_receivers: row.get::<_, i64>(5)?.max(0) as u64,
+                })
+            },
+        )?;
+        let buckets = rows.collect::<rusqlite::Result<Vec<_>>>()?;
+        let next_cursor = (buckets.len() == limit as usize)
+            .then(|| buckets.last().map(|b| b.bucket_start))
+            .flatten();
+        Ok((buckets, next_cursor))
+    }
+
+    async fn pg_client(&self) -> Result<tokio::sync::MutexGuard<'_, Client>> {
+        let conns = self
+            .pg_conns
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("PostgreSQL connections not initialized"))?;
+        let idx = self.pg_rr.fetch_add(1, Ordering::Relaxed) % conns.len();
+        Ok(conns[idx].lock().await)
+    }
+
+    fn pg_next_param(
+        params: &mut Vec<Box<dyn PgToSql + Sync + Send>>,
+        value: impl PgToSql + Sync + Send + 'static,
+    ) -> String {
+        params.push(Box::new(value));
+        format!("${}", params.len())
+    }
+
+    async fn pg_insert_transfers_batch(&self, transfers: &[TransferData]) -> Result<usize> {
+        if transfers.is_empty() {
+            return Ok(0);
+        }
+
+        let zero_blob = felt_to_blob(Felt::ZERO);
+        let mut token_vec = Vec::with_capacity(transfers.len());
+        let mut from_vec = Vec::with_capacity(transfers.len());
+        let mut to_vec = Vec::with_capacity(transfers.len());
+        let mut amount_vec = Vec::with_capacity(transfers.len());
+        let mut block_vec: Vec<String> = Vec::with_capacity(transfers.len());
+        let mut tx_hash_vec = Vec::with_capacity(transfers.len());
+        let mut ts_vec: Vec<String> = Vec::with_capacity(transfers.len());
+        let mut event_index_vec: Vec<Option<i64>> = Vec::with_capacity(transfers.len());
+
+        for transfer in transfers {
+            token_vec.push(felt_to_blob(transfer.token));
+            from_vec.push(felt_to_blob(transfer.from));
+            to_vec.push(felt_to_blob(transfer.to));
+            amount_vec.push(u256_to_blob(transfer.amount));
+            block_vec.push(transfer.block_number.to_string());
+            tx_hash_vec.push(felt_to_blob(transfer.tx_hash));
+            ts_vec.push(
+                transfer
+                    .timestamp
+                    .unwrap_or_else(|| chrono::Utc::now().timestamp())
+                    .to_string(),
+            );
+            event_index_vec.push(transfer.event_index.map(i64::from));
+        }
+
+        let client = self.pg_client().await?;
+        let row = client
+            .query_one(
+                "WITH inserted AS (
+                    INSERT INTO erc20.transfers (token, from_addr, to_addr, amount, block_number, tx_hash, timestamp, event_index)
+                    SELECT i.token, i.from_addr, i.to_addr, i.amount, i.block_number, i.tx_hash, i.timestamp, i.event_index
+                    FROM unnest(
+                        $1::bytea[],
+                        $2::bytea[],
+                        $3::bytea[],
+                        $4::bytea[],
                         $5::text[],
                         $6::bytea[],
-                        $7::text[]
-                    ) AS i(token, from_addr, to_addr, amount, block_number, tx_hash, timestamp)
+                        $7::text[],
+                        $9::bigint[]
+                    ) AS i(token, from_addr, to_addr, amount, block_number, tx_hash, timestamp, event_index)
                     ON CONFLICT (token, tx_hash, from_addr, to_addr) DO NOTHING
                     RETURNING id, token, from_addr, to_addr, block_number
                 ),
@@ -2268,9 +3024,29 @@ impl Erc20Storage {
                     &tx_hash_vec,
                     &ts_vec,
                     &zero_blob,
+                    &event_index_vec,
                 ],
             )
             .await?;
+
+        let provenance_rows: Vec<_> = transfers
+            .iter()
+            .filter_map(|t| {
+                t.provenance
+                    .as_ref()
+                    .map(|p| ([t.token, t.tx_hash, t.from, t.to], p))
+            })
+            .collect();
+        Self::pg_insert_provenance_rows(
+            &client,
+            "erc20.transfer_provenance",
+            "transfer_id",
+            "erc20.transfers",
+            ["from_addr", "to_addr"],
+            &provenance_rows,
+        )
+        .await?;
+
         Ok(row.get::<usize, i64>(0) as usize)
     }
 
@@ -2334,6 +3110,27 @@ impl Erc20Storage {
                     SELECT a.spender, a.token, a.id, 'spender', a.block_number
                     FROM inserted a
                     WHERE a.spender <> $8::bytea AND a.owner <> a.spender
+                ),
+                _allowances AS (
+                    INSERT INTO erc20.allowances (token, owner, spender, amount, block_number, tx_hash, timestamp)
+                    SELECT DISTINCT ON (i.token, i.owner, i.spender)
+                        i.token, i.owner, i.spender, i.amount, i.block_number, i.tx_hash, i.timestamp
+                    FROM unnest(
+                        $1::bytea[],
+                        $2::bytea[],
+                        $3::bytea[],
+                        $4::bytea[],
+                        $5::text[],
+                        $6::bytea[],
+                        $7::text[]
+                    ) WITH ORDINALITY AS i(token, owner, spender, amount, block_number, tx_hash, timestamp, ord)
+                    ORDER BY i.token, i.owner, i.spender, i.block_number::bigint DESC, i.ord DESC
+                    ON CONFLICT (token, owner, spender) DO UPDATE SET
+                        amount = EXCLUDED.amount,
+                        block_number = EXCLUDED.block_number,
+                        tx_hash = EXCLUDED.tx_hash,
+                        timestamp = EXCLUDED.timestamp
+                    WHERE EXCLUDED.block_number::bigint >= erc20.allowances.block_number::bigint
                 )
                 SELECT COUNT(*)::bigint FROM inserted",
                 &[
@@ -2348,9 +3145,99 @@ impl Erc20Storage {
                 ],
             )
             .await?;
+
+        let provenance_rows: Vec<_> = approvals
+            .iter()
+            .filter_map(|a| {
+                a.provenance
+                    .as_ref()
+                    .map(|p| ([a.token, a.tx_hash, a.owner, a.spender], p))
+            })
+            .collect();
+        Self::pg_insert_provenance_rows(
+            &client,
+            "erc20.approval_provenance",
+            "approval_id",
+            "erc20.approvals",
+            ["owner", "spender"],
+            &provenance_rows,
+        )
+        .await?;
+
         Ok(row.get::<usize, i64>(0) as usize)
     }
 
+    /// Inserts provenance for rows identified by their unique key
+    /// `(token, tx_hash, party_a, party_b)`, keeping the first recorded provenance.
+    async fn pg_insert_provenance_rows(
+        client: &Client,
+        table: &str,
+        id_col: &str,
+        source_table: &str,
+        party_cols: [&str; 2],
+        rows: &[([Felt; 4], &Provenance)],
+    ) -> Result<()> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+
+        let mut key_vecs: [Vec<Vec<u8>>; 4] = Default::default();
+        let mut extractor_vec = Vec::with_capacity(rows.len());
+        let mut decoder_vec = Vec::with_capacity(rows.len());
+        let mut batch_vec = Vec::with_capacity(rows.len());
+        let mut extracted_vec = Vec::with_capacity(rows.len());
+        let mut decoded_vec = Vec::with_capacity(rows.len());
+        for (key, provenance) in rows {
+            for (vec, felt) in key_vecs.iter_mut().zip(key) {
+                vec.push(felt_to_blob(*felt));
+            }
+            extractor_vec.push(provenance.extractor.clone());
+            decoder_vec.push(provenance.decoder.clone());
+            batch_vec.push(provenance.batch_id as i64);
+            extracted_vec.push(provenance.extracted_at);
+            decoded_vec.push(provenance.decoded_at);
+        }
+        let stored_at = chrono::Utc::now().timestamp_millis();
+        let [party_a, party_b] = party_cols;
+
+        client
+            .execute(
+                &format!(
+                    "INSERT INTO {table} ({id_col}, extractor, decoder, batch_id, extracted_at, decoded_at, stored_at)
+                     SELECT s.id, p.extractor, p.decoder, p.batch_id, p.extracted_at, p.decoded_at, $10
+                     FROM unnest(
+                        $1::bytea[],
+                        $2::bytea[],
+                        $3::bytea[],
+                        $4::bytea[],
+                        $5::text[],
+                        $6::text[],
+                        $7::bigint[],
+                        $8::bigint[],
+                        $9::bigint[]
+                     ) AS p(token, tx_hash, party_a, party_b, extractor, decoder, batch_id, extracted_at, decoded_at)
+                     JOIN {source_table} s
+                       ON s.token = p.token AND s.tx_hash = p.tx_hash
+                      AND s.{party_a} = p.party_a AND s.{party_b} = p.party_b
+                     ON CONFLICT ({id_col}) DO NOTHING"
+                ),
+                &[
+                    &key_vecs[0],
+                    &key_vecs[1],
+                    &key_vecs[2],
+                    &key_vecs[3],
+                    &extractor_vec,
+                    &decoder_vec,
+                    &batch_vec,
+                    &extracted_vec,
+                    &decoded_vec,
+                    &stored_at,
+                ],
+            )
+            .await?;
+        Ok(())
+    }
+
     async fn pg_get_transfers_filtered(
         &self,
         wallet: Option<Felt>,
@@ -2362,19 +3249,21 @@ impl Erc20Storage {
         block_to: Option<u64>,
         cursor: Option<TransferCursor>,
         limit: u32,
+        mask: &FieldMask,
     ) -> Result<(Vec<TransferData>, Option<TransferCursor>)> {
         let client = self.pg_client().await?;
+        let columns = transfer_columns(mask, "''::bytea", "NULL::text");
         let mut query = String::new();
         let mut params: Vec<Box<dyn PgToSql + Sync + Send>> = Vec::new();
 
         if let Some(wallet_addr) = wallet {
             let p = Self::pg_next_param(&mut params, felt_to_blob(wallet_addr));
-            query.push_str(
-                "SELECT DISTINCT t.id, t.token, t.from_addr, t.to_addr, t.amount, t.block_number, t.tx_hash, t.timestamp
+            query.push_str(&format!(
+                "SELECT DISTINCT {columns}
                  FROM erc20.wallet_activity wa
                  JOIN erc20.transfers t ON wa.transfer_id = t.id
-                 WHERE wa.wallet_address = ",
-            );
+                 WHERE wa.wallet_address = "
+            ));
             query.push_str(&p);
 
             match direction {
@@ -2394,11 +3283,11 @@ impl Erc20Storage {
                 query.push_str(&format!(" AND wa.token IN ({list})"));
             }
         } else {
-            query.push_str(
-                "SELECT t.id, t.token, t.from_addr, t.to_addr, t.amount, t.block_number, t.tx_hash, t.timestamp
+            query.push_str(&format!(
+                "SELECT {columns}
                  FROM erc20.transfers t
-                 WHERE 1=1",
-            );
+                 WHERE 1=1"
+            ));
 
             if let Some(from_addr) = from {
                 query.push_str(" AND t.from_addr = ");
@@ -2462,6 +3351,8 @@ impl Erc20Storage {
                 timestamp: row
                     .get::<usize, Option<String>>(7)
                     .and_then(|s| s.parse::<i64>().ok()),
+                event_index: None,
+                provenance: None,
             })
             .collect();
 
@@ -2477,6 +3368,46 @@ impl Erc20Storage {
         Ok((transfers, next_cursor))
     }
 
+    async fn pg_get_transfers_for_replay(
+        &self,
+        range: &ReplayRange,
+        after_id: Option<i64>,
+    ) -> Result<Vec<TransferData>> {
+        let client = self.pg_client().await?;
+        let mut params: Vec<Box<dyn PgToSql + Sync + Send>> = Vec::new();
+        let mut query = String::from(
+            "SELECT t.id, t.token, t.from_addr, t.to_addr, t.amount, t.block_number, t.tx_hash, t.timestamp
+             FROM erc20.transfers t",
+        );
+        query.push_str(&range.page_clause(after_id, |param| match param {
+            ReplayParam::Integer(value) => Self::pg_next_param(&mut params, value),
+            ReplayParam::Blob(value) => Self::pg_next_param(&mut params, value),
+        }));
+
+        let refs: Vec<&(dyn PgToSql + Sync)> = params
+            .iter()
+            .map(|p| p.as_ref() as &(dyn PgToSql + Sync))
+            .collect();
+        let rows = client.query(&query, &refs).await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| TransferData {
+                id: Some(row.get::<usize, i64>(0)),
+                token: blob_to_felt(&row.get::<usize, Vec<u8>>(1)),
+                from: blob_to_felt(&row.get::<usize, Vec<u8>>(2)),
+                to: blob_to_felt(&row.get::<usize, Vec<u8>>(3)),
+                amount: blob_to_u256(&row.get::<usize, Vec<u8>>(4)),
+                block_number: row.get::<usize, String>(5).parse::<u64>().unwrap_or(0),
+                tx_hash: blob_to_felt(&row.get::<usize, Vec<u8>>(6)),
+                timestamp: row
+                    .get::<usize, Option<String>>(7)
+                    .and_then(|s| s.parse::<i64>().ok()),
+                event_index: None,
+                provenance: None,
+            })
+            .collect())
+    }
+
     async fn pg_get_approvals_filtered(
         &self,
         account: Option<Felt>,
@@ -2578,6 +3509,7 @@ impl Erc20Storage {
                 timestamp: row
                     .get::<usize, Option<String>>(7)
                     .and_then(|s| s.parse::<i64>().ok()),
+                provenance: None,
             })
             .collect();
 
@@ -2709,6 +3641,105 @@ impl Erc20Storage {
         Ok((out, next_cursor))
     }
 
+    async fn pg_get_portfolio(&self, wallet: Felt) -> Result<Vec<PortfolioEntry>> {
+        let client = self.pg_client().await?;
+        let rows = client
+            .query(
+                "SELECT b.token, b.balance, b.last_block, m.name, m.symbol, m.decimals,
+                        (SELECT p.price_usd FROM erc20.token_prices p
+                         WHERE p.token = b.token
+                         ORDER BY p.block_window DESC
+                         LIMIT 1)
+                 FROM erc20.balances b
+                 LEFT JOIN erc20.token_metadata m ON m.token = b.token
+                 WHERE b.wallet = $1
+                 ORDER BY b.token ASC",
+                &[&felt_to_blob(wallet)],
+            )
+            .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let decimals: Option<String> = row.get(5);
+                PortfolioEntry {
+                    token: blob_to_felt(&row.get::<usize, Vec<u8>>(0)),
+                    balance: blob_to_u256(&row.get::<usize, Vec<u8>>(1)),
+                    last_block: row.get::<usize, String>(2).parse::<u64>().unwrap_or(0),
+                    name: row.get(3),
+                    symbol: row.get(4),
+                    decimals: decimals.and_then(|d| d.parse::<u8>().ok()),
+                    price_usd: row.get(6),
+                }
+            })
+            .filter(|entry| entry.balance != U256::from(0u64))
+            .collect())
+    }
+
+    async fn pg_get_allowances_filtered(
+        &self,
+        owner: Option<Felt>,
+        spender: Option<Felt>,
+        token: Option<Felt>,
+        cursor: Option<i64>,
+        limit: u32,
+    ) -> Result<(Vec<AllowanceData>, Option<i64>)> {
+        let client = self.pg_client().await?;
+        let mut query = String::from(
+            "SELECT id, token, owner, spender, amount, block_number, tx_hash, timestamp FROM erc20.allowances WHERE 1=1",
+        );
+        let mut params: Vec<Box<dyn PgToSql + Sync + Send>> = Vec::new();
+        if let Some(owner_addr) = owner {
+            query.push_str(" AND owner = ");
+            query.push_str(&Self::pg_next_param(&mut params, felt_to_blob(owner_addr)));
+        }
+        if let Some(spender_addr) = spender {
+            query.push_str(" AND spender = ");
+            query.push_str(&Self::pg_next_param(
+                &mut params,
+                felt_to_blob(spender_addr),
+            ));
+        }
+        if let Some(token_addr) = token {
+            query.push_str(" AND token = ");
+            query.push_str(&Self::pg_next_param(&mut params, felt_to_blob(token_addr)));
+        }
+        if let Some(c) = cursor {
+            query.push_str(" AND id > ");
+            query.push_str(&Self::pg_next_param(&mut params, c));
+        }
+        query.push_str(" ORDER BY id ASC LIMIT ");
+        query.push_str(&Self::pg_next_param(&mut params, limit as i64));
+
+        let refs: Vec<&(dyn PgToSql + Sync)> = params
+            .iter()
+            .map(|p| p.as_ref() as &(dyn PgToSql + Sync))
+            .collect();
+        let rows = client.query(&query, &refs).await?;
+        let mut out = Vec::new();
+        let mut last_id = None;
+        for row in rows {
+            let id: i64 = row.get(0);
+            last_id = Some(id);
+            out.push(AllowanceData {
+                token: blob_to_felt(&row.get::<usize, Vec<u8>>(1)),
+                owner: blob_to_felt(&row.get::<usize, Vec<u8>>(2)),
+                spender: blob_to_felt(&row.get::<usize, Vec<u8>>(3)),
+                amount: blob_to_u256(&row.get::<usize, Vec<u8>>(4)),
+                block_number: row.get::<usize, String>(5).parse::<u64>().unwrap_or(0),
+                tx_hash: blob_to_felt(&row.get::<usize, Vec<u8>>(6)),
+                timestamp: row
+                    .get::<usize, Option<String>>(7)
+                    .and_then(|s| s.parse::<i64>().ok()),
+            });
+        }
+        let next_cursor = if out.len() == limit as usize {
+            last_id
+        } else {
+            None
+        };
+        Ok((out, next_cursor))
+    }
+
     async fn pg_get_balances_batch(
         &self,
         pairs: &[(Felt, Felt)],
@@ -2756,6 +3787,103 @@ impl Erc20Storage {
         Ok(result)
     }
 
+    async fn pg_add_watched_accounts(&self, accounts: &[Felt]) -> Result<usize> {
+        let accounts = accounts
+            .iter()
+            .map(|a| felt_to_blob(*a))
+            .collect::<Vec<_>>();
+        let client = self.pg_client().await?;
+        let added = client
+            .execute(
+                "INSERT INTO erc20.balance_watchlist (account)
+                 SELECT DISTINCT account FROM unnest($1::bytea[]) AS w(account)
+                 ON CONFLICT (account) DO NOTHING",
+                &[&accounts],
+            )
+            .await?;
+        Ok(added as usize)
+    }
+
+    async fn pg_remove_watched_accounts(&self, accounts: &[Felt]) -> Result<usize> {
+        let accounts = accounts
+            .iter()
+            .map(|a| felt_to_blob(*a))
+            .collect::<Vec<_>>();
+        let client = self.pg_client().await?;
+        let removed = client
+            .execute(
+                "DELETE FROM erc20.balance_watchlist WHERE account = ANY($1::bytea[])",
+                &[&accounts],
+            )
+            .await?;
+        Ok(removed as usize)
+    }
+
+    async fn pg_get_watched_accounts(&self) -> Result<Vec<Felt>> {
+        let client = self.pg_client().await?;
+        let rows = client
+            .query(
+                "SELECT account FROM erc20.balance_watchlist ORDER BY account",
+                &[],
+            )
+            .await?;
+        Ok(rows
+            .iter()
+            .map(|row| blob_to_felt(&row.get::<usize, Vec<u8>>(0)))
+            .collect())
+    }
+
+    async fn pg_get_wallet_tokens(&self, wallet: Felt) -> Result<Vec<Felt>> {
+        let client = self.pg_client().await?;
+        let rows = client
+            .query(
+                "SELECT DISTINCT token FROM erc20.wallet_activity WHERE wallet_address = $1",
+                &[&felt_to_blob(wallet)],
+            )
+            .await?;
+        Ok(rows
+            .iter()
+            .map(|row| blob_to_felt(&row.get::<usize, Vec<u8>>(0)))
+            .collect())
+    }
+
+    async fn pg_upsert_fetched_balances(
+        &self,
+        balances: &[(Felt, Felt, U256)],
+        block_number: u64,
+    ) -> Result<()> {
+        let mut tokens = Vec::with_capacity(balances.len());
+        let mut wallets = Vec::with_capacity(balances.len());
+        let mut amounts = Vec::with_capacity(balances.len());
+        for (token, wallet, balance) in balances {
+            tokens.push(felt_to_blob(*token));
+            wallets.push(felt_to_blob(*wallet));
+            amounts.push(u256_to_blob(*balance));
+        }
+        let client = self.pg_client().await?;
+        client
+            .execute(
+                "INSERT INTO erc20.balances (token, wallet, balance, last_block, last_tx_hash, updated_at)
+                 SELECT token, wallet, balance, $4, $5, EXTRACT(EPOCH FROM NOW())::TEXT
+                 FROM unnest($1::bytea[], $2::bytea[], $3::bytea[]) AS b(token, wallet, balance)
+                 ON CONFLICT (token, wallet) DO UPDATE SET
+                     balance = EXCLUDED.balance,
+                     last_block = EXCLUDED.last_block,
+                     last_tx_hash = EXCLUDED.last_tx_hash,
+                     updated_at = EXCLUDED.updated_at
+                 WHERE erc20.balances.last_block::NUMERIC <= EXCLUDED.last_block::NUMERIC",
+                &[
+                    &tokens,
+                    &wallets,
+                    &amounts,
+                    &block_number.to_string(),
+                    &felt_to_blob(Felt::ZERO),
+                ],
+            )
+            .await?;
+        Ok(())
+    }
+
     async fn pg_apply_transfers_with_adjustments(
         &self,
         transfers: &[TransferData],
@@ -3094,4 +4222,475 @@ impl Erc20Storage {
         };
         Ok((out, next_cursor))
     }
+
+    async fn pg_upsert_token_prices(
+        &self,
+        block_window: u64,
+        block_number: u64,
+        prices: &[TokenPrice],
+        source: &str,
+    ) -> Result<usize> {
+        let mut client = self.pg_client().await?;
+        let tx = client.transaction().await?;
+        let stmt = tx
+            .prepare(
+                "INSERT INTO erc20.token_prices (token, block_window, block_number, price_usd, source)
+                 VALUES ($1, $2, $3, $4, $5)
+                 ON CONFLICT (token, block_window) DO UPDATE SET
+                     block_number = EXCLUDED.block_number,
+                     price_usd = EXCLUDED.price_usd,
+                     source = EXCLUDED.source",
+            )
+            .await?;
+        for price in prices {
+            tx.execute(
+                &stmt,
+                &[
+                    &felt_to_blob(price.token),
+                    &(block_window as i64),
+                    &(block_number as i64),
+                    &price.price_usd,
+                    &source,
+                ],
+            )
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(prices.len())
+    }
+
+    async fn pg_get_token_prices_at(
+        &self,
+        lookups: HashSet<(Felt, u64)>,
+    ) -> Result<HashMap<(Felt, u64), f64>> {
+        let client = self.pg_client().await?;
+        let stmt = client
+            .prepare(
+                "SELECT price_usd FROM erc20.token_prices
+                 WHERE token = $1 AND block_window <= $2
+                 ORDER BY block_window DESC
+                 LIMIT 1",
+            )
+            .await?;
+        let mut prices = HashMap::with_capacity(lookups.len());
+        for (token, block) in lookups {
+            let row = client
+                .query_opt(&stmt, &[&felt_to_blob(token), &clamp_block(block)])
+                .await?;
+            if let Some(row) = row {
+                prices.insert((token, block), row.get::<usize, f64>(0));
+            }
+        }
+        Ok(prices)
+    }
+    async fn pg_apply_supply_changes(
+        &self,
+        changes: &[SupplyChange],
+    ) -> Result<Vec<SupplySnapshot>> {
+        let mut client = self.pg_client().await?;
+        let tx = client.transaction().await?;
+        let tokens = changes
+            .iter()
+            .map(|c| c.token)
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .map(felt_to_blob)
+            .collect::<Vec<_>>();
+        let mut latest = tx
+            .query(
+                "SELECT token, block_number, minted, burned, total_minted, total_burned
+                 FROM erc20.token_supply WHERE token = ANY($1)",
+                &[&tokens],
+            )
+            .await?
+            .iter()
+            .map(|row| {
+                let snapshot = pg_supply_row(row);
+                (snapshot.token, snapshot)
+            })
+            .collect::<HashMap<_, _>>();
+        let snapshots = fold_supply_changes(&mut latest, changes);
+
+        let history_stmt = tx
+            .prepare(
+                "INSERT INTO erc20.token_supply_history
+                     (token, block_number, minted, burned, total_minted, total_burned, circulating)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7)
+                 ON CONFLICT (token, block_number) DO UPDATE SET
+                     minted = EXCLUDED.minted,
+                     burned = EXCLUDED.burned,
+                     total_minted = EXCLUDED.total_minted,
+                     total_burned = EXCLUDED.total_burned,
+                     circulating = EXCLUDED.circulating",
+            )
+            .await?;
+        let supply_stmt = tx
+            .prepare(
+                "INSERT INTO erc20.token_supply
+                     (token, block_number, minted, burned, total_minted, total_burned, circulating)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7)
+                 ON CONFLICT (token) DO UPDATE SET
+                     block_number = EXCLUDED.block_number,
+                     minted = EXCLUDED.minted,
+                     burned = EXCLUDED.burned,
+                     total_minted = EXCLUDED.total_minted,
+                     total_burned = EXCLUDED.total_burned,
+                     circulating = EXCLUDED.circulating",
+            )
+            .await?;
+        for (stmt, snapshot) in snapshots
+            .iter()
+            .map(|snapshot| (&history_stmt, snapshot))
+            .chain(latest.values().map(|snapshot| (&supply_stmt, snapshot)))
+        {
+            tx.execute(
+                stmt,
+                &[
+                    &felt_to_blob(snapshot.token),
+                    &clamp_block(snapshot.block_number),
+                    &u256_to_blob(snapshot.minted),
+                    &u256_to_blob(snapshot.burned),
+                    &u256_to_blob(snapshot.total_minted),
+                    &u256_to_blob(snapshot.total_burned),
+                    &u256_to_blob(snapshot.circulating()),
+                ],
+            )
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(snapshots)
+    }
+
+    async fn pg_get_supply_history(
+        &self,
+        token: Felt,
+        from_block: Option<u64>,
+        to_block: Option<u64>,
+        cursor: Option<u64>,
+        limit: u32,
+    ) -> Result<(Vec<SupplySnapshot>, Option<u64>)> {
+        let client = self.pg_client().await?;
+        let rows = client
+            .query(
+                "SELECT token, block_number, minted, burned, total_minted, total_burned
+                 FROM erc20.token_supply_history
+                 WHERE token = $1 AND block_number >= $2 AND block_number <= $3 AND block_number > $4
+                 ORDER BY block_number ASC
+                 LIMIT $5",
+                &[
+                    &felt_to_blob(token),
+                    &from_block.map_or(0, clamp_block),
+                    &to_block.map_or(i64::MAX, clamp_block),
+                    &cursor.map_or(-1, clamp_block),
+                    &i64::from(limit),
+                ],
+            )
+            .await?;
+        let snapshots = rows.iter().map(pg_supply_row).collect::<Vec<_>>();
+        let next_cursor = (snapshots.len() == limit as usize)
+            .then(|| snapshots.last().map(|s| s.block_number))
+            .flatten();
+        Ok((snapshots, next_cursor))
+    }
+
+    async fn pg_apply_volume_deltas(&self, deltas: &[VolumeDelta]) -> Result<usize> {
+        let mut client = self.pg_client().await?;
+        let tx = client.transaction().await?;
+        let participant_stmt = tx
+            .prepare(
+                "INSERT INTO erc20.token_volume_participants (token, interval, bucket_start, role, address)
+                 VALUES ($1, $2, $3, $4, $5)
+                 ON CONFLICT DO NOTHING",
+            )
+            .await?;
+        let volume_stmt = tx
+            .prepare(
+                "SELECT volume FROM erc20.token_volume
+                 WHERE token = $1 AND interval = $2 AND bucket_start = $3",
+            )
+            .await?;
+        let upsert_stmt = tx
+            .prepare(
+                "INSERT INTO erc20.token_volume
+                     (token, interval, bucket_start, transfer_count, volume, unique_senders, unique_receivers)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7)
+                 ON CONFLICT (token, interval, bucket_start) DO UPDATE SET
+                     transfer_count = erc20.token_volume.transfer_count + EXCLUDED.transfer_count,
+                     volume = EXCLUDED.volume,
+                     unique_senders = erc20.token_volume.unique_senders + EXCLUDED.unique_senders,
+                     unique_receivers = erc20.token_volume.unique_receivers + EXCLUDED.unique_receivers",
+            )
+            .await?;
+        for delta in deltas {
+            let token = felt_to_blob(delta.token);
+            let interval = delta.interval.as_str();
+            let mut new_participants = [0i64; 2];
+            for (role, addresses) in [&delta.senders, &delta.receivers].into_iter().enumerate() {
+                for address in addresses {
+                    new_participants[role] += tx
+                        .execute(
+                            &participant_stmt,
+                            &[
+                                &token,
+                                &interval,
+                                &delta.bucket_start,
+                                &(role as i16),
+                                &felt_to_blob(*address),
+                            ],
+                        )
+                        .await? as i64;
+                }
+            }
+            let previous = tx
+                .query_opt(&volume_stmt, &[&token, &interval, &delta.bucket_start])
+                .await?;
+            let volume = previous.map_or(delta.volume, |row| {
+                safe_u256_add(blob_to_u256(&row.get::<_, Vec<u8>>(0)), delta.volume)
+            });
+            tx.execute(
+                &upsert_stmt,
+                &[
+                    &token,
+                    &interval,
+                    &delta.bucket_start,
+                    &(delta.transfer_count as i64),
+                    &u256_to_blob(volume),
+                    &new_participants[0],
+                    &new_participants[1],
+                ],
+            )
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(deltas.len())
+    }
+
+    async fn pg_get_volume_series(
+        &self,
+        token: Felt,
+        interval: VolumeInterval,
+        from: Option<i64>,
+        to: Option<i64>,
+        cursor: Option<i64>,
+        limit: u32,
+    ) -> Result<(Vec<VolumeBucket>, Option<i64>)> {
+        let client = self.pg_client().await?;
+        let rows = client
+            .query(
+                "SELECT token, bucket_start, transfer_count, volume, unique_senders, unique_receivers
+                 FROM erc20.token_volume
+                 WHERE token = $1 AND interval = $2 AND bucket_start >= $3 AND bucket_start <= $4
+                     AND bucket_start > $5
+                 ORDER BY bucket_start ASC
+                 LIMIT $6",
+                &[
+                    &felt_to_blob(token),
+                    &interval.as_str(),
+                    &from.unwrap_or(i64::MIN),
+                    &to.unwrap_or(i64::MAX),
+                    &cursor.unwrap_or(i64::MIN),
+                    &i64::from(limit),
+                ],
+            )
+            .await?;
+        let buckets = rows
+            .iter()
+            .map(|row| VolumeBucket {
+                token: blob_to_felt(&row.get::<_, Vec<u8>>(0)),
+                interval,
+                bucket_start: row.get(1),
+                transfer_count: row.get::<_, i64>(2).max(0) as u64,
+                volume: blob_to_u256(&row.get::<_, Vec<u8>>(3)),
+                unique_senders: row.get::<_, i64>(4).max(0) as u64,
+                unique_receivers: row.get::<_, i64>(5).max(0) as u64,
+            })
+            .collect::<Vec<_>>();
+        let next_cursor = (buckets.len() == limit as usize)
+            .then(|| buckets.last().map(|b| b.bucket_start))
+            .flatten();
+        Ok((buckets, next_cursor))
+    }
+}
+
+/// Reads a `token_supply`/`token_supply_history` row (token, block_number, minted,
+/// burned, total_minted, total_burned).
+fn sqlite_supply_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<SupplySnapshot> {
+    Ok(SupplySnapshot {
+        token: blob_to_felt(&row.get::<_, Vec<u8>>(0)?),
+        block_number: row.get::<_, i64>(1)?.max(0) as u64,
+        minted: blob_to_u256(&row.get::<_, Vec<u8>>(2)?),
+        burned: blob_to_u256(&row.get::<_, Vec<u8>>(3)?),
+        total_minted: blob_to_u256(&row.get::<_, Vec<u8>>(4)?),
+        total_burned: blob_to_u256(&row.get::<_, Vec<u8>>(5)?),
+    })
+}
+
+/// PostgreSQL counterpart of [`sqlite_supply_row`].
+fn pg_supply_row(row: &tokio_postgres::Row) -> SupplySnapshot {
+    SupplySnapshot {
+        token: blob_to_felt(&row.get::<_, Vec<u8>>(0)),
+        block_number: row.get::<_, i64>(1).max(0) as u64,
+        minted: blob_to_u256(&row.get::<_, Vec<u8>>(2)),
+        burned: blob_to_u256(&row.get::<_, Vec<u8>>(3)),
+        total_minted: blob_to_u256(&row.get::<_, Vec<u8>>(4)),
+        total_burned: blob_to_u256(&row.get::<_, Vec<u8>>(5)),
+    }
+}
+
+/// Select list of a transfer query (id, token, from, to, amount, block_number, tx_hash,
+/// timestamp), with the columns of fields outside `mask` replaced by placeholders.
+fn transfer_columns(mask: &FieldMask, empty_blob: &str, null: &str) -> String {
+    format!(
+        "t.id, {}, {}, {}, {}, t.block_number, {}, {}",
+        mask.column("token", "t.token", empty_blob),
+        mask.column("from", "t.from_addr", empty_blob),
+        mask.column("to", "t.to_addr", empty_blob),
+        mask.column("amount", "t.amount", empty_blob),
+        mask.column("tx_hash", "t.tx_hash", empty_blob),
+        mask.column("timestamp", "t.timestamp", null),
+    )
+}
+
+/// Block number as a signed SQL integer, saturating at `i64::MAX`.
+fn clamp_block(block: u64) -> i64 {
+    i64::try_from(block).unwrap_or(i64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_db_path(test_name: &str) -> String {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .expect("system time before unix epoch")
+            .as_nanos();
+        std::env::temp_dir()
+            .join(format!("torii-erc20-{test_name}-{nanos}.db"))
+            .to_string_lossy()
+            .to_string()
+    }
+
+    #[tokio::test]
+    async fn replay_pages_transfers_of_a_block_range() {
+        let db_path = temp_db_path("replay");
+        let storage = Erc20Storage::new(&db_path).await.expect("create storage");
+        let transfer = |token: u64, block_number: u64| TransferData {
+            id: None,
+            token: Felt::from(token),
+            from: Felt::ZERO,
+            to: Felt::from(0x10u64),
+            amount: U256::from(block_number),
+            block_number,
+            tx_hash: Felt::from(block_number),
+            timestamp: None,
+            event_index: None,
+            provenance: None,
+        };
+        storage
+            .insert_transfers_batch(&[
+                transfer(1, 5),
+                transfer(1, 9),
+                transfer(2, 12),
+                transfer(1, 100),
+            ])
+            .await
+            .expect("insert transfers");
+        let blocks =
+            |page: Vec<TransferData>| page.iter().map(|t| t.block_number).collect::<Vec<_>>();
+
+        // Block 100 sorts before 9 as text.
+        let range = ReplayRange::new(Vec::new(), Some(9), Some(100), 2).unwrap();
+        let first = storage
+            .get_transfers_for_replay(&range, None)
+            .await
+            .expect("first page");
+        let after_id = first.last().and_then(|t| t.id);
+        assert_eq!(blocks(first), vec![9, 12]);
+        let second = storage
+            .get_transfers_for_replay(&range, after_id)
+            .await
+            .expect("second page");
+        assert_eq!(blocks(second), vec![100]);
+
+        let token_range = range.with_tokens(vec![Felt::from(1u64)]);
+        let page = storage
+            .get_transfers_for_replay(&token_range, None)
+            .await
+            .expect("token page");
+        assert_eq!(blocks(page), vec![9, 100]);
+
+        let empty = ReplayRange::new(Vec::new(), Some(101), None, 2).unwrap();
+        assert!(storage
+            .get_transfers_for_replay(&empty, None)
+            .await
+            .expect("empty page")
+            .is_empty());
+
+        let _ = std::fs::remove_file(db_path);
+    }
+
+    #[tokio::test]
+    async fn fetched_balances_never_overwrite_later_blocks() {
+        let db_path = temp_db_path("fetched-balances");
+        let storage = Erc20Storage::new(&db_path).await.expect("create storage");
+        let (token, wallet) = (Felt::from(1u64), Felt::from(0xa_u64));
+        let upsert = |amount: u64, block_number: u64| {
+            let storage = &storage;
+            async move {
+                storage
+                    .upsert_fetched_balances(&[(token, wallet, U256::from(amount))], block_number)
+                    .await
+                    .expect("upsert balance")
+            }
+        };
+
+        upsert(5, 9).await;
+        assert_eq!(
+            storage.get_balance_with_block(token, wallet).await.unwrap(),
+            Some((U256::from(5u64), 9))
+        );
+        // Block 100 sorts before 9 as text.
+        upsert(7, 100).await;
+        upsert(3, 20).await;
+        assert_eq!(
+            storage.get_balance_with_block(token, wallet).await.unwrap(),
+            Some((U256::from(7u64), 100))
+        );
+        upsert(8, 100).await;
+        assert_eq!(
+            storage.get_balance(token, wallet).await.unwrap(),
+            Some(U256::from(8u64))
+        );
+
+        let _ = std::fs::remove_file(db_path);
+    }
+
+    #[tokio::test]
+    async fn watched_accounts_are_persisted() {
+        let db_path = temp_db_path("watchlist");
+        let storage = Erc20Storage::new(&db_path).await.expect("create storage");
+        let accounts = [Felt::from(0xb_u64), Felt::from(0xa_u64)];
+
+        assert_eq!(storage.add_watched_accounts(&accounts).await.unwrap(), 2);
+        assert_eq!(
+            storage.add_watched_accounts(&accounts[..1]).await.unwrap(),
+            0
+        );
+        assert_eq!(
+            storage.get_watched_accounts().await.unwrap(),
+            vec![Felt::from(0xa_u64), Felt::from(0xb_u64)]
+        );
+        let removed = storage
+            .remove_watched_accounts(&[Felt::from(0xb_u64), Felt::from(0xc_u64)])
+            .await
+            .unwrap();
+        assert_eq!(removed, 1);
+        assert_eq!(
+            storage.get_watched_accounts().await.unwrap(),
+            vec![Felt::from(0xa_u64)]
+        );
+
+        let _ = std::fs::remove_file(db_path);
+    }
 }
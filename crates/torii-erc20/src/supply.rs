@@ -0,0 +1,241 @@
+//! Mint/burn classification and circulating supply tracking
+//!
+//! Transfers from the zero address are mints and transfers to it are burns. The sink
+//! aggregates them per token and block ([`supply_changes`]) and storage folds the
+//! changes into running totals ([`SupplySnapshot::apply`]): the latest totals of each
+//! token are kept in `token_supply` and one snapshot per token and block with supply
+//! changes in `token_supply_history`.
+//!
+//! Supply is computed from indexed events only: tokens minted before the indexed
+//! range are not counted, so the circulating supply saturates at zero when more is
+//! burned than was seen minted.
+
+use crate::storage::{safe_u256_add, TransferData};
+use starknet::core::types::{Felt, U256};
+use std::collections::{BTreeMap, HashMap};
+
+/// Effect of a transfer on the token supply
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferKind {
+    /// From the zero address
+    Mint,
+    /// To the zero address
+    Burn,
+    /// Between two accounts (supply unchanged)
+    Transfer,
+}
+
+impl TransferKind {
+    pub fn classify(from: Felt, to: Felt) -> Self {
+        match (from == Felt::ZERO, to == Felt::ZERO) {
+            (true, false) => Self::Mint,
+            (false, true) => Self::Burn,
+            _ => Self::Transfer,
+        }
+    }
+}
+
+/// Tokens minted and burned for one token in one block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SupplyChange {
+    pub token: Felt,
+    pub block_number: u64,
+    pub minted: U256,
+    pub burned: U256,
+}
+
+/// Supply of a token after a block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SupplySnapshot {
+    pub token: Felt,
+    pub block_number: u64,
+    /// Minted in this block
+    pub minted: U256,
+    /// Burned in this block
+    pub burned: U256,
+    /// Minted up to and including this block
+    pub total_minted: U256,
+    /// Burned up to and including this block
+    pub total_burned: U256,
+}
+
+impl SupplySnapshot {
+    /// Total minted minus total burned, saturating at zero.
+    pub fn circulating(&self) -> U256 {
+        if self.total_minted > self.total_burned {
+            self.total_minted - self.total_burned
+        } else {
+            U256::from(0u64)
+        }
+    }
+
+    /// Snapshot after applying `change` on top of `previous` (the token's latest snapshot).
+    ///
+    /// A change in the block of `previous` is merged into it, so batches splitting a
+    /// block still produce a single snapshot for it.
+    pub fn apply(previous: Option<&Self>, change: &SupplyChange) -> Self {
+        let zero = U256::from(0u64);
+        let (minted, burned, total_minted, total_burned) = match previous {
+            Some(prev) if prev.block_number == change.block_number => (
+                prev.minted,
+                prev.burned,
+                prev.total_minted,
+                prev.total_burned,
+            ),
+            Some(prev) => (zero, zero, prev.total_minted, prev.total_burned),
+            None => (zero, zero, zero, zero),
+        };
+        Self {
+            token: change.token,
+            block_number: change.block_number,
+            minted: safe_u256_add(minted, change.minted),
+            burned: safe_u256_add(burned, change.burned),
+            total_minted: safe_u256_add(total_minted, change.minted),
+            total_burned: safe_u256_add(total_burned, change.burned),
+        }
+    }
+}
+
+/// Aggregates the mints and burns of `transfers` per token and block, in block order.
+pub fn supply_changes(transfers: &[TransferData]) -> Vec<SupplyChange> {
+    let mut changes: BTreeMap<(u64, Felt), SupplyChange> = BTreeMap::new();
+    for transfer in transfers {
+        let kind = TransferKind::classify(transfer.from, transfer.to);
+        if kind == TransferKind::Transfer {
+            continue;
+        }
+        let change = changes
+            .entry((transfer.block_number, transfer.token))
+            .or_insert_with(|| SupplyChange {
+                token: transfer.token,
+                block_number: transfer.block_number,
+                minted: U256::from(0u64),
+                burned: U256::from(0u64),
+            });
+        if kind == TransferKind::Mint {
+            change.minted = safe_u256_add(change.minted, transfer.amount);
+        } else {
+            change.burned = safe_u256_add(change.burned, transfer.amount);
+        }
+    }
+    changes.into_values().collect()
+}
+
+/// Applies `changes` (in block order) to the latest snapshot of each token in `latest`.
+///
+/// Returns the snapshot of each change; `latest` ends up with the new latest snapshots.
+pub(crate) fn fold_supply_changes(
+    latest: &mut HashMap<Felt, SupplySnapshot>,
+    changes: &[SupplyChange],
+) -> Vec<SupplySnapshot> {
+    changes
+        .iter()
+        .map(|change| {
+            let snapshot = SupplySnapshot::apply(latest.get(&change.token), change);
+            latest.insert(change.token, snapshot.clone());
+            snapshot
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transfer(token: u64, from: u64, to: u64, amount: u64, block_number: u64) -> TransferData {
+        TransferData {
+            id: None,
+            token: Felt::from(token),
+            from: Felt::from(from),
+            to: Felt::from(to),
+            amount: U256::from(amount),
+            block_number,
+            tx_hash: Felt::ONE,
+            timestamp: None,
+            event_index: None,
+            provenance: None,
+        }
+    }
+
+    #[test]
+    fn test_classify_transfer() {
+        assert_eq!(
+            TransferKind::classify(Felt::ZERO, Felt::ONE),
+            TransferKind::Mint
+        );
+        assert_eq!(
+            TransferKind::classify(Felt::ONE, Felt::ZERO),
+            TransferKind::Burn
+        );
+        assert_eq!(
+            TransferKind::classify(Felt::ONE, Felt::TWO),
+            TransferKind::Transfer
+        );
+        assert_eq!(
+            TransferKind::classify(Felt::ZERO, Felt::ZERO),
+            TransferKind::Transfer
+        );
+    }
+
+    #[test]
+    fn test_supply_changes_per_token_and_block() {
+        let changes = supply_changes(&[
+            transfer(1, 0, 5, 100, 11),
+            transfer(1, 5, 6, 40, 11),
+            transfer(1, 6, 0, 30, 10),
+            transfer(1, 0, 6, 20, 11),
+            transfer(2, 0, 5, 7, 10),
+        ]);
+
+        assert_eq!(
+            changes,
+            vec![
+                SupplyChange {
+                    token: Felt::ONE,
+                    block_number: 10,
+                    minted: U256::from(0u64),
+                    burned: U256::from(30u64),
+                },
+                SupplyChange {
+                    token: Felt::TWO,
+                    block_number: 10,
+                    minted: U256::from(7u64),
+                    burned: U256::from(0u64),
+                },
+                SupplyChange {
+                    token: Felt::ONE,
+                    block_number: 11,
+                    minted: U256::from(120u64),
+                    burned: U256::from(0u64),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_snapshots_accumulate_and_merge_blocks() {
+        let change = |block_number, minted: u64, burned: u64| SupplyChange {
+            token: Felt::ONE,
+            block_number,
+            minted: U256::from(minted),
+            burned: U256::from(burned),
+        };
+
+        let first = SupplySnapshot::apply(None, &change(10, 100, 0));
+        let second = SupplySnapshot::apply(Some(&first), &change(12, 0, 30));
+        assert_eq!(second.minted, U256::from(0u64));
+        assert_eq!(second.burned, U256::from(30u64));
+        assert_eq!(second.circulating(), U256::from(70u64));
+
+        // A later batch with more events of block 12 updates the same snapshot.
+        let merged = SupplySnapshot::apply(Some(&second), &change(12, 5, 10));
+        assert_eq!(merged.block_number, 12);
+        assert_eq!(merged.minted, U256::from(5u64));
+        assert_eq!(merged.burned, U256::from(40u64));
+        assert_eq!(merged.circulating(), U256::from(65u64));
+
+        // Burning more than was seen minted saturates at zero.
+        let drained = SupplySnapshot::apply(Some(&merged), &change(13, 0, 1000));
+        assert_eq!(drained.circulating(), U256::from(0u64));
+    }
+}
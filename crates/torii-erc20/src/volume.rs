@@ -0,0 +1,176 @@
+//! Hourly and daily transfer volume rollups
+//!
+//! The sink aggregates transfers per token and time bucket ([`volume_deltas`]) and
+//! storage adds the deltas to `token_volume`, one row per token, interval and bucket,
+//! so charting clients read a series without scanning the transfer history.
+//!
+//! Unique senders and receivers are counted across batches by recording each
+//! participant of a bucket once in `token_volume_participants`. The zero address
+//! (mints and burns) is not counted as a participant. Transfers without a block
+//! timestamp cannot be bucketed and are skipped.
+
+use crate::storage::{safe_u256_add, TransferData};
+use starknet::core::types::{Felt, U256};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Width of a rollup bucket
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum VolumeInterval {
+    Hour,
+    Day,
+}
+
+impl VolumeInterval {
+    pub const ALL: [Self; 2] = [Self::Hour, Self::Day];
+
+    pub fn seconds(self) -> i64 {
+        match self {
+            Self::Hour => 3_600,
+            Self::Day => 86_400,
+        }
+    }
+
+    /// Start (unix seconds, UTC) of the bucket containing `timestamp`.
+    pub fn bucket_start(self, timestamp: i64) -> i64 {
+        timestamp.div_euclid(self.seconds()) * self.seconds()
+    }
+
+    /// Name stored in the `interval` column.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Hour => "hour",
+            Self::Day => "day",
+        }
+    }
+}
+
+/// Transfers of one token within one bucket of one batch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VolumeDelta {
+    pub token: Felt,
+    pub interval: VolumeInterval,
+    pub bucket_start: i64,
+    pub transfer_count: u64,
+    pub volume: U256,
+    pub senders: BTreeSet<Felt>,
+    pub receivers: BTreeSet<Felt>,
+}
+
+/// Rolled-up transfer activity of a token in one bucket
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VolumeBucket {
+    pub token: Felt,
+    pub interval: VolumeInterval,
+    /// Bucket start (unix seconds, UTC)
+    pub bucket_start: i64,
+    pub transfer_count: u64,
+    /// Sum of transferred amounts (raw token units)
+    pub volume: U256,
+    pub unique_senders: u64,
+    pub unique_receivers: u64,
+}
+
+/// Aggregates `transfers` per interval, token and bucket, in bucket order.
+pub fn volume_deltas(transfers: &[TransferData]) -> Vec<VolumeDelta> {
+    let mut deltas: BTreeMap<(VolumeInterval, i64, Felt), VolumeDelta> = BTreeMap::new();
+    for transfer in transfers {
+        let Some(timestamp) = transfer.timestamp else {
+            continue;
+        };
+        for interval in VolumeInterval::ALL {
+            let bucket_start = interval.bucket_start(timestamp);
+            let delta = deltas
+                .entry((interval, bucket_start, transfer.token))
+                .or_insert_with(|| VolumeDelta {
+                    token: transfer.token,
+                    interval,
+                    bucket_start,
+                    transfer_count: 0,
+                    volume: U256::from(0u64),
+                    senders: BTreeSet::new(),
+                    receivers: BTreeSet::new(),
+                });
+            delta.transfer_count += 1;
+            delta.volume = safe_u256_add(delta.volume, transfer.amount);
+            if transfer.from != Felt::ZERO {
+                delta.senders.insert(transfer.from);
+            }
+            if transfer.to != Felt::ZERO {
+                delta.receivers.insert(transfer.to);
+            }
+        }
+    }
+    deltas.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transfer(token: u64, from: u64, to: u64, amount: u64, timestamp: i64) -> TransferData {
+        TransferData {
+            id: None,
+            token: Felt::from(token),
+            from: Felt::from(from),
+            to: Felt::from(to),
+            amount: U256::from(amount),
+            block_number: 1,
+            tx_hash: Felt::ONE,
+            timestamp: Some(timestamp),
+            event_index: None,
+            provenance: None,
+        }
+    }
+
+    #[test]
+    fn test_bucket_start() {
+        assert_eq!(VolumeInterval::Hour.bucket_start(7_199), 3_600);
+        assert_eq!(VolumeInterval::Day.bucket_start(86_400 + 5), 86_400);
+        assert_eq!(VolumeInterval::Hour.bucket_start(-1), -3_600);
+    }
+
+    #[test]
+    fn test_volume_deltas_per_interval_and_bucket() {
+        let mut untimed = transfer(1, 5, 6, 1_000, 0);
+        untimed.timestamp = None;
+        let deltas = volume_deltas(&[
+            transfer(1, 5, 6, 10, 100),
+            transfer(1, 5, 7, 20, 200),
+            transfer(1, 0, 5, 30, 3_700),
+            transfer(2, 6, 0, 40, 100),
+            untimed,
+        ]);
+
+        let hourly: Vec<_> = deltas
+            .iter()
+            .filter(|d| d.interval == VolumeInterval::Hour)
+            .map(|d| {
+                (
+                    d.token,
+                    d.bucket_start,
+                    d.transfer_count,
+                    d.volume,
+                    d.senders.len(),
+                    d.receivers.len(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            hourly,
+            vec![
+                (Felt::ONE, 0, 2, U256::from(30u64), 1, 2),
+                (Felt::TWO, 0, 1, U256::from(40u64), 1, 0),
+                (Felt::ONE, 3_600, 1, U256::from(30u64), 0, 1),
+            ]
+        );
+
+        let daily: Vec<_> = deltas
+            .iter()
+            .filter(|d| d.interval == VolumeInterval::Day)
+            .collect();
+        assert_eq!(daily.len(), 2);
+        assert_eq!(daily[0].transfer_count, 3);
+        assert_eq!(daily[0].volume, U256::from(60u64));
+        assert_eq!(daily[0].receivers.len(), 3);
+    }
+}
@@ -0,0 +1,22 @@
+-- Owner changes per NFT: one row per transfer between different addresses
+-- (mints have a zero previous owner, burns a zero owner)
+CREATE TABLE IF NOT EXISTS erc721.ownership_history (
+    id BIGSERIAL PRIMARY KEY,
+    transfer_id BIGINT NOT NULL UNIQUE REFERENCES erc721.nft_transfers(id),
+    token BYTEA NOT NULL,
+    token_id BYTEA NOT NULL,
+    previous_owner BYTEA NOT NULL,
+    owner BYTEA NOT NULL,
+    block_number BIGINT NOT NULL,
+    tx_hash BYTEA NOT NULL,
+    timestamp TEXT
+);
+CREATE INDEX IF NOT EXISTS idx_ownership_history_token_id_block ON erc721.ownership_history(token, token_id, block_number DESC, id DESC);
+
+-- Backfill from the transfers indexed so far
+INSERT INTO erc721.ownership_history (transfer_id, token, token_id, previous_owner, owner, block_number, tx_hash, timestamp)
+SELECT id, token, token_id, from_addr, to_addr, block_number::BIGINT, tx_hash, timestamp
+FROM erc721.nft_transfers
+WHERE from_addr <> to_addr
+ORDER BY id
+ON CONFLICT (transfer_id) DO NOTHING;
@@ -0,0 +1,2 @@
+-- Owner index ordered like token IDs, for enumerating the tokens of an owner
+CREATE INDEX IF NOT EXISTS idx_nft_ownership_owner_token_token_id_ord ON erc721.nft_ownership(owner, token, octet_length(token_id), token_id);
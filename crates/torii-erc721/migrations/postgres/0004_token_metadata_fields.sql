@@ -0,0 +1,11 @@
+-- Display fields normalized from the metadata JSON (see NormalizedMetadata)
+ALTER TABLE erc721.token_uris ADD COLUMN IF NOT EXISTS name TEXT;
+ALTER TABLE erc721.token_uris ADD COLUMN IF NOT EXISTS description TEXT;
+ALTER TABLE erc721.token_uris ADD COLUMN IF NOT EXISTS image TEXT;
+
+-- Backfill from the metadata fetched so far (later refreshes apply the full normalization)
+UPDATE erc721.token_uris SET
+    name = CASE WHEN jsonb_typeof(metadata_json::jsonb -> 'name') = 'string' THEN NULLIF(TRIM(metadata_json::jsonb ->> 'name'), '') END,
+    description = CASE WHEN jsonb_typeof(metadata_json::jsonb -> 'description') = 'string' THEN NULLIF(TRIM(metadata_json::jsonb ->> 'description'), '') END,
+    image = CASE WHEN jsonb_typeof(metadata_json::jsonb -> 'image') = 'string' THEN NULLIF(TRIM(metadata_json::jsonb ->> 'image'), '') END
+WHERE metadata_json IS NOT NULL;
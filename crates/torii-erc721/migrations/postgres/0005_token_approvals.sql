@@ -0,0 +1,14 @@
+-- Current single-token approval per (token, token_id); transfers reset it
+CREATE TABLE IF NOT EXISTS erc721.nft_token_approvals (
+    token BYTEA NOT NULL,
+    token_id BYTEA NOT NULL,
+    owner BYTEA NOT NULL,
+    approved BYTEA NOT NULL,
+    block_number TEXT NOT NULL,
+    tx_hash BYTEA NOT NULL,
+    timestamp TEXT,
+    PRIMARY KEY (token, token_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_nft_token_approvals_owner ON erc721.nft_token_approvals(owner, token, octet_length(token_id), token_id);
+CREATE INDEX IF NOT EXISTS idx_nft_operators_owner ON erc721.nft_operators(owner, token, operator);
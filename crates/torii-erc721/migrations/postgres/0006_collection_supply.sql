@@ -0,0 +1,8 @@
+-- Supply of each collection, tracked from mints (from zero) and burns (to zero)
+CREATE TABLE IF NOT EXISTS erc721.collection_supply (
+    token BYTEA PRIMARY KEY,
+    block_number BIGINT NOT NULL,
+    total_minted BIGINT NOT NULL,
+    total_burned BIGINT NOT NULL,
+    supply BIGINT NOT NULL
+);
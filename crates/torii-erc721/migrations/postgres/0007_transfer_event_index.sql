@@ -0,0 +1,5 @@
+-- Position of the transfer among the events of its transaction (NULL for rows stored before)
+ALTER TABLE erc721.nft_transfers ADD COLUMN IF NOT EXISTS event_index BIGINT;
+
+-- Transaction lookups (GetTransactionEvents)
+CREATE INDEX IF NOT EXISTS idx_nft_transfers_tx ON erc721.nft_transfers(tx_hash, event_index);
@@ -0,0 +1,2 @@
+-- Point-in-time owner lookups (GetOwnerAtBlock): last transfer of an NFT up to a block
+CREATE INDEX IF NOT EXISTS idx_nft_transfers_token_id_block ON erc721.nft_transfers(token, token_id, (block_number::BIGINT) DESC, id DESC);
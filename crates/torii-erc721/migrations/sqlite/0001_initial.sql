@@ -0,0 +1,138 @@
+-- ERC721 storage schema (SQLite)
+
+-- NFT ownership (current state) - one owner per NFT
+CREATE TABLE IF NOT EXISTS nft_ownership (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    token BLOB NOT NULL,
+    token_id BLOB NOT NULL,
+    owner BLOB NOT NULL,
+    block_number TEXT NOT NULL,
+    tx_hash BLOB NOT NULL,
+    timestamp TEXT,
+    UNIQUE(token, token_id)
+);
+CREATE INDEX IF NOT EXISTS idx_nft_ownership_owner ON nft_ownership(owner);
+CREATE INDEX IF NOT EXISTS idx_nft_ownership_token ON nft_ownership(token);
+CREATE INDEX IF NOT EXISTS idx_nft_ownership_token_owner_token_id_ord ON nft_ownership(token, owner, length(token_id), token_id);
+CREATE INDEX IF NOT EXISTS idx_nft_ownership_owner_token_token_id_ord ON nft_ownership(owner, token, length(token_id), token_id);
+
+-- Transfer history
+CREATE TABLE IF NOT EXISTS nft_transfers (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    token BLOB NOT NULL,
+    token_id BLOB NOT NULL,
+    from_addr BLOB NOT NULL,
+    to_addr BLOB NOT NULL,
+    block_number TEXT NOT NULL,
+    tx_hash BLOB NOT NULL,
+    timestamp TEXT,
+    UNIQUE(token, tx_hash, token_id, from_addr, to_addr)
+);
+CREATE INDEX IF NOT EXISTS idx_nft_transfers_token ON nft_transfers(token);
+CREATE INDEX IF NOT EXISTS idx_nft_transfers_from ON nft_transfers(from_addr);
+CREATE INDEX IF NOT EXISTS idx_nft_transfers_to ON nft_transfers(to_addr);
+CREATE INDEX IF NOT EXISTS idx_nft_transfers_block ON nft_transfers(block_number DESC);
+CREATE INDEX IF NOT EXISTS idx_nft_transfers_token_id ON nft_transfers(token, token_id);
+
+-- Wallet activity table for efficient OR queries
+CREATE TABLE IF NOT EXISTS nft_wallet_activity (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    wallet_address BLOB NOT NULL,
+    token BLOB NOT NULL,
+    transfer_id INTEGER NOT NULL,
+    direction TEXT NOT NULL CHECK(direction IN ('sent', 'received', 'both')),
+    block_number TEXT NOT NULL,
+    FOREIGN KEY (transfer_id) REFERENCES nft_transfers(id)
+);
+CREATE INDEX IF NOT EXISTS idx_nft_wallet_activity_wallet_block ON nft_wallet_activity(wallet_address, block_number DESC);
+CREATE INDEX IF NOT EXISTS idx_nft_wallet_activity_wallet_token ON nft_wallet_activity(wallet_address, token, block_number DESC);
+
+-- Approvals (single token)
+CREATE TABLE IF NOT EXISTS nft_approvals (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    token BLOB NOT NULL,
+    token_id BLOB NOT NULL,
+    owner BLOB NOT NULL,
+    approved BLOB NOT NULL,
+    block_number TEXT NOT NULL,
+    tx_hash BLOB NOT NULL,
+    timestamp TEXT
+);
+
+-- Operator approvals (all tokens)
+CREATE TABLE IF NOT EXISTS nft_operators (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    token BLOB NOT NULL,
+    owner BLOB NOT NULL,
+    operator BLOB NOT NULL,
+    approved TEXT NOT NULL,
+    block_number TEXT NOT NULL,
+    tx_hash BLOB NOT NULL,
+    timestamp TEXT,
+    UNIQUE(token, owner, operator)
+);
+
+-- Token metadata table
+CREATE TABLE IF NOT EXISTS token_metadata (
+    token BLOB PRIMARY KEY,
+    name TEXT,
+    symbol TEXT,
+    total_supply BLOB
+);
+
+CREATE TABLE IF NOT EXISTS token_uris (
+    token BLOB NOT NULL,
+    token_id BLOB NOT NULL,
+    uri TEXT,
+    metadata_json TEXT,
+    updated_at TEXT NOT NULL DEFAULT (strftime('%s', 'now')),
+    PRIMARY KEY (token, token_id)
+);
+CREATE INDEX IF NOT EXISTS idx_token_uris_token ON token_uris(token);
+
+CREATE TABLE IF NOT EXISTS token_attributes (
+    token BLOB NOT NULL,
+    token_id BLOB NOT NULL,
+    key TEXT NOT NULL,
+    value TEXT NOT NULL,
+    PRIMARY KEY (token, token_id, key),
+    FOREIGN KEY (token, token_id) REFERENCES token_uris(token, token_id)
+);
+CREATE INDEX IF NOT EXISTS idx_token_attributes_token ON token_attributes(token);
+CREATE INDEX IF NOT EXISTS idx_token_attributes_key ON token_attributes(key);
+CREATE INDEX IF NOT EXISTS idx_token_attributes_key_value ON token_attributes(key, value);
+CREATE INDEX IF NOT EXISTS idx_token_attributes_token_key_value ON token_attributes(token, key, value);
+CREATE INDEX IF NOT EXISTS idx_token_attributes_token_token_id ON token_attributes(token, token_id);
+
+CREATE TABLE IF NOT EXISTS facet_keys (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    token BLOB NOT NULL,
+    key_norm TEXT NOT NULL,
+    key_display TEXT NOT NULL,
+    UNIQUE(token, key_norm)
+);
+CREATE INDEX IF NOT EXISTS idx_facet_keys_token_key_norm ON facet_keys(token, key_norm);
+
+CREATE TABLE IF NOT EXISTS facet_values (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    token BLOB NOT NULL,
+    facet_key_id INTEGER NOT NULL,
+    value_norm TEXT NOT NULL,
+    value_display TEXT NOT NULL,
+    token_count TEXT NOT NULL DEFAULT '0',
+    UNIQUE(token, facet_key_id, value_norm),
+    FOREIGN KEY (facet_key_id) REFERENCES facet_keys(id) ON DELETE CASCADE
+);
+CREATE INDEX IF NOT EXISTS idx_facet_values_token_key_value ON facet_values(token, facet_key_id, value_norm);
+
+CREATE TABLE IF NOT EXISTS facet_token_map (
+    token BLOB NOT NULL,
+    token_id BLOB NOT NULL,
+    facet_key_id INTEGER NOT NULL,
+    facet_value_id INTEGER NOT NULL,
+    PRIMARY KEY (token, token_id, facet_key_id),
+    FOREIGN KEY (facet_key_id) REFERENCES facet_keys(id) ON DELETE CASCADE,
+    FOREIGN KEY (facet_value_id) REFERENCES facet_values(id) ON DELETE CASCADE
+);
+CREATE INDEX IF NOT EXISTS idx_facet_token_map_token_value_token_id ON facet_token_map(token, facet_value_id, token_id);
+CREATE INDEX IF NOT EXISTS idx_facet_token_map_token_token_id_key_value ON facet_token_map(token, token_id, facet_key_id, facet_value_id);
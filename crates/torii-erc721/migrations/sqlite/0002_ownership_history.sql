@@ -0,0 +1,22 @@
+-- Owner changes per NFT: one row per transfer between different addresses
+-- (mints have a zero previous owner, burns a zero owner)
+CREATE TABLE IF NOT EXISTS ownership_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    transfer_id INTEGER NOT NULL UNIQUE,
+    token BLOB NOT NULL,
+    token_id BLOB NOT NULL,
+    previous_owner BLOB NOT NULL,
+    owner BLOB NOT NULL,
+    block_number INTEGER NOT NULL,
+    tx_hash BLOB NOT NULL,
+    timestamp TEXT,
+    FOREIGN KEY (transfer_id) REFERENCES nft_transfers(id)
+);
+CREATE INDEX IF NOT EXISTS idx_ownership_history_token_id_block ON ownership_history(token, token_id, block_number DESC, id DESC);
+
+-- Backfill from the transfers indexed so far
+INSERT OR IGNORE INTO ownership_history (transfer_id, token, token_id, previous_owner, owner, block_number, tx_hash, timestamp)
+SELECT id, token, token_id, from_addr, to_addr, CAST(block_number AS INTEGER), tx_hash, timestamp
+FROM nft_transfers
+WHERE from_addr <> to_addr
+ORDER BY id;
@@ -0,0 +1,11 @@
+-- Display fields normalized from the metadata JSON (see NormalizedMetadata)
+ALTER TABLE token_uris ADD COLUMN name TEXT;
+ALTER TABLE token_uris ADD COLUMN description TEXT;
+ALTER TABLE token_uris ADD COLUMN image TEXT;
+
+-- Backfill from the metadata fetched so far (later refreshes apply the full normalization)
+UPDATE token_uris SET
+    name = CASE WHEN json_type(metadata_json, '$.name') = 'text' THEN NULLIF(TRIM(json_extract(metadata_json, '$.name')), '') END,
+    description = CASE WHEN json_type(metadata_json, '$.description') = 'text' THEN NULLIF(TRIM(json_extract(metadata_json, '$.description')), '') END,
+    image = CASE WHEN json_type(metadata_json, '$.image') = 'text' THEN NULLIF(TRIM(json_extract(metadata_json, '$.image')), '') END
+WHERE json_valid(metadata_json);
@@ -0,0 +1,14 @@
+-- Current single-token approval per (token, token_id); transfers reset it
+CREATE TABLE IF NOT EXISTS nft_token_approvals (
+    token BLOB NOT NULL,
+    token_id BLOB NOT NULL,
+    owner BLOB NOT NULL,
+    approved BLOB NOT NULL,
+    block_number TEXT NOT NULL,
+    tx_hash BLOB NOT NULL,
+    timestamp TEXT,
+    PRIMARY KEY (token, token_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_nft_token_approvals_owner ON nft_token_approvals(owner, token, length(token_id), token_id);
+CREATE INDEX IF NOT EXISTS idx_nft_operators_owner ON nft_operators(owner, token, operator);
@@ -0,0 +1,8 @@
+-- Supply of each collection, tracked from mints (from zero) and burns (to zero)
+CREATE TABLE IF NOT EXISTS collection_supply (
+    token BLOB PRIMARY KEY,
+    block_number INTEGER NOT NULL,
+    total_minted INTEGER NOT NULL,
+    total_burned INTEGER NOT NULL,
+    supply INTEGER NOT NULL
+);
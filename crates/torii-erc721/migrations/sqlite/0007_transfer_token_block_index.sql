@@ -0,0 +1,2 @@
+-- Point-in-time owner lookups (GetOwnerAtBlock): last transfer of an NFT up to a block
+CREATE INDEX IF NOT EXISTS idx_nft_transfers_token_id_block ON nft_transfers(token, token_id, CAST(block_number AS INTEGER) DESC, id DESC);
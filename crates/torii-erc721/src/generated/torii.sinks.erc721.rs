@@ -90,6 +90,31 @@ pub struct Ownership {
     #[prost(uint64, tag = "4")]
     pub block_number: u64,
 }
+/// NFT owner change (one per transfer between different addresses)
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OwnershipChange {
+    /// Token contract address (32 bytes)
+    #[prost(bytes = "vec", tag = "1")]
+    pub token: ::prost::alloc::vec::Vec<u8>,
+    /// NFT token ID as U256 (variable length, up to 32 bytes)
+    #[prost(bytes = "vec", tag = "2")]
+    pub token_id: ::prost::alloc::vec::Vec<u8>,
+    /// Owner before the transfer (zero for mints, 32 bytes)
+    #[prost(bytes = "vec", tag = "3")]
+    pub previous_owner: ::prost::alloc::vec::Vec<u8>,
+    /// Owner after the transfer (zero for burns, 32 bytes)
+    #[prost(bytes = "vec", tag = "4")]
+    pub owner: ::prost::alloc::vec::Vec<u8>,
+    /// Block number of the transfer
+    #[prost(uint64, tag = "5")]
+    pub block_number: u64,
+    /// Transaction hash (32 bytes)
+    #[prost(bytes = "vec", tag = "6")]
+    pub tx_hash: ::prost::alloc::vec::Vec<u8>,
+    /// Unix timestamp of the block
+    #[prost(int64, tag = "7")]
+    pub timestamp: i64,
+}
 /// Filter for transfer queries and subscriptions
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct TransferFilter {
@@ -150,6 +175,12 @@ pub struct GetTransfersRequest {
     /// Maximum number of transfers to return (default: 100, max: 1000)
     #[prost(uint32, tag = "3")]
     pub limit: u32,
+    /// Attach the labels of the tokens and wallets in the returned transfers
+    #[prost(bool, tag = "4")]
+    pub include_labels: bool,
+    /// Transfer fields to return (empty = all). Other fields are left unset.
+    #[prost(string, repeated, tag = "5")]
+    pub field_mask: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
 /// Response for GetTransfers RPC
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -160,6 +191,22 @@ pub struct GetTransfersResponse {
     /// Cursor for next page (absent if no more results)
     #[prost(message, optional, tag = "2")]
     pub next_cursor: ::core::option::Option<Cursor>,
+    /// Labels of the labelled addresses in `transfers` (when include_labels is set)
+    #[prost(message, repeated, tag = "3")]
+    pub labels: ::prost::alloc::vec::Vec<AddressLabel>,
+}
+/// Human-readable label of an address (exchange, bridge, known spam, ...)
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AddressLabel {
+    /// Labelled address (32 bytes)
+    #[prost(bytes = "vec", tag = "1")]
+    pub address: ::prost::alloc::vec::Vec<u8>,
+    /// Display name
+    #[prost(string, tag = "2")]
+    pub name: ::prost::alloc::string::String,
+    /// Free-form tags (e.g. "cex", "spam")
+    #[prost(string, repeated, tag = "3")]
+    pub tags: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
 /// Request for GetOwnership RPC
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -173,6 +220,13 @@ pub struct GetOwnershipRequest {
     /// Maximum number of ownership records to return (default: 100, max: 1000)
     #[prost(uint32, tag = "3")]
     pub limit: u32,
+    /// Attach the labels of the tokens and owners in the returned records
+    #[prost(bool, tag = "4")]
+    pub include_labels: bool,
+    /// Ownership fields to return (empty = all). Other fields are left unset,
+    /// e.g. \["token", "token_id"\] to list the NFTs of a wallet.
+    #[prost(string, repeated, tag = "5")]
+    pub field_mask: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
 /// Response for GetOwnership RPC
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -183,6 +237,9 @@ pub struct GetOwnershipResponse {
     /// Cursor for next page (absent if no more results)
     #[prost(message, optional, tag = "2")]
     pub next_cursor: ::core::option::Option<Cursor>,
+    /// Labels of the labelled addresses in `ownership` (when include_labels is set)
+    #[prost(message, repeated, tag = "3")]
+    pub labels: ::prost::alloc::vec::Vec<AddressLabel>,
 }
 /// Request for GetOwner RPC
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -201,6 +258,169 @@ pub struct GetOwnerResponse {
     #[prost(bytes = "vec", optional, tag = "1")]
     pub owner: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
 }
+/// Request for GetOwnerAtBlock RPC
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetOwnerAtBlockRequest {
+    /// Token contract address (32 bytes)
+    #[prost(bytes = "vec", tag = "1")]
+    pub token: ::prost::alloc::vec::Vec<u8>,
+    /// NFT token ID as U256 (variable length, up to 32 bytes)
+    #[prost(bytes = "vec", tag = "2")]
+    pub token_id: ::prost::alloc::vec::Vec<u8>,
+    /// Block at the end of which the owner is returned
+    #[prost(uint64, tag = "3")]
+    pub block_number: u64,
+}
+/// Response for GetOwnerAtBlock RPC
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetOwnerAtBlockResponse {
+    /// Owner address at the end of the block (absent if not minted yet or burned)
+    #[prost(bytes = "vec", optional, tag = "1")]
+    pub owner: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+    /// Block of the transfer to the owner
+    #[prost(uint64, optional, tag = "2")]
+    pub acquired_block: ::core::option::Option<u64>,
+    /// Transaction of the transfer to the owner
+    #[prost(bytes = "vec", optional, tag = "3")]
+    pub acquired_tx_hash: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+}
+/// Request for GetOwnershipHistory RPC
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetOwnershipHistoryRequest {
+    /// Token contract address (32 bytes)
+    #[prost(bytes = "vec", tag = "1")]
+    pub token: ::prost::alloc::vec::Vec<u8>,
+    /// NFT token ID as U256 (variable length, up to 32 bytes)
+    #[prost(bytes = "vec", tag = "2")]
+    pub token_id: ::prost::alloc::vec::Vec<u8>,
+    /// Cursor from previous response (omit for first page)
+    #[prost(message, optional, tag = "3")]
+    pub cursor: ::core::option::Option<Cursor>,
+    /// Maximum number of owner changes to return (default: 100, max: 1000)
+    #[prost(uint32, tag = "4")]
+    pub limit: u32,
+}
+/// Response for GetOwnershipHistory RPC
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetOwnershipHistoryResponse {
+    /// Owner changes, newest first
+    #[prost(message, repeated, tag = "1")]
+    pub changes: ::prost::alloc::vec::Vec<OwnershipChange>,
+    /// Cursor for next page (absent if no more results)
+    #[prost(message, optional, tag = "2")]
+    pub next_cursor: ::core::option::Option<Cursor>,
+}
+/// Cursor for GetTokensByOwner (opaque to clients)
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OwnedTokenCursor {
+    /// Token contract address of the last returned token (32 bytes)
+    #[prost(bytes = "vec", tag = "1")]
+    pub token: ::prost::alloc::vec::Vec<u8>,
+    /// NFT token ID of the last returned token (U256 bytes)
+    #[prost(bytes = "vec", tag = "2")]
+    pub token_id: ::prost::alloc::vec::Vec<u8>,
+}
+/// NFT owned by an address
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OwnedToken {
+    /// Token contract address (32 bytes)
+    #[prost(bytes = "vec", tag = "1")]
+    pub token: ::prost::alloc::vec::Vec<u8>,
+    /// NFT token ID as U256 (variable length, up to 32 bytes)
+    #[prost(bytes = "vec", tag = "2")]
+    pub token_id: ::prost::alloc::vec::Vec<u8>,
+    /// Block number when ownership was last updated
+    #[prost(uint64, tag = "3")]
+    pub block_number: u64,
+    /// On-chain token URI (if requested and known)
+    #[prost(string, optional, tag = "4")]
+    pub uri: ::core::option::Option<::prost::alloc::string::String>,
+    /// Raw metadata JSON (if requested and cached)
+    #[prost(string, optional, tag = "5")]
+    pub metadata_json: ::core::option::Option<::prost::alloc::string::String>,
+}
+/// Request for GetTokensByOwner RPC
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetTokensByOwnerRequest {
+    /// Owner address (32 bytes)
+    #[prost(bytes = "vec", tag = "1")]
+    pub owner: ::prost::alloc::vec::Vec<u8>,
+    /// Cursor from previous response (omit for first page)
+    #[prost(message, optional, tag = "2")]
+    pub cursor: ::core::option::Option<OwnedTokenCursor>,
+    /// Maximum number of tokens to return (default: 100, max: 1000)
+    #[prost(uint32, tag = "3")]
+    pub limit: u32,
+    /// Inline token URI and metadata JSON in each token
+    #[prost(bool, tag = "4")]
+    pub include_metadata: bool,
+}
+/// Response for GetTokensByOwner RPC
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetTokensByOwnerResponse {
+    /// Owned tokens, ordered by contract then token ID
+    #[prost(message, repeated, tag = "1")]
+    pub tokens: ::prost::alloc::vec::Vec<OwnedToken>,
+    /// Cursor for next page (absent if no more results)
+    #[prost(message, optional, tag = "2")]
+    pub next_cursor: ::core::option::Option<OwnedTokenCursor>,
+}
+/// Request for GetApprovals RPC
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetApprovalsRequest {
+    /// Owner address (32 bytes)
+    #[prost(bytes = "vec", tag = "1")]
+    pub owner: ::prost::alloc::vec::Vec<u8>,
+    /// Cursor from previous response (omit for first page)
+    #[prost(message, optional, tag = "2")]
+    pub cursor: ::core::option::Option<OwnedTokenCursor>,
+    /// Maximum number of approvals to return (default: 100, max: 1000)
+    #[prost(uint32, tag = "3")]
+    pub limit: u32,
+}
+/// Response for GetApprovals RPC
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetApprovalsResponse {
+    /// Current approvals (cleared when the token is transferred), ordered by contract then token ID
+    #[prost(message, repeated, tag = "1")]
+    pub approvals: ::prost::alloc::vec::Vec<NftApproval>,
+    /// Cursor for next page (absent if no more results)
+    #[prost(message, optional, tag = "2")]
+    pub next_cursor: ::core::option::Option<OwnedTokenCursor>,
+}
+/// Cursor for GetApprovedOperators (opaque to clients)
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OperatorCursor {
+    /// Token contract address of the last returned operator (32 bytes)
+    #[prost(bytes = "vec", tag = "1")]
+    pub token: ::prost::alloc::vec::Vec<u8>,
+    /// Last returned operator address (32 bytes)
+    #[prost(bytes = "vec", tag = "2")]
+    pub operator: ::prost::alloc::vec::Vec<u8>,
+}
+/// Request for GetApprovedOperators RPC
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetApprovedOperatorsRequest {
+    /// Owner address (32 bytes)
+    #[prost(bytes = "vec", tag = "1")]
+    pub owner: ::prost::alloc::vec::Vec<u8>,
+    /// Cursor from previous response (omit for first page)
+    #[prost(message, optional, tag = "2")]
+    pub cursor: ::core::option::Option<OperatorCursor>,
+    /// Maximum number of operators to return (default: 100, max: 1000)
+    #[prost(uint32, tag = "3")]
+    pub limit: u32,
+}
+/// Response for GetApprovedOperators RPC
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetApprovedOperatorsResponse {
+    /// Approved operators, ordered by contract then operator
+    #[prost(message, repeated, tag = "1")]
+    pub operators: ::prost::alloc::vec::Vec<OperatorApproval>,
+    /// Cursor for next page (absent if no more results)
+    #[prost(message, optional, tag = "2")]
+    pub next_cursor: ::core::option::Option<OperatorCursor>,
+}
 /// OR-within-key filter values; AND logic is applied across keys.
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct AttributeFilter {
@@ -262,6 +482,22 @@ pub struct QueryTokensByAttributesResponse {
     #[prost(message, repeated, tag = "4")]
     pub facets: ::prost::alloc::vec::Vec<AttributeFacetCount>,
 }
+/// Request for ReplayTransfers RPC
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReplayTransfersRequest {
+    /// Minimum block number (inclusive)
+    #[prost(uint64, optional, tag = "1")]
+    pub block_from: ::core::option::Option<u64>,
+    /// Maximum block number (inclusive)
+    #[prost(uint64, optional, tag = "2")]
+    pub block_to: ::core::option::Option<u64>,
+    /// Token whitelist (empty = all tokens)
+    #[prost(bytes = "vec", repeated, tag = "3")]
+    pub tokens: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+    /// Rows read from storage per page (default: 500, max: 5000)
+    #[prost(uint32, tag = "4")]
+    pub page_size: u32,
+}
 /// Request for SubscribeTransfers RPC
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SubscribeTransfersRequest {
@@ -273,6 +509,13 @@ pub struct SubscribeTransfersRequest {
     pub filter: ::core::option::Option<TransferFilter>,
 }
 /// Update message for transfer subscriptions
+/// Last message of a subscription stream: the server is shutting down
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct StreamShutdown {
+    /// Last block fully indexed; resume (e.g. with ReplayTransfers) from the next block
+    #[prost(uint64, tag = "1")]
+    pub block_number: u64,
+}
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct TransferUpdate {
     /// The transfer event
@@ -281,6 +524,102 @@ pub struct TransferUpdate {
     /// Unix timestamp when the update was generated
     #[prost(int64, tag = "2")]
     pub timestamp: i64,
+    /// Set on the last update when the server shuts down (transfer is then absent)
+    #[prost(message, optional, tag = "3")]
+    pub shutdown: ::core::option::Option<StreamShutdown>,
+}
+/// Request for SubscribeMints RPC
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SubscribeMintsRequest {
+    /// Client identifier for logging/debugging
+    #[prost(string, tag = "1")]
+    pub client_id: ::prost::alloc::string::String,
+    /// Collection to follow (32 bytes, empty = every collection)
+    #[prost(bytes = "vec", tag = "2")]
+    pub contract: ::prost::alloc::vec::Vec<u8>,
+}
+/// Mint (from zero) or burn (to zero) of an NFT
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MintUpdate {
+    /// Token contract address (32 bytes)
+    #[prost(bytes = "vec", tag = "1")]
+    pub token: ::prost::alloc::vec::Vec<u8>,
+    /// NFT token ID as U256 (variable length, up to 32 bytes)
+    #[prost(bytes = "vec", tag = "2")]
+    pub token_id: ::prost::alloc::vec::Vec<u8>,
+    /// True for a burn, false for a mint
+    #[prost(bool, tag = "3")]
+    pub burn: bool,
+    /// Receiver of a mint or sender of a burn (32 bytes)
+    #[prost(bytes = "vec", tag = "4")]
+    pub account: ::prost::alloc::vec::Vec<u8>,
+    /// Block number of the mint or burn
+    #[prost(uint64, tag = "5")]
+    pub block_number: u64,
+    /// Transaction hash (32 bytes)
+    #[prost(bytes = "vec", tag = "6")]
+    pub tx_hash: ::prost::alloc::vec::Vec<u8>,
+    /// Supply of the collection once the indexed batch with this event is applied
+    #[prost(uint64, tag = "7")]
+    pub supply: u64,
+    /// Unix timestamp when the update was generated
+    #[prost(int64, tag = "8")]
+    pub timestamp: i64,
+    /// Set on the last update when the server shuts down (other fields are then empty)
+    #[prost(message, optional, tag = "9")]
+    pub shutdown: ::core::option::Option<StreamShutdown>,
+}
+/// Request for WatchAddresses RPC (each message replaces the watched set)
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct WatchAddressesRequest {
+    /// Client identifier for logging/debugging
+    #[prost(string, tag = "1")]
+    pub client_id: ::prost::alloc::string::String,
+    /// Account addresses to watch (32 bytes each, max 10000)
+    #[prost(bytes = "vec", repeated, tag = "2")]
+    pub addresses: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+}
+/// Update pushed to address watchers: a transfer from or to a watched address
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct WatchUpdate {
+    /// The transfer event
+    #[prost(message, optional, tag = "1")]
+    pub transfer: ::core::option::Option<NftTransfer>,
+    /// Resulting ownership of the transferred token
+    #[prost(message, optional, tag = "2")]
+    pub ownership: ::core::option::Option<Ownership>,
+    /// Unix timestamp when the update was generated
+    #[prost(int64, tag = "3")]
+    pub timestamp: i64,
+    /// Set on the last update when the server shuts down (transfer is then absent)
+    #[prost(message, optional, tag = "4")]
+    pub shutdown: ::core::option::Option<StreamShutdown>,
+}
+/// Request for GetTokenImage RPC
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetTokenImageRequest {
+    /// Token contract address (32 bytes)
+    #[prost(bytes = "vec", tag = "1")]
+    pub token: ::prost::alloc::vec::Vec<u8>,
+    /// Token ID as U256 (variable length, up to 32 bytes)
+    #[prost(bytes = "vec", tag = "2")]
+    pub token_id: ::prost::alloc::vec::Vec<u8>,
+}
+/// Response for GetTokenImage RPC (signed URLs of the assets in object storage)
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetTokenImageResponse {
+    /// Signed URL of the cached image
+    #[prost(string, tag = "1")]
+    pub image_url: ::prost::alloc::string::String,
+    /// Content type of the image
+    #[prost(string, tag = "2")]
+    pub content_type: ::prost::alloc::string::String,
+    /// Signed URL of the metadata JSON (absent if the token has none)
+    #[prost(string, optional, tag = "3")]
+    pub metadata_url: ::core::option::Option<::prost::alloc::string::String>,
+    /// Unix timestamp (seconds) when the URLs expire
+    #[prost(int64, tag = "4")]
+    pub expires_at: i64,
 }
 /// Request for GetTokenMetadata RPC
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -339,6 +678,15 @@ pub struct CollectionToken {
     /// Resolved static image URL (if requested)
     #[prost(string, optional, tag = "5")]
     pub image_url: ::core::option::Option<::prost::alloc::string::String>,
+    /// Name normalized from the metadata JSON
+    #[prost(string, optional, tag = "6")]
+    pub name: ::core::option::Option<::prost::alloc::string::String>,
+    /// Description normalized from the metadata JSON
+    #[prost(string, optional, tag = "7")]
+    pub description: ::core::option::Option<::prost::alloc::string::String>,
+    /// Image URI normalized from the metadata JSON (image, image_url, properties.image)
+    #[prost(string, optional, tag = "8")]
+    pub image: ::core::option::Option<::prost::alloc::string::String>,
 }
 /// Summary for one trait key
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -478,6 +826,32 @@ pub struct GetCollectionOverviewResponse {
     #[prost(message, repeated, tag = "1")]
     pub overviews: ::prost::alloc::vec::Vec<ContractCollectionOverview>,
 }
+/// Request for GetSupply RPC
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetSupplyRequest {
+    /// Collection contract address (32 bytes)
+    #[prost(bytes = "vec", tag = "1")]
+    pub contract: ::prost::alloc::vec::Vec<u8>,
+}
+/// Response for GetSupply RPC (supply tracked from indexed mints and burns)
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetSupplyResponse {
+    /// Collection contract address (32 bytes)
+    #[prost(bytes = "vec", tag = "1")]
+    pub contract: ::prost::alloc::vec::Vec<u8>,
+    /// Tokens minted (transfers from the zero address)
+    #[prost(uint64, tag = "2")]
+    pub total_minted: u64,
+    /// Tokens burned (transfers to the zero address)
+    #[prost(uint64, tag = "3")]
+    pub total_burned: u64,
+    /// Tokens in circulation (minted - burned)
+    #[prost(uint64, tag = "4")]
+    pub supply: u64,
+    /// Block of the last mint or burn (0 if none was indexed)
+    #[prost(uint64, tag = "5")]
+    pub block_number: u64,
+}
 /// Request for GetStats RPC
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
 pub struct GetStatsRequest {}
@@ -534,6 +908,46 @@ pub mod erc721_server {
             tonic::Response<super::GetOwnerResponse>,
             tonic::Status,
         >;
+        /// Get the owner of a specific NFT at the end of a block (snapshots)
+        async fn get_owner_at_block(
+            &self,
+            request: tonic::Request<super::GetOwnerAtBlockRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetOwnerAtBlockResponse>,
+            tonic::Status,
+        >;
+        /// Get the owner changes of a specific NFT (provenance), newest first
+        async fn get_ownership_history(
+            &self,
+            request: tonic::Request<super::GetOwnershipHistoryRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetOwnershipHistoryResponse>,
+            tonic::Status,
+        >;
+        /// Enumerate the NFTs owned by an address across every indexed collection
+        async fn get_tokens_by_owner(
+            &self,
+            request: tonic::Request<super::GetTokensByOwnerRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetTokensByOwnerResponse>,
+            tonic::Status,
+        >;
+        /// List the current single-token approvals granted by an owner
+        async fn get_approvals(
+            &self,
+            request: tonic::Request<super::GetApprovalsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetApprovalsResponse>,
+            tonic::Status,
+        >;
+        /// List the operators currently approved for all tokens of an owner
+        async fn get_approved_operators(
+            &self,
+            request: tonic::Request<super::GetApprovedOperatorsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetApprovedOperatorsResponse>,
+            tonic::Status,
+        >;
         /// Get token metadata (name, symbol)
         async fn get_token_metadata(
             &self,
@@ -542,6 +956,14 @@ pub mod erc721_server {
             tonic::Response<super::GetTokenMetadataResponse>,
             tonic::Status,
         >;
+        /// Get signed URLs of the image and metadata JSON cached in object storage
+        async fn get_token_image(
+            &self,
+            request: tonic::Request<super::GetTokenImageRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetTokenImageResponse>,
+            tonic::Status,
+        >;
         /// Query token IDs by flattened metadata attributes (supports intersections)
         async fn query_tokens_by_attributes(
             &self,
@@ -588,6 +1010,57 @@ pub mod erc721_server {
             tonic::Response<Self::SubscribeTransfersStream>,
             tonic::Status,
         >;
+        /// Server streaming response type for the SubscribeMints method.
+        type SubscribeMintsStream: tonic::codegen::tokio_stream::Stream<
+                Item = std::result::Result<super::MintUpdate, tonic::Status>,
+            >
+            + std::marker::Send
+            + 'static;
+        /// Subscribe to the mints and burns of one or every collection
+        async fn subscribe_mints(
+            &self,
+            request: tonic::Request<super::SubscribeMintsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<Self::SubscribeMintsStream>,
+            tonic::Status,
+        >;
+        /// Get the supply of a collection, tracked from indexed mints and burns
+        async fn get_supply(
+            &self,
+            request: tonic::Request<super::GetSupplyRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetSupplyResponse>,
+            tonic::Status,
+        >;
+        /// Server streaming response type for the WatchAddresses method.
+        type WatchAddressesStream: tonic::codegen::tokio_stream::Stream<
+                Item = std::result::Result<super::WatchUpdate, tonic::Status>,
+            >
+            + std::marker::Send
+            + 'static;
+        /// Watch account addresses: pushes only transfers/ownership changes involving them.
+        /// Send a new request on the stream to replace the watched set.
+        async fn watch_addresses(
+            &self,
+            request: tonic::Request<tonic::Streaming<super::WatchAddressesRequest>>,
+        ) -> std::result::Result<
+            tonic::Response<Self::WatchAddressesStream>,
+            tonic::Status,
+        >;
+        /// Server streaming response type for the ReplayTransfers method.
+        type ReplayTransfersStream: tonic::codegen::tokio_stream::Stream<
+                Item = std::result::Result<super::NftTransfer, tonic::Status>,
+            >
+            + std::marker::Send
+            + 'static;
+        /// Stream all stored transfers in a block range, in indexing order
+        async fn replay_transfers(
+            &self,
+            request: tonic::Request<super::ReplayTransfersRequest>,
+        ) -> std::result::Result<
+            tonic::Response<Self::ReplayTransfersStream>,
+            tonic::Status,
+        >;
         /// Get indexer statistics
         async fn get_stats(
             &self,
@@ -807,12 +1280,235 @@ pub mod erc721_server {
                     };
                     Box::pin(fut)
                 }
-                "/torii.sinks.erc721.Erc721/GetTokenMetadata" => {
+                "/torii.sinks.erc721.Erc721/GetOwnerAtBlock" => {
                     #[allow(non_camel_case_types)]
-                    struct GetTokenMetadataSvc<T: Erc721>(pub Arc<T>);
-                    impl<
-                        T: Erc721,
-                    > tonic::server::UnaryService<super::GetTokenMetadataRequest>
+                    struct GetOwnerAtBlockSvc<T: Erc721>(pub Arc<T>);
+                    impl<T: Erc721> tonic::server::UnaryService<super::GetOwnerAtBlockRequest>
+                    for GetOwnerAtBlockSvc<T> {
+                        type Response = super::GetOwnerAtBlockResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetOwnerAtBlockRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Erc721>::get_owner_at_block(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetOwnerAtBlockSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/torii.sinks.erc721.Erc721/GetOwnershipHistory" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetOwnershipHistorySvc<T: Erc721>(pub Arc<T>);
+                    impl<
+                        T: Erc721,
+                    > tonic::server::UnaryService<super::GetOwnershipHistoryRequest>
+                    for GetOwnershipHistorySvc<T> {
+                        type Response = super::GetOwnershipHistoryResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetOwnershipHistoryRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Erc721>::get_ownership_history(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetOwnershipHistorySvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/torii.sinks.erc721.Erc721/GetTokensByOwner" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetTokensByOwnerSvc<T: Erc721>(pub Arc<T>);
+                    impl<
+                        T: Erc721,
+                    > tonic::server::UnaryService<super::GetTokensByOwnerRequest>
+                    for GetTokensByOwnerSvc<T> {
+                        type Response = super::GetTokensByOwnerResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetTokensByOwnerRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Erc721>::get_tokens_by_owner(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetTokensByOwnerSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/torii.sinks.erc721.Erc721/GetApprovals" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetApprovalsSvc<T: Erc721>(pub Arc<T>);
+                    impl<
+                        T: Erc721,
+                    > tonic::server::UnaryService<super::GetApprovalsRequest>
+                    for GetApprovalsSvc<T> {
+                        type Response = super::GetApprovalsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetApprovalsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Erc721>::get_approvals(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetApprovalsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/torii.sinks.erc721.Erc721/GetApprovedOperators" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetApprovedOperatorsSvc<T: Erc721>(pub Arc<T>);
+                    impl<
+                        T: Erc721,
+                    > tonic::server::UnaryService<super::GetApprovedOperatorsRequest>
+                    for GetApprovedOperatorsSvc<T> {
+                        type Response = super::GetApprovedOperatorsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetApprovedOperatorsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Erc721>::get_approved_operators(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetApprovedOperatorsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/torii.sinks.erc721.Erc721/GetTokenMetadata" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetTokenMetadataSvc<T: Erc721>(pub Arc<T>);
+                    impl<
+                        T: Erc721,
+                    > tonic::server::UnaryService<super::GetTokenMetadataRequest>
                     for GetTokenMetadataSvc<T> {
                         type Response = super::GetTokenMetadataResponse;
                         type Future = BoxFuture<
@@ -852,6 +1548,51 @@ pub mod erc721_server {
                     };
                     Box::pin(fut)
                 }
+                "/torii.sinks.erc721.Erc721/GetTokenImage" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetTokenImageSvc<T: Erc721>(pub Arc<T>);
+                    impl<
+                        T: Erc721,
+                    > tonic::server::UnaryService<super::GetTokenImageRequest>
+                    for GetTokenImageSvc<T> {
+                        type Response = super::GetTokenImageResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetTokenImageRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Erc721>::get_token_image(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetTokenImageSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/torii.sinks.erc721.Erc721/QueryTokensByAttributes" => {
                     #[allow(non_camel_case_types)]
                     struct QueryTokensByAttributesSvc<T: Erc721>(pub Arc<T>);
@@ -1086,6 +1827,190 @@ pub mod erc721_server {
                     };
                     Box::pin(fut)
                 }
+                "/torii.sinks.erc721.Erc721/SubscribeMints" => {
+                    #[allow(non_camel_case_types)]
+                    struct SubscribeMintsSvc<T: Erc721>(pub Arc<T>);
+                    impl<
+                        T: Erc721,
+                    > tonic::server::ServerStreamingService<super::SubscribeMintsRequest>
+                    for SubscribeMintsSvc<T> {
+                        type Response = super::MintUpdate;
+                        type ResponseStream = T::SubscribeMintsStream;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::ResponseStream>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SubscribeMintsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Erc721>::subscribe_mints(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = SubscribeMintsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/torii.sinks.erc721.Erc721/GetSupply" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetSupplySvc<T: Erc721>(pub Arc<T>);
+                    impl<T: Erc721> tonic::server::UnaryService<super::GetSupplyRequest>
+                    for GetSupplySvc<T> {
+                        type Response = super::GetSupplyResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetSupplyRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Erc721>::get_supply(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetSupplySvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/torii.sinks.erc721.Erc721/WatchAddresses" => {
+                    #[allow(non_camel_case_types)]
+                    struct WatchAddressesSvc<T: Erc721>(pub Arc<T>);
+                    impl<
+                        T: Erc721,
+                    > tonic::server::StreamingService<super::WatchAddressesRequest>
+                    for WatchAddressesSvc<T> {
+                        type Response = super::WatchUpdate;
+                        type ResponseStream = T::WatchAddressesStream;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::ResponseStream>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                tonic::Streaming<super::WatchAddressesRequest>,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Erc721>::watch_addresses(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = WatchAddressesSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/torii.sinks.erc721.Erc721/ReplayTransfers" => {
+                    #[allow(non_camel_case_types)]
+                    struct ReplayTransfersSvc<T: Erc721>(pub Arc<T>);
+                    impl<
+                        T: Erc721,
+                    > tonic::server::ServerStreamingService<
+                        super::ReplayTransfersRequest,
+                    > for ReplayTransfersSvc<T> {
+                        type Response = super::NftTransfer;
+                        type ResponseStream = T::ReplayTransfersStream;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::ResponseStream>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ReplayTransfersRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Erc721>::replay_transfers(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ReplayTransfersSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/torii.sinks.erc721.Erc721/GetStats" => {
                     #[allow(non_camel_case_types)]
                     struct GetStatsSvc<T: Erc721>(pub Arc<T>);
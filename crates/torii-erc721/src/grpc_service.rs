@@ -1,57 +1,225 @@
 //! gRPC service implementation for ERC721 queries and subscriptions
 
 use crate::proto::{
-    erc721_server::Erc721 as Erc721Trait, AttributeFacetCount, CollectionToken,
-    ContractCollectionOverview, Cursor, GetCollectionOverviewRequest,
+    erc721_server::Erc721 as Erc721Trait, AddressLabel, AttributeFacetCount, CollectionToken,
+    ContractCollectionOverview, Cursor, GetApprovalsRequest, GetApprovalsResponse,
+    GetApprovedOperatorsRequest, GetApprovedOperatorsResponse, GetCollectionOverviewRequest,
     GetCollectionOverviewResponse, GetCollectionTokensRequest, GetCollectionTokensResponse,
-    GetCollectionTraitFacetsRequest, GetCollectionTraitFacetsResponse, GetOwnerRequest,
-    GetOwnerResponse, GetOwnershipRequest, GetOwnershipResponse, GetStatsRequest, GetStatsResponse,
-    GetTokenMetadataRequest, GetTokenMetadataResponse, GetTransfersRequest, GetTransfersResponse,
-    NftTransfer, Ownership, QueryTokensByAttributesRequest, QueryTokensByAttributesResponse,
+    GetCollectionTraitFacetsRequest, GetCollectionTraitFacetsResponse, GetOwnerAtBlockRequest,
+    GetOwnerAtBlockResponse, GetOwnerRequest, GetOwnerResponse, GetOwnershipHistoryRequest,
+    GetOwnershipHistoryResponse, GetOwnershipRequest, GetOwnershipResponse, GetStatsRequest,
+    GetStatsResponse, GetSupplyRequest, GetSupplyResponse, GetTokenImageRequest,
+    GetTokenImageResponse, GetTokenMetadataRequest, GetTokenMetadataResponse,
+    GetTokensByOwnerRequest, GetTokensByOwnerResponse, GetTransfersRequest, GetTransfersResponse,
+    MintUpdate, NftApproval, NftTransfer, OperatorApproval, OperatorCursor, OwnedToken,
+    OwnedTokenCursor, Ownership, OwnershipChange, QueryTokensByAttributesRequest,
+    QueryTokensByAttributesResponse, ReplayTransfersRequest, StreamShutdown, SubscribeMintsRequest,
     SubscribeTransfersRequest, TokenMetadataEntry, TraitSummary, TransferFilter, TransferUpdate,
+    WatchAddressesRequest, WatchUpdate,
 };
-use crate::storage::{Erc721Storage, NftTransferData, TransferCursor};
+use crate::sharding::ShardedErc721Storage;
+use crate::storage::{NftTransferData, TransferCursor};
 use async_trait::async_trait;
-use futures::stream::Stream;
+use futures::future::Either;
+use futures::stream::{Stream, StreamExt};
 use starknet::core::types::Felt;
 use starknet::core::types::U256;
 use std::collections::{HashMap, HashSet};
 use std::pin::Pin;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::broadcast;
-use tonic::{Request, Response, Status};
-use torii_common::{bytes_to_felt, bytes_to_u256, u256_to_bytes};
+use tonic::{Request, Response, Status, Streaming};
+use torii_common::{
+    bytes_to_felt, bytes_to_u256, replay, u256_to_bytes, AddressLabels, AddressWatchlist,
+    FieldMask, NormalizedMetadata, ObjectStore, ReplayRange,
+};
 
 const DEFAULT_PROJECT_ID: &str = "arcade-main";
 
+/// Updates buffered per address watcher before new ones are dropped
+const WATCH_CHANNEL_CAPACITY: usize = 1000;
+/// Maximum number of addresses in a single watchlist
+const MAX_WATCHED_ADDRESSES: usize = 10_000;
+/// Fields of `NftTransfer` selectable with a `field_mask`
+const TRANSFER_FIELDS: &[&str] = &[
+    "token",
+    "token_id",
+    "from",
+    "to",
+    "block_number",
+    "tx_hash",
+    "timestamp",
+];
+/// Fields of `Ownership` selectable with a `field_mask`
+const OWNERSHIP_FIELDS: &[&str] = &["token", "token_id", "owner", "block_number"];
+
+/// Parse the `field_mask` of a request against the fields of the returned message
+fn parse_field_mask(paths: &[String], allowed: &[&str]) -> Result<FieldMask, Status> {
+    FieldMask::parse(paths, allowed)
+        .map_err(|field| Status::invalid_argument(format!("unknown field_mask field: {field}")))
+}
+
 /// gRPC service implementation for ERC721
 #[derive(Clone)]
 pub struct Erc721Service {
-    storage: Arc<Erc721Storage>,
+    storage: ShardedErc721Storage,
     /// Broadcast channel for real-time transfer updates
     pub transfer_tx: broadcast::Sender<TransferUpdate>,
+    /// Broadcast channel for real-time mints and burns
+    pub mint_tx: broadcast::Sender<MintUpdate>,
+    /// Address watchers (WatchAddresses), indexed by watched address
+    watchlist: AddressWatchlist<WatchUpdate>,
+    /// Object store holding the cached token images (GetTokenImage)
+    object_store: Option<Arc<ObjectStore>>,
+    /// Validity of the signed URLs returned by GetTokenImage
+    image_url_ttl: Duration,
+    /// Address labels attached to responses on request (`include_labels`)
+    labels: Option<AddressLabels>,
 }
 
 impl Erc721Service {
     /// Creates a new Erc721Service
-    pub fn new(storage: Arc<Erc721Storage>) -> Self {
+    ///
+    /// Takes a single storage or a [`ShardedErc721Storage`].
+    pub fn new(storage: impl Into<ShardedErc721Storage>) -> Self {
         let (transfer_tx, _) = broadcast::channel(1000);
+        let (mint_tx, _) = broadcast::channel(1000);
 
         Self {
-            storage,
+            storage: storage.into(),
             transfer_tx,
+            mint_tx,
+            watchlist: AddressWatchlist::new(),
+            object_store: None,
+            image_url_ttl: Duration::from_secs(3600),
+            labels: None,
         }
     }
 
-    /// Broadcasts a transfer to all subscribers
+    /// Serves GetTokenImage from `store`, signing URLs valid for `url_ttl`
+    #[must_use]
+    pub fn with_object_store(mut self, store: Arc<ObjectStore>, url_ttl: Duration) -> Self {
+        self.object_store = Some(store);
+        self.image_url_ttl = url_ttl;
+        self
+    }
+
+    /// Attaches labels from `labels` to the responses requesting them
+    #[must_use]
+    pub fn with_labels(mut self, labels: AddressLabels) -> Self {
+        self.labels = Some(labels);
+        self
+    }
+
+    /// Labels of the labelled `addresses`, when requested and a label store is configured
+    fn labels_for(
+        &self,
+        include_labels: bool,
+        addresses: impl IntoIterator<Item = Felt>,
+    ) -> Vec<AddressLabel> {
+        match &self.labels {
+            Some(labels) if include_labels => labels
+                .lookup(addresses)
+                .into_iter()
+                .map(|label| AddressLabel {
+                    address: label.address.to_bytes_be().to_vec(),
+                    name: label.name,
+                    tags: label.tags,
+                })
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Broadcasts a transfer to all subscribers and to the watchers of its addresses
     pub fn broadcast_transfer(&self, transfer: NftTransfer) {
+        let timestamp = chrono::Utc::now().timestamp();
+        if !self.watchlist.is_empty() {
+            let addresses = watched_addresses(&[&transfer.from[..], &transfer.to[..]]);
+            let ownership = Ownership {
+                token: transfer.token.clone(),
+                token_id: transfer.token_id.clone(),
+                owner: transfer.to.clone(),
+                block_number: transfer.block_number,
+            };
+            self.watchlist.notify(
+                &addresses,
+                &WatchUpdate {
+                    transfer: Some(transfer.clone()),
+                    ownership: Some(ownership),
+                    timestamp,
+                    shutdown: None,
+                },
+            );
+        }
         let update = TransferUpdate {
             transfer: Some(transfer),
-            timestamp: chrono::Utc::now().timestamp(),
+            timestamp,
+            shutdown: None,
         };
         let _ = self.transfer_tx.send(update);
     }
 
+    /// Whether any client subscribed to mints and burns
+    pub fn has_mint_subscribers(&self) -> bool {
+        self.mint_tx.receiver_count() > 0
+    }
+
+    /// Broadcasts a mint or burn to the subscribers of its collection
+    pub fn broadcast_mint(&self, update: MintUpdate) {
+        let _ = self.mint_tx.send(update);
+    }
+
+    /// Ends every subscription and address watch stream with a shutdown update.
+    ///
+    /// `block_number` is the last fully indexed block, from which clients resume.
+    pub fn notify_shutdown(&self, block_number: u64) {
+        let timestamp = chrono::Utc::now().timestamp();
+        let shutdown = StreamShutdown { block_number };
+        self.watchlist.notify_all(&WatchUpdate {
+            transfer: None,
+            ownership: None,
+            timestamp,
+            shutdown: Some(shutdown),
+        });
+        let _ = self.transfer_tx.send(TransferUpdate {
+            transfer: None,
+            timestamp,
+            shutdown: Some(shutdown),
+        });
+        let _ = self.mint_tx.send(MintUpdate {
+            timestamp,
+            shutdown: Some(shutdown),
+            ..MintUpdate::default()
+        });
+    }
+
+    /// Clear the fields of a transfer left out of `mask`
+    fn mask_transfer(transfer: &mut NftTransfer, mask: &FieldMask) {
+        if !mask.includes("token") {
+            transfer.token.clear();
+        }
+        if !mask.includes("token_id") {
+            transfer.token_id.clear();
+        }
+        if !mask.includes("from") {
+            transfer.from.clear();
+        }
+        if !mask.includes("to") {
+            transfer.to.clear();
+        }
+        if !mask.includes("block_number") {
+            transfer.block_number = 0;
+        }
+        if !mask.includes("tx_hash") {
+            transfer.tx_hash.clear();
+        }
+        if !mask.includes("timestamp") {
+            transfer.timestamp = 0;
+        }
+    }
+
     /// Convert storage NftTransferData to proto NftTransfer
     fn transfer_data_to_proto(data: &NftTransferData) -> NftTransfer {
         NftTransfer {
@@ -195,6 +363,14 @@ impl Erc721Service {
             .into_iter()
             .map(|(token_id, uri, metadata_json)| (u256_to_bytes(token_id), (uri, metadata_json)))
             .collect();
+        let mut fields_by_token_id: HashMap<Vec<u8>, NormalizedMetadata> = self
+            .storage
+            .get_token_metadata_fields_batch(contract, &result.token_ids)
+            .await
+            .map_err(|e| Status::internal(format!("Query failed: {e}")))?
+            .into_iter()
+            .map(|(token_id, fields)| (u256_to_bytes(token_id), fields))
+            .collect();
 
         let tokens = result
             .token_ids
@@ -205,6 +381,9 @@ impl Erc721Service {
                     .get(&token_id_bytes)
                     .cloned()
                     .unwrap_or((None, None));
+                let fields = fields_by_token_id
+                    .remove(&token_id_bytes)
+                    .unwrap_or_default();
                 let image_url = if include_images {
                     Some(Self::static_image_url(contract, *token_id))
                 } else {
@@ -217,6 +396,9 @@ impl Erc721Service {
                     uri,
                     metadata_json,
                     image_url,
+                    name: fields.name,
+                    description: fields.description,
+                    image: fields.image,
                 }
             })
             .collect();
@@ -249,6 +431,13 @@ impl Erc721Trait for Erc721Service {
     ) -> Result<Response<GetTransfersResponse>, Status> {
         let req = request.into_inner();
         let filter = req.filter.unwrap_or_default();
+        let mask = parse_field_mask(&req.field_mask, TRANSFER_FIELDS)?;
+        // Labels need the token and addresses of each transfer.
+        let storage_mask = if req.include_labels {
+            mask.clone().with_fields(&["token", "from", "to"])
+        } else {
+            mask.clone()
+        };
 
         let wallet = filter.wallet.as_ref().and_then(|b| bytes_to_felt(b));
         let from = filter.from.as_ref().and_then(|b| bytes_to_felt(b));
@@ -273,7 +462,7 @@ impl Erc721Trait for Erc721Service {
 
         let (transfers, next_cursor) = self
             .storage
-            .get_transfers_filtered(
+            .get_transfers_projected(
                 wallet,
                 from,
                 to,
@@ -283,21 +472,34 @@ impl Erc721Trait for Erc721Service {
                 filter.block_to,
                 cursor,
                 limit,
+                &storage_mask,
             )
             .await
             .map_err(|e| Status::internal(format!("Query failed: {e}")))?;
 
-        let proto_transfers: Vec<NftTransfer> =
-            transfers.iter().map(Self::transfer_data_to_proto).collect();
+        let proto_transfers: Vec<NftTransfer> = transfers
+            .iter()
+            .map(|data| {
+                let mut transfer = Self::transfer_data_to_proto(data);
+                Self::mask_transfer(&mut transfer, &mask);
+                transfer
+            })
+            .collect();
 
         let proto_cursor = next_cursor.map(|c| Cursor {
             block_number: c.block_number,
             id: c.id,
         });
 
+        let labels = self.labels_for(
+            req.include_labels,
+            transfers.iter().flat_map(|t| [t.token, t.from, t.to]),
+        );
+
         Ok(Response::new(GetTransfersResponse {
             transfers: proto_transfers,
             next_cursor: proto_cursor,
+            labels,
         }))
     }
 
@@ -308,6 +510,12 @@ impl Erc721Trait for Erc721Service {
     ) -> Result<Response<GetOwnershipResponse>, Status> {
         let req = request.into_inner();
         let filter = req.filter.unwrap_or_default();
+        let mask = parse_field_mask(&req.field_mask, OWNERSHIP_FIELDS)?;
+        let storage_mask = if req.include_labels {
+            mask.clone().with_fields(&["token", "owner"])
+        } else {
+            mask.clone()
+        };
 
         let owner = filter.owner.as_ref().and_then(|b| bytes_to_felt(b));
         let tokens: Vec<Felt> = filter
@@ -331,17 +539,33 @@ impl Erc721Trait for Erc721Service {
 
         let (ownership, next_cursor) = self
             .storage
-            .get_ownership_by_owner(owner, &tokens, cursor, limit)
+            .get_ownership_projected(owner, &tokens, cursor, limit, &storage_mask)
             .await
             .map_err(|e| Status::internal(format!("Query failed: {e}")))?;
 
         let proto_ownership: Vec<Ownership> = ownership
             .iter()
             .map(|o| Ownership {
-                token: o.token.to_bytes_be().to_vec(),
-                token_id: u256_to_bytes(o.token_id),
-                owner: o.owner.to_bytes_be().to_vec(),
-                block_number: o.block_number,
+                token: if mask.includes("token") {
+                    o.token.to_bytes_be().to_vec()
+                } else {
+                    Vec::new()
+                },
+                token_id: if mask.includes("token_id") {
+                    u256_to_bytes(o.token_id)
+                } else {
+                    Vec::new()
+                },
+                owner: if mask.includes("owner") {
+                    o.owner.to_bytes_be().to_vec()
+                } else {
+                    Vec::new()
+                },
+                block_number: if mask.includes("block_number") {
+                    o.block_number
+                } else {
+                    0
+                },
             })
             .collect();
 
@@ -350,9 +574,15 @@ impl Erc721Trait for Erc721Service {
             id: c.id,
         });
 
+        let labels = self.labels_for(
+            req.include_labels,
+            ownership.iter().flat_map(|o| [o.token, o.owner]),
+        );
+
         Ok(Response::new(GetOwnershipResponse {
             ownership: proto_ownership,
             next_cursor: proto_cursor,
+            labels,
         }))
     }
 
@@ -378,6 +608,305 @@ impl Erc721Trait for Erc721Service {
         }))
     }
 
+    /// Get the owner of a specific NFT at the end of a block
+    async fn get_owner_at_block(
+        &self,
+        request: Request<GetOwnerAtBlockRequest>,
+    ) -> Result<Response<GetOwnerAtBlockResponse>, Status> {
+        let req = request.into_inner();
+
+        let token = bytes_to_felt(&req.token)
+            .ok_or_else(|| Status::invalid_argument("invalid token address"))?;
+        let token_id = bytes_to_u256(&req.token_id);
+
+        let change = self
+            .storage
+            .get_owner_at_block(token, token_id, req.block_number)
+            .await
+            .map_err(|e| Status::internal(format!("Query failed: {e}")))?;
+
+        // A burn leaves the NFT without owner.
+        let change = change.filter(|c| c.owner != Felt::ZERO);
+        Ok(Response::new(GetOwnerAtBlockResponse {
+            owner: change.as_ref().map(|c| c.owner.to_bytes_be().to_vec()),
+            acquired_block: change.as_ref().map(|c| c.block_number),
+            acquired_tx_hash: change.as_ref().map(|c| c.tx_hash.to_bytes_be().to_vec()),
+        }))
+    }
+
+    /// Get the owner changes of a specific NFT, newest first
+    async fn get_ownership_history(
+        &self,
+        request: Request<GetOwnershipHistoryRequest>,
+    ) -> Result<Response<GetOwnershipHistoryResponse>, Status> {
+        let req = request.into_inner();
+
+        let token = bytes_to_felt(&req.token)
+            .ok_or_else(|| Status::invalid_argument("invalid token address"))?;
+        let token_id = bytes_to_u256(&req.token_id);
+
+        let cursor = req.cursor.map(|c| crate::storage::OwnershipCursor {
+            block_number: c.block_number,
+            id: c.id,
+        });
+
+        let limit = if req.limit == 0 {
+            100
+        } else {
+            req.limit.min(1000)
+        };
+
+        let (changes, next_cursor) = self
+            .storage
+            .get_ownership_history(token, token_id, cursor, limit)
+            .await
+            .map_err(|e| Status::internal(format!("Query failed: {e}")))?;
+
+        let proto_changes: Vec<OwnershipChange> = changes
+            .iter()
+            .map(|c| OwnershipChange {
+                token: c.token.to_bytes_be().to_vec(),
+                token_id: u256_to_bytes(c.token_id),
+                previous_owner: c.previous_owner.to_bytes_be().to_vec(),
+                owner: c.owner.to_bytes_be().to_vec(),
+                block_number: c.block_number,
+                tx_hash: c.tx_hash.to_bytes_be().to_vec(),
+                timestamp: c.timestamp.unwrap_or(0),
+            })
+            .collect();
+
+        let proto_cursor = next_cursor.map(|c| Cursor {
+            block_number: c.block_number,
+            id: c.id,
+        });
+
+        Ok(Response::new(GetOwnershipHistoryResponse {
+            changes: proto_changes,
+            next_cursor: proto_cursor,
+        }))
+    }
+
+    /// Enumerate the NFTs owned by an address across every indexed collection
+    async fn get_tokens_by_owner(
+        &self,
+        request: Request<GetTokensByOwnerRequest>,
+    ) -> Result<Response<GetTokensByOwnerResponse>, Status> {
+        let req = request.into_inner();
+
+        let owner = bytes_to_felt(&req.owner)
+            .ok_or_else(|| Status::invalid_argument("invalid owner address"))?;
+        let cursor = req
+            .cursor
+            .map(|c| {
+                Ok::<_, Status>(crate::storage::OwnedTokenCursor {
+                    token: bytes_to_felt(&c.token)
+                        .ok_or_else(|| Status::invalid_argument("invalid cursor token"))?,
+                    token_id: bytes_to_u256(&c.token_id),
+                })
+            })
+            .transpose()?;
+
+        let limit = if req.limit == 0 {
+            100
+        } else {
+            req.limit.min(1000)
+        };
+
+        let (ownership, next_cursor) = self
+            .storage
+            .get_tokens_by_owner(owner, cursor, limit)
+            .await
+            .map_err(|e| Status::internal(format!("Query failed: {e}")))?;
+
+        let mut metadata: HashMap<(Felt, U256), (Option<String>, Option<String>)> = HashMap::new();
+        if req.include_metadata {
+            let mut token_ids_by_contract: HashMap<Felt, Vec<U256>> = HashMap::new();
+            for o in &ownership {
+                token_ids_by_contract
+                    .entry(o.token)
+                    .or_default()
+                    .push(o.token_id);
+            }
+            for (token, token_ids) in token_ids_by_contract {
+                let uri_rows = self
+                    .storage
+                    .get_token_uris_batch(token, &token_ids)
+                    .await
+                    .map_err(|e| Status::internal(format!("Query failed: {e}")))?;
+                metadata.extend(uri_rows.into_iter().map(|(token_id, uri, metadata_json)| {
+                    ((token, token_id), (uri, metadata_json))
+                }));
+            }
+        }
+
+        let tokens: Vec<OwnedToken> = ownership
+            .iter()
+            .map(|o| {
+                let (uri, metadata_json) = metadata
+                    .remove(&(o.token, o.token_id))
+                    .unwrap_or((None, None));
+                OwnedToken {
+                    token: o.token.to_bytes_be().to_vec(),
+                    token_id: u256_to_bytes(o.token_id),
+                    block_number: o.block_number,
+                    uri,
+                    metadata_json,
+                }
+            })
+            .collect();
+
+        let proto_cursor = next_cursor.map(|c| OwnedTokenCursor {
+            token: c.token.to_bytes_be().to_vec(),
+            token_id: u256_to_bytes(c.token_id),
+        });
+
+        Ok(Response::new(GetTokensByOwnerResponse {
+            tokens,
+            next_cursor: proto_cursor,
+        }))
+    }
+
+    /// List the current single-token approvals granted by an owner
+    async fn get_approvals(
+        &self,
+        request: Request<GetApprovalsRequest>,
+    ) -> Result<Response<GetApprovalsResponse>, Status> {
+        let req = request.into_inner();
+
+        let owner = bytes_to_felt(&req.owner)
+            .ok_or_else(|| Status::invalid_argument("invalid owner address"))?;
+        let cursor = req
+            .cursor
+            .map(|c| {
+                Ok::<_, Status>(crate::storage::OwnedTokenCursor {
+                    token: bytes_to_felt(&c.token)
+                        .ok_or_else(|| Status::invalid_argument("invalid cursor token"))?,
+                    token_id: bytes_to_u256(&c.token_id),
+                })
+            })
+            .transpose()?;
+
+        let limit = if req.limit == 0 {
+            100
+        } else {
+            req.limit.min(1000)
+        };
+
+        let (approvals, next_cursor) = self
+            .storage
+            .get_token_approvals(owner, cursor, limit)
+            .await
+            .map_err(|e| Status::internal(format!("Query failed: {e}")))?;
+
+        let approvals: Vec<NftApproval> = approvals
+            .into_iter()
+            .map(|a| NftApproval {
+                token: a.token.to_bytes_be().to_vec(),
+                token_id: u256_to_bytes(a.token_id),
+                owner: a.owner.to_bytes_be().to_vec(),
+                approved: a.approved.to_bytes_be().to_vec(),
+                block_number: a.block_number,
+                tx_hash: a.tx_hash.to_bytes_be().to_vec(),
+                timestamp: a.timestamp.unwrap_or(0),
+            })
+            .collect();
+
+        let proto_cursor = next_cursor.map(|c| OwnedTokenCursor {
+            token: c.token.to_bytes_be().to_vec(),
+            token_id: u256_to_bytes(c.token_id),
+        });
+
+        Ok(Response::new(GetApprovalsResponse {
+            approvals,
+            next_cursor: proto_cursor,
+        }))
+    }
+
+    /// List the operators currently approved for all tokens of an owner
+    async fn get_approved_operators(
+        &self,
+        request: Request<GetApprovedOperatorsRequest>,
+    ) -> Result<Response<GetApprovedOperatorsResponse>, Status> {
+        let req = request.into_inner();
+
+        let owner = bytes_to_felt(&req.owner)
+            .ok_or_else(|| Status::invalid_argument("invalid owner address"))?;
+        let cursor = req
+            .cursor
+            .map(|c| {
+                Ok::<_, Status>(crate::storage::OperatorCursor {
+                    token: bytes_to_felt(&c.token)
+                        .ok_or_else(|| Status::invalid_argument("invalid cursor token"))?,
+                    operator: bytes_to_felt(&c.operator)
+                        .ok_or_else(|| Status::invalid_argument("invalid cursor operator"))?,
+                })
+            })
+            .transpose()?;
+
+        let limit = if req.limit == 0 {
+            100
+        } else {
+            req.limit.min(1000)
+        };
+
+        let (operators, next_cursor) = self
+            .storage
+            .get_approved_operators(owner, cursor, limit)
+            .await
+            .map_err(|e| Status::internal(format!("Query failed: {e}")))?;
+
+        let operators: Vec<OperatorApproval> = operators
+            .into_iter()
+            .map(|o| OperatorApproval {
+                token: o.token.to_bytes_be().to_vec(),
+                owner: o.owner.to_bytes_be().to_vec(),
+                operator: o.operator.to_bytes_be().to_vec(),
+                approved: o.approved,
+                block_number: o.block_number,
+                tx_hash: o.tx_hash.to_bytes_be().to_vec(),
+                timestamp: o.timestamp.unwrap_or(0),
+            })
+            .collect();
+
+        let proto_cursor = next_cursor.map(|c| OperatorCursor {
+            token: c.token.to_bytes_be().to_vec(),
+            operator: c.operator.to_bytes_be().to_vec(),
+        });
+
+        Ok(Response::new(GetApprovedOperatorsResponse {
+            operators,
+            next_cursor: proto_cursor,
+        }))
+    }
+
+    /// Get signed URLs of the cached image and metadata of a token
+    async fn get_token_image(
+        &self,
+        request: Request<GetTokenImageRequest>,
+    ) -> Result<Response<GetTokenImageResponse>, Status> {
+        let req = request.into_inner();
+        let store = self
+            .object_store
+            .as_ref()
+            .ok_or_else(|| Status::failed_precondition("Image object store not configured"))?;
+        let token = bytes_to_felt(&req.token)
+            .ok_or_else(|| Status::invalid_argument("Invalid token address"))?;
+        let token_id = bytes_to_u256(&req.token_id);
+
+        let urls = store
+            .token_asset_urls(token, token_id, self.image_url_ttl)
+            .await
+            .map_err(|e| Status::internal(format!("Query failed: {e}")))?
+            .ok_or_else(|| Status::not_found("Token image not cached"))?;
+
+        Ok(Response::new(GetTokenImageResponse {
+            image_url: urls.image_url,
+            content_type: urls.content_type,
+            metadata_url: urls.metadata_url,
+            expires_at: urls.expires_at,
+        }))
+    }
+
     /// Get token metadata (name, symbol)
     async fn get_token_metadata(
         &self,
@@ -656,7 +1185,11 @@ impl Erc721Trait for Erc721Service {
                                 continue;
                             }
                         }
+                        let shutdown = update.shutdown.is_some();
                         yield update;
+                        if shutdown {
+                            break;
+                        }
                     }
                     Err(broadcast::error::RecvError::Lagged(skipped)) => {
                         tracing::warn!(
@@ -677,6 +1210,192 @@ impl Erc721Trait for Erc721Service {
         Ok(Response::new(Box::pin(stream)))
     }
 
+    /// Subscribe to real-time mints and burns
+    type SubscribeMintsStream = Pin<Box<dyn Stream<Item = Result<MintUpdate, Status>> + Send>>;
+
+    async fn subscribe_mints(
+        &self,
+        request: Request<SubscribeMintsRequest>,
+    ) -> Result<Response<Self::SubscribeMintsStream>, Status> {
+        let req = request.into_inner();
+        let contract = if req.contract.is_empty() {
+            None
+        } else {
+            Some(
+                bytes_to_felt(&req.contract)
+                    .ok_or_else(|| Status::invalid_argument("invalid contract address"))?,
+            )
+        };
+
+        tracing::info!(
+            target: "torii_erc721::grpc",
+            "New mint subscription from client: {}",
+            req.client_id
+        );
+
+        let mut rx = self.mint_tx.subscribe();
+
+        let stream = async_stream::try_stream! {
+            loop {
+                match rx.recv().await {
+                    Ok(update) => {
+                        let shutdown = update.shutdown.is_some();
+                        if !shutdown
+                            && contract.is_some_and(|c| bytes_to_felt(&update.token) != Some(c))
+                        {
+                            continue;
+                        }
+                        yield update;
+                        if shutdown {
+                            break;
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!(
+                            target: "torii_erc721::grpc",
+                            "Client {} lagged, skipped {} mint updates",
+                            req.client_id,
+                            skipped
+                        );
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => {
+                        break;
+                    }
+                }
+            }
+        };
+
+        Ok(Response::new(Box::pin(stream)))
+    }
+
+    /// Get the supply of a collection from its indexed mints and burns
+    async fn get_supply(
+        &self,
+        request: Request<GetSupplyRequest>,
+    ) -> Result<Response<GetSupplyResponse>, Status> {
+        let req = request.into_inner();
+        let contract = bytes_to_felt(&req.contract)
+            .ok_or_else(|| Status::invalid_argument("invalid contract address"))?;
+
+        let supply = self
+            .storage
+            .get_collection_supply(contract)
+            .await
+            .map_err(|e| Status::internal(format!("Query failed: {e}")))?;
+
+        let mut response = GetSupplyResponse {
+            contract: contract.to_bytes_be().to_vec(),
+            ..GetSupplyResponse::default()
+        };
+        if let Some(supply) = supply {
+            response.total_minted = supply.total_minted;
+            response.total_burned = supply.total_burned;
+            response.supply = supply.supply();
+            response.block_number = supply.block_number;
+        }
+        Ok(Response::new(response))
+    }
+
+    /// Watch account addresses (server-side filtered transfers and ownership changes)
+    type WatchAddressesStream = Pin<Box<dyn Stream<Item = Result<WatchUpdate, Status>> + Send>>;
+
+    async fn watch_addresses(
+        &self,
+        request: Request<Streaming<WatchAddressesRequest>>,
+    ) -> Result<Response<Self::WatchAddressesStream>, Status> {
+        let mut requests = request.into_inner();
+        let first = requests
+            .message()
+            .await?
+            .ok_or_else(|| Status::invalid_argument("Expected an initial watch request"))?;
+        let client_id = first.client_id;
+        let mut watcher = self.watchlist.watch(
+            parse_watched_addresses(&first.addresses)?,
+            WATCH_CHANNEL_CAPACITY,
+        );
+
+        tracing::info!(
+            target: "torii_erc721::grpc",
+            "New address watch from client {}: {} addresses",
+            client_id,
+            first.addresses.len()
+        );
+
+        let stream = async_stream::try_stream! {
+            let mut requests_open = true;
+            loop {
+                let event = tokio::select! {
+                    update = watcher.recv() => Either::Left(update),
+                    request = requests.message(), if requests_open => Either::Right(request),
+                };
+                match event {
+                    Either::Left(Some(update)) => {
+                        let shutdown = update.shutdown.is_some();
+                        yield update;
+                        if shutdown {
+                            break;
+                        }
+                    }
+                    Either::Left(None) => break,
+                    Either::Right(request) => match request? {
+                        Some(request) => {
+                            watcher.set_addresses(parse_watched_addresses(&request.addresses)?);
+                            tracing::debug!(
+                                target: "torii_erc721::grpc",
+                                "Client {} now watches {} addresses",
+                                client_id,
+                                request.addresses.len()
+                            );
+                        }
+                        // The client stopped updating its watchlist; keep streaming.
+                        None => requests_open = false,
+                    },
+                }
+            }
+
+            tracing::info!(
+                target: "torii_erc721::grpc",
+                "Address watch ended for client: {}",
+                client_id
+            );
+        };
+
+        Ok(Response::new(Box::pin(stream)))
+    }
+
+    /// Stream stored transfers in a block range (historical replay)
+    type ReplayTransfersStream = Pin<Box<dyn Stream<Item = Result<NftTransfer, Status>> + Send>>;
+
+    /// Pages through storage lazily: the next page is only read once the previous one has
+    /// been sent, so a slow client throttles the query through tonic's flow control.
+    async fn replay_transfers(
+        &self,
+        request: Request<ReplayTransfersRequest>,
+    ) -> Result<Response<Self::ReplayTransfersStream>, Status> {
+        let req = request.into_inner();
+        let tokens: Vec<Felt> = req.tokens.iter().filter_map(|b| bytes_to_felt(b)).collect();
+        let range = ReplayRange::new(tokens, req.block_from, req.block_to, req.page_size)
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+
+        tracing::info!(
+            target: "torii_erc721::grpc",
+            "ReplayTransfers: blocks={:?}..={:?}, tokens={}, page_size={}",
+            range.block_from,
+            range.block_to,
+            range.tokens.len(),
+            range.page_size
+        );
+
+        let stream = replay(self.storage.clone(), range).map(|transfer| {
+            transfer
+                .map(|transfer| Self::transfer_data_to_proto(&transfer))
+                .map_err(|e| Status::internal(format!("Replay query failed: {e}")))
+        });
+
+        Ok(Response::new(Box::pin(stream)))
+    }
+
     /// Get indexer statistics
     async fn get_stats(
         &self,
@@ -715,3 +1434,26 @@ impl Erc721Trait for Erc721Service {
         }))
     }
 }
+
+/// Parses the addresses of a watch request.
+fn parse_watched_addresses(addresses: &[Vec<u8>]) -> Result<Vec<Felt>, Status> {
+    if addresses.len() > MAX_WATCHED_ADDRESSES {
+        return Err(Status::invalid_argument(format!(
+            "Too many addresses to watch (max {MAX_WATCHED_ADDRESSES})"
+        )));
+    }
+    addresses
+        .iter()
+        .map(|address| {
+            bytes_to_felt(address).ok_or_else(|| Status::invalid_argument("Invalid address"))
+        })
+        .collect()
+}
+
+/// Addresses an update is routed by (malformed addresses are skipped).
+fn watched_addresses(addresses: &[&[u8]]) -> Vec<Felt> {
+    addresses
+        .iter()
+        .filter_map(|address| bytes_to_felt(address))
+        .collect()
+}
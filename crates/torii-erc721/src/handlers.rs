@@ -3,17 +3,17 @@ use async_trait::async_trait;
 use prost::Message;
 use prost_types::Any;
 use starknet::core::types::Felt;
-use starknet::providers::jsonrpc::{HttpTransport, JsonRpcClient};
 use std::collections::HashSet;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 use torii::command::CommandHandler;
 use torii::etl::sink::EventBus;
 use torii::UpdateType;
+use torii_common::RpcProvider;
 use torii_common::{process_token_uri_request, u256_to_bytes, MetadataFetcher, TokenUriRequest};
 
 use crate::proto;
-use crate::storage::Erc721Storage;
+use crate::sharding::ShardedErc721Storage;
 
 #[derive(Debug, Clone)]
 pub struct FetchErc721MetadataCommand {
@@ -28,7 +28,7 @@ pub struct RefreshErc721TokenUriCommand {
 
 pub struct Erc721MetadataCommandHandler {
     fetcher: Arc<MetadataFetcher>,
-    storage: Arc<Erc721Storage>,
+    storage: ShardedErc721Storage,
     event_bus: Mutex<Option<Arc<EventBus>>>,
     in_flight: Mutex<HashSet<Felt>>,
     max_retries: u8,
@@ -36,19 +36,26 @@ pub struct Erc721MetadataCommandHandler {
 
 impl Erc721MetadataCommandHandler {
     pub fn new(
-        provider: Arc<JsonRpcClient<HttpTransport>>,
-        storage: Arc<Erc721Storage>,
+        provider: Arc<RpcProvider>,
+        storage: impl Into<ShardedErc721Storage>,
         max_retries: u8,
     ) -> Self {
         Self {
             fetcher: Arc::new(MetadataFetcher::new(provider)),
-            storage,
+            storage: storage.into(),
             event_bus: Mutex::new(None),
             in_flight: Mutex::new(HashSet::new()),
             max_retries: max_retries.max(1),
         }
     }
 
+    /// Use a shared fetcher (e.g. one backed by a [`torii_common::MetadataCache`]).
+    #[must_use]
+    pub fn with_fetcher(mut self, fetcher: Arc<MetadataFetcher>) -> Self {
+        self.fetcher = fetcher;
+        self
+    }
+
     fn matches_metadata_filters(
         metadata: &proto::TokenMetadataEntry,
         filters: &std::collections::HashMap<String, String>,
@@ -205,20 +212,20 @@ impl CommandHandler for Erc721MetadataCommandHandler {
 
 pub struct Erc721TokenUriCommandHandler {
     fetcher: Arc<MetadataFetcher>,
-    storage: Arc<Erc721Storage>,
+    storage: ShardedErc721Storage,
     image_cache_dir: Option<PathBuf>,
     in_flight: Mutex<HashSet<(Felt, starknet::core::types::U256)>>,
 }
 
 impl Erc721TokenUriCommandHandler {
     pub fn new(
-        provider: Arc<JsonRpcClient<HttpTransport>>,
-        storage: Arc<Erc721Storage>,
+        provider: Arc<RpcProvider>,
+        storage: impl Into<ShardedErc721Storage>,
         image_cache_dir: Option<PathBuf>,
     ) -> Self {
         Self {
             fetcher: Arc::new(MetadataFetcher::new(provider)),
-            storage,
+            storage: storage.into(),
             image_cache_dir,
             in_flight: Mutex::new(HashSet::new()),
         }
@@ -253,11 +260,12 @@ impl CommandHandler for Erc721TokenUriCommandHandler {
 
         let result = process_token_uri_request(
             self.fetcher.as_ref(),
-            self.storage.as_ref(),
+            &self.storage,
             &TokenUriRequest {
                 contract: command.contract,
                 token_id: command.token_id,
                 standard: torii_common::TokenStandard::Erc721,
+                uri: None,
             },
             self.image_cache_dir.as_deref(),
         )
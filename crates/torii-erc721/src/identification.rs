@@ -4,6 +4,8 @@
 //! - `owner_of` function
 //! - `balance_of` function
 //! - `Transfer` event (with token_id)
+//!
+//! or, when the ABI is unavailable, their entry point selectors.
 
 use anyhow::Result;
 use starknet::core::types::Felt;
@@ -26,6 +28,9 @@ use torii::etl::identification::IdentificationRule;
 /// Note: ERC721 Transfer events differ from ERC20 Transfer events in that
 /// they include a `token_id` parameter. However, at the ABI level, both
 /// have a `Transfer` event - the decoder distinguishes them by event structure.
+///
+/// When the ABI is unavailable, the class must have `owner_of`, `get_approved` and
+/// `balance_of` entry points (snake_case or camelCase).
 pub struct Erc721Rule;
 
 impl Erc721Rule {
@@ -56,17 +61,26 @@ impl IdentificationRule for Erc721Rule {
         _class_hash: Felt,
         abi: &ContractAbi,
     ) -> Result<Vec<DecoderId>> {
-        // Check for owner_of function (distinguishes ERC721 from ERC20)
-        let has_owner_of = abi.has_function("owner_of") || abi.has_function("ownerOf");
+        let matches = if abi.has_abi() {
+            // Check for owner_of function (distinguishes ERC721 from ERC20)
+            let has_owner_of = abi.has_function("owner_of") || abi.has_function("ownerOf");
+
+            // Check for balance_of function
+            let has_balance_of = abi.has_function("balance_of") || abi.has_function("balanceOf");
 
-        // Check for balance_of function
-        let has_balance_of = abi.has_function("balance_of") || abi.has_function("balanceOf");
+            // Check for Transfer event
+            let has_transfer_event = abi.has_event("Transfer");
 
-        // Check for Transfer event
-        let has_transfer_event = abi.has_event("Transfer");
+            // ERC721 must have owner_of (which ERC20 doesn't have)
+            has_owner_of && has_balance_of && has_transfer_event
+        } else {
+            // Events are not visible in entry points: require get_approved instead
+            (abi.has_entry_point("owner_of") || abi.has_entry_point("ownerOf"))
+                && (abi.has_entry_point("get_approved") || abi.has_entry_point("getApproved"))
+                && (abi.has_entry_point("balance_of") || abi.has_entry_point("balanceOf"))
+        };
 
-        // ERC721 must have owner_of (which ERC20 doesn't have)
-        if has_owner_of && has_balance_of && has_transfer_event {
+        if matches {
             tracing::debug!(
                 target: "torii_erc721::identification",
                 "Contract matches ERC721 pattern"
@@ -81,6 +95,32 @@ impl IdentificationRule for Erc721Rule {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use starknet::core::types::{
+        ContractClass, EntryPointsByType, FlattenedSierraClass, SierraEntryPoint,
+    };
+    use starknet::core::utils::get_selector_from_name;
+
+    fn abi_without_json(entry_points: &[&str]) -> ContractAbi {
+        let external = entry_points
+            .iter()
+            .enumerate()
+            .map(|(i, name)| SierraEntryPoint {
+                selector: get_selector_from_name(name).unwrap(),
+                function_idx: i as u64,
+            })
+            .collect();
+        ContractAbi::from_contract_class(ContractClass::Sierra(FlattenedSierraClass {
+            sierra_program: Vec::new(),
+            contract_class_version: "0.1.0".to_string(),
+            entry_points_by_type: EntryPointsByType {
+                constructor: Vec::new(),
+                external,
+                l1_handler: Vec::new(),
+            },
+            abi: String::new(),
+        }))
+        .unwrap()
+    }
 
     #[test]
     fn test_erc721_rule_name() {
@@ -95,4 +135,21 @@ mod tests {
         assert_eq!(ids.len(), 1);
         assert_eq!(ids[0], DecoderId::new("erc721"));
     }
+
+    #[test]
+    fn test_erc721_rule_falls_back_to_entry_points() {
+        let rule = Erc721Rule::new();
+        let abi = abi_without_json(&["owner_of", "get_approved", "balance_of", "transfer_from"]);
+        assert!(!abi.has_abi());
+        assert_eq!(
+            rule.identify_by_abi(Felt::ONE, Felt::TWO, &abi).unwrap(),
+            vec![DecoderId::new("erc721")]
+        );
+
+        let abi = abi_without_json(&["transfer", "balance_of", "total_supply"]);
+        assert!(rule
+            .identify_by_abi(Felt::ONE, Felt::TWO, &abi)
+            .unwrap()
+            .is_empty());
+    }
 }
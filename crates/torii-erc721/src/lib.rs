@@ -9,6 +9,7 @@
 //! - [`Erc721Decoder`]: Decodes ERC721 Transfer, Approval, and ApprovalForAll events
 //! - [`Erc721Sink`]: Processes decoded events, stores in SQLite, and publishes updates
 //! - [`Erc721Storage`]: SQLite storage with ownership tracking and efficient pagination
+//! - [`ShardedErc721Storage`]: Storage split over several databases by token contract
 //! - [`Erc721Service`]: gRPC service for queries and real-time subscriptions
 //!
 //! # Example
@@ -35,12 +36,15 @@
 //!     .add_service(Erc721Server::new(grpc_service));
 //! ```
 
+pub mod api;
 pub mod decoder;
 pub mod grpc_service;
 pub mod handlers;
 pub mod identification;
+pub mod sharding;
 pub mod sink;
 pub mod storage;
+pub mod supply;
 pub mod synthetic;
 
 // Include generated protobuf code
@@ -58,6 +62,10 @@ pub use decoder::{
 pub use grpc_service::Erc721Service;
 pub use handlers::{Erc721MetadataCommandHandler, Erc721TokenUriCommandHandler};
 pub use identification::Erc721Rule;
+pub use sharding::ShardedErc721Storage;
 pub use sink::Erc721Sink;
-pub use storage::{Erc721Storage, NftOwnershipData, NftTransferData, TransferCursor};
+pub use storage::{
+    Erc721Storage, NftOwnershipData, NftTransferData, OwnershipChangeData, TransferCursor,
+};
+pub use supply::{CollectionSupply, SupplyChange, TransferKind};
 pub use synthetic::{SyntheticErc721Config, SyntheticErc721Extractor};
@@ -0,0 +1,667 @@
+//! ERC721 storage sharded by token contract
+//!
+//! [`ShardedErc721Storage`] splits the ERC721 tables over several [`Erc721Storage`] shards
+//! (see [`torii_common::sharding`]). This type mirrors the [`Erc721Storage`] methods used
+//! by the sink and the services: writes and collection-scoped queries are routed to the
+//! shard of the collection, other queries fan out to every shard.
+//!
+//! Transfer and ownership ids (and the cursors built from them) are global ids.
+
+use crate::storage::{
+    Erc721Storage, NftApprovalData, NftOwnershipData, NftTransferData, OperatorApprovalData,
+    OperatorCursor, OwnedTokenCursor, OwnershipChangeData, OwnershipCursor, TokenApprovalChange,
+    TokenAttributeQueryResult, TransferCursor,
+};
+use crate::supply::{CollectionSupply, SupplyChange};
+use anyhow::Result;
+use async_trait::async_trait;
+use futures::future::try_join_all;
+use starknet::core::types::{Felt, U256};
+use std::borrow::Cow;
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::sync::Arc;
+use torii_common::{
+    merge_pages, shard_url, FieldMask, NormalizedMetadata, ReplayRange, ReplaySource,
+    StorageShards, TokenUriResult, TokenUriStore,
+};
+
+/// Token metadata row: (token, name, symbol, total supply)
+type TokenMetadataRow = (Felt, Option<String>, Option<String>, Option<U256>);
+
+/// ERC721 storage split by token contract
+#[derive(Clone)]
+pub struct ShardedErc721Storage {
+    shards: StorageShards<Erc721Storage>,
+}
+
+impl From<Arc<Erc721Storage>> for ShardedErc721Storage {
+    fn from(storage: Arc<Erc721Storage>) -> Self {
+        Self::new(StorageShards::single(storage))
+    }
+}
+
+impl ShardedErc721Storage {
+    pub fn new(shards: StorageShards<Erc721Storage>) -> Self {
+        Self { shards }
+    }
+
+    /// Create or open `count` shards, derived from `db_path` with [`shard_url`].
+    pub async fn open(db_path: &str, count: usize) -> Result<Self> {
+        let mut shards = Vec::with_capacity(count.max(1));
+        for index in 0..count.max(1) {
+            shards.push(Arc::new(
+                Erc721Storage::new(&shard_url(db_path, index)).await?,
+            ));
+        }
+        Ok(Self::new(StorageShards::new(shards)))
+    }
+
+    pub fn shards(&self) -> &StorageShards<Erc721Storage> {
+        &self.shards
+    }
+
+    /// Storage of the shard of `token`.
+    pub fn for_token(&self, token: Felt) -> &Arc<Erc721Storage> {
+        self.shards.for_contract(token)
+    }
+
+    /// Splits `items` by shard of their token; a single shard borrows them as is.
+    fn split<'a, T: Clone>(
+        &self,
+        items: &'a [T],
+        token: impl Fn(&T) -> Felt,
+    ) -> Vec<(usize, Cow<'a, [T]>)> {
+        if !self.shards.is_sharded() {
+            return vec![(0, Cow::Borrowed(items))];
+        }
+        self.shards
+            .partition(items.iter().cloned(), token)
+            .into_iter()
+            .map(|(shard, items)| (shard, Cow::Owned(items)))
+            .collect()
+    }
+
+    pub async fn insert_transfers_batch(&self, transfers: &[NftTransferData]) -> Result<usize> {
+        let mut inserted = 0;
+        for (shard, transfers) in self.split(transfers, |t| t.token) {
+            inserted += self
+                .shards
+                .get(shard)
+                .insert_transfers_batch(&transfers)
+                .await?;
+        }
+        Ok(inserted)
+    }
+
+    pub async fn apply_supply_changes(
+        &self,
+        changes: &[SupplyChange],
+    ) -> Result<Vec<CollectionSupply>> {
+        let mut supplies = Vec::new();
+        for (shard, changes) in self.split(changes, |c| c.token) {
+            supplies.extend(
+                self.shards
+                    .get(shard)
+                    .apply_supply_changes(&changes)
+                    .await?,
+            );
+        }
+        Ok(supplies)
+    }
+
+    pub async fn get_collection_supply(&self, token: Felt) -> Result<Option<CollectionSupply>> {
+        self.for_token(token).get_collection_supply(token).await
+    }
+
+    pub async fn insert_operator_approvals_batch(
+        &self,
+        approvals: &[OperatorApprovalData],
+    ) -> Result<usize> {
+        let mut inserted = 0;
+        for (shard, approvals) in self.split(approvals, |a| a.token) {
+            inserted += self
+                .shards
+                .get(shard)
+                .insert_operator_approvals_batch(&approvals)
+                .await?;
+        }
+        Ok(inserted)
+    }
+
+    pub async fn apply_token_approval_changes(
+        &self,
+        changes: &[TokenApprovalChange],
+    ) -> Result<usize> {
+        let mut inserted = 0;
+        for (shard, changes) in self.split(changes, TokenApprovalChange::token) {
+            inserted += self
+                .shards
+                .get(shard)
+                .apply_token_approval_changes(&changes)
+                .await?;
+        }
+        Ok(inserted)
+    }
+
+    pub async fn has_token_metadata_batch(&self, tokens: &[Felt]) -> Result<HashSet<Felt>> {
+        let mut existing = HashSet::new();
+        for (shard, tokens) in self.split(tokens, |token| *token) {
+            existing.extend(
+                self.shards
+                    .get(shard)
+                    .has_token_metadata_batch(&tokens)
+                    .await?,
+            );
+        }
+        Ok(existing)
+    }
+
+    pub async fn has_token_uri_batch(
+        &self,
+        tokens: &[(Felt, U256)],
+    ) -> Result<HashSet<(Felt, U256)>> {
+        let mut existing = HashSet::new();
+        for (shard, tokens) in self.split(tokens, |(token, _)| *token) {
+            existing.extend(self.shards.get(shard).has_token_uri_batch(&tokens).await?);
+        }
+        Ok(existing)
+    }
+
+    pub async fn upsert_token_metadata(
+        &self,
+        token: Felt,
+        name: Option<&str>,
+        symbol: Option<&str>,
+        total_supply: Option<U256>,
+    ) -> Result<()> {
+        self.for_token(token)
+            .upsert_token_metadata(token, name, symbol, total_supply)
+            .await
+    }
+
+    pub async fn get_token_metadata(
+        &self,
+        token: Felt,
+    ) -> Result<Option<(Option<String>, Option<String>, Option<U256>)>> {
+        self.for_token(token).get_token_metadata(token).await
+    }
+
+    /// Get token metadata in token order, merged across shards
+    pub async fn get_token_metadata_paginated(
+        &self,
+        cursor: Option<Felt>,
+        limit: u32,
+    ) -> Result<(Vec<TokenMetadataRow>, Option<Felt>)> {
+        let pages = self
+            .shards
+            .try_fan_out(|_, storage| storage.get_token_metadata_paginated(cursor, limit))
+            .await?;
+        let more_in_shards = pages.iter().any(|(_, next)| next.is_some());
+        let (rows, truncated) = merge_pages(
+            pages.into_iter().map(|(rows, _)| rows),
+            limit as usize,
+            |row| row.0,
+        );
+        let next_cursor = if more_in_shards || truncated {
+            rows.last().map(|row| row.0)
+        } else {
+            None
+        };
+        Ok((rows, next_cursor))
+    }
+
+    pub async fn get_token_uris_by_contract(
+        &self,
+        token: Felt,
+    ) -> Result<Vec<(U256, Option<String>, Option<String>)>> {
+        self.for_token(token)
+            .get_token_uris_by_contract(token)
+            .await
+    }
+
+    pub async fn get_token_uris_batch(
+        &self,
+        token: Felt,
+        token_ids: &[U256],
+    ) -> Result<Vec<(U256, Option<String>, Option<String>)>> {
+        self.for_token(token)
+            .get_token_uris_batch(token, token_ids)
+            .await
+    }
+
+    pub async fn get_token_metadata_fields_batch(
+        &self,
+        token: Felt,
+        token_ids: &[U256],
+    ) -> Result<Vec<(U256, NormalizedMetadata)>> {
+        self.for_token(token)
+            .get_token_metadata_fields_batch(token, token_ids)
+            .await
+    }
+
+    pub async fn query_token_ids_by_attributes(
+        &self,
+        token: Felt,
+        filters: &[(String, Vec<String>)],
+        cursor_token_id: Option<U256>,
+        limit: u32,
+        include_facets: bool,
+        facet_limit: u32,
+    ) -> Result<TokenAttributeQueryResult> {
+        self.for_token(token)
+            .query_token_ids_by_attributes(
+                token,
+                filters,
+                cursor_token_id,
+                limit,
+                include_facets,
+                facet_limit,
+            )
+            .await
+    }
+
+    /// Get filtered transfers, merged across shards (see [`Erc721Storage::get_transfers_filtered`])
+    #[allow(clippy::too_many_arguments)]
+    pub async fn get_transfers_filtered(
+        &self,
+        wallet: Option<Felt>,
+        from: Option<Felt>,
+        to: Option<Felt>,
+        tokens: &[Felt],
+        token_ids: &[U256],
+        block_from: Option<u64>,
+        block_to: Option<u64>,
+        cursor: Option<TransferCursor>,
+        limit: u32,
+    ) -> Result<(Vec<NftTransferData>, Option<TransferCursor>)> {
+        self.get_transfers_projected(
+            wallet,
+            from,
+            to,
+            tokens,
+            token_ids,
+            block_from,
+            block_to,
+            cursor,
+            limit,
+            &FieldMask::all(),
+        )
+        .await
+    }
+
+    /// Get projected transfers, merged across shards (see [`Erc721Storage::get_transfers_projected`])
+    #[allow(clippy::too_many_arguments)]
+    pub async fn get_transfers_projected(
+        &self,
+        wallet: Option<Felt>,
+        from: Option<Felt>,
+        to: Option<Felt>,
+        tokens: &[Felt],
+        token_ids: &[U256],
+        block_from: Option<u64>,
+        block_to: Option<u64>,
+        cursor: Option<TransferCursor>,
+        limit: u32,
+        mask: &FieldMask,
+    ) -> Result<(Vec<NftTransferData>, Option<TransferCursor>)> {
+        let shards = &self.shards;
+        let pages = try_join_all(shards.select(tokens).into_iter().map(
+            |(shard, tokens)| async move {
+                let cursor = cursor.map(|c| TransferCursor {
+                    block_number: c.block_number,
+                    id: shards.local_cursor_before(shard, c.id),
+                });
+                let (mut transfers, _) = shards
+                    .get(shard)
+                    .get_transfers_projected(
+                        wallet, from, to, &tokens, token_ids, block_from, block_to, cursor, limit,
+                        mask,
+                    )
+                    .await?;
+                for transfer in &mut transfers {
+                    transfer.id = transfer.id.map(|id| shards.global_id(shard, id));
+                }
+                Ok::<_, anyhow::Error>(transfers)
+            },
+        ))
+        .await?;
+
+        let (transfers, _) =
+            merge_pages(pages, limit as usize, |t| Reverse((t.block_number, t.id)));
+        let next_cursor = if transfers.len() == limit as usize {
+            transfers.last().map(|t| TransferCursor {
+                block_number: t.block_number,
+                id: t.id.unwrap(),
+            })
+        } else {
+            None
+        };
+        Ok((transfers, next_cursor))
+    }
+
+    /// Transfers of a transaction from every shard, in event order
+    pub async fn get_transfers_by_tx(&self, tx_hash: Felt) -> Result<Vec<NftTransferData>> {
+        let shards = &self.shards;
+        let pages = try_join_all((0..shards.len()).map(|shard| async move {
+            let mut transfers = shards.get(shard).get_transfers_by_tx(tx_hash).await?;
+            for transfer in &mut transfers {
+                transfer.id = transfer.id.map(|id| shards.global_id(shard, id));
+            }
+            Ok::<_, anyhow::Error>(transfers)
+        }))
+        .await?;
+        let mut transfers: Vec<NftTransferData> = pages.into_iter().flatten().collect();
+        transfers.sort_by_key(|t| (t.event_index.is_none(), t.event_index, t.id));
+        Ok(transfers)
+    }
+
+    pub async fn get_owner(&self, token: Felt, token_id: U256) -> Result<Option<Felt>> {
+        self.for_token(token).get_owner(token, token_id).await
+    }
+
+    pub async fn get_owner_at_block(
+        &self,
+        token: Felt,
+        token_id: U256,
+        block_number: u64,
+    ) -> Result<Option<OwnershipChangeData>> {
+        self.for_token(token)
+            .get_owner_at_block(token, token_id, block_number)
+            .await
+    }
+
+    pub async fn get_ownership_history(
+        &self,
+        token: Felt,
+        token_id: U256,
+        cursor: Option<OwnershipCursor>,
+        limit: u32,
+    ) -> Result<(Vec<OwnershipChangeData>, Option<OwnershipCursor>)> {
+        self.for_token(token)
+            .get_ownership_history(token, token_id, cursor, limit)
+            .await
+    }
+
+    /// Get the NFTs of an owner, merged across shards (see [`Erc721Storage::get_ownership_by_owner`])
+    pub async fn get_ownership_by_owner(
+        &self,
+        owner: Felt,
+        tokens: &[Felt],
+        cursor: Option<OwnershipCursor>,
+        limit: u32,
+    ) -> Result<(Vec<NftOwnershipData>, Option<OwnershipCursor>)> {
+        self.get_ownership_projected(owner, tokens, cursor, limit, &FieldMask::all())
+            .await
+    }
+
+    /// Get the projected NFTs of an owner, merged across shards (see
+    /// [`Erc721Storage::get_ownership_projected`])
+    pub async fn get_ownership_projected(
+        &self,
+        owner: Felt,
+        tokens: &[Felt],
+        cursor: Option<OwnershipCursor>,
+        limit: u32,
+        mask: &FieldMask,
+    ) -> Result<(Vec<NftOwnershipData>, Option<OwnershipCursor>)> {
+        let shards = &self.shards;
+        let pages = try_join_all(shards.select(tokens).into_iter().map(
+            |(shard, tokens)| async move {
+                let cursor = cursor.map(|c| OwnershipCursor {
+                    block_number: c.block_number,
+                    id: shards.local_cursor_before(shard, c.id),
+                });
+                let (mut owned, _) = shards
+                    .get(shard)
+                    .get_ownership_projected(owner, &tokens, cursor, limit, mask)
+                    .await?;
+                for row in &mut owned {
+                    row.id = row.id.map(|id| shards.global_id(shard, id));
+                }
+                Ok::<_, anyhow::Error>(owned)
+            },
+        ))
+        .await?;
+
+        let (owned, _) = merge_pages(pages, limit as usize, |o| Reverse((o.block_number, o.id)));
+        let next_cursor = if owned.len() == limit as usize {
+            owned.last().map(|o| OwnershipCursor {
+                block_number: o.block_number,
+                id: o.id.unwrap(),
+            })
+        } else {
+            None
+        };
+        Ok((owned, next_cursor))
+    }
+
+    /// Get the NFTs of an owner in (token, token id) order, merged across shards
+    pub async fn get_tokens_by_owner(
+        &self,
+        owner: Felt,
+        cursor: Option<OwnedTokenCursor>,
+        limit: u32,
+    ) -> Result<(Vec<NftOwnershipData>, Option<OwnedTokenCursor>)> {
+        let pages = self
+            .shards
+            .try_fan_out(|_, storage| storage.get_tokens_by_owner(owner, cursor, limit))
+            .await?;
+        let more_in_shards = pages.iter().any(|(_, next)| next.is_some());
+        let (owned, truncated) = merge_pages(
+            pages.into_iter().map(|(owned, _)| owned),
+            limit as usize,
+            |o| (o.token, o.token_id.high(), o.token_id.low()),
+        );
+        let next_cursor = if more_in_shards || truncated {
+            owned.last().map(|o| OwnedTokenCursor {
+                token: o.token,
+                token_id: o.token_id,
+            })
+        } else {
+            None
+        };
+        Ok((owned, next_cursor))
+    }
+
+    pub async fn get_token_approvals(
+        &self,
+        owner: Felt,
+        cursor: Option<OwnedTokenCursor>,
+        limit: u32,
+    ) -> Result<(Vec<NftApprovalData>, Option<OwnedTokenCursor>)> {
+        let pages = self
+            .shards
+            .try_fan_out(|_, storage| storage.get_token_approvals(owner, cursor, limit))
+            .await?;
+        let more_in_shards = pages.iter().any(|(_, next)| next.is_some());
+        let (approvals, truncated) = merge_pages(
+            pages.into_iter().map(|(approvals, _)| approvals),
+            limit as usize,
+            |a| (a.token, a.token_id.high(), a.token_id.low()),
+        );
+        let next_cursor = if more_in_shards || truncated {
+            approvals.last().map(|a| OwnedTokenCursor {
+                token: a.token,
+                token_id: a.token_id,
+            })
+        } else {
+            None
+        };
+        Ok((approvals, next_cursor))
+    }
+
+    pub async fn get_approved_operators(
+        &self,
+        owner: Felt,
+        cursor: Option<OperatorCursor>,
+        limit: u32,
+    ) -> Result<(Vec<OperatorApprovalData>, Option<OperatorCursor>)> {
+        let pages = self
+            .shards
+            .try_fan_out(|_, storage| storage.get_approved_operators(owner, cursor, limit))
+            .await?;
+        let more_in_shards = pages.iter().any(|(_, next)| next.is_some());
+        let (operators, truncated) = merge_pages(
+            pages.into_iter().map(|(operators, _)| operators),
+            limit as usize,
+            |o| (o.token, o.operator),
+        );
+        let next_cursor = if more_in_shards || truncated {
+            operators.last().map(|o| OperatorCursor {
+                token: o.token,
+                operator: o.operator,
+            })
+        } else {
+            None
+        };
+        Ok((operators, next_cursor))
+    }
+
+    pub async fn get_transfer_count(&self) -> Result<u64> {
+        let counts = self
+            .shards
+            .try_fan_out(|_, storage| storage.get_transfer_count())
+            .await?;
+        Ok(counts.into_iter().sum())
+    }
+
+    /// Collections never span shards, so per-shard counts add up.
+    pub async fn get_token_count(&self) -> Result<u64> {
+        let counts = self
+            .shards
+            .try_fan_out(|_, storage| storage.get_token_count())
+            .await?;
+        Ok(counts.into_iter().sum())
+    }
+
+    pub async fn get_nft_count(&self) -> Result<u64> {
+        let counts = self
+            .shards
+            .try_fan_out(|_, storage| storage.get_nft_count())
+            .await?;
+        Ok(counts.into_iter().sum())
+    }
+
+    pub async fn get_latest_block(&self) -> Result<Option<u64>> {
+        let blocks = self
+            .shards
+            .try_fan_out(|_, storage| storage.get_latest_block())
+            .await?;
+        Ok(blocks.into_iter().flatten().max())
+    }
+
+    /// Runs SQLite maintenance on every shard.
+    pub async fn run_maintenance(&self) -> Result<()> {
+        self.shards
+            .try_fan_out(|_, storage| storage.run_maintenance())
+            .await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl TokenUriStore for ShardedErc721Storage {
+    async fn store_token_uris_batch(&self, results: &[TokenUriResult]) -> Result<()> {
+        self.shards.store_token_uris_batch(results).await
+    }
+}
+
+#[async_trait]
+impl ReplaySource for ShardedErc721Storage {
+    type Row = NftTransferData;
+
+    /// Transfers in id order, merged across shards by global id
+    async fn replay_page(
+        &self,
+        range: &ReplayRange,
+        after_id: Option<i64>,
+    ) -> Result<Vec<NftTransferData>> {
+        let shards = &self.shards;
+        let pages = try_join_all(shards.select(&range.tokens).into_iter().map(
+            |(shard, tokens)| async move {
+                let after_id = after_id.map(|id| shards.local_cursor_after(shard, id));
+                let mut transfers = shards
+                    .get(shard)
+                    .get_transfers_for_replay(&range.with_tokens(tokens), after_id)
+                    .await?;
+                for transfer in &mut transfers {
+                    transfer.id = transfer.id.map(|id| shards.global_id(shard, id));
+                }
+                Ok::<_, anyhow::Error>(transfers)
+            },
+        ))
+        .await?;
+
+        Ok(merge_pages(pages, range.page_size as usize, |t| t.id).0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_db_path(test_name: &str) -> String {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .expect("system time before unix epoch")
+            .as_nanos();
+        std::env::temp_dir()
+            .join(format!("torii-erc721-{test_name}-{nanos}.db"))
+            .to_string_lossy()
+            .to_string()
+    }
+
+    #[tokio::test]
+    async fn transfers_page_across_shards() {
+        let storage = ShardedErc721Storage::open(&temp_db_path("sharded-transfers"), 2)
+            .await
+            .expect("create storage");
+        let owner = Felt::from(0xabcu64);
+        let transfers: Vec<NftTransferData> = (1..=8u64)
+            .map(|n| NftTransferData {
+                id: None,
+                token: Felt::from(n),
+                token_id: U256::from(n),
+                from: Felt::ZERO,
+                to: owner,
+                block_number: n,
+                tx_hash: Felt::from(n),
+                timestamp: Some(1_700_000_000),
+                event_index: None,
+            })
+            .collect();
+        let used: HashSet<usize> = transfers
+            .iter()
+            .map(|t| storage.shards().index_of(t.token))
+            .collect();
+        assert_eq!(used.len(), 2);
+        storage
+            .insert_transfers_batch(&transfers)
+            .await
+            .expect("insert transfers");
+
+        let mut blocks = Vec::new();
+        let mut cursor = None;
+        loop {
+            let (page, next) = storage
+                .get_transfers_filtered(None, None, None, &[], &[], None, None, cursor, 3)
+                .await
+                .expect("transfers page");
+            blocks.extend(page.iter().map(|t| t.block_number));
+            cursor = next;
+            if cursor.is_none() {
+                break;
+            }
+        }
+        assert_eq!(blocks, (1..=8u64).rev().collect::<Vec<_>>());
+
+        let (owned, _) = storage
+            .get_tokens_by_owner(owner, None, 8)
+            .await
+            .expect("owned tokens");
+        let tokens: Vec<Felt> = owned.iter().map(|o| o.token).collect();
+        assert_eq!(tokens, (1..=8u64).map(Felt::from).collect::<Vec<_>>());
+        assert_eq!(storage.get_transfer_count().await.expect("count"), 8);
+    }
+}
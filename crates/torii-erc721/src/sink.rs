@@ -1,28 +1,36 @@
 //! ERC721 sink for processing NFT transfers, approvals, and ownership
 
+use crate::api::{self, Erc721ApiState};
 use crate::decoder::{
     BatchMetadataUpdate as DecodedBatchMetadataUpdate, MetadataUpdate as DecodedMetadataUpdate,
-    NftTransfer as DecodedNftTransfer, OperatorApproval as DecodedOperatorApproval,
+    NftApproval as DecodedNftApproval, NftTransfer as DecodedNftTransfer,
+    OperatorApproval as DecodedOperatorApproval,
 };
 use crate::grpc_service::Erc721Service;
 use crate::handlers::{FetchErc721MetadataCommand, RefreshErc721TokenUriCommand};
 use crate::proto;
-use crate::storage::{Erc721Storage, NftTransferData, OperatorApprovalData};
-use anyhow::Result;
+use crate::sharding::ShardedErc721Storage;
+use crate::storage::{NftApprovalData, NftTransferData, OperatorApprovalData, TokenApprovalChange};
+use crate::supply::{supply_changes, CollectionSupply, TransferKind};
 use async_trait::async_trait;
+use axum::routing::get;
 use axum::Router;
 use prost::Message;
 use prost_types::Any;
 use starknet::core::types::{Felt, U256};
-use starknet::providers::jsonrpc::{HttpTransport, JsonRpcClient};
 use std::collections::{HashMap, HashSet};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 use torii::command::CommandBusSender;
-use torii::etl::sink::{EventBus, TopicInfo};
+use torii::etl::sink::{
+    ColumnSchema, EnvelopeSchema, EventBus, SinkContractFilter, TableSchema, TopicInfo,
+};
 use torii::etl::{Envelope, ExtractionBatch, Sink, TypeId};
 use torii::grpc::UpdateType;
+use torii::ToriiResult;
 use torii_common::{u256_to_bytes, TokenStandard, TokenUriRequest, TokenUriSender};
+use torii_common::{MaintenanceSchedule, RpcProvider};
 
 /// Default threshold for "live" detection: 100 blocks from chain head.
 /// Events from blocks older than this won't be broadcast to real-time subscribers.
@@ -38,7 +46,7 @@ const LIVE_THRESHOLD_BLOCKS: u64 = 100;
 /// During historical indexing (more than 100 blocks from chain head), events are
 /// stored but not broadcast to avoid overwhelming real-time subscribers.
 pub struct Erc721Sink {
-    storage: Arc<Erc721Storage>,
+    storage: ShardedErc721Storage,
     event_bus: Option<Arc<EventBus>>,
     grpc_service: Option<Erc721Service>,
     /// Whether contract metadata commands should be dispatched.
@@ -54,12 +62,17 @@ pub struct Erc721Sink {
     /// In-memory counters to avoid full-table COUNT(*) in the ingest hot path.
     total_transfers: AtomicU64,
     total_operator_approvals: AtomicU64,
+    /// SQLite maintenance run on idle cycles (disabled by default).
+    maintenance: MaintenanceSchedule,
+    /// Contracts routed to the sink (all when unset)
+    contract_filter: Option<SinkContractFilter>,
 }
 
 impl Erc721Sink {
-    pub fn new(storage: Arc<Erc721Storage>) -> Self {
+    /// Writes go to `storage`, a single storage or a [`ShardedErc721Storage`].
+    pub fn new(storage: impl Into<ShardedErc721Storage>) -> Self {
         Self {
-            storage,
+            storage: storage.into(),
             event_bus: None,
             grpc_service: None,
             metadata_commands_enabled: false,
@@ -71,6 +84,8 @@ impl Erc721Sink {
             // Avoid startup full-table COUNT(*) scans on large datasets.
             total_transfers: AtomicU64::new(0),
             total_operator_approvals: AtomicU64::new(0),
+            maintenance: MaintenanceSchedule::disabled(),
+            contract_filter: None,
         }
     }
 
@@ -80,13 +95,25 @@ impl Erc721Sink {
         self
     }
 
+    /// Only process the transfers of the contracts allowed by `filter` (see [`SinkContractFilter`])
+    pub fn with_contract_filter(mut self, filter: SinkContractFilter) -> Self {
+        self.contract_filter = Some(filter);
+        self
+    }
+
+    /// Runs SQLite maintenance (vacuum, `ANALYZE`) on idle cycles, at most once per `interval`
+    pub fn with_maintenance_interval(mut self, interval: Duration) -> Self {
+        self.maintenance = MaintenanceSchedule::new(Some(interval));
+        self
+    }
+
     /// Get a reference to the storage
-    pub fn storage(&self) -> &Arc<Erc721Storage> {
+    pub fn storage(&self) -> &ShardedErc721Storage {
         &self.storage
     }
 
     /// Filter function for ERC721 transfer events
-    fn matches_transfer_filters(
+    pub fn matches_transfer_filters(
         transfer: &proto::NftTransfer,
         filters: &HashMap<String, String>,
     ) -> bool {
@@ -138,6 +165,7 @@ impl Erc721Sink {
                 contract,
                 token_id,
                 standard: TokenStandard::Erc721,
+                uri: None,
             });
         }
 
@@ -191,6 +219,77 @@ impl Erc721Sink {
             self.enqueue_token_uri_request(contract, token_id);
         }
     }
+
+    /// Folds the mints and burns of `transfers` into the collection supplies.
+    ///
+    /// Returns the supply of each changed collection. Failures are logged; the
+    /// transfers are already stored.
+    async fn record_supply_changes(&self, transfers: &[NftTransferData]) -> Vec<CollectionSupply> {
+        let changes = supply_changes(transfers);
+        if changes.is_empty() {
+            return Vec::new();
+        }
+        let start = std::time::Instant::now();
+        let supplies = match self.storage.apply_supply_changes(&changes).await {
+            Ok(supplies) => {
+                ::metrics::counter!("torii_erc721_supply_updates_total")
+                    .increment(supplies.len() as u64);
+                tracing::debug!(
+                    target: "torii_erc721::sink",
+                    count = supplies.len(),
+                    "Recorded collection supply changes"
+                );
+                supplies
+            }
+            Err(e) => {
+                tracing::error!(
+                    target: "torii_erc721::sink",
+                    error = %e,
+                    "Failed to record collection supply changes"
+                );
+                Vec::new()
+            }
+        };
+        ::metrics::histogram!("torii_erc721_sink_supply_duration_seconds")
+            .record(start.elapsed().as_secs_f64());
+        supplies
+    }
+
+    /// Publishes the mints and burns of `transfers` to the mint subscribers, with the
+    /// supply of their collection after the batch.
+    fn broadcast_mints(&self, transfers: &[NftTransferData], supplies: &[CollectionSupply]) {
+        let Some(grpc_service) = self
+            .grpc_service
+            .as_ref()
+            .filter(|service| service.has_mint_subscribers())
+        else {
+            return;
+        };
+
+        let supplies: HashMap<Felt, u64> = supplies
+            .iter()
+            .map(|supply| (supply.token, supply.supply()))
+            .collect();
+        let timestamp = chrono::Utc::now().timestamp();
+        for transfer in transfers {
+            let (burn, account) = match TransferKind::classify(transfer.from, transfer.to) {
+                TransferKind::Mint => (false, transfer.to),
+                TransferKind::Burn => (true, transfer.from),
+                TransferKind::Transfer => continue,
+            };
+            grpc_service.broadcast_mint(proto::MintUpdate {
+                token: transfer.token.to_bytes_be().to_vec(),
+                token_id: u256_to_bytes(transfer.token_id),
+                burn,
+                account: account.to_bytes_be().to_vec(),
+                block_number: transfer.block_number,
+                tx_hash: transfer.tx_hash.to_bytes_be().to_vec(),
+                supply: supplies.get(&transfer.token).copied().unwrap_or(0),
+                timestamp,
+                shutdown: None,
+            });
+        }
+    }
 }
 
 #[async_trait]
@@ -199,6 +298,14 @@ impl Sink for Erc721Sink {
         "erc721"
     }
 
+    fn version(&self) -> Option<&str> {
+        Some(env!("CARGO_PKG_VERSION"))
+    }
+
+    fn descriptor_set(&self) -> Option<&'static [u8]> {
+        Some(crate::FILE_DESCRIPTOR_SET)
+    }
+
     fn interested_types(&self) -> Vec<TypeId> {
         vec![
             TypeId::new("erc721.transfer"),
@@ -209,22 +316,49 @@ impl Sink for Erc721Sink {
         ]
     }
 
+    fn contract_filter(&self) -> Option<&SinkContractFilter> {
+        self.contract_filter.as_ref()
+    }
+
+    fn envelope_schemas(&self) -> Vec<EnvelopeSchema> {
+        vec![
+            EnvelopeSchema::new("erc721.transfer", "torii.sinks.erc721.NftTransfer"),
+            EnvelopeSchema::new("erc721.approval", "torii.sinks.erc721.NftApproval"),
+            EnvelopeSchema::new(
+                "erc721.approval_for_all",
+                "torii.sinks.erc721.OperatorApproval",
+            ),
+        ]
+    }
+
     async fn initialize(
         &mut self,
         event_bus: Arc<EventBus>,
         context: &torii::etl::sink::SinkContext,
-    ) -> Result<()> {
+    ) -> ToriiResult<()> {
+        // End the service's streams with the server-wide shutdown notice.
+        if let Some(grpc_service) = &self.grpc_service {
+            let grpc_service = grpc_service.clone();
+            let shutdown = event_bus.subscription_manager().shutdown_signal().clone();
+            tokio::spawn(async move {
+                let notice = shutdown.notified().await;
+                grpc_service.notify_shutdown(notice.block_number);
+            });
+        }
         self.event_bus = Some(event_bus);
         self.command_bus = Some(context.command_bus.clone());
         tracing::info!(target: "torii_erc721::sink", "ERC721 sink initialized");
         Ok(())
     }
 
-    async fn process(&self, envelopes: &[Envelope], batch: &ExtractionBatch) -> Result<()> {
+    async fn process(&self, envelopes: &[Envelope], batch: &ExtractionBatch) -> ToriiResult<()> {
         let mut transfers: Vec<NftTransferData> = Vec::with_capacity(envelopes.len());
         let mut operator_approvals: Vec<OperatorApprovalData> = Vec::with_capacity(envelopes.len());
+        // Approvals and the transfers that reset them, in event order
+        let mut approval_changes: Vec<TokenApprovalChange> = Vec::with_capacity(envelopes.len());
         let mut inserted_transfers: u64 = 0;
         let mut inserted_operator_approvals: u64 = 0;
+        let mut inserted_token_approvals: u64 = 0;
 
         // Get block timestamps from batch
         let block_timestamps: HashMap<u64, i64> = batch
@@ -248,9 +382,31 @@ impl Sink for Erc721Sink {
                         block_number: transfer.block_number,
                         tx_hash: transfer.transaction_hash,
                         timestamp,
+                        event_index: envelope.meta.event_index,
+                    });
+                    approval_changes.push(TokenApprovalChange::Transfer {
+                        token: transfer.token,
+                        token_id: transfer.token_id,
                     });
                 }
             }
+            // Handle single token approval
+            else if envelope.type_id == TypeId::new("erc721.approval") {
+                if let Some(approval) = envelope.body.as_any().downcast_ref::<DecodedNftApproval>()
+                {
+                    let timestamp = block_timestamps.get(&approval.block_number).copied();
+                    approval_changes.push(TokenApprovalChange::Approval(NftApprovalData {
+                        id: None,
+                        token: approval.token,
+                        token_id: approval.token_id,
+                        owner: approval.owner,
+                        approved: approval.approved,
+                        block_number: approval.block_number,
+                        tx_hash: approval.transaction_hash,
+                        timestamp,
+                    }));
+                }
+            }
             // Handle approval for all
             else if envelope.type_id == TypeId::new("erc721.approval_for_all") {
                 if let Some(approval) = envelope
@@ -300,8 +456,6 @@ impl Sink for Erc721Sink {
                     }
                 }
             }
-            // Note: erc721.approval (single token approval) could be handled similarly
-            // but is less commonly needed for indexing purposes
         }
 
         // Fetch metadata for any new token contracts.
@@ -418,7 +572,7 @@ impl Sink for Erc721Sink {
                         error = %e,
                         "Failed to batch insert transfers"
                     );
-                    return Err(e);
+                    return Err(e.into());
                 }
             };
 
@@ -433,9 +587,13 @@ impl Sink for Erc721Sink {
                     "Batch inserted NFT transfers"
                 );
 
+                let supplies = self.record_supply_changes(&transfers).await;
+
                 // Only broadcast to real-time subscribers when near chain head
                 let is_live = batch.is_live(LIVE_THRESHOLD_BLOCKS);
                 if is_live {
+                    self.broadcast_mints(&transfers, &supplies);
+
                     // Publish transfer events
                     for transfer in &transfers {
                         let proto_transfer = proto::NftTransfer {
@@ -451,7 +609,9 @@ impl Sink for Erc721Sink {
                         // Publish to EventBus
                         if let Some(event_bus) = &self.event_bus {
                             let mut buf = Vec::new();
-                            proto_transfer.encode(&mut buf)?;
+                            proto_transfer
+                                .encode(&mut buf)
+                                .map_err(anyhow::Error::from)?;
                             let any = Any {
                                 type_url: "type.googleapis.com/torii.sinks.erc721.NftTransfer"
                                     .to_string(),
@@ -502,17 +662,48 @@ impl Sink for Erc721Sink {
                         operator_approvals.len(),
                         e
                     );
-                    return Err(e);
+                    return Err(e.into());
+                }
+            }
+        }
+
+        // Apply token approvals (transfers reset the approval of their token)
+        if !approval_changes.is_empty() {
+            match self
+                .storage
+                .apply_token_approval_changes(&approval_changes)
+                .await
+            {
+                Ok(count) => {
+                    inserted_token_approvals = count as u64;
+                    if count > 0 {
+                        tracing::info!(
+                            target: "torii_erc721::sink",
+                            count = count,
+                            "Batch inserted token approvals"
+                        );
+                    }
+                }
+                Err(e) => {
+                    tracing::error!(
+                        target: "torii_erc721::sink",
+                        count = approval_changes.len(),
+                        error = %e,
+                        "Failed to apply token approval changes"
+                    );
+                    return Err(e.into());
                 }
             }
         }
 
         // Log combined statistics without full-table scans.
-        if inserted_transfers > 0 || inserted_operator_approvals > 0 {
+        if inserted_transfers > 0 || inserted_operator_approvals > 0 || inserted_token_approvals > 0
+        {
             tracing::info!(
                 target: "torii_erc721::sink",
                 batch_transfers = inserted_transfers,
                 batch_operator_approvals = inserted_operator_approvals,
+                batch_token_approvals = inserted_token_approvals,
                 total_transfers = self.total_transfers.load(Ordering::Relaxed),
                 total_operator_approvals = self.total_operator_approvals.load(Ordering::Relaxed),
                 blocks = batch.blocks.len(),
@@ -523,6 +714,25 @@ impl Sink for Erc721Sink {
         Ok(())
     }
 
+    async fn indexed_block(&self) -> ToriiResult<Option<u64>> {
+        // Block of the latest stored NFT transfer
+        Ok(self.storage.get_latest_block().await?)
+    }
+
+    async fn on_idle(&self) -> ToriiResult<()> {
+        if !self.maintenance.try_start() {
+            return Ok(());
+        }
+        let start = std::time::Instant::now();
+        self.storage.run_maintenance().await?;
+        tracing::info!(
+            target: "torii_erc721::sink",
+            elapsed_ms = start.elapsed().as_millis() as u64,
+            "Ran storage maintenance"
+        );
+        Ok(())
+    }
+
     fn topics(&self) -> Vec<TopicInfo> {
         vec![
             TopicInfo::new(
@@ -534,17 +744,148 @@ impl Sink for Erc721Sink {
                     "wallet".to_string(),
                 ],
                 "ERC721 NFT transfers. Use 'wallet' filter for from OR to matching.",
-            ),
+            )
+            .with_message_type("torii.sinks.erc721.NftTransfer"),
             TopicInfo::new(
                 "erc721.metadata",
                 vec!["token".to_string()],
                 "ERC721 token metadata updates (registered/updated token attributes).",
+            )
+            .with_message_type("torii.sinks.erc721.TokenMetadataEntry"),
+        ]
+    }
+
+    fn tables(&self) -> Vec<TableSchema> {
+        vec![
+            TableSchema::new(
+                "nft_ownership",
+                "Current owner per (token, token_id).",
+                vec![
+                    ColumnSchema::new("id", "i64"),
+                    ColumnSchema::new("token", "felt"),
+                    ColumnSchema::new("token_id", "u256"),
+                    ColumnSchema::new("owner", "felt"),
+                    ColumnSchema::new("block_number", "u64"),
+                    ColumnSchema::new("tx_hash", "felt"),
+                    ColumnSchema::new("timestamp", "i64").nullable(),
+                ],
+            ),
+            TableSchema::new(
+                "nft_transfers",
+                "One row per ERC721 Transfer event.",
+                vec![
+                    ColumnSchema::new("id", "i64"),
+                    ColumnSchema::new("token", "felt"),
+                    ColumnSchema::new("token_id", "u256"),
+                    ColumnSchema::new("from_addr", "felt"),
+                    ColumnSchema::new("to_addr", "felt"),
+                    ColumnSchema::new("block_number", "u64"),
+                    ColumnSchema::new("tx_hash", "felt"),
+                    ColumnSchema::new("timestamp", "i64").nullable(),
+                ],
+            ),
+            TableSchema::new(
+                "ownership_history",
+                "One row per owner change (transfer between different addresses).",
+                vec![
+                    ColumnSchema::new("id", "i64"),
+                    ColumnSchema::new("transfer_id", "i64"),
+                    ColumnSchema::new("token", "felt"),
+                    ColumnSchema::new("token_id", "u256"),
+                    ColumnSchema::new("previous_owner", "felt"),
+                    ColumnSchema::new("owner", "felt"),
+                    ColumnSchema::new("block_number", "u64"),
+                    ColumnSchema::new("tx_hash", "felt"),
+                    ColumnSchema::new("timestamp", "i64").nullable(),
+                ],
+            ),
+            TableSchema::new(
+                "nft_approvals",
+                "One row per ERC721 Approval event.",
+                vec![
+                    ColumnSchema::new("id", "i64"),
+                    ColumnSchema::new("token", "felt"),
+                    ColumnSchema::new("token_id", "u256"),
+                    ColumnSchema::new("owner", "felt"),
+                    ColumnSchema::new("approved", "felt"),
+                    ColumnSchema::new("block_number", "u64"),
+                    ColumnSchema::new("tx_hash", "felt"),
+                    ColumnSchema::new("timestamp", "i64").nullable(),
+                ],
+            ),
+            TableSchema::new(
+                "nft_token_approvals",
+                "Current approved address per (token, token_id); cleared by transfers.",
+                vec![
+                    ColumnSchema::new("token", "felt"),
+                    ColumnSchema::new("token_id", "u256"),
+                    ColumnSchema::new("owner", "felt"),
+                    ColumnSchema::new("approved", "felt"),
+                    ColumnSchema::new("block_number", "u64"),
+                    ColumnSchema::new("tx_hash", "felt"),
+                    ColumnSchema::new("timestamp", "i64").nullable(),
+                ],
+            ),
+            TableSchema::new(
+                "nft_operators",
+                "Current operator approval per (token, owner, operator).",
+                vec![
+                    ColumnSchema::new("id", "i64"),
+                    ColumnSchema::new("token", "felt"),
+                    ColumnSchema::new("owner", "felt"),
+                    ColumnSchema::new("operator", "felt"),
+                    ColumnSchema::new("approved", "bool"),
+                    ColumnSchema::new("block_number", "u64"),
+                    ColumnSchema::new("tx_hash", "felt"),
+                    ColumnSchema::new("timestamp", "i64").nullable(),
+                ],
+            ),
+            TableSchema::new(
+                "token_metadata",
+                "Token attributes fetched from the contract.",
+                vec![
+                    ColumnSchema::new("token", "felt"),
+                    ColumnSchema::new("name", "text").nullable(),
+                    ColumnSchema::new("symbol", "text").nullable(),
+                    ColumnSchema::new("total_supply", "u256").nullable(),
+                ],
+            ),
+            TableSchema::new(
+                "token_uris",
+                "Token URI and resolved metadata per (token, token_id).",
+                vec![
+                    ColumnSchema::new("token", "felt"),
+                    ColumnSchema::new("token_id", "u256"),
+                    ColumnSchema::new("uri", "text").nullable(),
+                    ColumnSchema::new("metadata_json", "json").nullable(),
+                    ColumnSchema::new("updated_at", "i64"),
+                ],
+            ),
+            TableSchema::new(
+                "collection_supply",
+                "Supply per collection, tracked from indexed mints and burns.",
+                vec![
+                    ColumnSchema::new("token", "felt"),
+                    ColumnSchema::new("block_number", "u64"),
+                    ColumnSchema::new("total_minted", "u64"),
+                    ColumnSchema::new("total_burned", "u64"),
+                    ColumnSchema::new("supply", "u64"),
+                ],
             ),
         ]
     }
 
     fn build_routes(&self) -> Router {
+        let state = Erc721ApiState {
+            storage: self.storage.clone(),
+        };
+
         Router::new()
+            .route(
+                "/erc721/transfers/export",
+                get(api::transfers_export_handler),
+            )
+            .with_state(state)
     }
 }
 
@@ -554,7 +895,7 @@ impl Erc721Sink {
         self
     }
 
-    pub fn with_metadata_fetching(self, _provider: Arc<JsonRpcClient<HttpTransport>>) -> Self {
+    pub fn with_metadata_fetching(self, _provider: Arc<RpcProvider>) -> Self {
         self.with_metadata_commands()
     }
 
@@ -9,10 +9,99 @@ use starknet::core::types::{Felt, U256};
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::{Arc, Mutex};
 use tokio_postgres::{types::ToSql as PgToSql, Client, NoTls};
+use torii::etl::migrations::{self, Migration};
+
+use crate::supply::{fold_supply_changes, CollectionSupply, SupplyChange};
 use torii_common::{
-    blob_to_felt, blob_to_u256, felt_to_blob, u256_to_blob, TokenUriResult, TokenUriStore,
+    apply_sqlite_encryption, blob_to_felt, blob_to_u256, felt_to_blob, split_page, u256_to_blob,
+    FieldMask, NormalizedMetadata, ReplayParam, ReplayRange, TokenUriResult, TokenUriStore,
+    SQLITE_MAINTENANCE_SQL,
 };
 
+/// Migration component name recorded in `schema_version`
+const MIGRATION_COMPONENT: &str = "erc721";
+
+/// Embedded schema migrations
+const SQLITE_MIGRATIONS: &[Migration] = &[
+    Migration::new(
+        1,
+        "initial",
+        include_str!("../migrations/sqlite/0001_initial.sql"),
+    ),
+    Migration::new(
+        2,
+        "ownership_history",
+        include_str!("../migrations/sqlite/0002_ownership_history.sql"),
+    ),
+    Migration::new(
+        3,
+        "token_metadata_fields",
+        include_str!("../migrations/sqlite/0003_token_metadata_fields.sql"),
+    ),
+    Migration::new(
+        4,
+        "token_approvals",
+        include_str!("../migrations/sqlite/0004_token_approvals.sql"),
+    ),
+    Migration::new(
+        5,
+        "collection_supply",
+        include_str!("../migrations/sqlite/0005_collection_supply.sql"),
+    ),
+    Migration::new(
+        6,
+        "transfer_event_index",
+        include_str!("../migrations/sqlite/0006_transfer_event_index.sql"),
+    ),
+    Migration::new(
+        7,
+        "transfer_token_block_index",
+        include_str!("../migrations/sqlite/0007_transfer_token_block_index.sql"),
+    ),
+];
+const POSTGRES_MIGRATIONS: &[Migration] = &[
+    Migration::new(
+        1,
+        "initial",
+        include_str!("../migrations/postgres/0001_initial.sql"),
+    ),
+    Migration::new(
+        2,
+        "ownership_history",
+        include_str!("../migrations/postgres/0002_ownership_history.sql"),
+    ),
+    Migration::new(
+        3,
+        "owner_token_index",
+        include_str!("../migrations/postgres/0003_owner_token_index.sql"),
+    ),
+    Migration::new(
+        4,
+        "token_metadata_fields",
+        include_str!("../migrations/postgres/0004_token_metadata_fields.sql"),
+    ),
+    Migration::new(
+        5,
+        "token_approvals",
+        include_str!("../migrations/postgres/0005_token_approvals.sql"),
+    ),
+    Migration::new(
+        6,
+        "collection_supply",
+        include_str!("../migrations/postgres/0006_collection_supply.sql"),
+    ),
+    Migration::new(
+        7,
+        "transfer_event_index",
+        include_str!("../migrations/postgres/0007_transfer_event_index.sql"),
+    ),
+    Migration::new(
+        8,
+        "transfer_token_block_index",
+        include_str!("../migrations/postgres/0008_transfer_token_block_index.sql"),
+    ),
+];
+
 const SQLITE_MAX_BIND_VARS: usize = 900;
 const SQLITE_TOKEN_BATCH_SIZE: usize = SQLITE_MAX_BIND_VARS;
 const SQLITE_TOKEN_PAIR_BATCH_SIZE: usize = SQLITE_MAX_BIND_VARS / 2;
@@ -31,6 +120,7 @@ enum StorageBackend {
 }
 
 /// NFT transfer data for batch insertion
+#[derive(Clone)]
 pub struct NftTransferData {
     pub id: Option<i64>,
     pub token: Felt,
@@ -40,6 +130,14 @@ pub struct NftTransferData {
     pub block_number: u64,
     pub tx_hash: Felt,
     pub timestamp: Option<i64>,
+    /// Position of the transfer among the events of its transaction
+    pub event_index: Option<u32>,
+}
+
+impl ReplayRow for NftTransferData {
+    fn replay_id(&self) -> Option<i64> {
+        self.id
+    }
 }
 
 /// NFT ownership data
@@ -51,7 +149,22 @@ pub struct NftOwnershipData {
     pub block_number: u64,
 }
 
+/// NFT owner change (one per transfer between different addresses)
+pub struct OwnershipChangeData {
+    pub id: Option<i64>,
+    pub token: Felt,
+    pub token_id: U256,
+    /// Zero for mints
+    pub previous_owner: Felt,
+    /// Zero for burns
+    pub owner: Felt,
+    pub block_number: u64,
+    pub tx_hash: Felt,
+    pub timestamp: Option<i64>,
+}
+
 /// NFT approval data
+#[derive(Clone)]
 pub struct NftApprovalData {
     pub id: Option<i64>,
     pub token: Felt,
@@ -63,7 +176,26 @@ pub struct NftApprovalData {
     pub timestamp: Option<i64>,
 }
 
+/// Change to the current approval of a token, applied in event order
+#[derive(Clone)]
+pub enum TokenApprovalChange {
+    /// `Approval` event; a zero `approved` address revokes the approval
+    Approval(NftApprovalData),
+    /// Transfers reset the approval of the token
+    Transfer { token: Felt, token_id: U256 },
+}
+
+impl TokenApprovalChange {
+    pub fn token(&self) -> Felt {
+        match self {
+            Self::Approval(approval) => approval.token,
+            Self::Transfer { token, .. } => *token,
+        }
+    }
+}
+
 /// Operator approval data
+#[derive(Clone)]
 pub struct OperatorApprovalData {
     pub id: Option<i64>,
     pub token: Felt,
@@ -89,6 +221,20 @@ pub struct OwnershipCursor {
     pub id: i64,
 }
 
+/// Cursor for enumerating the tokens of an owner (last returned token)
+#[derive(Debug, Clone, Copy)]
+pub struct OwnedTokenCursor {
+    pub token: Felt,
+    pub token_id: U256,
+}
+
+/// Cursor for enumerating the operators approved by an owner (last returned operator)
+#[derive(Debug, Clone, Copy)]
+pub struct OperatorCursor {
+    pub token: Felt,
+    pub operator: Felt,
+}
+
 /// Aggregated facet count for one key/value pair.
 pub struct AttributeFacetCount {
     pub key: String,
@@ -114,141 +260,13 @@ impl Erc721Storage {
     /// Create or open the database
     pub async fn new(db_path: &str) -> Result<Self> {
         if db_path.starts_with("postgres://") || db_path.starts_with("postgresql://") {
-            let (client, connection) = tokio_postgres::connect(db_path, NoTls).await?;
+            let (mut client, connection) = tokio_postgres::connect(db_path, NoTls).await?;
             tokio::spawn(async move {
                 if let Err(e) = connection.await {
                     tracing::error!(target: "torii_erc721::storage", error = %e, "PostgreSQL connection task failed");
                 }
             });
-            client.batch_execute(
-                r"
-                CREATE SCHEMA IF NOT EXISTS erc721;
-
-                CREATE TABLE IF NOT EXISTS erc721.nft_ownership (
-                    id BIGSERIAL PRIMARY KEY,
-                    token BYTEA NOT NULL,
-                    token_id BYTEA NOT NULL,
-                    owner BYTEA NOT NULL,
-                    block_number TEXT NOT NULL,
-                    tx_hash BYTEA NOT NULL,
-                    timestamp TEXT,
-                    UNIQUE(token, token_id)
-                );
-                CREATE INDEX IF NOT EXISTS idx_nft_ownership_owner ON erc721.nft_ownership(owner);
-                CREATE INDEX IF NOT EXISTS idx_nft_ownership_token ON erc721.nft_ownership(token);
-
-                CREATE TABLE IF NOT EXISTS erc721.nft_transfers (
-                    id BIGSERIAL PRIMARY KEY,
-                    token BYTEA NOT NULL,
-                    token_id BYTEA NOT NULL,
-                    from_addr BYTEA NOT NULL,
-                    to_addr BYTEA NOT NULL,
-                    block_number TEXT NOT NULL,
-                    tx_hash BYTEA NOT NULL,
-                    timestamp TEXT,
-                    UNIQUE(token, tx_hash, token_id, from_addr, to_addr)
-                );
-                CREATE INDEX IF NOT EXISTS idx_nft_transfers_token ON erc721.nft_transfers(token);
-                CREATE INDEX IF NOT EXISTS idx_nft_transfers_from ON erc721.nft_transfers(from_addr);
-                CREATE INDEX IF NOT EXISTS idx_nft_transfers_to ON erc721.nft_transfers(to_addr);
-                CREATE INDEX IF NOT EXISTS idx_nft_transfers_block ON erc721.nft_transfers(block_number DESC);
-                CREATE INDEX IF NOT EXISTS idx_nft_transfers_token_id ON erc721.nft_transfers(token, token_id);
-
-                CREATE TABLE IF NOT EXISTS erc721.nft_wallet_activity (
-                    id BIGSERIAL PRIMARY KEY,
-                    wallet_address BYTEA NOT NULL,
-                    token BYTEA NOT NULL,
-                    transfer_id BIGINT NOT NULL REFERENCES erc721.nft_transfers(id),
-                    direction TEXT NOT NULL CHECK(direction IN ('sent', 'received', 'both')),
-                    block_number TEXT NOT NULL
-                );
-                CREATE INDEX IF NOT EXISTS idx_nft_wallet_activity_wallet_block ON erc721.nft_wallet_activity(wallet_address, block_number DESC);
-                CREATE INDEX IF NOT EXISTS idx_nft_wallet_activity_wallet_token ON erc721.nft_wallet_activity(wallet_address, token, block_number DESC);
-
-                CREATE TABLE IF NOT EXISTS erc721.nft_approvals (
-                    id BIGSERIAL PRIMARY KEY,
-                    token BYTEA NOT NULL,
-                    token_id BYTEA NOT NULL,
-                    owner BYTEA NOT NULL,
-                    approved BYTEA NOT NULL,
-                    block_number TEXT NOT NULL,
-                    tx_hash BYTEA NOT NULL,
-                    timestamp TEXT
-                );
-
-                CREATE TABLE IF NOT EXISTS erc721.nft_operators (
-                    id BIGSERIAL PRIMARY KEY,
-                    token BYTEA NOT NULL,
-                    owner BYTEA NOT NULL,
-                    operator BYTEA NOT NULL,
-                    approved TEXT NOT NULL,
-                    block_number TEXT NOT NULL,
-                    tx_hash BYTEA NOT NULL,
-                    timestamp TEXT,
-                    UNIQUE(token, owner, operator)
-                );
-
-                CREATE TABLE IF NOT EXISTS erc721.token_metadata (
-                    token BYTEA PRIMARY KEY,
-                    name TEXT,
-                    symbol TEXT,
-                    total_supply BYTEA
-                );
-
-                CREATE TABLE IF NOT EXISTS erc721.token_uris (
-                    token BYTEA NOT NULL,
-                    token_id BYTEA NOT NULL,
-                    uri TEXT,
-                    metadata_json TEXT,
-                    updated_at TEXT NOT NULL DEFAULT (EXTRACT(EPOCH FROM NOW())::BIGINT::TEXT),
-                    PRIMARY KEY (token, token_id)
-                );
-                CREATE INDEX IF NOT EXISTS idx_token_uris_token ON erc721.token_uris(token);
-
-                CREATE TABLE IF NOT EXISTS erc721.token_attributes (
-                    token BYTEA NOT NULL,
-                    token_id BYTEA NOT NULL,
-                    key TEXT NOT NULL,
-                    value TEXT NOT NULL,
-                    PRIMARY KEY (token, token_id, key)
-                );
-                CREATE INDEX IF NOT EXISTS idx_token_attributes_token ON erc721.token_attributes(token);
-                CREATE INDEX IF NOT EXISTS idx_token_attributes_key ON erc721.token_attributes(key);
-                CREATE INDEX IF NOT EXISTS idx_token_attributes_key_value ON erc721.token_attributes(key, value);
-                CREATE INDEX IF NOT EXISTS idx_token_attributes_token_key_value ON erc721.token_attributes(token, key, value);
-                CREATE INDEX IF NOT EXISTS idx_token_attributes_token_token_id ON erc721.token_attributes(token, token_id);
-
-                CREATE TABLE IF NOT EXISTS erc721.facet_keys (
-                    id BIGSERIAL PRIMARY KEY,
-                    token BYTEA NOT NULL,
-                    key_norm TEXT NOT NULL,
-                    key_display TEXT NOT NULL,
-                    UNIQUE(token, key_norm)
-                );
-                CREATE INDEX IF NOT EXISTS idx_facet_keys_token_key_norm ON erc721.facet_keys(token, key_norm);
-
-                CREATE TABLE IF NOT EXISTS erc721.facet_values (
-                    id BIGSERIAL PRIMARY KEY,
-                    token BYTEA NOT NULL,
-                    facet_key_id BIGINT NOT NULL REFERENCES erc721.facet_keys(id) ON DELETE CASCADE,
-                    value_norm TEXT NOT NULL,
-                    value_display TEXT NOT NULL,
-                    token_count TEXT NOT NULL DEFAULT '0',
-                    UNIQUE(token, facet_key_id, value_norm)
-                );
-                CREATE INDEX IF NOT EXISTS idx_facet_values_token_key_value ON erc721.facet_values(token, facet_key_id, value_norm);
-
-                CREATE TABLE IF NOT EXISTS erc721.facet_token_map (
-                    token BYTEA NOT NULL,
-                    token_id BYTEA NOT NULL,
-                    facet_key_id BIGINT NOT NULL REFERENCES erc721.facet_keys(id) ON DELETE CASCADE,
-                    facet_value_id BIGINT NOT NULL REFERENCES erc721.facet_values(id) ON DELETE CASCADE,
-                    PRIMARY KEY (token, token_id, facet_key_id)
-                );
-                CREATE INDEX IF NOT EXISTS idx_facet_token_map_token_value_token_id ON erc721.facet_token_map(token, facet_value_id, token_id);
-                CREATE INDEX IF NOT EXISTS idx_facet_token_map_token_token_id_key_value ON erc721.facet_token_map(token, token_id, facet_key_id, facet_value_id);
-                ",
-            ).await?;
+            migrations::migrate(&mut client, MIGRATION_COMPONENT, POSTGRES_MIGRATIONS).await?;
 
             tracing::info!(target: "torii_erc721::storage", "PostgreSQL storage initialized");
             return Ok(Self {
@@ -258,11 +276,15 @@ impl Erc721Storage {
             });
         }
 
-        let conn = Connection::open(db_path)?;
+        let mut conn = Connection::open(db_path)?;
+        if apply_sqlite_encryption(&conn)? {
+            tracing::info!(target: "torii_erc721::storage", "SQLite storage encrypted with SQLCipher");
+        }
 
         // Enable WAL mode + Performance PRAGMAs
         conn.execute_batch(
-            "PRAGMA journal_mode=WAL;
+            "PRAGMA auto_vacuum=INCREMENTAL;
+             PRAGMA journal_mode=WAL;
              PRAGMA synchronous=NORMAL;
              PRAGMA foreign_keys=ON;
              PRAGMA cache_size=-64000;
@@ -275,210 +297,8 @@ impl Erc721Storage {
 
         tracing::info!(target: "torii_erc721::storage", "SQLite configured: WAL mode, 64MB cache, 256MB mmap, NORMAL sync");
 
-        // NFT ownership (current state) - one owner per NFT
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS nft_ownership (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                token BLOB NOT NULL,
-                token_id BLOB NOT NULL,
-                owner BLOB NOT NULL,
-                block_number TEXT NOT NULL,
-                tx_hash BLOB NOT NULL,
-                timestamp TEXT,
-                UNIQUE(token, token_id)
-            )",
-            [],
-        )?;
-
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_nft_ownership_owner ON nft_ownership(owner)",
-            [],
-        )?;
-
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_nft_ownership_token ON nft_ownership(token)",
-            [],
-        )?;
-
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_nft_ownership_token_owner_token_id_ord
-             ON nft_ownership(token, owner, length(token_id), token_id)",
-            [],
-        )?;
-
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_nft_ownership_owner_token_token_id_ord
-             ON nft_ownership(owner, token, length(token_id), token_id)",
-            [],
-        )?;
-
-        // Transfer history
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS nft_transfers (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                token BLOB NOT NULL,
-                token_id BLOB NOT NULL,
-                from_addr BLOB NOT NULL,
-                to_addr BLOB NOT NULL,
-                block_number TEXT NOT NULL,
-                tx_hash BLOB NOT NULL,
-                timestamp TEXT,
-                UNIQUE(token, tx_hash, token_id, from_addr, to_addr)
-            )",
-            [],
-        )?;
-
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_nft_transfers_token ON nft_transfers(token)",
-            [],
-        )?;
-
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_nft_transfers_from ON nft_transfers(from_addr)",
-            [],
-        )?;
-
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_nft_transfers_to ON nft_transfers(to_addr)",
-            [],
-        )?;
-
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_nft_transfers_block ON nft_transfers(block_number DESC)",
-            [],
-        )?;
-
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_nft_transfers_token_id ON nft_transfers(token, token_id)",
-            [],
-        )?;
-
-        // Wallet activity table for efficient OR queries
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS nft_wallet_activity (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                wallet_address BLOB NOT NULL,
-                token BLOB NOT NULL,
-                transfer_id INTEGER NOT NULL,
-                direction TEXT NOT NULL CHECK(direction IN ('sent', 'received', 'both')),
-                block_number TEXT NOT NULL,
-                FOREIGN KEY (transfer_id) REFERENCES nft_transfers(id)
-            )",
-            [],
-        )?;
-
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_nft_wallet_activity_wallet_block
-             ON nft_wallet_activity(wallet_address, block_number DESC)",
-            [],
-        )?;
-
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_nft_wallet_activity_wallet_token
-             ON nft_wallet_activity(wallet_address, token, block_number DESC)",
-            [],
-        )?;
-
-        // Approvals (single token)
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS nft_approvals (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                token BLOB NOT NULL,
-                token_id BLOB NOT NULL,
-                owner BLOB NOT NULL,
-                approved BLOB NOT NULL,
-                block_number TEXT NOT NULL,
-                tx_hash BLOB NOT NULL,
-                timestamp TEXT
-            )",
-            [],
-        )?;
-
-        // Operator approvals (all tokens)
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS nft_operators (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                token BLOB NOT NULL,
-                owner BLOB NOT NULL,
-                operator BLOB NOT NULL,
-                approved TEXT NOT NULL,
-                block_number TEXT NOT NULL,
-                tx_hash BLOB NOT NULL,
-                timestamp TEXT,
-                UNIQUE(token, owner, operator)
-            )",
-            [],
-        )?;
-
-        // Token metadata table
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS token_metadata (
-                token BLOB PRIMARY KEY,
-                name TEXT,
-                symbol TEXT,
-                total_supply BLOB
-            )",
-            [],
-        )?;
-
-        conn.execute_batch(
-            "CREATE TABLE IF NOT EXISTS token_uris (
-                token BLOB NOT NULL,
-                token_id BLOB NOT NULL,
-                uri TEXT,
-                metadata_json TEXT,
-                updated_at TEXT NOT NULL DEFAULT (strftime('%s', 'now')),
-                PRIMARY KEY (token, token_id)
-            );
-            CREATE INDEX IF NOT EXISTS idx_token_uris_token ON token_uris(token);
-
-            CREATE TABLE IF NOT EXISTS token_attributes (
-                token BLOB NOT NULL,
-                token_id BLOB NOT NULL,
-                key TEXT NOT NULL,
-                value TEXT NOT NULL,
-                PRIMARY KEY (token, token_id, key),
-                FOREIGN KEY (token, token_id) REFERENCES token_uris(token, token_id)
-            );
-            CREATE INDEX IF NOT EXISTS idx_token_attributes_token ON token_attributes(token);
-            CREATE INDEX IF NOT EXISTS idx_token_attributes_key ON token_attributes(key);
-            CREATE INDEX IF NOT EXISTS idx_token_attributes_key_value ON token_attributes(key, value);
-            CREATE INDEX IF NOT EXISTS idx_token_attributes_token_key_value ON token_attributes(token, key, value);
-            CREATE INDEX IF NOT EXISTS idx_token_attributes_token_token_id ON token_attributes(token, token_id);
-
-            CREATE TABLE IF NOT EXISTS facet_keys (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                token BLOB NOT NULL,
-                key_norm TEXT NOT NULL,
-                key_display TEXT NOT NULL,
-                UNIQUE(token, key_norm)
-            );
-            CREATE INDEX IF NOT EXISTS idx_facet_keys_token_key_norm ON facet_keys(token, key_norm);
-
-            CREATE TABLE IF NOT EXISTS facet_values (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                token BLOB NOT NULL,
-                facet_key_id INTEGER NOT NULL,
-                value_norm TEXT NOT NULL,
-                value_display TEXT NOT NULL,
-                token_count TEXT NOT NULL DEFAULT '0',
-                UNIQUE(token, facet_key_id, value_norm),
-                FOREIGN KEY (facet_key_id) REFERENCES facet_keys(id) ON DELETE CASCADE
-            );
-            CREATE INDEX IF NOT EXISTS idx_facet_values_token_key_value ON facet_values(token, facet_key_id, value_norm);
-
-            CREATE TABLE IF NOT EXISTS facet_token_map (
-                token BLOB NOT NULL,
-                token_id BLOB NOT NULL,
-                facet_key_id INTEGER NOT NULL,
-                facet_value_id INTEGER NOT NULL,
-                PRIMARY KEY (token, token_id, facet_key_id),
-                FOREIGN KEY (facet_key_id) REFERENCES facet_keys(id) ON DELETE CASCADE,
-                FOREIGN KEY (facet_value_id) REFERENCES facet_values(id) ON DELETE CASCADE
-            );
-            CREATE INDEX IF NOT EXISTS idx_facet_token_map_token_value_token_id ON facet_token_map(token, facet_value_id, token_id);
-            CREATE INDEX IF NOT EXISTS idx_facet_token_map_token_token_id_key_value ON facet_token_map(token, token_id, facet_key_id, facet_value_id);",
-        )?;
+        // Create or upgrade tables
+        migrations::migrate(&mut conn, MIGRATION_COMPONENT, SQLITE_MIGRATIONS).await?;
 
         tracing::info!(target: "torii_erc721::storage", db_path = %db_path, "ERC721 database initialized");
 
@@ -505,14 +325,18 @@ impl Erc721Storage {
 
         {
             let mut transfer_stmt = tx.prepare_cached(
-                "INSERT OR IGNORE INTO nft_transfers (token, token_id, from_addr, to_addr, block_number, tx_hash, timestamp)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, COALESCE(?7, strftime('%s', 'now')))",
+                "INSERT OR IGNORE INTO nft_transfers (token, token_id, from_addr, to_addr, block_number, tx_hash, timestamp, event_index)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, COALESCE(?7, strftime('%s', 'now')), ?8)",
             )?;
 
             let mut ownership_stmt = tx.prepare_cached(
                 "INSERT OR REPLACE INTO nft_ownership (token, token_id, owner, block_number, tx_hash, timestamp)
                  VALUES (?1, ?2, ?3, ?4, ?5, COALESCE(?6, strftime('%s', 'now')))",
             )?;
+            let mut history_stmt = tx.prepare_cached(
+                "INSERT OR IGNORE INTO ownership_history (transfer_id, token, token_id, previous_owner, owner, block_number, tx_hash, timestamp)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, COALESCE(?8, strftime('%s', 'now')))",
+            )?;
             let mut wallet_both_stmt = tx.prepare_cached(
                 "INSERT INTO nft_wallet_activity (wallet_address, token, transfer_id, direction, block_number)
                  VALUES (?1, ?2, ?3, 'both', ?4)",
@@ -542,6 +366,7 @@ impl Erc721Storage {
                     transfer.block_number.to_string(),
                     &tx_hash_blob,
                     transfer.timestamp.map(|t| t.to_string()),
+                    transfer.event_index,
                 ])?;
 
                 if rows > 0 {
@@ -560,6 +385,20 @@ impl Erc721Storage {
                         ])?;
                     }
 
+                    // Record the owner change
+                    if transfer.from != transfer.to {
+                        history_stmt.execute(params![
+                            transfer_id,
+                            &token_blob,
+                            &token_id_blob,
+                            &from_blob,
+                            &to_blob,
+                            i64::try_from(transfer.block_number).unwrap_or(i64::MAX),
+                            &tx_hash_blob,
+                            transfer.timestamp.map(|t| t.to_string()),
+                        ])?;
+                    }
+
                     // Insert wallet activity records
                     if transfer.from != Felt::ZERO
                         && transfer.to != Felt::ZERO
@@ -644,6 +483,87 @@ impl Erc721Storage {
         Ok(inserted)
     }
 
+    /// Apply token approval changes in a single transaction
+    ///
+    /// `Approval` events are appended to `nft_approvals` and replace the current approval of
+    /// the token; transfers and zero-address approvals clear it. Returns the number of
+    /// `Approval` events stored.
+    pub async fn apply_token_approval_changes(
+        &self,
+        changes: &[TokenApprovalChange],
+    ) -> Result<usize> {
+        if self.backend == StorageBackend::Postgres {
+            return self.pg_apply_token_approval_changes(changes).await;
+        }
+        if changes.is_empty() {
+            return Ok(0);
+        }
+
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+
+        let mut inserted = 0;
+
+        {
+            let mut history_stmt = tx.prepare_cached(
+                "INSERT INTO nft_approvals (token, token_id, owner, approved, block_number, tx_hash, timestamp)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, COALESCE(?7, strftime('%s', 'now')))",
+            )?;
+            let mut upsert_stmt = tx.prepare_cached(
+                "INSERT OR REPLACE INTO nft_token_approvals (token, token_id, owner, approved, block_number, tx_hash, timestamp)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, COALESCE(?7, strftime('%s', 'now')))",
+            )?;
+            let mut clear_stmt = tx.prepare_cached(
+                "DELETE FROM nft_token_approvals WHERE token = ?1 AND token_id = ?2",
+            )?;
+
+            for change in changes {
+                match change {
+                    TokenApprovalChange::Approval(approval) => {
+                        let token_blob = felt_to_blob(approval.token);
+                        let token_id_blob = u256_to_blob(approval.token_id);
+                        let owner_blob = felt_to_blob(approval.owner);
+                        let approved_blob = felt_to_blob(approval.approved);
+                        let tx_hash_blob = felt_to_blob(approval.tx_hash);
+                        let block_number = approval.block_number.to_string();
+                        let timestamp = approval.timestamp.map(|t| t.to_string());
+
+                        history_stmt.execute(params![
+                            &token_blob,
+                            &token_id_blob,
+                            &owner_blob,
+                            &approved_blob,
+                            &block_number,
+                            &tx_hash_blob,
+                            &timestamp,
+                        ])?;
+                        if approval.approved == Felt::ZERO {
+                            clear_stmt.execute(params![&token_blob, &token_id_blob])?;
+                        } else {
+                            upsert_stmt.execute(params![
+                                &token_blob,
+                                &token_id_blob,
+                                &owner_blob,
+                                &approved_blob,
+                                &block_number,
+                                &tx_hash_blob,
+                                &timestamp,
+                            ])?;
+                        }
+                        inserted += 1;
+                    }
+                    TokenApprovalChange::Transfer { token, token_id } => {
+                        clear_stmt
+                            .execute(params![felt_to_blob(*token), u256_to_blob(*token_id)])?;
+                    }
+                }
+            }
+        }
+
+        tx.commit()?;
+        Ok(inserted)
+    }
+
     /// Get filtered transfers with cursor-based pagination
     pub async fn get_transfers_filtered(
         &self,
@@ -656,26 +576,60 @@ impl Erc721Storage {
         block_to: Option<u64>,
         cursor: Option<TransferCursor>,
         limit: u32,
+    ) -> Result<(Vec<NftTransferData>, Option<TransferCursor>)> {
+        self.get_transfers_projected(
+            wallet,
+            from,
+            to,
+            tokens,
+            token_ids,
+            block_from,
+            block_to,
+            cursor,
+            limit,
+            &FieldMask::all(),
+        )
+        .await
+    }
+
+    /// Get filtered transfers, only selecting the columns of the fields in `mask`
+    ///
+    /// Unselected fields are left zeroed (`id` and `block_number` are always selected
+    /// for pagination).
+    #[allow(clippy::too_many_arguments)]
+    pub async fn get_transfers_projected(
+        &self,
+        wallet: Option<Felt>,
+        from: Option<Felt>,
+        to: Option<Felt>,
+        tokens: &[Felt],
+        token_ids: &[U256],
+        block_from: Option<u64>,
+        block_to: Option<u64>,
+        cursor: Option<TransferCursor>,
+        limit: u32,
+        mask: &FieldMask,
     ) -> Result<(Vec<NftTransferData>, Option<TransferCursor>)> {
         if self.backend == StorageBackend::Postgres {
             return self
                 .pg_get_transfers_filtered(
-                    wallet, from, to, tokens, token_ids, block_from, block_to, cursor, limit,
+                    wallet, from, to, tokens, token_ids, block_from, block_to, cursor, limit, mask,
                 )
                 .await;
         }
+        let columns = transfer_columns(mask, "X''", "NULL");
         let conn = self.conn.lock().unwrap();
 
         let mut query = String::new();
         let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
 
         if let Some(wallet_addr) = wallet {
-            query.push_str(
-                "SELECT DISTINCT t.id, t.token, t.token_id, t.from_addr, t.to_addr, t.block_number, t.tx_hash, t.timestamp
+            query.push_str(&format!(
+                "SELECT DISTINCT {columns}
                  FROM nft_wallet_activity wa
                  JOIN nft_transfers t ON wa.transfer_id = t.id
-                 WHERE wa.wallet_address = ?",
-            );
+                 WHERE wa.wallet_address = ?"
+            ));
             params_vec.push(Box::new(felt_to_blob(wallet_addr)));
 
             if !tokens.is_empty() {
@@ -686,11 +640,11 @@ impl Erc721Storage {
                 }
             }
         } else {
-            query.push_str(
-                "SELECT t.id, t.token, t.token_id, t.from_addr, t.to_addr, t.block_number, t.tx_hash, t.timestamp
+            query.push_str(&format!(
+                "SELECT {columns}
                  FROM nft_transfers t
-                 WHERE 1=1",
-            );
+                 WHERE 1=1"
+            ));
 
             if let Some(from_addr) = from {
                 query.push_str(" AND t.from_addr = ?");
@@ -762,6 +716,7 @@ impl Erc721Storage {
                 block_number: block_number_str.parse::<u64>().unwrap_or(0),
                 tx_hash: blob_to_felt(&tx_hash_bytes),
                 timestamp: timestamp_str.and_then(|s| s.parse::<i64>().ok()),
+                event_index: None,
             })
         })?;
 
@@ -779,6 +734,51 @@ impl Erc721Storage {
         Ok((transfers, next_cursor))
     }
 
+    /// Get a page of the transfers of `range` after row `after_id`, for historical replay
+    ///
+    /// Pages by row id (ascending) rather than block number, so that a replay follows the
+    /// order in which transfers were indexed and stays stable while new rows are appended.
+    pub async fn get_transfers_for_replay(
+        &self,
+        range: &ReplayRange,
+        after_id: Option<i64>,
+    ) -> Result<Vec<NftTransferData>> {
+        if self.backend == StorageBackend::Postgres {
+            return self.pg_get_transfers_for_replay(range, after_id).await;
+        }
+        let conn = self.conn.lock().unwrap();
+
+        let mut query = String::from(
+            "SELECT t.id, t.token, t.token_id, t.from_addr, t.to_addr, t.block_number, t.tx_hash, t.timestamp
+             FROM nft_transfers t",
+        );
+        let mut params = Vec::new();
+        query.push_str(&range.page_clause(after_id, |param| {
+            params.push(param);
+            "?".to_string()
+        }));
+
+        let mut stmt = conn.prepare_cached(&query)?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(&params), |row| {
+            let block_number_str: String = row.get(5)?;
+            let timestamp_str: Option<String> = row.get(7)?;
+
+            Ok(NftTransferData {
+                id: Some(row.get(0)?),
+                token: blob_to_felt(&row.get::<_, Vec<u8>>(1)?),
+                token_id: blob_to_u256(&row.get::<_, Vec<u8>>(2)?),
+                from: blob_to_felt(&row.get::<_, Vec<u8>>(3)?),
+                to: blob_to_felt(&row.get::<_, Vec<u8>>(4)?),
+                block_number: block_number_str.parse::<u64>().unwrap_or(0),
+                tx_hash: blob_to_felt(&row.get::<_, Vec<u8>>(6)?),
+                timestamp: timestamp_str.and_then(|s| s.parse::<i64>().ok()),
+                event_index: None,
+            })
+        })?;
+
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
     /// Get current owner of a specific NFT
     pub async fn get_owner(&self, token: Felt, token_id: U256) -> Result<Option<Felt>> {
         if self.backend == StorageBackend::Postgres {
@@ -799,6 +799,119 @@ impl Erc721Storage {
         }
     }
 
+    /// Get the last transfer of a specific NFT up to `block_number` (inclusive): its
+    /// recipient is the owner at the end of that block, zero if it was burned.
+    ///
+    /// Returns `None` if the NFT was not minted by then. The change `id` is the transfer id.
+    pub async fn get_owner_at_block(
+        &self,
+        token: Felt,
+        token_id: U256,
+        block_number: u64,
+    ) -> Result<Option<OwnershipChangeData>> {
+        if self.backend == StorageBackend::Postgres {
+            return self
+                .pg_get_owner_at_block(token, token_id, block_number)
+                .await;
+        }
+        let conn = self.conn.lock().unwrap();
+
+        let block_number = i64::try_from(block_number).unwrap_or(i64::MAX);
+        let mut stmt = conn.prepare_cached(
+            "SELECT id, token, token_id, from_addr, to_addr, block_number, tx_hash, timestamp
+             FROM nft_transfers
+             WHERE token = ?1 AND token_id = ?2 AND CAST(block_number AS INTEGER) <= ?3
+             ORDER BY CAST(block_number AS INTEGER) DESC, id DESC
+             LIMIT 1",
+        )?;
+        let result = stmt.query_row(
+            params![felt_to_blob(token), u256_to_blob(token_id), block_number],
+            |row| {
+                let block_number: String = row.get(5)?;
+                let timestamp: Option<String> = row.get(7)?;
+                Ok(OwnershipChangeData {
+                    id: Some(row.get(0)?),
+                    token: blob_to_felt(&row.get::<_, Vec<u8>>(1)?),
+                    token_id: blob_to_u256(&row.get::<_, Vec<u8>>(2)?),
+                    previous_owner: blob_to_felt(&row.get::<_, Vec<u8>>(3)?),
+                    owner: blob_to_felt(&row.get::<_, Vec<u8>>(4)?),
+                    block_number: block_number.parse::<u64>().unwrap_or(0),
+                    tx_hash: blob_to_felt(&row.get::<_, Vec<u8>>(6)?),
+                    timestamp: timestamp.and_then(|s| s.parse::<i64>().ok()),
+                })
+            },
+        );
+
+        match result {
+            Ok(change) => Ok(Some(change)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Get the owner changes of a specific NFT, newest first, with cursor-based pagination
+    pub async fn get_ownership_history(
+        &self,
+        token: Felt,
+        token_id: U256,
+        cursor: Option<OwnershipCursor>,
+        limit: u32,
+    ) -> Result<(Vec<OwnershipChangeData>, Option<OwnershipCursor>)> {
+        if self.backend == StorageBackend::Postgres {
+            return self
+                .pg_get_ownership_history(token, token_id, cursor, limit)
+                .await;
+        }
+        let conn = self.conn.lock().unwrap();
+
+        let (cursor_block, cursor_id) = cursor.map_or((i64::MAX, i64::MAX), |c| {
+            (i64::try_from(c.block_number).unwrap_or(i64::MAX), c.id)
+        });
+        let mut stmt = conn.prepare_cached(
+            "SELECT id, token, token_id, previous_owner, owner, block_number, tx_hash, timestamp
+             FROM ownership_history
+             WHERE token = ?1 AND token_id = ?2
+               AND (block_number < ?3 OR (block_number = ?3 AND id < ?4))
+             ORDER BY block_number DESC, id DESC
+             LIMIT ?5",
+        )?;
+        let rows = stmt.query_map(
+            params![
+                felt_to_blob(token),
+                u256_to_blob(token_id),
+                cursor_block,
+                cursor_id,
+                i64::from(limit),
+            ],
+            |row| {
+                let block_number: i64 = row.get(5)?;
+                let timestamp: Option<String> = row.get(7)?;
+                Ok(OwnershipChangeData {
+                    id: Some(row.get(0)?),
+                    token: blob_to_felt(&row.get::<_, Vec<u8>>(1)?),
+                    token_id: blob_to_u256(&row.get::<_, Vec<u8>>(2)?),
+                    previous_owner: blob_to_felt(&row.get::<_, Vec<u8>>(3)?),
+                    owner: blob_to_felt(&row.get::<_, Vec<u8>>(4)?),
+                    block_number: block_number as u64,
+                    tx_hash: blob_to_felt(&row.get::<_, Vec<u8>>(6)?),
+                    timestamp: timestamp.and_then(|s| s.parse::<i64>().ok()),
+                })
+            },
+        )?;
+        let changes: Vec<OwnershipChangeData> = rows.collect::<Result<_, _>>()?;
+
+        let next_cursor = if changes.len() == limit as usize {
+            changes.last().map(|c| OwnershipCursor {
+                block_number: c.block_number,
+                id: c.id.unwrap(),
+            })
+        } else {
+            None
+        };
+
+        Ok((changes, next_cursor))
+    }
+
     /// Get ownership records filtered by owner
     pub async fn get_ownership_by_owner(
         &self,
@@ -806,16 +919,31 @@ impl Erc721Storage {
         tokens: &[Felt],
         cursor: Option<OwnershipCursor>,
         limit: u32,
+    ) -> Result<(Vec<NftOwnershipData>, Option<OwnershipCursor>)> {
+        self.get_ownership_projected(owner, tokens, cursor, limit, &FieldMask::all())
+            .await
+    }
+
+    /// Get ownership records filtered by owner, only selecting the columns of the fields
+    /// in `mask` (`id` and `block_number` are always selected for pagination)
+    pub async fn get_ownership_projected(
+        &self,
+        owner: Felt,
+        tokens: &[Felt],
+        cursor: Option<OwnershipCursor>,
+        limit: u32,
+        mask: &FieldMask,
     ) -> Result<(Vec<NftOwnershipData>, Option<OwnershipCursor>)> {
         if self.backend == StorageBackend::Postgres {
             return self
-                .pg_get_ownership_by_owner(owner, tokens, cursor, limit)
+                .pg_get_ownership_by_owner(owner, tokens, cursor, limit, mask)
                 .await;
         }
         let conn = self.conn.lock().unwrap();
 
-        let mut query = String::from(
-            "SELECT id, token, token_id, owner, block_number FROM nft_ownership WHERE owner = ?",
+        let mut query = format!(
+            "SELECT {} FROM nft_ownership WHERE owner = ?",
+            ownership_columns(mask, "X''")
         );
         let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(felt_to_blob(owner))];
 
@@ -871,6 +999,173 @@ impl Erc721Storage {
         Ok((ownership, next_cursor))
     }
 
+    /// Get the tokens owned by `owner` across all collections, ordered by contract then
+    /// token ID, with cursor-based pagination
+    pub async fn get_tokens_by_owner(
+        &self,
+        owner: Felt,
+        cursor: Option<OwnedTokenCursor>,
+        limit: u32,
+    ) -> Result<(Vec<NftOwnershipData>, Option<OwnedTokenCursor>)> {
+        if self.backend == StorageBackend::Postgres {
+            return self.pg_get_tokens_by_owner(owner, cursor, limit).await;
+        }
+        let conn = self.conn.lock().unwrap();
+
+        let mut query = String::from(
+            "SELECT id, token, token_id, owner, block_number FROM nft_ownership WHERE owner = ?",
+        );
+        let mut params_vec: Vec<Box<dyn ToSql>> = vec![Box::new(felt_to_blob(owner))];
+        if let Some(c) = cursor {
+            let token_id = u256_to_blob(c.token_id);
+            query.push_str(
+                " AND (token > ? OR (token = ? AND (length(token_id) > ? \
+                 OR (length(token_id) = ? AND token_id > ?))))",
+            );
+            params_vec.push(Box::new(felt_to_blob(c.token)));
+            params_vec.push(Box::new(felt_to_blob(c.token)));
+            params_vec.push(Box::new(token_id.len() as i64));
+            params_vec.push(Box::new(token_id.len() as i64));
+            params_vec.push(Box::new(token_id));
+        }
+        query.push_str(" ORDER BY token ASC, length(token_id) ASC, token_id ASC LIMIT ?");
+        params_vec.push(Box::new(i64::from(limit) + 1));
+
+        let mut stmt = conn.prepare_cached(&query)?;
+        let params_refs: Vec<&dyn ToSql> =
+            params_vec.iter().map(std::convert::AsRef::as_ref).collect();
+        let rows = stmt.query_map(params_refs.as_slice(), |row| {
+            let block_number_str: String = row.get(4)?;
+            Ok(NftOwnershipData {
+                id: Some(row.get(0)?),
+                token: blob_to_felt(&row.get::<_, Vec<u8>>(1)?),
+                token_id: blob_to_u256(&row.get::<_, Vec<u8>>(2)?),
+                owner: blob_to_felt(&row.get::<_, Vec<u8>>(3)?),
+                block_number: block_number_str.parse::<u64>().unwrap_or(0),
+            })
+        })?;
+        let ownership: Vec<NftOwnershipData> = rows.collect::<Result<_, _>>()?;
+
+        Ok(split_page(ownership, limit as usize, |o| {
+            OwnedTokenCursor {
+                token: o.token,
+                token_id: o.token_id,
+            }
+        }))
+    }
+
+    /// Get the current single-token approvals granted by `owner`, ordered by contract then
+    /// token ID, with cursor-based pagination
+    pub async fn get_token_approvals(
+        &self,
+        owner: Felt,
+        cursor: Option<OwnedTokenCursor>,
+        limit: u32,
+    ) -> Result<(Vec<NftApprovalData>, Option<OwnedTokenCursor>)> {
+        if self.backend == StorageBackend::Postgres {
+            return self.pg_get_token_approvals(owner, cursor, limit).await;
+        }
+        let conn = self.conn.lock().unwrap();
+
+        let mut query = String::from(
+            "SELECT token, token_id, owner, approved, block_number, tx_hash, timestamp
+             FROM nft_token_approvals WHERE owner = ?",
+        );
+        let mut params_vec: Vec<Box<dyn ToSql>> = vec![Box::new(felt_to_blob(owner))];
+        if let Some(c) = cursor {
+            let token_id = u256_to_blob(c.token_id);
+            query.push_str(
+                " AND (token > ? OR (token = ? AND (length(token_id) > ? \
+                 OR (length(token_id) = ? AND token_id > ?))))",
+            );
+            params_vec.push(Box::new(felt_to_blob(c.token)));
+            params_vec.push(Box::new(felt_to_blob(c.token)));
+            params_vec.push(Box::new(token_id.len() as i64));
+            params_vec.push(Box::new(token_id.len() as i64));
+            params_vec.push(Box::new(token_id));
+        }
+        query.push_str(" ORDER BY token ASC, length(token_id) ASC, token_id ASC LIMIT ?");
+        params_vec.push(Box::new(i64::from(limit) + 1));
+
+        let mut stmt = conn.prepare_cached(&query)?;
+        let params_refs: Vec<&dyn ToSql> =
+            params_vec.iter().map(std::convert::AsRef::as_ref).collect();
+        let rows = stmt.query_map(params_refs.as_slice(), |row| {
+            let block_number_str: String = row.get(4)?;
+            let timestamp_str: Option<String> = row.get(6)?;
+            Ok(NftApprovalData {
+                id: None,
+                token: blob_to_felt(&row.get::<_, Vec<u8>>(0)?),
+                token_id: blob_to_u256(&row.get::<_, Vec<u8>>(1)?),
+                owner: blob_to_felt(&row.get::<_, Vec<u8>>(2)?),
+                approved: blob_to_felt(&row.get::<_, Vec<u8>>(3)?),
+                block_number: block_number_str.parse::<u64>().unwrap_or(0),
+                tx_hash: blob_to_felt(&row.get::<_, Vec<u8>>(5)?),
+                timestamp: timestamp_str.and_then(|s| s.parse::<i64>().ok()),
+            })
+        })?;
+        let approvals: Vec<NftApprovalData> = rows.collect::<Result<_, _>>()?;
+
+        Ok(split_page(approvals, limit as usize, |a| {
+            OwnedTokenCursor {
+                token: a.token,
+                token_id: a.token_id,
+            }
+        }))
+    }
+
+    /// Get the operators currently approved by `owner` (`ApprovalForAll`), ordered by
+    /// contract then operator, with cursor-based pagination
+    pub async fn get_approved_operators(
+        &self,
+        owner: Felt,
+        cursor: Option<OperatorCursor>,
+        limit: u32,
+    ) -> Result<(Vec<OperatorApprovalData>, Option<OperatorCursor>)> {
+        if self.backend == StorageBackend::Postgres {
+            return self.pg_get_approved_operators(owner, cursor, limit).await;
+        }
+        let conn = self.conn.lock().unwrap();
+
+        let mut query = String::from(
+            "SELECT id, token, owner, operator, block_number, tx_hash, timestamp
+             FROM nft_operators WHERE owner = ? AND approved = '1'",
+        );
+        let mut params_vec: Vec<Box<dyn ToSql>> = vec![Box::new(felt_to_blob(owner))];
+        if let Some(c) = cursor {
+            query.push_str(" AND (token > ? OR (token = ? AND operator > ?))");
+            params_vec.push(Box::new(felt_to_blob(c.token)));
+            params_vec.push(Box::new(felt_to_blob(c.token)));
+            params_vec.push(Box::new(felt_to_blob(c.operator)));
+        }
+        query.push_str(" ORDER BY token ASC, operator ASC LIMIT ?");
+        params_vec.push(Box::new(i64::from(limit) + 1));
+
+        let mut stmt = conn.prepare_cached(&query)?;
+        let params_refs: Vec<&dyn ToSql> =
+            params_vec.iter().map(std::convert::AsRef::as_ref).collect();
+        let rows = stmt.query_map(params_refs.as_slice(), |row| {
+            let block_number_str: String = row.get(4)?;
+            let timestamp_str: Option<String> = row.get(6)?;
+            Ok(OperatorApprovalData {
+                id: Some(row.get(0)?),
+                token: blob_to_felt(&row.get::<_, Vec<u8>>(1)?),
+                owner: blob_to_felt(&row.get::<_, Vec<u8>>(2)?),
+                operator: blob_to_felt(&row.get::<_, Vec<u8>>(3)?),
+                approved: true,
+                block_number: block_number_str.parse::<u64>().unwrap_or(0),
+                tx_hash: blob_to_felt(&row.get::<_, Vec<u8>>(5)?),
+                timestamp: timestamp_str.and_then(|s| s.parse::<i64>().ok()),
+            })
+        })?;
+        let operators: Vec<OperatorApprovalData> = rows.collect::<Result<_, _>>()?;
+
+        Ok(split_page(operators, limit as usize, |o| OperatorCursor {
+            token: o.token,
+            operator: o.operator,
+        }))
+    }
+
     /// Query token IDs by flattened metadata attributes.
     ///
     /// Filter semantics:
@@ -934,6 +1229,67 @@ impl Erc721Storage {
         )
     }
 
+    /// Get the transfers of a transaction, in event order
+    ///
+    /// Transfers stored before event indexes were recorded come last, in insertion order.
+    pub async fn get_transfers_by_tx(&self, tx_hash: Felt) -> Result<Vec<NftTransferData>> {
+        let tx_hash_blob = felt_to_blob(tx_hash);
+
+        if self.backend == StorageBackend::Postgres {
+            let client = self.pg_client().await?;
+            let rows = client
+                .query(
+                    "SELECT id, token, token_id, from_addr, to_addr, block_number, tx_hash, timestamp, event_index
+                     FROM erc721.nft_transfers
+                     WHERE tx_hash = $1
+                     ORDER BY event_index NULLS LAST, id",
+                    &[&tx_hash_blob],
+                )
+                .await?;
+            return Ok(rows
+                .into_iter()
+                .map(|row| NftTransferData {
+                    id: Some(row.get::<usize, i64>(0)),
+                    token: blob_to_felt(&row.get::<usize, Vec<u8>>(1)),
+                    token_id: blob_to_u256(&row.get::<usize, Vec<u8>>(2)),
+                    from: blob_to_felt(&row.get::<usize, Vec<u8>>(3)),
+                    to: blob_to_felt(&row.get::<usize, Vec<u8>>(4)),
+                    block_number: row.get::<usize, String>(5).parse::<u64>().unwrap_or(0),
+                    tx_hash: blob_to_felt(&row.get::<usize, Vec<u8>>(6)),
+                    timestamp: row
+                        .get::<usize, Option<String>>(7)
+                        .and_then(|s| s.parse::<i64>().ok()),
+                    event_index: row.get::<usize, Option<i64>>(8).map(|i| i as u32),
+                })
+                .collect());
+        }
+
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare_cached(
+            "SELECT id, token, token_id, from_addr, to_addr, block_number, tx_hash, timestamp, event_index
+             FROM nft_transfers
+             WHERE tx_hash = ?1
+             ORDER BY event_index IS NULL, event_index, id",
+        )?;
+        let rows = stmt.query_map(params![&tx_hash_blob], |row| {
+            let block_number_str: String = row.get(5)?;
+            let timestamp_str: Option<String> = row.get(7)?;
+
+            Ok(NftTransferData {
+                id: Some(row.get(0)?),
+                token: blob_to_felt(&row.get::<_, Vec<u8>>(1)?),
+                token_id: blob_to_u256(&row.get::<_, Vec<u8>>(2)?),
+                from: blob_to_felt(&row.get::<_, Vec<u8>>(3)?),
+                to: blob_to_felt(&row.get::<_, Vec<u8>>(4)?),
+                block_number: block_number_str.parse::<u64>().unwrap_or(0),
+                tx_hash: blob_to_felt(&row.get::<_, Vec<u8>>(6)?),
+                timestamp: timestamp_str.and_then(|s| s.parse::<i64>().ok()),
+                event_index: row.get::<_, Option<u32>>(8)?,
+            })
+        })?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+    }
+
     /// Get transfer count
     pub async fn get_transfer_count(&self) -> Result<u64> {
         if self.backend == StorageBackend::Postgres {
@@ -985,6 +1341,88 @@ impl Erc721Storage {
         Ok(block.and_then(|b| b.parse::<u64>().ok()))
     }
 
+    /// Runs SQLite maintenance: incremental vacuum, `ANALYZE` and `PRAGMA optimize`.
+    ///
+    /// A no-op on PostgreSQL, which relies on autovacuum.
+    pub async fn run_maintenance(&self) -> Result<()> {
+        if self.backend == StorageBackend::Postgres {
+            return Ok(());
+        }
+        let conn = self.conn.lock().unwrap();
+        conn.execute_batch(SQLITE_MAINTENANCE_SQL)?;
+        Ok(())
+    }
+
+    // ===== Collection Supply Methods =====
+
+    /// Folds mint/burn counts into the supply of their collections.
+    ///
+    /// Returns the new supply of each changed collection.
+    pub async fn apply_supply_changes(
+        &self,
+        changes: &[SupplyChange],
+    ) -> Result<Vec<CollectionSupply>> {
+        if changes.is_empty() {
+            return Ok(Vec::new());
+        }
+        if self.backend == StorageBackend::Postgres {
+            return self.pg_apply_supply_changes(changes).await;
+        }
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let mut latest = HashMap::new();
+        {
+            let mut stmt = tx.prepare_cached(
+                "SELECT token, block_number, total_minted, total_burned
+                 FROM collection_supply WHERE token = ?1",
+            )?;
+            for change in changes {
+                let mut rows = stmt.query(params![felt_to_blob(change.token)])?;
+                if let Some(row) = rows.next()? {
+                    latest.insert(change.token, sqlite_supply_row(row)?);
+                }
+            }
+        }
+        let supplies = fold_supply_changes(&mut latest, changes);
+        {
+            let mut stmt = tx.prepare_cached(
+                "INSERT INTO collection_supply
+                     (token, block_number, total_minted, total_burned, supply)
+                 VALUES (?1, ?2, ?3, ?4, ?5)
+                 ON CONFLICT(token) DO UPDATE SET
+                     block_number = excluded.block_number,
+                     total_minted = excluded.total_minted,
+                     total_burned = excluded.total_burned,
+                     supply = excluded.supply",
+            )?;
+            for supply in &supplies {
+                stmt.execute(params![
+                    felt_to_blob(supply.token),
+                    supply.block_number as i64,
+                    supply.total_minted as i64,
+                    supply.total_burned as i64,
+                    supply.supply() as i64,
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(supplies)
+    }
+
+    /// Supply of a collection, if any mint or burn was indexed
+    pub async fn get_collection_supply(&self, token: Felt) -> Result<Option<CollectionSupply>> {
+        if self.backend == StorageBackend::Postgres {
+            return self.pg_get_collection_supply(token).await;
+        }
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare_cached(
+            "SELECT token, block_number, total_minted, total_burned
+             FROM collection_supply WHERE token = ?1",
+        )?;
+        let mut rows = stmt.query(params![felt_to_blob(token)])?;
+        Ok(rows.next()?.map(sqlite_supply_row).transpose()?)
+    }
+
     // ===== Token Metadata Methods =====
 
     /// Check if metadata exists for a token
@@ -1259,33 +1697,78 @@ impl Erc721Storage {
         if self.backend == StorageBackend::Postgres {
             return self.pg_get_token_uris_by_contract(token).await;
         }
-        let conn = self.conn.lock().unwrap();
-        let token_blob = felt_to_blob(token);
-        let mut stmt = conn.prepare_cached(
-            "SELECT token_id, uri, metadata_json
-             FROM token_uris
-             WHERE token = ?1
-             ORDER BY token_id ASC",
-        )?;
-        let rows = stmt.query_map(params![&token_blob], |row| {
-            let token_id_bytes: Vec<u8> = row.get(0)?;
-            let uri: Option<String> = row.get(1)?;
-            let metadata_json: Option<String> = row.get(2)?;
-            Ok((blob_to_u256(&token_id_bytes), uri, metadata_json))
-        })?;
-        rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+        let conn = self.conn.lock().unwrap();
+        let token_blob = felt_to_blob(token);
+        let mut stmt = conn.prepare_cached(
+            "SELECT token_id, uri, metadata_json
+             FROM token_uris
+             WHERE token = ?1
+             ORDER BY token_id ASC",
+        )?;
+        let rows = stmt.query_map(params![&token_blob], |row| {
+            let token_id_bytes: Vec<u8> = row.get(0)?;
+            let uri: Option<String> = row.get(1)?;
+            let metadata_json: Option<String> = row.get(2)?;
+            Ok((blob_to_u256(&token_id_bytes), uri, metadata_json))
+        })?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+    }
+
+    pub async fn get_token_uris_batch(
+        &self,
+        token: Felt,
+        token_ids: &[U256],
+    ) -> Result<Vec<(U256, Option<String>, Option<String>)>> {
+        if token_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        if self.backend == StorageBackend::Postgres {
+            return self.pg_get_token_uris_batch(token, token_ids).await;
+        }
+
+        let conn = self.conn.lock().unwrap();
+        let token_blob = felt_to_blob(token);
+        let mut rows_out = Vec::new();
+        for chunk in token_ids.chunks(SQLITE_TOKEN_BATCH_SIZE) {
+            let placeholders = chunk.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+            let query = format!(
+                "SELECT token_id, uri, metadata_json
+                 FROM token_uris
+                 WHERE token = ?1 AND token_id IN ({placeholders})"
+            );
+            let mut params_vec: Vec<Box<dyn ToSql>> = Vec::with_capacity(chunk.len() + 1);
+            params_vec.push(Box::new(token_blob.clone()));
+            for token_id in chunk {
+                params_vec.push(Box::new(u256_to_blob(*token_id)));
+            }
+            let params_refs: Vec<&dyn ToSql> =
+                params_vec.iter().map(std::convert::AsRef::as_ref).collect();
+            let mut stmt = conn.prepare_cached(&query)?;
+            let rows = stmt.query_map(params_refs.as_slice(), |row| {
+                let token_id_bytes: Vec<u8> = row.get(0)?;
+                let uri: Option<String> = row.get(1)?;
+                let metadata_json: Option<String> = row.get(2)?;
+                Ok((blob_to_u256(&token_id_bytes), uri, metadata_json))
+            })?;
+            rows_out.extend(rows.collect::<std::result::Result<Vec<_>, _>>()?);
+        }
+
+        Ok(rows_out)
     }
 
-    pub async fn get_token_uris_batch(
+    /// Returns the normalized display fields (name, description, image) of tokens.
+    pub async fn get_token_metadata_fields_batch(
         &self,
         token: Felt,
         token_ids: &[U256],
-    ) -> Result<Vec<(U256, Option<String>, Option<String>)>> {
+    ) -> Result<Vec<(U256, NormalizedMetadata)>> {
         if token_ids.is_empty() {
             return Ok(Vec::new());
         }
         if self.backend == StorageBackend::Postgres {
-            return self.pg_get_token_uris_batch(token, token_ids).await;
+            return self
+                .pg_get_token_metadata_fields_batch(token, token_ids)
+                .await;
         }
 
         let conn = self.conn.lock().unwrap();
@@ -1294,7 +1777,7 @@ impl Erc721Storage {
         for chunk in token_ids.chunks(SQLITE_TOKEN_BATCH_SIZE) {
             let placeholders = chunk.iter().map(|_| "?").collect::<Vec<_>>().join(",");
             let query = format!(
-                "SELECT token_id, uri, metadata_json
+                "SELECT token_id, name, description, image
                  FROM token_uris
                  WHERE token = ?1 AND token_id IN ({placeholders})"
             );
@@ -1308,9 +1791,14 @@ impl Erc721Storage {
             let mut stmt = conn.prepare_cached(&query)?;
             let rows = stmt.query_map(params_refs.as_slice(), |row| {
                 let token_id_bytes: Vec<u8> = row.get(0)?;
-                let uri: Option<String> = row.get(1)?;
-                let metadata_json: Option<String> = row.get(2)?;
-                Ok((blob_to_u256(&token_id_bytes), uri, metadata_json))
+                Ok((
+                    blob_to_u256(&token_id_bytes),
+                    NormalizedMetadata {
+                        name: row.get(1)?,
+                        description: row.get(2)?,
+                        image: row.get(3)?,
+                    },
+                ))
             })?;
             rows_out.extend(rows.collect::<std::result::Result<Vec<_>, _>>()?);
         }
@@ -1639,6 +2127,7 @@ impl Erc721Storage {
         let mut block_vec: Vec<String> = Vec::with_capacity(transfers.len());
         let mut tx_hash_vec = Vec::with_capacity(transfers.len());
         let mut ts_vec: Vec<String> = Vec::with_capacity(transfers.len());
+        let mut event_index_vec: Vec<Option<i64>> = Vec::with_capacity(transfers.len());
 
         for transfer in transfers {
             token_vec.push(felt_to_blob(transfer.token));
@@ -1653,14 +2142,15 @@ impl Erc721Storage {
                     .unwrap_or_else(|| chrono::Utc::now().timestamp())
                     .to_string(),
             );
+            event_index_vec.push(transfer.event_index.map(i64::from));
         }
 
         let client = self.pg_client().await?;
         let row = client
             .query_one(
                 "WITH inserted AS (
-                    INSERT INTO erc721.nft_transfers (token, token_id, from_addr, to_addr, block_number, tx_hash, timestamp)
-                    SELECT i.token, i.token_id, i.from_addr, i.to_addr, i.block_number, i.tx_hash, i.timestamp
+                    INSERT INTO erc721.nft_transfers (token, token_id, from_addr, to_addr, block_number, tx_hash, timestamp, event_index)
+                    SELECT i.token, i.token_id, i.from_addr, i.to_addr, i.block_number, i.tx_hash, i.timestamp, i.event_index
                     FROM unnest(
                         $1::bytea[],
                         $2::bytea[],
@@ -1668,8 +2158,9 @@ impl Erc721Storage {
                         $4::bytea[],
                         $5::text[],
                         $6::bytea[],
-                        $7::text[]
-                    ) AS i(token, token_id, from_addr, to_addr, block_number, tx_hash, timestamp)
+                        $7::text[],
+                        $9::bigint[]
+                    ) AS i(token, token_id, from_addr, to_addr, block_number, tx_hash, timestamp, event_index)
                     ON CONFLICT (token, tx_hash, token_id, from_addr, to_addr) DO NOTHING
                     RETURNING id, token, token_id, from_addr, to_addr, block_number, tx_hash, timestamp
                 ),
@@ -1685,6 +2176,13 @@ impl Erc721Storage {
                         tx_hash = EXCLUDED.tx_hash,
                         timestamp = EXCLUDED.timestamp
                 ),
+                _history AS (
+                    INSERT INTO erc721.ownership_history (transfer_id, token, token_id, previous_owner, owner, block_number, tx_hash, timestamp)
+                    SELECT id, token, token_id, from_addr, to_addr, block_number::BIGINT, tx_hash, timestamp
+                    FROM inserted
+                    WHERE from_addr <> to_addr
+                    ON CONFLICT (transfer_id) DO NOTHING
+                ),
                 _activity AS (
                     INSERT INTO erc721.nft_wallet_activity (wallet_address, token, transfer_id, direction, block_number)
                     SELECT from_addr, token, id, 'both', block_number
@@ -1709,6 +2207,7 @@ impl Erc721Storage {
                     &tx_hash_vec,
                     &ts_vec,
                     &zero_blob,
+                    &event_index_vec,
                 ],
             )
             .await?;
@@ -1780,6 +2279,100 @@ impl Erc721Storage {
         Ok(approvals.len())
     }
 
+    async fn pg_apply_token_approval_changes(
+        &self,
+        changes: &[TokenApprovalChange],
+    ) -> Result<usize> {
+        if changes.is_empty() {
+            return Ok(0);
+        }
+
+        let mut client = self.pg_client().await?;
+        let tx = client.transaction().await?;
+        let history_stmt = tx
+            .prepare(
+                "INSERT INTO erc721.nft_approvals (token, token_id, owner, approved, block_number, tx_hash, timestamp)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7)",
+            )
+            .await?;
+        let upsert_stmt = tx
+            .prepare(
+                "INSERT INTO erc721.nft_token_approvals (token, token_id, owner, approved, block_number, tx_hash, timestamp)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7)
+                 ON CONFLICT (token, token_id) DO UPDATE SET
+                    owner = EXCLUDED.owner,
+                    approved = EXCLUDED.approved,
+                    block_number = EXCLUDED.block_number,
+                    tx_hash = EXCLUDED.tx_hash,
+                    timestamp = EXCLUDED.timestamp",
+            )
+            .await?;
+        let clear_stmt = tx
+            .prepare(
+                "DELETE FROM erc721.nft_token_approvals a
+                 USING unnest($1::bytea[], $2::bytea[]) AS c(token, token_id)
+                 WHERE a.token = c.token AND a.token_id = c.token_id",
+            )
+            .await?;
+
+        let mut inserted = 0;
+        // Consecutive transfers are cleared with one statement; order is kept across runs.
+        let mut clear_tokens: Vec<Vec<u8>> = Vec::new();
+        let mut clear_token_ids: Vec<Vec<u8>> = Vec::new();
+        for change in changes {
+            match change {
+                TokenApprovalChange::Transfer { token, token_id } => {
+                    clear_tokens.push(felt_to_blob(*token));
+                    clear_token_ids.push(u256_to_blob(*token_id));
+                }
+                TokenApprovalChange::Approval(approval) => {
+                    if !clear_tokens.is_empty() {
+                        tx.execute(&clear_stmt, &[&clear_tokens, &clear_token_ids])
+                            .await?;
+                        clear_tokens.clear();
+                        clear_token_ids.clear();
+                    }
+
+                    let token_blob = felt_to_blob(approval.token);
+                    let token_id_blob = u256_to_blob(approval.token_id);
+                    let owner_blob = felt_to_blob(approval.owner);
+                    let approved_blob = felt_to_blob(approval.approved);
+                    let tx_hash_blob = felt_to_blob(approval.tx_hash);
+                    let block_number = approval.block_number.to_string();
+                    let timestamp = approval
+                        .timestamp
+                        .unwrap_or_else(|| chrono::Utc::now().timestamp())
+                        .to_string();
+                    let row: [&(dyn PgToSql + Sync); 7] = [
+                        &token_blob,
+                        &token_id_blob,
+                        &owner_blob,
+                        &approved_blob,
+                        &block_number,
+                        &tx_hash_blob,
+                        &timestamp,
+                    ];
+
+                    tx.execute(&history_stmt, &row).await?;
+                    if approval.approved == Felt::ZERO {
+                        tx.execute(&clear_stmt, &[&vec![token_blob], &vec![token_id_blob]])
+                            .await?;
+                    } else {
+                        tx.execute(&upsert_stmt, &row).await?;
+                    }
+                    inserted += 1;
+                }
+            }
+        }
+        if !clear_tokens.is_empty() {
+            tx.execute(&clear_stmt, &[&clear_tokens, &clear_token_ids])
+                .await?;
+        }
+
+        tx.commit().await?;
+        Ok(inserted)
+    }
+
     #[allow(clippy::too_many_arguments)]
     async fn pg_get_transfers_filtered(
         &self,
@@ -1792,18 +2385,20 @@ impl Erc721Storage {
         block_to: Option<u64>,
         cursor: Option<TransferCursor>,
         limit: u32,
+        mask: &FieldMask,
     ) -> Result<(Vec<NftTransferData>, Option<TransferCursor>)> {
         let client = self.pg_client().await?;
+        let columns = transfer_columns(mask, "''::bytea", "NULL::text");
         let mut query = String::new();
         let mut params: Vec<Box<dyn PgToSql + Sync + Send>> = Vec::new();
 
         if let Some(wallet_addr) = wallet {
-            query.push_str(
-                "SELECT DISTINCT t.id, t.token, t.token_id, t.from_addr, t.to_addr, t.block_number, t.tx_hash, t.timestamp
+            query.push_str(&format!(
+                "SELECT DISTINCT {columns}
                  FROM erc721.nft_wallet_activity wa
                  JOIN erc721.nft_transfers t ON wa.transfer_id = t.id
-                 WHERE wa.wallet_address = ",
-            );
+                 WHERE wa.wallet_address = "
+            ));
             query.push_str(&Self::pg_next_param(&mut params, felt_to_blob(wallet_addr)));
             if !tokens.is_empty() {
                 let list = tokens
@@ -1814,11 +2409,11 @@ impl Erc721Storage {
                 query.push_str(&format!(" AND wa.token IN ({list})"));
             }
         } else {
-            query.push_str(
-                "SELECT t.id, t.token, t.token_id, t.from_addr, t.to_addr, t.block_number, t.tx_hash, t.timestamp
+            query.push_str(&format!(
+                "SELECT {columns}
                  FROM erc721.nft_transfers t
-                 WHERE 1=1",
-            );
+                 WHERE 1=1"
+            ));
             if let Some(from_addr) = from {
                 query.push_str(" AND t.from_addr = ");
                 query.push_str(&Self::pg_next_param(&mut params, felt_to_blob(from_addr)));
@@ -1880,6 +2475,7 @@ impl Erc721Storage {
                 block_number: row.get::<usize, String>(5).parse::<u64>().unwrap_or(0),
                 tx_hash: blob_to_felt(&row.get::<usize, Vec<u8>>(6)),
                 timestamp: row.get::<usize, String>(7).parse::<i64>().ok(),
+                event_index: None,
             })
             .collect();
         let next_cursor = if transfers.len() == limit as usize {
@@ -1887,78 +2483,354 @@ impl Erc721Storage {
                 block_number: t.block_number,
                 id: t.id.unwrap_or_default(),
             })
-        } else {
-            None
-        };
-        Ok((transfers, next_cursor))
-    }
-
-    async fn pg_get_owner(&self, token: Felt, token_id: U256) -> Result<Option<Felt>> {
-        let client = self.pg_client().await?;
-        let row = client
-            .query_opt(
-                "SELECT owner FROM erc721.nft_ownership WHERE token = $1 AND token_id = $2",
-                &[&felt_to_blob(token), &u256_to_blob(token_id)],
-            )
-            .await?;
-        Ok(row.map(|r| blob_to_felt(&r.get::<usize, Vec<u8>>(0))))
+        } else {
+            None
+        };
+        Ok((transfers, next_cursor))
+    }
+
+    async fn pg_get_transfers_for_replay(
+        &self,
+        range: &ReplayRange,
+        after_id: Option<i64>,
+    ) -> Result<Vec<NftTransferData>> {
+        let client = self.pg_client().await?;
+        let mut params: Vec<Box<dyn PgToSql + Sync + Send>> = Vec::new();
+        let mut query = String::from(
+            "SELECT t.id, t.token, t.token_id, t.from_addr, t.to_addr, t.block_number, t.tx_hash, t.timestamp
+             FROM erc721.nft_transfers t",
+        );
+        query.push_str(&range.page_clause(after_id, |param| match param {
+            ReplayParam::Integer(value) => Self::pg_next_param(&mut params, value),
+            ReplayParam::Blob(value) => Self::pg_next_param(&mut params, value),
+        }));
+
+        let refs: Vec<&(dyn PgToSql + Sync)> = params
+            .iter()
+            .map(|p| p.as_ref() as &(dyn PgToSql + Sync))
+            .collect();
+        let rows = client.query(&query, &refs).await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| NftTransferData {
+                id: Some(row.get::<usize, i64>(0)),
+                token: blob_to_felt(&row.get::<usize, Vec<u8>>(1)),
+                token_id: blob_to_u256(&row.get::<usize, Vec<u8>>(2)),
+                from: blob_to_felt(&row.get::<usize, Vec<u8>>(3)),
+                to: blob_to_felt(&row.get::<usize, Vec<u8>>(4)),
+                block_number: row.get::<usize, String>(5).parse::<u64>().unwrap_or(0),
+                tx_hash: blob_to_felt(&row.get::<usize, Vec<u8>>(6)),
+                timestamp: row.get::<usize, String>(7).parse::<i64>().ok(),
+                event_index: None,
+            })
+            .collect())
+    }
+
+    async fn pg_get_owner(&self, token: Felt, token_id: U256) -> Result<Option<Felt>> {
+        let client = self.pg_client().await?;
+        let row = client
+            .query_opt(
+                "SELECT owner FROM erc721.nft_ownership WHERE token = $1 AND token_id = $2",
+                &[&felt_to_blob(token), &u256_to_blob(token_id)],
+            )
+            .await?;
+        Ok(row.map(|r| blob_to_felt(&r.get::<usize, Vec<u8>>(0))))
+    }
+
+    async fn pg_get_owner_at_block(
+        &self,
+        token: Felt,
+        token_id: U256,
+        block_number: u64,
+    ) -> Result<Option<OwnershipChangeData>> {
+        let block_number = i64::try_from(block_number).unwrap_or(i64::MAX);
+        let client = self.pg_client().await?;
+        let row = client
+            .query_opt(
+                "SELECT id, token, token_id, from_addr, to_addr, block_number, tx_hash, timestamp
+                 FROM erc721.nft_transfers
+                 WHERE token = $1 AND token_id = $2 AND block_number::BIGINT <= $3
+                 ORDER BY block_number::BIGINT DESC, id DESC
+                 LIMIT 1",
+                &[&felt_to_blob(token), &u256_to_blob(token_id), &block_number],
+            )
+            .await?;
+
+        Ok(row.map(|row| OwnershipChangeData {
+            id: Some(row.get::<usize, i64>(0)),
+            token: blob_to_felt(&row.get::<usize, Vec<u8>>(1)),
+            token_id: blob_to_u256(&row.get::<usize, Vec<u8>>(2)),
+            previous_owner: blob_to_felt(&row.get::<usize, Vec<u8>>(3)),
+            owner: blob_to_felt(&row.get::<usize, Vec<u8>>(4)),
+            block_number: row.get::<usize, String>(5).parse::<u64>().unwrap_or(0),
+            tx_hash: blob_to_felt(&row.get::<usize, Vec<u8>>(6)),
+            timestamp: row
+                .get::<usize, Option<String>>(7)
+                .and_then(|s| s.parse::<i64>().ok()),
+        }))
+    }
+
+    async fn pg_get_ownership_history(
+        &self,
+        token: Felt,
+        token_id: U256,
+        cursor: Option<OwnershipCursor>,
+        limit: u32,
+    ) -> Result<(Vec<OwnershipChangeData>, Option<OwnershipCursor>)> {
+        let (cursor_block, cursor_id) = cursor.map_or((i64::MAX, i64::MAX), |c| {
+            (i64::try_from(c.block_number).unwrap_or(i64::MAX), c.id)
+        });
+        let client = self.pg_client().await?;
+        let rows = client
+            .query(
+                "SELECT id, token, token_id, previous_owner, owner, block_number, tx_hash, timestamp
+                 FROM erc721.ownership_history
+                 WHERE token = $1 AND token_id = $2
+                   AND (block_number < $3 OR (block_number = $3 AND id < $4))
+                 ORDER BY block_number DESC, id DESC
+                 LIMIT $5",
+                &[
+                    &felt_to_blob(token),
+                    &u256_to_blob(token_id),
+                    &cursor_block,
+                    &cursor_id,
+                    &i64::from(limit),
+                ],
+            )
+            .await?;
+
+        let changes: Vec<OwnershipChangeData> = rows
+            .into_iter()
+            .map(|row| OwnershipChangeData {
+                id: Some(row.get::<usize, i64>(0)),
+                token: blob_to_felt(&row.get::<usize, Vec<u8>>(1)),
+                token_id: blob_to_u256(&row.get::<usize, Vec<u8>>(2)),
+                previous_owner: blob_to_felt(&row.get::<usize, Vec<u8>>(3)),
+                owner: blob_to_felt(&row.get::<usize, Vec<u8>>(4)),
+                block_number: row.get::<usize, i64>(5) as u64,
+                tx_hash: blob_to_felt(&row.get::<usize, Vec<u8>>(6)),
+                timestamp: row
+                    .get::<usize, Option<String>>(7)
+                    .and_then(|s| s.parse::<i64>().ok()),
+            })
+            .collect();
+
+        let next_cursor = if changes.len() == limit as usize {
+            changes.last().map(|c| OwnershipCursor {
+                block_number: c.block_number,
+                id: c.id.unwrap(),
+            })
+        } else {
+            None
+        };
+
+        Ok((changes, next_cursor))
+    }
+
+    async fn pg_get_ownership_by_owner(
+        &self,
+        owner: Felt,
+        tokens: &[Felt],
+        cursor: Option<OwnershipCursor>,
+        limit: u32,
+        mask: &FieldMask,
+    ) -> Result<(Vec<NftOwnershipData>, Option<OwnershipCursor>)> {
+        let client = self.pg_client().await?;
+        let mut query = format!(
+            "SELECT {} FROM erc721.nft_ownership WHERE owner = ",
+            ownership_columns(mask, "''::bytea")
+        );
+        let mut params: Vec<Box<dyn PgToSql + Sync + Send>> = Vec::new();
+        query.push_str(&Self::pg_next_param(&mut params, felt_to_blob(owner)));
+        if !tokens.is_empty() {
+            let list = tokens
+                .iter()
+                .map(|token| Self::pg_next_param(&mut params, felt_to_blob(*token)))
+                .collect::<Vec<_>>()
+                .join(",");
+            query.push_str(&format!(" AND token IN ({list})"));
+        }
+        if let Some(c) = cursor {
+            let p1 = Self::pg_next_param(&mut params, c.block_number.to_string());
+            let p2 = Self::pg_next_param(&mut params, c.block_number.to_string());
+            let p3 = Self::pg_next_param(&mut params, c.id);
+            query.push_str(&format!(
+                " AND (block_number < {p1} OR (block_number = {p2} AND id < {p3}))"
+            ));
+        }
+        query.push_str(" ORDER BY block_number DESC, id DESC LIMIT ");
+        query.push_str(&Self::pg_next_param(&mut params, limit as i64));
+        let refs: Vec<&(dyn PgToSql + Sync)> = params
+            .iter()
+            .map(|p| p.as_ref() as &(dyn PgToSql + Sync))
+            .collect();
+        let rows = client.query(&query, &refs).await?;
+        let ownership: Vec<NftOwnershipData> = rows
+            .into_iter()
+            .map(|row| NftOwnershipData {
+                id: Some(row.get::<usize, i64>(0)),
+                token: blob_to_felt(&row.get::<usize, Vec<u8>>(1)),
+                token_id: blob_to_u256(&row.get::<usize, Vec<u8>>(2)),
+                owner: blob_to_felt(&row.get::<usize, Vec<u8>>(3)),
+                block_number: row.get::<usize, String>(4).parse::<u64>().unwrap_or(0),
+            })
+            .collect();
+        let next_cursor = if ownership.len() == limit as usize {
+            ownership.last().map(|o| OwnershipCursor {
+                block_number: o.block_number,
+                id: o.id.unwrap_or_default(),
+            })
+        } else {
+            None
+        };
+        Ok((ownership, next_cursor))
+    }
+
+    async fn pg_get_tokens_by_owner(
+        &self,
+        owner: Felt,
+        cursor: Option<OwnedTokenCursor>,
+        limit: u32,
+    ) -> Result<(Vec<NftOwnershipData>, Option<OwnedTokenCursor>)> {
+        let client = self.pg_client().await?;
+        let mut query = String::from(
+            "SELECT id, token, token_id, owner, block_number FROM erc721.nft_ownership WHERE owner = $1",
+        );
+        let mut params: Vec<Box<dyn PgToSql + Sync + Send>> = vec![Box::new(felt_to_blob(owner))];
+        if let Some(c) = cursor {
+            let token_id = u256_to_blob(c.token_id);
+            let token_param = Self::pg_next_param(&mut params, felt_to_blob(c.token));
+            let len_param = Self::pg_next_param(&mut params, token_id.len() as i32);
+            let token_id_param = Self::pg_next_param(&mut params, token_id);
+            query.push_str(&format!(
+                " AND (token > {token_param} OR (token = {token_param} AND \
+                 (octet_length(token_id) > {len_param} \
+                 OR (octet_length(token_id) = {len_param} AND token_id > {token_id_param}))))"
+            ));
+        }
+        query.push_str(" ORDER BY token ASC, octet_length(token_id) ASC, token_id ASC LIMIT ");
+        query.push_str(&Self::pg_next_param(&mut params, i64::from(limit) + 1));
+        let refs: Vec<&(dyn PgToSql + Sync)> = params
+            .iter()
+            .map(|p| p.as_ref() as &(dyn PgToSql + Sync))
+            .collect();
+        let rows = client.query(&query, &refs).await?;
+        let ownership: Vec<NftOwnershipData> = rows
+            .into_iter()
+            .map(|row| NftOwnershipData {
+                id: Some(row.get::<usize, i64>(0)),
+                token: blob_to_felt(&row.get::<usize, Vec<u8>>(1)),
+                token_id: blob_to_u256(&row.get::<usize, Vec<u8>>(2)),
+                owner: blob_to_felt(&row.get::<usize, Vec<u8>>(3)),
+                block_number: row.get::<usize, String>(4).parse::<u64>().unwrap_or(0),
+            })
+            .collect();
+        Ok(split_page(ownership, limit as usize, |o| {
+            OwnedTokenCursor {
+                token: o.token,
+                token_id: o.token_id,
+            }
+        }))
+    }
+
+    async fn pg_get_token_approvals(
+        &self,
+        owner: Felt,
+        cursor: Option<OwnedTokenCursor>,
+        limit: u32,
+    ) -> Result<(Vec<NftApprovalData>, Option<OwnedTokenCursor>)> {
+        let client = self.pg_client().await?;
+        let mut query = String::from(
+            "SELECT token, token_id, owner, approved, block_number, tx_hash, timestamp
+             FROM erc721.nft_token_approvals WHERE owner = $1",
+        );
+        let mut params: Vec<Box<dyn PgToSql + Sync + Send>> = vec![Box::new(felt_to_blob(owner))];
+        if let Some(c) = cursor {
+            let token_id = u256_to_blob(c.token_id);
+            let token_param = Self::pg_next_param(&mut params, felt_to_blob(c.token));
+            let len_param = Self::pg_next_param(&mut params, token_id.len() as i32);
+            let token_id_param = Self::pg_next_param(&mut params, token_id);
+            query.push_str(&format!(
+                " AND (token > {token_param} OR (token = {token_param} AND \
+                 (octet_length(token_id) > {len_param} \
+                 OR (octet_length(token_id) = {len_param} AND token_id > {token_id_param}))))"
+            ));
+        }
+        query.push_str(" ORDER BY token ASC, octet_length(token_id) ASC, token_id ASC LIMIT ");
+        query.push_str(&Self::pg_next_param(&mut params, i64::from(limit) + 1));
+        let refs: Vec<&(dyn PgToSql + Sync)> = params
+            .iter()
+            .map(|p| p.as_ref() as &(dyn PgToSql + Sync))
+            .collect();
+        let rows = client.query(&query, &refs).await?;
+        let approvals: Vec<NftApprovalData> = rows
+            .into_iter()
+            .map(|row| NftApprovalData {
+                id: None,
+                token: blob_to_felt(&row.get::<usize, Vec<u8>>(0)),
+                token_id: blob_to_u256(&row.get::<usize, Vec<u8>>(1)),
+                owner: blob_to_felt(&row.get::<usize, Vec<u8>>(2)),
+                approved: blob_to_felt(&row.get::<usize, Vec<u8>>(3)),
+                block_number: row.get::<usize, String>(4).parse::<u64>().unwrap_or(0),
+                tx_hash: blob_to_felt(&row.get::<usize, Vec<u8>>(5)),
+                timestamp: row
+                    .get::<usize, Option<String>>(6)
+                    .and_then(|s| s.parse::<i64>().ok()),
+            })
+            .collect();
+        Ok(split_page(approvals, limit as usize, |a| {
+            OwnedTokenCursor {
+                token: a.token,
+                token_id: a.token_id,
+            }
+        }))
     }
 
-    async fn pg_get_ownership_by_owner(
+    async fn pg_get_approved_operators(
         &self,
         owner: Felt,
-        tokens: &[Felt],
-        cursor: Option<OwnershipCursor>,
+        cursor: Option<OperatorCursor>,
         limit: u32,
-    ) -> Result<(Vec<NftOwnershipData>, Option<OwnershipCursor>)> {
+    ) -> Result<(Vec<OperatorApprovalData>, Option<OperatorCursor>)> {
         let client = self.pg_client().await?;
         let mut query = String::from(
-            "SELECT id, token, token_id, owner, block_number FROM erc721.nft_ownership WHERE owner = ",
+            "SELECT id, token, owner, operator, block_number, tx_hash, timestamp
+             FROM erc721.nft_operators WHERE owner = $1 AND approved = '1'",
         );
-        let mut params: Vec<Box<dyn PgToSql + Sync + Send>> = Vec::new();
-        query.push_str(&Self::pg_next_param(&mut params, felt_to_blob(owner)));
-        if !tokens.is_empty() {
-            let list = tokens
-                .iter()
-                .map(|token| Self::pg_next_param(&mut params, felt_to_blob(*token)))
-                .collect::<Vec<_>>()
-                .join(",");
-            query.push_str(&format!(" AND token IN ({list})"));
-        }
+        let mut params: Vec<Box<dyn PgToSql + Sync + Send>> = vec![Box::new(felt_to_blob(owner))];
         if let Some(c) = cursor {
-            let p1 = Self::pg_next_param(&mut params, c.block_number.to_string());
-            let p2 = Self::pg_next_param(&mut params, c.block_number.to_string());
-            let p3 = Self::pg_next_param(&mut params, c.id);
+            let token_param = Self::pg_next_param(&mut params, felt_to_blob(c.token));
+            let operator_param = Self::pg_next_param(&mut params, felt_to_blob(c.operator));
             query.push_str(&format!(
-                " AND (block_number < {p1} OR (block_number = {p2} AND id < {p3}))"
+                " AND (token > {token_param} OR (token = {token_param} AND operator > {operator_param}))"
             ));
         }
-        query.push_str(" ORDER BY block_number DESC, id DESC LIMIT ");
-        query.push_str(&Self::pg_next_param(&mut params, limit as i64));
+        query.push_str(" ORDER BY token ASC, operator ASC LIMIT ");
+        query.push_str(&Self::pg_next_param(&mut params, i64::from(limit) + 1));
         let refs: Vec<&(dyn PgToSql + Sync)> = params
             .iter()
             .map(|p| p.as_ref() as &(dyn PgToSql + Sync))
             .collect();
         let rows = client.query(&query, &refs).await?;
-        let ownership: Vec<NftOwnershipData> = rows
+        let operators: Vec<OperatorApprovalData> = rows
             .into_iter()
-            .map(|row| NftOwnershipData {
+            .map(|row| OperatorApprovalData {
                 id: Some(row.get::<usize, i64>(0)),
                 token: blob_to_felt(&row.get::<usize, Vec<u8>>(1)),
-                token_id: blob_to_u256(&row.get::<usize, Vec<u8>>(2)),
-                owner: blob_to_felt(&row.get::<usize, Vec<u8>>(3)),
+                owner: blob_to_felt(&row.get::<usize, Vec<u8>>(2)),
+                operator: blob_to_felt(&row.get::<usize, Vec<u8>>(3)),
+                approved: true,
                 block_number: row.get::<usize, String>(4).parse::<u64>().unwrap_or(0),
+                tx_hash: blob_to_felt(&row.get::<usize, Vec<u8>>(5)),
+                timestamp: row
+                    .get::<usize, Option<String>>(6)
+                    .and_then(|s| s.parse::<i64>().ok()),
             })
             .collect();
-        let next_cursor = if ownership.len() == limit as usize {
-            ownership.last().map(|o| OwnershipCursor {
-                block_number: o.block_number,
-                id: o.id.unwrap_or_default(),
-            })
-        } else {
-            None
-        };
-        Ok((ownership, next_cursor))
+        Ok(split_page(operators, limit as usize, |o| OperatorCursor {
+            token: o.token,
+            operator: o.operator,
+        }))
     }
 
     async fn pg_query_token_ids_by_facets(
@@ -2096,6 +2968,72 @@ impl Erc721Storage {
         Ok(v.and_then(|x| x.parse::<u64>().ok()))
     }
 
+    async fn pg_apply_supply_changes(
+        &self,
+        changes: &[SupplyChange],
+    ) -> Result<Vec<CollectionSupply>> {
+        let mut client = self.pg_client().await?;
+        let tx = client.transaction().await?;
+        let tokens = changes
+            .iter()
+            .map(|c| felt_to_blob(c.token))
+            .collect::<Vec<_>>();
+        let mut latest = tx
+            .query(
+                "SELECT token, block_number, total_minted, total_burned
+                 FROM erc721.collection_supply WHERE token = ANY($1)",
+                &[&tokens],
+            )
+            .await?
+            .iter()
+            .map(|row| {
+                let supply = pg_supply_row(row);
+                (supply.token, supply)
+            })
+            .collect::<HashMap<_, _>>();
+        let supplies = fold_supply_changes(&mut latest, changes);
+
+        let stmt = tx
+            .prepare(
+                "INSERT INTO erc721.collection_supply
+                     (token, block_number, total_minted, total_burned, supply)
+                 VALUES ($1, $2, $3, $4, $5)
+                 ON CONFLICT (token) DO UPDATE SET
+                     block_number = EXCLUDED.block_number,
+                     total_minted = EXCLUDED.total_minted,
+                     total_burned = EXCLUDED.total_burned,
+                     supply = EXCLUDED.supply",
+            )
+            .await?;
+        for supply in &supplies {
+            tx.execute(
+                &stmt,
+                &[
+                    &felt_to_blob(supply.token),
+                    &(supply.block_number as i64),
+                    &(supply.total_minted as i64),
+                    &(supply.total_burned as i64),
+                    &(supply.supply() as i64),
+                ],
+            )
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(supplies)
+    }
+
+    async fn pg_get_collection_supply(&self, token: Felt) -> Result<Option<CollectionSupply>> {
+        let client = self.pg_client().await?;
+        let row = client
+            .query_opt(
+                "SELECT token, block_number, total_minted, total_burned
+                 FROM erc721.collection_supply WHERE token = $1",
+                &[&felt_to_blob(token)],
+            )
+            .await?;
+        Ok(row.as_ref().map(pg_supply_row))
+    }
+
     async fn pg_has_token_metadata(&self, token: Felt) -> Result<bool> {
         let client = self.pg_client().await?;
         let row = client
@@ -2298,6 +3236,39 @@ impl Erc721Storage {
             .collect())
     }
 
+    async fn pg_get_token_metadata_fields_batch(
+        &self,
+        token: Felt,
+        token_ids: &[U256],
+    ) -> Result<Vec<(U256, NormalizedMetadata)>> {
+        let client = self.pg_client().await?;
+        let token_id_blobs: Vec<Vec<u8>> = token_ids
+            .iter()
+            .map(|token_id| u256_to_blob(*token_id))
+            .collect();
+        let rows = client
+            .query(
+                "SELECT token_id, name, description, image
+                 FROM erc721.token_uris
+                 WHERE token = $1 AND token_id = ANY($2::bytea[])",
+                &[&felt_to_blob(token), &token_id_blobs],
+            )
+            .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                (
+                    blob_to_u256(&row.get::<usize, Vec<u8>>(0)),
+                    NormalizedMetadata {
+                        name: row.get(1),
+                        description: row.get(2),
+                        image: row.get(3),
+                    },
+                )
+            })
+            .collect())
+    }
+
     async fn pg_get_token_uris_batch(
         &self,
         token: Felt,
@@ -2340,6 +3311,16 @@ impl TokenUriStore for Erc721Storage {
             .iter()
             .map(|result| extract_metadata_attributes(result.metadata_json.as_deref()))
             .collect::<Vec<_>>();
+        let expected_fields = results
+            .iter()
+            .map(|result| {
+                result
+                    .metadata_json
+                    .as_deref()
+                    .map(NormalizedMetadata::from_json)
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<_>>();
         let token_blobs = results
             .iter()
             .map(|result| felt_to_blob(result.contract))
@@ -2353,7 +3334,14 @@ impl TokenUriStore for Erc721Storage {
             let mut client = self.pg_client().await?;
             let mut changed_indexes = Vec::new();
             for (idx, result) in results.iter().enumerate() {
-                if !pg_token_uri_state_matches(&client, result, &expected_attributes[idx]).await? {
+                if !pg_token_uri_state_matches(
+                    &client,
+                    result,
+                    &expected_fields[idx],
+                    &expected_attributes[idx],
+                )
+                .await?
+                {
                     changed_indexes.push(idx);
                 }
             }
@@ -2379,21 +3367,29 @@ impl TokenUriStore for Erc721Storage {
             for idx in changed_indexes {
                 let result = &results[idx];
                 let attrs = &expected_attributes[idx];
+                let fields = &expected_fields[idx];
                 let token_blob = &token_blobs[idx];
                 let token_id_blob = &token_id_blobs[idx];
 
                 tx.execute(
-                    "INSERT INTO erc721.token_uris (token, token_id, uri, metadata_json, updated_at)
-                     VALUES ($1, $2, $3, $4, EXTRACT(EPOCH FROM NOW())::BIGINT::TEXT)
+                    "INSERT INTO erc721.token_uris
+                        (token, token_id, uri, metadata_json, name, description, image, updated_at)
+                     VALUES ($1, $2, $3, $4, $5, $6, $7, EXTRACT(EPOCH FROM NOW())::BIGINT::TEXT)
                      ON CONFLICT(token, token_id) DO UPDATE SET
                         uri = EXCLUDED.uri,
                         metadata_json = EXCLUDED.metadata_json,
+                        name = EXCLUDED.name,
+                        description = EXCLUDED.description,
+                        image = EXCLUDED.image,
                         updated_at = EXCLUDED.updated_at",
                     &[
                         &token_blob,
                         &token_id_blob,
                         &result.uri.as_deref(),
                         &result.metadata_json.as_deref(),
+                        &fields.name,
+                        &fields.description,
+                        &fields.image,
                     ],
                 )
                 .await?;
@@ -2432,7 +3428,12 @@ impl TokenUriStore for Erc721Storage {
         let mut conn = self.conn.lock().unwrap();
         let mut changed_indexes = Vec::new();
         for (idx, result) in results.iter().enumerate() {
-            if !sqlite_token_uri_state_matches(&conn, result, &expected_attributes[idx])? {
+            if !sqlite_token_uri_state_matches(
+                &conn,
+                result,
+                &expected_fields[idx],
+                &expected_attributes[idx],
+            )? {
                 changed_indexes.push(idx);
             }
         }
@@ -2456,11 +3457,15 @@ impl TokenUriStore for Erc721Storage {
         let tx = conn.transaction()?;
         {
             let mut upsert_stmt = tx.prepare_cached(
-                "INSERT INTO token_uris (token, token_id, uri, metadata_json, updated_at)
-                 VALUES (?1, ?2, ?3, ?4, strftime('%s', 'now'))
+                "INSERT INTO token_uris
+                    (token, token_id, uri, metadata_json, name, description, image, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, strftime('%s', 'now'))
                  ON CONFLICT(token, token_id) DO UPDATE SET
                     uri = excluded.uri,
                     metadata_json = excluded.metadata_json,
+                    name = excluded.name,
+                    description = excluded.description,
+                    image = excluded.image,
                     updated_at = excluded.updated_at",
             )?;
             let mut delete_attrs_stmt = tx.prepare_cached(
@@ -2474,6 +3479,7 @@ impl TokenUriStore for Erc721Storage {
             for idx in changed_indexes {
                 let result = &results[idx];
                 let attrs = &expected_attributes[idx];
+                let fields = &expected_fields[idx];
                 let token_blob = &token_blobs[idx];
                 let token_id_blob = &token_id_blobs[idx];
 
@@ -2481,7 +3487,10 @@ impl TokenUriStore for Erc721Storage {
                     token_blob,
                     token_id_blob,
                     result.uri.as_deref(),
-                    result.metadata_json.as_deref()
+                    result.metadata_json.as_deref(),
+                    fields.name,
+                    fields.description,
+                    fields.image
                 ])?;
                 delete_attrs_stmt.execute(params![token_blob, token_id_blob])?;
 
@@ -2506,6 +3515,50 @@ impl TokenUriStore for Erc721Storage {
     }
 }
 
+fn sqlite_supply_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<CollectionSupply> {
+    Ok(CollectionSupply {
+        token: blob_to_felt(&row.get::<_, Vec<u8>>(0)?),
+        block_number: row.get::<_, i64>(1)?.max(0) as u64,
+        total_minted: row.get::<_, i64>(2)?.max(0) as u64,
+        total_burned: row.get::<_, i64>(3)?.max(0) as u64,
+    })
+}
+
+/// PostgreSQL counterpart of [`sqlite_supply_row`].
+fn pg_supply_row(row: &tokio_postgres::Row) -> CollectionSupply {
+    CollectionSupply {
+        token: blob_to_felt(&row.get::<_, Vec<u8>>(0)),
+        block_number: row.get::<_, i64>(1).max(0) as u64,
+        total_minted: row.get::<_, i64>(2).max(0) as u64,
+        total_burned: row.get::<_, i64>(3).max(0) as u64,
+    }
+}
+
+/// Select list of a transfer query (id, token, token_id, from, to, block_number, tx_hash,
+/// timestamp), with the columns of fields outside `mask` replaced by placeholders.
+fn transfer_columns(mask: &FieldMask, empty_blob: &str, null: &str) -> String {
+    format!(
+        "t.id, {}, {}, {}, {}, t.block_number, {}, {}",
+        mask.column("token", "t.token", empty_blob),
+        mask.column("token_id", "t.token_id", empty_blob),
+        mask.column("from", "t.from_addr", empty_blob),
+        mask.column("to", "t.to_addr", empty_blob),
+        mask.column("tx_hash", "t.tx_hash", empty_blob),
+        mask.column("timestamp", "t.timestamp", null),
+    )
+}
+
+/// Select list of an ownership query (id, token, token_id, owner, block_number), with the
+/// columns of fields outside `mask` replaced by placeholders.
+fn ownership_columns(mask: &FieldMask, empty_blob: &str) -> String {
+    format!(
+        "id, {}, {}, {}, block_number",
+        mask.column("token", "token", empty_blob),
+        mask.column("token_id", "token_id", empty_blob),
+        mask.column("owner", "owner", empty_blob),
+    )
+}
+
 fn extract_metadata_attributes(metadata_json: Option<&str>) -> Vec<(String, String)> {
     let Some(metadata_json) = metadata_json else {
         return Vec::new();
@@ -2940,21 +3993,28 @@ async fn pg_apply_facet_sync_plan(
 fn sqlite_token_uri_state_matches(
     conn: &Connection,
     result: &TokenUriResult,
+    expected_fields: &NormalizedMetadata,
     expected_attributes: &[(String, String)],
 ) -> Result<bool> {
     let token_blob = felt_to_blob(result.contract);
     let token_id_blob = u256_to_blob(result.token_id);
     let row = conn.query_row(
-        "SELECT uri, metadata_json FROM token_uris WHERE token = ?1 AND token_id = ?2",
+        "SELECT uri, metadata_json, name, description, image
+         FROM token_uris WHERE token = ?1 AND token_id = ?2",
         params![&token_blob, &token_id_blob],
         |row| {
             Ok((
                 row.get::<usize, Option<String>>(0)?,
                 row.get::<usize, Option<String>>(1)?,
+                NormalizedMetadata {
+                    name: row.get(2)?,
+                    description: row.get(3)?,
+                    image: row.get(4)?,
+                },
             ))
         },
     );
-    let (existing_uri, existing_metadata_json) = match row {
+    let (existing_uri, existing_metadata_json, existing_fields) = match row {
         Ok(row) => row,
         Err(rusqlite::Error::QueryReturnedNoRows) => return Ok(false),
         Err(error) => return Err(error.into()),
@@ -2962,6 +4022,7 @@ fn sqlite_token_uri_state_matches(
 
     if existing_uri.as_deref() != result.uri.as_deref()
         || existing_metadata_json.as_deref() != result.metadata_json.as_deref()
+        || &existing_fields != expected_fields
     {
         return Ok(false);
     }
@@ -2982,13 +4043,15 @@ fn sqlite_token_uri_state_matches(
 async fn pg_token_uri_state_matches(
     client: &Client,
     result: &TokenUriResult,
+    expected_fields: &NormalizedMetadata,
     expected_attributes: &[(String, String)],
 ) -> Result<bool> {
     let token_blob = felt_to_blob(result.contract);
     let token_id_blob = u256_to_blob(result.token_id);
     let row = client
         .query_opt(
-            "SELECT uri, metadata_json FROM erc721.token_uris WHERE token = $1 AND token_id = $2",
+            "SELECT uri, metadata_json, name, description, image
+             FROM erc721.token_uris WHERE token = $1 AND token_id = $2",
             &[&token_blob, &token_id_blob],
         )
         .await?;
@@ -2998,8 +4061,14 @@ async fn pg_token_uri_state_matches(
 
     let existing_uri: Option<String> = row.get(0);
     let existing_metadata_json: Option<String> = row.get(1);
+    let existing_fields = NormalizedMetadata {
+        name: row.get(2),
+        description: row.get(3),
+        image: row.get(4),
+    };
     if existing_uri.as_deref() != result.uri.as_deref()
         || existing_metadata_json.as_deref() != result.metadata_json.as_deref()
+        || &existing_fields != expected_fields
     {
         return Ok(false);
     }
@@ -3044,6 +4113,314 @@ mod tests {
             .to_string()
     }
 
+    #[tokio::test]
+    async fn ownership_history_records_owner_changes() {
+        let db_path = temp_db_path("ownership-history");
+        let storage = Erc721Storage::new(&db_path).await.expect("create storage");
+        let token = Felt::from(0x721u64);
+        let transfer = |token_id: u64, from: u64, to: u64, block_number: u64| NftTransferData {
+            id: None,
+            token,
+            token_id: U256::from(token_id),
+            from: Felt::from(from),
+            to: Felt::from(to),
+            block_number,
+            tx_hash: Felt::from(block_number),
+            timestamp: Some(1_700_000_000),
+            event_index: None,
+        };
+        let transfers = [
+            transfer(1, 0, 10, 5),
+            transfer(1, 10, 11, 9),
+            transfer(2, 0, 10, 9),
+            transfer(1, 11, 11, 10),
+            transfer(1, 11, 0, 12),
+        ];
+        storage
+            .insert_transfers_batch(&transfers)
+            .await
+            .expect("insert transfers");
+        // Re-indexing the same transfers does not duplicate history.
+        storage
+            .insert_transfers_batch(&transfers)
+            .await
+            .expect("re-insert transfers");
+
+        let (page, cursor) = storage
+            .get_ownership_history(token, U256::from(1u64), None, 2)
+            .await
+            .expect("first page");
+        let owners: Vec<(u64, Felt, Felt)> = page
+            .iter()
+            .map(|c| (c.block_number, c.previous_owner, c.owner))
+            .collect();
+        assert_eq!(
+            owners,
+            vec![
+                (12, Felt::from(11u64), Felt::ZERO),
+                (9, Felt::from(10u64), Felt::from(11u64)),
+            ]
+        );
+
+        let (page, cursor) = storage
+            .get_ownership_history(token, U256::from(1u64), cursor, 2)
+            .await
+            .expect("second page");
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].previous_owner, Felt::ZERO);
+        assert_eq!(page[0].owner, Felt::from(10u64));
+        assert!(cursor.is_none());
+    }
+
+    #[tokio::test]
+    async fn owner_at_block_follows_transfer_history() {
+        let db_path = temp_db_path("owner-at-block");
+        let storage = Erc721Storage::new(&db_path).await.expect("create storage");
+        let token = Felt::from(0x721u64);
+        let transfer = |from: u64, to: u64, block_number: u64, tx: u64| NftTransferData {
+            id: None,
+            token,
+            token_id: U256::from(1u64),
+            from: Felt::from(from),
+            to: Felt::from(to),
+            block_number,
+            tx_hash: Felt::from(tx),
+            timestamp: None,
+            event_index: None,
+        };
+        storage
+            .insert_transfers_batch(&[
+                transfer(0, 10, 5, 1),
+                // Two transfers in block 9: the later one wins.
+                transfer(10, 11, 9, 2),
+                transfer(11, 12, 9, 3),
+                // Block 100 sorts before 9 as text.
+                transfer(12, 0, 100, 4),
+            ])
+            .await
+            .expect("insert transfers");
+
+        let owner_at = |block_number: u64| {
+            let storage = &storage;
+            async move {
+                storage
+                    .get_owner_at_block(token, U256::from(1u64), block_number)
+                    .await
+                    .expect("owner at block")
+                    .map(|change| (change.owner, change.block_number))
+            }
+        };
+        assert_eq!(owner_at(4).await, None);
+        assert_eq!(owner_at(5).await, Some((Felt::from(10u64), 5)));
+        assert_eq!(owner_at(8).await, Some((Felt::from(10u64), 5)));
+        assert_eq!(owner_at(9).await, Some((Felt::from(12u64), 9)));
+        assert_eq!(owner_at(99).await, Some((Felt::from(12u64), 9)));
+        assert_eq!(owner_at(100).await, Some((Felt::ZERO, 100)));
+        assert_eq!(
+            storage
+                .get_owner_at_block(token, U256::from(2u64), 100)
+                .await
+                .unwrap()
+                .map(|change| change.owner),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn tokens_by_owner_paginates_across_collections() {
+        let db_path = temp_db_path("tokens-by-owner");
+        let storage = Erc721Storage::new(&db_path).await.expect("create storage");
+        let owner = Felt::from(10u64);
+        let mint = |token: u64, token_id: U256, to: Felt, block_number: u64| NftTransferData {
+            id: None,
+            token: Felt::from(token),
+            token_id,
+            from: Felt::ZERO,
+            to,
+            block_number,
+            tx_hash: Felt::from(block_number),
+            timestamp: None,
+            event_index: None,
+        };
+        storage
+            .insert_transfers_batch(&[
+                mint(0x722, U256::from(3u64), owner, 1),
+                mint(0x721, U256::from(256u64), owner, 2),
+                mint(0x721, U256::from(2u64), owner, 3),
+                mint(0x721, U256::from(5u64), Felt::from(11u64), 4),
+                mint(0x722, U256::from(1u128 << 64), owner, 5),
+            ])
+            .await
+            .expect("insert transfers");
+
+        let mut tokens = Vec::new();
+        let mut cursor = None;
+        loop {
+            let (page, next_cursor) = storage
+                .get_tokens_by_owner(owner, cursor, 2)
+                .await
+                .expect("tokens by owner");
+            assert!(page.len() <= 2);
+            tokens.extend(page.iter().map(|o| (o.token, o.token_id)));
+            cursor = next_cursor;
+            if cursor.is_none() {
+                break;
+            }
+        }
+        assert_eq!(
+            tokens,
+            vec![
+                (Felt::from(0x721u64), U256::from(2u64)),
+                (Felt::from(0x721u64), U256::from(256u64)),
+                (Felt::from(0x722u64), U256::from(3u64)),
+                (Felt::from(0x722u64), U256::from(1u128 << 64)),
+            ]
+        );
+
+        let _ = std::fs::remove_file(db_path);
+    }
+
+    #[tokio::test]
+    async fn transfers_by_tx_follow_event_order() {
+        let db_path = temp_db_path("transfers-by-tx");
+        let storage = Erc721Storage::new(&db_path).await.expect("create storage");
+        let tx_hash = Felt::from(0xabcu64);
+        let transfer = |token_id: u64, tx_hash: Felt, event_index: Option<u32>| NftTransferData {
+            id: None,
+            token: Felt::from(0x721u64),
+            token_id: U256::from(token_id),
+            from: Felt::ZERO,
+            to: Felt::from(10u64),
+            block_number: 7,
+            tx_hash,
+            timestamp: None,
+            event_index,
+        };
+        storage
+            .insert_transfers_batch(&[
+                transfer(1, tx_hash, None),
+                transfer(2, tx_hash, Some(4)),
+                transfer(3, Felt::from(0xdefu64), Some(0)),
+                transfer(4, tx_hash, Some(1)),
+            ])
+            .await
+            .expect("insert transfers");
+
+        let transfers = storage
+            .get_transfers_by_tx(tx_hash)
+            .await
+            .expect("transfers by tx");
+        let order: Vec<(U256, Option<u32>)> = transfers
+            .iter()
+            .map(|t| (t.token_id, t.event_index))
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                (U256::from(4u64), Some(1)),
+                (U256::from(2u64), Some(4)),
+                (U256::from(1u64), None),
+            ]
+        );
+
+        let _ = std::fs::remove_file(db_path);
+    }
+
+    #[tokio::test]
+    async fn token_approvals_follow_events_and_transfers() {
+        let db_path = temp_db_path("token-approvals");
+        let storage = Erc721Storage::new(&db_path).await.expect("create storage");
+        let token = Felt::from(0x721u64);
+        let owner = Felt::from(10u64);
+        let approval = |token_id: u64, approved: u64, block_number: u64| {
+            TokenApprovalChange::Approval(NftApprovalData {
+                id: None,
+                token,
+                token_id: U256::from(token_id),
+                owner,
+                approved: Felt::from(approved),
+                block_number,
+                tx_hash: Felt::from(block_number),
+                timestamp: None,
+            })
+        };
+        let inserted = storage
+            .apply_token_approval_changes(&[
+                approval(1, 20, 5),
+                approval(2, 21, 5),
+                approval(3, 22, 6),
+                // Transferred after its approval: cleared
+                TokenApprovalChange::Transfer {
+                    token,
+                    token_id: U256::from(2u64),
+                },
+                // Revoked with the zero address
+                approval(3, 0, 7),
+                // Transferred, then approved by the new owner: kept
+                TokenApprovalChange::Transfer {
+                    token,
+                    token_id: U256::from(4u64),
+                },
+                approval(4, 23, 8),
+            ])
+            .await
+            .expect("apply approvals");
+        assert_eq!(inserted, 5);
+
+        let (approvals, next_cursor) = storage
+            .get_token_approvals(owner, None, 10)
+            .await
+            .expect("token approvals");
+        assert!(next_cursor.is_none());
+        assert_eq!(
+            approvals
+                .iter()
+                .map(|a| (a.token_id, a.approved))
+                .collect::<Vec<_>>(),
+            vec![
+                (U256::from(1u64), Felt::from(20u64)),
+                (U256::from(4u64), Felt::from(23u64)),
+            ]
+        );
+
+        let operator = |operator: u64, approved: bool, block_number: u64| OperatorApprovalData {
+            id: None,
+            token,
+            owner,
+            operator: Felt::from(operator),
+            approved,
+            block_number,
+            tx_hash: Felt::from(block_number),
+            timestamp: None,
+        };
+        storage
+            .insert_operator_approvals_batch(&[
+                operator(30, true, 5),
+                operator(31, true, 5),
+                operator(32, true, 5),
+                operator(31, false, 6),
+            ])
+            .await
+            .expect("insert operator approvals");
+        let (page, next_cursor) = storage
+            .get_approved_operators(owner, None, 1)
+            .await
+            .expect("approved operators");
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].operator, Felt::from(30u64));
+        let (page, next_cursor) = storage
+            .get_approved_operators(owner, next_cursor, 10)
+            .await
+            .expect("approved operators");
+        assert!(next_cursor.is_none());
+        assert_eq!(
+            page.iter().map(|o| o.operator).collect::<Vec<_>>(),
+            vec![Felt::from(32u64)]
+        );
+
+        let _ = std::fs::remove_file(db_path);
+    }
+
     #[tokio::test]
     async fn has_token_metadata_requires_complete_erc721_row() {
         let db_path = temp_db_path("complete-metadata");
@@ -3133,6 +4510,58 @@ mod tests {
         let _ = std::fs::remove_file(db_path);
     }
 
+    #[tokio::test]
+    async fn store_token_uri_normalizes_metadata_fields() {
+        let db_path = temp_db_path("token-uri-fields");
+        let storage = Erc721Storage::new(&db_path).await.expect("create storage");
+        let contract = Felt::from_hex_unchecked("0x123");
+        let result = TokenUriResult {
+            contract,
+            token_id: U256::from(7u64),
+            uri: Some("data:application/json;base64,...".to_owned()),
+            metadata_json: Some(
+                r#"{"name":"Beast #7","description":"A bear","properties":{"image":"ipfs://img/7"}}"#
+                    .to_owned(),
+            ),
+        };
+        storage
+            .store_token_uri(&result)
+            .await
+            .expect("insert token uri");
+
+        {
+            // Clear the fields as if the row predated the columns
+            let conn = storage.conn.lock().unwrap();
+            conn.execute(
+                "UPDATE token_uris SET image = NULL WHERE token = ?1",
+                params![felt_to_blob(contract)],
+            )
+            .expect("clear image");
+        }
+        storage
+            .store_token_uri(&result)
+            .await
+            .expect("store unchanged token uri");
+
+        let fields = storage
+            .get_token_metadata_fields_batch(contract, &[U256::from(7u64), U256::from(8u64)])
+            .await
+            .expect("read fields");
+        assert_eq!(
+            fields,
+            vec![(
+                U256::from(7u64),
+                NormalizedMetadata {
+                    name: Some("Beast #7".to_owned()),
+                    description: Some("A bear".to_owned()),
+                    image: Some("ipfs://img/7".to_owned()),
+                }
+            )]
+        );
+
+        let _ = std::fs::remove_file(db_path);
+    }
+
     #[tokio::test]
     async fn store_token_uri_repairs_missing_attributes_for_same_metadata() {
         let db_path = temp_db_path("token-uri-backfill");
@@ -3320,4 +4749,80 @@ mod tests {
 
         let _ = std::fs::remove_file(db_path);
     }
+
+    #[tokio::test]
+    async fn maintenance_runs_on_new_databases() {
+        let db_path = temp_db_path("maintenance");
+        let storage = Erc721Storage::new(&db_path).await.expect("create storage");
+        storage.run_maintenance().await.expect("run maintenance");
+
+        let auto_vacuum: i64 = storage
+            .conn
+            .lock()
+            .unwrap()
+            .query_row("PRAGMA auto_vacuum", [], |row| row.get(0))
+            .expect("read auto_vacuum");
+        // 2 = INCREMENTAL
+        assert_eq!(auto_vacuum, 2);
+
+        let _ = std::fs::remove_file(db_path);
+    }
+
+    #[tokio::test]
+    async fn replay_pages_transfers_of_a_block_range() {
+        let db_path = temp_db_path("replay");
+        let storage = Erc721Storage::new(&db_path).await.expect("create storage");
+        let transfer = |token: u64, block_number: u64| NftTransferData {
+            id: None,
+            token: Felt::from(token),
+            token_id: U256::from(block_number),
+            from: Felt::ZERO,
+            to: Felt::from(0x10u64),
+            block_number,
+            tx_hash: Felt::from(block_number),
+            timestamp: None,
+            event_index: None,
+        };
+        storage
+            .insert_transfers_batch(&[
+                transfer(1, 5),
+                transfer(1, 9),
+                transfer(2, 12),
+                transfer(1, 100),
+            ])
+            .await
+            .expect("insert transfers");
+        let blocks =
+            |page: Vec<NftTransferData>| page.iter().map(|t| t.block_number).collect::<Vec<_>>();
+
+        // Block 100 sorts before 9 as text.
+        let range = ReplayRange::new(Vec::new(), Some(9), Some(100), 2).unwrap();
+        let first = storage
+            .get_transfers_for_replay(&range, None)
+            .await
+            .expect("first page");
+        let after_id = first.last().and_then(|t| t.id);
+        assert_eq!(blocks(first), vec![9, 12]);
+        let second = storage
+            .get_transfers_for_replay(&range, after_id)
+            .await
+            .expect("second page");
+        assert_eq!(blocks(second), vec![100]);
+
+        let token_range = range.with_tokens(vec![Felt::from(1u64)]);
+        let page = storage
+            .get_transfers_for_replay(&token_range, None)
+            .await
+            .expect("token page");
+        assert_eq!(blocks(page), vec![9, 100]);
+
+        let empty = ReplayRange::new(Vec::new(), Some(101), None, 2).unwrap();
+        assert!(storage
+            .get_transfers_for_replay(&empty, None)
+            .await
+            .expect("empty page")
+            .is_empty());
+
+        let _ = std::fs::remove_file(db_path);
+    }
 }
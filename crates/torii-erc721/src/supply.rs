@@ -0,0 +1,183 @@
+//! Mint/burn classification and collection supply tracking
+//!
+//! Transfers from the zero address are mints and transfers to it are burns. The sink
+//! counts them per collection ([`supply_changes`]) and storage folds the counts into
+//! the collection totals ([`CollectionSupply::apply`]) kept in `collection_supply`.
+//!
+//! Supply is computed from indexed events only: tokens minted before the indexed
+//! range are not counted, so the supply saturates at zero when more tokens are burned
+//! than were seen minted.
+
+use crate::storage::NftTransferData;
+use starknet::core::types::Felt;
+use std::collections::{BTreeMap, HashMap};
+
+/// Effect of a transfer on the collection supply
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferKind {
+    /// From the zero address
+    Mint,
+    /// To the zero address
+    Burn,
+    /// Between two accounts (supply unchanged)
+    Transfer,
+}
+
+impl TransferKind {
+    pub fn classify(from: Felt, to: Felt) -> Self {
+        match (from == Felt::ZERO, to == Felt::ZERO) {
+            (true, false) => Self::Mint,
+            (false, true) => Self::Burn,
+            _ => Self::Transfer,
+        }
+    }
+}
+
+/// Tokens minted and burned in a collection by a batch of transfers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SupplyChange {
+    pub token: Felt,
+    /// Block of the last mint or burn
+    pub block_number: u64,
+    pub minted: u64,
+    pub burned: u64,
+}
+
+/// Supply of a collection
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CollectionSupply {
+    pub token: Felt,
+    /// Block of the last mint or burn
+    pub block_number: u64,
+    pub total_minted: u64,
+    pub total_burned: u64,
+}
+
+impl CollectionSupply {
+    /// Tokens in circulation: total minted minus total burned, saturating at zero.
+    pub fn supply(&self) -> u64 {
+        self.total_minted.saturating_sub(self.total_burned)
+    }
+
+    /// Supply after applying `change` on top of `previous`.
+    pub fn apply(previous: Option<&Self>, change: &SupplyChange) -> Self {
+        let (total_minted, total_burned, block_number) = previous.map_or((0, 0, 0), |prev| {
+            (prev.total_minted, prev.total_burned, prev.block_number)
+        });
+        Self {
+            token: change.token,
+            block_number: block_number.max(change.block_number),
+            total_minted: total_minted.saturating_add(change.minted),
+            total_burned: total_burned.saturating_add(change.burned),
+        }
+    }
+}
+
+/// Counts the mints and burns of `transfers` per collection.
+pub fn supply_changes(transfers: &[NftTransferData]) -> Vec<SupplyChange> {
+    let mut changes: BTreeMap<Felt, SupplyChange> = BTreeMap::new();
+    for transfer in transfers {
+        let kind = TransferKind::classify(transfer.from, transfer.to);
+        if kind == TransferKind::Transfer {
+            continue;
+        }
+        let change = changes
+            .entry(transfer.token)
+            .or_insert_with(|| SupplyChange {
+                token: transfer.token,
+                block_number: transfer.block_number,
+                minted: 0,
+                burned: 0,
+            });
+        change.block_number = change.block_number.max(transfer.block_number);
+        if kind == TransferKind::Mint {
+            change.minted += 1;
+        } else {
+            change.burned += 1;
+        }
+    }
+    changes.into_values().collect()
+}
+
+/// Applies `changes` to the supply of their collections in `latest`.
+///
+/// Returns the new supply of each changed collection.
+pub(crate) fn fold_supply_changes(
+    latest: &mut HashMap<Felt, CollectionSupply>,
+    changes: &[SupplyChange],
+) -> Vec<CollectionSupply> {
+    changes
+        .iter()
+        .map(|change| {
+            let supply = CollectionSupply::apply(latest.get(&change.token), change);
+            latest.insert(change.token, supply.clone());
+            supply
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use starknet::core::types::U256;
+
+    fn transfer(token: u64, from: u64, to: u64, block_number: u64) -> NftTransferData {
+        NftTransferData {
+            id: None,
+            token: Felt::from(token),
+            token_id: U256::from(1u64),
+            from: Felt::from(from),
+            to: Felt::from(to),
+            block_number,
+            tx_hash: Felt::ONE,
+            timestamp: None,
+            event_index: None,
+        }
+    }
+
+    #[test]
+    fn test_supply_changes_per_collection() {
+        let changes = supply_changes(&[
+            transfer(1, 0, 5, 10),
+            transfer(1, 0, 6, 11),
+            transfer(1, 5, 6, 12),
+            transfer(1, 6, 0, 12),
+            transfer(2, 0, 5, 10),
+        ]);
+        assert_eq!(
+            changes,
+            vec![
+                SupplyChange {
+                    token: Felt::ONE,
+                    block_number: 12,
+                    minted: 2,
+                    burned: 1,
+                },
+                SupplyChange {
+                    token: Felt::TWO,
+                    block_number: 10,
+                    minted: 1,
+                    burned: 0,
+                },
+            ]
+        );
+
+        let mut latest = HashMap::new();
+        let first = fold_supply_changes(&mut latest, &changes);
+        assert_eq!(first[0].supply(), 1);
+        let second = fold_supply_changes(
+            &mut latest,
+            &[SupplyChange {
+                token: Felt::ONE,
+                block_number: 13,
+                minted: 0,
+                burned: 3,
+            }],
+        );
+        assert_eq!(second[0].total_minted, 2);
+        assert_eq!(second[0].total_burned, 4);
+        // Burning more than was seen minted saturates at zero.
+        assert_eq!(second[0].supply(), 0);
+        assert_eq!(latest[&Felt::ONE].block_number, 13);
+    }
+}
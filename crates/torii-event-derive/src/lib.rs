@@ -0,0 +1,248 @@
+//! `#[derive(StarknetEvent)]` for Torii decoders
+//!
+//! Generates, for a struct with named fields:
+//! - `NAME`, `SELECTOR` and `TYPE_ID` associated constants
+//! - `torii::etl::envelope::TypedBody` (with a `Debug`-based `debug_repr`, so the struct
+//!   must implement `Debug`)
+//! - `torii::etl::decoder::StarknetEvent` (key/data parsing for each declared layout)
+//!
+//! See `torii::etl::decoder::starknet_event` for the attributes and runtime types.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, Ident, LitStr};
+
+const LAYOUTS: &[(&str, &str)] = &[
+    ("declared", "Declared"),
+    ("keys", "Keys"),
+    ("data", "Data"),
+    ("declared_compact", "DeclaredCompact"),
+    ("keys_compact", "KeysCompact"),
+    ("data_compact", "DataCompact"),
+];
+
+#[proc_macro_derive(StarknetEvent, attributes(starknet_event, event))]
+pub fn derive_starknet_event(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+struct EventAttrs {
+    name: String,
+    type_id: LitStr,
+    layouts: Vec<Ident>,
+}
+
+enum FieldKind {
+    Member { slot: Ident, default: bool },
+    FromAddress,
+    BlockNumber,
+    TransactionHash,
+}
+
+fn parse_event_attrs(input: &DeriveInput) -> syn::Result<EventAttrs> {
+    let mut name = input.ident.to_string();
+    let mut type_id = None;
+    let mut layouts = Vec::new();
+
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|a| a.path().is_ident("starknet_event"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                name = meta.value()?.parse::<LitStr>()?.value();
+            } else if meta.path.is_ident("type_id") {
+                type_id = Some(meta.value()?.parse::<LitStr>()?);
+            } else if meta.path.is_ident("layouts") {
+                meta.parse_nested_meta(|layout| {
+                    let ident = layout.path.get_ident().map(ToString::to_string);
+                    let variant = LAYOUTS
+                        .iter()
+                        .find(|(name, _)| Some(*name) == ident.as_deref())
+                        .ok_or_else(|| {
+                            layout.error(
+                                "unknown layout (expected declared, keys, data, \
+                                 declared_compact, keys_compact or data_compact)",
+                            )
+                        })?;
+                    layouts.push(Ident::new(variant.1, Span::call_site()));
+                    Ok(())
+                })?;
+            } else {
+                return Err(meta.error("unknown starknet_event attribute"));
+            }
+            Ok(())
+        })?;
+    }
+
+    let type_id = type_id.ok_or_else(|| {
+        syn::Error::new_spanned(
+            &input.ident,
+            "missing #[starknet_event(type_id = \"...\")] attribute",
+        )
+    })?;
+    if layouts.is_empty() {
+        layouts.push(Ident::new("Declared", Span::call_site()));
+    }
+    Ok(EventAttrs {
+        name,
+        type_id,
+        layouts,
+    })
+}
+
+fn parse_field_kind(field: &syn::Field) -> syn::Result<FieldKind> {
+    let mut kind = None;
+    let mut default = false;
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("event")) {
+        attr.parse_nested_meta(|meta| {
+            let next = if meta.path.is_ident("key") {
+                FieldKind::Member {
+                    slot: Ident::new("Key", Span::call_site()),
+                    default: false,
+                }
+            } else if meta.path.is_ident("data") {
+                FieldKind::Member {
+                    slot: Ident::new("Data", Span::call_site()),
+                    default: false,
+                }
+            } else if meta.path.is_ident("default") {
+                default = true;
+                return Ok(());
+            } else if meta.path.is_ident("from_address") {
+                FieldKind::FromAddress
+            } else if meta.path.is_ident("block_number") {
+                FieldKind::BlockNumber
+            } else if meta.path.is_ident("transaction_hash") {
+                FieldKind::TransactionHash
+            } else {
+                return Err(meta.error(
+                    "unknown event attribute (expected key, data, default, from_address, \
+                     block_number or transaction_hash)",
+                ));
+            };
+            if kind.replace(next).is_some() {
+                return Err(meta.error("field already has an event placement"));
+            }
+            Ok(())
+        })?;
+    }
+
+    match kind {
+        Some(FieldKind::Member { slot, .. }) => Ok(FieldKind::Member { slot, default }),
+        Some(_) if default => Err(syn::Error::new_spanned(
+            field,
+            "`default` only applies to key and data members",
+        )),
+        Some(kind) => Ok(kind),
+        None => Err(syn::Error::new_spanned(
+            field,
+            "missing #[event(...)] placement (key, data, from_address, block_number or \
+             transaction_hash)",
+        )),
+    }
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            input,
+            "StarknetEvent can only be derived for structs",
+        ));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(syn::Error::new_spanned(
+            input,
+            "StarknetEvent requires named fields",
+        ));
+    };
+
+    let attrs = parse_event_attrs(input)?;
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let name = &attrs.name;
+    let type_id = &attrs.type_id;
+    let layouts = &attrs.layouts;
+
+    let mut members = Vec::new();
+    let mut reads = Vec::new();
+    let mut inits = Vec::new();
+    for field in &fields.named {
+        let field_ident = field.ident.as_ref().expect("named field");
+        let ty = &field.ty;
+        match parse_field_kind(field)? {
+            FieldKind::Member { slot, default } => {
+                members.push(quote! {
+                    ::torii::etl::decoder::EventMember {
+                        slot: ::torii::etl::decoder::EventSlot::#slot,
+                        width: <#ty as ::torii::etl::decoder::EventField>::WIDTH,
+                        default: #default,
+                    }
+                });
+                reads.push(quote! {
+                    let #field_ident = reader.read::<#ty>()?;
+                });
+                inits.push(quote! { #field_ident });
+            }
+            FieldKind::FromAddress => inits.push(quote! { #field_ident: event.from_address }),
+            FieldKind::BlockNumber => {
+                inits.push(quote! { #field_ident: event.block_number.unwrap_or(0) });
+            }
+            FieldKind::TransactionHash => {
+                inits.push(quote! { #field_ident: event.transaction_hash });
+            }
+        }
+    }
+
+    Ok(quote! {
+        impl #impl_generics #ident #ty_generics #where_clause {
+            /// Event name
+            pub const NAME: &'static str = #name;
+            /// `sn_keccak` of the event name
+            pub const SELECTOR: ::starknet::core::types::Felt = ::starknet::macros::selector!(#name);
+            /// Envelope type id
+            pub const TYPE_ID: &'static str = #type_id;
+        }
+
+        impl #impl_generics ::torii::etl::envelope::TypedBody for #ident #ty_generics #where_clause {
+            fn envelope_type_id(&self) -> ::torii::etl::envelope::TypeId {
+                ::torii::etl::envelope::TypeId::new(#type_id)
+            }
+
+            fn as_any(&self) -> &dyn ::std::any::Any {
+                self
+            }
+
+            fn as_any_mut(&mut self) -> &mut dyn ::std::any::Any {
+                self
+            }
+
+            fn debug_repr(&self) -> ::std::option::Option<::std::string::String> {
+                ::std::option::Option::Some(::std::format!("{self:?}"))
+            }
+        }
+
+        impl #impl_generics ::torii::etl::decoder::StarknetEvent for #ident #ty_generics #where_clause {
+            const NAME: &'static str = #name;
+            const SELECTOR: ::starknet::core::types::Felt = ::starknet::macros::selector!(#name);
+            const TYPE_ID: &'static str = #type_id;
+            const LAYOUTS: &'static [::torii::etl::decoder::EventLayout] =
+                &[#(::torii::etl::decoder::EventLayout::#layouts),*];
+
+            fn decode_with(
+                event: &::starknet::core::types::EmittedEvent,
+                layout: ::torii::etl::decoder::EventLayout,
+            ) -> ::std::option::Option<Self> {
+                const MEMBERS: &[::torii::etl::decoder::EventMember] = &[#(#members),*];
+                let mut reader = ::torii::etl::decoder::EventReader::new(event, layout, MEMBERS)?;
+                #(#reads)*
+                ::std::option::Option::Some(Self { #(#inits),* })
+            }
+        }
+    })
+}
@@ -0,0 +1,186 @@
+//! Token contract fixtures deployed on Katana.
+//!
+//! The contracts live in `contracts/src/fixtures.cairo`; `scarb build` in `contracts/`
+//! writes the Sierra and CASM classes declared here to `contracts/target/dev` (override
+//! with `TORII_CONTRACTS_TARGET`).
+
+use anyhow::{bail, Context, Result};
+use starknet::accounts::{Account, ConnectedAccount};
+use starknet::contract::{ContractFactory, UdcSelector};
+use starknet::core::types::contract::{CompiledClass, SierraClass};
+use starknet::core::types::{Call, ExecutionResult, Felt, U256};
+use starknet::core::utils::get_selector_from_name;
+use starknet::providers::Provider;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::katana::DevAccount;
+
+/// Environment variable overriding the directory of the compiled contracts
+pub const CONTRACTS_TARGET_ENV: &str = "TORII_CONTRACTS_TARGET";
+
+/// Scarb package of the fixtures
+const PACKAGE: &str = "torii_contracts";
+
+/// How long to wait for a transaction receipt
+const RECEIPT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Fixture contract
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fixture {
+    Erc20,
+    Erc721,
+}
+
+impl Fixture {
+    /// Contract name in the Scarb artifacts
+    pub fn contract_name(self) -> &'static str {
+        match self {
+            Self::Erc20 => "TestErc20",
+            Self::Erc721 => "TestErc721",
+        }
+    }
+}
+
+/// Directory of the compiled fixture classes
+pub fn contracts_target() -> PathBuf {
+    std::env::var_os(CONTRACTS_TARGET_ENV).map_or_else(
+        || PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../contracts/target/dev"),
+        PathBuf::from,
+    )
+}
+
+/// Deploys fixtures and sends token transactions from the dev account
+pub struct Deployer {
+    account: DevAccount,
+}
+
+impl Deployer {
+    pub fn new(account: DevAccount) -> Self {
+        Self { account }
+    }
+
+    pub fn address(&self) -> Felt {
+        self.account.address()
+    }
+
+    /// Declares `fixture` and deploys one instance, returning its address.
+    pub async fn deploy(&self, fixture: Fixture) -> Result<Felt> {
+        let class_hash = self.declare(fixture).await?;
+        let factory = ContractFactory::new_with_udc(class_hash, &self.account, UdcSelector::Legacy);
+        let deployment = factory.deploy_v3(Vec::new(), Felt::ZERO, false);
+        let address = deployment.deployed_address();
+        let result = deployment
+            .send()
+            .await
+            .with_context(|| format!("Failed to deploy {}", fixture.contract_name()))?;
+        self.wait_for_receipt(result.transaction_hash).await?;
+        Ok(address)
+    }
+
+    async fn declare(&self, fixture: Fixture) -> Result<Felt> {
+        let target = contracts_target();
+        let name = fixture.contract_name();
+        let sierra_path = target.join(format!("{PACKAGE}_{name}.contract_class.json"));
+        let casm_path = target.join(format!("{PACKAGE}_{name}.compiled_contract_class.json"));
+        let sierra: SierraClass =
+            serde_json::from_slice(&std::fs::read(&sierra_path).with_context(|| {
+                format!(
+                    "Missing {} (run `scarb build` in contracts/)",
+                    sierra_path.display()
+                )
+            })?)?;
+        let casm: CompiledClass = serde_json::from_slice(
+            &std::fs::read(&casm_path)
+                .with_context(|| format!("Missing {}", casm_path.display()))?,
+        )?;
+
+        let class = sierra.flatten()?;
+        let class_hash = class.class_hash();
+        let result = self
+            .account
+            .declare_v3(Arc::new(class), casm.class_hash()?)
+            .send()
+            .await
+            .with_context(|| format!("Failed to declare {name}"))?;
+        self.wait_for_receipt(result.transaction_hash).await?;
+        Ok(class_hash)
+    }
+
+    /// Invokes `entrypoint` on `contract` and waits for the transaction to be mined.
+    pub async fn invoke(
+        &self,
+        contract: Felt,
+        entrypoint: &str,
+        calldata: Vec<Felt>,
+    ) -> Result<()> {
+        let call = Call {
+            to: contract,
+            selector: get_selector_from_name(entrypoint)?,
+            calldata,
+        };
+        let result = self
+            .account
+            .execute_v3(vec![call])
+            .send()
+            .await
+            .with_context(|| format!("Failed to invoke {entrypoint}"))?;
+        self.wait_for_receipt(result.transaction_hash).await
+    }
+
+    pub async fn erc20_mint(&self, token: Felt, recipient: Felt, amount: U256) -> Result<()> {
+        self.invoke(
+            token,
+            "mint",
+            [vec![recipient], u256_calldata(amount)].concat(),
+        )
+        .await
+    }
+
+    pub async fn erc20_transfer(&self, token: Felt, recipient: Felt, amount: U256) -> Result<()> {
+        self.invoke(
+            token,
+            "transfer",
+            [vec![recipient], u256_calldata(amount)].concat(),
+        )
+        .await
+    }
+
+    pub async fn erc721_mint(&self, token: Felt, to: Felt, token_id: U256) -> Result<()> {
+        self.invoke(token, "mint", [vec![to], u256_calldata(token_id)].concat())
+            .await
+    }
+
+    /// Transfers `token_id` from the dev account to `to`.
+    pub async fn erc721_transfer(&self, token: Felt, to: Felt, token_id: U256) -> Result<()> {
+        self.invoke(
+            token,
+            "transfer_from",
+            [vec![self.address(), to], u256_calldata(token_id)].concat(),
+        )
+        .await
+    }
+
+    async fn wait_for_receipt(&self, transaction_hash: Felt) -> Result<()> {
+        let provider = self.account.provider();
+        let started = Instant::now();
+        loop {
+            if let Ok(receipt) = provider.get_transaction_receipt(transaction_hash).await {
+                if let ExecutionResult::Reverted { reason } = receipt.receipt.execution_result() {
+                    bail!("Transaction {transaction_hash:#x} reverted: {reason}");
+                }
+                return Ok(());
+            }
+            if started.elapsed() > RECEIPT_TIMEOUT {
+                bail!("Transaction {transaction_hash:#x} not mined after {RECEIPT_TIMEOUT:?}");
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    }
+}
+
+/// `u256` calldata (low, high)
+pub fn u256_calldata(value: U256) -> Vec<Felt> {
+    vec![Felt::from(value.low()), Felt::from(value.high())]
+}
@@ -0,0 +1,211 @@
+//! Torii instance indexing a Katana devnet.
+//!
+//! [`ToriiHarness::start`] runs the full [`torii::run`] with a [`BlockRangeExtractor`]
+//! following the Katana chain head, the ERC20 and ERC721 sinks (SQLite databases in a
+//! temporary directory) and their gRPC services, then talks to it over gRPC like a client.
+
+use anyhow::{bail, Result};
+use starknet::core::types::Felt;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+use tokio::task::JoinHandle;
+use tonic::codec::ProstCodec;
+use tonic::codegen::http::uri::PathAndQuery;
+use tonic::transport::{Channel, Endpoint};
+use tonic::{Request, Status, Streaming};
+use torii::etl::decoder::DecoderId;
+use torii::etl::extractor::{BlockRangeConfig, BlockRangeExtractor};
+use torii_erc20::proto::erc20_server::Erc20Server;
+use torii_erc20::{Erc20Decoder, Erc20Service, Erc20Sink, ShardedErc20Storage};
+use torii_erc721::proto::erc721_server::Erc721Server;
+use torii_erc721::{Erc721Decoder, Erc721Service, Erc721Sink, ShardedErc721Storage};
+
+use crate::katana::{free_port, KatanaRunner};
+
+/// How long to wait for the Torii listener
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Token contracts indexed by the harness
+#[derive(Debug, Clone, Default)]
+pub struct IndexedContracts {
+    pub erc20: Vec<Felt>,
+    pub erc721: Vec<Felt>,
+}
+
+/// Running Torii, stopped on drop
+pub struct ToriiHarness {
+    port: u16,
+    handle: JoinHandle<()>,
+    _data_dir: TempDir,
+}
+
+impl ToriiHarness {
+    /// Starts Torii against `katana` and waits until its gRPC listener accepts connections.
+    pub async fn start(katana: &KatanaRunner, contracts: &IndexedContracts) -> Result<Self> {
+        let data_dir = tempfile::tempdir()?;
+        let port = free_port()?;
+
+        let extractor = BlockRangeExtractor::new(
+            katana.provider(),
+            BlockRangeConfig {
+                rpc_url: katana.url().to_string(),
+                batch_size: 10,
+                ..BlockRangeConfig::default()
+            },
+        );
+
+        let erc20_storage =
+            ShardedErc20Storage::open(&data_dir.path().join("erc20.db").to_string_lossy(), 1)
+                .await?;
+        let erc20_service = Erc20Service::new(erc20_storage.clone());
+        let erc20_sink = Erc20Sink::new(erc20_storage).with_grpc_service(erc20_service.clone());
+
+        let erc721_storage =
+            ShardedErc721Storage::open(&data_dir.path().join("erc721.db").to_string_lossy(), 1)
+                .await?;
+        let erc721_service = Erc721Service::new(erc721_storage.clone());
+        let erc721_sink = Erc721Sink::new(erc721_storage).with_grpc_service(erc721_service.clone());
+
+        let grpc_router = tonic::transport::Server::builder()
+            .add_service(Erc20Server::new(erc20_service))
+            .add_service(Erc721Server::new(erc721_service));
+
+        let mut config = torii::ToriiConfig::builder()
+            .port(port)
+            .host("127.0.0.1".to_string())
+            .cycle_interval(1)
+            .database_root(data_dir.path())
+            .engine_database_url(data_dir.path().join("engine.db").to_string_lossy())
+            .with_extractor(Box::new(extractor))
+            .add_decoder(Arc::new(Erc20Decoder::new()))
+            .add_decoder(Arc::new(Erc721Decoder::new()))
+            .add_sink_boxed(Box::new(erc20_sink))
+            .add_sink_boxed(Box::new(erc721_sink))
+            .with_grpc_router(grpc_router);
+        for address in &contracts.erc20 {
+            config = config.map_contract(*address, vec![DecoderId::new("erc20")]);
+        }
+        for address in &contracts.erc721 {
+            config = config.map_contract(*address, vec![DecoderId::new("erc721")]);
+        }
+        let config = config.build();
+
+        let handle = tokio::spawn(async move {
+            if let Err(error) = torii::run(config).await {
+                panic!("Torii exited with an error: {error}");
+            }
+        });
+
+        let harness = Self {
+            port,
+            handle,
+            _data_dir: data_dir,
+        };
+        harness.wait_ready().await?;
+        Ok(harness)
+    }
+
+    async fn wait_ready(&self) -> Result<()> {
+        let started = Instant::now();
+        loop {
+            if self.channel().await.is_ok() {
+                return Ok(());
+            }
+            if self.handle.is_finished() {
+                bail!("Torii exited during startup");
+            }
+            if started.elapsed() > STARTUP_TIMEOUT {
+                bail!("Torii not listening after {STARTUP_TIMEOUT:?}");
+            }
+            tokio::time::sleep(Duration::from_millis(200)).await;
+        }
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// New gRPC channel to the Torii listener
+    pub async fn channel(&self) -> Result<Channel> {
+        Ok(
+            Endpoint::from_shared(format!("http://127.0.0.1:{}", self.port))?
+                .connect()
+                .await?,
+        )
+    }
+
+    /// Calls the unary RPC at `path` (e.g. `/torii.sinks.erc20.Erc20/GetBalance`).
+    pub async fn unary<Req, Resp>(&self, path: &'static str, request: Req) -> Result<Resp>
+    where
+        Req: prost::Message + Send + Sync + 'static,
+        Resp: prost::Message + Default + Send + Sync + 'static,
+    {
+        let mut grpc = tonic::client::Grpc::new(self.channel().await?);
+        grpc.ready().await?;
+        let response = grpc
+            .unary(
+                Request::new(request),
+                PathAndQuery::from_static(path),
+                ProstCodec::<Req, Resp>::default(),
+            )
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    /// Opens the server-streaming RPC at `path`.
+    pub async fn server_streaming<Req, Resp>(
+        &self,
+        path: &'static str,
+        request: Req,
+    ) -> Result<Streaming<Resp>, Status>
+    where
+        Req: prost::Message + Send + Sync + 'static,
+        Resp: prost::Message + Default + Send + Sync + 'static,
+    {
+        let channel = self
+            .channel()
+            .await
+            .map_err(|e| Status::unavailable(e.to_string()))?;
+        let mut grpc = tonic::client::Grpc::new(channel);
+        grpc.ready()
+            .await
+            .map_err(|e| Status::unavailable(e.to_string()))?;
+        let response = grpc
+            .server_streaming(
+                Request::new(request),
+                PathAndQuery::from_static(path),
+                ProstCodec::<Req, Resp>::default(),
+            )
+            .await?;
+        Ok(response.into_inner())
+    }
+}
+
+impl Drop for ToriiHarness {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+/// Polls `check` until it returns a value or `timeout` elapses.
+///
+/// Indexing is asynchronous: queries only reflect a transaction once its block went
+/// through the ETL loop.
+pub async fn eventually<T, F, Fut>(timeout: Duration, mut check: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<Option<T>>>,
+{
+    let started = Instant::now();
+    loop {
+        if let Some(value) = check().await? {
+            return Ok(value);
+        }
+        if started.elapsed() > timeout {
+            bail!("Condition not met after {timeout:?}");
+        }
+        tokio::time::sleep(Duration::from_millis(250)).await;
+    }
+}
@@ -0,0 +1,118 @@
+//! Katana devnet process for the tests.
+//!
+//! [`KatanaRunner`] spawns the `katana` binary (`KATANA_BIN`, or `katana` from `PATH`) in
+//! dev mode on a free port, waits for its RPC to answer and kills it on drop. Blocks are
+//! mined instantly, one per transaction.
+
+use anyhow::{bail, Context, Result};
+use starknet::accounts::{ExecutionEncoding, SingleOwnerAccount};
+use starknet::core::types::Felt;
+use starknet::providers::jsonrpc::{HttpTransport, JsonRpcClient};
+use starknet::providers::{Provider, Url};
+use starknet::signers::{LocalWallet, SigningKey};
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::process::{Child, Command};
+use torii_common::{rate_limited_provider, RpcProvider};
+
+/// Environment variable overriding the Katana binary
+pub const KATANA_BIN_ENV: &str = "KATANA_BIN";
+
+/// Prefunded account 0 of `katana --dev` (default seed)
+pub const DEV_ACCOUNT_ADDRESS: Felt =
+    Felt::from_hex_unchecked("0x127fd5f1fe78a71f8bcd1fec63e3fe2f0486b6ecd5c86a0466c3a21fa5cfcec");
+/// Private key of [`DEV_ACCOUNT_ADDRESS`]
+pub const DEV_ACCOUNT_PRIVATE_KEY: Felt =
+    Felt::from_hex_unchecked("0xc5b2fcab997346f3ea1c00b002ecf6f382c5f9c9659a3894eb783c5320f912");
+
+/// How long to wait for the RPC of a new Katana to answer
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Account signing the test transactions
+pub type DevAccount = SingleOwnerAccount<JsonRpcClient<HttpTransport>, LocalWallet>;
+
+/// Running Katana devnet, killed on drop
+pub struct KatanaRunner {
+    child: Child,
+    url: Url,
+    chain_id: Felt,
+}
+
+impl KatanaRunner {
+    /// Binary used by [`KatanaRunner::spawn`]
+    pub fn program() -> String {
+        std::env::var(KATANA_BIN_ENV).unwrap_or_else(|_| "katana".to_string())
+    }
+
+    /// Spawns Katana on a free port and waits until its RPC answers.
+    pub async fn spawn() -> Result<Self> {
+        let port = free_port()?;
+        let program = Self::program();
+        let child = Command::new(&program)
+            .args([
+                "--dev",
+                "--dev.no-fee",
+                "--dev.no-account-validation",
+                "--http.port",
+                &port.to_string(),
+            ])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()
+            .with_context(|| format!("Failed to spawn {program} (set {KATANA_BIN_ENV})"))?;
+        let url = Url::parse(&format!("http://127.0.0.1:{port}"))?;
+
+        let mut runner = Self {
+            child,
+            url,
+            chain_id: Felt::ZERO,
+        };
+        runner.chain_id = runner.wait_ready().await?;
+        Ok(runner)
+    }
+
+    async fn wait_ready(&mut self) -> Result<Felt> {
+        let client = JsonRpcClient::new(HttpTransport::new(self.url.clone()));
+        let started = Instant::now();
+        loop {
+            if let Ok(chain_id) = client.chain_id().await {
+                return Ok(chain_id);
+            }
+            if let Some(status) = self.child.try_wait()? {
+                bail!("Katana exited during startup: {status}");
+            }
+            if started.elapsed() > STARTUP_TIMEOUT {
+                bail!("Katana RPC not ready after {STARTUP_TIMEOUT:?}");
+            }
+            tokio::time::sleep(Duration::from_millis(200)).await;
+        }
+    }
+
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    /// Provider used by Torii (no rate limit)
+    pub fn provider(&self) -> Arc<RpcProvider> {
+        Arc::new(rate_limited_provider(self.url.clone(), 0, 0))
+    }
+
+    /// Prefunded dev account
+    pub fn account(&self) -> DevAccount {
+        SingleOwnerAccount::new(
+            JsonRpcClient::new(HttpTransport::new(self.url.clone())),
+            LocalWallet::from(SigningKey::from_secret_scalar(DEV_ACCOUNT_PRIVATE_KEY)),
+            DEV_ACCOUNT_ADDRESS,
+            self.chain_id,
+            ExecutionEncoding::New,
+        )
+    }
+}
+
+/// Reserves a free local port (released right away for the caller to bind).
+pub fn free_port() -> Result<u16> {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+    Ok(listener.local_addr()?.port())
+}
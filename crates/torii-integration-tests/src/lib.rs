@@ -0,0 +1,15 @@
+//! End-to-end tests of the Torii pipeline against a Katana devnet
+//!
+//! [`katana`] spawns a Katana devnet, [`fixtures`] deploys minimal ERC20/ERC721
+//! contracts on it and sends token transactions, and [`harness`] runs the full
+//! [`torii::run`] with a block range extractor and the token sinks, then queries it over
+//! gRPC. The tests in `tests/` need a `katana` binary and the compiled fixtures, so they
+//! are ignored by default: `cargo test -p torii-integration-tests -- --ignored`.
+
+pub mod fixtures;
+pub mod harness;
+pub mod katana;
+
+pub use fixtures::{contracts_target, u256_calldata, Deployer, Fixture};
+pub use harness::{eventually, IndexedContracts, ToriiHarness};
+pub use katana::{DevAccount, KatanaRunner};
@@ -0,0 +1,196 @@
+//! Full pipeline: Katana -> BlockRangeExtractor -> decoders -> token sinks -> gRPC.
+//!
+//! Needs a `katana` binary (or `KATANA_BIN`) and the fixtures built with `scarb build`
+//! in `contracts/`; run with `cargo test -p torii-integration-tests -- --ignored`.
+
+use starknet::core::types::{Felt, U256};
+use std::time::Duration;
+use torii_common::{bytes_to_u256, u256_to_bytes};
+use torii_erc20::proto as erc20;
+use torii_erc721::proto as erc721;
+use torii_integration_tests::{
+    eventually, Deployer, Fixture, IndexedContracts, KatanaRunner, ToriiHarness,
+};
+
+const INDEXING_TIMEOUT: Duration = Duration::from_secs(60);
+
+fn felt_bytes(value: Felt) -> Vec<u8> {
+    value.to_bytes_be().to_vec()
+}
+
+#[tokio::test]
+#[ignore = "requires katana and the compiled contract fixtures"]
+async fn erc20_transfers_are_indexed_and_queryable() {
+    let katana = KatanaRunner::spawn().await.unwrap();
+    let deployer = Deployer::new(katana.account());
+    let token = deployer.deploy(Fixture::Erc20).await.unwrap();
+    let recipient = Felt::from(0x1234u64);
+
+    deployer
+        .erc20_mint(token, deployer.address(), U256::from(1_000u64))
+        .await
+        .unwrap();
+    deployer
+        .erc20_transfer(token, recipient, U256::from(250u64))
+        .await
+        .unwrap();
+
+    let torii = ToriiHarness::start(
+        &katana,
+        &IndexedContracts {
+            erc20: vec![token],
+            ..IndexedContracts::default()
+        },
+    )
+    .await
+    .unwrap();
+
+    for (wallet, expected) in [(recipient, 250u64), (deployer.address(), 750)] {
+        let balance = eventually(INDEXING_TIMEOUT, || async {
+            let response: erc20::GetBalanceResponse = torii
+                .unary(
+                    "/torii.sinks.erc20.Erc20/GetBalance",
+                    erc20::GetBalanceRequest {
+                        token: felt_bytes(token),
+                        wallet: felt_bytes(wallet),
+                        include_usd: false,
+                    },
+                )
+                .await?;
+            let balance = bytes_to_u256(&response.balance);
+            Ok((balance == U256::from(expected)).then_some(balance))
+        })
+        .await
+        .unwrap();
+        assert_eq!(balance, U256::from(expected));
+    }
+
+    let transfers: erc20::GetTransfersResponse = torii
+        .unary(
+            "/torii.sinks.erc20.Erc20/GetTransfers",
+            erc20::GetTransfersRequest {
+                filter: Some(erc20::TransferFilter {
+                    tokens: vec![felt_bytes(token)],
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(transfers.transfers.len(), 2);
+}
+
+#[tokio::test]
+#[ignore = "requires katana and the compiled contract fixtures"]
+async fn erc721_ownership_is_indexed_and_queryable() {
+    let katana = KatanaRunner::spawn().await.unwrap();
+    let deployer = Deployer::new(katana.account());
+    let token = deployer.deploy(Fixture::Erc721).await.unwrap();
+    let recipient = Felt::from(0x1234u64);
+
+    for token_id in [1u64, 2] {
+        deployer
+            .erc721_mint(token, deployer.address(), U256::from(token_id))
+            .await
+            .unwrap();
+    }
+    deployer
+        .erc721_transfer(token, recipient, U256::from(2u64))
+        .await
+        .unwrap();
+
+    let torii = ToriiHarness::start(
+        &katana,
+        &IndexedContracts {
+            erc721: vec![token],
+            ..IndexedContracts::default()
+        },
+    )
+    .await
+    .unwrap();
+
+    let owner = eventually(INDEXING_TIMEOUT, || async {
+        let response: erc721::GetOwnerResponse = torii
+            .unary(
+                "/torii.sinks.erc721.Erc721/GetOwner",
+                erc721::GetOwnerRequest {
+                    token: felt_bytes(token),
+                    token_id: u256_to_bytes(U256::from(2u64)),
+                },
+            )
+            .await?;
+        Ok(response
+            .owner
+            .filter(|owner| *owner == felt_bytes(recipient)))
+    })
+    .await
+    .unwrap();
+    assert_eq!(owner, felt_bytes(recipient));
+
+    let owned: erc721::GetTokensByOwnerResponse = torii
+        .unary(
+            "/torii.sinks.erc721.Erc721/GetTokensByOwner",
+            erc721::GetTokensByOwnerRequest {
+                owner: felt_bytes(deployer.address()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        owned
+            .tokens
+            .iter()
+            .map(|t| bytes_to_u256(&t.token_id))
+            .collect::<Vec<_>>(),
+        vec![U256::from(1u64)]
+    );
+}
+
+#[tokio::test]
+#[ignore = "requires katana and the compiled contract fixtures"]
+async fn erc20_subscription_streams_live_transfers() {
+    let katana = KatanaRunner::spawn().await.unwrap();
+    let deployer = Deployer::new(katana.account());
+    let token = deployer.deploy(Fixture::Erc20).await.unwrap();
+    let recipient = Felt::from(0x5678u64);
+
+    let torii = ToriiHarness::start(
+        &katana,
+        &IndexedContracts {
+            erc20: vec![token],
+            ..IndexedContracts::default()
+        },
+    )
+    .await
+    .unwrap();
+    let mut updates = torii
+        .server_streaming::<_, erc20::TransferUpdate>(
+            "/torii.sinks.erc20.Erc20/SubscribeTransfers",
+            erc20::SubscribeTransfersRequest {
+                client_id: "integration-test".to_string(),
+                filter: Some(erc20::TransferFilter {
+                    wallet: Some(felt_bytes(recipient)),
+                    ..Default::default()
+                }),
+            },
+        )
+        .await
+        .unwrap();
+
+    deployer
+        .erc20_mint(token, recipient, U256::from(42u64))
+        .await
+        .unwrap();
+
+    let update = tokio::time::timeout(INDEXING_TIMEOUT, updates.message())
+        .await
+        .expect("no transfer update before the timeout")
+        .unwrap()
+        .expect("subscription stream closed");
+    let transfer = update.transfer.expect("update without transfer");
+    assert_eq!(transfer.token, felt_bytes(token));
+    assert_eq!(transfer.to, felt_bytes(recipient));
+    assert_eq!(bytes_to_u256(&transfer.amount), U256::from(42u64));
+}
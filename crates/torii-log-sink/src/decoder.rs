@@ -1,4 +1,3 @@
-use anyhow::Result;
 use async_trait::async_trait;
 use starknet::core::types::EmittedEvent;
 use std::any::Any;
@@ -6,6 +5,7 @@ use torii::etl::{
     envelope::{Envelope, TypeId, TypedBody},
     Decoder,
 };
+use torii::ToriiResult;
 
 /// Decoded log entry
 #[derive(Debug, Clone)]
@@ -50,7 +50,11 @@ impl Decoder for LogDecoder {
         "log"
     }
 
-    async fn decode_event(&self, event: &EmittedEvent) -> Result<Vec<Envelope>> {
+    fn decoder_version(&self) -> Option<&str> {
+        Some(env!("CARGO_PKG_VERSION"))
+    }
+
+    async fn decode_event(&self, event: &EmittedEvent) -> ToriiResult<Vec<Envelope>> {
         // Apply key filter if specified
         if let Some(ref filter) = self.key_filter {
             let matches = event.keys.iter().any(|k| {
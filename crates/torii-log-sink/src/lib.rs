@@ -22,6 +22,7 @@ use torii::etl::{
     sink::{EventBus, Sink, TopicInfo},
 };
 use torii::grpc::UpdateType;
+use torii::ToriiResult;
 
 pub use decoder::{LogDecoder, LogEntry};
 pub use grpc_service::LogSinkService;
@@ -98,15 +99,19 @@ impl Sink for LogSink {
         "log"
     }
 
+    fn version(&self) -> Option<&str> {
+        Some(env!("CARGO_PKG_VERSION"))
+    }
+
+    fn descriptor_set(&self) -> Option<&'static [u8]> {
+        Some(FILE_DESCRIPTOR_SET)
+    }
+
     fn interested_types(&self) -> Vec<TypeId> {
         vec![TypeId::new("log.entry")]
     }
 
-    async fn process(
-        &self,
-        envelopes: &[Envelope],
-        _batch: &ExtractionBatch,
-    ) -> anyhow::Result<()> {
+    async fn process(&self, envelopes: &[Envelope], _batch: &ExtractionBatch) -> ToriiResult<()> {
         for envelope in envelopes {
             if envelope.type_id == TypeId::new("log.entry") {
                 if let Some(log_entry) = envelope.downcast_ref::<LogEntry>() {
@@ -136,7 +141,7 @@ impl Sink for LogSink {
                     // Broadcast to EventBus subscribers (central subscription).
                     if let Some(event_bus) = &self.event_bus {
                         let mut buf = Vec::new();
-                        proto_log.encode(&mut buf)?;
+                        proto_log.encode(&mut buf).map_err(anyhow::Error::from)?;
                         let any = Any {
                             type_url: "type.googleapis.com/torii.sinks.log.LogEntry".to_string(),
                             value: buf,
@@ -193,7 +198,7 @@ impl Sink for LogSink {
         &mut self,
         event_bus: Arc<EventBus>,
         _context: &torii::etl::sink::SinkContext,
-    ) -> anyhow::Result<()> {
+    ) -> ToriiResult<()> {
         self.event_bus = Some(event_bus);
         tracing::info!(target: "torii::sinks::log", "LogSink initialized with event bus");
         Ok(())
@@ -0,0 +1,372 @@
+// This file is @generated by prost-build.
+/// Offchain message signed by an account
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OffchainMessage {
+    /// SNIP-12 message hash (32 bytes)
+    #[prost(bytes = "vec", tag = "1")]
+    pub message_hash: ::prost::alloc::vec::Vec<u8>,
+    /// Signing account address (32 bytes)
+    #[prost(bytes = "vec", tag = "2")]
+    pub identity: ::prost::alloc::vec::Vec<u8>,
+    /// SNIP-12 typed data (JSON)
+    #[prost(string, tag = "3")]
+    pub typed_data: ::prost::alloc::string::String,
+    /// Signature felts (32 bytes each)
+    #[prost(bytes = "vec", repeated, tag = "4")]
+    pub signature: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+    /// Primary type of the typed data
+    #[prost(string, tag = "5")]
+    pub primary_type: ::prost::alloc::string::String,
+    /// Unix timestamp (seconds) at which the message was first relayed by this node
+    #[prost(int64, tag = "6")]
+    pub received_at: i64,
+}
+/// Request for PublishMessage RPC
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PublishMessageRequest {
+    /// Signing account address (32 bytes)
+    #[prost(bytes = "vec", tag = "1")]
+    pub identity: ::prost::alloc::vec::Vec<u8>,
+    /// SNIP-12 typed data (JSON)
+    #[prost(string, tag = "2")]
+    pub typed_data: ::prost::alloc::string::String,
+    /// Signature felts (32 bytes each)
+    #[prost(bytes = "vec", repeated, tag = "3")]
+    pub signature: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+}
+/// Response for PublishMessage RPC
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PublishMessageResponse {
+    /// SNIP-12 message hash (32 bytes)
+    #[prost(bytes = "vec", tag = "1")]
+    pub message_hash: ::prost::alloc::vec::Vec<u8>,
+    /// The message had already been relayed
+    #[prost(bool, tag = "2")]
+    pub duplicate: bool,
+}
+/// Request for GetMessages RPC
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetMessagesRequest {
+    /// Only messages signed by this account (32 bytes)
+    #[prost(bytes = "vec", optional, tag = "1")]
+    pub identity: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+    /// Only messages of this primary type
+    #[prost(string, optional, tag = "2")]
+    pub primary_type: ::core::option::Option<::prost::alloc::string::String>,
+    /// Only messages received strictly before this timestamp (pagination)
+    #[prost(int64, optional, tag = "3")]
+    pub before: ::core::option::Option<i64>,
+    /// Maximum number of messages to return (default: 100, max: 1000)
+    #[prost(uint32, tag = "4")]
+    pub limit: u32,
+}
+/// Response for GetMessages RPC
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetMessagesResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub messages: ::prost::alloc::vec::Vec<OffchainMessage>,
+}
+/// Request for SubscribeMessages RPC
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SubscribeMessagesRequest {
+    /// Only messages signed by this account (32 bytes)
+    #[prost(bytes = "vec", optional, tag = "1")]
+    pub identity: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+    /// Only messages of this primary type
+    #[prost(string, optional, tag = "2")]
+    pub primary_type: ::core::option::Option<::prost::alloc::string::String>,
+}
+/// Generated server implementations.
+pub mod relay_server {
+    #![allow(
+        unused_variables,
+        dead_code,
+        missing_docs,
+        clippy::wildcard_imports,
+        clippy::let_unit_value,
+    )]
+    use tonic::codegen::*;
+    /// Generated trait containing gRPC methods that should be implemented for use with RelayServer.
+    #[async_trait]
+    pub trait Relay: std::marker::Send + std::marker::Sync + 'static {
+        /// Validate, persist and relay a signed message
+        async fn publish_message(
+            &self,
+            request: tonic::Request<super::PublishMessageRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::PublishMessageResponse>,
+            tonic::Status,
+        >;
+        /// Query relayed messages, newest first
+        async fn get_messages(
+            &self,
+            request: tonic::Request<super::GetMessagesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetMessagesResponse>,
+            tonic::Status,
+        >;
+        /// Server streaming response type for the SubscribeMessages method.
+        type SubscribeMessagesStream: tonic::codegen::tokio_stream::Stream<
+                Item = std::result::Result<super::OffchainMessage, tonic::Status>,
+            >
+            + std::marker::Send
+            + 'static;
+        /// Subscribe to messages as they are relayed (from clients or peers)
+        async fn subscribe_messages(
+            &self,
+            request: tonic::Request<super::SubscribeMessagesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<Self::SubscribeMessagesStream>,
+            tonic::Status,
+        >;
+    }
+    /// Relay service - accepts signed offchain messages and serves the relayed ones
+    #[derive(Debug)]
+    pub struct RelayServer<T> {
+        inner: Arc<T>,
+        accept_compression_encodings: EnabledCompressionEncodings,
+        send_compression_encodings: EnabledCompressionEncodings,
+        max_decoding_message_size: Option<usize>,
+        max_encoding_message_size: Option<usize>,
+    }
+    impl<T> RelayServer<T> {
+        pub fn new(inner: T) -> Self {
+            Self::from_arc(Arc::new(inner))
+        }
+        pub fn from_arc(inner: Arc<T>) -> Self {
+            Self {
+                inner,
+                accept_compression_encodings: Default::default(),
+                send_compression_encodings: Default::default(),
+                max_decoding_message_size: None,
+                max_encoding_message_size: None,
+            }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> InterceptedService<Self, F>
+        where
+            F: tonic::service::Interceptor,
+        {
+            InterceptedService::new(Self::new(inner), interceptor)
+        }
+        /// Enable decompressing requests with the given encoding.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.accept_compression_encodings.enable(encoding);
+            self
+        }
+        /// Compress responses with the given encoding, if the client supports it.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.send_compression_encodings.enable(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.max_decoding_message_size = Some(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.max_encoding_message_size = Some(limit);
+            self
+        }
+    }
+    impl<T, B> tonic::codegen::Service<http::Request<B>> for RelayServer<T>
+    where
+        T: Relay,
+        B: Body + std::marker::Send + 'static,
+        B::Error: Into<StdError> + std::marker::Send + 'static,
+    {
+        type Response = http::Response<tonic::body::BoxBody>;
+        type Error = std::convert::Infallible;
+        type Future = BoxFuture<Self::Response, Self::Error>;
+        fn poll_ready(
+            &mut self,
+            _cx: &mut Context<'_>,
+        ) -> Poll<std::result::Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+        fn call(&mut self, req: http::Request<B>) -> Self::Future {
+            match req.uri().path() {
+                "/torii.relay.Relay/PublishMessage" => {
+                    #[allow(non_camel_case_types)]
+                    struct PublishMessageSvc<T: Relay>(pub Arc<T>);
+                    impl<
+                        T: Relay,
+                    > tonic::server::UnaryService<super::PublishMessageRequest>
+                    for PublishMessageSvc<T> {
+                        type Response = super::PublishMessageResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::PublishMessageRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Relay>::publish_message(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = PublishMessageSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/torii.relay.Relay/GetMessages" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetMessagesSvc<T: Relay>(pub Arc<T>);
+                    impl<T: Relay> tonic::server::UnaryService<super::GetMessagesRequest>
+                    for GetMessagesSvc<T> {
+                        type Response = super::GetMessagesResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetMessagesRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Relay>::get_messages(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetMessagesSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/torii.relay.Relay/SubscribeMessages" => {
+                    #[allow(non_camel_case_types)]
+                    struct SubscribeMessagesSvc<T: Relay>(pub Arc<T>);
+                    impl<
+                        T: Relay,
+                    > tonic::server::ServerStreamingService<
+                        super::SubscribeMessagesRequest,
+                    > for SubscribeMessagesSvc<T> {
+                        type Response = super::OffchainMessage;
+                        type ResponseStream = T::SubscribeMessagesStream;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::ResponseStream>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SubscribeMessagesRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Relay>::subscribe_messages(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = SubscribeMessagesSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                _ => {
+                    Box::pin(async move {
+                        let mut response = http::Response::new(empty_body());
+                        let headers = response.headers_mut();
+                        headers
+                            .insert(
+                                tonic::Status::GRPC_STATUS,
+                                (tonic::Code::Unimplemented as i32).into(),
+                            );
+                        headers
+                            .insert(
+                                http::header::CONTENT_TYPE,
+                                tonic::metadata::GRPC_CONTENT_TYPE,
+                            );
+                        Ok(response)
+                    })
+                }
+            }
+        }
+    }
+    impl<T> Clone for RelayServer<T> {
+        fn clone(&self) -> Self {
+            let inner = self.inner.clone();
+            Self {
+                inner,
+                accept_compression_encodings: self.accept_compression_encodings,
+                send_compression_encodings: self.send_compression_encodings,
+                max_decoding_message_size: self.max_decoding_message_size,
+                max_encoding_message_size: self.max_encoding_message_size,
+            }
+        }
+    }
+    /// Generated gRPC service name
+    pub const SERVICE_NAME: &str = "torii.relay.Relay";
+    impl<T> tonic::server::NamedService for RelayServer<T> {
+        const NAME: &'static str = SERVICE_NAME;
+    }
+}
@@ -0,0 +1,79 @@
+//! Message propagation between relay peers.
+//!
+//! [`Relay`](crate::Relay) publishes every accepted message on a [`GossipNetwork`] and
+//! relays the messages received from it after validating them like client messages.
+//! [`LocalGossip`] connects relays running in the same process; with the `libp2p`
+//! feature, `p2p::Libp2pGossip` connects relays over the network.
+
+use async_trait::async_trait;
+use tokio::sync::broadcast;
+
+/// Encoded message received from a peer
+#[derive(Debug, Clone)]
+pub struct GossipMessage {
+    /// Sending peer
+    pub peer: String,
+    /// Encoded `torii.relay.OffchainMessage`
+    pub payload: Vec<u8>,
+}
+
+/// Publish/subscribe transport between relay peers
+#[async_trait]
+pub trait GossipNetwork: Send + Sync {
+    /// Identifier of this peer
+    fn peer_id(&self) -> &str;
+
+    /// Sends `payload` to the other peers.
+    async fn publish(&self, payload: Vec<u8>) -> anyhow::Result<()>;
+
+    /// Messages from the other peers
+    fn subscribe(&self) -> broadcast::Receiver<GossipMessage>;
+}
+
+/// In-process gossip: every [`LocalGossip::join`]ed peer receives the messages of the
+/// others
+#[derive(Clone)]
+pub struct LocalGossip {
+    peer_id: String,
+    topic: broadcast::Sender<GossipMessage>,
+}
+
+impl LocalGossip {
+    /// Creates a topic with a first peer.
+    pub fn new(peer_id: impl Into<String>, capacity: usize) -> Self {
+        let (topic, _) = broadcast::channel(capacity);
+        Self {
+            peer_id: peer_id.into(),
+            topic,
+        }
+    }
+
+    /// Another peer on the same topic
+    #[must_use]
+    pub fn join(&self, peer_id: impl Into<String>) -> Self {
+        Self {
+            peer_id: peer_id.into(),
+            topic: self.topic.clone(),
+        }
+    }
+}
+
+#[async_trait]
+impl GossipNetwork for LocalGossip {
+    fn peer_id(&self) -> &str {
+        &self.peer_id
+    }
+
+    async fn publish(&self, payload: Vec<u8>) -> anyhow::Result<()> {
+        // No receiver is not an error: the peer may be alone on the topic.
+        let _ = self.topic.send(GossipMessage {
+            peer: self.peer_id.clone(),
+            payload,
+        });
+        Ok(())
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<GossipMessage> {
+        self.topic.subscribe()
+    }
+}
@@ -0,0 +1,122 @@
+//! gRPC service of the relay (`torii.relay.Relay`).
+
+use async_trait::async_trait;
+use futures::stream::Stream;
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::StreamExt;
+use tonic::{Request, Response, Status};
+
+use crate::message::{felt_from_bytes, SignedMessage};
+use crate::proto::{
+    relay_server::Relay as RelayTrait, GetMessagesRequest, GetMessagesResponse, OffchainMessage,
+    PublishMessageRequest, PublishMessageResponse, SubscribeMessagesRequest,
+};
+use crate::relay::{matches_filters, Origin, Relay};
+use crate::storage::MessageQuery;
+use crate::RelayError;
+
+/// gRPC service implementation for the relay
+#[derive(Clone)]
+pub struct RelayService {
+    relay: Arc<Relay>,
+}
+
+impl RelayService {
+    pub fn new(relay: Arc<Relay>) -> Self {
+        Self { relay }
+    }
+}
+
+impl From<RelayError> for Status {
+    fn from(error: RelayError) -> Self {
+        match error {
+            RelayError::Invalid(message) => Status::invalid_argument(message),
+            RelayError::Unauthorized => {
+                Status::permission_denied("Signature rejected by the identity's account")
+            }
+            RelayError::Internal(e) => Status::internal(format!("Relay failed: {e}")),
+        }
+    }
+}
+
+#[async_trait]
+impl RelayTrait for RelayService {
+    async fn publish_message(
+        &self,
+        request: Request<PublishMessageRequest>,
+    ) -> Result<Response<PublishMessageResponse>, Status> {
+        let req = request.into_inner();
+        let message = SignedMessage::from_proto(OffchainMessage {
+            identity: req.identity,
+            typed_data: req.typed_data,
+            signature: req.signature,
+            ..OffchainMessage::default()
+        })?;
+        let accepted = self.relay.submit(message, Origin::Client).await?;
+
+        Ok(Response::new(PublishMessageResponse {
+            message_hash: accepted.hash.to_bytes_be().to_vec(),
+            duplicate: accepted.duplicate,
+        }))
+    }
+
+    async fn get_messages(
+        &self,
+        request: Request<GetMessagesRequest>,
+    ) -> Result<Response<GetMessagesResponse>, Status> {
+        let req = request.into_inner();
+        let query = MessageQuery {
+            identity: req
+                .identity
+                .as_deref()
+                .map(|identity| felt_from_bytes(identity, "identity"))
+                .transpose()?,
+            primary_type: req.primary_type,
+            before: req.before,
+            limit: if req.limit == 0 {
+                100
+            } else {
+                req.limit.min(1000)
+            },
+        };
+        let messages = self
+            .relay
+            .storage()
+            .messages(&query)
+            .await
+            .map_err(|e| Status::internal(format!("Query failed: {e}")))?;
+
+        Ok(Response::new(GetMessagesResponse {
+            messages: messages.iter().map(SignedMessage::to_proto).collect(),
+        }))
+    }
+
+    type SubscribeMessagesStream =
+        Pin<Box<dyn Stream<Item = Result<OffchainMessage, Status>> + Send + 'static>>;
+
+    async fn subscribe_messages(
+        &self,
+        request: Request<SubscribeMessagesRequest>,
+    ) -> Result<Response<Self::SubscribeMessagesStream>, Status> {
+        let req = request.into_inner();
+        let mut filters = HashMap::new();
+        if let Some(identity) = req.identity {
+            let identity = felt_from_bytes(&identity, "identity")?;
+            filters.insert("identity".to_string(), format!("{identity:#x}"));
+        }
+        if let Some(primary_type) = req.primary_type {
+            filters.insert("primary_type".to_string(), primary_type);
+        }
+
+        let stream =
+            BroadcastStream::new(self.relay.subscribe()).filter_map(move |result| match result {
+                Ok(message) => matches_filters(&message, &filters).then_some(Ok(message)),
+                Err(e) => Some(Err(Status::internal(format!("Broadcast error: {e}")))),
+            });
+
+        Ok(Response::new(Box::pin(stream)))
+    }
+}
@@ -0,0 +1,122 @@
+//! Offchain message relay for Torii.
+//!
+//! Game clients publish SNIP-12 typed data signed by their account through the
+//! `torii.relay.Relay` gRPC service. The [`Relay`] checks the signature against the
+//! account contract ([`AccountSignatureVerifier`]), persists the message once
+//! ([`RelayStorage`]), republishes it on the EventBus (`relay` topic) and to
+//! `SubscribeMessages` streams, and gossips it to the other relay peers
+//! ([`GossipNetwork`]), which validate and relay it in turn.
+//!
+//! [`RelaySink`] plugs the relay into Torii: it consumes no envelopes, but receives the
+//! EventBus and starts the gossip listener when Torii starts.
+
+pub mod gossip;
+pub mod grpc_service;
+pub mod message;
+#[cfg(feature = "libp2p")]
+pub mod p2p;
+pub mod relay;
+pub mod storage;
+pub mod verifier;
+
+// Include generated protobuf code
+pub mod proto {
+    include!("generated/torii.relay.rs");
+}
+
+// File descriptor set for gRPC reflection
+pub const FILE_DESCRIPTOR_SET: &[u8] = include_bytes!("generated/relay_descriptor.bin");
+
+use async_trait::async_trait;
+use std::sync::Arc;
+use torii::axum::Router;
+use torii::etl::envelope::{Envelope, TypeId};
+use torii::etl::extractor::ExtractionBatch;
+use torii::etl::sink::{EventBus, Sink, SinkContext, TopicInfo};
+use torii::ToriiResult;
+
+pub use gossip::{GossipMessage, GossipNetwork, LocalGossip};
+pub use grpc_service::RelayService;
+pub use message::SignedMessage;
+#[cfg(feature = "libp2p")]
+pub use p2p::{Libp2pGossip, Libp2pGossipConfig};
+pub use relay::{Accepted, Origin, Relay, RELAY_TOPIC};
+pub use storage::{MessageQuery, RelayStorage};
+pub use verifier::{AccountSignatureVerifier, SignatureVerifier};
+
+/// Rejected or failed message submission
+#[derive(Debug, thiserror::Error)]
+pub enum RelayError {
+    /// Malformed message (identity, typed data, signature encoding)
+    #[error("invalid message: {0}")]
+    Invalid(String),
+    /// The identity's account did not accept the signature
+    #[error("signature rejected")]
+    Unauthorized,
+    #[error(transparent)]
+    Internal(#[from] anyhow::Error),
+}
+
+/// Sink wiring a [`Relay`] into Torii
+pub struct RelaySink {
+    relay: Arc<Relay>,
+}
+
+impl RelaySink {
+    pub fn new(relay: Arc<Relay>) -> Self {
+        Self { relay }
+    }
+
+    /// gRPC service to add to the Torii router
+    pub fn grpc_service(&self) -> RelayService {
+        RelayService::new(self.relay.clone())
+    }
+}
+
+#[async_trait]
+impl Sink for RelaySink {
+    fn name(&self) -> &'static str {
+        "relay"
+    }
+
+    fn version(&self) -> Option<&str> {
+        Some(env!("CARGO_PKG_VERSION"))
+    }
+
+    fn descriptor_set(&self) -> Option<&'static [u8]> {
+        Some(FILE_DESCRIPTOR_SET)
+    }
+
+    fn interested_types(&self) -> Vec<TypeId> {
+        Vec::new()
+    }
+
+    async fn process(&self, _envelopes: &[Envelope], _batch: &ExtractionBatch) -> ToriiResult<()> {
+        Ok(())
+    }
+
+    fn topics(&self) -> Vec<TopicInfo> {
+        vec![TopicInfo::new(
+            RELAY_TOPIC,
+            vec!["identity".to_string(), "primary_type".to_string()],
+            "Signed offchain messages relayed from clients and peers",
+        )
+        .with_message_type("torii.relay.OffchainMessage")]
+    }
+
+    fn build_routes(&self) -> Router {
+        Router::new()
+    }
+
+    async fn initialize(
+        &mut self,
+        event_bus: Arc<EventBus>,
+        _context: &SinkContext,
+    ) -> ToriiResult<()> {
+        self.relay.set_event_bus(event_bus);
+        if self.relay.spawn_gossip_listener().is_some() {
+            tracing::info!(target: "torii::relay", "Relay gossip listener started");
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,153 @@
+//! Signed offchain messages.
+
+use starknet::core::types::typed_data::{InlineTypeReference, TypedData};
+use starknet::core::types::Felt;
+use torii_common::bytes_to_felt;
+
+use crate::proto;
+use crate::RelayError;
+
+/// SNIP-12 typed data signed by an account
+#[derive(Debug, Clone)]
+pub struct SignedMessage {
+    /// SNIP-12 message hash for `identity`
+    pub hash: Felt,
+    /// Signing account
+    pub identity: Felt,
+    /// Typed data as received (JSON)
+    pub typed_data: String,
+    pub primary_type: String,
+    pub signature: Vec<Felt>,
+    /// When this node first relayed the message (unix seconds)
+    pub received_at: i64,
+}
+
+impl SignedMessage {
+    /// Parses `typed_data` and computes its message hash for `identity`.
+    ///
+    /// The signature is not checked here (see [`SignatureVerifier`](crate::SignatureVerifier)).
+    pub fn parse(
+        identity: Felt,
+        typed_data: String,
+        signature: Vec<Felt>,
+    ) -> Result<Self, RelayError> {
+        if signature.is_empty() {
+            return Err(RelayError::Invalid("empty signature".to_string()));
+        }
+        let parsed: TypedData = serde_json::from_str(&typed_data)
+            .map_err(|e| RelayError::Invalid(format!("invalid typed data: {e}")))?;
+        let hash = parsed
+            .message_hash(identity)
+            .map_err(|e| RelayError::Invalid(format!("invalid typed data: {e}")))?;
+
+        Ok(Self {
+            hash,
+            identity,
+            primary_type: match parsed.primary_type() {
+                InlineTypeReference::Custom(name) => name.clone(),
+                other => format!("{other:?}"),
+            },
+            typed_data,
+            signature,
+            received_at: chrono::Utc::now().timestamp(),
+        })
+    }
+
+    /// Parses a message received from a client or a peer.
+    pub fn from_proto(message: proto::OffchainMessage) -> Result<Self, RelayError> {
+        let identity = felt_from_bytes(&message.identity, "identity")?;
+        let signature = message
+            .signature
+            .iter()
+            .map(|felt| felt_from_bytes(felt, "signature"))
+            .collect::<Result<Vec<_>, _>>()?;
+        let parsed = Self::parse(identity, message.typed_data, signature)?;
+        if !message.message_hash.is_empty()
+            && felt_from_bytes(&message.message_hash, "message_hash")? != parsed.hash
+        {
+            return Err(RelayError::Invalid(
+                "message_hash does not match the typed data".to_string(),
+            ));
+        }
+        Ok(parsed)
+    }
+
+    pub fn to_proto(&self) -> proto::OffchainMessage {
+        proto::OffchainMessage {
+            message_hash: self.hash.to_bytes_be().to_vec(),
+            identity: self.identity.to_bytes_be().to_vec(),
+            typed_data: self.typed_data.clone(),
+            signature: self
+                .signature
+                .iter()
+                .map(|felt| felt.to_bytes_be().to_vec())
+                .collect(),
+            primary_type: self.primary_type.clone(),
+            received_at: self.received_at,
+        }
+    }
+}
+
+pub(crate) fn felt_from_bytes(bytes: &[u8], field: &str) -> Result<Felt, RelayError> {
+    bytes_to_felt(bytes).ok_or_else(|| RelayError::Invalid(format!("invalid {field}")))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) const TYPED_DATA: &str = r#"{
+        "types": {
+            "StarknetDomain": [
+                {"name": "name", "type": "shortstring"},
+                {"name": "version", "type": "shortstring"},
+                {"name": "chainId", "type": "shortstring"},
+                {"name": "revision", "type": "shortstring"}
+            ],
+            "Chat": [
+                {"name": "room", "type": "shortstring"},
+                {"name": "text", "type": "shortstring"}
+            ]
+        },
+        "primaryType": "Chat",
+        "domain": {"name": "game", "version": "1", "chainId": "SN_SEPOLIA", "revision": "1"},
+        "message": {"room": "lobby", "text": "gg"}
+    }"#;
+
+    #[test]
+    fn parses_typed_data_and_round_trips_through_proto() {
+        let identity = Felt::from(0x123_u64);
+        let message =
+            SignedMessage::parse(identity, TYPED_DATA.to_string(), vec![Felt::ONE, Felt::TWO])
+                .unwrap();
+        assert_eq!(message.primary_type, "Chat");
+        assert_ne!(message.hash, Felt::ZERO);
+
+        // The hash binds the signer.
+        let other = SignedMessage::parse(
+            Felt::from(0x456_u64),
+            TYPED_DATA.to_string(),
+            vec![Felt::ONE],
+        )
+        .unwrap();
+        assert_ne!(other.hash, message.hash);
+
+        let decoded = SignedMessage::from_proto(message.to_proto()).unwrap();
+        assert_eq!(decoded.hash, message.hash);
+        assert_eq!(decoded.signature, message.signature);
+
+        let mut tampered = message.to_proto();
+        tampered.message_hash = Felt::ONE.to_bytes_be().to_vec();
+        assert!(matches!(
+            SignedMessage::from_proto(tampered),
+            Err(RelayError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_malformed_messages() {
+        let identity = Felt::from(0x123_u64);
+        assert!(SignedMessage::parse(identity, TYPED_DATA.to_string(), Vec::new()).is_err());
+        assert!(SignedMessage::parse(identity, "{}".to_string(), vec![Felt::ONE]).is_err());
+    }
+}
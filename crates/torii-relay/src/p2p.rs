@@ -0,0 +1,275 @@
+//! libp2p transport between relay peers (`libp2p` feature).
+//!
+//! [`Libp2pGossip`] runs a gossipsub swarm over TCP (noise, yamux) on a background
+//! task. Every peer subscribes to the same topic and dials its bootstrap peers;
+//! gossipsub then forwards the published messages through the mesh, so peers only
+//! need a path to each other, not a direct connection.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use futures::StreamExt;
+use libp2p::gossipsub::{self, IdentTopic, MessageAuthenticity, PublishError, ValidationMode};
+use libp2p::swarm::SwarmEvent;
+use libp2p::{identity, noise, tcp, yamux, Multiaddr, Swarm, SwarmBuilder};
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc, oneshot, watch};
+
+use crate::gossip::{GossipMessage, GossipNetwork};
+
+/// Gossipsub topic of relayed messages
+pub const DEFAULT_TOPIC: &str = "torii-relay";
+
+/// [`Libp2pGossip`] configuration
+#[derive(Clone)]
+pub struct Libp2pGossipConfig {
+    /// Identity of the peer (a new ed25519 key if unset)
+    pub keypair: Option<identity::Keypair>,
+    /// Addresses to listen on (e.g. `/ip4/0.0.0.0/tcp/9900`); none to only dial out
+    pub listen_addrs: Vec<Multiaddr>,
+    /// Peers dialed on start to join the mesh
+    pub bootstrap_peers: Vec<Multiaddr>,
+    /// Gossipsub topic, shared by all the peers of a network
+    pub topic: String,
+    /// Messages buffered for slow subscribers
+    pub capacity: usize,
+    /// Gossipsub heartbeat (mesh maintenance) interval
+    pub heartbeat_interval: Duration,
+}
+
+impl Default for Libp2pGossipConfig {
+    fn default() -> Self {
+        Self {
+            keypair: None,
+            listen_addrs: Vec::new(),
+            bootstrap_peers: Vec::new(),
+            topic: DEFAULT_TOPIC.to_string(),
+            capacity: 1024,
+            heartbeat_interval: Duration::from_secs(1),
+        }
+    }
+}
+
+type PublishRequest = (Vec<u8>, oneshot::Sender<Result<()>>);
+
+/// Gossipsub peer; the swarm stops when the last handle is dropped
+#[derive(Clone)]
+pub struct Libp2pGossip {
+    peer_id: String,
+    commands: mpsc::Sender<PublishRequest>,
+    messages: broadcast::Sender<GossipMessage>,
+    listen_addrs: watch::Receiver<Vec<Multiaddr>>,
+}
+
+impl Libp2pGossip {
+    /// Starts the swarm on the current Tokio runtime: listens on the configured
+    /// addresses, subscribes to the topic and dials the bootstrap peers.
+    pub fn spawn(config: Libp2pGossipConfig) -> Result<Self> {
+        let keypair = config
+            .keypair
+            .unwrap_or_else(identity::Keypair::generate_ed25519);
+        let heartbeat_interval = config.heartbeat_interval;
+        let mut swarm = SwarmBuilder::with_existing_identity(keypair)
+            .with_tokio()
+            .with_tcp(
+                tcp::Config::default(),
+                noise::Config::new,
+                yamux::Config::default,
+            )
+            .context("Failed to set up the gossip transport")?
+            .with_behaviour(
+                |key| -> Result<gossipsub::Behaviour, Box<dyn std::error::Error + Send + Sync>> {
+                    let gossipsub_config = gossipsub::ConfigBuilder::default()
+                        .heartbeat_interval(heartbeat_interval)
+                        .validation_mode(ValidationMode::Strict)
+                        .build()
+                        .map_err(|e| e.to_string())?;
+                    Ok(gossipsub::Behaviour::new(
+                        MessageAuthenticity::Signed(key.clone()),
+                        gossipsub_config,
+                    )?)
+                },
+            )
+            .context("Failed to set up gossipsub")?
+            .with_swarm_config(|swarm_config| {
+                swarm_config.with_idle_connection_timeout(Duration::from_secs(60))
+            })
+            .build();
+
+        let topic = IdentTopic::new(config.topic);
+        swarm
+            .behaviour_mut()
+            .subscribe(&topic)
+            .context("Failed to subscribe to the gossip topic")?;
+        for address in config.listen_addrs {
+            swarm
+                .listen_on(address.clone())
+                .with_context(|| format!("Failed to listen on {address}"))?;
+        }
+        for address in config.bootstrap_peers {
+            swarm
+                .dial(address.clone())
+                .with_context(|| format!("Failed to dial bootstrap peer {address}"))?;
+        }
+
+        let peer_id = swarm.local_peer_id().to_string();
+        let capacity = config.capacity.max(1);
+        let (commands, command_rx) = mpsc::channel(capacity);
+        let (messages, _) = broadcast::channel(capacity);
+        let (listen_tx, listen_addrs) = watch::channel(Vec::new());
+        tokio::spawn(run_swarm(
+            swarm,
+            topic,
+            command_rx,
+            messages.clone(),
+            listen_tx,
+        ));
+
+        Ok(Self {
+            peer_id,
+            commands,
+            messages,
+            listen_addrs,
+        })
+    }
+
+    /// Addresses the swarm listens on so far
+    pub fn listen_addrs(&self) -> Vec<Multiaddr> {
+        self.listen_addrs.borrow().clone()
+    }
+
+    /// Waits until the swarm listens on at least one address, and returns them.
+    pub async fn wait_listening(&self) -> Result<Vec<Multiaddr>> {
+        let mut listen_addrs = self.listen_addrs.clone();
+        let addresses = listen_addrs
+            .wait_for(|addresses| !addresses.is_empty())
+            .await
+            .context("Gossip swarm stopped")?;
+        Ok(addresses.clone())
+    }
+}
+
+#[async_trait]
+impl GossipNetwork for Libp2pGossip {
+    fn peer_id(&self) -> &str {
+        &self.peer_id
+    }
+
+    async fn publish(&self, payload: Vec<u8>) -> Result<()> {
+        let (reply, result) = oneshot::channel();
+        self.commands
+            .send((payload, reply))
+            .await
+            .context("Gossip swarm stopped")?;
+        result.await.context("Gossip swarm stopped")?
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<GossipMessage> {
+        self.messages.subscribe()
+    }
+}
+
+async fn run_swarm(
+    mut swarm: Swarm<gossipsub::Behaviour>,
+    topic: IdentTopic,
+    mut commands: mpsc::Receiver<PublishRequest>,
+    messages: broadcast::Sender<GossipMessage>,
+    listen_addrs: watch::Sender<Vec<Multiaddr>>,
+) {
+    loop {
+        tokio::select! {
+            command = commands.recv() => {
+                let Some((payload, reply)) = command else {
+                    break;
+                };
+                let result = match swarm.behaviour_mut().publish(topic.clone(), payload) {
+                    // Like `LocalGossip`, being alone on the topic is not an error.
+                    Ok(_) | Err(PublishError::InsufficientPeers | PublishError::Duplicate) => Ok(()),
+                    Err(e) => Err(anyhow::anyhow!("Failed to publish gossip message: {e}")),
+                };
+                let _ = reply.send(result);
+            }
+            event = swarm.select_next_some() => match event {
+                SwarmEvent::Behaviour(gossipsub::Event::Message {
+                    propagation_source,
+                    message,
+                    ..
+                }) => {
+                    let peer = message.source.unwrap_or(propagation_source);
+                    // No subscriber is not an error: the relay may not listen yet.
+                    let _ = messages.send(GossipMessage {
+                        peer: peer.to_string(),
+                        payload: message.data,
+                    });
+                }
+                SwarmEvent::NewListenAddr { address, .. } => {
+                    tracing::info!(target: "torii::relay", %address, "Gossip listening");
+                    listen_addrs.send_modify(|addresses| addresses.push(address));
+                }
+                SwarmEvent::ConnectionEstablished { peer_id, .. } => {
+                    tracing::debug!(target: "torii::relay", peer = %peer_id, "Gossip peer connected");
+                }
+                SwarmEvent::OutgoingConnectionError { peer_id, error, .. } => {
+                    tracing::warn!(
+                        target: "torii::relay",
+                        peer = ?peer_id,
+                        error = %error,
+                        "Failed to connect to gossip peer"
+                    );
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Publishes `payload` from `from` until `to` receives it: the mesh forms within a
+    /// few heartbeats, and messages published before are not delivered.
+    async fn gossip(from: &Libp2pGossip, to: &Libp2pGossip, payload: &[u8]) -> GossipMessage {
+        let mut received = to.subscribe();
+        tokio::time::timeout(Duration::from_secs(10), async {
+            loop {
+                from.publish(payload.to_vec()).await.unwrap();
+                if let Ok(Ok(message)) =
+                    tokio::time::timeout(Duration::from_millis(200), received.recv()).await
+                {
+                    break message;
+                }
+            }
+        })
+        .await
+        .expect("message not gossiped")
+    }
+
+    #[tokio::test]
+    async fn swarms_exchange_messages() {
+        let config = Libp2pGossipConfig {
+            heartbeat_interval: Duration::from_millis(100),
+            ..Default::default()
+        };
+        let a = Libp2pGossip::spawn(Libp2pGossipConfig {
+            listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".parse().unwrap()],
+            ..config.clone()
+        })
+        .unwrap();
+        let a_addrs = a.wait_listening().await.unwrap();
+        // b only dials out.
+        let b = Libp2pGossip::spawn(Libp2pGossipConfig {
+            bootstrap_peers: a_addrs,
+            ..config
+        })
+        .unwrap();
+        assert_ne!(a.peer_id(), b.peer_id());
+
+        let message = gossip(&a, &b, b"hello").await;
+        assert_eq!(message.peer, a.peer_id());
+        assert_eq!(message.payload, b"hello");
+
+        let message = gossip(&b, &a, b"hi").await;
+        assert_eq!(message.peer, b.peer_id());
+        assert_eq!(message.payload, b"hi");
+    }
+}
@@ -0,0 +1,370 @@
+//! Relay core: validation, persistence and fan-out of offchain messages.
+
+use prost::Message;
+use prost_types::Any;
+use starknet::core::types::Felt;
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use torii::etl::sink::EventBus;
+use torii::grpc::UpdateType;
+
+use crate::gossip::GossipNetwork;
+use crate::message::SignedMessage;
+use crate::storage::RelayStorage;
+use crate::verifier::SignatureVerifier;
+use crate::{proto, RelayError};
+
+/// EventBus topic of relayed messages
+pub const RELAY_TOPIC: &str = "relay";
+
+/// Where a submitted message comes from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Origin {
+    /// A client of this node (PublishMessage)
+    Client,
+    /// Another relay peer
+    Peer(String),
+}
+
+impl Origin {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Client => "client",
+            Self::Peer(_) => "peer",
+        }
+    }
+}
+
+/// Outcome of an accepted submission
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Accepted {
+    pub hash: Felt,
+    /// The message had already been relayed (it is not relayed again)
+    pub duplicate: bool,
+}
+
+/// Offchain message relay
+///
+/// Messages submitted by clients or received from peers are checked with the
+/// [`SignatureVerifier`], stored once, then republished on the EventBus
+/// ([`RELAY_TOPIC`]), to SubscribeMessages streams and, for client messages, to peers.
+pub struct Relay {
+    storage: RelayStorage,
+    verifier: Arc<dyn SignatureVerifier>,
+    gossip: Option<Arc<dyn GossipNetwork>>,
+    event_bus: OnceLock<Arc<EventBus>>,
+    updates: broadcast::Sender<proto::OffchainMessage>,
+}
+
+impl Relay {
+    pub fn new(storage: RelayStorage, verifier: Arc<dyn SignatureVerifier>) -> Self {
+        let (updates, _) = broadcast::channel(1000);
+        Self {
+            storage,
+            verifier,
+            gossip: None,
+            event_bus: OnceLock::new(),
+            updates,
+        }
+    }
+
+    /// Exchanges messages with the peers of `gossip`
+    #[must_use]
+    pub fn with_gossip(mut self, gossip: Arc<dyn GossipNetwork>) -> Self {
+        self.gossip = Some(gossip);
+        self
+    }
+
+    pub fn storage(&self) -> &RelayStorage {
+        &self.storage
+    }
+
+    /// Relayed messages, as they are accepted
+    pub fn subscribe(&self) -> broadcast::Receiver<proto::OffchainMessage> {
+        self.updates.subscribe()
+    }
+
+    pub(crate) fn set_event_bus(&self, event_bus: Arc<EventBus>) {
+        let _ = self.event_bus.set(event_bus);
+    }
+
+    /// Validates, stores and relays `message`.
+    pub async fn submit(
+        &self,
+        message: SignedMessage,
+        origin: Origin,
+    ) -> Result<Accepted, RelayError> {
+        let result = self.try_submit(message, &origin).await;
+        let outcome = match &result {
+            Ok(Accepted {
+                duplicate: true, ..
+            }) => "duplicate",
+            Ok(_) => "accepted",
+            Err(RelayError::Invalid(_)) => "invalid",
+            Err(RelayError::Unauthorized) => "unauthorized",
+            Err(RelayError::Internal(_)) => "error",
+        };
+        ::metrics::counter!(
+            "torii_relay_messages_total",
+            "origin" => origin.label(),
+            "outcome" => outcome
+        )
+        .increment(1);
+        result
+    }
+
+    async fn try_submit(
+        &self,
+        message: SignedMessage,
+        origin: &Origin,
+    ) -> Result<Accepted, RelayError> {
+        let hash = message.hash;
+        // Peers keep gossiping messages around: skip the RPC verification of known ones.
+        if self.storage.contains(hash).await? {
+            return Ok(Accepted {
+                hash,
+                duplicate: true,
+            });
+        }
+        if !self.verifier.verify(&message).await? {
+            return Err(RelayError::Unauthorized);
+        }
+        if !self.storage.insert(&message).await? {
+            return Ok(Accepted {
+                hash,
+                duplicate: true,
+            });
+        }
+
+        let proto_message = message.to_proto();
+        tracing::debug!(
+            target: "torii::relay",
+            hash = %format!("{hash:#x}"),
+            identity = %format!("{:#x}", message.identity),
+            primary_type = %message.primary_type,
+            origin = origin.label(),
+            "Message relayed"
+        );
+
+        if let Some(event_bus) = self.event_bus.get() {
+            let any = Any {
+                type_url: "type.googleapis.com/torii.relay.OffchainMessage".to_string(),
+                value: proto_message.encode_to_vec(),
+            };
+            event_bus.publish_protobuf(
+                RELAY_TOPIC,
+                "relay.message",
+                &any,
+                &proto_message,
+                UpdateType::Created,
+                matches_filters,
+            );
+        }
+        let _ = self.updates.send(proto_message.clone());
+
+        if *origin == Origin::Client {
+            if let Some(gossip) = &self.gossip {
+                if let Err(e) = gossip.publish(proto_message.encode_to_vec()).await {
+                    tracing::warn!(
+                        target: "torii::relay",
+                        error = %e,
+                        "Failed to gossip message"
+                    );
+                }
+            }
+        }
+
+        Ok(Accepted {
+            hash,
+            duplicate: false,
+        })
+    }
+
+    /// Spawns the task relaying the messages received from peers, if gossip is enabled.
+    pub fn spawn_gossip_listener(self: &Arc<Self>) -> Option<JoinHandle<()>> {
+        let gossip = self.gossip.clone()?;
+        let relay = self.clone();
+        let mut messages = gossip.subscribe();
+        Some(tokio::spawn(async move {
+            loop {
+                let received = match messages.recv().await {
+                    Ok(received) => received,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!(
+                            target: "torii::relay",
+                            skipped,
+                            "Gossip listener lagged, messages dropped"
+                        );
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+                if received.peer == gossip.peer_id() {
+                    continue;
+                }
+
+                let message = proto::OffchainMessage::decode(received.payload.as_slice())
+                    .map_err(|e| RelayError::Invalid(format!("undecodable message: {e}")))
+                    .and_then(SignedMessage::from_proto);
+                let result = match message {
+                    Ok(message) => {
+                        relay
+                            .submit(message, Origin::Peer(received.peer.clone()))
+                            .await
+                    }
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = result {
+                    tracing::debug!(
+                        target: "torii::relay",
+                        peer = %received.peer,
+                        error = %e,
+                        "Rejected message from peer"
+                    );
+                }
+            }
+        }))
+    }
+}
+
+/// EventBus / SubscribeMessages filters: `identity` (hex) and `primary_type`
+pub(crate) fn matches_filters(
+    message: &proto::OffchainMessage,
+    filters: &HashMap<String, String>,
+) -> bool {
+    if let Some(identity) = filters.get("identity") {
+        let Ok(identity) = Felt::from_hex(identity) else {
+            return false;
+        };
+        if message.identity != identity.to_bytes_be() {
+            return false;
+        }
+    }
+    if let Some(primary_type) = filters.get("primary_type") {
+        if message.primary_type != *primary_type {
+            return false;
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gossip::LocalGossip;
+    use crate::message::tests::TYPED_DATA;
+    use crate::storage::MessageQuery;
+    use async_trait::async_trait;
+    use std::time::Duration;
+
+    /// Accepts signatures starting with `1`
+    struct FlagVerifier;
+
+    #[async_trait]
+    impl SignatureVerifier for FlagVerifier {
+        async fn verify(&self, message: &SignedMessage) -> anyhow::Result<bool> {
+            Ok(message.signature.first() == Some(&Felt::ONE))
+        }
+    }
+
+    async fn relay(gossip: Option<LocalGossip>) -> Arc<Relay> {
+        let storage = RelayStorage::open("sqlite::memory:").await.unwrap();
+        let mut relay = Relay::new(storage, Arc::new(FlagVerifier));
+        if let Some(gossip) = gossip {
+            relay = relay.with_gossip(Arc::new(gossip));
+        }
+        Arc::new(relay)
+    }
+
+    fn message(signature: Felt) -> SignedMessage {
+        SignedMessage::parse(
+            Felt::from(0x123_u64),
+            TYPED_DATA.to_string(),
+            vec![signature],
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn accepts_valid_messages_once() {
+        let relay = relay(None).await;
+        let mut updates = relay.subscribe();
+
+        assert!(matches!(
+            relay.submit(message(Felt::TWO), Origin::Client).await,
+            Err(RelayError::Unauthorized)
+        ));
+
+        let accepted = relay
+            .submit(message(Felt::ONE), Origin::Client)
+            .await
+            .unwrap();
+        assert!(!accepted.duplicate);
+        let duplicate = relay
+            .submit(message(Felt::ONE), Origin::Client)
+            .await
+            .unwrap();
+        assert!(duplicate.duplicate);
+        assert_eq!(duplicate.hash, accepted.hash);
+
+        let update = updates.try_recv().unwrap();
+        assert_eq!(update.message_hash, accepted.hash.to_bytes_be().to_vec());
+        assert!(updates.try_recv().is_err());
+
+        let stored = relay
+            .storage()
+            .messages(&MessageQuery {
+                identity: Some(Felt::from(0x123_u64)),
+                limit: 10,
+                ..MessageQuery::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored[0].primary_type, "Chat");
+        assert_eq!(stored[0].signature, vec![Felt::ONE]);
+    }
+
+    #[tokio::test]
+    async fn relays_messages_between_peers() {
+        let gossip = LocalGossip::new("a", 16);
+        let a = relay(Some(gossip.clone())).await;
+        let b = relay(Some(gossip.join("b"))).await;
+        let _listeners = (a.spawn_gossip_listener(), b.spawn_gossip_listener());
+        let mut b_updates = b.subscribe();
+
+        let accepted = a.submit(message(Felt::ONE), Origin::Client).await.unwrap();
+        let relayed = tokio::time::timeout(Duration::from_secs(5), b_updates.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(relayed.message_hash, accepted.hash.to_bytes_be().to_vec());
+        assert!(b.storage().contains(accepted.hash).await.unwrap());
+    }
+
+    #[test]
+    fn filters_by_identity_and_primary_type() {
+        let message = message(Felt::ONE).to_proto();
+        let filters = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+                .collect::<HashMap<_, _>>()
+        };
+        assert!(matches_filters(&message, &filters(&[])));
+        assert!(matches_filters(
+            &message,
+            &filters(&[("identity", "0x123"), ("primary_type", "Chat")])
+        ));
+        assert!(!matches_filters(
+            &message,
+            &filters(&[("identity", "0x456")])
+        ));
+        assert!(!matches_filters(
+            &message,
+            &filters(&[("primary_type", "Trade")])
+        ));
+    }
+}
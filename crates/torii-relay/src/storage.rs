@@ -0,0 +1,139 @@
+//! Persistence of relayed messages (SQLite or PostgreSQL).
+
+use anyhow::{Context, Result};
+use sqlx::any::{AnyPoolOptions, AnyRow};
+use sqlx::{Any, Pool, QueryBuilder, Row};
+use starknet::core::types::Felt;
+
+use crate::message::SignedMessage;
+
+const CREATE_TABLE: &str = "CREATE TABLE IF NOT EXISTS relay_messages (
+    message_hash TEXT PRIMARY KEY,
+    identity TEXT NOT NULL,
+    primary_type TEXT NOT NULL,
+    typed_data TEXT NOT NULL,
+    signature TEXT NOT NULL,
+    received_at BIGINT NOT NULL
+)";
+
+const CREATE_INDEX: &str = "CREATE INDEX IF NOT EXISTS idx_relay_messages_identity
+    ON relay_messages (identity, received_at)";
+
+/// Filter of [`RelayStorage::messages`]
+#[derive(Debug, Clone, Default)]
+pub struct MessageQuery {
+    pub identity: Option<Felt>,
+    pub primary_type: Option<String>,
+    /// Only messages received strictly before this timestamp
+    pub before: Option<i64>,
+    pub limit: u32,
+}
+
+/// Relayed messages, keyed by message hash
+#[derive(Clone)]
+pub struct RelayStorage {
+    pool: Pool<Any>,
+}
+
+impl RelayStorage {
+    /// Opens the store at `url` (PostgreSQL URL, `sqlite:` URL or SQLite file path).
+    pub async fn open(url: &str) -> Result<Self> {
+        sqlx::any::install_default_drivers();
+        let url = if url.starts_with("postgres://")
+            || url.starts_with("postgresql://")
+            || url.starts_with("sqlite:")
+        {
+            url.to_string()
+        } else {
+            format!("sqlite://{url}?mode=rwc")
+        };
+        let max_connections = if url.contains(":memory:") { 1 } else { 4 };
+        let pool = AnyPoolOptions::new()
+            .max_connections(max_connections)
+            .connect(&url)
+            .await
+            .context("Failed to connect to the relay database")?;
+        sqlx::query(CREATE_TABLE).execute(&pool).await?;
+        sqlx::query(CREATE_INDEX).execute(&pool).await?;
+        Ok(Self { pool })
+    }
+
+    /// Stores `message`, returning `false` if it was already stored.
+    pub async fn insert(&self, message: &SignedMessage) -> Result<bool> {
+        let signature: Vec<String> = message
+            .signature
+            .iter()
+            .map(|felt| format!("{felt:#x}"))
+            .collect();
+        let result = sqlx::query(
+            "INSERT INTO relay_messages
+                (message_hash, identity, primary_type, typed_data, signature, received_at)
+             VALUES ($1, $2, $3, $4, $5, $6)
+             ON CONFLICT (message_hash) DO NOTHING",
+        )
+        .bind(format!("{:#x}", message.hash))
+        .bind(format!("{:#x}", message.identity))
+        .bind(message.primary_type.as_str())
+        .bind(message.typed_data.as_str())
+        .bind(serde_json::to_string(&signature)?)
+        .bind(message.received_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Whether a message with this hash was stored
+    pub async fn contains(&self, hash: Felt) -> Result<bool> {
+        let row = sqlx::query("SELECT 1 FROM relay_messages WHERE message_hash = $1")
+            .bind(format!("{hash:#x}"))
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(row.is_some())
+    }
+
+    /// Messages matching `query`, newest first
+    pub async fn messages(&self, query: &MessageQuery) -> Result<Vec<SignedMessage>> {
+        let mut builder = QueryBuilder::<Any>::new(
+            "SELECT message_hash, identity, primary_type, typed_data, signature, received_at
+             FROM relay_messages WHERE 1 = 1",
+        );
+        if let Some(identity) = query.identity {
+            builder
+                .push(" AND identity = ")
+                .push_bind(format!("{identity:#x}"));
+        }
+        if let Some(primary_type) = &query.primary_type {
+            builder
+                .push(" AND primary_type = ")
+                .push_bind(primary_type.clone());
+        }
+        if let Some(before) = query.before {
+            builder.push(" AND received_at < ").push_bind(before);
+        }
+        builder
+            .push(" ORDER BY received_at DESC, message_hash LIMIT ")
+            .push_bind(i64::from(query.limit));
+
+        let rows = builder.build().fetch_all(&self.pool).await?;
+        rows.iter().map(message_from_row).collect()
+    }
+}
+
+fn message_from_row(row: &AnyRow) -> Result<SignedMessage> {
+    let felt = |column: &str| -> Result<Felt> {
+        let value: String = row.try_get(column)?;
+        Felt::from_hex(&value).with_context(|| format!("Invalid {column} {value}"))
+    };
+    let signature: Vec<String> = serde_json::from_str(&row.try_get::<String, _>("signature")?)?;
+    Ok(SignedMessage {
+        hash: felt("message_hash")?,
+        identity: felt("identity")?,
+        primary_type: row.try_get("primary_type")?,
+        typed_data: row.try_get("typed_data")?,
+        signature: signature
+            .iter()
+            .map(|felt| Felt::from_hex(felt))
+            .collect::<Result<_, _>>()?,
+        received_at: row.try_get("received_at")?,
+    })
+}
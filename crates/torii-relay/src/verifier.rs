@@ -0,0 +1,99 @@
+//! Signature verification against account contracts.
+
+use async_trait::async_trait;
+use starknet::core::types::{BlockId, BlockTag, Felt, FunctionCall};
+use starknet::macros::{selector, short_string};
+use starknet::providers::Provider;
+use std::collections::HashSet;
+use std::sync::{Arc, RwLock};
+
+use crate::message::SignedMessage;
+
+/// SNIP-6 `is_valid_signature` success value (`'VALID'`)
+const VALID: Felt = short_string!("VALID");
+
+/// Checks that a message was signed by its identity
+#[async_trait]
+pub trait SignatureVerifier: Send + Sync {
+    /// Whether `message.signature` is a valid signature of `message.hash` by
+    /// `message.identity`. Errors are for verifications that could not run (RPC failures).
+    async fn verify(&self, message: &SignedMessage) -> anyhow::Result<bool>;
+}
+
+/// Verifies signatures by calling `is_valid_signature` on the identity's account contract
+///
+/// Accounts without the SNIP-6 entrypoint are tried with the legacy `isValidSignature`
+/// (returning `1`). A reverted call (undeployed account, wrong signature length, ...) is an
+/// invalid signature.
+pub struct AccountSignatureVerifier<P> {
+    provider: Arc<P>,
+    known_accounts: Option<Arc<RwLock<HashSet<Felt>>>>,
+}
+
+impl<P> AccountSignatureVerifier<P> {
+    pub fn new(provider: Arc<P>) -> Self {
+        Self {
+            provider,
+            known_accounts: None,
+        }
+    }
+
+    /// Only accepts messages from `accounts` (e.g. the accounts indexed by a sink),
+    /// without calling the chain for other identities.
+    ///
+    /// The set is shared: accounts added later are accepted from then on.
+    #[must_use]
+    pub fn with_known_accounts(mut self, accounts: Arc<RwLock<HashSet<Felt>>>) -> Self {
+        self.known_accounts = Some(accounts);
+        self
+    }
+}
+
+#[async_trait]
+impl<P> SignatureVerifier for AccountSignatureVerifier<P>
+where
+    P: Provider + Send + Sync,
+{
+    async fn verify(&self, message: &SignedMessage) -> anyhow::Result<bool> {
+        if let Some(accounts) = &self.known_accounts {
+            if !accounts.read().unwrap().contains(&message.identity) {
+                return Ok(false);
+            }
+        }
+
+        let mut calldata = vec![message.hash, Felt::from(message.signature.len())];
+        calldata.extend_from_slice(&message.signature);
+
+        for entry_point_selector in [
+            selector!("is_valid_signature"),
+            selector!("isValidSignature"),
+        ] {
+            let call = FunctionCall {
+                contract_address: message.identity,
+                entry_point_selector,
+                calldata: calldata.clone(),
+            };
+            match self
+                .provider
+                .call(call, BlockId::Tag(BlockTag::Latest))
+                .await
+            {
+                Ok(result) => {
+                    return Ok(
+                        matches!(result.first(), Some(value) if *value == VALID || *value == Felt::ONE),
+                    );
+                }
+                Err(starknet::providers::ProviderError::StarknetError(e)) => {
+                    tracing::debug!(
+                        target: "torii::relay",
+                        identity = %format!("{:#x}", message.identity),
+                        error = %e,
+                        "Signature check reverted"
+                    );
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(false)
+    }
+}
@@ -78,6 +78,14 @@ pub struct TokenDbSetup {
     pub erc20_url: String,
     pub erc721_url: String,
     pub erc1155_url: String,
+    /// Address labels store (shares the token storage backend)
+    pub labels_url: String,
+    /// Offchain message relay store (shares the token storage backend)
+    pub relay_url: String,
+    /// Account activity store (shares the token storage backend)
+    pub accounts_url: String,
+    /// Token metadata cache (shares the token storage backend)
+    pub metadata_cache_url: String,
     pub engine_backend: DatabaseBackend,
     pub erc20_backend: DatabaseBackend,
     pub erc721_backend: DatabaseBackend,
@@ -111,6 +119,30 @@ pub fn resolve_token_db_setup(
         db_dir,
         "erc1155.db",
     );
+    let labels_url = resolve_storage_url(
+        storage_database_url,
+        engine_database_url,
+        db_dir,
+        "labels.db",
+    );
+    let relay_url = resolve_storage_url(
+        storage_database_url,
+        engine_database_url,
+        db_dir,
+        "relay.db",
+    );
+    let accounts_url = resolve_storage_url(
+        storage_database_url,
+        engine_database_url,
+        db_dir,
+        "accounts.db",
+    );
+    let metadata_cache_url = resolve_storage_url(
+        storage_database_url,
+        engine_database_url,
+        db_dir,
+        "metadata_cache.db",
+    );
 
     let engine_backend = backend_from_url_or_path(&engine_url);
     let erc20_backend = backend_from_url_or_path(&erc20_url);
@@ -134,6 +166,10 @@ pub fn resolve_token_db_setup(
         erc20_url,
         erc721_url,
         erc1155_url,
+        labels_url,
+        relay_url,
+        accounts_url,
+        metadata_cache_url,
         engine_backend,
         erc20_backend,
         erc721_backend,
@@ -165,6 +201,10 @@ mod tests {
         assert_eq!(setup.erc20_backend, DatabaseBackend::Sqlite);
         assert!(setup.engine_url.ends_with("engine.db"));
         assert!(setup.erc20_url.ends_with("erc20.db"));
+        assert!(setup.labels_url.ends_with("labels.db"));
+        assert!(setup.relay_url.ends_with("relay.db"));
+        assert!(setup.accounts_url.ends_with("accounts.db"));
+        assert!(setup.metadata_cache_url.ends_with("metadata_cache.db"));
     }
 
     #[test]
@@ -192,6 +232,7 @@ mod tests {
         .unwrap();
         assert_eq!(setup.engine_backend, DatabaseBackend::Postgres);
         assert_eq!(setup.erc721_backend, DatabaseBackend::Postgres);
+        assert_eq!(setup.labels_url, "postgres://localhost/torii");
     }
 
     #[test]
@@ -0,0 +1,200 @@
+//! Envelope documents and `_bulk` request encoding
+//!
+//! Each envelope becomes one document in the index of its type. The envelope id is
+//! used as the document id, so re-indexing a block range overwrites documents instead
+//! of duplicating them.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Time suffix appended to index names
+///
+/// With [`IndexRollover::None`] documents are written to `<prefix>-<type>`, which can
+/// be a write alias rolled over by an ILM/ISM policy. The dated variants write to
+/// `<prefix>-<type>-<date>` so retention can be handled by deleting old indices.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IndexRollover {
+    #[default]
+    None,
+    /// `<prefix>-<type>-YYYY.MM.DD`
+    Daily,
+    /// `<prefix>-<type>-YYYY.MM`
+    Monthly,
+}
+
+impl IndexRollover {
+    /// Index of a document of type `type_name` timestamped `timestamp`.
+    pub fn index_name(self, prefix: &str, type_name: &str, timestamp: DateTime<Utc>) -> String {
+        let base = format!("{}-{}", sanitize(prefix), sanitize(type_name));
+        match self {
+            Self::None => base,
+            Self::Daily => format!("{base}-{}", timestamp.format("%Y.%m.%d")),
+            Self::Monthly => format!("{base}-{}", timestamp.format("%Y.%m")),
+        }
+    }
+}
+
+/// Lowercases `name` and replaces characters not allowed in index names.
+fn sanitize(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| match c.to_ascii_lowercase() {
+            c @ ('a'..='z' | '0'..='9' | '_' | '-') => c,
+            _ => '-',
+        })
+        .collect();
+    name.trim_start_matches(['-', '_']).to_string()
+}
+
+/// Indexed envelope
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EnvelopeDocument {
+    /// Block timestamp, or the envelope creation time when the block is unknown
+    #[serde(rename = "@timestamp")]
+    pub timestamp: DateTime<Utc>,
+    pub envelope_id: String,
+    /// Registered name of the envelope type
+    #[serde(rename = "type")]
+    pub type_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_number: Option<u64>,
+    /// Emitting contract (hex)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_address: Option<String>,
+    pub metadata: BTreeMap<String, String>,
+    /// Serialized body, when the type has a serializer
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+}
+
+/// Document and the index it is written to
+#[derive(Debug, Clone, PartialEq)]
+pub struct BulkAction {
+    pub index: String,
+    pub document: EnvelopeDocument,
+}
+
+/// Encodes `actions` as an NDJSON `_bulk` body of `index` operations.
+pub fn encode_bulk(actions: &[BulkAction]) -> serde_json::Result<String> {
+    let mut body = String::new();
+    for action in actions {
+        body.push_str(&serde_json::to_string(&serde_json::json!({
+            "index": { "_index": action.index, "_id": action.document.envelope_id }
+        }))?);
+        body.push('\n');
+        body.push_str(&serde_json::to_string(&action.document)?);
+        body.push('\n');
+    }
+    Ok(body)
+}
+
+/// Item-level failures of a `_bulk` response
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct BulkFailures {
+    pub count: usize,
+    /// Reason of the first failed item
+    pub first_reason: Option<String>,
+}
+
+/// Collects the failed items of a `_bulk` response.
+///
+/// The response is only scanned when its `errors` flag is set.
+pub fn bulk_failures(response: &Value) -> BulkFailures {
+    let mut failures = BulkFailures::default();
+    if !response["errors"].as_bool().unwrap_or(false) {
+        return failures;
+    }
+    let items = response["items"].as_array().map_or(&[][..], Vec::as_slice);
+    for item in items {
+        let Some(error) = item
+            .as_object()
+            .and_then(|item| item.values().next())
+            .map(|result| &result["error"])
+            .filter(|error| !error.is_null())
+        else {
+            continue;
+        };
+        failures.count += 1;
+        if failures.first_reason.is_none() {
+            failures.first_reason = Some(
+                error["reason"]
+                    .as_str()
+                    .map_or_else(|| error.to_string(), ToString::to_string),
+            );
+        }
+    }
+    failures
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn document(id: &str) -> EnvelopeDocument {
+        EnvelopeDocument {
+            timestamp: Utc.timestamp_opt(1_700_000_000, 0).unwrap(),
+            envelope_id: id.to_string(),
+            type_name: "erc20.transfer".to_string(),
+            block_number: Some(42),
+            from_address: Some("0x1".to_string()),
+            metadata: BTreeMap::from([("token".to_string(), "0x1".to_string())]),
+            body: None,
+        }
+    }
+
+    #[test]
+    fn test_index_names() {
+        let ts = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+        assert_eq!(
+            IndexRollover::None.index_name("Torii", "erc20.transfer", ts),
+            "torii-erc20-transfer"
+        );
+        assert_eq!(
+            IndexRollover::Daily.index_name("torii", "erc20.transfer", ts),
+            "torii-erc20-transfer-2023.11.14"
+        );
+        assert_eq!(
+            IndexRollover::Monthly.index_name("_torii", "Log Entry", ts),
+            "torii-log-entry-2023.11"
+        );
+    }
+
+    #[test]
+    fn test_encode_bulk() {
+        let body = encode_bulk(&[BulkAction {
+            index: "torii-erc20-transfer".to_string(),
+            document: document("transfer_1"),
+        }])
+        .unwrap();
+        assert_eq!(
+            body,
+            concat!(
+                "{\"index\":{\"_id\":\"transfer_1\",\"_index\":\"torii-erc20-transfer\"}}\n",
+                "{\"@timestamp\":\"2023-11-14T22:13:20Z\",\"envelope_id\":\"transfer_1\",",
+                "\"type\":\"erc20.transfer\",\"block_number\":42,\"from_address\":\"0x1\",",
+                "\"metadata\":{\"token\":\"0x1\"}}\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_bulk_failures() {
+        let ok = serde_json::json!({ "errors": false, "items": [] });
+        assert_eq!(bulk_failures(&ok), BulkFailures::default());
+
+        let response = serde_json::json!({
+            "errors": true,
+            "items": [
+                { "index": { "_id": "a", "status": 201 } },
+                { "index": { "_id": "b", "status": 400, "error": { "type": "mapper_parsing_exception", "reason": "bad field" } } },
+                { "index": { "_id": "c", "status": 429, "error": { "type": "es_rejected_execution_exception" } } }
+            ]
+        });
+        let failures = bulk_failures(&response);
+        assert_eq!(failures.count, 2);
+        assert_eq!(failures.first_reason.as_deref(), Some("bad field"));
+    }
+}
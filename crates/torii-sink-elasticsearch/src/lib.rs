@@ -0,0 +1,308 @@
+//! Elasticsearch / OpenSearch sink
+//!
+//! Bulk-indexes envelopes as documents so indexed events can be explored in Kibana or
+//! OpenSearch Dashboards. Each registered envelope type is written to its own index
+//! (`<prefix>-<type>`, optionally with a date suffix, see [`IndexRollover`]); the
+//! envelope id is the document id, so replays overwrite documents.
+//!
+//! Envelope bodies are opaque to the sink: types are registered with a name and a
+//! serializer producing the document `body`. Envelopes of unregistered types are
+//! ignored.
+//!
+//! ```rust,ignore
+//! let sink = ElasticsearchSink::new(ElasticsearchConfig::new("http://localhost:9200"))?
+//!     .with_type(TypeId::new("erc20.transfer"), "erc20.transfer", |envelope| {
+//!         let transfer = envelope.downcast_ref::<Erc20Transfer>()?;
+//!         Some(serde_json::json!({ "amount": transfer.amount.to_string() }))
+//!     });
+//! ```
+
+pub mod document;
+
+pub use document::{BulkAction, EnvelopeDocument, IndexRollover};
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, TimeZone, Utc};
+use document::{bulk_failures, encode_bulk};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use torii::axum::Router;
+use torii::etl::envelope::{Envelope, TypeId};
+use torii::etl::extractor::ExtractionBatch;
+use torii::etl::sink::{EventBus, Sink, SinkContext, TopicInfo};
+use torii::ToriiResult;
+
+/// Documents sent per `_bulk` request
+pub const DEFAULT_MAX_BULK_ACTIONS: usize = 1000;
+/// Timeout of a single `_bulk` request
+const BULK_REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Serializes the body of an envelope into the document `body`.
+///
+/// Returning `None` indexes the envelope without a body.
+pub type BodySerializer = Arc<dyn Fn(&Envelope) -> Option<Value> + Send + Sync>;
+
+/// Credentials sent with every request
+#[derive(Debug, Clone)]
+pub enum ElasticsearchAuth {
+    Basic {
+        username: String,
+        password: String,
+    },
+    /// Encoded API key (`Authorization: ApiKey <key>`)
+    ApiKey(String),
+}
+
+/// Cluster connection and index naming
+#[derive(Debug, Clone)]
+pub struct ElasticsearchConfig {
+    /// Cluster URL (e.g. `http://localhost:9200`)
+    pub url: String,
+    pub auth: Option<ElasticsearchAuth>,
+    /// Prefix of every index name
+    pub index_prefix: String,
+    pub rollover: IndexRollover,
+    /// Documents sent per `_bulk` request
+    pub max_bulk_actions: usize,
+}
+
+impl ElasticsearchConfig {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            auth: None,
+            index_prefix: "torii".to_string(),
+            rollover: IndexRollover::None,
+            max_bulk_actions: DEFAULT_MAX_BULK_ACTIONS,
+        }
+    }
+
+    pub fn with_auth(mut self, auth: ElasticsearchAuth) -> Self {
+        self.auth = Some(auth);
+        self
+    }
+
+    pub fn with_index_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.index_prefix = prefix.into();
+        self
+    }
+
+    pub fn with_rollover(mut self, rollover: IndexRollover) -> Self {
+        self.rollover = rollover;
+        self
+    }
+
+    pub fn with_max_bulk_actions(mut self, max_bulk_actions: usize) -> Self {
+        self.max_bulk_actions = max_bulk_actions.max(1);
+        self
+    }
+}
+
+/// Indexed envelope type
+struct IndexedType {
+    name: String,
+    serializer: BodySerializer,
+}
+
+/// Sink bulk-indexing envelopes into Elasticsearch or OpenSearch
+pub struct ElasticsearchSink {
+    config: ElasticsearchConfig,
+    client: reqwest::Client,
+    bulk_url: String,
+    types: HashMap<TypeId, IndexedType>,
+}
+
+impl ElasticsearchSink {
+    pub fn new(config: ElasticsearchConfig) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(BULK_REQUEST_TIMEOUT)
+            .build()
+            .context("Failed to build Elasticsearch client")?;
+        let bulk_url = format!("{}/_bulk", config.url.trim_end_matches('/'));
+        Ok(Self {
+            config,
+            client,
+            bulk_url,
+            types: HashMap::new(),
+        })
+    }
+
+    /// Indexes envelopes of `type_id` under `name`, with bodies serialized by `serializer`.
+    ///
+    /// `name` is used in the index name (lowercased, with characters not allowed in index
+    /// names replaced by `-`) and as the document `type`.
+    pub fn with_type<F>(mut self, type_id: TypeId, name: impl Into<String>, serializer: F) -> Self
+    where
+        F: Fn(&Envelope) -> Option<Value> + Send + Sync + 'static,
+    {
+        self.types.insert(
+            type_id,
+            IndexedType {
+                name: name.into(),
+                serializer: Arc::new(serializer),
+            },
+        );
+        self
+    }
+
+    /// Indexes envelopes of `type_id` under `name` with their metadata only.
+    pub fn with_metadata_only_type(self, type_id: TypeId, name: impl Into<String>) -> Self {
+        self.with_type(type_id, name, |_| None)
+    }
+
+    /// Builds the bulk action of `envelope`, if its type is registered.
+    fn action(&self, envelope: &Envelope, batch: &ExtractionBatch) -> Option<BulkAction> {
+        let indexed = self.types.get(&envelope.type_id)?;
+        let timestamp = document_timestamp(envelope, batch);
+        Some(BulkAction {
+            index: self.config.rollover.index_name(
+                &self.config.index_prefix,
+                &indexed.name,
+                timestamp,
+            ),
+            document: EnvelopeDocument {
+                timestamp,
+                envelope_id: envelope.id.clone(),
+                type_name: indexed.name.clone(),
+                block_number: envelope.meta.block_number,
+                from_address: envelope
+                    .meta
+                    .contract
+                    .map(|address| format!("{address:#x}")),
+                metadata: envelope
+                    .metadata
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.to_string()))
+                    .collect(),
+                body: (indexed.serializer)(envelope),
+            },
+        })
+    }
+
+    /// Sends one `_bulk` request.
+    ///
+    /// Request failures are returned; rejected documents (mapping conflicts, ...) are
+    /// logged and counted so a single bad document does not stall indexing.
+    async fn send_bulk(&self, actions: &[BulkAction]) -> Result<()> {
+        let body = encode_bulk(actions).context("Failed to encode bulk request")?;
+        let mut request = self
+            .client
+            .post(&self.bulk_url)
+            .header(reqwest::header::CONTENT_TYPE, "application/x-ndjson")
+            .body(body);
+        request = match &self.config.auth {
+            Some(ElasticsearchAuth::Basic { username, password }) => {
+                request.basic_auth(username, Some(password))
+            }
+            Some(ElasticsearchAuth::ApiKey(key)) => {
+                request.header(reqwest::header::AUTHORIZATION, format!("ApiKey {key}"))
+            }
+            None => request,
+        };
+
+        let response: Value = request
+            .send()
+            .await
+            .context("Bulk request failed")?
+            .error_for_status()
+            .context("Bulk request failed")?
+            .json()
+            .await
+            .context("Invalid bulk response")?;
+
+        let failures = bulk_failures(&response);
+        ::metrics::counter!("torii_elasticsearch_documents_indexed_total")
+            .increment((actions.len() - failures.count) as u64);
+        if failures.count > 0 {
+            ::metrics::counter!("torii_elasticsearch_documents_failed_total")
+                .increment(failures.count as u64);
+            tracing::warn!(
+                target: "torii_sink_elasticsearch",
+                failed = failures.count,
+                total = actions.len(),
+                reason = failures.first_reason.as_deref().unwrap_or_default(),
+                "Documents rejected by bulk request"
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Block timestamp of the envelope, falling back to its creation time.
+fn document_timestamp(envelope: &Envelope, batch: &ExtractionBatch) -> DateTime<Utc> {
+    let seconds = envelope
+        .meta
+        .block_timestamp
+        .or_else(|| {
+            let block_number = envelope.meta.block_number?;
+            batch.blocks.get(&block_number).map(|block| block.timestamp)
+        })
+        .and_then(|timestamp| i64::try_from(timestamp).ok())
+        .unwrap_or(envelope.timestamp);
+    Utc.timestamp_opt(seconds, 0).single().unwrap_or_default()
+}
+
+#[async_trait]
+impl Sink for ElasticsearchSink {
+    fn name(&self) -> &'static str {
+        "elasticsearch"
+    }
+
+    fn version(&self) -> Option<&str> {
+        Some(env!("CARGO_PKG_VERSION"))
+    }
+
+    fn interested_types(&self) -> Vec<TypeId> {
+        self.types.keys().copied().collect()
+    }
+
+    async fn process(&self, envelopes: &[Envelope], batch: &ExtractionBatch) -> ToriiResult<()> {
+        let actions: Vec<BulkAction> = envelopes
+            .iter()
+            .filter_map(|envelope| self.action(envelope, batch))
+            .collect();
+        if actions.is_empty() {
+            return Ok(());
+        }
+
+        let start = Instant::now();
+        for chunk in actions.chunks(self.config.max_bulk_actions) {
+            self.send_bulk(chunk).await?;
+        }
+        ::metrics::histogram!("torii_elasticsearch_bulk_duration_seconds")
+            .record(start.elapsed().as_secs_f64());
+
+        tracing::debug!(
+            target: "torii_sink_elasticsearch",
+            documents = actions.len(),
+            "Indexed envelopes"
+        );
+        Ok(())
+    }
+
+    fn topics(&self) -> Vec<TopicInfo> {
+        Vec::new()
+    }
+
+    fn build_routes(&self) -> Router {
+        Router::new()
+    }
+
+    async fn initialize(
+        &mut self,
+        _event_bus: Arc<EventBus>,
+        _context: &SinkContext,
+    ) -> ToriiResult<()> {
+        tracing::info!(
+            target: "torii_sink_elasticsearch",
+            url = %self.config.url,
+            prefix = %self.config.index_prefix,
+            types = self.types.len(),
+            "Elasticsearch sink initialized"
+        );
+        Ok(())
+    }
+}
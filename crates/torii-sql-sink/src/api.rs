@@ -8,19 +8,29 @@ use serde::{Deserialize, Serialize};
 use sqlx::{Column, Row};
 use std::sync::Arc;
 
+use crate::query::{self, QueryDb, QueryParam, QueryPolicy};
 use crate::DbBackend;
 
 /// Shared state for SQL sink routes
 #[derive(Clone)]
 pub struct SqlSinkState {
     pub(crate) pool: Arc<sqlx::Pool<sqlx::Any>>,
+    pub(crate) query_db: QueryDb,
     pub(crate) backend: DbBackend,
+    pub(crate) policy: QueryPolicy,
 }
 
 /// Request body for SQL query endpoint
 #[derive(Deserialize)]
 pub struct SqlQueryRequest {
+    /// Statement, with `?`/`$1` (SQLite) or `$1` (PostgreSQL) placeholders
     pub query: String,
+    /// Bind parameters (JSON scalars), in placeholder order
+    #[serde(default)]
+    pub params: Vec<serde_json::Value>,
+    /// Maximum number of rows (capped by the sink's row limit)
+    #[serde(default)]
+    pub limit: Option<usize>,
 }
 
 /// Response for SQL query endpoints
@@ -28,29 +38,44 @@ pub struct SqlQueryRequest {
 pub struct SqlQueryResponse {
     pub rows: Vec<serde_json::Value>,
     pub count: usize,
+    /// Whether rows beyond the limit were dropped
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
 }
 
-/// POST /sql/query - Execute raw SQL query.
+/// POST /sql/query - Execute a SQL query.
 ///
-/// Allows clients to execute arbitrary SQL queries against the sink's database.
-/// This is useful for exploring the data and debugging.
-///
-/// **Warning**: In production, this should be secured or disabled.
+/// Allows clients to query the sink's database, with bind parameters. Only read
+/// statements are accepted unless the sink's [`QueryPolicy`] allows writes, and
+/// queries are bounded by its statement timeout and row limit.
 pub async fn sql_query_handler(
     State(state): State<SqlSinkState>,
     Json(req): Json<SqlQueryRequest>,
 ) -> Result<Json<SqlQueryResponse>, (StatusCode, String)> {
     tracing::info!(target: "torii::sinks::sql::api", "Executing query: {}", req.query);
 
-    let rows = sqlx::query(&req.query)
-        .fetch_all(state.pool.as_ref())
-        .await
-        .map_err(|e| {
-            tracing::error!(target: "torii::sinks::sql::api", "Query error: {}", e);
-            (StatusCode::BAD_REQUEST, format!("Query failed: {e}"))
-        })?;
-
-    let results: Vec<serde_json::Value> = rows
+    let into_response = |e: query::QueryError| {
+        tracing::error!(target: "torii::sinks::sql::api", "Query error: {}", e);
+        (e.status_code(), e.to_string())
+    };
+    let params = req
+        .params
+        .iter()
+        .map(QueryParam::from_json)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(into_response)?;
+    let result = query::fetch_rows(
+        state.query_db.clone(),
+        state.policy,
+        req.query,
+        params,
+        state.policy.row_limit(req.limit),
+    )
+    .await
+    .map_err(into_response)?;
+
+    let results: Vec<serde_json::Value> = result
+        .rows
         .into_iter()
         .map(|row| {
             let mut map = serde_json::Map::new();
@@ -78,6 +103,7 @@ pub async fn sql_query_handler(
     Ok(Json(SqlQueryResponse {
         rows: results,
         count,
+        truncated: result.truncated,
     }))
 }
 
@@ -135,5 +161,6 @@ pub async fn sql_events_handler(
     Ok(Json(SqlQueryResponse {
         rows: results,
         count,
+        truncated: false,
     }))
 }
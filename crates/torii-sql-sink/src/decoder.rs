@@ -6,10 +6,10 @@ use starknet::core::types::{EmittedEvent, Felt};
 use starknet::core::utils::parse_cairo_short_string;
 use starknet::macros::selector;
 use std::any::Any;
-use std::collections::HashMap;
 
 use torii::etl::decoder::Decoder;
-use torii::etl::envelope::{Envelope, TypeId, TypedBody};
+use torii::etl::envelope::{Envelope, EnvelopeMetadata, TypeId, TypedBody};
+use torii::ToriiResult;
 
 /// SqlInsert event type - represents a SQL insert operation.
 /// By deriving TypedBody, it allows the envelope to be downcast to this type.
@@ -97,7 +97,11 @@ impl Decoder for SqlDecoder {
         "sql"
     }
 
-    async fn decode_event(&self, event: &EmittedEvent) -> anyhow::Result<Vec<Envelope>> {
+    fn decoder_version(&self) -> Option<&str> {
+        Some(env!("CARGO_PKG_VERSION"))
+    }
+
+    async fn decode_event(&self, event: &EmittedEvent) -> ToriiResult<Vec<Envelope>> {
         if !self.is_interested(event) {
             return Ok(Vec::new());
         }
@@ -153,14 +157,14 @@ impl Decoder for SqlDecoder {
 
         // Create metadata, they are optional, but currently they can give more context to the envelope
         // without adding this information to the envelope body.
-        let mut metadata = HashMap::new();
-        metadata.insert("source".to_string(), "starknet".to_string());
-        metadata.insert("operation".to_string(), operation.to_string());
-        metadata.insert("table".to_string(), table_name.clone());
-        metadata.insert("value".to_string(), value.to_string());
+        let mut metadata = EnvelopeMetadata::new();
+        metadata.insert("source".to_string(), "starknet".into());
+        metadata.insert("operation".to_string(), operation.to_string().into());
+        metadata.insert("table".to_string(), table_name.clone().into());
+        metadata.insert("value".to_string(), value.to_string().into());
         metadata.insert(
             "from_address".to_string(),
-            format!("{:#x}", event.from_address),
+            format!("{:#x}", event.from_address).into(),
         );
 
         Ok(vec![Envelope::new(
@@ -31,15 +31,38 @@ pub struct SqlFilter {
     #[prost(uint64, optional, tag = "3")]
     pub block_number_lte: ::core::option::Option<u64>,
 }
+/// Bind parameter of a query (unset value binds NULL)
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryParam {
+    #[prost(oneof = "query_param::Value", tags = "1, 2, 3, 4")]
+    pub value: ::core::option::Option<query_param::Value>,
+}
+/// Nested message and enum types in `QueryParam`.
+pub mod query_param {
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Value {
+        #[prost(bool, tag = "1")]
+        BoolValue(bool),
+        #[prost(int64, tag = "2")]
+        IntValue(i64),
+        #[prost(double, tag = "3")]
+        FloatValue(f64),
+        #[prost(string, tag = "4")]
+        TextValue(::prost::alloc::string::String),
+    }
+}
 /// Request to execute a SQL query
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct QueryRequest {
-    /// SQL query string
+    /// SQL query string, with `?`/`$1` (SQLite) or `$1` (PostgreSQL) placeholders
     #[prost(string, tag = "1")]
     pub query: ::prost::alloc::string::String,
-    /// Optional limit for result set
+    /// Optional limit for result set (capped by the server's row limit)
     #[prost(int32, optional, tag = "2")]
     pub limit: ::core::option::Option<i32>,
+    /// Bind parameters, in placeholder order
+    #[prost(message, repeated, tag = "3")]
+    pub params: ::prost::alloc::vec::Vec<QueryParam>,
 }
 /// A single row in the query result
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -51,6 +74,70 @@ pub struct QueryRow {
         ::prost::alloc::string::String,
     >,
 }
+/// Result column of a streamed query
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ColumnMetadata {
+    /// Column name
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    /// SQL type reported by the driver (BOOLEAN, INTEGER, BIGINT, DOUBLE, TEXT, BLOB, ...;
+    /// NULL when unknown)
+    #[prost(string, tag = "2")]
+    pub sql_type: ::prost::alloc::string::String,
+    /// Whether the column can hold NULL (absent when the database cannot tell)
+    #[prost(bool, optional, tag = "3")]
+    pub nullable: ::core::option::Option<bool>,
+}
+/// Result columns, sent as the first StreamQuery frame
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryColumns {
+    #[prost(message, repeated, tag = "1")]
+    pub columns: ::prost::alloc::vec::Vec<ColumnMetadata>,
+}
+/// Typed value of a result cell (unset value is NULL)
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SqlValue {
+    #[prost(oneof = "sql_value::Value", tags = "1, 2, 3, 4, 5")]
+    pub value: ::core::option::Option<sql_value::Value>,
+}
+/// Nested message and enum types in `SqlValue`.
+pub mod sql_value {
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Value {
+        #[prost(int64, tag = "1")]
+        IntValue(i64),
+        #[prost(double, tag = "2")]
+        FloatValue(f64),
+        #[prost(bytes, tag = "3")]
+        BytesValue(::prost::alloc::vec::Vec<u8>),
+        #[prost(string, tag = "4")]
+        TextValue(::prost::alloc::string::String),
+        #[prost(bool, tag = "5")]
+        BoolValue(bool),
+    }
+}
+/// Row of a streamed query, values in column order
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TypedQueryRow {
+    #[prost(message, repeated, tag = "1")]
+    pub values: ::prost::alloc::vec::Vec<SqlValue>,
+}
+/// StreamQuery frame: the columns first, then one frame per row
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StreamQueryResponse {
+    #[prost(oneof = "stream_query_response::Frame", tags = "1, 2")]
+    pub frame: ::core::option::Option<stream_query_response::Frame>,
+}
+/// Nested message and enum types in `StreamQueryResponse`.
+pub mod stream_query_response {
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Frame {
+        #[prost(message, tag = "1")]
+        Columns(super::QueryColumns),
+        #[prost(message, tag = "2")]
+        Row(super::TypedQueryRow),
+    }
+}
 /// Response containing multiple rows (for unary Query RPC)
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct QueryResponse {
@@ -60,6 +147,9 @@ pub struct QueryResponse {
     /// Total number of rows returned
     #[prost(int32, tag = "2")]
     pub total_rows: i32,
+    /// Whether rows beyond the limit were dropped
+    #[prost(bool, tag = "3")]
+    pub truncated: bool,
 }
 /// Request to get the database schema
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -131,11 +221,12 @@ pub mod sql_sink_server {
         ) -> std::result::Result<tonic::Response<super::QueryResponse>, tonic::Status>;
         /// Server streaming response type for the StreamQuery method.
         type StreamQueryStream: tonic::codegen::tokio_stream::Stream<
-                Item = std::result::Result<super::QueryRow, tonic::Status>,
+                Item = std::result::Result<super::StreamQueryResponse, tonic::Status>,
             >
             + std::marker::Send
             + 'static;
-        /// Execute a SQL query and stream results row-by-row (for large result sets)
+        /// Execute a SQL query and stream results row-by-row (for large result sets).
+        /// The first frame describes the columns, the following ones hold typed rows.
         async fn stream_query(
             &self,
             request: tonic::Request<super::QueryRequest>,
@@ -291,7 +382,7 @@ pub mod sql_sink_server {
                         T: SqlSink,
                     > tonic::server::ServerStreamingService<super::QueryRequest>
                     for StreamQuerySvc<T> {
-                        type Response = super::QueryRow;
+                        type Response = super::StreamQueryResponse;
                         type ResponseStream = T::StreamQueryStream;
                         type Future = BoxFuture<
                             tonic::Response<Self::ResponseStream>,
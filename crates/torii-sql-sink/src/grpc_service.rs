@@ -8,29 +8,40 @@ use tokio::sync::broadcast;
 use tonic::{Request, Response, Status};
 
 use crate::proto::{
-    sql_sink_server::SqlSink as SqlSinkTrait, GetSchemaRequest, GetSchemaResponse, QueryRequest,
-    QueryResponse, QueryRow, SqlOperation, SqlOperationUpdate, SqlSubscribeRequest, TableSchema,
+    sql_sink_server::SqlSink as SqlSinkTrait, stream_query_response::Frame, GetSchemaRequest,
+    GetSchemaResponse, QueryColumns, QueryRequest, QueryResponse, QueryRow, SqlOperation,
+    SqlOperationUpdate, SqlSubscribeRequest, StreamQueryResponse, TableSchema, TypedQueryRow,
 };
+use crate::query::{self, QueryDb, QueryParam, QueryPolicy, QueryValue};
 use crate::DbBackend;
 
 /// gRPC service implementation for SqlSink
 #[derive(Clone)]
 pub struct SqlSinkService {
     pool: Arc<sqlx::Pool<sqlx::Any>>,
+    query_db: QueryDb,
     backend: DbBackend,
+    policy: QueryPolicy,
     /// Broadcast channel for real-time SQL operation updates
     pub update_tx: broadcast::Sender<SqlOperationUpdate>,
 }
 
 impl SqlSinkService {
     /// Creates a new SqlSinkService.
-    pub(crate) fn new(pool: Arc<sqlx::Pool<sqlx::Any>>, backend: DbBackend) -> Self {
+    pub(crate) fn new(
+        pool: Arc<sqlx::Pool<sqlx::Any>>,
+        query_db: QueryDb,
+        backend: DbBackend,
+        policy: QueryPolicy,
+    ) -> Self {
         // Create broadcast channel with capacity for 1000 pending updates
         let (tx, _rx) = broadcast::channel(1000);
 
         Self {
             pool,
+            query_db,
             backend,
+            policy,
             update_tx: tx,
         }
     }
@@ -46,6 +57,11 @@ impl SqlSinkService {
         let _ = self.update_tx.send(update);
     }
 
+    /// Limit requested by a client (absent or non-positive means none).
+    fn requested_limit(limit: Option<i32>) -> Option<usize> {
+        limit.and_then(|limit| usize::try_from(limit).ok())
+    }
+
     /// Helper to convert a row to a QueryRow proto message.
     fn row_to_proto(row: &AnyRow) -> QueryRow {
         let mut columns = std::collections::HashMap::new();
@@ -82,81 +98,102 @@ impl SqlSinkTrait for SqlSinkService {
         request: Request<QueryRequest>,
     ) -> Result<Response<QueryResponse>, Status> {
         let req = request.into_inner();
-        let query = req.query;
 
-        tracing::info!(target: "torii::sql_sink::grpc", "Executing query: {}", query);
-
-        let query_with_limit = if let Some(limit) = req.limit {
-            format!("{query} LIMIT {limit}")
-        } else {
-            query
-        };
+        tracing::info!(target: "torii::sql_sink::grpc", "Executing query: {}", req.query);
 
-        let rows = sqlx::query(&query_with_limit)
-            .fetch_all(self.pool.as_ref())
-            .await
-            .map_err(|e| Status::invalid_argument(format!("Query failed: {e}")))?;
+        let limit = self.policy.row_limit(Self::requested_limit(req.limit));
+        let params = req.params.into_iter().map(QueryParam::from).collect();
+        let result =
+            query::fetch_rows(self.query_db.clone(), self.policy, req.query, params, limit).await?;
 
-        let proto_rows: Vec<QueryRow> = rows.iter().map(Self::row_to_proto).collect();
+        let proto_rows: Vec<QueryRow> = result.rows.iter().map(Self::row_to_proto).collect();
 
         let total_rows = proto_rows.len() as i32;
 
         tracing::info!(
             target: "torii::sql_sink::grpc",
-            "Query returned {} rows",
-            total_rows
+            "Query returned {} rows (truncated: {})",
+            total_rows,
+            result.truncated
         );
 
         Ok(Response::new(QueryResponse {
             rows: proto_rows,
             total_rows,
+            truncated: result.truncated,
         }))
     }
 
     /// Stream query results row-by-row (for large result sets)
-    type StreamQueryStream = Pin<Box<dyn Stream<Item = Result<QueryRow, Status>> + Send>>;
+    type StreamQueryStream =
+        Pin<Box<dyn Stream<Item = Result<StreamQueryResponse, Status>> + Send>>;
 
     /// Streams query results row-by-row (for large result sets).
+    ///
+    /// The first frame holds the column metadata, the following ones typed rows. The
+    /// stream ends after the row limit.
     async fn stream_query(
         &self,
         request: Request<QueryRequest>,
     ) -> Result<Response<Self::StreamQueryStream>, Status> {
         let req = request.into_inner();
-        let query = req.query;
 
         tracing::info!(
             target: "torii::sql_sink::grpc",
             "Starting streaming query: {}",
-            query
+            req.query
         );
 
-        let query_with_limit = if let Some(limit) = req.limit {
-            format!("{query} LIMIT {limit}")
-        } else {
-            query
-        };
-
+        let limit = self.policy.row_limit(Self::requested_limit(req.limit));
+        let params = req.params.into_iter().map(QueryParam::from).collect();
+        let sql = req.query.clone();
+        let rows = query::execute(self.query_db.clone(), self.policy, req.query, params)?;
         let pool = self.pool.clone();
+        let timeout = self.policy.statement_timeout;
 
         let stream = async_stream::try_stream! {
-            let mut rows = sqlx::query(&query_with_limit).fetch(pool.as_ref());
-
             use futures::TryStreamExt;
 
-            let mut row_count = 0;
+            let described = match query::describe_columns(&pool, &sql, timeout).await {
+                Ok(columns) => Some(columns),
+                Err(e) => {
+                    tracing::debug!(
+                        target: "torii::sql_sink::grpc",
+                        "Could not describe query, using the first row: {}",
+                        e
+                    );
+                    None
+                }
+            };
+
+            let mut rows = std::pin::pin!(rows);
+            let mut next = rows.try_next().await?;
+            let columns = query::result_columns(described, next.as_ref());
+            yield StreamQueryResponse {
+                frame: Some(Frame::Columns(QueryColumns {
+                    columns: columns.into_iter().map(Into::into).collect(),
+                })),
+            };
 
-            while let Some(row) = rows.try_next().await.map_err(|e| {
-                Status::internal(format!("Stream error: {e}"))
-            })? {
+            let mut row_count = 0;
+            while let Some(row) = next {
                 row_count += 1;
-                let proto_row = SqlSinkService::row_to_proto(&row);
+                let values = QueryValue::decode_row(&row)?;
                 tracing::debug!(
                     target: "torii::sql_sink::grpc",
-                    "Yielding row {}: {:?}",
+                    "Yielding row {} ({} values)",
                     row_count,
-                    proto_row.columns.keys().collect::<Vec<_>>()
+                    values.len()
                 );
-                yield proto_row;
+                yield StreamQueryResponse {
+                    frame: Some(Frame::Row(TypedQueryRow {
+                        values: values.into_iter().map(Into::into).collect(),
+                    })),
+                };
+                if row_count == limit {
+                    break;
+                }
+                next = rows.try_next().await?;
             }
 
             tracing::info!(
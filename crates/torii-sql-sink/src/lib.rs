@@ -1,6 +1,7 @@
 pub mod api;
 pub mod decoder;
 pub mod grpc_service;
+pub mod query;
 pub mod samples;
 
 // Include generated protobuf code
@@ -18,8 +19,12 @@ use axum::{
 };
 use prost::Message;
 use prost_types::Any as ProtoAny;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
 use sqlx::{any::AnyPoolOptions, Any as SqlxAny, QueryBuilder};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use starknet::core::types::EmittedEvent;
 use torii::etl::{
@@ -28,10 +33,18 @@ use torii::etl::{
     sink::{EventBus, Sink, TopicInfo},
 };
 use torii::grpc::UpdateType;
+use torii::ToriiResult;
+use torii_common::{MaintenanceSchedule, SQLITE_MAINTENANCE_SQL};
 
 pub use decoder::{SqlDecoder, SqlInsert, SqlUpdate};
 pub use grpc_service::SqlSinkService;
 pub use proto::{SqlOperation as ProtoSqlOperation, SqlOperationUpdate};
+pub use query::{QueryError, QueryParam, QueryPolicy};
+
+use query::QueryDb;
+
+/// Number of in-memory SQLite databases opened, to name the next one
+static MEMORY_DATABASES: AtomicUsize = AtomicUsize::new(0);
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub(crate) enum DbBackend {
@@ -47,10 +60,15 @@ pub(crate) enum DbBackend {
 /// 3. **REST HTTP**: Exposes `/sql/query` and `/sql/events` endpoints
 pub struct SqlSink {
     pool: Arc<sqlx::Pool<SqlxAny>>,
+    /// Connections of client queries
+    query_db: QueryDb,
     backend: DbBackend,
+    query_policy: QueryPolicy,
     event_bus: Option<Arc<EventBus>>,
     /// Internal gRPC service (self-contained with broadcast channel)
     grpc_service: Arc<SqlSinkService>,
+    /// SQLite maintenance run on idle cycles (disabled by default).
+    maintenance: MaintenanceSchedule,
 }
 
 impl SqlSink {
@@ -106,14 +124,28 @@ impl SqlSink {
             DbBackend::Sqlite
         };
 
-        let db_url = if backend == DbBackend::Sqlite && database_url == ":memory:" {
-            "sqlite::memory:".to_string()
+        let db_url = if backend == DbBackend::Sqlite
+            && matches!(database_url, ":memory:" | "sqlite::memory:")
+        {
+            // Named so that the client query pool opens the same database.
+            format!(
+                "sqlite:file:torii-sql-sink-{}?mode=memory&cache=shared",
+                MEMORY_DATABASES.fetch_add(1, Ordering::Relaxed)
+            )
         } else {
             database_url.to_string()
         };
 
         let pool = AnyPoolOptions::new().connect(&db_url).await?;
         let pool = Arc::new(pool);
+        let query_db = match backend {
+            DbBackend::Sqlite => QueryDb::Sqlite(
+                SqlitePoolOptions::new()
+                    .connect_with(SqliteConnectOptions::from_str(&db_url)?)
+                    .await?,
+            ),
+            DbBackend::Postgres => QueryDb::Postgres(pool.clone()),
+        };
 
         if backend == DbBackend::Postgres {
             sqlx::query("CREATE SCHEMA IF NOT EXISTS sql_sink")
@@ -150,7 +182,13 @@ impl SqlSink {
         sqlx::query(create_table_sql).execute(pool.as_ref()).await?;
 
         // Create gRPC service internally (with its own broadcast channel)
-        let grpc_service = Arc::new(SqlSinkService::new(pool.clone(), backend));
+        let query_policy = QueryPolicy::default();
+        let grpc_service = Arc::new(SqlSinkService::new(
+            pool.clone(),
+            query_db.clone(),
+            backend,
+            query_policy,
+        ));
 
         tracing::info!(
             target: "torii::sinks::sql",
@@ -160,12 +198,37 @@ impl SqlSink {
 
         Ok(Self {
             pool,
+            query_db,
             backend,
+            query_policy,
             event_bus: None,
             grpc_service,
+            maintenance: MaintenanceSchedule::disabled(),
         })
     }
 
+    /// Sets the policy of `POST /sql/query` and the `Query`/`StreamQuery` RPCs.
+    ///
+    /// By default only read statements are accepted, with a 10s statement timeout and
+    /// at most 10 000 rows per query. Call before [`Self::get_grpc_service_impl`].
+    pub fn with_query_policy(mut self, policy: QueryPolicy) -> Self {
+        self.query_policy = policy;
+        self.grpc_service = Arc::new(SqlSinkService::new(
+            self.pool.clone(),
+            self.query_db.clone(),
+            self.backend,
+            policy,
+        ));
+        self
+    }
+
+    /// Runs SQLite maintenance (incremental vacuum, `ANALYZE`, `PRAGMA optimize`) on idle
+    /// cycles, at most once per `interval`. No-op on Postgres.
+    pub fn with_maintenance_interval(mut self, interval: Duration) -> Self {
+        self.maintenance = MaintenanceSchedule::new(Some(interval));
+        self
+    }
+
     /// Filters function for SQL sink (optimized - works on decoded data).
     ///
     /// Supports filters:
@@ -246,15 +309,19 @@ impl Sink for SqlSink {
         "sql"
     }
 
+    fn version(&self) -> Option<&str> {
+        Some(env!("CARGO_PKG_VERSION"))
+    }
+
+    fn descriptor_set(&self) -> Option<&'static [u8]> {
+        Some(FILE_DESCRIPTOR_SET)
+    }
+
     fn interested_types(&self) -> Vec<TypeId> {
         vec![TypeId::new("sql.insert"), TypeId::new("sql.update")]
     }
 
-    async fn process(
-        &self,
-        envelopes: &[Envelope],
-        _batch: &ExtractionBatch,
-    ) -> anyhow::Result<()> {
+    async fn process(&self, envelopes: &[Envelope], _batch: &ExtractionBatch) -> ToriiResult<()> {
         for envelope in envelopes {
             if envelope.type_id == TypeId::new("sql.insert") {
                 if let Some(insert) = envelope.downcast_ref::<SqlInsert>() {
@@ -286,7 +353,7 @@ impl Sink for SqlSink {
                     // Broadcast to EventBus subscribers (central subscription).
                     if let Some(event_bus) = &self.event_bus {
                         let mut buf = Vec::new();
-                        proto_msg.encode(&mut buf)?;
+                        proto_msg.encode(&mut buf).map_err(anyhow::Error::from)?;
                         let any = ProtoAny {
                             type_url: "type.googleapis.com/torii.sinks.sql.SqlOperation"
                                 .to_string(),
@@ -340,7 +407,7 @@ impl Sink for SqlSink {
                     // Broadcast to EventBus subscribers (central subscription).
                     if let Some(event_bus) = &self.event_bus {
                         let mut buf = Vec::new();
-                        proto_msg.encode(&mut buf)?;
+                        proto_msg.encode(&mut buf).map_err(anyhow::Error::from)?;
                         let any = ProtoAny {
                             type_url: "type.googleapis.com/torii.sinks.sql.SqlOperation"
                                 .to_string(),
@@ -389,7 +456,9 @@ impl Sink for SqlSink {
     fn build_routes(&self) -> Router {
         let state = api::SqlSinkState {
             pool: self.pool.clone(),
+            query_db: self.query_db.clone(),
             backend: self.backend,
+            policy: self.query_policy,
         };
 
         Router::new()
@@ -402,9 +471,25 @@ impl Sink for SqlSink {
         &mut self,
         event_bus: Arc<EventBus>,
         _context: &torii::etl::sink::SinkContext,
-    ) -> anyhow::Result<()> {
+    ) -> ToriiResult<()> {
         self.event_bus = Some(event_bus);
         tracing::info!(target: "torii::sinks::sql", "SqlSink initialized with event bus");
         Ok(())
     }
+
+    async fn on_idle(&self) -> ToriiResult<()> {
+        if self.backend != DbBackend::Sqlite || !self.maintenance.try_start() {
+            return Ok(());
+        }
+        let start = Instant::now();
+        sqlx::raw_sql(SQLITE_MAINTENANCE_SQL)
+            .execute(self.pool.as_ref())
+            .await?;
+        tracing::info!(
+            target: "torii::sinks::sql",
+            elapsed_ms = start.elapsed().as_millis() as u64,
+            "Ran database maintenance"
+        );
+        Ok(())
+    }
 }
@@ -0,0 +1,831 @@
+//! Execution of client-provided SQL
+//!
+//! Shared by `POST /sql/query` and the `Query`/`StreamQuery` RPCs. Statements are run
+//! under a [`QueryPolicy`]:
+//! - unless writes are allowed, only read statements (`SELECT`, `WITH`, `VALUES`,
+//!   `EXPLAIN`) are accepted, and they run in a `READ ONLY` transaction on PostgreSQL
+//!   or on a `query_only` connection on SQLite,
+//! - values are passed as bind parameters (`?`/`$1` on SQLite, `$1` on PostgreSQL)
+//!   instead of being formatted into the statement,
+//! - execution is bounded by a statement timeout and results by a row limit. The
+//!   database stops statements at the timeout: `statement_timeout` on PostgreSQL, a
+//!   progress handler on SQLite.
+
+use async_stream::try_stream;
+use futures::stream::{Stream, StreamExt, TryStreamExt};
+use sqlx::any::{AnyRow, AnyTypeInfoKind};
+use sqlx::query::Query;
+use sqlx::sqlite::SqlitePool;
+use sqlx::{Any, Column, Database, Encode, Executor, Row, Sqlite, Type, TypeInfo, ValueRef};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Default statement timeout
+pub const DEFAULT_STATEMENT_TIMEOUT: Duration = Duration::from_secs(10);
+/// Default maximum number of rows returned by a query
+pub const DEFAULT_MAX_ROWS: usize = 10_000;
+
+/// PostgreSQL `query_canceled` error code (raised by `statement_timeout`)
+const PG_QUERY_CANCELED: &str = "57014";
+/// SQLite `SQLITE_INTERRUPT` result code (raised once the progress handler stops a statement)
+const SQLITE_INTERRUPT: &str = "9";
+/// SQLite virtual machine instructions run between two deadline checks
+const SQLITE_PROGRESS_OPS: i32 = 1_000;
+
+/// Statements accepted in read-only mode
+const READ_STATEMENTS: &[&str] = &["SELECT", "WITH", "VALUES", "EXPLAIN"];
+
+/// Keywords rejected anywhere in a read-only statement
+const WRITE_KEYWORDS: &[&str] = &[
+    "INSERT", "UPDATE", "DELETE", "REPLACE", "UPSERT", "MERGE", "CREATE", "ALTER", "DROP",
+    "TRUNCATE", "ATTACH", "DETACH", "VACUUM", "REINDEX", "GRANT", "REVOKE", "PRAGMA", "COPY",
+    "CALL", "INTO", "LOCK",
+];
+
+/// Restrictions applied to client-provided queries
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueryPolicy {
+    /// Accept statements other than reads (INSERT, UPDATE, DDL, ...). Admin only.
+    pub allow_writes: bool,
+    /// Maximum execution time, including streaming of the results
+    pub statement_timeout: Duration,
+    /// Maximum number of rows returned (requests may ask for fewer)
+    pub max_rows: usize,
+}
+
+impl Default for QueryPolicy {
+    fn default() -> Self {
+        Self {
+            allow_writes: false,
+            statement_timeout: DEFAULT_STATEMENT_TIMEOUT,
+            max_rows: DEFAULT_MAX_ROWS,
+        }
+    }
+}
+
+impl QueryPolicy {
+    pub fn with_allow_writes(mut self, allow_writes: bool) -> Self {
+        self.allow_writes = allow_writes;
+        self
+    }
+
+    pub fn with_statement_timeout(mut self, timeout: Duration) -> Self {
+        self.statement_timeout = timeout;
+        self
+    }
+
+    pub fn with_max_rows(mut self, max_rows: usize) -> Self {
+        self.max_rows = max_rows.max(1);
+        self
+    }
+
+    /// Row limit of a request asking for at most `requested` rows.
+    pub fn row_limit(&self, requested: Option<usize>) -> usize {
+        requested
+            .filter(|limit| *limit > 0)
+            .map_or(self.max_rows, |limit| limit.min(self.max_rows))
+    }
+}
+
+/// Bind parameter of a query
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryParam {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Text(String),
+}
+
+impl QueryParam {
+    /// Converts a JSON scalar; arrays and objects are rejected.
+    pub fn from_json(value: &serde_json::Value) -> Result<Self, QueryError> {
+        match value {
+            serde_json::Value::Null => Ok(Self::Null),
+            serde_json::Value::Bool(value) => Ok(Self::Bool(*value)),
+            serde_json::Value::Number(number) => number
+                .as_i64()
+                .map(Self::Int)
+                .or_else(|| number.as_f64().map(Self::Float))
+                .ok_or_else(|| {
+                    QueryError::InvalidParameter(format!("unsupported number {number}"))
+                }),
+            serde_json::Value::String(value) => Ok(Self::Text(value.clone())),
+            other => Err(QueryError::InvalidParameter(format!(
+                "parameters must be scalars, got {other}"
+            ))),
+        }
+    }
+}
+
+impl From<crate::proto::QueryParam> for QueryParam {
+    fn from(param: crate::proto::QueryParam) -> Self {
+        use crate::proto::query_param::Value;
+        match param.value {
+            None => Self::Null,
+            Some(Value::BoolValue(value)) => Self::Bool(value),
+            Some(Value::IntValue(value)) => Self::Int(value),
+            Some(Value::FloatValue(value)) => Self::Float(value),
+            Some(Value::TextValue(value)) => Self::Text(value),
+        }
+    }
+}
+
+/// Result column of a query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ColumnInfo {
+    pub name: String,
+    /// Type reported by the driver (`BIGINT`, `DOUBLE`, `TEXT`, ...; `NULL` when unknown)
+    pub sql_type: String,
+    /// Whether the column can hold NULL, when the database can tell
+    pub nullable: Option<bool>,
+}
+
+impl From<ColumnInfo> for crate::proto::ColumnMetadata {
+    fn from(column: ColumnInfo) -> Self {
+        Self {
+            name: column.name,
+            sql_type: column.sql_type,
+            nullable: column.nullable,
+        }
+    }
+}
+
+/// Typed value of a result cell
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum QueryValue {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Text(String),
+    Bytes(Vec<u8>),
+}
+
+impl QueryValue {
+    /// Decodes column `idx` of `row` according to the type of its value.
+    pub(crate) fn decode(row: &AnyRow, idx: usize) -> Result<Self, QueryError> {
+        let raw = row.try_get_raw(idx)?;
+        if raw.is_null() {
+            return Ok(Self::Null);
+        }
+        let kind = raw.type_info().kind();
+        Ok(match kind {
+            AnyTypeInfoKind::Null => Self::Null,
+            AnyTypeInfoKind::Bool => Self::Bool(row.try_get(idx)?),
+            AnyTypeInfoKind::SmallInt | AnyTypeInfoKind::Integer | AnyTypeInfoKind::BigInt => {
+                Self::Int(row.try_get(idx)?)
+            }
+            AnyTypeInfoKind::Real => Self::Float(f64::from(row.try_get::<f32, _>(idx)?)),
+            AnyTypeInfoKind::Double => Self::Float(row.try_get(idx)?),
+            AnyTypeInfoKind::Text => Self::Text(row.try_get(idx)?),
+            AnyTypeInfoKind::Blob => Self::Bytes(row.try_get(idx)?),
+        })
+    }
+
+    /// Decodes all the columns of `row`.
+    pub(crate) fn decode_row(row: &AnyRow) -> Result<Vec<Self>, QueryError> {
+        (0..row.columns().len())
+            .map(|idx| Self::decode(row, idx))
+            .collect()
+    }
+}
+
+impl From<QueryValue> for crate::proto::SqlValue {
+    fn from(value: QueryValue) -> Self {
+        use crate::proto::sql_value::Value;
+        let value = match value {
+            QueryValue::Null => None,
+            QueryValue::Bool(value) => Some(Value::BoolValue(value)),
+            QueryValue::Int(value) => Some(Value::IntValue(value)),
+            QueryValue::Float(value) => Some(Value::FloatValue(value)),
+            QueryValue::Text(value) => Some(Value::TextValue(value)),
+            QueryValue::Bytes(value) => Some(Value::BytesValue(value)),
+        };
+        Self { value }
+    }
+}
+
+/// Error of a client-provided query
+#[derive(Debug)]
+pub enum QueryError {
+    /// Statement rejected in read-only mode
+    NotReadOnly(String),
+    InvalidParameter(String),
+    /// Statement exceeded the statement timeout
+    Timeout(Duration),
+    Database(sqlx::Error),
+}
+
+impl std::fmt::Display for QueryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotReadOnly(reason) => write!(f, "Only read queries are allowed: {reason}"),
+            Self::InvalidParameter(reason) => write!(f, "Invalid parameter: {reason}"),
+            Self::Timeout(timeout) => {
+                write!(f, "Query exceeded the {}ms timeout", timeout.as_millis())
+            }
+            Self::Database(e) => write!(f, "Query failed: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+impl From<sqlx::Error> for QueryError {
+    fn from(e: sqlx::Error) -> Self {
+        Self::Database(e)
+    }
+}
+
+impl From<QueryError> for tonic::Status {
+    fn from(e: QueryError) -> Self {
+        let message = e.to_string();
+        match e {
+            QueryError::NotReadOnly(_) => Self::permission_denied(message),
+            QueryError::InvalidParameter(_) | QueryError::Database(_) => {
+                Self::invalid_argument(message)
+            }
+            QueryError::Timeout(_) => Self::deadline_exceeded(message),
+        }
+    }
+}
+
+impl QueryError {
+    /// HTTP status of the error
+    pub fn status_code(&self) -> axum::http::StatusCode {
+        use axum::http::StatusCode;
+        match self {
+            Self::NotReadOnly(_) => StatusCode::FORBIDDEN,
+            Self::InvalidParameter(_) | Self::Database(_) => StatusCode::BAD_REQUEST,
+            Self::Timeout(_) => StatusCode::REQUEST_TIMEOUT,
+        }
+    }
+}
+
+/// Checks that `sql` is a single read statement.
+///
+/// Keywords are matched outside of comments, string literals and quoted identifiers.
+/// This is a guard against accidental writes; read-only queries also run in a
+/// `READ ONLY` transaction on PostgreSQL and on a `query_only` connection on SQLite.
+pub fn validate_read_only(sql: &str) -> Result<(), QueryError> {
+    let tokens = tokenize(sql);
+    let Some(first) = tokens.first() else {
+        return Err(QueryError::NotReadOnly("empty statement".to_string()));
+    };
+    if tokens
+        .iter()
+        .skip_while(|t| **t != Token::Semicolon)
+        .any(|t| *t != Token::Semicolon)
+    {
+        return Err(QueryError::NotReadOnly("multiple statements".to_string()));
+    }
+
+    match first {
+        Token::Word(word, _) if READ_STATEMENTS.contains(&word.as_str()) => {}
+        Token::Word(word, _) => {
+            return Err(QueryError::NotReadOnly(format!("`{word}` statement")));
+        }
+        _ => return Err(QueryError::NotReadOnly("not a statement".to_string())),
+    }
+
+    for token in &tokens {
+        let Token::Word(word, is_call) = token else {
+            continue;
+        };
+        // `replace(...)` is a string function.
+        if WRITE_KEYWORDS.contains(&word.as_str()) && !(word == "REPLACE" && *is_call) {
+            return Err(QueryError::NotReadOnly(format!("`{word}` is not allowed")));
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Token {
+    /// Uppercased keyword or identifier, and whether it is followed by `(`
+    Word(String, bool),
+    Semicolon,
+    /// Any other symbol, literal or quoted identifier
+    Other,
+}
+
+/// Splits `sql` into words and symbols, skipping comments, literals and quoted identifiers.
+fn tokenize(sql: &str) -> Vec<Token> {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '-' && next == Some('-') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '/' && next == Some('*') {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                i += 1;
+            }
+            i += 2;
+        } else if matches!(c, '\'' | '"' | '`') {
+            // Quotes are escaped by doubling them.
+            i += 1;
+            while i < chars.len() {
+                if chars[i] == c {
+                    if chars.get(i + 1) == Some(&c) {
+                        i += 2;
+                        continue;
+                    }
+                    break;
+                }
+                i += 1;
+            }
+            i += 1;
+            tokens.push(Token::Other);
+        } else if c == '$' && next.is_some_and(|n| n == '$' || n.is_alphabetic() || n == '_') {
+            // PostgreSQL dollar-quoted string: $tag$ ... $tag$
+            let tag_end = chars[i + 1..]
+                .iter()
+                .position(|c| *c == '$')
+                .map(|offset| i + 1 + offset);
+            let Some(tag_end) = tag_end.filter(|end| {
+                chars[i + 1..*end]
+                    .iter()
+                    .all(|c| c.is_alphanumeric() || *c == '_')
+            }) else {
+                i += 1;
+                tokens.push(Token::Other);
+                continue;
+            };
+            let tag: String = chars[i..=tag_end].iter().collect();
+            let rest: String = chars[tag_end + 1..].iter().collect();
+            i = match rest.find(&tag) {
+                Some(offset) => tag_end + 1 + rest[..offset].chars().count() + tag.chars().count(),
+                None => chars.len(),
+            };
+            tokens.push(Token::Other);
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len()
+                && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '$')
+            {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect::<String>().to_uppercase();
+            let is_call = chars[i..]
+                .iter()
+                .find(|c| !c.is_whitespace())
+                .is_some_and(|c| *c == '(');
+            tokens.push(Token::Word(word, is_call));
+        } else if c == ';' {
+            i += 1;
+            tokens.push(Token::Semicolon);
+        } else {
+            i += 1;
+            tokens.push(Token::Other);
+        }
+    }
+    tokens
+}
+
+fn bind_params<'q, DB>(
+    sql: &'q str,
+    params: &'q [QueryParam],
+) -> Query<'q, DB, <DB as Database>::Arguments<'q>>
+where
+    DB: Database,
+    Option<String>: Encode<'q, DB> + Type<DB>,
+    bool: Encode<'q, DB> + Type<DB>,
+    i64: Encode<'q, DB> + Type<DB>,
+    f64: Encode<'q, DB> + Type<DB>,
+    &'q str: Encode<'q, DB> + Type<DB>,
+{
+    params
+        .iter()
+        .fold(sqlx::query(sql), |query, param| match param {
+            QueryParam::Null => query.bind(None::<String>),
+            QueryParam::Bool(value) => query.bind(*value),
+            QueryParam::Int(value) => query.bind(*value),
+            QueryParam::Float(value) => query.bind(*value),
+            QueryParam::Text(value) => query.bind(value.as_str()),
+        })
+}
+
+fn database_error(e: sqlx::Error, timeout: Duration) -> QueryError {
+    let canceled = e
+        .as_database_error()
+        .and_then(sqlx::error::DatabaseError::code)
+        .is_some_and(|code| code == PG_QUERY_CANCELED || code == SQLITE_INTERRUPT);
+    if canceled {
+        QueryError::Timeout(timeout)
+    } else {
+        QueryError::Database(e)
+    }
+}
+
+/// Next row of `rows`, failing once `deadline` is reached.
+async fn next_row<S>(
+    rows: &mut S,
+    deadline: Instant,
+    timeout: Duration,
+) -> Result<Option<AnyRow>, QueryError>
+where
+    S: Stream<Item = Result<AnyRow, sqlx::Error>> + Unpin,
+{
+    tokio::time::timeout_at(deadline, rows.try_next())
+        .await
+        .map_err(|_| QueryError::Timeout(timeout))?
+        .map_err(|e| database_error(e, timeout))
+}
+
+/// Connections client queries run on
+#[derive(Clone)]
+pub(crate) enum QueryDb {
+    /// Pool dedicated to client queries: each query sets up `query_only` and the
+    /// progress handler of its connection, which the `Any` driver cannot reach.
+    Sqlite(SqlitePool),
+    Postgres(Arc<sqlx::Pool<Any>>),
+}
+
+/// Runs `sql` with `params` under `policy` and streams its rows.
+///
+/// The statement is validated before anything is sent to the database. Row limits
+/// are applied by the caller.
+pub(crate) fn execute(
+    db: QueryDb,
+    policy: QueryPolicy,
+    sql: String,
+    params: Vec<QueryParam>,
+) -> Result<impl Stream<Item = Result<AnyRow, QueryError>> + Send, QueryError> {
+    if !policy.allow_writes {
+        validate_read_only(&sql)?;
+    }
+    let timeout = policy.statement_timeout;
+
+    Ok(try_stream! {
+        let deadline = Instant::now() + timeout;
+        match db {
+            QueryDb::Postgres(pool) if !policy.allow_writes => {
+                let mut tx = tokio::time::timeout_at(deadline, pool.begin())
+                    .await
+                    .map_err(|_| QueryError::Timeout(timeout))??;
+                sqlx::query("SET TRANSACTION READ ONLY").execute(&mut *tx).await?;
+                sqlx::query(&format!("SET LOCAL statement_timeout = {}", timeout.as_millis()))
+                    .execute(&mut *tx)
+                    .await?;
+                let mut rows = bind_params::<Any>(&sql, &params).fetch(&mut *tx);
+                while let Some(row) = next_row(&mut rows, deadline, timeout).await? {
+                    yield row;
+                }
+            }
+            QueryDb::Postgres(pool) => {
+                let mut rows = bind_params::<Any>(&sql, &params).fetch(pool.as_ref());
+                while let Some(row) = next_row(&mut rows, deadline, timeout).await? {
+                    yield row;
+                }
+            }
+            QueryDb::Sqlite(pool) => {
+                let mut conn = tokio::time::timeout_at(deadline, pool.acquire())
+                    .await
+                    .map_err(|_| QueryError::Timeout(timeout))??;
+                // Set on every query: a dropped stream leaves the previous settings behind.
+                let query_only = if policy.allow_writes { "OFF" } else { "ON" };
+                sqlx::query(&format!("PRAGMA query_only = {query_only}"))
+                    .execute(&mut *conn)
+                    .await?;
+                let interrupt_at = deadline.into_std();
+                conn.lock_handle().await?.set_progress_handler(SQLITE_PROGRESS_OPS, move || {
+                    std::time::Instant::now() < interrupt_at
+                });
+                let mut rows = bind_params::<Sqlite>(&sql, &params)
+                    .fetch(&mut *conn)
+                    .map(|row| row.and_then(|row| AnyRow::try_from(&row)));
+                while let Some(row) = next_row(&mut rows, deadline, timeout).await? {
+                    yield row;
+                }
+            }
+        }
+    })
+}
+
+/// Describes the result columns of `sql` without running it.
+///
+/// Fails when the driver cannot prepare the statement or has no `Any` mapping for one
+/// of its column or parameter types.
+pub(crate) async fn describe_columns(
+    pool: &sqlx::Pool<Any>,
+    sql: &str,
+    timeout: Duration,
+) -> Result<Vec<ColumnInfo>, QueryError> {
+    let describe = tokio::time::timeout(timeout, pool.describe(sql))
+        .await
+        .map_err(|_| QueryError::Timeout(timeout))?
+        .map_err(|e| database_error(e, timeout))?;
+    Ok(describe
+        .columns()
+        .iter()
+        .enumerate()
+        .map(|(idx, column)| ColumnInfo {
+            name: column.name().to_string(),
+            sql_type: column.type_info().name().to_string(),
+            nullable: describe.nullable(idx),
+        })
+        .collect())
+}
+
+/// Result columns from the `described` ones and the first row of the result.
+///
+/// Types the database could not tell (e.g. SQLite expressions) are taken from the
+/// values of `first_row`; without a description, its columns are used as is.
+pub(crate) fn result_columns(
+    described: Option<Vec<ColumnInfo>>,
+    first_row: Option<&AnyRow>,
+) -> Vec<ColumnInfo> {
+    let Some(row) = first_row else {
+        return described.unwrap_or_default();
+    };
+    let observed = row.columns().iter().map(|column| {
+        let sql_type = match row.try_get_raw(column.ordinal()) {
+            Ok(raw) if column.type_info().is_null() => raw.type_info().name().to_string(),
+            _ => column.type_info().name().to_string(),
+        };
+        ColumnInfo {
+            name: column.name().to_string(),
+            sql_type,
+            nullable: None,
+        }
+    });
+    match described {
+        Some(mut columns) if columns.len() == row.columns().len() => {
+            for (column, observed) in columns.iter_mut().zip(observed) {
+                // Name of `AnyTypeInfoKind::Null`
+                if column.sql_type == "NULL" {
+                    column.sql_type = observed.sql_type;
+                }
+            }
+            columns
+        }
+        _ => observed.collect(),
+    }
+}
+
+/// Rows of a query, and whether rows beyond the limit were dropped
+pub(crate) struct QueryRows {
+    pub rows: Vec<AnyRow>,
+    pub truncated: bool,
+}
+
+/// Runs `sql` and collects up to `limit` rows.
+pub(crate) async fn fetch_rows(
+    db: QueryDb,
+    policy: QueryPolicy,
+    sql: String,
+    params: Vec<QueryParam>,
+    limit: usize,
+) -> Result<QueryRows, QueryError> {
+    let stream = execute(db, policy, sql, params)?;
+    let mut stream = std::pin::pin!(stream);
+    let mut rows = Vec::new();
+    while let Some(row) = stream.try_next().await? {
+        if rows.len() == limit {
+            return Ok(QueryRows {
+                rows,
+                truncated: true,
+            });
+        }
+        rows.push(row);
+    }
+    Ok(QueryRows {
+        rows,
+        truncated: false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accepts_read_statements() {
+        for sql in [
+            "SELECT * FROM sql_operation",
+            "  select count(*) from sql_operation;  ",
+            "WITH t AS (SELECT 1 AS x) SELECT x FROM t",
+            "VALUES (1), (2)",
+            "EXPLAIN SELECT 1",
+            "SELECT 'DELETE FROM x; DROP TABLE y' AS s -- INSERT\n",
+            "SELECT replace(table_name, 'a', 'b') FROM sql_operation /* UPDATE */",
+            "SELECT \"insert\" FROM t WHERE id = $1",
+            "SELECT $$DROP TABLE x$$",
+        ] {
+            validate_read_only(sql).unwrap_or_else(|e| panic!("{sql}: {e}"));
+        }
+    }
+
+    #[test]
+    fn test_rejects_writes_and_multiple_statements() {
+        for sql in [
+            "",
+            "INSERT INTO sql_operation (table_name) VALUES ('x')",
+            "update sql_operation set value = 1",
+            "DROP TABLE sql_operation",
+            "PRAGMA journal_mode = DELETE",
+            "SELECT 1; DELETE FROM sql_operation",
+            "SELECT 1; SELECT 2",
+            "WITH d AS (DELETE FROM sql_operation RETURNING *) SELECT * FROM d",
+            "SELECT * INTO backup FROM sql_operation",
+            "REPLACE INTO sql_operation (id) VALUES (1)",
+        ] {
+            assert!(
+                matches!(validate_read_only(sql), Err(QueryError::NotReadOnly(_))),
+                "{sql}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_row_limit() {
+        let policy = QueryPolicy::default().with_max_rows(100);
+        assert_eq!(policy.row_limit(None), 100);
+        assert_eq!(policy.row_limit(Some(0)), 100);
+        assert_eq!(policy.row_limit(Some(10)), 10);
+        assert_eq!(policy.row_limit(Some(1_000)), 100);
+    }
+
+    async fn sqlite_pool() -> SqlitePool {
+        sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_fetch_rows_binds_params_and_limits_rows() {
+        let pool = sqlite_pool().await;
+        sqlx::query("CREATE TABLE t (id INTEGER, name TEXT)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        for id in 0..5_i64 {
+            sqlx::query("INSERT INTO t (id, name) VALUES (?, ?)")
+                .bind(id)
+                .bind(format!("row-{id}"))
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        let db = QueryDb::Sqlite(pool.clone());
+
+        let policy = QueryPolicy::default();
+        let result = fetch_rows(
+            db.clone(),
+            policy,
+            "SELECT id, name FROM t WHERE id >= ? ORDER BY id".to_string(),
+            vec![QueryParam::Int(1)],
+            3,
+        )
+        .await
+        .unwrap();
+        assert!(result.truncated);
+        assert_eq!(result.rows.len(), 3);
+        assert_eq!(result.rows[0].get::<String, _>("name"), "row-1");
+
+        let result = fetch_rows(
+            db.clone(),
+            policy,
+            "SELECT id FROM t WHERE name = ?".to_string(),
+            vec![QueryParam::Text("row-4".to_string())],
+            3,
+        )
+        .await
+        .unwrap();
+        assert!(!result.truncated);
+        assert_eq!(result.rows.len(), 1);
+
+        let write = "DELETE FROM t".to_string();
+        assert!(matches!(
+            fetch_rows(db.clone(), policy, write.clone(), Vec::new(), 3).await,
+            Err(QueryError::NotReadOnly(_))
+        ));
+        fetch_rows(
+            db.clone(),
+            policy.with_allow_writes(true),
+            write,
+            Vec::new(),
+            3,
+        )
+        .await
+        .unwrap();
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM t")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 0);
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_queries_run_read_only_and_are_interrupted() {
+        let db = QueryDb::Sqlite(sqlite_pool().await);
+        let policy = QueryPolicy::default().with_statement_timeout(Duration::from_millis(200));
+        let query_only = |policy| {
+            fetch_rows(
+                db.clone(),
+                policy,
+                "SELECT query_only FROM pragma_query_only".to_string(),
+                Vec::new(),
+                1,
+            )
+        };
+        let read = query_only(policy).await.unwrap();
+        assert_eq!(read.rows[0].get::<i64, _>(0), 1);
+        let write = query_only(policy.with_allow_writes(true)).await.unwrap();
+        assert_eq!(write.rows[0].get::<i64, _>(0), 0);
+
+        let started = std::time::Instant::now();
+        let endless = "WITH RECURSIVE c(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM c) \
+                       SELECT count(*) FROM c";
+        assert!(matches!(
+            fetch_rows(db.clone(), policy, endless.to_string(), Vec::new(), 1).await,
+            Err(QueryError::Timeout(_))
+        ));
+        // The statement was stopped: the only connection serves the next query.
+        let result = query_only(policy).await.unwrap();
+        assert_eq!(result.rows.len(), 1);
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn test_column_metadata_and_typed_values() {
+        sqlx::any::install_default_drivers();
+        let pool = sqlx::any::AnyPoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::query("CREATE TABLE t (id INTEGER NOT NULL, name TEXT, data BLOB, score REAL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO t (id, name, data, score) VALUES (7, NULL, x'0102', 1.5)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let sql = "SELECT id, name, data, score, 'a' || id AS label FROM t";
+        let described = describe_columns(&pool, sql, DEFAULT_STATEMENT_TIMEOUT)
+            .await
+            .unwrap();
+        let row = sqlx::query(sql).fetch_one(&pool).await.unwrap();
+        let columns = result_columns(Some(described), Some(&row));
+        let column = |name: &str, sql_type: &str, nullable: bool| ColumnInfo {
+            name: name.to_string(),
+            sql_type: sql_type.to_string(),
+            nullable: Some(nullable),
+        };
+        assert_eq!(
+            columns,
+            vec![
+                column("id", "BIGINT", false),
+                column("name", "TEXT", true),
+                column("data", "BLOB", true),
+                column("score", "DOUBLE", true),
+                column("label", "TEXT", false),
+            ]
+        );
+        assert_eq!(
+            QueryValue::decode_row(&row).unwrap(),
+            vec![
+                QueryValue::Int(7),
+                QueryValue::Null,
+                QueryValue::Bytes(vec![1, 2]),
+                QueryValue::Float(1.5),
+                QueryValue::Text("a7".to_string()),
+            ]
+        );
+
+        // Without a description, the columns come from the row.
+        let observed = result_columns(None, Some(&row));
+        assert_eq!(
+            observed
+                .iter()
+                .map(|column| (
+                    column.name.as_str(),
+                    column.sql_type.as_str(),
+                    column.nullable
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("id", "BIGINT", None),
+                ("name", "TEXT", None),
+                ("data", "BLOB", None),
+                ("score", "DOUBLE", None),
+                ("label", "TEXT", None),
+            ]
+        );
+    }
+}
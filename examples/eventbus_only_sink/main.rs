@@ -20,6 +20,7 @@ use torii::etl::envelope::{Envelope, TypeId, TypedBody};
 use torii::etl::extractor::ExtractionBatch;
 use torii::etl::sink::{EventBus, Sink, SinkContext, TopicInfo};
 use torii::etl::Decoder;
+use torii::ToriiResult;
 use torii::{async_trait, run, ToriiConfig, UpdateType};
 
 // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
@@ -112,13 +113,17 @@ impl Sink for BroadcastSink {
         )]
     }
 
-    async fn initialize(&mut self, event_bus: Arc<EventBus>, _context: &SinkContext) -> Result<()> {
+    async fn initialize(
+        &mut self,
+        event_bus: Arc<EventBus>,
+        _context: &SinkContext,
+    ) -> ToriiResult<()> {
         self.event_bus = Some(event_bus);
         tracing::info!("BroadcastSink initialized (EventBus only, no storage)");
         Ok(())
     }
 
-    async fn process(&self, envelopes: &[Envelope], _batch: &ExtractionBatch) -> Result<()> {
+    async fn process(&self, envelopes: &[Envelope], _batch: &ExtractionBatch) -> ToriiResult<()> {
         let Some(event_bus) = &self.event_bus else {
             return Ok(());
         };
@@ -131,7 +136,9 @@ impl Sink for BroadcastSink {
             if let Some(broadcast_event) = envelope.downcast_ref::<BroadcastEvent>() {
                 // Encode as protobuf Any
                 let mut buf = Vec::new();
-                broadcast_event.encode(&mut buf)?;
+                broadcast_event
+                    .encode(&mut buf)
+                    .map_err(anyhow::Error::from)?;
                 let any = Any {
                     type_url: "type.googleapis.com/torii.example.BroadcastEvent".to_string(),
                     value: buf,
@@ -189,7 +196,7 @@ impl Decoder for BroadcastDecoder {
         "broadcast"
     }
 
-    async fn decode_event(&self, event: &EmittedEvent) -> Result<Vec<Envelope>> {
+    async fn decode_event(&self, event: &EmittedEvent) -> ToriiResult<Vec<Envelope>> {
         let broadcast_event = BroadcastEvent {
             event_id: format!("{:#x}", event.transaction_hash),
             from_address: format!("{:#x}", event.from_address),
@@ -258,5 +265,5 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     println!("🚀 Starting server...\n");
 
-    run(config).await
+    Ok(run(config).await?)
 }
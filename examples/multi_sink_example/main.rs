@@ -233,5 +233,5 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // 7. RUN SERVER
     // ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
 
-    run(config).await
+    Ok(run(config).await?)
 }
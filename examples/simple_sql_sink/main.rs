@@ -82,5 +82,5 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("Starting server...\n");
 
     // 5. Run! (auto-discovers and registers everything)
-    run(config).await
+    Ok(run(config).await?)
 }
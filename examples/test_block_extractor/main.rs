@@ -43,9 +43,15 @@ async fn main() -> Result<()> {
         batch_size: 5,
         retry_policy: RetryPolicy::default(),
         rpc_parallelism: 0,
+        max_concurrent_block_fetches: 0,
+        adaptive_batch: None,
+        include_receipts: false,
+        confirmation_depth: 0,
+        consolidate_event_cursors: false,
+        ignore_saved_state: false,
     };
 
-    let provider = JsonRpcClient::new(HttpTransport::new(Url::parse(&config.rpc_url)?));
+    let provider = JsonRpcClient::new(HttpTransport::new(Url::parse(&config.rpc_url)?).into());
 
     let mut extractor = BlockRangeExtractor::new(Arc::new(provider), config);
 
@@ -0,0 +1,10 @@
+-- Per-contract indexing statistics (one row per contract and decoder)
+CREATE TABLE IF NOT EXISTS engine.contract_stats (
+    contract_address TEXT NOT NULL,
+    decoder TEXT NOT NULL,
+    event_count BIGINT NOT NULL DEFAULT 0,
+    first_block BIGINT NOT NULL,
+    last_block BIGINT NOT NULL,
+    last_activity BIGINT NOT NULL,
+    PRIMARY KEY (contract_address, decoder)
+);
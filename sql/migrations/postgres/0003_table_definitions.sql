@@ -0,0 +1,12 @@
+-- Versioned table definitions persisted by decoders (e.g. Dojo models and events)
+CREATE TABLE IF NOT EXISTS engine.table_definitions (
+    decoder TEXT NOT NULL,
+    owner TEXT NOT NULL,
+    table_id TEXT NOT NULL,
+    version BIGINT NOT NULL,
+    definition TEXT NOT NULL,
+    block_number BIGINT NOT NULL,
+    tx_hash TEXT NOT NULL,
+    created_at BIGINT NOT NULL DEFAULT (EXTRACT(EPOCH FROM NOW())::BIGINT),
+    PRIMARY KEY (decoder, owner, table_id, version)
+);
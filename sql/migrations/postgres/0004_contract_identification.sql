@@ -0,0 +1,3 @@
+-- Identification provenance for the contract registry cache
+ALTER TABLE engine.contract_decoders ADD COLUMN IF NOT EXISTS source TEXT NOT NULL DEFAULT 'unknown';
+ALTER TABLE engine.contract_decoders ADD COLUMN IF NOT EXISTS class_hash TEXT;
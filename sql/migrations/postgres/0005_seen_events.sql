@@ -0,0 +1,7 @@
+-- Dedupe window of recently processed events (most recent rows have the highest id)
+CREATE TABLE IF NOT EXISTS engine.seen_events (
+    id BIGSERIAL PRIMARY KEY,
+    tx_hash TEXT NOT NULL,
+    event_index BIGINT NOT NULL,
+    UNIQUE (tx_hash, event_index)
+);
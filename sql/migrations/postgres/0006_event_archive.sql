@@ -0,0 +1,27 @@
+-- Archive of raw extracted events, replayed through decoders and sinks (--replay-from-block)
+CREATE TABLE IF NOT EXISTS engine.archived_blocks (
+    block_number BIGINT PRIMARY KEY,
+    block_hash TEXT NOT NULL,
+    parent_hash TEXT NOT NULL,
+    timestamp BIGINT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS engine.archived_transactions (
+    tx_hash TEXT PRIMARY KEY,
+    block_number BIGINT NOT NULL,
+    sender_address TEXT,
+    calldata TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS engine.archived_events (
+    id BIGSERIAL PRIMARY KEY,
+    block_number BIGINT NOT NULL,
+    tx_hash TEXT NOT NULL,
+    event_index BIGINT NOT NULL,
+    from_address TEXT NOT NULL,
+    event_keys TEXT NOT NULL,
+    event_data TEXT NOT NULL,
+    UNIQUE (tx_hash, event_index)
+);
+
+CREATE INDEX IF NOT EXISTS idx_archived_events_block ON engine.archived_events(block_number, id);
@@ -0,0 +1,6 @@
+-- Deployment blocks detected for contracts configured without a start block
+CREATE TABLE IF NOT EXISTS engine.contract_deployments (
+    contract_address TEXT PRIMARY KEY,
+    block_number BIGINT NOT NULL,
+    detected_at BIGINT NOT NULL
+);
@@ -0,0 +1,14 @@
+-- Raw event archive as zstd-compressed chunks, one per processed batch.
+-- Supersedes the row-per-event tables of 0006, which are kept readable for older archives.
+CREATE TABLE IF NOT EXISTS engine.archived_batches (
+    id BIGSERIAL PRIMARY KEY,
+    from_block BIGINT NOT NULL,
+    to_block BIGINT NOT NULL,
+    event_count BIGINT NOT NULL,
+    raw_size BIGINT NOT NULL,
+    data BYTEA NOT NULL,
+    archived_at BIGINT NOT NULL,
+    UNIQUE (from_block, to_block)
+);
+
+CREATE INDEX IF NOT EXISTS idx_archived_batches_to_block ON engine.archived_batches(to_block);
@@ -0,0 +1,6 @@
+-- Contract shards leased by the indexer instances sharing this database
+CREATE TABLE IF NOT EXISTS engine.shard_leases (
+    shard BIGINT PRIMARY KEY,
+    owner TEXT NOT NULL,
+    expires_at BIGINT NOT NULL
+);
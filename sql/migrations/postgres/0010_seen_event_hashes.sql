@@ -0,0 +1,10 @@
+-- Dedupe keys identify events by content instead of their position in a batch:
+-- the previous keys cannot be converted, so the window starts over.
+DROP TABLE IF EXISTS engine.seen_events;
+
+CREATE TABLE engine.seen_events (
+    id BIGSERIAL PRIMARY KEY,
+    tx_hash TEXT NOT NULL,
+    event_hash BIGINT NOT NULL,
+    UNIQUE (tx_hash, event_hash)
+);
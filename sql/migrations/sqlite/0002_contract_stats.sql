@@ -0,0 +1,10 @@
+-- Per-contract indexing statistics (one row per contract and decoder)
+CREATE TABLE IF NOT EXISTS contract_stats (
+    contract_address TEXT NOT NULL,      -- Hex string of contract address
+    decoder TEXT NOT NULL,               -- Decoder name
+    event_count INTEGER NOT NULL DEFAULT 0,
+    first_block INTEGER NOT NULL,
+    last_block INTEGER NOT NULL,
+    last_activity INTEGER NOT NULL,      -- Block timestamp of the last indexed event
+    PRIMARY KEY (contract_address, decoder)
+);
@@ -0,0 +1,12 @@
+-- Versioned table definitions persisted by decoders (e.g. Dojo models and events)
+CREATE TABLE IF NOT EXISTS table_definitions (
+    decoder TEXT NOT NULL,               -- Decoder owning the definitions (e.g. "dojo")
+    owner TEXT NOT NULL,                 -- Hex string of the contract declaring the table
+    table_id TEXT NOT NULL,              -- Hex string of the table id
+    version INTEGER NOT NULL,            -- 1 on registration, incremented on each upgrade
+    definition TEXT NOT NULL,            -- JSON-serialized table definition
+    block_number INTEGER NOT NULL,
+    tx_hash TEXT NOT NULL,
+    created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+    PRIMARY KEY (decoder, owner, table_id, version)
+);
@@ -0,0 +1,3 @@
+-- Identification provenance for the contract registry cache
+ALTER TABLE contract_decoders ADD COLUMN source TEXT NOT NULL DEFAULT 'unknown';  -- explicit, rule:<names>, proxy:<implementation>
+ALTER TABLE contract_decoders ADD COLUMN class_hash TEXT;                          -- Class hash observed at identification time
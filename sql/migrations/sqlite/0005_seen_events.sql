@@ -0,0 +1,7 @@
+-- Dedupe window of recently processed events (most recent rows have the highest id)
+CREATE TABLE IF NOT EXISTS seen_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    tx_hash TEXT NOT NULL,               -- Hex string of the transaction hash
+    event_index INTEGER NOT NULL,        -- Position of the event in its transaction
+    UNIQUE (tx_hash, event_index)
+);
@@ -0,0 +1,27 @@
+-- Archive of raw extracted events, replayed through decoders and sinks (--replay-from-block)
+CREATE TABLE IF NOT EXISTS archived_blocks (
+    block_number INTEGER PRIMARY KEY,
+    block_hash TEXT NOT NULL,            -- Hex string of the block hash
+    parent_hash TEXT NOT NULL,           -- Hex string of the parent block hash
+    timestamp INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS archived_transactions (
+    tx_hash TEXT PRIMARY KEY,            -- Hex string of the transaction hash
+    block_number INTEGER NOT NULL,
+    sender_address TEXT,                 -- Hex string of the sender (if any)
+    calldata TEXT NOT NULL               -- JSON array of hex felts
+);
+
+CREATE TABLE IF NOT EXISTS archived_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT, -- Archival order
+    block_number INTEGER NOT NULL,
+    tx_hash TEXT NOT NULL,
+    event_index INTEGER NOT NULL,        -- Position of the event in its transaction
+    from_address TEXT NOT NULL,          -- Hex string of the emitting contract
+    event_keys TEXT NOT NULL,            -- JSON array of hex felts
+    event_data TEXT NOT NULL,            -- JSON array of hex felts
+    UNIQUE (tx_hash, event_index)
+);
+
+CREATE INDEX IF NOT EXISTS idx_archived_events_block ON archived_events(block_number, id);
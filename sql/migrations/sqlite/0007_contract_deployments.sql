@@ -0,0 +1,6 @@
+-- Deployment blocks detected for contracts configured without a start block
+CREATE TABLE IF NOT EXISTS contract_deployments (
+    contract_address TEXT PRIMARY KEY,   -- Hex string of the contract address
+    block_number INTEGER NOT NULL,       -- First block with a class hash at the address
+    detected_at INTEGER NOT NULL         -- Unix timestamp of the detection
+);
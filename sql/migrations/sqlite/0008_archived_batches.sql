@@ -0,0 +1,14 @@
+-- Raw event archive as zstd-compressed chunks, one per processed batch.
+-- Supersedes the row-per-event tables of 0006, which are kept readable for older archives.
+CREATE TABLE IF NOT EXISTS archived_batches (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    from_block INTEGER NOT NULL,
+    to_block INTEGER NOT NULL,
+    event_count INTEGER NOT NULL,
+    raw_size INTEGER NOT NULL,           -- Size of the uncompressed payload in bytes
+    data BLOB NOT NULL,                  -- zstd-compressed JSON payload
+    archived_at INTEGER NOT NULL,
+    UNIQUE (from_block, to_block)
+);
+
+CREATE INDEX IF NOT EXISTS idx_archived_batches_to_block ON archived_batches(to_block);
@@ -0,0 +1,6 @@
+-- Contract shards leased by the indexer instances sharing this database
+CREATE TABLE IF NOT EXISTS shard_leases (
+    shard INTEGER PRIMARY KEY,           -- Shard number (0..shard_count)
+    owner TEXT NOT NULL,                 -- Instance id holding the lease
+    expires_at INTEGER NOT NULL          -- Unix timestamp the lease expires at
+);
@@ -0,0 +1,10 @@
+-- Dedupe keys identify events by content instead of their position in a batch:
+-- the previous keys cannot be converted, so the window starts over.
+DROP TABLE IF EXISTS seen_events;
+
+CREATE TABLE seen_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    tx_hash TEXT NOT NULL,               -- Hex string of the transaction hash
+    event_hash INTEGER NOT NULL,         -- Hash of the event content (see `dedupe::event_keys`)
+    UNIQUE (tx_hash, event_hash)
+);
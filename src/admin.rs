@@ -0,0 +1,732 @@
+//! Runtime control of the ETL loop through the `torii.Admin` gRPC service.
+//!
+//! [`EtlControl`] is shared between the extract stage and [`AdminService`]:
+//! - while paused, no new batch is extracted (batches already in flight are processed),
+//! - a triggered cycle ends the wait between extractions once caught up with the chain head,
+//! - the cycle interval applies from the next wait on,
+//! - [`EtlParams`] changed with `SetEtlParams` apply to the extractor from its next
+//!   extraction, and are persisted in the engine database to be restored on restart.
+//!
+//! The service also manages the [`AddressLabels`] attached to token query responses,
+//! when a store is configured.
+//!
+//! Like `EnterLameDuck`, the RPCs are rejected unless admin RPCs are enabled. When API
+//! keys are configured, [`create_admin_service`] only admits keys permitted every
+//! namespace; otherwise the service is unauthenticated and must not be reachable from
+//! untrusted networks.
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{watch, Mutex, Notify};
+use tonic::service::interceptor::InterceptedService;
+use tonic::{Request, Response, Status};
+use torii_common::{bytes_to_felt, AddressLabel, AddressLabels};
+
+use crate::etl::extractor::Extractor;
+use crate::etl::EngineDb;
+use crate::grpc::proto::{
+    self,
+    admin_server::{Admin, AdminServer},
+    DeleteAddressLabelsRequest, DeleteAddressLabelsResponse, ListAddressLabelsRequest,
+    ListAddressLabelsResponse, PauseIndexingRequest, PauseIndexingResponse, ResumeIndexingRequest,
+    ResumeIndexingResponse, SetAddressLabelsRequest, SetAddressLabelsResponse,
+    SetCycleIntervalRequest, SetCycleIntervalResponse, SetEtlParamsRequest, SetEtlParamsResponse,
+    TriggerCycleNowRequest, TriggerCycleNowResponse,
+};
+use crate::grpc::GrpcServerOptions;
+use crate::namespace::{NamespaceGuard, Namespaces};
+
+/// Stats key holding the serialized [`EtlParams`].
+const ETL_PARAMS_KEY: &str = "etl_params";
+
+/// ETL loop parameters tuned at runtime with `SetEtlParams`.
+///
+/// Unset parameters keep the configured value.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EtlParams {
+    /// Delay between extractions once caught up, in seconds
+    pub cycle_interval: Option<u64>,
+    /// Events fetched per extraction (event-based extractors)
+    pub events_per_cycle: Option<u64>,
+    /// Blocks covered per extraction
+    pub batch_size: Option<u64>,
+}
+
+impl EtlParams {
+    /// Loads the persisted parameters (none set if nothing was persisted yet).
+    pub async fn load(engine_db: &EngineDb) -> anyhow::Result<Self> {
+        match engine_db.get_stat(ETL_PARAMS_KEY).await? {
+            Some(raw) => serde_json::from_str(&raw).context("Invalid persisted ETL parameters"),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Persists the parameters.
+    pub async fn persist(&self, engine_db: &EngineDb) -> anyhow::Result<()> {
+        let raw = serde_json::to_string(self)?;
+        engine_db.set_stat(ETL_PARAMS_KEY, &raw).await
+    }
+
+    /// `self` with the parameters set in `changes` replaced.
+    pub fn merge(self, changes: Self) -> Self {
+        Self {
+            cycle_interval: changes.cycle_interval.or(self.cycle_interval),
+            events_per_cycle: changes.events_per_cycle.or(self.events_per_cycle),
+            batch_size: changes.batch_size.or(self.batch_size),
+        }
+    }
+
+    /// Applies the extractor sizes to `extractor`, warning about unsupported ones.
+    pub fn apply_to(&self, extractor: &mut dyn Extractor) {
+        if let Some(events) = self.events_per_cycle {
+            if !extractor.set_events_per_cycle(events) {
+                tracing::warn!(
+                    target: "torii::admin",
+                    extractor = extractor.extractor_type(),
+                    "Extractor has no events per cycle setting, ignoring it"
+                );
+            }
+        }
+        if let Some(blocks) = self.batch_size {
+            if !extractor.set_batch_size(blocks) {
+                tracing::warn!(
+                    target: "torii::admin",
+                    extractor = extractor.extractor_type(),
+                    "Extractor has no batch size setting, ignoring it"
+                );
+            }
+        }
+    }
+}
+
+/// Shared control state of the ETL loop.
+#[derive(Debug, Clone)]
+pub struct EtlControl {
+    paused: Arc<watch::Sender<bool>>,
+    trigger: Arc<Notify>,
+    cycle_interval_secs: Arc<AtomicU64>,
+    params: Arc<watch::Sender<EtlParams>>,
+}
+
+impl EtlControl {
+    /// Creates the control state of a running ETL loop waiting `cycle_interval` between
+    /// extractions once caught up.
+    pub fn new(cycle_interval: Duration) -> Self {
+        Self {
+            paused: Arc::new(watch::Sender::new(false)),
+            trigger: Arc::new(Notify::new()),
+            cycle_interval_secs: Arc::new(AtomicU64::new(cycle_interval.as_secs())),
+            params: Arc::new(watch::Sender::new(EtlParams::default())),
+        }
+    }
+
+    /// Whether extraction is paused.
+    pub fn is_paused(&self) -> bool {
+        *self.paused.borrow()
+    }
+
+    /// Pauses extraction. Returns `false` if it was already paused.
+    pub fn pause(&self) -> bool {
+        let changed = self
+            .paused
+            .send_if_modified(|paused| !std::mem::replace(paused, true));
+        if changed {
+            ::metrics::gauge!("torii_etl_paused").set(1.0);
+        }
+        changed
+    }
+
+    /// Resumes extraction. Returns `false` if it was not paused.
+    pub fn resume(&self) -> bool {
+        let changed = self
+            .paused
+            .send_if_modified(|paused| std::mem::replace(paused, false));
+        if changed {
+            ::metrics::gauge!("torii_etl_paused").set(0.0);
+        }
+        changed
+    }
+
+    /// Completes once extraction is not paused.
+    pub async fn resumed(&self) {
+        let mut paused = self.paused.subscribe();
+        // The sender lives as long as `self`.
+        let _ = paused.wait_for(|paused| !paused).await;
+    }
+
+    /// Ends the current (or next) wait between extractions.
+    pub fn trigger_cycle(&self) {
+        self.trigger.notify_one();
+    }
+
+    /// Delay between extractions once caught up with the chain head.
+    pub fn cycle_interval(&self) -> Duration {
+        Duration::from_secs(self.cycle_interval_secs.load(Ordering::Relaxed))
+    }
+
+    /// Sets the delay between extractions, returning the previous one.
+    pub fn set_cycle_interval(&self, interval: Duration) -> Duration {
+        Duration::from_secs(
+            self.cycle_interval_secs
+                .swap(interval.as_secs(), Ordering::Relaxed),
+        )
+    }
+
+    /// Parameters set at runtime, with the current cycle interval.
+    pub fn params(&self) -> EtlParams {
+        EtlParams {
+            cycle_interval: Some(self.cycle_interval().as_secs()),
+            ..*self.params.borrow()
+        }
+    }
+
+    /// Applies the parameters set in `changes` together: the cycle interval right away,
+    /// the extractor sizes from the next extraction. Returns the parameters in effect.
+    pub fn set_params(&self, changes: EtlParams) -> EtlParams {
+        if let Some(seconds) = changes.cycle_interval {
+            self.set_cycle_interval(Duration::from_secs(seconds));
+        }
+        self.params
+            .send_modify(|params| *params = params.merge(changes));
+        self.params()
+    }
+
+    /// Receiver of the parameters, notified when they change.
+    pub fn subscribe_params(&self) -> watch::Receiver<EtlParams> {
+        self.params.subscribe()
+    }
+
+    /// Waits for the cycle interval, or less if a cycle is triggered.
+    pub async fn wait_cycle(&self) {
+        tokio::select! {
+            () = tokio::time::sleep(self.cycle_interval()) => {}
+            () = self.trigger.notified() => {}
+        }
+    }
+}
+
+impl Default for EtlControl {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(3))
+    }
+}
+
+/// `torii.Admin` gRPC service.
+#[derive(Clone)]
+pub struct AdminService {
+    control: EtlControl,
+    enabled: bool,
+    labels: Option<AddressLabels>,
+    engine_db: Option<Arc<EngineDb>>,
+    /// Serializes `SetEtlParams` so the persisted and applied parameters agree.
+    params_lock: Arc<Mutex<()>>,
+}
+
+impl AdminService {
+    /// Creates the service; every RPC is rejected unless `enabled`.
+    pub fn new(control: EtlControl, enabled: bool) -> Self {
+        Self {
+            control,
+            enabled,
+            labels: None,
+            engine_db: None,
+            params_lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// Persists the parameters changed with `SetEtlParams` in `engine_db`.
+    #[must_use]
+    pub fn with_engine_db(mut self, engine_db: Arc<EngineDb>) -> Self {
+        self.engine_db = Some(engine_db);
+        self
+    }
+
+    /// Manages `labels` through the address label RPCs.
+    #[must_use]
+    pub fn with_address_labels(mut self, labels: AddressLabels) -> Self {
+        self.labels = Some(labels);
+        self
+    }
+
+    fn check_enabled(&self) -> Result<(), Status> {
+        if self.enabled {
+            Ok(())
+        } else {
+            Err(Status::permission_denied("Admin RPCs are disabled"))
+        }
+    }
+
+    fn labels(&self) -> Result<&AddressLabels, Status> {
+        self.check_enabled()?;
+        self.labels
+            .as_ref()
+            .ok_or_else(|| Status::failed_precondition("Address labels are not configured"))
+    }
+}
+
+fn address_from_bytes(bytes: &[u8]) -> Result<starknet::core::types::Felt, Status> {
+    bytes_to_felt(bytes).ok_or_else(|| Status::invalid_argument("Address exceeds 32 bytes"))
+}
+
+fn label_to_proto(label: AddressLabel) -> proto::AddressLabel {
+    proto::AddressLabel {
+        address: label.address.to_bytes_be().to_vec(),
+        name: label.name,
+        tags: label.tags,
+    }
+}
+
+#[tonic::async_trait]
+impl Admin for AdminService {
+    async fn pause_indexing(
+        &self,
+        _request: Request<PauseIndexingRequest>,
+    ) -> Result<Response<PauseIndexingResponse>, Status> {
+        self.check_enabled()?;
+        let already_paused = !self.control.pause();
+        if !already_paused {
+            tracing::info!(target: "torii::admin", "Indexing paused through admin RPC");
+        }
+        Ok(Response::new(PauseIndexingResponse { already_paused }))
+    }
+
+    async fn resume_indexing(
+        &self,
+        _request: Request<ResumeIndexingRequest>,
+    ) -> Result<Response<ResumeIndexingResponse>, Status> {
+        self.check_enabled()?;
+        let was_paused = self.control.resume();
+        if was_paused {
+            tracing::info!(target: "torii::admin", "Indexing resumed through admin RPC");
+        }
+        Ok(Response::new(ResumeIndexingResponse { was_paused }))
+    }
+
+    async fn trigger_cycle_now(
+        &self,
+        _request: Request<TriggerCycleNowRequest>,
+    ) -> Result<Response<TriggerCycleNowResponse>, Status> {
+        self.check_enabled()?;
+        if self.control.is_paused() {
+            return Err(Status::failed_precondition("Indexing is paused"));
+        }
+        self.control.trigger_cycle();
+        Ok(Response::new(TriggerCycleNowResponse {}))
+    }
+
+    async fn set_cycle_interval(
+        &self,
+        request: Request<SetCycleIntervalRequest>,
+    ) -> Result<Response<SetCycleIntervalResponse>, Status> {
+        self.check_enabled()?;
+        let seconds = request.into_inner().seconds;
+        if seconds == 0 {
+            return Err(Status::invalid_argument(
+                "Cycle interval must be at least 1 second",
+            ));
+        }
+        let previous = self
+            .control
+            .set_cycle_interval(Duration::from_secs(seconds));
+        tracing::info!(
+            target: "torii::admin",
+            previous_seconds = previous.as_secs(),
+            seconds,
+            "Cycle interval changed through admin RPC"
+        );
+        Ok(Response::new(SetCycleIntervalResponse {
+            previous_seconds: previous.as_secs(),
+        }))
+    }
+
+    async fn set_etl_params(
+        &self,
+        request: Request<SetEtlParamsRequest>,
+    ) -> Result<Response<SetEtlParamsResponse>, Status> {
+        self.check_enabled()?;
+        let request = request.into_inner();
+        let changes = EtlParams {
+            cycle_interval: request.cycle_interval,
+            events_per_cycle: request.events_per_cycle,
+            batch_size: request.batch_size,
+        };
+        for (name, value) in [
+            ("cycle_interval", changes.cycle_interval),
+            ("events_per_cycle", changes.events_per_cycle),
+            ("batch_size", changes.batch_size),
+        ] {
+            if value == Some(0) {
+                return Err(Status::invalid_argument(format!(
+                    "{name} must be at least 1"
+                )));
+            }
+        }
+
+        let _guard = self.params_lock.lock().await;
+        // Only the parameters set through this RPC are persisted, so the ones never set
+        // keep following the configuration on restart.
+        if let Some(engine_db) = &self.engine_db {
+            let stored = EtlParams::load(engine_db)
+                .await
+                .map_err(|e| Status::internal(format!("Failed to load ETL parameters: {e}")))?;
+            stored
+                .merge(changes)
+                .persist(engine_db)
+                .await
+                .map_err(|e| Status::internal(format!("Failed to persist ETL parameters: {e}")))?;
+        }
+        let params = self.control.set_params(changes);
+        tracing::info!(
+            target: "torii::admin",
+            cycle_interval = ?params.cycle_interval,
+            events_per_cycle = ?params.events_per_cycle,
+            batch_size = ?params.batch_size,
+            "ETL parameters changed through admin RPC"
+        );
+        Ok(Response::new(SetEtlParamsResponse {
+            cycle_interval: params.cycle_interval.unwrap_or_default(),
+            events_per_cycle: params.events_per_cycle,
+            batch_size: params.batch_size,
+        }))
+    }
+
+    async fn set_address_labels(
+        &self,
+        request: Request<SetAddressLabelsRequest>,
+    ) -> Result<Response<SetAddressLabelsResponse>, Status> {
+        let store = self.labels()?;
+        let labels = request
+            .into_inner()
+            .labels
+            .into_iter()
+            .map(|label| {
+                if label.name.is_empty() {
+                    return Err(Status::invalid_argument("Label name is required"));
+                }
+                Ok(
+                    AddressLabel::new(address_from_bytes(&label.address)?, label.name)
+                        .with_tags(label.tags),
+                )
+            })
+            .collect::<Result<Vec<_>, Status>>()?;
+        let count = labels.len();
+        store
+            .upsert(labels)
+            .await
+            .map_err(|e| Status::internal(format!("Failed to store labels: {e}")))?;
+        tracing::info!(target: "torii::admin", count, "Address labels set through admin RPC");
+        Ok(Response::new(SetAddressLabelsResponse {}))
+    }
+
+    async fn delete_address_labels(
+        &self,
+        request: Request<DeleteAddressLabelsRequest>,
+    ) -> Result<Response<DeleteAddressLabelsResponse>, Status> {
+        let store = self.labels()?;
+        let addresses = request
+            .into_inner()
+            .addresses
+            .iter()
+            .map(|address| address_from_bytes(address))
+            .collect::<Result<Vec<_>, Status>>()?;
+        let deleted = store
+            .remove(&addresses)
+            .await
+            .map_err(|e| Status::internal(format!("Failed to delete labels: {e}")))?;
+        tracing::info!(target: "torii::admin", deleted, "Address labels deleted through admin RPC");
+        Ok(Response::new(DeleteAddressLabelsResponse {
+            deleted: deleted as u32,
+        }))
+    }
+
+    async fn list_address_labels(
+        &self,
+        _request: Request<ListAddressLabelsRequest>,
+    ) -> Result<Response<ListAddressLabelsResponse>, Status> {
+        let labels = self
+            .labels()?
+            .list()
+            .into_iter()
+            .map(label_to_proto)
+            .collect();
+        Ok(Response::new(ListAddressLabelsResponse { labels }))
+    }
+}
+
+/// Creates the `torii.Admin` gRPC server.
+pub fn create_admin_service(
+    control: EtlControl,
+    enabled: bool,
+    labels: Option<AddressLabels>,
+    engine_db: Arc<EngineDb>,
+    namespaces: &Namespaces,
+    options: &GrpcServerOptions,
+) -> InterceptedService<AdminServer<AdminService>, NamespaceGuard> {
+    let mut service = AdminService::new(control, enabled).with_engine_db(engine_db);
+    if let Some(labels) = labels {
+        service = service.with_address_labels(labels);
+    }
+    InterceptedService::new(
+        crate::configure_grpc_server!(AdminServer::new(service), options),
+        namespaces.admin_interceptor(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn admin_rpcs_require_enabling() {
+        let control = EtlControl::default();
+        let denied = AdminService::new(control.clone(), false)
+            .pause_indexing(Request::new(PauseIndexingRequest {}))
+            .await
+            .unwrap_err();
+        assert_eq!(denied.code(), tonic::Code::PermissionDenied);
+        assert!(!control.is_paused());
+    }
+
+    #[tokio::test]
+    async fn admin_service_requires_an_admin_api_key() {
+        let engine_db = Arc::new(
+            EngineDb::new(crate::etl::engine_db::EngineDbConfig {
+                path: ":memory:".to_string(),
+            })
+            .await
+            .unwrap(),
+        );
+        let namespaces = Namespaces::new()
+            .with_api_key("key-a", ["game-a"])
+            .with_api_key("admin", [crate::namespace::ALL_NAMESPACES]);
+        let mut service = create_admin_service(
+            EtlControl::default(),
+            true,
+            None,
+            engine_db,
+            &namespaces,
+            &GrpcServerOptions::default(),
+        );
+
+        for (key, code) in [
+            (None, tonic::Code::Unauthenticated),
+            (Some("key-a"), tonic::Code::PermissionDenied),
+        ] {
+            let mut request = axum::http::Request::post("/torii.Admin/PauseIndexing");
+            if let Some(key) = key {
+                request = request.header(crate::namespace::API_KEY_HEADER, key);
+            }
+            let request = request.body(tonic::body::empty_body()).unwrap();
+            let response = tower::Service::call(&mut service, request).await.unwrap();
+            let status = response.headers().get("grpc-status").unwrap();
+            assert_eq!(status.to_str().unwrap(), (code as i32).to_string());
+        }
+    }
+
+    #[tokio::test]
+    async fn pause_resume_and_trigger() {
+        let control = EtlControl::default();
+        let service = AdminService::new(control.clone(), true);
+
+        let response = service
+            .pause_indexing(Request::new(PauseIndexingRequest {}))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(!response.already_paused);
+        assert!(control.is_paused());
+        let trigger = service
+            .trigger_cycle_now(Request::new(TriggerCycleNowRequest {}))
+            .await
+            .unwrap_err();
+        assert_eq!(trigger.code(), tonic::Code::FailedPrecondition);
+
+        let resumed = tokio::spawn({
+            let control = control.clone();
+            async move { control.resumed().await }
+        });
+        let response = service
+            .resume_indexing(Request::new(ResumeIndexingRequest {}))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(response.was_paused);
+        tokio::time::timeout(Duration::from_secs(1), resumed)
+            .await
+            .expect("resumed")
+            .unwrap();
+
+        // A triggered cycle ends the wait well before the interval.
+        control.set_cycle_interval(Duration::from_secs(3600));
+        service
+            .trigger_cycle_now(Request::new(TriggerCycleNowRequest {}))
+            .await
+            .unwrap();
+        tokio::time::timeout(Duration::from_secs(1), control.wait_cycle())
+            .await
+            .expect("triggered cycle");
+    }
+
+    #[tokio::test]
+    async fn set_cycle_interval_validates_and_returns_previous() {
+        let control = EtlControl::new(Duration::from_secs(3));
+        let service = AdminService::new(control.clone(), true);
+
+        let invalid = service
+            .set_cycle_interval(Request::new(SetCycleIntervalRequest { seconds: 0 }))
+            .await
+            .unwrap_err();
+        assert_eq!(invalid.code(), tonic::Code::InvalidArgument);
+
+        let response = service
+            .set_cycle_interval(Request::new(SetCycleIntervalRequest { seconds: 10 }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(response.previous_seconds, 3);
+        assert_eq!(control.cycle_interval(), Duration::from_secs(10));
+    }
+
+    #[tokio::test]
+    async fn set_etl_params_applies_and_persists() {
+        let engine_db = Arc::new(
+            EngineDb::new(crate::etl::engine_db::EngineDbConfig {
+                path: ":memory:".to_string(),
+            })
+            .await
+            .unwrap(),
+        );
+        let control = EtlControl::new(Duration::from_secs(3));
+        let service = AdminService::new(control.clone(), true).with_engine_db(engine_db.clone());
+        let mut params = control.subscribe_params();
+
+        let invalid = service
+            .set_etl_params(Request::new(SetEtlParamsRequest {
+                cycle_interval: Some(5),
+                events_per_cycle: None,
+                batch_size: Some(0),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(invalid.code(), tonic::Code::InvalidArgument);
+        assert_eq!(control.cycle_interval(), Duration::from_secs(3));
+
+        let response = service
+            .set_etl_params(Request::new(SetEtlParamsRequest {
+                cycle_interval: Some(5),
+                events_per_cycle: None,
+                batch_size: Some(50),
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(response.cycle_interval, 5);
+        assert_eq!(response.batch_size, Some(50));
+        assert_eq!(control.cycle_interval(), Duration::from_secs(5));
+        assert!(params.has_changed().unwrap());
+        assert_eq!(params.borrow_and_update().batch_size, Some(50));
+
+        // Later changes keep the parameters they leave out.
+        service
+            .set_etl_params(Request::new(SetEtlParamsRequest {
+                cycle_interval: None,
+                events_per_cycle: Some(200),
+                batch_size: None,
+            }))
+            .await
+            .unwrap();
+        assert_eq!(
+            EtlParams::load(&engine_db).await.unwrap(),
+            EtlParams {
+                cycle_interval: Some(5),
+                events_per_cycle: Some(200),
+                batch_size: Some(50),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn set_etl_params_persists_only_set_params() {
+        let engine_db = Arc::new(
+            EngineDb::new(crate::etl::engine_db::EngineDbConfig {
+                path: ":memory:".to_string(),
+            })
+            .await
+            .unwrap(),
+        );
+        let control = EtlControl::new(Duration::from_secs(3));
+        let service = AdminService::new(control.clone(), true).with_engine_db(engine_db.clone());
+
+        let response = service
+            .set_etl_params(Request::new(SetEtlParamsRequest {
+                cycle_interval: None,
+                events_per_cycle: None,
+                batch_size: Some(25),
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(response.cycle_interval, 3);
+        assert_eq!(
+            EtlParams::load(&engine_db).await.unwrap(),
+            EtlParams {
+                cycle_interval: None,
+                events_per_cycle: None,
+                batch_size: Some(25),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn address_labels_are_managed_when_configured() {
+        let unconfigured = AdminService::new(EtlControl::default(), true)
+            .list_address_labels(Request::new(ListAddressLabelsRequest {}))
+            .await
+            .unwrap_err();
+        assert_eq!(unconfigured.code(), tonic::Code::FailedPrecondition);
+
+        let labels = AddressLabels::open("sqlite::memory:").await.unwrap();
+        let service =
+            AdminService::new(EtlControl::default(), true).with_address_labels(labels.clone());
+        let address = vec![0x12, 0x34];
+        service
+            .set_address_labels(Request::new(SetAddressLabelsRequest {
+                labels: vec![proto::AddressLabel {
+                    address: address.clone(),
+                    name: "Bridge".to_string(),
+                    tags: vec!["bridge".to_string()],
+                }],
+            }))
+            .await
+            .unwrap();
+        assert_eq!(
+            labels
+                .get(starknet::core::types::Felt::from(0x1234_u64))
+                .unwrap()
+                .name,
+            "Bridge"
+        );
+
+        let listed = service
+            .list_address_labels(Request::new(ListAddressLabelsRequest {}))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(listed.labels.len(), 1);
+        assert_eq!(listed.labels[0].address.len(), 32);
+
+        let deleted = service
+            .delete_address_labels(Request::new(DeleteAddressLabelsRequest {
+                addresses: vec![address],
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(deleted.deleted, 1);
+        assert!(labels.is_empty());
+    }
+}
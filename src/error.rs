@@ -0,0 +1,125 @@
+//! Typed errors of the public API.
+//!
+//! [`run`](crate::run) and the [`Extractor`](crate::etl::Extractor),
+//! [`Decoder`](crate::etl::Decoder) and [`Sink`](crate::etl::Sink) traits return
+//! [`ToriiError`], so embedders can tell configuration problems from pipeline failures.
+//!
+//! Implementations keep using `anyhow` internally: `?` converts an [`anyhow::Error`]
+//! into [`ToriiError::Other`] (or back into the `ToriiError` it wraps), and the pipeline
+//! attributes untyped errors to the stage that raised them with [`ToriiError::in_stage`].
+
+/// Result type of the public API.
+pub type ToriiResult<T> = Result<T, ToriiError>;
+
+/// Error of the public API.
+#[derive(Debug, thiserror::Error)]
+pub enum ToriiError {
+    /// Invalid or inconsistent configuration.
+    #[error("configuration error: {0}")]
+    Config(String),
+    /// An extractor failed to fetch or commit chain data.
+    #[error("extractor error: {0:#}")]
+    Extractor(anyhow::Error),
+    /// A decoder failed to decode events.
+    #[error("decoder error: {0:#}")]
+    Decoder(anyhow::Error),
+    /// A sink failed to initialize, process or flush a batch.
+    #[error("sink error: {0:#}")]
+    Sink(anyhow::Error),
+    /// The gRPC/HTTP server failed.
+    #[error("gRPC error: {0:#}")]
+    Grpc(anyhow::Error),
+    /// The engine database or another store failed.
+    #[error("storage error: {0:#}")]
+    Storage(anyhow::Error),
+    /// Error not attributed to a stage yet.
+    #[error("{0:#}")]
+    Other(anyhow::Error),
+}
+
+/// Pipeline stage an untyped error is attributed to by [`ToriiError::in_stage`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    Extractor,
+    Decoder,
+    Sink,
+    Grpc,
+    Storage,
+}
+
+impl ToriiError {
+    /// Attributes an [`Other`](Self::Other) error to `stage`; typed errors are kept.
+    #[must_use]
+    pub fn in_stage(self, stage: Stage) -> Self {
+        let Self::Other(error) = self else {
+            return self;
+        };
+        match stage {
+            Stage::Extractor => Self::Extractor(error),
+            Stage::Decoder => Self::Decoder(error),
+            Stage::Sink => Self::Sink(error),
+            Stage::Grpc => Self::Grpc(error),
+            Stage::Storage => Self::Storage(error),
+        }
+    }
+
+    /// Stage the error is attributed to, if any.
+    pub fn stage(&self) -> Option<Stage> {
+        match self {
+            Self::Extractor(_) => Some(Stage::Extractor),
+            Self::Decoder(_) => Some(Stage::Decoder),
+            Self::Sink(_) => Some(Stage::Sink),
+            Self::Grpc(_) => Some(Stage::Grpc),
+            Self::Storage(_) => Some(Stage::Storage),
+            Self::Config(_) | Self::Other(_) => None,
+        }
+    }
+}
+
+impl From<anyhow::Error> for ToriiError {
+    fn from(error: anyhow::Error) -> Self {
+        match error.downcast::<Self>() {
+            Ok(error) => error,
+            Err(error) => Self::Other(error),
+        }
+    }
+}
+
+impl From<sqlx::Error> for ToriiError {
+    fn from(error: sqlx::Error) -> Self {
+        Self::Storage(error.into())
+    }
+}
+
+impl From<std::io::Error> for ToriiError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Other(error.into())
+    }
+}
+
+impl From<tonic::transport::Error> for ToriiError {
+    fn from(error: tonic::transport::Error) -> Self {
+        Self::Grpc(error.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_anyhow_errors() {
+        let typed: ToriiError = anyhow::Error::new(ToriiError::Config("bad".to_string())).into();
+        assert!(matches!(typed, ToriiError::Config(ref message) if message == "bad"));
+
+        let untyped: ToriiError = anyhow::anyhow!("boom").into();
+        assert!(matches!(untyped, ToriiError::Other(_)));
+        assert_eq!(untyped.stage(), None);
+
+        let sink = untyped.in_stage(Stage::Sink);
+        assert_eq!(sink.stage(), Some(Stage::Sink));
+        assert_eq!(sink.to_string(), "sink error: boom");
+        // Already attributed errors keep their stage.
+        assert_eq!(sink.in_stage(Stage::Decoder).stage(), Some(Stage::Sink));
+    }
+}
@@ -0,0 +1,149 @@
+//! Startup consistency check between the extraction cursor and the sink data
+//!
+//! Cursors are committed once the sinks flushed a batch, but a crash can still leave
+//! the stored cursor ahead of what a sink durably committed (e.g. a sink whose writes
+//! were not synced yet). At startup, the engine head is compared with the highest
+//! block each sink reports ([`Sink::indexed_block`]); when a sink is behind, the
+//! discrepancy is logged and, in [`StartupConsistency::Rewind`] mode, the extractor
+//! cursor and the head are rewound to the lowest sink block so that no block is
+//! skipped. Sinks re-process the blocks since, which they must tolerate (as with any
+//! cursor commit failure).
+
+use std::sync::Arc;
+
+use crate::error::ToriiResult;
+use crate::etl::extractor::Extractor;
+use crate::etl::{EngineDb, Sink};
+
+/// What the startup consistency check does about sinks behind the cursor.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StartupConsistency {
+    /// No check (the default).
+    #[default]
+    Disabled,
+    /// Log the sinks behind the cursor.
+    Warn,
+    /// Log the sinks behind the cursor and rewind the cursor to the lowest of them.
+    Rewind,
+}
+
+/// Block committed by a sink, as reported at startup.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SinkCheckpoint {
+    pub sink: String,
+    pub block: u64,
+}
+
+/// Block the cursor must be rewound to: the lowest sink checkpoint behind `head`.
+pub fn rewind_target(head: u64, checkpoints: &[SinkCheckpoint]) -> Option<u64> {
+    checkpoints
+        .iter()
+        .map(|checkpoint| checkpoint.block)
+        .filter(|block| *block < head)
+        .min()
+}
+
+/// Compares the engine head with the sink checkpoints and applies `mode`.
+///
+/// Must run before the first extraction. Returns the block the cursor was rewound to,
+/// if any.
+pub async fn check_startup_consistency(
+    mode: StartupConsistency,
+    engine_db: &EngineDb,
+    sinks: &[Arc<dyn Sink>],
+    extractor: &mut dyn Extractor,
+) -> ToriiResult<Option<u64>> {
+    if mode == StartupConsistency::Disabled {
+        return Ok(None);
+    }
+
+    let (head, _) = engine_db.get_head().await?;
+    if head == 0 {
+        return Ok(None);
+    }
+
+    let mut checkpoints = Vec::new();
+    for sink in sinks {
+        if let Some(block) = sink.indexed_block().await? {
+            checkpoints.push(SinkCheckpoint {
+                sink: sink.name().to_string(),
+                block,
+            });
+        }
+    }
+    let Some(target) = rewind_target(head, &checkpoints) else {
+        tracing::info!(
+            target: "torii::etl::consistency",
+            head,
+            sinks = checkpoints.len(),
+            "Sink data consistent with the cursor"
+        );
+        return Ok(None);
+    };
+
+    for checkpoint in checkpoints.iter().filter(|c| c.block < head) {
+        tracing::warn!(
+            target: "torii::etl::consistency",
+            sink = %checkpoint.sink,
+            sink_block = checkpoint.block,
+            head,
+            behind = head - checkpoint.block,
+            "Sink data behind the cursor"
+        );
+    }
+    ::metrics::gauge!("torii_startup_consistency_gap_blocks").set((head - target) as f64);
+
+    if mode == StartupConsistency::Warn {
+        return Ok(None);
+    }
+
+    if !extractor.rewind_cursor(target, engine_db).await? {
+        tracing::warn!(
+            target: "torii::etl::consistency",
+            extractor = extractor.extractor_type(),
+            "Extractor cannot rewind its cursor, keeping it"
+        );
+        return Ok(None);
+    }
+    engine_db.update_head(target, 0).await?;
+    ::metrics::counter!("torii_startup_rewinds_total").increment(1);
+    tracing::warn!(
+        target: "torii::etl::consistency",
+        from = head,
+        to = target,
+        "Rewound cursor to the lowest sink checkpoint"
+    );
+    Ok(Some(target))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checkpoint(sink: &str, block: u64) -> SinkCheckpoint {
+        SinkCheckpoint {
+            sink: sink.to_string(),
+            block,
+        }
+    }
+
+    #[test]
+    fn rewinds_to_the_lowest_sink_behind_the_head() {
+        assert_eq!(rewind_target(100, &[]), None);
+        assert_eq!(
+            rewind_target(100, &[checkpoint("erc20", 100), checkpoint("erc721", 120)]),
+            None
+        );
+        assert_eq!(
+            rewind_target(
+                100,
+                &[
+                    checkpoint("erc20", 90),
+                    checkpoint("erc721", 100),
+                    checkpoint("erc1155", 75)
+                ]
+            ),
+            Some(75)
+        );
+    }
+}
@@ -0,0 +1,179 @@
+//! Cumulative counters persisted across restarts.
+//!
+//! Prometheus counters start from zero on every restart. These counters are
+//! snapshotted into the `EngineDb` stats table periodically (and on shutdown)
+//! and reloaded at startup, so metrics and `/health` report cumulative figures.
+//!
+//! Work done after the last snapshot of a run that crashed is not counted.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+use crate::etl::engine_db::EngineDb;
+
+/// Stats key holding the serialized snapshot.
+const SNAPSHOT_KEY: &str = "metrics_snapshot";
+
+/// Counters cumulated over all runs.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CounterSnapshot {
+    /// Events processed by the sinks
+    pub events_processed: u64,
+    /// Envelopes successfully processed, per sink
+    pub sink_rows: BTreeMap<String, u64>,
+    /// Updates published to subscribers, per topic
+    pub topic_publishes: BTreeMap<String, u64>,
+    /// Uptime summed over all runs, in seconds
+    pub uptime_seconds: u64,
+    /// Number of runs (uptime segments), including the current one
+    pub runs: u64,
+}
+
+/// Counters of the current run, on top of the snapshot loaded at startup.
+#[derive(Debug)]
+pub struct CumulativeCounters {
+    baseline: CounterSnapshot,
+    events_processed: AtomicU64,
+    sink_rows: Mutex<HashMap<String, u64>>,
+    topic_publishes: Mutex<HashMap<String, u64>>,
+    started_at: Instant,
+}
+
+impl CumulativeCounters {
+    /// Creates counters starting from `baseline` (the previous runs).
+    pub fn new(baseline: CounterSnapshot) -> Self {
+        Self {
+            baseline,
+            events_processed: AtomicU64::new(0),
+            sink_rows: Mutex::new(HashMap::new()),
+            topic_publishes: Mutex::new(HashMap::new()),
+            started_at: Instant::now(),
+        }
+    }
+
+    /// Loads the last persisted snapshot (empty if none was persisted yet).
+    pub async fn load(engine_db: &EngineDb) -> Result<Self> {
+        let baseline = match engine_db.get_stat(SNAPSHOT_KEY).await? {
+            Some(raw) => serde_json::from_str(&raw).context("Invalid metrics snapshot")?,
+            None => CounterSnapshot::default(),
+        };
+        Ok(Self::new(baseline))
+    }
+
+    /// Records events processed by the sinks.
+    pub fn record_events(&self, count: u64) {
+        self.events_processed.fetch_add(count, Ordering::Relaxed);
+    }
+
+    /// Records envelopes successfully processed by a sink.
+    pub fn record_sink_rows(&self, sink: &str, rows: u64) {
+        let mut sink_rows = self.sink_rows.lock().unwrap();
+        *sink_rows.entry(sink.to_string()).or_default() += rows;
+    }
+
+    /// Records an update published on `topic`.
+    pub fn record_topic_publish(&self, topic: &str) {
+        let mut topic_publishes = self.topic_publishes.lock().unwrap();
+        match topic_publishes.get_mut(topic) {
+            Some(count) => *count += 1,
+            None => {
+                topic_publishes.insert(topic.to_string(), 1);
+            }
+        }
+    }
+
+    /// Updates published on `topic` over all runs.
+    pub fn topic_publishes(&self, topic: &str) -> u64 {
+        let current = self
+            .topic_publishes
+            .lock()
+            .unwrap()
+            .get(topic)
+            .copied()
+            .unwrap_or_default();
+        self.baseline
+            .topic_publishes
+            .get(topic)
+            .copied()
+            .unwrap_or_default()
+            + current
+    }
+
+    /// Cumulative figures: previous runs plus the current one.
+    pub fn snapshot(&self) -> CounterSnapshot {
+        let mut snapshot = self.baseline.clone();
+        snapshot.events_processed += self.events_processed.load(Ordering::Relaxed);
+        for (sink, rows) in self.sink_rows.lock().unwrap().iter() {
+            *snapshot.sink_rows.entry(sink.clone()).or_default() += rows;
+        }
+        for (topic, count) in self.topic_publishes.lock().unwrap().iter() {
+            *snapshot.topic_publishes.entry(topic.clone()).or_default() += count;
+        }
+        snapshot.uptime_seconds += self.started_at.elapsed().as_secs();
+        snapshot.runs += 1;
+        snapshot
+    }
+
+    /// Persists the cumulative figures.
+    pub async fn persist(&self, engine_db: &EngineDb) -> Result<()> {
+        let raw = serde_json::to_string(&self.snapshot())?;
+        engine_db.set_stat(SNAPSHOT_KEY, &raw).await
+    }
+
+    /// Publishes the cumulative figures as Prometheus gauges.
+    pub fn publish(&self) {
+        let snapshot = self.snapshot();
+        ::metrics::gauge!("torii_cumulative_events_processed")
+            .set(snapshot.events_processed as f64);
+        ::metrics::gauge!("torii_cumulative_uptime_seconds").set(snapshot.uptime_seconds as f64);
+        ::metrics::gauge!("torii_cumulative_runs").set(snapshot.runs as f64);
+        for (sink, rows) in snapshot.sink_rows {
+            ::metrics::gauge!("torii_cumulative_sink_rows", "sink" => sink).set(rows as f64);
+        }
+        for (topic, count) in snapshot.topic_publishes {
+            ::metrics::gauge!("torii_cumulative_topic_publishes", "topic" => topic)
+                .set(count as f64);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::etl::engine_db::EngineDbConfig;
+
+    #[tokio::test]
+    async fn test_counters_survive_restart() {
+        let engine_db = EngineDb::new(EngineDbConfig {
+            path: ":memory:".to_string(),
+        })
+        .await
+        .unwrap();
+
+        let counters = CumulativeCounters::load(&engine_db).await.unwrap();
+        counters.record_events(10);
+        counters.record_sink_rows("erc20", 4);
+        counters.record_topic_publish("erc20.transfer");
+        counters.persist(&engine_db).await.unwrap();
+
+        // Simulated restart: a new run continues from the persisted snapshot.
+        let counters = CumulativeCounters::load(&engine_db).await.unwrap();
+        counters.record_events(5);
+        counters.record_sink_rows("erc20", 1);
+        counters.record_sink_rows("erc721", 2);
+        counters.record_topic_publish("erc20.transfer");
+
+        let snapshot = counters.snapshot();
+        assert_eq!(snapshot.events_processed, 15);
+        assert_eq!(snapshot.sink_rows["erc20"], 5);
+        assert_eq!(snapshot.sink_rows["erc721"], 2);
+        assert_eq!(snapshot.topic_publishes["erc20.transfer"], 2);
+        assert_eq!(counters.topic_publishes("erc20.transfer"), 2);
+        assert_eq!(snapshot.runs, 2);
+    }
+}
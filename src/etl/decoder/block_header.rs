@@ -0,0 +1,136 @@
+//! Built-in decoding of block headers into `BlockHeaderV1` envelopes.
+//!
+//! Unlike event decoders, headers are read from the batch blocks: when enabled on the
+//! [`DecoderContext`](super::DecoderContext), every block of a batch carrying a full
+//! header (see [`BlockContext::header`]) yields one envelope, so analytics sinks can
+//! store chain metadata next to the decoded events.
+
+use starknet::core::types::{Felt, ResourcePrice};
+use std::any::Any;
+use std::collections::HashMap;
+
+use crate::etl::envelope::{Envelope, TypeId, TypedBody};
+use crate::etl::extractor::{BlockContext, ExtractionBatch};
+
+/// Type of [`BlockHeaderV1`] envelopes.
+pub const BLOCK_HEADER_V1: TypeId = TypeId::new("block.header.v1");
+
+/// Header of a block (version 1 of the envelope body)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockHeaderV1 {
+    pub number: u64,
+    pub hash: Felt,
+    pub parent_hash: Felt,
+    pub timestamp: u64,
+    pub sequencer_address: Felt,
+    pub l1_gas_price: ResourcePrice,
+    pub l1_data_gas_price: ResourcePrice,
+    pub l2_gas_price: ResourcePrice,
+    pub starknet_version: String,
+}
+
+impl BlockHeaderV1 {
+    /// Header of `block`, if its extractor fetched the full header.
+    pub fn from_block(block: &BlockContext) -> Option<Self> {
+        let header = block.header.as_ref()?;
+        Some(Self {
+            number: block.number,
+            hash: block.hash,
+            parent_hash: block.parent_hash,
+            timestamp: block.timestamp,
+            sequencer_address: header.sequencer_address,
+            l1_gas_price: header.l1_gas_price.clone(),
+            l1_data_gas_price: header.l1_data_gas_price.clone(),
+            l2_gas_price: header.l2_gas_price.clone(),
+            starknet_version: header.starknet_version.clone(),
+        })
+    }
+}
+
+impl TypedBody for BlockHeaderV1 {
+    fn envelope_type_id(&self) -> TypeId {
+        BLOCK_HEADER_V1
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn debug_repr(&self) -> Option<String> {
+        Some(format!("{self:?}"))
+    }
+}
+
+/// One `BlockHeaderV1` envelope per block of `batch` with a full header, in block order.
+pub fn block_header_envelopes(batch: &ExtractionBatch) -> Vec<Envelope> {
+    let mut headers: Vec<_> = batch
+        .blocks
+        .values()
+        .filter_map(|block| BlockHeaderV1::from_block(block))
+        .collect();
+    headers.sort_unstable_by_key(|header| header.number);
+    headers
+        .into_iter()
+        .map(|header| {
+            let (number, timestamp) = (header.number, header.timestamp);
+            let mut envelope =
+                Envelope::from_body(format!("block_header_{number}"), header, HashMap::new());
+            envelope.meta.block_number = Some(number);
+            envelope.meta.block_timestamp = Some(timestamp);
+            envelope
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::etl::extractor::BlockHeaderInfo;
+    use std::sync::Arc;
+
+    fn price(value: u64) -> ResourcePrice {
+        ResourcePrice {
+            price_in_fri: Felt::from(value),
+            price_in_wei: Felt::from(value * 10),
+        }
+    }
+
+    #[test]
+    fn headers_of_full_blocks_in_block_order() {
+        let mut batch = ExtractionBatch::empty();
+        for number in [12, 10] {
+            batch.blocks.insert(
+                number,
+                Arc::new(BlockContext {
+                    number,
+                    timestamp: 1_700_000_000 + number,
+                    header: Some(BlockHeaderInfo {
+                        sequencer_address: Felt::from(0x5e9_u64),
+                        l1_gas_price: price(1),
+                        l1_data_gas_price: price(2),
+                        l2_gas_price: price(3),
+                        starknet_version: "0.14.0".to_string(),
+                    }),
+                    ..Default::default()
+                }),
+            );
+        }
+        // Timestamp-only block (event extractors): no header envelope.
+        batch.add_block_context(11, Felt::ZERO, Felt::ZERO, 1_700_000_011);
+
+        let envelopes = block_header_envelopes(&batch);
+        let numbers: Vec<_> = envelopes.iter().map(|e| e.block_number()).collect();
+        assert_eq!(numbers, vec![Some(10), Some(12)]);
+
+        let header = envelopes[0].downcast_ref::<BlockHeaderV1>().unwrap();
+        assert_eq!(envelopes[0].type_id, BLOCK_HEADER_V1);
+        assert_eq!(envelopes[0].block_timestamp(), Some(1_700_000_010));
+        assert_eq!(header.sequencer_address, Felt::from(0x5e9_u64));
+        assert_eq!(header.l2_gas_price, price(3));
+        assert_eq!(header.starknet_version, "0.14.0");
+    }
+}
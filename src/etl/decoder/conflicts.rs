@@ -0,0 +1,140 @@
+//! Decoder conflict tracking.
+//!
+//! Events of contracts without an explicit or registry mapping are offered to
+//! every decoder. When several decoders decode the same event (e.g. an ERC20 and
+//! an ERC721 `Transfer` share their selector), the event is ambiguous: each
+//! decoder's output is kept, but the (contract, selector) pair is recorded so
+//! operators can add an explicit mapping.
+
+use starknet::core::types::Felt;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// A (contract, selector) pair claimed by several decoders.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecoderConflict {
+    /// Contract emitting the event
+    pub contract: Felt,
+    /// Event selector (first key)
+    pub selector: Felt,
+    /// Names of the decoders that decoded the event (sorted)
+    pub decoders: Vec<String>,
+    /// Number of ambiguous events seen
+    pub event_count: u64,
+    /// First block with an ambiguous event
+    pub first_block: u64,
+    /// Last block with an ambiguous event
+    pub last_block: u64,
+    /// Whether the registry has since identified the contract (its mapping is used from then on)
+    pub resolved: bool,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    conflicts: HashMap<(Felt, Felt), DecoderConflict>,
+    /// Contracts with at least one unresolved conflict
+    unresolved: HashSet<Felt>,
+}
+
+/// Shared record of decoder conflicts, cheap to clone.
+#[derive(Debug, Clone, Default)]
+pub struct DecoderConflicts {
+    inner: Arc<Mutex<Inner>>,
+    /// Set once a conflict is recorded, so mapped events skip the lock until then.
+    any: Arc<AtomicBool>,
+}
+
+impl DecoderConflicts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records an event of `contract` with `selector` decoded by several decoders.
+    ///
+    /// Returns `true` the first time the pair is seen.
+    pub fn record(
+        &self,
+        contract: Felt,
+        selector: Felt,
+        mut decoders: Vec<String>,
+        block: u64,
+    ) -> bool {
+        decoders.sort_unstable();
+        self.any.store(true, Ordering::Relaxed);
+        let mut inner = self.inner.lock().unwrap();
+        inner.unresolved.insert(contract);
+        let mut first = false;
+        let entry = inner
+            .conflicts
+            .entry((contract, selector))
+            .or_insert_with(|| {
+                first = true;
+                DecoderConflict {
+                    contract,
+                    selector,
+                    decoders: Vec::new(),
+                    event_count: 0,
+                    first_block: block,
+                    last_block: block,
+                    resolved: false,
+                }
+            });
+        for decoder in decoders {
+            if !entry.decoders.contains(&decoder) {
+                entry.decoders.push(decoder);
+            }
+        }
+        entry.decoders.sort_unstable();
+        entry.event_count += 1;
+        entry.first_block = entry.first_block.min(block);
+        entry.last_block = entry.last_block.max(block);
+        entry.resolved = false;
+        first
+    }
+
+    /// Marks the conflicts of `contract` as resolved by a registry mapping.
+    pub fn resolve(&self, contract: Felt) {
+        if !self.any.load(Ordering::Relaxed) {
+            return;
+        }
+        let mut inner = self.inner.lock().unwrap();
+        if !inner.unresolved.remove(&contract) {
+            return;
+        }
+        for conflict in inner.conflicts.values_mut() {
+            if conflict.contract == contract {
+                conflict.resolved = true;
+            }
+        }
+    }
+
+    /// Number of recorded (contract, selector) pairs.
+    pub fn len(&self) -> usize {
+        self.inner.lock().unwrap().conflicts.len()
+    }
+
+    /// Whether no conflict was recorded.
+    pub fn is_empty(&self) -> bool {
+        !self.any.load(Ordering::Relaxed)
+    }
+
+    /// Recorded conflicts, most frequent first.
+    pub fn snapshot(&self) -> Vec<DecoderConflict> {
+        let mut conflicts = self
+            .inner
+            .lock()
+            .unwrap()
+            .conflicts
+            .values()
+            .cloned()
+            .collect::<Vec<_>>();
+        conflicts.sort_by(|a, b| {
+            b.event_count
+                .cmp(&a.event_count)
+                .then(a.contract.cmp(&b.contract))
+                .then(a.selector.cmp(&b.selector))
+        });
+        conflicts
+    }
+}
@@ -9,17 +9,48 @@
 //! - Explicit contract mappings take highest priority
 //! - Registry mappings (from auto-identification) take second priority
 //! - Unmapped contracts with no registry fall back to all decoders
+//! - Events of unmapped contracts decoded by several decoders are recorded as
+//!   conflicts (see [`DecoderConflicts`])
 //! - Deterministic ordering: decoders are always called in sorted DecoderId order
+//! - Decoders and contract filter can be replaced at runtime through a
+//!   [`DecoderReloadHandle`]; each batch is decoded with a single snapshot
 
 use async_trait::async_trait;
 use starknet::core::types::{EmittedEvent, Felt};
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock as StdRwLock};
 use tokio::sync::RwLock;
 
-use super::{ContractFilter, Decoder, DecoderId};
-use crate::etl::engine_db::EngineDb;
-use crate::etl::envelope::Envelope;
+use super::{block_header_envelopes, ContractFilter, Decoder, DecoderConflicts, DecoderId};
+use crate::error::{Stage, ToriiResult};
+use crate::etl::engine_db::{ContractActivity, EngineDb};
+use crate::etl::envelope::{Envelope, MappingRule, Provenance};
+use crate::etl::extractor::ExtractionBatch;
+
+/// Stamps the source event, the producing decoder and its mapping rule on envelopes.
+///
+/// Source metadata is only set where the decoder did not set it.
+fn stamp_source(
+    envelopes: &mut [Envelope],
+    event: &EmittedEvent,
+    decoder_id: DecoderId,
+    decoder_name: &Arc<str>,
+    rule: MappingRule,
+) {
+    for envelope in envelopes {
+        envelope.meta.fill_from_event(event);
+        envelope.meta.decoder_id = Some(decoder_id);
+        envelope.meta.decoder = Some(decoder_name.clone());
+        envelope.meta.mapping_rule = Some(rule);
+    }
+}
+
+/// Records which decoder produced the envelopes and when.
+fn stamp_provenance(envelopes: &mut [Envelope], decoder_name: &str) {
+    for envelope in envelopes {
+        envelope.provenance = Some(Provenance::decoded_by(decoder_name));
+    }
+}
 
 fn event_preview(event: &EmittedEvent) -> String {
     format!(
@@ -28,6 +59,111 @@ fn event_preview(event: &EmittedEvent) -> String {
     )
 }
 
+/// Decoders and contract filter, replaced as a whole on reload
+struct DecoderSet {
+    /// Decoders indexed by their ID (hash of name)
+    decoders: HashMap<DecoderId, Arc<dyn Decoder>>,
+
+    /// Decoder names stamped on envelopes (shared, not allocated per envelope)
+    names: HashMap<DecoderId, Arc<str>>,
+
+    /// Contract filter (explicit mappings + blacklist)
+    contract_filter: ContractFilter,
+}
+
+impl DecoderSet {
+    fn new(
+        decoders: HashMap<DecoderId, Arc<dyn Decoder>>,
+        contract_filter: ContractFilter,
+    ) -> Self {
+        let names = decoders
+            .iter()
+            .map(|(id, decoder)| (*id, Arc::from(decoder.decoder_name())))
+            .collect();
+        Self {
+            decoders,
+            names,
+            contract_filter,
+        }
+    }
+
+    fn shared(
+        decoders: HashMap<DecoderId, Arc<dyn Decoder>>,
+        contract_filter: ContractFilter,
+    ) -> Arc<StdRwLock<Arc<Self>>> {
+        Arc::new(StdRwLock::new(Arc::new(Self::new(
+            decoders,
+            contract_filter,
+        ))))
+    }
+}
+
+/// Replaces the decoders and contract filter of a running [`DecoderContext`].
+///
+/// Obtained with [`DecoderContext::reload_handle`]. Cheap to clone.
+#[derive(Clone)]
+pub struct DecoderReloadHandle {
+    state: Arc<StdRwLock<Arc<DecoderSet>>>,
+    registry_cache: Arc<RwLock<HashMap<Felt, Vec<DecoderId>>>>,
+    has_registry: bool,
+}
+
+impl DecoderReloadHandle {
+    /// Decoders currently registered (sorted by DecoderId)
+    pub fn decoders(&self) -> Vec<Arc<dyn Decoder>> {
+        let set = self.state.read().unwrap().clone();
+        let mut decoders: Vec<_> = set.decoders.iter().collect();
+        decoders.sort_unstable_by_key(|(id, _)| **id);
+        decoders
+            .into_iter()
+            .map(|(_, decoder)| decoder.clone())
+            .collect()
+    }
+
+    /// Contract filter currently applied
+    pub fn contract_filter(&self) -> ContractFilter {
+        self.state.read().unwrap().contract_filter.clone()
+    }
+
+    /// Atomically replaces the decoders and contract filter.
+    ///
+    /// Explicit mappings are also inserted into the registry cache (when a registry is
+    /// configured). Batches being decoded finish with the previous decoders. Nothing is
+    /// replaced if the decoder names are not unique, the filter is invalid or a mapping
+    /// references an unknown decoder.
+    pub async fn swap(
+        &self,
+        decoders: Vec<Arc<dyn Decoder>>,
+        contract_filter: ContractFilter,
+    ) -> anyhow::Result<()> {
+        let decoders = DecoderContext::try_build_decoder_map(&decoders)?;
+        contract_filter.validate()?;
+        for (contract, decoder_ids) in &contract_filter.mappings {
+            if let Some(id) = decoder_ids.iter().find(|id| !decoders.contains_key(id)) {
+                anyhow::bail!("Contract {contract:#x} is mapped to unknown decoder {id:?}");
+            }
+        }
+
+        if self.has_registry {
+            let mut cache = self.registry_cache.write().await;
+            for (contract, decoder_ids) in &contract_filter.mappings {
+                cache.insert(*contract, decoder_ids.clone());
+            }
+        }
+
+        tracing::info!(
+            target: "torii::etl::decoder_context",
+            decoders = decoders.len(),
+            mappings = contract_filter.mappings.len(),
+            blacklisted = contract_filter.blacklist.len(),
+            denied_selectors = contract_filter.denied_selector_count(),
+            "Swapped decoders and contract filter"
+        );
+        *self.state.write().unwrap() = Arc::new(DecoderSet::new(decoders, contract_filter));
+        Ok(())
+    }
+}
+
 /// DecoderContext manages multiple decoders with contract filtering.
 ///
 /// Routes events to decoders based on:
@@ -37,15 +173,12 @@ fn event_preview(event: &EmittedEvent) -> String {
 ///
 #[allow(dead_code)]
 pub struct DecoderContext {
-    /// Decoders indexed by their ID (hash of name)
-    decoders: HashMap<DecoderId, Arc<dyn Decoder>>,
+    /// Current decoders and contract filter, swapped atomically on reload
+    state: Arc<StdRwLock<Arc<DecoderSet>>>,
 
     /// EngineDb for ETL state persistence (cursor, not contract mappings)
     engine_db: Arc<EngineDb>,
 
-    /// Contract filter (explicit mappings + blacklist)
-    contract_filter: ContractFilter,
-
     /// Cached mappings from ContractRegistry (populated externally via batch identification)
     /// Key: contract address, Value: list of decoder IDs
     /// Empty Vec means "identified but no decoders match"
@@ -54,6 +187,15 @@ pub struct DecoderContext {
 
     /// Whether a registry is configured (affects fallback behavior)
     has_registry: bool,
+
+    /// Whether decoded envelopes are stamped with provenance (debug only)
+    track_provenance: bool,
+
+    /// Whether batches also yield one `BlockHeaderV1` envelope per fully fetched block
+    block_headers: bool,
+
+    /// (contract, selector) pairs decoded by several decoders on the fallback path
+    conflicts: DecoderConflicts,
 }
 
 impl DecoderContext {
@@ -71,22 +213,30 @@ impl DecoderContext {
     ) -> Self {
         let decoder_map = Self::build_decoder_map(&decoders);
 
-        let filter_desc =
-            if contract_filter.mappings.is_empty() && contract_filter.blacklist.is_empty() {
-                "none (will try all decoders for all contracts)".to_string()
-            } else {
-                let mut parts = Vec::new();
-                if !contract_filter.mappings.is_empty() {
-                    parts.push(format!(
-                        "{} explicit mappings",
-                        contract_filter.mappings.len()
-                    ));
-                }
-                if !contract_filter.blacklist.is_empty() {
-                    parts.push(format!("{} blacklisted", contract_filter.blacklist.len()));
-                }
-                parts.join(", ")
-            };
+        let filter_desc = if contract_filter.mappings.is_empty()
+            && contract_filter.blacklist.is_empty()
+            && contract_filter.selector_denylist.is_empty()
+        {
+            "none (will try all decoders for all contracts)".to_string()
+        } else {
+            let mut parts = Vec::new();
+            if !contract_filter.mappings.is_empty() {
+                parts.push(format!(
+                    "{} explicit mappings",
+                    contract_filter.mappings.len()
+                ));
+            }
+            if !contract_filter.blacklist.is_empty() {
+                parts.push(format!("{} blacklisted", contract_filter.blacklist.len()));
+            }
+            if !contract_filter.selector_denylist.is_empty() {
+                parts.push(format!(
+                    "{} denied selectors",
+                    contract_filter.denied_selector_count()
+                ));
+            }
+            parts.join(", ")
+        };
 
         tracing::info!(
             target: "torii::etl::decoder_context",
@@ -96,11 +246,13 @@ impl DecoderContext {
         );
 
         Self {
-            decoders: decoder_map,
+            state: DecoderSet::shared(decoder_map, contract_filter),
             engine_db,
-            contract_filter,
             registry_cache: Arc::new(RwLock::new(HashMap::new())),
             has_registry: false,
+            track_provenance: false,
+            block_headers: false,
+            conflicts: DecoderConflicts::new(),
         }
     }
 
@@ -125,23 +277,31 @@ impl DecoderContext {
     ) -> Self {
         let decoder_map = Self::build_decoder_map(&decoders);
 
-        let filter_desc =
-            if contract_filter.mappings.is_empty() && contract_filter.blacklist.is_empty() {
-                "none (using registry for contract identification)".to_string()
-            } else {
-                let mut parts = Vec::new();
-                if !contract_filter.mappings.is_empty() {
-                    parts.push(format!(
-                        "{} explicit mappings",
-                        contract_filter.mappings.len()
-                    ));
-                }
-                if !contract_filter.blacklist.is_empty() {
-                    parts.push(format!("{} blacklisted", contract_filter.blacklist.len()));
-                }
-                parts.push("+ registry".to_string());
-                parts.join(", ")
-            };
+        let filter_desc = if contract_filter.mappings.is_empty()
+            && contract_filter.blacklist.is_empty()
+            && contract_filter.selector_denylist.is_empty()
+        {
+            "none (using registry for contract identification)".to_string()
+        } else {
+            let mut parts = Vec::new();
+            if !contract_filter.mappings.is_empty() {
+                parts.push(format!(
+                    "{} explicit mappings",
+                    contract_filter.mappings.len()
+                ));
+            }
+            if !contract_filter.blacklist.is_empty() {
+                parts.push(format!("{} blacklisted", contract_filter.blacklist.len()));
+            }
+            if !contract_filter.selector_denylist.is_empty() {
+                parts.push(format!(
+                    "{} denied selectors",
+                    contract_filter.denied_selector_count()
+                ));
+            }
+            parts.push("+ registry".to_string());
+            parts.join(", ")
+        };
 
         tracing::info!(
             target: "torii::etl::decoder_context",
@@ -151,19 +311,56 @@ impl DecoderContext {
         );
 
         Self {
-            decoders: decoder_map,
+            state: DecoderSet::shared(decoder_map, contract_filter),
             engine_db,
-            contract_filter,
             registry_cache,
             has_registry: true,
+            track_provenance: false,
+            block_headers: false,
+            conflicts: DecoderConflicts::new(),
+        }
+    }
+
+    /// Enable provenance stamping of decoded envelopes.
+    ///
+    /// Each envelope records the decoder that produced it and when. Intended
+    /// for debugging, as it allocates per envelope.
+    pub fn with_provenance(mut self, enabled: bool) -> Self {
+        self.track_provenance = enabled;
+        self
+    }
+
+    /// Emit a [`BlockHeaderV1`](super::BlockHeaderV1) envelope for every block of a
+    /// batch whose extractor fetched the full header (block-range, feeder gateway).
+    pub fn with_block_headers(mut self, enabled: bool) -> Self {
+        self.block_headers = enabled;
+        self
+    }
+
+    /// Get a handle replacing the decoders and contract filter at runtime
+    pub fn reload_handle(&self) -> DecoderReloadHandle {
+        DecoderReloadHandle {
+            state: self.state.clone(),
+            registry_cache: self.registry_cache.clone(),
+            has_registry: self.has_registry,
         }
     }
 
+    /// Current decoders and contract filter
+    fn current(&self) -> Arc<DecoderSet> {
+        self.state.read().unwrap().clone()
+    }
+
     /// Get the shared registry cache (for external updates)
     pub fn registry_cache(&self) -> Arc<RwLock<HashMap<Felt, Vec<DecoderId>>>> {
         self.registry_cache.clone()
     }
 
+    /// Get the shared conflict record (for diagnostics)
+    pub fn conflicts(&self) -> DecoderConflicts {
+        self.conflicts.clone()
+    }
+
     /// Check if registry is configured
     pub fn has_registry(&self) -> bool {
         self.has_registry
@@ -171,16 +368,23 @@ impl DecoderContext {
 
     /// Build decoder map from list (helper for constructor)
     fn build_decoder_map(decoders: &[Arc<dyn Decoder>]) -> HashMap<DecoderId, Arc<dyn Decoder>> {
+        Self::try_build_decoder_map(decoders).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    fn try_build_decoder_map(
+        decoders: &[Arc<dyn Decoder>],
+    ) -> anyhow::Result<HashMap<DecoderId, Arc<dyn Decoder>>> {
         let mut decoder_map = HashMap::new();
 
         for decoder in decoders {
             let name = decoder.decoder_name();
             let id = DecoderId::new(name);
 
-            assert!(
-                !decoder_map.contains_key(&id),
-                "Duplicate decoder name '{name}' (id: {id:?}). Decoder names must be unique!"
-            );
+            if decoder_map.contains_key(&id) {
+                anyhow::bail!(
+                    "Duplicate decoder name '{name}' (id: {id:?}). Decoder names must be unique!"
+                );
+            }
 
             tracing::debug!(
                 target: "torii::etl::decoder_context",
@@ -192,17 +396,17 @@ impl DecoderContext {
             decoder_map.insert(id, decoder.clone());
         }
 
-        decoder_map
+        Ok(decoder_map)
     }
 
     /// Get a decoder by its ID
-    pub fn get_decoder(&self, id: &DecoderId) -> Option<&Arc<dyn Decoder>> {
-        self.decoders.get(id)
+    pub fn get_decoder(&self, id: &DecoderId) -> Option<Arc<dyn Decoder>> {
+        self.current().decoders.get(id).cloned()
     }
 
     /// Get all registered decoder IDs (sorted for determinism)
     pub fn decoder_ids(&self) -> Vec<DecoderId> {
-        let mut ids: Vec<_> = self.decoders.keys().copied().collect();
+        let mut ids: Vec<_> = self.current().decoders.keys().copied().collect();
         ids.sort_unstable();
         ids
     }
@@ -212,18 +416,118 @@ impl DecoderContext {
         &self.engine_db
     }
 
-    /// Decode an event using specific decoders
+    /// Decode the events of `batch`, stamping block timestamps from the batch blocks.
+    ///
+    /// `event_indexes` holds the position of each event in its transaction, as assigned
+    /// by an [`EventIndexer`](crate::etl::EventIndexer) before dedupe. When it does not
+    /// have one index per event (e.g. empty), events are numbered within the batch.
+    ///
+    /// Block header envelopes, when enabled, follow the event envelopes.
+    pub async fn decode_batch(
+        &self,
+        batch: &ExtractionBatch,
+        event_indexes: &[u32],
+    ) -> ToriiResult<Vec<Envelope>> {
+        let event_indexes = (event_indexes.len() == batch.events.len()).then_some(event_indexes);
+        let mut envelopes = self
+            .decode_indexed(&batch.events, event_indexes)
+            .await
+            .map_err(|e| e.in_stage(Stage::Decoder))?;
+        for envelope in &mut envelopes {
+            if envelope.meta.block_timestamp.is_none() {
+                envelope.meta.block_timestamp = envelope
+                    .meta
+                    .block_number
+                    .and_then(|block| batch.blocks.get(&block))
+                    .map(|block| block.timestamp);
+            }
+        }
+        if self.block_headers {
+            envelopes.extend(block_header_envelopes(batch));
+        }
+        Ok(envelopes)
+    }
+
+    /// Aggregate per-contract indexing activity from decoded envelopes.
+    ///
+    /// Envelopes without a source contract, block or decoder are ignored. The
+    /// last activity is the timestamp of the latest block, taken from the batch.
+    pub fn contract_activity(
+        &self,
+        envelopes: &[Envelope],
+        batch: &ExtractionBatch,
+    ) -> Vec<ContractActivity> {
+        let set = self.current();
+        let mut activity: HashMap<(Felt, DecoderId), ContractActivity> = HashMap::new();
+
+        for envelope in envelopes {
+            let (Some(contract), Some(block), Some(decoder_id)) = (
+                envelope.meta.contract,
+                envelope.meta.block_number,
+                envelope.meta.decoder_id,
+            ) else {
+                continue;
+            };
+            let Some(decoder) = set.decoders.get(&decoder_id) else {
+                continue;
+            };
+            let timestamp = batch
+                .blocks
+                .get(&block)
+                .map_or(envelope.timestamp, |b| b.timestamp as i64);
+
+            let entry =
+                activity
+                    .entry((contract, decoder_id))
+                    .or_insert_with(|| ContractActivity {
+                        contract,
+                        decoder: decoder.decoder_name().to_string(),
+                        event_count: 0,
+                        first_block: block,
+                        last_block: block,
+                        last_activity: timestamp,
+                    });
+            entry.event_count += 1;
+            entry.first_block = entry.first_block.min(block);
+            entry.last_block = entry.last_block.max(block);
+            entry.last_activity = entry.last_activity.max(timestamp);
+        }
+
+        activity.into_values().collect()
+    }
+
+    /// Decode an event using specific decoders in order, selected through `rule`
+    ///
+    /// With `short_circuit`, the remaining decoders are skipped once one produced envelopes.
     async fn decode_with_decoders(
         &self,
+        set: &DecoderSet,
         event: &EmittedEvent,
         decoder_ids: &[DecoderId],
-    ) -> anyhow::Result<Vec<Envelope>> {
+        rule: MappingRule,
+        short_circuit: bool,
+    ) -> ToriiResult<Vec<Envelope>> {
         let mut all_envelopes = Vec::new();
 
-        for decoder_id in decoder_ids {
-            if let Some(decoder) = self.decoders.get(decoder_id) {
+        for (position, decoder_id) in decoder_ids.iter().enumerate() {
+            if short_circuit && !all_envelopes.is_empty() {
+                ::metrics::counter!("torii_decoder_short_circuit_skips_total")
+                    .increment((decoder_ids.len() - position) as u64);
+                break;
+            }
+            if let Some(decoder) = set.decoders.get(decoder_id) {
                 match decoder.decode_event(event).await {
-                    Ok(envelopes) => {
+                    Ok(mut envelopes) => {
+                        stamp_source(
+                            &mut envelopes,
+                            event,
+                            *decoder_id,
+                            &set.names[decoder_id],
+                            rule,
+                        );
+                        if self.track_provenance {
+                            stamp_provenance(&mut envelopes, decoder.decoder_name());
+                        }
                         if !envelopes.is_empty() {
                             tracing::trace!(
                                 target: "torii::etl::decoder_context",
@@ -270,14 +574,27 @@ impl DecoderContext {
     /// Decode an event using all registered decoders (fallback)
     async fn decode_with_all_decoders(
         &self,
+        set: &DecoderSet,
         event: &EmittedEvent,
-    ) -> anyhow::Result<Vec<Envelope>> {
+    ) -> ToriiResult<Vec<Envelope>> {
         let mut all_envelopes = Vec::new();
+        let mut claimed_by = Vec::new();
 
-        for decoder in self.decoders.values() {
+        for (decoder_id, decoder) in &set.decoders {
             match decoder.decode_event(event).await {
-                Ok(envelopes) => {
+                Ok(mut envelopes) => {
+                    stamp_source(
+                        &mut envelopes,
+                        event,
+                        *decoder_id,
+                        &set.names[decoder_id],
+                        MappingRule::Fallback,
+                    );
+                    if self.track_provenance {
+                        stamp_provenance(&mut envelopes, decoder.decoder_name());
+                    }
                     if !envelopes.is_empty() {
+                        claimed_by.push(decoder.decoder_name().to_string());
                         tracing::trace!(
                             target: "torii::etl::decoder_context",
                             "Decoder '{}' decoded event from {:#x} into {} envelope(s)",
@@ -309,25 +626,65 @@ impl DecoderContext {
             }
         }
 
+        if claimed_by.len() > 1 {
+            self.record_conflict(event, claimed_by);
+        }
+
         Ok(all_envelopes)
     }
-}
 
-#[async_trait]
-impl Decoder for DecoderContext {
-    fn decoder_name(&self) -> &'static str {
-        "context"
+    /// Records an event of an unmapped contract decoded by several decoders.
+    fn record_conflict(&self, event: &EmittedEvent, decoders: Vec<String>) {
+        let selector = event.keys.first().copied().unwrap_or_default();
+        ::metrics::counter!("torii_decoder_conflicts_total").increment(1);
+        let description = decoders.join(", ");
+        let first = self.conflicts.record(
+            event.from_address,
+            selector,
+            decoders,
+            event.block_number.unwrap_or_default(),
+        );
+        if first {
+            ::metrics::gauge!("torii_decoder_conflict_pairs").set(self.conflicts.len() as f64);
+            tracing::warn!(
+                target: "torii::etl::decoder_context",
+                contract = %format!("{:#x}", event.from_address),
+                selector = %format!("{selector:#x}"),
+                decoders = %description,
+                "Event decoded by several decoders; add an explicit contract mapping to disambiguate"
+            );
+        }
     }
 
-    async fn decode_event(&self, event: &EmittedEvent) -> anyhow::Result<Vec<Envelope>> {
-        // 1. Check blacklist first
-        if !self.contract_filter.allows(event.from_address) {
+    /// Decode an event with a snapshot of the decoders and contract filter
+    async fn decode_with_set(
+        &self,
+        set: &DecoderSet,
+        event: &EmittedEvent,
+    ) -> ToriiResult<Vec<Envelope>> {
+        // 1. Check blacklist and selector denylist first
+        if !set.contract_filter.allows(event.from_address) {
+            return Ok(Vec::new());
+        }
+        if event.keys.first().is_some_and(|selector| {
+            set.contract_filter
+                .denies_selector(event.from_address, *selector)
+        }) {
+            ::metrics::counter!("torii_decoder_denied_events_total").increment(1);
             return Ok(Vec::new());
         }
 
         // 2. Check explicit mappings (highest priority)
-        if let Some(decoder_ids) = self.contract_filter.get_decoders(event.from_address) {
-            return self.decode_with_decoders(event, decoder_ids).await;
+        if let Some(decoder_ids) = set.contract_filter.get_decoders(event.from_address) {
+            return self
+                .decode_with_decoders(
+                    set,
+                    event,
+                    decoder_ids,
+                    MappingRule::Explicit,
+                    set.contract_filter.short_circuits(event.from_address),
+                )
+                .await;
         }
 
         // 3. Check registry cache (if registry is configured)
@@ -344,7 +701,7 @@ impl Decoder for DecoderContext {
                 let invalid_ids: Vec<DecoderId> = decoder_ids
                     .iter()
                     .copied()
-                    .filter(|id| !self.decoders.contains_key(id))
+                    .filter(|id| !set.decoders.contains_key(id))
                     .collect();
                 if !invalid_ids.is_empty() {
                     drop(cache);
@@ -358,13 +715,17 @@ impl Decoder for DecoderContext {
                         invalid_decoder_ids = ?invalid_ids,
                         "Evicted stale decoder mapping from registry cache; falling back to all decoders"
                     );
-                    return self.decode_with_all_decoders(event).await;
+                    return self.decode_with_all_decoders(set, event).await;
                 }
 
                 // Clone to release lock before async decode
                 let decoder_ids = decoder_ids.clone();
                 drop(cache);
-                return self.decode_with_decoders(event, &decoder_ids).await;
+                // Registry-identified mappings take precedence over ambiguous fallback decoding.
+                self.conflicts.resolve(event.from_address);
+                return self
+                    .decode_with_decoders(set, event, &decoder_ids, MappingRule::Registry, false)
+                    .await;
             }
             // Not in registry cache = not yet identified, try all decoders
             // This enables auto-discovery: decoders can identify events they understand
@@ -374,18 +735,57 @@ impl Decoder for DecoderContext {
                 "Contract not in registry cache, trying all decoders"
             );
             drop(cache);
-            return self.decode_with_all_decoders(event).await;
+            return self.decode_with_all_decoders(set, event).await;
         }
 
         // 4. No registry: try all decoders (fallback for non-block-range extractors)
-        self.decode_with_all_decoders(event).await
+        self.decode_with_all_decoders(set, event).await
+    }
+}
+
+#[async_trait]
+impl Decoder for DecoderContext {
+    fn decoder_name(&self) -> &'static str {
+        "context"
+    }
+
+    async fn decode_event(&self, event: &EmittedEvent) -> ToriiResult<Vec<Envelope>> {
+        self.decode_with_set(&self.current(), event).await
     }
 
-    async fn decode(&self, events: &[EmittedEvent]) -> anyhow::Result<Vec<Envelope>> {
+    async fn decode(&self, events: &[EmittedEvent]) -> ToriiResult<Vec<Envelope>> {
+        self.decode_indexed(events, None).await
+    }
+}
+
+impl DecoderContext {
+    /// Decodes `events`, stamping each envelope with the index of its event from
+    /// `event_indexes`, or with its position among the events of its transaction in
+    /// `events` when there are no indexes.
+    async fn decode_indexed(
+        &self,
+        events: &[EmittedEvent],
+        event_indexes: Option<&[u32]>,
+    ) -> ToriiResult<Vec<Envelope>> {
+        // One snapshot per batch: a reload takes effect from the next batch.
+        let set = self.current();
         let mut all_envelopes = Vec::new();
+        let mut tx_event_counts: HashMap<Felt, u32> = HashMap::new();
 
-        for event in events {
-            let envelopes = self.decode_event(event).await?;
+        for (position, event) in events.iter().enumerate() {
+            let mut envelopes = self.decode_with_set(&set, event).await?;
+
+            let event_index = match event_indexes {
+                Some(indexes) => indexes[position],
+                None => {
+                    let count = tx_event_counts.entry(event.transaction_hash).or_insert(0);
+                    *count += 1;
+                    *count - 1
+                }
+            };
+            for envelope in &mut envelopes {
+                envelope.meta.event_index.get_or_insert(event_index);
+            }
 
             all_envelopes.extend(envelopes);
         }
@@ -395,7 +795,7 @@ impl Decoder for DecoderContext {
             "Decoded {} events into {} envelopes across {} decoders",
             events.len(),
             all_envelopes.len(),
-            self.decoders.len(),
+            set.decoders.len(),
         );
 
         Ok(all_envelopes)
@@ -439,7 +839,7 @@ mod tests {
             "ordered_decoder"
         }
 
-        async fn decode_event(&self, event: &EmittedEvent) -> anyhow::Result<Vec<Envelope>> {
+        async fn decode_event(&self, event: &EmittedEvent) -> ToriiResult<Vec<Envelope>> {
             if event.from_address != self.contract {
                 return Ok(Vec::new());
             }
@@ -454,6 +854,23 @@ mod tests {
         }
     }
 
+    struct TransferDecoder(&'static str);
+
+    #[async_trait]
+    impl Decoder for TransferDecoder {
+        fn decoder_name(&self) -> &'static str {
+            self.0
+        }
+
+        async fn decode_event(&self, event: &EmittedEvent) -> ToriiResult<Vec<Envelope>> {
+            Ok(vec![Envelope::new(
+                format!("{}-{:#x}", self.0, event.transaction_hash),
+                Box::new(TestBody { seq: 0 }),
+                HashMap::new(),
+            )])
+        }
+    }
+
     async fn make_engine_db() -> Arc<EngineDb> {
         Arc::new(
             EngineDb::new(EngineDbConfig {
@@ -491,4 +908,258 @@ mod tests {
 
         assert_eq!(actual, expected);
     }
+
+    #[tokio::test]
+    async fn decode_stamps_provenance_when_enabled() {
+        let contract = Felt::from(0x1234_u64);
+        let event = EmittedEvent {
+            from_address: contract,
+            keys: Vec::new(),
+            data: Vec::new(),
+            block_hash: None,
+            block_number: Some(7),
+            transaction_hash: Felt::from(8_u64),
+        };
+
+        let decoder: Arc<dyn Decoder> = Arc::new(OrderedDecoder { contract });
+        let context =
+            DecoderContext::new(vec![decoder], make_engine_db().await, ContractFilter::new());
+        let envelopes = Decoder::decode(&context, std::slice::from_ref(&event))
+            .await
+            .unwrap();
+        assert!(envelopes[0].provenance.is_none());
+
+        let decoder: Arc<dyn Decoder> = Arc::new(OrderedDecoder { contract });
+        let context =
+            DecoderContext::new(vec![decoder], make_engine_db().await, ContractFilter::new())
+                .with_provenance(true);
+        let envelopes = Decoder::decode(&context, &[event]).await.unwrap();
+        let provenance = envelopes[0].provenance.as_ref().unwrap();
+        assert_eq!(provenance.decoder, "ordered_decoder");
+        assert!(provenance.decoded_at > 0);
+    }
+
+    #[tokio::test]
+    async fn decode_stamps_decoder_and_mapping_rule() {
+        let mapped = Felt::from(0x1234_u64);
+        let unmapped = Felt::from(0x5678_u64);
+        let event = |from_address| EmittedEvent {
+            from_address,
+            keys: Vec::new(),
+            data: Vec::new(),
+            block_hash: None,
+            block_number: Some(7),
+            transaction_hash: Felt::from(8_u64),
+        };
+
+        let decoder: Arc<dyn Decoder> = Arc::new(TransferDecoder("transfer"));
+        let filter = ContractFilter::new().map_contract(mapped, vec![DecoderId::new("transfer")]);
+        let context = DecoderContext::new(vec![decoder], make_engine_db().await, filter);
+        let envelopes = Decoder::decode(&context, &[event(mapped), event(unmapped)])
+            .await
+            .unwrap();
+
+        let stamped: Vec<_> = envelopes
+            .iter()
+            .map(|envelope| {
+                (
+                    envelope.decoder_name(),
+                    envelope.meta.decoder_id,
+                    envelope.meta.mapping_rule,
+                )
+            })
+            .collect();
+        let transfer = Some(DecoderId::new("transfer"));
+        assert_eq!(
+            stamped,
+            vec![
+                (Some("transfer"), transfer, Some(MappingRule::Explicit)),
+                (Some("transfer"), transfer, Some(MappingRule::Fallback)),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn decode_short_circuits_mapped_decoders_in_order() {
+        let (short_circuited, exhaustive) = (Felt::from(0x1_u64), Felt::from(0x2_u64));
+        let event = |from_address| EmittedEvent {
+            from_address,
+            keys: Vec::new(),
+            data: Vec::new(),
+            block_hash: None,
+            block_number: Some(7),
+            transaction_hash: Felt::from(8_u64),
+        };
+        let order = vec![DecoderId::new("game"), DecoderId::new("erc20")];
+
+        let decoders: Vec<Arc<dyn Decoder>> = vec![
+            Arc::new(TransferDecoder("erc20")),
+            Arc::new(TransferDecoder("game")),
+        ];
+        let filter = ContractFilter::new()
+            .map_contract_first_match(short_circuited, order.clone())
+            .map_contract(exhaustive, order);
+        let context = DecoderContext::new(decoders, make_engine_db().await, filter);
+        let envelopes = Decoder::decode(&context, &[event(short_circuited), event(exhaustive)])
+            .await
+            .unwrap();
+
+        let ids: Vec<_> = envelopes.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, vec!["game-0x8", "game-0x8", "erc20-0x8"]);
+    }
+
+    #[tokio::test]
+    async fn decode_batch_stamps_event_meta() {
+        let contract = Felt::from(0x1234_u64);
+        let event = |tx: u64| EmittedEvent {
+            from_address: contract,
+            keys: Vec::new(),
+            data: Vec::new(),
+            block_hash: None,
+            block_number: Some(7),
+            transaction_hash: Felt::from(tx),
+        };
+        let mut batch = ExtractionBatch::empty();
+        batch.events = vec![event(1), event(2), event(1)];
+        batch.blocks.insert(
+            7,
+            Arc::new(crate::etl::extractor::BlockContext {
+                number: 7,
+                timestamp: 1_700_000_000,
+                ..Default::default()
+            }),
+        );
+
+        let decoder: Arc<dyn Decoder> = Arc::new(OrderedDecoder { contract });
+        let context =
+            DecoderContext::new(vec![decoder], make_engine_db().await, ContractFilter::new());
+        let envelopes = context.decode_batch(&batch, &[]).await.unwrap();
+
+        let meta = &envelopes[2].meta;
+        assert_eq!(meta.contract, Some(contract));
+        assert_eq!(meta.block_number, Some(7));
+        assert_eq!(meta.block_timestamp, Some(1_700_000_000));
+        assert_eq!(meta.transaction_hash, Some(Felt::ONE));
+        let indexes: Vec<_> = envelopes.iter().map(|e| e.meta.event_index).collect();
+        assert_eq!(indexes, vec![Some(0), Some(0), Some(1)]);
+    }
+
+    #[tokio::test]
+    async fn event_indexes_continue_across_batches() {
+        let contract = Felt::from(0x1234_u64);
+        let event = |tx: u64, data: u64| EmittedEvent {
+            from_address: contract,
+            keys: Vec::new(),
+            data: vec![Felt::from(data)],
+            block_hash: None,
+            block_number: Some(7),
+            transaction_hash: Felt::from(tx),
+        };
+        let decoder: Arc<dyn Decoder> = Arc::new(OrderedDecoder { contract });
+        let context =
+            DecoderContext::new(vec![decoder], make_engine_db().await, ContractFilter::new());
+        let mut indexer = crate::etl::EventIndexer::default();
+        let mut dedupe = crate::etl::EventDedupe::new(16);
+
+        // Transaction 1 is split across two batches, and its first event is extracted
+        // again with the second batch.
+        let mut first = ExtractionBatch::empty();
+        first.events = vec![event(1, 0), event(1, 1)];
+        let mut second = ExtractionBatch::empty();
+        second.events = vec![event(1, 1), event(1, 2), event(1, 3), event(2, 0)];
+
+        let mut indexes = Vec::new();
+        for batch in [&mut first, &mut second] {
+            let mut event_indexes = indexer.assign(&batch.events);
+            dedupe.filter(&mut batch.events, &mut event_indexes);
+            let envelopes = context.decode_batch(batch, &event_indexes).await.unwrap();
+            indexes.extend(envelopes.iter().map(|e| e.meta.event_index));
+        }
+        assert_eq!(second.events.len(), 3);
+        assert_eq!(indexes, vec![Some(0), Some(1), Some(2), Some(3), Some(0)]);
+    }
+
+    #[tokio::test]
+    async fn decode_drops_denied_selectors_before_decoders() {
+        let contract = Felt::from(0x1234_u64);
+        let event = |selector: u64| EmittedEvent {
+            from_address: contract,
+            keys: vec![Felt::from(selector)],
+            data: Vec::new(),
+            block_hash: None,
+            block_number: Some(1),
+            transaction_hash: Felt::from(selector),
+        };
+
+        let decoders: Vec<Arc<dyn Decoder>> = vec![Arc::new(TransferDecoder("erc20"))];
+        let filter = ContractFilter::new()
+            .map_contract(contract, vec![DecoderId::new("erc20")])
+            .deny_selector(contract, Felt::from(0x99_u64));
+        let context = DecoderContext::new(decoders, make_engine_db().await, filter);
+
+        let envelopes = Decoder::decode(&context, &[event(0x99), event(0x42)])
+            .await
+            .unwrap();
+        assert_eq!(envelopes.len(), 1);
+        assert_eq!(envelopes[0].id, "erc20-0x42");
+
+        // Other contracts are not affected by the denylist.
+        let other = EmittedEvent {
+            from_address: Felt::from(0x5678_u64),
+            ..event(0x99)
+        };
+        assert_eq!(Decoder::decode(&context, &[other]).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn decode_records_conflicts_until_registry_identifies_contract() {
+        let contract = Felt::from(0x1234_u64);
+        let selector = Felt::from(0x99_u64);
+        let event = |block: u64| EmittedEvent {
+            from_address: contract,
+            keys: vec![selector],
+            data: Vec::new(),
+            block_hash: None,
+            block_number: Some(block),
+            transaction_hash: Felt::from(block),
+        };
+
+        let decoders: Vec<Arc<dyn Decoder>> = vec![
+            Arc::new(TransferDecoder("erc20")),
+            Arc::new(TransferDecoder("erc721")),
+        ];
+        let registry_cache = Arc::new(RwLock::new(HashMap::new()));
+        let context = DecoderContext::with_registry(
+            decoders,
+            make_engine_db().await,
+            ContractFilter::new(),
+            registry_cache.clone(),
+        );
+        let conflicts = context.conflicts();
+
+        let envelopes = Decoder::decode(&context, &[event(5), event(9)])
+            .await
+            .unwrap();
+        assert_eq!(envelopes.len(), 4);
+
+        let snapshot = conflicts.snapshot();
+        assert_eq!(snapshot.len(), 1);
+        assert_eq!(snapshot[0].selector, selector);
+        assert_eq!(snapshot[0].decoders, vec!["erc20", "erc721"]);
+        assert_eq!(snapshot[0].event_count, 2);
+        assert_eq!((snapshot[0].first_block, snapshot[0].last_block), (5, 9));
+        assert!(!snapshot[0].resolved);
+
+        // Once identified, only the registry mapping decodes the contract's events.
+        registry_cache
+            .write()
+            .await
+            .insert(contract, vec![DecoderId::new("erc20")]);
+        let envelopes = Decoder::decode(&context, &[event(10)]).await.unwrap();
+        assert_eq!(envelopes.len(), 1);
+
+        let snapshot = conflicts.snapshot();
+        assert_eq!(snapshot[0].event_count, 2);
+        assert!(snapshot[0].resolved);
+    }
 }
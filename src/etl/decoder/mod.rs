@@ -1,4 +1,8 @@
+pub mod block_header;
+pub mod conflicts;
 pub mod context;
+pub mod reload;
+pub mod starknet_event;
 
 use async_trait::async_trait;
 use starknet::core::types::{EmittedEvent, Felt};
@@ -6,8 +10,18 @@ use std::collections::{hash_map::DefaultHasher, HashMap, HashSet};
 use std::hash::{Hash, Hasher};
 
 use super::envelope::Envelope;
+use crate::error::ToriiResult;
 
-pub use context::DecoderContext;
+pub use block_header::{block_header_envelopes, BlockHeaderV1, BLOCK_HEADER_V1};
+pub use conflicts::{DecoderConflict, DecoderConflicts};
+pub use context::{DecoderContext, DecoderReloadHandle};
+pub use reload::{
+    ContractMapping, DecoderConfig, DecoderConfigWatcher, DecoderFactory, DecoderSpec,
+    ReloadSummary,
+};
+pub use starknet_event::{
+    EventField, EventLayout, EventMember, EventReader, EventSlot, SelectorAliases, StarknetEvent,
+};
 
 /// Decoder transforms blockchain events into typed envelopes
 ///
@@ -16,7 +30,7 @@ pub use context::DecoderContext;
 /// - Examining raw blockchain events.
 /// - Filtering events they're interested in (by contract address, event keys, etc.).
 /// - Creating typed `Envelope` wrappers with specific `TypeId`s.
-/// - **Populating envelope metadata** with event-specific data for sink access.
+/// - **Populating envelope metadata** with decoder-specific data for sink access.
 /// - Skipping events they don't recognize.
 ///
 /// # Multi-Decoder Pattern
@@ -27,12 +41,14 @@ pub use context::DecoderContext;
 ///
 /// # Metadata Best Practice
 ///
-/// **Important**: If sinks need access to original event data (like block number, transaction hash,
-/// contract address, etc.), the decoder should add this information to the envelope's **metadata** (or body if it's relevant).
+/// The `DecoderContext` stamps the source event on every envelope as typed
+/// [`EnvelopeMeta`](crate::etl::envelope::EnvelopeMeta): block number and timestamp,
+/// transaction hash, event index and contract. If sinks need other event data, the decoder
+/// should add it to the envelope's **metadata** (or body if it's relevant).
 ///
 /// Why? Sinks should avoid iterating through `batch.events` (O(n) operation). Instead:
-/// - Decoder extracts relevant event fields → envelope metadata
-/// - Sink reads metadata → O(1) access
+/// - Decoder extracts relevant event fields → envelope meta and metadata
+/// - Sink reads them → O(1) access
 ///
 /// For block/transaction context, sinks can use the enriched batch HashMaps:
 /// - `batch.blocks[&block_number]` - O(1) lookup for block timestamp, hash, etc.
@@ -64,7 +80,7 @@ pub use context::DecoderContext;
 ///         "my_decoder"
 ///     }
 ///
-///     async fn decode_event(&self, event: &EmittedEvent) -> anyhow::Result<Vec<Envelope>> {
+///     async fn decode_event(&self, event: &EmittedEvent) -> ToriiResult<Vec<Envelope>> {
 ///         if !self.is_interested(event) {
 ///             return Ok(Vec::new());
 ///         }
@@ -72,14 +88,12 @@ pub use context::DecoderContext;
 ///         // Extract only the data you need from the event
 ///         let body = MyEventType { /* decoded fields */ };
 ///
-///         // Add event-specific data to metadata for sink access
+///         // Block, transaction and contract are stamped by the DecoderContext;
+///         // add decoder-specific data to metadata for sink access
 ///         let mut metadata = HashMap::new();
-///         metadata.insert("block_number".to_string(),
-///             event.block_number.unwrap_or(0).to_string());
-///         metadata.insert("from_address".to_string(),
-///             format!("{:#x}", event.from_address));
+///         metadata.insert("kind".to_string(), "my_event".into());
 ///
-///         Ok(vec![Envelope::new("my_key", Box::new(body), metadata)])
+///         Ok(vec![Envelope::from_body("my_key".to_string(), body, metadata)])
 ///     }
 /// }
 /// ```
@@ -90,6 +104,10 @@ pub use context::DecoderContext;
 /// - Process events without cloning.
 /// - Only extract data for events the decoder is interested in.
 /// - Multiple decoders process the same events without memory duplication.
+///
+/// **Allocations**: build envelopes with `Envelope::from_body` (one allocation per body).
+/// Decoders producing many envelopes at once can use `EnvelopeSlab` to store all bodies
+/// in a single allocation and format repeated addresses once.
 #[async_trait]
 pub trait Decoder: Send + Sync {
     /// Returns the unique name of this decoder
@@ -109,6 +127,13 @@ pub trait Decoder: Send + Sync {
     /// ```
     fn decoder_name(&self) -> &str;
 
+    /// Returns the version of this decoder (reported by `GetCapabilities`)
+    ///
+    /// Decoder crates typically return `Some(env!("CARGO_PKG_VERSION"))`.
+    fn decoder_version(&self) -> Option<&str> {
+        None
+    }
+
     /// Decode a single event into typed envelopes
     ///
     /// This is the primary method that decoders should implement.
@@ -119,7 +144,7 @@ pub trait Decoder: Send + Sync {
     ///
     /// # Returns
     /// Vector of envelopes produced from this event (empty if not interested).
-    async fn decode_event(&self, event: &EmittedEvent) -> anyhow::Result<Vec<Envelope>>;
+    async fn decode_event(&self, event: &EmittedEvent) -> ToriiResult<Vec<Envelope>>;
 
     /// Decode multiple events into typed envelopes (convenience method)
     ///
@@ -131,7 +156,7 @@ pub trait Decoder: Send + Sync {
     ///
     /// # Returns
     /// Vector of all envelopes produced from all events.
-    async fn decode(&self, events: &[EmittedEvent]) -> anyhow::Result<Vec<Envelope>> {
+    async fn decode(&self, events: &[EmittedEvent]) -> ToriiResult<Vec<Envelope>> {
         let mut all_envelopes = Vec::new();
         for event in events {
             let envelopes = self.decode_event(event).await?;
@@ -189,6 +214,18 @@ impl DecoderId {
 ///    - Use for noisy contracts that emit many irrelevant events
 ///    - Can coexist with mappings (but contract can't be in both)
 ///
+/// 3. **Selector denylist** (contract → HashSet<selector>):
+///    - Drops events whose selector (first key) is denied for their contract, before
+///      any decoder runs
+///    - Use for contracts mixing relevant events with floods of irrelevant ones
+///      (e.g. oracle updates)
+///
+/// 4. **Short-circuiting** (HashSet<Felt> of mapped contracts):
+///    - Mapped decoders run in mapping order, which is their priority
+///    - For short-circuited contracts, decoding stops at the first decoder producing
+///      envelopes, instead of running every mapped decoder on every event
+///    - Use for busy contracts mapped to several decoders that never claim the same event
+///
 /// For unmapped contracts (not in mappings or blacklist), the behavior depends on
 /// whether a `ContractRegistry` is configured:
 /// - With registry: Auto-identification via ABI inspection
@@ -203,14 +240,19 @@ impl DecoderId {
 /// ```rust,ignore
 /// use crate::etl::decoder::{ContractFilter, DecoderId};
 /// use starknet::core::types::Felt;
+/// use starknet::macros::selector;
 ///
 /// let usdc = Felt::from_hex("0x123...").unwrap();
 /// let noisy = Felt::from_hex("0xabc...").unwrap();
+/// let game = Felt::from_hex("0xdef...").unwrap();
 /// let erc20_id = DecoderId::new("erc20");
+/// let game_id = DecoderId::new("game_events");
 ///
 /// let filter = ContractFilter::new()
-///     .map_contract(usdc, vec![erc20_id])  // Explicit mapping
-///     .blacklist_contract(noisy);          // Blacklist noisy contract
+///     .map_contract(usdc, vec![erc20_id])           // Explicit mapping
+///     .blacklist_contract(noisy)                    // Blacklist noisy contract
+///     .deny_selector(usdc, selector!("Approval"))   // Drop USDC approvals
+///     .map_contract_first_match(game, vec![game_id, erc20_id]); // game_events first
 /// ```
 #[derive(Debug, Clone, Default)]
 pub struct ContractFilter {
@@ -219,6 +261,12 @@ pub struct ContractFilter {
 
     /// Blacklist: contracts to ignore entirely
     pub blacklist: HashSet<Felt>,
+
+    /// Selector denylist: contract → event selectors to drop
+    pub selector_denylist: HashMap<Felt, HashSet<Felt>>,
+
+    /// Mapped contracts whose decoding stops at the first decoder producing envelopes
+    pub short_circuit: HashSet<Felt>,
 }
 
 impl ContractFilter {
@@ -236,6 +284,13 @@ impl ContractFilter {
         !self.blacklist.contains(&contract)
     }
 
+    /// Check if events of a contract with a selector are dropped before decoding
+    pub fn denies_selector(&self, contract: Felt, selector: Felt) -> bool {
+        self.selector_denylist
+            .get(&contract)
+            .is_some_and(|denied| denied.contains(&selector))
+    }
+
     /// Get decoders for a contract
     ///
     /// # Returns
@@ -245,6 +300,11 @@ impl ContractFilter {
         self.mappings.get(&contract)
     }
 
+    /// Check if decoding of a mapped contract stops at the first decoder producing envelopes
+    pub fn short_circuits(&self, contract: Felt) -> bool {
+        self.short_circuit.contains(&contract)
+    }
+
     /// Validate configuration (no contract in both mapping and blacklist)
     pub fn validate(&self) -> anyhow::Result<()> {
         for addr in self.mappings.keys() {
@@ -261,6 +321,14 @@ impl ContractFilter {
         self
     }
 
+    /// Add explicit mapping whose decoders are tried in order, stopping at the first
+    /// one producing envelopes
+    pub fn map_contract_first_match(mut self, contract: Felt, decoder_ids: Vec<DecoderId>) -> Self {
+        self.mappings.insert(contract, decoder_ids);
+        self.short_circuit.insert(contract);
+        self
+    }
+
     /// Add contract to blacklist
     pub fn blacklist_contract(mut self, contract: Felt) -> Self {
         self.blacklist.insert(contract);
@@ -272,4 +340,27 @@ impl ContractFilter {
         self.blacklist.extend(contracts);
         self
     }
+
+    /// Drop events of `contract` with `selector` before decoding
+    pub fn deny_selector(mut self, contract: Felt, selector: Felt) -> Self {
+        self.selector_denylist
+            .entry(contract)
+            .or_default()
+            .insert(selector);
+        self
+    }
+
+    /// Drop events of `contract` with any of `selectors` before decoding
+    pub fn deny_selectors(mut self, contract: Felt, selectors: Vec<Felt>) -> Self {
+        self.selector_denylist
+            .entry(contract)
+            .or_default()
+            .extend(selectors);
+        self
+    }
+
+    /// Number of denied (contract, selector) pairs
+    pub fn denied_selector_count(&self) -> usize {
+        self.selector_denylist.values().map(HashSet::len).sum()
+    }
 }
@@ -0,0 +1,635 @@
+//! Decoder hot-reload from a configuration file.
+//!
+//! The decoder/contract sections of a TOML file (e.g. `torii.toml` or
+//! `torii-tokens.toml`) are applied to a running [`DecoderContext`] without a
+//! restart. Other sections of the file are ignored.
+//!
+//! ```toml
+//! # Explicit contract mappings
+//! [[contracts]]
+//! address = "0x049d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7"
+//! decoders = ["erc20"]
+//!
+//! # Decoders tried in order, stopping at the first one producing envelopes
+//! [[contracts]]
+//! address = "0x0abc"
+//! decoders = ["game_events", "erc20"]
+//! short_circuit = true
+//!
+//! # Contracts to ignore
+//! [blacklist]
+//! contracts = ["0x0123"]
+//!
+//! # Events dropped before decoding, by selector or event name
+//! [[selector_denylist]]
+//! contract = "0x0456"
+//! selectors = ["PriceUpdate", "0x0789"]
+//!
+//! # Decoders instantiated by a registered `DecoderFactory` of the same kind
+//! [[decoders]]
+//! name = "game_events"
+//! kind = "abi"
+//! abi = "abis/game.json"
+//! ```
+//!
+//! On every change of the file, the [`DecoderConfigWatcher`]:
+//! - inserts new explicit mappings into the contract filter and the registry cache,
+//! - blacklists contracts whose mapping was removed from the file,
+//! - adds the selectors of `[[selector_denylist]]` entries to the selector denylist,
+//! - instantiates decoders of new `[[decoders]]` entries,
+//!
+//! and swaps the result in with a [`DecoderReloadHandle`]. An invalid file is
+//! logged and leaves the current decoders in place.
+//!
+//! [`DecoderContext`]: super::DecoderContext
+
+use anyhow::{Context, Result};
+use starknet::core::types::Felt;
+use starknet::core::utils::get_selector_from_name;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio_util::sync::CancellationToken;
+use toml_edit::{DocumentMut, Table};
+
+use super::{ContractFilter, Decoder, DecoderId, DecoderReloadHandle};
+
+/// Default interval between checks of the configuration file
+pub const DEFAULT_DECODER_CONFIG_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Explicit mapping of a contract to decoders
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractMapping {
+    pub address: Felt,
+    /// Decoder names, in priority order
+    pub decoders: Vec<String>,
+    /// Stop at the first decoder producing envelopes
+    pub short_circuit: bool,
+}
+
+/// Event selectors of a contract dropped before decoding
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelectorDenylist {
+    pub contract: Felt,
+    pub selectors: Vec<Felt>,
+}
+
+/// Decoder instantiated from the configuration
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DecoderSpec {
+    /// Decoder name, used in contract mappings
+    pub name: String,
+    /// Factory kind (see [`DecoderFactory::kind`])
+    pub kind: String,
+    /// ABI file, resolved relative to the configuration file
+    pub abi: Option<PathBuf>,
+}
+
+/// Decoder/contract sections of a configuration file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DecoderConfig {
+    pub contracts: Vec<ContractMapping>,
+    pub blacklist: Vec<Felt>,
+    pub selector_denylist: Vec<SelectorDenylist>,
+    pub decoders: Vec<DecoderSpec>,
+}
+
+impl DecoderConfig {
+    /// Reads the decoder/contract sections of the TOML file at `path`.
+    pub fn load(path: &Path) -> Result<Self> {
+        let source = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read decoder config {}", path.display()))?;
+        let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+        Self::parse(&source, base_dir)
+            .with_context(|| format!("Invalid decoder config {}", path.display()))
+    }
+
+    /// Parses the decoder/contract sections of a TOML document.
+    ///
+    /// Relative ABI paths are resolved against `base_dir`.
+    pub fn parse(source: &str, base_dir: &Path) -> Result<Self> {
+        let document: DocumentMut = source.parse()?;
+        let mut config = Self::default();
+
+        for table in tables(&document, "contracts")? {
+            config.contracts.push(ContractMapping {
+                address: parse_felt(required_str(table, "contracts", "address")?)?,
+                decoders: string_array(table, "decoders")?,
+                short_circuit: optional_bool(table, "short_circuit")?.unwrap_or(false),
+            });
+        }
+
+        if let Some(blacklist) = document.get("blacklist") {
+            let blacklist = blacklist
+                .as_table()
+                .context("`blacklist` must be a table")?;
+            for contract in string_array(blacklist, "contracts")? {
+                config.blacklist.push(parse_felt(&contract)?);
+            }
+        }
+
+        for table in tables(&document, "selector_denylist")? {
+            config.selector_denylist.push(SelectorDenylist {
+                contract: parse_felt(required_str(table, "selector_denylist", "contract")?)?,
+                selectors: string_array(table, "selectors")?
+                    .iter()
+                    .map(|selector| parse_selector(selector))
+                    .collect::<Result<_>>()?,
+            });
+        }
+
+        for table in tables(&document, "decoders")? {
+            config.decoders.push(DecoderSpec {
+                name: required_str(table, "decoders", "name")?.to_string(),
+                kind: required_str(table, "decoders", "kind")?.to_string(),
+                abi: optional_str(table, "abi")?.map(|abi| base_dir.join(abi)),
+            });
+        }
+
+        Ok(config)
+    }
+}
+
+/// Entries of a `[[key]]` array of tables (none if absent)
+fn tables<'a>(document: &'a DocumentMut, key: &str) -> Result<Vec<&'a Table>> {
+    match document.get(key) {
+        None => Ok(Vec::new()),
+        Some(item) => Ok(item
+            .as_array_of_tables()
+            .with_context(|| format!("`{key}` must be an array of tables ([[{key}]])"))?
+            .iter()
+            .collect()),
+    }
+}
+
+fn optional_str<'a>(table: &'a Table, key: &str) -> Result<Option<&'a str>> {
+    table
+        .get(key)
+        .map(|item| {
+            item.as_str()
+                .with_context(|| format!("`{key}` must be a string"))
+        })
+        .transpose()
+}
+
+fn optional_bool(table: &Table, key: &str) -> Result<Option<bool>> {
+    table
+        .get(key)
+        .map(|item| {
+            item.as_bool()
+                .with_context(|| format!("`{key}` must be a boolean"))
+        })
+        .transpose()
+}
+
+fn required_str<'a>(table: &'a Table, section: &str, key: &str) -> Result<&'a str> {
+    optional_str(table, key)?.with_context(|| format!("[[{section}]] entry without `{key}`"))
+}
+
+fn string_array(table: &Table, key: &str) -> Result<Vec<String>> {
+    let Some(item) = table.get(key) else {
+        return Ok(Vec::new());
+    };
+    item.as_array()
+        .with_context(|| format!("`{key}` must be an array of strings"))?
+        .iter()
+        .map(|value| {
+            value
+                .as_str()
+                .map(ToString::to_string)
+                .with_context(|| format!("`{key}` must be an array of strings"))
+        })
+        .collect()
+}
+
+fn parse_felt(value: &str) -> Result<Felt> {
+    Felt::from_hex(value).with_context(|| format!("Invalid contract address {value}"))
+}
+
+/// Hex selector, or event name hashed with `starknet_keccak`
+fn parse_selector(value: &str) -> Result<Felt> {
+    if value.starts_with("0x") {
+        Felt::from_hex(value).with_context(|| format!("Invalid selector {value}"))
+    } else {
+        get_selector_from_name(value).with_context(|| format!("Invalid event name {value}"))
+    }
+}
+
+/// Instantiates decoders declared in the configuration.
+///
+/// Factories are registered per `kind`; a `[[decoders]]` entry is handed to the
+/// factory of its kind (e.g. one building ABI-driven decoders from `abi`).
+pub trait DecoderFactory: Send + Sync {
+    /// Value of `kind` handled by this factory
+    fn kind(&self) -> &str;
+
+    /// Creates the decoder of `spec`.
+    ///
+    /// The decoder's `decoder_name()` must be `spec.name`.
+    fn create(&self, spec: &DecoderSpec) -> Result<Arc<dyn Decoder>>;
+}
+
+/// Changes applied by a reload
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReloadSummary {
+    /// Contracts mapped by the file that were not mapped before
+    pub mapped: usize,
+    /// Contracts blacklisted because their mapping was removed
+    pub unmapped: usize,
+    /// Decoders instantiated from new `[[decoders]]` entries
+    pub decoders_created: usize,
+    /// Decoders dropped with their `[[decoders]]` entry
+    pub decoders_dropped: usize,
+}
+
+/// Watches a configuration file and applies its decoder/contract sections.
+///
+/// The decoders and contract filter present when the watcher is created (from
+/// code or CLI flags) are kept; the file adds to them.
+pub struct DecoderConfigWatcher {
+    path: PathBuf,
+    handle: DecoderReloadHandle,
+    factories: HashMap<String, Arc<dyn DecoderFactory>>,
+    base_decoders: Vec<Arc<dyn Decoder>>,
+    base_filter: ContractFilter,
+    /// Decoders instantiated from the current `[[decoders]]` entries
+    instantiated: HashMap<DecoderSpec, Arc<dyn Decoder>>,
+    /// Contracts mapped by the last applied file
+    mapped: HashSet<Felt>,
+    /// Contracts whose mapping was removed from the file
+    unmapped: HashSet<Felt>,
+    last_modified: Option<SystemTime>,
+}
+
+impl DecoderConfigWatcher {
+    pub fn new(path: impl Into<PathBuf>, handle: DecoderReloadHandle) -> Self {
+        Self {
+            path: path.into(),
+            base_decoders: handle.decoders(),
+            base_filter: handle.contract_filter(),
+            handle,
+            factories: HashMap::new(),
+            instantiated: HashMap::new(),
+            mapped: HashSet::new(),
+            unmapped: HashSet::new(),
+            last_modified: None,
+        }
+    }
+
+    /// Registers the factory instantiating `[[decoders]]` entries of its kind.
+    pub fn with_factory(mut self, factory: Arc<dyn DecoderFactory>) -> Self {
+        self.factories.insert(factory.kind().to_string(), factory);
+        self
+    }
+
+    /// Path of the watched file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Reads the file and applies it.
+    pub async fn reload(&mut self) -> Result<ReloadSummary> {
+        self.last_modified = modified(&self.path);
+        let config = DecoderConfig::load(&self.path)?;
+        self.apply(config).await
+    }
+
+    /// Applies `config` on top of the base decoders and contract filter.
+    ///
+    /// Nothing changes if the configuration is invalid.
+    pub async fn apply(&mut self, config: DecoderConfig) -> Result<ReloadSummary> {
+        let mut summary = ReloadSummary::default();
+
+        let mut instantiated = HashMap::new();
+        for spec in config.decoders {
+            if instantiated.contains_key(&spec) {
+                continue;
+            }
+            let decoder = if let Some(decoder) = self.instantiated.get(&spec) {
+                decoder.clone()
+            } else {
+                summary.decoders_created += 1;
+                self.create_decoder(&spec)?
+            };
+            instantiated.insert(spec, decoder);
+        }
+        summary.decoders_dropped = self
+            .instantiated
+            .keys()
+            .filter(|spec| !instantiated.contains_key(*spec))
+            .count();
+
+        let mut filter = self.base_filter.clone();
+        let mapped: HashSet<Felt> = config
+            .contracts
+            .iter()
+            .map(|mapping| mapping.address)
+            .collect();
+        for mapping in config.contracts {
+            let decoder_ids = mapping
+                .decoders
+                .iter()
+                .map(|name| DecoderId::new(name))
+                .collect();
+            filter.mappings.insert(mapping.address, decoder_ids);
+            if mapping.short_circuit {
+                filter.short_circuit.insert(mapping.address);
+            } else {
+                filter.short_circuit.remove(&mapping.address);
+            }
+        }
+        summary.mapped = mapped.difference(&self.mapped).count();
+
+        let mut unmapped: HashSet<Felt> = self
+            .unmapped
+            .iter()
+            .chain(self.mapped.difference(&mapped))
+            .filter(|contract| !filter.mappings.contains_key(*contract))
+            .copied()
+            .collect();
+        unmapped.retain(|contract| !mapped.contains(contract));
+        summary.unmapped = unmapped.difference(&self.unmapped).count();
+        filter.blacklist.extend(config.blacklist);
+        filter.blacklist.extend(unmapped.iter().copied());
+        for denylist in config.selector_denylist {
+            filter
+                .selector_denylist
+                .entry(denylist.contract)
+                .or_default()
+                .extend(denylist.selectors);
+        }
+
+        let decoders = self
+            .base_decoders
+            .iter()
+            .cloned()
+            .chain(instantiated.values().cloned())
+            .collect();
+        self.handle.swap(decoders, filter).await?;
+
+        self.instantiated = instantiated;
+        self.mapped = mapped;
+        self.unmapped = unmapped;
+        Ok(summary)
+    }
+
+    fn create_decoder(&self, spec: &DecoderSpec) -> Result<Arc<dyn Decoder>> {
+        let factory = self.factories.get(&spec.kind).with_context(|| {
+            format!(
+                "No decoder factory for kind `{}` (decoder `{}`)",
+                spec.kind, spec.name
+            )
+        })?;
+        let decoder = factory
+            .create(spec)
+            .with_context(|| format!("Failed to create decoder `{}`", spec.name))?;
+        if decoder.decoder_name() != spec.name {
+            anyhow::bail!(
+                "Decoder `{}` created by factory `{}` is named `{}`",
+                spec.name,
+                spec.kind,
+                decoder.decoder_name()
+            );
+        }
+        Ok(decoder)
+    }
+
+    /// Checks the file every `poll_interval` and reloads it when it changed, until
+    /// `shutdown` is cancelled.
+    pub async fn run(mut self, poll_interval: Duration, shutdown: CancellationToken) {
+        let mut ticker = tokio::time::interval(poll_interval);
+        ticker.tick().await;
+        loop {
+            tokio::select! {
+                () = shutdown.cancelled() => break,
+                _ = ticker.tick() => {}
+            }
+            let modified = modified(&self.path);
+            if modified.is_none() || modified == self.last_modified {
+                continue;
+            }
+            match self.reload().await {
+                Ok(summary) => {
+                    ::metrics::counter!("torii_decoder_config_reloads_total", "result" => "ok")
+                        .increment(1);
+                    tracing::info!(
+                        target: "torii::etl::decoder_reload",
+                        path = %self.path.display(),
+                        mapped = summary.mapped,
+                        unmapped = summary.unmapped,
+                        decoders_created = summary.decoders_created,
+                        decoders_dropped = summary.decoders_dropped,
+                        "Reloaded decoder config"
+                    );
+                }
+                Err(e) => {
+                    ::metrics::counter!("torii_decoder_config_reloads_total", "result" => "error")
+                        .increment(1);
+                    tracing::warn!(
+                        target: "torii::etl::decoder_reload",
+                        path = %self.path.display(),
+                        error = %format!("{e:#}"),
+                        "Invalid decoder config, keeping current decoders"
+                    );
+                }
+            }
+        }
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ToriiResult;
+    use crate::etl::decoder::DecoderContext;
+    use crate::etl::engine_db::{EngineDb, EngineDbConfig};
+    use crate::etl::envelope::Envelope;
+    use async_trait::async_trait;
+    use starknet::core::types::EmittedEvent;
+    use tokio::sync::RwLock;
+
+    struct NamedDecoder(String);
+
+    #[async_trait]
+    impl Decoder for NamedDecoder {
+        fn decoder_name(&self) -> &str {
+            &self.0
+        }
+
+        async fn decode_event(&self, _event: &EmittedEvent) -> ToriiResult<Vec<Envelope>> {
+            Ok(Vec::new())
+        }
+    }
+
+    struct NamedFactory;
+
+    impl DecoderFactory for NamedFactory {
+        fn kind(&self) -> &'static str {
+            "abi"
+        }
+
+        fn create(&self, spec: &DecoderSpec) -> Result<Arc<dyn Decoder>> {
+            Ok(Arc::new(NamedDecoder(spec.name.clone())))
+        }
+    }
+
+    async fn context() -> (DecoderContext, Arc<RwLock<HashMap<Felt, Vec<DecoderId>>>>) {
+        let engine_db = Arc::new(
+            EngineDb::new(EngineDbConfig {
+                path: "sqlite::memory:".to_string(),
+            })
+            .await
+            .unwrap(),
+        );
+        let cache = Arc::new(RwLock::new(HashMap::new()));
+        let context = DecoderContext::with_registry(
+            vec![Arc::new(NamedDecoder("erc20".to_string()))],
+            engine_db,
+            ContractFilter::new(),
+            cache.clone(),
+        );
+        (context, cache)
+    }
+
+    #[test]
+    fn parses_decoder_sections() {
+        let config = DecoderConfig::parse(
+            r#"
+            [server]
+            port = 8080
+
+            [[contracts]]
+            address = "0x10"
+            decoders = ["erc20", "game"]
+            short_circuit = true
+
+            [blacklist]
+            contracts = ["0x20"]
+
+            [[selector_denylist]]
+            contract = "0x30"
+            selectors = ["0x40", "PriceUpdate"]
+
+            [[decoders]]
+            name = "game"
+            kind = "abi"
+            abi = "abis/game.json"
+            "#,
+            Path::new("/etc/torii"),
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.contracts,
+            vec![ContractMapping {
+                address: Felt::from(0x10_u64),
+                decoders: vec!["erc20".to_string(), "game".to_string()],
+                short_circuit: true,
+            }]
+        );
+        assert_eq!(config.blacklist, vec![Felt::from(0x20_u64)]);
+        assert_eq!(
+            config.selector_denylist,
+            vec![SelectorDenylist {
+                contract: Felt::from(0x30_u64),
+                selectors: vec![
+                    Felt::from(0x40_u64),
+                    get_selector_from_name("PriceUpdate").unwrap()
+                ],
+            }]
+        );
+        assert_eq!(
+            config.decoders[0].abi.as_deref(),
+            Some(Path::new("/etc/torii/abis/game.json"))
+        );
+
+        assert!(DecoderConfig::parse("[[contracts]]\ndecoders = []", Path::new(".")).is_err());
+        assert!(DecoderConfig::parse("contracts = 1", Path::new(".")).is_err());
+        assert!(DecoderConfig::parse(
+            "[[contracts]]\naddress = \"0x1\"\nshort_circuit = \"yes\"",
+            Path::new(".")
+        )
+        .is_err());
+        assert!(DecoderConfig::parse(
+            "[[selector_denylist]]\nselectors = [\"0x1\"]",
+            Path::new(".")
+        )
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn apply_maps_new_contracts_and_blacklists_removed_ones() {
+        let (context, cache) = context().await;
+        let mut watcher = DecoderConfigWatcher::new("torii.toml", context.reload_handle())
+            .with_factory(Arc::new(NamedFactory));
+        let mapping = |address: u64, decoders: &[&str]| ContractMapping {
+            address: Felt::from(address),
+            decoders: decoders.iter().map(ToString::to_string).collect(),
+            short_circuit: false,
+        };
+        let game = DecoderSpec {
+            name: "game".to_string(),
+            kind: "abi".to_string(),
+            abi: None,
+        };
+
+        let summary = watcher
+            .apply(DecoderConfig {
+                contracts: vec![mapping(1, &["erc20"]), mapping(2, &["game"])],
+                blacklist: Vec::new(),
+                selector_denylist: vec![SelectorDenylist {
+                    contract: Felt::from(1_u64),
+                    selectors: vec![Felt::from(0x99_u64)],
+                }],
+                decoders: vec![game.clone()],
+            })
+            .await
+            .unwrap();
+        assert_eq!(summary.mapped, 2);
+        assert_eq!(summary.decoders_created, 1);
+        assert!(context
+            .reload_handle()
+            .contract_filter()
+            .denies_selector(Felt::from(1_u64), Felt::from(0x99_u64)));
+        assert!(context.get_decoder(&DecoderId::new("game")).is_some());
+        assert_eq!(
+            cache.read().await.get(&Felt::from(2_u64)),
+            Some(&vec![DecoderId::new("game")])
+        );
+
+        // Contract 2 is removed from the file: it is blacklisted.
+        let summary = watcher
+            .apply(DecoderConfig {
+                contracts: vec![mapping(1, &["erc20"])],
+                blacklist: Vec::new(),
+                selector_denylist: Vec::new(),
+                decoders: vec![game],
+            })
+            .await
+            .unwrap();
+        assert_eq!(summary.unmapped, 1);
+        assert_eq!(summary.decoders_created, 0);
+        let filter = context.reload_handle().contract_filter();
+        assert!(!filter.allows(Felt::from(2_u64)));
+        assert!(filter.allows(Felt::from(1_u64)));
+
+        // Mappings to unknown decoders are rejected and leave the context untouched.
+        let err = watcher
+            .apply(DecoderConfig {
+                contracts: vec![mapping(3, &["missing"])],
+                ..DecoderConfig::default()
+            })
+            .await;
+        assert!(err.is_err());
+        assert!(context.get_decoder(&DecoderId::new("game")).is_some());
+        assert!(!context
+            .reload_handle()
+            .contract_filter()
+            .allows(Felt::from(2_u64)));
+    }
+}
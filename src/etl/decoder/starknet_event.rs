@@ -0,0 +1,479 @@
+//! Declarative Starknet event decoding (`#[derive(StarknetEvent)]`)
+//!
+//! Decoders map the keys and data of an [`EmittedEvent`] to an envelope body. Instead of
+//! indexing `keys`/`data` by hand, a body struct annotates where each field lives and
+//! derives the parsing, its selector constant and its [`TypedBody`] impl:
+//!
+//! ```rust,ignore
+//! use torii::etl::decoder::StarknetEvent;
+//!
+//! #[derive(Debug, Clone, StarknetEvent)]
+//! #[starknet_event(type_id = "erc20.transfer", layouts(declared, data))]
+//! pub struct Transfer {
+//!     #[event(key)]
+//!     pub from: Felt,
+//!     #[event(key)]
+//!     pub to: Felt,
+//!     #[event(data)]
+//!     pub amount: U256,
+//!     #[event(from_address)]
+//!     pub token: Felt,
+//!     #[event(block_number)]
+//!     pub block_number: u64,
+//!     #[event(transaction_hash)]
+//!     pub transaction_hash: Felt,
+//! }
+//!
+//! if let Some(transfer) = Transfer::decode(&event) { /* ... */ }
+//! ```
+//!
+//! Struct attributes (`#[starknet_event(...)]`):
+//! - `type_id = "..."`: envelope type id (required)
+//! - `name = "..."`: event name hashed into the selector (defaults to the struct name)
+//! - `layouts(...)`: [`EventLayout`]s tried in order (defaults to `declared`)
+//!
+//! Field attributes (`#[event(...)]`):
+//! - `key` / `data`: event member, in declaration order (field types implement [`EventField`])
+//! - `default`: the member may be omitted at the end of the declared layout (zero value)
+//! - `from_address`, `block_number`, `transaction_hash`: event context
+//!
+//! The generated code refers to `::starknet`, which the deriving crate must depend on.
+//!
+//! Events emitted under other selectors than the body's (component-scoped events,
+//! renamed events) are mapped back to it with [`SelectorAliases`] before decoding.
+
+use starknet::core::types::{EmittedEvent, Felt, U256};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+
+use crate::etl::envelope::TypedBody;
+
+pub use torii_event_derive::StarknetEvent;
+
+/// Where the members of an event are read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventLayout {
+    /// Members in keys or data as annotated (`#[event(key)]` / `#[event(data)]`)
+    Declared,
+    /// Every member in keys
+    Keys,
+    /// Every member in data (legacy, pre-keys contracts)
+    Data,
+    /// [`Declared`](Self::Declared) with wide members (e.g. `U256`) as a single felt
+    DeclaredCompact,
+    /// [`Keys`](Self::Keys) with wide members as a single felt
+    KeysCompact,
+    /// [`Data`](Self::Data) with wide members as a single felt
+    DataCompact,
+}
+
+impl EventLayout {
+    fn is_compact(self) -> bool {
+        matches!(
+            self,
+            Self::DeclaredCompact | Self::KeysCompact | Self::DataCompact
+        )
+    }
+
+    fn is_declared(self) -> bool {
+        matches!(self, Self::Declared | Self::DeclaredCompact)
+    }
+}
+
+/// Declared placement of an event member.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventSlot {
+    Key,
+    Data,
+}
+
+/// Event member: placement, felt width and whether it may be omitted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventMember {
+    pub slot: EventSlot,
+    pub width: usize,
+    pub default: bool,
+}
+
+/// Value decodable from event felts.
+pub trait EventField: Sized {
+    /// Felts taken in non-compact layouts.
+    const WIDTH: usize;
+
+    /// Decodes exactly [`WIDTH`](Self::WIDTH) felts.
+    fn from_felts(felts: &[Felt]) -> Option<Self>;
+
+    /// Decodes the single-felt encoding of compact layouts.
+    fn from_compact(felt: Felt) -> Option<Self> {
+        Self::from_felts(&[felt])
+    }
+
+    /// Value of an omitted member.
+    fn zero() -> Self;
+}
+
+impl EventField for Felt {
+    const WIDTH: usize = 1;
+
+    fn from_felts(felts: &[Felt]) -> Option<Self> {
+        felts.first().copied()
+    }
+
+    fn zero() -> Self {
+        Felt::ZERO
+    }
+}
+
+impl EventField for bool {
+    const WIDTH: usize = 1;
+
+    fn from_felts(felts: &[Felt]) -> Option<Self> {
+        felts.first().map(|felt| *felt != Felt::ZERO)
+    }
+
+    fn zero() -> Self {
+        false
+    }
+}
+
+macro_rules! int_event_field {
+    ($($t:ty),*) => {
+        $(
+            impl EventField for $t {
+                const WIDTH: usize = 1;
+
+                fn from_felts(felts: &[Felt]) -> Option<Self> {
+                    felts.first().and_then(|felt| (*felt).try_into().ok())
+                }
+
+                fn zero() -> Self {
+                    0
+                }
+            }
+        )*
+    };
+}
+
+int_event_field!(u8, u16, u32, u64, u128);
+
+/// `u256` as `(low, high)` felts. Words over 128 bits decode as zero, like Cairo
+/// serialization never produces them.
+impl EventField for U256 {
+    const WIDTH: usize = 2;
+
+    fn from_felts(felts: &[Felt]) -> Option<Self> {
+        let [low, high] = felts else {
+            return None;
+        };
+        Some(U256::from_words(
+            (*low).try_into().unwrap_or(0),
+            (*high).try_into().unwrap_or(0),
+        ))
+    }
+
+    fn from_compact(felt: Felt) -> Option<Self> {
+        Some(U256::from(u128::try_from(felt).unwrap_or(0)))
+    }
+
+    fn zero() -> Self {
+        U256::from(0u64)
+    }
+}
+
+/// Event body decoded from an [`EmittedEvent`], usually derived.
+pub trait StarknetEvent: TypedBody + Sized {
+    /// Event name
+    const NAME: &'static str;
+    /// `sn_keccak` of the event name (`keys[0]`)
+    const SELECTOR: Felt;
+    /// Envelope type id
+    const TYPE_ID: &'static str;
+    /// Layouts tried in order by [`decode`](Self::decode)
+    const LAYOUTS: &'static [EventLayout];
+
+    /// Decodes `event` with `layout`, without checking the selector.
+    fn decode_with(event: &EmittedEvent, layout: EventLayout) -> Option<Self>;
+
+    /// Decodes `event` with the first matching layout.
+    ///
+    /// Returns `None` if the selector differs or no layout matches the event's keys
+    /// and data lengths.
+    fn decode(event: &EmittedEvent) -> Option<Self> {
+        if event.keys.first() != Some(&Self::SELECTOR) {
+            return None;
+        }
+        Self::LAYOUTS
+            .iter()
+            .find_map(|layout| Self::decode_with(event, *layout))
+    }
+
+    /// Envelope id of the body decoded from `event`:
+    /// `{type_id with '_' for '.'}_{block_number}_{tx_hash}`.
+    fn envelope_id(event: &EmittedEvent) -> String {
+        format!(
+            "{}_{}_{:#x}",
+            Self::TYPE_ID.replace('.', "_"),
+            event.block_number.unwrap_or(0),
+            event.transaction_hash
+        )
+    }
+}
+
+/// Alternative selectors under which a decoder's events are emitted.
+///
+/// - Component variants: a Cairo component embedded without `#[flat]` (e.g. OpenZeppelin's
+///   `ERC20Component` as `ERC20Event: ERC20Component::Event`) emits its events with the
+///   selector of the contract's variant (`ERC20Event`) in `keys[0]`, followed by the event
+///   selector (`Transfer`) and its members.
+/// - Aliases: an event emitted under another name, with the same members.
+///
+/// [`normalize`](Self::normalize) rewrites such events to the flat layout expected by
+/// [`StarknetEvent::decode`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SelectorAliases {
+    components: HashSet<Felt>,
+    aliases: HashMap<Felt, Felt>,
+}
+
+impl SelectorAliases {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Treats `selector` in `keys[0]` as a component variant, followed by the event selector.
+    pub fn with_component(mut self, selector: Felt) -> Self {
+        self.components.insert(selector);
+        self
+    }
+
+    /// Treats `alias` as the event selector `selector`.
+    pub fn with_alias(mut self, alias: Felt, selector: Felt) -> Self {
+        self.aliases.insert(alias, selector);
+        self
+    }
+
+    /// Adds the component variants and aliases of `other`.
+    pub fn merge(mut self, other: &Self) -> Self {
+        self.components.extend(&other.components);
+        self.aliases.extend(&other.aliases);
+        self
+    }
+
+    /// Returns `event` with the component variant key removed and an aliased selector
+    /// replaced, borrowing it when it needs neither.
+    pub fn normalize<'a>(&self, event: &'a EmittedEvent) -> Cow<'a, EmittedEvent> {
+        let scoped = event.keys.len() > 1 && self.components.contains(&event.keys[0]);
+        let keys = if scoped {
+            &event.keys[1..]
+        } else {
+            &event.keys[..]
+        };
+        let alias = keys.first().and_then(|selector| self.aliases.get(selector));
+        if !scoped && alias.is_none() {
+            return Cow::Borrowed(event);
+        }
+
+        let mut normalized = event.clone();
+        normalized.keys = keys.to_vec();
+        if let Some(selector) = alias {
+            normalized.keys[0] = *selector;
+        }
+        Cow::Owned(normalized)
+    }
+}
+
+/// Reads the members of an event for one layout (used by the derived code).
+pub struct EventReader<'a> {
+    keys: &'a [Felt],
+    data: &'a [Felt],
+    layout: EventLayout,
+    members: &'a [EventMember],
+    omit_defaults: bool,
+    next: usize,
+}
+
+impl<'a> EventReader<'a> {
+    /// Returns `None` if the event's keys (selector excluded) and data do not have the
+    /// lengths required by `members` in `layout`.
+    pub fn new(
+        event: &'a EmittedEvent,
+        layout: EventLayout,
+        members: &'a [EventMember],
+    ) -> Option<Self> {
+        let (_, keys) = event.keys.split_first()?;
+        let mut reader = Self {
+            keys,
+            data: &event.data,
+            layout,
+            members,
+            omit_defaults: false,
+            next: 0,
+        };
+        if reader.lengths() == (keys.len(), event.data.len()) {
+            return Some(reader);
+        }
+        reader.omit_defaults = true;
+        (layout.is_declared()
+            && members.iter().any(|member| member.default)
+            && reader.lengths() == (keys.len(), event.data.len()))
+            .then_some(reader)
+    }
+
+    fn slot(&self, member: &EventMember) -> EventSlot {
+        match self.layout {
+            EventLayout::Keys | EventLayout::KeysCompact => EventSlot::Key,
+            EventLayout::Data | EventLayout::DataCompact => EventSlot::Data,
+            EventLayout::Declared | EventLayout::DeclaredCompact => member.slot,
+        }
+    }
+
+    fn width(&self, member: &EventMember) -> usize {
+        if self.omit_defaults && member.default {
+            0
+        } else if self.layout.is_compact() {
+            member.width.min(1)
+        } else {
+            member.width
+        }
+    }
+
+    fn lengths(&self) -> (usize, usize) {
+        self.members
+            .iter()
+            .fold((0, 0), |(keys, data), member| match self.slot(member) {
+                EventSlot::Key => (keys + self.width(member), data),
+                EventSlot::Data => (keys, data + self.width(member)),
+            })
+    }
+
+    /// Reads the next member.
+    pub fn read<T: EventField>(&mut self) -> Option<T> {
+        let member = *self.members.get(self.next)?;
+        let offset = self.members[..self.next]
+            .iter()
+            .filter(|previous| self.slot(previous) == self.slot(&member))
+            .map(|previous| self.width(previous))
+            .sum::<usize>();
+        self.next += 1;
+
+        let felts = match self.slot(&member) {
+            EventSlot::Key => self.keys,
+            EventSlot::Data => self.data,
+        };
+        match self.width(&member) {
+            0 => Some(T::zero()),
+            1 if self.layout.is_compact() => T::from_compact(*felts.get(offset)?),
+            width => T::from_felts(felts.get(offset..offset + width)?),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use starknet::macros::selector;
+
+    #[derive(Debug, Clone, PartialEq, StarknetEvent)]
+    #[starknet_event(type_id = "test.transfer", layouts(declared, data, declared_compact))]
+    struct Transfer {
+        #[event(key)]
+        from: Felt,
+        #[event(key)]
+        to: Felt,
+        #[event(data, default)]
+        amount: U256,
+        #[event(from_address)]
+        token: Felt,
+        #[event(block_number)]
+        block_number: u64,
+        #[event(transaction_hash)]
+        transaction_hash: Felt,
+    }
+
+    fn event(keys: &[u64], data: &[u64]) -> EmittedEvent {
+        EmittedEvent {
+            from_address: Felt::from(0x42_u64),
+            keys: std::iter::once(selector!("Transfer"))
+                .chain(keys.iter().map(|key| Felt::from(*key)))
+                .collect(),
+            data: data.iter().map(|value| Felt::from(*value)).collect(),
+            block_hash: None,
+            block_number: Some(7),
+            transaction_hash: Felt::from(0x99_u64),
+        }
+    }
+
+    #[test]
+    fn decodes_every_layout() {
+        assert_eq!(Transfer::SELECTOR, selector!("Transfer"));
+        assert_eq!(Transfer::NAME, "Transfer");
+
+        let expected = |amount: u64| Transfer {
+            from: Felt::ONE,
+            to: Felt::TWO,
+            amount: U256::from(amount),
+            token: Felt::from(0x42_u64),
+            block_number: 7,
+            transaction_hash: Felt::from(0x99_u64),
+        };
+        assert_eq!(
+            Transfer::decode(&event(&[1, 2], &[5, 0])),
+            Some(expected(5))
+        );
+        assert_eq!(
+            Transfer::decode(&event(&[], &[1, 2, 6, 0])),
+            Some(expected(6))
+        );
+        assert_eq!(Transfer::decode(&event(&[1, 2], &[8])), Some(expected(8)));
+        assert_eq!(Transfer::decode(&event(&[1, 2], &[])), Some(expected(0)));
+
+        let high = Transfer::decode(&event(&[1, 2], &[0, 1])).unwrap();
+        assert_eq!(high.amount, U256::from_words(0, 1));
+
+        // Keys layout not enabled, unknown lengths and other selectors are rejected.
+        assert_eq!(Transfer::decode(&event(&[1, 2, 5, 0], &[])), None);
+        assert_eq!(Transfer::decode(&event(&[], &[1, 2])), None);
+        let mut other = event(&[1, 2], &[5, 0]);
+        other.keys[0] = selector!("Approval");
+        assert_eq!(Transfer::decode(&other), None);
+
+        let body = expected(0);
+        assert_eq!(
+            body.envelope_type_id(),
+            crate::etl::TypeId::new("test.transfer")
+        );
+        assert_eq!(
+            Transfer::envelope_id(&event(&[1, 2], &[])),
+            "test_transfer_7_0x99"
+        );
+    }
+
+    #[test]
+    fn normalizes_component_and_aliased_events() {
+        let aliases = SelectorAliases::new()
+            .with_component(selector!("ERC20Event"))
+            .with_alias(selector!("TokenTransfer"), selector!("Transfer"));
+        let flat = event(&[1, 2], &[5, 0]);
+        assert!(matches!(aliases.normalize(&flat), Cow::Borrowed(_)));
+
+        let mut scoped = flat.clone();
+        scoped.keys.insert(0, selector!("ERC20Event"));
+        assert_eq!(aliases.normalize(&scoped).into_owned(), flat);
+        assert_eq!(Transfer::decode(&scoped), None);
+        assert!(Transfer::decode(&aliases.normalize(&scoped)).is_some());
+
+        let mut renamed = flat.clone();
+        renamed.keys[0] = selector!("TokenTransfer");
+        assert_eq!(aliases.normalize(&renamed).into_owned(), flat);
+        renamed.keys.insert(0, selector!("ERC20Event"));
+        assert_eq!(aliases.normalize(&renamed).into_owned(), flat);
+
+        // A lone component key is left as is.
+        let mut component_only = flat.clone();
+        component_only.keys = vec![selector!("ERC20Event")];
+        assert!(matches!(
+            aliases.normalize(&component_only),
+            Cow::Borrowed(_)
+        ));
+    }
+}
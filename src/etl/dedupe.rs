@@ -0,0 +1,331 @@
+//! Dedupe window of recently processed events.
+//!
+//! Switching extraction modes or overlapping cursors can extract the same events
+//! twice. The ETL loop drops events whose key is in the window before decoding
+//! them, so sinks never see them again.
+//!
+//! Keys are built from the transaction hash and the content of the event, not from
+//! its position in a batch: batches can split the events of a transaction anywhere,
+//! so positions are not stable across extractions. Keys of processed batches are
+//! persisted in the `EngineDb` so the window survives restarts.
+
+use anyhow::Result;
+use starknet::core::types::{EmittedEvent, Felt};
+use std::collections::{HashMap, HashSet, VecDeque};
+use xxhash_rust::xxh3::Xxh3;
+
+use crate::etl::engine_db::EngineDb;
+
+/// Event key: transaction hash and hash of the event content (see [`event_keys`]).
+pub type EventKey = (Felt, u64);
+
+/// Bounded set of the most recently seen event keys.
+#[derive(Debug)]
+pub struct EventDedupe {
+    capacity: usize,
+    order: VecDeque<EventKey>,
+    seen: HashSet<EventKey>,
+}
+
+impl EventDedupe {
+    /// Creates an empty window of `capacity` keys.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            order: VecDeque::with_capacity(capacity),
+            seen: HashSet::with_capacity(capacity),
+        }
+    }
+
+    /// Loads the keys persisted by previous runs.
+    pub async fn load(engine_db: &EngineDb, capacity: usize) -> Result<Self> {
+        let mut dedupe = Self::new(capacity);
+        for key in engine_db.get_seen_events(capacity).await? {
+            dedupe.insert(key);
+        }
+        Ok(dedupe)
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+
+    pub fn contains(&self, key: &EventKey) -> bool {
+        self.seen.contains(key)
+    }
+
+    /// Adds `key`, evicting the oldest key when the window is full.
+    ///
+    /// Returns `false` if the key was already in the window.
+    pub fn insert(&mut self, key: EventKey) -> bool {
+        if self.capacity == 0 {
+            return true;
+        }
+        if !self.seen.insert(key) {
+            return false;
+        }
+        if self.order.len() == self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.seen.remove(&oldest);
+            }
+        }
+        self.order.push_back(key);
+        true
+    }
+
+    /// Drops already seen events from `events`, with their `event_indexes`, and adds
+    /// the others to the window.
+    ///
+    /// Returns the keys of the kept events, to persist once they are processed.
+    pub fn filter(
+        &mut self,
+        events: &mut Vec<EmittedEvent>,
+        event_indexes: &mut Vec<u32>,
+    ) -> Vec<EventKey> {
+        let keys = event_keys(events);
+        let keep: Vec<bool> = keys.iter().map(|key| self.insert(*key)).collect();
+        let mut flags = keep.iter();
+        events.retain(|_| *flags.next().expect("one flag per event"));
+        if event_indexes.len() == keep.len() {
+            let mut flags = keep.iter();
+            event_indexes.retain(|_| *flags.next().expect("one flag per index"));
+        }
+        keys.into_iter()
+            .zip(keep)
+            .filter_map(|(key, keep)| keep.then_some(key))
+            .collect()
+    }
+}
+
+/// Assigns events the position of the event in its transaction.
+///
+/// Extractors can end a batch in the middle of a transaction, so the events of the
+/// last transaction of a batch are remembered: when the next batch starts with the
+/// rest of that transaction, its numbering continues, and events extracted again
+/// keep their index. Indexes must be assigned on the extracted events, before dedupe
+/// drops any of them.
+#[derive(Debug, Default)]
+pub struct EventIndexer {
+    /// Last transaction of the previous batch, with the indexes of its events.
+    last_tx: Option<(Felt, HashMap<EventKey, u32>)>,
+}
+
+impl EventIndexer {
+    /// Indexes of `events` in their transactions.
+    pub fn assign(&mut self, events: &[EmittedEvent]) -> Vec<u32> {
+        let Some(last_event) = events.last() else {
+            return Vec::new();
+        };
+        let keys = event_keys(events);
+        let mut carried = HashMap::new();
+        let mut tx_event_counts: HashMap<Felt, u32> = HashMap::new();
+        if let Some((tx_hash, indexes)) = self.last_tx.take() {
+            tx_event_counts.insert(tx_hash, indexes.len() as u32);
+            carried = indexes;
+        }
+
+        let indexes: Vec<u32> = events
+            .iter()
+            .zip(&keys)
+            .map(|(event, key)| match carried.get(key) {
+                Some(index) => *index,
+                None => {
+                    let count = tx_event_counts.entry(event.transaction_hash).or_insert(0);
+                    *count += 1;
+                    *count - 1
+                }
+            })
+            .collect();
+
+        let tx_hash = last_event.transaction_hash;
+        carried.retain(|(carried_tx, _), _| *carried_tx == tx_hash);
+        carried.extend(
+            keys.into_iter()
+                .zip(&indexes)
+                .filter(|((event_tx, _), _)| *event_tx == tx_hash)
+                .map(|(key, index)| (key, *index)),
+        );
+        self.last_tx = Some((tx_hash, carried));
+        indexes
+    }
+}
+
+/// Keys of `events`.
+///
+/// The content hash covers the emitting contract, keys and data of the event. Events
+/// of a transaction with identical content are told apart by their occurrence among
+/// the identical events of `events`, so such duplicates only dedupe reliably when
+/// they are extracted in the same batch.
+pub fn event_keys(events: &[EmittedEvent]) -> Vec<EventKey> {
+    let mut occurrences: HashMap<(Felt, u64), u64> = HashMap::new();
+    events
+        .iter()
+        .map(|event| {
+            let content = content_hash(event, 0);
+            let occurrence = occurrences
+                .entry((event.transaction_hash, content))
+                .or_insert(0);
+            let key = (event.transaction_hash, content_hash(event, *occurrence));
+            *occurrence += 1;
+            key
+        })
+        .collect()
+}
+
+fn content_hash(event: &EmittedEvent, occurrence: u64) -> u64 {
+    let mut hasher = Xxh3::new();
+    hasher.update(&event.from_address.to_bytes_be());
+    for felts in [&event.keys, &event.data] {
+        hasher.update(&(felts.len() as u64).to_le_bytes());
+        for felt in felts {
+            hasher.update(&felt.to_bytes_be());
+        }
+    }
+    hasher.update(&occurrence.to_le_bytes());
+    hasher.digest()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::etl::engine_db::EngineDbConfig;
+
+    fn filter(dedupe: &mut EventDedupe, events: &mut Vec<EmittedEvent>) -> Vec<EventKey> {
+        dedupe.filter(events, &mut Vec::new())
+    }
+
+    fn event(tx_hash: u64, data: u64) -> EmittedEvent {
+        EmittedEvent {
+            from_address: Felt::ONE,
+            keys: vec![],
+            data: vec![Felt::from(data)],
+            block_hash: None,
+            block_number: Some(1),
+            transaction_hash: Felt::from(tx_hash),
+        }
+    }
+
+    #[test]
+    fn drops_seen_events() {
+        let mut dedupe = EventDedupe::new(16);
+        let mut first = vec![event(1, 0), event(1, 1), event(2, 0)];
+        let kept = filter(&mut dedupe, &mut first);
+        assert_eq!(first.len(), 3);
+        assert_eq!(kept, event_keys(&first));
+
+        // Overlapping batch: transaction 2 was already processed.
+        let mut second = vec![event(2, 0), event(3, 0)];
+        let kept = filter(&mut dedupe, &mut second);
+        assert_eq!(second.len(), 1);
+        assert_eq!(second[0].transaction_hash, Felt::from(3_u64));
+        assert_eq!(kept, event_keys(&second));
+    }
+
+    #[test]
+    fn keeps_transactions_split_across_batches() {
+        let mut dedupe = EventDedupe::new(16);
+        let mut first = vec![event(1, 0), event(1, 1)];
+        filter(&mut dedupe, &mut first);
+
+        // The rest of transaction 1 comes first in the next batch.
+        let mut second = vec![event(1, 2), event(1, 3), event(2, 0)];
+        filter(&mut dedupe, &mut second);
+        assert_eq!(second.len(), 3);
+
+        // Replaying the whole transaction at once drops every event.
+        let mut replayed = vec![event(1, 0), event(1, 1), event(1, 2), event(1, 3)];
+        filter(&mut dedupe, &mut replayed);
+        assert!(replayed.is_empty());
+    }
+
+    #[test]
+    fn counts_identical_events_of_a_transaction() {
+        let keys = event_keys(&[event(1, 0), event(1, 0), event(2, 0)]);
+        assert_ne!(keys[0], keys[1]);
+        assert_eq!(keys[1].1, content_hash(&event(1, 0), 1));
+        // The same content in another transaction has the same hash.
+        assert_eq!(keys[0].1, keys[2].1);
+    }
+
+    #[test]
+    fn indexes_continue_across_batches() {
+        let mut indexer = EventIndexer::default();
+        assert_eq!(
+            indexer.assign(&[event(1, 0), event(2, 0), event(2, 1)]),
+            [0, 0, 1]
+        );
+        // Transaction 2 goes on, with its last event extracted again.
+        assert_eq!(
+            indexer.assign(&[event(2, 1), event(2, 2), event(3, 0)]),
+            [1, 2, 0]
+        );
+        // Only the last transaction of a batch is carried over.
+        assert_eq!(indexer.assign(&[event(2, 3), event(3, 1)]), [0, 1]);
+        assert_eq!(indexer.assign(&[]), Vec::<u32>::new());
+        assert_eq!(indexer.assign(&[event(3, 2)]), [2]);
+    }
+
+    #[test]
+    fn drops_indexes_of_seen_events() {
+        let mut dedupe = EventDedupe::new(16);
+        let mut events = vec![event(1, 0), event(1, 1)];
+        dedupe.filter(&mut events, &mut vec![0, 1]);
+
+        let mut events = vec![event(1, 1), event(1, 2)];
+        let mut indexes = vec![1, 2];
+        dedupe.filter(&mut events, &mut indexes);
+        assert_eq!(events, vec![event(1, 2)]);
+        assert_eq!(indexes, vec![2]);
+    }
+
+    #[test]
+    fn evicts_oldest_keys() {
+        let mut dedupe = EventDedupe::new(2);
+        assert!(dedupe.insert((Felt::ONE, 0)));
+        assert!(dedupe.insert((Felt::TWO, 0)));
+        assert!(!dedupe.insert((Felt::TWO, 0)));
+        assert!(dedupe.insert((Felt::THREE, 0)));
+        assert_eq!(dedupe.len(), 2);
+        assert!(!dedupe.contains(&(Felt::ONE, 0)));
+        assert!(dedupe.contains(&(Felt::THREE, 0)));
+
+        // A zero capacity window keeps every event.
+        let mut disabled = EventDedupe::new(0);
+        let mut events = vec![event(1, 0)];
+        filter(&mut disabled, &mut events);
+        filter(&mut disabled, &mut events);
+        assert_eq!(events.len(), 1);
+        assert!(disabled.is_empty());
+    }
+
+    #[tokio::test]
+    async fn reloads_persisted_window() {
+        let db = EngineDb::new(EngineDbConfig {
+            path: ":memory:".to_string(),
+        })
+        .await
+        .unwrap();
+
+        let mut dedupe = EventDedupe::new(8);
+        let mut events = vec![event(1, 0), event(1, 1)];
+        let kept = filter(&mut dedupe, &mut events);
+        db.record_seen_events(&kept, dedupe.capacity())
+            .await
+            .unwrap();
+
+        let mut reloaded = EventDedupe::load(&db, 8).await.unwrap();
+        assert_eq!(reloaded.len(), 2);
+        let mut replayed = vec![event(1, 0), event(1, 1), event(1, 2)];
+        filter(&mut reloaded, &mut replayed);
+        assert_eq!(replayed.len(), 1);
+        assert_eq!(replayed[0].data, vec![Felt::from(2_u64)]);
+    }
+}
@@ -4,16 +4,131 @@
 //! This will be enhanced with actual Torii features in the future.
 
 use anyhow::{Context, Result};
+use futures::Stream;
 use sqlx::{any::AnyPoolOptions, sqlite::SqliteConnectOptions, Any, ConnectOptions, Pool, Row};
-use starknet::core::types::Felt;
-use std::collections::HashMap;
+use starknet::core::types::{EmittedEvent, Felt};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::str::FromStr;
+use torii_common::encryption::{
+    ensure_sqlcipher, sqlite_encryption_key, sqlite_key_sql, SQLITE_CIPHER_VERSION_SQL,
+    SQLITE_KEY_CHECK_SQL,
+};
 
 use crate::etl::decoder::DecoderId;
-
-/// Embedded SQL schemas
-const SQLITE_SCHEMA_SQL: &str = include_str!("../../sql/engine_schema.sql");
-const POSTGRES_SCHEMA_SQL: &str = include_str!("../../sql/engine_schema_postgres.sql");
+use crate::etl::dedupe::{event_keys, EventKey};
+use crate::etl::event_archive::{decode_chunk_into, encode_chunk, ArchivedBatch};
+use crate::etl::extractor::ExtractionBatch;
+use crate::etl::migrations::{self, Migration, SqlDialect, SqlxMigrationExecutor};
+use crate::etl::sharding::ShardLease;
+
+/// Migration component name recorded in `schema_version`
+const MIGRATION_COMPONENT: &str = "engine";
+
+/// Embedded schema migrations
+const SQLITE_MIGRATIONS: &[Migration] = &[
+    Migration::new(
+        1,
+        "initial",
+        include_str!("../../sql/migrations/sqlite/0001_initial.sql"),
+    ),
+    Migration::new(
+        2,
+        "contract_stats",
+        include_str!("../../sql/migrations/sqlite/0002_contract_stats.sql"),
+    ),
+    Migration::new(
+        3,
+        "table_definitions",
+        include_str!("../../sql/migrations/sqlite/0003_table_definitions.sql"),
+    ),
+    Migration::new(
+        4,
+        "contract_identification",
+        include_str!("../../sql/migrations/sqlite/0004_contract_identification.sql"),
+    ),
+    Migration::new(
+        5,
+        "seen_events",
+        include_str!("../../sql/migrations/sqlite/0005_seen_events.sql"),
+    ),
+    Migration::new(
+        6,
+        "event_archive",
+        include_str!("../../sql/migrations/sqlite/0006_event_archive.sql"),
+    ),
+    Migration::new(
+        7,
+        "contract_deployments",
+        include_str!("../../sql/migrations/sqlite/0007_contract_deployments.sql"),
+    ),
+    Migration::new(
+        8,
+        "archived_batches",
+        include_str!("../../sql/migrations/sqlite/0008_archived_batches.sql"),
+    ),
+    Migration::new(
+        9,
+        "shard_leases",
+        include_str!("../../sql/migrations/sqlite/0009_shard_leases.sql"),
+    ),
+    Migration::new(
+        10,
+        "seen_event_hashes",
+        include_str!("../../sql/migrations/sqlite/0010_seen_event_hashes.sql"),
+    ),
+];
+const POSTGRES_MIGRATIONS: &[Migration] = &[
+    Migration::new(
+        1,
+        "initial",
+        include_str!("../../sql/migrations/postgres/0001_initial.sql"),
+    ),
+    Migration::new(
+        2,
+        "contract_stats",
+        include_str!("../../sql/migrations/postgres/0002_contract_stats.sql"),
+    ),
+    Migration::new(
+        3,
+        "table_definitions",
+        include_str!("../../sql/migrations/postgres/0003_table_definitions.sql"),
+    ),
+    Migration::new(
+        4,
+        "contract_identification",
+        include_str!("../../sql/migrations/postgres/0004_contract_identification.sql"),
+    ),
+    Migration::new(
+        5,
+        "seen_events",
+        include_str!("../../sql/migrations/postgres/0005_seen_events.sql"),
+    ),
+    Migration::new(
+        6,
+        "event_archive",
+        include_str!("../../sql/migrations/postgres/0006_event_archive.sql"),
+    ),
+    Migration::new(
+        7,
+        "contract_deployments",
+        include_str!("../../sql/migrations/postgres/0007_contract_deployments.sql"),
+    ),
+    Migration::new(
+        8,
+        "archived_batches",
+        include_str!("../../sql/migrations/postgres/0008_archived_batches.sql"),
+    ),
+    Migration::new(
+        9,
+        "shard_leases",
+        include_str!("../../sql/migrations/postgres/0009_shard_leases.sql"),
+    ),
+    Migration::new(
+        10,
+        "seen_event_hashes",
+        include_str!("../../sql/migrations/postgres/0010_seen_event_hashes.sql"),
+    ),
+];
 
 /// Engine database configuration
 #[derive(Debug, Clone)]
@@ -81,8 +196,44 @@ impl EngineDb {
             5
         };
 
-        let pool = AnyPoolOptions::new()
-            .max_connections(max_connections)
+        let mut pool_options = AnyPoolOptions::new().max_connections(max_connections);
+        // SQLCipher: every pooled connection is keyed before its first read.
+        let encryption_key = match backend {
+            DbBackend::Sqlite if !is_sqlite_memory_path(&config.path) => sqlite_encryption_key()?,
+            _ => None,
+        };
+        if let Some(key) = encryption_key {
+            let key_sql = sqlite_key_sql(&key);
+            pool_options = pool_options.after_connect(move |conn, _meta| {
+                let key_sql = key_sql.clone();
+                Box::pin(async move {
+                    sqlx::query(&key_sql).execute(&mut *conn).await?;
+                    let cipher_version: Option<String> =
+                        sqlx::query_scalar(SQLITE_CIPHER_VERSION_SQL)
+                            .fetch_optional(&mut *conn)
+                            .await?;
+                    ensure_sqlcipher(cipher_version.as_deref())
+                        .map_err(|e| sqlx::Error::Configuration(e.into()))?;
+                    // A wrong key only shows on the first read of the file.
+                    sqlx::query(SQLITE_KEY_CHECK_SQL)
+                        .execute(&mut *conn)
+                        .await
+                        .map_err(|e| {
+                            sqlx::Error::Configuration(
+                                format!(
+                                    "Cannot read the engine database with the configured \
+                                     encryption key (wrong key?): {e}"
+                                )
+                                .into(),
+                            )
+                        })?;
+                    Ok(())
+                })
+            });
+            tracing::info!(target: "torii::etl::engine_db", "Engine database encrypted with SQLCipher");
+        }
+
+        let pool = pool_options
             .connect(&database_url)
             .await
             .context("Failed to connect to engine database")?;
@@ -95,6 +246,11 @@ impl EngineDb {
         Ok(db)
     }
 
+    /// Closes the connections: later queries fail.
+    pub async fn close(&self) {
+        self.pool.close().await;
+    }
+
     fn sql<'a>(&self, sqlite: &'a str, postgres: &'a str) -> &'a str {
         match self.backend {
             DbBackend::Sqlite => sqlite,
@@ -102,6 +258,13 @@ impl EngineDb {
         }
     }
 
+    fn dialect(&self) -> SqlDialect {
+        match self.backend {
+            DbBackend::Sqlite => SqlDialect::Sqlite,
+            DbBackend::Postgres => SqlDialect::Postgres,
+        }
+    }
+
     fn table<'a>(&self, sqlite: &'a str, postgres: &'a str) -> &'a str {
         self.sql(sqlite, postgres)
     }
@@ -111,8 +274,8 @@ impl EngineDb {
         // Apply SQLite-only tuning.
         self.apply_pragmas().await?;
 
-        // Load tables from SQL file
-        self.load_schema_from_sql().await?;
+        // Create or upgrade tables
+        self.run_migrations().await?;
 
         tracing::info!(target: "torii::etl::engine_db", "Engine database schema initialized");
 
@@ -142,55 +305,26 @@ impl EngineDb {
         Ok(())
     }
 
-    /// Load schema from SQL file
-    async fn load_schema_from_sql(&self) -> Result<()> {
-        let schema_sql = match self.backend {
-            DbBackend::Sqlite => SQLITE_SCHEMA_SQL,
-            DbBackend::Postgres => POSTGRES_SCHEMA_SQL,
+    /// Apply pending schema migrations
+    async fn run_migrations(&self) -> Result<()> {
+        let migrations = match self.backend {
+            DbBackend::Sqlite => SQLITE_MIGRATIONS,
+            DbBackend::Postgres => POSTGRES_MIGRATIONS,
         };
 
-        for statement in schema_sql.split(';') {
-            let statement = statement.trim();
-
-            // Skip empty statements
-            if statement.is_empty() {
-                continue;
-            }
-
-            // Remove comment lines
-            let sql_lines: Vec<&str> = statement
-                .lines()
-                .filter(|line| {
-                    let trimmed = line.trim();
-                    !trimmed.is_empty() && !trimmed.starts_with("--")
-                })
-                .collect();
+        let mut executor = SqlxMigrationExecutor::new(&self.pool, self.dialect());
+        let version = migrations::migrate(&mut executor, MIGRATION_COMPONENT, migrations).await?;
 
-            if sql_lines.is_empty() {
-                continue;
-            }
-
-            let clean_sql = sql_lines.join("\n");
-
-            tracing::debug!(
-                target: "torii::etl::engine_db",
-                "Executing SQL: {}",
-                clean_sql.lines().next().unwrap_or("")
-            );
-
-            sqlx::query(&clean_sql)
-                .execute(&self.pool)
-                .await
-                .context(format!(
-                    "Failed to execute SQL: {}",
-                    clean_sql.lines().next().unwrap_or("")
-                ))?;
-        }
-
-        tracing::debug!(target: "torii::etl::engine_db", "Schema loaded successfully");
+        tracing::debug!(target: "torii::etl::engine_db", version, "Schema up to date");
         Ok(())
     }
 
+    /// Current schema version of the engine database
+    pub async fn schema_version(&self) -> Result<u32> {
+        let mut executor = SqlxMigrationExecutor::new(&self.pool, self.dialect());
+        migrations::current_version(&mut executor, MIGRATION_COMPONENT).await
+    }
+
     /// Get the current head (block number and event count)
     pub async fn get_head(&self) -> Result<(u64, u64)> {
         let table = self.table("head", "engine.head");
@@ -379,6 +513,22 @@ impl EngineDb {
             .collect())
     }
 
+    /// List the state rows of every extractor, as `(extractor_type, state_key, state_value)`.
+    pub async fn list_extractor_states(&self) -> Result<Vec<(String, String, String)>> {
+        let table = self.table("extractor_state", "engine.extractor_state");
+        let rows = sqlx::query(&format!(
+            "SELECT extractor_type, state_key, state_value FROM {table} \
+             ORDER BY extractor_type, state_key"
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.get(0), row.get(1), row.get(2)))
+            .collect())
+    }
+
     /// Delete extractor state
     ///
     /// # Arguments
@@ -539,6 +689,699 @@ impl EngineDb {
         Ok(timestamp.map(|ts| ts as u64))
     }
 
+    // ===== Event Dedupe Window =====
+
+    /// Get the most recently processed event keys, oldest first.
+    ///
+    /// # Arguments
+    /// * `limit` - Maximum number of keys to return
+    pub async fn get_seen_events(&self, limit: usize) -> Result<Vec<EventKey>> {
+        let table = self.table("seen_events", "engine.seen_events");
+        let sql = match self.backend {
+            DbBackend::Sqlite => {
+                format!("SELECT tx_hash, event_hash FROM {table} ORDER BY id DESC LIMIT ?")
+            }
+            DbBackend::Postgres => {
+                format!("SELECT tx_hash, event_hash FROM {table} ORDER BY id DESC LIMIT $1")
+            }
+        };
+
+        let rows = sqlx::query(&sql)
+            .bind(limit as i64)
+            .fetch_all(&self.pool)
+            .await?;
+
+        let mut keys = Vec::with_capacity(rows.len());
+        for row in rows.iter().rev() {
+            let tx_hash: String = row.get(0);
+            let event_hash: i64 = row.get(1);
+            let tx_hash = Felt::from_hex(&tx_hash)
+                .with_context(|| format!("Invalid transaction hash {tx_hash}"))?;
+            keys.push((tx_hash, event_hash as u64));
+        }
+
+        Ok(keys)
+    }
+
+    /// Record processed event keys, keeping only the `keep` most recent ones.
+    pub async fn record_seen_events(&self, keys: &[EventKey], keep: usize) -> Result<()> {
+        if keys.is_empty() {
+            return Ok(());
+        }
+
+        let table = self.table("seen_events", "engine.seen_events");
+        let (insert, prune) = match self.backend {
+            DbBackend::Sqlite => (
+                format!("INSERT OR IGNORE INTO {table} (tx_hash, event_hash) VALUES (?, ?)"),
+                format!(
+                    "DELETE FROM {table} WHERE id < (SELECT MIN(id) FROM \
+                     (SELECT id FROM {table} ORDER BY id DESC LIMIT ?) recent)"
+                ),
+            ),
+            DbBackend::Postgres => (
+                format!(
+                    "INSERT INTO {table} (tx_hash, event_hash) VALUES ($1, $2) \
+                     ON CONFLICT (tx_hash, event_hash) DO NOTHING"
+                ),
+                format!(
+                    "DELETE FROM {table} WHERE id < (SELECT MIN(id) FROM \
+                     (SELECT id FROM {table} ORDER BY id DESC LIMIT $1) recent)"
+                ),
+            ),
+        };
+
+        let mut tx = self.pool.begin().await?;
+        for (tx_hash, event_hash) in keys {
+            sqlx::query(&insert)
+                .bind(format!("{tx_hash:#x}"))
+                // Stored bit for bit: hashes above `i64::MAX` are negative in the table.
+                .bind(*event_hash as i64)
+                .execute(&mut *tx)
+                .await?;
+        }
+        sqlx::query(&prune)
+            .bind(keep as i64)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    // ===== Raw Event Archive =====
+
+    /// Archive the raw events of a processed batch with their block and transaction
+    /// context (receipts included) as one zstd-compressed chunk keyed by block range,
+    /// so they can be replayed through decoders and sinks later.
+    ///
+    /// A batch already archived (same block range) is ignored; events archived twice
+    /// across overlapping chunks are deduplicated when read back.
+    pub async fn archive_batch(&self, batch: &ExtractionBatch) -> Result<()> {
+        let Some(chunk) = encode_chunk(batch)? else {
+            return Ok(());
+        };
+
+        let sql = match self.backend {
+            DbBackend::Sqlite => {
+                "INSERT OR IGNORE INTO archived_batches \
+                 (from_block, to_block, event_count, raw_size, data, archived_at) \
+                 VALUES (?, ?, ?, ?, ?, ?)"
+            }
+            DbBackend::Postgres => {
+                "INSERT INTO engine.archived_batches \
+                 (from_block, to_block, event_count, raw_size, data, archived_at) \
+                 VALUES ($1, $2, $3, $4, $5, $6) \
+                 ON CONFLICT (from_block, to_block) DO NOTHING"
+            }
+        };
+        sqlx::query(sql)
+            .bind(chunk.from_block as i64)
+            .bind(chunk.to_block as i64)
+            .bind(chunk.event_count as i64)
+            .bind(chunk.raw_size as i64)
+            .bind(chunk.data.as_slice())
+            .bind(chrono::Utc::now().timestamp())
+            .execute(&self.pool)
+            .await?;
+
+        ::metrics::counter!("torii_archive_events_total").increment(chunk.event_count as u64);
+        ::metrics::counter!("torii_archive_raw_bytes_total").increment(chunk.raw_size as u64);
+        ::metrics::counter!("torii_archive_compressed_bytes_total")
+            .increment(chunk.data.len() as u64);
+        Ok(())
+    }
+
+    /// Get the lowest and highest block numbers of archived events.
+    pub async fn get_archived_block_range(&self) -> Result<Option<(u64, u64)>> {
+        let chunks = self.table("archived_batches", "engine.archived_batches");
+        let legacy = self.table("archived_events", "engine.archived_events");
+        let row = sqlx::query(&format!(
+            "SELECT MIN(low), MAX(high) FROM ( \
+             SELECT MIN(from_block) AS low, MAX(to_block) AS high FROM {chunks} \
+             UNION ALL \
+             SELECT MIN(block_number) AS low, MAX(block_number) AS high FROM {legacy} \
+             ) AS ranges"
+        ))
+        .fetch_one(&self.pool)
+        .await?;
+
+        let min: Option<i64> = row.get(0);
+        let max: Option<i64> = row.get(1);
+        Ok(min.zip(max).map(|(min, max)| (min as u64, max as u64)))
+    }
+
+    /// Rebuild an extraction batch from the archived events of blocks
+    /// `from_block..=to_block`, in archival order.
+    pub async fn get_archived_batch(
+        &self,
+        from_block: u64,
+        to_block: u64,
+    ) -> Result<ExtractionBatch> {
+        let mut batch = self.get_legacy_archived_batch(from_block, to_block).await?;
+        let mut seen: HashSet<EventKey> = event_keys(&batch.events).into_iter().collect();
+        for (id, _, _) in self.archived_chunks(from_block, to_block).await? {
+            let data = self.archived_chunk_data(id).await?;
+            decode_chunk_into(&data, from_block, to_block, &mut seen, &mut batch)?;
+        }
+        Ok(batch)
+    }
+
+    /// Iterate the archive chunks overlapping blocks `from_block..=to_block` in block order,
+    /// decompressing one chunk at a time.
+    ///
+    /// Each item holds the events of the chunk within the range, without the events of
+    /// previous items. Archives written before chunked archiving are not included.
+    pub fn archived_batches(
+        &self,
+        from_block: u64,
+        to_block: u64,
+    ) -> impl Stream<Item = Result<ArchivedBatch>> + '_ {
+        let state = (None::<std::vec::IntoIter<(i64, u64, u64)>>, HashSet::new());
+        futures::stream::try_unfold(state, move |(chunks, mut seen)| async move {
+            let mut chunks = match chunks {
+                Some(chunks) => chunks,
+                None => self
+                    .archived_chunks(from_block, to_block)
+                    .await?
+                    .into_iter(),
+            };
+            let Some((id, chunk_from, chunk_to)) = chunks.next() else {
+                return Ok(None);
+            };
+            let data = self.archived_chunk_data(id).await?;
+            let mut batch = ExtractionBatch::empty();
+            decode_chunk_into(&data, from_block, to_block, &mut seen, &mut batch)?;
+            let archived = ArchivedBatch {
+                from_block: chunk_from.max(from_block),
+                to_block: chunk_to.min(to_block),
+                batch,
+            };
+            Ok(Some((archived, (Some(chunks), seen))))
+        })
+    }
+
+    /// `(id, from_block, to_block)` of the archive chunks overlapping a block range.
+    async fn archived_chunks(
+        &self,
+        from_block: u64,
+        to_block: u64,
+    ) -> Result<Vec<(i64, u64, u64)>> {
+        let table = self.table("archived_batches", "engine.archived_batches");
+        let sql = match self.backend {
+            DbBackend::Sqlite => format!(
+                "SELECT id, from_block, to_block FROM {table} \
+                 WHERE to_block >= ? AND from_block <= ? ORDER BY from_block, id"
+            ),
+            DbBackend::Postgres => format!(
+                "SELECT id, from_block, to_block FROM {table} \
+                 WHERE to_block >= $1 AND from_block <= $2 ORDER BY from_block, id"
+            ),
+        };
+        let rows = sqlx::query(&sql)
+            .bind(from_block as i64)
+            .bind(to_block as i64)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let from: i64 = row.get(1);
+                let to: i64 = row.get(2);
+                (row.get(0), from as u64, to as u64)
+            })
+            .collect())
+    }
+
+    async fn archived_chunk_data(&self, id: i64) -> Result<Vec<u8>> {
+        let table = self.table("archived_batches", "engine.archived_batches");
+        let sql = match self.backend {
+            DbBackend::Sqlite => format!("SELECT data FROM {table} WHERE id = ?"),
+            DbBackend::Postgres => format!("SELECT data FROM {table} WHERE id = $1"),
+        };
+        Ok(sqlx::query_scalar(&sql)
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await?)
+    }
+
+    /// Events archived one row per event, before chunked archiving.
+    async fn get_legacy_archived_batch(
+        &self,
+        from_block: u64,
+        to_block: u64,
+    ) -> Result<ExtractionBatch> {
+        let blocks_table = self.table("archived_blocks", "engine.archived_blocks");
+        let transactions_table =
+            self.table("archived_transactions", "engine.archived_transactions");
+        let events_table = self.table("archived_events", "engine.archived_events");
+        let range = self.sql("BETWEEN ? AND ?", "BETWEEN $1 AND $2");
+        let events_sql = format!(
+            "SELECT block_number, tx_hash, from_address, event_keys, event_data \
+             FROM {events_table} WHERE block_number {range} ORDER BY block_number, id"
+        );
+        let blocks_sql = format!(
+            "SELECT block_number, block_hash, parent_hash, timestamp \
+             FROM {blocks_table} WHERE block_number {range}"
+        );
+        let transactions_sql = format!(
+            "SELECT tx_hash, block_number, sender_address, calldata \
+             FROM {transactions_table} WHERE block_number {range}"
+        );
+
+        let event_rows = sqlx::query(&events_sql)
+            .bind(from_block as i64)
+            .bind(to_block as i64)
+            .fetch_all(&self.pool)
+            .await?;
+        if event_rows.is_empty() {
+            return Ok(ExtractionBatch::empty());
+        }
+
+        let block_rows = sqlx::query(&blocks_sql)
+            .bind(from_block as i64)
+            .bind(to_block as i64)
+            .fetch_all(&self.pool)
+            .await?;
+        let transaction_rows = sqlx::query(&transactions_sql)
+            .bind(from_block as i64)
+            .bind(to_block as i64)
+            .fetch_all(&self.pool)
+            .await?;
+
+        let mut batch = ExtractionBatch::with_capacities(
+            event_rows.len(),
+            block_rows.len(),
+            transaction_rows.len(),
+            0,
+            0,
+        );
+        for row in &block_rows {
+            let block_number: i64 = row.get(0);
+            let timestamp: i64 = row.get(3);
+            batch.add_block_context(
+                block_number as u64,
+                parse_felt(&row.get::<String, _>(1))?,
+                parse_felt(&row.get::<String, _>(2))?,
+                timestamp as u64,
+            );
+        }
+        for row in &transaction_rows {
+            let block_number: i64 = row.get(1);
+            let sender_address: Option<String> = row.get(2);
+            batch.add_transaction_context(
+                parse_felt(&row.get::<String, _>(0))?,
+                block_number as u64,
+                sender_address.as_deref().map(parse_felt).transpose()?,
+                felts_from_json(&row.get::<String, _>(3))?,
+            );
+        }
+        for row in &event_rows {
+            let block_number: i64 = row.get(0);
+            let block_number = block_number as u64;
+            batch.events.push(EmittedEvent {
+                from_address: parse_felt(&row.get::<String, _>(2))?,
+                keys: felts_from_json(&row.get::<String, _>(3))?,
+                data: felts_from_json(&row.get::<String, _>(4))?,
+                block_hash: batch.blocks.get(&block_number).map(|block| block.hash),
+                block_number: Some(block_number),
+                transaction_hash: parse_felt(&row.get::<String, _>(1))?,
+            });
+        }
+
+        Ok(batch)
+    }
+
+    // ===== Contract Deployments =====
+
+    /// Get the detected deployment block of a contract.
+    pub async fn get_deployment_block(&self, contract: Felt) -> Result<Option<u64>> {
+        let table = self.table("contract_deployments", "engine.contract_deployments");
+        let sql = match self.backend {
+            DbBackend::Sqlite => {
+                format!("SELECT block_number FROM {table} WHERE contract_address = ?")
+            }
+            DbBackend::Postgres => {
+                format!("SELECT block_number FROM {table} WHERE contract_address = $1")
+            }
+        };
+
+        let block: Option<i64> = sqlx::query_scalar(&sql)
+            .bind(format!("{contract:#x}"))
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(block.map(|block| block as u64))
+    }
+
+    /// Persist the detected deployment block of a contract.
+    pub async fn set_deployment_block(&self, contract: Felt, block_number: u64) -> Result<()> {
+        let table = self.table("contract_deployments", "engine.contract_deployments");
+        let sql = match self.backend {
+            DbBackend::Sqlite => format!(
+                "INSERT INTO {table} (contract_address, block_number, detected_at) VALUES (?, ?, ?) \
+                 ON CONFLICT(contract_address) DO UPDATE SET \
+                 block_number = excluded.block_number, detected_at = excluded.detected_at"
+            ),
+            DbBackend::Postgres => format!(
+                "INSERT INTO {table} (contract_address, block_number, detected_at) VALUES ($1, $2, $3) \
+                 ON CONFLICT(contract_address) DO UPDATE SET \
+                 block_number = EXCLUDED.block_number, detected_at = EXCLUDED.detected_at"
+            ),
+        };
+
+        sqlx::query(&sql)
+            .bind(format!("{contract:#x}"))
+            .bind(block_number as i64)
+            .bind(chrono::Utc::now().timestamp())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    // ===== Shard Leases =====
+
+    /// Claims or renews the lease of `shard` for `owner` until `expires_at` (Unix seconds).
+    ///
+    /// The lease is granted when the shard is free, already leased by `owner`, or its
+    /// lease expired before `now`. Returns whether `owner` holds the lease.
+    pub async fn claim_shard_lease(
+        &self,
+        shard: u32,
+        owner: &str,
+        now: i64,
+        expires_at: i64,
+    ) -> Result<bool> {
+        let sql = match self.backend {
+            DbBackend::Sqlite => {
+                "INSERT INTO shard_leases (shard, owner, expires_at) VALUES (?, ?, ?) \
+                 ON CONFLICT(shard) DO UPDATE SET \
+                 owner = excluded.owner, expires_at = excluded.expires_at \
+                 WHERE shard_leases.owner = excluded.owner OR shard_leases.expires_at < ?"
+            }
+            DbBackend::Postgres => {
+                "INSERT INTO engine.shard_leases AS lease (shard, owner, expires_at) \
+                 VALUES ($1, $2, $3) \
+                 ON CONFLICT(shard) DO UPDATE SET \
+                 owner = EXCLUDED.owner, expires_at = EXCLUDED.expires_at \
+                 WHERE lease.owner = EXCLUDED.owner OR lease.expires_at < $4"
+            }
+        };
+
+        let result = sqlx::query(sql)
+            .bind(i64::from(shard))
+            .bind(owner)
+            .bind(expires_at)
+            .bind(now)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Releases every shard lease held by `owner`.
+    pub async fn release_shard_leases(&self, owner: &str) -> Result<()> {
+        let table = self.table("shard_leases", "engine.shard_leases");
+        let sql = match self.backend {
+            DbBackend::Sqlite => format!("DELETE FROM {table} WHERE owner = ?"),
+            DbBackend::Postgres => format!("DELETE FROM {table} WHERE owner = $1"),
+        };
+
+        sqlx::query(&sql).bind(owner).execute(&self.pool).await?;
+        Ok(())
+    }
+
+    /// List the shard leases (expired ones included), ordered by shard.
+    pub async fn list_shard_leases(&self) -> Result<Vec<ShardLease>> {
+        let table = self.table("shard_leases", "engine.shard_leases");
+        let rows = sqlx::query(&format!(
+            "SELECT shard, owner, expires_at FROM {table} ORDER BY shard"
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| ShardLease {
+                shard: row.get::<i64, _>(0) as u32,
+                owner: row.get(1),
+                expires_at: row.get(2),
+            })
+            .collect())
+    }
+
+    // ===== Contract Statistics =====
+
+    /// Accumulate per-contract indexing activity (called after sink processing).
+    ///
+    /// Counts are added to existing rows and block ranges are widened. Batches that
+    /// are re-processed after a restart are counted again.
+    pub async fn record_contract_activity(&self, activity: &[ContractActivity]) -> Result<()> {
+        if activity.is_empty() {
+            return Ok(());
+        }
+
+        let sql = match self.backend {
+            DbBackend::Sqlite => {
+                "INSERT INTO contract_stats \
+                 (contract_address, decoder, event_count, first_block, last_block, last_activity) \
+                 VALUES (?, ?, ?, ?, ?, ?) \
+                 ON CONFLICT(contract_address, decoder) DO UPDATE SET \
+                 event_count = contract_stats.event_count + excluded.event_count, \
+                 first_block = MIN(contract_stats.first_block, excluded.first_block), \
+                 last_block = MAX(contract_stats.last_block, excluded.last_block), \
+                 last_activity = MAX(contract_stats.last_activity, excluded.last_activity)"
+            }
+            DbBackend::Postgres => {
+                "INSERT INTO engine.contract_stats AS s \
+                 (contract_address, decoder, event_count, first_block, last_block, last_activity) \
+                 VALUES ($1, $2, $3, $4, $5, $6) \
+                 ON CONFLICT(contract_address, decoder) DO UPDATE SET \
+                 event_count = s.event_count + EXCLUDED.event_count, \
+                 first_block = LEAST(s.first_block, EXCLUDED.first_block), \
+                 last_block = GREATEST(s.last_block, EXCLUDED.last_block), \
+                 last_activity = GREATEST(s.last_activity, EXCLUDED.last_activity)"
+            }
+        };
+
+        let mut tx = self.pool.begin().await?;
+        for entry in activity {
+            sqlx::query(sql)
+                .bind(format!("{:#x}", entry.contract))
+                .bind(&entry.decoder)
+                .bind(entry.event_count as i64)
+                .bind(entry.first_block as i64)
+                .bind(entry.last_block as i64)
+                .bind(entry.last_activity)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Get per-contract indexing statistics.
+    ///
+    /// # Arguments
+    /// * `contracts` - Contracts to look up (empty = all indexed contracts)
+    ///
+    /// # Returns
+    /// Statistics ordered by contract address; unknown contracts are omitted
+    pub async fn get_contract_stats(&self, contracts: &[Felt]) -> Result<Vec<ContractStats>> {
+        let table = self.table("contract_stats", "engine.contract_stats");
+        let mut sql = format!(
+            "SELECT contract_address, decoder, event_count, first_block, last_block, last_activity \
+             FROM {table}"
+        );
+        if !contracts.is_empty() {
+            let placeholders = match self.backend {
+                DbBackend::Sqlite => vec!["?"; contracts.len()].join(", "),
+                DbBackend::Postgres => (1..=contracts.len())
+                    .map(|i| format!("${i}"))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            };
+            sql.push_str(&format!(" WHERE contract_address IN ({placeholders})"));
+        }
+
+        let mut query = sqlx::query(&sql);
+        for contract in contracts {
+            query = query.bind(format!("{contract:#x}"));
+        }
+        let rows = query.fetch_all(&self.pool).await?;
+
+        let mut stats: BTreeMap<Felt, ContractStats> = BTreeMap::new();
+        for row in rows {
+            let addr_hex: String = row.get(0);
+            let contract = Felt::from_hex(&addr_hex)
+                .context(format!("Invalid contract address: {addr_hex}"))?;
+            let decoder: String = row.get(1);
+            let event_count = row.get::<i64, _>(2) as u64;
+            let first_block = row.get::<i64, _>(3) as u64;
+            let last_block = row.get::<i64, _>(4) as u64;
+            let last_activity: i64 = row.get(5);
+
+            let entry = stats.entry(contract).or_insert_with(|| ContractStats {
+                contract,
+                first_block,
+                last_block,
+                events_by_decoder: BTreeMap::new(),
+                last_activity,
+            });
+            entry.first_block = entry.first_block.min(first_block);
+            entry.last_block = entry.last_block.max(last_block);
+            entry.last_activity = entry.last_activity.max(last_activity);
+            *entry.events_by_decoder.entry(decoder).or_default() += event_count;
+        }
+
+        Ok(stats.into_values().collect())
+    }
+
+    // ===== Table Definitions =====
+
+    /// Store a table definition as a new version.
+    ///
+    /// Definitions are kept per decoder, owner and table. Storing the same definition
+    /// as the latest version (e.g. when a batch is re-processed) does not add a version.
+    ///
+    /// # Returns
+    /// The version of the stored definition (1 on registration)
+    pub async fn save_table_definition(
+        &self,
+        decoder: &str,
+        owner: Felt,
+        table_id: Felt,
+        definition: &str,
+        block_number: u64,
+        tx_hash: Felt,
+    ) -> Result<u32> {
+        let table = self.table("table_definitions", "engine.table_definitions");
+        let latest_sql = match self.backend {
+            DbBackend::Sqlite => format!(
+                "SELECT version, definition FROM {table} \
+                 WHERE decoder = ? AND owner = ? AND table_id = ? \
+                 ORDER BY version DESC LIMIT 1"
+            ),
+            DbBackend::Postgres => format!(
+                "SELECT version, definition FROM {table} \
+                 WHERE decoder = $1 AND owner = $2 AND table_id = $3 \
+                 ORDER BY version DESC LIMIT 1"
+            ),
+        };
+        let insert_sql = match self.backend {
+            DbBackend::Sqlite => format!(
+                "INSERT INTO {table} \
+                 (decoder, owner, table_id, version, definition, block_number, tx_hash) \
+                 VALUES (?, ?, ?, ?, ?, ?, ?)"
+            ),
+            DbBackend::Postgres => format!(
+                "INSERT INTO {table} \
+                 (decoder, owner, table_id, version, definition, block_number, tx_hash) \
+                 VALUES ($1, $2, $3, $4, $5, $6, $7)"
+            ),
+        };
+        let owner_hex = format!("{owner:#x}");
+        let table_id_hex = format!("{table_id:#x}");
+
+        let mut tx = self.pool.begin().await?;
+        let latest = sqlx::query(&latest_sql)
+            .bind(decoder)
+            .bind(&owner_hex)
+            .bind(&table_id_hex)
+            .fetch_optional(&mut *tx)
+            .await?
+            .map(|row| (row.get::<i64, _>(0) as u32, row.get::<String, _>(1)));
+
+        let version = match latest {
+            Some((version, latest_definition)) if latest_definition == definition => {
+                return Ok(version);
+            }
+            Some((version, _)) => version + 1,
+            None => 1,
+        };
+        sqlx::query(&insert_sql)
+            .bind(decoder)
+            .bind(&owner_hex)
+            .bind(&table_id_hex)
+            .bind(i64::from(version))
+            .bind(definition)
+            .bind(block_number as i64)
+            .bind(format!("{tx_hash:#x}"))
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(version)
+    }
+
+    /// Get the latest version of the table definitions stored by a decoder.
+    ///
+    /// # Arguments
+    /// * `decoder` - Decoder owning the definitions
+    /// * `owners` - Contracts declaring the tables (empty = all)
+    pub async fn get_table_definitions(
+        &self,
+        decoder: &str,
+        owners: &[Felt],
+    ) -> Result<Vec<TableDefinition>> {
+        let table = self.table("table_definitions", "engine.table_definitions");
+        let placeholder = |i: usize| match self.backend {
+            DbBackend::Sqlite => "?".to_string(),
+            DbBackend::Postgres => format!("${i}"),
+        };
+        let mut sql = format!(
+            "SELECT owner, table_id, version, definition, block_number, tx_hash FROM {table} d \
+             WHERE decoder = {} AND version = (\
+                 SELECT MAX(version) FROM {table} \
+                 WHERE decoder = d.decoder AND owner = d.owner AND table_id = d.table_id)",
+            placeholder(1)
+        );
+        if !owners.is_empty() {
+            let placeholders = (2..owners.len() + 2)
+                .map(placeholder)
+                .collect::<Vec<_>>()
+                .join(", ");
+            sql.push_str(&format!(" AND owner IN ({placeholders})"));
+        }
+        sql.push_str(" ORDER BY owner, table_id");
+
+        let mut query = sqlx::query(&sql).bind(decoder);
+        for owner in owners {
+            query = query.bind(format!("{owner:#x}"));
+        }
+        let rows = query.fetch_all(&self.pool).await?;
+        rows.iter().map(table_definition_from_row).collect()
+    }
+
+    /// Get every stored version of a table definition, oldest first.
+    pub async fn get_table_definition_history(
+        &self,
+        decoder: &str,
+        owner: Felt,
+        table_id: Felt,
+    ) -> Result<Vec<TableDefinition>> {
+        let table = self.table("table_definitions", "engine.table_definitions");
+        let sql = match self.backend {
+            DbBackend::Sqlite => format!(
+                "SELECT owner, table_id, version, definition, block_number, tx_hash FROM {table} \
+                 WHERE decoder = ? AND owner = ? AND table_id = ? ORDER BY version"
+            ),
+            DbBackend::Postgres => format!(
+                "SELECT owner, table_id, version, definition, block_number, tx_hash FROM {table} \
+                 WHERE decoder = $1 AND owner = $2 AND table_id = $3 ORDER BY version"
+            ),
+        };
+
+        let rows = sqlx::query(&sql)
+            .bind(decoder)
+            .bind(format!("{owner:#x}"))
+            .bind(format!("{table_id:#x}"))
+            .fetch_all(&self.pool)
+            .await?;
+        rows.iter().map(table_definition_from_row).collect()
+    }
+
     // ===== Contract Decoder Persistence =====
 
     /// Get all contract decoder mappings from database.
@@ -582,6 +1425,8 @@ impl EngineDb {
 
     /// Set decoder IDs for a contract.
     ///
+    /// The mapping is recorded with an [`IdentificationSource::Explicit`] source.
+    ///
     /// # Arguments
     /// * `contract` - Contract address
     /// * `decoder_ids` - List of decoder IDs (can be empty)
@@ -601,16 +1446,18 @@ impl EngineDb {
 
         let sql = match self.backend {
             DbBackend::Sqlite => format!(
-                "INSERT INTO {table} (contract_address, decoder_ids, identified_at) \
-                 VALUES (?, ?, strftime('%s', 'now')) \
+                "INSERT INTO {table} (contract_address, decoder_ids, identified_at, source, class_hash) \
+                 VALUES (?, ?, strftime('%s', 'now'), 'explicit', NULL) \
                  ON CONFLICT(contract_address) \
-                 DO UPDATE SET decoder_ids = excluded.decoder_ids, identified_at = strftime('%s', 'now')"
+                 DO UPDATE SET decoder_ids = excluded.decoder_ids, identified_at = strftime('%s', 'now'), \
+                 source = excluded.source, class_hash = excluded.class_hash"
             ),
             DbBackend::Postgres => format!(
-                "INSERT INTO {table} (contract_address, decoder_ids, identified_at) \
-                 VALUES ($1, $2, EXTRACT(EPOCH FROM NOW())::BIGINT) \
+                "INSERT INTO {table} (contract_address, decoder_ids, identified_at, source, class_hash) \
+                 VALUES ($1, $2, EXTRACT(EPOCH FROM NOW())::BIGINT, 'explicit', NULL) \
                  ON CONFLICT(contract_address) \
-                 DO UPDATE SET decoder_ids = EXCLUDED.decoder_ids, identified_at = EXTRACT(EPOCH FROM NOW())::BIGINT"
+                 DO UPDATE SET decoder_ids = EXCLUDED.decoder_ids, identified_at = EXTRACT(EPOCH FROM NOW())::BIGINT, \
+                 source = EXCLUDED.source, class_hash = EXCLUDED.class_hash"
             ),
         };
 
@@ -717,6 +1564,115 @@ impl EngineDb {
             None => Ok(None),
         }
     }
+
+    /// Get all contract identifications, including their provenance.
+    pub async fn get_all_contract_identifications(&self) -> Result<Vec<ContractIdentification>> {
+        let table = self.table("contract_decoders", "engine.contract_decoders");
+        let rows = sqlx::query(&format!(
+            "SELECT contract_address, decoder_ids, identified_at, source, class_hash FROM {table}"
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut results = Vec::with_capacity(rows.len());
+        for row in rows {
+            let addr_hex: String = row.get(0);
+            let decoder_ids_str: String = row.get(1);
+            let source: String = row.get(3);
+            let class_hash: Option<String> = row.get(4);
+
+            results.push(ContractIdentification {
+                contract: Felt::from_hex(&addr_hex)
+                    .context(format!("Invalid contract address: {addr_hex}"))?,
+                decoder_ids: decoder_ids_str
+                    .split(',')
+                    .filter_map(|s| s.trim().parse::<u64>().ok())
+                    .map(DecoderId::from_u64)
+                    .collect(),
+                source: IdentificationSource::decode(&source),
+                class_hash: class_hash
+                    .map(|hex| Felt::from_hex(&hex).context(format!("Invalid class hash: {hex}")))
+                    .transpose()?,
+                identified_at: row.get(2),
+            });
+        }
+
+        Ok(results)
+    }
+
+    /// Persist contract identifications with their provenance in a single transaction.
+    pub async fn set_contract_identifications_batch(
+        &self,
+        identifications: &[ContractIdentification],
+    ) -> Result<()> {
+        if identifications.is_empty() {
+            return Ok(());
+        }
+
+        let table = self.table("contract_decoders", "engine.contract_decoders");
+        let sql = match self.backend {
+            DbBackend::Sqlite => format!(
+                "INSERT INTO {table} (contract_address, decoder_ids, identified_at, source, class_hash) \
+                 VALUES (?, ?, ?, ?, ?) \
+                 ON CONFLICT(contract_address) \
+                 DO UPDATE SET decoder_ids = excluded.decoder_ids, identified_at = excluded.identified_at, \
+                 source = excluded.source, class_hash = excluded.class_hash"
+            ),
+            DbBackend::Postgres => format!(
+                "INSERT INTO {table} (contract_address, decoder_ids, identified_at, source, class_hash) \
+                 VALUES ($1, $2, $3, $4, $5) \
+                 ON CONFLICT(contract_address) \
+                 DO UPDATE SET decoder_ids = EXCLUDED.decoder_ids, identified_at = EXCLUDED.identified_at, \
+                 source = EXCLUDED.source, class_hash = EXCLUDED.class_hash"
+            ),
+        };
+
+        let mut tx = self.pool.begin().await?;
+        for identification in identifications {
+            let decoder_ids_str: String = identification
+                .decoder_ids
+                .iter()
+                .map(|id| id.as_u64().to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+
+            sqlx::query(&sql)
+                .bind(format!("{:#x}", identification.contract))
+                .bind(decoder_ids_str)
+                .bind(identification.identified_at)
+                .bind(identification.source.encode())
+                .bind(identification.class_hash.map(|hash| format!("{hash:#x}")))
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+}
+
+fn table_definition_from_row(row: &sqlx::any::AnyRow) -> Result<TableDefinition> {
+    let parse_felt = |index: usize| {
+        let hex: String = row.get(index);
+        Felt::from_hex(&hex).context(format!("Invalid felt in table definition: {hex}"))
+    };
+    Ok(TableDefinition {
+        owner: parse_felt(0)?,
+        table_id: parse_felt(1)?,
+        version: row.get::<i64, _>(2) as u32,
+        definition: row.get(3),
+        block_number: row.get::<i64, _>(4) as u64,
+        tx_hash: parse_felt(5)?,
+    })
+}
+
+fn parse_felt(value: &str) -> Result<Felt> {
+    Felt::from_hex(value).with_context(|| format!("Invalid felt {value}"))
+}
+
+fn felts_from_json(value: &str) -> Result<Vec<Felt>> {
+    let felts: Vec<String> = serde_json::from_str(value).context("Invalid felt array")?;
+    felts.iter().map(|felt| parse_felt(felt)).collect()
 }
 
 fn is_sqlite_memory_path(path: &str) -> bool {
@@ -754,6 +1710,112 @@ pub struct EngineStats {
     pub start_time: String,
 }
 
+/// Indexing activity of one contract for one decoder within a batch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractActivity {
+    pub contract: Felt,
+    pub decoder: String,
+    pub event_count: u64,
+    pub first_block: u64,
+    pub last_block: u64,
+    /// Block timestamp of the last event (unix seconds)
+    pub last_activity: i64,
+}
+
+/// Stored version of a decoder table definition
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableDefinition {
+    /// Contract declaring the table
+    pub owner: Felt,
+    pub table_id: Felt,
+    /// 1 on registration, incremented on each upgrade
+    pub version: u32,
+    /// Serialized definition (JSON)
+    pub definition: String,
+    /// Block of the registration or upgrade
+    pub block_number: u64,
+    pub tx_hash: Felt,
+}
+
+/// How a contract's decoders were determined
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IdentificationSource {
+    /// Mapped explicitly by configuration or a decoder
+    Explicit,
+    /// Matched by the named identification rules against the contract's own ABI
+    Rules(Vec<String>),
+    /// Matched against the ABI of the implementation behind a proxy
+    ProxyResolved { implementation: Felt },
+    /// Recorded before provenance was tracked
+    Unknown,
+}
+
+impl IdentificationSource {
+    /// Encodes the source as stored in the `source` column.
+    pub fn encode(&self) -> String {
+        match self {
+            Self::Explicit => "explicit".to_string(),
+            Self::Rules(rules) => format!("rule:{}", rules.join(",")),
+            Self::ProxyResolved { implementation } => format!("proxy:{implementation:#x}"),
+            Self::Unknown => "unknown".to_string(),
+        }
+    }
+
+    /// Decodes a stored `source` column, falling back to [`Self::Unknown`].
+    pub fn decode(value: &str) -> Self {
+        if value == "explicit" {
+            Self::Explicit
+        } else if let Some(rules) = value.strip_prefix("rule:") {
+            Self::Rules(
+                rules
+                    .split(',')
+                    .filter(|rule| !rule.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+            )
+        } else if let Some(implementation) = value
+            .strip_prefix("proxy:")
+            .and_then(|hex| Felt::from_hex(hex).ok())
+        {
+            Self::ProxyResolved { implementation }
+        } else {
+            Self::Unknown
+        }
+    }
+}
+
+/// Persisted identification of a contract
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractIdentification {
+    pub contract: Felt,
+    /// Matched decoders (empty when nothing matched)
+    pub decoder_ids: Vec<DecoderId>,
+    pub source: IdentificationSource,
+    /// Class hash the identification was made against, if known
+    pub class_hash: Option<Felt>,
+    /// Unix seconds of the (re-)identification
+    pub identified_at: i64,
+}
+
+/// Per-contract indexing statistics
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractStats {
+    pub contract: Felt,
+    pub first_block: u64,
+    pub last_block: u64,
+    /// Envelope counts keyed by decoder name
+    pub events_by_decoder: BTreeMap<String, u64>,
+    /// Block timestamp of the last indexed event (unix seconds)
+    pub last_activity: i64,
+}
+
+impl ContractStats {
+    /// Total envelope count across decoders
+    pub fn total_events(&self) -> u64 {
+        self.events_by_decoder.values().sum()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -815,6 +1877,63 @@ mod tests {
         assert_eq!(missing, None);
     }
 
+    #[tokio::test]
+    async fn test_deployment_blocks() {
+        let db = EngineDb::new(EngineDbConfig {
+            path: ":memory:".to_string(),
+        })
+        .await
+        .unwrap();
+        let contract = Felt::from(0xabc_u64);
+
+        assert_eq!(db.get_deployment_block(contract).await.unwrap(), None);
+        db.set_deployment_block(contract, 1234).await.unwrap();
+        db.set_deployment_block(contract, 1200).await.unwrap();
+        assert_eq!(db.get_deployment_block(contract).await.unwrap(), Some(1200));
+    }
+
+    #[tokio::test]
+    async fn test_archived_batches_iterate_chunks() {
+        use futures::TryStreamExt;
+
+        let db = EngineDb::new(EngineDbConfig {
+            path: ":memory:".to_string(),
+        })
+        .await
+        .unwrap();
+        let batch = |blocks: &[u64]| {
+            let mut batch = ExtractionBatch::empty();
+            for &block_number in blocks {
+                batch.add_block_context(block_number, Felt::from(block_number), Felt::ZERO, 0);
+                batch.events.push(EmittedEvent {
+                    from_address: Felt::ONE,
+                    keys: vec![],
+                    data: vec![Felt::from(block_number)],
+                    block_hash: Some(Felt::from(block_number)),
+                    block_number: Some(block_number),
+                    transaction_hash: Felt::from(block_number),
+                });
+            }
+            batch
+        };
+
+        db.archive_batch(&batch(&[1, 2])).await.unwrap();
+        db.archive_batch(&batch(&[2, 3])).await.unwrap();
+        db.archive_batch(&batch(&[5])).await.unwrap();
+        assert_eq!(db.get_archived_block_range().await.unwrap(), Some((1, 5)));
+
+        let chunks: Vec<_> = db.archived_batches(2, 4).try_collect().await.unwrap();
+        assert_eq!(chunks.len(), 2);
+        assert_eq!((chunks[0].from_block, chunks[0].to_block), (2, 2));
+        assert_eq!(chunks[0].batch.len(), 1);
+        // Block 2 was already yielded by the first chunk.
+        assert_eq!((chunks[1].from_block, chunks[1].to_block), (2, 3));
+        assert_eq!(chunks[1].batch.len(), 1);
+        assert_eq!(chunks[1].batch.events[0].block_number, Some(3));
+
+        assert_eq!(db.get_archived_batch(1, 5).await.unwrap().len(), 4);
+    }
+
     #[tokio::test]
     async fn test_get_all_extractor_states() {
         let config = EngineDbConfig {
@@ -841,6 +1960,17 @@ mod tests {
                 ("0x2".to_string(), "block:20".to_string())
             ]
         );
+
+        let all = db.list_extractor_states().await.unwrap();
+        assert_eq!(all.len(), 3);
+        assert_eq!(
+            all[2],
+            (
+                "other".to_string(),
+                "cursor".to_string(),
+                "block:30".to_string()
+            )
+        );
     }
 
     #[tokio::test]
@@ -858,4 +1988,191 @@ mod tests {
         assert_eq!(events, 0);
         assert!(db_path.exists());
     }
+
+    #[tokio::test]
+    async fn test_engine_db_reopen_keeps_schema_version() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("engine.db");
+        let config = EngineDbConfig {
+            path: db_path.to_string_lossy().to_string(),
+        };
+
+        let latest = SQLITE_MIGRATIONS.len() as u32;
+
+        let db = EngineDb::new(config.clone()).await.unwrap();
+        db.update_head(42, 7).await.unwrap();
+        assert_eq!(db.schema_version().await.unwrap(), latest);
+        drop(db);
+
+        let db = EngineDb::new(config).await.unwrap();
+        assert_eq!(db.schema_version().await.unwrap(), latest);
+        assert_eq!(db.get_head().await.unwrap(), (42, 7));
+    }
+
+    #[tokio::test]
+    async fn test_seen_events_window() {
+        let db = EngineDb::new(EngineDbConfig {
+            path: ":memory:".to_string(),
+        })
+        .await
+        .unwrap();
+
+        // Includes hashes above `i64::MAX`.
+        let keys: Vec<EventKey> = (0..5_u64)
+            .map(|i| (Felt::from(0x100 + i), u64::MAX - i))
+            .collect();
+        db.record_seen_events(&keys[..3], 4).await.unwrap();
+        // Already recorded keys are ignored.
+        db.record_seen_events(&keys[1..], 4).await.unwrap();
+
+        assert_eq!(db.get_seen_events(10).await.unwrap(), keys[1..].to_vec());
+        assert_eq!(db.get_seen_events(2).await.unwrap(), keys[3..].to_vec());
+    }
+
+    #[tokio::test]
+    async fn test_contract_stats_accumulate() {
+        let db = EngineDb::new(EngineDbConfig {
+            path: ":memory:".to_string(),
+        })
+        .await
+        .unwrap();
+        let token = Felt::from(0x123_u64);
+        let other = Felt::from(0x456_u64);
+        let activity = |contract, decoder: &str, events, first, last, ts| ContractActivity {
+            contract,
+            decoder: decoder.to_string(),
+            event_count: events,
+            first_block: first,
+            last_block: last,
+            last_activity: ts,
+        };
+
+        db.record_contract_activity(&[
+            activity(token, "erc20", 3, 10, 12, 1000),
+            activity(other, "erc721", 1, 11, 11, 900),
+        ])
+        .await
+        .unwrap();
+        db.record_contract_activity(&[
+            activity(token, "erc20", 2, 20, 25, 2000),
+            activity(token, "custom", 1, 5, 5, 500),
+        ])
+        .await
+        .unwrap();
+
+        let stats = db.get_contract_stats(&[token]).await.unwrap();
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].first_block, 5);
+        assert_eq!(stats[0].last_block, 25);
+        assert_eq!(stats[0].last_activity, 2000);
+        assert_eq!(stats[0].events_by_decoder["erc20"], 5);
+        assert_eq!(stats[0].events_by_decoder["custom"], 1);
+        assert_eq!(stats[0].total_events(), 6);
+
+        assert_eq!(db.get_contract_stats(&[]).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_table_definitions_are_versioned() {
+        let db = EngineDb::new(EngineDbConfig {
+            path: ":memory:".to_string(),
+        })
+        .await
+        .unwrap();
+        let world = Felt::from(0x1_u64);
+        let other_world = Felt::from(0x2_u64);
+        let table = Felt::from(0xaa_u64);
+        let tx = Felt::from(0xff_u64);
+
+        let save = |owner, definition: &'static str, block| {
+            db.save_table_definition("dojo", owner, table, definition, block, tx)
+        };
+        assert_eq!(save(world, "{\"v\":1}", 10).await.unwrap(), 1);
+        // Re-saving the latest definition does not add a version.
+        assert_eq!(save(world, "{\"v\":1}", 10).await.unwrap(), 1);
+        assert_eq!(save(world, "{\"v\":2}", 20).await.unwrap(), 2);
+        assert_eq!(save(other_world, "{\"v\":1}", 30).await.unwrap(), 1);
+
+        let latest = db.get_table_definitions("dojo", &[world]).await.unwrap();
+        assert_eq!(latest.len(), 1);
+        assert_eq!(
+            (
+                latest[0].version,
+                latest[0].definition.as_str(),
+                latest[0].block_number
+            ),
+            (2, "{\"v\":2}", 20)
+        );
+        assert_eq!(latest[0].tx_hash, tx);
+        assert_eq!(
+            db.get_table_definitions("dojo", &[]).await.unwrap().len(),
+            2
+        );
+        assert!(db
+            .get_table_definitions("other", &[])
+            .await
+            .unwrap()
+            .is_empty());
+
+        let history = db
+            .get_table_definition_history("dojo", world, table)
+            .await
+            .unwrap();
+        assert_eq!(
+            history.iter().map(|d| d.version).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_contract_identifications_round_trip() {
+        let db = EngineDb::new(EngineDbConfig {
+            path: ":memory:".to_string(),
+        })
+        .await
+        .unwrap();
+        let erc20 = Felt::from(0x10_u64);
+        let upgraded = Felt::from(0x20_u64);
+
+        db.set_contract_decoders(Felt::from(0x30_u64), &[DecoderId::from_u64(3)])
+            .await
+            .unwrap();
+        let identifications = vec![
+            ContractIdentification {
+                contract: erc20,
+                decoder_ids: vec![DecoderId::from_u64(1), DecoderId::from_u64(2)],
+                source: IdentificationSource::Rules(vec!["erc20".to_string()]),
+                class_hash: Some(Felt::from(0xc1_u64)),
+                identified_at: 100,
+            },
+            ContractIdentification {
+                contract: upgraded,
+                decoder_ids: vec![DecoderId::from_u64(1)],
+                source: IdentificationSource::ProxyResolved {
+                    implementation: Felt::from(0xc2_u64),
+                },
+                class_hash: None,
+                identified_at: 200,
+            },
+        ];
+        db.set_contract_identifications_batch(&identifications)
+            .await
+            .unwrap();
+
+        let mut loaded = db.get_all_contract_identifications().await.unwrap();
+        loaded.sort_by_key(|identification| identification.contract);
+        assert_eq!(loaded.len(), 3);
+        assert_eq!(&loaded[..2], identifications.as_slice());
+        assert_eq!(loaded[2].source, IdentificationSource::Explicit);
+
+        // Legacy readers still see the mapping.
+        assert_eq!(
+            db.get_contract_decoders(erc20).await.unwrap(),
+            Some(vec![DecoderId::from_u64(1), DecoderId::from_u64(2)])
+        );
+        assert_eq!(
+            IdentificationSource::decode("bogus"),
+            IdentificationSource::Unknown
+        );
+    }
 }
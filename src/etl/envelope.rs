@@ -3,8 +3,13 @@
 use starknet::core::types::{EmittedEvent, Felt};
 use std::any::Any;
 use std::collections::HashMap;
+use std::ops::Deref;
+use std::str::FromStr;
+use std::sync::Arc;
 use xxhash_rust::const_xxh3::xxh3_64;
 
+use crate::etl::decoder::DecoderId;
+
 /// Type identifier based on a string hash
 /// This allows sinks to identify and downcast envelope bodies
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -31,6 +36,13 @@ pub trait TypedBody: Send + Sync {
     fn envelope_type_id(&self) -> TypeId;
     fn as_any(&self) -> &dyn Any;
     fn as_any_mut(&mut self) -> &mut dyn Any;
+
+    /// `Debug` rendering of the body, used by golden decoder snapshots.
+    ///
+    /// `None` unless the body opts in; `#[derive(StarknetEvent)]` bodies always do.
+    fn debug_repr(&self) -> Option<String> {
+        None
+    }
 }
 
 /// Helper macro to implement TypedBody
@@ -53,8 +65,173 @@ macro_rules! typed_body_impl {
     };
 }
 
+/// Where an envelope came from, recorded when provenance tracking is enabled.
+///
+/// This is a debugging aid to answer "where did this row come from and when"
+/// during incident analysis. Timestamps are unix milliseconds.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Provenance {
+    /// Extractor type that produced the source event.
+    pub extractor: String,
+    /// Name of the decoder that produced the envelope.
+    pub decoder: String,
+    /// Monotonic ETL batch id (resets on restart).
+    pub batch_id: u64,
+    /// When the batch was extracted.
+    pub extracted_at: i64,
+    /// When the envelope was decoded.
+    pub decoded_at: i64,
+}
+
+impl Provenance {
+    /// Creates a provenance record for an envelope decoded now by `decoder`.
+    ///
+    /// Extractor and batch fields are filled in later by the ETL loop.
+    pub fn decoded_by(decoder: &str) -> Self {
+        Self {
+            decoder: decoder.to_string(),
+            decoded_at: chrono::Utc::now().timestamp_millis(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Rule through which the `DecoderContext` selected the decoder of a contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MappingRule {
+    /// Explicit contract mapping (configuration or decoder config file).
+    Explicit,
+    /// Contract identified by the contract registry.
+    Registry,
+    /// Unmapped contract: every decoder was tried.
+    Fallback,
+}
+
+impl MappingRule {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Explicit => "explicit",
+            Self::Registry => "registry",
+            Self::Fallback => "fallback",
+        }
+    }
+}
+
+impl std::fmt::Display for MappingRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Typed metadata of the source event of an envelope.
+///
+/// Filled in by the `DecoderContext` from the raw event, keeping the fields a decoder
+/// already set, and from the batch for the block timestamp, along with the decoder
+/// that produced the envelope and why it was selected. Decoder-specific extras stay in
+/// [`Envelope::metadata`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EnvelopeMeta {
+    /// Block of the source event.
+    pub block_number: Option<u64>,
+    /// Timestamp of that block (unix seconds).
+    pub block_timestamp: Option<u64>,
+    /// Transaction that emitted the source event.
+    pub transaction_hash: Option<Felt>,
+    /// Index of the source event within its transaction, among the events of the batch.
+    pub event_index: Option<u32>,
+    /// Contract that emitted the source event.
+    pub contract: Option<Felt>,
+    /// Decoder that produced the envelope (used for per-contract indexing statistics).
+    pub decoder_id: Option<DecoderId>,
+    /// Name of that decoder.
+    pub decoder: Option<Arc<str>>,
+    /// Rule that mapped the contract to that decoder.
+    pub mapping_rule: Option<MappingRule>,
+}
+
+impl EnvelopeMeta {
+    /// Fills the fields not set yet from the raw event.
+    ///
+    /// The raw event carries neither the block timestamp nor the event index.
+    pub fn fill_from_event(&mut self, event: &EmittedEvent) {
+        self.contract.get_or_insert(event.from_address);
+        self.transaction_hash.get_or_insert(event.transaction_hash);
+        if self.block_number.is_none() {
+            self.block_number = event.block_number;
+        }
+    }
+}
+
+/// Shared body of an envelope.
+///
+/// Either allocated on its own, or stored in a batch [`EnvelopeSlab`] shared by all the
+/// envelopes of the batch (one allocation for all bodies). Dereferences to the body.
+#[derive(Clone)]
+pub struct EnvelopeBody(BodyRepr);
+
+#[derive(Clone)]
+enum BodyRepr {
+    Owned(Arc<dyn TypedBody>),
+    Slab {
+        bodies: Arc<dyn SlabBodies>,
+        index: usize,
+    },
+}
+
+/// Contiguous storage of the bodies of an [`EnvelopeSlab`].
+trait SlabBodies: Send + Sync {
+    fn get(&self, index: usize) -> &(dyn TypedBody + 'static);
+}
+
+impl<T: TypedBody + 'static> SlabBodies for Vec<T> {
+    fn get(&self, index: usize) -> &(dyn TypedBody + 'static) {
+        &self[index]
+    }
+}
+
+impl EnvelopeBody {
+    /// Mutable access to the body, if it is not shared (slab bodies are always shared).
+    fn get_mut(&mut self) -> Option<&mut (dyn TypedBody + 'static)> {
+        match &mut self.0 {
+            BodyRepr::Owned(body) => Arc::get_mut(body),
+            BodyRepr::Slab { .. } => None,
+        }
+    }
+}
+
+impl Deref for EnvelopeBody {
+    type Target = dyn TypedBody;
+
+    fn deref(&self) -> &Self::Target {
+        match &self.0 {
+            BodyRepr::Owned(body) => body.as_ref(),
+            BodyRepr::Slab { bodies, index } => bodies.get(*index),
+        }
+    }
+}
+
+impl From<Arc<dyn TypedBody>> for EnvelopeBody {
+    fn from(body: Arc<dyn TypedBody>) -> Self {
+        Self(BodyRepr::Owned(body))
+    }
+}
+
+impl From<Box<dyn TypedBody>> for EnvelopeBody {
+    fn from(body: Box<dyn TypedBody>) -> Self {
+        Self(BodyRepr::Owned(Arc::from(body)))
+    }
+}
+
+/// Decoder-specific metadata of an [`Envelope`].
+///
+/// Values are shared strings so interned hex (see [`EnvelopeSlab::hex`]) is not copied.
+pub type EnvelopeMetadata = HashMap<String, Arc<str>>;
+
 /// Envelope wraps transformed data with metadata
 /// This is the core data structure that flows through the ETL pipeline
+///
+/// Cloning an envelope is cheap: the body is shared, only the id and metadata are copied.
+#[derive(Clone)]
 pub struct Envelope {
     /// Unique identifier for this envelope
     pub id: String,
@@ -63,36 +240,199 @@ pub struct Envelope {
     pub type_id: TypeId,
 
     /// The actual data (can be downcast by sinks)
-    pub body: Box<dyn TypedBody>,
+    pub body: EnvelopeBody,
 
-    /// Metadata that sinks can use for filtering
-    pub metadata: HashMap<String, String>,
+    /// Decoder-specific metadata that sinks can use for filtering
+    pub metadata: EnvelopeMetadata,
 
     /// Timestamp when this envelope was created
     pub timestamp: i64,
+
+    /// Typed metadata of the source event, set by the `DecoderContext`.
+    ///
+    /// `meta.contract` is used by `MultiSink` for per-sink contract routing.
+    pub meta: EnvelopeMeta,
+
+    /// Provenance of this envelope, only set when provenance tracking is enabled.
+    pub provenance: Option<Provenance>,
 }
 
 impl Envelope {
     /// Creates a new envelope.
-    pub fn new(id: String, body: Box<dyn TypedBody>, metadata: HashMap<String, String>) -> Self {
+    ///
+    /// Prefer [`Envelope::from_body`] when the body type is known: boxing the body
+    /// first costs an extra allocation and copy.
+    pub fn new(id: String, body: Box<dyn TypedBody>, metadata: EnvelopeMetadata) -> Self {
         let type_id = body.envelope_type_id();
+        Self::with_body(
+            id,
+            type_id,
+            body.into(),
+            metadata,
+            chrono::Utc::now().timestamp(),
+        )
+    }
+
+    /// Creates a new envelope, allocating the body once.
+    pub fn from_body<T: TypedBody + 'static>(
+        id: String,
+        body: T,
+        metadata: EnvelopeMetadata,
+    ) -> Self {
+        let type_id = body.envelope_type_id();
+        let body: Arc<dyn TypedBody> = Arc::new(body);
+        Self::with_body(
+            id,
+            type_id,
+            body.into(),
+            metadata,
+            chrono::Utc::now().timestamp(),
+        )
+    }
+
+    fn with_body(
+        id: String,
+        type_id: TypeId,
+        body: EnvelopeBody,
+        metadata: EnvelopeMetadata,
+        timestamp: i64,
+    ) -> Self {
         Self {
             id,
             type_id,
             body,
             metadata,
-            timestamp: chrono::Utc::now().timestamp(),
+            timestamp,
+            meta: EnvelopeMeta::default(),
+            provenance: None,
         }
     }
 
+    /// Sets the contract that emitted the source event.
+    pub fn with_from_address(mut self, from_address: Felt) -> Self {
+        self.meta.contract = Some(from_address);
+        self
+    }
+
+    /// Contract that emitted the source event.
+    pub fn contract(&self) -> Option<Felt> {
+        self.meta.contract
+    }
+
+    /// Block of the source event.
+    pub fn block_number(&self) -> Option<u64> {
+        self.meta.block_number
+    }
+
+    /// Timestamp (unix seconds) of the block of the source event.
+    pub fn block_timestamp(&self) -> Option<u64> {
+        self.meta.block_timestamp
+    }
+
+    /// Transaction that emitted the source event.
+    pub fn transaction_hash(&self) -> Option<Felt> {
+        self.meta.transaction_hash
+    }
+
+    /// Name of the decoder that produced the envelope.
+    pub fn decoder_name(&self) -> Option<&str> {
+        self.meta.decoder.as_deref()
+    }
+
+    /// Parses the decoder-specific metadata entry `key`.
+    ///
+    /// Returns `None` when the entry is missing or does not parse as `T`.
+    pub fn metadata_as<T: FromStr>(&self, key: &str) -> Option<T> {
+        self.metadata.get(key)?.parse().ok()
+    }
+
     /// Tries to downcast the body to a concrete type.
     pub fn downcast_ref<T: 'static>(&self) -> Option<&T> {
         self.body.as_any().downcast_ref::<T>()
     }
 
     /// Tries to downcast the body to a mutable concrete type.
+    ///
+    /// Returns `None` if the body is shared with a clone of this envelope or
+    /// stored in an [`EnvelopeSlab`].
     pub fn downcast_mut<T: 'static>(&mut self) -> Option<&mut T> {
-        self.body.as_any_mut().downcast_mut::<T>()
+        self.body.get_mut()?.as_any_mut().downcast_mut::<T>()
+    }
+}
+
+/// Builder storing the bodies of many envelopes of the same type in one allocation.
+///
+/// Useful for decoders producing several envelopes at once (e.g. batch transfers,
+/// or an overridden `Decoder::decode`). Bodies are read-only once built.
+///
+/// The slab can also intern the hex representation of repeated addresses (contracts,
+/// transaction hashes) used in envelope ids and metadata, formatting each one once.
+pub struct EnvelopeSlab<T> {
+    bodies: Vec<T>,
+    headers: Vec<(String, EnvelopeMetadata, Option<Felt>)>,
+    hex: HashMap<Felt, Arc<str>>,
+}
+
+impl<T: TypedBody + 'static> EnvelopeSlab<T> {
+    /// Creates an empty slab with room for `capacity` envelopes.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            bodies: Vec::with_capacity(capacity),
+            headers: Vec::with_capacity(capacity),
+            hex: HashMap::new(),
+        }
+    }
+
+    /// Adds an envelope.
+    pub fn push(
+        &mut self,
+        id: String,
+        body: T,
+        metadata: EnvelopeMetadata,
+        from_address: Option<Felt>,
+    ) {
+        self.bodies.push(body);
+        self.headers.push((id, metadata, from_address));
+    }
+
+    /// Returns the `{:#x}` representation of `value`, formatted once per slab.
+    pub fn hex(&mut self, value: Felt) -> Arc<str> {
+        self.hex
+            .entry(value)
+            .or_insert_with(|| format!("{value:#x}").into())
+            .clone()
+    }
+
+    /// Number of envelopes in the slab.
+    pub fn len(&self) -> usize {
+        self.bodies.len()
+    }
+
+    /// Whether the slab is empty.
+    pub fn is_empty(&self) -> bool {
+        self.bodies.is_empty()
+    }
+
+    /// Builds the envelopes, sharing a single allocation for all bodies.
+    pub fn into_envelopes(self) -> Vec<Envelope> {
+        let type_ids: Vec<TypeId> = self.bodies.iter().map(T::envelope_type_id).collect();
+        let bodies: Arc<dyn SlabBodies> = Arc::new(self.bodies);
+        let timestamp = chrono::Utc::now().timestamp();
+
+        self.headers
+            .into_iter()
+            .zip(type_ids)
+            .enumerate()
+            .map(|(index, ((id, metadata, from_address), type_id))| {
+                let body = EnvelopeBody(BodyRepr::Slab {
+                    bodies: bodies.clone(),
+                    index,
+                });
+                let mut envelope = Envelope::with_body(id, type_id, body, metadata, timestamp);
+                envelope.meta.contract = from_address;
+                envelope
+            })
+            .collect()
     }
 }
 
@@ -105,6 +445,8 @@ impl std::fmt::Debug for Envelope {
             .field("type_id", &self.type_id)
             .field("metadata", &self.metadata)
             .field("timestamp", &self.timestamp)
+            .field("meta", &self.meta)
+            .field("provenance", &self.provenance)
             .finish()
     }
 }
@@ -138,7 +480,8 @@ pub trait EventMsg: Send + Sync + 'static {
     where
         Self: Sized,
     {
-        Envelope::new(self.event_id(), Box::new(self.to_body(raw)), HashMap::new())
+        Envelope::from_body(self.event_id(), self.to_body(raw), EnvelopeMetadata::new())
+            .with_from_address(raw.from_address)
     }
 }
 
@@ -175,11 +518,13 @@ impl From<&EmittedEvent> for MetaData {
 
 impl<T: EventMsg + Send + Sync + 'static> From<EventBody<T>> for Envelope {
     fn from(value: EventBody<T>) -> Self {
-        Envelope::new(
+        let from_address = value.metadata.from_address;
+        Envelope::from_body(
             value.msg.event_id(),
-            Box::new(value),
+            value,
             HashMap::new(), // You can add relevant metadata here
         )
+        .with_from_address(from_address)
     }
 }
 
@@ -199,3 +544,80 @@ where
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Transfer {
+        amount: u64,
+    }
+
+    crate::typed_body_impl!(Transfer, "test.transfer");
+
+    #[test]
+    fn slab_interns_repeated_hex() {
+        let mut slab = EnvelopeSlab::<Transfer>::with_capacity(2);
+        let token = slab.hex(Felt::from(0xabc_u64));
+        assert_eq!(&*token, "0xabc");
+        assert!(Arc::ptr_eq(&token, &slab.hex(Felt::from(0xabc_u64))));
+        assert!(!Arc::ptr_eq(&token, &slab.hex(Felt::from(0xabd_u64))));
+    }
+
+    #[test]
+    fn slab_envelopes_look_up_their_own_body() {
+        let contract = Felt::from(0x1_u64);
+        let mut slab = EnvelopeSlab::with_capacity(3);
+        for amount in [10, 20, 30] {
+            let metadata = HashMap::from([("amount".to_string(), amount.to_string().into())]);
+            slab.push(
+                format!("transfer-{amount}"),
+                Transfer { amount },
+                metadata,
+                Some(contract),
+            );
+        }
+        assert_eq!(slab.len(), 3);
+
+        let mut envelopes = slab.into_envelopes();
+        for (envelope, amount) in envelopes.iter().zip([10, 20, 30]) {
+            assert_eq!(envelope.id, format!("transfer-{amount}"));
+            assert_eq!(envelope.type_id, TypeId::new("test.transfer"));
+            assert_eq!(envelope.contract(), Some(contract));
+            assert_eq!(envelope.metadata_as::<u64>("amount"), Some(amount));
+            assert_eq!(envelope.downcast_ref::<Transfer>().unwrap().amount, amount);
+        }
+        // Slab bodies are shared, so they are read-only.
+        assert!(envelopes[0].downcast_mut::<Transfer>().is_none());
+    }
+
+    #[test]
+    fn each_batch_builds_a_fresh_slab() {
+        let mut first = EnvelopeSlab::with_capacity(1);
+        let hex = first.hex(Felt::from(0xabc_u64));
+        first.push(
+            "first".to_string(),
+            Transfer { amount: 1 },
+            HashMap::new(),
+            None,
+        );
+        let first = first.into_envelopes();
+
+        let mut second = EnvelopeSlab::with_capacity(1);
+        assert!(second.is_empty());
+        assert!(!Arc::ptr_eq(&hex, &second.hex(Felt::from(0xabc_u64))));
+        second.push(
+            "second".to_string(),
+            Transfer { amount: 2 },
+            HashMap::new(),
+            None,
+        );
+        let second = second.into_envelopes();
+
+        // Envelopes of the previous batch keep their bodies.
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].downcast_ref::<Transfer>().unwrap().amount, 1);
+        assert_eq!(first[0].contract(), None);
+        assert_eq!(second[0].downcast_ref::<Transfer>().unwrap().amount, 2);
+    }
+}
@@ -0,0 +1,297 @@
+//! Compressed raw event archive chunks
+//!
+//! With event archiving enabled, each processed batch is stored in the engine database as
+//! one chunk keyed by its block range: its events, block and transaction context (receipts
+//! included) serialized as JSON and compressed with zstd. Chunks keep full fidelity at a
+//! fraction of the size of row-per-event storage, and are read back by the
+//! [`ArchiveExtractor`](crate::etl::extractor::ArchiveExtractor) or iterated in block order
+//! with [`EngineDb::archived_batches`](crate::etl::EngineDb::archived_batches).
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use starknet::core::types::{EmittedEvent, ExecutionResult, Felt, PriceUnit};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::etl::dedupe::{event_keys, EventKey};
+use crate::etl::extractor::{ExtractionBatch, TransactionContext, TransactionReceiptInfo};
+
+/// zstd compression level of archive chunks (the zstd default).
+pub const ARCHIVE_ZSTD_LEVEL: i32 = 3;
+
+/// Archived batch read back from the engine database.
+#[derive(Debug, Clone)]
+pub struct ArchivedBatch {
+    /// First block of the archived batch
+    pub from_block: u64,
+    /// Last block of the archived batch
+    pub to_block: u64,
+    pub batch: ExtractionBatch,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ChunkBlock {
+    number: u64,
+    hash: Felt,
+    parent_hash: Felt,
+    timestamp: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ChunkReceipt {
+    actual_fee: Felt,
+    fee_unit: PriceUnit,
+    /// Set for reverted transactions
+    revert_reason: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ChunkTransaction {
+    hash: Felt,
+    block_number: u64,
+    sender_address: Option<Felt>,
+    calldata: Vec<Felt>,
+    receipt: Option<ChunkReceipt>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ChunkEvent {
+    block_number: Option<u64>,
+    block_hash: Option<Felt>,
+    tx_hash: Felt,
+    /// Position of the event among the events of its transaction in the chunk
+    event_index: u32,
+    from_address: Felt,
+    keys: Vec<Felt>,
+    data: Vec<Felt>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Chunk {
+    blocks: Vec<ChunkBlock>,
+    transactions: Vec<ChunkTransaction>,
+    events: Vec<ChunkEvent>,
+}
+
+/// Encoded archive chunk of a batch.
+pub(crate) struct EncodedChunk {
+    pub from_block: u64,
+    pub to_block: u64,
+    pub event_count: usize,
+    /// Size of the uncompressed payload in bytes
+    pub raw_size: usize,
+    /// zstd-compressed payload
+    pub data: Vec<u8>,
+}
+
+/// Block range covered by the events and blocks of `batch`.
+pub fn batch_block_range(batch: &ExtractionBatch) -> Option<(u64, u64)> {
+    batch
+        .events
+        .iter()
+        .filter_map(|event| event.block_number)
+        .chain(batch.blocks.keys().copied())
+        .fold(None, |range, block| match range {
+            None => Some((block, block)),
+            Some((low, high)) => Some((low.min(block), high.max(block))),
+        })
+}
+
+/// Serializes and compresses `batch`. Returns `None` for a batch without events.
+pub(crate) fn encode_chunk(batch: &ExtractionBatch) -> Result<Option<EncodedChunk>> {
+    let Some((from_block, to_block)) = batch_block_range(batch).filter(|_| !batch.is_empty())
+    else {
+        return Ok(None);
+    };
+
+    let mut blocks: Vec<_> = batch
+        .blocks
+        .values()
+        .map(|block| ChunkBlock {
+            number: block.number,
+            hash: block.hash,
+            parent_hash: block.parent_hash,
+            timestamp: block.timestamp,
+        })
+        .collect();
+    blocks.sort_by_key(|block| block.number);
+    let mut transactions: Vec<_> = batch
+        .transactions
+        .values()
+        .map(|tx| ChunkTransaction {
+            hash: tx.hash,
+            block_number: tx.block_number,
+            sender_address: tx.sender_address,
+            calldata: tx.calldata.clone(),
+            receipt: tx.receipt.as_ref().map(|receipt| ChunkReceipt {
+                actual_fee: receipt.actual_fee,
+                fee_unit: receipt.fee_unit,
+                revert_reason: receipt.execution_result.revert_reason().map(str::to_string),
+            }),
+        })
+        .collect();
+    transactions.sort_by_key(|tx| (tx.block_number, tx.hash));
+    let mut tx_event_counts: HashMap<Felt, u32> = HashMap::new();
+    let events = batch
+        .events
+        .iter()
+        .map(|event| {
+            let count = tx_event_counts.entry(event.transaction_hash).or_insert(0);
+            let event_index = *count;
+            *count += 1;
+            ChunkEvent {
+                block_number: event.block_number,
+                block_hash: event.block_hash,
+                tx_hash: event.transaction_hash,
+                event_index,
+                from_address: event.from_address,
+                keys: event.keys.clone(),
+                data: event.data.clone(),
+            }
+        })
+        .collect();
+
+    let raw = serde_json::to_vec(&Chunk {
+        blocks,
+        transactions,
+        events,
+    })?;
+    let data = zstd::encode_all(raw.as_slice(), ARCHIVE_ZSTD_LEVEL)
+        .context("Failed to compress archive chunk")?;
+    Ok(Some(EncodedChunk {
+        from_block,
+        to_block,
+        event_count: batch.len(),
+        raw_size: raw.len(),
+        data,
+    }))
+}
+
+/// Decompresses a chunk into `batch`, keeping the events of blocks `from_block..=to_block`
+/// whose key (see [`event_keys`]) is not in `seen` yet.
+pub(crate) fn decode_chunk_into(
+    data: &[u8],
+    from_block: u64,
+    to_block: u64,
+    seen: &mut HashSet<EventKey>,
+    batch: &mut ExtractionBatch,
+) -> Result<()> {
+    let raw = zstd::decode_all(data).context("Failed to decompress archive chunk")?;
+    let chunk: Chunk = serde_json::from_slice(&raw).context("Invalid archive chunk")?;
+    let in_range = |block: u64| (from_block..=to_block).contains(&block);
+
+    for block in chunk.blocks.into_iter().filter(|b| in_range(b.number)) {
+        batch.add_block_context(block.number, block.hash, block.parent_hash, block.timestamp);
+    }
+    for tx in chunk
+        .transactions
+        .into_iter()
+        .filter(|tx| in_range(tx.block_number))
+    {
+        batch.transactions.insert(
+            tx.hash,
+            Arc::new(TransactionContext {
+                hash: tx.hash,
+                block_number: tx.block_number,
+                sender_address: tx.sender_address,
+                calldata: tx.calldata,
+                receipt: tx.receipt.map(|receipt| TransactionReceiptInfo {
+                    actual_fee: receipt.actual_fee,
+                    fee_unit: receipt.fee_unit,
+                    execution_result: match receipt.revert_reason {
+                        Some(reason) => ExecutionResult::Reverted { reason },
+                        None => ExecutionResult::Succeeded,
+                    },
+                }),
+            }),
+        );
+    }
+    let events: Vec<EmittedEvent> = chunk
+        .events
+        .into_iter()
+        .filter(|event| in_range(event.block_number.unwrap_or(0)))
+        .map(|event| EmittedEvent {
+            from_address: event.from_address,
+            keys: event.keys,
+            data: event.data,
+            block_hash: event.block_hash,
+            block_number: event.block_number,
+            transaction_hash: event.tx_hash,
+        })
+        .collect();
+    let keys = event_keys(&events);
+    batch.events.extend(
+        events
+            .into_iter()
+            .zip(keys)
+            .filter_map(|(event, key)| seen.insert(key).then_some(event)),
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_batches_with_receipts() {
+        let mut batch = ExtractionBatch::empty();
+        for block_number in [5, 6] {
+            batch.add_block_context(
+                block_number,
+                Felt::from(block_number),
+                Felt::from(block_number - 1),
+                100 + block_number,
+            );
+            batch.transactions.insert(
+                Felt::from(block_number),
+                Arc::new(TransactionContext {
+                    hash: Felt::from(block_number),
+                    block_number,
+                    sender_address: None,
+                    calldata: vec![Felt::ONE],
+                    receipt: Some(TransactionReceiptInfo {
+                        actual_fee: Felt::TWO,
+                        fee_unit: PriceUnit::Fri,
+                        execution_result: ExecutionResult::Reverted {
+                            reason: "out of gas".to_string(),
+                        },
+                    }),
+                }),
+            );
+            for data in 0..50_u64 {
+                batch.events.push(EmittedEvent {
+                    from_address: Felt::from(0x42_u64),
+                    keys: vec![Felt::ONE],
+                    data: vec![Felt::from(data)],
+                    block_hash: Some(Felt::from(block_number)),
+                    block_number: Some(block_number),
+                    transaction_hash: Felt::from(block_number),
+                });
+            }
+        }
+
+        let chunk = encode_chunk(&batch).unwrap().unwrap();
+        assert_eq!((chunk.from_block, chunk.to_block), (5, 6));
+        assert_eq!(chunk.event_count, 100);
+        assert!(chunk.data.len() < chunk.raw_size / 4);
+
+        let mut seen = HashSet::new();
+        let mut decoded = ExtractionBatch::empty();
+        decode_chunk_into(&chunk.data, 6, 6, &mut seen, &mut decoded).unwrap();
+        assert_eq!(decoded.len(), 50);
+        assert_eq!(decoded.events[..], batch.events[50..]);
+        assert_eq!(decoded.blocks[&6].timestamp, 106);
+        let receipt = decoded.transactions[&Felt::from(6_u64)]
+            .receipt
+            .as_ref()
+            .unwrap();
+        assert!(receipt.is_reverted());
+
+        // Events already read are skipped.
+        decode_chunk_into(&chunk.data, 5, 6, &mut seen, &mut decoded).unwrap();
+        assert_eq!(decoded.len(), 100);
+
+        assert!(encode_chunk(&ExtractionBatch::empty()).unwrap().is_none());
+    }
+}
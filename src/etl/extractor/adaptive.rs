@@ -0,0 +1,245 @@
+//! Adaptive batch sizing for extractors
+//!
+//! A fixed batch size either underutilizes the RPC or causes timeouts. The
+//! controller measures per-cycle latency (extract + decode + sink) and grows or
+//! shrinks the batch size within configurable bounds to keep cycle time near a target.
+
+use std::time::Duration;
+
+/// Adaptive batch sizing configuration
+#[derive(Debug, Clone)]
+pub struct AdaptiveBatchConfig {
+    /// Smallest batch size the controller may choose
+    pub min_batch_size: u64,
+
+    /// Largest batch size the controller may choose
+    pub max_batch_size: u64,
+
+    /// Cycle time (extract + decode + sink) to aim for
+    pub target_cycle_time: Duration,
+
+    /// Maximum factor the batch size may grow or shrink by in a single adjustment
+    pub max_step_factor: f64,
+
+    /// Relative deviation from the target within which the batch size is left unchanged
+    pub tolerance: f64,
+}
+
+impl Default for AdaptiveBatchConfig {
+    fn default() -> Self {
+        Self {
+            min_batch_size: 10,
+            max_batch_size: 1000,
+            target_cycle_time: Duration::from_secs(5),
+            max_step_factor: 2.0,
+            tolerance: 0.2,
+        }
+    }
+}
+
+impl AdaptiveBatchConfig {
+    /// Creates a new configuration with the given bounds and target cycle time.
+    pub fn new(min_batch_size: u64, max_batch_size: u64, target_cycle_time: Duration) -> Self {
+        Self {
+            min_batch_size,
+            max_batch_size,
+            target_cycle_time,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the maximum per-adjustment growth/shrink factor.
+    pub fn with_max_step_factor(mut self, factor: f64) -> Self {
+        self.max_step_factor = factor;
+        self
+    }
+
+    /// Sets the relative tolerance around the target cycle time.
+    pub fn with_tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Validates the configuration.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.min_batch_size == 0 {
+            anyhow::bail!("Adaptive batch min_batch_size must be at least 1");
+        }
+        if self.min_batch_size > self.max_batch_size {
+            anyhow::bail!(
+                "Adaptive batch min_batch_size ({}) exceeds max_batch_size ({})",
+                self.min_batch_size,
+                self.max_batch_size
+            );
+        }
+        if self.target_cycle_time.is_zero() {
+            anyhow::bail!("Adaptive batch target_cycle_time must be non-zero");
+        }
+        if self.max_step_factor < 1.0 {
+            anyhow::bail!("Adaptive batch max_step_factor must be >= 1.0");
+        }
+        if !(0.0..1.0).contains(&self.tolerance) {
+            anyhow::bail!("Adaptive batch tolerance must be in [0, 1)");
+        }
+        Ok(())
+    }
+}
+
+/// Latency measurements of one ETL cycle, fed back to the extractor.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CycleFeedback {
+    /// Number of blocks covered by the batch
+    pub blocks: u64,
+
+    /// Number of events in the batch
+    pub events: u64,
+
+    /// Time spent extracting the batch
+    pub extract: Duration,
+
+    /// Time spent decoding the batch
+    pub decode: Duration,
+
+    /// Time spent in sinks
+    pub sink: Duration,
+}
+
+impl CycleFeedback {
+    /// Total cycle latency.
+    pub fn total(&self) -> Duration {
+        self.extract + self.decode + self.sink
+    }
+}
+
+/// Controller that adjusts the batch size from cycle latency feedback.
+///
+/// The adjustment is proportional: the observed throughput (blocks per second)
+/// is scaled to the target cycle time. Feedback carries the size of the batch it
+/// measured, so stale feedback from prefetched batches is still meaningful.
+#[derive(Debug, Clone)]
+pub struct AdaptiveBatchController {
+    config: AdaptiveBatchConfig,
+    batch_size: u64,
+}
+
+impl AdaptiveBatchController {
+    /// Creates a controller starting at `initial_batch_size` (clamped to bounds).
+    pub fn new(config: AdaptiveBatchConfig, initial_batch_size: u64) -> Self {
+        let batch_size = initial_batch_size.clamp(config.min_batch_size, config.max_batch_size);
+        Self { config, batch_size }
+    }
+
+    /// Current batch size.
+    pub fn batch_size(&self) -> u64 {
+        self.batch_size
+    }
+
+    /// Bounds and target of the controller.
+    pub fn config(&self) -> &AdaptiveBatchConfig {
+        &self.config
+    }
+
+    /// Records a cycle measurement and returns the (possibly updated) batch size.
+    pub fn observe(&mut self, feedback: &CycleFeedback) -> u64 {
+        let total = feedback.total().as_secs_f64();
+        if feedback.blocks == 0 || total <= 0.0 {
+            return self.batch_size;
+        }
+
+        let target = self.config.target_cycle_time.as_secs_f64();
+        let ratio = target / total;
+        if (ratio - 1.0).abs() <= self.config.tolerance {
+            return self.batch_size;
+        }
+
+        let current = self.batch_size as f64;
+        let step = self.config.max_step_factor;
+        let desired = (feedback.blocks as f64 * ratio).clamp(current / step, current * step);
+        self.batch_size =
+            (desired.round() as u64).clamp(self.config.min_batch_size, self.config.max_batch_size);
+        self.batch_size
+    }
+
+    /// Shrinks the batch size by the maximum step (sinks are falling behind) and
+    /// returns it. Cycle feedback grows it back once the sinks keep up.
+    pub fn throttle(&mut self) -> u64 {
+        let shrunk = (self.batch_size as f64 / self.config.max_step_factor).round() as u64;
+        self.batch_size = shrunk.clamp(self.config.min_batch_size, self.config.max_batch_size);
+        self.batch_size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feedback(blocks: u64, total_ms: u64) -> CycleFeedback {
+        CycleFeedback {
+            blocks,
+            events: 0,
+            extract: Duration::from_millis(total_ms),
+            decode: Duration::ZERO,
+            sink: Duration::ZERO,
+        }
+    }
+
+    fn config() -> AdaptiveBatchConfig {
+        AdaptiveBatchConfig::new(10, 1000, Duration::from_secs(2))
+    }
+
+    #[test]
+    fn test_grows_when_cycles_are_fast() {
+        let mut controller = AdaptiveBatchController::new(config(), 100);
+        assert_eq!(controller.observe(&feedback(100, 1000)), 200);
+    }
+
+    #[test]
+    fn test_shrinks_when_cycles_are_slow() {
+        let mut controller = AdaptiveBatchController::new(config(), 100);
+        assert_eq!(controller.observe(&feedback(100, 4000)), 50);
+    }
+
+    #[test]
+    fn test_step_is_bounded() {
+        let mut controller = AdaptiveBatchController::new(config(), 100);
+        // 20x faster than target, but growth is capped at max_step_factor.
+        assert_eq!(controller.observe(&feedback(100, 100)), 200);
+    }
+
+    #[test]
+    fn test_respects_bounds() {
+        let mut controller = AdaptiveBatchController::new(config(), 900);
+        assert_eq!(controller.observe(&feedback(900, 500)), 1000);
+
+        let mut controller = AdaptiveBatchController::new(config(), 15);
+        assert_eq!(controller.observe(&feedback(15, 10_000)), 10);
+    }
+
+    #[test]
+    fn test_within_tolerance_is_stable() {
+        let mut controller = AdaptiveBatchController::new(config(), 100);
+        assert_eq!(controller.observe(&feedback(100, 2100)), 100);
+        assert_eq!(controller.observe(&feedback(0, 5000)), 100);
+    }
+
+    #[test]
+    fn test_throttle_shrinks_to_min() {
+        let mut controller = AdaptiveBatchController::new(config(), 100);
+        assert_eq!(controller.throttle(), 50);
+        assert_eq!(controller.throttle(), 25);
+        assert_eq!(controller.throttle(), 13);
+        assert_eq!(controller.throttle(), 10);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(config().validate().is_ok());
+        assert!(AdaptiveBatchConfig::new(0, 10, Duration::from_secs(1))
+            .validate()
+            .is_err());
+        assert!(AdaptiveBatchConfig::new(20, 10, Duration::from_secs(1))
+            .validate()
+            .is_err());
+        assert!(config().with_max_step_factor(0.5).validate().is_err());
+    }
+}
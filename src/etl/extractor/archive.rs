@@ -0,0 +1,224 @@
+//! Archive extractor replaying raw events stored in the engine database
+//!
+//! With event archiving enabled, the ETL loop stores the raw events of every processed
+//! batch (see [`EngineDb::archive_batch`]). This extractor reads them back block range
+//! by block range, so decoders and sinks can be re-run (e.g. after a sink schema change)
+//! without fetching the chain again.
+
+use async_trait::async_trait;
+
+use crate::error::ToriiResult;
+use crate::etl::engine_db::EngineDb;
+
+use super::{ExtractionBatch, Extractor};
+
+/// Archive extractor configuration
+#[derive(Debug, Clone)]
+pub struct ArchiveConfig {
+    /// First block to replay
+    pub from_block: u64,
+
+    /// Last block to replay (None = last archived block)
+    pub to_block: Option<u64>,
+
+    /// Number of blocks read per batch
+    pub batch_size: u64,
+}
+
+impl Default for ArchiveConfig {
+    fn default() -> Self {
+        Self {
+            from_block: 0,
+            to_block: None,
+            batch_size: 1000,
+        }
+    }
+}
+
+/// Extractor replaying archived events in block order
+///
+/// Replays are one-shot: the extractor finishes after the last block and does not
+/// persist a cursor, so an interrupted replay starts over.
+pub struct ArchiveExtractor {
+    config: ArchiveConfig,
+    /// Next block to read
+    current_block: u64,
+    /// Last block to read, resolved on the first extraction
+    end_block: Option<u64>,
+    finished: bool,
+}
+
+impl ArchiveExtractor {
+    pub fn new(config: ArchiveConfig) -> Self {
+        Self {
+            current_block: config.from_block,
+            end_block: None,
+            finished: false,
+            config,
+        }
+    }
+}
+
+#[async_trait]
+impl Extractor for ArchiveExtractor {
+    fn set_start_block(&mut self, start_block: u64) {
+        self.current_block = start_block;
+    }
+
+    fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    async fn extract(
+        &mut self,
+        _cursor: Option<String>,
+        engine_db: &EngineDb,
+    ) -> ToriiResult<ExtractionBatch> {
+        let end_block = if let Some(end_block) = self.end_block {
+            end_block
+        } else {
+            let Some((_, last_archived)) = engine_db.get_archived_block_range().await? else {
+                tracing::warn!(
+                    target: "torii::etl::archive",
+                    "No archived events to replay"
+                );
+                self.finished = true;
+                return Ok(ExtractionBatch::empty());
+            };
+            let end_block = self
+                .config
+                .to_block
+                .map_or(last_archived, |to_block| to_block.min(last_archived));
+            tracing::info!(
+                target: "torii::etl::archive",
+                from_block = self.current_block,
+                to_block = end_block,
+                "Replaying archived events"
+            );
+            self.end_block = Some(end_block);
+            end_block
+        };
+
+        // Skip block ranges without archived events.
+        while self.current_block <= end_block {
+            let to_block = self
+                .current_block
+                .saturating_add(self.config.batch_size.max(1) - 1)
+                .min(end_block);
+            let mut batch = engine_db
+                .get_archived_batch(self.current_block, to_block)
+                .await?;
+            self.current_block = to_block + 1;
+
+            if !batch.is_empty() {
+                tracing::debug!(
+                    target: "torii::etl::archive",
+                    to_block,
+                    events = batch.len(),
+                    "Read archived batch"
+                );
+                batch.cursor = Some(format!("archive:{to_block}"));
+                self.finished = self.current_block > end_block;
+                return Ok(batch);
+            }
+        }
+
+        tracing::info!(target: "torii::etl::archive", "Replay complete");
+        self.finished = true;
+        Ok(ExtractionBatch::empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::etl::engine_db::EngineDbConfig;
+    use starknet::core::types::{EmittedEvent, Felt};
+
+    fn event(block_number: u64, tx_hash: u64) -> EmittedEvent {
+        EmittedEvent {
+            from_address: Felt::from(0x42_u64),
+            keys: vec![Felt::from(0x1_u64)],
+            data: vec![Felt::from(block_number)],
+            block_hash: Some(Felt::from(block_number)),
+            block_number: Some(block_number),
+            transaction_hash: Felt::from(tx_hash),
+        }
+    }
+
+    fn batch(blocks: &[u64]) -> ExtractionBatch {
+        let mut batch = ExtractionBatch::empty();
+        for &block_number in blocks {
+            batch.add_block_context(
+                block_number,
+                Felt::from(block_number),
+                Felt::from(block_number - 1),
+                1_700_000_000 + block_number,
+            );
+            let tx_hash = 0x1000 + block_number;
+            batch.add_transaction_context(
+                Felt::from(tx_hash),
+                block_number,
+                Some(Felt::from(0x99_u64)),
+                vec![Felt::ONE, Felt::TWO],
+            );
+            batch.events.push(event(block_number, tx_hash));
+            batch.events.push(event(block_number, tx_hash));
+        }
+        batch
+    }
+
+    #[tokio::test]
+    async fn replays_archived_events_in_block_order() {
+        let db = EngineDb::new(EngineDbConfig {
+            path: ":memory:".to_string(),
+        })
+        .await
+        .unwrap();
+
+        db.archive_batch(&batch(&[10, 11])).await.unwrap();
+        db.archive_batch(&batch(&[30])).await.unwrap();
+        // Re-archiving is a no-op.
+        db.archive_batch(&batch(&[11])).await.unwrap();
+        assert_eq!(db.get_archived_block_range().await.unwrap(), Some((10, 30)));
+
+        let mut extractor = ArchiveExtractor::new(ArchiveConfig {
+            from_block: 11,
+            to_block: None,
+            batch_size: 10,
+        });
+
+        let first = extractor.extract(None, &db).await.unwrap();
+        assert_eq!(first.len(), 2);
+        assert!(first.events.iter().all(|e| e.block_number == Some(11)));
+        assert_eq!(first.blocks[&11].timestamp, 1_700_000_011);
+        let tx = &first.transactions[&Felt::from(0x1000_u64 + 11)];
+        assert_eq!(tx.sender_address, Some(Felt::from(0x99_u64)));
+        assert_eq!(tx.calldata, vec![Felt::ONE, Felt::TWO]);
+        assert!(!extractor.is_finished());
+
+        // Next range (21..=30) only holds block 30.
+        let second = extractor.extract(None, &db).await.unwrap();
+        assert_eq!(second.len(), 2);
+        assert_eq!(second.events[0].block_number, Some(30));
+        assert_eq!(second.events[0].block_hash, Some(Felt::from(30_u64)));
+        assert!(extractor.is_finished());
+    }
+
+    #[tokio::test]
+    async fn finishes_without_archive() {
+        let db = EngineDb::new(EngineDbConfig {
+            path: ":memory:".to_string(),
+        })
+        .await
+        .unwrap();
+
+        let mut extractor = ArchiveExtractor::new(ArchiveConfig::default());
+        assert!(extractor.extract(None, &db).await.unwrap().is_empty());
+        assert!(extractor.is_finished());
+    }
+}
@@ -2,26 +2,32 @@
 //!
 //! Fetches blocks in batches and extracts all events from transaction receipts.
 //! Supports automatic cursor persistence and retry logic for network failures.
+//!
+//! Blocks are read through a [`ChainReader`], the JSON-RPC [`RpcProvider`] by default.
 
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use futures::stream::{self, StreamExt};
-use starknet::core::types::MaybePreConfirmedBlockWithReceipts;
-use starknet::providers::jsonrpc::{HttpTransport, JsonRpcClient};
-use starknet::providers::{Provider, ProviderResponseData};
-use std::collections::HashMap;
+use starknet::core::types::{Felt, MaybePreConfirmedBlockWithReceipts};
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
+use torii_common::RpcProvider;
 
+use crate::error::{ToriiError, ToriiResult};
 use crate::etl::engine_db::EngineDb;
-use crate::etl::extractor::starknet_helpers::{
-    block_into_contexts, block_with_receipts_batch_from_block_range,
+use crate::etl::extractor::consolidation::{
+    clear_skip_range, consolidate_event_cursors, load_skip_ranges,
 };
+use crate::etl::extractor::starknet_helpers::block_into_contexts;
 
-use super::{ExtractionBatch, Extractor, RetryPolicy};
+use super::{
+    AdaptiveBatchConfig, AdaptiveBatchController, ChainReader, CycleFeedback, ExtractionBatch,
+    Extractor, ExtractorCapabilities, RetryPolicy,
+};
 
-const EXTRACTOR_TYPE: &str = "block_range";
-const STATE_KEY: &str = "last_block";
+pub(crate) const EXTRACTOR_TYPE: &str = "block_range";
+pub(crate) const STATE_KEY: &str = "last_block";
 
 /// Block range extractor configuration
 #[derive(Debug, Clone)]
@@ -44,6 +50,40 @@ pub struct BlockRangeConfig {
     /// Number of subrange RPC requests to execute concurrently.
     /// `0` means auto-tune from available CPU.
     pub rpc_parallelism: usize,
+
+    /// Block subranges of a batch fetched at the same time (0 = `rpc_parallelism`).
+    ///
+    /// Subranges are assembled in block order once fetched. When some of them fail,
+    /// only the missing blocks are fetched again, following `retry_policy`.
+    pub max_concurrent_block_fetches: usize,
+
+    /// Adaptive batch sizing (None = fixed `batch_size`).
+    ///
+    /// When set, `batch_size` is the initial size and the extractor adjusts it
+    /// from cycle latency feedback within the configured bounds.
+    pub adaptive_batch: Option<AdaptiveBatchConfig>,
+
+    /// Populate `TransactionContext::receipt` (actual fee, execution status) and keep
+    /// reverted transactions in `batch.transactions` so sinks can account for their fees.
+    ///
+    /// Receipts already come with `starknet_getBlockWithReceipts`, so this adds no RPC
+    /// calls, but it grows every batch by one context per reverted transaction.
+    pub include_receipts: bool,
+
+    /// Number of blocks to stay behind the chain head (0 = index up to the head).
+    ///
+    /// A block is only extracted once `confirmation_depth` blocks were built on top of
+    /// it, trading latency for protection against shallow reorgs. Also caps `to_block`.
+    pub confirmation_depth: u64,
+
+    /// Fold the event-mode per-contract cursors into the block-range cursor on startup.
+    ///
+    /// Used when switching an existing database from `event` to `block-range` mode; see
+    /// [`consolidate_event_cursors`](super::consolidate_event_cursors).
+    pub consolidate_event_cursors: bool,
+
+    /// Start from `from_block` even if a cursor was persisted (one-off range re-indexing).
+    pub ignore_saved_state: bool,
 }
 
 impl Default for BlockRangeConfig {
@@ -55,10 +95,109 @@ impl Default for BlockRangeConfig {
             batch_size: 100,
             retry_policy: RetryPolicy::default(),
             rpc_parallelism: 0,
+            max_concurrent_block_fetches: 0,
+            adaptive_batch: None,
+            include_receipts: false,
+            confirmation_depth: 0,
+            consolidate_event_cursors: false,
+            ignore_saved_state: false,
         }
     }
 }
 
+/// Resolves the first block to extract: cursor > saved state > `from_block`.
+///
+/// Shared by extractors using the block-range cursor (`block:N`, persisted under
+/// the `block_range` extractor state), so they can resume from each other.
+pub(crate) async fn resolve_start_block(
+    cursor: Option<String>,
+    engine_db: &EngineDb,
+    from_block: u64,
+) -> Result<u64> {
+    if let Some(cursor_str) = cursor {
+        // Parse cursor: "block:N"
+        let Some(block_str) = cursor_str.strip_prefix("block:") else {
+            anyhow::bail!("Invalid cursor format: expected 'block:N', got '{cursor_str}'");
+        };
+        let block = block_str
+            .parse::<u64>()
+            .context("Invalid cursor format")?
+            .saturating_add(1); // Resume from next block
+        tracing::info!(
+            target: "torii::etl::block_range",
+            "Resuming from cursor: block {}",
+            block
+        );
+        return Ok(block);
+    }
+
+    // Try loading from EngineDb
+    if let Some(saved_state) = engine_db
+        .get_extractor_state(EXTRACTOR_TYPE, STATE_KEY)
+        .await?
+    {
+        let block = saved_state
+            .parse::<u64>()
+            .context("Invalid saved state")?
+            .saturating_add(1); // Resume from next block
+        tracing::info!(
+            target: "torii::etl::block_range",
+            "Resuming from saved state: block {}",
+            block
+        );
+        return Ok(block);
+    }
+
+    // Start from config
+    tracing::info!(
+        target: "torii::etl::block_range",
+        "Starting from configured block: {}",
+        from_block
+    );
+    Ok(from_block)
+}
+
+/// Persists a block-range cursor (`block:N`); other cursor formats are ignored.
+pub(crate) async fn commit_block_cursor(cursor: &str, engine_db: &EngineDb) -> Result<()> {
+    if let Some(block_str) = cursor.strip_prefix("block:") {
+        let block_num: u64 = block_str.parse().context("Invalid cursor format")?;
+        engine_db
+            .set_extractor_state(EXTRACTOR_TYPE, STATE_KEY, &block_num.to_string())
+            .await
+            .context("Failed to commit cursor")?;
+        tracing::debug!(
+            target: "torii::etl::block_range",
+            "Committed cursor: block {}",
+            block_num
+        );
+    }
+    Ok(())
+}
+
+/// Rewinds the persisted block-range cursor to `block` if it is ahead of it.
+pub(crate) async fn rewind_block_cursor(block: u64, engine_db: &EngineDb) -> Result<()> {
+    let Some(saved_state) = engine_db
+        .get_extractor_state(EXTRACTOR_TYPE, STATE_KEY)
+        .await?
+    else {
+        return Ok(());
+    };
+    let saved_block = saved_state.parse::<u64>().context("Invalid saved state")?;
+    if saved_block > block {
+        engine_db
+            .set_extractor_state(EXTRACTOR_TYPE, STATE_KEY, &block.to_string())
+            .await
+            .context("Failed to rewind cursor")?;
+        tracing::info!(
+            target: "torii::etl::block_range",
+            from = saved_block,
+            to = block,
+            "Rewound cursor"
+        );
+    }
+    Ok(())
+}
+
 #[derive(Debug)]
 struct PreparedBatch {
     next_block: u64,
@@ -81,11 +220,16 @@ struct PreparedBatch {
 /// - Returns empty batch (with `is_finished() = false`)
 /// - Caller should wait and retry (the mainloop handles this)
 /// - On next call, checks for new blocks
-/// Block range extractor using JsonRpcClient with HTTP transport.
+///
+/// # Chain Access
+///
+/// Blocks come from a [`ChainReader`]: the JSON-RPC [`RpcProvider`] (with optionally
+/// rate-limited HTTP transport) by default, or a custom reader for appchains with a
+/// non-standard RPC. Batch subranges are capped at the reader's `max_chunk`.
 #[derive(Debug)]
-pub struct BlockRangeExtractor {
-    /// Provider to fetch data from.
-    provider: Arc<JsonRpcClient<HttpTransport>>,
+pub struct BlockRangeExtractor<R: ChainReader = RpcProvider> {
+    /// Reader to fetch blocks from.
+    provider: Arc<R>,
 
     /// Configuration.
     config: BlockRangeConfig,
@@ -95,9 +239,15 @@ pub struct BlockRangeExtractor {
 
     /// Whether we've reached the configured end block.
     reached_end: bool,
+
+    /// Adaptive batch size controller (None = fixed batch size).
+    batch_controller: Option<AdaptiveBatchController>,
+
+    /// Contracts already indexed in event mode, mapped to their last processed block.
+    skip_ranges: HashMap<Felt, u64>,
 }
 
-impl BlockRangeExtractor {
+impl<R: ChainReader> BlockRangeExtractor<R> {
     fn resolved_rpc_parallelism(config: &BlockRangeConfig) -> usize {
         if config.rpc_parallelism == 0 {
             std::thread::available_parallelism()
@@ -108,73 +258,98 @@ impl BlockRangeExtractor {
         }
     }
 
-    /// Creates a new block range extractor with the given provider.
+    /// Creates a new block range extractor reading blocks from `provider`.
+    ///
+    /// `include_receipts` is turned off (with a warning) if the reader does not
+    /// support receipts.
     ///
     /// # Example
     ///
     /// ```rust,ignore
     /// use starknet::providers::jsonrpc::{JsonRpcClient, HttpTransport};
+    /// use torii_common::RateLimitedTransport;
     ///
-    /// let provider = JsonRpcClient::new(HttpTransport::new(url));
+    /// let transport = RateLimitedTransport::new(HttpTransport::new(url)).with_limiter(limiter);
+    /// let provider = JsonRpcClient::new(transport);
     /// let extractor = BlockRangeExtractor::new(Arc::new(provider), config);
     /// ```
-    pub fn new(provider: Arc<JsonRpcClient<HttpTransport>>, config: BlockRangeConfig) -> Self {
+    pub fn new(provider: Arc<R>, mut config: BlockRangeConfig) -> Self {
+        if config.include_receipts && !provider.capabilities().supports_receipts {
+            tracing::warn!(
+                target: "torii::etl::block_range",
+                "Chain reader does not support receipts, not populating transaction receipts"
+            );
+            config.include_receipts = false;
+        }
+        let batch_controller = config.adaptive_batch.clone().and_then(|adaptive| {
+            if let Err(e) = adaptive.validate() {
+                tracing::warn!(
+                    target: "torii::etl::block_range",
+                    error = %e,
+                    "Invalid adaptive batch config, using fixed batch size"
+                );
+                return None;
+            }
+            Some(AdaptiveBatchController::new(adaptive, config.batch_size))
+        });
         Self {
             provider,
             config,
             current_block: 0,
             reached_end: false,
+            batch_controller,
+            skip_ranges: HashMap::new(),
         }
     }
 
+    /// Current batch size (adaptive when configured, fixed otherwise).
+    pub fn batch_size(&self) -> u64 {
+        self.batch_controller
+            .as_ref()
+            .map_or(self.config.batch_size, AdaptiveBatchController::batch_size)
+    }
+
     /// Initializes the extractor state from cursor or config.
     async fn initialize(&mut self, cursor: Option<String>, engine_db: &EngineDb) -> Result<()> {
-        // Priority: cursor > saved state > config.from_block
-        if let Some(cursor_str) = cursor {
-            // Parse cursor: "block:N"
-            if let Some(block_str) = cursor_str.strip_prefix("block:") {
-                self.current_block = block_str
-                    .parse::<u64>()
-                    .context("Invalid cursor format")?
-                    .saturating_add(1); // Resume from next block
-                tracing::info!(
-                    target: "torii::etl::block_range",
-                    "Resuming from cursor: block {}",
-                    self.current_block
-                );
-            } else {
-                anyhow::bail!("Invalid cursor format: expected 'block:N', got '{cursor_str}'");
-            }
-        } else {
-            // Try loading from EngineDb
-            if let Some(saved_state) = engine_db
-                .get_extractor_state(EXTRACTOR_TYPE, STATE_KEY)
-                .await?
-            {
-                self.current_block = saved_state
-                    .parse::<u64>()
-                    .context("Invalid saved state")?
-                    .saturating_add(1); // Resume from next block
-                tracing::info!(
-                    target: "torii::etl::block_range",
-                    "Resuming from saved state: block {}",
-                    self.current_block
-                );
-            } else {
-                // Start from config
-                self.current_block = self.config.from_block;
-                tracing::info!(
-                    target: "torii::etl::block_range",
-                    "Starting from configured block: {}",
-                    self.current_block
-                );
-            }
+        if self.config.ignore_saved_state {
+            self.current_block = self.config.from_block;
+            self.skip_ranges = load_skip_ranges(engine_db).await?;
+            return Ok(());
         }
-
+        if self.config.consolidate_event_cursors && cursor.is_none() {
+            consolidate_event_cursors(engine_db).await?;
+        }
+        self.current_block = resolve_start_block(cursor, engine_db, self.config.from_block).await?;
+        self.skip_ranges = load_skip_ranges(engine_db).await?;
         Ok(())
     }
 
-    /// Fetches a batch of blocks with receipts using JSON-RPC batch requests.
+    /// Drops events of contracts already processed up to a skip range.
+    fn apply_skip_ranges(&self, batch: &mut ExtractionBatch) {
+        if self.skip_ranges.is_empty() {
+            return;
+        }
+        let before = batch.events.len();
+        batch.events.retain(|event| {
+            let Some(until_block) = self.skip_ranges.get(&event.from_address) else {
+                return true;
+            };
+            event
+                .block_number
+                .is_none_or(|block_number| block_number > *until_block)
+        });
+        let skipped = before - batch.events.len();
+        if skipped > 0 {
+            ::metrics::counter!("torii_block_range_skipped_events_total").increment(skipped as u64);
+            tracing::debug!(
+                target: "torii::etl::block_range",
+                skipped,
+                "Skipped events already processed in event mode"
+            );
+        }
+    }
+
+    /// Fetches a batch of blocks with receipts with one reader call.
     ///
     /// Every block in the range **must** be a mined block on Starknet. Otherwise, the request will fail.
     ///
@@ -186,66 +361,122 @@ impl BlockRangeExtractor {
     /// # Returns
     ///
     /// A vector of blocks with receipts.
-    async fn fetch_blocks_batch_with(
-        provider: Arc<JsonRpcClient<HttpTransport>>,
-        retry_policy: RetryPolicy,
+    async fn fetch_blocks_batch(
+        provider: Arc<R>,
         from_block: u64,
         to_block: u64,
     ) -> Result<Vec<MaybePreConfirmedBlockWithReceipts>> {
-        let requests = block_with_receipts_batch_from_block_range(from_block, to_block);
         let fetch_start = Instant::now();
-        let responses = retry_policy
-            .execute(|| {
-                let provider = provider.clone();
-                let requests_ref = &requests;
-                async move {
-                    provider
-                        .batch_requests(requests_ref)
-                        .await
-                        .context("Failed to execute batch request for blocks")
-                }
-            })
-            .await;
+        let blocks = provider.blocks_with_receipts(from_block, to_block).await;
         ::metrics::histogram!("torii_rpc_block_range_fetch_duration_seconds")
             .record(fetch_start.elapsed().as_secs_f64());
 
-        let responses = match responses {
-            Ok(responses) => {
-                ::metrics::counter!(
-                    "torii_rpc_requests_total",
-                    "method" => "get_block_with_receipts_batch",
-                    "status" => "ok"
-                )
-                .increment(1);
-                responses
-            }
-            Err(err) => {
-                ::metrics::counter!(
-                    "torii_rpc_requests_total",
-                    "method" => "get_block_with_receipts_batch",
-                    "status" => "error"
-                )
-                .increment(1);
-                return Err(err);
-            }
+        let status = if blocks.is_ok() { "ok" } else { "error" };
+        ::metrics::counter!(
+            "torii_rpc_requests_total",
+            "method" => "get_block_with_receipts_batch",
+            "status" => status
+        )
+        .increment(1);
+        blocks
+    }
+
+    /// Fetches blocks `from_block..=to_block`, split into subranges fetched concurrently.
+    ///
+    /// Subranges (at most `max_chunk` blocks each) are assembled in block order. Failed
+    /// subranges are fetched again (and only them) with the backoff of the retry policy,
+    /// until it runs out of retries.
+    async fn fetch_blocks_concurrently(
+        provider: Arc<R>,
+        config: &BlockRangeConfig,
+        from_block: u64,
+        to_block: u64,
+    ) -> Result<Vec<MaybePreConfirmedBlockWithReceipts>> {
+        let concurrency = if config.max_concurrent_block_fetches > 0 {
+            config.max_concurrent_block_fetches
+        } else {
+            Self::resolved_rpc_parallelism(config)
         };
+        ::metrics::gauge!("torii_rpc_parallelism").set(concurrency as f64);
 
-        let mut blocks = Vec::with_capacity((to_block - from_block + 1) as usize);
-        for (idx, response) in responses.into_iter().enumerate() {
-            let block_num = from_block + idx as u64;
-            match response {
-                ProviderResponseData::GetBlockWithReceipts(block) => {
-                    blocks.push(block);
-                }
-                _ => {
-                    anyhow::bail!(
-                        "Unexpected response type for block {block_num}: expected GetBlockWithReceipts"
-                    );
+        let total_blocks = (to_block - from_block + 1) as usize;
+        let mut chunk_size = total_blocks.div_ceil(concurrency).max(1) as u64;
+        if let Some(max_chunk) = provider.capabilities().max_chunk {
+            chunk_size = chunk_size.min(max_chunk.max(1));
+        }
+        let mut pending: Vec<(u64, u64)> = (from_block..=to_block)
+            .step_by(chunk_size as usize)
+            .map(|start| (start, (start + chunk_size - 1).min(to_block)))
+            .collect();
+        let mut fetched = BTreeMap::new();
+        let retry_policy = &config.retry_policy;
+        let mut backoff = retry_policy.initial_backoff;
+        let mut attempt = 0;
+
+        loop {
+            let results = stream::iter(pending)
+                .map(|(range_start, range_end)| {
+                    let provider = provider.clone();
+                    async move {
+                        let chunk_fetch_start = Instant::now();
+                        let blocks =
+                            Self::fetch_blocks_batch(provider, range_start, range_end).await;
+                        ::metrics::histogram!(
+                            "torii_rpc_chunk_duration_seconds",
+                            "extractor" => "block_range",
+                            "method" => "get_block_with_receipts_batch"
+                        )
+                        .record(chunk_fetch_start.elapsed().as_secs_f64());
+                        ((range_start, range_end), blocks)
+                    }
+                })
+                .buffer_unordered(concurrency)
+                .collect::<Vec<_>>()
+                .await;
+
+            pending = Vec::new();
+            let mut last_error = None;
+            for (range, blocks) in results {
+                match blocks {
+                    Ok(blocks) => {
+                        fetched.insert(range.0, blocks);
+                    }
+                    Err(err) => {
+                        pending.push(range);
+                        last_error = Some(err);
+                    }
                 }
             }
+            let Some(err) = last_error else {
+                break;
+            };
+
+            attempt += 1;
+            ::metrics::counter!("torii_rpc_retries_total").increment(1);
+            let missing_blocks: u64 = pending.iter().map(|(start, end)| end - start + 1).sum();
+            if attempt > retry_policy.max_retries {
+                return Err(err.context(format!(
+                    "Failed to fetch {missing_blocks} of {total_blocks} blocks after {attempt} attempts"
+                )));
+            }
+            ::metrics::counter!("torii_block_range_partial_refetch_total").increment(1);
+            tracing::warn!(
+                target: "torii::etl::block_range",
+                error = ?err,
+                missing_ranges = pending.len(),
+                missing_blocks,
+                attempt,
+                "Block fetch partially failed, fetching the missing blocks again in {:?}",
+                backoff
+            );
+            tokio::time::sleep(retry_policy.jittered(backoff)).await;
+            backoff = Duration::from_secs_f64(
+                (backoff.as_secs_f64() * retry_policy.backoff_multiplier)
+                    .min(retry_policy.max_backoff.as_secs_f64()),
+            );
         }
 
-        Ok(blocks)
+        Ok(fetched.into_values().flatten().collect())
     }
 
     /// Check if we've reached the end of the configured range
@@ -258,16 +489,17 @@ impl BlockRangeExtractor {
     }
 
     async fn prepare_batch_for(
-        provider: Arc<JsonRpcClient<HttpTransport>>,
+        provider: Arc<R>,
         config: BlockRangeConfig,
         current_block: u64,
     ) -> Result<PreparedBatch> {
         let total_start = Instant::now();
         let chain_head = provider.block_number().await?;
 
-        let batch_end = if let Some(to_block) = config.to_block {
+        let fixed_to_block = config.to_block.filter(|_| config.confirmation_depth == 0);
+        let batch_end = if let Some(to_block) = fixed_to_block {
             (current_block + config.batch_size - 1).min(to_block)
-        } else if current_block > chain_head {
+        } else if current_block.saturating_add(config.confirmation_depth) > chain_head {
             let batch = ExtractionBatch {
                 events: Vec::new(),
                 blocks: HashMap::new(),
@@ -282,7 +514,10 @@ impl BlockRangeExtractor {
                 batch,
             });
         } else {
-            (current_block + config.batch_size - 1).min(chain_head)
+            let confirmed_head = chain_head - config.confirmation_depth;
+            (current_block + config.batch_size - 1)
+                .min(confirmed_head)
+                .min(config.to_block.unwrap_or(u64::MAX))
         };
 
         tracing::info!(
@@ -294,44 +529,8 @@ impl BlockRangeExtractor {
         );
 
         let fetch_start = Instant::now();
-        let rpc_parallelism = Self::resolved_rpc_parallelism(&config);
-        ::metrics::gauge!("torii_rpc_parallelism").set(rpc_parallelism as f64);
-
-        let total_blocks = (batch_end - current_block + 1) as usize;
-        let chunk_size = total_blocks.div_ceil(rpc_parallelism).max(1) as u64;
-        let mut fetched_ranges = stream::iter(
-            (current_block..=batch_end)
-                .step_by(chunk_size as usize)
-                .map(|start| (start, (start + chunk_size - 1).min(batch_end)))
-                .enumerate(),
-        )
-        .map(|(range_index, (range_start, range_end))| {
-            let provider = provider.clone();
-            let retry_policy = config.retry_policy.clone();
-            async move {
-                let chunk_fetch_start = Instant::now();
-                let blocks =
-                    Self::fetch_blocks_batch_with(provider, retry_policy, range_start, range_end)
-                        .await?;
-                ::metrics::histogram!(
-                    "torii_rpc_chunk_duration_seconds",
-                    "extractor" => "block_range",
-                    "method" => "get_block_with_receipts_batch"
-                )
-                .record(chunk_fetch_start.elapsed().as_secs_f64());
-                Ok::<_, anyhow::Error>((range_index, blocks))
-            }
-        })
-        .buffer_unordered(rpc_parallelism)
-        .collect::<Vec<_>>()
-        .await
-        .into_iter()
-        .collect::<anyhow::Result<Vec<_>>>()?;
-        fetched_ranges.sort_by_key(|(range_index, _)| *range_index);
-        let blocks = fetched_ranges
-            .into_iter()
-            .flat_map(|(_, blocks)| blocks)
-            .collect::<Vec<_>>();
+        let blocks =
+            Self::fetch_blocks_concurrently(provider, &config, current_block, batch_end).await?;
         let fetch_ms = fetch_start.elapsed().as_millis();
 
         let transform_start = Instant::now();
@@ -342,7 +541,7 @@ impl BlockRangeExtractor {
         let mut all_deployed_contracts = Vec::new();
 
         for block in blocks {
-            let block_data = block_into_contexts(block)?;
+            let block_data = block_into_contexts(block, config.include_receipts)?;
 
             all_events.reserve(block_data.events.len());
             transactions_map.reserve(block_data.transactions.len());
@@ -396,27 +595,103 @@ impl BlockRangeExtractor {
 }
 
 #[async_trait]
-impl Extractor for BlockRangeExtractor {
+impl<R: ChainReader + 'static> Extractor for BlockRangeExtractor<R> {
     fn is_finished(&self) -> bool {
         self.reached_end
     }
     fn set_start_block(&mut self, start_block: u64) {
         self.current_block = start_block.max(self.current_block);
     }
-    async fn commit_cursor(&mut self, cursor: &str, engine_db: &EngineDb) -> Result<()> {
-        if let Some(block_str) = cursor.strip_prefix("block:") {
-            let block_num: u64 = block_str.parse().context("Invalid cursor format")?;
-            engine_db
-                .set_extractor_state(EXTRACTOR_TYPE, STATE_KEY, &block_num.to_string())
-                .await
-                .context("Failed to commit cursor")?;
+    async fn commit_cursor(&mut self, cursor: &str, engine_db: &EngineDb) -> ToriiResult<()> {
+        commit_block_cursor(cursor, engine_db).await?;
+        let Some(block) = cursor
+            .strip_prefix("block:")
+            .and_then(|block| block.parse::<u64>().ok())
+        else {
+            return Ok(());
+        };
+        let passed: Vec<Felt> = self
+            .skip_ranges
+            .iter()
+            .filter(|(_, until_block)| **until_block <= block)
+            .map(|(contract, _)| *contract)
+            .collect();
+        for contract in passed {
+            clear_skip_range(engine_db, contract).await?;
+            self.skip_ranges.remove(&contract);
+        }
+        Ok(())
+    }
+
+    async fn rewind_cursor(&mut self, block: u64, engine_db: &EngineDb) -> ToriiResult<bool> {
+        rewind_block_cursor(block, engine_db).await?;
+        if self.current_block != 0 {
+            self.current_block = self.current_block.min(block.saturating_add(1));
+            self.reached_end = false;
+        }
+        Ok(true)
+    }
+
+    async fn chain_head(&self) -> ToriiResult<Option<u64>> {
+        let head = self
+            .provider
+            .block_number()
+            .await
+            .context("Failed to fetch chain head")
+            .map_err(ToriiError::Extractor)?;
+        Ok(Some(head))
+    }
+
+    fn capabilities(&self) -> ExtractorCapabilities {
+        // Only mined blocks are extracted.
+        ExtractorCapabilities {
+            supports_pending: false,
+            ..self.provider.capabilities()
+        }
+    }
+
+    fn observe_cycle(&mut self, feedback: &CycleFeedback) {
+        let Some(controller) = self.batch_controller.as_mut() else {
+            return;
+        };
+
+        let previous = controller.batch_size();
+        let batch_size = controller.observe(feedback);
+        ::metrics::gauge!("torii_etl_adaptive_batch_size", "extractor" => EXTRACTOR_TYPE)
+            .set(batch_size as f64);
+        if batch_size != previous {
             tracing::debug!(
                 target: "torii::etl::block_range",
-                "Committed cursor: block {}",
-                block_num
+                previous,
+                batch_size,
+                cycle_ms = feedback.total().as_millis() as u64,
+                "Adjusted adaptive batch size"
             );
         }
-        Ok(())
+    }
+
+    fn observe_backpressure(&mut self) {
+        let Some(controller) = self.batch_controller.as_mut() else {
+            return;
+        };
+
+        let batch_size = controller.throttle();
+        ::metrics::gauge!("torii_etl_adaptive_batch_size", "extractor" => EXTRACTOR_TYPE)
+            .set(batch_size as f64);
+        tracing::debug!(
+            target: "torii::etl::block_range",
+            batch_size,
+            "Shrunk adaptive batch size, sinks are falling behind"
+        );
+    }
+
+    fn set_batch_size(&mut self, blocks: u64) -> bool {
+        self.config.batch_size = blocks.max(1);
+        if let Some(controller) = self.batch_controller.as_mut() {
+            *controller =
+                AdaptiveBatchController::new(controller.config().clone(), self.config.batch_size);
+        }
+        true
     }
 
     fn as_any(&self) -> &dyn std::any::Any {
@@ -427,7 +702,7 @@ impl Extractor for BlockRangeExtractor {
         &mut self,
         cursor: Option<String>,
         engine_db: &EngineDb,
-    ) -> Result<ExtractionBatch> {
+    ) -> ToriiResult<ExtractionBatch> {
         // Initialize only on first call - after that, extractor maintains its own state
         // The cursor parameter is for initial resume from checkpoint, not every iteration
         if self.current_block == 0 {
@@ -448,13 +723,12 @@ impl Extractor for BlockRangeExtractor {
             return Ok(ExtractionBatch::empty());
         }
 
-        let prepared = Self::prepare_batch_for(
-            self.provider.clone(),
-            self.config.clone(),
-            self.current_block,
-        )
-        .await?;
+        let mut config = self.config.clone();
+        config.batch_size = self.batch_size();
+        let mut prepared =
+            Self::prepare_batch_for(self.provider.clone(), config, self.current_block).await?;
         self.current_block = prepared.next_block;
+        self.apply_skip_ranges(&mut prepared.batch);
 
         tracing::debug!(
             target: "torii::etl::block_range",
@@ -465,3 +739,198 @@ impl Extractor for BlockRangeExtractor {
         Ok(prepared.batch)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::etl::engine_db::EngineDbConfig;
+    use std::sync::Mutex;
+
+    /// Appchain-like reader: serves at most 2 blocks per call and no receipts.
+    #[derive(Debug, Default)]
+    struct ChunkedReader {
+        requested: Mutex<Vec<(u64, u64)>>,
+    }
+
+    fn empty_block(number: u64) -> MaybePreConfirmedBlockWithReceipts {
+        serde_json::from_value(serde_json::json!({
+            "status": "ACCEPTED_ON_L2",
+            "block_hash": format!("{:#x}", number + 0x100),
+            "parent_hash": format!("{:#x}", number + 0xff),
+            "block_number": number,
+            "new_root": "0x0",
+            "timestamp": 1000 + number,
+            "sequencer_address": "0x0",
+            "l1_gas_price": { "price_in_fri": "0x1", "price_in_wei": "0x1" },
+            "l2_gas_price": { "price_in_fri": "0x1", "price_in_wei": "0x1" },
+            "l1_data_gas_price": { "price_in_fri": "0x1", "price_in_wei": "0x1" },
+            "l1_da_mode": "BLOB",
+            "starknet_version": "0.14.0",
+            "transactions": [],
+        }))
+        .unwrap()
+    }
+
+    #[async_trait]
+    impl ChainReader for ChunkedReader {
+        async fn block_number(&self) -> Result<u64> {
+            Ok(9)
+        }
+
+        async fn blocks_with_receipts(
+            &self,
+            from_block: u64,
+            to_block: u64,
+        ) -> Result<Vec<MaybePreConfirmedBlockWithReceipts>> {
+            self.requested.lock().unwrap().push((from_block, to_block));
+            Ok((from_block..=to_block).map(empty_block).collect())
+        }
+
+        fn capabilities(&self) -> ExtractorCapabilities {
+            ExtractorCapabilities {
+                max_chunk: Some(2),
+                ..ExtractorCapabilities::default()
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn custom_reader_chunks_are_capped_at_max_chunk() {
+        let db = EngineDb::new(EngineDbConfig {
+            path: ":memory:".to_string(),
+        })
+        .await
+        .unwrap();
+        let reader = Arc::new(ChunkedReader::default());
+        let mut extractor = BlockRangeExtractor::new(
+            reader.clone(),
+            BlockRangeConfig {
+                from_block: 1,
+                to_block: Some(5),
+                batch_size: 5,
+                rpc_parallelism: 1,
+                include_receipts: true,
+                ..BlockRangeConfig::default()
+            },
+        );
+        assert!(!extractor.config.include_receipts);
+        assert_eq!(
+            extractor.capabilities(),
+            ExtractorCapabilities {
+                supports_receipts: false,
+                supports_pending: false,
+                max_chunk: Some(2),
+            }
+        );
+
+        let batch = extractor.extract(None, &db).await.unwrap();
+        let mut blocks: Vec<u64> = batch.blocks.keys().copied().collect();
+        blocks.sort_unstable();
+        assert_eq!(blocks, vec![1, 2, 3, 4, 5]);
+        assert_eq!(batch.cursor.as_deref(), Some("block:5"));
+
+        let mut requested = reader.requested.lock().unwrap().clone();
+        requested.sort_unstable();
+        assert_eq!(requested, vec![(1, 2), (3, 4), (5, 5)]);
+    }
+
+    /// Reader failing the fetches of the ranges starting at a `failures` key, that many
+    /// times each.
+    #[derive(Debug, Default)]
+    struct FlakyReader {
+        failures: Mutex<HashMap<u64, usize>>,
+        requested: Mutex<Vec<(u64, u64)>>,
+    }
+
+    impl FlakyReader {
+        fn failing(failures: impl IntoIterator<Item = (u64, usize)>) -> Arc<Self> {
+            Arc::new(Self {
+                failures: Mutex::new(failures.into_iter().collect()),
+                requested: Mutex::default(),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl ChainReader for FlakyReader {
+        async fn block_number(&self) -> Result<u64> {
+            Ok(100)
+        }
+
+        async fn blocks_with_receipts(
+            &self,
+            from_block: u64,
+            to_block: u64,
+        ) -> Result<Vec<MaybePreConfirmedBlockWithReceipts>> {
+            self.requested.lock().unwrap().push((from_block, to_block));
+            if let Some(remaining) = self.failures.lock().unwrap().get_mut(&from_block) {
+                if *remaining > 0 {
+                    *remaining -= 1;
+                    anyhow::bail!("blocks {from_block}-{to_block} unavailable");
+                }
+            }
+            Ok((from_block..=to_block).map(empty_block).collect())
+        }
+    }
+
+    fn concurrent_config(max_retries: u32) -> BlockRangeConfig {
+        BlockRangeConfig {
+            max_concurrent_block_fetches: 4,
+            retry_policy: RetryPolicy::new(
+                max_retries,
+                Duration::from_millis(1),
+                Duration::from_millis(1),
+                1.0,
+            ),
+            ..BlockRangeConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn only_failed_subranges_are_fetched_again() {
+        let reader = FlakyReader::failing([(4, 1)]);
+        let blocks = BlockRangeExtractor::fetch_blocks_concurrently(
+            reader.clone(),
+            &concurrent_config(2),
+            0,
+            7,
+        )
+        .await
+        .unwrap();
+
+        let numbers: Vec<u64> = blocks
+            .iter()
+            .map(|block| match block {
+                MaybePreConfirmedBlockWithReceipts::Block(block) => block.block_number,
+                MaybePreConfirmedBlockWithReceipts::PreConfirmedBlock(_) => unreachable!(),
+            })
+            .collect();
+        assert_eq!(numbers, (0..=7).collect::<Vec<_>>());
+        let mut requested = reader.requested.lock().unwrap().clone();
+        requested.sort_unstable();
+        assert_eq!(requested, vec![(0, 1), (2, 3), (4, 5), (4, 5), (6, 7)]);
+    }
+
+    #[tokio::test]
+    async fn fetch_gives_up_once_retries_run_out() {
+        let reader = FlakyReader::failing([(2, usize::MAX), (6, usize::MAX)]);
+        let err = BlockRangeExtractor::fetch_blocks_concurrently(
+            reader.clone(),
+            &concurrent_config(1),
+            0,
+            7,
+        )
+        .await
+        .unwrap_err();
+
+        assert!(err
+            .to_string()
+            .contains("Failed to fetch 4 of 8 blocks after 2 attempts"));
+        let mut requested = reader.requested.lock().unwrap().clone();
+        requested.sort_unstable();
+        assert_eq!(
+            requested,
+            vec![(0, 1), (2, 3), (2, 3), (4, 5), (6, 7), (6, 7)]
+        );
+    }
+}
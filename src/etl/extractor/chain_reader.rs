@@ -0,0 +1,79 @@
+//! Chain access used by the block range extractor
+//!
+//! [`BlockRangeExtractor`](super::BlockRangeExtractor) only needs the chain head and
+//! ranges of blocks with their receipts. [`ChainReader`] is that surface: it is
+//! implemented for the JSON-RPC [`RpcProvider`], and L3s or appchains with a modified
+//! RPC schema can implement it for their own client instead of forking the extractor.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use starknet::core::types::MaybePreConfirmedBlockWithReceipts;
+use starknet::providers::{Provider, ProviderResponseData};
+use torii_common::RpcProvider;
+
+use super::starknet_helpers::block_with_receipts_batch_from_block_range;
+use super::ExtractorCapabilities;
+
+/// Source of mined blocks for the block range extractor
+#[async_trait]
+pub trait ChainReader: Send + Sync {
+    /// Latest block number of the chain.
+    async fn block_number(&self) -> Result<u64>;
+
+    /// Blocks `from_block..=to_block` with their receipts, in block order.
+    ///
+    /// Every block of the range is mined (the extractor stays behind the head).
+    async fn blocks_with_receipts(
+        &self,
+        from_block: u64,
+        to_block: u64,
+    ) -> Result<Vec<MaybePreConfirmedBlockWithReceipts>>;
+
+    /// What the source supports. `max_chunk` caps the blocks requested per call of
+    /// [`blocks_with_receipts`](Self::blocks_with_receipts).
+    ///
+    /// Defaults to receipts support with unbounded chunks.
+    fn capabilities(&self) -> ExtractorCapabilities {
+        ExtractorCapabilities {
+            supports_receipts: true,
+            ..ExtractorCapabilities::default()
+        }
+    }
+}
+
+#[async_trait]
+impl ChainReader for RpcProvider {
+    async fn block_number(&self) -> Result<u64> {
+        Ok(Provider::block_number(self).await?)
+    }
+
+    /// Fetches the range with one JSON-RPC batch request.
+    async fn blocks_with_receipts(
+        &self,
+        from_block: u64,
+        to_block: u64,
+    ) -> Result<Vec<MaybePreConfirmedBlockWithReceipts>> {
+        let requests = block_with_receipts_batch_from_block_range(from_block, to_block);
+        let responses = self
+            .batch_requests(&requests)
+            .await
+            .context("Failed to execute batch request for blocks")?;
+
+        let mut blocks = Vec::with_capacity((to_block - from_block + 1) as usize);
+        for (idx, response) in responses.into_iter().enumerate() {
+            let block_num = from_block + idx as u64;
+            match response {
+                ProviderResponseData::GetBlockWithReceipts(block) => {
+                    blocks.push(block);
+                }
+                _ => {
+                    anyhow::bail!(
+                        "Unexpected response type for block {block_num}: expected GetBlockWithReceipts"
+                    );
+                }
+            }
+        }
+
+        Ok(blocks)
+    }
+}
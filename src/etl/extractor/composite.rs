@@ -40,11 +40,11 @@
 //!     .build();
 //! ```
 
-use anyhow::Result;
 use async_trait::async_trait;
 
+use crate::error::ToriiResult;
 use crate::etl::engine_db::EngineDb;
-use crate::etl::extractor::{ExtractionBatch, Extractor};
+use crate::etl::extractor::{ExtractionBatch, Extractor, ExtractorCapabilities};
 
 /// Composite extractor that wraps multiple extractors.
 ///
@@ -105,7 +105,7 @@ impl Extractor for CompositeExtractor {
         &mut self,
         _cursor: Option<String>,
         engine_db: &EngineDb,
-    ) -> Result<ExtractionBatch> {
+    ) -> ToriiResult<ExtractionBatch> {
         if self.extractors.is_empty() {
             return Ok(ExtractionBatch::empty());
         }
@@ -150,7 +150,7 @@ impl Extractor for CompositeExtractor {
         self.extractors.iter().all(|e| e.is_finished())
     }
 
-    async fn commit_cursor(&mut self, _cursor: &str, engine_db: &EngineDb) -> Result<()> {
+    async fn commit_cursor(&mut self, _cursor: &str, engine_db: &EngineDb) -> ToriiResult<()> {
         // Delegate to all extractors - each manages its own state
         for extractor in &mut self.extractors {
             extractor.commit_cursor("", engine_db).await?;
@@ -158,6 +158,47 @@ impl Extractor for CompositeExtractor {
         Ok(())
     }
 
+    async fn rewind_cursor(&mut self, block: u64, engine_db: &EngineDb) -> ToriiResult<bool> {
+        let mut rewound = false;
+        for extractor in &mut self.extractors {
+            rewound |= extractor.rewind_cursor(block, engine_db).await?;
+        }
+        Ok(rewound)
+    }
+
+    async fn chain_head(&self) -> ToriiResult<Option<u64>> {
+        let mut head = None;
+        for extractor in &self.extractors {
+            head = head.max(extractor.chain_head().await?);
+        }
+        Ok(head)
+    }
+
+    /// Capabilities shared by every child extractor.
+    fn capabilities(&self) -> ExtractorCapabilities {
+        self.extractors
+            .iter()
+            .map(|extractor| extractor.capabilities())
+            .reduce(ExtractorCapabilities::intersect)
+            .unwrap_or_default()
+    }
+
+    fn set_events_per_cycle(&mut self, events: u64) -> bool {
+        let mut applied = false;
+        for extractor in &mut self.extractors {
+            applied |= extractor.set_events_per_cycle(events);
+        }
+        applied
+    }
+
+    fn set_batch_size(&mut self, blocks: u64) -> bool {
+        let mut applied = false;
+        for extractor in &mut self.extractors {
+            applied |= extractor.set_batch_size(blocks);
+        }
+        applied
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -0,0 +1,224 @@
+//! Consolidation of event-mode cursors into the block-range cursor
+//!
+//! The event extractor persists one cursor per contract, while the block-range
+//! extractor follows a single global cursor. When an indexer switches from
+//! `event` to `block-range` mode, the per-contract cursors are folded into a global
+//! starting block (the minimum of the contract cursors), and contracts that were
+//! already indexed past that block get a skip range so their events are not
+//! processed twice.
+
+use anyhow::{Context, Result};
+use starknet::core::types::Felt;
+use std::collections::HashMap;
+
+use crate::etl::engine_db::EngineDb;
+
+use super::block_range;
+use super::event;
+
+/// Extractor state type holding the skip ranges (`state_key` = contract, value = last block).
+pub(crate) const SKIP_EXTRACTOR_TYPE: &str = "block_range_skip";
+
+/// Events of `contract` up to and including `until_block` were already processed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SkipRange {
+    pub contract: Felt,
+    pub until_block: u64,
+}
+
+/// Outcome of folding per-contract cursors into the block-range cursor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CursorConsolidation {
+    /// First block the block-range extractor processes.
+    pub start_block: u64,
+    /// Contracts whose events are dropped up to their `until_block`.
+    pub skip_ranges: Vec<SkipRange>,
+}
+
+impl CursorConsolidation {
+    /// Plans the consolidation of per-contract cursors (`(contract, next_block)`).
+    ///
+    /// `resume_block` is the block the block-range cursor already resumes from, if any;
+    /// it wins over the contract cursors since every contract was processed up to it.
+    /// Returns `None` when there are no contract cursors.
+    pub fn plan(resume_block: Option<u64>, cursors: &[(Felt, u64)]) -> Option<Self> {
+        let min_next = cursors.iter().map(|(_, next)| *next).min()?;
+        let start_block = resume_block.unwrap_or(min_next);
+
+        let mut skip_ranges: Vec<SkipRange> = cursors
+            .iter()
+            .filter(|(_, next)| *next > start_block)
+            .map(|(contract, next)| SkipRange {
+                contract: *contract,
+                until_block: next - 1,
+            })
+            .collect();
+        skip_ranges.sort_by_key(|range| range.contract);
+
+        Some(Self {
+            start_block,
+            skip_ranges,
+        })
+    }
+}
+
+/// Folds the persisted event-mode cursors into the block-range cursor.
+///
+/// Sets the block-range cursor to the minimum contract cursor when none exists yet,
+/// and replaces the persisted skip ranges. Event-mode states are left untouched, so
+/// running this again is a no-op and switching back to event mode keeps working.
+///
+/// A contract cursor holding a continuation token is treated as not having processed
+/// its current block, so that block is indexed again for that contract.
+pub async fn consolidate_event_cursors(
+    engine_db: &EngineDb,
+) -> Result<Option<CursorConsolidation>> {
+    let mut cursors = Vec::new();
+    for (state_key, state_value) in engine_db
+        .get_all_extractor_states(event::EXTRACTOR_TYPE)
+        .await?
+    {
+        let Ok(contract) = Felt::from_hex(&state_key) else {
+            continue;
+        };
+        let next_block = event::persisted_next_block(&state_value)
+            .with_context(|| format!("failed to parse event cursor for {state_key}"))?;
+        cursors.push((contract, next_block));
+    }
+
+    let resume_block = engine_db
+        .get_extractor_state(block_range::EXTRACTOR_TYPE, block_range::STATE_KEY)
+        .await?
+        .map(|saved| saved.parse::<u64>().context("Invalid saved state"))
+        .transpose()?
+        .map(|last_block| last_block.saturating_add(1));
+
+    let Some(consolidation) = CursorConsolidation::plan(resume_block, &cursors) else {
+        return Ok(None);
+    };
+
+    if resume_block.is_none() && consolidation.start_block > 0 {
+        engine_db
+            .set_extractor_state(
+                block_range::EXTRACTOR_TYPE,
+                block_range::STATE_KEY,
+                &(consolidation.start_block - 1).to_string(),
+            )
+            .await
+            .context("Failed to persist consolidated cursor")?;
+    }
+
+    for (state_key, _) in engine_db
+        .get_all_extractor_states(SKIP_EXTRACTOR_TYPE)
+        .await?
+    {
+        engine_db
+            .delete_extractor_state(SKIP_EXTRACTOR_TYPE, &state_key)
+            .await?;
+    }
+    for range in &consolidation.skip_ranges {
+        engine_db
+            .set_extractor_state(
+                SKIP_EXTRACTOR_TYPE,
+                &format!("{:#x}", range.contract),
+                &range.until_block.to_string(),
+            )
+            .await
+            .context("Failed to persist skip range")?;
+    }
+
+    tracing::info!(
+        target: "torii::etl::block_range",
+        contracts = cursors.len(),
+        start_block = consolidation.start_block,
+        skip_ranges = consolidation.skip_ranges.len(),
+        "Consolidated event-mode cursors"
+    );
+
+    Ok(Some(consolidation))
+}
+
+/// Loads the persisted skip ranges as `contract -> until_block`.
+pub(crate) async fn load_skip_ranges(engine_db: &EngineDb) -> Result<HashMap<Felt, u64>> {
+    let mut ranges = HashMap::new();
+    for (state_key, state_value) in engine_db
+        .get_all_extractor_states(SKIP_EXTRACTOR_TYPE)
+        .await?
+    {
+        let Ok(contract) = Felt::from_hex(&state_key) else {
+            continue;
+        };
+        let until_block = state_value
+            .parse::<u64>()
+            .with_context(|| format!("Invalid skip range for {state_key}"))?;
+        ranges.insert(contract, until_block);
+    }
+    Ok(ranges)
+}
+
+/// Drops a skip range once the block-range cursor has moved past it.
+pub(crate) async fn clear_skip_range(engine_db: &EngineDb, contract: Felt) -> Result<()> {
+    engine_db
+        .delete_extractor_state(SKIP_EXTRACTOR_TYPE, &format!("{contract:#x}"))
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::etl::engine_db::EngineDbConfig;
+
+    #[test]
+    fn plan_starts_at_minimum_cursor_and_skips_contracts_ahead() {
+        let a = Felt::from(0xa_u64);
+        let b = Felt::from(0xb_u64);
+        let plan = CursorConsolidation::plan(None, &[(b, 250), (a, 100)]).unwrap();
+        assert_eq!(plan.start_block, 100);
+        assert_eq!(
+            plan.skip_ranges,
+            vec![SkipRange {
+                contract: b,
+                until_block: 249
+            }]
+        );
+
+        // An existing block-range cursor wins and hides ranges it already covers.
+        let plan = CursorConsolidation::plan(Some(300), &[(a, 100), (b, 250)]).unwrap();
+        assert_eq!(plan.start_block, 300);
+        assert!(plan.skip_ranges.is_empty());
+
+        assert!(CursorConsolidation::plan(Some(10), &[]).is_none());
+    }
+
+    #[tokio::test]
+    async fn consolidates_event_cursors_into_block_range_state() {
+        let db = EngineDb::new(EngineDbConfig {
+            path: ":memory:".to_string(),
+        })
+        .await
+        .unwrap();
+        db.set_extractor_state("event", "0xa", "block:100")
+            .await
+            .unwrap();
+        db.set_extractor_state("event", "0xb", "block:250|token:abc")
+            .await
+            .unwrap();
+
+        for _ in 0..2 {
+            let consolidation = consolidate_event_cursors(&db).await.unwrap().unwrap();
+            assert_eq!(consolidation.start_block, 100);
+            assert_eq!(
+                db.get_extractor_state("block_range", "last_block")
+                    .await
+                    .unwrap(),
+                Some("99".to_string())
+            );
+            let ranges = load_skip_ranges(&db).await.unwrap();
+            assert_eq!(ranges.len(), 1);
+            assert_eq!(ranges[&Felt::from(0xb_u64)], 249);
+        }
+
+        clear_skip_range(&db, Felt::from(0xb_u64)).await.unwrap();
+        assert!(load_skip_ranges(&db).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,124 @@
+//! Deployment block detection for contracts configured without a start block.
+//!
+//! A contract has a class hash at every block from its deployment on, so the deployment
+//! block is found by binary search over `starknet_getClassHashAt` (about `log2(head)`
+//! requests, ~25 on mainnet). Detected blocks are persisted in the [`EngineDb`], so the
+//! search runs once per contract.
+
+use anyhow::{Context, Result};
+use starknet::core::types::{BlockId, Felt, StarknetError};
+use starknet::providers::{Provider, ProviderError};
+use std::future::Future;
+use torii_common::RpcProvider;
+
+use crate::etl::engine_db::EngineDb;
+use crate::etl::extractor::RetryPolicy;
+
+/// Finds the first block in `0..=head` for which `is_deployed` holds.
+///
+/// `is_deployed` must be monotonic (false up to the deployment block, true from it on).
+/// Returns `None` if the contract is not deployed at `head`.
+pub async fn search_deployment_block<F, Fut>(head: u64, mut is_deployed: F) -> Result<Option<u64>>
+where
+    F: FnMut(u64) -> Fut,
+    Fut: Future<Output = Result<bool>>,
+{
+    if !is_deployed(head).await? {
+        return Ok(None);
+    }
+    let (mut low, mut high) = (0, head);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if is_deployed(mid).await? {
+            high = mid;
+        } else {
+            low = mid + 1;
+        }
+    }
+    Ok(Some(low))
+}
+
+/// Finds the deployment block of `contract` with `starknet_getClassHashAt`.
+///
+/// Returns `None` if the contract is not deployed at `head`.
+pub async fn find_deployment_block(
+    provider: &RpcProvider,
+    retry_policy: &RetryPolicy,
+    contract: Felt,
+    head: u64,
+) -> Result<Option<u64>> {
+    search_deployment_block(head, |block| async move {
+        let start = std::time::Instant::now();
+        let deployed = retry_policy
+            .execute(|| async move {
+                match provider
+                    .get_class_hash_at(BlockId::Number(block), contract)
+                    .await
+                {
+                    Ok(_) => Ok(true),
+                    Err(ProviderError::StarknetError(StarknetError::ContractNotFound)) => Ok(false),
+                    Err(e) => Err(e).context("Failed to fetch class hash"),
+                }
+            })
+            .await;
+        ::metrics::histogram!("torii_rpc_request_duration_seconds", "method" => "get_class_hash_at")
+            .record(start.elapsed().as_secs_f64());
+        deployed
+    })
+    .await
+}
+
+/// Resolves the deployment block of `contract`, from the [`EngineDb`] or by searching
+/// up to `head` (the detected block is then persisted).
+pub async fn resolve_deployment_block(
+    provider: &RpcProvider,
+    retry_policy: &RetryPolicy,
+    engine_db: &EngineDb,
+    contract: Felt,
+    head: u64,
+) -> Result<Option<u64>> {
+    if let Some(block) = engine_db.get_deployment_block(contract).await? {
+        return Ok(Some(block));
+    }
+
+    let start = std::time::Instant::now();
+    let block = find_deployment_block(provider, retry_policy, contract, head).await?;
+    if let Some(block) = block {
+        engine_db.set_deployment_block(contract, block).await?;
+        tracing::info!(
+            target: "torii::etl::deployment",
+            contract = %format!("{contract:#x}"),
+            block,
+            elapsed_ms = start.elapsed().as_millis() as u64,
+            "Detected contract deployment block"
+        );
+        ::metrics::counter!("torii_deployment_block_detections_total").increment(1);
+    }
+    Ok(block)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[tokio::test]
+    async fn search_finds_first_deployed_block() {
+        for deployment in [0, 1, 7, 999, 1000] {
+            let calls = Cell::new(0);
+            let block = search_deployment_block(1000, |block| {
+                calls.set(calls.get() + 1);
+                async move { Ok(block >= deployment) }
+            })
+            .await
+            .unwrap();
+            assert_eq!(block, Some(deployment));
+            assert!(calls.get() <= 12);
+        }
+
+        let block = search_deployment_block(1000, |_| async { Ok(false) })
+            .await
+            .unwrap();
+        assert_eq!(block, None);
+    }
+}
@@ -12,6 +12,9 @@
 //! - **ETL integration**: Produces standard `ExtractionBatch` output for the decoder/sink pipeline
 //! - **Block timestamp caching**: Efficiently fetches and caches block timestamps
 //! - **Chain head following**: Set `to_block = u64::MAX` to follow chain head indefinitely
+//! - **Confirmation depth**: Optionally stay `confirmation_depth` blocks behind the chain head
+//! - **Deployment block detection**: Contracts configured with `from_block: 0` can start at
+//!   their detected deployment block (`detect_deployment_block`)
 //!
 //! # Example
 //!
@@ -37,7 +40,7 @@
 //!     retry_policy: RetryPolicy::default(),
 //! };
 //!
-//! let provider = Arc::new(JsonRpcClient::new(HttpTransport::new(url)));
+//! let provider = Arc::new(JsonRpcClient::new(HttpTransport::new(url).into()));
 //! let extractor = EventExtractor::new(provider, config);
 //!
 //! // Use with torii::run() or in custom ETL loop
@@ -52,16 +55,19 @@ use starknet::core::types::{
     requests::GetEventsRequest, BlockId, EmittedEvent, EventFilter, EventFilterWithPage, Felt,
     ResultPageRequest,
 };
-use starknet::providers::jsonrpc::{HttpTransport, JsonRpcClient};
 use starknet::providers::{Provider, ProviderRequestData, ProviderResponseData};
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use torii_common::RpcProvider;
 
+use crate::error::{ToriiError, ToriiResult};
 use crate::etl::engine_db::EngineDb;
+use crate::etl::extractor::deployment::resolve_deployment_block;
 use crate::etl::extractor::event_common;
-use crate::etl::extractor::{ExtractionBatch, Extractor, RetryPolicy};
+use crate::etl::extractor::{ExtractionBatch, Extractor, ExtractorCapabilities, RetryPolicy};
+use crate::etl::sharding::ShardAssignment;
 
-const EXTRACTOR_TYPE: &str = "event";
+pub(crate) const EXTRACTOR_TYPE: &str = "event";
 
 /// Delay between polls when following chain head and caught up.
 const CHAIN_HEAD_POLL_DELAY: std::time::Duration = std::time::Duration::from_secs(5);
@@ -101,6 +107,26 @@ pub struct EventExtractorConfig {
     /// Maximum number of independent RPC request chunks to execute concurrently.
     /// `0` means auto-tune from available CPU.
     pub rpc_parallelism: usize,
+
+    /// Number of blocks to stay behind the chain head (0 = index up to the head).
+    ///
+    /// Contracts following the chain head only query blocks with at least
+    /// `confirmation_depth` blocks built on top of them, trading latency for
+    /// protection against shallow reorgs. Fixed ranges are not affected.
+    pub confirmation_depth: u64,
+
+    /// Detect the deployment block of contracts configured with `from_block: 0`.
+    ///
+    /// When such a contract starts without saved state, its deployment block is found by
+    /// binary search over `starknet_getClassHashAt`, persisted in EngineDb and used as the
+    /// start of its cursor, skipping the empty blocks before the deployment.
+    pub detect_deployment_block: bool,
+
+    /// Only extract the contracts of these shards (horizontal scaling).
+    ///
+    /// Contracts of other shards, configured or loaded from the saved state of other
+    /// instances, are skipped. `None` extracts every contract.
+    pub shards: Option<ShardAssignment>,
 }
 
 impl Default for EventExtractorConfig {
@@ -112,6 +138,9 @@ impl Default for EventExtractorConfig {
             retry_policy: RetryPolicy::default(),
             ignore_saved_state: false,
             rpc_parallelism: 0,
+            confirmation_depth: 0,
+            detect_deployment_block: false,
+            shards: None,
         }
     }
 }
@@ -139,6 +168,14 @@ struct ContractState {
     waiting_for_blocks: bool,
 }
 
+/// Next block to fetch recorded in a persisted contract state (`block:N[|token:T]`).
+///
+/// Blocks below `N` are fully processed; block `N` may be partially processed when a
+/// continuation token is present.
+pub(crate) fn persisted_next_block(value: &str) -> Result<u64> {
+    ContractState::deserialize(Felt::ZERO, u64::MAX, value).map(|state| state.current_block)
+}
+
 impl ContractState {
     fn new(config: &ContractEventConfig) -> Self {
         Self {
@@ -261,7 +298,7 @@ impl ContractState {
 #[derive(Debug)]
 pub struct EventExtractor {
     /// Provider for RPC requests.
-    provider: Arc<JsonRpcClient<HttpTransport>>,
+    provider: Arc<RpcProvider>,
 
     /// Configuration.
     config: EventExtractorConfig,
@@ -277,6 +314,10 @@ pub struct EventExtractor {
     /// Cached chain head block number. Updated periodically.
     chain_head: Option<u64>,
 
+    /// Last block at least `confirmation_depth` behind the cached chain head, capping
+    /// the ranges of contracts following the chain head.
+    confirmed_head: Option<u64>,
+
     /// Whether any contract is following chain head.
     has_following_contracts: bool,
 }
@@ -287,7 +328,7 @@ impl EventExtractor {
     }
 
     /// Create a new event extractor.
-    pub fn new(provider: Arc<JsonRpcClient<HttpTransport>>, config: EventExtractorConfig) -> Self {
+    pub fn new(provider: Arc<RpcProvider>, config: EventExtractorConfig) -> Self {
         let has_following_contracts = config.contracts.iter().any(|c| c.to_block == u64::MAX);
         Self {
             provider,
@@ -295,11 +336,20 @@ impl EventExtractor {
             contract_states: HashMap::new(),
             initialized: false,
             chain_head: None,
+            confirmed_head: None,
             has_following_contracts,
             start_block: 0,
         }
     }
 
+    /// Whether `address` belongs to the shards of this instance.
+    fn owns(&self, address: Felt) -> bool {
+        self.config
+            .shards
+            .as_ref()
+            .is_none_or(|shards| shards.owns(address))
+    }
+
     /// Fetch the current chain head block number.
     async fn fetch_chain_head(&self) -> Result<u64> {
         let start = std::time::Instant::now();
@@ -341,14 +391,53 @@ impl EventExtractor {
         Ok(block)
     }
 
+    /// Start block of a contract starting fresh: its detected deployment block when
+    /// detection applies, the configured `from_block` otherwise.
+    async fn fresh_start_block(
+        &self,
+        engine_db: &EngineDb,
+        contract_config: &ContractEventConfig,
+        chain_head: &mut Option<u64>,
+    ) -> Result<u64> {
+        if !self.config.detect_deployment_block || contract_config.from_block != 0 {
+            return Ok(contract_config.from_block);
+        }
+
+        let head = match *chain_head {
+            Some(head) => head,
+            None => *chain_head.insert(self.fetch_chain_head().await?),
+        };
+        let deployment = resolve_deployment_block(
+            &self.provider,
+            &self.config.retry_policy,
+            engine_db,
+            contract_config.address,
+            head,
+        )
+        .await?;
+        Ok(deployment.unwrap_or_else(|| {
+            tracing::warn!(
+                target: "torii::etl::event",
+                contract = %format!("{:#x}", contract_config.address),
+                head,
+                "Contract not deployed yet; starting from the chain head"
+            );
+            head
+        }))
+    }
+
     /// Initialize contract states from config or persisted state.
     async fn initialize(&mut self, engine_db: &EngineDb) -> Result<()> {
         if self.initialized {
             return Ok(());
         }
 
+        let mut chain_head = None;
         for contract_config in &self.config.contracts {
             let address = contract_config.address;
+            if !self.owns(address) {
+                continue;
+            }
             let state_key = format!("{address:#x}");
 
             let state = if self.config.ignore_saved_state {
@@ -359,7 +448,11 @@ impl EventExtractor {
                     to_block = contract_config.to_block,
                     "Ignoring saved state for contract; starting from configured from_block"
                 );
-                ContractState::new(contract_config)
+                let mut state = ContractState::new(contract_config);
+                state.current_block = self
+                    .fresh_start_block(engine_db, contract_config, &mut chain_head)
+                    .await?;
+                state
             } else {
                 // Try to load persisted state
                 if let Some(saved_state) = engine_db
@@ -382,7 +475,11 @@ impl EventExtractor {
                         to_block = contract_config.to_block,
                         "Starting fresh extraction for contract"
                     );
-                    ContractState::new(contract_config)
+                    let mut state = ContractState::new(contract_config);
+                    state.current_block = self
+                        .fresh_start_block(engine_db, contract_config, &mut chain_head)
+                        .await?;
+                    state
                 }
             };
 
@@ -408,7 +505,7 @@ impl EventExtractor {
                 continue;
             };
 
-            if self.contract_states.contains_key(&address) {
+            if self.contract_states.contains_key(&address) || !self.owns(address) {
                 continue;
             }
 
@@ -446,7 +543,7 @@ impl EventExtractor {
             .values()
             .filter(|state| state.is_active())
             .map(|state| {
-                let range_end = state.range_end(self.config.block_batch_size, self.chain_head);
+                let range_end = state.range_end(self.config.block_batch_size, self.confirmed_head);
                 if state.continuation_token.is_none() {
                     tracing::trace!(
                         target: "torii::etl::event",
@@ -543,7 +640,7 @@ impl Extractor for EventExtractor {
         &mut self,
         _cursor: Option<String>,
         engine_db: &EngineDb,
-    ) -> Result<ExtractionBatch> {
+    ) -> ToriiResult<ExtractionBatch> {
         // Initialize on first call
         self.initialize(engine_db).await?;
         self.refresh_dynamic_contract_states(engine_db).await?;
@@ -557,10 +654,22 @@ impl Extractor for EventExtractor {
         if self.has_following_contracts {
             let chain_head = self.fetch_chain_head().await?;
             self.chain_head = Some(chain_head);
+            let Some(confirmed_head) = chain_head.checked_sub(self.config.confirmation_depth)
+            else {
+                tracing::debug!(
+                    target: "torii::etl::event",
+                    chain_head,
+                    confirmation_depth = self.config.confirmation_depth,
+                    "No confirmed blocks yet, waiting for new blocks"
+                );
+                tokio::time::sleep(CHAIN_HEAD_POLL_DELAY).await;
+                return Ok(ExtractionBatch::empty());
+            };
+            self.confirmed_head = Some(confirmed_head);
 
             // Wake up any contracts that were waiting for new blocks
             for state in self.contract_states.values_mut() {
-                state.wake_if_new_blocks(chain_head);
+                state.wake_if_new_blocks(confirmed_head);
             }
         }
 
@@ -614,12 +723,14 @@ impl Extractor for EventExtractor {
             })
             .await?;
 
-        anyhow::ensure!(
-            responses.len() == addresses.len(),
-            "Event batch response length mismatch: expected {} responses, got {}",
-            addresses.len(),
-            responses.len()
-        );
+        if responses.len() != addresses.len() {
+            return Err(anyhow::anyhow!(
+                "Event batch response length mismatch: expected {} responses, got {}",
+                addresses.len(),
+                responses.len()
+            )
+            .into());
+        }
 
         // Process responses and update state
         let mut all_events = Vec::new();
@@ -651,8 +762,8 @@ impl Extractor for EventExtractor {
                     // Current range complete, advance to next
                     let completed_from = state.current_block;
                     let completed_to =
-                        state.range_end(self.config.block_batch_size, self.chain_head);
-                    state.advance_block_range(self.config.block_batch_size, self.chain_head);
+                        state.range_end(self.config.block_batch_size, self.confirmed_head);
+                    state.advance_block_range(self.config.block_batch_size, self.confirmed_head);
                     any_advanced = true;
 
                     tracing::trace!(
@@ -682,7 +793,9 @@ impl Extractor for EventExtractor {
                     }
                 }
             } else {
-                anyhow::bail!("Unexpected response type for contract {address:#x}");
+                return Err(
+                    anyhow::anyhow!("Unexpected response type for contract {address:#x}").into(),
+                );
             }
         }
 
@@ -736,7 +849,7 @@ impl Extractor for EventExtractor {
         self.initialized && self.contract_states.values().all(|s| s.finished)
     }
 
-    async fn commit_cursor(&mut self, _cursor: &str, engine_db: &EngineDb) -> Result<()> {
+    async fn commit_cursor(&mut self, _cursor: &str, engine_db: &EngineDb) -> ToriiResult<()> {
         // Persist each contract's state individually
         for state in self.contract_states.values() {
             engine_db
@@ -756,6 +869,73 @@ impl Extractor for EventExtractor {
         Ok(())
     }
 
+    async fn rewind_cursor(&mut self, block: u64, engine_db: &EngineDb) -> ToriiResult<bool> {
+        for (state_key, state_value) in engine_db.get_all_extractor_states(EXTRACTOR_TYPE).await? {
+            let Ok(address) = Felt::from_hex(&state_key) else {
+                continue;
+            };
+            if !self.owns(address) {
+                continue;
+            }
+            let state =
+                ContractState::deserialize(address, u64::MAX, &state_value).with_context(|| {
+                    format!("failed to deserialize extractor state for {state_key}")
+                })?;
+            let from_block = self
+                .config
+                .contracts
+                .iter()
+                .find(|contract| contract.address == address)
+                .map_or(0, |contract| contract.from_block);
+            let resume = block.saturating_add(1).max(from_block);
+            if state.current_block > resume {
+                engine_db
+                    .set_extractor_state(EXTRACTOR_TYPE, &state_key, &format!("block:{resume}"))
+                    .await
+                    .with_context(|| format!("Failed to rewind state for contract {state_key}"))?;
+                tracing::info!(
+                    target: "torii::etl::event",
+                    contract = state_key,
+                    from = state.current_block,
+                    to = resume,
+                    "Rewound contract cursor"
+                );
+            }
+        }
+
+        // Reload the rewound states on the next extraction.
+        self.contract_states.clear();
+        self.initialized = false;
+        Ok(true)
+    }
+
+    async fn chain_head(&self) -> ToriiResult<Option<u64>> {
+        let head = self
+            .fetch_chain_head()
+            .await
+            .map_err(ToriiError::Extractor)?;
+        Ok(Some(head))
+    }
+
+    fn capabilities(&self) -> ExtractorCapabilities {
+        ExtractorCapabilities {
+            max_chunk: Some(self.config.chunk_size),
+            ..ExtractorCapabilities::default()
+        }
+    }
+
+    /// Events per `starknet_getEvents` page.
+    fn set_events_per_cycle(&mut self, events: u64) -> bool {
+        self.config.chunk_size = events.max(1);
+        true
+    }
+
+    /// Blocks queried per range before moving to the next one.
+    fn set_batch_size(&mut self, blocks: u64) -> bool {
+        self.config.block_batch_size = blocks.max(1);
+        true
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -764,6 +944,7 @@ impl Extractor for EventExtractor {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use starknet::providers::jsonrpc::{HttpTransport, JsonRpcClient};
 
     #[test]
     fn test_contract_state_serialize_deserialize() {
@@ -931,9 +1112,10 @@ mod tests {
 
     #[tokio::test]
     async fn test_build_batch_populates_transaction_context() {
-        let provider = Arc::new(JsonRpcClient::new(HttpTransport::new(
-            starknet::providers::Url::parse("http://localhost:5050").unwrap(),
-        )));
+        let provider = Arc::new(JsonRpcClient::new(
+            HttpTransport::new(starknet::providers::Url::parse("http://localhost:5050").unwrap())
+                .into(),
+        ));
         let extractor = EventExtractor::new(provider, EventExtractorConfig::default());
         let engine_db = EngineDb::new(crate::etl::engine_db::EngineDbConfig {
             path: "sqlite::memory:".to_string(),
@@ -969,9 +1151,10 @@ mod tests {
     #[tokio::test]
     async fn test_initialize_resumes_from_saved_state_by_default() {
         let address = Felt::from(0x123_u64);
-        let provider = Arc::new(JsonRpcClient::new(HttpTransport::new(
-            starknet::providers::Url::parse("http://localhost:5050").unwrap(),
-        )));
+        let provider = Arc::new(JsonRpcClient::new(
+            HttpTransport::new(starknet::providers::Url::parse("http://localhost:5050").unwrap())
+                .into(),
+        ));
         let mut extractor = EventExtractor::new(
             provider,
             EventExtractorConfig {
@@ -1008,9 +1191,10 @@ mod tests {
     #[tokio::test]
     async fn test_initialize_can_ignore_saved_state() {
         let address = Felt::from(0x456_u64);
-        let provider = Arc::new(JsonRpcClient::new(HttpTransport::new(
-            starknet::providers::Url::parse("http://localhost:5050").unwrap(),
-        )));
+        let provider = Arc::new(JsonRpcClient::new(
+            HttpTransport::new(starknet::providers::Url::parse("http://localhost:5050").unwrap())
+                .into(),
+        ));
         let mut extractor = EventExtractor::new(
             provider,
             EventExtractorConfig {
@@ -1047,9 +1231,10 @@ mod tests {
 
     #[tokio::test]
     async fn test_refresh_dynamic_contract_states_loads_runtime_contracts() {
-        let provider = Arc::new(JsonRpcClient::new(HttpTransport::new(
-            starknet::providers::Url::parse("http://localhost:5050").unwrap(),
-        )));
+        let provider = Arc::new(JsonRpcClient::new(
+            HttpTransport::new(starknet::providers::Url::parse("http://localhost:5050").unwrap())
+                .into(),
+        ));
         let mut extractor = EventExtractor::new(provider, EventExtractorConfig::default());
         let engine_db = EngineDb::new(crate::etl::engine_db::EngineDbConfig {
             path: "sqlite::memory:".to_string(),
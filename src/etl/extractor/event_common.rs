@@ -4,10 +4,10 @@ use starknet::core::types::{
     requests::{GetBlockWithTxHashesRequest, GetTransactionReceiptRequest},
     BlockId, EmittedEvent, ExecutionResult, Felt, MaybePreConfirmedBlockWithTxHashes,
 };
-use starknet::providers::jsonrpc::{HttpTransport, JsonRpcClient};
 use starknet::providers::{Provider, ProviderRequestData, ProviderResponseData};
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use torii_common::RpcProvider;
 
 use crate::etl::engine_db::EngineDb;
 use crate::etl::extractor::{BlockContext, ExtractionBatch, RetryPolicy, TransactionContext};
@@ -25,7 +25,7 @@ pub(crate) fn resolved_rpc_parallelism(configured: usize) -> usize {
 }
 
 pub(crate) async fn fetch_block_timestamps(
-    provider: Arc<JsonRpcClient<HttpTransport>>,
+    provider: Arc<RpcProvider>,
     retry_policy: &RetryPolicy,
     rpc_parallelism: usize,
     block_numbers: &[u64],
@@ -135,7 +135,7 @@ pub(crate) async fn fetch_block_timestamps(
 }
 
 pub(crate) async fn fetch_successful_transaction_hashes(
-    provider: Arc<JsonRpcClient<HttpTransport>>,
+    provider: Arc<RpcProvider>,
     retry_policy: &RetryPolicy,
     rpc_parallelism: usize,
     tx_hashes: &[Felt],
@@ -245,7 +245,7 @@ pub(crate) fn filter_events_by_tx_hashes(
 }
 
 pub(crate) async fn build_batch(
-    provider: Arc<JsonRpcClient<HttpTransport>>,
+    provider: Arc<RpcProvider>,
     retry_policy: &RetryPolicy,
     rpc_parallelism: usize,
     events: Vec<EmittedEvent>,
@@ -277,6 +277,7 @@ pub(crate) async fn build_batch(
                 timestamp,
                 hash: Felt::ZERO,
                 parent_hash: Felt::ZERO,
+                header: None,
             }),
         );
     }
@@ -292,6 +293,7 @@ pub(crate) async fn build_batch(
                         block_number,
                         sender_address: None,
                         calldata: Vec::new(),
+                        receipt: None,
                     })
                 });
         }
@@ -0,0 +1,391 @@
+//! Feeder gateway extractor for fetching blocks without JSON-RPC access
+//!
+//! Fetches blocks (with receipts and events) from the Starknet sequencer feeder
+//! gateway API. This is a fallback for deployments without a full-node JSON-RPC
+//! endpoint; it is slower than [`BlockRangeExtractor`](super::BlockRangeExtractor)
+//! since the feeder gateway has no batch endpoint and is rate limited.
+//!
+//! The cursor (`block:N`) and its persisted state are shared with the block range
+//! extractor, so an indexer can switch between both without re-indexing.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use futures::stream::{self, StreamExt};
+use starknet::core::types::{BlockId, Felt, MaybePreConfirmedBlockWithReceipts};
+use starknet::providers::{Provider, SequencerGatewayProvider, Url};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::error::ToriiResult;
+use crate::etl::engine_db::EngineDb;
+use crate::etl::extractor::block_range::{
+    commit_block_cursor, resolve_start_block, rewind_block_cursor,
+};
+use crate::etl::extractor::starknet_helpers::block_into_contexts;
+
+use super::{ExtractionBatch, Extractor, ExtractorCapabilities, RetryPolicy};
+
+const EXTRACTOR_TYPE: &str = "feeder_gateway";
+
+/// Feeder gateway extractor configuration
+#[derive(Debug, Clone)]
+pub struct FeederGatewayConfig {
+    /// Feeder gateway URL (e.g. `https://alpha-mainnet.starknet.io/feeder_gateway`)
+    pub feeder_gateway_url: String,
+
+    /// Starting block number
+    pub from_block: u64,
+
+    /// Ending block number (None = follow chain head indefinitely)
+    pub to_block: Option<u64>,
+
+    /// Number of blocks to fetch per batch
+    pub batch_size: u64,
+
+    /// Number of block requests in flight (the feeder gateway fetches one block per request)
+    pub concurrency: usize,
+
+    /// Retry policy for network failures (applied per block request)
+    pub retry_policy: RetryPolicy,
+}
+
+impl Default for FeederGatewayConfig {
+    fn default() -> Self {
+        Self {
+            feeder_gateway_url: "https://alpha-mainnet.starknet.io/feeder_gateway".to_string(),
+            from_block: 0,
+            to_block: None,
+            batch_size: 20,
+            concurrency: 4,
+            retry_policy: RetryPolicy::default(),
+        }
+    }
+}
+
+impl FeederGatewayConfig {
+    /// Builds a gateway provider for the configured feeder gateway URL.
+    ///
+    /// `chain_id` is only reported through `Provider::chain_id` and is not used for extraction.
+    pub fn provider(&self, chain_id: Felt) -> Result<SequencerGatewayProvider> {
+        let url = Url::parse(&self.feeder_gateway_url)
+            .with_context(|| format!("Invalid feeder gateway URL: {}", self.feeder_gateway_url))?;
+        // The gateway URL is only used to submit transactions, which the extractor never does.
+        Ok(SequencerGatewayProvider::new(url.clone(), url, chain_id))
+    }
+}
+
+/// Feeder gateway extractor.
+///
+/// Fetches blocks sequentially in batches from the feeder gateway and builds the
+/// same enriched extraction batches as the block range extractor.
+///
+/// # Cursor Management
+///
+/// The cursor is stored as "block:N" where N is the last successfully processed block,
+/// under the same extractor state as the block range extractor.
+///
+/// # Chain Head Polling
+///
+/// When `to_block` is None and the extractor reaches the chain head, it returns an
+/// empty batch (with `is_finished() = false`) and checks for new blocks on the next call.
+#[derive(Debug)]
+pub struct FeederGatewayExtractor {
+    /// Provider to fetch data from.
+    provider: Arc<SequencerGatewayProvider>,
+
+    /// Configuration.
+    config: FeederGatewayConfig,
+
+    /// Next block to fetch.
+    current_block: u64,
+
+    /// Whether the start block was resolved from cursor/state/config.
+    initialized: bool,
+
+    /// Whether we've reached the configured end block.
+    reached_end: bool,
+}
+
+impl FeederGatewayExtractor {
+    /// Creates a new feeder gateway extractor with the given provider.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let config = FeederGatewayConfig::default();
+    /// let provider = config.provider(starknet::core::chain_id::MAINNET)?;
+    /// let extractor = FeederGatewayExtractor::new(Arc::new(provider), config);
+    /// ```
+    pub fn new(provider: Arc<SequencerGatewayProvider>, config: FeederGatewayConfig) -> Self {
+        Self {
+            provider,
+            config,
+            current_block: 0,
+            initialized: false,
+            reached_end: false,
+        }
+    }
+
+    /// Fetches a single block with receipts, retrying on failure.
+    async fn fetch_block(
+        provider: Arc<SequencerGatewayProvider>,
+        retry_policy: RetryPolicy,
+        block_number: u64,
+    ) -> Result<MaybePreConfirmedBlockWithReceipts> {
+        let fetch_start = Instant::now();
+        let result = retry_policy
+            .execute(|| {
+                let provider = provider.clone();
+                async move {
+                    provider
+                        .get_block_with_receipts(BlockId::Number(block_number))
+                        .await
+                        .with_context(|| {
+                            format!("Failed to fetch block {block_number} from feeder gateway")
+                        })
+                }
+            })
+            .await;
+        ::metrics::histogram!("torii_feeder_gateway_fetch_duration_seconds")
+            .record(fetch_start.elapsed().as_secs_f64());
+
+        let status = if result.is_ok() { "ok" } else { "error" };
+        ::metrics::counter!(
+            "torii_rpc_requests_total",
+            "method" => "feeder_gateway_get_block",
+            "status" => status
+        )
+        .increment(1);
+
+        result
+    }
+
+    /// Check if we've reached the end of the configured range
+    fn should_stop(&self) -> bool {
+        self.config
+            .to_block
+            .is_some_and(|to_block| self.current_block > to_block)
+    }
+
+    async fn prepare_batch(&self) -> Result<(u64, ExtractionBatch)> {
+        let total_start = Instant::now();
+        let current_block = self.current_block;
+        let chain_head = self
+            .config
+            .retry_policy
+            .execute(|| async {
+                self.provider
+                    .block_number()
+                    .await
+                    .context("Failed to fetch chain head from feeder gateway")
+            })
+            .await?;
+
+        let batch_size = self.config.batch_size.max(1);
+        let batch_end = if current_block > chain_head {
+            let mut batch = ExtractionBatch::empty();
+            batch.cursor = Some(format!("block:{}", current_block.saturating_sub(1)));
+            batch.chain_head = Some(chain_head);
+            return Ok((current_block, batch));
+        } else {
+            let end = (current_block + batch_size - 1).min(chain_head);
+            self.config
+                .to_block
+                .map_or(end, |to_block| end.min(to_block))
+        };
+
+        tracing::info!(
+            target: "torii::etl::feeder_gateway",
+            "Fetching blocks {}-{} from feeder gateway",
+            current_block,
+            batch_end
+        );
+
+        let blocks = stream::iter(current_block..=batch_end)
+            .map(|block_number| {
+                Self::fetch_block(
+                    self.provider.clone(),
+                    self.config.retry_policy.clone(),
+                    block_number,
+                )
+            })
+            .buffered(self.config.concurrency.max(1))
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut batch = ExtractionBatch::empty();
+        let mut blocks_map = HashMap::with_capacity(blocks.len());
+        for block in blocks {
+            let block_data = block_into_contexts(block, false)?;
+
+            blocks_map.insert(
+                block_data.block_context.number,
+                block_data.block_context.into(),
+            );
+            for tx_ctx in block_data.transactions {
+                batch.transactions.insert(tx_ctx.hash, Arc::new(tx_ctx));
+            }
+            batch.events.extend(block_data.events);
+            batch
+                .declared_classes
+                .extend(block_data.declared_classes.into_iter().map(Arc::new));
+            batch
+                .deployed_contracts
+                .extend(block_data.deployed_contracts.into_iter().map(Arc::new));
+        }
+        batch.blocks = blocks_map;
+        batch.cursor = Some(format!("block:{batch_end}"));
+        batch.chain_head = Some(chain_head);
+
+        tracing::info!(
+            target: "torii::etl::feeder_gateway",
+            "Extracted {} events from {} blocks ({} transactions) [total={}ms]",
+            batch.events.len(),
+            batch.blocks.len(),
+            batch.transactions.len(),
+            total_start.elapsed().as_millis()
+        );
+
+        Ok((batch_end + 1, batch))
+    }
+}
+
+#[async_trait]
+impl Extractor for FeederGatewayExtractor {
+    fn is_finished(&self) -> bool {
+        self.reached_end
+    }
+
+    fn set_start_block(&mut self, start_block: u64) {
+        self.current_block = start_block.max(self.current_block);
+    }
+
+    async fn commit_cursor(&mut self, cursor: &str, engine_db: &EngineDb) -> ToriiResult<()> {
+        Ok(commit_block_cursor(cursor, engine_db).await?)
+    }
+
+    async fn rewind_cursor(&mut self, block: u64, engine_db: &EngineDb) -> ToriiResult<bool> {
+        rewind_block_cursor(block, engine_db).await?;
+        if self.initialized {
+            self.current_block = self.current_block.min(block.saturating_add(1));
+            self.reached_end = false;
+        }
+        Ok(true)
+    }
+
+    fn capabilities(&self) -> ExtractorCapabilities {
+        // The feeder gateway serves one block per request.
+        ExtractorCapabilities {
+            max_chunk: Some(1),
+            ..ExtractorCapabilities::default()
+        }
+    }
+
+    fn extractor_type(&self) -> &'static str {
+        EXTRACTOR_TYPE
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    async fn extract(
+        &mut self,
+        cursor: Option<String>,
+        engine_db: &EngineDb,
+    ) -> ToriiResult<ExtractionBatch> {
+        if !self.initialized {
+            let start = resolve_start_block(cursor, engine_db, self.config.from_block).await?;
+            self.current_block = start.max(self.current_block);
+            self.initialized = true;
+        }
+
+        if self.reached_end {
+            return Ok(ExtractionBatch::empty());
+        }
+
+        if self.should_stop() {
+            tracing::info!(
+                target: "torii::etl::feeder_gateway",
+                "Reached configured end block"
+            );
+            self.reached_end = true;
+            return Ok(ExtractionBatch::empty());
+        }
+
+        let (next_block, batch) = self.prepare_batch().await?;
+        self.current_block = next_block;
+
+        Ok(batch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::etl::engine_db::EngineDbConfig;
+
+    async fn engine_db() -> EngineDb {
+        EngineDb::new(EngineDbConfig {
+            path: ":memory:".to_string(),
+        })
+        .await
+        .unwrap()
+    }
+
+    /// Extractor whose gateway is never reached by the tests.
+    fn extractor(from_block: u64, to_block: Option<u64>) -> FeederGatewayExtractor {
+        let config = FeederGatewayConfig {
+            feeder_gateway_url: "http://127.0.0.1:1/feeder_gateway".to_string(),
+            from_block,
+            to_block,
+            retry_policy: RetryPolicy::no_retry(),
+            ..FeederGatewayConfig::default()
+        };
+        let provider = config.provider(Felt::ZERO).unwrap();
+        FeederGatewayExtractor::new(Arc::new(provider), config)
+    }
+
+    #[test]
+    fn invalid_gateway_url_is_rejected() {
+        let config = FeederGatewayConfig {
+            feeder_gateway_url: "not a url".to_string(),
+            ..FeederGatewayConfig::default()
+        };
+        assert!(config.provider(Felt::ZERO).is_err());
+    }
+
+    #[tokio::test]
+    async fn resumes_from_the_block_range_cursor() {
+        let db = engine_db().await;
+        // Committed by the block range extractor before switching to the gateway.
+        commit_block_cursor("block:41", &db).await.unwrap();
+
+        let mut extractor = extractor(0, Some(41));
+        let batch = extractor.extract(None, &db).await.unwrap();
+        assert_eq!(extractor.current_block, 42);
+        assert!(batch.is_empty());
+        assert!(extractor.is_finished());
+    }
+
+    #[tokio::test]
+    async fn rewind_resumes_a_finished_extractor() {
+        let db = engine_db().await;
+        let mut extractor = extractor(10, Some(5));
+        extractor.extract(None, &db).await.unwrap();
+        assert!(extractor.is_finished());
+
+        extractor.commit_cursor("block:9", &db).await.unwrap();
+        assert!(extractor.rewind_cursor(3, &db).await.unwrap());
+        assert_eq!(extractor.current_block, 4);
+        assert!(!extractor.is_finished());
+        assert_eq!(
+            resolve_start_block(None, &db, 0).await.unwrap(),
+            4,
+            "the persisted cursor is rewound too"
+        );
+    }
+}
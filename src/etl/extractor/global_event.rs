@@ -8,17 +8,18 @@ use async_trait::async_trait;
 use starknet::core::types::{
     requests::GetEventsRequest, BlockId, EventFilter, EventFilterWithPage, Felt, ResultPageRequest,
 };
-use starknet::providers::jsonrpc::{HttpTransport, JsonRpcClient};
 use starknet::providers::{Provider, ProviderRequestData, ProviderResponseData};
 use std::collections::HashSet;
 use std::sync::Arc;
+use torii_common::RpcProvider;
 
+use crate::error::{ToriiError, ToriiResult};
 use crate::etl::engine_db::EngineDb;
 use crate::etl::extractor::event_common::{
     build_batch, fetch_successful_transaction_hashes, filter_events_by_tx_hashes,
     resolved_rpc_parallelism,
 };
-use crate::etl::extractor::{ExtractionBatch, Extractor, RetryPolicy};
+use crate::etl::extractor::{ExtractionBatch, Extractor, ExtractorCapabilities, RetryPolicy};
 
 const EXTRACTOR_TYPE: &str = "global_event";
 const STATE_KEY: &str = "global";
@@ -150,7 +151,7 @@ impl GlobalState {
 
 #[derive(Debug)]
 pub struct GlobalEventExtractor {
-    provider: Arc<JsonRpcClient<HttpTransport>>,
+    provider: Arc<RpcProvider>,
     config: GlobalEventExtractorConfig,
     state: GlobalState,
     initialized: bool,
@@ -159,10 +160,7 @@ pub struct GlobalEventExtractor {
 }
 
 impl GlobalEventExtractor {
-    pub fn new(
-        provider: Arc<JsonRpcClient<HttpTransport>>,
-        config: GlobalEventExtractorConfig,
-    ) -> Self {
+    pub fn new(provider: Arc<RpcProvider>, config: GlobalEventExtractorConfig) -> Self {
         let state = GlobalState::new(&config);
         let follows_head = config.to_block == u64::MAX;
         Self {
@@ -269,7 +267,7 @@ impl Extractor for GlobalEventExtractor {
         &mut self,
         _cursor: Option<String>,
         engine_db: &EngineDb,
-    ) -> Result<ExtractionBatch> {
+    ) -> ToriiResult<ExtractionBatch> {
         self.initialize(engine_db).await?;
 
         if self.is_finished() {
@@ -310,7 +308,11 @@ impl Extractor for GlobalEventExtractor {
 
         let events_page = match response {
             ProviderResponseData::GetEvents(events_page) => events_page,
-            _ => anyhow::bail!("Unexpected response type for global event request"),
+            _ => {
+                return Err(
+                    anyhow::anyhow!("Unexpected response type for global event request").into(),
+                )
+            }
         };
 
         let mut all_events = events_page.events;
@@ -369,7 +371,7 @@ impl Extractor for GlobalEventExtractor {
         self.initialized && self.state.finished
     }
 
-    async fn commit_cursor(&mut self, _cursor: &str, engine_db: &EngineDb) -> Result<()> {
+    async fn commit_cursor(&mut self, _cursor: &str, engine_db: &EngineDb) -> ToriiResult<()> {
         engine_db
             .set_extractor_state(EXTRACTOR_TYPE, STATE_KEY, &self.state.serialize())
             .await
@@ -377,6 +379,60 @@ impl Extractor for GlobalEventExtractor {
         Ok(())
     }
 
+    async fn rewind_cursor(&mut self, block: u64, engine_db: &EngineDb) -> ToriiResult<bool> {
+        let Some(saved_state) = engine_db
+            .get_extractor_state(EXTRACTOR_TYPE, STATE_KEY)
+            .await?
+        else {
+            return Ok(true);
+        };
+        let state = GlobalState::deserialize(self.config.to_block, &saved_state)?;
+        let resume = block.saturating_add(1).max(self.config.from_block);
+        if state.current_block > resume {
+            engine_db
+                .set_extractor_state(EXTRACTOR_TYPE, STATE_KEY, &format!("block:{resume}"))
+                .await
+                .context("Failed to rewind global event state")?;
+            tracing::info!(
+                target: "torii::etl::global_event",
+                from = state.current_block,
+                to = resume,
+                "Rewound cursor"
+            );
+        }
+
+        // Reload the rewound state on the next extraction.
+        self.initialized = false;
+        Ok(true)
+    }
+
+    async fn chain_head(&self) -> ToriiResult<Option<u64>> {
+        let head = self
+            .fetch_chain_head()
+            .await
+            .map_err(ToriiError::Extractor)?;
+        Ok(Some(head))
+    }
+
+    fn capabilities(&self) -> ExtractorCapabilities {
+        ExtractorCapabilities {
+            max_chunk: Some(self.config.chunk_size),
+            ..ExtractorCapabilities::default()
+        }
+    }
+
+    /// Events per `starknet_getEvents` page.
+    fn set_events_per_cycle(&mut self, events: u64) -> bool {
+        self.config.chunk_size = events.max(1);
+        true
+    }
+
+    /// Blocks queried per range before moving to the next one.
+    fn set_batch_size(&mut self, blocks: u64) -> bool {
+        self.config.block_batch_size = blocks.max(1);
+        true
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -385,6 +441,7 @@ impl Extractor for GlobalEventExtractor {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use starknet::providers::jsonrpc::{HttpTransport, JsonRpcClient};
 
     #[test]
     fn test_global_state_serialize_deserialize() {
@@ -425,9 +482,10 @@ mod tests {
 
     #[tokio::test]
     async fn test_initialize_resumes_saved_state() {
-        let provider = Arc::new(JsonRpcClient::new(HttpTransport::new(
-            starknet::providers::Url::parse("http://localhost:5050").unwrap(),
-        )));
+        let provider = Arc::new(JsonRpcClient::new(
+            HttpTransport::new(starknet::providers::Url::parse("http://localhost:5050").unwrap())
+                .into(),
+        ));
 
         let mut extractor = GlobalEventExtractor::new(
             provider,
@@ -1,9 +1,15 @@
 //! Extractor trait for fetching events from various sources
 
+pub mod adaptive;
+pub mod archive;
 pub mod block_range;
+pub mod chain_reader;
 pub mod composite;
+pub mod consolidation;
+pub mod deployment;
 pub mod event;
 pub mod event_common;
+pub mod feeder_gateway;
 pub mod global_event;
 pub mod retry;
 pub mod sample;
@@ -12,16 +18,21 @@ pub mod synthetic;
 pub mod synthetic_adapter;
 pub mod synthetic_erc20;
 
+use crate::error::ToriiResult;
 use crate::etl::engine_db::EngineDb;
-use anyhow::Result;
 use async_trait::async_trait;
-use starknet::core::types::{EmittedEvent, Felt};
+use starknet::core::types::{EmittedEvent, ExecutionResult, Felt, PriceUnit, ResourcePrice};
 use std::collections::HashMap;
 use std::sync::Arc;
 
+pub use adaptive::{AdaptiveBatchConfig, AdaptiveBatchController, CycleFeedback};
+pub use archive::{ArchiveConfig, ArchiveExtractor};
 pub use block_range::{BlockRangeConfig, BlockRangeExtractor};
+pub use chain_reader::ChainReader;
 pub use composite::CompositeExtractor;
+pub use consolidation::{consolidate_event_cursors, CursorConsolidation, SkipRange};
 pub use event::{ContractEventConfig, EventExtractor, EventExtractorConfig};
+pub use feeder_gateway::{FeederGatewayConfig, FeederGatewayExtractor};
 pub use global_event::{GlobalEventExtractor, GlobalEventExtractorConfig};
 pub use retry::RetryPolicy;
 pub use sample::SampleExtractor;
@@ -37,6 +48,18 @@ pub struct BlockContext {
     pub hash: Felt,
     pub parent_hash: Felt,
     pub timestamp: u64,
+    /// Remaining header fields, only populated by extractors fetching full blocks
+    pub header: Option<BlockHeaderInfo>,
+}
+
+/// Block header fields beyond number, hashes and timestamp
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockHeaderInfo {
+    pub sequencer_address: Felt,
+    pub l1_gas_price: ResourcePrice,
+    pub l1_data_gas_price: ResourcePrice,
+    pub l2_gas_price: ResourcePrice,
+    pub starknet_version: String,
 }
 
 /// Transaction context information
@@ -46,6 +69,22 @@ pub struct TransactionContext {
     pub block_number: u64,
     pub sender_address: Option<Felt>,
     pub calldata: Vec<Felt>,
+    /// Receipt data, only populated by extractors with receipts enabled
+    pub receipt: Option<TransactionReceiptInfo>,
+}
+
+/// Fee and execution status of a transaction, taken from its receipt
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionReceiptInfo {
+    pub actual_fee: Felt,
+    pub fee_unit: PriceUnit,
+    pub execution_result: ExecutionResult,
+}
+
+impl TransactionReceiptInfo {
+    pub fn is_reverted(&self) -> bool {
+        matches!(self.execution_result, ExecutionResult::Reverted { .. })
+    }
 }
 
 #[derive(Debug, Clone, Default)]
@@ -218,6 +257,7 @@ impl ExtractionBatch {
                 hash,
                 parent_hash,
                 timestamp,
+                header: None,
             }),
         );
     }
@@ -236,6 +276,7 @@ impl ExtractionBatch {
                 block_number,
                 sender_address,
                 calldata,
+                receipt: None,
             }),
         );
     }
@@ -321,6 +362,32 @@ impl ExtractionBatch {
     }
 }
 
+/// What an extractor (or the chain source behind it) supports
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExtractorCapabilities {
+    /// Can populate `TransactionContext::receipt`
+    pub supports_receipts: bool,
+    /// Can extract pending (pre-confirmed) blocks
+    pub supports_pending: bool,
+    /// Largest chunk served per request: blocks for block-based extractors, events per
+    /// page for event-based ones (None = unbounded)
+    pub max_chunk: Option<u64>,
+}
+
+impl ExtractorCapabilities {
+    /// Capabilities shared by `self` and `other` (smallest chunk).
+    pub fn intersect(self, other: Self) -> Self {
+        Self {
+            supports_receipts: self.supports_receipts && other.supports_receipts,
+            supports_pending: self.supports_pending && other.supports_pending,
+            max_chunk: match (self.max_chunk, other.max_chunk) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            },
+        }
+    }
+}
+
 /// Extractor trait for fetching enriched event batches
 #[async_trait]
 pub trait Extractor: Send + Sync {
@@ -348,7 +415,7 @@ pub trait Extractor: Send + Sync {
         &mut self,
         cursor: Option<String>,
         engine_db: &EngineDb,
-    ) -> Result<ExtractionBatch>;
+    ) -> ToriiResult<ExtractionBatch>;
 
     /// Check if the extractor has finished its configured range
     ///
@@ -398,10 +465,72 @@ pub trait Extractor: Send + Sync {
     ///
     /// The default implementation does nothing (no-op). Extractors that need
     /// cursor persistence should override this method.
-    async fn commit_cursor(&mut self, _cursor: &str, _engine_db: &EngineDb) -> Result<()> {
+    async fn commit_cursor(&mut self, _cursor: &str, _engine_db: &EngineDb) -> ToriiResult<()> {
         Ok(())
     }
 
+    /// Rewind the persisted cursor so extraction resumes right after `block`.
+    ///
+    /// Called once at startup, before the first `extract()`, by the startup consistency
+    /// check when sinks committed less than the cursor claims. Cursors already at or
+    /// before `block` are left untouched. Returns `false` if the extractor does not
+    /// support rewinding (the default).
+    async fn rewind_cursor(&mut self, _block: u64, _engine_db: &EngineDb) -> ToriiResult<bool> {
+        Ok(false)
+    }
+
+    /// Fetch the current chain head without extracting anything.
+    ///
+    /// Used by [`validate`](crate::validate) to check that the chain source is reachable.
+    /// Returns `None` for extractors without a chain connection (the default).
+    async fn chain_head(&self) -> ToriiResult<Option<u64>> {
+        Ok(None)
+    }
+
+    /// Receive latency feedback for a processed batch.
+    ///
+    /// Called by the ETL loop after a batch has been decoded and stored. Extractors
+    /// with adaptive batch sizing use it to tune the size of upcoming batches.
+    /// The default implementation ignores the feedback.
+    fn observe_cycle(&mut self, _feedback: &CycleFeedback) {}
+
+    /// Notified when the sinks fall behind, before the ETL loop waits for them.
+    ///
+    /// Extractors with adaptive batch sizing shrink their upcoming batches.
+    /// The default implementation does nothing.
+    fn observe_backpressure(&mut self) {}
+
+    /// Replace the number of events fetched per extraction, from the next extraction on.
+    ///
+    /// Called by the ETL loop when the parameters are changed at runtime (`SetEtlParams`).
+    /// Returns `false` if the extractor has no such setting (the default).
+    fn set_events_per_cycle(&mut self, _events: u64) -> bool {
+        false
+    }
+
+    /// Replace the number of blocks covered per extraction, from the next extraction on.
+    ///
+    /// With adaptive batch sizing, this restarts the adjustments from `blocks`.
+    /// Returns `false` if the extractor has no such setting (the default).
+    fn set_batch_size(&mut self, _blocks: u64) -> bool {
+        false
+    }
+
+    /// What this extractor supports, so callers can check it before relying on
+    /// receipts or pending blocks. Defaults to none of them, with unbounded chunks.
+    fn capabilities(&self) -> ExtractorCapabilities {
+        ExtractorCapabilities::default()
+    }
+
+    /// Short name of the extractor type, used for provenance and logging.
+    ///
+    /// Defaults to the unqualified Rust type name.
+    fn extractor_type(&self) -> &'static str {
+        let name = std::any::type_name::<Self>();
+        let name = name.split('<').next().unwrap_or(name);
+        name.rsplit("::").next().unwrap_or(name)
+    }
+
     /// Downcast to Any for type checking
     fn as_any(&self) -> &dyn std::any::Any;
 }
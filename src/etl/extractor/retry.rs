@@ -3,6 +3,7 @@
 //! Provides configurable retry logic for network requests and transient failures.
 
 use anyhow::Result;
+use rand::Rng;
 use std::future::Future;
 use std::time::Duration;
 use tokio::time::sleep;
@@ -21,6 +22,12 @@ pub struct RetryPolicy {
 
     /// Backoff multiplier (e.g., 2.0 for exponential backoff)
     pub backoff_multiplier: f64,
+
+    /// Random jitter applied to each backoff, as a fraction in `[0, 1]`.
+    ///
+    /// Each sleep is reduced by a random amount of up to `jitter * backoff`, so that
+    /// concurrent callers retrying against a flapping endpoint do not synchronize.
+    pub jitter: f64,
 }
 
 impl Default for RetryPolicy {
@@ -30,6 +37,7 @@ impl Default for RetryPolicy {
             initial_backoff: Duration::from_secs(1),
             max_backoff: Duration::from_secs(60),
             backoff_multiplier: 2.0,
+            jitter: 0.0,
         }
     }
 }
@@ -47,7 +55,23 @@ impl RetryPolicy {
             initial_backoff,
             max_backoff,
             backoff_multiplier,
+            jitter: 0.0,
+        }
+    }
+
+    /// Sets the backoff jitter fraction (clamped to `[0, 1]`).
+    pub fn with_jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Returns the duration to sleep for a given backoff, with jitter applied.
+    pub fn jittered(&self, backoff: Duration) -> Duration {
+        if self.jitter <= 0.0 {
+            return backoff;
         }
+        let sample: f64 = rand::thread_rng().gen();
+        backoff.mul_f64(self.jitter.mul_add(-sample, 1.0))
     }
 
     /// Creates a policy with no retries (fail immediately).
@@ -57,6 +81,7 @@ impl RetryPolicy {
             initial_backoff: Duration::ZERO,
             max_backoff: Duration::ZERO,
             backoff_multiplier: 1.0,
+            jitter: 0.0,
         }
     }
 
@@ -67,6 +92,7 @@ impl RetryPolicy {
             initial_backoff: Duration::from_millis(500),
             max_backoff: Duration::from_secs(30),
             backoff_multiplier: 1.5,
+            jitter: 0.0,
         }
     }
 
@@ -129,7 +155,7 @@ impl RetryPolicy {
                         backoff
                     );
 
-                    sleep(backoff).await;
+                    sleep(self.jittered(backoff)).await;
 
                     // Calculate next backoff with exponential growth
                     backoff = Duration::from_secs_f64(
@@ -235,4 +261,19 @@ mod tests {
         // Only one attempt
         assert_eq!(counter.load(Ordering::SeqCst), 1);
     }
+
+    #[test]
+    fn test_jitter_stays_within_bounds() {
+        let backoff = Duration::from_millis(1000);
+        assert_eq!(RetryPolicy::default().jittered(backoff), backoff);
+
+        let policy = RetryPolicy::default().with_jitter(0.5);
+        for _ in 0..100 {
+            let delay = policy.jittered(backoff);
+            assert!(delay <= backoff);
+            assert!(delay >= Duration::from_millis(500));
+        }
+
+        assert!(RetryPolicy::default().with_jitter(3.0).jittered(backoff) <= backoff);
+    }
 }
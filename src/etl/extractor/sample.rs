@@ -3,11 +3,11 @@
 //! This extractor is designed for demos and testing. Events are defined
 //! in main.rs and passed to the extractor at initialization.
 
-use anyhow::Result;
 use async_trait::async_trait;
 use starknet::core::types::{EmittedEvent, Felt};
 use std::{collections::HashMap, sync::Arc};
 
+use crate::error::ToriiResult;
 use crate::etl::engine_db::EngineDb;
 
 use super::{BlockContext, ExtractionBatch, Extractor, TransactionContext};
@@ -82,6 +82,11 @@ impl Extractor for SampleExtractor {
         false // Sample extractor cycles infinitely, never finishes
     }
 
+    fn set_events_per_cycle(&mut self, events: u64) -> bool {
+        self.batch_size = events.max(1) as usize;
+        true
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -90,7 +95,7 @@ impl Extractor for SampleExtractor {
         &mut self,
         _cursor: Option<String>,
         _engine_db: &EngineDb,
-    ) -> Result<ExtractionBatch> {
+    ) -> ToriiResult<ExtractionBatch> {
         // Generate next batch of events
         let events = self.next_batch();
 
@@ -112,6 +117,7 @@ impl Extractor for SampleExtractor {
                             Felt::ZERO
                         },
                         timestamp: 1700000000 + block_num, // Realistic timestamp
+                        header: None,
                     })
                 });
             }
@@ -137,6 +143,7 @@ impl Extractor for SampleExtractor {
                             Felt::from(self.current_block), // param1
                             Felt::from(42),                 // param2
                         ],
+                        receipt: None,
                     })
                 });
         }
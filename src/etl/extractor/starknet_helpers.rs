@@ -1,16 +1,21 @@
 use anyhow::{Context, Result};
 use starknet::core::types::contract::{AbiEntry, TypedAbiEvent};
 use starknet::core::types::requests::GetClassAtRequest;
+use starknet::core::types::FeePayment;
 use starknet::core::types::LegacyContractAbiEntry;
 use starknet::core::types::{
     requests::GetBlockWithReceiptsRequest, BlockId, ContractClass, DeclareTransactionContent,
     DeployAccountTransactionContent, EmittedEvent, ExecutionResult, Felt, InvokeTransactionContent,
     MaybePreConfirmedBlockWithReceipts, TransactionContent, TransactionReceipt,
 };
+use starknet::core::utils::get_selector_from_name;
 use starknet::providers::{Provider, ProviderRequestData, ProviderResponseData};
 use std::collections::HashSet;
 
-use super::{BlockContext, BlockData, DeclaredClass, DeployedContract, TransactionContext};
+use super::{
+    BlockContext, BlockData, BlockHeaderInfo, DeclaredClass, DeployedContract, TransactionContext,
+    TransactionReceiptInfo,
+};
 
 #[inline]
 fn is_execution_succeeded(execution_result: &ExecutionResult) -> bool {
@@ -22,6 +27,22 @@ fn is_receipt_succeeded(receipt: &TransactionReceipt) -> bool {
     is_execution_succeeded(receipt.execution_result())
 }
 
+/// Extracts the fee and execution status of a receipt.
+fn receipt_info(receipt: &TransactionReceipt) -> TransactionReceiptInfo {
+    let FeePayment { amount, unit } = match receipt {
+        TransactionReceipt::Invoke(r) => &r.actual_fee,
+        TransactionReceipt::L1Handler(r) => &r.actual_fee,
+        TransactionReceipt::Declare(r) => &r.actual_fee,
+        TransactionReceipt::Deploy(r) => &r.actual_fee,
+        TransactionReceipt::DeployAccount(r) => &r.actual_fee,
+    };
+    TransactionReceiptInfo {
+        actual_fee: *amount,
+        fee_unit: *unit,
+        execution_result: receipt.execution_result().clone(),
+    }
+}
+
 /// Builds a batch of `GetBlockWithReceipts` requests for a range of block numbers.
 ///
 /// # Arguments
@@ -135,7 +156,10 @@ where
 /// # Returns
 ///
 /// A `BlockData` structure containing all extracted information.
-pub fn block_into_contexts(block: MaybePreConfirmedBlockWithReceipts) -> Result<BlockData> {
+pub fn block_into_contexts(
+    block: MaybePreConfirmedBlockWithReceipts,
+    include_receipts: bool,
+) -> Result<BlockData> {
     // Skip pending/pre-confirmed blocks
     let block_with_receipts = match block {
         MaybePreConfirmedBlockWithReceipts::Block(b) => b,
@@ -156,6 +180,13 @@ pub fn block_into_contexts(block: MaybePreConfirmedBlockWithReceipts) -> Result<
         hash: block_with_receipts.block_hash,
         parent_hash: block_with_receipts.parent_hash,
         timestamp: block_with_receipts.timestamp,
+        header: Some(BlockHeaderInfo {
+            sequencer_address: block_with_receipts.sequencer_address,
+            l1_gas_price: block_with_receipts.l1_gas_price,
+            l1_data_gas_price: block_with_receipts.l1_data_gas_price,
+            l2_gas_price: block_with_receipts.l2_gas_price,
+            starknet_version: block_with_receipts.starknet_version,
+        }),
     };
 
     let transactions = block_with_receipts.transactions;
@@ -180,16 +211,6 @@ pub fn block_into_contexts(block: MaybePreConfirmedBlockWithReceipts) -> Result<
             TransactionReceipt::DeployAccount(r) => r.transaction_hash,
         };
 
-        if !is_receipt_succeeded(&receipt) {
-            skipped_reverted += 1;
-            tracing::debug!(
-                target: "torii::etl::block_range",
-                tx_hash = %format!("{:#x}", tx_hash),
-                "Skipping reverted transaction"
-            );
-            continue;
-        }
-
         // Extract transaction data based on type, including metadata for declares and deploys
         let (sender_address, calldata, declare_info, deploy_account_class) = match tx {
             TransactionContent::Invoke(content) => match content {
@@ -243,13 +264,33 @@ pub fn block_into_contexts(block: MaybePreConfirmedBlockWithReceipts) -> Result<
             },
         };
 
+        let succeeded = is_receipt_succeeded(&receipt);
+        let receipt_info = include_receipts.then(|| receipt_info(&receipt));
+
         // Build transaction context
-        transaction_contexts.push(TransactionContext {
+        let transaction_context = TransactionContext {
             hash: tx_hash,
             block_number: block_with_receipts.block_number,
             sender_address,
             calldata,
-        });
+            receipt: receipt_info,
+        };
+
+        if !succeeded {
+            skipped_reverted += 1;
+            tracing::debug!(
+                target: "torii::etl::block_range",
+                tx_hash = %format!("{:#x}", tx_hash),
+                "Skipping reverted transaction"
+            );
+            // Reverted transactions have no effects, but their fee was still charged.
+            if include_receipts {
+                transaction_contexts.push(transaction_context);
+            }
+            continue;
+        }
+
+        transaction_contexts.push(transaction_context);
 
         // Extract declared classes from Declare transactions
         if let Some((class_hash, compiled_class_hash)) = declare_info {
@@ -322,6 +363,7 @@ pub fn block_into_contexts(block: MaybePreConfirmedBlockWithReceipts) -> Result<
 #[cfg(test)]
 mod tests {
     use super::*;
+    use starknet::core::types::PriceUnit;
 
     #[test]
     fn execution_success_predicate_handles_succeeded_and_reverted() {
@@ -330,18 +372,104 @@ mod tests {
             reason: "reverted".to_string(),
         }));
     }
+
+    fn invoke(hash: &str, sender: &str, execution: serde_json::Value) -> serde_json::Value {
+        let mut receipt = serde_json::json!({
+            "type": "INVOKE",
+            "transaction_hash": hash,
+            "actual_fee": { "amount": "0x64", "unit": "FRI" },
+            "finality_status": "ACCEPTED_ON_L2",
+            "messages_sent": [],
+            "events": [{ "from_address": "0x5", "keys": ["0x6"], "data": [] }],
+            "execution_resources": { "l1_gas": 0, "l1_data_gas": 0, "l2_gas": 0 },
+        });
+        receipt
+            .as_object_mut()
+            .unwrap()
+            .extend(execution.as_object().unwrap().clone());
+        serde_json::json!({
+            "transaction": {
+                "type": "INVOKE",
+                "version": "0x1",
+                "transaction_hash": hash,
+                "sender_address": sender,
+                "calldata": ["0x1"],
+                "max_fee": "0x0",
+                "signature": [],
+                "nonce": "0x0",
+            },
+            "receipt": receipt,
+        })
+    }
+
+    fn block() -> MaybePreConfirmedBlockWithReceipts {
+        serde_json::from_value(serde_json::json!({
+            "status": "ACCEPTED_ON_L2",
+            "block_hash": "0x10",
+            "parent_hash": "0xf",
+            "block_number": 7,
+            "new_root": "0x0",
+            "timestamp": 1000,
+            "sequencer_address": "0x0",
+            "l1_gas_price": { "price_in_fri": "0x1", "price_in_wei": "0x1" },
+            "l2_gas_price": { "price_in_fri": "0x1", "price_in_wei": "0x1" },
+            "l1_data_gas_price": { "price_in_fri": "0x1", "price_in_wei": "0x1" },
+            "l1_da_mode": "BLOB",
+            "starknet_version": "0.14.0",
+            "transactions": [
+                invoke("0x1", "0xa", serde_json::json!({ "execution_status": "SUCCEEDED" })),
+                invoke(
+                    "0x2",
+                    "0xb",
+                    serde_json::json!({ "execution_status": "REVERTED", "revert_reason": "out of gas" }),
+                ),
+            ],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn block_into_contexts_skips_reverted_transactions_without_receipts() {
+        let data = block_into_contexts(block(), false).unwrap();
+        let header = data.block_context.header.as_ref().unwrap();
+        assert_eq!(header.starknet_version, "0.14.0");
+        assert_eq!(header.l1_gas_price.price_in_fri, Felt::ONE);
+        assert_eq!(data.transactions.len(), 1);
+        assert_eq!(data.transactions[0].hash, Felt::from(1_u64));
+        assert!(data.transactions[0].receipt.is_none());
+        assert_eq!(data.events.len(), 1);
+    }
+
+    #[test]
+    fn block_into_contexts_includes_receipts_and_reverted_transactions() {
+        let data = block_into_contexts(block(), true).unwrap();
+        assert_eq!(data.transactions.len(), 2);
+        assert_eq!(data.events.len(), 1);
+
+        let succeeded = data.transactions[0].receipt.as_ref().unwrap();
+        assert_eq!(succeeded.actual_fee, Felt::from(100_u64));
+        assert_eq!(succeeded.fee_unit, PriceUnit::Fri);
+        assert!(!succeeded.is_reverted());
+
+        let reverted = &data.transactions[1];
+        assert_eq!(reverted.sender_address, Some(Felt::from(0xb_u64)));
+        assert!(reverted.receipt.as_ref().unwrap().is_reverted());
+    }
 }
 
 /// Parsed contract ABI
 ///
 /// Simplified representation of a contract's ABI for identification purposes.
-/// Contains function and event signatures extracted from the contract class.
+/// Contains function and event signatures extracted from the contract class, and the
+/// selectors of its external entry points, which are available even when the ABI is not
+/// (legacy class without ABI, Sierra ABI that fails to parse).
 #[derive(Debug, Clone)]
 pub struct ContractAbi {
     pub abi: Option<Vec<AbiEntry>>,
     pub legacy_abi: Option<Vec<LegacyContractAbiEntry>>,
     functions: HashSet<String>,
     events: HashSet<String>,
+    selectors: HashSet<Felt>,
 }
 
 impl ContractAbi {
@@ -359,10 +487,34 @@ impl ContractAbi {
         let mut legacy_abi: Option<Vec<LegacyContractAbiEntry>> = None;
         let mut functions = HashSet::new();
         let mut events = HashSet::new();
+        let selectors: HashSet<Felt>;
 
         match class {
             ContractClass::Sierra(sierra) => {
-                let parsed_abi: Vec<AbiEntry> = serde_json::from_str(&sierra.abi)?;
+                selectors = sierra
+                    .entry_points_by_type
+                    .external
+                    .iter()
+                    .map(|entry_point| entry_point.selector)
+                    .collect();
+                let parsed_abi: Vec<AbiEntry> = match serde_json::from_str(&sierra.abi) {
+                    Ok(parsed_abi) => parsed_abi,
+                    Err(e) if !selectors.is_empty() => {
+                        tracing::debug!(
+                            target: "torii::etl::identification",
+                            error = %e,
+                            "Unparseable Sierra ABI, keeping entry point selectors only"
+                        );
+                        return Ok(Self {
+                            abi: None,
+                            legacy_abi: None,
+                            functions,
+                            events,
+                            selectors,
+                        });
+                    }
+                    Err(e) => return Err(e.into()),
+                };
                 for entry in &parsed_abi {
                     match entry {
                         AbiEntry::Function(func) => {
@@ -398,6 +550,12 @@ impl ContractAbi {
                 abi = Some(parsed_abi);
             }
             ContractClass::Legacy(legacy) => {
+                selectors = legacy
+                    .entry_points_by_type
+                    .external
+                    .iter()
+                    .map(|entry_point| entry_point.selector)
+                    .collect();
                 if let Some(ref legacy_abi_vec) = legacy.abi {
                     for entry in legacy_abi_vec {
                         match entry {
@@ -420,9 +578,25 @@ impl ContractAbi {
             legacy_abi,
             functions,
             events,
+            selectors,
         })
     }
 
+    /// Whether function and event names are known (the class ABI was parsed).
+    ///
+    /// When `false`, only [`has_entry_point`](Self::has_entry_point) is meaningful.
+    pub fn has_abi(&self) -> bool {
+        self.abi.is_some() || self.legacy_abi.is_some()
+    }
+
+    /// Whether the class has an external entry point for the function `name`.
+    ///
+    /// Matches the entry point selectors, so it works without the ABI, but only for
+    /// unqualified names (`balance_of`, not `IERC20::balance_of`).
+    pub fn has_entry_point(&self, name: &str) -> bool {
+        get_selector_from_name(name).is_ok_and(|selector| self.selectors.contains(&selector))
+    }
+
     pub fn has_function(&self, name: &str) -> bool {
         if self.functions.contains(name) {
             return true;
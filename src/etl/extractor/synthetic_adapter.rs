@@ -1,9 +1,9 @@
 //! Adapter that exposes a [`SyntheticExtractor`] as a standard [`Extractor`].
 
 use anyhow::Context;
-use anyhow::Result;
 use async_trait::async_trait;
 
+use crate::error::ToriiResult;
 use crate::etl::engine_db::EngineDb;
 
 use super::{ExtractionBatch, Extractor, SyntheticExtractor};
@@ -45,7 +45,7 @@ where
         &mut self,
         cursor: Option<String>,
         engine_db: &EngineDb,
-    ) -> Result<ExtractionBatch> {
+    ) -> ToriiResult<ExtractionBatch> {
         let cursor = if self.initialized {
             cursor
         } else {
@@ -60,14 +60,14 @@ where
             }
         };
 
-        self.inner.extract(cursor).await
+        Ok(self.inner.extract(cursor).await?)
     }
 
     fn is_finished(&self) -> bool {
         self.inner.is_finished()
     }
 
-    async fn commit_cursor(&mut self, cursor: &str, engine_db: &EngineDb) -> Result<()> {
+    async fn commit_cursor(&mut self, cursor: &str, engine_db: &EngineDb) -> ToriiResult<()> {
         engine_db
             .set_extractor_state(self.inner.extractor_name(), STATE_KEY, cursor)
             .await
@@ -76,7 +76,8 @@ where
                     "failed to commit synthetic cursor for {}",
                     self.inner.extractor_name()
                 )
-            })
+            })?;
+        Ok(())
     }
 
     fn as_any(&self) -> &dyn std::any::Any {
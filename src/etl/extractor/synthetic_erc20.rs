@@ -11,6 +11,7 @@ use starknet::macros::selector;
 use std::collections::HashMap;
 use std::sync::Arc;
 
+use crate::error::ToriiResult;
 use crate::etl::engine_db::EngineDb;
 
 use super::{BlockContext, ExtractionBatch, Extractor, TransactionContext};
@@ -199,6 +200,7 @@ impl SyntheticErc20Extractor {
                     },
                     // 12-second blocks anchored to a fixed epoch.
                     timestamp: 1_700_000_000 + (block_number * 12),
+                    header: None,
                 }),
             );
 
@@ -236,6 +238,7 @@ impl SyntheticErc20Extractor {
                         block_number,
                         sender_address: Some(from),
                         calldata: vec![token, from, to, amount_low],
+                        receipt: None,
                     }),
                 );
 
@@ -264,7 +267,7 @@ impl Extractor for SyntheticErc20Extractor {
         &mut self,
         cursor: Option<String>,
         engine_db: &EngineDb,
-    ) -> Result<ExtractionBatch> {
+    ) -> ToriiResult<ExtractionBatch> {
         self.initialize(cursor, engine_db).await?;
         if self.finished {
             return Ok(ExtractionBatch::empty());
@@ -283,12 +286,13 @@ impl Extractor for SyntheticErc20Extractor {
         self.finished
     }
 
-    async fn commit_cursor(&mut self, cursor: &str, engine_db: &EngineDb) -> Result<()> {
+    async fn commit_cursor(&mut self, cursor: &str, engine_db: &EngineDb) -> ToriiResult<()> {
         let block_num = Self::parse_cursor(cursor)?;
         engine_db
             .set_extractor_state(EXTRACTOR_TYPE, STATE_KEY, &block_num.to_string())
             .await
-            .context("failed to commit synthetic extractor cursor")
+            .context("failed to commit synthetic extractor cursor")?;
+        Ok(())
     }
 
     fn as_any(&self) -> &dyn std::any::Any {
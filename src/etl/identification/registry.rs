@@ -3,6 +3,10 @@
 //! The registry loads cached mappings from database and provides shared access.
 //! It also supports runtime identification of unknown contracts by fetching their ABIs
 //! and running identification rules.
+//!
+//! Each identification is persisted with its provenance (source and class hash). When an
+//! identification TTL is configured, known contracts are periodically re-checked and
+//! re-identified if their class hash changed (contract upgrade).
 
 use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
 
@@ -15,14 +19,14 @@ use async_trait::async_trait;
 use futures::stream::{self, StreamExt};
 use starknet::core::types::requests::{GetClassHashAtRequest, GetClassRequest};
 use starknet::core::types::{BlockId, BlockTag, Felt};
-use starknet::providers::jsonrpc::{HttpTransport, JsonRpcClient};
 use starknet::providers::{Provider, ProviderRequestData, ProviderResponseData};
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, RwLock};
+use torii_common::RpcProvider;
 
 use super::IdentificationRule;
 use crate::etl::decoder::DecoderId;
-use crate::etl::engine_db::EngineDb;
-use crate::etl::extractor::ContractAbi;
+use crate::etl::engine_db::{ContractIdentification, EngineDb, IdentificationSource};
+use crate::etl::extractor::{ContractAbi, RetryPolicy};
 
 /// Trait for contract identification (object-safe).
 ///
@@ -63,7 +67,7 @@ pub trait ContractIdentifier: Send + Sync {
 /// This reduces N×2 sequential API calls to just 2 batch calls.
 pub struct ContractRegistry {
     /// Starknet provider for fetching contract ABIs (JsonRpcClient for batch support)
-    provider: Arc<JsonRpcClient<HttpTransport>>,
+    provider: Arc<RpcProvider>,
 
     /// Engine database for persistence
     engine_db: Arc<EngineDb>,
@@ -83,6 +87,66 @@ pub struct ContractRegistry {
 
     /// Maximum number of chunked RPC requests to execute concurrently.
     rpc_parallelism: usize,
+
+    /// Retry policy (with jitter) for identification RPC batches.
+    retry_policy: RetryPolicy,
+
+    /// Maximum number of contracts identified per call (0 = unlimited).
+    ///
+    /// Each contract costs up to two RPC calls (class hash + class). Contracts over
+    /// the budget, or whose RPC batch failed after retries, are deferred to the next call.
+    identification_budget: usize,
+
+    /// Contracts deferred to subsequent identification calls, in arrival order.
+    deferred: Mutex<DeferredQueue>,
+
+    /// Provenance of positive identifications, keyed by contract.
+    identifications: RwLock<HashMap<Felt, ContractIdentification>>,
+
+    /// Age after which a rule-based identification is re-checked against the
+    /// contract's current class hash (`None` = never).
+    identification_ttl: Option<std::time::Duration>,
+
+    /// Contracts never identified (e.g. known spam tokens).
+    denylist: HashSet<Felt>,
+
+    /// Contracts eligible for identification (empty = all).
+    allowlist: HashSet<Felt>,
+}
+
+/// Bounded FIFO of contracts awaiting identification.
+struct DeferredQueue {
+    set: HashSet<Felt>,
+    order: VecDeque<Felt>,
+    capacity: usize,
+}
+
+impl DeferredQueue {
+    fn new(capacity: usize) -> Self {
+        Self {
+            set: HashSet::new(),
+            order: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    /// Defers a contract. Returns false if the queue is full or it is already queued.
+    fn push(&mut self, contract: Felt) -> bool {
+        if self.set.len() >= self.capacity || !self.set.insert(contract) {
+            return false;
+        }
+        self.order.push_back(contract);
+        true
+    }
+
+    fn drain(&mut self) -> Vec<Felt> {
+        self.set.clear();
+        self.order.drain(..).collect()
+    }
 }
 
 /// Bounded in-memory negative cache (FIFO/LRU-like).
@@ -142,13 +206,16 @@ impl ContractRegistry {
     /// Maximum number of contracts to keep in negative cache.
     const NEGATIVE_CACHE_CAPACITY: usize = 100_000;
 
+    /// Maximum number of contracts waiting in the deferred queue.
+    const DEFERRED_CAPACITY: usize = 100_000;
+
     /// Create a new contract registry.
     ///
     /// # Arguments
     ///
     /// * `provider` - JsonRpcClient provider for fetching ABIs (supports batch requests)
     /// * `engine_db` - Database for persistence
-    pub fn new(provider: Arc<JsonRpcClient<HttpTransport>>, engine_db: Arc<EngineDb>) -> Self {
+    pub fn new(provider: Arc<RpcProvider>, engine_db: Arc<EngineDb>) -> Self {
         Self {
             provider,
             engine_db,
@@ -158,9 +225,27 @@ impl ContractRegistry {
                 Self::NEGATIVE_CACHE_CAPACITY,
             ))),
             rpc_parallelism: 0,
+            retry_policy: Self::default_retry_policy(),
+            identification_budget: 0,
+            deferred: Mutex::new(DeferredQueue::new(Self::DEFERRED_CAPACITY)),
+            identifications: RwLock::new(HashMap::new()),
+            identification_ttl: None,
+            denylist: HashSet::new(),
+            allowlist: HashSet::new(),
         }
     }
 
+    /// Retry policy of identification RPC batches unless [`Self::with_retry_policy`] is used.
+    pub fn default_retry_policy() -> RetryPolicy {
+        RetryPolicy::new(
+            3,
+            std::time::Duration::from_millis(500),
+            std::time::Duration::from_secs(10),
+            2.0,
+        )
+        .with_jitter(0.5)
+    }
+
     /// Add an identification rule.
     ///
     /// Rules are run in order during identification. All matching decoders
@@ -180,6 +265,85 @@ impl ContractRegistry {
         self
     }
 
+    /// Set the retry policy for identification RPC batches.
+    ///
+    /// Defaults to 3 retries with exponential backoff (500ms to 10s) and 50% jitter.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Set the maximum number of contracts identified per call (0 = unlimited).
+    ///
+    /// Remaining unknown contracts are deferred to subsequent calls, so that a burst of
+    /// new contracts (or a flapping RPC) cannot trigger an unbounded number of requests.
+    pub fn with_identification_budget(mut self, budget: usize) -> Self {
+        self.identification_budget = budget;
+        self
+    }
+
+    /// Set the age after which identifications are re-checked for upgrades.
+    ///
+    /// Stale contracts seen in a batch have their class hash re-fetched; if it changed,
+    /// the contract is re-identified. Explicit mappings are never re-checked. Re-checks
+    /// share the identification budget, after unknown contracts. Disabled by default.
+    pub fn with_identification_ttl(mut self, ttl: std::time::Duration) -> Self {
+        self.identification_ttl = Some(ttl);
+        self
+    }
+
+    /// Never identify `contracts` (e.g. known spam tokens).
+    ///
+    /// Their events are skipped unless an explicit mapping covers them, and
+    /// identifications persisted before they were denied are ignored.
+    pub fn with_denylist(mut self, contracts: impl IntoIterator<Item = Felt>) -> Self {
+        self.denylist.extend(contracts);
+        self
+    }
+
+    /// Only identify `contracts`; other unmapped contracts are skipped like denied ones.
+    ///
+    /// An empty allowlist (the default) allows every contract.
+    pub fn with_allowlist(mut self, contracts: impl IntoIterator<Item = Felt>) -> Self {
+        self.allowlist.extend(contracts);
+        self
+    }
+
+    /// Whether `contract` is excluded from identification by the deny/allow lists.
+    pub fn is_excluded(&self, contract: Felt) -> bool {
+        self.denylist.contains(&contract)
+            || (!self.allowlist.is_empty() && !self.allowlist.contains(&contract))
+    }
+
+    /// Get the persisted provenance of a positively identified contract.
+    pub async fn identification(&self, contract: Felt) -> Option<ContractIdentification> {
+        self.identifications.read().await.get(&contract).cloned()
+    }
+
+    /// Number of contracts currently deferred to subsequent identification calls.
+    pub async fn deferred_count(&self) -> usize {
+        self.deferred.lock().await.len()
+    }
+
+    /// Defer contracts to the next identification call.
+    async fn defer(&self, contracts: impl IntoIterator<Item = Felt>) {
+        let mut deferred = self.deferred.lock().await;
+        let mut dropped = 0usize;
+        for contract in contracts {
+            if !deferred.push(contract) && deferred.len() >= Self::DEFERRED_CAPACITY {
+                dropped += 1;
+            }
+        }
+        if dropped > 0 {
+            tracing::warn!(
+                target: "torii::etl::identification",
+                dropped,
+                "Deferred identification queue full, dropping contracts"
+            );
+        }
+        ::metrics::gauge!("torii_registry_identify_deferred").set(deferred.len() as f64);
+    }
+
     fn resolved_rpc_parallelism(&self) -> usize {
         if self.rpc_parallelism == 0 {
             std::thread::available_parallelism()
@@ -195,12 +359,16 @@ impl ContractRegistry {
     /// This should be called during initialization to restore
     /// previously identified contracts.
     pub async fn load_from_db(&self) -> Result<usize> {
-        let mappings = self.engine_db.get_all_contract_decoders().await?;
+        let mappings = self.engine_db.get_all_contract_identifications().await?;
         let mut loaded_positive = 0usize;
         let mut loaded_empty = 0usize;
 
-        for (contract, decoder_ids, _timestamp) in mappings {
-            if decoder_ids.is_empty() {
+        for identification in mappings {
+            let contract = identification.contract;
+            if self.is_excluded(contract) {
+                continue;
+            }
+            if identification.decoder_ids.is_empty() {
                 self.cache_empty(contract).await;
                 loaded_empty += 1;
                 continue;
@@ -211,8 +379,12 @@ impl ContractRegistry {
             }
             {
                 let mut cache = self.cache.write().await;
-                cache.insert(contract, decoder_ids);
+                cache.insert(contract, identification.decoder_ids.clone());
             }
+            self.identifications
+                .write()
+                .await
+                .insert(contract, identification);
             loaded_positive += 1;
         }
 
@@ -243,6 +415,10 @@ impl ContractRegistry {
     /// 4. Running all identification rules
     /// 5. Caching positives in memory and database, negatives in bounded memory
     ///
+    /// Known contracts whose identification is older than the TTL (see
+    /// [`Self::with_identification_ttl`]) join step 2; the unchanged ones only get
+    /// their timestamp refreshed, upgraded ones go through steps 3-5 again.
+    ///
     /// # Performance
     ///
     /// Uses batch JSON-RPC requests to minimize API calls:
@@ -261,26 +437,71 @@ impl ContractRegistry {
         &self,
         contract_addresses: &[Felt],
     ) -> Result<HashMap<Felt, Vec<DecoderId>>> {
-        // Deduplicate and filter out contracts already in cache
-        let unique_addresses: HashSet<Felt> = contract_addresses.iter().copied().collect();
+        // Previously deferred contracts go first, then the new ones.
+        let mut candidates = self.deferred.lock().await.drain();
+        candidates.extend_from_slice(contract_addresses);
+
+        // Deduplicate (keeping arrival order) and filter out contracts already in cache
+        let mut seen = HashSet::with_capacity(candidates.len());
         let cache = self.cache.read().await;
         let negative_cache = self.negative_cache.read().await;
-        let unknown: Vec<Felt> = unique_addresses
+        // Excluded contracts are split off: they are cached without decoders so that their
+        // events are skipped instead of being tried against every decoder.
+        let (excluded, mut unknown): (Vec<Felt>, Vec<Felt>) = candidates
             .into_iter()
-            .filter(|addr| !cache.contains_key(addr) && !negative_cache.contains(addr))
-            .collect();
+            .filter(|addr| {
+                seen.insert(*addr) && !cache.contains_key(addr) && !negative_cache.contains(addr)
+            })
+            .partition(|contract| self.is_excluded(*contract));
         drop(cache);
         drop(negative_cache);
 
-        if unknown.is_empty() {
+        if !excluded.is_empty() {
+            ::metrics::counter!("torii_registry_identify_excluded_total")
+                .increment(excluded.len() as u64);
+            for contract in excluded {
+                self.cache_empty(contract).await;
+            }
+        }
+
+        if self.identification_budget > 0 && unknown.len() > self.identification_budget {
+            let over_budget = unknown.split_off(self.identification_budget);
+            tracing::debug!(
+                target: "torii::etl::identification",
+                budget = self.identification_budget,
+                deferred = over_budget.len(),
+                "Identification budget exhausted, deferring remaining contracts"
+            );
+            ::metrics::counter!("torii_registry_identify_deferred_total", "reason" => "budget")
+                .increment(over_budget.len() as u64);
+            self.defer(over_budget).await;
+        } else {
+            ::metrics::gauge!("torii_registry_identify_deferred")
+                .set(self.deferred.lock().await.len() as f64);
+        }
+
+        let recheck_budget = if self.identification_budget == 0 {
+            usize::MAX
+        } else {
+            self.identification_budget.saturating_sub(unknown.len())
+        };
+        let stale = self
+            .stale_contracts(contract_addresses, recheck_budget)
+            .await;
+
+        if unknown.is_empty() && stale.is_empty() {
             return Ok(HashMap::new());
         }
 
         tracing::debug!(
             target: "torii::etl::identification",
+            stale = stale.len(),
             "Identifying {} unknown contracts using batch requests",
             unknown.len()
         );
+        let now = unix_now();
+        let stale_set: HashSet<Felt> = stale.iter().copied().collect();
+        let lookups: Vec<Felt> = unknown.iter().chain(&stale).copied().collect();
 
         // BATCH 1: Fetch all class hashes (chunked to respect RPC limits)
         let mut contract_to_class: HashMap<Felt, Felt> = HashMap::new();
@@ -288,7 +509,7 @@ impl ContractRegistry {
         let rpc_parallelism = self.resolved_rpc_parallelism();
         ::metrics::gauge!("torii_rpc_parallelism").set(rpc_parallelism as f64);
 
-        let class_hash_tasks = unknown
+        let class_hash_tasks = lookups
             .chunks(MAX_BATCH_SIZE)
             .enumerate()
             .map(|(chunk_index, chunk)| {
@@ -305,17 +526,21 @@ impl ContractRegistry {
                 async move {
                     let chunk_start = std::time::Instant::now();
                     let class_hash_responses = self
-                        .provider
-                        .batch_requests(&class_hash_requests)
-                        .await
-                        .context("Failed to batch fetch class hashes")?;
+                        .retry_policy
+                        .execute(|| async {
+                            self.provider
+                                .batch_requests(&class_hash_requests)
+                                .await
+                                .context("Failed to batch fetch class hashes")
+                        })
+                        .await;
                     ::metrics::histogram!(
                         "torii_rpc_chunk_duration_seconds",
                         "extractor" => "registry",
                         "method" => "get_class_hash_at_batch"
                     )
                     .record(chunk_start.elapsed().as_secs_f64());
-                    Ok::<_, anyhow::Error>((chunk_index, chunk_addresses, class_hash_responses))
+                    (chunk_index, chunk_addresses, class_hash_responses)
                 }
             })
             .collect::<Vec<_>>();
@@ -323,17 +548,29 @@ impl ContractRegistry {
         let mut class_hash_chunks = stream::iter(class_hash_tasks)
             .buffer_unordered(rpc_parallelism)
             .collect::<Vec<_>>()
-            .await
-            .into_iter()
-            .collect::<anyhow::Result<Vec<_>>>()?;
+            .await;
         class_hash_chunks.sort_by_key(|(chunk_index, _, _)| *chunk_index);
 
+        let mut last_error = None;
         for (_, chunk_addresses, class_hash_responses) in class_hash_chunks {
+            let class_hash_responses = match class_hash_responses {
+                Ok(responses) => responses,
+                Err(e) => {
+                    // Retries exhausted: try these contracts again on the next call
+                    // instead of caching them as unidentifiable.
+                    ::metrics::counter!("torii_registry_identify_deferred_total", "reason" => "rpc_error")
+                        .increment(chunk_addresses.len() as u64);
+                    self.defer(chunk_addresses).await;
+                    last_error = Some(e);
+                    continue;
+                }
+            };
+
             // Map contract → class_hash, track failures
             for (addr, response) in chunk_addresses.iter().zip(class_hash_responses) {
                 if let ProviderResponseData::GetClassHashAt(class_hash) = response {
                     contract_to_class.insert(*addr, class_hash);
-                } else {
+                } else if !stale_set.contains(addr) {
                     tracing::debug!(
                         target: "torii::etl::identification",
                         contract = %format!("{:#x}", addr),
@@ -344,8 +581,46 @@ impl ContractRegistry {
             }
         }
 
+        // Stale contracts keeping their class hash only need a refreshed timestamp.
+        let mut records: Vec<ContractIdentification> = Vec::new();
+        let mut upgraded: HashSet<Felt> = HashSet::new();
+        if !stale.is_empty() {
+            let mut identifications = self.identifications.write().await;
+            for contract in &stale {
+                let Some(&class_hash) = contract_to_class.get(contract) else {
+                    continue;
+                };
+                let Some(identification) = identifications.get_mut(contract) else {
+                    contract_to_class.remove(contract);
+                    continue;
+                };
+                if identification
+                    .class_hash
+                    .is_none_or(|previous| previous == class_hash)
+                {
+                    identification.class_hash = Some(class_hash);
+                    identification.identified_at = now;
+                    records.push(identification.clone());
+                    contract_to_class.remove(contract);
+                } else {
+                    tracing::info!(
+                        target: "torii::etl::identification",
+                        contract = %format!("{:#x}", contract),
+                        previous_class_hash = ?identification.class_hash,
+                        class_hash = %format!("{:#x}", class_hash),
+                        "Contract class changed, re-identifying"
+                    );
+                    upgraded.insert(*contract);
+                }
+            }
+        }
+
         if contract_to_class.is_empty() {
-            return Ok(HashMap::new());
+            self.persist_identifications(&records).await;
+            return match last_error {
+                Some(e) => Err(e),
+                None => Ok(HashMap::new()),
+            };
         }
 
         // BATCH 2: Fetch unique classes (deduplicated by class hash, chunked to respect RPC limits)
@@ -383,17 +658,21 @@ impl ContractRegistry {
                 async move {
                     let chunk_start = std::time::Instant::now();
                     let class_responses = self
-                        .provider
-                        .batch_requests(&class_requests)
-                        .await
-                        .context("Failed to batch fetch classes")?;
+                        .retry_policy
+                        .execute(|| async {
+                            self.provider
+                                .batch_requests(&class_requests)
+                                .await
+                                .context("Failed to batch fetch classes")
+                        })
+                        .await;
                     ::metrics::histogram!(
                         "torii_rpc_chunk_duration_seconds",
                         "extractor" => "registry",
                         "method" => "get_class_batch"
                     )
                     .record(chunk_start.elapsed().as_secs_f64());
-                    Ok::<_, anyhow::Error>((chunk_index, chunk_hashes, class_responses))
+                    (chunk_index, chunk_hashes, class_responses)
                 }
             })
             .collect::<Vec<_>>();
@@ -401,17 +680,32 @@ impl ContractRegistry {
         let mut class_chunks = stream::iter(class_tasks)
             .buffer_unordered(rpc_parallelism)
             .collect::<Vec<_>>()
-            .await
-            .into_iter()
-            .collect::<anyhow::Result<Vec<_>>>()?;
+            .await;
         class_chunks.sort_by_key(|(chunk_index, _, _)| *chunk_index);
 
+        let mut failed_classes: HashSet<Felt> = HashSet::new();
         for (_, chunk_hashes, class_responses) in class_chunks {
+            let class_responses = match class_responses {
+                Ok(responses) => responses,
+                Err(e) => {
+                    failed_classes.extend(chunk_hashes);
+                    last_error = Some(e);
+                    continue;
+                }
+            };
+
             for (class_hash, response) in chunk_hashes.iter().zip(class_responses) {
                 match response {
                     ProviderResponseData::GetClass(contract_class) => {
                         match ContractAbi::from_contract_class(contract_class) {
                             Ok(abi) => {
+                                if !abi.has_abi() {
+                                    // Rules fall back to entry point selectors.
+                                    ::metrics::counter!(
+                                        "torii_registry_identify_selectors_only_total"
+                                    )
+                                    .increment(1);
+                                }
                                 class_to_abi.insert(*class_hash, abi);
                             }
                             Err(e) => {
@@ -440,13 +734,48 @@ impl ContractRegistry {
         let mut positives: HashMap<Felt, Vec<DecoderId>> = HashMap::new();
         let mut negatives: Vec<Felt> = Vec::new();
 
+        if !failed_classes.is_empty() {
+            let failed_contracts: Vec<Felt> = contract_to_class
+                .iter()
+                .filter(|(_, class_hash)| failed_classes.contains(class_hash))
+                .map(|(contract, _)| *contract)
+                .collect();
+            contract_to_class.retain(|_, class_hash| !failed_classes.contains(class_hash));
+            ::metrics::counter!("torii_registry_identify_deferred_total", "reason" => "rpc_error")
+                .increment(failed_contracts.len() as u64);
+            self.defer(failed_contracts).await;
+        }
+
+        if let Some(e) = &last_error {
+            let deferred = self.deferred_count().await;
+            tracing::warn!(
+                target: "torii::etl::identification",
+                error = %e,
+                deferred,
+                "Identification RPC failed after retries, deferring affected contracts"
+            );
+        }
+
         for (contract_address, class_hash) in &contract_to_class {
-            let decoder_ids = if let Some(abi) = class_to_abi.get(class_hash) {
+            let (decoder_ids, matched_rules) = if let Some(abi) = class_to_abi.get(class_hash) {
                 self.run_rules(*contract_address, *class_hash, abi)
             } else {
-                Vec::new()
+                (Vec::new(), Vec::new())
             };
 
+            if upgraded.contains(contract_address) {
+                ::metrics::counter!("torii_registry_reidentified_total").increment(1);
+            }
+            if !decoder_ids.is_empty() || upgraded.contains(contract_address) {
+                records.push(ContractIdentification {
+                    contract: *contract_address,
+                    decoder_ids: decoder_ids.clone(),
+                    source: IdentificationSource::Rules(matched_rules),
+                    class_hash: Some(*class_hash),
+                    identified_at: now,
+                });
+            }
+
             if decoder_ids.is_empty() {
                 negatives.push(*contract_address);
             } else {
@@ -463,6 +792,17 @@ impl ContractRegistry {
         }
 
         // Batch update caches
+        {
+            let mut identifications = self.identifications.write().await;
+            for record in &records {
+                if record.decoder_ids.is_empty() {
+                    identifications.remove(&record.contract);
+                } else {
+                    identifications.insert(record.contract, record.clone());
+                }
+            }
+        }
+
         if !negatives.is_empty() {
             let mut negative_cache = self.negative_cache.write().await;
             let mut cache = self.cache.write().await;
@@ -484,35 +824,66 @@ impl ContractRegistry {
                     negative_cache.remove(contract_address);
                 }
             }
-            {
-                let mut cache = self.cache.write().await;
-                for (contract_address, decoder_ids) in &positives {
-                    cache.insert(*contract_address, decoder_ids.clone());
-                }
-            }
-
-            // Batch persist to database
-            if let Err(e) = self.engine_db.set_contract_decoders_batch(&positives).await {
-                tracing::warn!(
-                    target: "torii::etl::identification",
-                    count = positives.len(),
-                    error = %e,
-                    "Failed to batch persist contract identifications"
-                );
+            let mut cache = self.cache.write().await;
+            for (contract_address, decoder_ids) in &positives {
+                cache.insert(*contract_address, decoder_ids.clone());
             }
         }
 
+        self.persist_identifications(&records).await;
+
         Ok(results)
     }
 
+    /// Known, non-explicit contracts whose identification is older than the TTL.
+    async fn stale_contracts(&self, contracts: &[Felt], limit: usize) -> Vec<Felt> {
+        let Some(ttl) = self.identification_ttl else {
+            return Vec::new();
+        };
+        let cutoff = unix_now().saturating_sub(i64::try_from(ttl.as_secs()).unwrap_or(i64::MAX));
+        let identifications = self.identifications.read().await;
+        let mut seen = HashSet::new();
+        contracts
+            .iter()
+            .copied()
+            .filter(|contract| {
+                seen.insert(*contract)
+                    && identifications.get(contract).is_some_and(|identification| {
+                        identification.source != IdentificationSource::Explicit
+                            && identification.identified_at <= cutoff
+                    })
+            })
+            .take(limit)
+            .collect()
+    }
+
+    /// Batch persist identifications with their provenance (failures are logged).
+    async fn persist_identifications(&self, records: &[ContractIdentification]) {
+        if let Err(e) = self
+            .engine_db
+            .set_contract_identifications_batch(records)
+            .await
+        {
+            tracing::warn!(
+                target: "torii::etl::identification",
+                count = records.len(),
+                error = %e,
+                "Failed to batch persist contract identifications"
+            );
+        }
+    }
+
     /// Run all identification rules on a contract's ABI.
+    ///
+    /// Returns the matched decoders and the names of the rules that matched.
     fn run_rules(
         &self,
         contract_address: Felt,
         class_hash: Felt,
         abi: &ContractAbi,
-    ) -> Vec<DecoderId> {
+    ) -> (Vec<DecoderId>, Vec<String>) {
         let mut matched_decoders = BTreeSet::new();
+        let mut matched_rules = Vec::new();
         for rule in &self.rules {
             match rule.identify_by_abi(contract_address, class_hash, abi) {
                 Ok(decoder_ids) => {
@@ -525,6 +896,7 @@ impl ContractRegistry {
                             "Rule matched"
                         );
                         matched_decoders.extend(decoder_ids);
+                        matched_rules.push(rule.name().to_string());
                     }
                 }
                 Err(e) => {
@@ -538,7 +910,7 @@ impl ContractRegistry {
                 }
             }
         }
-        matched_decoders.into_iter().collect()
+        (matched_decoders.into_iter().collect(), matched_rules)
     }
 
     /// Cache empty result for a contract that failed identification.
@@ -556,6 +928,14 @@ impl ContractRegistry {
     }
 }
 
+fn unix_now() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| {
+            i64::try_from(elapsed.as_secs()).unwrap_or(i64::MAX)
+        })
+}
+
 #[async_trait]
 impl ContractIdentifier for ContractRegistry {
     async fn identify_contracts(
@@ -570,3 +950,112 @@ impl ContractIdentifier for ContractRegistry {
         ContractRegistry::shared_cache(self)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::etl::engine_db::EngineDbConfig;
+    use axum::extract::State;
+    use axum::http::StatusCode;
+    use axum::Json;
+    use starknet::providers::Url;
+
+    type Requests = Arc<std::sync::Mutex<Vec<Vec<Felt>>>>;
+
+    /// JSON-RPC endpoint recording the contracts of each batch and failing it.
+    async fn failing_rpc(
+        State(requests): State<Requests>,
+        Json(batch): Json<Vec<serde_json::Value>>,
+    ) -> StatusCode {
+        let contracts = batch
+            .iter()
+            .filter_map(|request| {
+                let params = &request["params"];
+                let address = params
+                    .get("contract_address")
+                    .or_else(|| params.get(1))?
+                    .as_str()?;
+                Felt::from_hex(address).ok()
+            })
+            .collect();
+        requests.lock().unwrap().push(contracts);
+        StatusCode::SERVICE_UNAVAILABLE
+    }
+
+    async fn registry(budget: usize) -> (ContractRegistry, Requests) {
+        let requests = Requests::default();
+        let app = axum::Router::new()
+            .route("/", axum::routing::post(failing_rpc))
+            .with_state(requests.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = Url::parse(&format!("http://{}", listener.local_addr().unwrap())).unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let engine_db = Arc::new(
+            EngineDb::new(EngineDbConfig {
+                path: ":memory:".to_string(),
+            })
+            .await
+            .unwrap(),
+        );
+        let registry = ContractRegistry::new(
+            Arc::new(torii_common::rate_limited_provider(url, 0, 0)),
+            engine_db,
+        )
+        .with_retry_policy(RetryPolicy::no_retry())
+        .with_identification_budget(budget);
+        (registry, requests)
+    }
+
+    fn contracts(range: std::ops::Range<u64>) -> Vec<Felt> {
+        range.map(Felt::from).collect()
+    }
+
+    #[test]
+    fn deferred_queue_dedups_and_is_bounded() {
+        let mut queue = DeferredQueue::new(2);
+        assert!(queue.push(Felt::ONE));
+        assert!(!queue.push(Felt::ONE));
+        assert!(queue.push(Felt::TWO));
+        assert!(!queue.push(Felt::THREE));
+        assert_eq!(queue.drain(), vec![Felt::ONE, Felt::TWO]);
+        assert_eq!(queue.len(), 0);
+        assert!(queue.push(Felt::ONE));
+    }
+
+    #[tokio::test]
+    async fn failed_contracts_are_retried_next_cycle() {
+        let (registry, requests) = registry(0).await;
+
+        assert!(registry.identify_contracts(&contracts(1..3)).await.is_err());
+        assert_eq!(registry.deferred_count().await, 2);
+
+        // The deferred contracts go first, before the contracts of the new cycle.
+        assert!(registry.identify_contracts(&contracts(3..4)).await.is_err());
+        assert_eq!(
+            *requests.lock().unwrap(),
+            vec![contracts(1..3), contracts(1..4)]
+        );
+        assert_eq!(registry.deferred_count().await, 3);
+    }
+
+    #[tokio::test]
+    async fn identification_budget_is_enforced_per_cycle() {
+        let (registry, requests) = registry(2).await;
+
+        assert!(registry.identify_contracts(&contracts(1..6)).await.is_err());
+        assert_eq!(requests.lock().unwrap().last(), Some(&contracts(1..3)));
+        assert_eq!(registry.deferred_count().await, 5);
+
+        // Contracts over the budget are looked up in the next cycles, in arrival order.
+        assert!(registry.identify_contracts(&[]).await.is_err());
+        assert_eq!(requests.lock().unwrap().last(), Some(&contracts(3..5)));
+        assert!(registry.identify_contracts(&[]).await.is_err());
+        assert_eq!(
+            requests.lock().unwrap().last(),
+            Some(&vec![Felt::from(5u64), Felt::from(1u64)])
+        );
+        assert_eq!(requests.lock().unwrap().len(), 3);
+        assert_eq!(registry.deferred_count().await, 5);
+    }
+}
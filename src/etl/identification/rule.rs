@@ -67,6 +67,9 @@ pub trait IdentificationRule: Send + Sync {
     /// Returns decoder IDs if the ABI matches this rule's patterns.
     /// Returns an empty Vec if no match.
     ///
+    /// Rules are also run on classes whose ABI is unavailable ([`ContractAbi::has_abi`]
+    /// is `false`): only [`ContractAbi::has_entry_point`] can match them.
+    ///
     /// # Arguments
     ///
     /// * `contract_address` - The contract address being identified
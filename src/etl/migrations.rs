@@ -0,0 +1,311 @@
+//! Versioned, forward-only schema migrations.
+//!
+//! Each component (the engine database, each token storage, ...) embeds an ordered
+//! list of [`Migration`] scripts. Applied versions are recorded per component in a
+//! shared `schema_version` table, so several components can live in the same database.
+//! At startup [`migrate`] applies every script newer than the recorded version, each
+//! one atomically together with its version row.
+//!
+//! Version 1 of every built-in component is its original `CREATE TABLE IF NOT EXISTS`
+//! schema, so databases created before versioning are adopted without changes.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use sqlx::{Any, Pool};
+
+/// Advisory lock key serializing `schema_version` creation across processes (PostgreSQL).
+const PG_MIGRATION_LOCK_KEY: i64 = 0x746f_7269_6930;
+
+/// SQL dialect of a migration executor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SqlDialect {
+    Sqlite,
+    Postgres,
+}
+
+/// A single embedded migration script.
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    /// Version number (strictly increasing, starting at 1)
+    pub version: u32,
+    /// Short human-readable name
+    pub name: &'static str,
+    /// SQL script (may contain several statements)
+    pub sql: &'static str,
+}
+
+impl Migration {
+    pub const fn new(version: u32, name: &'static str, sql: &'static str) -> Self {
+        Self { version, name, sql }
+    }
+}
+
+/// Database connection able to run migration scripts.
+#[async_trait]
+pub trait MigrationExecutor: Send {
+    /// Dialect used to create and query the `schema_version` table.
+    fn dialect(&self) -> SqlDialect;
+
+    /// Executes one or more statements in a single transaction.
+    async fn execute_atomic(&mut self, sql: &str) -> Result<()>;
+
+    /// Runs a query returning at most one integer column.
+    async fn query_i64(&mut self, sql: &str) -> Result<Option<i64>>;
+}
+
+/// Applies pending `migrations` for `component` and returns the resulting schema version.
+///
+/// Fails if the database was migrated by a newer binary (unknown version recorded).
+pub async fn migrate<E: MigrationExecutor + ?Sized>(
+    executor: &mut E,
+    component: &str,
+    migrations: &[Migration],
+) -> Result<u32> {
+    validate(migrations).with_context(|| format!("Invalid migrations for {component}"))?;
+
+    executor
+        .execute_atomic(&create_version_table_sql(executor.dialect()))
+        .await
+        .context("Failed to create schema_version table")?;
+
+    let current = current_version(executor, component).await?;
+    let latest = migrations.last().map_or(0, |m| m.version);
+    if current > latest {
+        anyhow::bail!(
+            "{component} schema is at version {current}, but this binary only knows up to version {latest}"
+        );
+    }
+
+    for migration in migrations.iter().filter(|m| m.version > current) {
+        tracing::info!(
+            target: "torii::etl::migrations",
+            component,
+            version = migration.version,
+            name = migration.name,
+            "Applying migration"
+        );
+        let script = format!(
+            "{}\n;\n{}",
+            migration.sql,
+            record_version_sql(executor.dialect(), component, migration)
+        );
+        executor.execute_atomic(&script).await.with_context(|| {
+            format!(
+                "Failed to apply {component} migration {} ({})",
+                migration.version, migration.name
+            )
+        })?;
+    }
+
+    if latest > current {
+        tracing::info!(
+            target: "torii::etl::migrations",
+            component,
+            from = current,
+            to = latest,
+            "Schema migrated"
+        );
+    }
+
+    Ok(latest)
+}
+
+/// Returns the schema version recorded for `component` (0 if none).
+pub async fn current_version<E: MigrationExecutor + ?Sized>(
+    executor: &mut E,
+    component: &str,
+) -> Result<u32> {
+    let version = executor
+        .query_i64(&format!(
+            "SELECT MAX(version) FROM schema_version WHERE component = {}",
+            quote(component)
+        ))
+        .await
+        .context("Failed to read schema_version")?;
+    Ok(version.map_or(0, |v| v as u32))
+}
+
+fn validate(migrations: &[Migration]) -> Result<()> {
+    let mut expected = 1;
+    for migration in migrations {
+        if migration.version != expected {
+            anyhow::bail!(
+                "expected migration version {expected}, found {} ({})",
+                migration.version,
+                migration.name
+            );
+        }
+        expected += 1;
+    }
+    Ok(())
+}
+
+fn create_version_table_sql(dialect: SqlDialect) -> String {
+    let table = "CREATE TABLE IF NOT EXISTS schema_version (
+        component TEXT NOT NULL,
+        version INTEGER NOT NULL,
+        name TEXT NOT NULL,
+        applied_at BIGINT NOT NULL,
+        PRIMARY KEY (component, version)
+    )";
+    match dialect {
+        SqlDialect::Sqlite => table.to_string(),
+        // Concurrent `CREATE TABLE IF NOT EXISTS` can race on PostgreSQL.
+        SqlDialect::Postgres => {
+            format!("SELECT pg_advisory_xact_lock({PG_MIGRATION_LOCK_KEY});\n{table}")
+        }
+    }
+}
+
+fn record_version_sql(dialect: SqlDialect, component: &str, migration: &Migration) -> String {
+    let now = match dialect {
+        SqlDialect::Sqlite => "CAST(strftime('%s', 'now') AS INTEGER)",
+        SqlDialect::Postgres => "EXTRACT(EPOCH FROM NOW())::BIGINT",
+    };
+    format!(
+        "INSERT INTO schema_version (component, version, name, applied_at) VALUES ({}, {}, {}, {now})",
+        quote(component),
+        migration.version,
+        quote(migration.name)
+    )
+}
+
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// Migration executor over an sqlx `Any` pool.
+pub struct SqlxMigrationExecutor<'a> {
+    pool: &'a Pool<Any>,
+    dialect: SqlDialect,
+}
+
+impl<'a> SqlxMigrationExecutor<'a> {
+    pub fn new(pool: &'a Pool<Any>, dialect: SqlDialect) -> Self {
+        Self { pool, dialect }
+    }
+}
+
+#[async_trait]
+impl MigrationExecutor for SqlxMigrationExecutor<'_> {
+    fn dialect(&self) -> SqlDialect {
+        self.dialect
+    }
+
+    async fn execute_atomic(&mut self, sql: &str) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        let conn: &mut sqlx::AnyConnection = &mut tx;
+        sqlx::Executor::execute(conn, sqlx::raw_sql(sql)).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn query_i64(&mut self, sql: &str) -> Result<Option<i64>> {
+        Ok(sqlx::query_scalar::<_, Option<i64>>(sql)
+            .fetch_optional(self.pool)
+            .await?
+            .flatten())
+    }
+}
+
+#[async_trait]
+impl MigrationExecutor for rusqlite::Connection {
+    fn dialect(&self) -> SqlDialect {
+        SqlDialect::Sqlite
+    }
+
+    async fn execute_atomic(&mut self, sql: &str) -> Result<()> {
+        let tx = self.transaction()?;
+        tx.execute_batch(sql)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    async fn query_i64(&mut self, sql: &str) -> Result<Option<i64>> {
+        Ok(self.query_row(sql, [], |row| row.get::<_, Option<i64>>(0))?)
+    }
+}
+
+#[async_trait]
+impl MigrationExecutor for tokio_postgres::Client {
+    fn dialect(&self) -> SqlDialect {
+        SqlDialect::Postgres
+    }
+
+    async fn execute_atomic(&mut self, sql: &str) -> Result<()> {
+        let tx = self.transaction().await?;
+        tx.batch_execute(sql).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn query_i64(&mut self, sql: &str) -> Result<Option<i64>> {
+        let row = self.query_opt(sql, &[]).await?;
+        Ok(row.and_then(|row| row.get::<_, Option<i64>>(0)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIGRATIONS: &[Migration] = &[
+        Migration::new(
+            1,
+            "initial",
+            "CREATE TABLE IF NOT EXISTS items (id INTEGER PRIMARY KEY)",
+        ),
+        Migration::new(2, "add_name", "ALTER TABLE items ADD COLUMN name TEXT"),
+    ];
+
+    #[tokio::test]
+    async fn test_applies_pending_migrations_once() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+
+        assert_eq!(
+            migrate(&mut conn, "test", &MIGRATIONS[..1]).await.unwrap(),
+            1
+        );
+        assert_eq!(migrate(&mut conn, "test", MIGRATIONS).await.unwrap(), 2);
+        // Re-running is a no-op (ALTER TABLE would fail if applied twice).
+        assert_eq!(migrate(&mut conn, "test", MIGRATIONS).await.unwrap(), 2);
+
+        conn.execute("INSERT INTO items (id, name) VALUES (1, 'a')", [])
+            .unwrap();
+        assert_eq!(current_version(&mut conn, "other").await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_failed_migration_is_rolled_back() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        let broken = [
+            MIGRATIONS[0],
+            Migration::new(
+                2,
+                "broken",
+                "CREATE TABLE extra (id INTEGER); INSERT INTO missing VALUES (1)",
+            ),
+        ];
+
+        assert!(migrate(&mut conn, "test", &broken).await.is_err());
+        assert_eq!(current_version(&mut conn, "test").await.unwrap(), 1);
+        let extra: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM sqlite_master WHERE name = 'extra'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(extra, 0);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_newer_schema_and_gaps() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        migrate(&mut conn, "test", MIGRATIONS).await.unwrap();
+        assert!(migrate(&mut conn, "test", &MIGRATIONS[..1]).await.is_err());
+
+        let gap = [Migration::new(2, "gap", "SELECT 1")];
+        assert!(migrate(&mut conn, "gapped", &gap).await.is_err());
+    }
+}
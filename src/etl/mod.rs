@@ -1,18 +1,35 @@
+pub mod consistency;
+pub mod counters;
 pub mod decoder;
+pub mod dedupe;
 pub mod engine_db;
 pub mod envelope;
 pub mod event;
+pub mod event_archive;
 pub mod extractor;
 pub mod identification;
+pub mod migrations;
+pub mod sharding;
 pub mod sink;
 
+pub use consistency::StartupConsistency;
+pub use counters::{CounterSnapshot, CumulativeCounters};
 pub use decoder::{Decoder, DecoderContext};
-pub use engine_db::{EngineDb, EngineStats};
-pub use envelope::{Envelope, EventBody, EventMsg, MetaData, TypeId, TypedBody};
+pub use dedupe::{EventDedupe, EventIndexer};
+pub use engine_db::{
+    ContractActivity, ContractIdentification, ContractStats, EngineDb, EngineStats,
+    IdentificationSource, TableDefinition,
+};
+pub use envelope::{
+    Envelope, EnvelopeBody, EnvelopeMeta, EnvelopeMetadata, EnvelopeSlab, EventBody, EventMsg,
+    MappingRule, MetaData, Provenance, TypeId, TypedBody,
+};
+pub use event_archive::ArchivedBatch;
 pub use extractor::{
-    BlockContext, ContractAbi, EventContext, ExtractionBatch, Extractor, SampleExtractor,
-    SyntheticErc20Config, SyntheticErc20Extractor, SyntheticExtractor, SyntheticExtractorAdapter,
-    TransactionContext,
+    ArchiveConfig, ArchiveExtractor, BlockContext, ContractAbi, EventContext, ExtractionBatch,
+    Extractor, SampleExtractor, SyntheticErc20Config, SyntheticErc20Extractor, SyntheticExtractor,
+    SyntheticExtractorAdapter, TransactionContext,
 };
 pub use identification::{ContractRegistry, IdentificationRule};
-pub use sink::{MultiSink, Sink};
+pub use sharding::{ShardAssignment, ShardCoordinator, ShardingConfig};
+pub use sink::{MultiSink, Sink, SinkBackpressure, SinkContractFilter, SinkWorkerConfig};
@@ -0,0 +1,407 @@
+//! Distributed work sharding over contracts
+//!
+//! Several indexer instances sharing one (Postgres) engine database can split the
+//! contracts between them. Contracts are hashed into `shard_count` shards
+//! ([`shard_of`]); each instance claims up to `max_shards` of them through lease rows in
+//! the engine database and only extracts the contracts of its shards
+//! ([`ShardAssignment::owns`]).
+//!
+//! Leases are renewed from the moment they are claimed ([`ShardCoordinator::start_renewal`])
+//! and expire `lease_ttl` after the last renewal, so the shards of a crashed instance are
+//! taken over by a standby instance waiting in [`ShardCoordinator::acquire`]. An instance
+//! that cannot renew its leases gives up one renewal interval before they expire, and
+//! stops indexing rather than race the instance taking its shards over.
+//! Every instance runs the same configuration (`shard_count` must match); per-contract
+//! cursors are disjoint, while the engine head is shared and only indicative.
+
+use anyhow::{Context, Result};
+use starknet::core::types::Felt;
+use std::collections::BTreeSet;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
+
+use crate::etl::EngineDb;
+
+/// Shard of `contract` among `shard_count` shards.
+///
+/// Uses the low 64 bits of the address, which are uniformly distributed for
+/// deployed contracts (addresses are hashes).
+pub fn shard_of(contract: Felt, shard_count: u32) -> u32 {
+    let bytes = contract.to_bytes_be();
+    let mut low = [0u8; 8];
+    low.copy_from_slice(&bytes[24..]);
+    (u64::from_be_bytes(low) % u64::from(shard_count.max(1))) as u32
+}
+
+/// Lease of a shard, as stored in the engine database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShardLease {
+    pub shard: u32,
+    /// Instance id holding the lease
+    pub owner: String,
+    /// Unix timestamp the lease expires at
+    pub expires_at: i64,
+}
+
+/// Sharding configuration of an instance.
+#[derive(Debug, Clone)]
+pub struct ShardingConfig {
+    /// Number of shards the contracts are split into (same on every instance).
+    pub shard_count: u32,
+    /// Shards claimed by this instance at most.
+    pub max_shards: u32,
+    /// Unique id of this instance, recorded as lease owner.
+    pub instance_id: String,
+    /// Validity of a lease after its last renewal.
+    pub lease_ttl: Duration,
+}
+
+impl ShardingConfig {
+    /// `shard_count` shards, one per instance, 30s leases and the default instance id.
+    pub fn new(shard_count: u32) -> Self {
+        Self {
+            shard_count,
+            max_shards: 1,
+            instance_id: default_instance_id(),
+            lease_ttl: Duration::from_secs(30),
+        }
+    }
+
+    /// Interval between lease renewals (and claim attempts while standing by).
+    pub fn renew_interval(&self) -> Duration {
+        (self.lease_ttl / 3).max(Duration::from_secs(1))
+    }
+
+    /// Time without a successful renewal after which the leases are given up: the
+    /// next attempt would come after they expired.
+    pub fn give_up_after(&self) -> Duration {
+        self.lease_ttl.saturating_sub(self.renew_interval())
+    }
+}
+
+/// Instance id from the host name (`HOSTNAME`) and the process id.
+pub fn default_instance_id() -> String {
+    let host = std::env::var("HOSTNAME").unwrap_or_else(|_| "torii".to_string());
+    format!("{host}-{}", std::process::id())
+}
+
+/// Shards owned by an instance.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShardAssignment {
+    pub shard_count: u32,
+    pub shards: BTreeSet<u32>,
+}
+
+impl ShardAssignment {
+    /// Whether `contract` belongs to one of the owned shards.
+    pub fn owns(&self, contract: Felt) -> bool {
+        self.shards.contains(&shard_of(contract, self.shard_count))
+    }
+}
+
+/// Claims, renews and releases the shard leases of an instance.
+pub struct ShardCoordinator {
+    engine_db: Arc<EngineDb>,
+    config: ShardingConfig,
+    claimed: Mutex<BTreeSet<u32>>,
+    /// When the leases were last claimed or renewed (they expire `lease_ttl` later)
+    renewed_at: Mutex<Option<Instant>>,
+    /// Stops the background renewal started by [`ShardCoordinator::start_renewal`]
+    renewal: Mutex<Option<CancellationToken>>,
+    lost: CancellationToken,
+}
+
+impl ShardCoordinator {
+    pub fn new(engine_db: Arc<EngineDb>, config: ShardingConfig) -> Self {
+        Self {
+            engine_db,
+            config,
+            claimed: Mutex::new(BTreeSet::new()),
+            renewed_at: Mutex::new(None),
+            renewal: Mutex::new(None),
+            lost: CancellationToken::new(),
+        }
+    }
+
+    pub fn config(&self) -> &ShardingConfig {
+        &self.config
+    }
+
+    /// Shards currently claimed by this instance.
+    pub fn assignment(&self) -> ShardAssignment {
+        ShardAssignment {
+            shard_count: self.config.shard_count,
+            shards: self.claimed.lock().unwrap().clone(),
+        }
+    }
+
+    fn lease_window(&self) -> (i64, i64) {
+        let now = chrono::Utc::now().timestamp();
+        let ttl = i64::try_from(self.config.lease_ttl.as_secs()).unwrap_or(i64::MAX);
+        (now, now.saturating_add(ttl))
+    }
+
+    /// Claims free or expired shards, up to `max_shards` in total.
+    pub async fn try_acquire(&self) -> Result<ShardAssignment> {
+        let mut claimed = self.claimed.lock().unwrap().clone();
+        let first_claim = claimed.is_empty().then(Instant::now);
+        for shard in 0..self.config.shard_count {
+            if claimed.len() >= self.config.max_shards as usize {
+                break;
+            }
+            if claimed.contains(&shard) {
+                continue;
+            }
+            let (now, expires_at) = self.lease_window();
+            if self
+                .engine_db
+                .claim_shard_lease(shard, &self.config.instance_id, now, expires_at)
+                .await
+                .with_context(|| format!("Failed to claim shard {shard}"))?
+            {
+                claimed.insert(shard);
+            }
+        }
+        if !claimed.is_empty() {
+            if let Some(claimed_at) = first_claim {
+                *self.renewed_at.lock().unwrap() = Some(claimed_at);
+            }
+        }
+        *self.claimed.lock().unwrap() = claimed;
+        Ok(self.assignment())
+    }
+
+    /// Claims shards, standing by until at least one is available.
+    pub async fn acquire(&self) -> Result<ShardAssignment> {
+        loop {
+            let assignment = self.try_acquire().await?;
+            if !assignment.shards.is_empty() {
+                ::metrics::gauge!("torii_shards_owned").set(assignment.shards.len() as f64);
+                tracing::info!(
+                    target: "torii::etl::sharding",
+                    instance = %self.config.instance_id,
+                    shards = ?assignment.shards,
+                    shard_count = self.config.shard_count,
+                    "Claimed contract shards"
+                );
+                return Ok(assignment);
+            }
+            tracing::info!(
+                target: "torii::etl::sharding",
+                instance = %self.config.instance_id,
+                shard_count = self.config.shard_count,
+                "Every shard is leased, standing by"
+            );
+            tokio::time::sleep(self.config.renew_interval()).await;
+        }
+    }
+
+    /// Renews the leases of the claimed shards. Returns `false` if one was taken over.
+    pub async fn renew(&self) -> Result<bool> {
+        let claimed = self.claimed.lock().unwrap().clone();
+        let renewed_at = Instant::now();
+        for shard in claimed {
+            let (now, expires_at) = self.lease_window();
+            if !self
+                .engine_db
+                .claim_shard_lease(shard, &self.config.instance_id, now, expires_at)
+                .await
+                .with_context(|| format!("Failed to renew shard {shard}"))?
+            {
+                tracing::error!(
+                    target: "torii::etl::sharding",
+                    instance = %self.config.instance_id,
+                    shard,
+                    "Shard lease taken over by another instance"
+                );
+                return Ok(false);
+            }
+        }
+        *self.renewed_at.lock().unwrap() = Some(renewed_at);
+        Ok(true)
+    }
+
+    /// Renews the leases until `shutdown` is cancelled.
+    ///
+    /// Fails when a lease is taken over, or when no renewal succeeded for
+    /// [`ShardingConfig::give_up_after`] (the leases expire before the next attempt and
+    /// another instance may then own the shards): the instance must stop indexing.
+    pub async fn renew_until(&self, shutdown: CancellationToken) -> Result<()> {
+        loop {
+            tokio::select! {
+                () = shutdown.cancelled() => return Ok(()),
+                () = tokio::time::sleep(self.config.renew_interval()) => {}
+            }
+            match self.renew().await {
+                Ok(true) => {}
+                Ok(false) => {
+                    ::metrics::counter!("torii_shard_leases_lost_total").increment(1);
+                    anyhow::bail!("Shard lease lost");
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        target: "torii::etl::sharding",
+                        error = %e,
+                        "Failed to renew shard leases"
+                    );
+                    let renewed_at = *self.renewed_at.lock().unwrap();
+                    if renewed_at.is_none_or(|at| at.elapsed() >= self.config.give_up_after()) {
+                        ::metrics::counter!("torii_shard_leases_lost_total").increment(1);
+                        anyhow::bail!("Shard leases expired without renewal: {e}");
+                    }
+                }
+            }
+        }
+    }
+
+    /// Renews the leases in the background until they are released.
+    ///
+    /// Call it right after acquiring the shards: leases expire `lease_ttl` after they are
+    /// claimed, whatever the instance does meanwhile. Losing them is signalled by
+    /// [`ShardCoordinator::leases_lost`]. Does nothing if the renewal already runs.
+    pub fn start_renewal(self: &Arc<Self>) {
+        let mut renewal = self.renewal.lock().unwrap();
+        if renewal.is_some() {
+            return;
+        }
+        let stop = CancellationToken::new();
+        *renewal = Some(stop.clone());
+        let coordinator = Arc::clone(self);
+        tokio::spawn(async move {
+            if let Err(e) = coordinator.renew_until(stop).await {
+                tracing::error!(
+                    target: "torii::etl::sharding",
+                    instance = %coordinator.config.instance_id,
+                    error = %e,
+                    "Lost contract shard leases"
+                );
+                coordinator.lost.cancel();
+            }
+        });
+    }
+
+    /// Completes once the leases were taken over or expired without renewal.
+    pub async fn leases_lost(&self) {
+        self.lost.cancelled().await;
+    }
+
+    /// Releases the leases of the claimed shards, for another instance to take over.
+    pub async fn release(&self) -> Result<()> {
+        if let Some(renewal) = self.renewal.lock().unwrap().take() {
+            renewal.cancel();
+        }
+        self.engine_db
+            .release_shard_leases(&self.config.instance_id)
+            .await?;
+        self.claimed.lock().unwrap().clear();
+        ::metrics::gauge!("torii_shards_owned").set(0.0);
+        tracing::info!(
+            target: "torii::etl::sharding",
+            instance = %self.config.instance_id,
+            "Released contract shards"
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::etl::engine_db::EngineDbConfig;
+
+    fn config(instance_id: &str, max_shards: u32) -> ShardingConfig {
+        ShardingConfig {
+            shard_count: 3,
+            max_shards,
+            instance_id: instance_id.to_string(),
+            lease_ttl: Duration::from_secs(30),
+        }
+    }
+
+    #[tokio::test]
+    async fn instances_claim_disjoint_shards() {
+        let engine_db = Arc::new(
+            EngineDb::new(EngineDbConfig {
+                path: ":memory:".to_string(),
+            })
+            .await
+            .unwrap(),
+        );
+        let a = ShardCoordinator::new(engine_db.clone(), config("a", 2));
+        let b = ShardCoordinator::new(engine_db.clone(), config("b", 2));
+        let c = ShardCoordinator::new(engine_db.clone(), config("c", 2));
+
+        assert_eq!(
+            a.try_acquire().await.unwrap().shards,
+            BTreeSet::from([0, 1])
+        );
+        assert_eq!(b.try_acquire().await.unwrap().shards, BTreeSet::from([2]));
+        assert!(c.try_acquire().await.unwrap().shards.is_empty());
+        assert!(a.renew().await.unwrap());
+
+        // Released shards are taken over by the standby instance.
+        a.release().await.unwrap();
+        assert_eq!(
+            c.try_acquire().await.unwrap().shards,
+            BTreeSet::from([0, 1])
+        );
+        let owners: Vec<_> = engine_db
+            .list_shard_leases()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|lease| lease.owner)
+            .collect();
+        assert_eq!(owners, ["c", "c", "b"]);
+
+        // Expired leases are taken over; the previous owner fails to renew.
+        let now = chrono::Utc::now().timestamp();
+        engine_db
+            .claim_shard_lease(2, "b", now, now - 1)
+            .await
+            .unwrap();
+        assert!(engine_db
+            .claim_shard_lease(2, "d", now, now + 30)
+            .await
+            .unwrap());
+        assert!(!b.renew().await.unwrap());
+
+        let assignment = c.assignment();
+        let owned = (0..100_u64)
+            .map(Felt::from)
+            .filter(|contract| assignment.owns(*contract))
+            .count();
+        assert_eq!(owned, 67);
+    }
+
+    #[tokio::test]
+    async fn gives_up_leases_before_they_expire() {
+        let engine_db = Arc::new(
+            EngineDb::new(EngineDbConfig {
+                path: ":memory:".to_string(),
+            })
+            .await
+            .unwrap(),
+        );
+        let sharding = ShardingConfig {
+            lease_ttl: Duration::from_secs(3),
+            ..config("a", 1)
+        };
+        assert_eq!(sharding.give_up_after(), Duration::from_secs(2));
+        let coordinator = Arc::new(ShardCoordinator::new(engine_db.clone(), sharding));
+        assert_eq!(
+            coordinator.try_acquire().await.unwrap().shards,
+            BTreeSet::from([0])
+        );
+
+        // Renewals fail from now on: the leases are given up before the TTL elapses.
+        let acquired_at = Instant::now();
+        coordinator.start_renewal();
+        engine_db.close().await;
+        tokio::time::timeout(Duration::from_secs(3), coordinator.leases_lost())
+            .await
+            .expect("leases given up before expiring");
+        assert!(acquired_at.elapsed() >= Duration::from_secs(2));
+    }
+}
@@ -3,14 +3,17 @@ pub mod multi;
 use async_trait::async_trait;
 use axum::Router;
 use prost_types::Any;
+use starknet::core::types::Felt;
+use std::collections::HashSet;
 use std::path::PathBuf;
 use std::sync::Arc;
 
 use super::envelope::{Envelope, TypeId};
 use crate::command::CommandBusSender;
+use crate::error::ToriiResult;
 use crate::grpc::SubscriptionManager;
 
-pub use multi::MultiSink;
+pub use multi::{MultiSink, SinkBackpressure, SinkWorkerConfig};
 
 // Re-export for external sink authors
 pub use tonic;
@@ -35,7 +38,7 @@ pub struct SinkContext {
 }
 
 /// Topic information provided by a sink
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TopicInfo {
     /// Topic name (e.g., "sql", "entities")
     pub name: String,
@@ -43,6 +46,9 @@ pub struct TopicInfo {
     pub available_filters: Vec<String>,
     /// Description of what this topic contains
     pub description: String,
+    /// Fully-qualified protobuf type of the published messages
+    /// (e.g., "torii.sinks.erc20.Transfer"), if known
+    pub message_type: Option<String>,
 }
 
 impl TopicInfo {
@@ -55,8 +61,205 @@ impl TopicInfo {
             name: name.into(),
             available_filters,
             description: description.into(),
+            message_type: None,
         }
     }
+
+    /// Sets the protobuf type of the messages published on this topic.
+    pub fn with_message_type(mut self, message_type: impl Into<String>) -> Self {
+        self.message_type = Some(message_type.into());
+        self
+    }
+}
+
+/// Column of a table written by a sink
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnSchema {
+    /// Column name
+    pub name: String,
+    /// Logical type: "felt" (32-byte big-endian), "u256" (big-endian bytes),
+    /// "u64", "i64", "bool", "text" or "json"
+    pub data_type: String,
+    /// Whether the column may be NULL
+    pub nullable: bool,
+}
+
+impl ColumnSchema {
+    /// Creates a non-nullable column.
+    pub fn new(name: impl Into<String>, data_type: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            data_type: data_type.into(),
+            nullable: false,
+        }
+    }
+
+    /// Marks the column as nullable.
+    pub fn nullable(mut self) -> Self {
+        self.nullable = true;
+        self
+    }
+}
+
+/// Table written by a sink
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableSchema {
+    /// Table name
+    pub name: String,
+    /// Description of what a row represents
+    pub description: String,
+    /// Columns, in table order
+    pub columns: Vec<ColumnSchema>,
+}
+
+impl TableSchema {
+    pub fn new(
+        name: impl Into<String>,
+        description: impl Into<String>,
+        columns: Vec<ColumnSchema>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            description: description.into(),
+            columns,
+        }
+    }
+}
+
+/// Envelope type consumed by a sink, with the protobuf message describing it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvelopeSchema {
+    /// Envelope type name (e.g., "erc20.transfer"), hashed into the [`TypeId`]
+    pub type_name: String,
+    /// Protobuf type URL (e.g., "type.googleapis.com/torii.sinks.erc20.Transfer")
+    pub type_url: String,
+}
+
+impl EnvelopeSchema {
+    /// Maps the envelope type `type_name` to the fully-qualified protobuf `message_type`.
+    pub fn new(type_name: impl Into<String>, message_type: &str) -> Self {
+        Self {
+            type_name: type_name.into(),
+            type_url: format!("type.googleapis.com/{message_type}"),
+        }
+    }
+
+    /// [`TypeId`] of the envelopes of this type.
+    pub fn type_id(&self) -> TypeId {
+        TypeId::new(&self.type_name)
+    }
+}
+
+/// Machine-readable description of a sink's output (reported by `DescribeSinks` and
+/// `GetSchema`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SinkDescription {
+    /// Sink name
+    pub name: String,
+    /// Sink version, if reported
+    pub version: Option<String>,
+    /// Topics published by the sink
+    pub topics: Vec<TopicInfo>,
+    /// Tables written by the sink
+    pub tables: Vec<TableSchema>,
+    /// Encoded file descriptor set of the sink's protobuf API, if any
+    pub descriptor_set: Option<&'static [u8]>,
+    /// Envelope types consumed by the sink with their protobuf messages
+    pub envelope_types: Vec<EnvelopeSchema>,
+}
+
+impl SinkDescription {
+    /// Describes a sink from its name, version, topics, tables and schema.
+    pub fn of(sink: &dyn Sink) -> Self {
+        Self {
+            name: sink.name().to_string(),
+            version: sink.version().map(str::to_string),
+            topics: sink.topics(),
+            tables: sink.tables(),
+            descriptor_set: sink.descriptor_set(),
+            envelope_types: sink.envelope_schemas(),
+        }
+    }
+}
+
+/// Per-sink contract routing (allowlist + denylist)
+///
+/// Enforced by `MultiSink` before envelopes reach the sink, which lets several
+/// instances of the same sink shard contracts into different databases.
+///
+/// - Denylisted contracts are always dropped.
+/// - An empty allowlist accepts every contract that is not denylisted.
+/// - A non-empty allowlist only accepts listed contracts; envelopes without a
+///   known `from_address` are dropped in that case.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let game_a = SinkContractFilter::new().allow_contract(world_a);
+/// let everything_else = SinkContractFilter::new().deny_contract(world_a);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SinkContractFilter {
+    /// Contracts routed to the sink (empty = all contracts)
+    pub allowlist: HashSet<Felt>,
+
+    /// Contracts never routed to the sink
+    pub denylist: HashSet<Felt>,
+}
+
+impl SinkContractFilter {
+    /// Create an empty filter (routes every contract)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a contract to the allowlist
+    pub fn allow_contract(mut self, contract: Felt) -> Self {
+        self.allowlist.insert(contract);
+        self
+    }
+
+    /// Add multiple contracts to the allowlist
+    pub fn allow_contracts(mut self, contracts: impl IntoIterator<Item = Felt>) -> Self {
+        self.allowlist.extend(contracts);
+        self
+    }
+
+    /// Add a contract to the denylist
+    pub fn deny_contract(mut self, contract: Felt) -> Self {
+        self.denylist.insert(contract);
+        self
+    }
+
+    /// Add multiple contracts to the denylist
+    pub fn deny_contracts(mut self, contracts: impl IntoIterator<Item = Felt>) -> Self {
+        self.denylist.extend(contracts);
+        self
+    }
+
+    /// Check if the filter routes everything (no allowlist, no denylist)
+    pub fn is_empty(&self) -> bool {
+        self.allowlist.is_empty() && self.denylist.is_empty()
+    }
+
+    /// Check if an envelope from `contract` should be routed to the sink
+    pub fn allows(&self, contract: Option<Felt>) -> bool {
+        match contract {
+            Some(contract) => {
+                !self.denylist.contains(&contract)
+                    && (self.allowlist.is_empty() || self.allowlist.contains(&contract))
+            }
+            None => self.allowlist.is_empty(),
+        }
+    }
+
+    /// Validate configuration (no contract in both allowlist and denylist)
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if let Some(contract) = self.allowlist.intersection(&self.denylist).next() {
+            anyhow::bail!("Contract {contract:#x} appears in both sink allowlist and denylist");
+        }
+        Ok(())
+    }
 }
 
 /// Sink trait - processes envelopes and exposes functionality
@@ -75,9 +278,24 @@ pub trait Sink: Send + Sync {
     /// Get the name of this sink
     fn name(&self) -> &str;
 
+    /// Get the version of this sink (reported by `GetCapabilities`)
+    ///
+    /// Sink crates typically return `Some(env!("CARGO_PKG_VERSION"))`.
+    fn version(&self) -> Option<&str> {
+        None
+    }
+
     /// Get the type IDs this sink is interested in
     fn interested_types(&self) -> Vec<TypeId>;
 
+    /// Get the contract routing filter for this sink
+    ///
+    /// When set, `MultiSink` only hands this sink the envelopes whose
+    /// `from_address` passes the filter. Defaults to no filtering.
+    fn contract_filter(&self) -> Option<&SinkContractFilter> {
+        None
+    }
+
     /// Process a batch of envelopes with enriched context
     ///
     /// # Arguments
@@ -108,7 +326,8 @@ pub trait Sink: Send + Sync {
     /// # Performance Best Practices
     ///
     /// **DO:**
-    /// - Use `envelope.metadata` for event-specific data extracted by the decoder.
+    /// - Use `envelope.meta` for the source event (block, timestamp, transaction, contract).
+    /// - Use `envelope.metadata` for decoder-specific data extracted by the decoder.
     /// - Use `batch.blocks[&block_number]` for fast O(1) block context lookups.
     /// - Use `batch.transactions[&tx_hash]` for fast O(1) transaction context lookups.
     ///
@@ -119,20 +338,20 @@ pub trait Sink: Send + Sync {
     /// # Example
     ///
     /// ```rust,ignore
-    /// async fn process(&self, envelopes: &[Envelope], batch: &ExtractionBatch) -> Result<()> {
+    /// async fn process(&self, envelopes: &[Envelope], batch: &ExtractionBatch) -> ToriiResult<()> {
     ///     for envelope in envelopes {
     ///         let insert = envelope.downcast_ref::<SqlInsert>()?;
     ///
-    ///         // Fast: Access via metadata (set by decoder)
-    ///         let block_number: u64 = envelope.metadata.get("block_number")
-    ///             .unwrap().parse()?;
+    ///         // Fast: typed source metadata (set by the DecoderContext)
+    ///         let block_number = envelope.meta.block_number.unwrap_or_default();
+    ///         let contract = envelope.meta.contract;
     ///
     ///         // Fast: O(1) HashMap lookup
     ///         let block = &batch.blocks[&block_number];
     ///         println!("Block timestamp: {}", block.timestamp);
     ///
     ///         // If you need more event data, decoder should add it to metadata
-    ///         let from_address = envelope.metadata.get("from_address").unwrap();
+    ///         let token_id: Option<u64> = envelope.metadata_as("token_id");
     ///     }
     ///     Ok(())
     /// }
@@ -141,7 +360,38 @@ pub trait Sink: Send + Sync {
         &self,
         envelopes: &[Envelope],
         batch: &crate::etl::extractor::ExtractionBatch,
-    ) -> anyhow::Result<()>;
+    ) -> ToriiResult<()>;
+
+    /// Flush data buffered by [`process`](Self::process) to durable storage
+    ///
+    /// Called after each processed batch and once on graceful shutdown. The extractor
+    /// cursor of a batch is only committed once every sink flushed successfully, so
+    /// sinks buffering writes (Kafka, ClickHouse, Parquet, ...) never lose data on
+    /// restart. A failed flush is retried on the next batch. Defaults to a no-op for
+    /// sinks writing synchronously in `process`.
+    async fn flush(&self) -> ToriiResult<()> {
+        Ok(())
+    }
+
+    /// Highest block whose data this sink committed to its own storage
+    ///
+    /// Used by the startup consistency check to detect a cursor ahead of the sink data
+    /// (e.g. after a crash). Sinks derive it from what they stored, so it is a lower
+    /// bound: blocks without data for the sink are not counted. Defaults to `None`
+    /// (not tracked), which excludes the sink from the check.
+    async fn indexed_block(&self) -> ToriiResult<Option<u64>> {
+        Ok(None)
+    }
+
+    /// Called on idle ETL cycles: an empty batch was extracted and no data is left to
+    /// flush (e.g. the indexer caught up with the chain head)
+    ///
+    /// Sinks can run storage maintenance here (`ANALYZE`, vacuum, ...). The next cycle
+    /// waits for it, so keep it bounded or rate-limited. Failures are logged and do not
+    /// affect indexing. Defaults to a no-op.
+    async fn on_idle(&self) -> ToriiResult<()> {
+        Ok(())
+    }
 
     /// Get topic information provided by this sink
     ///
@@ -149,6 +399,30 @@ pub trait Sink: Send + Sync {
     /// This is used by the ListTopics gRPC endpoint to inform clients about available subscriptions.
     fn topics(&self) -> Vec<TopicInfo>;
 
+    /// Get the tables written by this sink
+    ///
+    /// Used by the DescribeSinks gRPC endpoint so tooling can build queries against
+    /// the sink's storage. Sinks without a queryable schema return nothing (the default).
+    fn tables(&self) -> Vec<TableSchema> {
+        Vec::new()
+    }
+
+    /// Get the encoded file descriptor set of this sink's protobuf API
+    ///
+    /// Served with the core descriptors by `/schema` and the GetSchema gRPC endpoint,
+    /// so client code generators can bootstrap from a live server. Sink crates
+    /// typically return their `FILE_DESCRIPTOR_SET`. Defaults to `None`.
+    fn descriptor_set(&self) -> Option<&'static [u8]> {
+        None
+    }
+
+    /// Get the protobuf messages of the envelope types consumed by this sink
+    ///
+    /// Reported in the TypeId catalog of `/schema` and GetSchema. Defaults to none.
+    fn envelope_schemas(&self) -> Vec<EnvelopeSchema> {
+        Vec::new()
+    }
+
     /// Build HTTP routes for this sink
     ///
     /// Sinks can expose custom HTTP endpoints by implementing this method.
@@ -180,7 +454,7 @@ pub trait Sink: Send + Sync {
         &mut self,
         event_bus: Arc<EventBus>,
         context: &SinkContext,
-    ) -> anyhow::Result<()>;
+    ) -> ToriiResult<()>;
 }
 
 /// EventBus allows sinks to publish updates to gRPC subscribers
@@ -205,6 +479,10 @@ impl EventBus {
     /// a client's subscription filters. This keeps filtering logic in the sink,
     /// not in the core.
     ///
+    /// Updates are numbered per topic and buffered, so clients reconnecting with
+    /// `resume_from_sequence` get the updates they missed (see
+    /// [`SubscriptionManager::resume_subscriptions`]).
+    ///
     /// # Arguments
     /// * `topic` - Topic name (e.g., "sql", "events")
     /// * `type_id` - Type identifier (e.g., "sql.row_inserted")
@@ -213,8 +491,11 @@ impl EventBus {
     /// * `update_type` - Type of update (Created, Updated, Deleted)
     /// * `filter_fn` - Sink-provided function to check if data matches filters
     ///
+    /// Returns the number of clients the update was sent to.
+    ///
     /// # Performance
-    /// Cost: 1 encode + 0 decodes (vs 1 encode + N decodes in naive approach)
+    /// Cost: 1 encode + 0 decodes (vs 1 encode + N decodes in naive approach).
+    /// The decoded data is cloned once to filter replayed updates.
     pub fn publish_protobuf<F, T>(
         &self,
         topic: &str,
@@ -223,42 +504,27 @@ impl EventBus {
         decoded: &T,
         update_type: crate::grpc::UpdateType,
         filter_fn: F,
-    ) where
-        F: Fn(&T, &std::collections::HashMap<String, String>) -> bool,
-        T: ?Sized,
+    ) -> usize
+    where
+        F: Fn(&T, &std::collections::HashMap<String, String>) -> bool + Send + Sync + 'static,
+        T: Clone + Send + Sync + 'static,
     {
         use crate::grpc::TopicUpdate;
 
-        let clients = self.subscription_manager.clients().read().unwrap();
-        let timestamp = chrono::Utc::now().timestamp();
-        let mut sent_count = 0;
-
-        for (client_id, client_sub) in clients.iter() {
-            if let Some(filters) = client_sub.topics.get(topic) {
-                // Sink decides if data matches client filters
-                // Uses decoded data - no decode overhead!
-                if filter_fn(decoded, filters) {
-                    let update = TopicUpdate {
-                        topic: topic.to_string(),
-                        update_type: update_type as i32,
-                        timestamp,
-                        type_id: type_id.to_string(),
-                        data: Some(data.clone()),
-                    };
-
-                    if let Err(e) = client_sub.tx.try_send(update) {
-                        tracing::debug!(
-                            target: "torii::etl::event_bus",
-                            "Failed to send to client {}: {}",
-                            client_id,
-                            e
-                        );
-                    } else {
-                        sent_count += 1;
-                    }
-                }
-            }
-        }
+        let update = TopicUpdate {
+            topic: topic.to_string(),
+            update_type: update_type as i32,
+            timestamp: chrono::Utc::now().timestamp(),
+            type_id: type_id.to_string(),
+            data: Some(data.clone()),
+            // Assigned by the subscription manager
+            sequence: 0,
+            topic_sequence: 0,
+        };
+        let decoded = decoded.clone();
+        let sent_count = self
+            .subscription_manager
+            .publish(update, move |filters| filter_fn(&decoded, filters));
 
         tracing::debug!(
             target: "torii::etl::event_bus",
@@ -267,6 +533,7 @@ impl EventBus {
             type_id,
             sent_count
         );
+        sent_count
     }
 
     /// Get the subscription manager for advanced use cases
@@ -1,32 +1,289 @@
-//! MultiSink runs multiple sinks in sequence
+//! MultiSink fans each batch out to multiple sinks concurrently
 //!
-//! Each sink processes envelopes independently.
+//! Each sink processes envelopes independently, in batch order.
 //! Sinks can filter by TypeId to only process events they're interested in.
+//! Sinks exposing a `SinkContractFilter` only receive envelopes from the contracts it allows.
+//! How many sinks run at once and how long each may take is configured with
+//! [`MultiSink::with_max_concurrency`] and [`SinkWorkerConfig`].
+//! Batches queued for the sinks are tracked by [`SinkBackpressure`], which the ETL
+//! loop uses to slow extraction down while the sinks fall behind.
 
 use async_trait::async_trait;
 use axum::Router;
 use futures::future::join_all;
+use futures::StreamExt;
+use std::borrow::Cow;
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 
-use super::{EventBus, Sink, SinkContext};
+use super::{EventBus, Sink, SinkContext, SinkDescription};
+use crate::error::{Stage, ToriiError, ToriiResult};
+use crate::etl::counters::CumulativeCounters;
 use crate::etl::envelope::Envelope;
 use crate::etl::extractor::ExtractionBatch;
+use crate::namespace::Namespaces;
+
+/// Depth of the batches queued for a [`MultiSink`], shared with the extract stage.
+///
+/// The pipeline marks a batch queued once it is decoded and done once the sinks
+/// processed it. Above the threshold the sinks are saturated: extraction waits until
+/// the depth drops back to the threshold, then resumes.
+#[derive(Debug)]
+pub struct SinkBackpressure {
+    depth: tokio::sync::watch::Sender<usize>,
+    /// Queued batches tolerated before throttling (0 = disabled)
+    threshold: usize,
+}
+
+impl SinkBackpressure {
+    pub fn new(threshold: usize) -> Self {
+        Self {
+            depth: tokio::sync::watch::Sender::new(0),
+            threshold,
+        }
+    }
+
+    /// Queued batches tolerated before throttling (0 = disabled).
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    /// Batches queued for or being processed by the sinks.
+    pub fn depth(&self) -> usize {
+        *self.depth.borrow()
+    }
+
+    /// Records a batch handed to the sinks.
+    pub fn batch_queued(&self) {
+        self.depth.send_modify(|depth| *depth += 1);
+        self.publish();
+    }
+
+    /// Records a batch the sinks are done with (processed or failed).
+    pub fn batch_done(&self) {
+        self.depth
+            .send_modify(|depth| *depth = depth.saturating_sub(1));
+        self.publish();
+    }
+
+    /// Whether the queued depth exceeds the threshold.
+    pub fn is_saturated(&self) -> bool {
+        self.threshold > 0 && self.depth() > self.threshold
+    }
+
+    /// Resolves once the queued depth is back to the threshold.
+    pub async fn drained(&self) {
+        if self.threshold == 0 {
+            return;
+        }
+        let threshold = self.threshold;
+        // The sender lives in `self`, so the channel cannot close while waiting.
+        let _ = self
+            .depth
+            .subscribe()
+            .wait_for(|depth| *depth <= threshold)
+            .await;
+    }
+
+    fn publish(&self) {
+        ::metrics::gauge!("torii_sink_queued_batches").set(self.depth() as f64);
+    }
+}
+
+impl Default for SinkBackpressure {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+/// Execution settings of one sink within a [`MultiSink`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SinkWorkerConfig {
+    /// Maximum time `process` may take for one batch (None = unbounded).
+    ///
+    /// A sink exceeding it is handled like a failed sink; the other sinks are not affected.
+    pub timeout: Option<Duration>,
+    /// Process batches alone, once the concurrent sinks are done (e.g. sinks contending
+    /// for the same database writer).
+    pub exclusive: bool,
+}
+
+impl SinkWorkerConfig {
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn exclusive(mut self) -> Self {
+        self.exclusive = true;
+        self
+    }
+}
 
 /// MultiSink runs multiple sinks and merges their routes
 pub struct MultiSink {
     sinks: Vec<Arc<dyn Sink>>,
+    /// Cumulative per-sink row counts (None = not tracked)
+    counters: Option<Arc<CumulativeCounters>>,
+    /// Prefix of the sink routes (None = mounted at the root)
+    route_prefix: Option<String>,
+    /// Sinks processing a batch at the same time (0 = all of them)
+    max_concurrency: usize,
+    /// Per-sink settings, by sink name
+    workers: HashMap<String, SinkWorkerConfig>,
+    /// Queued batch depth reported to the extract stage
+    backpressure: Arc<SinkBackpressure>,
 }
 
 impl MultiSink {
     /// Create a new MultiSink with a list of sinks
     pub fn new(sinks: Vec<Arc<dyn Sink>>) -> Self {
-        Self { sinks }
+        Self {
+            sinks,
+            counters: None,
+            route_prefix: None,
+            max_concurrency: 0,
+            workers: HashMap::new(),
+            backpressure: Arc::new(SinkBackpressure::default()),
+        }
+    }
+
+    /// Process a batch with at most `limit` sinks at the same time (0 = all sinks, the default)
+    pub fn with_max_concurrency(mut self, limit: usize) -> Self {
+        self.max_concurrency = limit;
+        self
+    }
+
+    /// Throttle extraction once more than `threshold` batches are queued for the sinks
+    /// (0 = never, the default)
+    pub fn with_backpressure_threshold(mut self, threshold: usize) -> Self {
+        self.backpressure = Arc::new(SinkBackpressure::new(threshold));
+        self
+    }
+
+    /// Queued batch depth of the sinks, polled by the extract stage
+    pub fn backpressure(&self) -> Arc<SinkBackpressure> {
+        self.backpressure.clone()
+    }
+
+    /// Set the execution settings of the sink named `sink`
+    pub fn with_worker_config(mut self, sink: impl Into<String>, config: SinkWorkerConfig) -> Self {
+        self.workers.insert(sink.into(), config);
+        self
+    }
+
+    /// Set the execution settings of several sinks, by sink name
+    pub fn with_worker_configs(
+        mut self,
+        configs: impl IntoIterator<Item = (String, SinkWorkerConfig)>,
+    ) -> Self {
+        self.workers.extend(configs);
+        self
+    }
+
+    fn worker_config(&self, sink: &dyn Sink) -> SinkWorkerConfig {
+        self.workers.get(sink.name()).copied().unwrap_or_default()
+    }
+
+    /// Process the batch with one sink, bounded by its timeout.
+    ///
+    /// Returns the sink, the number of envelopes routed to it, the processing duration
+    /// and the result.
+    async fn run_sink(
+        &self,
+        sink: Arc<dyn Sink>,
+        envelopes: &[Envelope],
+        batch: &ExtractionBatch,
+    ) -> (Arc<dyn Sink>, usize, Duration, ToriiResult<()>) {
+        let routed = Self::route(sink.as_ref(), envelopes);
+        let sink_start = std::time::Instant::now();
+        let result = match self.worker_config(sink.as_ref()).timeout {
+            Some(timeout) => tokio::time::timeout(timeout, sink.process(&routed, batch))
+                .await
+                .unwrap_or_else(|_| {
+                    ::metrics::counter!("torii_sink_timeouts_total", "sink" => sink.name().to_string())
+                        .increment(1);
+                    Err(ToriiError::Sink(anyhow::anyhow!("timed out after {timeout:?}")))
+                }),
+            None => sink.process(&routed, batch).await,
+        };
+        let result = result.map_err(|e| e.in_stage(Stage::Sink));
+        (sink, routed.len(), sink_start.elapsed(), result)
+    }
+
+    /// Record envelopes successfully processed by each sink in `counters`
+    pub fn with_counters(mut self, counters: Arc<CumulativeCounters>) -> Self {
+        self.counters = Some(counters);
+        self
+    }
+
+    /// Mount the routes of each sink under `<prefix>/<sink name>` (e.g. `/sinks/sql/...`)
+    ///
+    /// With None, routes are mounted at the root and may collide across sinks.
+    pub fn with_route_prefix(mut self, prefix: Option<String>) -> Self {
+        self.route_prefix = prefix;
+        self
     }
 
     /// Get all sinks (useful for accessing specific sinks after creation)
     pub fn sinks(&self) -> &[Arc<dyn Sink>] {
         &self.sinks
     }
+
+    /// Describe the output (topics and tables) of each sink
+    pub fn describe(&self) -> Vec<SinkDescription> {
+        self.sinks
+            .iter()
+            .map(|sink| SinkDescription::of(sink.as_ref()))
+            .collect()
+    }
+
+    /// Build the routes of every sink, each admitting only the requests whose API key
+    /// permits the sink namespace (see [`Namespaces::authorize_routes`])
+    pub fn build_authorized_routes(&self, namespaces: &Namespaces) -> Router {
+        self.merge_routes(|sink, routes| namespaces.authorize_routes(sink.name(), routes))
+    }
+
+    fn merge_routes(&self, wrap: impl Fn(&dyn Sink, Router) -> Router) -> Router {
+        let mut router = Router::new();
+        for sink in &self.sinks {
+            let routes = wrap(sink.as_ref(), sink.build_routes());
+            router = match &self.route_prefix {
+                Some(prefix) => router.nest(&sink_route_path(prefix, sink.name()), routes),
+                None => router.merge(routes),
+            };
+        }
+        router
+    }
+
+    /// Select the envelopes routed to a sink according to its contract filter.
+    ///
+    /// Borrows the whole batch when the sink has no filter (or the filter keeps
+    /// everything), and only clones envelopes (cheap, shared bodies) otherwise.
+    fn route<'a>(sink: &dyn Sink, envelopes: &'a [Envelope]) -> Cow<'a, [Envelope]> {
+        let Some(filter) = sink.contract_filter().filter(|filter| !filter.is_empty()) else {
+            return Cow::Borrowed(envelopes);
+        };
+
+        if envelopes
+            .iter()
+            .all(|envelope| filter.allows(envelope.meta.contract))
+        {
+            return Cow::Borrowed(envelopes);
+        }
+
+        let routed: Vec<Envelope> = envelopes
+            .iter()
+            .filter(|envelope| filter.allows(envelope.meta.contract))
+            .cloned()
+            .collect();
+
+        ::metrics::counter!("torii_sink_envelopes_filtered_total", "sink" => sink.name().to_string())
+            .increment((envelopes.len() - routed.len()) as u64);
+
+        Cow::Owned(routed)
+    }
 }
 
 #[async_trait]
@@ -40,15 +297,33 @@ impl Sink for MultiSink {
         vec![]
     }
 
-    async fn process(&self, envelopes: &[Envelope], batch: &ExtractionBatch) -> anyhow::Result<()> {
-        let sink_results = join_all(self.sinks.iter().map(|sink| async move {
-            let sink_start = std::time::Instant::now();
-            let result = sink.process(envelopes, batch).await;
-            (sink, sink_start.elapsed(), result)
-        }))
-        .await;
+    async fn process(&self, envelopes: &[Envelope], batch: &ExtractionBatch) -> ToriiResult<()> {
+        let (exclusive, concurrent): (Vec<_>, Vec<_>) = self
+            .sinks
+            .iter()
+            .cloned()
+            .partition(|sink| self.worker_config(sink.as_ref()).exclusive);
+
+        let limit = if self.max_concurrency == 0 {
+            concurrent.len().max(1)
+        } else {
+            self.max_concurrency
+        };
+        // Futures are collected first: mapping inside the stream trips the `Send` check of
+        // the boxed `process` future.
+        let runs: Vec<_> = concurrent
+            .into_iter()
+            .map(|sink| self.run_sink(sink, envelopes, batch))
+            .collect();
+        let mut sink_results: Vec<_> = futures::stream::iter(runs)
+            .buffer_unordered(limit)
+            .collect()
+            .await;
+        for sink in exclusive {
+            sink_results.push(self.run_sink(sink, envelopes, batch).await);
+        }
 
-        for (sink, elapsed, result) in sink_results {
+        for (sink, rows, elapsed, result) in sink_results {
             if let Err(e) = result {
                 tracing::error!(
                     target: "torii::etl::multi_sink",
@@ -60,6 +335,8 @@ impl Sink for MultiSink {
                     .increment(1);
                 // TODO: Currently, if a sink fails at processing an event, it will not be retried.
                 // We should see a better mechanism here, is it better to retry and stop the whole process if it fails again?
+            } else if let Some(counters) = &self.counters {
+                counters.record_sink_rows(sink.name(), rows as u64);
             }
             ::metrics::histogram!("torii_sink_process_duration_seconds", "sink" => sink.name().to_string())
                 .record(elapsed.as_secs_f64());
@@ -75,6 +352,69 @@ impl Sink for MultiSink {
         Ok(())
     }
 
+    async fn flush(&self) -> ToriiResult<()> {
+        let flush_results = join_all(self.sinks.iter().map(|sink| async move {
+            let flush_start = std::time::Instant::now();
+            let result = sink.flush().await;
+            (sink, flush_start.elapsed(), result)
+        }))
+        .await;
+
+        let mut failed = Vec::new();
+        for (sink, elapsed, result) in flush_results {
+            if let Err(e) = result {
+                tracing::error!(
+                    target: "torii::etl::multi_sink",
+                    "Sink '{}' flush failed: {}",
+                    sink.name(),
+                    e
+                );
+                ::metrics::counter!("torii_sink_flush_failures_total", "sink" => sink.name().to_string())
+                    .increment(1);
+                failed.push(sink.name().to_string());
+            }
+            ::metrics::histogram!("torii_sink_flush_duration_seconds", "sink" => sink.name().to_string())
+                .record(elapsed.as_secs_f64());
+        }
+
+        if failed.is_empty() {
+            Ok(())
+        } else {
+            Err(ToriiError::Sink(anyhow::anyhow!(
+                "Sinks failed to flush: {}",
+                failed.join(", ")
+            )))
+        }
+    }
+
+    async fn indexed_block(&self) -> ToriiResult<Option<u64>> {
+        // The lowest block committed by the sinks tracking one
+        let mut indexed = None;
+        for sink in &self.sinks {
+            if let Some(block) = sink.indexed_block().await? {
+                indexed = Some(indexed.map_or(block, |b: u64| b.min(block)));
+            }
+        }
+        Ok(indexed)
+    }
+
+    async fn on_idle(&self) -> ToriiResult<()> {
+        for sink in &self.sinks {
+            let start = std::time::Instant::now();
+            if let Err(e) = sink.on_idle().await {
+                tracing::warn!(
+                    target: "torii::etl::multi_sink",
+                    "Sink '{}' idle maintenance failed: {}",
+                    sink.name(),
+                    e
+                );
+            }
+            ::metrics::histogram!("torii_sink_idle_duration_seconds", "sink" => sink.name().to_string())
+                .record(start.elapsed().as_secs_f64());
+        }
+        Ok(())
+    }
+
     fn topics(&self) -> Vec<super::TopicInfo> {
         // Aggregate topics from all sinks
         let mut all_topics = Vec::new();
@@ -84,20 +424,27 @@ impl Sink for MultiSink {
         all_topics
     }
 
+    fn tables(&self) -> Vec<super::TableSchema> {
+        self.sinks.iter().flat_map(|sink| sink.tables()).collect()
+    }
+
+    fn envelope_schemas(&self) -> Vec<super::EnvelopeSchema> {
+        self.sinks
+            .iter()
+            .flat_map(|sink| sink.envelope_schemas())
+            .collect()
+    }
+
     fn build_routes(&self) -> Router {
         // Merge all sink routes into a single router
-        let mut router = Router::new();
-        for sink in &self.sinks {
-            router = router.merge(sink.build_routes());
-        }
-        router
+        self.merge_routes(|_, routes| routes)
     }
 
     async fn initialize(
         &mut self,
         _event_bus: Arc<EventBus>,
         _context: &SinkContext,
-    ) -> anyhow::Result<()> {
+    ) -> ToriiResult<()> {
         // Initialize all sinks with the event bus
         for _sink in &mut self.sinks {
             // We need to get mutable access, but sinks are Arc'd
@@ -111,11 +458,22 @@ impl Sink for MultiSink {
     }
 }
 
+/// `/<prefix>/<sink name>`, ignoring leading and trailing slashes of the prefix
+fn sink_route_path(prefix: &str, sink_name: &str) -> String {
+    let prefix = prefix.trim_matches('/');
+    if prefix.is_empty() {
+        format!("/{sink_name}")
+    } else {
+        format!("/{prefix}/{sink_name}")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::etl::envelope::{Envelope, TypeId};
     use crate::etl::extractor::ExtractionBatch;
+    use crate::etl::sink::SinkContractFilter;
     use crate::grpc::SubscriptionManager;
     use std::collections::HashMap;
     use std::sync::atomic::{AtomicUsize, Ordering};
@@ -141,7 +499,7 @@ mod tests {
             &self,
             _envelopes: &[Envelope],
             _batch: &ExtractionBatch,
-        ) -> anyhow::Result<()> {
+        ) -> ToriiResult<()> {
             Ok(())
         }
 
@@ -157,7 +515,7 @@ mod tests {
             &mut self,
             _event_bus: Arc<EventBus>,
             _context: &SinkContext,
-        ) -> anyhow::Result<()> {
+        ) -> ToriiResult<()> {
             Ok(())
         }
     }
@@ -217,7 +575,7 @@ mod tests {
             &self,
             _envelopes: &[Envelope],
             _batch: &ExtractionBatch,
-        ) -> anyhow::Result<()> {
+        ) -> ToriiResult<()> {
             let current = self.active.fetch_add(1, Ordering::SeqCst) + 1;
             self.max_active.fetch_max(current, Ordering::SeqCst);
             self.barrier.wait().await;
@@ -238,7 +596,7 @@ mod tests {
             &mut self,
             _event_bus: Arc<EventBus>,
             _context: &SinkContext,
-        ) -> anyhow::Result<()> {
+        ) -> ToriiResult<()> {
             Ok(())
         }
     }
@@ -274,4 +632,379 @@ mod tests {
 
         assert!(max_active.load(Ordering::SeqCst) >= 2);
     }
+
+    struct TrackedSink {
+        name: &'static str,
+        delay: Duration,
+        active: Arc<AtomicUsize>,
+        max_active: Arc<AtomicUsize>,
+        completed: Arc<std::sync::Mutex<Vec<&'static str>>>,
+    }
+
+    #[async_trait]
+    impl Sink for TrackedSink {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn interested_types(&self) -> Vec<TypeId> {
+            vec![]
+        }
+
+        async fn process(
+            &self,
+            _envelopes: &[Envelope],
+            _batch: &ExtractionBatch,
+        ) -> ToriiResult<()> {
+            let current = self.active.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_active.fetch_max(current, Ordering::SeqCst);
+            sleep(self.delay).await;
+            self.active.fetch_sub(1, Ordering::SeqCst);
+            self.completed.lock().unwrap().push(self.name);
+            Ok(())
+        }
+
+        fn topics(&self) -> Vec<super::super::TopicInfo> {
+            vec![]
+        }
+
+        fn build_routes(&self) -> Router {
+            Router::new()
+        }
+
+        async fn initialize(
+            &mut self,
+            _event_bus: Arc<EventBus>,
+            _context: &SinkContext,
+        ) -> ToriiResult<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_multi_sink_worker_limits() {
+        let active = Arc::new(AtomicUsize::new(0));
+        let max_active = Arc::new(AtomicUsize::new(0));
+        let completed = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = |name: &'static str, delay_ms: u64| -> Arc<dyn Sink> {
+            Arc::new(TrackedSink {
+                name,
+                delay: Duration::from_millis(delay_ms),
+                active: active.clone(),
+                max_active: max_active.clone(),
+                completed: completed.clone(),
+            })
+        };
+        let batch = ExtractionBatch::empty();
+
+        // At most two sinks at once; the exclusive sink runs alone, after the others.
+        let multi_sink = MultiSink::new(vec![
+            sink("archive", 5),
+            sink("sql", 10),
+            sink("log", 10),
+            sink("kafka", 10),
+        ])
+        .with_max_concurrency(2)
+        .with_worker_config("archive", SinkWorkerConfig::default().exclusive());
+        multi_sink.process(&[], &batch).await.unwrap();
+        assert_eq!(max_active.load(Ordering::SeqCst), 2);
+        assert_eq!(completed.lock().unwrap().len(), 4);
+        assert_eq!(completed.lock().unwrap().last(), Some(&"archive"));
+
+        // A sink over its timeout is cancelled without failing the batch or the other sinks.
+        completed.lock().unwrap().clear();
+        let multi_sink = MultiSink::new(vec![sink("slow", 1000), sink("fast", 1)])
+            .with_worker_config(
+                "slow",
+                SinkWorkerConfig::default().with_timeout(Duration::from_millis(20)),
+            );
+        timeout(Duration::from_millis(500), multi_sink.process(&[], &batch))
+            .await
+            .expect("timed out sink should be cancelled")
+            .unwrap();
+        assert_eq!(*completed.lock().unwrap(), vec!["fast"]);
+    }
+
+    struct FlushingSink {
+        name: String,
+        fail: bool,
+        flushes: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl Sink for FlushingSink {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        fn interested_types(&self) -> Vec<TypeId> {
+            vec![]
+        }
+
+        async fn process(
+            &self,
+            _envelopes: &[Envelope],
+            _batch: &ExtractionBatch,
+        ) -> ToriiResult<()> {
+            Ok(())
+        }
+
+        async fn flush(&self) -> ToriiResult<()> {
+            self.flushes.fetch_add(1, Ordering::SeqCst);
+            if self.fail {
+                return Err(anyhow::anyhow!("broker unavailable").into());
+            }
+            Ok(())
+        }
+
+        fn topics(&self) -> Vec<super::super::TopicInfo> {
+            vec![]
+        }
+
+        fn build_routes(&self) -> Router {
+            Router::new()
+        }
+
+        async fn initialize(
+            &mut self,
+            _event_bus: Arc<EventBus>,
+            _context: &SinkContext,
+        ) -> ToriiResult<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_multi_sink_flushes_every_sink() {
+        let flushes = Arc::new(AtomicUsize::new(0));
+        let sink = |name: &str, fail: bool| -> Arc<dyn Sink> {
+            Arc::new(FlushingSink {
+                name: name.to_string(),
+                fail,
+                flushes: flushes.clone(),
+            })
+        };
+
+        let multi_sink = MultiSink::new(vec![sink("kafka", false), sink("clickhouse", false)]);
+        multi_sink.flush().await.unwrap();
+        assert_eq!(flushes.load(Ordering::SeqCst), 2);
+
+        // A failing sink fails the flush, but every sink is still flushed.
+        let multi_sink = MultiSink::new(vec![sink("kafka", true), sink("clickhouse", false)]);
+        let error = multi_sink.flush().await.unwrap_err();
+        assert!(error.to_string().contains("kafka"));
+        assert_eq!(flushes.load(Ordering::SeqCst), 4);
+    }
+
+    struct TestBody;
+
+    crate::typed_body_impl!(TestBody, "test.event");
+
+    struct RecordingSink {
+        filter: Option<SinkContractFilter>,
+        received: std::sync::Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl Sink for RecordingSink {
+        fn name(&self) -> &'static str {
+            "recording"
+        }
+
+        fn interested_types(&self) -> Vec<TypeId> {
+            vec![TypeId::new("test.event")]
+        }
+
+        fn contract_filter(&self) -> Option<&SinkContractFilter> {
+            self.filter.as_ref()
+        }
+
+        async fn process(
+            &self,
+            envelopes: &[Envelope],
+            _batch: &ExtractionBatch,
+        ) -> ToriiResult<()> {
+            self.received
+                .lock()
+                .unwrap()
+                .extend(envelopes.iter().map(|envelope| envelope.id.clone()));
+            Ok(())
+        }
+
+        fn topics(&self) -> Vec<super::super::TopicInfo> {
+            vec![]
+        }
+
+        fn build_routes(&self) -> Router {
+            Router::new()
+        }
+
+        async fn initialize(
+            &mut self,
+            _event_bus: Arc<EventBus>,
+            _context: &SinkContext,
+        ) -> ToriiResult<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_multi_sink_routes_by_contract_filter() {
+        use starknet::core::types::Felt;
+
+        let game_a = Felt::from(0xa_u64);
+        let game_b = Felt::from(0xb_u64);
+        let envelope = |id: &str, contract: Option<Felt>| {
+            let envelope = Envelope::new(id.to_string(), Box::new(TestBody), HashMap::new());
+            match contract {
+                Some(contract) => envelope.with_from_address(contract),
+                None => envelope,
+            }
+        };
+        let envelopes = vec![
+            envelope("a1", Some(game_a)),
+            envelope("b1", Some(game_b)),
+            envelope("unknown", None),
+            envelope("a2", Some(game_a)),
+        ];
+
+        let sink_a = Arc::new(RecordingSink {
+            filter: Some(SinkContractFilter::new().allow_contract(game_a)),
+            received: std::sync::Mutex::default(),
+        });
+        let sink_not_a = Arc::new(RecordingSink {
+            filter: Some(SinkContractFilter::new().deny_contract(game_a)),
+            received: std::sync::Mutex::default(),
+        });
+        let sink_all = Arc::new(RecordingSink {
+            filter: None,
+            received: std::sync::Mutex::default(),
+        });
+
+        let multi_sink = MultiSink::new(vec![sink_a.clone(), sink_not_a.clone(), sink_all.clone()]);
+        multi_sink
+            .process(&envelopes, &ExtractionBatch::empty())
+            .await
+            .unwrap();
+
+        assert_eq!(*sink_a.received.lock().unwrap(), vec!["a1", "a2"]);
+        assert_eq!(*sink_not_a.received.lock().unwrap(), vec!["b1", "unknown"]);
+        assert_eq!(
+            *sink_all.received.lock().unwrap(),
+            vec!["a1", "b1", "unknown", "a2"]
+        );
+    }
+
+    #[test]
+    fn test_sink_contract_filter_rejects_overlap() {
+        use starknet::core::types::Felt;
+
+        let contract = Felt::from(1_u64);
+        let filter = SinkContractFilter::new()
+            .allow_contract(contract)
+            .deny_contract(contract);
+        assert!(filter.validate().is_err());
+    }
+
+    struct RoutedSink {
+        name: &'static str,
+    }
+
+    #[async_trait]
+    impl Sink for RoutedSink {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn interested_types(&self) -> Vec<TypeId> {
+            vec![]
+        }
+
+        async fn process(
+            &self,
+            _envelopes: &[Envelope],
+            _batch: &ExtractionBatch,
+        ) -> ToriiResult<()> {
+            Ok(())
+        }
+
+        fn topics(&self) -> Vec<super::super::TopicInfo> {
+            vec![]
+        }
+
+        fn build_routes(&self) -> Router {
+            let name = self.name;
+            Router::new().route("/ping", axum::routing::get(move || async move { name }))
+        }
+
+        async fn initialize(
+            &mut self,
+            _event_bus: Arc<EventBus>,
+            _context: &SinkContext,
+        ) -> ToriiResult<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_route_prefix_mounts_each_sink() {
+        use axum::body::Body;
+        use axum::http::{Request, StatusCode};
+        use tower::ServiceExt;
+
+        let sinks: Vec<Arc<dyn Sink>> = vec![
+            Arc::new(RoutedSink { name: "sql" }),
+            Arc::new(RoutedSink { name: "log" }),
+        ];
+        let router = MultiSink::new(sinks)
+            .with_route_prefix(Some("/sinks/".to_string()))
+            .build_routes();
+
+        for (uri, expected) in [("/sinks/sql/ping", "sql"), ("/sinks/log/ping", "log")] {
+            let response = router
+                .clone()
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            assert_eq!(body, expected);
+        }
+
+        assert_eq!(sink_route_path("", "sql"), "/sql");
+    }
+
+    #[tokio::test]
+    async fn test_backpressure_saturates_and_drains() {
+        let backpressure = MultiSink::new(vec![])
+            .with_backpressure_threshold(2)
+            .backpressure();
+        for _ in 0..3 {
+            backpressure.batch_queued();
+        }
+        assert_eq!(backpressure.depth(), 3);
+        assert!(backpressure.is_saturated());
+
+        let waiter = {
+            let backpressure = backpressure.clone();
+            tokio::spawn(async move { backpressure.drained().await })
+        };
+        tokio::task::yield_now().await;
+        assert!(!waiter.is_finished());
+
+        backpressure.batch_done();
+        tokio::time::timeout(Duration::from_secs(1), waiter)
+            .await
+            .expect("resumes once drained")
+            .unwrap();
+        assert!(!backpressure.is_saturated());
+
+        // Disabled by default.
+        let backpressure = SinkBackpressure::default();
+        backpressure.batch_queued();
+        assert!(!backpressure.is_saturated());
+        backpressure.drained().await;
+    }
 }
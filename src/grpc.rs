@@ -4,28 +4,102 @@
 //! and broadcasts updates from sinks to subscribed clients.
 
 use futures_util::StreamExt as FuturesStreamExt;
-use std::collections::HashMap;
+use starknet::core::types::Felt;
+use std::collections::{BTreeSet, HashMap, VecDeque};
 use std::pin::Pin;
-use std::sync::{Arc, RwLock};
-use tokio::sync::mpsc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::{mpsc, oneshot, watch};
 use tokio_stream::wrappers::ReceiverStream;
 use tokio_stream::Stream;
 use tonic::codec::CompressionEncoding;
 use tonic::{Request, Response, Status, Streaming};
 
+use crate::etl::counters::CumulativeCounters;
+use crate::etl::decoder::{DecoderConflict, DecoderConflicts};
+use crate::etl::engine_db::{ContractStats, EngineDb};
+use crate::etl::sink::{SinkDescription, TableSchema, TopicInfo};
+use crate::lame_duck::LameDuck;
+use crate::namespace::{Namespaces, DEFAULT_NAMESPACE};
+use crate::schema::ServerSchema;
+
 pub mod proto {
     tonic::include_proto!("torii");
 }
 
 // Re-export commonly used types
-pub use proto::{TopicUpdate, UpdateType};
+pub use proto::{AvailableTopics, LagNotice, ShutdownNotice, TopicUpdate, UpdateType};
 
 use proto::{
     torii_server::{Torii, ToriiServer},
-    GetVersionRequest, GetVersionResponse, ListTopicsRequest, ListTopicsResponse,
-    SubscriptionRequest, TopicSubscription,
+    DescribeSinksRequest, DescribeSinksResponse, EnterLameDuckRequest, EnterLameDuckResponse,
+    GetCapabilitiesRequest, GetCapabilitiesResponse, GetContractStatsRequest,
+    GetContractStatsResponse, GetDecoderConflictsRequest, GetDecoderConflictsResponse,
+    GetSchemaRequest, GetSchemaResponse, GetTopicsRequest, GetTopicsResponse, GetVersionRequest,
+    GetVersionResponse, ListTopicsRequest, ListTopicsResponse, SubscriptionRequest, TopicStats,
+    TopicSubscription,
 };
 
+/// Git commit the server was built from (embedded by `build.rs`).
+pub const GIT_COMMIT: &str = env!("TORII_GIT_COMMIT");
+
+/// Protocol capabilities supported by the core `torii.Torii` service.
+///
+/// Clients can check these through `GetCapabilities` to adapt their behavior.
+pub const PROTOCOL_CAPABILITIES: &[&str] = &[
+    "admin_service",
+    "describe_sinks",
+    "enter_lame_duck",
+    "get_capabilities",
+    "get_contract_stats",
+    "get_decoder_conflicts",
+    "get_schema",
+    "get_topics",
+    "list_topics",
+    "resume_from_sequence",
+    "subscribe_to_topics",
+    "subscribe_to_topics_stream",
+    "topic_patterns",
+    "available_topics",
+    "grpc_web",
+    "gzip",
+    "namespaces",
+];
+
+/// Default number of updates buffered per topic for resumed subscriptions.
+pub const DEFAULT_REPLAY_BUFFER_SIZE: usize = 1024;
+
+/// Capacity of a subscription stream for live updates (replayed updates come on top).
+const CLIENT_CHANNEL_CAPACITY: usize = 100;
+
+/// Name and version of a registered pipeline component (sink or decoder).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComponentVersion {
+    pub name: String,
+    pub version: Option<String>,
+}
+
+impl ComponentVersion {
+    pub fn new(name: impl Into<String>, version: Option<&str>) -> Self {
+        Self {
+            name: name.into(),
+            version: version.map(str::to_string),
+        }
+    }
+}
+
+/// Server capabilities reported by `GetCapabilities`.
+#[derive(Debug, Clone, Default)]
+pub struct ServerCapabilities {
+    /// Runtime features enabled on this server (e.g., "metrics", "tls")
+    pub features: Vec<String>,
+    /// Registered sinks
+    pub sinks: Vec<ComponentVersion>,
+    /// Registered decoders
+    pub decoders: Vec<ComponentVersion>,
+}
+
 /// Client subscription information
 #[derive(Clone, Debug)]
 pub struct ClientSubscription {
@@ -33,15 +107,289 @@ pub struct ClientSubscription {
     pub topics: HashMap<String, HashMap<String, String>>,
     /// A channel to send topic updates to the client
     pub tx: mpsc::Sender<TopicUpdate>,
+    /// Namespace the client subscribed in; it only receives updates of its topics
+    pub namespace: String,
+    /// Updates dropped because the channel was full
+    lag: Arc<Mutex<SubscriberLag>>,
+}
+
+impl ClientSubscription {
+    /// Number of updates dropped for this client since it registered.
+    pub fn dropped_updates(&self) -> u64 {
+        self.lag.lock().unwrap().total
+    }
+
+    /// Queues `update` for the client, preceded by a `LAGGED` notice when updates were
+    /// dropped since the last delivered one. Returns whether `update` was queued.
+    ///
+    /// A full channel drops `update` and records it for the next notice.
+    fn deliver(&self, client_id: &str, update: TopicUpdate) -> bool {
+        let mut lag = self.lag.lock().unwrap();
+        if lag.pending > 0 && self.tx.try_send(lag.notice()).is_ok() {
+            tracing::info!(
+                target: "torii::grpc",
+                "Client {} caught up after {} dropped updates",
+                client_id,
+                lag.pending
+            );
+            ::metrics::counter!("torii_subscription_lag_notices_total").increment(1);
+            lag.clear_pending();
+        }
+        // Updates are not queued behind an undelivered notice.
+        let result = if lag.pending > 0 {
+            Err(TrySendError::Full(update))
+        } else {
+            self.tx.try_send(update)
+        };
+        match result {
+            Ok(()) => true,
+            Err(TrySendError::Full(update)) => {
+                if lag.pending == 0 {
+                    tracing::warn!(
+                        target: "torii::grpc",
+                        "Client {} is reading too slowly, dropping updates (channel full)",
+                        client_id
+                    );
+                }
+                ::metrics::counter!(
+                    "torii_subscription_dropped_updates_total",
+                    "topic" => update.topic.clone()
+                )
+                .increment(1);
+                lag.record(update);
+                false
+            }
+            Err(TrySendError::Closed(_)) => {
+                tracing::debug!(
+                    target: "torii::etl::event_bus",
+                    "Failed to send to client {}: channel closed",
+                    client_id
+                );
+                false
+            }
+        }
+    }
+
+    /// Filters applying to `topic`: those of its exact subscription, otherwise those of
+    /// the longest matching topic pattern (see [`topic_matches`]).
+    pub fn filters_for(&self, topic: &str) -> Option<&HashMap<String, String>> {
+        topic_filters(&self.topics, topic)
+    }
+}
+
+/// Filters of `topic` among the `topics` subscriptions (see [`ClientSubscription::filters_for`]).
+fn topic_filters<'a>(
+    topics: &'a HashMap<String, HashMap<String, String>>,
+    topic: &str,
+) -> Option<&'a HashMap<String, String>> {
+    if let Some(filters) = topics.get(topic) {
+        return Some(filters);
+    }
+    topics
+        .iter()
+        .filter(|(pattern, _)| pattern.contains('*') && topic_matches(pattern, topic))
+        .max_by(|(a, _), (b, _)| a.len().cmp(&b.len()).then_with(|| b.cmp(a)))
+        .map(|(_, filters)| filters)
+}
+
+/// Updates dropped for a client, reported by a `LAGGED` notice once it reads again.
+#[derive(Debug, Default)]
+struct SubscriberLag {
+    /// Dropped since the last delivered update
+    pending: u64,
+    first_sequence: u64,
+    last_sequence: u64,
+    topics: BTreeSet<String>,
+    /// Dropped since the client registered
+    total: u64,
+}
+
+impl SubscriberLag {
+    fn record(&mut self, update: TopicUpdate) {
+        if self.pending == 0 {
+            self.first_sequence = update.sequence;
+        }
+        self.pending += 1;
+        self.total += 1;
+        self.last_sequence = update.sequence;
+        self.topics.insert(update.topic);
+    }
+
+    fn clear_pending(&mut self) {
+        self.pending = 0;
+        self.topics.clear();
+    }
+
+    fn notice(&self) -> TopicUpdate {
+        use prost::Message;
+
+        let notice = LagNotice {
+            dropped: self.pending,
+            first_sequence: self.first_sequence,
+            last_sequence: self.last_sequence,
+            topics: self.topics.iter().cloned().collect(),
+        };
+        TopicUpdate {
+            topic: String::new(),
+            update_type: UpdateType::Lagged as i32,
+            timestamp: chrono::Utc::now().timestamp(),
+            type_id: "torii.lagged".to_string(),
+            data: Some(prost_types::Any {
+                type_url: "type.googleapis.com/torii.LagNotice".to_string(),
+                value: notice.encode_to_vec(),
+            }),
+            sequence: self.last_sequence,
+            topic_sequence: 0,
+        }
+    }
+}
+
+/// Whether `topic` matches `pattern`, where `*` matches any (possibly empty) sequence
+/// of characters, e.g. `erc20.*` matches `erc20.transfer`.
+pub fn topic_matches(pattern: &str, topic: &str) -> bool {
+    let Some((prefix, rest)) = pattern.split_once('*') else {
+        return pattern == topic;
+    };
+    let Some(mut remaining) = topic.strip_prefix(prefix) else {
+        return false;
+    };
+    let mut parts: Vec<&str> = rest.split('*').collect();
+    let suffix = parts.pop().unwrap_or_default();
+    for part in parts {
+        let Some(index) = remaining.find(part) else {
+            return false;
+        };
+        remaining = &remaining[index + part.len()..];
+    }
+    remaining.ends_with(suffix)
+}
+
+/// Decides whether a buffered update matches a client's topic filters.
+type ReplayFilter = Arc<dyn Fn(&HashMap<String, String>) -> bool + Send + Sync>;
+
+/// Published updates of a topic.
+#[derive(Default)]
+struct TopicLog {
+    /// Per-topic sequence number of the last published update
+    sequence: u64,
+    /// Most recent updates, oldest first, replayed to resumed subscriptions
+    buffer: VecDeque<(TopicUpdate, ReplayFilter)>,
+    /// Last published update, reported by `GetTopics`
+    last: Option<TopicUpdate>,
+}
+
+/// Publishing activity of a topic since the server started.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TopicActivity {
+    /// Updates published (the last topic sequence)
+    pub published: u64,
+    /// Clients subscribed to the topic, directly or through a pattern
+    pub subscribers: usize,
+    /// Last published update
+    pub last_update: Option<TopicUpdate>,
+}
+
+impl TopicLog {
+    /// Buffered updates published after `sequence` and matching `filters`.
+    ///
+    /// Fails when some of them are no longer buffered or `sequence` was never published
+    /// (e.g. it comes from a previous server run).
+    fn replay_after(
+        &self,
+        topic: &str,
+        sequence: u64,
+        filters: &HashMap<String, String>,
+    ) -> Result<Vec<TopicUpdate>, Status> {
+        if sequence > self.sequence {
+            return Err(Status::out_of_range(format!(
+                "Topic '{topic}' is at sequence {}, cannot resume from {sequence}",
+                self.sequence
+            )));
+        }
+        let oldest = self.sequence + 1 - self.buffer.len() as u64;
+        if sequence + 1 < oldest {
+            return Err(Status::out_of_range(format!(
+                "Updates of topic '{topic}' after sequence {sequence} are no longer buffered"
+            )));
+        }
+        Ok(self
+            .buffer
+            .iter()
+            .filter(|(update, matches)| update.topic_sequence > sequence && matches(filters))
+            .map(|(update, _)| update.clone())
+            .collect())
+    }
+}
+
+/// Shutdown notification shared by every subscription stream.
+///
+/// Sinks serving their own streams wait on [`ShutdownSignal::notified`] to send
+/// a final message with the notice, so clients can resume after the restart.
+#[derive(Clone)]
+pub struct ShutdownSignal {
+    tx: Arc<watch::Sender<Option<ShutdownNotice>>>,
+}
+
+impl ShutdownSignal {
+    pub fn new() -> Self {
+        Self {
+            tx: Arc::new(watch::Sender::new(None)),
+        }
+    }
+
+    /// Publishes the notice. Returns `false` if a notice was already published.
+    pub fn notify(&self, notice: ShutdownNotice) -> bool {
+        self.tx.send_if_modified(|current| {
+            if current.is_some() {
+                return false;
+            }
+            *current = Some(notice);
+            true
+        })
+    }
+
+    /// The published notice, if the server is shutting down.
+    pub fn notice(&self) -> Option<ShutdownNotice> {
+        *self.tx.borrow()
+    }
+
+    /// Completes with the notice once the server is shutting down.
+    pub async fn notified(&self) -> ShutdownNotice {
+        let mut rx = self.tx.subscribe();
+        // The sender lives in `self`, so the channel cannot be closed.
+        let notice = rx.wait_for(Option::is_some).await.map(|notice| *notice);
+        notice.ok().flatten().unwrap_or_default()
+    }
+}
+
+impl Default for ShutdownSignal {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 /// Centralized subscription manager
 ///
 /// Manages client subscriptions and broadcasts updates from sinks to subscribed clients.
+/// Each client has its own bounded channel: publishing never waits for a slow client,
+/// its updates are dropped instead, counted, and reported to it by a `LAGGED` update
+/// once it reads again.
 #[derive(Clone)]
 pub struct SubscriptionManager {
     /// Mapping of client IDs to their subscriptions
     clients: Arc<RwLock<HashMap<String, ClientSubscription>>>,
+    /// Sequence number of the last published update
+    sequence: Arc<AtomicU64>,
+    /// Per-topic sequence numbers and replay buffers
+    topic_logs: Arc<Mutex<HashMap<String, TopicLog>>>,
+    /// Updates buffered per topic (0 = resuming is not supported)
+    replay_buffer_size: usize,
+    /// Shutdown notification for subscription streams
+    shutdown: ShutdownSignal,
+    /// Topic -> namespace of the sink publishing it (unlisted = default namespace)
+    topic_namespaces: Arc<RwLock<HashMap<String, String>>>,
+    /// Counters cumulating the published updates per topic across restarts
+    counters: Arc<OnceLock<Arc<CumulativeCounters>>>,
 }
 
 impl SubscriptionManager {
@@ -49,32 +397,233 @@ impl SubscriptionManager {
     pub fn new() -> Self {
         SubscriptionManager {
             clients: Arc::new(RwLock::new(HashMap::new())),
+            sequence: Arc::new(AtomicU64::new(0)),
+            topic_logs: Arc::new(Mutex::new(HashMap::new())),
+            replay_buffer_size: DEFAULT_REPLAY_BUFFER_SIZE,
+            shutdown: ShutdownSignal::new(),
+            topic_namespaces: Arc::new(RwLock::new(HashMap::new())),
+            counters: Arc::new(OnceLock::new()),
+        }
+    }
+
+    /// Records published updates per topic in `counters` (persisted across restarts).
+    ///
+    /// Only the first call has an effect.
+    pub fn set_counters(&self, counters: Arc<CumulativeCounters>) {
+        let _ = self.counters.set(counters);
+    }
+
+    /// Counters the published updates are recorded in, if set.
+    pub fn counters(&self) -> Option<&Arc<CumulativeCounters>> {
+        self.counters.get()
+    }
+
+    /// Publishing activity of `topic` since startup.
+    pub fn topic_activity(&self, topic: &str) -> TopicActivity {
+        let clients = self.clients.read().unwrap();
+        let namespace = self.topic_namespace(topic);
+        let subscribers = clients
+            .values()
+            .filter(|client| client.namespace == namespace && client.filters_for(topic).is_some())
+            .count();
+        let logs = self.topic_logs.lock().unwrap();
+        let log = logs.get(topic);
+        TopicActivity {
+            published: log.map_or(0, |log| log.sequence),
+            subscribers,
+            last_update: log.and_then(|log| log.last.clone()),
         }
     }
 
+    /// Sets the namespace of each topic (see [`Namespaces::topic_namespaces`]).
+    pub fn set_topic_namespaces(&self, topic_namespaces: HashMap<String, String>) {
+        *self.topic_namespaces.write().unwrap() = topic_namespaces;
+    }
+
+    /// Namespace of the sink publishing `topic`.
+    ///
+    /// Topics missing from the sink topic lists resolve through their first
+    /// `.`-separated segment (e.g. `erc20.metadata` -> sink `erc20`).
+    pub fn topic_namespace(&self, topic: &str) -> String {
+        let topic_namespaces = self.topic_namespaces.read().unwrap();
+        topic_namespaces
+            .get(topic)
+            .or_else(|| {
+                let (prefix, _) = topic.split_once('.')?;
+                topic_namespaces.get(prefix)
+            })
+            .map_or_else(|| DEFAULT_NAMESPACE.to_string(), Clone::clone)
+    }
+
+    /// Sets the number of updates buffered per topic for resumed subscriptions
+    /// (default: [`DEFAULT_REPLAY_BUFFER_SIZE`], 0 = disabled).
+    pub fn with_replay_buffer_size(mut self, size: usize) -> Self {
+        self.replay_buffer_size = size;
+        self
+    }
+
+    /// Channel capacity of a new subscription stream, leaving room for a full replay.
+    pub fn stream_capacity(&self) -> usize {
+        CLIENT_CHANNEL_CAPACITY + self.replay_buffer_size
+    }
+
     /// Returns a reference to the mapping of client IDs to their subscriptions
     pub fn clients(&self) -> &Arc<RwLock<HashMap<String, ClientSubscription>>> {
         &self.clients
     }
 
-    /// Registers a new client with the subscription manager
+    /// Allocates the sequence number of a new published update.
+    pub fn next_sequence(&self) -> u64 {
+        self.sequence.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    /// Sends `update` to the clients subscribed to its topic whose filters match, and
+    /// buffers it for resumed subscriptions.
+    ///
+    /// Assigns the update's server-wide and per-topic sequence numbers. Returns the
+    /// number of clients the update was sent to.
+    pub fn publish<F>(&self, mut update: TopicUpdate, matches: F) -> usize
+    where
+        F: Fn(&HashMap<String, String>) -> bool + Send + Sync + 'static,
+    {
+        // Clients are locked before the topic logs, like in `resume_subscriptions`, so a
+        // resumed client gets each update either replayed or live, never both.
+        let clients = self.clients.read().unwrap();
+        let namespace = self.topic_namespace(&update.topic);
+        let mut logs = self.topic_logs.lock().unwrap();
+        let log = match logs.get_mut(&update.topic) {
+            Some(log) => log,
+            None => logs.entry(update.topic.clone()).or_default(),
+        };
+        log.sequence += 1;
+        update.topic_sequence = log.sequence;
+        update.sequence = self.next_sequence();
+
+        let mut sent_count = 0;
+        for (client_id, client_sub) in clients.iter() {
+            if client_sub.namespace != namespace {
+                continue;
+            }
+            let Some(filters) = client_sub.filters_for(&update.topic) else {
+                continue;
+            };
+            if !matches(filters) {
+                continue;
+            }
+            if client_sub.deliver(client_id, update.clone()) {
+                sent_count += 1;
+            }
+        }
+
+        if let Some(counters) = self.counters.get() {
+            counters.record_topic_publish(&update.topic);
+        }
+        log.last = Some(update.clone());
+        if self.replay_buffer_size > 0 {
+            if log.buffer.len() >= self.replay_buffer_size {
+                log.buffer.pop_front();
+            }
+            log.buffer.push_back((update, Arc::new(matches)));
+        }
+        sent_count
+    }
+
+    /// Returns the shutdown signal (for sinks serving their own streams).
+    pub fn shutdown_signal(&self) -> &ShutdownSignal {
+        &self.shutdown
+    }
+
+    /// Ends every subscription stream with a `SHUTDOWN` update.
+    ///
+    /// `block_number` is the last fully indexed block. Returns the number of
+    /// clients the notice was delivered to.
+    pub fn shutdown(&self, block_number: u64) -> usize {
+        use prost::Message;
+
+        let notice = ShutdownNotice {
+            block_number,
+            sequence: self.sequence.load(Ordering::Relaxed),
+        };
+        if !self.shutdown.notify(notice) {
+            return 0;
+        }
+
+        let update = TopicUpdate {
+            topic: String::new(),
+            update_type: UpdateType::Shutdown as i32,
+            timestamp: chrono::Utc::now().timestamp(),
+            type_id: "torii.shutdown".to_string(),
+            data: Some(prost_types::Any {
+                type_url: "type.googleapis.com/torii.ShutdownNotice".to_string(),
+                value: notice.encode_to_vec(),
+            }),
+            sequence: notice.sequence,
+            topic_sequence: 0,
+        };
+
+        // Dropping the senders ends the streams once the notice is flushed.
+        let clients = std::mem::take(&mut *self.clients.write().unwrap());
+        let delivered = clients
+            .values()
+            .filter(|client| client.tx.try_send(update.clone()).is_ok())
+            .count();
+        tracing::info!(
+            target: "torii::grpc",
+            block_number,
+            sequence = notice.sequence,
+            "Sent shutdown notice to {}/{} subscribers",
+            delivered,
+            clients.len()
+        );
+        delivered
+    }
+
+    /// Registers a new client with the subscription manager, in the default namespace
     pub fn register_client(&self, client_id: String, tx: mpsc::Sender<TopicUpdate>) {
+        self.register_client_in_namespace(client_id, DEFAULT_NAMESPACE.to_string(), tx);
+    }
+
+    /// Registers a new client receiving the updates of the topics of `namespace`
+    pub fn register_client_in_namespace(
+        &self,
+        client_id: String,
+        namespace: String,
+        tx: mpsc::Sender<TopicUpdate>,
+    ) {
         let mut clients = self.clients.write().unwrap();
+        tracing::info!(
+            target: "torii::grpc",
+            "Client {} registered in namespace '{}'",
+            client_id,
+            namespace
+        );
         clients.insert(
-            client_id.clone(),
+            client_id,
             ClientSubscription {
                 topics: HashMap::new(),
                 tx,
+                namespace,
+                lag: Arc::default(),
             },
         );
-        tracing::info!(target: "torii::grpc", "Client {} registered", client_id);
     }
 
     /// Unregisters a client from the subscription manager
     pub fn unregister_client(&self, client_id: &str) {
         let mut clients = self.clients.write().unwrap();
-        clients.remove(client_id);
-        tracing::info!(target: "torii::grpc", "Client {} unregistered", client_id);
+        let dropped = clients
+            .remove(client_id)
+            .map_or(0, |client| client.dropped_updates());
+        if dropped > 0 {
+            tracing::info!(
+                target: "torii::grpc",
+                "Client {} unregistered ({} updates dropped)",
+                client_id,
+                dropped
+            );
+        } else {
+            tracing::info!(target: "torii::grpc", "Client {} unregistered", client_id);
+        }
     }
 
     /// Updates the subscriptions for a client
@@ -84,39 +633,117 @@ impl SubscriptionManager {
         topics: Vec<TopicSubscription>,
         unsubscribe_topics: Vec<String>,
     ) {
+        // Nothing to replay, so this cannot fail.
+        let _ = self.resume_subscriptions(client_id, topics, unsubscribe_topics, &HashMap::new());
+    }
+
+    /// Updates the subscriptions for a client, then replays the buffered updates of
+    /// the `resume_from` topics published after the given per-topic sequence numbers.
+    ///
+    /// Returns the number of replayed updates. Fails with `OUT_OF_RANGE` when some
+    /// updates to replay are no longer buffered.
+    pub fn resume_subscriptions(
+        &self,
+        client_id: &str,
+        topics: Vec<TopicSubscription>,
+        unsubscribe_topics: Vec<String>,
+        resume_from: &HashMap<String, u64>,
+    ) -> Result<usize, Status> {
         let mut clients = self.clients.write().unwrap();
-        if let Some(client) = clients.get_mut(client_id) {
-            for topic in unsubscribe_topics {
-                if client.topics.remove(&topic).is_some() {
-                    tracing::info!(
-                        target: "torii::grpc",
-                        "Client {} unsubscribed from topic '{}'",
-                        client_id,
-                        topic
-                    );
-                }
+        let Some(client) = clients.get_mut(client_id) else {
+            return Ok(0);
+        };
+        // Exact topics must belong to the client's namespace (patterns only ever match
+        // its topics, see `publish`).
+        for topic in topics
+            .iter()
+            .map(|topic_sub| topic_sub.topic.as_str())
+            .filter(|topic| !topic.contains('*'))
+            .chain(resume_from.keys().map(String::as_str))
+        {
+            if self.topic_namespace(topic) != client.namespace {
+                return Err(Status::permission_denied(format!(
+                    "Topic '{topic}' is not in namespace '{}'",
+                    client.namespace
+                )));
             }
+        }
+        // Collect the replay against the updated subscriptions before applying them, so
+        // a resume that fails leaves the client subscriptions untouched.
+        let mut updated_topics = client.topics.clone();
+        for topic in &unsubscribe_topics {
+            updated_topics.remove(topic);
+        }
+        for topic_sub in &topics {
+            updated_topics.insert(topic_sub.topic.clone(), topic_sub.filters.clone());
+        }
+        let mut replay = Vec::new();
+        if !resume_from.is_empty() {
+            let logs = self.topic_logs.lock().unwrap();
+            for (topic, &sequence) in resume_from {
+                let filters = topic_filters(&updated_topics, topic).ok_or_else(|| {
+                    Status::invalid_argument(format!("Cannot resume unsubscribed topic '{topic}'"))
+                })?;
+                let updates = match logs.get(topic) {
+                    Some(log) => log.replay_after(topic, sequence, filters)?,
+                    None => TopicLog::default().replay_after(topic, sequence, filters)?,
+                };
+                replay.extend(updates);
+            }
+            if replay.len() > client.tx.capacity() {
+                return Err(Status::resource_exhausted(format!(
+                    "Too many updates to replay ({}), resume fewer topics at once",
+                    replay.len()
+                )));
+            }
+        }
 
-            for topic_sub in topics {
-                client
-                    .topics
-                    .insert(topic_sub.topic.clone(), topic_sub.filters.clone());
-                tracing::debug!(
+        for topic in unsubscribe_topics {
+            if client.topics.contains_key(&topic) {
+                tracing::info!(
                     target: "torii::grpc",
-                    "Client {} subscribed to topic '{}' with {} filters",
+                    "Client {} unsubscribed from topic '{}'",
                     client_id,
-                    topic_sub.topic,
-                    topic_sub.filters.len()
+                    topic
                 );
             }
-
-            tracing::info!(
+        }
+        for topic_sub in &topics {
+            tracing::debug!(
                 target: "torii::grpc",
-                "Client {} updated subscriptions: {} topics",
+                "Client {} subscribed to topic '{}' with {} filters",
                 client_id,
-                client.topics.len()
+                topic_sub.topic,
+                topic_sub.filters.len()
             );
         }
+        client.topics = updated_topics;
+
+        tracing::info!(
+            target: "torii::grpc",
+            "Client {} updated subscriptions: {} topics",
+            client_id,
+            client.topics.len()
+        );
+
+        if resume_from.is_empty() {
+            return Ok(0);
+        }
+
+        // Replay across topics in publication order.
+        replay.sort_by_key(|update| update.sequence);
+        let replayed = replay.len();
+        for update in replay {
+            let _ = client.tx.try_send(update);
+        }
+        tracing::info!(
+            target: "torii::grpc",
+            "Client {} resumed {} topics, replayed {} updates",
+            client_id,
+            resume_from.len(),
+            replayed
+        );
+        Ok(replayed)
     }
 }
 
@@ -134,6 +761,13 @@ impl Default for SubscriptionManager {
 pub struct GrpcState {
     subscription_manager: Arc<SubscriptionManager>,
     topics: Vec<crate::etl::sink::TopicInfo>,
+    capabilities: ServerCapabilities,
+    sink_descriptions: Vec<SinkDescription>,
+    engine_db: Option<Arc<EngineDb>>,
+    decoder_conflicts: Option<DecoderConflicts>,
+    lame_duck: Option<LameDuck>,
+    admin_rpc: bool,
+    namespaces: Namespaces,
 }
 
 impl GrpcState {
@@ -144,12 +778,155 @@ impl GrpcState {
         GrpcState {
             subscription_manager,
             topics,
+            capabilities: ServerCapabilities::default(),
+            sink_descriptions: Vec::new(),
+            engine_db: None,
+            decoder_conflicts: None,
+            lame_duck: None,
+            admin_rpc: false,
+            namespaces: Namespaces::default(),
         }
     }
 
+    /// Sets the sink namespaces and API keys checked by queries and subscriptions.
+    ///
+    /// The topic namespaces of the subscription manager are set separately (see
+    /// [`SubscriptionManager::set_topic_namespaces`]).
+    pub fn with_namespaces(mut self, namespaces: Namespaces) -> Self {
+        self.namespaces = namespaces;
+        self
+    }
+
+    /// Sets the capabilities reported by `GetCapabilities`.
+    pub fn with_capabilities(mut self, capabilities: ServerCapabilities) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    /// Sets the sink descriptions reported by `DescribeSinks`.
+    pub fn with_sink_descriptions(mut self, sink_descriptions: Vec<SinkDescription>) -> Self {
+        self.sink_descriptions = sink_descriptions;
+        self
+    }
+
+    /// Sets the engine database backing `GetContractStats`.
+    pub fn with_engine_db(mut self, engine_db: Arc<EngineDb>) -> Self {
+        self.engine_db = Some(engine_db);
+        self
+    }
+
+    /// Sets the conflict record reported by `GetDecoderConflicts`.
+    pub fn with_decoder_conflicts(mut self, conflicts: DecoderConflicts) -> Self {
+        self.decoder_conflicts = Some(conflicts);
+        self
+    }
+
+    /// Sets the lame-duck state entered by `EnterLameDuck`.
+    pub fn with_lame_duck(mut self, lame_duck: LameDuck) -> Self {
+        self.lame_duck = Some(lame_duck);
+        self
+    }
+
+    /// Enables admin RPCs (`EnterLameDuck`). Disabled by default.
+    ///
+    /// When API keys are configured (see [`Self::with_namespaces`]), admin RPCs require a
+    /// key permitted every namespace.
+    pub fn with_admin_rpc(mut self, enabled: bool) -> Self {
+        self.admin_rpc = enabled;
+        self
+    }
+
     pub fn subscription_manager(&self) -> &Arc<SubscriptionManager> {
         &self.subscription_manager
     }
+
+    pub fn capabilities(&self) -> &ServerCapabilities {
+        &self.capabilities
+    }
+}
+
+impl From<&ComponentVersion> for proto::ComponentInfo {
+    fn from(component: &ComponentVersion) -> Self {
+        proto::ComponentInfo {
+            name: component.name.clone(),
+            version: component.version.clone().unwrap_or_default(),
+        }
+    }
+}
+
+impl proto::TopicInfo {
+    /// Converts `topic`, leaving its namespace to the caller.
+    fn from_topic(topic: &TopicInfo, sink_name: &str) -> Self {
+        proto::TopicInfo {
+            name: topic.name.clone(),
+            sink_name: sink_name.to_string(),
+            available_filters: topic.available_filters.clone(),
+            description: topic.description.clone(),
+            message_type: topic.message_type.clone().unwrap_or_default(),
+            namespace: String::new(),
+        }
+    }
+}
+
+impl From<&TableSchema> for proto::TableInfo {
+    fn from(table: &TableSchema) -> Self {
+        proto::TableInfo {
+            name: table.name.clone(),
+            description: table.description.clone(),
+            columns: table
+                .columns
+                .iter()
+                .map(|column| proto::ColumnInfo {
+                    name: column.name.clone(),
+                    data_type: column.data_type.clone(),
+                    nullable: column.nullable,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<&SinkDescription> for proto::SinkDescription {
+    fn from(sink: &SinkDescription) -> Self {
+        proto::SinkDescription {
+            name: sink.name.clone(),
+            version: sink.version.clone().unwrap_or_default(),
+            topics: sink
+                .topics
+                .iter()
+                .map(|topic| proto::TopicInfo::from_topic(topic, &sink.name))
+                .collect(),
+            tables: sink.tables.iter().map(Into::into).collect(),
+            namespace: String::new(),
+        }
+    }
+}
+
+impl From<ContractStats> for proto::ContractStats {
+    fn from(stats: ContractStats) -> Self {
+        proto::ContractStats {
+            contract: stats.contract.to_bytes_be().to_vec(),
+            first_block: stats.first_block,
+            last_block: stats.last_block,
+            total_events: stats.total_events(),
+            events_by_decoder: stats.events_by_decoder.into_iter().collect(),
+            last_activity: stats.last_activity,
+        }
+    }
+}
+
+impl From<DecoderConflict> for proto::DecoderConflict {
+    fn from(conflict: DecoderConflict) -> Self {
+        proto::DecoderConflict {
+            contract: conflict.contract.to_bytes_be().to_vec(),
+            selector: conflict.selector.to_bytes_be().to_vec(),
+            decoders: conflict.decoders,
+            event_count: conflict.event_count,
+            first_block: conflict.first_block,
+            last_block: conflict.last_block,
+            resolved: conflict.resolved,
+        }
+    }
 }
 
 // gRPC service implementation
@@ -161,6 +938,46 @@ impl ToriiService {
     pub fn new(state: GrpcState) -> Self {
         ToriiService { state }
     }
+
+    /// Topics of the registered sinks, with their namespace.
+    fn topic_infos(&self) -> Vec<proto::TopicInfo> {
+        let subscription_manager = self.state.subscription_manager();
+        self.state
+            .topics
+            .iter()
+            .map(|topic_info| proto::TopicInfo {
+                namespace: subscription_manager.topic_namespace(&topic_info.name),
+                ..proto::TopicInfo::from_topic(topic_info, "")
+            })
+            .collect()
+    }
+
+    /// Topics of `namespace`, as listed by `ListTopics`.
+    fn namespace_topic_infos(&self, namespace: &str) -> Vec<proto::TopicInfo> {
+        self.topic_infos()
+            .into_iter()
+            .filter(|topic| topic.namespace == namespace)
+            .collect()
+    }
+
+    /// `TOPICS` update opening every subscription stream.
+    fn available_topics_update(topics: Vec<proto::TopicInfo>) -> TopicUpdate {
+        use prost::Message;
+
+        let topics = AvailableTopics { topics };
+        TopicUpdate {
+            topic: String::new(),
+            update_type: UpdateType::Topics as i32,
+            timestamp: chrono::Utc::now().timestamp(),
+            type_id: "torii.available_topics".to_string(),
+            data: Some(prost_types::Any {
+                type_url: "type.googleapis.com/torii.AvailableTopics".to_string(),
+                value: topics.encode_to_vec(),
+            }),
+            sequence: 0,
+            topic_sequence: 0,
+        }
+    }
 }
 
 #[tonic::async_trait]
@@ -172,6 +989,98 @@ impl Torii for ToriiService {
         Ok(Response::new(GetVersionResponse {
             version: env!("CARGO_PKG_VERSION").to_string(),
             build_time: chrono::Utc::now().format("%Y-%m-%d").to_string(),
+            git_commit: GIT_COMMIT.to_string(),
+        }))
+    }
+
+    async fn get_capabilities(
+        &self,
+        _request: Request<GetCapabilitiesRequest>,
+    ) -> Result<Response<GetCapabilitiesResponse>, Status> {
+        let capabilities = self.state.capabilities();
+
+        Ok(Response::new(GetCapabilitiesResponse {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            git_commit: GIT_COMMIT.to_string(),
+            features: capabilities.features.clone(),
+            sinks: capabilities.sinks.iter().map(Into::into).collect(),
+            decoders: capabilities.decoders.iter().map(Into::into).collect(),
+            capabilities: PROTOCOL_CAPABILITIES
+                .iter()
+                .map(|capability| (*capability).to_string())
+                .collect(),
+        }))
+    }
+
+    async fn get_contract_stats(
+        &self,
+        request: Request<GetContractStatsRequest>,
+    ) -> Result<Response<GetContractStatsResponse>, Status> {
+        let engine_db = self
+            .state
+            .engine_db
+            .as_ref()
+            .ok_or_else(|| Status::unavailable("Contract stats are not available"))?;
+
+        let contracts = request
+            .into_inner()
+            .contracts
+            .iter()
+            .map(|bytes| {
+                if bytes.len() > 32 {
+                    return Err(Status::invalid_argument("Invalid contract address"));
+                }
+                Ok(Felt::from_bytes_be_slice(bytes))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let stats = engine_db
+            .get_contract_stats(&contracts)
+            .await
+            .map_err(|e| Status::internal(format!("Failed to load contract stats: {e}")))?;
+
+        Ok(Response::new(GetContractStatsResponse {
+            stats: stats.into_iter().map(Into::into).collect(),
+        }))
+    }
+
+    async fn get_decoder_conflicts(
+        &self,
+        _request: Request<GetDecoderConflictsRequest>,
+    ) -> Result<Response<GetDecoderConflictsResponse>, Status> {
+        let conflicts = self
+            .state
+            .decoder_conflicts
+            .as_ref()
+            .ok_or_else(|| Status::unavailable("Decoder conflicts are not available"))?;
+
+        Ok(Response::new(GetDecoderConflictsResponse {
+            conflicts: conflicts.snapshot().into_iter().map(Into::into).collect(),
+        }))
+    }
+
+    async fn enter_lame_duck(
+        &self,
+        request: Request<EnterLameDuckRequest>,
+    ) -> Result<Response<EnterLameDuckResponse>, Status> {
+        if !self.state.admin_rpc {
+            return Err(Status::permission_denied("Admin RPCs are disabled"));
+        }
+        self.state.namespaces.authorize_admin(request.metadata())?;
+        let lame_duck = self
+            .state
+            .lame_duck
+            .as_ref()
+            .ok_or_else(|| Status::unavailable("Lame-duck mode is not available"))?;
+
+        let already_active = !lame_duck.enter();
+        if !already_active {
+            tracing::info!(target: "torii::grpc", "Lame-duck mode requested through admin RPC");
+        }
+
+        Ok(Response::new(EnterLameDuckResponse {
+            already_active,
+            drain_period_seconds: lame_duck.drain_period().as_secs(),
         }))
     }
 
@@ -187,27 +1096,111 @@ impl Torii for ToriiService {
             metadata
         );
 
-        let topics = self
+        let namespace = self
             .state
-            .topics
-            .iter()
-            .map(|topic_info| proto::TopicInfo {
-                name: topic_info.name.clone(),
-                sink_name: String::new(), // Can be populated by sinks if needed
-                available_filters: topic_info.available_filters.clone(),
-                description: topic_info.description.clone(),
-            })
-            .collect();
+            .namespaces
+            .authorize(metadata, &request.get_ref().namespace)?;
+        let topics = self.namespace_topic_infos(&namespace);
 
         tracing::info!(
             target: "torii::grpc",
-            "ListTopics returning {} topics",
-            self.state.topics.len()
+            "ListTopics returning {} topics of namespace '{}'",
+            topics.len(),
+            namespace
         );
 
         Ok(Response::new(ListTopicsResponse { topics }))
     }
 
+    async fn get_topics(
+        &self,
+        request: Request<GetTopicsRequest>,
+    ) -> Result<Response<GetTopicsResponse>, Status> {
+        let namespace = self
+            .state
+            .namespaces
+            .authorize(request.metadata(), &request.get_ref().namespace)?;
+        let requested = &request.get_ref().topics;
+        let subscription_manager = self.state.subscription_manager();
+
+        let topics = self
+            .namespace_topic_infos(&namespace)
+            .into_iter()
+            .filter(|topic| requested.is_empty() || requested.contains(&topic.name))
+            .map(|topic| {
+                let activity = subscription_manager.topic_activity(&topic.name);
+                let publish_count = subscription_manager
+                    .counters()
+                    .map_or(activity.published, |counters| {
+                        counters.topic_publishes(&topic.name)
+                    });
+                let last_update = activity.last_update.unwrap_or_default();
+                TopicStats {
+                    topic: Some(topic),
+                    publish_count,
+                    run_publish_count: activity.published,
+                    subscriber_count: activity.subscribers as u32,
+                    last_published_at: last_update.timestamp,
+                    last_type_id: last_update.type_id,
+                    last_message: last_update.data,
+                }
+            })
+            .collect();
+
+        Ok(Response::new(GetTopicsResponse { topics }))
+    }
+
+    async fn describe_sinks(
+        &self,
+        request: Request<DescribeSinksRequest>,
+    ) -> Result<Response<DescribeSinksResponse>, Status> {
+        let namespaces = &self.state.namespaces;
+        let namespace = namespaces.authorize(request.metadata(), &request.get_ref().namespace)?;
+        let sinks = self
+            .state
+            .sink_descriptions
+            .iter()
+            .filter(|sink| namespaces.namespace_of(&sink.name) == namespace)
+            .map(|sink| {
+                let mut sink = proto::SinkDescription::from(sink);
+                sink.namespace.clone_from(&namespace);
+                for topic in &mut sink.topics {
+                    topic.namespace.clone_from(&namespace);
+                }
+                sink
+            })
+            .collect();
+        Ok(Response::new(DescribeSinksResponse { sinks }))
+    }
+
+    async fn get_schema(
+        &self,
+        request: Request<GetSchemaRequest>,
+    ) -> Result<Response<GetSchemaResponse>, Status> {
+        let namespaces = &self.state.namespaces;
+        let namespace = namespaces.authorize(request.metadata(), &request.get_ref().namespace)?;
+        let schema = ServerSchema::from_sinks(
+            self.state
+                .sink_descriptions
+                .iter()
+                .filter(|sink| namespaces.namespace_of(&sink.name) == namespace),
+        )
+        .map_err(|e| Status::internal(format!("Invalid sink descriptor set: {e}")))?;
+        Ok(Response::new(GetSchemaResponse {
+            file_descriptor_set: schema.file_descriptor_set,
+            envelope_types: schema
+                .envelope_types
+                .into_iter()
+                .map(|envelope| proto::EnvelopeTypeInfo {
+                    sink: envelope.sink,
+                    type_name: envelope.type_name,
+                    type_id: envelope.type_id,
+                    type_url: envelope.type_url,
+                })
+                .collect(),
+        }))
+    }
+
     type SubscribeToTopicsStreamStream =
         Pin<Box<dyn Stream<Item = Result<TopicUpdate, Status>> + Send>>;
 
@@ -223,18 +1216,30 @@ impl Torii for ToriiService {
             metadata
         );
 
+        let namespace = self
+            .state
+            .namespaces
+            .authorize(metadata, &request.get_ref().namespace)?;
         let sub_req = request.into_inner();
-        let (tx, rx) = mpsc::channel(100);
         let subscription_manager = self.state.subscription_manager().clone();
+        let (tx, rx) = mpsc::channel(subscription_manager.stream_capacity());
         let client_id = sub_req.client_id.clone();
 
-        // Register client and set up subscriptions
-        subscription_manager.register_client(client_id.clone(), tx.clone());
-        subscription_manager.update_subscriptions(
+        // Register client, push the available topics, set up subscriptions and replay
+        // resumed topics
+        let _ = tx.try_send(Self::available_topics_update(
+            self.namespace_topic_infos(&namespace),
+        ));
+        subscription_manager.register_client_in_namespace(client_id.clone(), namespace, tx.clone());
+        if let Err(status) = subscription_manager.resume_subscriptions(
             &client_id,
             sub_req.topics,
             sub_req.unsubscribe_topics,
-        );
+            &sub_req.resume_from_sequence,
+        ) {
+            subscription_manager.unregister_client(&client_id);
+            return Err(status);
+        }
 
         tracing::info!(
             target: "torii::grpc",
@@ -244,11 +1249,14 @@ impl Torii for ToriiService {
 
         // Clean up as soon as the stream receiver is dropped (client disconnects).
         // This avoids stale client entries accumulating in SubscriptionManager.
+        // On shutdown, release the sender so the stream ends after the notice.
         let cleanup_manager = subscription_manager;
         let cleanup_id = client_id;
         tokio::spawn(async move {
-            tx.closed().await;
-            cleanup_manager.unregister_client(&cleanup_id);
+            tokio::select! {
+                () = tx.closed() => cleanup_manager.unregister_client(&cleanup_id),
+                _ = cleanup_manager.shutdown_signal().notified() => {}
+            }
         });
 
         // Convert mpsc receiver to stream
@@ -263,30 +1271,75 @@ impl Torii for ToriiService {
         &self,
         request: Request<Streaming<SubscriptionRequest>>,
     ) -> Result<Response<Self::SubscribeToTopicsStream>, Status> {
+        let metadata = request.metadata().clone();
         let mut stream = request.into_inner();
-        let (tx, rx) = mpsc::channel(100);
         let subscription_manager = self.state.subscription_manager().clone();
+        let (tx, rx) = mpsc::channel(subscription_manager.stream_capacity());
+        // A failed resume or namespace check ends the stream with its status.
+        let (error_tx, error_rx) = oneshot::channel::<Status>();
+        let namespaces = self.state.namespaces.clone();
+        let topics = self.topic_infos();
 
         // Spawn task to handle incoming subscription requests
         tokio::spawn(async move {
             let mut client_id: Option<String> = None;
+            let mut client_namespace = String::new();
+            let shutdown = subscription_manager.shutdown_signal().clone();
 
-            while let Some(result) = stream.next().await {
+            loop {
+                // On shutdown, release the sender so the stream ends after the notice.
+                let result = tokio::select! {
+                    result = stream.next() => result,
+                    _ = shutdown.notified() => break,
+                };
+                let Some(result) = result else {
+                    break;
+                };
                 match result {
                     Ok(sub_req) => {
-                        // First request establishes client ID
+                        // First request establishes client ID and namespace, and gets the
+                        // available topics. Later requests cannot switch namespaces.
                         if client_id.is_none() {
+                            let namespace =
+                                match namespaces.authorize(&metadata, &sub_req.namespace) {
+                                    Ok(namespace) => namespace,
+                                    Err(status) => {
+                                        let _ = error_tx.send(status);
+                                        break;
+                                    }
+                                };
+                            let available_topics = topics
+                                .iter()
+                                .filter(|topic| topic.namespace == namespace)
+                                .cloned()
+                                .collect();
+                            let _ = tx.try_send(Self::available_topics_update(available_topics));
                             client_id = Some(sub_req.client_id.clone());
-                            subscription_manager
-                                .register_client(sub_req.client_id.clone(), tx.clone());
+                            client_namespace.clone_from(&namespace);
+                            subscription_manager.register_client_in_namespace(
+                                sub_req.client_id.clone(),
+                                namespace,
+                                tx.clone(),
+                            );
+                        } else if !sub_req.namespace.is_empty()
+                            && sub_req.namespace != client_namespace
+                        {
+                            let _ = error_tx.send(Status::invalid_argument(
+                                "A subscription stream cannot switch namespaces",
+                            ));
+                            break;
                         }
 
-                        // Update subscriptions
-                        subscription_manager.update_subscriptions(
+                        // Update subscriptions and replay resumed topics
+                        if let Err(status) = subscription_manager.resume_subscriptions(
                             &sub_req.client_id,
                             sub_req.topics,
                             sub_req.unsubscribe_topics,
-                        );
+                            &sub_req.resume_from_sequence,
+                        ) {
+                            let _ = error_tx.send(status);
+                            break;
+                        }
                     }
                     Err(e) => {
                         tracing::error!(target: "torii::grpc", "Error receiving subscription request: {}", e);
@@ -301,13 +1354,709 @@ impl Torii for ToriiService {
             }
         });
 
-        // Convert mpsc receiver to stream
-        let output_stream = ReceiverStream::new(rx).map(Ok).boxed();
+        // Convert mpsc receiver to stream, ending with the resume error if any
+        let error = futures_util::stream::once(error_rx)
+            .filter_map(|status| std::future::ready(status.ok().map(Err)));
+        let output_stream = ReceiverStream::new(rx).map(Ok).chain(error).boxed();
 
         Ok(Response::new(output_stream))
     }
 }
 
-pub fn create_grpc_service(state: GrpcState) -> ToriiServer<ToriiService> {
-    ToriiServer::new(ToriiService::new(state)).accept_compressed(CompressionEncoding::Gzip)
+/// Compression and message size limits applied to every gRPC service.
+///
+/// Services always accept gzip and zstd compressed requests. Responses are compressed
+/// with `send_compression` when the client advertises support for it. Unset limits
+/// keep the tonic defaults (4 MiB decoding, unlimited encoding).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GrpcServerOptions {
+    /// Encoding used to compress responses (`None` = uncompressed).
+    pub send_compression: Option<CompressionEncoding>,
+    /// Largest request message accepted, in bytes.
+    pub max_decoding_message_size: Option<usize>,
+    /// Largest response message sent, in bytes.
+    pub max_encoding_message_size: Option<usize>,
+}
+
+impl GrpcServerOptions {
+    /// Compresses responses with `encoding` for clients accepting it.
+    pub fn with_send_compression(mut self, encoding: CompressionEncoding) -> Self {
+        self.send_compression = Some(encoding);
+        self
+    }
+
+    /// Sets the largest request message accepted, in bytes.
+    pub fn with_max_decoding_message_size(mut self, limit: usize) -> Self {
+        self.max_decoding_message_size = Some(limit);
+        self
+    }
+
+    /// Sets the largest response message sent, in bytes.
+    pub fn with_max_encoding_message_size(mut self, limit: usize) -> Self {
+        self.max_encoding_message_size = Some(limit);
+        self
+    }
+}
+
+/// Applies [`GrpcServerOptions`] to a tonic-generated server.
+///
+/// Generated servers expose the same compression and size methods without sharing a
+/// trait, so sink services are configured through this macro before being added to
+/// the router passed to `ToriiConfigBuilder::with_grpc_router`.
+///
+/// ```rust,ignore
+/// let options = GrpcServerOptions::default().with_send_compression(CompressionEncoding::Zstd);
+/// let server = torii::configure_grpc_server!(Erc20Server::new(service), options);
+/// ```
+#[macro_export]
+macro_rules! configure_grpc_server {
+    ($server:expr, $options:expr) => {{
+        let options: &$crate::grpc::GrpcServerOptions = &$options;
+        let mut server = $server
+            .accept_compressed($crate::tonic::codec::CompressionEncoding::Gzip)
+            .accept_compressed($crate::tonic::codec::CompressionEncoding::Zstd);
+        if let Some(encoding) = options.send_compression {
+            server = server.send_compressed(encoding);
+        }
+        if let Some(limit) = options.max_decoding_message_size {
+            server = server.max_decoding_message_size(limit);
+        }
+        if let Some(limit) = options.max_encoding_message_size {
+            server = server.max_encoding_message_size(limit);
+        }
+        server
+    }};
+}
+
+pub fn create_grpc_service(
+    state: GrpcState,
+    options: &GrpcServerOptions,
+) -> ToriiServer<ToriiService> {
+    configure_grpc_server!(ToriiServer::new(ToriiService::new(state)), options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn get_capabilities_reports_registered_components() {
+        let state = GrpcState::new(Arc::new(SubscriptionManager::new()), Vec::new())
+            .with_capabilities(ServerCapabilities {
+                features: vec!["metrics".to_string()],
+                sinks: vec![ComponentVersion::new("erc20", Some("1.2.3"))],
+                decoders: vec![ComponentVersion::new("custom", None)],
+            });
+        let service = ToriiService::new(state);
+
+        let response = service
+            .get_capabilities(Request::new(GetCapabilitiesRequest {}))
+            .await
+            .unwrap()
+            .into_inner();
+
+        assert_eq!(response.version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(response.git_commit, GIT_COMMIT);
+        assert_eq!(response.features, vec!["metrics"]);
+        assert_eq!(response.sinks[0].name, "erc20");
+        assert_eq!(response.sinks[0].version, "1.2.3");
+        assert_eq!(response.decoders[0].name, "custom");
+        assert!(response.decoders[0].version.is_empty());
+        assert!(response
+            .capabilities
+            .contains(&"subscribe_to_topics".to_string()));
+    }
+
+    #[tokio::test]
+    async fn describe_sinks_reports_topics_and_tables() {
+        use crate::etl::sink::ColumnSchema;
+
+        let state = GrpcState::new(Arc::new(SubscriptionManager::new()), Vec::new())
+            .with_sink_descriptions(vec![SinkDescription {
+                name: "erc20".to_string(),
+                version: None,
+                topics: vec![TopicInfo::new("erc20.transfer", vec![], "Transfers")
+                    .with_message_type("torii.sinks.erc20.Transfer")],
+                tables: vec![TableSchema::new(
+                    "transfers",
+                    "Transfers",
+                    vec![
+                        ColumnSchema::new("token", "felt"),
+                        ColumnSchema::new("timestamp", "i64").nullable(),
+                    ],
+                )],
+                descriptor_set: None,
+                envelope_types: Vec::new(),
+            }]);
+
+        let response = ToriiService::new(state)
+            .describe_sinks(Request::new(DescribeSinksRequest::default()))
+            .await
+            .unwrap()
+            .into_inner();
+
+        let sink = &response.sinks[0];
+        assert_eq!(sink.name, "erc20");
+        assert!(sink.version.is_empty());
+        assert_eq!(sink.topics[0].sink_name, "erc20");
+        assert_eq!(sink.topics[0].message_type, "torii.sinks.erc20.Transfer");
+        let columns = &sink.tables[0].columns;
+        assert_eq!(sink.tables[0].name, "transfers");
+        assert_eq!(
+            (columns[0].data_type.as_str(), columns[0].nullable),
+            ("felt", false)
+        );
+        assert_eq!(
+            (columns[1].name.as_str(), columns[1].nullable),
+            ("timestamp", true)
+        );
+    }
+
+    #[tokio::test]
+    async fn get_schema_reports_descriptors_and_envelope_types() {
+        use crate::etl::envelope::TypeId;
+        use crate::etl::sink::EnvelopeSchema;
+        use prost::Message;
+
+        let state = GrpcState::new(Arc::new(SubscriptionManager::new()), Vec::new())
+            .with_sink_descriptions(vec![SinkDescription {
+                name: "erc20".to_string(),
+                version: None,
+                topics: Vec::new(),
+                tables: Vec::new(),
+                descriptor_set: None,
+                envelope_types: vec![EnvelopeSchema::new(
+                    "erc20.transfer",
+                    "torii.sinks.erc20.Transfer",
+                )],
+            }]);
+
+        let response = ToriiService::new(state)
+            .get_schema(Request::new(GetSchemaRequest::default()))
+            .await
+            .unwrap()
+            .into_inner();
+
+        let descriptors =
+            prost_types::FileDescriptorSet::decode(response.file_descriptor_set.as_slice())
+                .unwrap();
+        assert!(descriptors
+            .file
+            .iter()
+            .any(|file| file.name() == "torii.proto"));
+        let envelope = &response.envelope_types[0];
+        assert_eq!(
+            (envelope.sink.as_str(), envelope.type_name.as_str()),
+            ("erc20", "erc20.transfer")
+        );
+        assert_eq!(envelope.type_id, TypeId::new("erc20.transfer").as_u64());
+        assert_eq!(
+            envelope.type_url,
+            "type.googleapis.com/torii.sinks.erc20.Transfer"
+        );
+    }
+
+    #[tokio::test]
+    async fn get_decoder_conflicts_reports_ambiguous_events() {
+        let conflicts = DecoderConflicts::new();
+        conflicts.record(
+            Felt::from(0x1234_u64),
+            Felt::from(0x99_u64),
+            vec!["erc721".to_string(), "erc20".to_string()],
+            42,
+        );
+        let service = ToriiService::new(
+            GrpcState::new(Arc::new(SubscriptionManager::new()), Vec::new())
+                .with_decoder_conflicts(conflicts),
+        );
+
+        let response = service
+            .get_decoder_conflicts(Request::new(GetDecoderConflictsRequest {}))
+            .await
+            .unwrap()
+            .into_inner();
+
+        let conflict = &response.conflicts[0];
+        assert_eq!(
+            conflict.contract,
+            Felt::from(0x1234_u64).to_bytes_be().to_vec()
+        );
+        assert_eq!(conflict.decoders, vec!["erc20", "erc721"]);
+        assert_eq!((conflict.event_count, conflict.first_block), (1, 42));
+        assert!(!conflict.resolved);
+    }
+
+    #[tokio::test]
+    async fn shutdown_ends_streams_with_notice() {
+        use prost::Message;
+
+        let manager = SubscriptionManager::new();
+        let (tx, mut rx) = mpsc::channel(8);
+        manager.register_client("client".to_string(), tx);
+        manager.next_sequence();
+        manager.next_sequence();
+
+        assert_eq!(manager.shutdown(42), 1);
+        assert_eq!(manager.shutdown(43), 0);
+
+        let update = rx.recv().await.unwrap();
+        assert_eq!(update.update_type, UpdateType::Shutdown as i32);
+        let notice = ShutdownNotice::decode(update.data.unwrap().value.as_slice()).unwrap();
+        assert_eq!((notice.block_number, notice.sequence), (42, 2));
+        assert_eq!(manager.shutdown_signal().notified().await, notice);
+        assert!(rx.recv().await.is_none());
+    }
+
+    fn topic_update(topic: &str) -> TopicUpdate {
+        TopicUpdate {
+            topic: topic.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn subscribe(topic: &str, filters: &[(&str, &str)]) -> Vec<TopicSubscription> {
+        vec![TopicSubscription {
+            topic: topic.to_string(),
+            filters: filters
+                .iter()
+                .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+                .collect(),
+            filter_data: None,
+        }]
+    }
+
+    #[tokio::test]
+    async fn publish_numbers_updates_per_topic() {
+        let manager = SubscriptionManager::new();
+        let (tx, mut rx) = mpsc::channel(8);
+        manager.register_client("client".to_string(), tx);
+        manager.update_subscriptions("client", subscribe("a", &[]), Vec::new());
+
+        assert_eq!(manager.publish(topic_update("a"), |_| true), 1);
+        assert_eq!(manager.publish(topic_update("b"), |_| true), 0);
+        assert_eq!(manager.publish(topic_update("a"), |_| true), 1);
+
+        let first = rx.recv().await.unwrap();
+        let second = rx.recv().await.unwrap();
+        assert_eq!((first.sequence, first.topic_sequence), (1, 1));
+        assert_eq!((second.sequence, second.topic_sequence), (3, 2));
+    }
+
+    #[tokio::test]
+    async fn get_topics_reports_publishing_activity() {
+        use crate::etl::counters::CounterSnapshot;
+
+        let manager = Arc::new(SubscriptionManager::new());
+        let mut baseline = CounterSnapshot::default();
+        baseline
+            .topic_publishes
+            .insert("erc20.transfer".to_string(), 10);
+        manager.set_counters(Arc::new(CumulativeCounters::new(baseline)));
+        let (tx, _rx) = mpsc::channel(8);
+        manager.register_client("client".to_string(), tx);
+        manager.update_subscriptions("client", subscribe("erc20.*", &[]), Vec::new());
+        for timestamp in [100, 200] {
+            manager.publish(
+                TopicUpdate {
+                    timestamp,
+                    type_id: "erc20.transfer".to_string(),
+                    ..topic_update("erc20.transfer")
+                },
+                |_| true,
+            );
+        }
+
+        let service = ToriiService::new(GrpcState::new(
+            manager,
+            vec![
+                TopicInfo::new("erc20.transfer", vec![], "Transfers"),
+                TopicInfo::new("erc721.transfer", vec![], "NFT transfers"),
+            ],
+        ));
+        let topics = service
+            .get_topics(Request::new(GetTopicsRequest::default()))
+            .await
+            .unwrap()
+            .into_inner()
+            .topics;
+
+        let stats: Vec<_> = topics
+            .iter()
+            .map(|stats| {
+                (
+                    stats.topic.as_ref().unwrap().name.as_str(),
+                    stats.publish_count,
+                    stats.run_publish_count,
+                    stats.subscriber_count,
+                    stats.last_published_at,
+                )
+            })
+            .collect();
+        assert_eq!(
+            stats,
+            vec![
+                ("erc20.transfer", 12, 2, 1, 200),
+                ("erc721.transfer", 0, 0, 0, 0),
+            ]
+        );
+        assert_eq!(topics[0].last_type_id, "erc20.transfer");
+    }
+
+    #[tokio::test]
+    async fn slow_client_gets_lag_notice() {
+        use prost::Message;
+
+        let manager = SubscriptionManager::new();
+        let (tx, mut rx) = mpsc::channel(2);
+        manager.register_client("client".to_string(), tx);
+        manager.update_subscriptions("client", subscribe("*", &[]), Vec::new());
+
+        assert_eq!(manager.publish(topic_update("a"), |_| true), 1);
+        assert_eq!(manager.publish(topic_update("a"), |_| true), 1);
+        assert_eq!(manager.publish(topic_update("b"), |_| true), 0);
+        assert_eq!(manager.publish(topic_update("c"), |_| true), 0);
+        assert_eq!(rx.recv().await.unwrap().sequence, 1);
+        // One free slot: the notice takes it, the update is dropped too.
+        assert_eq!(manager.publish(topic_update("a"), |_| true), 0);
+        assert_eq!(rx.recv().await.unwrap().sequence, 2);
+        let notice = |update: TopicUpdate| {
+            assert_eq!(update.update_type, UpdateType::Lagged as i32);
+            let notice = LagNotice::decode(update.data.unwrap().value.as_slice()).unwrap();
+            (
+                notice.dropped,
+                notice.first_sequence,
+                notice.last_sequence,
+                notice.topics,
+            )
+        };
+        assert_eq!(
+            notice(rx.recv().await.unwrap()),
+            (2, 3, 4, vec!["b".to_string(), "c".to_string()])
+        );
+
+        assert_eq!(manager.publish(topic_update("b"), |_| true), 1);
+        assert_eq!(
+            notice(rx.recv().await.unwrap()),
+            (1, 5, 5, vec!["a".to_string()])
+        );
+        assert_eq!(rx.recv().await.unwrap().sequence, 6);
+        assert_eq!(
+            manager.clients().read().unwrap()["client"].dropped_updates(),
+            3
+        );
+    }
+
+    #[test]
+    fn topic_patterns_match_topics() {
+        assert!(topic_matches("erc20.transfer", "erc20.transfer"));
+        assert!(!topic_matches("erc20.transfer", "erc20.transfers"));
+        assert!(topic_matches("erc20.*", "erc20.transfer"));
+        assert!(topic_matches("erc20.*", "erc20.balance.update"));
+        assert!(!topic_matches("erc20.*", "erc721.transfer"));
+        assert!(topic_matches("*.transfer", "erc721.transfer"));
+        assert!(topic_matches("erc*.trans*r", "erc1155.transfer"));
+        assert!(!topic_matches("erc*.trans*r", "erc1155.transfers"));
+        assert!(topic_matches("*", "sql"));
+    }
+
+    #[tokio::test]
+    async fn publish_matches_topic_patterns() {
+        let manager = SubscriptionManager::new();
+        let (tx, mut rx) = mpsc::channel(8);
+        manager.register_client("client".to_string(), tx);
+        let mut topics = subscribe("erc20.*", &[("owner", "alice")]);
+        topics.extend(subscribe("erc20.balance", &[]));
+        manager.update_subscriptions("client", topics, Vec::new());
+
+        let alice =
+            |filters: &HashMap<String, String>| filters.get("owner").is_none_or(|o| o == "alice");
+        let bob =
+            |filters: &HashMap<String, String>| filters.get("owner").is_none_or(|o| o == "bob");
+        assert_eq!(manager.publish(topic_update("erc20.transfer"), alice), 1);
+        assert_eq!(manager.publish(topic_update("erc20.transfer"), bob), 0);
+        // The exact subscription has no filters.
+        assert_eq!(manager.publish(topic_update("erc20.balance"), bob), 1);
+        assert_eq!(manager.publish(topic_update("erc721.transfer"), alice), 0);
+
+        assert_eq!(rx.recv().await.unwrap().topic, "erc20.transfer");
+        assert_eq!(rx.recv().await.unwrap().topic, "erc20.balance");
+
+        manager.update_subscriptions("client", Vec::new(), vec!["erc20.*".to_string()]);
+        assert_eq!(manager.publish(topic_update("erc20.transfer"), alice), 0);
+    }
+
+    #[tokio::test]
+    async fn subscribe_pushes_available_topics() {
+        use prost::Message;
+
+        let state = GrpcState::new(
+            Arc::new(SubscriptionManager::new()),
+            vec![
+                TopicInfo::new("erc20.transfer", vec![], "Transfers"),
+                TopicInfo::new("sql", vec![], "SQL operations"),
+            ],
+        );
+        let service = ToriiService::new(state);
+        let mut stream = service
+            .subscribe_to_topics_stream(Request::new(SubscriptionRequest {
+                client_id: "client".to_string(),
+                topics: subscribe("erc20.*", &[]),
+                ..Default::default()
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+
+        let update = stream.next().await.unwrap().unwrap();
+        assert_eq!(update.update_type, UpdateType::Topics as i32);
+        let topics = AvailableTopics::decode(update.data.unwrap().value.as_slice()).unwrap();
+        let names: Vec<_> = topics.topics.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["erc20.transfer", "sql"]);
+    }
+
+    #[tokio::test]
+    async fn namespaces_scope_topics_and_updates() {
+        let manager = Arc::new(SubscriptionManager::new());
+        manager.set_topic_namespaces(HashMap::from([
+            ("erc20".to_string(), "game-a".to_string()),
+            ("erc721".to_string(), "game-b".to_string()),
+        ]));
+        assert_eq!(manager.topic_namespace("erc20.transfer"), "game-a");
+        assert_eq!(manager.topic_namespace("sql"), DEFAULT_NAMESPACE);
+
+        let namespaces = Namespaces::new()
+            .with_sink("erc20", "game-a")
+            .with_sink("erc721", "game-b")
+            .with_api_key("key-a", ["game-a"]);
+        let state = GrpcState::new(
+            manager.clone(),
+            vec![
+                TopicInfo::new("erc20.transfer", vec![], "Transfers"),
+                TopicInfo::new("erc721.transfer", vec![], "NFT transfers"),
+            ],
+        )
+        .with_namespaces(namespaces);
+        let service = ToriiService::new(state);
+
+        let mut request = Request::new(ListTopicsRequest {
+            namespace: "game-a".to_string(),
+        });
+        request
+            .metadata_mut()
+            .insert(crate::namespace::API_KEY_HEADER, "key-a".parse().unwrap());
+        let topics = service
+            .list_topics(request)
+            .await
+            .unwrap()
+            .into_inner()
+            .topics;
+        assert_eq!(topics.len(), 1);
+        assert_eq!(
+            (topics[0].name.as_str(), topics[0].namespace.as_str()),
+            ("erc20.transfer", "game-a")
+        );
+
+        let mut request = Request::new(ListTopicsRequest {
+            namespace: "game-b".to_string(),
+        });
+        request
+            .metadata_mut()
+            .insert(crate::namespace::API_KEY_HEADER, "key-a".parse().unwrap());
+        let status = service.list_topics(request).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::PermissionDenied);
+
+        // A wildcard subscription only receives the updates of the client's namespace.
+        let (tx, mut rx) = mpsc::channel(8);
+        manager.register_client_in_namespace("client".to_string(), "game-a".to_string(), tx);
+        manager.update_subscriptions("client", subscribe("*", &[]), Vec::new());
+        assert_eq!(
+            manager.publish(topic_update("erc721.transfer"), |_| true),
+            0
+        );
+        assert_eq!(manager.publish(topic_update("erc20.transfer"), |_| true), 1);
+        assert_eq!(rx.recv().await.unwrap().topic, "erc20.transfer");
+    }
+
+    #[tokio::test]
+    async fn resume_replays_missed_updates() {
+        let manager = SubscriptionManager::new().with_replay_buffer_size(3);
+        for i in 0..4 {
+            let owner = if i % 2 == 0 { "alice" } else { "bob" };
+            manager.publish(topic_update("a"), move |filters| {
+                filters.get("owner").is_none_or(|o| o == owner)
+            });
+        }
+
+        let (tx, mut rx) = mpsc::channel(manager.stream_capacity());
+        manager.register_client("client".to_string(), tx);
+        let resume = |sequence: u64| HashMap::from([("a".to_string(), sequence)]);
+
+        // Topic sequences 2..=4 are buffered: resuming after 1 replays them all.
+        let replayed = manager
+            .resume_subscriptions("client", subscribe("a", &[]), Vec::new(), &resume(1))
+            .unwrap();
+        assert_eq!(replayed, 3);
+        for expected in 2..=4 {
+            assert_eq!(rx.recv().await.unwrap().topic_sequence, expected);
+        }
+
+        // Replayed updates go through the client's filters.
+        let replayed = manager
+            .resume_subscriptions(
+                "client",
+                subscribe("a", &[("owner", "alice")]),
+                Vec::new(),
+                &resume(1),
+            )
+            .unwrap();
+        assert_eq!(replayed, 1);
+        assert_eq!(rx.recv().await.unwrap().topic_sequence, 3);
+
+        // Nothing missed after the last update.
+        let replayed = manager
+            .resume_subscriptions("client", Vec::new(), Vec::new(), &resume(4))
+            .unwrap();
+        assert_eq!(replayed, 0);
+
+        // Evicted, unknown or unsubscribed sequences cannot be resumed.
+        let evicted = manager
+            .resume_subscriptions("client", Vec::new(), Vec::new(), &resume(0))
+            .unwrap_err();
+        assert_eq!(evicted.code(), tonic::Code::OutOfRange);
+        let ahead = manager
+            .resume_subscriptions("client", Vec::new(), Vec::new(), &resume(5))
+            .unwrap_err();
+        assert_eq!(ahead.code(), tonic::Code::OutOfRange);
+        let unsubscribed = manager
+            .resume_subscriptions(
+                "client",
+                Vec::new(),
+                Vec::new(),
+                &HashMap::from([("b".to_string(), 0)]),
+            )
+            .unwrap_err();
+        assert_eq!(unsubscribed.code(), tonic::Code::InvalidArgument);
+
+        // A failed resume leaves the subscriptions unchanged.
+        let evicted = manager
+            .resume_subscriptions("client", subscribe("b", &[]), Vec::new(), &resume(0))
+            .unwrap_err();
+        assert_eq!(evicted.code(), tonic::Code::OutOfRange);
+        let clients = manager.clients().read().unwrap();
+        let client = &clients["client"];
+        assert_eq!(client.topics.len(), 1);
+        assert!(client.filters_for("b").is_none());
+    }
+
+    #[tokio::test]
+    async fn enter_lame_duck_requires_admin_rpc() {
+        let lame_duck = LameDuck::new(std::time::Duration::from_secs(20));
+        let state = GrpcState::new(Arc::new(SubscriptionManager::new()), Vec::new())
+            .with_lame_duck(lame_duck.clone());
+
+        let denied = ToriiService::new(state.clone())
+            .enter_lame_duck(Request::new(EnterLameDuckRequest {}))
+            .await
+            .unwrap_err();
+        assert_eq!(denied.code(), tonic::Code::PermissionDenied);
+        assert!(!lame_duck.is_active());
+
+        let service = ToriiService::new(state.with_admin_rpc(true));
+        let response = service
+            .enter_lame_duck(Request::new(EnterLameDuckRequest {}))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(!response.already_active);
+        assert_eq!(response.drain_period_seconds, 20);
+        assert!(lame_duck.is_active());
+
+        let response = service
+            .enter_lame_duck(Request::new(EnterLameDuckRequest {}))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(response.already_active);
+    }
+
+    #[tokio::test]
+    async fn enter_lame_duck_requires_an_admin_api_key() {
+        let lame_duck = LameDuck::new(std::time::Duration::from_secs(20));
+        let namespaces = Namespaces::new()
+            .with_api_key("key-a", ["game-a"])
+            .with_api_key("admin", [crate::namespace::ALL_NAMESPACES]);
+        let service = ToriiService::new(
+            GrpcState::new(Arc::new(SubscriptionManager::new()), Vec::new())
+                .with_lame_duck(lame_duck.clone())
+                .with_admin_rpc(true)
+                .with_namespaces(namespaces),
+        );
+        let request = |key: Option<&str>| {
+            let mut request = Request::new(EnterLameDuckRequest {});
+            if let Some(key) = key {
+                request
+                    .metadata_mut()
+                    .insert(crate::namespace::API_KEY_HEADER, key.parse().unwrap());
+            }
+            request
+        };
+
+        let denied = service.enter_lame_duck(request(None)).await.unwrap_err();
+        assert_eq!(denied.code(), tonic::Code::Unauthenticated);
+        let denied = service
+            .enter_lame_duck(request(Some("key-a")))
+            .await
+            .unwrap_err();
+        assert_eq!(denied.code(), tonic::Code::PermissionDenied);
+        assert!(!lame_duck.is_active());
+
+        service
+            .enter_lame_duck(request(Some("admin")))
+            .await
+            .unwrap();
+        assert!(lame_duck.is_active());
+    }
+
+    #[tokio::test]
+    async fn get_contract_stats_reads_engine_db() {
+        let engine_db = Arc::new(
+            EngineDb::new(crate::etl::engine_db::EngineDbConfig {
+                path: ":memory:".to_string(),
+            })
+            .await
+            .unwrap(),
+        );
+        let contract = Felt::from(0xabc_u64);
+        engine_db
+            .record_contract_activity(&[crate::etl::ContractActivity {
+                contract,
+                decoder: "erc20".to_string(),
+                event_count: 4,
+                first_block: 7,
+                last_block: 9,
+                last_activity: 1234,
+            }])
+            .await
+            .unwrap();
+
+        let state = GrpcState::new(Arc::new(SubscriptionManager::new()), Vec::new())
+            .with_engine_db(engine_db);
+        let service = ToriiService::new(state);
+
+        let response = service
+            .get_contract_stats(Request::new(GetContractStatsRequest {
+                contracts: vec![contract.to_bytes_be().to_vec()],
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+
+        assert_eq!(response.stats.len(), 1);
+        let stats = &response.stats[0];
+        assert_eq!(stats.contract, contract.to_bytes_be().to_vec());
+        assert_eq!((stats.first_block, stats.last_block), (7, 9));
+        assert_eq!(stats.events_by_decoder["erc20"], 4);
+        assert_eq!(stats.total_events, 4);
+        assert_eq!(stats.last_activity, 1234);
+    }
 }
@@ -3,15 +3,25 @@
 //! Provides core HTTP endpoints and a simple state pattern that can be extended.
 //! Sinks can add their own routes via the `Sink::build_routes()` method.
 
+use anyhow::Context;
 use axum::{
-    extract::State,
-    http::StatusCode,
-    response::{IntoResponse, Json},
+    extract::{Query, State},
+    http::{header, HeaderMap, HeaderName, HeaderValue, StatusCode},
+    response::{Html, IntoResponse, Json, Response},
     routing::get,
     Router,
 };
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use tower_http::cors::{AllowHeaders, AllowOrigin, Any as CorsAny, CorsLayer};
+
+use crate::etl::counters::{CounterSnapshot, CumulativeCounters};
+use crate::etl::engine_db::EngineDb;
+use crate::lame_duck::LameDuck;
+use crate::schema::ServerSchema;
+use crate::status::{
+    render_html, ContractStatus, CursorStatus, IndexerStatus, StatusResponse, STATUS_CONTRACTS,
+};
 
 /// HTTP server state.
 ///
@@ -21,6 +31,16 @@ use std::sync::Arc;
 pub struct HttpState {
     pub version: String,
     pub startup_time: i64,
+    /// Counters cumulated across restarts (reported by `/health` when set).
+    pub counters: Option<Arc<CumulativeCounters>>,
+    /// Lame-duck state (`/health` reports `503 draining` once entered).
+    pub lame_duck: Option<LameDuck>,
+    /// Live ETL state reported by `/status`.
+    pub status: Option<IndexerStatus>,
+    /// Engine database read by `/status` (cursors, committed head and contract counts).
+    pub engine_db: Option<Arc<EngineDb>>,
+    /// Descriptors and envelope type catalog served by `/schema` (core API only if unset).
+    pub schema: Option<Arc<ServerSchema>>,
 }
 
 impl HttpState {
@@ -28,6 +48,11 @@ impl HttpState {
         Self {
             version: env!("CARGO_PKG_VERSION").to_string(),
             startup_time: chrono::Utc::now().timestamp(),
+            counters: None,
+            lame_duck: None,
+            status: None,
+            engine_db: None,
+            schema: None,
         }
     }
 }
@@ -44,19 +69,36 @@ pub struct HealthResponse {
     pub status: String,
     pub version: String,
     pub uptime_seconds: i64,
+    /// Figures cumulated across restarts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cumulative: Option<CounterSnapshot>,
 }
 
 /// Health check endpoint.
-async fn health_handler(State(state): State<Arc<HttpState>>) -> Json<HealthResponse> {
+///
+/// Returns `503` with status `draining` in lame-duck mode, so load balancers and
+/// readiness probes stop routing new clients to this instance.
+async fn health_handler(State(state): State<Arc<HttpState>>) -> (StatusCode, Json<HealthResponse>) {
     let now = chrono::Utc::now().timestamp();
     let uptime = now - state.startup_time;
     crate::metrics::set_uptime_seconds(uptime as f64);
 
-    Json(HealthResponse {
-        status: "healthy".to_string(),
-        version: state.version.clone(),
-        uptime_seconds: uptime,
-    })
+    let draining = state.lame_duck.as_ref().is_some_and(LameDuck::is_active);
+    let (code, status) = if draining {
+        (StatusCode::SERVICE_UNAVAILABLE, "draining")
+    } else {
+        (StatusCode::OK, "healthy")
+    };
+
+    (
+        code,
+        Json(HealthResponse {
+            status: status.to_string(),
+            version: state.version.clone(),
+            uptime_seconds: uptime,
+            cumulative: state.counters.as_ref().map(|counters| counters.snapshot()),
+        }),
+    )
 }
 
 /// Prometheus metrics endpoint.
@@ -78,16 +120,191 @@ async fn metrics_handler() -> impl IntoResponse {
     }
 }
 
+/// Query parameters of the status endpoint.
+#[derive(Debug, Default, Deserialize)]
+pub struct StatusQuery {
+    /// `json` or `html` (default: negotiated from the `Accept` header, HTML otherwise)
+    pub format: Option<String>,
+}
+
+/// Indexer status endpoint: progress, cursors, sink checkpoints, contract counts and
+/// recent errors, as an HTML dashboard or JSON.
+async fn status_handler(
+    State(state): State<Arc<HttpState>>,
+    Query(query): Query<StatusQuery>,
+    headers: HeaderMap,
+) -> Response {
+    let mut status = StatusResponse {
+        version: state.version.clone(),
+        uptime_seconds: chrono::Utc::now().timestamp() - state.startup_time,
+        indexer: state
+            .status
+            .as_ref()
+            .map(IndexerStatus::snapshot)
+            .unwrap_or_default(),
+        ..StatusResponse::default()
+    };
+
+    if let Some(engine_db) = &state.engine_db {
+        match engine_db.get_head().await {
+            Ok((block, events)) => {
+                status.head_block = Some(block);
+                status.head_events = Some(events);
+            }
+            Err(e) => tracing::warn!(target: "torii::http", error = %e, "Failed to read head"),
+        }
+        match engine_db.list_extractor_states().await {
+            Ok(states) => {
+                status.cursors = states
+                    .into_iter()
+                    .map(|(extractor, key, value)| CursorStatus {
+                        extractor,
+                        key,
+                        value,
+                    })
+                    .collect();
+            }
+            Err(e) => {
+                tracing::warn!(target: "torii::http", error = %e, "Failed to read cursors");
+            }
+        }
+        match engine_db.get_contract_stats(&[]).await {
+            Ok(stats) => {
+                status.contract_count = stats.len();
+                let mut contracts: Vec<ContractStatus> = stats
+                    .iter()
+                    .map(|stats| ContractStatus {
+                        contract: format!("{:#x}", stats.contract),
+                        events: stats.total_events(),
+                        first_block: stats.first_block,
+                        last_block: stats.last_block,
+                        last_activity: stats.last_activity,
+                    })
+                    .collect();
+                contracts.sort_by(|a, b| b.events.cmp(&a.events));
+                contracts.truncate(STATUS_CONTRACTS);
+                status.contracts = contracts;
+            }
+            Err(e) => {
+                tracing::warn!(target: "torii::http", error = %e, "Failed to read contract stats");
+            }
+        }
+    }
+
+    let json = match query.format.as_deref() {
+        Some(format) => format.eq_ignore_ascii_case("json"),
+        None => headers
+            .get(header::ACCEPT)
+            .and_then(|accept| accept.to_str().ok())
+            .is_some_and(|accept| {
+                accept.contains("application/json") && !accept.contains("text/html")
+            }),
+    };
+    if json {
+        Json(status).into_response()
+    } else {
+        Html(render_html(&status)).into_response()
+    }
+}
+
+/// Schema endpoint: file descriptor set of the core and sink APIs (base64) with the
+/// catalog of envelope TypeIds to protobuf type URLs, for client code generators.
+async fn schema_handler(State(state): State<Arc<HttpState>>) -> Response {
+    match &state.schema {
+        Some(schema) => Json(schema.as_ref()).into_response(),
+        None => match ServerSchema::from_sinks([]) {
+            Ok(schema) => Json(schema).into_response(),
+            Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+        },
+    }
+}
+
 /// Create the core HTTP router with basic endpoints.
 pub fn create_http_router() -> Router {
-    let state = Arc::new(HttpState::new());
+    create_http_router_with_state(HttpState::new())
+}
+
+/// Create the core HTTP router with the given state.
+pub fn create_http_router_with_state(state: HttpState) -> Router {
+    let state = Arc::new(state);
 
     Router::new()
         .route("/health", get(health_handler))
         .route("/metrics", get(metrics_handler))
+        .route("/status", get(status_handler))
+        .route("/schema", get(schema_handler))
         .with_state(state)
 }
 
+/// Request headers sent by gRPC-Web clients, always allowed with an explicit header list.
+const GRPC_WEB_REQUEST_HEADERS: &[&str] =
+    &["content-type", "x-grpc-web", "x-user-agent", "grpc-timeout"];
+
+/// Response headers exposed to browsers (gRPC-Web status and framing).
+const EXPOSED_HEADERS: &[&str] = &[
+    "grpc-status",
+    "grpc-message",
+    "grpc-status-details-bin",
+    "x-grpc-web",
+    "content-type",
+];
+
+/// CORS policy of the HTTP/gRPC listener.
+///
+/// Empty lists allow any origin or request header (the default).
+#[derive(Debug, Clone, Default)]
+pub struct CorsConfig {
+    /// Allowed origins (e.g. `https://app.example.com`)
+    pub allowed_origins: Vec<String>,
+    /// Allowed request headers, in addition to the ones gRPC-Web clients send
+    pub allowed_headers: Vec<String>,
+}
+
+impl CorsConfig {
+    /// Builds the CORS layer, failing on invalid origins or header names.
+    pub fn layer(&self) -> anyhow::Result<CorsLayer> {
+        let allow_origin = if self.allowed_origins.is_empty() {
+            AllowOrigin::any()
+        } else {
+            AllowOrigin::list(
+                self.allowed_origins
+                    .iter()
+                    .map(|origin| {
+                        HeaderValue::from_str(origin)
+                            .with_context(|| format!("Invalid CORS origin: {origin}"))
+                    })
+                    .collect::<anyhow::Result<Vec<_>>>()?,
+            )
+        };
+        let allow_headers = if self.allowed_headers.is_empty() {
+            AllowHeaders::any()
+        } else {
+            AllowHeaders::list(
+                GRPC_WEB_REQUEST_HEADERS
+                    .iter()
+                    .copied()
+                    .chain(self.allowed_headers.iter().map(String::as_str))
+                    .map(|header| {
+                        HeaderName::from_bytes(header.as_bytes())
+                            .with_context(|| format!("Invalid CORS header: {header}"))
+                    })
+                    .collect::<anyhow::Result<Vec<_>>>()?,
+            )
+        };
+
+        Ok(CorsLayer::new()
+            .allow_origin(allow_origin)
+            .allow_methods(CorsAny)
+            .allow_headers(allow_headers)
+            .expose_headers(
+                EXPOSED_HEADERS
+                    .iter()
+                    .map(|header| HeaderName::from_static(header))
+                    .collect::<Vec<_>>(),
+            ))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -118,6 +335,167 @@ mod tests {
 
         assert_eq!(health_response.status, "healthy");
         assert!(health_response.uptime_seconds >= 0);
+        assert!(health_response.cumulative.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_health_endpoint_reports_draining() {
+        let lame_duck = LameDuck::default();
+        lame_duck.enter();
+        let app = create_http_router_with_state(HttpState {
+            lame_duck: Some(lame_duck),
+            ..HttpState::new()
+        });
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/health")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let health_response: HealthResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(health_response.status, "draining");
+    }
+
+    async fn preflight(cors: &CorsConfig, origin: &str, headers: &str) -> axum::http::HeaderMap {
+        let app = create_http_router().layer(cors.layer().unwrap());
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("OPTIONS")
+                    .uri("/health")
+                    .header("origin", origin)
+                    .header("access-control-request-method", "POST")
+                    .header("access-control-request-headers", headers)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        response.headers().clone()
+    }
+
+    #[tokio::test]
+    async fn test_cors_allows_any_origin_by_default() {
+        let headers = preflight(&CorsConfig::default(), "https://any.example", "x-custom").await;
+        assert_eq!(headers["access-control-allow-origin"], "*");
+        assert_eq!(headers["access-control-allow-headers"], "*");
+    }
+
+    #[tokio::test]
+    async fn test_cors_restricts_origins_and_headers() {
+        let cors = CorsConfig {
+            allowed_origins: vec!["https://app.example".to_string()],
+            allowed_headers: vec!["authorization".to_string()],
+        };
+
+        let headers = preflight(&cors, "https://app.example", "authorization").await;
+        assert_eq!(
+            headers["access-control-allow-origin"],
+            "https://app.example"
+        );
+        let allowed = headers["access-control-allow-headers"].to_str().unwrap();
+        assert!(allowed.contains("x-grpc-web") && allowed.contains("authorization"));
+
+        let headers = preflight(&cors, "https://other.example", "authorization").await;
+        assert!(!headers.contains_key("access-control-allow-origin"));
+    }
+
+    #[test]
+    fn test_cors_rejects_invalid_origin() {
+        let cors = CorsConfig {
+            allowed_origins: vec!["https://bad\norigin".to_string()],
+            ..CorsConfig::default()
+        };
+        assert!(cors.layer().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_status_endpoint() {
+        let status = IndexerStatus::new().with_sinks(["log"]);
+        status.record_cycle(42, Some(50));
+        status.record_error("decode", "bad event");
+        let engine_db = EngineDb::new(crate::etl::engine_db::EngineDbConfig {
+            path: ":memory:".to_string(),
+        })
+        .await
+        .unwrap();
+        engine_db
+            .set_extractor_state("event", "0x1", "block:42")
+            .await
+            .unwrap();
+        let app = create_http_router_with_state(HttpState {
+            status: Some(status),
+            engine_db: Some(Arc::new(engine_db)),
+            ..HttpState::new()
+        });
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/status?format=json")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let status: StatusResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(status.indexer.lag_blocks, Some(8));
+        assert_eq!(status.indexer.sinks[0].block, Some(42));
+        assert_eq!(status.indexer.recent_errors[0].stage, "decode");
+        assert_eq!(status.cursors[0].value, "block:42");
+        assert_eq!(status.head_block, Some(0));
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/status")
+                    .header("accept", "text/html")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            response.headers()["content-type"],
+            "text/html; charset=utf-8"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_schema_endpoint_serves_core_descriptors() {
+        let app = create_http_router();
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/schema")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let schema: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert!(!schema["file_descriptor_set"].as_str().unwrap().is_empty());
+        assert_eq!(schema["envelope_types"], serde_json::json!([]));
     }
 
     #[tokio::test]
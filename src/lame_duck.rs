@@ -0,0 +1,132 @@
+//! Lame-duck mode for rolling deploys.
+//!
+//! When lame-duck mode is entered (on SIGTERM/SIGINT or through the `EnterLameDuck`
+//! admin RPC), the server:
+//! - rejects new gRPC subscriptions (`Subscribe*` and `Watch*` methods) with `UNAVAILABLE`,
+//! - reports `/health` as `503 draining` so load balancers stop routing to it,
+//! - stops the ETL loop once the current batch is done,
+//! - keeps serving existing streams and queries for the drain period,
+//! - ends every subscription stream with a shutdown notice (last indexed block), then exits.
+
+use axum::{extract::Request, extract::State, middleware::Next, response::Response};
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+use tonic::Status;
+
+/// Shared lame-duck state.
+#[derive(Debug, Clone, Default)]
+pub struct LameDuck {
+    token: CancellationToken,
+    drain_period: Duration,
+}
+
+impl LameDuck {
+    /// Creates the lame-duck state with the given drain period.
+    pub fn new(drain_period: Duration) -> Self {
+        Self {
+            token: CancellationToken::new(),
+            drain_period,
+        }
+    }
+
+    /// How long existing streams and queries are served after entering lame-duck mode.
+    pub fn drain_period(&self) -> Duration {
+        self.drain_period
+    }
+
+    /// Whether lame-duck mode was entered.
+    pub fn is_active(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// Enters lame-duck mode. Returns `false` if it was already active.
+    pub fn enter(&self) -> bool {
+        let first = !self.token.is_cancelled();
+        self.token.cancel();
+        if first {
+            ::metrics::gauge!("torii_lame_duck").set(1.0);
+        }
+        first
+    }
+
+    /// Completes once lame-duck mode is entered.
+    pub async fn entered(&self) {
+        self.token.cancelled().await;
+    }
+}
+
+/// Axum middleware rejecting new gRPC subscriptions while in lame-duck mode.
+///
+/// Applies to every service on the router (core and sinks): any method whose name
+/// starts with `Subscribe` or `Watch` is a new subscription. Streams opened before lame-duck
+/// mode are not affected.
+pub async fn reject_new_subscriptions(
+    State(lame_duck): State<LameDuck>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if lame_duck.is_active() && is_subscription(request.uri().path()) {
+        ::metrics::counter!("torii_lame_duck_rejected_subscriptions_total").increment(1);
+        return Status::unavailable("Server is draining (lame-duck mode)")
+            .into_http()
+            .map(axum::body::Body::new);
+    }
+    next.run(request).await
+}
+
+fn is_subscription(path: &str) -> bool {
+    path.rsplit('/')
+        .next()
+        .is_some_and(|method| method.starts_with("Subscribe") || method.starts_with("Watch"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::routing::post;
+    use axum::Router;
+    use tower::ServiceExt;
+
+    fn router(lame_duck: LameDuck) -> Router {
+        Router::new()
+            .route(
+                "/torii.Torii/SubscribeToTopicsStream",
+                post(|| async { "ok" }),
+            )
+            .route("/torii.Torii/ListTopics", post(|| async { "ok" }))
+            .layer(axum::middleware::from_fn_with_state(
+                lame_duck,
+                reject_new_subscriptions,
+            ))
+    }
+
+    async fn grpc_status(router: Router, path: &str) -> Option<String> {
+        let response = router
+            .oneshot(axum::http::Request::post(path).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        response
+            .headers()
+            .get("grpc-status")
+            .map(|v| v.to_str().unwrap().to_string())
+    }
+
+    #[tokio::test]
+    async fn test_rejects_only_new_subscriptions_when_active() {
+        let lame_duck = LameDuck::new(Duration::from_secs(10));
+        let path = "/torii.Torii/SubscribeToTopicsStream";
+        assert_eq!(grpc_status(router(lame_duck.clone()), path).await, None);
+
+        assert!(lame_duck.enter());
+        assert!(!lame_duck.enter());
+        assert_eq!(
+            grpc_status(router(lame_duck.clone()), path).await,
+            Some((tonic::Code::Unavailable as i32).to_string())
+        );
+        assert_eq!(
+            grpc_status(router(lame_duck), "/torii.Torii/ListTopics").await,
+            None
+        );
+    }
+}
@@ -3,11 +3,22 @@
 //! This library aims at providing a modular and high-performance blockchain indexer.
 //! The current implementation is still WIP, but gives a good idea of the architecture and the capabilities.
 
+// Lets derived code (`::torii::...` paths) compile inside this crate.
+extern crate self as torii;
+
+pub mod admin;
 pub mod command;
+pub mod error;
 pub mod etl;
 pub mod grpc;
 pub mod http;
+pub mod lame_duck;
 pub mod metrics;
+pub mod namespace;
+pub mod publisher;
+pub mod schema;
+pub mod status;
+pub mod validate;
 
 // Include generated protobuf code
 pub mod proto {
@@ -20,10 +31,17 @@ pub mod proto {
 pub use async_trait::async_trait;
 pub use axum;
 pub use tokio;
+pub use tokio_rustls::rustls;
 pub use tonic;
 
 // Re-export UpdateType for sink implementations
-pub use grpc::UpdateType;
+pub use grpc::{GrpcServerOptions, UpdateType};
+pub use namespace::Namespaces;
+
+pub use error::{Stage, ToriiError, ToriiResult};
+pub use publisher::Publisher;
+pub use status::IndexerStatus;
+pub use validate::{validate, ValidationReport};
 
 use axum::Router as AxumRouter;
 use std::fs::File;
@@ -34,19 +52,23 @@ use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio_util::sync::CancellationToken;
-use tonic::codec::CompressionEncoding;
 use tonic::transport::Server;
 use tower::Service;
-use tower_http::cors::{Any as CorsAny, CorsLayer};
 
+use admin::{create_admin_service, EtlControl, EtlParams};
 use command::{CommandBus, CommandHandler};
-use etl::decoder::{ContractFilter, DecoderId};
+use etl::decoder::{ContractFilter, DecoderConfigWatcher, DecoderFactory, DecoderId};
 use etl::extractor::{Extractor, SyntheticExtractor, SyntheticExtractorAdapter};
 use etl::identification::{ContractIdentifier, IdentificationRule};
 use etl::sink::{EventBus, Sink};
-use etl::{Decoder, DecoderContext, MultiSink, SampleExtractor};
-use grpc::{create_grpc_service, GrpcState, SubscriptionManager};
-use http::create_http_router;
+use etl::{
+    CounterSnapshot, CumulativeCounters, Decoder, DecoderContext, MultiSink, SampleExtractor,
+};
+use grpc::{
+    create_grpc_service, ComponentVersion, GrpcState, ServerCapabilities, SubscriptionManager,
+};
+use http::{create_http_router_with_state, CorsConfig, HttpState};
+use lame_duck::LameDuck;
 
 // Include the file descriptor set generated at build time.
 // This is also exported publicly so external sink authors can use it for reflection.
@@ -75,6 +97,7 @@ pub const TORII_DESCRIPTOR_SET: &[u8] = FILE_DESCRIPTOR_SET;
 /// Configuration for Torii server with pluggable sinks and decoders.
 #[derive(Debug, Clone)]
 pub struct EtlConcurrencyConfig {
+    /// Capacity of the bounded queues between pipeline stages (extract → decode → sink).
     pub max_prefetch_batches: usize,
 }
 
@@ -92,6 +115,7 @@ impl EtlConcurrencyConfig {
     }
 }
 
+#[allow(clippy::struct_excessive_bools)]
 pub struct ToriiConfig {
     /// Port to listen on.
     pub port: u16,
@@ -193,8 +217,109 @@ pub struct ToriiConfig {
     /// Command bus queue size.
     pub command_bus_queue_size: usize,
 
+    /// Handles publishing updates from outside the sinks, attached on startup.
+    pub publishers: Vec<Publisher>,
+
     /// Optional TLS listener configuration.
     pub tls: Option<ToriiTlsConfig>,
+
+    /// Whether envelopes carry provenance (extractor, decoder, batch id, timestamps).
+    ///
+    /// Debug flag: sinks that support it persist the provenance alongside stored rows.
+    pub provenance: bool,
+
+    /// Whether a structured record is logged for every decoded envelope (debug).
+    ///
+    /// Records carry the source event, the decoder and the rule that mapped the contract
+    /// to it (`torii::etl::envelopes` log target).
+    pub debug_envelopes: bool,
+
+    /// Whether every fully fetched block yields a `block.header.v1` envelope
+    /// ([`etl::decoder::BlockHeaderV1`]).
+    pub block_headers: bool,
+
+    /// Compression and message size limits of the core gRPC services.
+    ///
+    /// Sink services in `partial_grpc_router` are configured by their owner, with
+    /// [`configure_grpc_server!`] and the same options.
+    pub grpc_options: GrpcServerOptions,
+
+    /// Interval in seconds between snapshots of cumulative counters (default: 30, 0 = disabled).
+    ///
+    /// Snapshots are stored in the engine database so metrics and `/health` report
+    /// cumulative figures across restarts.
+    pub metrics_snapshot_interval: u64,
+
+    /// Lame-duck drain period in seconds (default: 0 = exit right away).
+    ///
+    /// On SIGTERM/SIGINT (or `EnterLameDuck`), new subscriptions are rejected and
+    /// `/health` reports `503` while existing streams and queries are served for
+    /// this duration before the server exits.
+    pub drain_period: u64,
+
+    /// Whether admin RPCs (`EnterLameDuck`, the `torii.Admin` service) are enabled.
+    ///
+    /// With API keys configured, admin RPCs require a key permitted every namespace;
+    /// without, anyone reaching the gRPC port can use them.
+    pub admin_rpc: bool,
+
+    /// Updates buffered per topic for resumed subscriptions (default: 1024, 0 = disabled).
+    pub replay_buffer_size: usize,
+
+    /// CORS policy of the listener (default: any origin and header).
+    pub cors: CorsConfig,
+
+    /// Prefix under which sink routes are mounted as `<prefix>/<sink name>/...`.
+    ///
+    /// If None, sink routes are mounted at the root.
+    pub sink_route_prefix: Option<String>,
+
+    /// Sinks processing a batch at the same time (0 = all of them, the default).
+    pub max_concurrent_sinks: usize,
+
+    /// Batches queued for the sinks above which extraction is throttled (0 = never).
+    pub sink_backpressure_threshold: usize,
+
+    /// Namespace of each sink and the API keys permitted to access them.
+    pub namespaces: Namespaces,
+
+    /// Per-sink timeout and exclusivity, by sink name.
+    pub sink_workers: std::collections::HashMap<String, etl::sink::SinkWorkerConfig>,
+
+    /// TOML file whose decoder/contract sections are reloaded at runtime.
+    ///
+    /// See [`etl::decoder::reload`] for the format.
+    pub decoder_config: Option<PathBuf>,
+
+    /// Factories instantiating the `[[decoders]]` entries of the decoder config.
+    pub decoder_factories: Vec<Arc<dyn DecoderFactory>>,
+
+    /// Interval in seconds between checks of the decoder config (default: 5).
+    pub decoder_config_poll_interval: u64,
+
+    /// Address labels managed through the `torii.Admin` label RPCs.
+    pub address_labels: Option<torii_common::AddressLabels>,
+
+    /// Whether the raw events of processed batches are archived in the engine database.
+    ///
+    /// Archived events can be replayed through decoders and sinks with an
+    /// [`ArchiveExtractor`](etl::extractor::ArchiveExtractor).
+    pub archive_events: bool,
+
+    /// Number of recently processed events remembered to drop duplicates (default: 0 = disabled).
+    ///
+    /// Events are keyed by transaction hash and content; the window is persisted in the
+    /// engine database.
+    pub dedupe_window: usize,
+
+    /// Startup check of the cursor against the blocks committed by the sinks
+    /// (default: disabled).
+    pub startup_consistency: etl::StartupConsistency,
+
+    /// Coordinator of the contract shards leased by this instance (horizontal scaling).
+    ///
+    /// Its leases are renewed while the server runs and released on shutdown.
+    pub shard_coordinator: Option<Arc<etl::ShardCoordinator>>,
 }
 
 impl ToriiConfig {
@@ -203,11 +328,17 @@ impl ToriiConfig {
     }
 }
 
+/// TLS configuration of the combined HTTP/gRPC listener.
+///
+/// Either PEM certificate/key paths, or a pre-built rustls [`ServerConfig`](rustls::ServerConfig)
+/// (e.g. for client authentication or certificate resolvers). When a server config is
+/// set, the paths are ignored.
 #[derive(Debug, Clone)]
 pub struct ToriiTlsConfig {
     pub cert_path: PathBuf,
     pub key_path: PathBuf,
     pub alpn_protocols: Vec<Vec<u8>>,
+    pub server_config: Option<Arc<rustls::ServerConfig>>,
 }
 
 impl ToriiTlsConfig {
@@ -216,6 +347,18 @@ impl ToriiTlsConfig {
             cert_path: cert_path.into(),
             key_path: key_path.into(),
             alpn_protocols: vec![b"h2".to_vec(), b"http/1.1".to_vec()],
+            server_config: None,
+        }
+    }
+
+    /// Uses a pre-built rustls server configuration.
+    ///
+    /// If the configuration has no ALPN protocols, the default ones (`h2`, `http/1.1`)
+    /// are set, as gRPC requires HTTP/2.
+    pub fn from_server_config(server_config: Arc<rustls::ServerConfig>) -> Self {
+        Self {
+            server_config: Some(server_config),
+            ..Self::new(PathBuf::new(), PathBuf::new())
         }
     }
 
@@ -234,6 +377,7 @@ impl ToriiTlsConfig {
 
 /// Builder for ToriiConfig.
 #[derive(Default)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct ToriiConfigBuilder {
     port: Option<u16>,
     host: Option<String>,
@@ -261,7 +405,30 @@ pub struct ToriiConfigBuilder {
     etl_concurrency: Option<EtlConcurrencyConfig>,
     command_handlers: Vec<Box<dyn CommandHandler>>,
     command_bus_queue_size: Option<usize>,
+    publishers: Vec<Publisher>,
     tls: Option<ToriiTlsConfig>,
+    provenance: bool,
+    debug_envelopes: bool,
+    block_headers: bool,
+    grpc_options: GrpcServerOptions,
+    metrics_snapshot_interval: Option<u64>,
+    drain_period: Option<u64>,
+    admin_rpc: bool,
+    replay_buffer_size: Option<usize>,
+    cors: CorsConfig,
+    sink_route_prefix: Option<String>,
+    max_concurrent_sinks: usize,
+    sink_backpressure_threshold: usize,
+    namespaces: Namespaces,
+    sink_workers: std::collections::HashMap<String, etl::sink::SinkWorkerConfig>,
+    decoder_config: Option<PathBuf>,
+    decoder_factories: Vec<Arc<dyn DecoderFactory>>,
+    decoder_config_poll_interval: Option<u64>,
+    address_labels: Option<torii_common::AddressLabels>,
+    archive_events: bool,
+    dedupe_window: usize,
+    startup_consistency: etl::StartupConsistency,
+    shard_coordinator: Option<Arc<etl::ShardCoordinator>>,
 }
 
 impl ToriiConfigBuilder {
@@ -453,6 +620,24 @@ impl ToriiConfigBuilder {
         self
     }
 
+    /// Drop events of `contract` with any of `selectors` before decoding.
+    ///
+    /// Use for contracts emitting floods of irrelevant events (e.g. oracle updates)
+    /// next to the ones being indexed; other events of the contract are decoded as usual.
+    pub fn deny_selectors(
+        mut self,
+        contract: starknet::core::types::Felt,
+        selectors: Vec<starknet::core::types::Felt>,
+    ) -> Self {
+        self.contract_filter
+            .get_or_insert_with(ContractFilter::new)
+            .selector_denylist
+            .entry(contract)
+            .or_default()
+            .extend(selectors);
+        self
+    }
+
     /// Add identification rule for auto-discovery.
     ///
     /// Identification rules are used to automatically identify contract types
@@ -571,6 +756,13 @@ impl ToriiConfigBuilder {
         self
     }
 
+    /// Attaches a [`Publisher`] to the instance, so the application can publish its own
+    /// updates to subscribers. Its topics are advertised along with the sinks' topics.
+    pub fn with_publisher(mut self, publisher: Publisher) -> Self {
+        self.publishers.push(publisher);
+        self
+    }
+
     pub fn command_bus_queue_size(mut self, size: usize) -> Self {
         self.command_bus_queue_size = Some(size.max(1));
         self
@@ -581,6 +773,233 @@ impl ToriiConfigBuilder {
         self
     }
 
+    /// Enables provenance tracking on envelopes (debug only).
+    ///
+    /// Each envelope records the extractor type, decoder name, ETL batch id and
+    /// extraction/decoding timestamps, so sinks can answer "where did this row
+    /// come from and when" during incident analysis. Disabled by default.
+    pub fn with_provenance(mut self, enabled: bool) -> Self {
+        self.provenance = enabled;
+        self
+    }
+
+    /// Logs a structured record for every decoded envelope (debug only).
+    ///
+    /// Each record names the decoder that produced the envelope and the rule that
+    /// mapped its contract to that decoder (explicit mapping, registry identification or
+    /// fallback to every decoder), to diagnose why an event ended up in a sink.
+    /// Disabled by default.
+    pub fn with_debug_envelopes(mut self, enabled: bool) -> Self {
+        self.debug_envelopes = enabled;
+        self
+    }
+
+    /// Emits a `block.header.v1` envelope ([`etl::decoder::BlockHeaderV1`]) per block.
+    ///
+    /// Headers carry the sequencer address, gas prices and Starknet version, and are
+    /// only available from extractors fetching full blocks (block-range, feeder
+    /// gateway). Batches without events are not decoded, so their blocks yield no
+    /// header. Disabled by default.
+    pub fn with_block_headers(mut self, enabled: bool) -> Self {
+        self.block_headers = enabled;
+        self
+    }
+
+    /// Sets the compression and message size limits of the core gRPC services.
+    ///
+    /// Requests compressed with gzip or zstd are always accepted; responses are
+    /// uncompressed and message sizes use the tonic defaults unless configured.
+    pub fn with_grpc_options(mut self, options: GrpcServerOptions) -> Self {
+        self.grpc_options = options;
+        self
+    }
+
+    /// Sets the interval in seconds between snapshots of cumulative counters.
+    ///
+    /// Counters (events processed, per-sink rows, uptime) are persisted in the
+    /// engine database and reloaded at startup. `0` disables periodic snapshots;
+    /// a final snapshot is still taken on graceful shutdown. Default is 30 seconds.
+    pub fn metrics_snapshot_interval(mut self, seconds: u64) -> Self {
+        self.metrics_snapshot_interval = Some(seconds);
+        self
+    }
+
+    /// Reloads the decoder/contract sections of a TOML file at runtime.
+    ///
+    /// New explicit mappings are added to the contract filter and registry cache,
+    /// contracts removed from the file are blacklisted, `[[selector_denylist]]` entries
+    /// extend the selector denylist and new `[[decoders]]` entries
+    /// are instantiated by the factory of their kind, without restarting. The file
+    /// is applied once at startup; an invalid file then fails startup, while later
+    /// invalid edits are logged and ignored.
+    pub fn decoder_config(mut self, path: impl Into<PathBuf>) -> Self {
+        self.decoder_config = Some(path.into());
+        self
+    }
+
+    /// Registers a factory for `[[decoders]]` entries of the decoder config.
+    pub fn add_decoder_factory(mut self, factory: Arc<dyn DecoderFactory>) -> Self {
+        self.decoder_factories.push(factory);
+        self
+    }
+
+    /// Sets the interval in seconds between checks of the decoder config. Default is 5 seconds.
+    pub fn decoder_config_poll_interval(mut self, seconds: u64) -> Self {
+        self.decoder_config_poll_interval = Some(seconds);
+        self
+    }
+
+    /// Sets the lame-duck drain period in seconds.
+    ///
+    /// On shutdown, the server first enters lame-duck mode: new subscriptions are
+    /// rejected, `/health` reports `503 draining` and the ETL loop stops after the
+    /// current batch, while existing streams and queries keep being served for this
+    /// duration. A second signal ends the drain early. Default is 0 (no drain).
+    pub fn drain_period(mut self, seconds: u64) -> Self {
+        self.drain_period = Some(seconds);
+        self
+    }
+
+    /// Enables admin RPCs (`EnterLameDuck`, the `torii.Admin` service controlling the ETL loop).
+    ///
+    /// Disabled by default. When API keys are configured (see [`Self::with_namespaces`]),
+    /// admin RPCs require a key permitted [`namespace::ALL_NAMESPACES`], and Torii refuses
+    /// to start if there is none. Without API keys they are unauthenticated: only enable
+    /// them when the gRPC port is isolated from untrusted networks.
+    pub fn with_admin_rpc(mut self, enabled: bool) -> Self {
+        self.admin_rpc = enabled;
+        self
+    }
+
+    /// Exposes `labels` to the `torii.Admin` label RPCs (`SetAddressLabels`, ...).
+    ///
+    /// Share the same store with the token services attaching labels to responses.
+    pub fn with_address_labels(mut self, labels: torii_common::AddressLabels) -> Self {
+        self.address_labels = Some(labels);
+        self
+    }
+
+    /// Archives the raw events of processed batches in the engine database.
+    ///
+    /// Events are stored with their block and transaction context (receipts included)
+    /// as one zstd-compressed chunk per batch once the sinks processed them, so they
+    /// can be replayed with an
+    /// [`ArchiveExtractor`](etl::extractor::ArchiveExtractor), e.g. after a sink
+    /// schema change, without fetching the chain again. Disabled by default.
+    pub fn archive_events(mut self, enabled: bool) -> Self {
+        self.archive_events = enabled;
+        self
+    }
+
+    /// Drops events already processed among the last `events` ones before decoding.
+    ///
+    /// Switching extraction modes or overlapping cursors can extract the same events
+    /// again; they are recognized by transaction hash and content and never reach the sinks.
+    /// The window is persisted in the engine database. `0` disables deduplication
+    /// (the default).
+    pub fn dedupe_window(mut self, events: usize) -> Self {
+        self.dedupe_window = events;
+        self
+    }
+
+    /// Checks at startup that the sinks committed the blocks the cursor claims.
+    ///
+    /// After a crash, the stored cursor can be ahead of a sink's data. The engine head
+    /// is compared with the block each sink reports
+    /// ([`Sink::indexed_block`](etl::Sink::indexed_block)); sinks behind are logged and,
+    /// with [`StartupConsistency::Rewind`](etl::StartupConsistency::Rewind), the cursor
+    /// is rewound to the lowest of them. Disabled by default.
+    pub fn startup_consistency(mut self, mode: etl::StartupConsistency) -> Self {
+        self.startup_consistency = mode;
+        self
+    }
+
+    /// Keeps the shard leases of `coordinator` while running.
+    ///
+    /// The shards must be acquired ([`ShardCoordinator::acquire`](etl::ShardCoordinator::acquire))
+    /// before building the extractor restricted to them, and their renewal started
+    /// right away ([`ShardCoordinator::start_renewal`](etl::ShardCoordinator::start_renewal),
+    /// otherwise started when running). Losing a lease enters lame-duck mode so that the
+    /// instance stops indexing shards another instance took over. They are released
+    /// once the ETL loop stopped.
+    pub fn with_shard_coordinator(mut self, coordinator: Arc<etl::ShardCoordinator>) -> Self {
+        self.shard_coordinator = Some(coordinator);
+        self
+    }
+
+    /// Sets the number of updates buffered per topic for resumed subscriptions.
+    ///
+    /// Clients reconnecting with `resume_from_sequence` get the missed updates from
+    /// this buffer; resuming further back fails. `0` disables resuming. Default is 1024.
+    pub fn replay_buffer_size(mut self, size: usize) -> Self {
+        self.replay_buffer_size = Some(size);
+        self
+    }
+
+    /// Restricts CORS to the given origins (e.g. `https://app.example.com`).
+    ///
+    /// Any origin is allowed by default.
+    pub fn cors_allowed_origins(mut self, origins: Vec<String>) -> Self {
+        self.cors.allowed_origins = origins;
+        self
+    }
+
+    /// Restricts CORS request headers to the given ones.
+    ///
+    /// The headers sent by gRPC-Web clients (`content-type`, `x-grpc-web`,
+    /// `x-user-agent`, `grpc-timeout`) stay allowed. Any header is allowed by default.
+    pub fn cors_allowed_headers(mut self, headers: Vec<String>) -> Self {
+        self.cors.allowed_headers = headers;
+        self
+    }
+
+    /// Mounts the HTTP routes of each sink under `<prefix>/<sink name>`.
+    ///
+    /// For example, with `/sinks` the routes of the `sql` sink are served under
+    /// `/sinks/sql/...`, so routes of different sinks cannot collide. Sink routes
+    /// are mounted at the root by default.
+    pub fn sink_route_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.sink_route_prefix = Some(prefix.into());
+        self
+    }
+
+    /// Limits how many sinks process a batch at the same time.
+    ///
+    /// Sinks run concurrently by default; each sink still receives the envelopes of a
+    /// batch in order.
+    pub fn max_concurrent_sinks(mut self, limit: usize) -> Self {
+        self.max_concurrent_sinks = limit;
+        self
+    }
+
+    /// Throttles extraction while more than `batches` decoded batches wait for the sinks.
+    ///
+    /// The extract stage pauses (and adaptive extractors shrink their batches) until
+    /// the sinks catch up, then resumes. Disabled by default (0).
+    pub fn sink_backpressure_threshold(mut self, batches: usize) -> Self {
+        self.sink_backpressure_threshold = batches;
+        self
+    }
+
+    /// Registers sinks under namespaces and sets the API keys of the gRPC API.
+    ///
+    /// Clients of `ListTopics`, `DescribeSinks` and the subscriptions only see the
+    /// namespace they request (see [`namespace`]).
+    pub fn with_namespaces(mut self, namespaces: Namespaces) -> Self {
+        self.namespaces = namespaces;
+        self
+    }
+
+    /// Sets the processing timeout and exclusivity of the sink named `sink`.
+    pub fn sink_worker(
+        mut self,
+        sink: impl Into<String>,
+        config: etl::sink::SinkWorkerConfig,
+    ) -> Self {
+        self.sink_workers.insert(sink.into(), config);
+        self
+    }
+
     /// Builds the Torii configuration.
     ///
     /// # Panics
@@ -616,7 +1035,32 @@ impl ToriiConfigBuilder {
             etl_concurrency: self.etl_concurrency.unwrap_or_default(),
             command_handlers: self.command_handlers,
             command_bus_queue_size: self.command_bus_queue_size.unwrap_or(4096),
+            publishers: self.publishers,
             tls: self.tls,
+            provenance: self.provenance,
+            debug_envelopes: self.debug_envelopes,
+            block_headers: self.block_headers,
+            grpc_options: self.grpc_options,
+            metrics_snapshot_interval: self.metrics_snapshot_interval.unwrap_or(30),
+            drain_period: self.drain_period.unwrap_or(0),
+            admin_rpc: self.admin_rpc,
+            replay_buffer_size: self
+                .replay_buffer_size
+                .unwrap_or(grpc::DEFAULT_REPLAY_BUFFER_SIZE),
+            cors: self.cors,
+            sink_route_prefix: self.sink_route_prefix,
+            max_concurrent_sinks: self.max_concurrent_sinks,
+            sink_backpressure_threshold: self.sink_backpressure_threshold,
+            namespaces: self.namespaces,
+            sink_workers: self.sink_workers,
+            decoder_config: self.decoder_config,
+            decoder_factories: self.decoder_factories,
+            decoder_config_poll_interval: self.decoder_config_poll_interval.unwrap_or(5).max(1),
+            address_labels: self.address_labels,
+            archive_events: self.archive_events,
+            dedupe_window: self.dedupe_window,
+            startup_consistency: self.startup_consistency,
+            shard_coordinator: self.shard_coordinator,
         }
     }
 }
@@ -627,10 +1071,24 @@ impl ToriiConfigBuilder {
 ///
 /// TODO: this function is just too big. But it has the whole workflow.
 /// This will be split into smaller functions in the future with associated configuration for each step.
-pub async fn run(config: ToriiConfig) -> Result<(), Box<dyn std::error::Error>> {
+pub async fn run(config: ToriiConfig) -> ToriiResult<()> {
     tracing::info!(target: "torii::main", "Starting Torii with {} sink(s) and {} decoder(s)",
         config.sinks.len(), config.decoders.len());
 
+    if config.admin_rpc {
+        if !config.namespaces.requires_api_key() {
+            tracing::warn!(
+                target: "torii::main",
+                "Admin RPCs are enabled without API keys: keep the gRPC port off untrusted networks"
+            );
+        } else if !config.namespaces.has_admin_key() {
+            return Err(ToriiError::Config(format!(
+                "admin RPCs are enabled but no API key is permitted '{}'",
+                namespace::ALL_NAMESPACES
+            )));
+        }
+    }
+
     match metrics::init_from_env() {
         Ok(true) => {
             tracing::info!(target: "torii::main", "Prometheus metrics enabled at /metrics");
@@ -644,11 +1102,17 @@ pub async fn run(config: ToriiConfig) -> Result<(), Box<dyn std::error::Error>>
         }
     }
 
-    let subscription_manager = Arc::new(SubscriptionManager::new());
+    let subscription_manager =
+        Arc::new(SubscriptionManager::new().with_replay_buffer_size(config.replay_buffer_size));
     let event_bus = Arc::new(EventBus::new(subscription_manager.clone()));
     for handler in &config.command_handlers {
         handler.attach_event_bus(event_bus.clone());
     }
+    for publisher in &config.publishers {
+        if !publisher.attach(event_bus.clone()) {
+            tracing::warn!(target: "torii::main", "Publisher already attached to another instance");
+        }
+    }
     let command_bus = CommandBus::new(config.command_handlers, config.command_bus_queue_size)?;
 
     // Create SinkContext for initialization
@@ -661,13 +1125,25 @@ pub async fn run(config: ToriiConfig) -> Result<(), Box<dyn std::error::Error>>
 
     for mut sink in config.sinks {
         // Box is used for sinks since we need to call initialize (mutable reference).
-        sink.initialize(event_bus.clone(), &sink_context).await?;
+        sink.initialize(event_bus.clone(), &sink_context)
+            .await
+            .map_err(|e| e.in_stage(Stage::Sink))?;
+        if let Some(filter) = sink.contract_filter() {
+            filter
+                .validate()
+                .map_err(|e| ToriiError::Config(format!("{e:#}")))?;
+            tracing::info!(
+                target: "torii::main",
+                sink = sink.name(),
+                allowlist = filter.allowlist.len(),
+                denylist = filter.denylist.len(),
+                "Sink contract routing enabled"
+            );
+        }
         // Convert Box<dyn Sink> to Arc<dyn Sink> since now we can use it immutably.
         initialized_sinks.push(Arc::from(sink));
     }
 
-    let multi_sink = Arc::new(MultiSink::new(initialized_sinks));
-
     // Create EngineDb (needed by DecoderContext)
     let engine_db_path = config.engine_database_url.clone().unwrap_or_else(|| {
         config
@@ -682,6 +1158,27 @@ pub async fn run(config: ToriiConfig) -> Result<(), Box<dyn std::error::Error>>
     let engine_db = etl::EngineDb::new(engine_db_config).await?;
     let engine_db = Arc::new(engine_db);
 
+    // Counters cumulated across restarts (snapshotted into the engine DB).
+    let counters = match CumulativeCounters::load(&engine_db).await {
+        Ok(counters) => counters,
+        Err(e) => {
+            tracing::warn!(target: "torii::main", error = %e, "Failed to load metrics snapshot, starting from zero");
+            CumulativeCounters::new(CounterSnapshot::default())
+        }
+    };
+    let counters = Arc::new(counters);
+    counters.publish();
+    subscription_manager.set_counters(counters.clone());
+
+    let multi_sink = Arc::new(
+        MultiSink::new(initialized_sinks)
+            .with_counters(counters.clone())
+            .with_route_prefix(config.sink_route_prefix.clone())
+            .with_max_concurrency(config.max_concurrent_sinks)
+            .with_backpressure_threshold(config.sink_backpressure_threshold)
+            .with_worker_configs(config.sink_workers.clone()),
+    );
+
     // Create extractor early so we can get the provider for contract identification
     let extractor: Box<dyn Extractor> = if let Some(extractor) = config.extractor {
         tracing::info!(target: "torii::etl", "Using configured extractor");
@@ -702,6 +1199,55 @@ pub async fn run(config: ToriiConfig) -> Result<(), Box<dyn std::error::Error>>
             config.events_per_cycle,
         ))
     };
+    let extractor_capabilities = extractor.capabilities();
+    tracing::info!(
+        target: "torii::etl",
+        extractor = extractor.extractor_type(),
+        supports_receipts = extractor_capabilities.supports_receipts,
+        supports_pending = extractor_capabilities.supports_pending,
+        max_chunk = ?extractor_capabilities.max_chunk,
+        "Extractor capabilities"
+    );
+
+    let mut features = Vec::new();
+    if metrics::is_enabled() {
+        features.push("metrics".to_string());
+    }
+    if config.tls.is_some() {
+        features.push("tls".to_string());
+    }
+    if config.contract_identifier.is_some() {
+        features.push("contract_identification".to_string());
+    }
+    if multi_sink
+        .sinks()
+        .iter()
+        .any(|sink| sink.contract_filter().is_some())
+    {
+        features.push("sink_contract_routing".to_string());
+    }
+    if config.provenance {
+        features.push("provenance".to_string());
+    }
+    if config.block_headers {
+        features.push("block_headers".to_string());
+    }
+    if config.decoder_config.is_some() {
+        features.push("decoder_hot_reload".to_string());
+    }
+    let capabilities = ServerCapabilities {
+        features,
+        sinks: multi_sink
+            .sinks()
+            .iter()
+            .map(|sink| ComponentVersion::new(sink.name(), sink.version()))
+            .collect(),
+        decoders: config
+            .decoders
+            .iter()
+            .map(|decoder| ComponentVersion::new(decoder.decoder_name(), decoder.decoder_version()))
+            .collect(),
+    };
 
     // Create DecoderContext with contract filtering and optional registry
     let decoder_context = if let Some(registry_cache) = config.registry_cache {
@@ -722,35 +1268,115 @@ pub async fn run(config: ToriiConfig) -> Result<(), Box<dyn std::error::Error>>
         );
         DecoderContext::new(config.decoders, engine_db.clone(), config.contract_filter)
     };
+    let track_provenance = config.provenance;
+    let decoder_context = decoder_context.with_provenance(track_provenance);
+    if track_provenance {
+        tracing::info!(target: "torii::etl", "Envelope provenance tracking enabled (debug)");
+    }
+    let decoder_context = decoder_context.with_block_headers(config.block_headers);
+    let debug_envelopes = config.debug_envelopes;
+    if debug_envelopes {
+        tracing::info!(target: "torii::etl", "Envelope debug records enabled");
+    }
+
+    let decoder_config_watcher = match config.decoder_config {
+        Some(path) => {
+            let mut watcher = config.decoder_factories.into_iter().fold(
+                DecoderConfigWatcher::new(path, decoder_context.reload_handle()),
+                DecoderConfigWatcher::with_factory,
+            );
+            let summary = watcher.reload().await?;
+            tracing::info!(
+                target: "torii::etl",
+                path = %watcher.path().display(),
+                mapped = summary.mapped,
+                decoders = summary.decoders_created,
+                "Decoder config loaded (hot reload enabled)"
+            );
+            Some(watcher)
+        }
+        None => None,
+    };
 
-    let topics = multi_sink.topics();
+    let mut topics = multi_sink.topics();
+    for publisher in &config.publishers {
+        topics.extend_from_slice(publisher.topics());
+    }
 
-    let grpc_state = GrpcState::new(subscription_manager.clone(), topics);
-    let grpc_service = create_grpc_service(grpc_state);
+    let lame_duck = LameDuck::new(Duration::from_secs(config.drain_period));
+    let indexer_status =
+        IndexerStatus::new().with_sinks(multi_sink.describe().into_iter().map(|sink| sink.name));
+    subscription_manager
+        .set_topic_namespaces(config.namespaces.topic_namespaces(&multi_sink.describe()));
+    let grpc_state = GrpcState::new(subscription_manager.clone(), topics)
+        .with_capabilities(capabilities)
+        .with_sink_descriptions(multi_sink.describe())
+        .with_engine_db(engine_db.clone())
+        .with_decoder_conflicts(decoder_context.conflicts())
+        .with_lame_duck(lame_duck.clone())
+        .with_admin_rpc(config.admin_rpc)
+        .with_namespaces(config.namespaces.clone());
+    let grpc_service = create_grpc_service(grpc_state, &config.grpc_options);
+    let etl_control = EtlControl::new(Duration::from_secs(config.cycle_interval));
+    match EtlParams::load(&engine_db).await {
+        Ok(params) if params != EtlParams::default() => {
+            tracing::info!(
+                target: "torii::etl",
+                cycle_interval = ?params.cycle_interval,
+                events_per_cycle = ?params.events_per_cycle,
+                batch_size = ?params.batch_size,
+                "Restored ETL parameters set through admin RPC"
+            );
+            etl_control.set_params(params);
+        }
+        Ok(_) => {}
+        Err(e) => tracing::warn!(
+            target: "torii::etl",
+            error = %e,
+            "Failed to load persisted ETL parameters, using the configured ones"
+        ),
+    }
+    let admin_service = create_admin_service(
+        etl_control.clone(),
+        config.admin_rpc,
+        config.address_labels.clone(),
+        engine_db.clone(),
+        &config.namespaces,
+        &config.grpc_options,
+    );
 
     let has_user_grpc_services = config.partial_grpc_router.is_some();
     let mut grpc_router = if let Some(partial_router) = config.partial_grpc_router {
         tracing::info!(target: "torii::main", "Using user-provided gRPC router with sink services");
-        partial_router.add_service(tonic_web::enable(grpc_service))
+        partial_router
+            .add_service(tonic_web::enable(grpc_service))
+            .add_service(tonic_web::enable(admin_service))
     } else {
         Server::builder()
             // Accept HTTP/1.1 requests required for gRPC-Web to work.
             .accept_http1(true)
             .add_service(tonic_web::enable(grpc_service))
+            .add_service(tonic_web::enable(admin_service))
     };
 
     if config.custom_reflection {
         tracing::info!(target: "torii::main", "Using custom reflection services (user-provided)");
     } else {
-        let reflection_v1 = tonic_reflection::server::Builder::configure()
-            .register_encoded_file_descriptor_set(FILE_DESCRIPTOR_SET)
-            .build_v1()?
-            .accept_compressed(CompressionEncoding::Gzip);
+        let reflection_v1 = configure_grpc_server!(
+            tonic_reflection::server::Builder::configure()
+                .register_encoded_file_descriptor_set(FILE_DESCRIPTOR_SET)
+                .build_v1()
+                .map_err(|e| ToriiError::Grpc(e.into()))?,
+            config.grpc_options
+        );
 
-        let reflection_v1alpha = tonic_reflection::server::Builder::configure()
-            .register_encoded_file_descriptor_set(FILE_DESCRIPTOR_SET)
-            .build_v1alpha()?
-            .accept_compressed(CompressionEncoding::Gzip);
+        let reflection_v1alpha = configure_grpc_server!(
+            tonic_reflection::server::Builder::configure()
+                .register_encoded_file_descriptor_set(FILE_DESCRIPTOR_SET)
+                .build_v1alpha()
+                .map_err(|e| ToriiError::Grpc(e.into()))?,
+            config.grpc_options
+        );
 
         grpc_router = grpc_router
             .add_service(tonic_web::enable(reflection_v1))
@@ -759,20 +1385,21 @@ pub async fn run(config: ToriiConfig) -> Result<(), Box<dyn std::error::Error>>
         tracing::info!(target: "torii::main", "Added reflection services (core descriptors only)");
     }
 
-    let sinks_routes = multi_sink.build_routes();
-    let http_router = create_http_router().merge(sinks_routes);
+    let sinks_routes = multi_sink.build_authorized_routes(&config.namespaces);
+    let http_state = HttpState {
+        counters: Some(counters.clone()),
+        lame_duck: Some(lame_duck.clone()),
+        status: Some(indexer_status.clone()),
+        engine_db: Some(engine_db.clone()),
+        schema: Some(Arc::new(
+            schema::ServerSchema::from_sinks(&multi_sink.describe())
+                .map_err(|e| ToriiError::Sink(e.into()))?,
+        )),
+        ..HttpState::new()
+    };
+    let http_router = create_http_router_with_state(http_state).merge(sinks_routes);
 
-    let cors = CorsLayer::new()
-        .allow_origin(CorsAny)
-        .allow_methods(CorsAny)
-        .allow_headers(CorsAny)
-        .expose_headers(vec![
-            axum::http::HeaderName::from_static("grpc-status"),
-            axum::http::HeaderName::from_static("grpc-message"),
-            axum::http::HeaderName::from_static("grpc-status-details-bin"),
-            axum::http::HeaderName::from_static("x-grpc-web"),
-            axum::http::HeaderName::from_static("content-type"),
-        ]);
+    let cors = config.cors.layer()?;
 
     // Until some compatibility issues are resolved with axum, we need to allow this deprecated code.
     // See: https://github.com/hyperium/tonic/issues/1964.
@@ -780,23 +1407,46 @@ pub async fn run(config: ToriiConfig) -> Result<(), Box<dyn std::error::Error>>
     let app = AxumRouter::new()
         .merge(grpc_router.into_router())
         .merge(http_router)
+        .layer(axum::middleware::from_fn_with_state(
+            lame_duck.clone(),
+            lame_duck::reject_new_subscriptions,
+        ))
         .layer(cors);
 
-    let addr: SocketAddr = format!("{}:{}", config.host, config.port).parse()?;
-    let tls_acceptor = config.tls.as_ref().map(build_tls_acceptor).transpose()?;
+    let addr: SocketAddr = format!("{}:{}", config.host, config.port)
+        .parse()
+        .map_err(|e| ToriiError::Config(format!("invalid listen address: {e}")))?;
+    let tls_acceptor = config
+        .tls
+        .as_ref()
+        .map(build_tls_acceptor)
+        .transpose()
+        .map_err(|e| ToriiError::Config(format!("invalid TLS configuration: {e}")))?;
     tracing::info!(target: "torii::main", "Server listening on {}", addr);
     if let Some(tls) = &config.tls {
-        tracing::info!(
-            target: "torii::main",
-            cert = %tls.cert_path.display(),
-            key = %tls.key_path.display(),
-            alpn = ?tls.alpn_names(),
-            "TLS enabled for listener"
-        );
+        if tls.server_config.is_some() {
+            tracing::info!(
+                target: "torii::main",
+                "TLS enabled for listener (custom rustls server config)"
+            );
+        } else {
+            tracing::info!(
+                target: "torii::main",
+                cert = %tls.cert_path.display(),
+                key = %tls.key_path.display(),
+                alpn = ?tls.alpn_names(),
+                "TLS enabled for listener"
+            );
+        }
     }
 
     tracing::info!(target: "torii::main", "gRPC Services:");
-    tracing::info!(target: "torii::main", "   torii.Torii - Core service");
+    tracing::info!(target: "torii::main", "   torii.Torii - Core service (commit {})", grpc::GIT_COMMIT);
+    tracing::info!(
+        target: "torii::main",
+        "   torii.Admin - ETL control ({})",
+        if config.admin_rpc { "enabled" } else { "disabled" }
+    );
     if has_user_grpc_services {
         tracing::info!(target: "torii::main", "   + User-provided sink gRPC services");
     }
@@ -807,18 +1457,46 @@ pub async fn run(config: ToriiConfig) -> Result<(), Box<dyn std::error::Error>>
     // Setup and start the ETL pipeline.
     let etl_multi_sink = multi_sink.clone();
     let etl_engine_db = engine_db.clone();
-    let cycle_interval = config.cycle_interval;
+    let etl_counters = counters.clone();
+    let etl_status = indexer_status.clone();
     let etl_shutdown_token = shutdown_token.clone();
     let etl_concurrency = config.etl_concurrency.clone();
 
-    // Move multi_decoder into the task (can't clone since it owns the registry)
-    let etl_decoder_context = decoder_context;
+    // Shared by the decode and sink stages.
+    let etl_decoder_context = Arc::new(decoder_context);
+
+    let archive_events = config.archive_events;
+
+    // Dedupe window, checked by the decode stage and persisted by the sink stage.
+    let dedupe_window = config.dedupe_window;
+    let mut event_dedupe = if dedupe_window > 0 {
+        let dedupe = etl::EventDedupe::load(&engine_db, dedupe_window).await?;
+        tracing::info!(
+            target: "torii::etl",
+            window = dedupe_window,
+            loaded = dedupe.len(),
+            "Event deduplication enabled"
+        );
+        Some(dedupe)
+    } else {
+        None
+    };
+    // Carries the positions of events in their transactions across batches.
+    let mut event_indexer = etl::EventIndexer::default();
 
     // Optional contract identifier for runtime identification
     let contract_identifier = config.contract_identifier;
 
-    // Extractor was already created earlier (to get provider), make it mutable for the ETL loop
-    let extractor = Arc::new(tokio::sync::Mutex::new(extractor));
+    // The extractor is owned by the extract stage, which also commits cursors.
+    let extractor_type = extractor.extractor_type();
+    let mut extractor = extractor;
+    etl::consistency::check_startup_consistency(
+        config.startup_consistency,
+        &engine_db,
+        multi_sink.sinks(),
+        extractor.as_mut(),
+    )
+    .await?;
 
     let etl_handle = tokio::spawn(async move {
         tracing::info!(target: "torii::etl", "Starting ETL pipeline...");
@@ -826,17 +1504,75 @@ pub async fn run(config: ToriiConfig) -> Result<(), Box<dyn std::error::Error>>
         // Wait a bit for the server to be ready.
         tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
 
+        // The pipeline runs three stages connected by bounded channels:
+        //
+        //   extract ──PrefetchedBatch──▶ decode ──DecodedBatch──▶ sink
+        //      ▲                                                   │
+        //      └───────────────────── BatchAck ────────────────────┘
+        //
+        // Full channels apply backpressure to the upstream stage. The extract stage
+        // owns the extractor and commits a cursor only once the sink stage acknowledged
+        // its batch, so cursors never get ahead of sink processing.
         #[derive(Debug)]
         struct PrefetchedBatch {
             batch: etl::extractor::ExtractionBatch,
             cursor: Option<String>,
             extractor_finished: bool,
+            batch_id: u64,
+            extracted_at: i64,
+            extract_duration: std::time::Duration,
+        }
+
+        struct DecodedBatch {
+            prefetched: PrefetchedBatch,
+            envelopes: Vec<etl::Envelope>,
+            decode_duration: std::time::Duration,
+            /// Keys to add to the persisted dedupe window once processed.
+            seen_events: Vec<etl::dedupe::EventKey>,
+        }
+
+        /// Sent by the sink stage once a batch is durably processed.
+        struct BatchAck {
+            cursor: Option<String>,
+            feedback: Option<etl::extractor::CycleFeedback>,
+        }
+
+        /// Commits the cursor of an acknowledged batch and feeds its latency back.
+        async fn handle_ack(
+            extractor: &mut Box<dyn Extractor>,
+            engine_db: &etl::EngineDb,
+            committed_cursor: &mut Option<String>,
+            ack: BatchAck,
+        ) {
+            if let Some(ref cursor_str) = ack.cursor {
+                if committed_cursor.as_ref() != Some(cursor_str) {
+                    if let Err(e) = extractor.commit_cursor(cursor_str, engine_db).await {
+                        tracing::error!(target: "torii::etl", "Failed to commit cursor: {}", e);
+                        ::metrics::counter!("torii_cursor_commit_failures_total").increment(1);
+                        // Continue anyway - cursor will be re-processed on restart (safe, just duplicate work)
+                    } else {
+                        *committed_cursor = ack.cursor;
+                    }
+                }
+            }
+            if let Some(feedback) = ack.feedback {
+                // Adaptive batch sizing.
+                extractor.observe_cycle(&feedback);
+            }
         }
 
         let prefetch_capacity = etl_concurrency.resolved_prefetch_batches();
         let (prefetch_tx, mut prefetch_rx) =
             tokio::sync::mpsc::channel::<PrefetchedBatch>(prefetch_capacity);
+        let (decoded_tx, mut decoded_rx) =
+            tokio::sync::mpsc::channel::<DecodedBatch>(prefetch_capacity);
+        // At most every batch in flight (both queues plus one per downstream stage) can be
+        // awaiting acknowledgment, so the sink stage never blocks on this channel.
+        let (ack_tx, mut ack_rx) =
+            tokio::sync::mpsc::channel::<BatchAck>(prefetch_capacity.saturating_mul(2) + 2);
         let queue_depth = Arc::new(AtomicUsize::new(0));
+        let decoded_depth = Arc::new(AtomicUsize::new(0));
+        let sink_backpressure = etl_multi_sink.backpressure();
 
         let (identify_tx, identify_handle) = if let Some(identifier) = contract_identifier.clone() {
             let (tx, mut rx) = tokio::sync::mpsc::channel::<Vec<starknet::core::types::Felt>>(
@@ -865,36 +1601,105 @@ pub async fn run(config: ToriiConfig) -> Result<(), Box<dyn std::error::Error>>
             (None, None)
         };
 
-        let producer_extractor = extractor.clone();
         let producer_engine_db = etl_engine_db.clone();
         let producer_shutdown = etl_shutdown_token.clone();
         let producer_identify_tx = identify_tx.clone();
         let producer_queue_depth = queue_depth.clone();
+        let producer_control = etl_control.clone();
+        let producer_status = etl_status.clone();
+        let producer_backpressure = sink_backpressure.clone();
 
         let producer_handle = tokio::spawn(async move {
             let mut cursor: Option<String> = None;
+            let mut committed_cursor: Option<String> = None;
+            let mut next_batch_id: u64 = 0;
+            let mut params = producer_control.subscribe_params();
+            params.borrow_and_update().apply_to(extractor.as_mut());
 
             loop {
+                while let Ok(ack) = ack_rx.try_recv() {
+                    handle_ack(
+                        &mut extractor,
+                        &producer_engine_db,
+                        &mut committed_cursor,
+                        ack,
+                    )
+                    .await;
+                }
+
                 if producer_shutdown.is_cancelled() {
                     tracing::info!(target: "torii::etl", "Shutdown requested, stopping prefetch producer");
                     break;
                 }
 
-                let batch = {
-                    let mut extractor = producer_extractor.lock().await;
-                    extractor.extract(cursor.clone(), &producer_engine_db).await
-                };
+                if producer_control.is_paused() {
+                    tracing::info!(target: "torii::etl", "Indexing paused");
+                    let resumed = producer_control.resumed();
+                    tokio::pin!(resumed);
+                    loop {
+                        tokio::select! {
+                            () = &mut resumed => break,
+                            () = producer_shutdown.cancelled() => break,
+                            Some(ack) = ack_rx.recv() => handle_ack(&mut extractor, &producer_engine_db, &mut committed_cursor, ack).await,
+                        }
+                    }
+                    if producer_shutdown.is_cancelled() {
+                        continue;
+                    }
+                    tracing::info!(target: "torii::etl", "Indexing resumed");
+                }
 
+                if producer_backpressure.is_saturated() {
+                    // Sinks are falling behind: shrink upcoming batches and hold extraction
+                    // until the queued depth is back to the threshold.
+                    tracing::debug!(
+                        target: "torii::etl",
+                        depth = producer_backpressure.depth(),
+                        threshold = producer_backpressure.threshold(),
+                        "Sinks falling behind, throttling extraction"
+                    );
+                    ::metrics::counter!("torii_etl_backpressure_total").increment(1);
+                    extractor.observe_backpressure();
+                    let wait_start = std::time::Instant::now();
+                    let drained = producer_backpressure.drained();
+                    tokio::pin!(drained);
+                    loop {
+                        tokio::select! {
+                            () = &mut drained => break,
+                            () = producer_shutdown.cancelled() => break,
+                            Some(ack) = ack_rx.recv() => handle_ack(&mut extractor, &producer_engine_db, &mut committed_cursor, ack).await,
+                        }
+                    }
+                    ::metrics::histogram!("torii_etl_backpressure_wait_seconds")
+                        .record(wait_start.elapsed().as_secs_f64());
+                    if producer_shutdown.is_cancelled() {
+                        continue;
+                    }
+                }
+
+                if params.has_changed().unwrap_or(false) {
+                    params.borrow_and_update().apply_to(extractor.as_mut());
+                }
+
+                let extract_start = std::time::Instant::now();
+                let batch = extractor
+                    .extract(cursor.clone(), &producer_engine_db)
+                    .await
+                    .map_err(|e| e.in_stage(Stage::Extractor));
+                let extract_duration = extract_start.elapsed();
+
+                let extracted_at = chrono::Utc::now().timestamp_millis();
                 let batch = match batch {
                     Ok(batch) => batch,
                     Err(e) => {
                         tracing::error!(target: "torii::etl", "Extract failed: {}", e);
+                        producer_status.record_error("extract", &e);
                         ::metrics::counter!("torii_etl_cycle_total", "status" => "extract_error")
                             .increment(1);
                         if producer_shutdown.is_cancelled() {
                             break;
                         }
-                        tokio::time::sleep(tokio::time::Duration::from_secs(cycle_interval)).await;
+                        tokio::time::sleep(producer_control.cycle_interval()).await;
                         continue;
                     }
                 };
@@ -942,23 +1747,29 @@ pub async fn run(config: ToriiConfig) -> Result<(), Box<dyn std::error::Error>>
                     }
                 }
 
-                let extractor_finished = {
-                    let extractor = producer_extractor.lock().await;
-                    extractor.is_finished()
-                };
+                let extractor_finished = extractor.is_finished();
+                let batch_id = next_batch_id;
+                next_batch_id += 1;
 
+                // Wait for queue space, committing acknowledged cursors meanwhile.
                 let stall_start = std::time::Instant::now();
-                if prefetch_tx
-                    .send(PrefetchedBatch {
-                        batch,
-                        cursor: new_cursor.clone(),
-                        extractor_finished,
-                    })
-                    .await
-                    .is_err()
-                {
+                let permit = loop {
+                    tokio::select! {
+                        permit = prefetch_tx.reserve() => break permit.ok(),
+                        Some(ack) = ack_rx.recv() => handle_ack(&mut extractor, &producer_engine_db, &mut committed_cursor, ack).await,
+                    }
+                };
+                let Some(permit) = permit else {
                     break;
-                }
+                };
+                permit.send(PrefetchedBatch {
+                    batch,
+                    cursor: new_cursor.clone(),
+                    extractor_finished,
+                    batch_id,
+                    extracted_at,
+                    extract_duration,
+                });
                 ::metrics::histogram!("torii_etl_prefetch_stall_seconds")
                     .record(stall_start.elapsed().as_secs_f64());
                 producer_queue_depth.fetch_add(1, Ordering::Relaxed);
@@ -979,185 +1790,323 @@ pub async fn run(config: ToriiConfig) -> Result<(), Box<dyn std::error::Error>>
                     );
                 }
 
-                if prefetch_tx.is_closed() || producer_shutdown.is_cancelled() {
+                if producer_shutdown.is_cancelled() {
                     break;
                 }
 
-                if let Some(ref prefetched_cursor) = cursor {
-                    tracing::trace!(
-                        target: "torii::etl",
-                        cursor = prefetched_cursor,
-                        "Advanced producer cursor"
-                    );
-                }
-
                 if should_pause {
-                    tokio::time::sleep(tokio::time::Duration::from_secs(cycle_interval)).await;
+                    // Until the cycle interval elapses or a cycle is triggered (`torii.Admin`).
+                    let wait_cycle = producer_control.wait_cycle();
+                    tokio::pin!(wait_cycle);
+                    loop {
+                        tokio::select! {
+                            () = &mut wait_cycle => break,
+                            Some(ack) = ack_rx.recv() => handle_ack(&mut extractor, &producer_engine_db, &mut committed_cursor, ack).await,
+                        }
+                    }
                 }
             }
+
+            // Downstream stages drain the queues, then close the ack channel.
+            drop(prefetch_tx);
+            while let Some(ack) = ack_rx.recv().await {
+                handle_ack(
+                    &mut extractor,
+                    &producer_engine_db,
+                    &mut committed_cursor,
+                    ack,
+                )
+                .await;
+            }
         });
 
-        let mut committed_cursor: Option<String> = None;
-        let mut shutdown_requested = false;
+        let decode_engine_db = etl_engine_db.clone();
+        let decode_decoder_context = etl_decoder_context.clone();
+        let decode_queue_depth = queue_depth.clone();
+        let decode_decoded_depth = decoded_depth.clone();
+        let decode_status = etl_status.clone();
+        let decode_backpressure = sink_backpressure.clone();
 
-        loop {
-            ::metrics::gauge!("torii_etl_inflight_cycles").set(1.0);
+        let decode_handle = tokio::spawn(async move {
+            loop {
+                let wait_start = std::time::Instant::now();
+                let Some(mut prefetched) = prefetch_rx.recv().await else {
+                    break;
+                };
+                ::metrics::histogram!("torii_etl_prefetch_stall_seconds")
+                    .record(wait_start.elapsed().as_secs_f64());
+                decode_queue_depth.fetch_sub(1, Ordering::Relaxed);
+                ::metrics::gauge!("torii_etl_prefetch_queue_depth")
+                    .set(decode_queue_depth.load(Ordering::Relaxed) as f64);
+
+                // Index events in their transactions, then drop events already
+                // processed before decoding them.
+                let mut event_indexes = event_indexer.assign(&prefetched.batch.events);
+                let seen_events = match event_dedupe.as_mut() {
+                    Some(dedupe) => {
+                        let extracted = prefetched.batch.events.len();
+                        let seen_events =
+                            dedupe.filter(&mut prefetched.batch.events, &mut event_indexes);
+                        let duplicates = extracted - prefetched.batch.events.len();
+                        if duplicates > 0 {
+                            tracing::debug!(
+                                target: "torii::etl",
+                                duplicates,
+                                "Dropped already processed events"
+                            );
+                            ::metrics::counter!("torii_etl_duplicate_events_total")
+                                .increment(duplicates as u64);
+                        }
+                        seen_events
+                    }
+                    None => Vec::new(),
+                };
 
-            let wait_start = std::time::Instant::now();
-            let next_batch = if shutdown_requested {
-                prefetch_rx.recv().await
-            } else {
-                tokio::select! {
-                    maybe_batch = prefetch_rx.recv() => maybe_batch,
-                    () = etl_shutdown_token.cancelled() => {
-                        shutdown_requested = true;
-                        continue;
+                let batch = &prefetched.batch;
+                let decode_start = std::time::Instant::now();
+                let envelopes = if batch.is_empty() {
+                    Vec::new()
+                } else {
+                    tracing::info!(
+                        target: "torii::etl",
+                        "Extracted {} events",
+                        batch.len()
+                    );
+                    ::metrics::counter!("torii_events_extracted_total")
+                        .increment(batch.len() as u64);
+                    ::metrics::counter!("torii_tx_processed_total")
+                        .increment(batch.transactions.len() as u64);
+                    ::metrics::counter!("torii_extract_batch_size_total", "unit" => "events")
+                        .increment(batch.events.len() as u64);
+                    ::metrics::counter!("torii_extract_batch_size_total", "unit" => "blocks")
+                        .increment(batch.blocks.len() as u64);
+                    ::metrics::counter!("torii_extract_batch_size_total", "unit" => "transactions")
+                        .increment(batch.transactions.len() as u64);
+
+                    // Update the engine DB stats for now here. Temporary.
+                    let latest_block = batch.blocks.keys().max().copied().unwrap_or(0);
+                    if let Err(e) = decode_engine_db
+                        .update_head(latest_block, batch.len() as u64)
+                        .await
+                    {
+                        tracing::warn!(target: "torii::etl", "Failed to update engine DB: {}", e);
+                    }
+
+                    // Transform the events into envelopes.
+                    let mut envelopes = match decode_decoder_context
+                        .decode_batch(batch, &event_indexes)
+                        .await
+                    {
+                        Ok(envelopes) => envelopes,
+                        Err(e) => {
+                            tracing::error!(target: "torii::etl", "Decode failed: {}", e);
+                            decode_status.record_error("decode", &e);
+                            ::metrics::counter!("torii_decode_failures_total", "stage" => "decode")
+                                .increment(1);
+                            ::metrics::counter!("torii_etl_cycle_total", "status" => "decode_error")
+                                .increment(1);
+                            continue;
+                        }
+                    };
+                    ::metrics::counter!("torii_events_decoded_total")
+                        .increment(batch.events.len() as u64);
+                    ::metrics::counter!("torii_decode_envelopes_total")
+                        .increment(envelopes.len() as u64);
+
+                    if track_provenance {
+                        for provenance in envelopes.iter_mut().filter_map(|e| e.provenance.as_mut())
+                        {
+                            provenance.extractor = extractor_type.to_string();
+                            provenance.batch_id = prefetched.batch_id;
+                            provenance.extracted_at = prefetched.extracted_at;
+                        }
                     }
+                    if debug_envelopes {
+                        for envelope in &envelopes {
+                            log_envelope(envelope, prefetched.batch_id);
+                        }
+                    }
+                    envelopes
+                };
+                let decode_duration = decode_start.elapsed();
+
+                let stall_start = std::time::Instant::now();
+                decode_backpressure.batch_queued();
+                if decoded_tx
+                    .send(DecodedBatch {
+                        prefetched,
+                        envelopes,
+                        decode_duration,
+                        seen_events,
+                    })
+                    .await
+                    .is_err()
+                {
+                    break;
                 }
-            };
-            ::metrics::histogram!("torii_etl_prefetch_stall_seconds")
-                .record(wait_start.elapsed().as_secs_f64());
+                ::metrics::histogram!("torii_etl_decode_stall_seconds")
+                    .record(stall_start.elapsed().as_secs_f64());
+                decode_decoded_depth.fetch_add(1, Ordering::Relaxed);
+                ::metrics::gauge!("torii_etl_decoded_queue_depth")
+                    .set(decode_decoded_depth.load(Ordering::Relaxed) as f64);
+            }
+        });
 
-            let Some(prefetched) = next_batch else {
-                ::metrics::gauge!("torii_etl_inflight_cycles").set(0.0);
-                break;
-            };
-            queue_depth.fetch_sub(1, Ordering::Relaxed);
-            ::metrics::gauge!("torii_etl_prefetch_queue_depth")
-                .set(queue_depth.load(Ordering::Relaxed) as f64);
+        // Sink stage: load decoded batches and acknowledge them for cursor commit once
+        // every sink flushed. After a failed flush, the ack waits for the next successful one.
+        let mut unflushed_ack: Option<BatchAck> = None;
+        while let Some(decoded) = decoded_rx.recv().await {
+            decoded_depth.fetch_sub(1, Ordering::Relaxed);
+            ::metrics::gauge!("torii_etl_decoded_queue_depth")
+                .set(decoded_depth.load(Ordering::Relaxed) as f64);
+            ::metrics::gauge!("torii_etl_inflight_cycles").set(1.0);
 
             let cycle_start = std::time::Instant::now();
+            let DecodedBatch {
+                prefetched,
+                envelopes,
+                decode_duration,
+                seen_events,
+            } = decoded;
             let batch = prefetched.batch;
-            let new_cursor = prefetched.cursor;
 
             if batch.is_empty() {
-                if let Some(ref cursor_str) = new_cursor {
-                    if committed_cursor.as_ref() != Some(cursor_str) {
-                        let commit_result = {
-                            let mut extractor = extractor.lock().await;
-                            extractor.commit_cursor(cursor_str, &etl_engine_db).await
-                        };
-                        if let Err(e) = commit_result {
-                            tracing::error!(
-                                target: "torii::etl",
-                                "Failed to commit cursor for empty batch: {}",
-                                e
-                            );
-                            ::metrics::counter!("torii_cursor_commit_failures_total").increment(1);
-                        } else {
-                            committed_cursor.clone_from(&new_cursor);
-                        }
+                // Empty batches still advance the cursor, unless buffered data is unflushed.
+                let ack = BatchAck {
+                    cursor: prefetched.cursor,
+                    feedback: None,
+                };
+                if unflushed_ack.is_some() {
+                    if let Err(e) = etl_multi_sink.flush().await {
+                        tracing::error!(target: "torii::etl", "Sink flush failed: {}", e);
+                        etl_status.record_error("flush", &e);
+                        unflushed_ack = Some(ack);
+                    } else {
+                        unflushed_ack = None;
+                        let _ = ack_tx.send(ack).await;
                     }
+                } else {
+                    let _ = ack_tx.send(ack).await;
+                    // Nothing pending: let the sinks run their maintenance.
+                    let _ = etl_multi_sink.on_idle().await;
                 }
+                sink_backpressure.batch_done();
 
                 if prefetched.extractor_finished {
                     tracing::info!(target: "torii::etl", "Extractor finished, stopping ETL loop");
-                    ::metrics::counter!("torii_etl_cycle_total", "status" => "empty").increment(1);
-                    ::metrics::histogram!("torii_etl_cycle_duration_seconds")
-                        .record(cycle_start.elapsed().as_secs_f64());
-                    ::metrics::gauge!("torii_etl_inflight_cycles").set(0.0);
-                    break;
                 }
-
                 ::metrics::counter!("torii_etl_cycle_total", "status" => "empty").increment(1);
                 ::metrics::histogram!("torii_etl_cycle_duration_seconds")
-                    .record(cycle_start.elapsed().as_secs_f64());
+                    .record((cycle_start.elapsed() + decode_duration).as_secs_f64());
                 ::metrics::gauge!("torii_etl_inflight_cycles").set(0.0);
-
-                if shutdown_requested {
-                    continue;
-                }
                 continue;
             }
 
-            tracing::info!(
-                target: "torii::etl",
-                "Extracted {} events",
-                batch.len()
-            );
-            ::metrics::counter!("torii_events_extracted_total").increment(batch.len() as u64);
-            ::metrics::counter!("torii_tx_processed_total")
-                .increment(batch.transactions.len() as u64);
-            ::metrics::counter!("torii_extract_batch_size_total", "unit" => "events")
-                .increment(batch.events.len() as u64);
-            ::metrics::counter!("torii_extract_batch_size_total", "unit" => "blocks")
-                .increment(batch.blocks.len() as u64);
-            ::metrics::counter!("torii_extract_batch_size_total", "unit" => "transactions")
-                .increment(batch.transactions.len() as u64);
-
-            // Update the engine DB stats for now here. Temporary.
-            let latest_block = batch.blocks.keys().max().copied().unwrap_or(0);
-            if let Err(e) = etl_engine_db
-                .update_head(latest_block, batch.len() as u64)
-                .await
-            {
-                tracing::warn!(target: "torii::etl", "Failed to update engine DB: {}", e);
-            }
-
-            // Transform the events into envelopes.
-            let envelopes = match etl_decoder_context.decode(&batch.events).await {
-                Ok(envelopes) => envelopes,
-                Err(e) => {
-                    tracing::error!(target: "torii::etl", "Decode failed: {}", e);
-                    ::metrics::counter!("torii_decode_failures_total", "stage" => "decode")
-                        .increment(1);
-                    ::metrics::counter!("torii_etl_cycle_total", "status" => "decode_error")
-                        .increment(1);
-                    ::metrics::histogram!("torii_etl_cycle_duration_seconds")
-                        .record(cycle_start.elapsed().as_secs_f64());
-                    ::metrics::gauge!("torii_etl_inflight_cycles").set(0.0);
-                    continue;
-                }
-            };
-            ::metrics::counter!("torii_events_decoded_total").increment(batch.events.len() as u64);
-            ::metrics::counter!("torii_decode_envelopes_total").increment(envelopes.len() as u64);
-
             // Load the envelopes into the sinks.
+            let sink_start = std::time::Instant::now();
             if let Err(e) = etl_multi_sink.process(&envelopes, &batch).await {
                 tracing::error!(target: "torii::etl", "Sink processing failed: {}", e);
+                etl_status.record_error("sink", &e);
+                sink_backpressure.batch_done();
                 ::metrics::counter!("torii_etl_cycle_total", "status" => "sink_error").increment(1);
                 ::metrics::histogram!("torii_etl_cycle_duration_seconds")
-                    .record(cycle_start.elapsed().as_secs_f64());
+                    .record((cycle_start.elapsed() + decode_duration).as_secs_f64());
                 ::metrics::gauge!("torii_etl_inflight_cycles").set(0.0);
                 continue;
             }
 
+            let flush_result = etl_multi_sink.flush().await;
+            let sink_duration = sink_start.elapsed();
+            sink_backpressure.batch_done();
+
+            // Maintain per-contract indexing statistics (GetContractStats).
+            let activity = etl_decoder_context.contract_activity(&envelopes, &batch);
+            if let Err(e) = etl_engine_db.record_contract_activity(&activity).await {
+                tracing::warn!(target: "torii::etl", "Failed to record contract stats: {}", e);
+            }
+
             // Count successfully processed payloads (post-sink processing).
             ::metrics::counter!("torii_events_processed_total")
                 .increment(batch.events.len() as u64);
+            etl_counters.record_events(batch.events.len() as u64);
+            etl_counters.publish();
             ::metrics::counter!("torii_transactions_processed_total")
                 .increment(batch.transactions.len() as u64);
 
-            // CRITICAL: Commit cursor ONLY AFTER successful sink processing.
-            // This ensures no data loss if the process is killed during extraction or sink processing.
-            if let Some(ref cursor_str) = new_cursor {
-                let commit_result = {
-                    let mut extractor = extractor.lock().await;
-                    extractor.commit_cursor(cursor_str, &etl_engine_db).await
-                };
-                if let Err(e) = commit_result {
-                    tracing::error!(target: "torii::etl", "Failed to commit cursor: {}", e);
-                    ::metrics::counter!("torii_cursor_commit_failures_total").increment(1);
-                    // Continue anyway - cursor will be re-processed on restart (safe, just duplicate work)
-                } else {
-                    committed_cursor.clone_from(&new_cursor);
+            // CRITICAL: Acknowledge (and so commit the cursor) ONLY AFTER successful sink
+            // processing and flush. This ensures no data loss if the process is killed during
+            // extraction or sink processing, or before buffered sink writes are durable.
+            let ack = BatchAck {
+                cursor: prefetched.cursor,
+                feedback: Some(etl::extractor::CycleFeedback {
+                    blocks: batch.blocks.len() as u64,
+                    events: batch.events.len() as u64,
+                    extract: prefetched.extract_duration,
+                    decode: decode_duration,
+                    sink: sink_duration,
+                }),
+            };
+            if let Err(e) = flush_result {
+                tracing::error!(target: "torii::etl", "Sink flush failed, cursor not committed: {}", e);
+                etl_status.record_error("flush", &e);
+                ::metrics::counter!("torii_etl_cycle_total", "status" => "flush_error")
+                    .increment(1);
+                ::metrics::histogram!("torii_etl_cycle_duration_seconds")
+                    .record((cycle_start.elapsed() + decode_duration).as_secs_f64());
+                ::metrics::gauge!("torii_etl_inflight_cycles").set(0.0);
+                unflushed_ack = Some(ack);
+                continue;
+            }
+            unflushed_ack = None;
+            let _ = ack_tx.send(ack).await;
+
+            if archive_events {
+                if let Err(e) = etl_engine_db.archive_batch(&batch).await {
+                    tracing::warn!(target: "torii::etl", "Failed to archive events: {}", e);
                 }
             }
 
+            if !seen_events.is_empty() {
+                if let Err(e) = etl_engine_db
+                    .record_seen_events(&seen_events, dedupe_window)
+                    .await
+                {
+                    tracing::warn!(target: "torii::etl", "Failed to record dedupe window: {}", e);
+                }
+            }
+
+            let latest_block = batch.blocks.keys().max().copied().unwrap_or(0);
             if let Some(chain_head) = batch.chain_head {
                 let gap = chain_head.saturating_sub(latest_block);
                 ::metrics::gauge!("torii_etl_cycle_gap_blocks").set(gap as f64);
             }
+            etl_status.record_cycle(latest_block, batch.chain_head);
             ::metrics::gauge!("torii_etl_last_success_timestamp_seconds")
                 .set(chrono::Utc::now().timestamp() as f64);
             ::metrics::counter!("torii_etl_cycle_total", "status" => "ok").increment(1);
             ::metrics::histogram!("torii_etl_cycle_duration_seconds")
-                .record(cycle_start.elapsed().as_secs_f64());
+                .record((cycle_start.elapsed() + decode_duration).as_secs_f64());
             ::metrics::gauge!("torii_etl_inflight_cycles").set(0.0);
 
             tracing::info!(target: "torii::etl", "ETL cycle complete");
         }
 
+        // Graceful shutdown: flush buffered sink data before the last cursor commit.
+        match etl_multi_sink.flush().await {
+            Ok(()) => {
+                if let Some(ack) = unflushed_ack {
+                    let _ = ack_tx.send(ack).await;
+                }
+            }
+            Err(e) => {
+                tracing::error!(target: "torii::etl", "Final sink flush failed: {}", e);
+            }
+        }
+        drop(ack_tx);
+
+        if let Err(e) = decode_handle.await {
+            tracing::warn!(target: "torii::etl", error = %e, "Decode stage join failed");
+        }
         if let Err(e) = producer_handle.await {
             tracing::warn!(target: "torii::etl", error = %e, "Prefetch producer join failed");
         }
@@ -1170,39 +2119,104 @@ pub async fn run(config: ToriiConfig) -> Result<(), Box<dyn std::error::Error>>
         tracing::info!(target: "torii::etl", "ETL loop completed gracefully");
     });
 
+    // Periodically snapshot cumulative counters so they survive restarts.
+    let snapshot_handle = (config.metrics_snapshot_interval > 0).then(|| {
+        let counters = counters.clone();
+        let engine_db = engine_db.clone();
+        let shutdown = shutdown_token.clone();
+        let interval = Duration::from_secs(config.metrics_snapshot_interval);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await;
+            loop {
+                tokio::select! {
+                    () = shutdown.cancelled() => break,
+                    _ = ticker.tick() => {}
+                }
+                if let Err(e) = counters.persist(&engine_db).await {
+                    tracing::warn!(target: "torii::main", error = %e, "Failed to persist metrics snapshot");
+                }
+                counters.publish();
+            }
+        })
+    });
+
+    let decoder_config_handle = decoder_config_watcher.map(|watcher| {
+        let interval = Duration::from_secs(config.decoder_config_poll_interval);
+        tokio::spawn(watcher.run(interval, shutdown_token.clone()))
+    });
+
+    let shard_lease_handle = config.shard_coordinator.clone().map(|coordinator| {
+        // Usually started right after acquiring the shards; renewed until released.
+        coordinator.start_renewal();
+        let shutdown = shutdown_token.clone();
+        let lame_duck = lame_duck.clone();
+        tokio::spawn(async move {
+            tokio::select! {
+                () = shutdown.cancelled() => {}
+                () = coordinator.leases_lost() => {
+                    tracing::error!(
+                        target: "torii::main",
+                        "Lost contract shard leases, shutting down"
+                    );
+                    lame_duck.enter();
+                }
+            }
+        })
+    });
+
     // Setup signal handlers for graceful shutdown
     let server_shutdown_token = shutdown_token.clone();
+    let shutdown_subscriptions = subscription_manager.clone();
+    let shutdown_engine_db = engine_db.clone();
     let shutdown_timeout = config.shutdown_timeout;
+    let drain_period = lame_duck.drain_period();
 
     let shutdown_signal = async move {
-        let ctrl_c = async {
-            tokio::signal::ctrl_c()
-                .await
-                .expect("Failed to install Ctrl+C handler");
-        };
-
-        #[cfg(unix)]
-        let terminate = async {
-            tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
-                .expect("Failed to install SIGTERM handler")
-                .recv()
-                .await;
-        };
-
-        #[cfg(not(unix))]
-        let terminate = std::future::pending::<()>();
-
         tokio::select! {
-            () = ctrl_c => {
+            () = ctrl_c() => {
                 tracing::info!(target: "torii::main", "Received SIGINT (Ctrl+C), initiating graceful shutdown...");
             }
-            () = terminate => {
+            () = terminate() => {
                 tracing::info!(target: "torii::main", "Received SIGTERM, initiating graceful shutdown...");
             }
+            () = lame_duck.entered() => {
+                tracing::info!(target: "torii::main", "Lame-duck mode requested, initiating graceful shutdown...");
+            }
         }
 
-        // Signal shutdown to ETL loop
+        // Reject new subscriptions and fail health checks while draining.
+        lame_duck.enter();
+
+        // Signal shutdown to ETL loop (the current batch is completed)
         server_shutdown_token.cancel();
+
+        if !drain_period.is_zero() {
+            tracing::info!(
+                target: "torii::main",
+                "Lame-duck mode: serving existing streams and queries for {}s",
+                drain_period.as_secs()
+            );
+            tokio::select! {
+                () = tokio::time::sleep(drain_period) => {}
+                () = ctrl_c() => {
+                    tracing::info!(target: "torii::main", "Received second SIGINT, ending drain early");
+                }
+                () = terminate() => {
+                    tracing::info!(target: "torii::main", "Received second SIGTERM, ending drain early");
+                }
+            }
+        }
+
+        // End subscription streams with a notice clients can resume from.
+        let block_number = match shutdown_engine_db.get_head().await {
+            Ok((block_number, _)) => block_number,
+            Err(e) => {
+                tracing::warn!(target: "torii::main", error = %e, "Failed to read head for shutdown notice");
+                0
+            }
+        };
+        shutdown_subscriptions.shutdown(block_number);
     };
 
     let listener = tokio::net::TcpListener::bind(addr).await?;
@@ -1273,9 +2287,9 @@ pub async fn run(config: ToriiConfig) -> Result<(), Box<dyn std::error::Error>>
             }
         }
         () = async {
-            // Wait for shutdown signal + timeout
+            // Wait for shutdown signal + drain period + timeout
             shutdown_token.cancelled().await;
-            tokio::time::sleep(Duration::from_secs(SERVER_SHUTDOWN_TIMEOUT_SECS)).await;
+            tokio::time::sleep(drain_period + Duration::from_secs(SERVER_SHUTDOWN_TIMEOUT_SECS)).await;
         } => {
             tracing::warn!(
                 target: "torii::main",
@@ -1304,15 +2318,83 @@ pub async fn run(config: ToriiConfig) -> Result<(), Box<dyn std::error::Error>>
         }
     }
 
+    if let Some(handle) = snapshot_handle {
+        handle.abort();
+    }
+    if let Some(handle) = decoder_config_handle {
+        handle.abort();
+    }
+    if let Some(handle) = shard_lease_handle {
+        handle.abort();
+    }
+    if let Some(coordinator) = &config.shard_coordinator {
+        if let Err(e) = coordinator.release().await {
+            tracing::warn!(target: "torii::main", error = %e, "Failed to release shard leases");
+        }
+    }
+    if let Err(e) = counters.persist(&engine_db).await {
+        tracing::warn!(target: "torii::main", error = %e, "Failed to persist metrics snapshot");
+    }
+
     tracing::info!(target: "torii::main", "Torii shutdown complete");
     command_bus.shutdown().await;
 
     Ok(())
 }
 
+/// Logs the debug record of a decoded envelope (`--debug-envelopes`).
+fn log_envelope(envelope: &etl::Envelope, batch_id: u64) {
+    let hex = |felt: Option<starknet::core::types::Felt>| felt.map(|felt| format!("{felt:#x}"));
+    tracing::info!(
+        target: "torii::etl::envelopes",
+        batch_id,
+        id = %envelope.id,
+        type_id = envelope.type_id.as_u64(),
+        contract = ?hex(envelope.meta.contract),
+        block_number = ?envelope.meta.block_number,
+        transaction_hash = ?hex(envelope.meta.transaction_hash),
+        event_index = ?envelope.meta.event_index,
+        decoder = ?envelope.decoder_name(),
+        decoder_id = ?envelope.meta.decoder_id.map(|id| id.as_u64()),
+        mapping_rule = ?envelope.meta.mapping_rule.map(etl::MappingRule::as_str),
+        metadata = ?envelope.metadata,
+        "Decoded envelope"
+    );
+}
+
+async fn ctrl_c() {
+    tokio::signal::ctrl_c()
+        .await
+        .expect("Failed to install Ctrl+C handler");
+}
+
+#[cfg(unix)]
+async fn terminate() {
+    tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+        .expect("Failed to install SIGTERM handler")
+        .recv()
+        .await;
+}
+
+#[cfg(not(unix))]
+async fn terminate() {
+    std::future::pending::<()>().await;
+}
+
 fn build_tls_acceptor(
     config: &ToriiTlsConfig,
 ) -> Result<tokio_rustls::TlsAcceptor, Box<dyn std::error::Error>> {
+    if let Some(server_config) = &config.server_config {
+        if !server_config.alpn_protocols.is_empty() {
+            return Ok(tokio_rustls::TlsAcceptor::from(server_config.clone()));
+        }
+        let mut server_config = server_config.as_ref().clone();
+        server_config
+            .alpn_protocols
+            .clone_from(&config.alpn_protocols);
+        return Ok(tokio_rustls::TlsAcceptor::from(Arc::new(server_config)));
+    }
+
     ensure_rustls_crypto_provider();
     let cert_chain = load_cert_chain(&config.cert_path)?;
     let private_key = load_private_key(&config.key_path)?;
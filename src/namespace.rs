@@ -0,0 +1,473 @@
+//! Multi-tenant namespaces on the gRPC API.
+//!
+//! Sinks register under a namespace (e.g. one per game or project), and so do the
+//! topics they publish. Clients name the namespace they query or subscribe to in
+//! their requests, and only see the topics, sinks and updates of that namespace.
+//!
+//! Sinks without a namespace belong to [`DEFAULT_NAMESPACE`]. Once any sink is
+//! registered under a namespace, requests must name one. When API keys are
+//! configured, requests carry a key (`x-api-key: <key>` or
+//! `authorization: Bearer <key>`) that must permit the requested namespace.
+//!
+//! Sink gRPC services and HTTP routes are checked against the namespace of their sink:
+//! wrap services with [`Namespaces::interceptor`] and routes with
+//! [`Namespaces::authorize_routes`]. The admin service requires a key permitted
+//! [`ALL_NAMESPACES`] (see [`Namespaces::admin_interceptor`]).
+
+use axum::extract::{Request, State};
+use axum::http::StatusCode;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::Router;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::Arc;
+use tonic::metadata::MetadataMap;
+use tonic::service::Interceptor;
+use tonic::Status;
+
+use crate::etl::sink::SinkDescription;
+
+/// Namespace of sinks and topics registered without one.
+pub const DEFAULT_NAMESPACE: &str = "default";
+
+/// Metadata key carrying the API key of a request.
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Namespace granting an API key access to every namespace.
+pub const ALL_NAMESPACES: &str = "*";
+
+/// Namespace of each sink, and the namespaces each API key may access.
+#[derive(Debug, Clone, Default)]
+pub struct Namespaces {
+    /// Sink name -> namespace
+    sinks: HashMap<String, String>,
+    /// API key -> permitted namespaces (`*` = all)
+    api_keys: HashMap<String, HashSet<String>>,
+}
+
+impl Namespaces {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the sink named `sink` under `namespace`.
+    #[must_use]
+    pub fn with_sink(mut self, sink: impl Into<String>, namespace: impl Into<String>) -> Self {
+        self.sinks.insert(sink.into(), namespace.into());
+        self
+    }
+
+    /// Permits `key` to access `namespaces` ([`ALL_NAMESPACES`] for every namespace).
+    #[must_use]
+    pub fn with_api_key(
+        mut self,
+        key: impl Into<String>,
+        namespaces: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.api_keys
+            .entry(key.into())
+            .or_default()
+            .extend(namespaces.into_iter().map(Into::into));
+        self
+    }
+
+    /// Namespace of the sink named `sink`.
+    pub fn namespace_of(&self, sink: &str) -> &str {
+        self.sinks
+            .get(sink)
+            .map_or(DEFAULT_NAMESPACE, String::as_str)
+    }
+
+    /// Namespace of each topic published by `sinks`, keyed by topic and sink name
+    /// (see [`crate::grpc::SubscriptionManager::topic_namespace`]).
+    pub fn topic_namespaces(&self, sinks: &[SinkDescription]) -> HashMap<String, String> {
+        let mut topic_namespaces = HashMap::new();
+        for sink in sinks {
+            let namespace = self.namespace_of(&sink.name);
+            topic_namespaces.insert(sink.name.clone(), namespace.to_string());
+            for topic in &sink.topics {
+                topic_namespaces.insert(topic.name.clone(), namespace.to_string());
+            }
+        }
+        topic_namespaces
+    }
+
+    /// Registered namespaces, including the default one.
+    pub fn namespaces(&self) -> BTreeSet<&str> {
+        self.sinks
+            .values()
+            .map(String::as_str)
+            .chain([DEFAULT_NAMESPACE])
+            .collect()
+    }
+
+    /// Whether requests must name their namespace (some sink has one).
+    pub fn is_required(&self) -> bool {
+        !self.sinks.is_empty()
+    }
+
+    /// Whether requests must carry an API key.
+    pub fn requires_api_key(&self) -> bool {
+        !self.api_keys.is_empty()
+    }
+
+    /// Resolves the namespace of a request and checks its API key permits it.
+    ///
+    /// An empty `requested` namespace stands for [`DEFAULT_NAMESPACE`], unless
+    /// namespaces are required.
+    pub fn authorize(&self, metadata: &MetadataMap, requested: &str) -> Result<String, Status> {
+        let namespace = if requested.is_empty() {
+            if self.is_required() {
+                return Err(Status::invalid_argument("A namespace is required"));
+            }
+            DEFAULT_NAMESPACE
+        } else {
+            requested
+        };
+        if !self.namespaces().contains(namespace) {
+            return Err(Status::not_found(format!(
+                "Unknown namespace '{namespace}'"
+            )));
+        }
+
+        if self.requires_api_key() {
+            let permitted = self.permitted_namespaces(metadata)?;
+            if !permitted.contains(namespace) && !permitted.contains(ALL_NAMESPACES) {
+                return Err(Status::permission_denied(format!(
+                    "API key is not permitted to access namespace '{namespace}'"
+                )));
+            }
+        }
+        Ok(namespace.to_string())
+    }
+
+    /// Checks the API key of an admin request is permitted [`ALL_NAMESPACES`].
+    ///
+    /// Without API keys every request is admitted.
+    pub fn authorize_admin(&self, metadata: &MetadataMap) -> Result<(), Status> {
+        if self.requires_api_key()
+            && !self
+                .permitted_namespaces(metadata)?
+                .contains(ALL_NAMESPACES)
+        {
+            return Err(Status::permission_denied(
+                "API key is not permitted to use admin RPCs",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Whether some API key is permitted [`ALL_NAMESPACES`], and may use admin RPCs.
+    pub fn has_admin_key(&self) -> bool {
+        self.api_keys
+            .values()
+            .any(|namespaces| namespaces.contains(ALL_NAMESPACES))
+    }
+
+    fn permitted_namespaces(&self, metadata: &MetadataMap) -> Result<&HashSet<String>, Status> {
+        let key = api_key(metadata).ok_or_else(|| Status::unauthenticated("Missing API key"))?;
+        self.api_keys
+            .get(key)
+            .ok_or_else(|| Status::unauthenticated("Invalid API key"))
+    }
+
+    /// gRPC interceptor admitting the requests whose API key permits the namespaces of
+    /// `sinks`, for the services of these sinks.
+    ///
+    /// ```ignore
+    /// let service = InterceptedService::new(Erc20Server::new(service), namespaces.interceptor(&["erc20"]));
+    /// ```
+    pub fn interceptor(&self, sinks: &[&str]) -> NamespaceGuard {
+        let mut namespaces: Vec<String> = sinks
+            .iter()
+            .map(|sink| self.namespace_of(sink).to_string())
+            .collect();
+        namespaces.sort();
+        namespaces.dedup();
+        NamespaceGuard {
+            config: Arc::new(self.clone()),
+            namespaces,
+            admin: false,
+        }
+    }
+
+    /// gRPC interceptor admitting the requests whose API key may use admin RPCs
+    /// (see [`Namespaces::authorize_admin`]), for the `torii.Admin` service.
+    pub fn admin_interceptor(&self) -> NamespaceGuard {
+        NamespaceGuard {
+            config: Arc::new(self.clone()),
+            namespaces: Vec::new(),
+            admin: true,
+        }
+    }
+
+    /// `routes` of the sink named `sink`, admitting the requests whose API key permits
+    /// the sink namespace.
+    pub fn authorize_routes(&self, sink: &str, routes: Router) -> Router {
+        routes.route_layer(axum::middleware::from_fn_with_state(
+            self.interceptor(&[sink]),
+            authorize_http,
+        ))
+    }
+}
+
+/// Checks requests to sink services and routes against the namespaces of their sinks
+/// (see [`Namespaces::interceptor`]).
+#[derive(Debug, Clone)]
+pub struct NamespaceGuard {
+    config: Arc<Namespaces>,
+    namespaces: Vec<String>,
+    /// Admits admin keys only
+    admin: bool,
+}
+
+impl NamespaceGuard {
+    fn check(&self, metadata: &MetadataMap) -> Result<(), Status> {
+        if self.admin {
+            return self.config.authorize_admin(metadata);
+        }
+        for namespace in &self.namespaces {
+            self.config.authorize(metadata, namespace)?;
+        }
+        Ok(())
+    }
+}
+
+impl Interceptor for NamespaceGuard {
+    fn call(&mut self, request: tonic::Request<()>) -> Result<tonic::Request<()>, Status> {
+        self.check(request.metadata())?;
+        Ok(request)
+    }
+}
+
+/// Axum middleware rejecting the sink route requests a [`NamespaceGuard`] does not admit.
+async fn authorize_http(
+    State(guard): State<NamespaceGuard>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let metadata = MetadataMap::from_headers(request.headers().clone());
+    match guard.check(&metadata) {
+        Ok(()) => next.run(request).await,
+        Err(status) => (http_status(status.code()), status.message().to_string()).into_response(),
+    }
+}
+
+fn http_status(code: tonic::Code) -> StatusCode {
+    match code {
+        tonic::Code::InvalidArgument => StatusCode::BAD_REQUEST,
+        tonic::Code::Unauthenticated => StatusCode::UNAUTHORIZED,
+        tonic::Code::PermissionDenied => StatusCode::FORBIDDEN,
+        tonic::Code::NotFound => StatusCode::NOT_FOUND,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// API key of a request: `x-api-key`, or a bearer `authorization` token.
+fn api_key(metadata: &MetadataMap) -> Option<&str> {
+    if let Some(key) = metadata.get(API_KEY_HEADER).and_then(|v| v.to_str().ok()) {
+        return Some(key.trim());
+    }
+    metadata
+        .get("authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata(key: &str, value: &str) -> MetadataMap {
+        let mut metadata = MetadataMap::new();
+        metadata.insert(
+            tonic::metadata::AsciiMetadataKey::from_bytes(key.as_bytes()).unwrap(),
+            value.parse().unwrap(),
+        );
+        metadata
+    }
+
+    #[test]
+    fn single_tenant_defaults_to_default_namespace() {
+        let namespaces = Namespaces::new();
+        assert!(!namespaces.is_required());
+        assert_eq!(
+            namespaces.authorize(&MetadataMap::new(), "").unwrap(),
+            DEFAULT_NAMESPACE
+        );
+        assert_eq!(
+            namespaces
+                .authorize(&MetadataMap::new(), "game")
+                .unwrap_err()
+                .code(),
+            tonic::Code::NotFound
+        );
+    }
+
+    #[test]
+    fn registered_namespaces_are_required_and_authorized() {
+        let namespaces = Namespaces::new()
+            .with_sink("erc20", "game-a")
+            .with_sink("erc721", "game-b")
+            .with_api_key("key-a", ["game-a"])
+            .with_api_key("admin", [ALL_NAMESPACES]);
+        assert_eq!(namespaces.namespace_of("erc721"), "game-b");
+        assert_eq!(namespaces.namespace_of("log"), DEFAULT_NAMESPACE);
+
+        let key_a = metadata(API_KEY_HEADER, "key-a");
+        assert_eq!(
+            namespaces.authorize(&key_a, "").unwrap_err().code(),
+            tonic::Code::InvalidArgument
+        );
+        assert_eq!(namespaces.authorize(&key_a, "game-a").unwrap(), "game-a");
+        assert_eq!(
+            namespaces.authorize(&key_a, "game-b").unwrap_err().code(),
+            tonic::Code::PermissionDenied
+        );
+        assert_eq!(
+            namespaces
+                .authorize(&MetadataMap::new(), "game-a")
+                .unwrap_err()
+                .code(),
+            tonic::Code::Unauthenticated
+        );
+        assert_eq!(
+            namespaces
+                .authorize(&metadata(API_KEY_HEADER, "nope"), "game-a")
+                .unwrap_err()
+                .code(),
+            tonic::Code::Unauthenticated
+        );
+
+        let admin = metadata("authorization", "Bearer admin");
+        assert_eq!(namespaces.authorize(&admin, "game-b").unwrap(), "game-b");
+        assert_eq!(
+            namespaces.authorize(&admin, DEFAULT_NAMESPACE).unwrap(),
+            DEFAULT_NAMESPACE
+        );
+    }
+
+    #[test]
+    fn admin_requires_a_key_permitted_all_namespaces() {
+        assert!(Namespaces::new()
+            .authorize_admin(&MetadataMap::new())
+            .is_ok());
+
+        let namespaces = Namespaces::new()
+            .with_api_key("key-a", ["game-a"])
+            .with_api_key("admin", [ALL_NAMESPACES]);
+        assert!(namespaces.has_admin_key());
+        assert_eq!(
+            namespaces
+                .authorize_admin(&MetadataMap::new())
+                .unwrap_err()
+                .code(),
+            tonic::Code::Unauthenticated
+        );
+        assert_eq!(
+            namespaces
+                .authorize_admin(&metadata(API_KEY_HEADER, "key-a"))
+                .unwrap_err()
+                .code(),
+            tonic::Code::PermissionDenied
+        );
+        assert!(namespaces
+            .authorize_admin(&metadata(API_KEY_HEADER, "admin"))
+            .is_ok());
+    }
+
+    fn two_tenants() -> Namespaces {
+        Namespaces::new()
+            .with_sink("erc20", "game-a")
+            .with_sink("erc721", "game-b")
+            .with_api_key("key-a", ["game-a"])
+    }
+
+    async fn grpc_call(
+        namespaces: &Namespaces,
+        sink: &str,
+        path: &str,
+        key: &str,
+    ) -> Option<String> {
+        let service = tower::service_fn(|_: axum::http::Request<tonic::body::BoxBody>| async {
+            Ok::<_, std::convert::Infallible>(axum::http::Response::new(tonic::body::empty_body()))
+        });
+        let mut service = tonic::service::interceptor::InterceptedService::new(
+            service,
+            namespaces.interceptor(&[sink]),
+        );
+        let request = axum::http::Request::post(path)
+            .header(API_KEY_HEADER, key)
+            .body(tonic::body::empty_body())
+            .unwrap();
+        let response = tower::Service::call(&mut service, request).await.unwrap();
+        response
+            .headers()
+            .get("grpc-status")
+            .map(|v| v.to_str().unwrap().to_string())
+    }
+
+    #[tokio::test]
+    async fn sink_services_reject_keys_of_other_namespaces() {
+        let namespaces = two_tenants();
+        assert_eq!(
+            grpc_call(
+                &namespaces,
+                "erc20",
+                "/torii.sinks.erc20.Erc20/GetBalance",
+                "key-a"
+            )
+            .await,
+            None
+        );
+        assert_eq!(
+            grpc_call(
+                &namespaces,
+                "erc721",
+                "/torii.sinks.erc721.Erc721/GetOwnership",
+                "key-a"
+            )
+            .await,
+            Some((tonic::Code::PermissionDenied as i32).to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn sink_routes_reject_keys_of_other_namespaces() {
+        use tower::ServiceExt;
+
+        let namespaces = two_tenants();
+        let router = Router::new()
+            .merge(namespaces.authorize_routes(
+                "erc20",
+                Router::new().route("/erc20/stats", axum::routing::get(|| async { "ok" })),
+            ))
+            .merge(namespaces.authorize_routes(
+                "erc721",
+                Router::new().route("/erc721/stats", axum::routing::get(|| async { "ok" })),
+            ));
+        let get = |path: &str, key: Option<&str>| {
+            let mut request = axum::http::Request::get(path);
+            if let Some(key) = key {
+                request = request.header("authorization", format!("Bearer {key}"));
+            }
+            router
+                .clone()
+                .oneshot(request.body(axum::body::Body::empty()).unwrap())
+        };
+
+        assert_eq!(
+            get("/erc20/stats", Some("key-a")).await.unwrap().status(),
+            StatusCode::OK
+        );
+        assert_eq!(
+            get("/erc721/stats", Some("key-a")).await.unwrap().status(),
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            get("/erc721/stats", None).await.unwrap().status(),
+            StatusCode::UNAUTHORIZED
+        );
+    }
+}
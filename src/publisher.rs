@@ -0,0 +1,164 @@
+//! Publishing updates from outside the sinks
+//!
+//! A [`Publisher`] lets an embedding application push its own notifications through the
+//! `SubscribeToTopics` stream without implementing a [`Sink`](crate::etl::Sink):
+//!
+//! ```rust,ignore
+//! let publisher = Publisher::new()
+//!     .with_topic(TopicInfo::new("alerts", vec!["severity".to_string()], "Application alerts"));
+//! let config = ToriiConfig::builder().with_publisher(publisher.clone()).build();
+//! tokio::spawn(torii::run(config));
+//!
+//! publisher.publish(
+//!     "alerts",
+//!     &alert,
+//!     UpdateType::Created,
+//!     HashMap::from([("severity".to_string(), "high".to_string())]),
+//! )?;
+//! ```
+
+use prost_types::Any;
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+
+use crate::error::{ToriiError, ToriiResult};
+use crate::etl::sink::{EventBus, TopicInfo};
+use crate::grpc::UpdateType;
+
+/// Handle publishing updates onto the topics of a running Torii instance
+///
+/// Cheap to clone: clones share the same instance. The handle is attached to the
+/// [`EventBus`] when [`run`](crate::run) starts; publishing before that fails.
+#[derive(Clone, Default)]
+pub struct Publisher {
+    event_bus: Arc<OnceLock<Arc<EventBus>>>,
+    topics: Vec<TopicInfo>,
+}
+
+impl Publisher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Advertise a topic in `ListTopics` and the topics sent to new subscribers
+    pub fn with_topic(mut self, topic: TopicInfo) -> Self {
+        self.topics.push(topic);
+        self
+    }
+
+    /// Topics advertised by this publisher
+    pub fn topics(&self) -> &[TopicInfo] {
+        &self.topics
+    }
+
+    /// Whether the publisher is attached to a running instance
+    pub fn is_attached(&self) -> bool {
+        self.event_bus.get().is_some()
+    }
+
+    /// Attach the publisher to the event bus of a running instance.
+    ///
+    /// Returns `false` if it was already attached.
+    pub fn attach(&self, event_bus: Arc<EventBus>) -> bool {
+        self.event_bus.set(event_bus).is_ok()
+    }
+
+    /// Publish `message` to the subscribers of `topic`.
+    ///
+    /// A subscriber receives the update when each of its filters equals the value of the
+    /// same field in `filter_fields` (subscribers without filters receive every update).
+    /// Returns the number of subscribers the update was sent to.
+    pub fn publish<M>(
+        &self,
+        topic: &str,
+        message: &M,
+        update_type: UpdateType,
+        filter_fields: HashMap<String, String>,
+    ) -> ToriiResult<usize>
+    where
+        M: prost::Name,
+    {
+        let event_bus = self.event_bus.get().ok_or_else(|| {
+            ToriiError::Other(anyhow::anyhow!(
+                "publisher is not attached to a running Torii instance"
+            ))
+        })?;
+
+        let data = Any {
+            type_url: M::type_url(),
+            value: message.encode_to_vec(),
+        };
+        Ok(event_bus.publish_protobuf(
+            topic,
+            &M::full_name(),
+            &data,
+            &filter_fields,
+            update_type,
+            |fields, filters| {
+                filters
+                    .iter()
+                    .all(|(key, value)| fields.get(key) == Some(value))
+            },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grpc::proto::TopicSubscription;
+    use crate::grpc::SubscriptionManager;
+    use tokio::sync::mpsc;
+
+    fn subscribe(manager: &SubscriptionManager, client: &str, filters: &[(&str, &str)]) {
+        manager.update_subscriptions(
+            client,
+            vec![TopicSubscription {
+                topic: "alerts".to_string(),
+                filters: filters
+                    .iter()
+                    .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+                    .collect(),
+                filter_data: None,
+            }],
+            Vec::new(),
+        );
+    }
+
+    #[test]
+    fn publishes_to_matching_subscribers() {
+        let manager = Arc::new(SubscriptionManager::new());
+        let (all_tx, mut all_rx) = mpsc::channel(8);
+        let (low_tx, mut low_rx) = mpsc::channel(8);
+        manager.register_client("all".to_string(), all_tx);
+        manager.register_client("low".to_string(), low_tx);
+        subscribe(&manager, "all", &[]);
+        subscribe(&manager, "low", &[("severity", "low")]);
+
+        let publisher = Publisher::new();
+        let message = prost_types::Duration {
+            seconds: 5,
+            nanos: 0,
+        };
+        let fields = HashMap::from([("severity".to_string(), "high".to_string())]);
+        assert!(publisher
+            .publish("alerts", &message, UpdateType::Created, fields.clone())
+            .is_err());
+
+        assert!(publisher.attach(Arc::new(EventBus::new(manager))));
+        assert!(publisher.is_attached());
+        let sent = publisher
+            .publish("alerts", &message, UpdateType::Created, fields)
+            .unwrap();
+        assert_eq!(sent, 1);
+
+        let update = all_rx.try_recv().unwrap();
+        assert_eq!(update.topic, "alerts");
+        assert_eq!(update.type_id, "google.protobuf.Duration");
+        assert_eq!(
+            update.data.unwrap().type_url,
+            "type.googleapis.com/google.protobuf.Duration"
+        );
+        assert!(low_rx.try_recv().is_err());
+    }
+}
@@ -0,0 +1,121 @@
+//! Schema registry served by `/schema` and the `GetSchema` RPC.
+//!
+//! Combines the protobuf descriptors of the core API and of the registered sinks with
+//! a catalog of the envelope types the sinks consume, so client code generators can
+//! bootstrap from a live server.
+
+use base64::Engine;
+use prost::Message;
+use prost_types::FileDescriptorSet;
+use serde::{Serialize, Serializer};
+use std::collections::HashSet;
+
+use crate::etl::sink::SinkDescription;
+
+/// Entry of the envelope type catalog
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EnvelopeTypeEntry {
+    /// Sink consuming the envelope type
+    pub sink: String,
+    /// Envelope type name (e.g., "erc20.transfer")
+    pub type_name: String,
+    /// `TypeId` of the envelopes (xxh3-64 hash of the type name)
+    pub type_id: u64,
+    /// Protobuf type URL (e.g., "type.googleapis.com/torii.sinks.erc20.Transfer")
+    pub type_url: String,
+}
+
+/// Protobuf descriptors and envelope type catalog of a server
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ServerSchema {
+    /// Encoded `google.protobuf.FileDescriptorSet` of the core and sink APIs
+    /// (base64 in JSON)
+    #[serde(serialize_with = "serialize_base64")]
+    pub file_descriptor_set: Vec<u8>,
+    /// Envelope types consumed by the sinks
+    pub envelope_types: Vec<EnvelopeTypeEntry>,
+}
+
+impl ServerSchema {
+    /// Schema of the core API and of `sinks`.
+    pub fn from_sinks<'a>(
+        sinks: impl IntoIterator<Item = &'a SinkDescription>,
+    ) -> Result<Self, prost::DecodeError> {
+        let mut descriptor_sets = vec![crate::TORII_DESCRIPTOR_SET];
+        let mut envelope_types = Vec::new();
+        for sink in sinks {
+            descriptor_sets.extend(sink.descriptor_set);
+            envelope_types.extend(
+                sink.envelope_types
+                    .iter()
+                    .map(|envelope| EnvelopeTypeEntry {
+                        sink: sink.name.clone(),
+                        type_name: envelope.type_name.clone(),
+                        type_id: envelope.type_id().as_u64(),
+                        type_url: envelope.type_url.clone(),
+                    }),
+            );
+        }
+        Ok(Self {
+            file_descriptor_set: merge_descriptor_sets(descriptor_sets)?.encode_to_vec(),
+            envelope_types,
+        })
+    }
+}
+
+/// Merges encoded file descriptor sets, keeping the first file of each name.
+///
+/// Sink descriptor sets may embed the same imported files (well-known types, shared
+/// protos), which a single set must only contain once.
+pub fn merge_descriptor_sets<'a>(
+    descriptor_sets: impl IntoIterator<Item = &'a [u8]>,
+) -> Result<FileDescriptorSet, prost::DecodeError> {
+    let mut names = HashSet::new();
+    let mut merged = FileDescriptorSet::default();
+    for descriptor_set in descriptor_sets {
+        for file in FileDescriptorSet::decode(descriptor_set)?.file {
+            if names.insert(file.name().to_string()) {
+                merged.file.push(file);
+            }
+        }
+    }
+    Ok(merged)
+}
+
+fn serialize_base64<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&base64::engine::general_purpose::STANDARD.encode(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::etl::envelope::TypeId;
+    use crate::etl::sink::EnvelopeSchema;
+
+    #[test]
+    fn sink_descriptors_are_merged_with_the_core_ones() {
+        let core = FileDescriptorSet::decode(crate::TORII_DESCRIPTOR_SET).unwrap();
+        let sink = SinkDescription {
+            name: "echo".to_string(),
+            version: None,
+            topics: Vec::new(),
+            tables: Vec::new(),
+            // Same files as the core: merged once.
+            descriptor_set: Some(crate::TORII_DESCRIPTOR_SET),
+            envelope_types: vec![EnvelopeSchema::new("echo.message", "torii.TopicUpdate")],
+        };
+
+        let schema = ServerSchema::from_sinks([&sink]).unwrap();
+        let merged = FileDescriptorSet::decode(schema.file_descriptor_set.as_slice()).unwrap();
+        assert_eq!(merged.file.len(), core.file.len());
+        assert_eq!(
+            schema.envelope_types,
+            vec![EnvelopeTypeEntry {
+                sink: "echo".to_string(),
+                type_name: "echo.message".to_string(),
+                type_id: TypeId::new("echo.message").as_u64(),
+                type_url: "type.googleapis.com/torii.TopicUpdate".to_string(),
+            }]
+        );
+    }
+}
@@ -0,0 +1,356 @@
+//! Indexer status dashboard (`/status`).
+//!
+//! [`IndexerStatus`] is fed by the ETL loop: chain head, last indexed block, sink
+//! checkpoints and recent errors. The `/status` endpoint combines it with the extractor
+//! cursors and per-contract counts of the engine database, rendered as an HTML page for
+//! browsers or as JSON (`?format=json` or `Accept: application/json`).
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+
+/// Number of recent errors kept for the status page.
+pub const RECENT_ERRORS: usize = 20;
+
+/// Number of contracts listed on the status page (most active first).
+pub const STATUS_CONTRACTS: usize = 100;
+
+/// Error reported by a stage of the ETL loop.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatusError {
+    /// Unix timestamp (seconds)
+    pub timestamp: i64,
+    /// ETL stage (`extract`, `decode`, `sink`, `flush`)
+    pub stage: String,
+    pub message: String,
+}
+
+/// Last block committed through a sink.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SinkCheckpoint {
+    pub sink: String,
+    /// Last committed block (`None` until the first processed batch)
+    pub block: Option<u64>,
+    /// Unix timestamp (seconds) of the last commit
+    pub updated_at: Option<i64>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    chain_head: Option<u64>,
+    indexed_block: Option<u64>,
+    last_cycle_at: Option<i64>,
+    errors: VecDeque<StatusError>,
+    sinks: BTreeMap<String, SinkCheckpoint>,
+}
+
+/// Live indexer state, shared between the ETL loop and the `/status` endpoint.
+///
+/// Cheap to clone: clones share the same state.
+#[derive(Debug, Clone, Default)]
+pub struct IndexerStatus {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl IndexerStatus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the sinks whose checkpoints are reported.
+    pub fn with_sinks<I, S>(self, sinks: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        {
+            let mut inner = self.inner.lock().unwrap();
+            for sink in sinks {
+                let sink = sink.into();
+                inner.sinks.insert(
+                    sink.clone(),
+                    SinkCheckpoint {
+                        sink,
+                        block: None,
+                        updated_at: None,
+                    },
+                );
+            }
+        }
+        self
+    }
+
+    /// Records a batch processed by every sink, up to `block`.
+    pub fn record_cycle(&self, block: u64, chain_head: Option<u64>) {
+        let now = chrono::Utc::now().timestamp();
+        let mut inner = self.inner.lock().unwrap();
+        inner.indexed_block = Some(inner.indexed_block.map_or(block, |b| b.max(block)));
+        if chain_head.is_some() {
+            inner.chain_head = chain_head;
+        }
+        inner.last_cycle_at = Some(now);
+        for checkpoint in inner.sinks.values_mut() {
+            checkpoint.block = Some(checkpoint.block.map_or(block, |b| b.max(block)));
+            checkpoint.updated_at = Some(now);
+        }
+    }
+
+    /// Records an error of an ETL stage, keeping the [`RECENT_ERRORS`] most recent ones.
+    pub fn record_error(&self, stage: &str, message: impl ToString) {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.errors.len() == RECENT_ERRORS {
+            inner.errors.pop_front();
+        }
+        inner.errors.push_back(StatusError {
+            timestamp: chrono::Utc::now().timestamp(),
+            stage: stage.to_string(),
+            message: message.to_string(),
+        });
+    }
+
+    /// Current state, with the most recent errors first.
+    pub fn snapshot(&self) -> StatusSnapshot {
+        let inner = self.inner.lock().unwrap();
+        StatusSnapshot {
+            chain_head: inner.chain_head,
+            indexed_block: inner.indexed_block,
+            lag_blocks: inner
+                .chain_head
+                .zip(inner.indexed_block)
+                .map(|(head, block)| head.saturating_sub(block)),
+            last_cycle_at: inner.last_cycle_at,
+            recent_errors: inner.errors.iter().rev().cloned().collect(),
+            sinks: inner.sinks.values().cloned().collect(),
+        }
+    }
+}
+
+/// Point-in-time view of an [`IndexerStatus`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatusSnapshot {
+    /// Chain head seen by the extractor
+    pub chain_head: Option<u64>,
+    /// Last block processed by the sinks
+    pub indexed_block: Option<u64>,
+    /// Blocks between the chain head and the last indexed block
+    pub lag_blocks: Option<u64>,
+    /// Unix timestamp (seconds) of the last successful cycle
+    pub last_cycle_at: Option<i64>,
+    pub recent_errors: Vec<StatusError>,
+    pub sinks: Vec<SinkCheckpoint>,
+}
+
+/// Extractor cursor persisted in the engine database.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CursorStatus {
+    pub extractor: String,
+    pub key: String,
+    pub value: String,
+}
+
+/// Indexing counts of a contract.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContractStatus {
+    /// Contract address (hex)
+    pub contract: String,
+    pub events: u64,
+    pub first_block: u64,
+    pub last_block: u64,
+    /// Block timestamp of the last indexed event (unix seconds)
+    pub last_activity: i64,
+}
+
+/// Body of the `/status` endpoint.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StatusResponse {
+    pub version: String,
+    pub uptime_seconds: i64,
+    /// Committed head block of the engine database
+    pub head_block: Option<u64>,
+    /// Events counted by the engine database head
+    pub head_events: Option<u64>,
+    #[serde(flatten)]
+    pub indexer: StatusSnapshot,
+    pub cursors: Vec<CursorStatus>,
+    /// Number of indexed contracts (only the [`STATUS_CONTRACTS`] most active are listed)
+    pub contract_count: usize,
+    pub contracts: Vec<ContractStatus>,
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn optional<T: ToString>(value: Option<T>) -> String {
+    value.map_or_else(|| "-".to_string(), |value| value.to_string())
+}
+
+fn timestamp(value: Option<i64>) -> String {
+    value
+        .and_then(|ts| chrono::DateTime::from_timestamp(ts, 0))
+        .map_or_else(|| "-".to_string(), |dt| dt.to_rfc3339())
+}
+
+fn table(html: &mut String, title: &str, headers: &[&str], rows: &[Vec<String>]) {
+    let _ = write!(html, "<h2>{}</h2>", escape(title));
+    if rows.is_empty() {
+        html.push_str("<p class=\"empty\">none</p>");
+        return;
+    }
+    html.push_str("<table><tr>");
+    for header in headers {
+        let _ = write!(html, "<th>{}</th>", escape(header));
+    }
+    html.push_str("</tr>");
+    for row in rows {
+        html.push_str("<tr>");
+        for cell in row {
+            let _ = write!(html, "<td>{}</td>", escape(cell));
+        }
+        html.push_str("</tr>");
+    }
+    html.push_str("</table>");
+}
+
+/// Renders the status page as a self-contained HTML document.
+pub fn render_html(status: &StatusResponse) -> String {
+    let mut html = String::from(
+        "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>Torii status</title>\
+         <style>body{font-family:sans-serif;margin:2em}table{border-collapse:collapse}\
+         th,td{border:1px solid #ccc;padding:4px 8px;text-align:left}\
+         td{font-family:monospace}.empty{color:#888}</style></head><body>",
+    );
+    let _ = write!(html, "<h1>Torii {}</h1>", escape(&status.version));
+
+    let indexer = &status.indexer;
+    table(
+        &mut html,
+        "Progress",
+        &["Metric", "Value"],
+        &[
+            vec!["Chain head".into(), optional(indexer.chain_head)],
+            vec!["Indexed block".into(), optional(indexer.indexed_block)],
+            vec!["Lag (blocks)".into(), optional(indexer.lag_blocks)],
+            vec!["Committed head".into(), optional(status.head_block)],
+            vec!["Events".into(), optional(status.head_events)],
+            vec!["Last cycle".into(), timestamp(indexer.last_cycle_at)],
+            vec!["Uptime (s)".into(), status.uptime_seconds.to_string()],
+        ],
+    );
+
+    let cursors: Vec<_> = status
+        .cursors
+        .iter()
+        .map(|c| vec![c.extractor.clone(), c.key.clone(), c.value.clone()])
+        .collect();
+    table(
+        &mut html,
+        "Cursors",
+        &["Extractor", "Key", "Value"],
+        &cursors,
+    );
+
+    let sinks: Vec<_> = indexer
+        .sinks
+        .iter()
+        .map(|s| vec![s.sink.clone(), optional(s.block), timestamp(s.updated_at)])
+        .collect();
+    table(
+        &mut html,
+        "Sink checkpoints",
+        &["Sink", "Block", "Updated"],
+        &sinks,
+    );
+
+    let contracts: Vec<_> = status
+        .contracts
+        .iter()
+        .map(|c| {
+            vec![
+                c.contract.clone(),
+                c.events.to_string(),
+                c.first_block.to_string(),
+                c.last_block.to_string(),
+                timestamp(Some(c.last_activity)),
+            ]
+        })
+        .collect();
+    table(
+        &mut html,
+        &format!("Contracts ({})", status.contract_count),
+        &[
+            "Contract",
+            "Events",
+            "First block",
+            "Last block",
+            "Last activity",
+        ],
+        &contracts,
+    );
+
+    let errors: Vec<_> = indexer
+        .recent_errors
+        .iter()
+        .map(|e| {
+            vec![
+                timestamp(Some(e.timestamp)),
+                e.stage.clone(),
+                e.message.clone(),
+            ]
+        })
+        .collect();
+    table(
+        &mut html,
+        "Recent errors",
+        &["Time", "Stage", "Message"],
+        &errors,
+    );
+
+    html.push_str("</body></html>");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracks_progress_checkpoints_and_errors() {
+        let status = IndexerStatus::new().with_sinks(["erc20", "log"]);
+        assert_eq!(status.snapshot().sinks[0].block, None);
+
+        status.record_cycle(90, Some(100));
+        status.record_cycle(80, None);
+        for i in 0..=RECENT_ERRORS {
+            status.record_error("sink", format!("error {i}"));
+        }
+
+        let snapshot = status.snapshot();
+        assert_eq!(snapshot.chain_head, Some(100));
+        assert_eq!(snapshot.indexed_block, Some(90));
+        assert_eq!(snapshot.lag_blocks, Some(10));
+        assert!(snapshot.sinks.iter().all(|s| s.block == Some(90)));
+        assert_eq!(snapshot.recent_errors.len(), RECENT_ERRORS);
+        assert_eq!(
+            snapshot.recent_errors[0].message,
+            format!("error {RECENT_ERRORS}")
+        );
+
+        let html = render_html(&StatusResponse {
+            indexer: snapshot,
+            cursors: vec![CursorStatus {
+                extractor: "event".into(),
+                key: "<0x1>".into(),
+                value: "block:90".into(),
+            }],
+            ..StatusResponse::default()
+        });
+        assert!(html.contains("&lt;0x1&gt;"));
+        assert!(html.contains("<td>erc20</td><td>90</td>"));
+    }
+}
@@ -0,0 +1,248 @@
+//! Dry-run validation of a [`ToriiConfig`].
+//!
+//! [`validate`] builds every component the way [`run`](crate::run) does (sinks are
+//! initialized, so their databases are opened and migrated; the engine database, the
+//! decoder config file, the listen address and TLS files are checked) and asks the
+//! extractor for the chain head to check the RPC connection. Nothing is extracted and
+//! no server is started: the result is a [`ValidationReport`] describing the plan.
+
+use std::fmt;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use crate::command::CommandBus;
+use crate::error::{Stage, ToriiError, ToriiResult};
+use crate::etl::decoder::DecoderConfigWatcher;
+use crate::etl::engine_db::{EngineDb, EngineDbConfig};
+use crate::etl::sink::{EventBus, SinkContext};
+use crate::etl::DecoderContext;
+use crate::grpc::SubscriptionManager;
+use crate::ToriiConfig;
+
+/// Sink as it would be run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SinkPlan {
+    pub name: String,
+    pub version: Option<String>,
+    /// EventBus topics published by the sink.
+    pub topics: Vec<String>,
+    /// Contracts routed to the sink, if it restricts them.
+    pub routed_contracts: Option<usize>,
+}
+
+/// Plan of a validated configuration, returned by [`validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationReport {
+    pub listen_address: SocketAddr,
+    pub tls: bool,
+    pub engine_database: String,
+    /// Extractor type, `None` when the sample extractor would be used.
+    pub extractor: Option<String>,
+    /// Chain head reported by the extractor, `None` if it has no chain connection.
+    pub chain_head: Option<u64>,
+    pub decoders: Vec<String>,
+    pub sinks: Vec<SinkPlan>,
+    /// Contracts explicitly mapped to decoders (including the decoder config file).
+    pub mapped_contracts: usize,
+    pub blacklisted_contracts: usize,
+    pub command_handlers: usize,
+    pub contract_identification: bool,
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Torii plan (dry run):")?;
+        writeln!(
+            f,
+            "  listen:     {}{}",
+            self.listen_address,
+            if self.tls { " (TLS)" } else { "" }
+        )?;
+        writeln!(f, "  engine db:  {}", self.engine_database)?;
+        match (&self.extractor, self.chain_head) {
+            (Some(extractor), Some(head)) => {
+                writeln!(f, "  extractor:  {extractor} (chain head {head})")?;
+            }
+            (Some(extractor), None) => writeln!(f, "  extractor:  {extractor}")?,
+            (None, _) => writeln!(f, "  extractor:  sample events")?,
+        }
+        writeln!(f, "  decoders:   {}", self.decoders.join(", "))?;
+        writeln!(
+            f,
+            "  contracts:  {} mapped, {} blacklisted, auto-identification {}",
+            self.mapped_contracts,
+            self.blacklisted_contracts,
+            if self.contract_identification {
+                "enabled"
+            } else {
+                "disabled"
+            }
+        )?;
+        writeln!(f, "  commands:   {} handler(s)", self.command_handlers)?;
+        writeln!(f, "  sinks:")?;
+        for sink in &self.sinks {
+            write!(f, "    - {}", sink.name)?;
+            if let Some(version) = &sink.version {
+                write!(f, " {version}")?;
+            }
+            if let Some(contracts) = sink.routed_contracts {
+                write!(f, " ({contracts} routed contract(s))")?;
+            }
+            writeln!(f)?;
+            if !sink.topics.is_empty() {
+                writeln!(f, "      topics: {}", sink.topics.join(", "))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Validates `config` without starting the ETL loop or the server.
+///
+/// Fails with the error [`run`](crate::run) would fail with at startup: an invalid
+/// contract filter or listen address, a sink that cannot initialize, an unreachable
+/// engine database or RPC, an unreadable decoder config file.
+pub async fn validate(config: ToriiConfig) -> ToriiResult<ValidationReport> {
+    config
+        .contract_filter
+        .validate()
+        .map_err(|e| ToriiError::Config(format!("{e:#}")))?;
+
+    let listen_address: SocketAddr = format!("{}:{}", config.host, config.port)
+        .parse()
+        .map_err(|e| ToriiError::Config(format!("invalid listen address: {e}")))?;
+    if let Some(tls) = &config.tls {
+        crate::build_tls_acceptor(tls)
+            .map_err(|e| ToriiError::Config(format!("invalid TLS configuration: {e}")))?;
+    }
+    config.cors.layer()?;
+
+    // Sinks publish nowhere: the bus has no subscribers.
+    let event_bus = Arc::new(EventBus::new(Arc::new(SubscriptionManager::new())));
+    let command_handlers = config.command_handlers.len();
+    let command_bus = CommandBus::new(config.command_handlers, config.command_bus_queue_size)?;
+    let sink_context = SinkContext {
+        database_root: config.database_root.clone(),
+        command_bus: command_bus.sender(),
+    };
+
+    let mut sinks = Vec::with_capacity(config.sinks.len());
+    for mut sink in config.sinks {
+        sink.initialize(event_bus.clone(), &sink_context)
+            .await
+            .map_err(|e| e.in_stage(Stage::Sink))?;
+        let routed_contracts = match sink.contract_filter() {
+            Some(filter) => {
+                filter
+                    .validate()
+                    .map_err(|e| ToriiError::Config(format!("{e:#}")))?;
+                Some(filter.allowlist.len())
+            }
+            None => None,
+        };
+        sinks.push(SinkPlan {
+            name: sink.name().to_string(),
+            version: sink.version().map(str::to_string),
+            topics: sink.topics().into_iter().map(|topic| topic.name).collect(),
+            routed_contracts,
+        });
+    }
+    command_bus.shutdown().await;
+
+    let engine_database = config.engine_database_url.clone().unwrap_or_else(|| {
+        config
+            .database_root
+            .join("engine.db")
+            .to_string_lossy()
+            .to_string()
+    });
+    let engine_db = Arc::new(
+        EngineDb::new(EngineDbConfig {
+            path: engine_database.clone(),
+        })
+        .await?,
+    );
+
+    let decoders = config
+        .decoders
+        .iter()
+        .map(|decoder| decoder.decoder_name().to_string())
+        .collect();
+    let mut mapped_contracts = config.contract_filter.mappings.len();
+    let blacklisted_contracts = config.contract_filter.blacklist.len();
+    if let Some(path) = config.decoder_config {
+        let decoder_context =
+            DecoderContext::new(config.decoders, engine_db, config.contract_filter);
+        let mut watcher = config.decoder_factories.into_iter().fold(
+            DecoderConfigWatcher::new(path, decoder_context.reload_handle()),
+            DecoderConfigWatcher::with_factory,
+        );
+        mapped_contracts += watcher
+            .reload()
+            .await
+            .map_err(|e| ToriiError::Config(format!("{e:#}")))?
+            .mapped;
+    }
+
+    let (extractor, chain_head) = match &config.extractor {
+        Some(extractor) => (
+            Some(extractor.extractor_type().to_string()),
+            extractor
+                .chain_head()
+                .await
+                .map_err(|e| e.in_stage(Stage::Extractor))?,
+        ),
+        None => (None, None),
+    };
+
+    Ok(ValidationReport {
+        listen_address,
+        tls: config.tls.is_some(),
+        engine_database,
+        extractor,
+        chain_head,
+        decoders,
+        sinks,
+        mapped_contracts,
+        blacklisted_contracts,
+        command_handlers,
+        contract_identification: config.contract_identifier.is_some(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::etl::decoder::DecoderId;
+    use crate::ToriiConfigBuilder;
+    use starknet::core::types::Felt;
+
+    #[tokio::test]
+    async fn reports_the_plan_without_running() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = ToriiConfigBuilder::default()
+            .port(9090)
+            .database_root(dir.path())
+            .map_contract(Felt::ONE, vec![DecoderId::new("erc20")])
+            .blacklist_contract(Felt::TWO)
+            .build();
+
+        let report = validate(config).await.unwrap();
+        assert_eq!(report.listen_address.port(), 9090);
+        assert_eq!(report.mapped_contracts, 1);
+        assert_eq!(report.blacklisted_contracts, 1);
+        assert_eq!(report.extractor, None);
+        assert!(report.sinks.is_empty());
+        assert!(dir.path().join("engine.db").exists());
+        assert!(report.to_string().contains("extractor:  sample events"));
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_listen_address() {
+        let config = ToriiConfigBuilder::default()
+            .host("not an address".to_string())
+            .engine_database_url(":memory:")
+            .build();
+        assert!(matches!(validate(config).await, Err(ToriiError::Config(_))));
+    }
+}